@@ -1,4 +1,10 @@
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashSet};
 use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
 
 fn main() {
     // Enable additional optimizations for release builds
@@ -51,5 +57,168 @@ fn main() {
         }
     }
 
+    // Generate the gRPC server from the engine API definition
+    #[cfg(feature = "grpc")]
+    tonic_build::configure()
+        .build_client(false)
+        .compile_protos(&["proto/bear_ai.proto"], &["proto"])
+        .expect("Failed to compile proto/bear_ai.proto");
+
+    // Embed a CycloneDX SBOM of the shipped dependencies for the license attribution report
+    write_sbom();
+
     tauri_build::build()
 }
+
+/// Resolve the dependency graph for this build's target and features with `cargo metadata`
+/// and write OUT_DIR/sbom.json (CycloneDX 1.5) plus OUT_DIR/license-texts.json, which holds each
+/// distinct license/notice file found in the dependency sources once, keyed by content hash.
+/// Build-only dependencies and proc macros are left out since none of their code ships.
+fn write_sbom() {
+    println!("cargo:rerun-if-changed=Cargo.lock");
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    let (components, texts, error) = match collect_components() {
+        Ok((components, texts)) => (components, texts, None),
+        Err(error) => {
+            println!("cargo:warning=SBOM generation failed: {}", error);
+            (Vec::new(), BTreeMap::new(), Some(error))
+        }
+    };
+
+    let mut properties = Vec::new();
+    if let Some(error) = error {
+        properties.push(json!({ "name": "bear-ai:sbom-error", "value": error }));
+    }
+    let sbom = json!({
+        "bomFormat": "CycloneDX",
+        "specVersion": "1.5",
+        "version": 1,
+        "metadata": {
+            "component": {
+                "type": "application",
+                "name": env::var("CARGO_PKG_NAME").unwrap(),
+                "version": env::var("CARGO_PKG_VERSION").unwrap(),
+            },
+            "properties": properties,
+        },
+        "components": components,
+    });
+    fs::write(out_dir.join("sbom.json"), serde_json::to_string_pretty(&sbom).unwrap()).unwrap();
+    fs::write(out_dir.join("license-texts.json"), serde_json::to_string(&texts).unwrap()).unwrap();
+}
+
+fn collect_components() -> Result<(Vec<Value>, BTreeMap<String, Value>), String> {
+    let features: Vec<String> = env::vars()
+        .filter_map(|(key, _)| key.strip_prefix("CARGO_FEATURE_").map(|f| f.to_lowercase().replace('_', "-")))
+        .collect();
+    let output = Command::new(env::var("CARGO").unwrap_or_else(|_| "cargo".to_string()))
+        .args(["metadata", "--format-version", "1", "--offline", "--no-default-features"])
+        .args(["--features", &features.join(",")])
+        .args(["--filter-platform", &env::var("TARGET").unwrap()])
+        .arg("--manifest-path")
+        .arg(Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).join("Cargo.toml"))
+        .output()
+        .map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    let metadata: Value = serde_json::from_slice(&output.stdout).map_err(|e| e.to_string())?;
+
+    let packages: BTreeMap<&str, &Value> = metadata["packages"]
+        .as_array()
+        .ok_or("cargo metadata returned no packages")?
+        .iter()
+        .filter_map(|p| Some((p["id"].as_str()?, p)))
+        .collect();
+    let nodes: BTreeMap<&str, &Value> = metadata["resolve"]["nodes"]
+        .as_array()
+        .ok_or("cargo metadata returned no resolve graph")?
+        .iter()
+        .filter_map(|n| Some((n["id"].as_str()?, n)))
+        .collect();
+    let is_proc_macro = |package: &Value| {
+        package["targets"].as_array().is_some_and(|targets| {
+            targets.iter().all(|t| t["kind"].as_array().is_some_and(|k| k.iter().any(|k| k == "proc-macro")))
+        })
+    };
+
+    // Walk normal dependency edges from the root package
+    let root = metadata["resolve"]["root"].as_str().ok_or("No root package")?;
+    let mut shipped = HashSet::new();
+    let mut queue = vec![root];
+    while let Some(id) = queue.pop() {
+        let Some(node) = nodes.get(id) else { continue };
+        for dep in node["deps"].as_array().into_iter().flatten() {
+            let normal = dep["dep_kinds"].as_array().into_iter().flatten().any(|k| k["kind"].is_null());
+            let Some(dep_id) = dep["pkg"].as_str() else { continue };
+            if normal && packages.get(dep_id).is_some_and(|p| !is_proc_macro(p)) && shipped.insert(dep_id) {
+                queue.push(dep_id);
+            }
+        }
+    }
+
+    let mut components = Vec::new();
+    let mut texts = BTreeMap::new();
+    for (id, package) in &packages {
+        if !shipped.contains(id) {
+            continue;
+        }
+        let (name, version) = (package["name"].as_str().unwrap_or_default(), package["version"].as_str().unwrap_or_default());
+        let mut text_ids = Vec::new();
+        let mut copyright = Vec::new();
+        let dir = Path::new(package["manifest_path"].as_str().unwrap_or_default()).parent().map(Path::to_path_buf);
+        for path in dir.map(license_files).unwrap_or_default() {
+            let Ok(text) = fs::read_to_string(&path) else { continue };
+            for line in text.lines().map(str::trim) {
+                let is_notice = (line.starts_with("Copyright") || line.starts_with('©')) && line.contains(char::is_numeric);
+                if is_notice && !line.contains('[') && !line.contains('{') && !copyright.iter().any(|c| c == line) {
+                    copyright.push(line.to_string());
+                }
+            }
+            let hash = hex_prefix(&Sha256::digest(text.as_bytes()));
+            let file = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+            texts.entry(hash.clone()).or_insert_with(|| json!({ "file": file, "text": text }));
+            text_ids.push(json!({ "name": "bear-ai:license-text", "value": hash }));
+        }
+
+        let mut component = json!({
+            "type": "library",
+            "bom-ref": format!("pkg:cargo/{}@{}", name, version),
+            "name": name,
+            "version": version,
+            "purl": format!("pkg:cargo/{}@{}", name, version),
+            "author": package["authors"].as_array().map(|a| a.iter().filter_map(Value::as_str).collect::<Vec<_>>().join(", ")),
+            "copyright": (!copyright.is_empty()).then(|| copyright.join("\n")),
+            "properties": text_ids,
+        });
+        if let Some(license) = package["license"].as_str() {
+            component["licenses"] = json!([{ "expression": license }]);
+        }
+        if let Some(repository) = package["repository"].as_str() {
+            component["externalReferences"] = json!([{ "type": "vcs", "url": repository }]);
+        }
+        components.push(component);
+    }
+    Ok((components, texts))
+}
+
+/// LICENSE*, COPYING*, NOTICE* and COPYRIGHT* files at the top of a crate's source
+fn license_files(dir: PathBuf) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            let name = path.file_name().unwrap_or_default().to_string_lossy().to_uppercase();
+            path.is_file()
+                && ["LICENSE", "LICENCE", "COPYING", "NOTICE", "COPYRIGHT"].iter().any(|p| name.starts_with(p))
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+fn hex_prefix(digest: &[u8]) -> String {
+    digest[..8].iter().map(|b| format!("{:02x}", b)).collect()
+}
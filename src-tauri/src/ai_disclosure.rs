@@ -0,0 +1,365 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::docx_writer::DocxBlock;
+use crate::local_api::{authenticated_user, SessionStorage};
+use crate::security::{ActionOutcome, Permission, SecurityAction, SecurityManager};
+
+/// AI-Use Disclosures for BEAR AI
+/// A disclosure block appended to generated memos, chat exports and reports. Templates are kept
+/// per jurisdiction ("US", "UK", "EU", ...); a sub-jurisdiction such as "US-NY" uses its own
+/// template when there is one and the country's otherwise, and anything unmatched uses
+/// "default". Users may leave the block out of an export unless the firm's policy makes it
+/// mandatory. Templates may use {tool}, {model}, {date} and {jurisdiction}.
+pub const DEFAULT_TEMPLATE: &str = "default";
+const TOOL_NAME: &str = "BEAR AI Legal Assistant";
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DisclosureTemplate {
+    pub jurisdiction: String,
+    pub title: String,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DisclosureSettings {
+    pub enabled: bool, // include the block unless an export says otherwise
+    pub default_jurisdiction: Option<String>,
+    pub templates: Vec<DisclosureTemplate>,
+}
+
+impl Default for DisclosureSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            default_jurisdiction: None,
+            templates: default_templates(),
+        }
+    }
+}
+
+/// Firm-wide policy; a mandatory disclosure cannot be switched off per export or in settings
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DisclosurePolicy {
+    pub mandatory: bool,
+    pub updated_by: Option<String>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct DisclosureState {
+    settings: DisclosureSettings,
+    policy: DisclosurePolicy,
+}
+
+/// A template filled in for one export
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Disclosure {
+    pub jurisdiction: String,
+    pub title: String,
+    pub text: String,
+}
+
+pub fn default_templates() -> Vec<DisclosureTemplate> {
+    let template = |jurisdiction: &str, title: &str, text: &str| DisclosureTemplate {
+        jurisdiction: jurisdiction.to_string(),
+        title: title.to_string(),
+        text: text.to_string(),
+    };
+    vec![
+        template(
+            DEFAULT_TEMPLATE,
+            "AI-use disclosure",
+            "This document was prepared with the assistance of {tool}, a generative AI system (model: {model}), on {date}. \
+             The responsible lawyer must review its content and verify every authority cited before relying on it.",
+        ),
+        template(
+            "US",
+            "Certification regarding use of generative AI",
+            "Generative artificial intelligence ({tool}, model {model}) was used in preparing this document. \
+             Counsel certifies that the language drafted with it and every citation to authority have been checked \
+             for accuracy by a person, as required by the rules and standing orders of the court where they apply.",
+        ),
+        template(
+            "UK",
+            "Use of AI tools",
+            "A generative AI tool ({tool}, model {model}) was used in preparing this document on {date}. The author \
+             has checked its content and the authorities relied on, and takes personal responsibility for it.",
+        ),
+        template(
+            "EU",
+            "Notice of AI-generated content",
+            "This document contains content generated with the assistance of an AI system ({tool}, model {model}) \
+             on {date}. It is disclosed in line with the transparency obligations of Regulation (EU) 2024/1689 \
+             (the AI Act).",
+        ),
+        template(
+            "CA",
+            "Declaration of use of artificial intelligence",
+            "Artificial intelligence ({tool}, model {model}) was used to generate content in this document.",
+        ),
+        template(
+            "NL",
+            "Verklaring gebruik van AI",
+            "Dit document is opgesteld met behulp van een generatief AI-systeem ({tool}, model {model}) op {date}. \
+             De inhoud en alle aangehaalde bronnen moeten door de verantwoordelijke advocaat worden gecontroleerd.",
+        ),
+    ]
+}
+
+/// The template for a jurisdiction: an exact match, then the part before a "-", then the
+/// default template, then the built-in default
+pub fn template_for(templates: &[DisclosureTemplate], jurisdiction: Option<&str>) -> DisclosureTemplate {
+    let find = |code: &str| templates.iter().find(|t| t.jurisdiction.eq_ignore_ascii_case(code.trim()));
+    jurisdiction
+        .and_then(|j| find(j).or_else(|| j.split_once('-').and_then(|(country, _)| find(country))))
+        .or_else(|| find(DEFAULT_TEMPLATE))
+        .cloned()
+        .unwrap_or_else(|| default_templates().remove(0))
+}
+
+pub fn render(template: &DisclosureTemplate, jurisdiction: Option<&str>, model: Option<&str>, date: NaiveDate) -> Disclosure {
+    let jurisdiction = jurisdiction.unwrap_or(&template.jurisdiction).to_string();
+    let fill = |text: &str| {
+        text.replace("{tool}", TOOL_NAME)
+            .replace("{model}", model.unwrap_or("unspecified"))
+            .replace("{date}", &date.format("%Y-%m-%d").to_string())
+            .replace("{jurisdiction}", &jurisdiction)
+    };
+    Disclosure {
+        title: fill(&template.title),
+        text: fill(&template.text),
+        jurisdiction,
+    }
+}
+
+impl Disclosure {
+    pub fn to_blocks(&self) -> Vec<DocxBlock> {
+        vec![DocxBlock::Heading(1, self.title.clone()), DocxBlock::Paragraph(self.text.clone())]
+    }
+
+    pub fn to_markdown(&self) -> String {
+        format!("## {}\n\n{}\n", self.title, self.text)
+    }
+
+    pub fn to_text(&self) -> String {
+        format!("{}\n{}\n", self.title.to_uppercase(), self.text)
+    }
+}
+
+/// A mandatory disclosure must say something; an empty template would satisfy the policy with
+/// a bare heading
+fn check_templates(templates: &[DisclosureTemplate]) -> Result<()> {
+    match templates.iter().find(|t| t.text.trim().is_empty()) {
+        Some(template) => Err(anyhow!(
+            "The {} disclosure template is empty, which a mandatory disclosure does not allow",
+            template.jurisdiction
+        )),
+        None => Ok(()),
+    }
+}
+
+pub struct Disclosures {
+    path: PathBuf,
+    state: Mutex<DisclosureState>,
+}
+
+impl Disclosures {
+    pub fn new(app_data_dir: &Path) -> Result<Self> {
+        let path = app_data_dir.join("ai_disclosures.json");
+        let state = if path.exists() {
+            serde_json::from_str(&fs::read_to_string(&path)?)?
+        } else {
+            DisclosureState::default()
+        };
+        Ok(Self {
+            path,
+            state: Mutex::new(state),
+        })
+    }
+
+    fn persist(&self, state: &DisclosureState) -> Result<()> {
+        fs::write(&self.path, serde_json::to_string_pretty(state)?)?;
+        Ok(())
+    }
+
+    pub fn settings(&self) -> DisclosureSettings {
+        self.state.lock().unwrap().settings.clone()
+    }
+
+    pub fn set_settings(&self, settings: DisclosureSettings) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        if state.policy.mandatory && !settings.enabled {
+            return Err(anyhow!("AI-use disclosures are mandatory under the firm's policy"));
+        }
+        if state.policy.mandatory {
+            check_templates(&settings.templates)?;
+        }
+        state.settings = settings;
+        self.persist(&state)
+    }
+
+    pub fn policy(&self) -> DisclosurePolicy {
+        self.state.lock().unwrap().policy.clone()
+    }
+
+    pub fn set_policy(&self, mandatory: bool, user: &str) -> Result<DisclosurePolicy> {
+        let mut state = self.state.lock().unwrap();
+        if mandatory {
+            check_templates(&state.settings.templates)?;
+        }
+        state.policy = DisclosurePolicy {
+            mandatory,
+            updated_by: Some(user.to_string()),
+            updated_at: Some(Utc::now()),
+        };
+        if mandatory {
+            state.settings.enabled = true;
+        }
+        self.persist(&state)?;
+        Ok(state.policy.clone())
+    }
+
+    /// The disclosure for an export, or None when it is left out. `include` is the export's
+    /// own choice, which a mandatory policy overrides.
+    pub fn resolve(&self, jurisdiction: Option<&str>, model: Option<&str>, include: Option<bool>) -> Option<Disclosure> {
+        let state = self.state.lock().unwrap();
+        if !state.policy.mandatory && !include.unwrap_or(state.settings.enabled) {
+            return None;
+        }
+        let jurisdiction = jurisdiction
+            .filter(|j| !j.trim().is_empty())
+            .or(state.settings.default_jurisdiction.as_deref());
+        let template = template_for(&state.settings.templates, jurisdiction);
+        Some(render(&template, jurisdiction, model, Utc::now().date_naive()))
+    }
+}
+
+pub type DisclosureStorage = Arc<Disclosures>;
+type SecurityStorage = Arc<Mutex<SecurityManager>>;
+
+fn audit_change(security: &SecurityStorage, user: &str, change: &str) {
+    let details = HashMap::from([
+        ("changed_by".to_string(), user.to_string()),
+        ("change".to_string(), change.to_string()),
+    ]);
+    let _ = security.lock().unwrap().write_audit_entry(
+        SecurityAction::SettingsChange,
+        "ai_disclosure",
+        ActionOutcome::Success,
+        Some(details),
+    );
+}
+
+/// The user the session signed in as, when they may change the firm's configuration
+fn require_configuration(session_id: &str, sessions: &SessionStorage, security: &SecurityStorage) -> Result<String, String> {
+    let user = authenticated_user(session_id, sessions)?;
+    if !security.lock().unwrap().check_permission(&user, Permission::SystemConfiguration) {
+        return Err("Changing AI-use disclosures needs permission to configure the system".to_string());
+    }
+    Ok(user)
+}
+
+#[tauri::command]
+pub async fn disclosure_get_settings(disclosures: tauri::State<'_, DisclosureStorage>) -> Result<DisclosureSettings, String> {
+    Ok(disclosures.settings())
+}
+
+#[tauri::command]
+pub async fn disclosure_set_settings(
+    session_id: String,
+    settings: DisclosureSettings,
+    disclosures: tauri::State<'_, DisclosureStorage>,
+    sessions: tauri::State<'_, SessionStorage>,
+    security: tauri::State<'_, SecurityStorage>,
+) -> Result<(), String> {
+    let user = require_configuration(&session_id, &sessions, &security)?;
+    disclosures.set_settings(settings).map_err(|e| e.to_string())?;
+    audit_change(&security, &user, "disclosure templates updated");
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn disclosure_get_policy(disclosures: tauri::State<'_, DisclosureStorage>) -> Result<DisclosurePolicy, String> {
+    Ok(disclosures.policy())
+}
+
+#[tauri::command]
+pub async fn disclosure_set_policy(
+    session_id: String,
+    mandatory: bool,
+    disclosures: tauri::State<'_, DisclosureStorage>,
+    sessions: tauri::State<'_, SessionStorage>,
+    security: tauri::State<'_, SecurityStorage>,
+) -> Result<DisclosurePolicy, String> {
+    let user = require_configuration(&session_id, &sessions, &security)?;
+    let policy = disclosures.set_policy(mandatory, &user).map_err(|e| e.to_string())?;
+    let change = if mandatory { "disclosure made mandatory" } else { "disclosure made optional" };
+    audit_change(&security, &user, change);
+    Ok(policy)
+}
+
+/// The block an export for this jurisdiction and model would carry
+#[tauri::command]
+pub async fn disclosure_preview(
+    jurisdiction: Option<String>,
+    model: Option<String>,
+    disclosures: tauri::State<'_, DisclosureStorage>,
+) -> Result<Option<Disclosure>, String> {
+    Ok(disclosures.resolve(jurisdiction.as_deref(), model.as_deref(), None))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_template_lookup_falls_back() {
+        let templates = default_templates();
+        assert_eq!(template_for(&templates, Some("us")).jurisdiction, "US");
+        assert_eq!(template_for(&templates, Some("US-NY")).jurisdiction, "US");
+        assert_eq!(template_for(&templates, Some("JP")).jurisdiction, DEFAULT_TEMPLATE);
+        assert_eq!(template_for(&[], None).jurisdiction, DEFAULT_TEMPLATE);
+
+        let date = NaiveDate::from_ymd_opt(2026, 3, 2).unwrap();
+        let disclosure = render(&template_for(&templates, None), None, Some("llama3"), date);
+        assert!(disclosure.text.contains("BEAR AI Legal Assistant"));
+        assert!(disclosure.text.contains("model: llama3"));
+        assert!(disclosure.text.contains("2026-03-02"));
+        assert!(!disclosure.text.contains('{'));
+    }
+
+    #[test]
+    fn test_mandatory_policy_overrides_opt_out() {
+        let dir = tempfile::tempdir().unwrap();
+        let disclosures = Disclosures::new(dir.path()).unwrap();
+        assert!(disclosures.resolve(Some("UK"), None, Some(false)).is_none());
+        assert_eq!(disclosures.resolve(Some("UK"), None, None).unwrap().title, "Use of AI tools");
+
+        disclosures.set_policy(true, "admin").unwrap();
+        assert!(disclosures.resolve(None, None, Some(false)).is_some());
+        let mut settings = disclosures.settings();
+        settings.enabled = false;
+        assert!(disclosures.set_settings(settings).is_err());
+
+        // A mandatory disclosure cannot be emptied, nor made mandatory while a template is empty
+        let mut settings = disclosures.settings();
+        settings.templates[1].text = "  ".to_string();
+        assert!(disclosures.set_settings(settings.clone()).is_err());
+        disclosures.set_policy(false, "admin").unwrap();
+        disclosures.set_settings(settings).unwrap();
+        assert!(disclosures.set_policy(true, "admin").is_err());
+        let mut settings = disclosures.settings();
+        settings.templates[1].text = default_templates()[1].text.clone();
+        disclosures.set_settings(settings).unwrap();
+        disclosures.set_policy(true, "admin").unwrap();
+
+        let reopened = Disclosures::new(dir.path()).unwrap();
+        assert!(reopened.policy().mandatory);
+    }
+}
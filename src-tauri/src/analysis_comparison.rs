@@ -0,0 +1,336 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::Path;
+use uuid::Uuid;
+
+use crate::document_analyzer::{self, ComplianceStatus, DocumentAnalysis, DocumentType, EntityType, RiskLevel};
+use crate::incremental_analysis::{self, ChangeStatus, FindingChange, FindingKind, SectionFindings};
+use crate::local_api::{authenticated_user, AnalyzerStorage, SessionStorage};
+use crate::review_queue::ReviewQueueStorage;
+
+/// Analysis Comparison for BEAR AI
+/// Compares the analyses of two versions of a document, typically our draft and the
+/// counterparty's redline, and reports what changed legally: risk scores, clauses, compliance
+/// flags and entities such as parties, amounts and dates.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VersionSummary {
+    pub path: String,
+    pub filename: String,
+    pub document_type: Option<DocumentType>,
+    pub risk_score: f32,
+    pub risks: usize,
+    pub clauses: usize,
+    pub compliance_issues: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnalysisComparison {
+    pub id: String,
+    pub generated_at: DateTime<Utc>,
+    pub previous: VersionSummary,
+    pub current: VersionSummary,
+    pub risk_score_change: f32,
+    pub clause_types_added: Vec<String>,
+    pub clause_types_removed: Vec<String>,
+    pub changes: Vec<FindingChange>,
+    pub report: String, // Markdown
+}
+
+fn severity_weight(level: &RiskLevel) -> f32 {
+    match level {
+        RiskLevel::Low => 1.0,
+        RiskLevel::Medium => 2.0,
+        RiskLevel::High => 3.0,
+        RiskLevel::Critical => 4.0,
+    }
+}
+
+/// Sum of severity (1 for low to 4 for critical) times likelihood over a document's risks
+pub fn risk_score(analysis: &DocumentAnalysis) -> f32 {
+    analysis.risks.iter().map(|r| severity_weight(&r.severity) * r.likelihood).sum()
+}
+
+fn summarize(path: &str, analysis: &DocumentAnalysis) -> VersionSummary {
+    VersionSummary {
+        path: path.to_string(),
+        filename: analysis.metadata.filename.clone(),
+        document_type: analysis.metadata.document_type.clone(),
+        risk_score: risk_score(analysis),
+        risks: analysis.risks.len(),
+        clauses: analysis.clauses.len(),
+        compliance_issues: analysis
+            .compliance_flags
+            .iter()
+            .filter(|f| !matches!(f.compliance_status, ComplianceStatus::Compliant | ComplianceStatus::NotApplicable))
+            .count(),
+    }
+}
+
+fn clause_types(analysis: &DocumentAnalysis) -> BTreeSet<String> {
+    analysis.clauses.iter().map(|c| format!("{:?}", c.clause_type)).collect()
+}
+
+// Report order: the changes a reviewer weighs first come first
+const REPORTED_KINDS: [(FindingKind, &str); 4] = [
+    (FindingKind::Risk, "Risks"),
+    (FindingKind::Clause, "Clauses"),
+    (FindingKind::ComplianceFlag, "Compliance"),
+    (FindingKind::Entity, "Parties, amounts and dates"),
+];
+
+/// Compare the analyses of two versions of a document
+pub fn compare_versions(previous_path: &str, previous: &DocumentAnalysis, current_path: &str, current: &DocumentAnalysis) -> AnalysisComparison {
+    let mut changes = incremental_analysis::compare(
+        previous,
+        current,
+        &[SectionFindings::of(previous)],
+        &[SectionFindings::of(current)],
+    );
+    // Legal terms are vocabulary, not obligations; key terms and the summary are restated elsewhere
+    changes.retain(|c| {
+        REPORTED_KINDS.iter().any(|(kind, _)| *kind == c.kind) && !(c.kind == FindingKind::Entity && c.label.starts_with(&format!("{:?}:", EntityType::LegalTerm)))
+    });
+    for change in &mut changes {
+        change.section = None;
+    }
+    changes.sort_by_key(|c| REPORTED_KINDS.iter().position(|(kind, _)| *kind == c.kind));
+
+    let (before, after) = (clause_types(previous), clause_types(current));
+    let previous = summarize(previous_path, previous);
+    let current = summarize(current_path, current);
+    let mut comparison = AnalysisComparison {
+        id: Uuid::new_v4().to_string(),
+        generated_at: Utc::now(),
+        risk_score_change: current.risk_score - previous.risk_score,
+        clause_types_added: after.difference(&before).cloned().collect(),
+        clause_types_removed: before.difference(&after).cloned().collect(),
+        previous,
+        current,
+        changes,
+        report: String::new(),
+    };
+    comparison.report = render_markdown(&comparison);
+    comparison
+}
+
+fn status_label(status: ChangeStatus) -> &'static str {
+    match status {
+        ChangeStatus::New => "Added",
+        ChangeStatus::Removed => "Removed",
+        ChangeStatus::Changed => "Changed",
+    }
+}
+
+/// "What changed legally" between the two versions
+pub fn render_markdown(comparison: &AnalysisComparison) -> String {
+    let (previous, current) = (&comparison.previous, &comparison.current);
+    let mut out = format!(
+        "# What Changed Legally: {} → {}\n\nGenerated {}.\n\n",
+        previous.filename,
+        current.filename,
+        comparison.generated_at.format("%Y-%m-%d %H:%M UTC")
+    );
+
+    let direction = if comparison.risk_score_change > 0.005 {
+        "risk increased"
+    } else if comparison.risk_score_change < -0.005 {
+        "risk decreased"
+    } else {
+        "risk unchanged"
+    };
+    out.push_str(&format!(
+        "## Overview\n\n| | Previous | Current |\n|---|---|---|\n\
+         | Risk score | {:.2} | {:.2} ({:+.2}, {}) |\n| Risks | {} | {} |\n| Clauses | {} | {} |\n| Compliance issues | {} | {} |\n\n",
+        previous.risk_score,
+        current.risk_score,
+        comparison.risk_score_change,
+        direction,
+        previous.risks,
+        current.risks,
+        previous.clauses,
+        current.clauses,
+        previous.compliance_issues,
+        current.compliance_issues
+    ));
+    if !comparison.clause_types_added.is_empty() {
+        out.push_str(&format!("Clause types added: {}.\n\n", comparison.clause_types_added.join(", ")));
+    }
+    if !comparison.clause_types_removed.is_empty() {
+        out.push_str(&format!("Clause types removed: {}.\n\n", comparison.clause_types_removed.join(", ")));
+    }
+    if comparison.changes.is_empty() {
+        out.push_str("No legal changes found.\n");
+        return out;
+    }
+
+    for (kind, heading) in REPORTED_KINDS {
+        let changes: Vec<&FindingChange> = comparison.changes.iter().filter(|c| c.kind == kind).collect();
+        if changes.is_empty() {
+            continue;
+        }
+        out.push_str(&format!("## {}\n\n", heading));
+        for change in changes {
+            out.push_str(&format!("- **{}**: {}\n", status_label(change.status), change.label));
+            if let Some(before) = &change.before {
+                out.push_str(&format!("  - Before: {}\n", before));
+            }
+            if let Some(after) = &change.after {
+                out.push_str(&format!("  - After: {}\n", after));
+            }
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Analyze two versions of a document and report what changed legally between them
+#[tauri::command]
+pub async fn compare_analyses(
+    session_id: String,
+    analyzer: tauri::State<'_, AnalyzerStorage>,
+    review: tauri::State<'_, ReviewQueueStorage>,
+    sessions: tauri::State<'_, SessionStorage>,
+    doc_v1: String,
+    doc_v2: String,
+) -> Result<AnalysisComparison, String> {
+    let user = authenticated_user(&session_id, &sessions)?;
+    let previous = analyzer
+        .analyze_document(Path::new(&doc_v1))
+        .await
+        .map_err(|e| e.to_string())?;
+    let current = analyzer
+        .analyze_document(Path::new(&doc_v2))
+        .await
+        .map_err(|e| e.to_string())?;
+    // The report is reviewed against the version it assesses
+    document_analyzer::submit_analysis_for_review(Path::new(&doc_v2), &analyzer, &review, &user)?;
+    Ok(compare_versions(&doc_v1, &previous, &doc_v2, &current))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document_analyzer::{ClauseType, ComplianceFlag, ContractClause, LegalEntity, RiskAssessment, RiskType};
+
+    fn analysis(filename: &str) -> DocumentAnalysis {
+        DocumentAnalysis {
+            metadata: serde_json::from_value(serde_json::json!({
+                "id": "a", "filename": filename, "file_type": "docx", "size": 1,
+                "uploaded_at": "2024-01-01T00:00:00Z", "processed_at": null, "document_type": "Contract",
+                "language": "en", "page_count": null, "word_count": null,
+                "security_classification": "Confidential"
+            }))
+            .unwrap(),
+            extracted_text: String::new(),
+            entities: Vec::new(),
+            clauses: Vec::new(),
+            risks: Vec::new(),
+            key_terms: Vec::new(),
+            citations: Vec::new(),
+            summary: None,
+            sentiment_analysis: None,
+            compliance_flags: Vec::new(),
+            output_language: None,
+        }
+    }
+
+    fn clause(clause_type: ClauseType, text: &str) -> ContractClause {
+        ContractClause {
+            clause_type,
+            text: text.to_string(),
+            risk_level: RiskLevel::Medium,
+            suggestions: Vec::new(),
+            standard_language: None,
+            page_number: None,
+            section: None,
+        }
+    }
+
+    fn risk(severity: RiskLevel, likelihood: f32) -> RiskAssessment {
+        RiskAssessment {
+            risk_type: RiskType::Financial,
+            description: "Uncapped liability".to_string(),
+            severity,
+            likelihood,
+            impact: String::new(),
+            mitigation_strategies: Vec::new(),
+            related_clauses: Vec::new(),
+        }
+    }
+
+    fn entity(entity_type: EntityType, text: &str) -> LegalEntity {
+        LegalEntity {
+            entity_type,
+            text: text.to_string(),
+            confidence: 0.9,
+            start_pos: 0,
+            end_pos: text.len(),
+            context: text.to_string(),
+            normalized_value: None,
+        }
+    }
+
+    fn flag(status: ComplianceStatus) -> ComplianceFlag {
+        ComplianceFlag {
+            regulation: "GDPR".to_string(),
+            requirement: "Data processing agreement".to_string(),
+            compliance_status: status,
+            recommendation: String::new(),
+            priority: RiskLevel::High,
+        }
+    }
+
+    #[test]
+    fn test_risk_score() {
+        let mut draft = analysis("draft.docx");
+        assert_eq!(risk_score(&draft), 0.0);
+        draft.risks = vec![risk(RiskLevel::Low, 0.5), risk(RiskLevel::Critical, 0.25)];
+        assert!((risk_score(&draft) - 1.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_compare_counterparty_redline() {
+        let mut draft = analysis("draft.docx");
+        draft.clauses = vec![
+            clause(ClauseType::LiabilityClause, "Liability is capped at the fees paid."),
+            clause(ClauseType::GoverningLaw, "Dutch law applies."),
+        ];
+        draft.risks = vec![risk(RiskLevel::Medium, 0.5)];
+        draft.entities = vec![entity(EntityType::MonetaryAmount, "EUR 10,000"), entity(EntityType::LegalTerm, "indemnify")];
+        draft.compliance_flags = vec![flag(ComplianceStatus::Compliant)];
+
+        let mut redline = analysis("redline.docx");
+        redline.clauses = vec![
+            clause(ClauseType::LiabilityClause, "Liability is unlimited."),
+            clause(ClauseType::GoverningLaw, "Dutch law applies."),
+            clause(ClauseType::NonCompete, "The supplier shall not compete for two years."),
+        ];
+        redline.risks = vec![risk(RiskLevel::High, 0.5)];
+        redline.entities = vec![entity(EntityType::MonetaryAmount, "EUR 50,000")];
+        redline.compliance_flags = vec![flag(ComplianceStatus::NonCompliant)];
+
+        let comparison = compare_versions("draft.docx", &draft, "redline.docx", &redline);
+        assert!((comparison.risk_score_change - 0.5).abs() < 1e-6);
+        assert_eq!(comparison.current.compliance_issues, 1);
+        assert_eq!(comparison.clause_types_added, vec!["NonCompete".to_string()]);
+        assert!(comparison.clause_types_removed.is_empty());
+
+        let changes: Vec<(FindingKind, ChangeStatus, &str)> =
+            comparison.changes.iter().map(|c| (c.kind, c.status, c.label.as_str())).collect();
+        assert_eq!(
+            changes,
+            vec![
+                (FindingKind::Risk, ChangeStatus::Changed, "Financial: Uncapped liability"),
+                (FindingKind::Clause, ChangeStatus::Changed, "LiabilityClause"),
+                (FindingKind::Clause, ChangeStatus::New, "NonCompete"),
+                (FindingKind::ComplianceFlag, ChangeStatus::Changed, "GDPR: Data processing agreement"),
+                (FindingKind::Entity, ChangeStatus::Removed, "MonetaryAmount: EUR 10,000"),
+                (FindingKind::Entity, ChangeStatus::New, "MonetaryAmount: EUR 50,000"),
+            ]
+        );
+        assert!(comparison.report.contains("| Risk score | 1.00 | 1.50 (+0.50, risk increased) |"));
+        assert!(comparison.report.contains("  - After: High severity, 50% likelihood\n"));
+        assert!(comparison.report.contains("## Clauses\n\n- **Changed**: LiabilityClause\n  - Before: Liability is capped at the fees paid.\n  - After: Liability is unlimited."));
+    }
+}
@@ -0,0 +1,415 @@
+use anyhow::{anyhow, Result};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use crate::document_analyzer::{DocumentAnalysis, DocumentType, EntityType, KeyTerm, LegalEntity};
+use crate::enterprise_management::BarrierStorage;
+use crate::local_api::{authenticated_user, AnalyzerStorage, SessionStorage};
+use crate::locale_formats;
+use crate::matters::MatterStorage;
+use crate::security::SecurityManager;
+
+/// Anonymized Analytics Export for BEAR AI
+/// Builds shareable datasets (clause frequencies, risk distributions) from analyzed documents.
+/// No free text leaves the export: documents are reduced to categories, dates are generalized
+/// to years, amounts to bands, and any count describing fewer than `k` documents is suppressed.
+/// Optional Laplace noise (epsilon) adds differential privacy on top of the published counts;
+/// cells are suppressed on their noisy counts, so whether a cell appears reveals no exact count.
+/// Key terms are only counted from a fixed legal vocabulary, since extracted terms can be names.
+const DEFAULT_K: usize = 5;
+const SUPPRESSED: &str = "*";
+const MAX_SUPPRESSED_FRACTION: f64 = 0.1;
+const KEY_TERM_VOCABULARY: &[&str] = &[
+    "agreement", "arbitration", "assignment", "audit", "breach", "clause", "compliance", "confidentiality",
+    "consideration", "contract", "covenant", "damages", "default", "delivery", "dispute", "estoppel", "exclusivity",
+    "fee", "fees", "indemnification", "indemnity", "injunction", "insurance", "interest", "invoice", "jurisdiction",
+    "lease", "liability", "license", "licence", "negligence", "notice", "obligation", "payment", "penalty",
+    "remedy", "renewal", "regulation", "rent", "royalty", "severability", "statute", "subcontractor", "termination",
+    "warranty",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnonymizationOptions {
+    pub k: Option<usize>,
+    pub epsilon: Option<f64>, // Laplace noise scale 1/epsilon on each count; None = exact counts
+    pub include_records: bool, // also publish generalized per-document records
+}
+
+/// One document reduced to quasi-identifiers and categories
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct AnonymizedRecord {
+    pub document_type: String,
+    pub language: String,
+    pub period: String,      // year, five-year range, or "*"
+    pub amount_band: String, // band of the largest amount, or "*"
+    pub clause_types: BTreeSet<String>,
+    pub risk_severities: BTreeSet<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CountRow {
+    pub key: String,
+    pub count: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnonymizedDataset {
+    pub generated_at: String,
+    pub k: usize,
+    pub epsilon: Option<f64>,
+    pub document_count: u64,
+    pub document_types: Vec<CountRow>,
+    pub clause_frequencies: Vec<CountRow>,
+    pub risk_distribution: Vec<CountRow>,
+    pub amount_bands: Vec<CountRow>,
+    pub periods: Vec<CountRow>,
+    pub key_terms: Vec<CountRow>,
+    pub records: Vec<AnonymizedRecord>,
+    pub suppressed_cells: usize,
+    pub suppressed_records: usize,
+}
+
+fn document_type_label(document_type: &Option<DocumentType>) -> String {
+    match document_type {
+        // Custom type names are free text and may name the client
+        Some(DocumentType::Other(_)) | None => "Other".to_string(),
+        Some(t) => format!("{:?}", t),
+    }
+}
+
+/// Amount of a monetary entity, from its normalized "EUR 1000.00" form when available
+fn parse_amount(entity: &LegalEntity) -> Option<f64> {
+    match &entity.normalized_value {
+        Some(normalized) => normalized.rsplit(' ').next()?.parse().ok(),
+        None => locale_formats::parse_localized_number(entity.text.trim_start_matches(|c: char| !c.is_ascii_digit())),
+    }
+}
+
+fn amount_band(amount: f64) -> &'static str {
+    match amount {
+        a if a < 10_000.0 => "<10k",
+        a if a < 100_000.0 => "10k-100k",
+        a if a < 1_000_000.0 => "100k-1M",
+        a if a < 10_000_000.0 => "1M-10M",
+        _ => ">=10M",
+    }
+}
+
+/// Coarser band used when a record's class is too small at full detail
+fn coarse_amount_band(band: &str) -> &'static str {
+    match band {
+        "<10k" | "10k-100k" => "<100k",
+        "100k-1M" | "1M-10M" => "100k-10M",
+        SUPPRESSED => SUPPRESSED,
+        _ => ">=10M",
+    }
+}
+
+fn extract_year(text: &str) -> Option<i32> {
+    let re = regex::Regex::new(r"\b(19|20)\d{2}\b").unwrap();
+    re.find(text).and_then(|m| m.as_str().parse().ok())
+}
+
+fn five_year_period(year: &str) -> String {
+    match year.parse::<i32>() {
+        Ok(y) => {
+            let start = y - y.rem_euclid(5);
+            format!("{}-{}", start, start + 4)
+        }
+        Err(_) => SUPPRESSED.to_string(),
+    }
+}
+
+pub fn anonymize_document(analysis: &DocumentAnalysis) -> AnonymizedRecord {
+    let latest_year = analysis
+        .entities
+        .iter()
+        .filter(|e| matches!(e.entity_type, EntityType::Date))
+        .filter_map(|e| extract_year(e.normalized_value.as_deref().unwrap_or(&e.text)))
+        .max();
+    let largest_amount = analysis
+        .entities
+        .iter()
+        .filter(|e| matches!(e.entity_type, EntityType::MonetaryAmount))
+        .filter_map(parse_amount)
+        .fold(None, |max: Option<f64>, a| Some(max.map_or(a, |m| m.max(a))));
+
+    AnonymizedRecord {
+        document_type: document_type_label(&analysis.metadata.document_type),
+        language: analysis.metadata.language.clone(),
+        period: latest_year.map_or(SUPPRESSED.to_string(), |y| y.to_string()),
+        amount_band: largest_amount.map_or(SUPPRESSED, amount_band).to_string(),
+        clause_types: analysis
+            .clauses
+            .iter()
+            .map(|c| match &c.clause_type {
+                crate::document_analyzer::ClauseType::Other(_) => "Other".to_string(),
+                t => format!("{:?}", t),
+            })
+            .collect(),
+        risk_severities: analysis
+            .risks
+            .iter()
+            .map(|r| format!("{:?}/{:?}", r.risk_type, r.severity))
+            .collect(),
+    }
+}
+
+fn quasi_identifiers(record: &AnonymizedRecord) -> (String, String, String, String) {
+    (
+        record.document_type.clone(),
+        record.language.clone(),
+        record.period.clone(),
+        record.amount_band.clone(),
+    )
+}
+
+/// Full-domain generalization: every record is generalized to the same level, starting with
+/// exact values, until at most `MAX_SUPPRESSED_FRACTION` of records sit in quasi-identifier
+/// classes (document type, language, period, amount band) smaller than k. Those records are
+/// dropped. Returns the published records and the number dropped.
+pub fn k_anonymize(records: &[AnonymizedRecord], k: usize) -> (Vec<AnonymizedRecord>, usize) {
+    let generalizers: [fn(&mut AnonymizedRecord); 3] = [
+        |r| {
+            r.period = five_year_period(&r.period);
+            r.amount_band = coarse_amount_band(&r.amount_band).to_string();
+        },
+        |r| {
+            r.period = SUPPRESSED.to_string();
+            r.amount_band = SUPPRESSED.to_string();
+        },
+        |r| r.language = SUPPRESSED.to_string(),
+    ];
+    let budget = (records.len() as f64 * MAX_SUPPRESSED_FRACTION).floor() as usize;
+
+    let mut best: Option<(Vec<AnonymizedRecord>, usize)> = None;
+    let mut current = records.to_vec();
+    for level in 0..=generalizers.len() {
+        if level > 0 {
+            current.iter_mut().for_each(generalizers[level - 1]);
+        }
+        let mut classes: HashMap<_, usize> = HashMap::new();
+        for record in &current {
+            *classes.entry(quasi_identifiers(record)).or_default() += 1;
+        }
+        let kept: Vec<AnonymizedRecord> = current
+            .iter()
+            .filter(|r| classes[&quasi_identifiers(r)] >= k)
+            .cloned()
+            .collect();
+        let dropped = current.len() - kept.len();
+        if !matches!(&best, Some((_, d)) if *d <= dropped) {
+            best = Some((kept, dropped));
+        }
+        if dropped <= budget {
+            break;
+        }
+    }
+
+    let (mut published, dropped) = best.unwrap_or_default();
+    published.sort();
+    (published, dropped)
+}
+
+/// Laplace(0, 1/epsilon) noise, rounded and clamped so counts stay non-negative
+fn noisy_count(count: usize, epsilon: Option<f64>) -> u64 {
+    match epsilon {
+        Some(epsilon) if epsilon > 0.0 => {
+            let u: f64 = rand::thread_rng().gen_range(-0.5..0.5);
+            let noise = -(1.0 / epsilon) * u.signum() * (1.0 - 2.0 * u.abs()).ln();
+            (count as f64 + noise).round().max(0.0) as u64
+        }
+        _ => count as u64,
+    }
+}
+
+/// Turn raw counts into published rows, dropping any cell whose published (noisy) count is
+/// below k. Thresholding the exact count would let a cell's presence tell it apart from k - 1.
+fn publish_counts(counts: BTreeMap<String, usize>, k: usize, epsilon: Option<f64>, suppressed: &mut usize) -> Vec<CountRow> {
+    let mut rows = Vec::new();
+    for (key, count) in counts {
+        let count = noisy_count(count, epsilon);
+        if count < k as u64 {
+            *suppressed += 1;
+            continue;
+        }
+        rows.push(CountRow { key, count });
+    }
+    rows.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.key.cmp(&b.key)));
+    rows
+}
+
+/// Key terms of the vocabulary, counted once per document; any other term is left out
+fn key_term_counts<'a>(documents: impl IntoIterator<Item = &'a [KeyTerm]>) -> BTreeMap<String, usize> {
+    let mut counts = BTreeMap::new();
+    for key_terms in documents {
+        let terms: BTreeSet<String> = key_terms
+            .iter()
+            .map(|t| t.term.trim().to_lowercase())
+            .filter(|t| KEY_TERM_VOCABULARY.contains(&t.as_str()))
+            .collect();
+        for term in terms {
+            *counts.entry(term).or_default() += 1;
+        }
+    }
+    counts
+}
+
+pub fn build_dataset(analyses: &[DocumentAnalysis], options: &AnonymizationOptions) -> Result<AnonymizedDataset> {
+    let k = options.k.unwrap_or(DEFAULT_K).max(2);
+    if analyses.len() < k {
+        return Err(anyhow!(
+            "At least {} documents are needed for an anonymized export (k = {})",
+            k,
+            k
+        ));
+    }
+    let epsilon = options.epsilon;
+    let records: Vec<AnonymizedRecord> = analyses.iter().map(anonymize_document).collect();
+
+    let mut document_types = BTreeMap::new();
+    let mut clauses = BTreeMap::new();
+    let mut risks = BTreeMap::new();
+    let mut amounts = BTreeMap::new();
+    let mut periods = BTreeMap::new();
+    for record in &records {
+        *document_types.entry(record.document_type.clone()).or_default() += 1;
+        *amounts.entry(record.amount_band.clone()).or_default() += 1;
+        *periods.entry(record.period.clone()).or_default() += 1;
+        for clause in &record.clause_types {
+            *clauses.entry(clause.clone()).or_default() += 1;
+        }
+        for risk in &record.risk_severities {
+            *risks.entry(risk.clone()).or_default() += 1;
+        }
+    }
+
+    let mut suppressed_cells = 0;
+    let (published, suppressed_records) = if options.include_records {
+        k_anonymize(&records, k)
+    } else {
+        (Vec::new(), 0)
+    };
+
+    Ok(AnonymizedDataset {
+        generated_at: chrono::Utc::now().to_rfc3339(),
+        k,
+        epsilon,
+        document_count: noisy_count(records.len(), epsilon),
+        document_types: publish_counts(document_types, k, epsilon, &mut suppressed_cells),
+        clause_frequencies: publish_counts(clauses, k, epsilon, &mut suppressed_cells),
+        risk_distribution: publish_counts(risks, k, epsilon, &mut suppressed_cells),
+        amount_bands: publish_counts(amounts, k, epsilon, &mut suppressed_cells),
+        periods: publish_counts(periods, k, epsilon, &mut suppressed_cells),
+        key_terms: publish_counts(key_term_counts(analyses.iter().map(|a| a.key_terms.as_slice())), k, epsilon, &mut suppressed_cells),
+        records: published,
+        suppressed_cells,
+        suppressed_records,
+    })
+}
+
+#[tauri::command]
+pub async fn export_anonymized_analytics(
+    session_id: String,
+    file_paths: Vec<String>,
+    output_path: String,
+    options: AnonymizationOptions,
+    analyzer: tauri::State<'_, AnalyzerStorage>,
+    sessions: tauri::State<'_, SessionStorage>,
+    matters: tauri::State<'_, MatterStorage>,
+    barriers: tauri::State<'_, BarrierStorage>,
+    security: tauri::State<'_, Arc<Mutex<SecurityManager>>>,
+) -> Result<AnonymizedDataset, String> {
+    let user = authenticated_user(&session_id, &sessions)?;
+    barriers.check_export(&security, &user, &matters.export_items(&file_paths))?;
+    let mut analyses = Vec::new();
+    for file_path in &file_paths {
+        let analysis = analyzer
+            .analyze_document(Path::new(file_path))
+            .await
+            .map_err(|e| format!("{}: {}", file_path, e))?;
+        analyses.push(analysis);
+    }
+
+    let dataset = build_dataset(&analyses, &options).map_err(|e| e.to_string())?;
+    let json = serde_json::to_string_pretty(&dataset).map_err(|e| e.to_string())?;
+    std::fs::write(&output_path, json).map_err(|e| e.to_string())?;
+    Ok(dataset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(document_type: &str, period: &str, amount_band: &str) -> AnonymizedRecord {
+        AnonymizedRecord {
+            document_type: document_type.to_string(),
+            language: "en".to_string(),
+            period: period.to_string(),
+            amount_band: amount_band.to_string(),
+            clause_types: BTreeSet::new(),
+            risk_severities: BTreeSet::new(),
+        }
+    }
+
+    #[test]
+    fn test_k_anonymize_generalizes_before_dropping() {
+        let records = vec![
+            record("Lease", "2021", "10k-100k"),
+            record("Lease", "2021", "10k-100k"),
+            record("Lease", "2023", "<10k"),
+            record("NDA", "2019", "1M-10M"),
+        ];
+        let (published, dropped) = k_anonymize(&records, 2);
+
+        // Five-year periods put all three leases in one class; the lone NDA is dropped
+        assert_eq!(dropped, 1);
+        assert_eq!(published.len(), 3);
+        assert!(published
+            .iter()
+            .all(|r| r.document_type == "Lease" && r.period == "2020-2024" && r.amount_band == "<100k"));
+    }
+
+    #[test]
+    fn test_publish_counts_suppresses_small_cells() {
+        let mut counts = BTreeMap::new();
+        counts.insert("PaymentTerms".to_string(), 7);
+        counts.insert("NonCompete".to_string(), 2);
+        let mut suppressed = 0;
+        let rows = publish_counts(counts, 5, None, &mut suppressed);
+
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].key, "PaymentTerms");
+        assert_eq!(rows[0].count, 7);
+        assert_eq!(suppressed, 1);
+        assert_eq!(amount_band(1_250_000.0), "1M-10M");
+
+        // With noise, every published count clears k, whatever the exact count was
+        let mut counts = BTreeMap::new();
+        counts.insert("PaymentTerms".to_string(), 5);
+        for _ in 0..200 {
+            let rows = publish_counts(counts.clone(), 5, Some(0.5), &mut suppressed);
+            assert!(rows.iter().all(|r| r.count >= 5));
+        }
+    }
+
+    #[test]
+    fn test_key_terms_come_from_the_vocabulary() {
+        use crate::document_analyzer::TermCategory;
+        let term = |term: &str| KeyTerm {
+            term: term.to_string(),
+            definition: None,
+            importance: 1.0,
+            frequency: 2,
+            category: TermCategory::Legal,
+        };
+        // A client's name is never counted, however many documents it appears in
+        let terms = vec![term("Indemnity"), term("Vandelay"), term("termination"), term("indemnity")];
+        let counts = key_term_counts([terms.as_slice(), terms.as_slice()]);
+        let counts: Vec<(String, usize)> = counts.into_iter().collect();
+        assert_eq!(counts, vec![("indemnity".to_string(), 2), ("termination".to_string(), 2)]);
+    }
+}
@@ -0,0 +1,300 @@
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use crate::document_analyzer::DocumentAnalysis;
+use crate::llm_manager::{LLMManager, ModelInfo};
+use crate::document_acl::DocumentAclStorage;
+use crate::local_api::{generate_uuid, store_document, validate_session, AnalyzerStorage, Document, DocumentStorage, SessionStorage};
+use crate::pii_detector::{PIIDetectionResult, PIIDetector, PIIMatch};
+use crate::speech_to_text::{SpeechToTextStorage, Transcript, TranscriptionOptions};
+
+/// Audio Evidence Ingestion for BEAR AI
+/// Turns recordings (depositions, interviews, voicemails) into searchable documents:
+/// transcribes with Whisper, separates speaker turns when a diarizing model is installed,
+/// and runs entity and PII analysis on the transcript
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AudioEvidenceReport {
+    pub document: Document,
+    pub transcript: Transcript,
+    pub audio_sha256: String, // hash of the original recording, for chain of custody
+    pub diarized: bool,
+    pub speaker_count: usize,
+    pub analysis: Option<DocumentAnalysis>,
+    pub pii: PIIDetectionResult,
+    pub pii_segments: Vec<PiiSegment>,
+}
+
+/// A transcript segment containing personal data, so reviewers can jump to it in the recording
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PiiSegment {
+    pub segment_index: usize,
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub speaker: u32, // 1-based, as shown in the transcript
+    pub pii_types: Vec<String>,
+}
+
+/// Pick the Whisper model for an evidence recording: the requested one, else an installed
+/// tinydiarize model when speaker turns are wanted, else any installed Whisper model
+fn select_whisper_model(models: &[ModelInfo], requested: Option<&str>, diarize: bool) -> Option<String> {
+    if let Some(requested) = requested {
+        return Some(requested.to_string());
+    }
+
+    let installed: Vec<&ModelInfo> = models
+        .iter()
+        .filter(|m| m.installed && m.id.contains("whisper"))
+        .collect();
+
+    installed
+        .iter()
+        .find(|m| diarize && m.id.contains("tdrz"))
+        .or_else(|| installed.first())
+        .map(|m| m.id.clone())
+}
+
+/// Map PII matches in `text_with_timestamps` (one line per non-empty segment) back to segments
+fn pii_segments(transcript: &Transcript, timestamped_text: &str, matches: &[PIIMatch]) -> Vec<PiiSegment> {
+    let segments: Vec<_> = transcript
+        .segments
+        .iter()
+        .enumerate()
+        .filter(|(_, s)| !s.text.trim().is_empty())
+        .collect();
+
+    let mut by_line: Vec<BTreeSet<String>> = vec![BTreeSet::new(); segments.len()];
+    for pii in matches {
+        let offset = pii.start.min(timestamped_text.len());
+        let line = timestamped_text.as_bytes()[..offset].iter().filter(|&&b| b == b'\n').count();
+        let pii_type = serde_json::to_value(&pii.pii_type)
+            .ok()
+            .and_then(|v| v.as_str().map(String::from))
+            .unwrap_or_default();
+        if let Some(types) = by_line.get_mut(line) {
+            types.insert(pii_type);
+        }
+    }
+
+    segments
+        .into_iter()
+        .zip(by_line)
+        .filter(|(_, types)| !types.is_empty())
+        .map(|((segment_index, segment), types)| PiiSegment {
+            segment_index,
+            start_ms: segment.start_ms,
+            end_ms: segment.end_ms,
+            speaker: segment.speaker_turn + 1,
+            pii_types: types.into_iter().collect(),
+        })
+        .collect()
+}
+
+fn sha256_file(path: &Path) -> Result<String> {
+    use sha2::{Digest, Sha256};
+    let mut file = std::fs::File::open(path).with_context(|| format!("Cannot open {}", path.display()))?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Transcribe a recording and analyze the transcript. The returned document is not yet
+/// registered in the workspace.
+pub async fn ingest_audio(
+    manager: &LLMManager,
+    stt: &SpeechToTextStorage,
+    analyzer: &AnalyzerStorage,
+    audio_path: &Path,
+    model_id: Option<&str>,
+    language: Option<String>,
+    diarize: bool,
+) -> Result<AudioEvidenceReport> {
+    let audio_sha256 = sha256_file(audio_path)?;
+
+    let models = manager.list_models().await?;
+    let model_id = select_whisper_model(&models, model_id, diarize)
+        .ok_or_else(|| anyhow!("No Whisper model is installed; download one from the model registry"))?;
+
+    let options = TranscriptionOptions {
+        language,
+        ..Default::default()
+    };
+    let transcript = stt.transcribe_file(&model_id, audio_path, &options).await?;
+    let text_path = stt.save_transcript_text(&transcript).await?;
+    let timestamped_text = transcript.text_with_timestamps();
+
+    // Entity extraction is best effort; the transcript is kept even if analysis fails
+    let analysis = match analyzer.analyze_document(&text_path).await {
+        Ok(analysis) => Some(analysis),
+        Err(e) => {
+            log::warn!("Analysis of transcript {} failed: {}", transcript.id, e);
+            None
+        }
+    };
+
+    let pii = PIIDetector::new(None).detect_pii(&timestamped_text);
+    let pii_segments = pii_segments(&transcript, &timestamped_text, &pii.matches);
+
+    let speakers: BTreeSet<u32> = transcript.segments.iter().map(|s| s.speaker_turn).collect();
+    let diarized = model_id.contains("tdrz");
+
+    let file_name = audio_path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "recording".to_string());
+    let mut tags = vec![
+        "audio-evidence".to_string(),
+        format!("transcript:{}", transcript.id),
+        format!("sha256:{}", audio_sha256),
+    ];
+    if pii.has_pii {
+        tags.push("contains-pii".to_string());
+    }
+
+    let document = Document {
+        id: generate_uuid(),
+        name: format!("{} (transcript)", file_name),
+        category: "evidence".to_string(),
+        file_size: std::fs::metadata(&text_path).map(|m| m.len()).unwrap_or(0),
+        created_at: chrono::Utc::now().to_rfc3339(),
+        tags,
+        status: if analysis.is_some() { "analyzed" } else { "uploaded" }.to_string(),
+        content_type: "text/plain".to_string(),
+    };
+
+    Ok(AudioEvidenceReport {
+        document,
+        audio_sha256,
+        diarized,
+        speaker_count: speakers.len(),
+        analysis,
+        pii,
+        pii_segments,
+        transcript,
+    })
+}
+
+#[tauri::command]
+pub async fn ingest_audio_evidence(
+    session_id: String,
+    audio_path: String,
+    model_id: Option<String>,
+    language: Option<String>,
+    diarize: Option<bool>,
+    category: Option<String>,
+    manager: tauri::State<'_, std::sync::Arc<LLMManager>>,
+    stt: tauri::State<'_, SpeechToTextStorage>,
+    analyzer: tauri::State<'_, AnalyzerStorage>,
+    sessions: tauri::State<'_, SessionStorage>,
+    document_storage: tauri::State<'_, DocumentStorage>,
+    workspace_stats: tauri::State<'_, crate::workspace_stats::WorkspaceStatsStorage>,
+    acl: tauri::State<'_, DocumentAclStorage>,
+) -> Result<AudioEvidenceReport, String> {
+    if !validate_session(&session_id, &sessions)? {
+        return Err("Unauthorized".to_string());
+    }
+
+    let workspace = acl.workspace_key(&session_id);
+    let ingest = ingest_audio(
+        &manager,
+        &stt,
+        &analyzer,
+        Path::new(&audio_path),
+        model_id.as_deref(),
+        language,
+        diarize.unwrap_or(true),
+    );
+    let mut report = crate::workspace_stats::in_workspace(&workspace, ingest)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if let Some(category) = category {
+        report.document.category = category;
+    }
+
+    store_document(report.document.clone(), &session_id, &sessions, &document_storage, &acl)?;
+    workspace_stats.record_analysis(&workspace, "audio_evidence");
+    workspace_stats.invalidate();
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pii_detector::PIIType;
+    use crate::speech_to_text::TranscriptSegment;
+    use std::path::PathBuf;
+
+    fn model(id: &str, installed: bool) -> ModelInfo {
+        ModelInfo {
+            id: id.to_string(),
+            name: id.to_string(),
+            description: String::new(),
+            size: 0,
+            quantization: String::new(),
+            format: "GGML".to_string(),
+            path: PathBuf::new(),
+            download_url: None,
+            legal_optimized: false,
+            installed,
+            version: "1".to_string(),
+            created_at: 0,
+            license: None,
+        }
+    }
+
+    #[test]
+    fn test_select_whisper_model_prefers_diarizing_model() {
+        let models = vec![
+            model("whisper-base-en", true),
+            model("whisper-small-en-tdrz", true),
+            model("whisper-large-v3-turbo", false),
+        ];
+        assert_eq!(select_whisper_model(&models, None, true).as_deref(), Some("whisper-small-en-tdrz"));
+        assert_eq!(select_whisper_model(&models, None, false).as_deref(), Some("whisper-base-en"));
+        assert_eq!(select_whisper_model(&[model("phi3", true)], None, true), None);
+    }
+
+    #[test]
+    fn test_pii_segments_map_to_transcript_lines() {
+        let segment = |start_ms, text: &str, speaker_turn| TranscriptSegment {
+            start_ms,
+            end_ms: start_ms + 1000,
+            text: text.to_string(),
+            speaker_turn,
+        };
+        let transcript = Transcript {
+            id: "t1".to_string(),
+            model_id: "whisper-small-en-tdrz".to_string(),
+            language: Some("en".to_string()),
+            source: "file".to_string(),
+            duration_ms: 3000,
+            segments: vec![
+                segment(0, "State your email.", 0),
+                segment(1000, " ", 0),
+                segment(2000, "It is jane@example.com.", 1),
+            ],
+            created_at: chrono::Utc::now().to_rfc3339(),
+        };
+        let text = transcript.text_with_timestamps();
+        let start = text.find("jane@").unwrap();
+        let pii = PIIMatch {
+            pii_type: PIIType::Email,
+            text: "jane@example.com".to_string(),
+            start,
+            end: start + 16,
+            confidence: 0.9,
+            hash: String::new(),
+            is_legal_privileged: None,
+            country: None,
+        };
+
+        let segments = pii_segments(&transcript, &text, &[pii]);
+        assert_eq!(segments.len(), 1);
+        assert_eq!(segments[0].segment_index, 2);
+        assert_eq!(segments[0].speaker, 2);
+        assert_eq!(segments[0].pii_types, vec!["email".to_string()]);
+    }
+}
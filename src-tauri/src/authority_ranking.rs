@@ -0,0 +1,318 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::case_analytics::labelled_date;
+use crate::nemotron_rag::{LegalDocument, PrecedentialValue, RAGChunk};
+
+/// Authority Weighting for BEAR AI
+/// Ranking boosts for the weight of an authority rather than only its relevance: binding over
+/// persuasive precedent, a supreme court over an appellate or trial court, and recent documents
+/// over old ones. Chunks carry the precedential value, court and decision date of their document
+/// in metadata, recorded at indexing; the boosts applied are kept on each retrieved chunk.
+// Chunk metadata keys recorded at indexing
+const PRECEDENTIAL_VALUE_KEY: &str = "precedential_value";
+const DECISION_DATE_KEY: &str = "decision_date";
+const DECISION_DATE_LABELS: &str = "decided|date of decision|judgment date|dated";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CourtLevel {
+    Supreme,
+    Appellate,
+    Trial,
+}
+
+/// Added to a chunk's relevance score; recency decays from `recency` for a document dated today
+/// by half every `recency_half_life_days`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuthorityWeights {
+    pub binding: f32,
+    pub persuasive: f32,
+    pub not_precedential: f32,
+    pub supreme_court: f32,
+    pub appellate_court: f32,
+    pub trial_court: f32,
+    pub recency: f32,
+    pub recency_half_life_days: f32,
+}
+
+impl Default for AuthorityWeights {
+    fn default() -> Self {
+        Self {
+            binding: 0.2,
+            persuasive: 0.05,
+            not_precedential: 0.0,
+            supreme_court: 0.1,
+            appellate_court: 0.05,
+            trial_court: 0.0,
+            recency: 0.05,
+            recency_half_life_days: 3650.0, // old precedent still binds; recency only breaks ties
+        }
+    }
+}
+
+impl AuthorityWeights {
+    pub fn validate(&self) -> Result<()> {
+        let weights = [
+            self.binding,
+            self.persuasive,
+            self.not_precedential,
+            self.supreme_court,
+            self.appellate_court,
+            self.trial_court,
+            self.recency,
+        ];
+        if weights.iter().any(|w| !w.is_finite() || *w < 0.0) {
+            return Err(anyhow!("Authority weights must be zero or positive"));
+        }
+        if !self.recency_half_life_days.is_finite() || self.recency_half_life_days <= 0.0 {
+            return Err(anyhow!("The recency half-life must be positive"));
+        }
+        Ok(())
+    }
+
+    /// Whether any weight is set; all zero leaves the ranking alone
+    pub fn is_enabled(&self) -> bool {
+        [
+            self.binding,
+            self.persuasive,
+            self.not_precedential,
+            self.supreme_court,
+            self.appellate_court,
+            self.trial_court,
+            self.recency,
+        ]
+        .iter()
+        .any(|w| *w > 0.0)
+    }
+
+    fn precedential(&self, value: &PrecedentialValue) -> f32 {
+        match value {
+            PrecedentialValue::Binding => self.binding,
+            PrecedentialValue::Persuasive => self.persuasive,
+            PrecedentialValue::NotPrecedential => self.not_precedential,
+        }
+    }
+
+    fn court(&self, level: CourtLevel) -> f32 {
+        match level {
+            CourtLevel::Supreme => self.supreme_court,
+            CourtLevel::Appellate => self.appellate_court,
+            CourtLevel::Trial => self.trial_court,
+        }
+    }
+}
+
+/// The boosts a retrieved chunk received, and what they were based on
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuthorityBoost {
+    pub precedential_value: Option<PrecedentialValue>,
+    pub court_level: Option<CourtLevel>,
+    pub age_days: Option<i64>,
+    pub precedential: f32,
+    pub court: f32,
+    pub recency: f32,
+    pub total: f32,
+}
+
+/// The level of a court from its name; None when the name does not say. "Appellate Division"
+/// is checked first as New York calls its trial courts supreme courts.
+pub fn court_level(court: &str) -> Option<CourtLevel> {
+    let court = court.to_lowercase();
+    let has = |words: &[&str]| words.iter().any(|w| court.contains(w));
+    if has(&["appellate division", "appeal", "appellate", " cir.", "circuit court of appeals"]) {
+        Some(CourtLevel::Appellate)
+    } else if has(&["supreme court of new york", "supreme court of the state of new york", "new york supreme court"]) {
+        Some(CourtLevel::Trial)
+    } else if has(&["supreme court", "house of lords"]) {
+        Some(CourtLevel::Supreme)
+    } else if has(&["district court", "superior court", "circuit court", "county court", "trial", "magistrate", "bankruptcy", "chancery", "common pleas", "tribunal"]) {
+        Some(CourtLevel::Trial)
+    } else {
+        None
+    }
+}
+
+/// Chunk metadata recording what authority weighting needs to know about `document`
+pub fn chunk_metadata(document: &LegalDocument) -> HashMap<String, String> {
+    let mut metadata = HashMap::from([(
+        PRECEDENTIAL_VALUE_KEY.to_string(),
+        format!("{:?}", document.metadata.precedential_value),
+    )]);
+    // The date the document states, not when its file was last written
+    if let Some(date) = labelled_date(&document.content, DECISION_DATE_LABELS) {
+        metadata.insert(DECISION_DATE_KEY.to_string(), date.format("%Y-%m-%d").to_string());
+    }
+    if let Some(court) = document.metadata.court.as_ref().filter(|c| !c.trim().is_empty()) {
+        metadata.insert("court".to_string(), court.clone());
+    }
+    metadata
+}
+
+fn precedential_value(metadata: &HashMap<String, String>) -> Option<PrecedentialValue> {
+    match metadata.get(PRECEDENTIAL_VALUE_KEY)?.as_str() {
+        "Binding" => Some(PrecedentialValue::Binding),
+        "Persuasive" => Some(PrecedentialValue::Persuasive),
+        "NotPrecedential" => Some(PrecedentialValue::NotPrecedential),
+        _ => None,
+    }
+}
+
+/// Filing date of a filing, else the decision date of its document. Chunks indexed before the
+/// decision date was recorded only have the file's modification time, which says nothing about
+/// the authority's age; their own text is searched instead.
+fn document_date(chunk: &RAGChunk) -> Option<NaiveDate> {
+    ["filing_date", DECISION_DATE_KEY]
+        .iter()
+        .filter_map(|key| chunk.metadata.get(*key))
+        .find_map(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+        .or_else(|| labelled_date(&chunk.content, DECISION_DATE_LABELS))
+}
+
+pub fn authority_boost(chunk: &RAGChunk, weights: &AuthorityWeights, now: DateTime<Utc>) -> AuthorityBoost {
+    let precedential_value = precedential_value(&chunk.metadata);
+    let court_level = chunk.metadata.get("court").and_then(|c| court_level(c));
+    let age_days = document_date(chunk).map(|date| (now.date_naive() - date).num_days().max(0));
+
+    let precedential = precedential_value.as_ref().map(|v| weights.precedential(v)).unwrap_or(0.0);
+    let court = court_level.map(|l| weights.court(l)).unwrap_or(0.0);
+    let recency = age_days
+        .map(|days| weights.recency * 0.5f32.powf(days as f32 / weights.recency_half_life_days))
+        .unwrap_or(0.0);
+    AuthorityBoost {
+        precedential_value,
+        court_level,
+        age_days,
+        precedential,
+        court,
+        recency,
+        total: precedential + court + recency,
+    }
+}
+
+/// Record the authority boost on every chunk and reorder them by relevance plus boost.
+/// Relevance is the rerank score when there is one, otherwise the chunk's confidence; chunks
+/// with equal scores keep their order.
+pub fn apply_authority_weights(chunks: Vec<RAGChunk>, weights: &AuthorityWeights, now: DateTime<Utc>) -> Vec<RAGChunk> {
+    let mut scored: Vec<(f32, RAGChunk)> = chunks
+        .into_iter()
+        .map(|mut chunk| {
+            let boost = authority_boost(&chunk, weights, now);
+            let relevance = chunk.rerank.as_ref().map(|r| r.score).unwrap_or(chunk.confidence);
+            let score = relevance + boost.total;
+            chunk.authority = Some(boost);
+            (score, chunk)
+        })
+        .collect();
+    scored.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+    scored.into_iter().map(|(_, chunk)| chunk).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn chunk(id: &str, confidence: f32, metadata: &[(&str, &str)]) -> RAGChunk {
+        RAGChunk {
+            id: id.to_string(),
+            document_id: id.to_string(),
+            content: String::new(),
+            embedding: Vec::new(),
+            chunk_index: 0,
+            tokens: 0,
+            overlap: 0,
+            legal_concepts: Vec::new(),
+            cited_authorities: Vec::new(),
+            confidence,
+            temporal_relevance: 1.0,
+            created_at: Utc::now(),
+            metadata: metadata.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+            rerank: None,
+            authority: None,
+        }
+    }
+
+    #[test]
+    fn test_classifies_court_levels() {
+        assert_eq!(court_level("Supreme Court of the United States"), Some(CourtLevel::Supreme));
+        assert_eq!(court_level("United States Court of Appeals for the Ninth Circuit"), Some(CourtLevel::Appellate));
+        assert_eq!(court_level("9th Cir."), Some(CourtLevel::Appellate));
+        assert_eq!(court_level("Supreme Court, Appellate Division, First Department"), Some(CourtLevel::Appellate));
+        assert_eq!(court_level("SUPREME COURT OF THE STATE OF NEW YORK"), Some(CourtLevel::Trial));
+        assert_eq!(court_level("United States District Court, Southern District of New York"), Some(CourtLevel::Trial));
+        assert_eq!(court_level("Circuit Court of Cook County"), Some(CourtLevel::Trial));
+        assert_eq!(court_level("Office of the Registrar"), None);
+    }
+
+    #[test]
+    fn test_boosts_binding_higher_court_and_recent_authority() {
+        let now = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+        let weights = AuthorityWeights::default();
+        let chunks = vec![
+            chunk("unpublished", 0.8, &[("precedential_value", "NotPrecedential"), ("court", "District Court for Oregon")]),
+            chunk("binding", 0.7, &[("precedential_value", "Binding"), ("court", "Ninth Circuit Court of Appeals"), ("decision_date", "2016-01-01")]),
+            chunk("supreme", 0.6, &[("precedential_value", "Binding"), ("court", "Supreme Court of the United States"), ("decision_date", "1990-06-01")]),
+            chunk("plain", 0.75, &[]),
+        ];
+
+        let ranked = apply_authority_weights(chunks, &weights, now);
+        let ids: Vec<&str> = ranked.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(ids, vec!["binding", "supreme", "unpublished", "plain"]);
+
+        let binding = ranked[0].authority.as_ref().unwrap();
+        assert_eq!(binding.court_level, Some(CourtLevel::Appellate));
+        assert_eq!(binding.age_days, Some(3653));
+        assert!((binding.recency - 0.025).abs() < 0.001, "{}", binding.recency);
+        assert!((binding.total - (0.2 + 0.05 + binding.recency)).abs() < 1e-6);
+        assert_eq!(ranked[3].authority.as_ref().unwrap().total, 0.0);
+
+        // A filing's own date counts over the decision it was filed in
+        let filed = chunk("filed", 0.5, &[("filing_date", "2025-12-31"), ("decision_date", "2000-01-01")]);
+        assert_eq!(authority_boost(&filed, &weights, now).age_days, Some(1));
+
+        let zero = AuthorityWeights { binding: 0.0, persuasive: 0.0, supreme_court: 0.0, appellate_court: 0.0, recency: 0.0, ..weights.clone() };
+        assert!(!zero.is_enabled());
+        assert!(AuthorityWeights { recency_half_life_days: 0.0, ..weights }.validate().is_err());
+    }
+
+    #[test]
+    fn test_dates_authority_by_its_decision_not_its_file() {
+        let now = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+        let weights = AuthorityWeights::default();
+
+        let mut document: LegalDocument = serde_json::from_value(serde_json::json!({
+            "id": "doc-1",
+            "title": "Smith v. Jones",
+            "content": "SMITH v. JONES\nDecided: March 1, 2023\nThe appeal is dismissed.",
+            "jurisdiction": "US",
+            "document_type": "CaseLaw",
+            "last_updated": "2025-12-30T00:00:00Z",
+            "citations": [],
+            "metadata": {
+                "court": null,
+                "judge": null,
+                "parties": [],
+                "topics": [],
+                "precedential_value": "Binding",
+                "confidence": 1.0
+            }
+        }))
+        .unwrap();
+        let metadata = chunk_metadata(&document);
+        assert_eq!(metadata.get("decision_date").map(String::as_str), Some("2023-03-01"));
+        assert!(!metadata.contains_key("document_date"));
+
+        document.content = "A contract with no date on it.".to_string();
+        assert!(!chunk_metadata(&document).contains_key("decision_date"));
+
+        // Chunks indexed before the decision date was recorded carry the file's date instead
+        let mut legacy = chunk("legacy", 0.5, &[("document_date", "2025-12-30")]);
+        assert_eq!(authority_boost(&legacy, &weights, now).age_days, None);
+        legacy.content = "Judgment date: 1 June 2020. The claim fails.".to_string();
+        assert_eq!(authority_boost(&legacy, &weights, now).age_days, Some(2040));
+    }
+}
@@ -0,0 +1,803 @@
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::net::{IpAddr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use uuid::Uuid;
+
+use crate::local_api::{perform_file_analysis, AnalyzerStorage, ANALYSIS_TYPES};
+use crate::review_queue::{self, ReviewItem, ReviewQueueStorage, ReviewStatus, Submission, WorkProductKind};
+
+/// Automation API for BEAR AI
+/// A small polling-friendly REST surface for no-code tools such as Zapier and Make. Clients
+/// authenticate with scoped API keys; analyses are listed by an increasing cursor so a poller
+/// only ever sees each finished analysis once. A key sees only the analyses it submitted, and
+/// a result only once an attorney approved it in review; until then it waits without a cursor.
+/// Documents are only fetched from public addresses, without following redirects, so a key
+/// cannot make the app reach into the firm's network.
+const DEFAULT_ADDRESS: &str = "127.0.0.1:8787";
+const MAX_FEED_RECORDS: usize = 1000;
+const MAX_REQUEST_BYTES: usize = 1024 * 1024;
+const MAX_DOWNLOAD_BYTES: usize = 50 * 1024 * 1024;
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(120);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+const DEFAULT_PAGE_SIZE: usize = 50;
+const MAX_PAGE_SIZE: usize = 200;
+const REVIEW_SOURCE: &str = "automation_api";
+const REVIEW_PREFIX: &str = "automation:";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ApiScope {
+    #[serde(rename = "analyses:read")]
+    AnalysesRead,
+    #[serde(rename = "analyses:write")]
+    AnalysesWrite,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutomationApiKey {
+    pub id: String,
+    pub name: String,
+    pub key_prefix: String, // first characters of the key, for display
+    key_hash: String,
+    pub scopes: Vec<ApiScope>,
+    pub created_at: String,
+    pub last_used_at: Option<String>,
+}
+
+/// Returned once at creation; only the hash of `secret` is stored
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreatedApiKey {
+    pub key: AutomationApiKey,
+    pub secret: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AnalysisStatus {
+    Pending,
+    PendingReview, // finished, held back until a reviewer approves the result
+    Completed,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutomationAnalysis {
+    pub id: String,
+    pub cursor: Option<u64>, // assigned when the analysis finishes
+    pub status: AnalysisStatus,
+    pub analysis_type: String,
+    pub document_id: Option<String>,
+    pub document_name: String,
+    pub source_url: Option<String>,
+    pub created_at: String,
+    pub completed_at: Option<String>,
+    pub result: Option<serde_json::Value>,
+    pub error: Option<String>,
+    #[serde(default)]
+    pub submitted_by: Option<String>, // id of the API key that submitted it; None for the app's own
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnalysisPage {
+    pub data: Vec<AutomationAnalysis>,
+    pub cursor: u64, // pass back as `since` on the next poll
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutomationApiStatus {
+    pub running: bool,
+    pub address: Option<String>,
+    pub key_count: usize,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct AutomationState {
+    keys: Vec<AutomationApiKey>,
+    analyses: VecDeque<AutomationAnalysis>,
+    last_cursor: u64,
+}
+
+fn hash_key(secret: &str) -> String {
+    hex::encode(Sha256::digest(secret.as_bytes()))
+}
+
+/// Finished analyses `key_id` submitted after `since`, oldest first, so a poller can page forward
+fn analyses_since(analyses: &VecDeque<AutomationAnalysis>, key_id: &str, since: u64, limit: usize) -> AnalysisPage {
+    let mut data: Vec<AutomationAnalysis> = analyses
+        .iter()
+        .filter(|a| a.submitted_by.as_deref() == Some(key_id))
+        .filter(|a| a.cursor.map_or(false, |c| c > since))
+        .cloned()
+        .collect();
+    data.sort_by_key(|a| a.cursor);
+    data.truncate(limit);
+    let cursor = data.last().and_then(|a| a.cursor).unwrap_or(since);
+    AnalysisPage { data, cursor }
+}
+
+pub struct AutomationApi {
+    path: PathBuf,
+    state: Mutex<AutomationState>,
+    analyzer: AnalyzerStorage,
+    review: ReviewQueueStorage,
+    server: Mutex<Option<(String, tauri::async_runtime::JoinHandle<()>)>>,
+}
+
+impl AutomationApi {
+    pub fn new(app_data_dir: &Path, analyzer: AnalyzerStorage, review: ReviewQueueStorage) -> Result<Self> {
+        let path = app_data_dir.join("automation_api.json");
+        let state = if path.exists() {
+            serde_json::from_str(&fs::read_to_string(&path)?)?
+        } else {
+            AutomationState::default()
+        };
+
+        Ok(Self {
+            path,
+            state: Mutex::new(state),
+            analyzer,
+            review,
+            server: Mutex::new(None),
+        })
+    }
+
+    fn persist(&self, state: &AutomationState) -> Result<()> {
+        fs::write(&self.path, serde_json::to_string_pretty(state)?)?;
+        Ok(())
+    }
+
+    pub fn create_key(&self, name: &str, scopes: Vec<ApiScope>) -> Result<CreatedApiKey> {
+        if scopes.is_empty() {
+            return Err(anyhow!("At least one scope is required"));
+        }
+
+        let secret = format!("bear_{}", hex::encode(rand::random::<[u8; 24]>()));
+        let key = AutomationApiKey {
+            id: Uuid::new_v4().to_string(),
+            name: name.to_string(),
+            key_prefix: secret[..12].to_string(),
+            key_hash: hash_key(&secret),
+            scopes,
+            created_at: chrono::Utc::now().to_rfc3339(),
+            last_used_at: None,
+        };
+
+        let mut state = self.state.lock().unwrap();
+        state.keys.push(key.clone());
+        self.persist(&state)?;
+        Ok(CreatedApiKey { key, secret })
+    }
+
+    pub fn revoke_key(&self, key_id: &str) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        let before = state.keys.len();
+        state.keys.retain(|k| k.id != key_id);
+        if state.keys.len() == before {
+            return Err(anyhow!("API key {} not found", key_id));
+        }
+        self.persist(&state)
+    }
+
+    pub fn list_keys(&self) -> Vec<AutomationApiKey> {
+        self.state.lock().unwrap().keys.clone()
+    }
+
+    /// Resolve a presented key and check that it carries `scope`
+    fn authorize(&self, secret: &str, scope: Option<ApiScope>) -> Result<AutomationApiKey, (u16, String)> {
+        let hash = hash_key(secret);
+        let mut state = self.state.lock().unwrap();
+        let key = state
+            .keys
+            .iter_mut()
+            .find(|k| k.key_hash == hash)
+            .ok_or_else(|| (401, "Invalid API key".to_string()))?;
+        if let Some(scope) = scope {
+            if !key.scopes.contains(&scope) {
+                return Err((403, format!("API key lacks the {} scope", scope_name(scope))));
+            }
+        }
+        key.last_used_at = Some(chrono::Utc::now().to_rfc3339());
+        Ok(key.clone())
+    }
+
+    /// Add an analysis to the feed; finished analyses get the next cursor once released
+    pub fn record(&self, mut analysis: AutomationAnalysis) {
+        let mut state = self.state.lock().unwrap();
+        if !matches!(analysis.status, AnalysisStatus::Pending | AnalysisStatus::PendingReview) {
+            state.last_cursor += 1;
+            analysis.cursor = Some(state.last_cursor);
+        }
+        match state.analyses.iter_mut().find(|a| a.id == analysis.id) {
+            Some(existing) => *existing = analysis,
+            None => state.analyses.push_back(analysis),
+        }
+        while state.analyses.len() > MAX_FEED_RECORDS {
+            state.analyses.pop_front();
+        }
+        if let Err(e) = self.persist(&state) {
+            log::warn!("Failed to save automation analysis feed: {}", e);
+        }
+    }
+
+    pub fn analyses_since(&self, key_id: &str, since: u64, limit: usize) -> AnalysisPage {
+        analyses_since(&self.state.lock().unwrap().analyses, key_id, since, limit)
+    }
+
+    /// An analysis `key_id` submitted; a result still in review is withheld
+    pub fn get_analysis(&self, key_id: &str, id: &str) -> Option<AutomationAnalysis> {
+        let mut analysis = self
+            .state
+            .lock()
+            .unwrap()
+            .analyses
+            .iter()
+            .find(|a| a.id == id && a.submitted_by.as_deref() == Some(key_id))
+            .cloned()?;
+        if analysis.status == AnalysisStatus::PendingReview {
+            analysis.result = None;
+        }
+        Some(analysis)
+    }
+
+    /// Queue a finished result for attorney review, and release it at once if the same result
+    /// was approved before
+    fn submit_for_review(&self, analysis: &mut AutomationAnalysis, result: serde_json::Value) -> Result<()> {
+        let submission = Submission {
+            kind: WorkProductKind::Analysis,
+            title: format!("Automation analysis of {}", analysis.document_name),
+            source: REVIEW_SOURCE.to_string(),
+            reference: format!("{}{}", REVIEW_PREFIX, analysis.id),
+            path: None,
+            matter_id: None,
+            content_sha256: review_queue::json_sha256(&result),
+        };
+        let released = self
+            .review
+            .check_released(&submission.reference, Some(submission.kind), &submission.content_sha256)
+            .is_ok();
+        if !released {
+            let submitted_by = format!("automation key {}", analysis.submitted_by.as_deref().unwrap_or_default());
+            self.review.submit(submission, &submitted_by)?;
+        }
+        analysis.status = if released { AnalysisStatus::Completed } else { AnalysisStatus::PendingReview };
+        analysis.result = Some(result);
+        Ok(())
+    }
+
+    /// Release or withdraw a result held for review once a reviewer decided on it
+    pub fn review_decided(&self, item: &ReviewItem) {
+        let Some(id) = item.reference.strip_prefix(REVIEW_PREFIX).filter(|_| item.source == REVIEW_SOURCE) else {
+            return;
+        };
+        let held = self
+            .state
+            .lock()
+            .unwrap()
+            .analyses
+            .iter()
+            .find(|a| a.id == id && a.status == AnalysisStatus::PendingReview)
+            .cloned();
+        let Some(mut analysis) = held else {
+            return;
+        };
+        match item.status {
+            ReviewStatus::Approved => analysis.status = AnalysisStatus::Completed,
+            ReviewStatus::Rejected => {
+                analysis.status = AnalysisStatus::Failed;
+                analysis.result = None;
+                analysis.error = Some("The result was rejected in attorney review".to_string());
+            }
+            ReviewStatus::Pending => return,
+        }
+        self.record(analysis);
+    }
+
+    /// Queue analysis of a document at `url` for the key `key_id`; the result shows up in its
+    /// feed when done
+    pub fn submit_url(self: &Arc<Self>, key_id: &str, url: &str, analysis_type: &str) -> Result<AutomationAnalysis> {
+        let parsed = reqwest::Url::parse(url).map_err(|e| anyhow!("Invalid document URL: {}", e))?;
+        if !matches!(parsed.scheme(), "http" | "https") {
+            return Err(anyhow!("Document URL must use http or https"));
+        }
+        if !ANALYSIS_TYPES.contains(&analysis_type) {
+            return Err(anyhow!("Unknown analysis type: {}", analysis_type));
+        }
+
+        let document_name = parsed
+            .path_segments()
+            .and_then(|mut s| s.next_back())
+            .filter(|s| !s.is_empty())
+            .unwrap_or("document")
+            .to_string();
+        let pending = AutomationAnalysis {
+            id: Uuid::new_v4().to_string(),
+            cursor: None,
+            status: AnalysisStatus::Pending,
+            analysis_type: analysis_type.to_string(),
+            document_id: None,
+            document_name,
+            source_url: Some(url.to_string()),
+            created_at: chrono::Utc::now().to_rfc3339(),
+            completed_at: None,
+            result: None,
+            error: None,
+            submitted_by: Some(key_id.to_string()),
+        };
+        self.record(pending.clone());
+
+        let api = Arc::clone(self);
+        let mut analysis = pending.clone();
+        tauri::async_runtime::spawn(async move {
+            match api.download_and_analyze(parsed, &analysis.document_name, &analysis.analysis_type).await {
+                Ok(result) => {
+                    if let Err(e) = api.submit_for_review(&mut analysis, result) {
+                        log::warn!("Automation analysis {} not queued for review: {}", analysis.id, e);
+                        analysis.status = AnalysisStatus::Failed;
+                        analysis.error = Some("The result could not be queued for attorney review".to_string());
+                    }
+                }
+                Err(e) => {
+                    log::warn!("Automation analysis {} failed: {}", analysis.id, e);
+                    analysis.status = AnalysisStatus::Failed;
+                    analysis.error = Some(e.to_string());
+                }
+            }
+            analysis.completed_at = Some(chrono::Utc::now().to_rfc3339());
+            api.record(analysis);
+        });
+
+        Ok(pending)
+    }
+
+    async fn download_and_analyze(
+        &self,
+        url: reqwest::Url,
+        document_name: &str,
+        analysis_type: &str,
+    ) -> Result<serde_json::Value> {
+        let host = url
+            .host_str()
+            .ok_or_else(|| anyhow!("Document URL has no host"))?
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .to_string();
+        let port = url.port_or_known_default().unwrap_or(80);
+        let addresses: Vec<SocketAddr> = match host.parse::<IpAddr>() {
+            Ok(ip) => vec![SocketAddr::new(ip, port)],
+            Err(_) => tokio::net::lookup_host((host.as_str(), port)).await?.collect(),
+        };
+        let address = *addresses.first().ok_or_else(|| anyhow!("Cannot resolve {}", host))?;
+        if !addresses.iter().all(|a| is_public_address(a.ip())) {
+            return Err(anyhow!("Document URL points to a private or local address"));
+        }
+
+        // Connect to the address just checked, so a second lookup cannot lead elsewhere
+        let client = reqwest::Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .resolve(&host, address)
+            .timeout(DOWNLOAD_TIMEOUT)
+            .build()?;
+        let mut response = client.get(url).send().await?.error_for_status()?;
+        if response.status().is_redirection() {
+            return Err(anyhow!("Document URL redirects elsewhere; give the final address"));
+        }
+        let too_large = || anyhow!("Document exceeds the {} MB download limit", MAX_DOWNLOAD_BYTES / 1024 / 1024);
+        if response.content_length().map_or(false, |len| len as usize > MAX_DOWNLOAD_BYTES) {
+            return Err(too_large());
+        }
+        // Without a Content-Length the body is read until it passes the limit
+        let mut bytes = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            if bytes.len() + chunk.len() > MAX_DOWNLOAD_BYTES {
+                return Err(too_large());
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+
+        // The analyzer picks a parser from the extension, so keep the original one
+        let extension = Path::new(document_name)
+            .extension()
+            .and_then(|e| e.to_str())
+            .ok_or_else(|| anyhow!("Cannot determine the document type of {}", document_name))?;
+        let file = tempfile::Builder::new()
+            .suffix(&format!(".{}", extension))
+            .tempfile()?;
+        fs::write(file.path(), &bytes)?;
+
+        perform_file_analysis(analysis_type, file.path(), &self.analyzer)
+            .await
+            .map_err(|e| anyhow!(e))
+    }
+
+    /// Serve the automation endpoints on `address` until stopped
+    pub async fn start(self: &Arc<Self>, address: &str) -> Result<String> {
+        if let Some((running, handle)) = self.server.lock().unwrap().take() {
+            handle.abort();
+            log::info!("Restarting automation API previously on {}", running);
+        }
+
+        let listener = TcpListener::bind(address)
+            .await
+            .with_context(|| format!("Failed to bind automation API to {}", address))?;
+        let bound = listener.local_addr()?.to_string();
+        log::info!("Automation API listening on {}", bound);
+
+        let api = Arc::clone(self);
+        let handle = tauri::async_runtime::spawn(async move {
+            loop {
+                match listener.accept().await {
+                    Ok((stream, _)) => {
+                        let api = Arc::clone(&api);
+                        tokio::spawn(async move {
+                            if let Err(e) = api.handle_connection(stream).await {
+                                log::debug!("Automation API connection error: {}", e);
+                            }
+                        });
+                    }
+                    Err(e) => log::error!("Failed to accept automation API connection: {}", e),
+                }
+            }
+        });
+
+        *self.server.lock().unwrap() = Some((bound.clone(), handle));
+        Ok(bound)
+    }
+
+    pub fn stop(&self) {
+        if let Some((address, handle)) = self.server.lock().unwrap().take() {
+            handle.abort();
+            log::info!("Automation API on {} stopped", address);
+        }
+    }
+
+    pub fn status(&self) -> AutomationApiStatus {
+        let address = self.server.lock().unwrap().as_ref().map(|(a, _)| a.clone());
+        AutomationApiStatus {
+            running: address.is_some(),
+            address,
+            key_count: self.state.lock().unwrap().keys.len(),
+        }
+    }
+
+    async fn handle_connection(self: Arc<Self>, mut stream: TcpStream) -> Result<()> {
+        // A client that stops sending is dropped rather than holding the connection open
+        let (status, body) = match tokio::time::timeout(REQUEST_TIMEOUT, read_request(&mut stream)).await {
+            Ok(Ok(request)) => self.route(request).await,
+            Ok(Err(e)) => (400, error_body(&e.to_string())),
+            Err(_) => (408, error_body("Request timed out")),
+        };
+
+        let body = serde_json::to_string(&body)?;
+        let response = format!(
+            "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            reason_phrase(status),
+            body.len(),
+            body
+        );
+        stream.write_all(response.as_bytes()).await?;
+        stream.shutdown().await?;
+        Ok(())
+    }
+
+    async fn route(self: &Arc<Self>, request: HttpRequest) -> (u16, serde_json::Value) {
+        let segments: Vec<&str> = request.path.trim_matches('/').split('/').collect();
+        let scope = match (request.method.as_str(), segments.as_slice()) {
+            ("GET", ["v1", "me"]) => None,
+            ("GET", ["v1", "analyses"]) | ("GET", ["v1", "analyses", _]) => Some(ApiScope::AnalysesRead),
+            ("POST", ["v1", "analyses"]) => Some(ApiScope::AnalysesWrite),
+            _ => return (404, error_body("Not found")),
+        };
+
+        let secret = request
+            .headers
+            .get("authorization")
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .or_else(|| request.headers.get("x-api-key").map(String::as_str))
+            .unwrap_or_default();
+        let key = match self.authorize(secret.trim(), scope) {
+            Ok(key) => key,
+            Err((status, message)) => return (status, error_body(&message)),
+        };
+
+        match (request.method.as_str(), segments.as_slice()) {
+            // Lets Zapier and Make verify a key when the connection is set up
+            ("GET", ["v1", "me"]) => (
+                200,
+                serde_json::json!({ "key_id": key.id, "name": key.name, "scopes": key.scopes }),
+            ),
+            ("GET", ["v1", "analyses"]) => {
+                let since = request.query.get("since").and_then(|s| s.parse().ok()).unwrap_or(0);
+                let limit = request
+                    .query
+                    .get("limit")
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(DEFAULT_PAGE_SIZE)
+                    .clamp(1, MAX_PAGE_SIZE);
+                (200, serde_json::json!(self.analyses_since(&key.id, since, limit)))
+            }
+            ("GET", ["v1", "analyses", id]) => match self.get_analysis(&key.id, id) {
+                Some(analysis) => (200, serde_json::json!(analysis)),
+                None => (404, error_body("Analysis not found")),
+            },
+            _ => {
+                #[derive(Deserialize)]
+                struct SubmitRequest {
+                    url: String,
+                    analysis_type: Option<String>,
+                }
+
+                let submit: SubmitRequest = match serde_json::from_slice(&request.body) {
+                    Ok(submit) => submit,
+                    Err(e) => return (400, error_body(&format!("Invalid request body: {}", e))),
+                };
+                let analysis_type = submit.analysis_type.as_deref().unwrap_or("full_analysis");
+                match self.submit_url(&key.id, &submit.url, analysis_type) {
+                    Ok(analysis) => (202, serde_json::json!(analysis)),
+                    Err(e) => (400, error_body(&e.to_string())),
+                }
+            }
+        }
+    }
+}
+
+fn scope_name(scope: ApiScope) -> String {
+    serde_json::to_value(scope)
+        .ok()
+        .and_then(|v| v.as_str().map(String::from))
+        .unwrap_or_default()
+}
+
+fn error_body(message: &str) -> serde_json::Value {
+    serde_json::json!({ "error": message })
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        202 => "Accepted",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        408 => "Request Timeout",
+        _ => "Internal Server Error",
+    }
+}
+
+/// Whether an address is on the public internet: not loopback, private, link-local, shared,
+/// multicast or otherwise reserved
+fn is_public_address(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            let [a, b, ..] = v4.octets();
+            !(v4.is_private()
+                || v4.is_loopback()
+                || v4.is_link_local()
+                || v4.is_unspecified()
+                || v4.is_broadcast()
+                || v4.is_multicast()
+                || v4.is_documentation()
+                || a == 0
+                || (a == 100 && (64..128).contains(&b)) // shared address space
+                || (a == 192 && b == 0 && v4.octets()[2] == 0)
+                || a >= 240)
+        }
+        IpAddr::V6(v6) => match v6.to_ipv4_mapped() {
+            Some(v4) => is_public_address(IpAddr::V4(v4)),
+            None => {
+                let first = v6.segments()[0];
+                !(v6.is_loopback()
+                    || v6.is_unspecified()
+                    || v6.is_multicast()
+                    || (first & 0xfe00) == 0xfc00 // unique local
+                    || (first & 0xffc0) == 0xfe80) // link-local
+            }
+        },
+    }
+}
+
+#[derive(Debug)]
+struct HttpRequest {
+    method: String,
+    path: String,
+    query: HashMap<String, String>,
+    headers: HashMap<String, String>, // lower-cased names
+    body: Vec<u8>,
+}
+
+/// Parse the request line and headers; the body is read separately
+fn parse_request_head(head: &str) -> Result<HttpRequest> {
+    let mut lines = head.split("\r\n");
+    let request_line = lines.next().ok_or_else(|| anyhow!("Empty request"))?;
+    let mut parts = request_line.split_whitespace();
+    let (method, target) = match (parts.next(), parts.next()) {
+        (Some(method), Some(target)) => (method, target),
+        _ => return Err(anyhow!("Malformed request line")),
+    };
+
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let query: HashMap<String, String> = serde_urlencoded::from_str(query).unwrap_or_default();
+    let headers = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_ascii_lowercase(), value.trim().to_string()))
+        .collect();
+
+    Ok(HttpRequest {
+        method: method.to_string(),
+        path: path.to_string(),
+        query,
+        headers,
+        body: Vec::new(),
+    })
+}
+
+async fn read_request(stream: &mut TcpStream) -> Result<HttpRequest> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 4096];
+    let head_end = loop {
+        let read = stream.read(&mut chunk).await?;
+        if read == 0 {
+            return Err(anyhow!("Connection closed before the request was complete"));
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+        if let Some(pos) = buffer.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos;
+        }
+        if buffer.len() > MAX_REQUEST_BYTES {
+            return Err(anyhow!("Request headers too large"));
+        }
+    };
+
+    let mut request = parse_request_head(&String::from_utf8_lossy(&buffer[..head_end]))?;
+    let content_length: usize = request
+        .headers
+        .get("content-length")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0);
+    if content_length > MAX_REQUEST_BYTES {
+        return Err(anyhow!("Request body too large"));
+    }
+
+    let mut body = buffer[head_end + 4..].to_vec();
+    while body.len() < content_length {
+        let read = stream.read(&mut chunk).await?;
+        if read == 0 {
+            break;
+        }
+        body.extend_from_slice(&chunk[..read]);
+    }
+    body.truncate(content_length);
+    request.body = body;
+    Ok(request)
+}
+
+pub type AutomationStorage = Arc<AutomationApi>;
+
+#[tauri::command]
+pub async fn automation_api_start(
+    address: Option<String>,
+    automation: tauri::State<'_, AutomationStorage>,
+) -> Result<AutomationApiStatus, String> {
+    automation
+        .start(address.as_deref().unwrap_or(DEFAULT_ADDRESS))
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(automation.status())
+}
+
+#[tauri::command]
+pub async fn automation_api_stop(
+    automation: tauri::State<'_, AutomationStorage>,
+) -> Result<AutomationApiStatus, String> {
+    automation.stop();
+    Ok(automation.status())
+}
+
+#[tauri::command]
+pub async fn automation_api_status(
+    automation: tauri::State<'_, AutomationStorage>,
+) -> Result<AutomationApiStatus, String> {
+    Ok(automation.status())
+}
+
+#[tauri::command]
+pub async fn automation_create_key(
+    name: String,
+    scopes: Vec<ApiScope>,
+    automation: tauri::State<'_, AutomationStorage>,
+) -> Result<CreatedApiKey, String> {
+    automation.create_key(&name, scopes).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn automation_list_keys(
+    automation: tauri::State<'_, AutomationStorage>,
+) -> Result<Vec<AutomationApiKey>, String> {
+    Ok(automation.list_keys())
+}
+
+#[tauri::command]
+pub async fn automation_revoke_key(
+    key_id: String,
+    automation: tauri::State<'_, AutomationStorage>,
+) -> Result<(), String> {
+    automation.revoke_key(&key_id).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_request_head() {
+        let request = parse_request_head(
+            "GET /v1/analyses?since=12&limit=5 HTTP/1.1\r\nHost: localhost\r\nAuthorization: Bearer bear_abc",
+        )
+        .unwrap();
+        assert_eq!(request.method, "GET");
+        assert_eq!(request.path, "/v1/analyses");
+        assert_eq!(request.query.get("since").map(String::as_str), Some("12"));
+        assert_eq!(request.headers.get("authorization").map(String::as_str), Some("Bearer bear_abc"));
+        assert!(parse_request_head("").is_err());
+    }
+
+    #[test]
+    fn test_only_public_addresses_are_fetched() {
+        for blocked in ["127.0.0.1", "10.1.2.3", "172.16.0.1", "192.168.1.10", "169.254.169.254", "100.64.0.1", "0.0.0.0", "::1", "fd00::1", "fe80::1", "::ffff:127.0.0.1"] {
+            assert!(!is_public_address(blocked.parse().unwrap()), "{} should be blocked", blocked);
+        }
+        for public in ["93.184.216.34", "2606:2800:220:1::1"] {
+            assert!(is_public_address(public.parse().unwrap()), "{} should be allowed", public);
+        }
+    }
+
+    #[test]
+    fn test_analyses_since_cursor() {
+        let analysis = |id: &str, cursor: Option<u64>, key: Option<&str>| AutomationAnalysis {
+            id: id.to_string(),
+            cursor,
+            status: if cursor.is_some() { AnalysisStatus::Completed } else { AnalysisStatus::Pending },
+            analysis_type: "full_analysis".to_string(),
+            document_id: None,
+            document_name: format!("{}.pdf", id),
+            source_url: None,
+            created_at: String::new(),
+            completed_at: None,
+            result: None,
+            error: None,
+            submitted_by: key.map(String::from),
+        };
+        let feed: VecDeque<_> = vec![
+            analysis("a", Some(1), Some("k1")),
+            analysis("b", None, Some("k1")),
+            analysis("c", Some(3), Some("k1")),
+            analysis("d", Some(2), Some("k1")),
+            analysis("e", Some(4), Some("k2")),
+            analysis("f", Some(5), None),
+        ]
+        .into_iter()
+        .collect();
+
+        let page = analyses_since(&feed, "k1", 1, 10);
+        let ids: Vec<&str> = page.data.iter().map(|a| a.id.as_str()).collect();
+        assert_eq!(ids, vec!["d", "c"]);
+        assert_eq!(page.cursor, 3);
+
+        let page = analyses_since(&feed, "k1", 3, 10);
+        assert!(page.data.is_empty());
+        assert_eq!(page.cursor, 3);
+
+        // Another key's analyses and the app's own never show up
+        let page = analyses_since(&feed, "k2", 0, 10);
+        let ids: Vec<&str> = page.data.iter().map(|a| a.id.as_str()).collect();
+        assert_eq!(ids, vec!["e"]);
+    }
+}
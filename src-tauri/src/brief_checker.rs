@@ -0,0 +1,430 @@
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use uuid::Uuid;
+
+use crate::llm_manager::LLMManager;
+use crate::research_memo::{authority_patterns, normalize_citation, RetrievedPassage};
+use crate::text_processing::truncate_chars;
+
+/// Brief Argument Checking for BEAR AI
+/// Reviews a draft brief the way opposing counsel would. Each cited authority is looked up in
+/// the RAG index, the proposition it is cited for is checked against the source text by the
+/// local model, and the index is searched for authority that cuts the other way. Nothing is
+/// changed in the draft; the result is a review report listing what to fix before filing.
+const MAX_PROPOSITIONS: usize = 40;
+const MAX_SOURCE_PASSAGES: usize = 3;
+const COUNTER_CANDIDATES: usize = 3;
+const MAX_EXCERPT_CHARS: usize = 1200;
+/// A citation sentence with fewer words of its own is read with the sentence before it
+const MIN_PROPOSITION_WORDS: usize = 5;
+const SYSTEM_PROMPT: &str = "You are opposing counsel checking a brief. Be strict and rely only on the text provided.";
+const MAX_TOKENS: i32 = 300;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BriefCheckRequest {
+    pub path: Option<String>, // the draft brief; `text` is used when no file is given
+    pub text: Option<String>,
+    pub jurisdiction: Option<String>,
+    pub model: Option<String>, // defaults to the model the generation server has ready
+}
+
+/// An authority together with the proposition the brief cites it for
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CitedProposition {
+    pub citation: String,
+    pub proposition: String,
+    pub paragraph: usize, // 1-based
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthorityStatus {
+    Verified, // the retriever verified the citation
+    InCorpus, // found in indexed text but not verified
+    NotFound,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SupportVerdict {
+    Supported,
+    PartiallySupported,
+    NotSupported,
+    Contradicted,
+    NoSourceText, // the authority's text is not in the index, so nothing was compared
+    Unclear,      // the model's answer could not be read
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CitationCheck {
+    pub citation: String,
+    pub proposition: String,
+    pub paragraph: usize,
+    pub status: AuthorityStatus,
+    pub sources: Vec<String>, // titles of the passages the proposition was checked against
+    pub support: SupportVerdict,
+    pub explanation: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AdverseAssessment {
+    Adverse,
+    Distinguishable,
+}
+
+/// Indexed authority that may undercut one of the brief's propositions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CounterAuthority {
+    pub document_id: String,
+    pub title: String,
+    pub excerpt: String,
+    pub cited_authorities: Vec<String>,
+    pub against: String, // the proposition it undercuts
+    pub paragraph: usize,
+    pub assessment: AdverseAssessment,
+    pub explanation: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewSummary {
+    pub citations: usize,
+    pub not_found: usize,
+    pub unsupported: usize, // not supported or contradicted
+    pub adverse: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BriefReview {
+    pub id: String,
+    pub brief: Option<String>,
+    pub jurisdiction: Option<String>,
+    pub model: String,
+    pub checks: Vec<CitationCheck>,
+    pub counter_authorities: Vec<CounterAuthority>,
+    pub summary: ReviewSummary,
+    pub generated_at: String,
+}
+
+/// Byte offsets where sentences end in `text`, skipping the periods of citations and abbreviations
+fn sentence_ends(text: &str) -> Vec<usize> {
+    let boundary = Regex::new(r#"([.!?]["')\]]*)\s+["'(]?[A-Z]"#).unwrap();
+    let abbreviations = ["v", "vs", "no", "nos", "inc", "corp", "co", "ltd", "cir", "supp", "ct", "app", "id", "al", "cf", "see", "art", "sec", "para"];
+    boundary
+        .captures_iter(text)
+        .map(|caps| caps.get(1).unwrap())
+        .filter(|end| {
+            let word = text[..end.start()].split_whitespace().last().unwrap_or("");
+            let word = word.trim_start_matches(|c: char| !c.is_alphanumeric());
+            // "U.S." and "F." end in a capital or an inner period; neither ends a sentence
+            let initial = word.chars().count() == 1 && word.chars().all(char::is_uppercase);
+            !(initial || word.contains('.') || abbreviations.contains(&word.to_lowercase().as_str()))
+        })
+        .map(|end| end.end())
+        .collect()
+}
+
+fn own_words(sentence: &str, patterns: &[Regex]) -> usize {
+    let mut rest = sentence.to_string();
+    for pattern in patterns {
+        rest = pattern.replace_all(&rest, " ").to_string();
+    }
+    let signals = ["see", "also", "cf", "accord", "e.g", "but", "contra", "compare", "with", "id"];
+    rest.split_whitespace()
+        .map(|w| w.trim_matches(|c: char| !c.is_alphanumeric()).to_lowercase())
+        .filter(|w| w.chars().any(char::is_alphabetic) && !signals.contains(&w.as_str()))
+        .count()
+}
+
+/// Every citation in the brief with the sentence it supports
+pub fn extract_propositions(text: &str) -> Vec<CitedProposition> {
+    let patterns = authority_patterns();
+    let mut propositions = Vec::new();
+    for (index, paragraph) in text.split("\n\n").map(str::trim).filter(|p| !p.is_empty()).enumerate() {
+        let ends = sentence_ends(paragraph);
+        let mut found: Vec<(usize, usize)> = patterns
+            .iter()
+            .flat_map(|p| p.find_iter(paragraph).map(|m| (m.start(), m.end())))
+            .collect();
+        found.sort_unstable();
+        found.dedup_by(|later, kept| later.0 < kept.1); // overlapping matches of different patterns
+
+        for (start, end) in found {
+            let sentence_start = |offset: usize| ends.iter().rev().find(|e| **e <= offset).copied().unwrap_or(0);
+            let mut from = sentence_start(start);
+            let to = ends.iter().find(|e| **e >= end).copied().unwrap_or(paragraph.len());
+            if own_words(&paragraph[from..to], &patterns) < MIN_PROPOSITION_WORDS && from > 0 {
+                from = sentence_start(from - 1);
+            }
+            propositions.push(CitedProposition {
+                citation: paragraph[start..end].to_string(),
+                proposition: paragraph[from..to].trim().to_string(),
+                paragraph: index + 1,
+            });
+        }
+    }
+    propositions.truncate(MAX_PROPOSITIONS);
+    propositions
+}
+
+fn mentions(passage: &RetrievedPassage, key: &str) -> bool {
+    normalize_citation(&passage.content).contains(key)
+        || passage.cited_authorities.iter().any(|c| normalize_citation(c) == key)
+        || passage.title.as_deref().map(|t| normalize_citation(t).contains(key)).unwrap_or(false)
+}
+
+/// Whether an authority is in the index, and the passages that carry its text
+pub fn authority_status(citation: &str, passages: &[RetrievedPassage]) -> (AuthorityStatus, Vec<RetrievedPassage>) {
+    let key = normalize_citation(citation);
+    let matching: Vec<RetrievedPassage> = passages.iter().filter(|p| mentions(p, &key)).cloned().collect();
+    let status = if matching.iter().any(|p| p.verified_citations.iter().any(|c| normalize_citation(c) == key)) {
+        AuthorityStatus::Verified
+    } else if !matching.is_empty() {
+        AuthorityStatus::InCorpus
+    } else {
+        AuthorityStatus::NotFound
+    };
+    (status, matching)
+}
+
+fn passage_label(passage: &RetrievedPassage) -> String {
+    passage.title.clone().unwrap_or_else(|| passage.document_id.clone())
+}
+
+fn support_prompt(proposition: &CitedProposition, sources: &[RetrievedPassage]) -> String {
+    let mut prompt = format!(
+        "A brief cites {} for the proposition below. Compare the proposition with the source text only.\n\n\
+         Proposition:\n{}\n",
+        proposition.citation, proposition.proposition
+    );
+    for (i, source) in sources.iter().enumerate() {
+        prompt.push_str(&format!(
+            "\nSource {} ({}):\n{}\n",
+            i + 1,
+            passage_label(source),
+            truncate_chars(&source.content, MAX_EXCERPT_CHARS)
+        ));
+    }
+    prompt.push_str(
+        "\nAnswer on the first line with exactly one of SUPPORTED, PARTIALLY SUPPORTED, NOT SUPPORTED or \
+         CONTRADICTED, then explain in two sentences what the source actually says.",
+    );
+    prompt
+}
+
+fn counter_prompt(proposition: &CitedProposition, candidate: &RetrievedPassage, jurisdiction: Option<&str>) -> String {
+    format!(
+        "A brief argues the proposition below{}. Decide whether the passage is authority the other side could \
+         use against it.\n\nProposition:\n{}\n\nPassage ({}):\n{}\n\n\
+         Answer on the first line with exactly one of ADVERSE, DISTINGUISHABLE or NOT ADVERSE, then explain in \
+         two sentences.",
+        jurisdiction.map(|j| format!(" under {} law", j)).unwrap_or_default(),
+        proposition.proposition,
+        passage_label(candidate),
+        truncate_chars(&candidate.content, MAX_EXCERPT_CHARS)
+    )
+}
+
+/// Split a labelled answer into its label and the explanation that follows
+fn parse_verdict<'a>(answer: &'a str, labels: &[&str]) -> (Option<usize>, &'a str) {
+    let answer = answer.trim();
+    let first = answer.lines().next().unwrap_or("").trim_matches(|c: char| !c.is_alphanumeric() && c != ' ');
+    let upper = first.to_uppercase();
+    // Longest label first, so "NOT SUPPORTED" is not read as "SUPPORTED"
+    let mut order: Vec<usize> = (0..labels.len()).collect();
+    order.sort_by_key(|i| std::cmp::Reverse(labels[*i].len()));
+    let label = order.into_iter().find(|i| upper.starts_with(labels[*i]));
+    let explanation = match label {
+        Some(i) => answer[answer.find(first).unwrap_or(0) + labels[i].len()..].trim_start_matches([':', '.', '-', ' ', '\n']),
+        None => answer,
+    };
+    (label, explanation.trim())
+}
+
+pub fn parse_support(answer: &str) -> (SupportVerdict, String) {
+    let labels = ["SUPPORTED", "PARTIALLY SUPPORTED", "NOT SUPPORTED", "CONTRADICTED"];
+    let (label, explanation) = parse_verdict(answer, &labels);
+    let verdict = match label {
+        Some(0) => SupportVerdict::Supported,
+        Some(1) => SupportVerdict::PartiallySupported,
+        Some(2) => SupportVerdict::NotSupported,
+        Some(3) => SupportVerdict::Contradicted,
+        _ => SupportVerdict::Unclear,
+    };
+    (verdict, explanation.to_string())
+}
+
+pub fn parse_adverse(answer: &str) -> (Option<AdverseAssessment>, String) {
+    let (label, explanation) = parse_verdict(answer, &["ADVERSE", "DISTINGUISHABLE", "NOT ADVERSE"]);
+    let assessment = match label {
+        Some(0) => Some(AdverseAssessment::Adverse),
+        Some(1) => Some(AdverseAssessment::Distinguishable),
+        _ => None,
+    };
+    (assessment, explanation.to_string())
+}
+
+/// Review a draft brief; `retrieve` queries the RAG index
+pub async fn review_brief<F, Fut>(
+    request: &BriefCheckRequest,
+    text: &str,
+    llm: &LLMManager,
+    retrieve: F,
+) -> Result<BriefReview>
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<Vec<RetrievedPassage>>>,
+{
+    let propositions = extract_propositions(text);
+    if propositions.is_empty() {
+        return Err(anyhow!("The draft cites no authorities that can be checked"));
+    }
+    let model = match &request.model {
+        Some(model) => model.clone(),
+        None => llm
+            .default_generation_model()
+            .await
+            .ok_or_else(|| anyhow!("No model is available to review the brief"))?,
+    };
+
+    let mut lookups: HashMap<String, (AuthorityStatus, Vec<RetrievedPassage>)> = HashMap::new();
+    let mut checks = Vec::new();
+    let mut counter_authorities: Vec<CounterAuthority> = Vec::new();
+    let mut reported: HashSet<String> = HashSet::new();
+    for proposition in &propositions {
+        let key = normalize_citation(&proposition.citation);
+        if !lookups.contains_key(&key) {
+            let found = authority_status(&proposition.citation, &retrieve(proposition.citation.clone()).await?);
+            lookups.insert(key.clone(), found);
+        }
+        let (status, cited_passages) = lookups[&key].clone();
+
+        // The passages that best match the proposition, if they carry the authority, come first
+        let topical = retrieve(proposition.proposition.clone()).await?;
+        let mut sources: Vec<RetrievedPassage> = topical.iter().filter(|p| mentions(p, &key)).cloned().collect();
+        for passage in cited_passages {
+            if !sources.iter().any(|s| s.chunk_id == passage.chunk_id) {
+                sources.push(passage);
+            }
+        }
+        sources.truncate(MAX_SOURCE_PASSAGES);
+
+        let (support, explanation) = if sources.is_empty() {
+            (SupportVerdict::NoSourceText, "The authority's text is not in the document index.".to_string())
+        } else {
+            parse_support(&llm.complete(&model, SYSTEM_PROMPT, support_prompt(proposition, &sources), MAX_TOKENS, 0.1).await?)
+        };
+        checks.push(CitationCheck {
+            citation: proposition.citation.clone(),
+            proposition: proposition.proposition.clone(),
+            paragraph: proposition.paragraph,
+            status,
+            sources: sources.iter().map(passage_label).collect(),
+            support,
+            explanation,
+        });
+
+        // Authority the brief already relies on is not counter-authority
+        let relied_on: HashSet<&str> = sources.iter().map(|s| s.document_id.as_str()).collect();
+        let mut candidates: Vec<&RetrievedPassage> = Vec::new();
+        for passage in &topical {
+            let seen = candidates.iter().any(|c| c.document_id == passage.document_id);
+            if !seen && !relied_on.contains(passage.document_id.as_str()) && !reported.contains(&passage.document_id) {
+                candidates.push(passage);
+            }
+        }
+        candidates.truncate(COUNTER_CANDIDATES);
+        for candidate in candidates {
+            let prompt = counter_prompt(proposition, candidate, request.jurisdiction.as_deref());
+            let answer = llm.complete(&model, SYSTEM_PROMPT, prompt, MAX_TOKENS, 0.1).await?;
+            if let (Some(assessment), explanation) = parse_adverse(&answer) {
+                reported.insert(candidate.document_id.clone());
+                counter_authorities.push(CounterAuthority {
+                    document_id: candidate.document_id.clone(),
+                    title: passage_label(candidate),
+                    excerpt: truncate_chars(&candidate.content, 400).to_string(),
+                    cited_authorities: candidate.cited_authorities.clone(),
+                    against: proposition.proposition.clone(),
+                    paragraph: proposition.paragraph,
+                    assessment,
+                    explanation,
+                });
+            }
+        }
+    }
+
+    let summary = ReviewSummary {
+        citations: checks.len(),
+        not_found: checks.iter().filter(|c| c.status == AuthorityStatus::NotFound).count(),
+        unsupported: checks
+            .iter()
+            .filter(|c| matches!(c.support, SupportVerdict::NotSupported | SupportVerdict::Contradicted))
+            .count(),
+        adverse: counter_authorities.iter().filter(|c| c.assessment == AdverseAssessment::Adverse).count(),
+    };
+    Ok(BriefReview {
+        id: Uuid::new_v4().to_string(),
+        brief: request.path.clone(),
+        jurisdiction: request.jurisdiction.clone(),
+        model,
+        checks,
+        counter_authorities,
+        summary,
+        generated_at: Utc::now().to_rfc3339(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn passage(document_id: &str, content: &str, verified: &[&str]) -> RetrievedPassage {
+        RetrievedPassage {
+            chunk_id: format!("{}-0", document_id),
+            document_id: document_id.to_string(),
+            title: Some(document_id.to_string()),
+            content: content.to_string(),
+            cited_authorities: Vec::new(),
+            verified_citations: verified.iter().map(|c| c.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_extract_propositions_with_citation_sentences() {
+        let brief = "ARGUMENT\n\nA state may not impose an undue burden before viability. Planned Parenthood v. Casey, 505 U.S. 833, 878 (1992). The statute does exactly that.\n\nThe burden is substantial here. See 410 U.S. 113.";
+        let found = extract_propositions(brief);
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].citation, "505 U.S. 833");
+        assert_eq!(
+            found[0].proposition,
+            "A state may not impose an undue burden before viability. Planned Parenthood v. Casey, 505 U.S. 833, 878 (1992)."
+        );
+        assert_eq!(found[0].paragraph, 2);
+        // A bare "See" cite is read with the sentence it supports
+        assert_eq!(found[1].proposition, "The burden is substantial here. See 410 U.S. 113.");
+    }
+
+    #[test]
+    fn test_authority_status_and_verdicts() {
+        let passages = vec![
+            passage("casey", "We hold that an undue burden exists ... 505 U.S. 833", &["505 U.S. 833"]),
+            passage("commentary", "Commentators discuss 410  U.S. 113 at length.", &[]),
+        ];
+        assert_eq!(authority_status("505 U.S. 833", &passages).0, AuthorityStatus::Verified);
+        let (status, matching) = authority_status("410 U.S. 113", &passages);
+        assert_eq!((status, matching.len()), (AuthorityStatus::InCorpus, 1));
+        assert_eq!(authority_status("999 F.3d 1", &passages).0, AuthorityStatus::NotFound);
+
+        let (verdict, explanation) = parse_support("NOT SUPPORTED: The court said the opposite.");
+        assert_eq!(verdict, SupportVerdict::NotSupported);
+        assert_eq!(explanation, "The court said the opposite.");
+        assert_eq!(parse_support("**Partially supported**\nOnly as to notice.").0, SupportVerdict::PartiallySupported);
+        assert_eq!(parse_support("I cannot tell.").0, SupportVerdict::Unclear);
+        assert_eq!(parse_adverse("NOT ADVERSE. Different issue.").0, None);
+        assert_eq!(parse_adverse("Adverse - it rejects the test.").0, Some(AdverseAssessment::Adverse));
+    }
+}
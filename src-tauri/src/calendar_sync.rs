@@ -0,0 +1,809 @@
+use anyhow::{anyhow, Result};
+use base64::Engine;
+use chrono::NaiveDate;
+use reqwest::{Method, StatusCode};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::document_analyzer::{DocumentAnalysis, EntityType};
+use crate::enterprise_management::BarrierStorage;
+use crate::local_api::{authenticated_user, AnalyzerStorage, SessionStorage};
+use crate::matters::MatterStorage;
+use crate::security::SecurityManager;
+
+/// Calendar Sync for BEAR AI
+/// Turns obligation and limitation dates found by the document analyzer into calendar events,
+/// exports them as ICS and keeps a CalDAV calendar in step as documents change. Events that
+/// were edited or deleted in the calendar are never overwritten; they are reported as conflicts.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum DeadlineKind {
+    Obligation,
+    Limitation,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ExtractedDeadline {
+    pub uid: String, // stable across re-analysis of unchanged text
+    pub document_id: String,
+    pub document_name: String,
+    pub kind: DeadlineKind,
+    pub date: NaiveDate,
+    pub context: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalDavConfig {
+    pub calendar_url: String, // collection URL, ending in '/'
+    pub username: String,
+    password_encrypted: String, // base64 of the SecurityManager ciphertext
+    #[serde(default = "default_reminder_days")]
+    pub reminder_days: u32,
+}
+
+fn default_reminder_days() -> u32 {
+    7
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictReason {
+    ModifiedRemotely,
+    DeletedRemotely,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncedEvent {
+    pub uid: String,
+    pub document_id: String,
+    pub href: String,
+    pub etag: Option<String>,
+    pub content_hash: String,
+    pub conflict: Option<ConflictReason>,
+    #[serde(default)]
+    pub detached: bool, // the user kept the calendar copy; we no longer touch it
+}
+
+/// Decrypted CalDAV settings for one sync run
+pub struct CalDavCredentials {
+    config: CalDavConfig,
+    password: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CalendarSyncReport {
+    pub created: usize,
+    pub updated: usize,
+    pub deleted: usize,
+    pub unchanged: usize,
+    pub conflicts: Vec<String>, // uids left untouched because the calendar copy changed
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CalendarSyncState {
+    config: Option<CalDavConfig>,
+    sync_token: Option<String>,
+    events: HashMap<String, SyncedEvent>,
+}
+
+const LIMITATION_KEYWORDS: &[&str] = &[
+    "limitation period",
+    "statute of limitation",
+    "time-barred",
+    "time barred",
+    "prescription",
+    "must be brought",
+    "claims must",
+    "limitation",
+];
+
+// Phrases that tie a date to something owed by then; "shall", "due" or "notice" alone appear
+// around every date in a contract, effective dates and recitals included
+const OBLIGATION_KEYWORDS: &[&str] = &[
+    "no later than",
+    "not later than",
+    "on or before",
+    "deadline",
+    "due date",
+    "due on",
+    "due by",
+    "is due",
+    "are due",
+    "payable on",
+    "payable by",
+    "expires on",
+    "shall expire",
+    "notice by",
+];
+
+/// Whether `phrase` occurs in `text` as whole words
+fn contains_phrase(text: &str, phrase: &str) -> bool {
+    text.match_indices(phrase).any(|(i, _)| {
+        let before = text[..i].chars().next_back();
+        let after = text[i + phrase.len()..].chars().next();
+        !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+    })
+}
+
+fn classify_deadline(context: &str) -> Option<DeadlineKind> {
+    let context = context.to_lowercase();
+    if LIMITATION_KEYWORDS.iter().any(|k| context.contains(k)) {
+        Some(DeadlineKind::Limitation)
+    } else if OBLIGATION_KEYWORDS.iter().any(|k| contains_phrase(&context, k)) {
+        Some(DeadlineKind::Obligation)
+    } else {
+        None
+    }
+}
+
+/// Parse US-style date text; only needed for analyses cached before dates carried a normalized value
+fn parse_deadline_date(text: &str) -> Option<NaiveDate> {
+    let text = text.trim();
+    ["%m/%d/%Y", "%m-%d-%Y", "%B %d, %Y", "%b %d, %Y"]
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(text, format).ok())
+}
+
+/// Obligation and limitation deadlines among the dates found in an analysis
+pub fn deadlines_from_analysis(document_id: &str, document_name: &str, analysis: &DocumentAnalysis) -> Vec<ExtractedDeadline> {
+    let mut deadlines: Vec<ExtractedDeadline> = Vec::new();
+    for entity in analysis.entities.iter().filter(|e| matches!(e.entity_type, EntityType::Date)) {
+        let date = entity
+            .normalized_value
+            .as_deref()
+            .and_then(|iso| NaiveDate::parse_from_str(iso, "%Y-%m-%d").ok())
+            .or_else(|| parse_deadline_date(&entity.text));
+        let (Some(date), Some(kind)) = (date, classify_deadline(&entity.context)) else {
+            continue;
+        };
+        let context = entity.context.split_whitespace().collect::<Vec<_>>().join(" ");
+
+        let mut hasher = Sha256::new();
+        hasher.update(format!("{}|{:?}|{}|{}", document_id, kind, date, context.to_lowercase()));
+        let uid = format!("{}@bear-ai", &hex::encode(hasher.finalize())[..32]);
+
+        if !deadlines.iter().any(|d| d.uid == uid) {
+            deadlines.push(ExtractedDeadline {
+                uid,
+                document_id: document_id.to_string(),
+                document_name: document_name.to_string(),
+                kind,
+                date,
+                context,
+            });
+        }
+    }
+    deadlines.sort_by_key(|d| d.date);
+    deadlines
+}
+
+fn escape_ics_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+fn vevent(deadline: &ExtractedDeadline, reminder_days: u32) -> String {
+    let title = match deadline.kind {
+        DeadlineKind::Obligation => "Obligation deadline",
+        DeadlineKind::Limitation => "Limitation deadline",
+    };
+    [
+        "BEGIN:VEVENT".to_string(),
+        format!("UID:{}", deadline.uid),
+        format!("DTSTAMP:{}", chrono::Utc::now().format("%Y%m%dT%H%M%SZ")),
+        format!("DTSTART;VALUE=DATE:{}", deadline.date.format("%Y%m%d")),
+        format!("DTEND;VALUE=DATE:{}", deadline.date.succ_opt().unwrap_or(deadline.date).format("%Y%m%d")),
+        format!("SUMMARY:{}", escape_ics_text(&format!("{}: {}", title, deadline.document_name))),
+        format!("DESCRIPTION:{}", escape_ics_text(&deadline.context)),
+        format!("CATEGORIES:{}", escape_ics_text(title)),
+        format!("X-BEAR-DOCUMENT-ID:{}", escape_ics_text(&deadline.document_id)),
+        "BEGIN:VALARM".to_string(),
+        "ACTION:DISPLAY".to_string(),
+        format!("DESCRIPTION:{}", escape_ics_text(title)),
+        format!("TRIGGER:-P{}D", reminder_days),
+        "END:VALARM".to_string(),
+        "END:VEVENT".to_string(),
+    ]
+    .join("\r\n")
+}
+
+/// A VCALENDAR holding one event per deadline
+pub fn render_ics(deadlines: &[ExtractedDeadline], reminder_days: u32) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//BEAR AI//Legal Assistant//EN".to_string(),
+    ];
+    lines.extend(deadlines.iter().map(|d| vevent(d, reminder_days)));
+    lines.push("END:VCALENDAR".to_string());
+    lines.join("\r\n") + "\r\n"
+}
+
+// Hash of the event fields we control, so DTSTAMP changes do not count as edits
+fn content_hash(deadline: &ExtractedDeadline, reminder_days: u32) -> String {
+    hex::encode(Sha256::digest(
+        format!("{:?}|{}|{}|{}|{}", deadline.kind, deadline.date, deadline.document_name, deadline.context, reminder_days).as_bytes(),
+    ))
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct SyncChanges {
+    sync_token: Option<String>,
+    changed: Vec<(String, Option<String>)>, // href, etag
+    deleted: Vec<String>,
+}
+
+fn xml_elements<'a>(xml: &'a str, name: &str) -> Vec<&'a str> {
+    let pattern = format!(r"(?s)<(?:[\w-]+:)?{0}(?:\s[^>]*)?>(.*?)</(?:[\w-]+:)?{0}>", name);
+    regex::Regex::new(&pattern)
+        .map(|re| re.captures_iter(xml).filter_map(|c| c.get(1)).map(|m| m.as_str()).collect())
+        .unwrap_or_default()
+}
+
+/// Parse a sync-collection REPORT multistatus (RFC 6578)
+fn parse_sync_response(xml: &str) -> SyncChanges {
+    let mut changes = SyncChanges {
+        sync_token: xml_elements(xml, "sync-token").last().map(|t| t.trim().to_string()),
+        ..Default::default()
+    };
+    for response in xml_elements(xml, "response") {
+        let Some(href) = xml_elements(response, "href").first().map(|h| h.trim().to_string()) else {
+            continue;
+        };
+        // Deleted members carry a response-level 404 and no propstat
+        let removed = xml_elements(response, "status").iter().any(|s| s.contains(" 404"))
+            && xml_elements(response, "propstat").is_empty();
+        if removed {
+            changes.deleted.push(href);
+        } else {
+            let etag = xml_elements(response, "getetag")
+                .first()
+                .map(|e| e.trim().replace("&quot;", "\""));
+            changes.changed.push((href, etag));
+        }
+    }
+    changes
+}
+
+fn href_path(href: &str) -> String {
+    reqwest::Url::parse(href)
+        .map(|u| u.path().to_string())
+        .unwrap_or_else(|_| href.to_string())
+}
+
+pub struct CalendarSync {
+    path: PathBuf,
+    state: Mutex<CalendarSyncState>,
+    client: reqwest::Client,
+}
+
+impl CalendarSync {
+    pub fn new(app_data_dir: &Path) -> Result<Self> {
+        let path = app_data_dir.join("calendar_sync.json");
+        let state = if path.exists() {
+            serde_json::from_str(&fs::read_to_string(&path)?)?
+        } else {
+            CalendarSyncState::default()
+        };
+
+        Ok(Self {
+            path,
+            state: Mutex::new(state),
+            client: reqwest::Client::new(),
+        })
+    }
+
+    fn persist(&self, state: &CalendarSyncState) -> Result<()> {
+        fs::write(&self.path, serde_json::to_string_pretty(state)?)?;
+        Ok(())
+    }
+
+    /// Point sync at a calendar; switching calendars forgets what was pushed to the old one
+    pub fn configure(
+        &self,
+        calendar_url: &str,
+        username: &str,
+        password: &str,
+        reminder_days: Option<u32>,
+        security: &SecurityManager,
+    ) -> Result<()> {
+        let mut calendar_url = calendar_url.trim().to_string();
+        if !calendar_url.starts_with("https://") {
+            return Err(anyhow!("CalDAV calendar URL must use https"));
+        }
+        if !calendar_url.ends_with('/') {
+            calendar_url.push('/');
+        }
+
+        let encrypted = security.encrypt_data(password.as_bytes())?;
+        let mut state = self.state.lock().unwrap();
+        if state.config.as_ref().map(|c| &c.calendar_url) != Some(&calendar_url) {
+            state.sync_token = None;
+            state.events.clear();
+        }
+        state.config = Some(CalDavConfig {
+            calendar_url,
+            username: username.to_string(),
+            password_encrypted: base64::engine::general_purpose::STANDARD.encode(encrypted),
+            reminder_days: reminder_days.unwrap_or_else(default_reminder_days),
+        });
+        self.persist(&state)
+    }
+
+    pub fn config(&self) -> Option<CalDavConfig> {
+        self.state.lock().unwrap().config.clone()
+    }
+
+    pub fn events(&self) -> Vec<SyncedEvent> {
+        self.state.lock().unwrap().events.values().cloned().collect()
+    }
+
+    fn tracks_document(&self, document_id: &str) -> bool {
+        self.state.lock().unwrap().events.values().any(|e| e.document_id == document_id)
+    }
+
+    pub fn credentials(&self, security: &SecurityManager) -> Result<CalDavCredentials> {
+        let config = self.config().ok_or_else(|| anyhow!("No CalDAV calendar is configured"))?;
+        let encrypted = base64::engine::general_purpose::STANDARD.decode(&config.password_encrypted)?;
+        let password = String::from_utf8(security.decrypt_data(&encrypted)?)?;
+        Ok(CalDavCredentials { config, password })
+    }
+
+    /// Pull remote changes since the last sync token and flag the events we manage that
+    /// someone edited or deleted in the calendar
+    async fn refresh_remote_changes(&self, credentials: &CalDavCredentials) -> Result<()> {
+        let CalDavCredentials { config, password } = credentials;
+        let sync_token = self.state.lock().unwrap().sync_token.clone();
+        let body = format!(
+            r#"<?xml version="1.0" encoding="utf-8"?><d:sync-collection xmlns:d="DAV:"><d:sync-token>{}</d:sync-token><d:sync-level>1</d:sync-level><d:prop><d:getetag/></d:prop></d:sync-collection>"#,
+            sync_token.clone().unwrap_or_default()
+        );
+        let response = self
+            .client
+            .request(Method::from_bytes(b"REPORT")?, &config.calendar_url)
+            .basic_auth(&config.username, Some(password))
+            .header("Content-Type", "application/xml; charset=utf-8")
+            .header("Depth", "1")
+            .body(body)
+            .send()
+            .await?;
+
+        // An expired token means starting over; our stored ETags still guard every write
+        if sync_token.is_some() && matches!(response.status(), StatusCode::FORBIDDEN | StatusCode::CONFLICT) {
+            log::warn!("CalDAV sync token rejected, starting a fresh sync");
+            self.state.lock().unwrap().sync_token = None;
+            return Ok(());
+        }
+        let status = response.status();
+        if !status.is_success() {
+            return Err(anyhow!("CalDAV sync-collection failed: HTTP {}", status));
+        }
+        let changes = parse_sync_response(&response.text().await?);
+
+        let mut state = self.state.lock().unwrap();
+        if sync_token.is_some() {
+            for event in state.events.values_mut() {
+                let path = href_path(&event.href);
+                if changes.deleted.iter().any(|h| href_path(h) == path) {
+                    event.conflict = Some(ConflictReason::DeletedRemotely);
+                } else if let Some((_, etag)) = changes.changed.iter().find(|(h, _)| href_path(h) == path) {
+                    match &event.etag {
+                        // The server did not return an ETag on our write; adopt the first one seen
+                        None => event.etag = etag.clone(),
+                        Some(ours) if etag.as_ref().map_or(false, |theirs| theirs != ours) => {
+                            event.conflict = Some(ConflictReason::ModifiedRemotely);
+                        }
+                        Some(_) => {}
+                    }
+                }
+            }
+        }
+        state.sync_token = changes.sync_token;
+        self.persist(&state)
+    }
+
+    async fn put_event(
+        &self,
+        credentials: &CalDavCredentials,
+        href: &str,
+        deadline: &ExtractedDeadline,
+        etag: Option<&str>,
+    ) -> Result<(StatusCode, Option<String>)> {
+        let mut request = self
+            .client
+            .put(href)
+            .basic_auth(&credentials.config.username, Some(&credentials.password))
+            .header("Content-Type", "text/calendar; charset=utf-8")
+            .body(render_ics(std::slice::from_ref(deadline), credentials.config.reminder_days));
+        request = match etag {
+            Some(etag) => request.header("If-Match", etag),
+            None => request.header("If-None-Match", "*"),
+        };
+        let response = request.send().await?;
+        let etag = response
+            .headers()
+            .get("ETag")
+            .and_then(|v| v.to_str().ok())
+            .map(String::from);
+        Ok((response.status(), etag))
+    }
+
+    async fn delete_event(&self, credentials: &CalDavCredentials, event: &SyncedEvent) -> Result<StatusCode> {
+        let mut request = self
+            .client
+            .delete(&event.href)
+            .basic_auth(&credentials.config.username, Some(&credentials.password));
+        if let Some(etag) = &event.etag {
+            request = request.header("If-Match", etag);
+        }
+        Ok(request.send().await?.status())
+    }
+
+    /// Make the calendar match the current deadlines of one document
+    pub async fn sync_document(
+        &self,
+        document_id: &str,
+        deadlines: &[ExtractedDeadline],
+        credentials: &CalDavCredentials,
+    ) -> Result<CalendarSyncReport> {
+        let config = &credentials.config;
+        self.refresh_remote_changes(credentials).await?;
+
+        let mut report = CalendarSyncReport::default();
+        for deadline in deadlines {
+            let hash = content_hash(deadline, config.reminder_days);
+            let existing = self.state.lock().unwrap().events.get(&deadline.uid).cloned();
+            let href = match &existing {
+                Some(event) if event.detached => {
+                    report.unchanged += 1;
+                    continue;
+                }
+                Some(event) if event.conflict.is_some() => {
+                    report.conflicts.push(event.uid.clone());
+                    continue;
+                }
+                Some(event) if event.content_hash == hash => {
+                    report.unchanged += 1;
+                    continue;
+                }
+                Some(event) => event.href.clone(),
+                None => format!("{}{}.ics", config.calendar_url, deadline.uid.replace('@', "-")),
+            };
+
+            let etag = existing.as_ref().and_then(|e| e.etag.as_deref());
+            match self.put_event(credentials, &href, deadline, etag).await {
+                Ok((status, new_etag)) if status.is_success() => {
+                    if existing.is_some() {
+                        report.updated += 1;
+                    } else {
+                        report.created += 1;
+                    }
+                    self.state.lock().unwrap().events.insert(
+                        deadline.uid.clone(),
+                        SyncedEvent {
+                            uid: deadline.uid.clone(),
+                            document_id: document_id.to_string(),
+                            href,
+                            etag: new_etag,
+                            content_hash: hash,
+                            conflict: None,
+                            detached: false,
+                        },
+                    );
+                }
+                // The calendar copy changed since we last wrote it: keep theirs
+                Ok((StatusCode::PRECONDITION_FAILED, _)) => {
+                    report.conflicts.push(deadline.uid.clone());
+                    let mut state = self.state.lock().unwrap();
+                    let event = state.events.entry(deadline.uid.clone()).or_insert_with(|| SyncedEvent {
+                        uid: deadline.uid.clone(),
+                        document_id: document_id.to_string(),
+                        href,
+                        etag: None,
+                        content_hash: String::new(),
+                        conflict: None,
+                        detached: false,
+                    });
+                    event.conflict = Some(ConflictReason::ModifiedRemotely);
+                }
+                Ok((status, _)) => report.errors.push(format!("{}: HTTP {}", deadline.uid, status)),
+                Err(e) => report.errors.push(format!("{}: {}", deadline.uid, e)),
+            }
+        }
+
+        // Deadlines that disappeared from the document
+        let stale: Vec<SyncedEvent> = self
+            .state
+            .lock()
+            .unwrap()
+            .events
+            .values()
+            .filter(|e| e.document_id == document_id && !deadlines.iter().any(|d| d.uid == e.uid))
+            .cloned()
+            .collect();
+        for event in stale {
+            if event.detached {
+                self.state.lock().unwrap().events.remove(&event.uid);
+                continue;
+            }
+            if event.conflict.is_some() {
+                report.conflicts.push(event.uid.clone());
+                continue;
+            }
+            match self.delete_event(credentials, &event).await {
+                Ok(status) if status.is_success() || status == StatusCode::NOT_FOUND => {
+                    report.deleted += 1;
+                    self.state.lock().unwrap().events.remove(&event.uid);
+                }
+                Ok(StatusCode::PRECONDITION_FAILED) => {
+                    report.conflicts.push(event.uid.clone());
+                    if let Some(e) = self.state.lock().unwrap().events.get_mut(&event.uid) {
+                        e.conflict = Some(ConflictReason::ModifiedRemotely);
+                    }
+                }
+                Ok(status) => report.errors.push(format!("{}: HTTP {}", event.uid, status)),
+                Err(e) => report.errors.push(format!("{}: {}", event.uid, e)),
+            }
+        }
+
+        let state = self.state.lock().unwrap();
+        self.persist(&state)?;
+        Ok(report)
+    }
+
+    /// Settle a conflict: `keep_local` replaces the calendar copy on the next sync of the
+    /// document, otherwise the calendar copy is kept and left alone from now on
+    pub async fn resolve_conflict(&self, uid: &str, keep_local: bool, credentials: &CalDavCredentials) -> Result<()> {
+        let event = self
+            .state
+            .lock()
+            .unwrap()
+            .events
+            .get(uid)
+            .cloned()
+            .ok_or_else(|| anyhow!("Calendar event {} is not tracked", uid))?;
+
+        if keep_local && event.conflict == Some(ConflictReason::ModifiedRemotely) {
+            let unconditional = SyncedEvent { etag: None, ..event.clone() };
+            let status = self.delete_event(credentials, &unconditional).await?;
+            if !(status.is_success() || status == StatusCode::NOT_FOUND) {
+                return Err(anyhow!("Could not replace calendar event: HTTP {}", status));
+            }
+        }
+
+        let mut state = self.state.lock().unwrap();
+        if keep_local {
+            state.events.remove(uid);
+        } else if let Some(event) = state.events.get_mut(uid) {
+            event.conflict = None;
+            event.detached = true;
+        }
+        self.persist(&state)
+    }
+}
+
+pub type CalendarSyncStorage = Arc<CalendarSync>;
+type SecurityStorage = Arc<Mutex<SecurityManager>>;
+
+fn caldav_credentials(calendar: &CalendarSync, security: &SecurityStorage) -> Result<CalDavCredentials, String> {
+    calendar
+        .credentials(&security.lock().unwrap())
+        .map_err(|e| e.to_string())
+}
+
+async fn analyze_deadlines(
+    analyzer: &AnalyzerStorage,
+    document_id: &str,
+    file_path: &str,
+) -> Result<Vec<ExtractedDeadline>, String> {
+    let path = Path::new(file_path);
+    let analysis = analyzer.analyze_document(path).await.map_err(|e| e.to_string())?;
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| document_id.to_string());
+    Ok(deadlines_from_analysis(document_id, &name, &analysis))
+}
+
+fn log_hook_report(action: &str, document_id: &str, report: Result<CalendarSyncReport, String>) {
+    match report {
+        Ok(report) if report.errors.is_empty() => {}
+        Ok(report) => log::warn!("Calendar {} of {} left errors: {}", action, document_id, report.errors.join("; ")),
+        Err(e) => log::warn!("Calendar {} of {} failed: {}", action, document_id, e),
+    }
+}
+
+/// Push the deadlines of a newly indexed file to the configured calendar. Does nothing until a
+/// CalDAV calendar is configured; failures are logged so ingest never fails on them.
+pub async fn sync_ingested_document(
+    calendar: &CalendarSync,
+    analyzer: &AnalyzerStorage,
+    security: &SecurityStorage,
+    document_id: &str,
+    path: &Path,
+) {
+    if calendar.config().is_none() {
+        return;
+    }
+    let report = async {
+        let deadlines = analyze_deadlines(analyzer, document_id, &path.to_string_lossy()).await?;
+        let credentials = caldav_credentials(calendar, security)?;
+        calendar
+            .sync_document(document_id, &deadlines, &credentials)
+            .await
+            .map_err(|e| e.to_string())
+    }
+    .await;
+    log_hook_report("sync", document_id, report);
+}
+
+/// Take a deleted document's events off the calendar; failures are logged so deletion never
+/// fails on them
+pub async fn remove_deleted_document(calendar: &CalendarSync, security: &SecurityStorage, document_id: &str) {
+    if calendar.config().is_none() || !calendar.tracks_document(document_id) {
+        return;
+    }
+    let report = match caldav_credentials(calendar, security) {
+        Ok(credentials) => calendar
+            .sync_document(document_id, &[], &credentials)
+            .await
+            .map_err(|e| e.to_string()),
+        Err(e) => Err(e),
+    };
+    log_hook_report("removal", document_id, report);
+}
+
+#[tauri::command]
+pub async fn calendar_extract_deadlines(
+    document_id: String,
+    file_path: String,
+    analyzer: tauri::State<'_, AnalyzerStorage>,
+) -> Result<Vec<ExtractedDeadline>, String> {
+    analyze_deadlines(&analyzer, &document_id, &file_path).await
+}
+
+#[tauri::command]
+pub async fn calendar_export_ics(
+    session_id: String,
+    document_id: String,
+    file_path: String,
+    output_path: String,
+    analyzer: tauri::State<'_, AnalyzerStorage>,
+    calendar: tauri::State<'_, CalendarSyncStorage>,
+    sessions: tauri::State<'_, SessionStorage>,
+    matters: tauri::State<'_, MatterStorage>,
+    barriers: tauri::State<'_, BarrierStorage>,
+    security: tauri::State<'_, SecurityStorage>,
+) -> Result<usize, String> {
+    let user = authenticated_user(&session_id, &sessions)?;
+    let mut items = matters.export_items(&[file_path.clone()]);
+    items.push((document_id.clone(), None));
+    barriers.check_export(&security, &user, &items)?;
+    let deadlines = analyze_deadlines(&analyzer, &document_id, &file_path).await?;
+    let reminder_days = calendar.config().map(|c| c.reminder_days).unwrap_or_else(default_reminder_days);
+    fs::write(&output_path, render_ics(&deadlines, reminder_days)).map_err(|e| e.to_string())?;
+    Ok(deadlines.len())
+}
+
+#[tauri::command]
+pub async fn calendar_configure_caldav(
+    calendar_url: String,
+    username: String,
+    password: String,
+    reminder_days: Option<u32>,
+    calendar: tauri::State<'_, CalendarSyncStorage>,
+    security: tauri::State<'_, SecurityStorage>,
+) -> Result<(), String> {
+    let security = security.lock().unwrap();
+    calendar
+        .configure(&calendar_url, &username, &password, reminder_days, &security)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn calendar_sync_document(
+    document_id: String,
+    file_path: String,
+    analyzer: tauri::State<'_, AnalyzerStorage>,
+    calendar: tauri::State<'_, CalendarSyncStorage>,
+    security: tauri::State<'_, SecurityStorage>,
+) -> Result<CalendarSyncReport, String> {
+    let deadlines = analyze_deadlines(&analyzer, &document_id, &file_path).await?;
+    let credentials = caldav_credentials(&calendar, &security)?;
+    calendar
+        .sync_document(&document_id, &deadlines, &credentials)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Remove a document's events, e.g. after the document was deleted
+#[tauri::command]
+pub async fn calendar_remove_document(
+    document_id: String,
+    calendar: tauri::State<'_, CalendarSyncStorage>,
+    security: tauri::State<'_, SecurityStorage>,
+) -> Result<CalendarSyncReport, String> {
+    let credentials = caldav_credentials(&calendar, &security)?;
+    calendar
+        .sync_document(&document_id, &[], &credentials)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn calendar_list_synced_events(
+    calendar: tauri::State<'_, CalendarSyncStorage>,
+) -> Result<Vec<SyncedEvent>, String> {
+    Ok(calendar.events())
+}
+
+#[tauri::command]
+pub async fn calendar_resolve_conflict(
+    uid: String,
+    keep_local: bool,
+    calendar: tauri::State<'_, CalendarSyncStorage>,
+    security: tauri::State<'_, SecurityStorage>,
+) -> Result<(), String> {
+    let credentials = caldav_credentials(&calendar, &security)?;
+    calendar
+        .resolve_conflict(&uid, keep_local, &credentials)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deadline_classification_and_dates() {
+        assert_eq!(parse_deadline_date("03/15/2026"), NaiveDate::from_ymd_opt(2026, 3, 15));
+        assert_eq!(parse_deadline_date("January 5, 2027"), NaiveDate::from_ymd_opt(2027, 1, 5));
+        assert_eq!(parse_deadline_date("Smarch 5, 2027"), None);
+
+        assert_eq!(
+            classify_deadline("any claim is subject to a limitation period ending March 1, 2027"),
+            Some(DeadlineKind::Limitation)
+        );
+        assert_eq!(
+            classify_deadline("Supplier shall deliver the goods no later than 03/15/2026"),
+            Some(DeadlineKind::Obligation)
+        );
+        assert_eq!(classify_deadline("This Agreement is dated 01/01/2025 between"), None);
+        assert_eq!(classify_deadline("The first instalment is due on 1 March 2026"), Some(DeadlineKind::Obligation));
+
+        // Words that surround most dates in a contract do not make them deadlines
+        assert_eq!(classify_deadline("The parties shall meet on 03/15/2026 to discuss the project"), None);
+        assert_eq!(classify_deadline("Due diligence was completed on 01/10/2025"), None);
+        assert_eq!(classify_deadline("Notice of the meeting was given on 02/02/2025"), None);
+        assert_eq!(classify_deadline("without undue on-site delay, starting 02/02/2025"), None);
+    }
+
+    #[test]
+    fn test_parse_sync_response() {
+        let xml = r#"<?xml version="1.0"?>
+<d:multistatus xmlns:d="DAV:">
+  <d:response>
+    <d:href>/cal/abc-bear-ai.ics</d:href>
+    <d:propstat><d:prop><d:getetag>&quot;2&quot;</d:getetag></d:prop><d:status>HTTP/1.1 200 OK</d:status></d:propstat>
+  </d:response>
+  <d:response>
+    <d:href>/cal/old-bear-ai.ics</d:href>
+    <d:status>HTTP/1.1 404 Not Found</d:status>
+  </d:response>
+  <d:sync-token>http://example.com/sync/42</d:sync-token>
+</d:multistatus>"#;
+
+        let changes = parse_sync_response(xml);
+        assert_eq!(changes.sync_token.as_deref(), Some("http://example.com/sync/42"));
+        assert_eq!(changes.changed, vec![("/cal/abc-bear-ai.ics".to_string(), Some("\"2\"".to_string()))]);
+        assert_eq!(changes.deleted, vec!["/cal/old-bear-ai.ics".to_string()]);
+    }
+}
@@ -0,0 +1,471 @@
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, Utc};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+
+use crate::locale_formats::{self, detect_date_order};
+use crate::nemotron_rag::{DocumentType, LegalDocument};
+
+/// Case Outcome and Judge Analytics for BEAR AI
+/// When case law or opinions are indexed, the judge, court, filing and decision dates and any
+/// motion rulings are recorded alongside the RAG index. Statistics are computed over that local
+/// corpus only; every figure lists the documents it was drawn from, and grant rates are withheld
+/// until a judge has enough rulings on a motion type to say anything.
+pub const MIN_RULINGS_FOR_RATE: usize = 5;
+
+const DISCLAIMER: &str = "These statistics reflect only the case documents indexed in this BEAR AI workspace. \
+They are not a complete record of any judge's decisions and must not be relied on to predict the outcome of a matter.";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MotionOutcome {
+    Granted,
+    GrantedInPart,
+    Denied,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MotionRuling {
+    pub motion_type: String, // "motion to dismiss", "motion for summary judgment"
+    pub outcome: MotionOutcome,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaseRecord {
+    pub document_id: String,
+    pub title: String,
+    pub jurisdiction: String,
+    pub court: Option<String>,
+    pub judge: Option<String>,
+    pub filed_on: Option<NaiveDate>,
+    pub decided_on: Option<NaiveDate>,
+    pub rulings: Vec<MotionRuling>,
+    pub indexed_at: DateTime<Utc>,
+}
+
+/// A document a statistic was computed from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceCase {
+    pub document_id: String,
+    pub title: String,
+    pub decided_on: Option<NaiveDate>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MotionStatistics {
+    pub motion_type: String,
+    pub rulings: usize,
+    pub granted: usize,
+    pub granted_in_part: usize,
+    pub denied: usize,
+    pub grant_rate: Option<f64>, // granted in part counts as granted; None below MIN_RULINGS_FOR_RATE
+    pub sources: Vec<String>,    // document ids
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JudgeStatistics {
+    pub judge: String,
+    pub courts: Vec<String>,
+    pub cases: usize,
+    pub motions: Vec<MotionStatistics>,
+    pub average_days_to_decision: Option<f64>,
+    pub decision_time_sample: usize, // cases with both a filing and a decision date
+    pub sources: Vec<SourceCase>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JudgeAnalyticsReport {
+    pub generated_at: DateTime<Utc>,
+    pub corpus_cases: usize,
+    pub cases_without_judge: usize,
+    pub min_rulings_for_rate: usize,
+    pub judges: Vec<JudgeStatistics>,
+    pub disclaimer: String,
+}
+
+fn clean_name(name: &str) -> String {
+    name.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_matches(|c: char| c == ',' || c == '.' || c == ';')
+        .to_string()
+}
+
+fn judge_key(name: &str) -> String {
+    let lower = name.to_lowercase();
+    let stripped = ["the honourable ", "the honorable ", "hon. ", "hon ", "judge ", "justice "]
+        .iter()
+        .fold(lower.as_str(), |rest, prefix| rest.strip_prefix(prefix).unwrap_or(rest));
+    clean_name(stripped)
+}
+
+fn extract_judge(text: &str) -> Option<String> {
+    let labelled = Regex::new(
+        r"(?m)^\s*(?:Before|BEFORE|Judge|JUDGE|Presiding Judge|PRESIDING JUDGE)\s*:\s*(?:(?:the\s+)?Hon(?:ourable|orable|\.)?\s+)?(?:Judge\s+|Justice\s+)?([A-Z][\w.'-]+(?:[ \t]+[A-Z][\w.'-]+){0,3})",
+    )
+    .unwrap();
+    let titled = Regex::new(
+        r"([A-Z][\w.'-]+(?:[ \t]+[A-Z][\w.'-]+){0,3}),\s+(?:United States\s+)?(?:District|Circuit|Magistrate|Presiding|Chief)\s+Judge\b",
+    )
+    .unwrap();
+    labelled
+        .captures(text)
+        .or_else(|| titled.captures(text))
+        .map(|caps| clean_name(&caps[1]))
+}
+
+fn extract_court(text: &str) -> Option<String> {
+    text.lines()
+        .take(40)
+        .map(str::trim)
+        .find(|line| line.len() < 120 && line.to_lowercase().contains("court"))
+        .map(clean_name)
+}
+
+/// Date following a "Filed:" or "Decided:" style label
+pub(crate) fn labelled_date(text: &str, labels: &str) -> Option<NaiveDate> {
+    let order = detect_date_order("en", text);
+    let re = Regex::new(&format!(r"(?i)\b(?:{})\b\s*:?\s*(?:on\s+)?", labels)).unwrap();
+    let date = re.find_iter(text).find_map(|m| {
+        let rest: String = text[m.end()..].chars().take(40).collect();
+        locale_formats::find_dates(&rest, order)
+            .into_iter()
+            .find(|date| date.start == 0)
+            .and_then(|date| NaiveDate::parse_from_str(&date.normalized, "%Y-%m-%d").ok())
+    });
+    date
+}
+
+const HEDGES: &[&str] = &["should", "must", "will", "would", "could", "may", "might", "cannot", "not"];
+
+/// Motion rulings such as "Defendant's motion to dismiss is GRANTED"; a later ruling on the same
+/// motion type (usually the order at the end) replaces an earlier mention
+pub fn extract_rulings(text: &str) -> Vec<MotionRuling> {
+    let re = Regex::new(
+        r"(?i)\b(motion|application|petition)\s+(to|for)\s+([a-z][a-z' -]{2,40}?)\s+(?:is|was|be|shall be)\s+(?:hereby\s+)?(granted in part and denied in part|granted in part|granted|denied)\b",
+    )
+    .unwrap();
+    let mut rulings: Vec<MotionRuling> = Vec::new();
+    for caps in re.captures_iter(text) {
+        // "the motion to dismiss should be granted" is argument, not a ruling
+        if caps[3].split_whitespace().any(|w| HEDGES.contains(&w.to_lowercase().as_str())) {
+            continue;
+        }
+        let motion_type = format!("{} {} {}", &caps[1], &caps[2], clean_name(&caps[3])).to_lowercase();
+        let outcome = match caps[4].to_lowercase().as_str() {
+            "granted" => MotionOutcome::Granted,
+            "denied" => MotionOutcome::Denied,
+            _ => MotionOutcome::GrantedInPart,
+        };
+        rulings.retain(|r| r.motion_type != motion_type);
+        rulings.push(MotionRuling { motion_type, outcome });
+    }
+    rulings
+}
+
+/// Build the analytics record for an indexed document; metadata set by the caller wins over
+/// what is extracted from the text
+pub fn extract_case_record(document: &LegalDocument) -> CaseRecord {
+    let text = &document.content;
+    CaseRecord {
+        document_id: document.id.clone(),
+        title: document.title.clone(),
+        jurisdiction: document.jurisdiction.clone(),
+        court: document.metadata.court.clone().or_else(|| extract_court(text)),
+        judge: document.metadata.judge.clone().or_else(|| extract_judge(text)),
+        filed_on: labelled_date(text, "filed|date filed|submitted"),
+        decided_on: labelled_date(text, "decided|date of decision|judgment date|dated"),
+        rulings: extract_rulings(text),
+        indexed_at: Utc::now(),
+    }
+}
+
+/// Judge-level statistics over the given records, optionally for judges matching `judge`
+pub fn build_report(records: &[CaseRecord], judge: Option<&str>) -> JudgeAnalyticsReport {
+    let filter = judge.map(judge_key);
+    let mut by_judge: BTreeMap<String, Vec<&CaseRecord>> = BTreeMap::new();
+    let mut cases_without_judge = 0;
+    for record in records {
+        match &record.judge {
+            Some(name) => {
+                let key = judge_key(name);
+                if !matches!(&filter, Some(f) if !key.contains(f.as_str())) {
+                    by_judge.entry(key).or_default().push(record);
+                }
+            }
+            None => cases_without_judge += 1,
+        }
+    }
+
+    let mut judges: Vec<JudgeStatistics> = by_judge
+        .into_values()
+        .map(|cases| {
+            // Show the name as most often written in the corpus
+            let mut spellings: HashMap<&str, usize> = HashMap::new();
+            for case in &cases {
+                *spellings.entry(case.judge.as_deref().unwrap_or_default()).or_default() += 1;
+            }
+            let name = spellings
+                .into_iter()
+                .max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(a.0)))
+                .map(|(name, _)| name.to_string())
+                .unwrap_or_default();
+
+            let courts: BTreeSet<String> = cases.iter().filter_map(|c| c.court.clone()).collect();
+
+            let mut motions: BTreeMap<&str, MotionStatistics> = BTreeMap::new();
+            for case in &cases {
+                for ruling in &case.rulings {
+                    let stats = motions.entry(ruling.motion_type.as_str()).or_insert_with(|| MotionStatistics {
+                        motion_type: ruling.motion_type.clone(),
+                        rulings: 0,
+                        granted: 0,
+                        granted_in_part: 0,
+                        denied: 0,
+                        grant_rate: None,
+                        sources: Vec::new(),
+                    });
+                    stats.rulings += 1;
+                    match ruling.outcome {
+                        MotionOutcome::Granted => stats.granted += 1,
+                        MotionOutcome::GrantedInPart => stats.granted_in_part += 1,
+                        MotionOutcome::Denied => stats.denied += 1,
+                    }
+                    stats.sources.push(case.document_id.clone());
+                }
+            }
+            let motions = motions
+                .into_values()
+                .map(|mut stats| {
+                    if stats.rulings >= MIN_RULINGS_FOR_RATE {
+                        stats.grant_rate = Some((stats.granted + stats.granted_in_part) as f64 / stats.rulings as f64);
+                    }
+                    stats
+                })
+                .collect();
+
+            let durations: Vec<i64> = cases
+                .iter()
+                .filter_map(|c| Some((c.decided_on? - c.filed_on?).num_days()))
+                .filter(|days| *days >= 0)
+                .collect();
+
+            JudgeStatistics {
+                judge: name,
+                courts: courts.into_iter().collect(),
+                cases: cases.len(),
+                motions,
+                average_days_to_decision: if durations.is_empty() {
+                    None
+                } else {
+                    Some(durations.iter().sum::<i64>() as f64 / durations.len() as f64)
+                },
+                decision_time_sample: durations.len(),
+                sources: cases
+                    .iter()
+                    .map(|c| SourceCase {
+                        document_id: c.document_id.clone(),
+                        title: c.title.clone(),
+                        decided_on: c.decided_on,
+                    })
+                    .collect(),
+            }
+        })
+        .collect();
+    judges.sort_by(|a, b| b.cases.cmp(&a.cases).then_with(|| a.judge.cmp(&b.judge)));
+
+    JudgeAnalyticsReport {
+        generated_at: Utc::now(),
+        corpus_cases: records.len(),
+        cases_without_judge,
+        min_rulings_for_rate: MIN_RULINGS_FOR_RATE,
+        judges,
+        disclaimer: DISCLAIMER.to_string(),
+    }
+}
+
+pub struct CaseCorpus {
+    path: PathBuf,
+    records: Mutex<Vec<CaseRecord>>,
+}
+
+impl CaseCorpus {
+    pub fn new(app_data_dir: &Path) -> Result<Self> {
+        let path = app_data_dir.join("case_corpus.json");
+        let records = if path.exists() {
+            serde_json::from_str(&fs::read_to_string(&path)?)?
+        } else {
+            Vec::new()
+        };
+        Ok(CaseCorpus {
+            path,
+            records: Mutex::new(records),
+        })
+    }
+
+    /// Add a record, replacing an earlier one for the same document
+    pub fn record(&self, record: CaseRecord) -> Result<()> {
+        let mut records = self.records.lock().unwrap();
+        records.retain(|r| r.document_id != record.document_id);
+        records.push(record);
+        fs::write(&self.path, serde_json::to_string_pretty(&*records)?)?;
+        Ok(())
+    }
+
+    /// Drop the record of a deleted document
+    pub fn remove(&self, document_id: &str) -> Result<()> {
+        let mut records = self.records.lock().unwrap();
+        let before = records.len();
+        records.retain(|r| r.document_id != document_id);
+        if records.len() != before {
+            fs::write(&self.path, serde_json::to_string_pretty(&*records)?)?;
+        }
+        Ok(())
+    }
+
+    pub fn records(&self) -> Vec<CaseRecord> {
+        self.records.lock().unwrap().clone()
+    }
+
+    /// Report over the records of the documents `admit` lets the reader see
+    pub fn report(&self, judge: Option<&str>, admit: impl Fn(&CaseRecord) -> bool) -> JudgeAnalyticsReport {
+        let records: Vec<CaseRecord> = self.records.lock().unwrap().iter().filter(|r| admit(r)).cloned().collect();
+        build_report(&records, judge)
+    }
+}
+
+lazy_static! {
+    static ref GLOBAL_CASE_CORPUS: RwLock<Option<Arc<CaseCorpus>>> = RwLock::new(None);
+}
+
+/// Initialize the global case corpus
+pub fn initialize_case_corpus(app_data_dir: &Path) -> Result<Arc<CaseCorpus>> {
+    let corpus = Arc::new(CaseCorpus::new(app_data_dir)?);
+    *GLOBAL_CASE_CORPUS.write().unwrap() = Some(corpus.clone());
+    Ok(corpus)
+}
+
+/// Get the global case corpus
+pub fn get_case_corpus() -> Option<Arc<CaseCorpus>> {
+    GLOBAL_CASE_CORPUS.read().unwrap().clone()
+}
+
+/// Record an indexed case-law document; other document types and an uninitialized corpus are ignored
+pub fn record_indexed_document(document: &LegalDocument) -> Result<()> {
+    if !matches!(document.document_type, DocumentType::CaseLaw | DocumentType::Opinion) {
+        return Ok(());
+    }
+    match get_case_corpus() {
+        Some(corpus) => corpus.record(extract_case_record(document)),
+        None => Ok(()),
+    }
+}
+
+/// Drop a deleted document from the corpus; ignored when the corpus is not initialized
+pub fn remove_indexed_document(document_id: &str) -> Result<()> {
+    match get_case_corpus() {
+        Some(corpus) => corpus.remove(document_id),
+        None => Ok(()),
+    }
+}
+
+/// Judge analytics over the indexed documents `admit` lets the reader see
+pub fn judge_analytics(judge: Option<String>, admit: impl Fn(&CaseRecord) -> bool) -> Result<JudgeAnalyticsReport, String> {
+    let corpus = get_case_corpus().ok_or_else(|| "Case corpus not initialized".to_string())?;
+    Ok(corpus.report(judge.as_deref(), admit))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nemotron_rag::{DocumentMetadata, PrecedentialValue};
+
+    fn case(id: &str, content: &str) -> LegalDocument {
+        LegalDocument {
+            id: id.to_string(),
+            title: format!("Case {}", id),
+            content: content.to_string(),
+            jurisdiction: "US".to_string(),
+            document_type: DocumentType::CaseLaw,
+            last_updated: Utc::now(),
+            citations: Vec::new(),
+            metadata: DocumentMetadata {
+                court: None,
+                judge: None,
+                parties: Vec::new(),
+                topics: Vec::new(),
+                precedential_value: PrecedentialValue::Persuasive,
+                confidence: 1.0,
+            },
+        }
+    }
+
+    #[test]
+    fn test_extract_case_record() {
+        let document = case(
+            "a",
+            "UNITED STATES DISTRICT COURT FOR THE DISTRICT OF DELAWARE\n\
+             Before: Hon. Maria Lopez\n\
+             Filed: January 10, 2023\n\
+             Decided: March 1, 2023\n\n\
+             Defendant argues the motion to dismiss should be granted. \
+             The motion for summary judgment is denied. \
+             For these reasons, Defendant's motion to dismiss is GRANTED IN PART.",
+        );
+        let record = extract_case_record(&document);
+
+        assert_eq!(record.judge.as_deref(), Some("Maria Lopez"));
+        assert_eq!(record.court.as_deref(), Some("UNITED STATES DISTRICT COURT FOR THE DISTRICT OF DELAWARE"));
+        assert_eq!(record.filed_on, NaiveDate::from_ymd_opt(2023, 1, 10));
+        assert_eq!(record.decided_on, NaiveDate::from_ymd_opt(2023, 3, 1));
+        assert_eq!(
+            record.rulings,
+            vec![
+                MotionRuling { motion_type: "motion for summary judgment".to_string(), outcome: MotionOutcome::Denied },
+                MotionRuling { motion_type: "motion to dismiss".to_string(), outcome: MotionOutcome::GrantedInPart },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_report_withholds_small_samples() {
+        let mut records: Vec<CaseRecord> = (0..6)
+            .map(|i| {
+                let outcome = if i < 4 { "granted" } else { "denied" };
+                extract_case_record(&case(
+                    &i.to_string(),
+                    &format!(
+                        "Judge: Hon. Maria Lopez\nFiled: 2023-01-01\nDecided: 2023-01-{:02}\nThe motion to dismiss is {}.",
+                        11 + i,
+                        outcome
+                    ),
+                ))
+            })
+            .collect();
+        records.push(extract_case_record(&case(
+            "b",
+            "Before: Judge Tom Baker\nThe motion to compel is granted.",
+        )));
+        records.push(extract_case_record(&case("c", "Order without a named judge.")));
+
+        let report = build_report(&records, None);
+        assert_eq!(report.corpus_cases, 8);
+        assert_eq!(report.cases_without_judge, 1);
+        assert_eq!(report.judges[0].judge, "Maria Lopez");
+
+        let lopez = &report.judges[0];
+        assert_eq!(lopez.cases, 6);
+        assert_eq!(lopez.motions[0].grant_rate, Some(4.0 / 6.0));
+        assert_eq!(lopez.average_days_to_decision, Some(12.5));
+        assert_eq!(lopez.sources.len(), 6);
+        assert_eq!(report.judges[1].motions[0].grant_rate, None);
+
+        let baker = build_report(&records, Some("judge tom baker"));
+        assert_eq!(baker.judges.len(), 1);
+        assert_eq!(baker.judges[0].cases, 1);
+    }
+}
@@ -0,0 +1,666 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::future::Future;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::intranet_crawler::parse_html;
+use crate::matters::{Matter, MatterRegistry, MatterStatus, MatterStorage};
+use crate::nemotron_rag::{DocumentMetadata, DocumentType, LegalDocument, PrecedentialValue};
+
+/// Channel Ingestion for BEAR AI
+/// Reads Slack workspace exports and Microsoft Teams channel exports, files each channel's
+/// conversations under a matter as one transcript per day and indexes them for retrieval.
+/// Every line of a transcript keeps its author and timestamp.
+const SLACK_SKIPPED_SUBTYPES: &[&str] = &[
+    "channel_join",
+    "channel_leave",
+    "group_join",
+    "group_leave",
+    "channel_archive",
+    "channel_unarchive",
+    "bot_add",
+    "bot_remove",
+];
+const SLACK_METADATA_FILES: &[&str] = &[
+    "users.json",
+    "channels.json",
+    "groups.json",
+    "dms.json",
+    "mpims.json",
+    "integration_logs.json",
+    "canvases.json",
+    "org_users.json",
+];
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum ChatPlatform {
+    Slack,
+    Teams,
+}
+
+impl ChatPlatform {
+    fn label(&self) -> &'static str {
+        match self {
+            ChatPlatform::Slack => "Slack",
+            ChatPlatform::Teams => "Teams",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ChannelMessage {
+    pub platform: ChatPlatform,
+    pub channel: String,
+    pub message_id: String,
+    pub thread_id: Option<String>, // the thread's parent message when this is a reply
+    pub author: String,
+    pub author_id: Option<String>,
+    pub timestamp: DateTime<Utc>,
+    pub text: String,
+    pub attachments: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChannelSummary {
+    pub platform: ChatPlatform,
+    pub channel: String,
+    pub messages: usize,
+    pub first_message_at: DateTime<Utc>,
+    pub last_message_at: DateTime<Utc>,
+    pub authors: Vec<String>,
+    pub suggested_matter: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChannelIngestRequest {
+    pub archive_path: String, // Slack export ZIP or folder, Teams JSON file or folder
+    pub matter_id: Option<String>, // for channels without their own mapping
+    #[serde(default)]
+    pub channel_matters: HashMap<String, String>, // channel name -> matter id
+    #[serde(default)]
+    pub channels: Vec<String>, // empty: every channel in the archive
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IngestedConversation {
+    pub document_id: String,
+    pub matter_id: String,
+    pub platform: ChatPlatform,
+    pub channel: String,
+    pub date: NaiveDate,
+    pub path: String,
+    pub messages: usize,
+    pub authors: Vec<String>,
+    pub first_message_at: DateTime<Utc>,
+    pub last_message_at: DateTime<Utc>,
+    pub chunks: Option<usize>, // None when indexing failed; the transcript is still filed
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChannelIngestReport {
+    pub conversations: Vec<IngestedConversation>,
+    pub unchanged: usize,
+    pub unassigned_channels: Vec<String>, // no matter given or recognisable from the name
+    pub errors: Vec<String>,
+}
+
+fn sha256_hex(text: &str) -> String {
+    format!("{:x}", Sha256::digest(text.as_bytes()))
+}
+
+/// JSON files of an export as (path inside the export, contents)
+fn archive_entries(path: &Path) -> Result<Vec<(String, Vec<u8>)>> {
+    let mut entries = Vec::new();
+    if path.is_dir() {
+        for entry in walkdir::WalkDir::new(path).into_iter().filter_map(|e| e.ok()) {
+            if entry.file_type().is_file() && entry.path().extension().is_some_and(|e| e.eq_ignore_ascii_case("json")) {
+                let name = entry.path().strip_prefix(path)?.to_string_lossy().replace('\\', "/");
+                entries.push((name, fs::read(entry.path())?));
+            }
+        }
+    } else if path.extension().is_some_and(|e| e.eq_ignore_ascii_case("zip")) {
+        let mut archive = zip::ZipArchive::new(fs::File::open(path)?)?;
+        for i in 0..archive.len() {
+            let mut file = archive.by_index(i)?;
+            if file.is_file() && file.name().to_lowercase().ends_with(".json") {
+                let name = file.name().to_string();
+                let mut contents = Vec::new();
+                file.read_to_end(&mut contents)?;
+                entries.push((name, contents));
+            }
+        }
+    } else {
+        let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        entries.push((name, fs::read(path)?));
+    }
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(entries)
+}
+
+fn file_name(entry: &str) -> &str {
+    entry.rsplit('/').next().unwrap_or(entry)
+}
+
+fn file_stem(entry: &str) -> &str {
+    let name = file_name(entry);
+    name.rsplit_once('.').map_or(name, |(stem, _)| stem)
+}
+
+/// Slack user ids to the names people know them by
+fn slack_users(users: &Value) -> HashMap<String, String> {
+    users
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|user| {
+            let id = user["id"].as_str()?;
+            let name = [&user["real_name"], &user["profile"]["real_name"], &user["profile"]["display_name"], &user["name"]]
+                .iter()
+                .filter_map(|v| v.as_str())
+                .find(|n| !n.trim().is_empty())?;
+            Some((id.to_string(), name.to_string()))
+        })
+        .collect()
+}
+
+/// Replace Slack's <@U123>, <#C123|name> and <url|label> markup with readable text
+fn slack_text(text: &str, users: &HashMap<String, String>) -> String {
+    let markup = Regex::new(r"<([^>|]+)(?:\|([^>]*))?>").unwrap();
+    let text = markup.replace_all(text, |cap: &regex::Captures| {
+        let target = &cap[1];
+        let label = cap.get(2).map(|m| m.as_str()).filter(|l| !l.is_empty());
+        if let Some(user) = target.strip_prefix('@') {
+            format!("@{}", label.or_else(|| users.get(user).map(String::as_str)).unwrap_or(user))
+        } else if let Some(channel) = target.strip_prefix('#') {
+            format!("#{}", label.unwrap_or(channel))
+        } else if let Some(special) = target.strip_prefix('!') {
+            format!("@{}", label.unwrap_or(special.split('^').next().unwrap_or(special)))
+        } else {
+            match label {
+                Some(label) if label != target => format!("{} ({})", label, target),
+                _ => target.to_string(),
+            }
+        }
+    });
+    text.replace("&lt;", "<").replace("&gt;", ">").replace("&amp;", "&")
+}
+
+fn slack_message(channel: &str, message: &Value, users: &HashMap<String, String>) -> Option<ChannelMessage> {
+    if message["subtype"].as_str().is_some_and(|s| SLACK_SKIPPED_SUBTYPES.contains(&s)) {
+        return None;
+    }
+    let ts = message["ts"].as_str()?;
+    let (seconds, fraction) = ts.split_once('.').unwrap_or((ts, "0"));
+    let nanos = format!("{:0<9}", &fraction[..fraction.len().min(9)]).parse().unwrap_or(0);
+    let timestamp = Utc.timestamp_opt(seconds.parse().ok()?, nanos).single()?;
+
+    let author_id = message["user"].as_str().map(str::to_string);
+    let author = author_id
+        .as_ref()
+        .and_then(|id| users.get(id).cloned())
+        .or_else(|| message["user_profile"]["real_name"].as_str().map(str::to_string))
+        .or_else(|| message["username"].as_str().map(str::to_string))
+        .or_else(|| author_id.clone())
+        .unwrap_or_else(|| "Unknown".to_string());
+    let attachments: Vec<String> = message["files"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|f| f["name"].as_str().or_else(|| f["title"].as_str()).map(str::to_string))
+        .collect();
+    let text = slack_text(message["text"].as_str().unwrap_or(""), users);
+    if text.trim().is_empty() && attachments.is_empty() {
+        return None;
+    }
+
+    Some(ChannelMessage {
+        platform: ChatPlatform::Slack,
+        channel: channel.to_string(),
+        message_id: message["client_msg_id"].as_str().unwrap_or(ts).to_string(),
+        thread_id: message["thread_ts"].as_str().filter(|t| *t != ts).map(str::to_string),
+        author,
+        author_id,
+        timestamp,
+        text,
+        attachments,
+    })
+}
+
+fn teams_message(channel: &str, message: &Value) -> Option<ChannelMessage> {
+    if message["messageType"].as_str().is_some_and(|t| t != "message") || !message["deletedDateTime"].is_null() {
+        return None;
+    }
+    let timestamp = DateTime::parse_from_rfc3339(message["createdDateTime"].as_str()?)
+        .ok()?
+        .with_timezone(&Utc);
+    let from = &message["from"];
+    let sender = if from["user"].is_object() { &from["user"] } else { &from["application"] };
+    let content = message["body"]["content"].as_str().unwrap_or("");
+    let mut text = if message["body"]["contentType"].as_str().is_some_and(|t| t.eq_ignore_ascii_case("html")) {
+        parse_html(content).text.replace('\n', " ")
+    } else {
+        content.trim().to_string()
+    };
+    if let Some(subject) = message["subject"].as_str().filter(|s| !s.trim().is_empty()) {
+        text = format!("{}: {}", subject.trim(), text);
+    }
+    let attachments: Vec<String> = message["attachments"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|a| a["name"].as_str().filter(|n| !n.is_empty()).map(str::to_string))
+        .collect();
+    if text.trim().is_empty() && attachments.is_empty() {
+        return None;
+    }
+
+    Some(ChannelMessage {
+        platform: ChatPlatform::Teams,
+        channel: channel.to_string(),
+        message_id: message["id"].as_str().unwrap_or("").to_string(),
+        thread_id: message["replyToId"].as_str().map(str::to_string),
+        author: sender["displayName"].as_str().unwrap_or("Unknown").to_string(),
+        author_id: sender["id"].as_str().map(str::to_string),
+        timestamp,
+        text,
+        attachments,
+    })
+}
+
+/// Messages from a Slack export (channel folders of daily JSON files) or Teams exports
+/// (Graph chatMessage arrays, bare or under "value"/"messages"), oldest first
+pub fn parse_export(entries: &[(String, Vec<u8>)]) -> Result<Vec<ChannelMessage>> {
+    let users = entries
+        .iter()
+        .find(|(name, _)| file_name(name) == "users.json")
+        .and_then(|(_, data)| serde_json::from_slice(data).ok())
+        .map(|users: Value| slack_users(&users))
+        .unwrap_or_default();
+
+    let mut messages = Vec::new();
+    for (name, data) in entries {
+        if SLACK_METADATA_FILES.contains(&file_name(name)) {
+            continue;
+        }
+        let Ok(value) = serde_json::from_slice::<Value>(data) else {
+            log::warn!("Skipping unreadable export file {}", name);
+            continue;
+        };
+        let items = match &value {
+            Value::Array(items) => items,
+            _ => match value["value"].as_array().or_else(|| value["messages"].as_array()) {
+                Some(items) => items,
+                None => continue,
+            },
+        };
+        for item in items {
+            if item.get("ts").is_some() {
+                // Slack: <channel>/<YYYY-MM-DD>.json
+                let channel = name.rsplit('/').nth(1).unwrap_or_else(|| file_stem(name));
+                messages.extend(slack_message(channel, item, &users));
+            } else if item.get("createdDateTime").is_some() {
+                let channel = value["channelName"]
+                    .as_str()
+                    .or_else(|| value["displayName"].as_str())
+                    .unwrap_or_else(|| file_stem(name));
+                messages.extend(teams_message(channel, item));
+            }
+        }
+    }
+    if messages.is_empty() {
+        return Err(anyhow!("No Slack or Teams messages found in the export"));
+    }
+    messages.sort_by_key(|m| m.timestamp);
+    Ok(messages)
+}
+
+pub fn read_export(path: &Path) -> Result<Vec<ChannelMessage>> {
+    parse_export(&archive_entries(path)?)
+}
+
+fn name_tokens(text: &str) -> BTreeSet<String> {
+    const IGNORED: &[&str] = &["the", "and", "inc", "ltd", "llc", "llp", "corp", "plc", "gmbh", "matter", "case", "team"];
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|t| t.len() >= 3 && !IGNORED.contains(t))
+        .map(str::to_string)
+        .collect()
+}
+
+/// The open matter a channel's name points at, when exactly one matches best
+pub fn suggest_matter(channel: &str, matters: &[Matter]) -> Option<String> {
+    let channel_tokens = name_tokens(channel);
+    let channel_lower = channel.to_lowercase();
+    let mut scored: Vec<(usize, &Matter)> = matters
+        .iter()
+        .filter(|m| m.status != MatterStatus::Closed)
+        .map(|m| {
+            if channel_lower.contains(&m.id.to_lowercase()) {
+                return (usize::MAX, m);
+            }
+            let matter_tokens: BTreeSet<String> = name_tokens(&m.name).union(&name_tokens(&m.client)).cloned().collect();
+            (channel_tokens.intersection(&matter_tokens).count(), m)
+        })
+        .filter(|(score, _)| *score > 0)
+        .collect();
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    match scored.as_slice() {
+        [(best, matter), rest @ ..] if !rest.first().is_some_and(|(next, _)| next >= best) => Some(matter.id.clone()),
+        _ => None,
+    }
+}
+
+pub fn summarize_channels(messages: &[ChannelMessage], matters: &[Matter]) -> Vec<ChannelSummary> {
+    let mut channels: BTreeMap<(ChatPlatform, String), Vec<&ChannelMessage>> = BTreeMap::new();
+    for message in messages {
+        channels.entry((message.platform, message.channel.clone())).or_default().push(message);
+    }
+    channels
+        .into_iter()
+        .map(|((platform, channel), messages)| ChannelSummary {
+            platform,
+            messages: messages.len(),
+            first_message_at: messages[0].timestamp,
+            last_message_at: messages[messages.len() - 1].timestamp,
+            authors: messages.iter().map(|m| m.author.clone()).collect::<BTreeSet<_>>().into_iter().collect(),
+            suggested_matter: suggest_matter(&channel, matters),
+            channel,
+        })
+        .collect()
+}
+
+/// One day of a channel as a transcript; thread replies are marked so they read in context
+pub fn render_transcript(platform: ChatPlatform, channel: &str, date: NaiveDate, messages: &[&ChannelMessage]) -> String {
+    let mut lines = vec![format!("{} #{} - {}", platform.label(), channel, date.format("%Y-%m-%d")), String::new()];
+    for message in messages {
+        let mut line = format!(
+            "{}[{}] {}: {}",
+            if message.thread_id.is_some() { "  ↳ " } else { "" },
+            message.timestamp.format("%H:%M:%S UTC"),
+            message.author,
+            message.text.trim()
+        );
+        for attachment in &message.attachments {
+            line.push_str(&format!(" [attachment: {}]", attachment));
+        }
+        lines.push(line);
+    }
+    lines.join("\n")
+}
+
+/// Channel transcripts written under the app data directory, with the hashes of what was
+/// indexed so re-importing an export only touches days that changed
+pub struct ChannelArchives {
+    pub dir: PathBuf,
+    ledger_path: PathBuf,
+    ledger: Mutex<HashMap<String, String>>, // document id -> sha256 of the indexed transcript
+}
+
+impl ChannelArchives {
+    pub fn new(app_data_dir: &Path) -> Self {
+        let dir = app_data_dir.join("channel_archives");
+        let ledger_path = dir.join("ingested.json");
+        let ledger = fs::read_to_string(&ledger_path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default();
+        Self { dir, ledger_path, ledger: Mutex::new(ledger) }
+    }
+
+    fn persist(&self, ledger: &HashMap<String, String>) -> Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        fs::write(&self.ledger_path, serde_json::to_string_pretty(ledger)?)?;
+        Ok(())
+    }
+
+    /// File the export's conversations under their matters and hand each new or changed day
+    /// to `index`, which stores it with the given chunk metadata and returns the chunk count
+    pub async fn ingest<F, Fut>(&self, request: &ChannelIngestRequest, matters: &MatterRegistry, mut index: F) -> Result<ChannelIngestReport>
+    where
+        F: FnMut(LegalDocument, HashMap<String, String>) -> Fut,
+        Fut: Future<Output = Result<usize>>,
+    {
+        let archive = Path::new(&request.archive_path);
+        let archive_name = archive.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        let messages: Vec<ChannelMessage> = read_export(archive)?
+            .into_iter()
+            .filter(|m| request.channels.is_empty() || request.channels.contains(&m.channel))
+            .filter(|m| !request.since.is_some_and(|since| m.timestamp < since))
+            .filter(|m| !request.until.is_some_and(|until| m.timestamp > until))
+            .collect();
+
+        let all_matters = matters.list();
+        let mut days: BTreeMap<(ChatPlatform, String, NaiveDate), Vec<&ChannelMessage>> = BTreeMap::new();
+        for message in &messages {
+            days.entry((message.platform, message.channel.clone(), message.timestamp.date_naive()))
+                .or_default()
+                .push(message);
+        }
+
+        let mut report = ChannelIngestReport::default();
+        let mut channel_matter: HashMap<String, Option<Matter>> = HashMap::new();
+        let mut attached: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for ((platform, channel, date), day) in days {
+            let matter = channel_matter
+                .entry(channel.clone())
+                .or_insert_with(|| {
+                    let id = request
+                        .channel_matters
+                        .get(&channel)
+                        .cloned()
+                        .or_else(|| request.matter_id.clone())
+                        .or_else(|| suggest_matter(&channel, &all_matters))?;
+                    all_matters.iter().find(|m| m.id == id).cloned()
+                })
+                .clone();
+            let Some(matter) = matter else {
+                if !report.unassigned_channels.contains(&channel) {
+                    report.unassigned_channels.push(channel.clone());
+                }
+                continue;
+            };
+
+            let transcript = render_transcript(platform, &channel, date, &day);
+            let sha = sha256_hex(&transcript);
+            let slug: String = channel
+                .to_lowercase()
+                .chars()
+                .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+                .collect();
+            let document_id = format!("{}-{}-{}", platform.label().to_lowercase(), slug, date.format("%Y-%m-%d"));
+            let matter_dir = self.dir.join(&matter.id);
+            fs::create_dir_all(&matter_dir)?;
+            let path = matter_dir.join(format!("{}.txt", document_id));
+            let path_string = path.to_string_lossy().to_string();
+            attached.entry(matter.id.clone()).or_default().push(path_string.clone());
+
+            let indexed = self.ledger.lock().unwrap().get(&document_id) == Some(&sha);
+            if indexed && path.exists() {
+                report.unchanged += 1;
+                continue;
+            }
+            fs::write(&path, &transcript)?;
+
+            let authors: Vec<String> = day.iter().map(|m| m.author.clone()).collect::<BTreeSet<_>>().into_iter().collect();
+            let first = day[0].timestamp;
+            let last = day[day.len() - 1].timestamp;
+            let mut metadata = HashMap::new();
+            metadata.insert("source".to_string(), "channel_archive".to_string());
+            metadata.insert("platform".to_string(), platform.label().to_lowercase());
+            metadata.insert("channel".to_string(), channel.clone());
+            metadata.insert("matter_id".to_string(), matter.id.clone());
+            metadata.insert("conversation_date".to_string(), date.to_string());
+            metadata.insert("first_message_at".to_string(), first.to_rfc3339());
+            metadata.insert("last_message_at".to_string(), last.to_rfc3339());
+            metadata.insert("authors".to_string(), authors.join(", "));
+            metadata.insert("message_count".to_string(), day.len().to_string());
+            metadata.insert("export".to_string(), archive_name.clone());
+            metadata.insert("file_path".to_string(), path_string.clone());
+
+            let document = LegalDocument {
+                id: document_id.clone(),
+                title: format!("{} #{} - {}", platform.label(), channel, date),
+                content: transcript,
+                jurisdiction: matter.jurisdiction.clone().unwrap_or_else(|| "Internal".to_string()),
+                document_type: DocumentType::Brief,
+                last_updated: last,
+                citations: Vec::new(),
+                metadata: DocumentMetadata {
+                    court: None,
+                    judge: None,
+                    parties: Vec::new(),
+                    topics: vec![matter.name.clone(), channel.clone()],
+                    precedential_value: PrecedentialValue::NotPrecedential,
+                    confidence: 1.0,
+                },
+            };
+            let chunks = match index(document, metadata).await {
+                Ok(chunks) => {
+                    self.ledger.lock().unwrap().insert(document_id.clone(), sha);
+                    Some(chunks)
+                }
+                Err(e) => {
+                    report.errors.push(format!("{} not indexed: {}", document_id, e));
+                    None
+                }
+            };
+
+            report.conversations.push(IngestedConversation {
+                document_id,
+                matter_id: matter.id.clone(),
+                platform,
+                channel,
+                date,
+                path: path_string,
+                messages: day.len(),
+                authors,
+                first_message_at: first,
+                last_message_at: last,
+                chunks,
+            });
+        }
+
+        for (matter_id, paths) in attached {
+            matters.attach_documents(&matter_id, paths)?;
+        }
+        self.persist(&self.ledger.lock().unwrap())?;
+        Ok(report)
+    }
+}
+
+pub type ChannelArchiveStorage = Arc<ChannelArchives>;
+
+/// Channels in an export with their message counts and the matter each name suggests
+#[tauri::command]
+pub async fn channel_archive_preview(
+    archive_path: String,
+    matters: tauri::State<'_, MatterStorage>,
+) -> Result<Vec<ChannelSummary>, String> {
+    let messages = read_export(Path::new(&archive_path)).map_err(|e| e.to_string())?;
+    Ok(summarize_channels(&messages, &matters.list()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn matter(id: &str, name: &str, client: &str, status: MatterStatus) -> Matter {
+        serde_json::from_value(json!({
+            "id": id, "name": name, "client": client, "adverse_parties": [], "related_parties": [],
+            "description": null, "status": status, "created_at": "2026-01-01T00:00:00Z", "activated_at": null
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_slack_export_parsing_and_matter_suggestion() {
+        let users = json!([
+            { "id": "U1", "name": "asmith", "real_name": "Alice Smith" },
+            { "id": "U2", "name": "bjones", "profile": { "real_name": "", "display_name": "Bob" } }
+        ]);
+        let day = json!([
+            { "type": "message", "subtype": "channel_join", "user": "U2", "text": "<@U2> has joined the channel", "ts": "1772357400.000100" },
+            { "type": "message", "user": "U1", "text": "<@U2> draft SPA is on <https://dms.firm.local/1|the DMS> &amp; ready", "ts": "1772357400.000200",
+              "client_msg_id": "m-1", "thread_ts": "1772357400.000200", "files": [{ "name": "SPA v3.docx" }] },
+            { "type": "message", "user": "U2", "text": "Thanks, reviewing in <#C9|acme-deal>", "ts": "1772358000.000000", "thread_ts": "1772357400.000200" }
+        ]);
+        let entries = vec![
+            ("export/users.json".to_string(), serde_json::to_vec(&users).unwrap()),
+            ("export/channels.json".to_string(), b"[]".to_vec()),
+            ("export/acme-globex-merger/2026-03-01.json".to_string(), serde_json::to_vec(&day).unwrap()),
+        ];
+        let messages = parse_export(&entries).unwrap();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].channel, "acme-globex-merger");
+        assert_eq!(messages[0].author, "Alice Smith");
+        assert_eq!(messages[0].message_id, "m-1");
+        assert_eq!(messages[0].thread_id, None);
+        assert_eq!(messages[0].text, "@Bob draft SPA is on the DMS (https://dms.firm.local/1) & ready");
+        assert_eq!(messages[1].thread_id.as_deref(), Some("1772357400.000200"));
+        assert_eq!(messages[1].text, "Thanks, reviewing in #acme-deal");
+
+        let day: Vec<&ChannelMessage> = messages.iter().collect();
+        let transcript = render_transcript(ChatPlatform::Slack, "acme-globex-merger", messages[0].timestamp.date_naive(), &day);
+        assert_eq!(
+            transcript,
+            "Slack #acme-globex-merger - 2026-03-01\n\n\
+             [09:30:00 UTC] Alice Smith: @Bob draft SPA is on the DMS (https://dms.firm.local/1) & ready [attachment: SPA v3.docx]\n  \
+             ↳ [09:40:00 UTC] Bob: Thanks, reviewing in #acme-deal"
+        );
+
+        let matters = vec![
+            matter("M-1", "Acme / Globex merger", "Acme Holdings Inc.", MatterStatus::Active),
+            matter("M-2", "Acme employment dispute", "Acme Holdings Inc.", MatterStatus::Active),
+            matter("M-3", "Globex merger", "Globex", MatterStatus::Closed),
+        ];
+        assert_eq!(suggest_matter("acme-globex-merger", &matters).as_deref(), Some("M-1"));
+        assert_eq!(suggest_matter("acme-general", &matters), None); // M-1 and M-2 tie
+        assert_eq!(suggest_matter("random", &matters), None);
+        assert_eq!(suggest_matter("m-2-witnesses", &matters).as_deref(), Some("M-2"));
+    }
+
+    #[test]
+    fn test_teams_export_parsing() {
+        let export = json!({
+            "channelName": "Litigation - Smith v Jones",
+            "value": [
+                { "id": "1", "messageType": "message", "createdDateTime": "2026-03-02T14:05:00Z", "deletedDateTime": null,
+                  "subject": "Disclosure", "from": { "user": { "id": "aad-1", "displayName": "Carol White" } },
+                  "body": { "contentType": "html", "content": "<p>Please see the <b>list of documents</b></p><p>Due Friday</p>" },
+                  "attachments": [{ "name": "List.xlsx" }] },
+                { "id": "2", "messageType": "message", "createdDateTime": "2026-03-02T14:10:00Z", "replyToId": "1",
+                  "from": { "user": { "id": "aad-2", "displayName": "Dan Black" } }, "body": { "contentType": "text", "content": " Noted " } },
+                { "id": "3", "messageType": "systemEventMessage", "createdDateTime": "2026-03-02T14:11:00Z", "body": { "content": "<systemEventMessage/>" } },
+                { "id": "4", "messageType": "message", "createdDateTime": "2026-03-02T14:12:00Z", "deletedDateTime": "2026-03-02T14:13:00Z",
+                  "from": { "user": { "displayName": "Dan Black" } }, "body": { "contentType": "text", "content": "oops" } }
+            ]
+        });
+        let messages = parse_export(&[("teams.json".to_string(), serde_json::to_vec(&export).unwrap())]).unwrap();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].platform, ChatPlatform::Teams);
+        assert_eq!(messages[0].channel, "Litigation - Smith v Jones");
+        assert_eq!(messages[0].author, "Carol White");
+        assert_eq!(messages[0].author_id.as_deref(), Some("aad-1"));
+        assert_eq!(messages[0].text, "Disclosure: Please see the list of documents Due Friday");
+        assert_eq!(messages[0].attachments, vec!["List.xlsx"]);
+        assert_eq!(messages[1].thread_id.as_deref(), Some("1"));
+        assert_eq!(messages[1].text, "Noted");
+
+        let summaries = summarize_channels(&messages, &[matter("M-7", "Smith v Jones", "Smith", MatterStatus::Intake)]);
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].authors, vec!["Carol White", "Dan Black"]);
+        assert_eq!(summaries[0].suggested_matter.as_deref(), Some("M-7"));
+    }
+}
@@ -0,0 +1,495 @@
+use anyhow::{anyhow, Result};
+use chrono::{Duration, NaiveDate};
+use plotters::coord::Shift;
+use plotters::prelude::*;
+use plotters::style::text_anchor::{HPos, Pos, VPos};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::document_analyzer::{DocumentAnalysis, RiskLevel};
+
+/// Charts and Exhibits for BEAR AI
+/// Risk distributions, chronologies and damages schedules are rendered with plotters as SVG
+/// (for reports and client bundles) or PNG (for pasting into briefs). Colours, the font and an
+/// optional footer line come from the firm's chart theme, so exhibits carry the firm's branding
+/// without touching the rendering code.
+const WIDTH: u32 = 960;
+const HEIGHT: u32 = 540;
+const FOOTER_HEIGHT: u32 = 28;
+const TIMELINE_LANES: usize = 4;
+const MAX_EVENT_LABEL_CHARS: usize = 40;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChartTheme {
+    pub font_family: String,
+    pub background: String, // "#RRGGBB"
+    pub text: String,
+    pub grid: String,
+    pub palette: Vec<String>, // series colours, cycled
+    pub low: String,          // risk severity colours
+    pub medium: String,
+    pub high: String,
+    pub critical: String,
+    pub footer: Option<String>, // e.g. the firm name, printed under every chart
+}
+
+impl Default for ChartTheme {
+    fn default() -> Self {
+        Self {
+            font_family: "sans-serif".to_string(),
+            background: "#FFFFFF".to_string(),
+            text: "#1F2937".to_string(),
+            grid: "#D1D5DB".to_string(),
+            palette: ["#1D4ED8", "#0F766E", "#B45309", "#7C3AED", "#BE185D", "#4B5563"]
+                .iter()
+                .map(|c| c.to_string())
+                .collect(),
+            low: "#16A34A".to_string(),
+            medium: "#F59E0B".to_string(),
+            high: "#DC2626".to_string(),
+            critical: "#7F1D1D".to_string(),
+            footer: None,
+        }
+    }
+}
+
+pub fn parse_color(hex: &str) -> Result<RGBColor> {
+    let digits = hex.trim().trim_start_matches('#');
+    if digits.len() != 6 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(anyhow!("Invalid colour {:?}; expected #RRGGBB", hex));
+    }
+    let channel = |i: usize| u8::from_str_radix(&digits[i..i + 2], 16).unwrap();
+    Ok(RGBColor(channel(0), channel(2), channel(4)))
+}
+
+/// Theme colours parsed once per chart
+struct Colors {
+    background: RGBColor,
+    text: RGBColor,
+    grid: RGBColor,
+    palette: Vec<RGBColor>,
+    severity: [RGBColor; 4], // low, medium, high, critical
+}
+
+impl Colors {
+    fn from_theme(theme: &ChartTheme) -> Result<Self> {
+        let palette = theme.palette.iter().map(|c| parse_color(c)).collect::<Result<Vec<_>>>()?;
+        if palette.is_empty() {
+            return Err(anyhow!("The chart palette needs at least one colour"));
+        }
+        Ok(Self {
+            background: parse_color(&theme.background)?,
+            text: parse_color(&theme.text)?,
+            grid: parse_color(&theme.grid)?,
+            palette,
+            severity: [
+                parse_color(&theme.low)?,
+                parse_color(&theme.medium)?,
+                parse_color(&theme.high)?,
+                parse_color(&theme.critical)?,
+            ],
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimelineEvent {
+    pub date: String, // YYYY-MM-DD
+    pub label: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DamagesItem {
+    pub head: String, // head of damage, e.g. "Lost profits"
+    pub amount: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RiskCounts {
+    pub low: usize,
+    pub medium: usize,
+    pub high: usize,
+    pub critical: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ChartSpec {
+    RiskDistribution { title: Option<String>, counts: RiskCounts },
+    Timeline { title: Option<String>, events: Vec<TimelineEvent> },
+    DamagesSchedule { title: Option<String>, currency: String, items: Vec<DamagesItem> },
+}
+
+/// Risk distribution over the risks found in a set of analyses
+pub fn risk_distribution(analyses: &[&DocumentAnalysis]) -> ChartSpec {
+    let mut counts = RiskCounts::default();
+    for risk in analyses.iter().flat_map(|a| a.risks.iter()) {
+        match risk.severity {
+            RiskLevel::Low => counts.low += 1,
+            RiskLevel::Medium => counts.medium += 1,
+            RiskLevel::High => counts.high += 1,
+            RiskLevel::Critical => counts.critical += 1,
+        }
+    }
+    ChartSpec::RiskDistribution { title: None, counts }
+}
+
+/// "1,234,567" or "1,234.50"
+fn format_amount(value: f64) -> String {
+    let cents = (value.abs() * 100.0).round() as u64;
+    let whole = (cents / 100).to_string();
+    let mut grouped = String::new();
+    for (i, digit) in whole.chars().enumerate() {
+        if i > 0 && (whole.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    let sign = if value < 0.0 { "-" } else { "" };
+    match cents % 100 {
+        0 => format!("{}{}", sign, grouped),
+        fraction => format!("{}{}.{:02}", sign, grouped, fraction),
+    }
+}
+
+fn chart_error<E: std::fmt::Debug>(e: E) -> anyhow::Error {
+    anyhow!("Chart rendering failed: {:?}", e)
+}
+
+fn text_style<'a>(theme: &'a ChartTheme, size: u32, color: &RGBColor) -> TextStyle<'a> {
+    (theme.font_family.as_str(), size).into_font().color(color)
+}
+
+fn draw_bars<DB: DrawingBackend>(
+    area: &DrawingArea<DB, Shift>,
+    theme: &ChartTheme,
+    colors: &Colors,
+    title: &str,
+    y_desc: &str,
+    bars: &[(String, f64, RGBColor)],
+) -> Result<()> {
+    let max = bars.iter().map(|(_, value, _)| *value).fold(0.0, f64::max);
+    let y_max = if max > 0.0 { max * 1.15 } else { 1.0 };
+
+    let mut chart = ChartBuilder::on(area)
+        .caption(title, text_style(theme, 24, &colors.text))
+        .margin(20)
+        .x_label_area_size(40)
+        .y_label_area_size(80)
+        .build_cartesian_2d((0..bars.len()).into_segmented(), 0f64..y_max)
+        .map_err(chart_error)?;
+
+    chart
+        .configure_mesh()
+        .disable_x_mesh()
+        .bold_line_style(colors.grid)
+        .light_line_style(colors.grid.mix(0.3))
+        .axis_style(colors.text)
+        .label_style(text_style(theme, 14, &colors.text))
+        .axis_desc_style(text_style(theme, 14, &colors.text))
+        .y_desc(y_desc)
+        .x_labels(bars.len())
+        .x_label_formatter(&|value| match value {
+            SegmentValue::CenterOf(i) => bars.get(*i).map(|(label, _, _)| label.clone()).unwrap_or_default(),
+            _ => String::new(),
+        })
+        .y_label_formatter(&|value| format_amount(*value))
+        .draw()
+        .map_err(chart_error)?;
+
+    chart
+        .draw_series(bars.iter().enumerate().map(|(i, (_, value, color))| {
+            let mut bar = Rectangle::new([(SegmentValue::Exact(i), 0.0), (SegmentValue::Exact(i + 1), *value)], color.filled());
+            bar.set_margin(0, 0, 12, 12);
+            bar
+        }))
+        .map_err(chart_error)?;
+
+    // Value above each bar
+    let value_style = text_style(theme, 13, &colors.text).pos(Pos::new(HPos::Center, VPos::Bottom));
+    chart
+        .draw_series(bars.iter().enumerate().map(|(i, (_, value, _))| {
+            Text::new(format_amount(*value), (SegmentValue::CenterOf(i), *value + y_max * 0.01), value_style.clone())
+        }))
+        .map_err(chart_error)?;
+    Ok(())
+}
+
+fn draw_timeline<DB: DrawingBackend>(
+    area: &DrawingArea<DB, Shift>,
+    theme: &ChartTheme,
+    colors: &Colors,
+    title: &str,
+    events: &[TimelineEvent],
+) -> Result<()> {
+    let mut dated: Vec<(NaiveDate, &str)> = events
+        .iter()
+        .map(|e| {
+            NaiveDate::parse_from_str(e.date.trim(), "%Y-%m-%d")
+                .map(|date| (date, e.label.as_str()))
+                .map_err(|_| anyhow!("Invalid event date {:?}; expected YYYY-MM-DD", e.date))
+        })
+        .collect::<Result<_>>()?;
+    if dated.is_empty() {
+        return Err(anyhow!("A timeline needs at least one event"));
+    }
+    dated.sort_by_key(|(date, _)| *date);
+
+    // Pad both ends so the first and last labels are not cut off
+    let start = dated[0].0 - Duration::days(15);
+    let end = dated[dated.len() - 1].0 + Duration::days(45);
+    let span = (end - start).num_days();
+
+    let mut chart = ChartBuilder::on(area)
+        .caption(title, text_style(theme, 24, &colors.text))
+        .margin(20)
+        .x_label_area_size(40)
+        .build_cartesian_2d(0i64..span, 0f64..(TIMELINE_LANES as f64 + 1.0))
+        .map_err(chart_error)?;
+
+    chart
+        .configure_mesh()
+        .disable_y_mesh()
+        .disable_y_axis()
+        .bold_line_style(colors.grid)
+        .light_line_style(colors.grid.mix(0.3))
+        .axis_style(colors.text)
+        .label_style(text_style(theme, 13, &colors.text))
+        .x_labels(8)
+        .x_label_formatter(&|day| (start + Duration::days(*day)).format("%d %b %Y").to_string())
+        .draw()
+        .map_err(chart_error)?;
+
+    chart
+        .draw_series(LineSeries::new([(0, 0.5), (span, 0.5)], colors.text.stroke_width(2)))
+        .map_err(chart_error)?;
+
+    // Events alternate between lanes so neighbouring labels do not overlap
+    let placed: Vec<(i64, f64, String, RGBColor)> = dated
+        .iter()
+        .enumerate()
+        .map(|(i, (date, label))| {
+            let mut label: String = label.chars().take(MAX_EVENT_LABEL_CHARS).collect();
+            if label.chars().count() == MAX_EVENT_LABEL_CHARS {
+                label.push_str("...");
+            }
+            (
+                (*date - start).num_days(),
+                (i % TIMELINE_LANES) as f64 + 1.0,
+                format!("{}  {}", date.format("%d %b %Y"), label),
+                colors.palette[i % colors.palette.len()],
+            )
+        })
+        .collect();
+
+    chart
+        .draw_series(placed.iter().map(|(x, lane, _, color)| PathElement::new(vec![(*x, 0.5), (*x, *lane)], *color)))
+        .map_err(chart_error)?;
+    let label_style = text_style(theme, 13, &colors.text).pos(Pos::new(HPos::Left, VPos::Center));
+    chart
+        .draw_series(placed.iter().map(|(x, lane, label, color)| {
+            EmptyElement::at((*x, *lane))
+                + Circle::new((0, 0), 5, color.filled())
+                + Text::new(label.clone(), (10, 0), label_style.clone())
+        }))
+        .map_err(chart_error)?;
+    Ok(())
+}
+
+fn draw<DB: DrawingBackend>(root: &DrawingArea<DB, Shift>, spec: &ChartSpec, theme: &ChartTheme) -> Result<()> {
+    let colors = Colors::from_theme(theme)?;
+    root.fill(&colors.background).map_err(chart_error)?;
+
+    let area = match &theme.footer {
+        Some(footer) => {
+            let (body, foot) = root.split_vertically((HEIGHT - FOOTER_HEIGHT) as i32);
+            foot.draw_text(footer, &text_style(theme, 12, &colors.text), (20, 6))
+                .map_err(chart_error)?;
+            body
+        }
+        None => root.clone(),
+    };
+
+    match spec {
+        ChartSpec::RiskDistribution { title, counts } => {
+            let bars = [
+                ("Low", counts.low),
+                ("Medium", counts.medium),
+                ("High", counts.high),
+                ("Critical", counts.critical),
+            ]
+            .iter()
+            .zip(colors.severity.iter())
+            .map(|((label, count), color)| (label.to_string(), *count as f64, *color))
+            .collect::<Vec<_>>();
+            let title = title.clone().unwrap_or_else(|| "Risk distribution".to_string());
+            draw_bars(&area, theme, &colors, &title, "Risks", &bars)
+        }
+        ChartSpec::Timeline { title, events } => {
+            let title = title.clone().unwrap_or_else(|| "Chronology".to_string());
+            draw_timeline(&area, theme, &colors, &title, events)
+        }
+        ChartSpec::DamagesSchedule { title, currency, items } => {
+            let bars = items
+                .iter()
+                .enumerate()
+                .map(|(i, item)| (item.head.clone(), item.amount, colors.palette[i % colors.palette.len()]))
+                .collect::<Vec<_>>();
+            let total: f64 = items.iter().map(|item| item.amount).sum();
+            let title = format!(
+                "{} (total {} {})",
+                title.clone().unwrap_or_else(|| "Damages schedule".to_string()),
+                currency,
+                format_amount(total)
+            );
+            draw_bars(&area, theme, &colors, &title, currency, &bars)
+        }
+    }
+}
+
+pub fn render_svg(spec: &ChartSpec, theme: &ChartTheme) -> Result<String> {
+    let mut svg = String::new();
+    {
+        let root = SVGBackend::with_string(&mut svg, (WIDTH, HEIGHT)).into_drawing_area();
+        draw(&root, spec, theme)?;
+        root.present().map_err(chart_error)?;
+    }
+    Ok(svg)
+}
+
+/// Render to an .svg or .png file, chosen by the extension
+pub fn render_chart_file(spec: &ChartSpec, theme: &ChartTheme, output_path: &Path) -> Result<()> {
+    if let Some(parent) = output_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let extension = output_path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase());
+    match extension.as_deref() {
+        Some("svg") => fs::write(output_path, render_svg(spec, theme)?)?,
+        Some("png") => {
+            let root = BitMapBackend::new(output_path, (WIDTH, HEIGHT)).into_drawing_area();
+            draw(&root, spec, theme)?;
+            root.present().map_err(chart_error)?;
+        }
+        _ => return Err(anyhow!("Charts are written as .svg or .png")),
+    }
+    Ok(())
+}
+
+#[derive(Debug)]
+pub struct ChartThemePreference {
+    path: PathBuf,
+    theme: Mutex<ChartTheme>,
+}
+
+impl ChartThemePreference {
+    pub fn new(app_data_dir: &Path) -> Self {
+        let path = app_data_dir.join("chart_theme.json");
+        let theme = fs::read_to_string(&path)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+        ChartThemePreference {
+            path,
+            theme: Mutex::new(theme),
+        }
+    }
+
+    pub fn get(&self) -> ChartTheme {
+        self.theme.lock().unwrap().clone()
+    }
+
+    pub fn set(&self, theme: ChartTheme) -> Result<()> {
+        Colors::from_theme(&theme)?;
+        fs::write(&self.path, serde_json::to_string_pretty(&theme)?)?;
+        *self.theme.lock().unwrap() = theme;
+        Ok(())
+    }
+}
+
+pub type ChartThemeStorage = Arc<ChartThemePreference>;
+
+#[tauri::command]
+pub async fn get_chart_theme(themes: tauri::State<'_, ChartThemeStorage>) -> Result<ChartTheme, String> {
+    Ok(themes.get())
+}
+
+#[tauri::command]
+pub async fn set_chart_theme(theme: ChartTheme, themes: tauri::State<'_, ChartThemeStorage>) -> Result<(), String> {
+    themes.set(theme).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn render_chart(
+    spec: ChartSpec,
+    output_path: String,
+    themes: tauri::State<'_, ChartThemeStorage>,
+) -> Result<String, String> {
+    render_chart_file(&spec, &themes.get(), Path::new(&output_path)).map_err(|e| e.to_string())?;
+    Ok(output_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_theme_colours_and_amounts() {
+        assert_eq!(parse_color("#1d4ed8").unwrap(), RGBColor(0x1D, 0x4E, 0xD8));
+        assert!(parse_color("blue").is_err());
+
+        let dir = tempfile::tempdir().unwrap();
+        let themes = ChartThemePreference::new(dir.path());
+        let mut theme = themes.get();
+        theme.palette = vec!["#zzzzzz".to_string()];
+        assert!(themes.set(theme.clone()).is_err());
+        theme.palette = vec!["#112233".to_string()];
+        theme.footer = Some("Van Dijk Advocaten".to_string());
+        themes.set(theme.clone()).unwrap();
+        assert_eq!(ChartThemePreference::new(dir.path()).get(), theme);
+
+        assert_eq!(format_amount(1234567.0), "1,234,567");
+        assert_eq!(format_amount(-1500.5), "-1,500.50");
+        assert_eq!(format_amount(999.0), "999");
+    }
+
+    #[test]
+    fn test_renders_themed_svg_charts() {
+        let theme = ChartTheme {
+            high: "#AB0000".to_string(),
+            footer: Some("Van Dijk Advocaten".to_string()),
+            ..ChartTheme::default()
+        };
+        let risks = ChartSpec::RiskDistribution {
+            title: None,
+            counts: RiskCounts { low: 3, medium: 2, high: 1, critical: 0 },
+        };
+        let svg = render_svg(&risks, &theme).unwrap();
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains("Risk distribution"));
+        assert!(svg.contains("Medium"));
+        assert!(svg.contains("Van Dijk Advocaten"));
+        assert!(svg.to_uppercase().contains("#AB0000"));
+
+        let damages = ChartSpec::DamagesSchedule {
+            title: None,
+            currency: "EUR".to_string(),
+            items: vec![
+                DamagesItem { head: "Lost profits".to_string(), amount: 250000.0 },
+                DamagesItem { head: "Remediation costs".to_string(), amount: 42500.5 },
+            ],
+        };
+        assert!(render_svg(&damages, &theme).unwrap().contains("total EUR 292,500.50"));
+
+        let timeline = ChartSpec::Timeline {
+            title: Some("Key dates".to_string()),
+            events: vec![TimelineEvent { date: "03/01/2024".to_string(), label: "Notice".to_string() }],
+        };
+        assert!(render_svg(&timeline, &theme).is_err());
+    }
+}
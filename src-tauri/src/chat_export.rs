@@ -2,9 +2,21 @@ use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use printpdf::*;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+use crate::ai_disclosure::{Disclosure, DisclosureStorage};
+use crate::docx_writer::{self, DocxBlock};
+use crate::enterprise_management::BarrierStorage;
+use crate::license_attribution::PdfFlow;
+use crate::llm_manager::LLMManager;
+use crate::local_api::{authenticated_user, SessionStorage};
+use crate::nemotron_rag::{RetrievalProvenance, RetrievalResult};
+use crate::provenance::{self, ProvenanceClaim, ProvenanceSource, ProvenanceStorage};
+use crate::review_queue::{self, ReviewQueueStorage, Submission, WorkProductKind};
+use crate::security::SecurityManager;
 
 /// Chat Export Engine for BEAR AI
 /// Provides export functionality for chat conversations in multiple formats
@@ -15,6 +27,8 @@ pub struct ChatMessage {
     pub role: MessageRole,
     pub content: String,
     pub metadata: Option<HashMap<String, String>>,
+    #[serde(default)]
+    pub retrieval_provenance: Option<RetrievalProvenance>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -41,6 +55,12 @@ pub struct ExportOptions {
     pub format_style: ExportStyle,
     pub page_header: Option<String>,
     pub page_footer: Option<String>,
+    #[serde(default)]
+    pub include_disclosure: Option<bool>, // None follows the disclosure settings
+    #[serde(default)]
+    pub jurisdiction: Option<String>, // picks the disclosure template
+    #[serde(default)]
+    pub provenance_sidecar: bool, // Markdown carries its manifest; other formats always get a sidecar
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -70,6 +90,7 @@ impl ChatExporter {
         &self,
         session: &ChatSession,
         options: &ExportOptions,
+        disclosure: Option<&Disclosure>,
     ) -> Result<PathBuf> {
         let filename = format!("chat_export_{}.md", session.id);
         let file_path = self.export_path.join(&filename);
@@ -123,7 +144,30 @@ impl ChatExporter {
 
             content.push_str("\n\n");
             content.push_str(&message.content);
-            content.push_str("\n\n---\n\n");
+            content.push_str("\n\n");
+
+            if options.include_metadata {
+                if let Some(provenance) = &message.retrieval_provenance {
+                    content.push_str(&format!("**Sources** (query: \"{}\")\n\n", provenance.query));
+                    for chunk in &provenance.chunks {
+                        content.push_str(&format!(
+                            "- {} — chunk {} (`{}`), score {:.2}, sha256 `{}`\n",
+                            chunk.document_title.as_deref().unwrap_or(&chunk.document_id),
+                            chunk.chunk_index,
+                            chunk.chunk_id,
+                            chunk.score,
+                            &chunk.content_sha256[..12.min(chunk.content_sha256.len())]
+                        ));
+                    }
+                    content.push('\n');
+                }
+            }
+
+            content.push_str("---\n\n");
+        }
+
+        if let Some(disclosure) = disclosure {
+            content.push_str(&format!("\n{}", disclosure.to_markdown()));
         }
 
         // Add footer
@@ -145,6 +189,7 @@ impl ChatExporter {
         &self,
         session: &ChatSession,
         options: &ExportOptions,
+        disclosure: Option<&Disclosure>,
     ) -> Result<PathBuf> {
         let filename = format!("chat_export_{}.txt", session.id);
         let file_path = self.export_path.join(&filename);
@@ -195,6 +240,27 @@ impl ChatExporter {
             content.push('\n');
             content.push_str(&message.content);
             content.push_str("\n\n");
+
+            if options.include_metadata {
+                if let Some(provenance) = &message.retrieval_provenance {
+                    content.push_str(&format!("SOURCES (query: \"{}\"):\n", provenance.query));
+                    for chunk in &provenance.chunks {
+                        content.push_str(&format!(
+                            "  - {} / chunk {} [{}] score {:.2} sha256 {}\n",
+                            chunk.document_title.as_deref().unwrap_or(&chunk.document_id),
+                            chunk.chunk_index,
+                            chunk.chunk_id,
+                            chunk.score,
+                            chunk.content_sha256
+                        ));
+                    }
+                    content.push('\n');
+                }
+            }
+        }
+
+        if let Some(disclosure) = disclosure {
+            content.push_str(&format!("\n{}", disclosure.to_text()));
         }
 
         // Add footer
@@ -216,6 +282,7 @@ impl ChatExporter {
         &self,
         session: &ChatSession,
         options: &ExportOptions,
+        disclosure: Option<&Disclosure>,
     ) -> Result<PathBuf> {
         let filename = format!("chat_export_{}.pdf", session.id);
         let file_path = self.export_path.join(&filename);
@@ -345,9 +412,46 @@ impl ChatExporter {
                 y_position -= line_height;
             }
 
+            // Sources the answer was grounded on
+            if options.include_metadata {
+                if let Some(provenance) = &message.retrieval_provenance {
+                    for chunk in &provenance.chunks {
+                        let source_line = format!(
+                            "Source: {} / chunk {} (score {:.2})",
+                            chunk.document_title.as_deref().unwrap_or(&chunk.document_id),
+                            chunk.chunk_index,
+                            chunk.score
+                        );
+                        current_layer.use_text(&source_line, 8.0, margin_left + Mm(5.0), y_position, &font);
+                        y_position -= line_height;
+                    }
+                }
+            }
+
             y_position -= line_height * 0.5;
         }
 
+        if let Some(disclosure) = disclosure {
+            y_position -= line_height;
+            current_layer.use_text(&disclosure.title, 10.0, margin_left, y_position, &font_bold);
+            y_position -= line_height;
+            let mut line = String::new();
+            for word in disclosure.text.split_whitespace() {
+                if !line.is_empty() && line.len() + word.len() + 1 > 90 {
+                    current_layer.use_text(&line, 8.0, margin_left, y_position, &font);
+                    y_position -= line_height;
+                    line.clear();
+                }
+                if !line.is_empty() {
+                    line.push(' ');
+                }
+                line.push_str(word);
+            }
+            if !line.is_empty() {
+                current_layer.use_text(&line, 8.0, margin_left, y_position, &font);
+            }
+        }
+
         // Add footer
         y_position = Mm(20.0);
         current_layer.use_text(
@@ -379,35 +483,425 @@ impl ChatExporter {
         session: &ChatSession,
         format: &str,
         options: &ExportOptions,
+        disclosure: Option<&Disclosure>,
     ) -> Result<PathBuf> {
         match format.to_lowercase().as_str() {
-            "markdown" | "md" => self.export_to_markdown(session, options).await,
-            "txt" | "text" => self.export_to_txt(session, options).await,
-            "pdf" => self.export_to_pdf(session, options).await,
+            "markdown" | "md" => self.export_to_markdown(session, options, disclosure).await,
+            "txt" | "text" => self.export_to_txt(session, options, disclosure).await,
+            "pdf" => self.export_to_pdf(session, options, disclosure).await,
             _ => Err(anyhow::anyhow!("Unsupported export format: {}", format)),
         }
     }
 }
 
+/// What exporting a session must clear the information barriers for: the chat itself and every
+/// document its answers drew on, under the matter the chat belongs to
+pub fn export_items(session: &ChatSession) -> Vec<(String, Option<String>)> {
+    let matter = session.metadata.get("matter_id").cloned();
+    let documents = session
+        .messages
+        .iter()
+        .filter_map(|m| m.retrieval_provenance.as_ref())
+        .flat_map(|p| p.chunks.iter())
+        .map(|chunk| chunk.document_id.clone());
+    std::iter::once(format!("chat:{}", session.id))
+        .chain(documents)
+        .map(|document| (document, matter.clone()))
+        .collect()
+}
+
+/// The transcript a reviewer approves: every message's role and text, in order
+pub fn content_sha256(session: &ChatSession) -> String {
+    let transcript: Vec<(String, &str)> = session
+        .messages
+        .iter()
+        .map(|m| (format!("{:?}", m.role), m.content.as_str()))
+        .collect();
+    review_queue::sha256_hex(serde_json::to_string(&transcript).unwrap_or_default().as_bytes())
+}
+
+/// What an exported chat's provenance manifest records: the prompts sent and every chunk
+/// retrieved for an answer
+pub fn provenance_claim(session: &ChatSession) -> ProvenanceClaim {
+    let prompts: Vec<String> = session
+        .messages
+        .iter()
+        .filter(|m| !matches!(m.role, MessageRole::Assistant))
+        .map(|m| m.content.clone())
+        .collect();
+    let mut sources: Vec<ProvenanceSource> = Vec::new();
+    for chunk in session
+        .messages
+        .iter()
+        .filter_map(|m| m.retrieval_provenance.as_ref())
+        .flat_map(|p| p.chunks.iter())
+    {
+        if !sources.iter().any(|s| s.chunk_id.as_deref() == Some(chunk.chunk_id.as_str())) {
+            sources.push(ProvenanceSource {
+                document_id: chunk.document_id.clone(),
+                title: chunk.document_title.clone(),
+                chunk_id: Some(chunk.chunk_id.clone()),
+                content_sha256: Some(chunk.content_sha256.clone()),
+            });
+        }
+    }
+    ProvenanceClaim {
+        title: session.title.clone(),
+        work_product: "chat_export".to_string(),
+        model: session.metadata.get("model").cloned().unwrap_or_else(|| "unspecified".to_string()),
+        prompts_sha256: provenance::prompts_sha256(&prompts),
+        sources,
+    }
+}
+
+// Longest source excerpt in a detailed memo
+const MEMO_EXCERPT_CHARS: usize = 400;
+
+/// Layout of an exported research memo
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MemoTemplate {
+    #[default]
+    Standard, // Issue, Brief Answer, Analysis, Sources
+    Short,    // Issue, Brief Answer, Sources
+    Detailed, // Standard, with source excerpts and how the sources were retrieved
+}
+
+/// An answer and the retrieval it was generated from, to be written up as a research memo.
+/// Sources are numbered in retrieval order, as inline [1] markers in the answer number them.
+/// The retrieval is never taken from the caller: the export runs it again for the question in
+/// the caller's scope.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResearchMemoExport {
+    pub question: String,
+    pub answer: String,
+    #[serde(default, skip_deserializing)]
+    pub result: RetrievalResult,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub jurisdiction: Option<String>, // also picks the disclosure template
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub template: MemoTemplate,
+    #[serde(default)]
+    pub include_disclosure: Option<bool>, // None follows the disclosure settings
+    #[serde(default)]
+    pub provenance_sidecar: bool,
+}
+
+/// The brief answer is the answer's first paragraph and the analysis the paragraphs after it;
+/// a one-paragraph answer is its own analysis, briefed by its first sentence
+fn split_answer(answer: &str) -> (String, Vec<String>) {
+    let paragraphs: Vec<&str> = answer.split("\n\n").map(str::trim).filter(|p| !p.is_empty()).collect();
+    match paragraphs.as_slice() {
+        [] => (String::new(), Vec::new()),
+        [only] => {
+            let brief = only.split_inclusive(". ").next().unwrap_or(only).trim();
+            (brief.to_string(), vec![only.to_string()])
+        }
+        [first, rest @ ..] => (first.to_string(), rest.iter().map(|p| p.to_string()).collect()),
+    }
+}
+
+fn excerpt(text: &str) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.chars().count() <= MEMO_EXCERPT_CHARS {
+        return text;
+    }
+    format!("{}...", text.chars().take(MEMO_EXCERPT_CHARS).collect::<String>().trim_end())
+}
+
+fn memo_blocks(memo: &ResearchMemoExport, generated_on: &str, disclosure: Option<&Disclosure>) -> Vec<DocxBlock> {
+    let result = &memo.result;
+    let mut blocks = vec![
+        DocxBlock::Title(memo.title.clone().unwrap_or_else(|| "Legal Research Memorandum".to_string())),
+        DocxBlock::Paragraph(format!(
+            "Jurisdiction: {}\nDate: {}\nPrepared with: {}",
+            memo.jurisdiction.as_deref().unwrap_or("Not specified"),
+            generated_on,
+            memo.model.as_deref().unwrap_or("BEAR AI Legal Assistant")
+        )),
+        DocxBlock::Heading(1, "Issue".to_string()),
+        DocxBlock::Paragraph(memo.question.trim().to_string()),
+    ];
+
+    let (brief, analysis) = split_answer(&memo.answer);
+    blocks.push(DocxBlock::Heading(1, "Brief Answer".to_string()));
+    blocks.push(DocxBlock::Paragraph(brief));
+    if memo.template != MemoTemplate::Short {
+        blocks.push(DocxBlock::Heading(1, "Analysis".to_string()));
+        blocks.extend(analysis.into_iter().map(DocxBlock::Paragraph));
+    }
+
+    blocks.push(DocxBlock::Heading(1, "Sources".to_string()));
+    if result.chunks.is_empty() {
+        blocks.push(DocxBlock::Paragraph("No indexed material was retrieved for this question.".to_string()));
+    }
+    for (index, chunk) in result.chunks.iter().enumerate() {
+        let title = result
+            .documents
+            .iter()
+            .find(|d| d.id == chunk.document_id)
+            .map(|d| d.title.as_str())
+            .unwrap_or(&chunk.document_id);
+        let verified: Vec<&str> = result
+            .citations
+            .iter()
+            .filter(|c| c.verified && c.source_document == chunk.document_id && chunk.cited_authorities.contains(&c.text))
+            .map(|c| c.text.as_str())
+            .collect();
+        let mut line = format!("[{}] {}, passage {}", index + 1, title, chunk.chunk_index + 1);
+        if !verified.is_empty() {
+            line.push_str(&format!(" (cites {})", verified.join("; ")));
+        }
+        blocks.push(DocxBlock::Bullet(line));
+        if memo.template == MemoTemplate::Detailed {
+            blocks.push(DocxBlock::Paragraph(excerpt(&chunk.content)));
+        }
+    }
+
+    if memo.template == MemoTemplate::Detailed {
+        blocks.push(DocxBlock::Heading(1, "Retrieval Notes".to_string()));
+        blocks.push(DocxBlock::Paragraph(format!(
+            "Retrieval confidence {:.0}%. {} passage(s) from {} document(s); {} contradiction(s) flagged between them.",
+            result.confidence * 100.0,
+            result.chunks.len(),
+            result.chunks.iter().map(|c| c.document_id.as_str()).collect::<HashSet<_>>().len(),
+            result.contradictions.len()
+        )));
+        blocks.extend(result.reasoning.iter().map(|step| DocxBlock::Bullet(step.clone())));
+    }
+    if let Some(disclosure) = disclosure {
+        blocks.extend(disclosure.to_blocks());
+    }
+    blocks
+}
+
+/// Lay report blocks out as a PDF
+fn write_pdf(path: &Path, title: &str, blocks: &[DocxBlock]) -> Result<()> {
+    let mut pdf = PdfFlow::new(title)?;
+    for block in blocks {
+        match block {
+            DocxBlock::Title(text) => {
+                pdf.text(text, 16.0, 1, 60);
+                pdf.gap(2.0);
+            }
+            DocxBlock::Heading(1, text) => {
+                pdf.gap(4.0);
+                pdf.text(text, 12.0, 1, 80);
+                pdf.gap(1.0);
+            }
+            DocxBlock::Heading(_, text) => {
+                pdf.gap(2.0);
+                pdf.text(text, 10.0, 1, 95);
+            }
+            DocxBlock::Paragraph(text) => {
+                pdf.text(text, 9.0, 0, 110);
+                pdf.gap(1.5);
+            }
+            DocxBlock::Bullet(text) => pdf.text(&format!("- {}", text), 9.0, 0, 108),
+            DocxBlock::Table { header, rows } => {
+                pdf.text(&header.join(" | "), 8.0, 1, 120);
+                for row in rows {
+                    pdf.text(&row.join(" | "), 8.0, 0, 120);
+                }
+                pdf.gap(1.5);
+            }
+        }
+    }
+    pdf.save(path)
+}
+
+impl ChatExporter {
+    /// Write a research memo as "docx" or "pdf" into the export directory
+    pub fn export_research_memo(
+        &self,
+        memo: &ResearchMemoExport,
+        format: &str,
+        disclosure: Option<&Disclosure>,
+    ) -> Result<PathBuf> {
+        if memo.question.trim().is_empty() || memo.answer.trim().is_empty() {
+            return Err(anyhow::anyhow!("A research memo needs the question and the answer"));
+        }
+        let blocks = memo_blocks(memo, &Utc::now().format("%Y-%m-%d").to_string(), disclosure);
+        let id = Uuid::new_v4();
+        match format.to_lowercase().as_str() {
+            "docx" => {
+                let path = self.export_path.join(format!("research_memo_{}.docx", id));
+                docx_writer::write_docx(&path, &blocks)?;
+                Ok(path)
+            }
+            "pdf" => {
+                let path = self.export_path.join(format!("research_memo_{}.pdf", id));
+                write_pdf(&path, memo.title.as_deref().unwrap_or("Legal Research Memorandum"), &blocks)?;
+                Ok(path)
+            }
+            _ => Err(anyhow::anyhow!("Unsupported research memo format: {}", format)),
+        }
+    }
+}
+
+impl ResearchMemoExport {
+    /// The documents the memo quotes, under the matter each chunk is filed in
+    pub fn export_items(&self) -> Vec<(String, Option<String>)> {
+        self.result
+            .chunks
+            .iter()
+            .map(|c| (c.document_id.clone(), c.metadata.get("matter_id").cloned()))
+            .collect()
+    }
+
+    /// What a reviewer approves: the question, the answer and the passages it draws on
+    pub fn content_sha256(&self) -> String {
+        let sources: Vec<(&str, &str)> = self.result.chunks.iter().map(|c| (c.id.as_str(), c.content.as_str())).collect();
+        let content = serde_json::json!({ "question": self.question, "answer": self.answer, "sources": sources });
+        review_queue::json_sha256(&content)
+    }
+
+    /// The review item an export of this memo is released through
+    fn review_submission(&self) -> Submission {
+        let content_sha256 = self.content_sha256();
+        Submission {
+            kind: WorkProductKind::Memo,
+            title: self.title.clone().unwrap_or_else(|| format!("Research memo: {}", self.question.trim())),
+            source: "research_memo_export".to_string(),
+            reference: format!("research_memo_export:{}", &content_sha256[..16]),
+            path: None,
+            matter_id: None,
+            content_sha256,
+        }
+    }
+
+    /// What the memo's provenance manifest records: the question and every retrieved chunk
+    pub fn provenance_claim(&self) -> ProvenanceClaim {
+        let titles: HashMap<&str, &str> =
+            self.result.documents.iter().map(|d| (d.id.as_str(), d.title.as_str())).collect();
+        ProvenanceClaim {
+            title: self.title.clone().unwrap_or_else(|| format!("Research memo: {}", self.question.trim())),
+            work_product: "research_memo".to_string(),
+            model: self.model.clone().unwrap_or_else(|| "unspecified".to_string()),
+            prompts_sha256: provenance::prompts_sha256(&[self.question.clone()]),
+            sources: self
+                .result
+                .chunks
+                .iter()
+                .map(|c| {
+                    ProvenanceSource::new(&c.document_id, titles.get(c.document_id.as_str()).copied(), Some(&c.id), &c.content)
+                })
+                .collect(),
+        }
+    }
+}
+
 // Tauri commands for chat export
 #[tauri::command]
 pub async fn export_chat_session(
+    session_id: String,
     exporter: tauri::State<'_, std::sync::Arc<std::sync::Mutex<ChatExporter>>>,
+    disclosures: tauri::State<'_, DisclosureStorage>,
+    provenance: tauri::State<'_, ProvenanceStorage>,
+    llm: tauri::State<'_, std::sync::Arc<LLMManager>>,
+    security: tauri::State<'_, std::sync::Arc<std::sync::Mutex<SecurityManager>>>,
+    sessions: tauri::State<'_, SessionStorage>,
+    barriers: tauri::State<'_, BarrierStorage>,
+    review: tauri::State<'_, ReviewQueueStorage>,
     session_data: String,
     format: String,
     options_data: String,
 ) -> Result<String, String> {
     let session: ChatSession = serde_json::from_str(&session_data)
         .map_err(|e| format!("Failed to parse session data: {}", e))?;
+    let user = authenticated_user(&session_id, &sessions)?;
+    barriers.check_export(&security, &user, &export_items(&session))?;
+    let reference = format!("chat:{}", session.id);
+    let transcript_sha256 = content_sha256(&session);
+    let submission = Submission {
+        kind: WorkProductKind::Memo,
+        title: format!("Chat transcript: {}", session.title),
+        source: "chat_export".to_string(),
+        reference: reference.clone(),
+        path: None,
+        matter_id: session.metadata.get("matter_id").cloned(),
+        content_sha256: transcript_sha256.clone(),
+    };
+    if let Err(reason) = review_queue::release_or_submit(&review, &user, submission) {
+        review_queue::audit_withheld(&security, &reference, &reason);
+        return Err(reason);
+    }
 
     let options: ExportOptions = serde_json::from_str(&options_data)
         .map_err(|e| format!("Failed to parse export options: {}", e))?;
 
+    let disclosure = disclosures.resolve(
+        options.jurisdiction.as_deref(),
+        session.metadata.get("model").map(String::as_str),
+        options.include_disclosure,
+    );
     let exporter = exporter.lock().unwrap();
     let file_path = exporter
-        .export_session(&session, &format, &options)
+        .export_session(&session, &format, &options, disclosure.as_ref())
         .await
         .map_err(|e| format!("Export failed: {}", e))?;
+    provenance
+        .stamp(
+            &file_path,
+            provenance_claim(&session),
+            options.provenance_sidecar,
+            &llm,
+            &security.lock().unwrap(),
+        )
+        .map_err(|e| format!("Failed to record the export's provenance: {}", e))?;
+    review
+        .record_export(&reference, WorkProductKind::Memo, &transcript_sha256, &file_path)
+        .map_err(|e| e.to_string())?;
+
+    Ok(file_path.to_string_lossy().to_string())
+}
+
+/// Export a research memo whose `result` the desktop command retrieved for its question in the
+/// session's scope
+#[allow(clippy::too_many_arguments)]
+pub async fn export_research_memo(
+    session_id: String,
+    exporter: tauri::State<'_, std::sync::Arc<std::sync::Mutex<ChatExporter>>>,
+    disclosures: tauri::State<'_, DisclosureStorage>,
+    provenance: tauri::State<'_, ProvenanceStorage>,
+    llm: tauri::State<'_, std::sync::Arc<LLMManager>>,
+    security: tauri::State<'_, std::sync::Arc<std::sync::Mutex<SecurityManager>>>,
+    sessions: tauri::State<'_, SessionStorage>,
+    barriers: tauri::State<'_, BarrierStorage>,
+    review: tauri::State<'_, ReviewQueueStorage>,
+    memo: ResearchMemoExport,
+    format: Option<String>, // "docx" (default) or "pdf"
+) -> Result<String, String> {
+    let user = authenticated_user(&session_id, &sessions)?;
+    barriers.check_export(&security, &user, &memo.export_items())?;
+    let submission = memo.review_submission();
+    let (reference, memo_sha256) = (submission.reference.clone(), submission.content_sha256.clone());
+    if let Err(reason) = review_queue::release_or_submit(&review, &user, submission) {
+        review_queue::audit_withheld(&security, &reference, &reason);
+        return Err(reason);
+    }
+    let disclosure = disclosures.resolve(memo.jurisdiction.as_deref(), memo.model.as_deref(), memo.include_disclosure);
+    let file_path = exporter
+        .lock()
+        .unwrap()
+        .export_research_memo(&memo, format.as_deref().unwrap_or("docx"), disclosure.as_ref())
+        .map_err(|e| format!("Export failed: {}", e))?;
+    provenance
+        .stamp(
+            &file_path,
+            memo.provenance_claim(),
+            memo.provenance_sidecar,
+            &llm,
+            &security.lock().unwrap(),
+        )
+        .map_err(|e| format!("Failed to record the export's provenance: {}", e))?;
+    review
+        .record_export(&reference, WorkProductKind::Memo, &memo_sha256, &file_path)
+        .map_err(|e| e.to_string())?;
 
     Ok(file_path.to_string_lossy().to_string())
 }
@@ -437,7 +931,110 @@ pub async fn create_export_options(
         format_style,
         page_header: Some("BEAR AI Legal Assistant - Chat Export".to_string()),
         page_footer: Some("Confidential - Legal Professional Privilege May Apply".to_string()),
+        include_disclosure: None,
+        jurisdiction: None,
+        provenance_sidecar: false,
     };
 
     serde_json::to_string(&options).map_err(|e| e.to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nemotron_rag::{CitationInfo, PrecedentialValue, RAGChunk};
+
+    fn chunk(document_id: &str, chunk_index: usize, content: &str, cites: &[&str]) -> RAGChunk {
+        RAGChunk {
+            id: format!("{}-chunk-{}", document_id, chunk_index),
+            document_id: document_id.to_string(),
+            content: content.to_string(),
+            embedding: vec![],
+            chunk_index,
+            tokens: 0,
+            overlap: 0,
+            legal_concepts: vec![],
+            cited_authorities: cites.iter().map(|c| c.to_string()).collect(),
+            confidence: 0.8,
+            temporal_relevance: 1.0,
+            created_at: Utc::now(),
+            metadata: HashMap::new(),
+            rerank: None,
+            authority: None,
+        }
+    }
+
+    fn memo(answer: &str, template: MemoTemplate) -> ResearchMemoExport {
+        ResearchMemoExport {
+            question: " Is the limitation clause enforceable? ".to_string(),
+            answer: answer.to_string(),
+            result: RetrievalResult {
+                chunks: vec![
+                    chunk("msa", 3, "Liability is capped at the fees paid.", &["Photo Production v Securicor"]),
+                    chunk("memo", 0, &"word ".repeat(200), &[]),
+                ],
+                citations: vec![CitationInfo {
+                    id: "c1".to_string(),
+                    text: "Photo Production v Securicor".to_string(),
+                    source_document: "msa".to_string(),
+                    verified: true,
+                    confidence: 0.9,
+                    precedential_value: PrecedentialValue::Binding,
+                }],
+                confidence: 0.8,
+                reasoning: vec!["Dense retrieval over legal_chunks".to_string()],
+                ..Default::default()
+            },
+            title: None,
+            jurisdiction: Some("UK".to_string()),
+            model: None,
+            template,
+            include_disclosure: None,
+            provenance_sidecar: false,
+        }
+    }
+
+    #[test]
+    fn test_split_answer() {
+        let (brief, analysis) = split_answer("Yes, it is enforceable [1].\n\nThe cap is reasonable.\n\n  \n\nIt was negotiated.");
+        assert_eq!(brief, "Yes, it is enforceable [1].");
+        assert_eq!(analysis, vec!["The cap is reasonable.", "It was negotiated."]);
+
+        let (brief, analysis) = split_answer("Yes. The cap is reasonable and was negotiated.");
+        assert_eq!(brief, "Yes.");
+        assert_eq!(analysis, vec!["Yes. The cap is reasonable and was negotiated."]);
+
+        assert_eq!(split_answer(" \n\n "), (String::new(), Vec::new()));
+    }
+
+    #[test]
+    fn test_memo_blocks_follow_the_template() {
+        let answer = "Yes, it is enforceable [1].\n\nThe cap is reasonable.";
+        let blocks = memo_blocks(&memo(answer, MemoTemplate::Standard), "2026-03-02", None);
+        assert_eq!(blocks[0], DocxBlock::Title("Legal Research Memorandum".to_string()));
+        assert!(matches!(&blocks[1], DocxBlock::Paragraph(p) if p.contains("Jurisdiction: UK") && p.contains("2026-03-02")));
+        assert_eq!(blocks[3], DocxBlock::Paragraph("Is the limitation clause enforceable?".to_string()));
+        assert!(blocks.contains(&DocxBlock::Heading(1, "Analysis".to_string())));
+        assert!(blocks.contains(&DocxBlock::Paragraph("The cap is reasonable.".to_string())));
+        // Sources are numbered in retrieval order, with the verified authorities they cite
+        assert!(blocks.contains(&DocxBlock::Bullet("[1] msa, passage 4 (cites Photo Production v Securicor)".to_string())));
+        assert!(blocks.contains(&DocxBlock::Bullet("[2] memo, passage 1".to_string())));
+        assert!(!blocks.contains(&DocxBlock::Heading(1, "Retrieval Notes".to_string())));
+
+        let short = memo_blocks(&memo(answer, MemoTemplate::Short), "2026-03-02", None);
+        assert!(!short.contains(&DocxBlock::Heading(1, "Analysis".to_string())));
+
+        let detailed = memo_blocks(&memo(answer, MemoTemplate::Detailed), "2026-03-02", None);
+        assert!(detailed.contains(&DocxBlock::Heading(1, "Retrieval Notes".to_string())));
+        let excerpt = detailed.iter().find_map(|b| match b {
+            DocxBlock::Paragraph(p) if p.starts_with("word") => Some(p.clone()),
+            _ => None,
+        });
+        assert!(excerpt.is_some_and(|e| e.ends_with("...") && e.chars().count() <= MEMO_EXCERPT_CHARS + 3));
+
+        let mut empty = memo(answer, MemoTemplate::Standard);
+        empty.result = RetrievalResult::default();
+        let blocks = memo_blocks(&empty, "2026-03-02", None);
+        assert!(blocks.contains(&DocxBlock::Paragraph("No indexed material was retrieved for this question.".to_string())));
+    }
+}
@@ -0,0 +1,431 @@
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+
+use crate::local_api::AnalyzerStorage;
+use crate::nemotron_rag::{self, DocumentType, LegalDocument};
+
+/// Chunking Profiles for BEAR AI
+/// Chunk size and overlap per document type: contracts split at clauses, long judgments in
+/// larger overlapping windows, statutes section by section. The profiles in force when a
+/// collection is first indexed are stored with it and used for every later document, so a
+/// collection is never chunked two ways; changing the defaults affects new collections only.
+/// Document types without a profile use the RAG configuration's global size and overlap.
+// Smallest chunk a profile may ask for, in words
+const MIN_CHUNK_SIZE: usize = 16;
+const MAX_CHUNK_SIZE: usize = 4096;
+// Characters of each chunk shown by a preview
+const PREVIEW_CHARS: usize = 400;
+
+fn default_split_on_sections() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChunkingProfile {
+    pub max_chunk_size: usize, // words
+    pub chunk_overlap: usize,  // words repeated from the previous chunk of a split section
+    #[serde(default = "default_split_on_sections")]
+    pub split_on_sections: bool, // start chunks at headings such as "Section 4" or "§ 12"
+}
+
+/// The sizes of the default RAG configuration
+impl Default for ChunkingProfile {
+    fn default() -> Self {
+        Self {
+            max_chunk_size: 512,
+            chunk_overlap: 50,
+            split_on_sections: true,
+        }
+    }
+}
+
+impl ChunkingProfile {
+    pub fn validate(&self) -> Result<()> {
+        if !(MIN_CHUNK_SIZE..=MAX_CHUNK_SIZE).contains(&self.max_chunk_size) {
+            return Err(anyhow!(
+                "Chunk size must be between {} and {} words",
+                MIN_CHUNK_SIZE,
+                MAX_CHUNK_SIZE
+            ));
+        }
+        if self.chunk_overlap >= self.max_chunk_size / 2 {
+            return Err(anyhow!("Chunk overlap must be less than half the chunk size"));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ChunkingProfiles {
+    #[serde(default)]
+    pub default: Option<ChunkingProfile>, // for types without a profile; None for the RAG configuration's
+    #[serde(default)]
+    pub by_type: BTreeMap<DocumentType, ChunkingProfile>,
+}
+
+impl ChunkingProfiles {
+    pub fn profile(&self, document_type: &DocumentType, fallback: &ChunkingProfile) -> ChunkingProfile {
+        self.by_type
+            .get(document_type)
+            .or(self.default.as_ref())
+            .unwrap_or(fallback)
+            .clone()
+    }
+}
+
+/// Profiles stored with a collection when it was first indexed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CollectionChunking {
+    pub profiles: ChunkingProfiles, // `default` always set, so a later configuration change does not apply
+    pub pinned_at: String,
+    #[serde(default)]
+    pub changed_at: Option<String>, // set by hand since; documents indexed before keep the old chunking
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChunkingSettings {
+    pub profiles: ChunkingProfiles, // for collections not yet indexed
+    #[serde(default)]
+    pub collections: BTreeMap<String, CollectionChunking>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChunkPreview {
+    pub index: usize,
+    pub words: usize,
+    pub tokens: usize,
+    pub overlap: usize,
+    pub excerpt: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChunkingPreview {
+    pub document_type: DocumentType,
+    pub collection: Option<String>,
+    pub profile: ChunkingProfile,
+    pub chunks: Vec<ChunkPreview>,
+}
+
+/// Settings are re-read from disk for every change: the desktop commands and the RAG index in
+/// the library crate each hold a store over the same file
+pub struct ChunkingProfileStore {
+    path: PathBuf,
+    lock: Mutex<()>,
+}
+
+impl ChunkingProfileStore {
+    pub fn new(app_data_dir: &Path) -> Self {
+        Self {
+            path: app_data_dir.join("chunking_profiles.json"),
+            lock: Mutex::new(()),
+        }
+    }
+
+    fn load(&self) -> Result<ChunkingSettings> {
+        if !self.path.exists() {
+            return Ok(ChunkingSettings::default());
+        }
+        Ok(serde_json::from_str(&fs::read_to_string(&self.path)?)?)
+    }
+
+    fn update<T>(&self, update: impl FnOnce(&mut ChunkingSettings) -> Result<T>) -> Result<T> {
+        let _guard = self.lock.lock().unwrap();
+        let mut settings = self.load()?;
+        let result = update(&mut settings)?;
+        fs::write(&self.path, serde_json::to_string_pretty(&settings)?)?;
+        Ok(result)
+    }
+
+    pub fn settings(&self) -> Result<ChunkingSettings> {
+        let _guard = self.lock.lock().unwrap();
+        self.load()
+    }
+
+    /// Set the profile for `document_type`, or the default when None, for new collections or
+    /// for `collection`
+    pub fn set_profile(
+        &self,
+        collection: Option<&str>,
+        document_type: Option<DocumentType>,
+        profile: ChunkingProfile,
+    ) -> Result<ChunkingSettings> {
+        profile.validate()?;
+        self.update(|settings| {
+            let profiles = match collection {
+                None => &mut settings.profiles,
+                Some(collection) => {
+                    let pinned = settings
+                        .collections
+                        .get_mut(collection)
+                        .ok_or_else(|| anyhow!("Collection {} has not been indexed", collection))?;
+                    pinned.changed_at = Some(Utc::now().to_rfc3339());
+                    &mut pinned.profiles
+                }
+            };
+            match document_type {
+                Some(document_type) => {
+                    profiles.by_type.insert(document_type, profile);
+                }
+                None => profiles.default = Some(profile),
+            }
+            Ok(settings.clone())
+        })
+    }
+
+    /// Remove the profile for `document_type` from the defaults for new collections
+    pub fn clear_profile(&self, document_type: &DocumentType) -> Result<ChunkingSettings> {
+        self.update(|settings| {
+            settings.profiles.by_type.remove(document_type);
+            Ok(settings.clone())
+        })
+    }
+
+    /// The profile a document would be chunked with, without pinning anything
+    pub fn profile_in_effect(
+        &self,
+        collection: Option<&str>,
+        document_type: &DocumentType,
+        fallback: &ChunkingProfile,
+    ) -> Result<ChunkingProfile> {
+        let settings = self.settings()?;
+        let profiles = collection
+            .and_then(|c| settings.collections.get(c))
+            .map_or(&settings.profiles, |pinned| &pinned.profiles);
+        Ok(profiles.profile(document_type, fallback))
+    }
+
+    /// The profile to chunk a document of `collection` with; the collection's first document
+    /// pins the current profiles to it
+    pub fn profile_for(
+        &self,
+        collection: &str,
+        document_type: &DocumentType,
+        fallback: &ChunkingProfile,
+    ) -> Result<ChunkingProfile> {
+        if let Some(pinned) = self.settings()?.collections.get(collection) {
+            return Ok(pinned.profiles.profile(document_type, fallback));
+        }
+        self.update(|settings| {
+            let profiles = settings.profiles.clone();
+            let pinned = settings
+                .collections
+                .entry(collection.to_string())
+                .or_insert_with(|| CollectionChunking {
+                    profiles: ChunkingProfiles {
+                        default: Some(profiles.default.unwrap_or_else(|| fallback.clone())),
+                        by_type: profiles.by_type,
+                    },
+                    pinned_at: Utc::now().to_rfc3339(),
+                    changed_at: None,
+                });
+            Ok(pinned.profiles.profile(document_type, fallback))
+        })
+    }
+}
+
+lazy_static! {
+    static ref GLOBAL_CHUNKING_PROFILES: RwLock<Option<Arc<ChunkingProfileStore>>> = RwLock::new(None);
+}
+
+/// Initialize the global chunking profiles read during indexing
+pub fn initialize_chunking_profiles(app_data_dir: &Path) -> Arc<ChunkingProfileStore> {
+    let store = Arc::new(ChunkingProfileStore::new(app_data_dir));
+    *GLOBAL_CHUNKING_PROFILES.write().unwrap() = Some(store.clone());
+    store
+}
+
+/// The profile to index a document with; `fallback` (the RAG configuration's sizes) when the
+/// profiles are not initialized or cannot be read
+pub fn indexing_profile(collection: &str, document_type: &DocumentType, fallback: ChunkingProfile) -> ChunkingProfile {
+    let Some(store) = GLOBAL_CHUNKING_PROFILES.read().unwrap().clone() else {
+        return fallback;
+    };
+    match store.profile_for(collection, document_type, &fallback) {
+        Ok(profile) => profile,
+        Err(e) => {
+            log::warn!("Chunking profiles unavailable, using the configured sizes: {}", e);
+            fallback
+        }
+    }
+}
+
+/// How `document` would be chunked with `profile`
+pub fn preview_document(document: &LegalDocument, collection: Option<String>, profile: ChunkingProfile) -> ChunkingPreview {
+    let content = nemotron_rag::clean_legal_text(&document.content);
+    let chunks = nemotron_rag::legal_chunks(&content, document, &profile)
+        .into_iter()
+        .map(|chunk| ChunkPreview {
+            index: chunk.chunk_index,
+            words: chunk.content.split_whitespace().count(),
+            tokens: chunk.tokens,
+            overlap: chunk.overlap,
+            excerpt: if chunk.content.chars().count() > PREVIEW_CHARS {
+                format!("{}...", chunk.content.chars().take(PREVIEW_CHARS).collect::<String>())
+            } else {
+                chunk.content
+            },
+        })
+        .collect();
+    ChunkingPreview {
+        document_type: document.document_type.clone(),
+        collection,
+        profile,
+        chunks,
+    }
+}
+
+pub type ChunkingProfileStorage = Arc<ChunkingProfileStore>;
+
+#[tauri::command]
+pub async fn get_chunking_profiles(
+    store: tauri::State<'_, ChunkingProfileStorage>,
+) -> Result<ChunkingSettings, String> {
+    store.settings().map_err(|e| e.to_string())
+}
+
+/// Set a chunking profile for new collections, or for an indexed `collection` (whose documents
+/// must be re-ingested to be chunked the new way)
+#[tauri::command]
+pub async fn set_chunking_profile(
+    collection: Option<String>,
+    document_type: Option<DocumentType>,
+    profile: ChunkingProfile,
+    store: tauri::State<'_, ChunkingProfileStorage>,
+) -> Result<ChunkingSettings, String> {
+    store
+        .set_profile(collection.as_deref(), document_type, profile)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn clear_chunking_profile(
+    document_type: DocumentType,
+    store: tauri::State<'_, ChunkingProfileStorage>,
+) -> Result<ChunkingSettings, String> {
+    store.clear_profile(&document_type).map_err(|e| e.to_string())
+}
+
+/// Show how a file would be chunked, with `profile` or else the one in effect for its type and
+/// collection
+#[tauri::command]
+pub async fn preview_chunking(
+    file_path: String,
+    document_type: DocumentType,
+    collection: Option<String>,
+    profile: Option<ChunkingProfile>,
+    analyzer: tauri::State<'_, AnalyzerStorage>,
+    store: tauri::State<'_, ChunkingProfileStorage>,
+) -> Result<ChunkingPreview, String> {
+    let profile = match profile {
+        Some(profile) => {
+            profile.validate().map_err(|e| e.to_string())?;
+            profile
+        }
+        None => store
+            .profile_in_effect(collection.as_deref(), &document_type, &ChunkingProfile::default())
+            .map_err(|e| e.to_string())?,
+    };
+    let content = analyzer
+        .extract_text(Path::new(&file_path))
+        .await
+        .map_err(|e| format!("{}: {}", file_path, e))?;
+    let document = nemotron_rag::legal_document_from_file(
+        Path::new(&file_path),
+        content,
+        &nemotron_rag::BulkIngestRequest {
+            directory: String::new(),
+            recursive: None,
+            collection: collection.clone(),
+            document_type: Some(document_type),
+            jurisdiction: None,
+            matter_id: None,
+            ocr_languages: None,
+        },
+    );
+    Ok(preview_document(&document, collection, profile))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(max_chunk_size: usize, chunk_overlap: usize) -> ChunkingProfile {
+        ChunkingProfile {
+            max_chunk_size,
+            chunk_overlap,
+            split_on_sections: true,
+        }
+    }
+
+    #[test]
+    fn test_collections_keep_the_profiles_they_were_indexed_with() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = ChunkingProfileStore::new(dir.path());
+        let configured = profile(512, 50);
+        store.set_profile(None, Some(DocumentType::Contract), profile(200, 20)).unwrap();
+
+        assert_eq!(store.profile_for("deals", &DocumentType::Contract, &configured).unwrap(), profile(200, 20));
+        assert_eq!(store.profile_for("deals", &DocumentType::CaseLaw, &configured).unwrap(), configured);
+
+        // New defaults and a new configuration leave the indexed collection alone
+        store.set_profile(None, Some(DocumentType::Contract), profile(300, 30)).unwrap();
+        store.set_profile(None, None, profile(800, 80)).unwrap();
+        let reconfigured = profile(1000, 100);
+        assert_eq!(store.profile_for("deals", &DocumentType::Contract, &reconfigured).unwrap(), profile(200, 20));
+        assert_eq!(store.profile_for("deals", &DocumentType::CaseLaw, &reconfigured).unwrap(), configured);
+        assert_eq!(store.profile_for("cases", &DocumentType::CaseLaw, &reconfigured).unwrap(), profile(800, 80));
+
+        // A second store over the same file sees the pins
+        let other = ChunkingProfileStore::new(dir.path());
+        assert_eq!(
+            other.profile_in_effect(Some("deals"), &DocumentType::Contract, &reconfigured).unwrap(),
+            profile(200, 20)
+        );
+        other.set_profile(Some("deals"), Some(DocumentType::Contract), profile(250, 25)).unwrap();
+        assert!(store.settings().unwrap().collections["deals"].changed_at.is_some());
+
+        assert!(store.set_profile(Some("unknown"), None, profile(200, 20)).is_err());
+        assert!(store.set_profile(None, None, profile(100, 60)).is_err());
+        assert!(store.set_profile(None, None, profile(8, 0)).is_err());
+    }
+
+    #[test]
+    fn test_preview_follows_the_profile() {
+        let body: Vec<String> = (0..50).map(|i| format!("w{}", i)).collect();
+        let document = LegalDocument {
+            id: "msa".to_string(),
+            title: "MSA".to_string(),
+            content: format!("Section 1 Definitions apply. Section 2 {}", body.join(" ")),
+            jurisdiction: "General".to_string(),
+            document_type: DocumentType::Contract,
+            last_updated: Utc::now(),
+            citations: vec![],
+            metadata: nemotron_rag::DocumentMetadata {
+                court: None,
+                judge: None,
+                parties: vec![],
+                topics: vec![],
+                precedential_value: nemotron_rag::PrecedentialValue::NotPrecedential,
+                confidence: 1.0,
+            },
+        };
+
+        let preview = preview_document(&document, None, profile(20, 5));
+        let words: Vec<usize> = preview.chunks.iter().map(|c| c.words).collect();
+        assert_eq!(words, vec![4, 20, 20, 20, 7]);
+        assert_eq!(preview.chunks[2].overlap, 5);
+
+        let unsplit = ChunkingProfile {
+            split_on_sections: false,
+            ..profile(20, 5)
+        };
+        let words: Vec<usize> = preview_document(&document, None, unsplit).chunks.iter().map(|c| c.words).collect();
+        assert_eq!(words, vec![20, 20, 20, 11]);
+    }
+}
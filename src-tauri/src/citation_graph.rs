@@ -0,0 +1,416 @@
+use anyhow::Result;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::nemotron_rag::{DocumentType, GraphRelation, LegalDocument, RelationType};
+
+/// Citation Graph for BEAR AI
+/// Links indexed documents by the authorities they cite. A document is known by the reporter
+/// citations in its title or opening lines (550 U.S. 544) and by its case name; a citation
+/// resolves to an indexed document when its key matches one of those, so citations of a precedent
+/// indexed later link up once it arrives. Citations that match no indexed document stay in the
+/// graph as external authorities, which lets chains run through precedents that are not indexed.
+// Path searches stop after this many hops
+const MAX_PATH_HOPS: usize = 8;
+
+// Opening lines of an opinion that carry its own reporter citation
+const HEADER_LINES: usize = 5;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct GraphDocument {
+    document_id: String,
+    title: String,
+    matter_id: Option<String>,
+    keys: BTreeSet<String>, // keys other documents cite this one by
+    cites: Vec<String>,     // citations as written in the document
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CitationNode {
+    pub id: String,                  // document id, or "authority:<key>" for an authority that is not indexed
+    pub document_id: Option<String>, // None for external authorities
+    pub title: String,               // document title, or the citation as written
+    pub matter_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CitationLink {
+    pub node: CitationNode,
+    pub citations: Vec<String>, // the citations, as written, that make the link
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CitationPath {
+    pub nodes: Vec<CitationNode>, // each node cites the next
+    pub hops: usize,
+    pub reversed: bool, // the chain runs from `to` to `from`: the later document cites the earlier one
+}
+
+/// Keys a citation can be matched on: "550 U.S. 544, 570 (2007)" and "550 US 544" share
+/// "550 us 544"; "Bell Atlantic Corp. v. Twombly" is keyed by its words. Other citations
+/// (statutes, rules) are keyed by their normalized text.
+pub fn citation_keys(citation: &str) -> Vec<String> {
+    let reporter = Regex::new(r"\b(\d{1,4})\s+([A-Z][A-Za-z0-9.' ]{0,20}?)\s+(\d{1,5})\b").unwrap();
+    let words = |text: &str| {
+        text.split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .map(str::to_lowercase)
+            .collect::<Vec<_>>()
+    };
+
+    let mut keys = Vec::new();
+    if let Some(caps) = reporter.captures(citation) {
+        keys.push(format!("{} {} {}", &caps[1], words(&caps[2]).concat(), &caps[3]));
+    }
+    let name = citation.split(|c: char| c.is_ascii_digit()).next().unwrap_or("");
+    if name.contains(" v. ") || name.contains(" v ") || name.starts_with("In re ") {
+        let name: Vec<String> = words(name).into_iter().filter(|w| w != "v" && w != "vs").collect();
+        if name.len() >= 2 {
+            keys.push(format!("name:{}", name.join(" ")));
+        }
+    }
+    if keys.is_empty() {
+        let text = words(citation).join(" ");
+        if !text.is_empty() {
+            keys.push(text);
+        }
+    }
+    keys
+}
+
+/// Keys an indexed document is cited by: its case name and reporter citation from the title, and
+/// for case law the reporter citations in the opening lines
+fn own_keys(document: &LegalDocument) -> BTreeSet<String> {
+    let mut keys: BTreeSet<String> = BTreeSet::new();
+    let title_keys = citation_keys(&document.title);
+    // A title like "Services Agreement" is not a citation of anything
+    if title_keys.iter().any(|k| k.starts_with("name:") || k.chars().next().is_some_and(|c| c.is_ascii_digit())) {
+        keys.extend(title_keys);
+    }
+    if matches!(document.document_type, DocumentType::CaseLaw | DocumentType::Opinion) {
+        let reporter = Regex::new(r"\b\d{1,4}\s+[A-Z][A-Za-z0-9.' ]{0,20}?\s+\d{1,5}\b").unwrap();
+        for line in document.content.lines().take(HEADER_LINES) {
+            for found in reporter.find_iter(line) {
+                keys.extend(citation_keys(found.as_str()));
+            }
+        }
+    }
+    keys
+}
+
+/// Citations between indexed documents, kept in an append-only log so the graph survives restarts
+#[derive(Default)]
+pub struct CitationGraph {
+    path: Option<PathBuf>,
+    documents: BTreeMap<String, GraphDocument>,
+}
+
+impl CitationGraph {
+    /// In-memory graph that is not persisted
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Graph persisted to a JSON lines log; a later entry for a document replaces earlier ones
+    pub fn open(path: &Path) -> Result<Self> {
+        let mut documents = BTreeMap::new();
+        if path.exists() {
+            for line in fs::read_to_string(path)?.lines().filter(|l| !l.trim().is_empty()) {
+                let document: GraphDocument = serde_json::from_str(line)?;
+                documents.insert(document.document_id.clone(), document);
+            }
+        }
+        Ok(CitationGraph {
+            path: Some(path.to_path_buf()),
+            documents,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.documents.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.documents.is_empty()
+    }
+
+    /// Record an indexed document with the citations in `document.citations`, replacing what was
+    /// recorded for it before
+    pub fn add_document(&mut self, document: &LegalDocument, matter_id: Option<&str>) -> Result<()> {
+        let keys = own_keys(document);
+        let mut cites: Vec<String> = Vec::new();
+        for citation in &document.citations {
+            let citation = citation.trim();
+            // A document quoting its own citation does not cite itself
+            if !citation.is_empty() && !cites.iter().any(|c| c == citation) && !citation_keys(citation).iter().any(|k| keys.contains(k)) {
+                cites.push(citation.to_string());
+            }
+        }
+        let entry = GraphDocument {
+            document_id: document.id.clone(),
+            title: document.title.clone(),
+            matter_id: matter_id.map(str::to_string),
+            keys,
+            cites,
+        };
+        if let Some(path) = &self.path {
+            let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+            writeln!(file, "{}", serde_json::to_string(&entry)?)?;
+        }
+        self.documents.insert(entry.document_id.clone(), entry);
+        Ok(())
+    }
+
+    /// Forget a deleted document; the log is rewritten without it. Documents citing it keep their
+    /// citations, which now resolve to the authority rather than the document.
+    pub fn remove_document(&mut self, document_id: &str) -> Result<()> {
+        if self.documents.remove(document_id).is_none() {
+            return Ok(());
+        }
+        if let Some(path) = &self.path {
+            let mut log = String::new();
+            for entry in self.documents.values() {
+                log.push_str(&serde_json::to_string(entry)?);
+                log.push('\n');
+            }
+            fs::write(path, log)?;
+        }
+        Ok(())
+    }
+
+    fn key_index(&self) -> HashMap<&str, &GraphDocument> {
+        let mut index = HashMap::new();
+        for document in self.documents.values() {
+            for key in &document.keys {
+                index.entry(key.as_str()).or_insert(document);
+            }
+        }
+        index
+    }
+
+    fn document_node(document: &GraphDocument) -> CitationNode {
+        CitationNode {
+            id: document.document_id.clone(),
+            document_id: Some(document.document_id.clone()),
+            title: document.title.clone(),
+            matter_id: document.matter_id.clone(),
+        }
+    }
+
+    /// The indexed document a citation refers to, or an external authority
+    fn resolve(&self, index: &HashMap<&str, &GraphDocument>, citation: &str) -> Option<CitationNode> {
+        let keys = citation_keys(citation);
+        if let Some(document) = keys.iter().find_map(|k| index.get(k.as_str())) {
+            return Some(Self::document_node(document));
+        }
+        keys.first().map(|key| CitationNode {
+            id: format!("authority:{}", key),
+            document_id: None,
+            title: citation.to_string(),
+            matter_id: None,
+        })
+    }
+
+    /// A document id, or a citation of an indexed document or of an external authority
+    fn lookup(&self, index: &HashMap<&str, &GraphDocument>, node: &str) -> Option<CitationNode> {
+        match self.documents.get(node) {
+            Some(document) => Some(Self::document_node(document)),
+            None => self.resolve(index, node.strip_prefix("authority:").unwrap_or(node)),
+        }
+    }
+
+    fn outgoing(&self, index: &HashMap<&str, &GraphDocument>, document: &GraphDocument) -> Vec<CitationLink> {
+        let mut links: Vec<CitationLink> = Vec::new();
+        for citation in &document.cites {
+            let Some(node) = self.resolve(index, citation) else {
+                continue;
+            };
+            if node.id == document.document_id {
+                continue;
+            }
+            match links.iter_mut().find(|l| l.node.id == node.id) {
+                Some(link) => link.citations.push(citation.clone()),
+                None => links.push(CitationLink {
+                    node,
+                    citations: vec![citation.clone()],
+                }),
+            }
+        }
+        links
+    }
+
+    /// Authorities cited by a document: indexed documents first, then external authorities
+    pub fn cited_by(&self, document: &str) -> Vec<CitationLink> {
+        let index = self.key_index();
+        let Some(document) = self.documents.get(document) else {
+            return Vec::new();
+        };
+        let mut links = self.outgoing(&index, document);
+        links.sort_by_key(|l| l.node.document_id.is_none());
+        links
+    }
+
+    /// Indexed documents that cite a document or an authority (given by id or citation)
+    pub fn citing_documents(&self, node: &str) -> Vec<CitationLink> {
+        let index = self.key_index();
+        let Some(target) = self.lookup(&index, node) else {
+            return Vec::new();
+        };
+        self.documents
+            .values()
+            .filter_map(|document| {
+                let link = self.outgoing(&index, document).into_iter().find(|l| l.node.id == target.id)?;
+                Some(CitationLink {
+                    node: Self::document_node(document),
+                    citations: link.citations,
+                })
+            })
+            .collect()
+    }
+
+    fn shortest_directed(&self, index: &HashMap<&str, &GraphDocument>, from: &CitationNode, to: &CitationNode) -> Option<Vec<CitationNode>> {
+        let mut previous: HashMap<String, CitationNode> = HashMap::new();
+        let mut queue = VecDeque::from([(from.clone(), 0)]);
+        previous.insert(from.id.clone(), from.clone());
+        while let Some((node, depth)) = queue.pop_front() {
+            if node.id == to.id {
+                let mut path = vec![node];
+                while path.last().is_some_and(|n| n.id != from.id) {
+                    path.push(previous[&path.last().unwrap().id].clone());
+                }
+                path.reverse();
+                return Some(path);
+            }
+            // External authorities cite nothing we know of
+            let Some(document) = self.documents.get(&node.id) else {
+                continue;
+            };
+            if depth == MAX_PATH_HOPS {
+                continue;
+            }
+            for link in self.outgoing(index, document) {
+                if !previous.contains_key(&link.node.id) {
+                    previous.insert(link.node.id.clone(), node.clone());
+                    queue.push_back((link.node, depth + 1));
+                }
+            }
+        }
+        None
+    }
+
+    /// Shortest chain of citations from one document or authority to another, following
+    /// citations in either direction
+    pub fn shortest_path(&self, from: &str, to: &str) -> Option<CitationPath> {
+        let index = self.key_index();
+        let (from, to) = (self.lookup(&index, from)?, self.lookup(&index, to)?);
+        let (nodes, reversed) = match self.shortest_directed(&index, &from, &to) {
+            Some(nodes) => (nodes, false),
+            None => (self.shortest_directed(&index, &to, &from)?, true),
+        };
+        Some(CitationPath {
+            hops: nodes.len() - 1,
+            nodes,
+            reversed,
+        })
+    }
+
+    /// Citations between the given documents and other indexed documents, for retrieval results
+    pub fn relations(&self, document_ids: &[String]) -> Vec<GraphRelation> {
+        let index = self.key_index();
+        let mut relations = Vec::new();
+        for document in document_ids.iter().filter_map(|id| self.documents.get(id)) {
+            for link in self.outgoing(&index, document) {
+                if let Some(target) = link.node.document_id {
+                    relations.push(GraphRelation {
+                        source_doc: document.document_id.clone(),
+                        target_doc: target,
+                        relation_type: RelationType::Cites,
+                        strength: 1.0,
+                    });
+                }
+            }
+        }
+        relations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nemotron_rag::{DocumentMetadata, PrecedentialValue};
+    use chrono::Utc;
+
+    fn document(id: &str, title: &str, content: &str, citations: &[&str]) -> LegalDocument {
+        LegalDocument {
+            id: id.to_string(),
+            title: title.to_string(),
+            content: content.to_string(),
+            jurisdiction: "US".to_string(),
+            document_type: DocumentType::CaseLaw,
+            last_updated: Utc::now(),
+            citations: citations.iter().map(|c| c.to_string()).collect(),
+            metadata: DocumentMetadata {
+                court: None,
+                judge: None,
+                parties: Vec::new(),
+                topics: Vec::new(),
+                precedential_value: PrecedentialValue::Binding,
+                confidence: 1.0,
+            },
+        }
+    }
+
+    #[test]
+    fn test_citation_keys() {
+        assert_eq!(citation_keys("550 U.S. 544, 570 (2007)"), vec!["550 us 544".to_string()]);
+        assert_eq!(
+            citation_keys("Bell Atlantic Corp. v. Twombly, 550 US 544 (2007)"),
+            vec!["550 us 544".to_string(), "name:bell atlantic corp twombly".to_string()]
+        );
+        assert_eq!(citation_keys("42 U.S.C. § 1983"), vec!["42 u s c 1983".to_string()]);
+    }
+
+    #[test]
+    fn test_traversal_and_persistence() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("citation_graph.jsonl");
+        let mut graph = CitationGraph::open(&path).unwrap();
+
+        // The brief cites Iqbal before Iqbal is indexed
+        let brief = document("brief", "Motion to Dismiss", "", &["Ashcroft v. Iqbal, 556 U.S. 662 (2009)", "42 U.S.C. § 1983"]);
+        graph.add_document(&brief, Some("m1")).unwrap();
+        assert_eq!(graph.cited_by("brief")[0].node.id, "authority:556 us 662");
+
+        let iqbal = document(
+            "iqbal",
+            "Ashcroft v. Iqbal",
+            "556 U.S. 662 (2009)\nSupreme Court of the United States",
+            &["Bell Atlantic Corp. v. Twombly, 550 U.S. 544 (2007)", "556 U.S. 662"],
+        );
+        graph.add_document(&iqbal, None).unwrap();
+        graph
+            .add_document(&document("twombly", "Bell Atlantic Corp. v. Twombly, 550 U.S. 544", "", &[]), None)
+            .unwrap();
+
+        let cited = graph.cited_by("brief");
+        assert_eq!(cited[0].node.document_id.as_deref(), Some("iqbal"));
+        assert_eq!(cited[1].node.title, "42 U.S.C. § 1983");
+        assert_eq!(graph.cited_by("iqbal").len(), 1); // its own citation is not a link
+
+        let citing: Vec<String> = graph.citing_documents("550 U.S. 544").into_iter().map(|l| l.node.id).collect();
+        assert_eq!(citing, vec!["iqbal".to_string()]);
+
+        let reopened = CitationGraph::open(&path).unwrap();
+        assert_eq!(reopened.len(), 3);
+        let path = reopened.shortest_path("brief", "twombly").unwrap();
+        let ids: Vec<&str> = path.nodes.iter().map(|n| n.id.as_str()).collect();
+        assert_eq!((ids, path.hops, path.reversed), (vec!["brief", "iqbal", "twombly"], 2, false));
+        assert!(reopened.shortest_path("twombly", "brief").unwrap().reversed);
+        assert!(reopened.shortest_path("twombly", "42 U.S.C. § 1983").is_none());
+        assert_eq!(reopened.relations(&["iqbal".to_string()])[0].target_doc, "twombly");
+    }
+}
@@ -0,0 +1,343 @@
+use anyhow::{anyhow, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use crate::document_qa::split_pages;
+use crate::llm_manager::{EmbeddingBatchRequest, LLMManager};
+use crate::local_api::AnalyzerStorage;
+use crate::nemotron_rag::{legal_section_boundaries, split_into_chunks};
+
+/// Clause Search for BEAR AI
+/// Semantic search inside one document. The document is cut into clauses page by page, at
+/// section headings, numbered paragraphs and blank lines, so every clause belongs to a single
+/// page; clauses are embedded once per document and embedding model and kept in memory, and a
+/// query returns the clauses closest to it with the page each one is on.
+const MIN_CLAUSE_WORDS: usize = 30;
+const MAX_CLAUSE_WORDS: usize = 250;
+const CLAUSE_OVERLAP_WORDS: usize = 30;
+const MAX_HEADING_WORDS: usize = 12;
+const DEFAULT_TOP_K: usize = 5;
+const MAX_TOP_K: usize = 25;
+/// Documents whose clause embeddings are kept; the least recently searched is dropped beyond this
+const MAX_INDEXED_DOCUMENTS: usize = 4;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Clause {
+    pub index: usize,
+    pub page: u32,
+    pub heading: Option<String>, // "12.3 Indemnification", when the clause starts with one
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClauseSearchRequest {
+    pub path: String,
+    pub query: String,
+    pub top_k: Option<usize>,
+    pub embedding_model: Option<String>, // defaults to a loaded model that can embed
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClauseMatch {
+    pub clause: Clause,
+    pub score: f32, // cosine similarity to the query
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClauseSearchResult {
+    pub path: String,
+    pub query: String,
+    pub embedding_model: String,
+    pub clauses_indexed: usize,
+    pub pages_estimated: bool, // the file has no page breaks; see document_qa::split_pages
+    pub matches: Vec<ClauseMatch>,
+}
+
+fn clause_start() -> Regex {
+    Regex::new(r"^\s*(?:(?:Section|SECTION|Article|ARTICLE|Clause|CLAUSE|Schedule|SCHEDULE)\s+[0-9IVXLC]+|§\s*\d+|\d+(?:\.\d+)*[.)]?\s+\S|\([a-z0-9]{1,4}\)\s+\S|[A-Z][A-Z0-9 ,&\-]{3,}$)").unwrap()
+}
+
+/// A clause's heading: its first line, when that line starts a clause and is short
+fn heading_of(text: &str, starts: &Regex) -> Option<String> {
+    let first = text.lines().map(str::trim).find(|l| !l.is_empty())?;
+    (starts.is_match(first) && first.split_whitespace().count() <= MAX_HEADING_WORDS).then(|| first.to_string())
+}
+
+/// Pieces of a page that each begin a new clause: section headings anywhere, then numbered or
+/// all-caps lines, then blank lines once the clause so far is long enough to stand alone
+fn page_clauses(page: &str, starts: &Regex) -> Vec<String> {
+    let mut clauses = Vec::new();
+    for (start, end) in legal_section_boundaries(page) {
+        let mut current: Vec<&str> = Vec::new();
+        let mut words = 0;
+        for line in page[start..end].lines() {
+            let blank = line.trim().is_empty();
+            let starts_clause = !blank && starts.is_match(line);
+            if (starts_clause || (blank && words >= MIN_CLAUSE_WORDS)) && words > 0 {
+                clauses.push(current.join("\n"));
+                current.clear();
+                words = 0;
+            }
+            if !blank {
+                words += line.split_whitespace().count();
+                current.push(line.trim());
+            }
+        }
+        if words > 0 {
+            clauses.push(current.join("\n"));
+        }
+    }
+    clauses
+}
+
+/// The document's clauses in reading order, long ones split with overlap; and whether the
+/// pages are estimated
+pub fn segment_clauses(text: &str) -> (Vec<Clause>, bool) {
+    let (pages, pages_estimated) = split_pages(text);
+    let starts = clause_start();
+    let mut clauses = Vec::new();
+    for (i, page) in pages.iter().enumerate() {
+        for clause in page_clauses(page, &starts) {
+            let heading = heading_of(&clause, &starts);
+            let parts = if clause.split_whitespace().count() > MAX_CLAUSE_WORDS {
+                split_into_chunks(&clause, MAX_CLAUSE_WORDS, CLAUSE_OVERLAP_WORDS)
+            } else {
+                vec![clause]
+            };
+            for text in parts {
+                clauses.push(Clause {
+                    index: clauses.len(),
+                    page: i as u32 + 1,
+                    heading: heading.clone(),
+                    text,
+                });
+            }
+        }
+    }
+    (clauses, pages_estimated)
+}
+
+fn cosine(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm = a.iter().map(|x| x * x).sum::<f32>().sqrt() * b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        dot / norm
+    } else {
+        0.0
+    }
+}
+
+/// One document's clauses and their embeddings under one model
+pub struct ClauseIndex {
+    path: String,
+    modified: Option<SystemTime>,
+    model: String,
+    pages_estimated: bool,
+    clauses: Vec<Clause>,
+    embeddings: Vec<Vec<f32>>,
+}
+
+impl ClauseIndex {
+    /// Segment the text and embed every clause; `embed` returns one vector per input, in order
+    pub async fn build<F, Fut>(path: &str, text: &str, modified: Option<SystemTime>, model: &str, embed: F) -> Result<Self>
+    where
+        F: Fn(Vec<String>) -> Fut,
+        Fut: Future<Output = Result<Vec<Vec<f32>>>>,
+    {
+        let (clauses, pages_estimated) = segment_clauses(text);
+        if clauses.is_empty() {
+            return Err(anyhow!("No text could be found in {}", path));
+        }
+        // The heading goes with the text it heads, so "indemnification" finds clause 12 by name
+        let inputs = clauses
+            .iter()
+            .map(|c| match &c.heading {
+                Some(heading) if !c.text.starts_with(heading.as_str()) => format!("{}\n{}", heading, c.text),
+                _ => c.text.clone(),
+            })
+            .collect();
+        let embeddings = embed(inputs).await?;
+        if embeddings.len() != clauses.len() {
+            return Err(anyhow!("Expected {} clause embeddings, got {}", clauses.len(), embeddings.len()));
+        }
+        Ok(Self {
+            path: path.to_string(),
+            modified,
+            model: model.to_string(),
+            pages_estimated,
+            clauses,
+            embeddings,
+        })
+    }
+
+    /// The clauses closest to the query vector, best first
+    pub fn rank(&self, query: &[f32], top_k: usize) -> Vec<ClauseMatch> {
+        let mut scored: Vec<(f32, &Clause)> = self.embeddings.iter().map(|e| cosine(query, e)).zip(&self.clauses).collect();
+        scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+        scored
+            .into_iter()
+            .take(top_k)
+            .map(|(score, clause)| ClauseMatch {
+                clause: clause.clone(),
+                score,
+            })
+            .collect()
+    }
+}
+
+/// Clause indexes of recently searched documents, in memory only
+#[derive(Default)]
+pub struct ClauseSearch {
+    indexes: Mutex<Vec<Arc<ClauseIndex>>>, // most recently used last
+}
+
+impl ClauseSearch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn get(&self, path: &str, model: &str, modified: Option<SystemTime>) -> Option<Arc<ClauseIndex>> {
+        let mut indexes = self.indexes.lock().unwrap();
+        let position = indexes.iter().position(|i| i.path == path && i.model == model && i.modified == modified)?;
+        let index = indexes.remove(position);
+        indexes.push(index.clone());
+        Some(index)
+    }
+
+    fn insert(&self, index: ClauseIndex) -> Arc<ClauseIndex> {
+        let index = Arc::new(index);
+        let mut indexes = self.indexes.lock().unwrap();
+        indexes.retain(|i| !(i.path == index.path && i.model == index.model));
+        indexes.push(index.clone());
+        if indexes.len() > MAX_INDEXED_DOCUMENTS {
+            indexes.remove(0);
+        }
+        index
+    }
+}
+
+pub type ClauseSearchStorage = Arc<ClauseSearch>;
+
+async fn embed(llm: &LLMManager, model: &str, inputs: Vec<String>) -> Result<Vec<Vec<f32>>> {
+    let request = EmbeddingBatchRequest {
+        model: model.to_string(),
+        inputs,
+        batch_size: None,
+        max_parallel: None,
+        options: None,
+    };
+    Ok(llm.embeddings_batch(request).await?.embeddings)
+}
+
+/// The clauses of one document most relevant to a free-text query, with their pages
+#[tauri::command]
+pub async fn search_within_document(
+    request: ClauseSearchRequest,
+    search: tauri::State<'_, ClauseSearchStorage>,
+    analyzer: tauri::State<'_, AnalyzerStorage>,
+    llm: tauri::State<'_, Arc<LLMManager>>,
+) -> Result<ClauseSearchResult, String> {
+    if request.query.trim().is_empty() {
+        return Err("Enter what to look for in the document".to_string());
+    }
+    let model = match &request.embedding_model {
+        Some(model) => model.clone(),
+        None => llm
+            .find_loaded_model("embed")
+            .map(|m| m.model_id)
+            .ok_or_else(|| "No embedding model is loaded to search the document".to_string())?,
+    };
+
+    let modified = std::fs::metadata(&request.path).and_then(|m| m.modified()).ok();
+    let index = match search.get(&request.path, &model, modified) {
+        Some(index) => index,
+        None => {
+            let text = analyzer.extract_text(Path::new(&request.path)).await.map_err(|e| e.to_string())?;
+            let index = ClauseIndex::build(&request.path, &text, modified, &model, |inputs| embed(&llm, &model, inputs))
+                .await
+                .map_err(|e| e.to_string())?;
+            search.insert(index)
+        }
+    };
+
+    let query = embed(&llm, &model, vec![request.query.clone()])
+        .await
+        .map_err(|e| e.to_string())?
+        .pop()
+        .ok_or_else(|| "The embedding model returned no vector for the query".to_string())?;
+    let matches = index.rank(&query, request.top_k.unwrap_or(DEFAULT_TOP_K).clamp(1, MAX_TOP_K));
+    Ok(ClauseSearchResult {
+        path: request.path,
+        query: request.query,
+        embedding_model: model,
+        clauses_indexed: index.clauses.len(),
+        pages_estimated: index.pages_estimated,
+        matches,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AGREEMENT: &str = "SERVICES AGREEMENT\n\
+        1. Definitions\nCapitalised terms have the meanings given in this clause.\n\
+        2. Fees\nThe customer pays the fees within thirty days of each invoice.\n\u{c}\
+        3. Indemnification\nThe supplier shall indemnify the customer against third party claims.\n\
+        (a) This indemnity survives termination.\n\u{c}";
+
+    #[test]
+    fn test_segment_clauses_by_page_and_heading() {
+        let (clauses, estimated) = segment_clauses(AGREEMENT);
+        assert!(!estimated);
+        let headings: Vec<Option<&str>> = clauses.iter().map(|c| c.heading.as_deref()).collect();
+        assert_eq!(
+            headings,
+            vec![
+                Some("SERVICES AGREEMENT"),
+                Some("1. Definitions"),
+                Some("2. Fees"),
+                Some("3. Indemnification"),
+                Some("(a) This indemnity survives termination.")
+            ]
+        );
+        assert_eq!(clauses[3].page, 2);
+        assert!(clauses[3].text.contains("indemnify the customer"));
+
+        let long = format!("RECITALS\n{}", vec!["term"; MAX_CLAUSE_WORDS + 100].join(" "));
+        let (clauses, _) = segment_clauses(&long);
+        assert_eq!(clauses.len(), 2);
+        assert!(clauses.iter().all(|c| c.heading.as_deref() == Some("RECITALS")));
+    }
+
+    #[tokio::test]
+    async fn test_rank_returns_closest_clause_with_page() {
+        // Bag-of-words stand-in for an embedding model
+        let vocabulary = ["indemnif", "fee", "definition"];
+        let vector = |text: &str| {
+            let text = text.to_lowercase();
+            vocabulary.iter().map(|w| text.matches(w).count() as f32).collect::<Vec<f32>>()
+        };
+        let index = ClauseIndex::build("msa.pdf", AGREEMENT, None, "embed-test", |inputs| {
+            let vectors = inputs.iter().map(|i| vector(i)).collect();
+            async move { Ok(vectors) }
+        })
+        .await
+        .unwrap();
+
+        let matches = index.rank(&vector("what does it say about indemnification"), 2);
+        assert_eq!(matches[0].clause.heading.as_deref(), Some("3. Indemnification"));
+        assert_eq!(matches[0].clause.page, 2);
+        assert!(matches[0].score > matches[1].score);
+
+        let search = ClauseSearch::new();
+        search.insert(index);
+        assert!(search.get("msa.pdf", "embed-test", None).is_some());
+        assert!(search.get("msa.pdf", "other-model", None).is_none());
+    }
+}
@@ -0,0 +1,578 @@
+use anyhow::{anyhow, Context, Result};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::case_analytics;
+use crate::corpus_topics;
+use crate::document_analyzer::DocumentAnalyzer;
+use crate::jurisdiction::JurisdictionScope;
+use crate::nemotron_rag::{self, NemotronConfig, NemotronRAG, QueryContext};
+use crate::ocr_ingest;
+use crate::ocr_processor::{OcrConfiguration, OcrProcessor, RecognitionMode};
+use crate::pii_detector::{PIIDetector, RiskLevel};
+use crate::regulatory_monitor;
+use crate::retrieval_scope::RetrievalScope;
+use crate::SCOPED_OVERFETCH;
+
+/// Headless command line for BEAR AI
+/// Runs the analysis engine in batch jobs on servers and CI pipelines without the
+/// desktop shell. Results are written as JSON lines, one per input file.
+pub const USAGE: &str = "\
+Usage: bear-ai-legal-assistant <command> [options] <paths...>
+
+Commands:
+  analyze <paths...>       Full document analysis (entities, clauses, risks)
+  ocr <paths...>           OCR images and scanned PDFs
+  detect-pii <paths...>    Detect personal data
+  index <paths...>         Chunk, embed and store documents in the RAG index
+  query <text...>          Retrieve passages from the RAG index
+  serve-grpc               Serve the engine API over gRPC with mTLS (`grpc` feature)
+
+Options:
+  --data-dir <dir>         Working directory (default: the desktop app data directory)
+  --output <file>          Write results to a file instead of stdout
+  --pretty                 Pretty-print JSON
+  --lang <codes>           OCR languages, e.g. eng+nld (ocr, index)
+  --handwriting            Use the handwriting recognition path (ocr)
+  --fail-on <level>        Exit with status 3 when PII at or above low|medium|high|critical is found (detect-pii)
+  --jurisdiction <name>    Jurisdiction recorded with indexed documents (index), or to rank passages by (query)
+  --jurisdiction-scope <s> prefer|binding|within|exact; all but prefer drop passages outside it (query)
+  --case-law               Index as case law and record judges and rulings for analytics (index)
+  --rag-config <file>      NemotronConfig as JSON (index, query)
+  --local-embeddings       Embed with the local model server instead of NeMo cloud (index, query)
+  --local-store            Keep the index in <data-dir>/vector_store.sqlite3 instead of Qdrant (index, query)
+  --rerank-model <name>    Rerank passages with this cross-encoder on the local model server (query)
+  --top-k <n>              Maximum passages returned (query)
+  --court <name>           Only passages from filings in this court (query)
+  --docket <number>        Only passages from filings with this docket number (query)
+  --latency-budget <ms>    Skip optional retrieval stages to answer within this many milliseconds (query)
+  --as-user <name>         Return only passages this user may read under the workspace access lists and
+                           information barriers; without it only passages open to everyone (query, serve-grpc)
+  --listen <addr>          Address to listen on, default 0.0.0.0:50051 (serve-grpc)
+  --tls-cert <file>        Server certificate chain, PEM (serve-grpc)
+  --tls-key <file>         Server private key, PEM (serve-grpc)
+  --client-ca <file>       CA that signs client certificates, PEM (serve-grpc)
+  --no-rag                 Serve without the RAG index (serve-grpc)
+  --document-root <dirs>   Directories clients may name documents in by path, separated like PATH;
+                           without it clients must send document content (serve-grpc)
+";
+
+pub const EXIT_OK: i32 = 0;
+pub const EXIT_FAILURES: i32 = 1; // at least one input could not be processed
+pub const EXIT_USAGE: i32 = 2;
+pub const EXIT_PII_FOUND: i32 = 3;
+
+// Options that take a value; everything else starting with "--" is a flag
+const VALUE_OPTIONS: &[&str] = &[
+    "data-dir",
+    "output",
+    "lang",
+    "fail-on",
+    "jurisdiction",
+    "jurisdiction-scope",
+    "rag-config",
+    "top-k",
+    "court",
+    "docket",
+    "latency-budget",
+    "as-user",
+    "rerank-model",
+    "listen",
+    "tls-cert",
+    "tls-key",
+    "client-ca",
+    "document-root",
+];
+
+#[derive(Debug, PartialEq)]
+struct CliArgs {
+    command: String,
+    positional: Vec<String>,
+    options: HashMap<String, String>,
+    flags: HashSet<String>,
+}
+
+fn parse_args(args: &[String]) -> Result<CliArgs> {
+    let mut iter = args.iter();
+    let command = iter.next().ok_or_else(|| anyhow!("No command given"))?.clone();
+
+    let mut positional = Vec::new();
+    let mut options = HashMap::new();
+    let mut flags = HashSet::new();
+
+    while let Some(arg) = iter.next() {
+        let Some(name) = arg.strip_prefix("--") else {
+            positional.push(arg.clone());
+            continue;
+        };
+
+        // Both "--name value" and "--name=value" are accepted
+        if let Some((name, value)) = name.split_once('=') {
+            options.insert(name.to_string(), value.to_string());
+        } else if VALUE_OPTIONS.contains(&name) {
+            let value = iter.next().ok_or_else(|| anyhow!("--{} needs a value", name))?;
+            options.insert(name.to_string(), value.clone());
+        } else {
+            flags.insert(name.to_string());
+        }
+    }
+
+    Ok(CliArgs {
+        command,
+        positional,
+        options,
+        flags,
+    })
+}
+
+fn risk_rank(level: &RiskLevel) -> u8 {
+    match level {
+        RiskLevel::Low => 0,
+        RiskLevel::Medium => 1,
+        RiskLevel::High => 2,
+        RiskLevel::Critical => 3,
+    }
+}
+
+fn parse_risk_level(value: &str) -> Result<RiskLevel> {
+    match value.to_lowercase().as_str() {
+        "low" => Ok(RiskLevel::Low),
+        "medium" => Ok(RiskLevel::Medium),
+        "high" => Ok(RiskLevel::High),
+        "critical" => Ok(RiskLevel::Critical),
+        other => Err(anyhow!("Unknown risk level: {}", other)),
+    }
+}
+
+/// Expand directories into the files below them, in a stable order
+fn collect_inputs(paths: &[String]) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for path in paths {
+        let path = Path::new(path);
+        if path.is_dir() {
+            let mut found: Vec<PathBuf> = walkdir::WalkDir::new(path)
+                .into_iter()
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.file_type().is_file())
+                .map(|entry| entry.into_path())
+                .collect();
+            found.sort();
+            files.extend(found);
+        } else if path.exists() {
+            files.push(path.to_path_buf());
+        } else {
+            return Err(anyhow!("No such file or directory: {}", path.display()));
+        }
+    }
+    Ok(files)
+}
+
+#[derive(Serialize)]
+struct FileResult<T: Serialize> {
+    file: String,
+    ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<T>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+struct Output {
+    writer: Box<dyn Write>,
+    pretty: bool,
+    failures: usize,
+}
+
+impl Output {
+    fn open(args: &CliArgs) -> Result<Self> {
+        let writer: Box<dyn Write> = match args.options.get("output") {
+            Some(path) => Box::new(fs::File::create(path).with_context(|| format!("Cannot create {}", path))?),
+            None => Box::new(std::io::stdout()),
+        };
+        Ok(Self {
+            writer,
+            pretty: args.flags.contains("pretty"),
+            failures: 0,
+        })
+    }
+
+    fn write<T: Serialize>(&mut self, value: &T) -> Result<()> {
+        let line = if self.pretty {
+            serde_json::to_string_pretty(value)?
+        } else {
+            serde_json::to_string(value)?
+        };
+        writeln!(self.writer, "{}", line)?;
+        Ok(())
+    }
+
+    fn file_result<T: Serialize>(&mut self, file: &Path, result: Result<T>) -> Result<()> {
+        let entry = match result {
+            Ok(result) => FileResult {
+                file: file.display().to_string(),
+                ok: true,
+                result: Some(result),
+                error: None,
+            },
+            Err(e) => {
+                log::error!("{}: {}", file.display(), e);
+                self.failures += 1;
+                FileResult {
+                    file: file.display().to_string(),
+                    ok: false,
+                    result: None,
+                    error: Some(e.to_string()),
+                }
+            }
+        };
+        self.write(&entry)
+    }
+
+    fn exit_code(&self) -> i32 {
+        if self.failures > 0 {
+            EXIT_FAILURES
+        } else {
+            EXIT_OK
+        }
+    }
+}
+
+fn data_dir(args: &CliArgs) -> Result<PathBuf> {
+    let dir = match args.options.get("data-dir") {
+        Some(dir) => PathBuf::from(dir),
+        None => dirs::data_dir()
+            .ok_or_else(|| anyhow!("Could not determine app data directory; pass --data-dir"))?
+            .join("bear-ai"),
+    };
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+/// Access lists and barriers of the workspace in the data directory, as `--as-user` sees them
+fn retrieval_scope(args: &CliArgs) -> Result<RetrievalScope> {
+    let user = args.options.get("as-user").map(String::as_str).unwrap_or_default();
+    RetrievalScope::open(&data_dir(args)?, user)
+}
+
+fn rag_config(args: &CliArgs) -> Result<NemotronConfig> {
+    let mut config = match args.options.get("rag-config") {
+        Some(path) => serde_json::from_str(&fs::read_to_string(path).with_context(|| format!("Cannot read {}", path))?)?,
+        None => crate::create_default_nemotron_config(),
+    };
+    if let Ok(key) = std::env::var("NEMOTRON_API_KEY") {
+        config.nemotron_api_key = key;
+    }
+    if args.flags.contains("local-embeddings") {
+        config.embedding_backend = nemotron_rag::EmbeddingBackend::Local;
+    }
+    if let Some(model) = args.options.get("rerank-model") {
+        config.rerank_backend = nemotron_rag::RerankBackend::Local;
+        config.reranking_model = model.clone();
+    }
+    if config.citation_graph_path.is_none() {
+        config.citation_graph_path = Some(data_dir(args)?.join("citation_graph.jsonl").to_string_lossy().into_owned());
+    }
+    if args.flags.contains("local-store") {
+        config.vector_db_type = nemotron_rag::VectorDbType::SqliteLocal;
+        config.local_store_path = Some(data_dir(args)?.join("vector_store.sqlite3").to_string_lossy().into_owned());
+    }
+    Ok(config)
+}
+
+async fn run_analyze(args: &CliArgs, output: &mut Output) -> Result<()> {
+    let analyzer = DocumentAnalyzer::new(&data_dir(args)?, None)?;
+    for file in collect_inputs(&args.positional)? {
+        let result = analyzer.analyze_document(&file).await;
+        output.file_result(&file, result)?;
+    }
+    Ok(())
+}
+
+fn ocr_config(args: &CliArgs) -> OcrConfiguration {
+    let mut config = OcrConfiguration::default();
+    if let Some(lang) = args.options.get("lang") {
+        config.languages = lang.split('+').map(|l| l.to_string()).collect();
+    }
+    config
+}
+
+async fn run_ocr(args: &CliArgs, output: &mut Output) -> Result<()> {
+    let mut config = ocr_config(args);
+    if args.flags.contains("handwriting") {
+        config.recognition_mode = RecognitionMode::Handwriting;
+    }
+
+    let processor = OcrProcessor::new(config);
+    if !processor.is_available() {
+        return Err(anyhow!("Tesseract OCR not available"));
+    }
+
+    for file in collect_inputs(&args.positional)? {
+        let path = file.to_string_lossy();
+        let is_pdf = file
+            .extension()
+            .map(|e| e.eq_ignore_ascii_case("pdf"))
+            .unwrap_or(false);
+        let result = if is_pdf {
+            processor.extract_text_from_pdf(&path).await
+        } else {
+            processor.extract_text_from_image(&path).await.map(|r| vec![r])
+        };
+        output.file_result(&file, result)?;
+    }
+    Ok(())
+}
+
+async fn run_detect_pii(args: &CliArgs, output: &mut Output) -> Result<bool> {
+    let fail_on = args.options.get("fail-on").map(|l| parse_risk_level(l)).transpose()?;
+    let analyzer = DocumentAnalyzer::new(&data_dir(args)?, None)?;
+    let mut detector = PIIDetector::new(None);
+    let mut threshold_reached = false;
+
+    for file in collect_inputs(&args.positional)? {
+        let result = match analyzer.extract_text(&file).await {
+            Ok(text) => {
+                let detection = detector.detect_pii(&text);
+                if let Some(level) = &fail_on {
+                    threshold_reached |= detection.has_pii && risk_rank(&detection.risk_level) >= risk_rank(level);
+                }
+                Ok(detection)
+            }
+            Err(e) => Err(e),
+        };
+        output.file_result(&file, result)?;
+    }
+    Ok(threshold_reached)
+}
+
+async fn run_index(args: &CliArgs, output: &mut Output) -> Result<()> {
+    let analyzer = DocumentAnalyzer::new(&data_dir(args)?, None)?;
+    let mut rag = NemotronRAG::new(rag_config(args)?).await?;
+    rag.initialize().await?;
+    case_analytics::initialize_case_corpus(&data_dir(args)?)?;
+    corpus_topics::initialize_document_vectors(&data_dir(args)?);
+    regulatory_monitor::initialize_reference_log(&data_dir(args)?);
+    let document_type = if args.flags.contains("case-law") {
+        nemotron_rag::DocumentType::CaseLaw
+    } else {
+        nemotron_rag::DocumentType::Contract
+    };
+
+    let jurisdiction = args
+        .options
+        .get("jurisdiction")
+        .cloned()
+        .unwrap_or_else(|| "General".to_string());
+    // Image-only pages of scanned PDFs are read by OCR when Tesseract is installed
+    let ocr = OcrProcessor::new(ocr_config(args));
+
+    for file in collect_inputs(&args.positional)? {
+        let result = async {
+            let text = ocr_ingest::extract_ingest_text(&analyzer, Some(&ocr), &file).await?;
+            let metadata = text.metadata();
+            let document = nemotron_rag::LegalDocument {
+                id: uuid::Uuid::new_v4().to_string(),
+                title: file
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+                content: text.text,
+                jurisdiction: jurisdiction.clone(),
+                document_type: document_type.clone(),
+                last_updated: chrono::Utc::now(),
+                citations: Vec::new(),
+                metadata: nemotron_rag::DocumentMetadata {
+                    court: None,
+                    judge: None,
+                    parties: Vec::new(),
+                    topics: Vec::new(),
+                    precedential_value: nemotron_rag::PrecedentialValue::NotPrecedential,
+                    confidence: 1.0,
+                },
+            };
+            let document_id = document.id.clone();
+            let chunks = rag.process_document_into("legal_chunks", document, metadata).await?;
+            Ok(serde_json::json!({ "document_id": document_id, "chunks": chunks.len(), "ocr_pages": text.ocr_pages.len() }))
+        }
+        .await;
+        output.file_result(&file, result)?;
+    }
+    Ok(())
+}
+
+async fn run_query(args: &CliArgs, output: &mut Output) -> Result<()> {
+    let query = args.positional.join(" ");
+    if query.trim().is_empty() {
+        return Err(anyhow!("query needs a search text"));
+    }
+    let max_results = args
+        .options
+        .get("top-k")
+        .map(|k| k.parse::<usize>())
+        .transpose()
+        .context("--top-k must be a number")?;
+    let latency_budget_ms = args
+        .options
+        .get("latency-budget")
+        .map(|ms| ms.parse::<u64>())
+        .transpose()
+        .context("--latency-budget must be a number of milliseconds")?;
+    let jurisdiction_scope = args
+        .options
+        .get("jurisdiction-scope")
+        .map(|s| JurisdictionScope::parse(s))
+        .transpose()?
+        .unwrap_or_default();
+    let scope = retrieval_scope(args)?;
+
+    let mut rag = NemotronRAG::new(rag_config(args)?).await?;
+    rag.initialize().await?;
+    let max_results = max_results.unwrap_or(rag.config().max_results);
+
+    let result = rag
+        .retrieve(QueryContext {
+            query,
+            jurisdiction: args.options.get("jurisdiction").cloned(),
+            document_types: None,
+            time_range: None,
+            precedential_only: None,
+            require_citations: None,
+            max_results: Some(max_results * SCOPED_OVERFETCH),
+            confidence_threshold: None,
+            court: args.options.get("court").cloned(),
+            docket_number: args.options.get("docket").cloned(),
+            jurisdiction_scope,
+            latency_budget_ms,
+        })
+        .await?;
+    output.write(&scope.filter_top(result, "retrieval", max_results))
+}
+
+#[cfg(feature = "grpc")]
+async fn run_serve_grpc(args: &CliArgs) -> Result<()> {
+    use crate::grpc_server::{serve, BearAiEngineService, GrpcServerConfig};
+
+    let required = |name: &str| {
+        args.options
+            .get(name)
+            .map(PathBuf::from)
+            .ok_or_else(|| anyhow!("--{} is required; the gRPC server only runs with mutual TLS", name))
+    };
+    let config = GrpcServerConfig {
+        listen_addr: args
+            .options
+            .get("listen")
+            .map(|a| a.as_str())
+            .unwrap_or("0.0.0.0:50051")
+            .parse()
+            .context("--listen must be an address such as 0.0.0.0:50051")?,
+        cert_path: required("tls-cert")?,
+        key_path: required("tls-key")?,
+        client_ca_path: required("client-ca")?,
+    };
+
+    let analyzer = std::sync::Arc::new(DocumentAnalyzer::new(&data_dir(args)?, None)?);
+    let rag = if args.flags.contains("no-rag") {
+        None
+    } else {
+        let mut rag = NemotronRAG::new(rag_config(args)?).await?;
+        rag.initialize().await?;
+        Some(std::sync::Arc::new(rag))
+    };
+
+    let document_roots: Vec<PathBuf> = args
+        .options
+        .get("document-root")
+        .map(|roots| std::env::split_paths(roots).collect())
+        .unwrap_or_default();
+    let service = BearAiEngineService::new(analyzer, rag, retrieval_scope(args)?, &document_roots)?;
+    serve(config, service).await
+}
+
+#[cfg(not(feature = "grpc"))]
+async fn run_serve_grpc(_args: &CliArgs) -> Result<()> {
+    Err(anyhow!("This build does not include the gRPC server; rebuild with --features grpc"))
+}
+
+/// Run a CLI invocation (arguments without the program name) and return the exit status
+pub async fn run(args: Vec<String>) -> i32 {
+    let args = match parse_args(&args) {
+        Ok(args) if args.command != "help" && !args.flags.contains("help") => args,
+        Ok(_) => {
+            print!("{}", USAGE);
+            return EXIT_OK;
+        }
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            return EXIT_USAGE;
+        }
+    };
+
+    let mut output = match Output::open(&args) {
+        Ok(output) => output,
+        Err(e) => {
+            eprintln!("{}", e);
+            return EXIT_USAGE;
+        }
+    };
+
+    let result = match args.command.as_str() {
+        "analyze" => run_analyze(&args, &mut output).await.map(|_| output.exit_code()),
+        "ocr" => run_ocr(&args, &mut output).await.map(|_| output.exit_code()),
+        "detect-pii" => run_detect_pii(&args, &mut output).await.map(|found| {
+            if found {
+                EXIT_PII_FOUND
+            } else {
+                output.exit_code()
+            }
+        }),
+        "index" => run_index(&args, &mut output).await.map(|_| output.exit_code()),
+        "query" => run_query(&args, &mut output).await.map(|_| EXIT_OK),
+        "serve-grpc" => run_serve_grpc(&args).await.map(|_| EXIT_OK),
+        other => {
+            eprintln!("Unknown command: {}\n\n{}", other, USAGE);
+            return EXIT_USAGE;
+        }
+    };
+
+    match result {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("{}: {}", args.command, e);
+            EXIT_FAILURES
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let parsed = parse_args(&args(&["detect-pii", "contracts/", "--fail-on", "high", "--pretty", "--output=out.jsonl", "memo.txt"])).unwrap();
+        assert_eq!(parsed.command, "detect-pii");
+        assert_eq!(parsed.positional, vec!["contracts/", "memo.txt"]);
+        assert_eq!(parsed.options.get("fail-on").map(|s| s.as_str()), Some("high"));
+        assert_eq!(parsed.options.get("output").map(|s| s.as_str()), Some("out.jsonl"));
+        assert!(parsed.flags.contains("pretty"));
+
+        assert!(parse_args(&args(&["ocr", "--lang"])).is_err());
+        assert!(parse_args(&[]).is_err());
+    }
+
+    #[test]
+    fn test_risk_threshold_ordering() {
+        let high = parse_risk_level("HIGH").unwrap();
+        assert!(risk_rank(&RiskLevel::Critical) >= risk_rank(&high));
+        assert!(risk_rank(&RiskLevel::Medium) < risk_rank(&high));
+        assert!(parse_risk_level("severe").is_err());
+    }
+}
@@ -0,0 +1,344 @@
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use zip::write::SimpleFileOptions;
+use zip::{AesMode, CompressionMethod, ZipWriter};
+
+use crate::ai_disclosure::{Disclosure, DisclosureStorage};
+use crate::charts::{self, ChartTheme, ChartThemeStorage};
+use crate::document_analyzer::{self, DocumentAnalysis, RiskLevel};
+use crate::enterprise_management::BarrierStorage;
+use crate::local_api::{authenticated_user, AnalyzerStorage, SessionStorage};
+use crate::matters::MatterStorage;
+use crate::pii_detector::PIIDetector;
+use crate::review_queue::{self, ReviewQueueStorage};
+use crate::security::{ActionOutcome, SecurityAction, SecurityManager};
+
+/// Client Portal Bundles for BEAR AI
+/// Packages documents for client delivery in a single AES-256 password-protected ZIP, which
+/// opens in 7-Zip, WinZip and most archive tools. The analysis report is PII-redacted before
+/// it is written; the bundle contents are recorded in the audit log.
+const MIN_PASSWORD_LENGTH: usize = 12;
+const RISK_CHART_ENTRY: &str = "charts/risk_distribution.svg";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClientBundleRequest {
+    pub document_paths: Vec<String>,
+    pub output_path: String,
+    pub password: String,
+    pub matter_name: Option<String>,
+    pub recipient: Option<String>,
+    pub memo: Option<String>, // attorney's note placed at the top of the summary memo
+    #[serde(default)]
+    pub jurisdiction: Option<String>, // picks the AI-use disclosure template
+    #[serde(default)]
+    pub include_disclosure: Option<bool>, // None follows the disclosure settings
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleEntry {
+    pub path: String,
+    pub size: u64,
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClientBundleManifest {
+    pub bundle_path: String,
+    pub bundle_sha256: String,
+    pub matter_name: Option<String>,
+    pub recipient: Option<String>,
+    pub entries: Vec<BundleEntry>,
+    pub redactions: usize,
+    pub created_at: String,
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(bytes))
+}
+
+/// A name not yet used in the bundle: "lease.pdf", then "lease (2).pdf", ...
+fn unique_entry_name(used: &[String], name: &str) -> String {
+    if !used.iter().any(|u| u == name) {
+        return name.to_string();
+    }
+    let path = Path::new(name);
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or(name);
+    let extension = path.extension().and_then(|e| e.to_str());
+    (2..)
+        .map(|n| match extension {
+            Some(ext) => format!("{} ({}).{}", stem, n, ext),
+            None => format!("{} ({})", stem, n),
+        })
+        .find(|candidate| !used.iter().any(|u| u == candidate))
+        .expect("unbounded range yields a free name")
+}
+
+/// Write every entry AES-256 encrypted with the same password
+fn write_encrypted_zip(path: &Path, entries: &[(String, Vec<u8>)], password: &str) -> Result<()> {
+    let mut zip = ZipWriter::new(fs::File::create(path)?);
+    let options = SimpleFileOptions::default()
+        .compression_method(CompressionMethod::Deflated)
+        .with_aes_encryption(AesMode::Aes256, password);
+    for (name, content) in entries {
+        zip.start_file(name.as_str(), options)?;
+        zip.write_all(content)?;
+    }
+    zip.finish()?;
+    Ok(())
+}
+
+/// Mask PII in generated text; returns the text and the number of redactions
+fn redact(detector: &mut PIIDetector, text: &str) -> (String, usize) {
+    let detection = detector.detect_pii(text);
+    (detector.mask_text(text, &detection.matches), detection.matches.len())
+}
+
+fn render_analysis_report(documents: &[(String, DocumentAnalysis)], risk_chart: bool) -> String {
+    let mut report = String::from("# Document Analysis Report\n");
+    if risk_chart {
+        report.push_str(&format!("\n![Risk distribution]({})\n", RISK_CHART_ENTRY));
+    }
+    for (name, analysis) in documents {
+        report.push_str(&format!("\n## {}\n\n", name));
+        if let Some(summary) = &analysis.summary {
+            report.push_str(&format!("{}\n\n", summary));
+        }
+        if !analysis.risks.is_empty() {
+            report.push_str("### Risks\n\n");
+            for risk in &analysis.risks {
+                report.push_str(&format!("- **{:?}**: {}\n", risk.severity, risk.description));
+                for mitigation in &risk.mitigation_strategies {
+                    report.push_str(&format!("  - {}\n", mitigation));
+                }
+            }
+            report.push('\n');
+        }
+        if !analysis.key_terms.is_empty() {
+            report.push_str("### Key terms\n\n");
+            for term in &analysis.key_terms {
+                report.push_str(&format!("- {}\n", term.term));
+            }
+            report.push('\n');
+        }
+    }
+    report
+}
+
+fn render_summary_memo(request: &ClientBundleRequest, documents: &[(String, DocumentAnalysis)]) -> String {
+    let mut memo = String::from("# Summary Memo\n\n");
+    if let Some(recipient) = &request.recipient {
+        memo.push_str(&format!("**To:** {}\n", recipient));
+    }
+    if let Some(matter) = &request.matter_name {
+        memo.push_str(&format!("**Matter:** {}\n", matter));
+    }
+    memo.push_str(&format!("**Date:** {}\n\n", chrono::Utc::now().format("%B %-d, %Y")));
+    if let Some(note) = &request.memo {
+        memo.push_str(&format!("{}\n\n", note.trim()));
+    }
+
+    memo.push_str("## Documents enclosed\n\n");
+    for (name, analysis) in documents {
+        let serious = analysis
+            .risks
+            .iter()
+            .filter(|r| matches!(r.severity, RiskLevel::High | RiskLevel::Critical))
+            .count();
+        let note = match serious {
+            0 => "no high risks identified".to_string(),
+            1 => "1 high risk identified".to_string(),
+            n => format!("{} high risks identified", n),
+        };
+        memo.push_str(&format!("- {} ({})\n", name, note));
+    }
+    memo.push_str("\nPersonal data has been redacted from the enclosed analysis report. Please contact us with any questions.\n");
+    memo
+}
+
+pub async fn export_bundle(
+    request: &ClientBundleRequest,
+    analyzer: &AnalyzerStorage,
+    theme: &ChartTheme,
+    disclosure: Option<&Disclosure>,
+) -> Result<ClientBundleManifest> {
+    if request.document_paths.is_empty() {
+        return Err(anyhow!("Select at least one document"));
+    }
+    if request.password.chars().count() < MIN_PASSWORD_LENGTH {
+        return Err(anyhow!("Bundle password must be at least {} characters", MIN_PASSWORD_LENGTH));
+    }
+
+    let mut entries: Vec<(String, Vec<u8>)> = Vec::new();
+    let mut used = Vec::new();
+    let mut analyzed = Vec::new();
+    for document_path in &request.document_paths {
+        let path = Path::new(document_path);
+        let name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .ok_or_else(|| anyhow!("Invalid document path: {}", document_path))?;
+        let content = fs::read(path).map_err(|e| anyhow!("Cannot read {}: {}", document_path, e))?;
+        let name = unique_entry_name(&used, name);
+        used.push(name.clone());
+
+        analyzed.push((name.clone(), analyzer.recorded_analysis(path).await?));
+        entries.push((format!("documents/{}", name), content));
+    }
+
+    let analyses: Vec<&DocumentAnalysis> = analyzed.iter().map(|(_, analysis)| analysis).collect();
+    let chart = match charts::render_svg(&charts::risk_distribution(&analyses), theme) {
+        Ok(svg) => {
+            entries.push((RISK_CHART_ENTRY.to_string(), svg.into_bytes()));
+            true
+        }
+        Err(e) => {
+            log::warn!("Risk chart not included in bundle: {}", e);
+            false
+        }
+    };
+
+    let mut detector = PIIDetector::new(None);
+    let (mut report, redactions) = redact(&mut detector, &render_analysis_report(&analyzed, chart));
+    let mut summary = render_summary_memo(request, &analyzed);
+    if let Some(disclosure) = disclosure {
+        report.push_str(&format!("\n{}", disclosure.to_markdown()));
+        summary.push_str(&format!("\n{}", disclosure.to_markdown()));
+    }
+    entries.push(("analysis_report.md".to_string(), report.into_bytes()));
+    entries.push(("summary_memo.md".to_string(), summary.into_bytes()));
+
+    let mut bundle_entries: Vec<BundleEntry> = entries
+        .iter()
+        .map(|(name, content)| BundleEntry {
+            path: name.clone(),
+            size: content.len() as u64,
+            sha256: sha256_hex(content),
+        })
+        .collect();
+    // The manifest inside the bundle lets the client verify what they received
+    let manifest = serde_json::to_vec_pretty(&bundle_entries)?;
+    bundle_entries.push(BundleEntry {
+        path: "manifest.json".to_string(),
+        size: manifest.len() as u64,
+        sha256: sha256_hex(&manifest),
+    });
+    entries.push(("manifest.json".to_string(), manifest));
+
+    let output = Path::new(&request.output_path);
+    write_encrypted_zip(output, &entries, &request.password)?;
+
+    Ok(ClientBundleManifest {
+        bundle_path: request.output_path.clone(),
+        bundle_sha256: sha256_hex(&fs::read(output)?),
+        matter_name: request.matter_name.clone(),
+        recipient: request.recipient.clone(),
+        entries: bundle_entries,
+        redactions,
+        created_at: chrono::Utc::now().to_rfc3339(),
+    })
+}
+
+fn audit_details(manifest: &ClientBundleManifest) -> HashMap<String, String> {
+    let mut details = HashMap::new();
+    details.insert("bundle_sha256".to_string(), manifest.bundle_sha256.clone());
+    details.insert("recipient".to_string(), manifest.recipient.clone().unwrap_or_default());
+    details.insert("matter".to_string(), manifest.matter_name.clone().unwrap_or_default());
+    details.insert("redactions".to_string(), manifest.redactions.to_string());
+    details.insert(
+        "entries".to_string(),
+        manifest
+            .entries
+            .iter()
+            .map(|e| format!("{} ({} bytes, sha256 {})", e.path, e.size, e.sha256))
+            .collect::<Vec<_>>()
+            .join("; "),
+    );
+    details
+}
+
+#[tauri::command]
+pub async fn export_client_bundle(
+    session_id: String,
+    request: ClientBundleRequest,
+    analyzer: tauri::State<'_, AnalyzerStorage>,
+    security: tauri::State<'_, Arc<Mutex<SecurityManager>>>,
+    chart_theme: tauri::State<'_, ChartThemeStorage>,
+    sessions: tauri::State<'_, SessionStorage>,
+    barriers: tauri::State<'_, BarrierStorage>,
+    matters: tauri::State<'_, MatterStorage>,
+    review: tauri::State<'_, ReviewQueueStorage>,
+    disclosures: tauri::State<'_, DisclosureStorage>,
+) -> Result<ClientBundleManifest, String> {
+    let user = authenticated_user(&session_id, &sessions)?;
+    barriers.check_export(&security, &user, &matters.export_items(&request.document_paths))?;
+
+    // The bundle carries the AI analysis of every document, so each one needs an approved analysis
+    for path in &request.document_paths {
+        if let Err(reason) = document_analyzer::check_analysis_released(Path::new(path), &analyzer, &review) {
+            review_queue::audit_withheld(&security, &request.output_path, &reason);
+            return Err(reason);
+        }
+    }
+
+    let disclosure = disclosures.resolve(request.jurisdiction.as_deref(), None, request.include_disclosure);
+    let result = export_bundle(&request, &analyzer, &chart_theme.get(), disclosure.as_ref()).await;
+
+    let (outcome, details) = match &result {
+        Ok(manifest) => (ActionOutcome::Success, audit_details(manifest)),
+        Err(e) => {
+            let mut details = HashMap::new();
+            details.insert("error".to_string(), e.to_string());
+            details.insert("documents".to_string(), request.document_paths.join("; "));
+            (ActionOutcome::Failure, details)
+        }
+    };
+    if let Err(e) = security
+        .lock()
+        .unwrap()
+        .write_audit_entry(SecurityAction::DataExport, &request.output_path, outcome, Some(details))
+    {
+        log::error!("Failed to audit client bundle export: {}", e);
+    }
+
+    result.map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn test_unique_entry_name() {
+        let used = vec!["lease.pdf".to_string(), "lease (2).pdf".to_string(), "notes".to_string()];
+        assert_eq!(unique_entry_name(&used, "nda.pdf"), "nda.pdf");
+        assert_eq!(unique_entry_name(&used, "lease.pdf"), "lease (3).pdf");
+        assert_eq!(unique_entry_name(&used, "notes"), "notes (2)");
+    }
+
+    #[test]
+    fn test_encrypted_zip_requires_password() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bundle.zip");
+        let entries = vec![("documents/a.txt".to_string(), b"privileged".to_vec())];
+        write_encrypted_zip(&path, &entries, "correct horse battery").unwrap();
+
+        let mut archive = zip::ZipArchive::new(fs::File::open(&path).unwrap()).unwrap();
+        assert!(archive.by_index(0).is_err());
+        assert!(archive.by_index_decrypt(0, b"wrong password!!").is_err());
+
+        let mut content = String::new();
+        archive
+            .by_index_decrypt(0, b"correct horse battery")
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+        assert_eq!(content, "privileged");
+    }
+}
@@ -0,0 +1,386 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::document_analyzer::EntityType;
+use crate::local_api::AnalyzerStorage;
+use crate::ocr_processor::{OcrConfiguration, OcrProcessor, SignatureDetectionReport};
+
+/// Contract Execution Tracking for BEAR AI
+/// Follows each agreement from draft to fully executed, using the parties extracted by the
+/// document analyzer and the signature blocks found on the scanned copies
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecutionStatus {
+    Drafted,
+    Sent,
+    PartiallySigned,
+    FullyExecuted,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatusTransition {
+    pub from: Option<ExecutionStatus>,
+    pub to: ExecutionStatus,
+    pub at: String, // RFC 3339
+    pub source: String, // "manual" | "signature_scan"
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContractExecution {
+    pub document_id: String,
+    pub name: String,
+    pub parties: Vec<String>,
+    pub status: ExecutionStatus,
+    pub signatures_found: usize,
+    pub signatures_expected: usize,
+    pub transitions: Vec<StatusTransition>,
+}
+
+impl ContractExecution {
+    /// When the contract entered its current status
+    fn status_since(&self) -> Option<DateTime<Utc>> {
+        self.transitions
+            .last()
+            .and_then(|t| DateTime::parse_from_rfc3339(&t.at).ok())
+            .map(|t| t.with_timezone(&Utc))
+    }
+}
+
+/// An agreement that has not been fully executed within the threshold
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnexecutedContract {
+    pub document_id: String,
+    pub name: String,
+    pub status: ExecutionStatus,
+    pub days_in_status: i64,
+    pub missing_signatures: usize,
+}
+
+/// Status implied by a signature scan. A scan never moves a contract backwards, so a
+/// contract marked as sent stays sent while no signatures are found.
+fn status_from_signatures(current: ExecutionStatus, signed: usize, expected: usize) -> ExecutionStatus {
+    let scanned = if expected > 0 && signed >= expected {
+        ExecutionStatus::FullyExecuted
+    } else if signed > 0 {
+        ExecutionStatus::PartiallySigned
+    } else {
+        ExecutionStatus::Drafted
+    };
+    current.max(scanned)
+}
+
+fn unexecuted_since(contracts: &[ContractExecution], threshold_days: i64, now: DateTime<Utc>) -> Vec<UnexecutedContract> {
+    let mut overdue: Vec<UnexecutedContract> = contracts
+        .iter()
+        .filter(|c| c.status != ExecutionStatus::FullyExecuted)
+        .filter_map(|c| {
+            let days_in_status = (now - c.status_since()?).num_days();
+            (days_in_status >= threshold_days).then(|| UnexecutedContract {
+                document_id: c.document_id.clone(),
+                name: c.name.clone(),
+                status: c.status,
+                days_in_status,
+                missing_signatures: c.signatures_expected.saturating_sub(c.signatures_found),
+            })
+        })
+        .collect();
+    overdue.sort_by(|a, b| b.days_in_status.cmp(&a.days_in_status));
+    overdue
+}
+
+pub struct ContractExecutionTracker {
+    path: PathBuf,
+    contracts: Mutex<HashMap<String, ContractExecution>>,
+}
+
+impl ContractExecutionTracker {
+    pub fn new(app_data_dir: &Path) -> Result<Self> {
+        let path = app_data_dir.join("contract_execution.json");
+        let contracts = if path.exists() {
+            serde_json::from_str(&fs::read_to_string(&path)?)?
+        } else {
+            HashMap::new()
+        };
+
+        Ok(Self {
+            path,
+            contracts: Mutex::new(contracts),
+        })
+    }
+
+    fn persist(&self, contracts: &HashMap<String, ContractExecution>) -> Result<()> {
+        fs::write(&self.path, serde_json::to_string_pretty(contracts)?)?;
+        Ok(())
+    }
+
+    /// Start tracking a contract as drafted; re-registering updates its name and parties
+    pub fn register(&self, document_id: &str, name: &str, parties: Vec<String>) -> Result<ContractExecution> {
+        let mut contracts = self.contracts.lock().unwrap();
+        let contract = contracts
+            .entry(document_id.to_string())
+            .or_insert_with(|| ContractExecution {
+                document_id: document_id.to_string(),
+                name: name.to_string(),
+                parties: Vec::new(),
+                status: ExecutionStatus::Drafted,
+                signatures_found: 0,
+                signatures_expected: 0,
+                transitions: vec![StatusTransition {
+                    from: None,
+                    to: ExecutionStatus::Drafted,
+                    at: Utc::now().to_rfc3339(),
+                    source: "manual".to_string(),
+                    note: None,
+                }],
+            });
+        contract.name = name.to_string();
+        contract.signatures_expected = contract.signatures_expected.max(parties.len());
+        contract.parties = parties;
+
+        let contract = contract.clone();
+        self.persist(&contracts)?;
+        Ok(contract)
+    }
+
+    /// Track a new version of a contract. Signatures on the version it replaces do not carry
+    /// over, so it goes back to drafted; its history is kept.
+    pub fn register_revision(&self, document_id: &str, name: &str, parties: Vec<String>) -> Result<ContractExecution> {
+        self.register(document_id, name, parties)?;
+        let mut contracts = self.contracts.lock().unwrap();
+        let contract = contracts
+            .get_mut(document_id)
+            .ok_or_else(|| anyhow!("Contract {} is not tracked", document_id))?;
+        contract.signatures_found = 0;
+        contract.signatures_expected = contract.parties.len();
+        if contract.status != ExecutionStatus::Drafted {
+            contract.transitions.push(StatusTransition {
+                from: Some(contract.status),
+                to: ExecutionStatus::Drafted,
+                at: Utc::now().to_rfc3339(),
+                source: "revision".to_string(),
+                note: Some("New version generated".to_string()),
+            });
+            contract.status = ExecutionStatus::Drafted;
+        }
+
+        let contract = contract.clone();
+        self.persist(&contracts)?;
+        Ok(contract)
+    }
+
+    pub fn set_status(
+        &self,
+        document_id: &str,
+        status: ExecutionStatus,
+        source: &str,
+        note: Option<String>,
+    ) -> Result<ContractExecution> {
+        let mut contracts = self.contracts.lock().unwrap();
+        let contract = contracts
+            .get_mut(document_id)
+            .ok_or_else(|| anyhow!("Contract {} is not tracked", document_id))?;
+
+        if contract.status != status {
+            contract.transitions.push(StatusTransition {
+                from: Some(contract.status),
+                to: status,
+                at: Utc::now().to_rfc3339(),
+                source: source.to_string(),
+                note,
+            });
+            contract.status = status;
+        }
+
+        let contract = contract.clone();
+        self.persist(&contracts)?;
+        Ok(contract)
+    }
+
+    /// Update signature counts from a scan of the latest copy and move the status forward
+    pub fn apply_signature_scan(&self, document_id: &str, report: &SignatureDetectionReport) -> Result<ContractExecution> {
+        let blocks: Vec<_> = report.pages.iter().flat_map(|p| &p.signature_blocks).collect();
+        let signed = blocks.iter().filter(|b| b.signed).count();
+
+        let status = {
+            let mut contracts = self.contracts.lock().unwrap();
+            let contract = contracts
+                .get_mut(document_id)
+                .ok_or_else(|| anyhow!("Contract {} is not tracked", document_id))?;
+            // Every party is expected to sign, even if its block was not recognized
+            contract.signatures_expected = blocks.len().max(contract.parties.len());
+            contract.signatures_found = signed;
+            status_from_signatures(contract.status, signed, contract.signatures_expected)
+        };
+
+        self.set_status(
+            document_id,
+            status,
+            "signature_scan",
+            Some(format!("{} of {} signature blocks signed", signed, blocks.len())),
+        )
+    }
+
+    pub fn get(&self, document_id: &str) -> Option<ContractExecution> {
+        self.contracts.lock().unwrap().get(document_id).cloned()
+    }
+
+    pub fn list(&self) -> Vec<ContractExecution> {
+        let mut contracts: Vec<_> = self.contracts.lock().unwrap().values().cloned().collect();
+        contracts.sort_by(|a, b| a.name.cmp(&b.name));
+        contracts
+    }
+
+    /// Contracts that have sat in a status other than fully executed for `threshold_days` or more
+    pub fn unexecuted(&self, threshold_days: i64) -> Vec<UnexecutedContract> {
+        unexecuted_since(&self.list(), threshold_days, Utc::now())
+    }
+}
+
+pub type ContractExecutionStorage = Arc<ContractExecutionTracker>;
+
+#[tauri::command]
+pub async fn contract_execution_register(
+    document_id: String,
+    name: String,
+    file_path: Option<String>,
+    parties: Option<Vec<String>>,
+    tracker: tauri::State<'_, ContractExecutionStorage>,
+    analyzer: tauri::State<'_, AnalyzerStorage>,
+) -> Result<ContractExecution, String> {
+    let parties = match (parties, file_path) {
+        (Some(parties), _) => parties,
+        (None, Some(file_path)) => {
+            let analysis = analyzer
+                .analyze_document(Path::new(&file_path))
+                .await
+                .map_err(|e| e.to_string())?;
+            let mut parties: Vec<String> = analysis
+                .entities
+                .iter()
+                .filter(|e| matches!(e.entity_type, EntityType::ContractParty))
+                .map(|e| e.text.trim().to_string())
+                .collect();
+            parties.sort();
+            parties.dedup();
+            parties
+        }
+        (None, None) => Vec::new(),
+    };
+
+    tracker.register(&document_id, &name, parties).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn contract_execution_set_status(
+    document_id: String,
+    status: ExecutionStatus,
+    note: Option<String>,
+    tracker: tauri::State<'_, ContractExecutionStorage>,
+) -> Result<ContractExecution, String> {
+    tracker
+        .set_status(&document_id, status, "manual", note)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn contract_execution_scan(
+    document_id: String,
+    file_path: String,
+    config: Option<OcrConfiguration>,
+    tracker: tauri::State<'_, ContractExecutionStorage>,
+) -> Result<ContractExecution, String> {
+    let processor = OcrProcessor::new(config.unwrap_or_default());
+    let report = processor
+        .detect_signatures(&file_path)
+        .await
+        .map_err(|e| format!("Signature detection failed: {}", e))?;
+
+    tracker
+        .apply_signature_scan(&document_id, &report)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn contract_execution_list(
+    tracker: tauri::State<'_, ContractExecutionStorage>,
+) -> Result<Vec<ContractExecution>, String> {
+    Ok(tracker.list())
+}
+
+#[tauri::command]
+pub async fn contract_execution_unexecuted(
+    threshold_days: Option<i64>,
+    tracker: tauri::State<'_, ContractExecutionStorage>,
+) -> Result<Vec<UnexecutedContract>, String> {
+    Ok(tracker.unexecuted(threshold_days.unwrap_or(14)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_from_signatures_only_moves_forward() {
+        use ExecutionStatus::*;
+        assert_eq!(status_from_signatures(Drafted, 0, 2), Drafted);
+        assert_eq!(status_from_signatures(Sent, 0, 2), Sent);
+        assert_eq!(status_from_signatures(Sent, 1, 2), PartiallySigned);
+        assert_eq!(status_from_signatures(PartiallySigned, 2, 2), FullyExecuted);
+        assert_eq!(status_from_signatures(FullyExecuted, 1, 2), FullyExecuted);
+        assert_eq!(status_from_signatures(Drafted, 0, 0), Drafted);
+    }
+
+    #[test]
+    fn test_unexecuted_since_threshold() {
+        let now = Utc::now();
+        let contract = |id: &str, status, days_ago: i64| ContractExecution {
+            document_id: id.to_string(),
+            name: id.to_string(),
+            parties: vec!["Acme B.V.".to_string(), "Beta Ltd".to_string()],
+            status,
+            signatures_found: 1,
+            signatures_expected: 2,
+            transitions: vec![StatusTransition {
+                from: None,
+                to: status,
+                at: (now - chrono::Duration::days(days_ago)).to_rfc3339(),
+                source: "manual".to_string(),
+                note: None,
+            }],
+        };
+        let contracts = vec![
+            contract("nda", ExecutionStatus::Sent, 20),
+            contract("lease", ExecutionStatus::PartiallySigned, 30),
+            contract("msa", ExecutionStatus::Sent, 3),
+            contract("spa", ExecutionStatus::FullyExecuted, 60),
+        ];
+
+        let overdue = unexecuted_since(&contracts, 14, now);
+        let ids: Vec<&str> = overdue.iter().map(|c| c.document_id.as_str()).collect();
+        assert_eq!(ids, vec!["lease", "nda"]);
+        assert_eq!(overdue[0].missing_signatures, 1);
+    }
+
+    #[test]
+    fn test_revision_goes_back_to_drafted() {
+        let dir = tempfile::tempdir().unwrap();
+        let tracker = ContractExecutionTracker::new(dir.path()).unwrap();
+        let parties = vec!["Firm LLP".to_string(), "Acme B.V.".to_string()];
+        tracker.register("letter", "Engagement letter", parties.clone()).unwrap();
+        tracker.set_status("letter", ExecutionStatus::FullyExecuted, "manual", None).unwrap();
+
+        // Re-registering alone keeps the status; a revision does not
+        assert_eq!(tracker.register("letter", "Engagement letter", parties.clone()).unwrap().status, ExecutionStatus::FullyExecuted);
+        let revised = tracker.register_revision("letter", "Engagement letter", parties.clone()).unwrap();
+        assert_eq!(revised.status, ExecutionStatus::Drafted);
+        assert_eq!((revised.signatures_found, revised.signatures_expected), (0, 2));
+        assert_eq!(revised.transitions.last().unwrap().source, "revision");
+        assert_eq!(tracker.register_revision("new", "Engagement letter", parties).unwrap().transitions.len(), 1);
+    }
+}
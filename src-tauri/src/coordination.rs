@@ -0,0 +1,204 @@
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::mcp_server::AgentType;
+
+/// Agent Coordination Strategies for BEAR AI
+/// How the agents behind a workflow step reach an answer. Hierarchical runs the step's own
+/// agent; parallel consensus asks a panel and has a judge model reconcile the answers; debate
+/// alternates proposal, challenge and revision before a judge rules. Every intermediate answer
+/// is returned with the step so the lawyer can see how the result was reached.
+pub const MAX_DEBATE_ROUNDS: u32 = 3;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum CoordinationStrategy {
+    #[default]
+    Hierarchical,
+    ParallelConsensus {
+        panel: Vec<AgentType>,       // answer alongside the step's own agent
+        judge_model: Option<String>, // defaults to the step agent's model
+    },
+    Debate {
+        challenger: AgentType,
+        rounds: u32,
+        judge_model: Option<String>,
+    },
+}
+
+impl CoordinationStrategy {
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            CoordinationStrategy::Hierarchical => Ok(()),
+            CoordinationStrategy::ParallelConsensus { panel, .. } if panel.is_empty() => {
+                Err(anyhow!("Parallel consensus needs at least one panel agent"))
+            }
+            CoordinationStrategy::ParallelConsensus { .. } => Ok(()),
+            CoordinationStrategy::Debate { rounds, .. } if *rounds == 0 || *rounds > MAX_DEBATE_ROUNDS => {
+                Err(anyhow!("Debates run between 1 and {} rounds", MAX_DEBATE_ROUNDS))
+            }
+            CoordinationStrategy::Debate { .. } => Ok(()),
+        }
+    }
+}
+
+/// Model calls one step makes under a strategy, for budget estimates
+pub fn model_calls(strategy: &CoordinationStrategy) -> u64 {
+    match strategy {
+        CoordinationStrategy::Hierarchical => 1,
+        // Each panel member plus the step agent, then the judge
+        CoordinationStrategy::ParallelConsensus { panel, .. } => panel.len() as u64 + 2,
+        // Opening, a challenge and a revision per round, then the judge
+        CoordinationStrategy::Debate { rounds, .. } => 2 + 2 * *rounds as u64,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CoordinationRole {
+    PanelAnswer,
+    Proposal,
+    Challenge,
+    Revision,
+    Verdict,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntermediateOutput {
+    pub role: CoordinationRole,
+    pub agent_id: Option<String>, // None for the judge model
+    pub model_id: Option<String>,
+    pub round: u32,
+    pub output: String,
+}
+
+/// Judge prompt reconciling independent panel answers
+pub fn consensus_prompt(task_input: &str, answers: &[IntermediateOutput]) -> String {
+    let mut prompt = String::from(
+        "You are a senior lawyer reviewing answers that several assistants gave independently to the same task. \
+         Reconcile them into one answer: keep the points they agree on, resolve each disagreement by explaining \
+         which answer is better supported and why, and list anything that remains uncertain. \
+         Do not introduce authorities that none of the answers cite.\n\n",
+    );
+    prompt.push_str(&format!("Task:\n{}\n", task_input.trim()));
+    for (i, answer) in answers.iter().enumerate() {
+        prompt.push_str(&format!(
+            "\nAnswer {} ({}):\n{}\n",
+            i + 1,
+            answer.agent_id.as_deref().unwrap_or("unknown"),
+            answer.output.trim()
+        ));
+    }
+    prompt.push_str("\nReconciled answer:\n");
+    prompt
+}
+
+/// Input for the challenger: attack the current position
+pub fn challenge_input(task_input: &str, position: &str) -> String {
+    format!(
+        "Critically examine the position below. Identify legal or factual errors, omitted issues, weak \
+         reasoning and the strongest counterarguments. Be specific and do not restate the position.\n\n\
+         Task:\n{}\n\nPosition:\n{}",
+        task_input.trim(),
+        position.trim()
+    )
+}
+
+/// Input for the proposer: answer the critique and revise
+pub fn revision_input(task_input: &str, position: &str, critique: &str) -> String {
+    format!(
+        "Revise your position in light of the critique below. Concede points that are correct, rebut those \
+         that are not and give your complete revised answer.\n\n\
+         Task:\n{}\n\nYour position:\n{}\n\nCritique:\n{}",
+        task_input.trim(),
+        position.trim(),
+        critique.trim()
+    )
+}
+
+/// Judge prompt ruling on a debate transcript
+pub fn debate_verdict_prompt(task_input: &str, transcript: &[IntermediateOutput]) -> String {
+    let mut prompt = String::from(
+        "You are a senior lawyer judging a structured debate between two assistants. Give the final answer \
+         to the task, state which objections were resolved and how, and list the points that remain \
+         contested and need human review.\n\n",
+    );
+    prompt.push_str(&format!("Task:\n{}\n", task_input.trim()));
+    for entry in transcript {
+        let label = match entry.role {
+            CoordinationRole::Proposal => "Opening position".to_string(),
+            CoordinationRole::Challenge => format!("Challenge (round {})", entry.round),
+            CoordinationRole::Revision => format!("Revised position (round {})", entry.round),
+            CoordinationRole::PanelAnswer | CoordinationRole::Verdict => continue,
+        };
+        prompt.push_str(&format!("\n{}:\n{}\n", label, entry.output.trim()));
+    }
+    prompt.push_str("\nFinal answer:\n");
+    prompt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(role: CoordinationRole, agent: &str, round: u32, output: &str) -> IntermediateOutput {
+        IntermediateOutput {
+            role,
+            agent_id: Some(agent.to_string()),
+            model_id: None,
+            round,
+            output: output.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_strategy_serde_and_validation() {
+        let strategy: CoordinationStrategy = serde_json::from_str(
+            r#"{"mode": "debate", "challenger": "RiskAssessor", "rounds": 2, "judge_model": null}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            strategy,
+            CoordinationStrategy::Debate {
+                challenger: AgentType::RiskAssessor,
+                rounds: 2,
+                judge_model: None
+            }
+        );
+        assert!(strategy.validate().is_ok());
+        assert_eq!(model_calls(&strategy), 6);
+        assert_eq!(CoordinationStrategy::default(), CoordinationStrategy::Hierarchical);
+
+        let endless = CoordinationStrategy::Debate {
+            challenger: AgentType::RiskAssessor,
+            rounds: MAX_DEBATE_ROUNDS + 1,
+            judge_model: None,
+        };
+        assert!(endless.validate().is_err());
+        let empty_panel = CoordinationStrategy::ParallelConsensus { panel: Vec::new(), judge_model: None };
+        assert!(empty_panel.validate().is_err());
+    }
+
+    #[test]
+    fn test_judge_prompts_include_every_contribution() {
+        let answers = vec![
+            entry(CoordinationRole::PanelAnswer, "contract_analyzer", 0, "The cap is 100% of fees."),
+            entry(CoordinationRole::PanelAnswer, "risk_assessor", 0, "The cap excludes data breaches."),
+        ];
+        let prompt = consensus_prompt("Assess the liability cap", &answers);
+        assert!(prompt.contains("Answer 1 (contract_analyzer):\nThe cap is 100% of fees."));
+        assert!(prompt.contains("Answer 2 (risk_assessor)"));
+
+        let transcript = vec![
+            entry(CoordinationRole::Proposal, "contract_analyzer", 0, "Enforceable."),
+            entry(CoordinationRole::Challenge, "risk_assessor", 1, "Unfair terms rules apply."),
+            entry(CoordinationRole::Revision, "contract_analyzer", 1, "Enforceable between businesses."),
+        ];
+        let prompt = debate_verdict_prompt("Is the cap enforceable?", &transcript);
+        let opening = prompt.find("Opening position").unwrap();
+        let challenge = prompt.find("Challenge (round 1)").unwrap();
+        let revision = prompt.find("Revised position (round 1)").unwrap();
+        assert!(opening < challenge && challenge < revision);
+        assert!(challenge_input("Task", "Enforceable.").contains("Position:\nEnforceable."));
+    }
+}
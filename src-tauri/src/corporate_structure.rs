@@ -0,0 +1,464 @@
+use anyhow::Result;
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use crate::enterprise_management::BarrierStorage;
+use crate::local_api::{authenticated_user, AnalyzerStorage, SessionStorage};
+use crate::matters::{self, MatterStorage};
+use crate::security::SecurityManager;
+
+/// Corporate Structure Extraction for BEAR AI
+/// Due-diligence packets describe a group piecemeal: "Alpha Holdings B.V. holds 60% of the
+/// shares in Beta GmbH" in one document, "Gamma Ltd, a wholly owned subsidiary of Beta GmbH"
+/// in another. Ownership statements are extracted from every document, entities are merged
+/// across documents by normalised name, and the result is a graph of parents, subsidiaries and
+/// percentages with the effective ownership each top-level owner holds down the chain. The
+/// graph exports as JSON or Graphviz DOT.
+// A trailing dot only ends a name, so a name never runs on into the next sentence
+const NAME: &str = r"([A-Z][\w&'-]*(?:\.[\w&'-]+)*(?: (?:[A-Z][\w&'-]*(?:\.[\w&'-]+)*|&|of|and|van|de|der|du)){0,7}\.?)";
+const PERCENT: &str = r"(\d{1,3}(?:[.,]\d+)?)\s?(?:%|(?i:per\s?cent|percent))";
+
+// Defined terms and roles that read like names but are not entities
+const ROLE_WORDS: &[&str] = &[
+    "company", "seller", "sellers", "buyer", "purchaser", "parent", "target", "group", "shareholder", "subsidiary", "it",
+    "this", "each", "such", "party",
+];
+
+#[derive(Clone, Copy)]
+enum Relation {
+    // parent, percentage, child capture groups
+    Owns,
+    // child, percentage, parent
+    OwnedBy,
+    // child, wholly-owned marker, parent
+    SubsidiaryOf,
+    // parent, sole-shareholder marker, child
+    ParentOf,
+}
+
+lazy_static! {
+    static ref PATTERNS: Vec<(Regex, Relation)> = vec![
+        (
+            Regex::new(&format!(
+                r"{},?\s+(?i:(?:which\s+|who\s+)?(?:owns|holds|has))\s+(?i:(?:approximately|about|legally and beneficially|directly|indirectly)\s+)?{}\s+(?i:of\s+(?:the\s+)?(?:(?:entire\s+)?(?:issued\s+)?(?:share\s+capital|shares|stock|equity|(?:membership\s+|partnership\s+)?interests?|voting\s+rights)\s+(?:in|of)\s+)?){}",
+                NAME, PERCENT, NAME
+            ))
+            .unwrap(),
+            Relation::Owns,
+        ),
+        (
+            Regex::new(&format!(
+                r"{},?\s+(?i:(?:is\s+|which\s+is\s+)?(?:a\s+)?){}[- ](?i:owned\s+(?:subsidiary\s+)?(?:by|of))\s+{}",
+                NAME, PERCENT, NAME
+            ))
+            .unwrap(),
+            Relation::OwnedBy,
+        ),
+        (
+            Regex::new(&format!(
+                r"{},?\s+(?i:(?:is\s+|which\s+is\s+)?(?:a|an)\s+(wholly[- ]owned\s+)?(?:direct\s+|indirect\s+)?subsidiary\s+of)\s+{}",
+                NAME, NAME
+            ))
+            .unwrap(),
+            Relation::SubsidiaryOf,
+        ),
+        (
+            Regex::new(&format!(
+                r"{},?\s+(?i:(?:is\s+)?(?:the\s+)?(sole\s+shareholder|parent(?:\s+company)?|holding\s+company)\s+of)\s+{}",
+                NAME, NAME
+            ))
+            .unwrap(),
+            Relation::ParentOf,
+        ),
+    ];
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CorporateEntity {
+    pub id: String, // normalised name
+    pub name: String,
+    pub aliases: Vec<String>, // other spellings found
+    pub documents: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OwnershipSource {
+    pub document: String,
+    pub excerpt: String,
+    pub percentage: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Ownership {
+    pub parent_id: String,
+    pub child_id: String,
+    pub percentage: Option<f64>, // None when a document only says "subsidiary"
+    pub conflicting: bool, // documents state different percentages
+    pub sources: Vec<OwnershipSource>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UltimateOwner {
+    pub entity_id: String,
+    pub owner_id: String,
+    pub effective_percentage: Option<f64>, // None when a link in the chain has no percentage
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CorporateStructure {
+    pub entities: Vec<CorporateEntity>,
+    pub ownerships: Vec<Ownership>,
+    pub ultimate_owners: Vec<UltimateOwner>,
+    pub skipped_documents: Vec<String>, // could not be read
+}
+
+impl CorporateStructure {
+    /// The documents the structure was read from
+    pub fn source_documents(&self) -> Vec<String> {
+        let documents: BTreeSet<&String> = self
+            .entities
+            .iter()
+            .flat_map(|e| &e.documents)
+            .chain(self.ownerships.iter().flat_map(|o| o.sources.iter().map(|s| &s.document)))
+            .collect();
+        documents.into_iter().cloned().collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StructureFormat {
+    Json,
+    Graphviz,
+}
+
+/// Ownership statement found in a single document
+#[derive(Debug, Clone, PartialEq)]
+pub struct OwnershipStatement {
+    pub parent: String,
+    pub child: String,
+    pub percentage: Option<f64>,
+    pub excerpt: String,
+}
+
+fn clean_name(name: &str) -> Option<String> {
+    let name = name.trim().trim_end_matches([',', ';', ':']);
+    let name = name.strip_prefix("The ").unwrap_or(name);
+    // A final dot ends the sentence unless it closes an abbreviation such as "B.V."
+    let bare = name.trim_end_matches('.');
+    let abbreviated = bare.rsplit(' ').next().map(|word| word.contains('.')).unwrap_or(false);
+    let name = if abbreviated { name } else { bare };
+    let key = entity_key(name);
+    if key.is_empty() || ROLE_WORDS.contains(&key.as_str()) {
+        return None;
+    }
+    Some(name.to_string())
+}
+
+/// Merge key: lowercase tokens without corporate suffixes, so "Beta GmbH" and "BETA" meet
+pub fn entity_key(name: &str) -> String {
+    let tokens: Vec<String> = matters::name_tokens(&name.replace('.', "")).into_iter().collect();
+    tokens.join(" ")
+}
+
+fn percentage(captures: &Captures, group: usize) -> Option<f64> {
+    captures
+        .get(group)
+        .and_then(|m| m.as_str().replace(',', ".").parse::<f64>().ok())
+        .filter(|p| *p > 0.0 && *p <= 100.0)
+}
+
+/// Extract ownership statements from a document's text
+pub fn extract_statements(text: &str) -> Vec<OwnershipStatement> {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let mut statements = Vec::new();
+    for (pattern, relation) in PATTERNS.iter() {
+        for captures in pattern.captures_iter(&text) {
+            let (parent, child, percentage) = match relation {
+                Relation::Owns => (&captures[1], &captures[3], percentage(&captures, 2)),
+                Relation::OwnedBy => (&captures[3], &captures[1], percentage(&captures, 2)),
+                Relation::SubsidiaryOf => (&captures[3], &captures[1], captures.get(2).map(|_| 100.0)),
+                Relation::ParentOf => (
+                    &captures[1],
+                    &captures[3],
+                    Some(100.0).filter(|_| captures[2].to_lowercase().starts_with("sole")),
+                ),
+            };
+            let (Some(parent), Some(child)) = (clean_name(parent), clean_name(child)) else {
+                continue;
+            };
+            if entity_key(&parent) == entity_key(&child) {
+                continue;
+            }
+            statements.push(OwnershipStatement {
+                parent,
+                child,
+                percentage,
+                excerpt: captures[0].to_string(),
+            });
+        }
+    }
+    statements
+}
+
+/// Merge the statements of several documents into one structure
+pub fn build_structure(documents: &[(String, Vec<OwnershipStatement>)]) -> CorporateStructure {
+    let mut entities: BTreeMap<String, CorporateEntity> = BTreeMap::new();
+    let mut ownerships: BTreeMap<(String, String), Ownership> = BTreeMap::new();
+
+    for (document, statements) in documents {
+        for statement in statements {
+            for name in [&statement.parent, &statement.child] {
+                let key = entity_key(name);
+                let entity = entities.entry(key.clone()).or_insert_with(|| CorporateEntity {
+                    id: key,
+                    name: name.clone(),
+                    aliases: Vec::new(),
+                    documents: Vec::new(),
+                });
+                if entity.name != *name && !entity.aliases.contains(name) {
+                    // Keep the most complete spelling as the display name
+                    if name.len() > entity.name.len() {
+                        let previous = std::mem::replace(&mut entity.name, name.clone());
+                        entity.aliases.push(previous);
+                    } else {
+                        entity.aliases.push(name.clone());
+                    }
+                }
+                if !entity.documents.contains(document) {
+                    entity.documents.push(document.clone());
+                }
+            }
+
+            let (parent_id, child_id) = (entity_key(&statement.parent), entity_key(&statement.child));
+            let ownership = ownerships
+                .entry((parent_id.clone(), child_id.clone()))
+                .or_insert_with(|| Ownership {
+                    parent_id,
+                    child_id,
+                    percentage: None,
+                    conflicting: false,
+                    sources: Vec::new(),
+                });
+            if let Some(stated) = statement.percentage {
+                match ownership.percentage {
+                    None => ownership.percentage = Some(stated),
+                    Some(known) if (known - stated).abs() > f64::EPSILON => ownership.conflicting = true,
+                    Some(_) => {}
+                }
+            }
+            ownership.sources.push(OwnershipSource {
+                document: document.clone(),
+                excerpt: statement.excerpt.clone(),
+                percentage: statement.percentage,
+            });
+        }
+    }
+
+    let ownerships: Vec<Ownership> = ownerships.into_values().collect();
+    let ultimate_owners = ultimate_owners(&ownerships);
+    CorporateStructure {
+        entities: entities.into_values().collect(),
+        ownerships,
+        ultimate_owners,
+        skipped_documents: Vec::new(),
+    }
+}
+
+/// Effective stake of every top-level owner in every owned entity, multiplied down the chain
+/// and summed over parallel chains
+fn ultimate_owners(ownerships: &[Ownership]) -> Vec<UltimateOwner> {
+    fn owners_of(
+        entity: &str,
+        ownerships: &[Ownership],
+        path: &mut BTreeSet<String>,
+    ) -> BTreeMap<String, Option<f64>> {
+        let mut owners: BTreeMap<String, Option<f64>> = BTreeMap::new();
+        let mut add = |owner: String, share: Option<f64>| {
+            let total = owners.entry(owner).or_insert(Some(0.0));
+            *total = total.zip(share).map(|(a, b)| a + b);
+        };
+        for edge in ownerships.iter().filter(|o| o.child_id == entity) {
+            // Circular holdings end the walk
+            if !path.insert(edge.parent_id.clone()) {
+                continue;
+            }
+            let upstream = owners_of(&edge.parent_id, ownerships, path);
+            if upstream.is_empty() {
+                add(edge.parent_id.clone(), edge.percentage);
+            }
+            for (owner, share) in upstream {
+                add(owner, share.zip(edge.percentage).map(|(a, b)| a * b / 100.0));
+            }
+            path.remove(&edge.parent_id);
+        }
+        owners
+    }
+
+    let children: BTreeSet<&String> = ownerships.iter().map(|o| &o.child_id).collect();
+    let mut result = Vec::new();
+    for entity in children {
+        let mut path = BTreeSet::from([entity.clone()]);
+        for (owner, share) in owners_of(entity, ownerships, &mut path) {
+            result.push(UltimateOwner {
+                entity_id: entity.clone(),
+                owner_id: owner,
+                effective_percentage: share.map(|s| (s * 100.0).round() / 100.0),
+            });
+        }
+    }
+    result
+}
+
+fn dot_escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn format_percentage(value: f64) -> String {
+    if value.fract() == 0.0 {
+        format!("{:.0}%", value)
+    } else {
+        format!("{}%", value)
+    }
+}
+
+/// Graphviz DOT with parents above their subsidiaries
+pub fn to_graphviz(structure: &CorporateStructure) -> String {
+    let mut dot = String::from("digraph corporate_structure {\n    rankdir=TB;\n    node [shape=box];\n");
+    for entity in &structure.entities {
+        dot.push_str(&format!("    \"{}\" [label=\"{}\"];\n", dot_escape(&entity.id), dot_escape(&entity.name)));
+    }
+    for ownership in &structure.ownerships {
+        let mut label = ownership.percentage.map(format_percentage).unwrap_or_else(|| "subsidiary".to_string());
+        if ownership.conflicting {
+            label.push_str(" (conflicting)");
+        }
+        let style = if ownership.conflicting { ", color=red" } else { "" };
+        dot.push_str(&format!(
+            "    \"{}\" -> \"{}\" [label=\"{}\"{}];\n",
+            dot_escape(&ownership.parent_id),
+            dot_escape(&ownership.child_id),
+            dot_escape(&label),
+            style
+        ));
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+pub fn export_structure(structure: &CorporateStructure, format: StructureFormat, output_path: &Path) -> Result<()> {
+    if let Some(parent) = output_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let content = match format {
+        StructureFormat::Json => serde_json::to_string_pretty(structure)?,
+        StructureFormat::Graphviz => to_graphviz(structure),
+    };
+    fs::write(output_path, content)?;
+    Ok(())
+}
+
+/// Build the corporate structure described by a set of due-diligence documents
+#[tauri::command]
+pub async fn corporate_structure_extract(
+    file_paths: Vec<String>,
+    analyzer: tauri::State<'_, AnalyzerStorage>,
+) -> Result<CorporateStructure, String> {
+    let mut documents = Vec::new();
+    let mut skipped = Vec::new();
+    for file_path in file_paths {
+        match analyzer.extract_text(Path::new(&file_path)).await {
+            Ok(text) => documents.push((file_path, extract_statements(&text))),
+            Err(e) => {
+                log::warn!("Skipping {} for corporate structure: {}", file_path, e);
+                skipped.push(file_path);
+            }
+        }
+    }
+    let mut structure = build_structure(&documents);
+    structure.skipped_documents = skipped;
+    Ok(structure)
+}
+
+#[tauri::command]
+pub async fn corporate_structure_export(
+    session_id: String,
+    structure: CorporateStructure,
+    format: StructureFormat,
+    output_path: String,
+    sessions: tauri::State<'_, SessionStorage>,
+    matters: tauri::State<'_, MatterStorage>,
+    barriers: tauri::State<'_, BarrierStorage>,
+    security: tauri::State<'_, Arc<Mutex<SecurityManager>>>,
+) -> Result<String, String> {
+    let user = authenticated_user(&session_id, &sessions)?;
+    barriers.check_export(&security, &user, &matters.export_items(&structure.source_documents()))?;
+    export_structure(&structure, format, Path::new(&output_path)).map_err(|e| e.to_string())?;
+    Ok(output_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extracts_ownership_statements() {
+        let text = "Alpha Holdings B.V. holds 60% of the issued share capital in Beta GmbH. \
+            The remaining shares are held by Delta Capital LLC, which owns 40 per cent of Beta GmbH. \
+            Gamma Ltd, a wholly owned subsidiary of Beta GmbH, operates the UK business. \
+            The Seller is the parent company of Epsilon SA.";
+        let statements = extract_statements(text);
+
+        let find = |child: &str| statements.iter().filter(|s| s.child.starts_with(child)).collect::<Vec<_>>();
+        let beta = find("Beta");
+        assert_eq!(beta.len(), 2);
+        assert!(beta.iter().any(|s| s.parent == "Alpha Holdings B.V." && s.percentage == Some(60.0)));
+        assert!(beta.iter().any(|s| s.parent == "Delta Capital LLC" && s.percentage == Some(40.0)));
+        let gamma = find("Gamma");
+        assert_eq!(gamma.len(), 1);
+        assert_eq!(gamma[0].parent, "Beta GmbH");
+        assert_eq!(gamma[0].percentage, Some(100.0));
+        // "The Seller" is a defined term, not an entity
+        assert!(find("Epsilon").is_empty());
+    }
+
+    #[test]
+    fn test_merges_documents_and_computes_ultimate_owners() {
+        let documents = vec![
+            ("spa.pdf".to_string(), extract_statements("Alpha Holdings B.V. owns 60% of the shares in Beta GmbH.")),
+            (
+                "register.pdf".to_string(),
+                extract_statements("BETA GMBH holds 50% of Gamma Ltd. Alpha Holdings BV owns 70% of Beta GmbH."),
+            ),
+            ("memo.docx".to_string(), extract_statements("Alpha Holdings owns 20% of Gamma Ltd.")),
+        ];
+        let structure = build_structure(&documents);
+
+        assert_eq!(structure.entities.len(), 3);
+        let alpha = structure.entities.iter().find(|e| e.id == "alpha holdings").unwrap();
+        assert_eq!(alpha.name, "Alpha Holdings B.V.");
+        assert_eq!(alpha.documents.len(), 3);
+
+        let alpha_beta = structure.ownerships.iter().find(|o| o.child_id == "beta").unwrap();
+        assert!(alpha_beta.conflicting);
+        assert_eq!(alpha_beta.sources.len(), 2);
+
+        // 60% x 50% through Beta plus 20% held directly
+        let gamma_owner = structure
+            .ultimate_owners
+            .iter()
+            .find(|u| u.entity_id == "gamma" && u.owner_id == "alpha holdings")
+            .unwrap();
+        assert_eq!(gamma_owner.effective_percentage, Some(50.0));
+
+        let dot = to_graphviz(&structure);
+        assert!(dot.contains("\"alpha holdings\" [label=\"Alpha Holdings B.V.\"];"));
+        assert!(dot.contains("\"beta\" -> \"gamma\" [label=\"50%\"];"));
+        assert!(dot.contains("[label=\"60% (conflicting)\", color=red]"));
+    }
+}
@@ -0,0 +1,872 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+
+use crate::document_acl::DocumentAclStorage;
+use crate::enterprise_management::BarrierStorage;
+use crate::llm_manager::{GenerateOptions, GenerateRequest, LLMManager};
+use crate::local_api::{authenticated_user, SessionStorage};
+use crate::nemotron_rag::{DocumentType, LegalDocument, RAGChunk};
+use crate::retrieval_scope::{RetrievalScope, ScreenedDocuments};
+use crate::security::SecurityManager;
+use crate::text_processing::{self, LanguageTools};
+
+/// Corpus Topic Clustering for BEAR AI
+/// Every indexed document leaves a document vector (the normalized mean of its chunk
+/// embeddings) and its most frequent terms in an append-only log next to the RAG index.
+/// Clustering runs spherical k-means over those vectors on demand; clusters are labelled by the
+/// local LLM from their most distinctive terms, or by the terms themselves when no model is loaded.
+/// The same vectors answer "which earlier documents are closest to this one" for precedent lookup.
+/// Each vector records the embedding model it came from, and a log holding vectors from more than
+/// one model, or of more than one dimension, is refused until the corpus is re-indexed.
+const TERMS_PER_DOCUMENT: usize = 40;
+const TERMS_PER_CLUSTER: usize = 8;
+const MAX_ITERATIONS: usize = 50;
+const MAX_CLUSTERS: usize = 30;
+const DEFAULT_SIMILAR_DOCUMENTS: usize = 10;
+const SHARED_TERMS_SHOWN: usize = 5;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentVector {
+    pub document_id: String,
+    pub title: String,
+    #[serde(default)]
+    pub collection: String, // vector collection the chunks were stored in
+    #[serde(default)]
+    pub document_type: Option<DocumentType>,
+    pub language: String,
+    #[serde(default)]
+    pub embedding_model: String, // empty for vectors logged before the model was recorded
+    pub embedding: Vec<f32>,
+    pub terms: Vec<(String, u32)>,
+    pub indexed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClusterMember {
+    pub document_id: String,
+    pub title: String,
+    pub similarity: f32, // cosine similarity to the cluster centroid
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopicCluster {
+    pub id: usize,
+    pub label: String,
+    pub labelled_by_model: bool,
+    pub terms: Vec<String>,
+    pub size: usize,
+    pub cohesion: f32, // mean member similarity
+    pub members: Vec<ClusterMember>,
+}
+
+/// Cluster without its member list, for browsing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClusterSummary {
+    pub id: usize,
+    pub label: String,
+    pub terms: Vec<String>,
+    pub size: usize,
+    pub cohesion: f32,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SimilarityFilter {
+    pub collection: Option<String>,
+    pub document_types: Option<Vec<DocumentType>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimilarDocument {
+    pub document_id: String,
+    pub title: String,
+    pub collection: String,
+    pub document_type: Option<DocumentType>,
+    pub similarity: f32,
+    pub shared_terms: Vec<String>, // frequent terms both documents use, to show why they match
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CorpusClustering {
+    pub generated_at: DateTime<Utc>,
+    pub documents: usize,
+    pub clusters: Vec<TopicCluster>,
+}
+
+fn normalize(vector: &mut [f32]) {
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|v| *v /= norm);
+    }
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+/// Most frequent content words, counted by stem and reported in their most common form
+fn top_terms(text: &str, language: &str) -> Vec<(String, u32)> {
+    let tools = LanguageTools::for_language(language);
+    let mut by_stem: HashMap<String, HashMap<String, u32>> = HashMap::new();
+    for word in text_processing::words(text) {
+        if word.chars().count() > 3 && !tools.is_stop_word(&word) {
+            *by_stem.entry(tools.stem(&word)).or_default().entry(word).or_default() += 1;
+        }
+    }
+    let mut terms: Vec<(String, u32)> = by_stem
+        .into_values()
+        .map(|forms| {
+            let total = forms.values().sum();
+            let surface = forms
+                .into_iter()
+                .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(&a.0)))
+                .map(|(word, _)| word)
+                .unwrap_or_default();
+            (surface, total)
+        })
+        .collect();
+    terms.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    terms.truncate(TERMS_PER_DOCUMENT);
+    terms
+}
+
+/// Document vector for an indexed document whose chunks `embedding_model` embedded; None when
+/// they carry no embeddings
+pub fn document_vector(
+    collection: &str,
+    embedding_model: &str,
+    document: &LegalDocument,
+    chunks: &[RAGChunk],
+) -> Option<DocumentVector> {
+    let dimension = chunks.iter().map(|c| c.embedding.len()).find(|len| *len > 0)?;
+    let mut embedding = vec![0.0f32; dimension];
+    for chunk in chunks.iter().filter(|c| c.embedding.len() == dimension) {
+        embedding.iter_mut().zip(&chunk.embedding).for_each(|(sum, v)| *sum += v);
+    }
+    normalize(&mut embedding);
+
+    let language = text_processing::detect_language(&document.content);
+    Some(DocumentVector {
+        document_id: document.id.clone(),
+        title: document.title.clone(),
+        collection: collection.to_string(),
+        document_type: Some(document.document_type.clone()),
+        terms: top_terms(&document.content, &language),
+        language,
+        embedding_model: embedding_model.to_string(),
+        embedding,
+        indexed_at: Utc::now(),
+    })
+}
+
+/// Vectors from different embedding models do not compare, and those of different dimensions
+/// cannot be compared at all; a vector without a recorded model only needs the same dimension
+pub fn check_embeddings(documents: &[DocumentVector]) -> Result<()> {
+    let Some(first) = documents.first() else {
+        return Ok(());
+    };
+    if let Some(other) = documents.iter().find(|d| d.embedding.len() != first.embedding.len()) {
+        return Err(anyhow!(
+            "Document vectors have {} and {} dimensions ({} and {}); re-index the corpus with one embedding model",
+            first.embedding.len(),
+            other.embedding.len(),
+            first.document_id,
+            other.document_id
+        ));
+    }
+    let mut models = documents.iter().map(|d| d.embedding_model.as_str()).filter(|m| !m.is_empty());
+    if let Some(model) = models.next() {
+        if let Some(other) = models.find(|m| *m != model) {
+            return Err(anyhow!(
+                "Documents were embedded with {} and {}; re-index the corpus with one embedding model",
+                model,
+                other
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Spherical k-means over unit vectors. Seeds are picked farthest-first from the document closest
+/// to the corpus mean, so the same corpus always gives the same clusters.
+pub fn spherical_kmeans(vectors: &[Vec<f32>], k: usize) -> (Vec<usize>, Vec<Vec<f32>>) {
+    let k = k.clamp(1, vectors.len().max(1));
+    if vectors.is_empty() {
+        return (Vec::new(), Vec::new());
+    }
+    let dimension = vectors[0].len();
+
+    let mut mean = vec![0.0f32; dimension];
+    for vector in vectors {
+        mean.iter_mut().zip(vector).for_each(|(m, v)| *m += v);
+    }
+    normalize(&mut mean);
+    let first = (0..vectors.len())
+        .max_by(|&a, &b| dot(&vectors[a], &mean).total_cmp(&dot(&vectors[b], &mean)))
+        .unwrap_or(0);
+    let mut centroids = vec![vectors[first].clone()];
+    while centroids.len() < k {
+        let farthest = (0..vectors.len())
+            .min_by(|&a, &b| {
+                let closest = |i: usize| centroids.iter().map(|c| dot(&vectors[i], c)).fold(f32::MIN, f32::max);
+                closest(a).total_cmp(&closest(b))
+            })
+            .unwrap_or(0);
+        centroids.push(vectors[farthest].clone());
+    }
+
+    let mut assignments = vec![usize::MAX; vectors.len()];
+    for _ in 0..MAX_ITERATIONS {
+        let mut changed = false;
+        for (i, vector) in vectors.iter().enumerate() {
+            let best = (0..centroids.len())
+                .max_by(|&a, &b| dot(vector, &centroids[a]).total_cmp(&dot(vector, &centroids[b])))
+                .unwrap_or(0);
+            if assignments[i] != best {
+                assignments[i] = best;
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+        // An emptied cluster keeps its previous centroid
+        for (cluster, centroid) in centroids.iter_mut().enumerate() {
+            let mut sum = vec![0.0f32; dimension];
+            let mut members = 0;
+            for (vector, _) in vectors.iter().zip(&assignments).filter(|(_, a)| **a == cluster) {
+                sum.iter_mut().zip(vector).for_each(|(s, v)| *s += v);
+                members += 1;
+            }
+            if members > 0 {
+                normalize(&mut sum);
+                *centroid = sum;
+            }
+        }
+    }
+    (assignments, centroids)
+}
+
+/// Documents closest to `query` by cosine similarity of their document vectors, best first
+pub fn rank_similar(
+    query: &DocumentVector,
+    candidates: &[DocumentVector],
+    top_k: usize,
+    filter: &SimilarityFilter,
+) -> Vec<SimilarDocument> {
+    let mut similar: Vec<SimilarDocument> = candidates
+        .iter()
+        .filter(|c| c.document_id != query.document_id && c.embedding.len() == query.embedding.len())
+        .filter(|c| filter.collection.iter().all(|collection| &c.collection == collection))
+        .filter(|c| match (&filter.document_types, &c.document_type) {
+            (Some(types), Some(document_type)) => types.contains(document_type),
+            (Some(_), None) => false,
+            (None, _) => true,
+        })
+        .map(|c| SimilarDocument {
+            document_id: c.document_id.clone(),
+            title: c.title.clone(),
+            collection: c.collection.clone(),
+            document_type: c.document_type.clone(),
+            similarity: dot(&query.embedding, &c.embedding),
+            shared_terms: query
+                .terms
+                .iter()
+                .filter(|(term, _)| c.terms.iter().any(|(other, _)| other == term))
+                .take(SHARED_TERMS_SHOWN)
+                .map(|(term, _)| term.clone())
+                .collect(),
+        })
+        .collect();
+    similar.sort_by(|a, b| b.similarity.total_cmp(&a.similarity));
+    similar.truncate(top_k);
+    similar
+}
+
+/// Terms frequent in the cluster but rare in the rest of the corpus
+fn distinctive_terms(members: &[&DocumentVector], document_frequency: &HashMap<&str, usize>, corpus_size: usize) -> Vec<String> {
+    let mut cluster_frequency: HashMap<&str, usize> = HashMap::new();
+    for member in members {
+        for (term, _) in &member.terms {
+            *cluster_frequency.entry(term.as_str()).or_default() += 1;
+        }
+    }
+    let mut scored: Vec<(&str, f64)> = cluster_frequency
+        .into_iter()
+        .map(|(term, count)| {
+            let idf = (corpus_size as f64 / document_frequency[term] as f64).ln() + 1.0;
+            (term, count as f64 / members.len() as f64 * idf)
+        })
+        .collect();
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+    scored.into_iter().take(TERMS_PER_CLUSTER).map(|(term, _)| term.to_string()).collect()
+}
+
+/// Default number of clusters: sqrt(n / 2), the usual rule of thumb, capped for browsing
+pub fn default_cluster_count(documents: usize) -> usize {
+    ((documents as f64 / 2.0).sqrt().round() as usize).clamp(1, MAX_CLUSTERS)
+}
+
+/// Cluster document vectors; clusters come back largest first and labelled by their terms
+pub fn cluster_documents(documents: &[DocumentVector], k: Option<usize>) -> CorpusClustering {
+    let vectors: Vec<Vec<f32>> = documents.iter().map(|d| d.embedding.clone()).collect();
+    let k = k.unwrap_or_else(|| default_cluster_count(documents.len()));
+    let (assignments, centroids) = spherical_kmeans(&vectors, k);
+
+    let mut document_frequency: HashMap<&str, usize> = HashMap::new();
+    for document in documents {
+        for (term, _) in &document.terms {
+            *document_frequency.entry(term.as_str()).or_default() += 1;
+        }
+    }
+
+    let mut clusters: Vec<TopicCluster> = centroids
+        .iter()
+        .enumerate()
+        .filter_map(|(cluster, centroid)| {
+            let members: Vec<&DocumentVector> = documents
+                .iter()
+                .zip(&assignments)
+                .filter(|(_, a)| **a == cluster)
+                .map(|(d, _)| d)
+                .collect();
+            if members.is_empty() {
+                return None;
+            }
+            let terms = distinctive_terms(&members, &document_frequency, documents.len());
+            let mut members: Vec<ClusterMember> = members
+                .iter()
+                .map(|d| ClusterMember {
+                    document_id: d.document_id.clone(),
+                    title: d.title.clone(),
+                    similarity: dot(&d.embedding, centroid),
+                })
+                .collect();
+            members.sort_by(|a, b| b.similarity.total_cmp(&a.similarity));
+            Some(TopicCluster {
+                id: 0,
+                label: terms.iter().take(3).cloned().collect::<Vec<_>>().join(", "),
+                labelled_by_model: false,
+                terms,
+                size: members.len(),
+                cohesion: members.iter().map(|m| m.similarity).sum::<f32>() / members.len() as f32,
+                members,
+            })
+        })
+        .collect();
+    clusters.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| b.cohesion.total_cmp(&a.cohesion)));
+    for (id, cluster) in clusters.iter_mut().enumerate() {
+        cluster.id = id + 1;
+    }
+
+    CorpusClustering {
+        generated_at: Utc::now(),
+        documents: documents.len(),
+        clusters,
+    }
+}
+
+async fn label_cluster(llm: &LLMManager, model: &str, cluster: &TopicCluster) -> Result<String> {
+    let titles: Vec<&str> = cluster.members.iter().take(5).map(|m| m.title.as_str()).collect();
+    let request = GenerateRequest {
+        model: model.to_string(),
+        prompt: format!(
+            "These documents from a legal production were grouped together.\nDistinctive terms: {}\nExample titles: {}\n\nName the common theme in at most five words. Reply with the name only.",
+            cluster.terms.join(", "),
+            titles.join("; ")
+        ),
+        stream: Some(false),
+        options: Some(GenerateOptions {
+            temperature: Some(0.2),
+            ..Default::default()
+        }),
+        system: None,
+        template: None,
+        context: None,
+        raw: None,
+    };
+    let response = llm.generate_response(request).await?;
+    let label = response
+        .response
+        .lines()
+        .map(|line| line.trim().trim_matches(|c| c == '"' || c == '.' || c == '*'))
+        .find(|line| !line.is_empty())
+        .ok_or_else(|| anyhow!("Model returned an empty label"))?;
+    Ok(label.to_string())
+}
+
+/// Append-only log of document vectors; the last entry for a document wins
+pub struct DocumentVectorLog {
+    path: PathBuf,
+}
+
+impl DocumentVectorLog {
+    pub fn new(app_data_dir: &Path) -> Self {
+        DocumentVectorLog {
+            path: app_data_dir.join("document_vectors.jsonl"),
+        }
+    }
+
+    pub fn append(&self, vector: &DocumentVector) -> Result<()> {
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(vector)?)?;
+        Ok(())
+    }
+
+    pub fn load(&self) -> Result<Vec<DocumentVector>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let mut latest: Vec<DocumentVector> = Vec::new();
+        let mut position: HashMap<String, usize> = HashMap::new();
+        for line in fs::read_to_string(&self.path)?.lines().filter(|l| !l.trim().is_empty()) {
+            let vector: DocumentVector = serde_json::from_str(line)?;
+            match position.get(&vector.document_id) {
+                Some(&i) => latest[i] = vector,
+                None => {
+                    position.insert(vector.document_id.clone(), latest.len());
+                    latest.push(vector);
+                }
+            }
+        }
+        Ok(latest)
+    }
+
+    /// Rewrite the log without a deleted document
+    pub fn remove(&self, document_id: &str) -> Result<()> {
+        let entries = self.load()?;
+        if !entries.iter().any(|e| e.document_id == document_id) {
+            return Ok(());
+        }
+        let mut log = String::new();
+        for entry in entries.iter().filter(|e| e.document_id != document_id) {
+            log.push_str(&serde_json::to_string(entry)?);
+            log.push('\n');
+        }
+        fs::write(&self.path, log)?;
+        Ok(())
+    }
+}
+
+lazy_static! {
+    static ref GLOBAL_VECTOR_LOG: RwLock<Option<Arc<DocumentVectorLog>>> = RwLock::new(None);
+}
+
+/// Initialize the global document vector log written during indexing
+pub fn initialize_document_vectors(app_data_dir: &Path) -> Arc<DocumentVectorLog> {
+    let log = Arc::new(DocumentVectorLog::new(app_data_dir));
+    *GLOBAL_VECTOR_LOG.write().unwrap() = Some(log.clone());
+    log
+}
+
+/// Record an indexed document; ignored when the log is not initialized
+pub fn record_indexed_document(
+    collection: &str,
+    embedding_model: &str,
+    document: &LegalDocument,
+    chunks: &[RAGChunk],
+) -> Result<()> {
+    let Some(log) = GLOBAL_VECTOR_LOG.read().unwrap().clone() else {
+        return Ok(());
+    };
+    match document_vector(collection, embedding_model, document, chunks) {
+        Some(vector) => log.append(&vector),
+        None => Ok(()),
+    }
+}
+
+/// Drop a deleted document from the log; ignored when the log is not initialized
+pub fn remove_indexed_document(document_id: &str) -> Result<()> {
+    match GLOBAL_VECTOR_LOG.read().unwrap().clone() {
+        Some(log) => log.remove(document_id),
+        None => Ok(()),
+    }
+}
+
+/// Clusters of the indexed corpus. The vector log is re-read on every build so documents indexed
+/// since the last build are included.
+pub struct CorpusTopics {
+    log: DocumentVectorLog,
+    path: PathBuf,
+    latest: Mutex<Option<CorpusClustering>>,
+}
+
+impl CorpusTopics {
+    pub fn new(app_data_dir: &Path) -> Self {
+        let path = app_data_dir.join("corpus_clusters.json");
+        let latest = fs::read_to_string(&path)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok());
+        CorpusTopics {
+            log: DocumentVectorLog::new(app_data_dir),
+            path,
+            latest: Mutex::new(latest),
+        }
+    }
+
+    /// Cluster the corpus and label the clusters; labels fall back to terms without a model
+    pub async fn build(&self, k: Option<usize>, model: Option<String>, llm: &LLMManager) -> Result<CorpusClustering> {
+        let documents = self.log.load()?;
+        if documents.is_empty() {
+            return Err(anyhow!("No indexed documents to cluster"));
+        }
+        check_embeddings(&documents)?;
+        let mut clustering = cluster_documents(&documents, k);
+
+        let model = match model {
+            Some(model) => Some(model),
+            None => llm.default_generation_model().await,
+        };
+        if let Some(model) = model {
+            for cluster in clustering.clusters.iter_mut() {
+                match label_cluster(llm, &model, cluster).await {
+                    Ok(label) => {
+                        cluster.label = label;
+                        cluster.labelled_by_model = true;
+                    }
+                    Err(e) => log::warn!("Cluster {} keeps its term label: {}", cluster.id, e),
+                }
+            }
+        }
+
+        fs::write(&self.path, serde_json::to_string_pretty(&clustering)?)?;
+        *self.latest.lock().unwrap() = Some(clustering.clone());
+        Ok(clustering)
+    }
+
+    /// Forget a deleted document: its vector, and its place in the last build, whose clusters
+    /// otherwise keep listing it until the next build
+    pub fn remove_document(&self, document_id: &str) -> Result<()> {
+        self.log.remove(document_id)?;
+        let mut latest = self.latest.lock().unwrap();
+        let Some(clustering) = latest.as_mut() else {
+            return Ok(());
+        };
+        let before = clustering.clusters.iter().map(|c| c.members.len()).sum::<usize>();
+        for cluster in clustering.clusters.iter_mut() {
+            cluster.members.retain(|m| m.document_id != document_id);
+            cluster.size = cluster.members.len();
+        }
+        clustering.clusters.retain(|c| !c.members.is_empty());
+        if clustering.clusters.iter().map(|c| c.members.len()).sum::<usize>() == before {
+            return Ok(());
+        }
+        clustering.documents = clustering.documents.saturating_sub(1);
+        fs::write(&self.path, serde_json::to_string_pretty(&*clustering)?)?;
+        Ok(())
+    }
+
+    /// Clusters as the reader sees them: only the members `admit` lets through, and no cluster
+    /// without any
+    fn visible(&self, admit: &dyn Fn(&str) -> bool) -> Vec<TopicCluster> {
+        self.latest
+            .lock()
+            .unwrap()
+            .iter()
+            .flat_map(|clustering| &clustering.clusters)
+            .filter_map(|c| {
+                let members: Vec<ClusterMember> = c.members.iter().filter(|m| admit(&m.document_id)).cloned().collect();
+                (!members.is_empty()).then(|| TopicCluster {
+                    size: members.len(),
+                    members,
+                    ..c.clone()
+                })
+            })
+            .collect()
+    }
+
+    pub fn list(&self, admit: &dyn Fn(&str) -> bool) -> Vec<ClusterSummary> {
+        self.visible(admit)
+            .into_iter()
+            .map(|c| ClusterSummary {
+                id: c.id,
+                label: c.label,
+                terms: c.terms,
+                size: c.size,
+                cohesion: c.cohesion,
+            })
+            .collect()
+    }
+
+    pub fn get(&self, cluster_id: usize, admit: &dyn Fn(&str) -> bool) -> Option<TopicCluster> {
+        self.visible(admit).into_iter().find(|c| c.id == cluster_id)
+    }
+
+    /// Indexed documents closest to an indexed document, e.g. the nearest precedent for a new
+    /// draft; documents `admit` holds back are neither matched against nor returned
+    pub fn similar_documents(
+        &self,
+        document_id: &str,
+        top_k: usize,
+        filter: &SimilarityFilter,
+        admit: &dyn Fn(&str) -> bool,
+    ) -> Result<Vec<SimilarDocument>> {
+        let mut documents = self.log.load()?;
+        check_embeddings(&documents)?;
+        documents.retain(|d| admit(&d.document_id));
+        let query = documents
+            .iter()
+            .find(|d| d.document_id == document_id)
+            .ok_or_else(|| anyhow!("Document {} has no stored embedding; index it first", document_id))?;
+        Ok(rank_similar(query, &documents, top_k, filter))
+    }
+
+    /// Cluster a document was placed in by the last build
+    pub fn cluster_of(&self, document_id: &str, admit: &dyn Fn(&str) -> bool) -> Option<ClusterSummary> {
+        if !admit(document_id) {
+            return None;
+        }
+        let id = self
+            .latest
+            .lock()
+            .unwrap()
+            .as_ref()?
+            .clusters
+            .iter()
+            .find(|c| c.members.iter().any(|m| m.document_id == document_id))?
+            .id;
+        self.list(admit).into_iter().find(|c| c.id == id)
+    }
+}
+
+pub type CorpusTopicsStorage = Arc<CorpusTopics>;
+
+/// Run `f` with the documents the session's user may see, auditing what a barrier held back
+fn with_scope<T>(
+    session_id: &str,
+    sessions: &SessionStorage,
+    acl: &DocumentAclStorage,
+    barriers: &BarrierStorage,
+    security: &Arc<Mutex<SecurityManager>>,
+    attempt: &str,
+    f: impl FnOnce(&dyn Fn(&str) -> bool) -> T,
+) -> Result<T, String> {
+    let scope = RetrievalScope::for_session(session_id, sessions, acl, barriers, security)?;
+    let screened = ScreenedDocuments::default();
+    let result = f(&|document_id: &str| scope.admit_document(document_id, None, &screened));
+    scope.audit(screened, attempt);
+    Ok(result)
+}
+
+#[tauri::command]
+pub async fn corpus_build_clusters(
+    session_id: String,
+    k: Option<usize>,
+    model: Option<String>,
+    topics: tauri::State<'_, CorpusTopicsStorage>,
+    llm: tauri::State<'_, Arc<LLMManager>>,
+    acl: tauri::State<'_, DocumentAclStorage>,
+    sessions: tauri::State<'_, SessionStorage>,
+    barriers: tauri::State<'_, BarrierStorage>,
+    security: tauri::State<'_, Arc<Mutex<SecurityManager>>>,
+) -> Result<Vec<ClusterSummary>, String> {
+    authenticated_user(&session_id, &sessions)?;
+    topics.build(k, model, &llm).await.map_err(|e| e.to_string())?;
+    with_scope(&session_id, &sessions, &acl, &barriers, &security, "topic clusters", |admit| topics.list(admit))
+}
+
+#[tauri::command]
+pub async fn corpus_list_clusters(
+    session_id: String,
+    topics: tauri::State<'_, CorpusTopicsStorage>,
+    acl: tauri::State<'_, DocumentAclStorage>,
+    sessions: tauri::State<'_, SessionStorage>,
+    barriers: tauri::State<'_, BarrierStorage>,
+    security: tauri::State<'_, Arc<Mutex<SecurityManager>>>,
+) -> Result<Vec<ClusterSummary>, String> {
+    with_scope(&session_id, &sessions, &acl, &barriers, &security, "topic clusters", |admit| topics.list(admit))
+}
+
+#[tauri::command]
+pub async fn corpus_get_cluster(
+    session_id: String,
+    cluster_id: usize,
+    topics: tauri::State<'_, CorpusTopicsStorage>,
+    acl: tauri::State<'_, DocumentAclStorage>,
+    sessions: tauri::State<'_, SessionStorage>,
+    barriers: tauri::State<'_, BarrierStorage>,
+    security: tauri::State<'_, Arc<Mutex<SecurityManager>>>,
+) -> Result<TopicCluster, String> {
+    with_scope(&session_id, &sessions, &acl, &barriers, &security, "topic clusters", |admit| topics.get(cluster_id, admit))?
+        .ok_or_else(|| format!("Cluster {} not found", cluster_id))
+}
+
+#[tauri::command]
+pub async fn corpus_document_cluster(
+    session_id: String,
+    document_id: String,
+    topics: tauri::State<'_, CorpusTopicsStorage>,
+    acl: tauri::State<'_, DocumentAclStorage>,
+    sessions: tauri::State<'_, SessionStorage>,
+    barriers: tauri::State<'_, BarrierStorage>,
+    security: tauri::State<'_, Arc<Mutex<SecurityManager>>>,
+) -> Result<Option<ClusterSummary>, String> {
+    with_scope(&session_id, &sessions, &acl, &barriers, &security, "topic clusters", |admit| {
+        topics.cluster_of(&document_id, admit)
+    })
+}
+
+#[tauri::command]
+pub async fn find_similar_documents(
+    session_id: String,
+    document_id: String,
+    top_k: Option<usize>,
+    collection: Option<String>,
+    document_types: Option<Vec<DocumentType>>,
+    topics: tauri::State<'_, CorpusTopicsStorage>,
+    acl: tauri::State<'_, DocumentAclStorage>,
+    sessions: tauri::State<'_, SessionStorage>,
+    barriers: tauri::State<'_, BarrierStorage>,
+    security: tauri::State<'_, Arc<Mutex<SecurityManager>>>,
+) -> Result<Vec<SimilarDocument>, String> {
+    let filter = SimilarityFilter {
+        collection,
+        document_types,
+    };
+    let top_k = top_k.unwrap_or(DEFAULT_SIMILAR_DOCUMENTS);
+    with_scope(&session_id, &sessions, &acl, &barriers, &security, "similar documents", |admit| {
+        topics.similar_documents(&document_id, top_k, &filter, admit)
+    })?
+    .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document(id: &str, embedding: Vec<f32>, terms: &[&str]) -> DocumentVector {
+        let mut embedding = embedding;
+        normalize(&mut embedding);
+        DocumentVector {
+            document_id: id.to_string(),
+            title: format!("Document {}", id),
+            collection: "legal_chunks".to_string(),
+            document_type: Some(DocumentType::Contract),
+            language: "en".to_string(),
+            embedding_model: "nomic-embed-text".to_string(),
+            embedding,
+            terms: terms.iter().map(|t| (t.to_string(), 3)).collect(),
+            indexed_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_clusters_group_similar_documents() {
+        let documents = vec![
+            document("lease-1", vec![1.0, 0.1, 0.0], &["tenant", "rent", "premises", "notice"]),
+            document("lease-2", vec![0.9, 0.2, 0.0], &["tenant", "rent", "deposit", "notice"]),
+            document("lease-3", vec![1.0, 0.0, 0.1], &["landlord", "rent", "premises", "notice"]),
+            document("email-1", vec![0.0, 0.1, 1.0], &["invoice", "payment", "overdue", "notice"]),
+            document("email-2", vec![0.1, 0.0, 0.9], &["invoice", "payment", "reminder", "notice"]),
+        ];
+        let clustering = cluster_documents(&documents, Some(2));
+
+        assert_eq!(clustering.documents, 5);
+        assert_eq!(clustering.clusters.len(), 2);
+        let leases = &clustering.clusters[0];
+        assert_eq!(leases.id, 1);
+        assert_eq!(leases.size, 3);
+        assert!(leases.members.iter().all(|m| m.document_id.starts_with("lease")));
+        assert_eq!(&leases.terms[..3], &["rent".to_string(), "premises".to_string(), "tenant".to_string()]);
+        // A term every document shares says nothing about the cluster
+        assert_ne!(leases.terms[0], "notice");
+        assert_eq!(clustering.clusters[1].label, "invoice, payment, overdue");
+    }
+
+    #[test]
+    fn test_similar_documents_respect_filters() {
+        let mut brief = document("brief", vec![0.95, 0.05, 0.0], &["indemnity", "supplier", "brief"]);
+        brief.document_type = Some(DocumentType::Brief);
+        let documents = vec![
+            document("draft", vec![1.0, 0.0, 0.0], &["indemnity", "supplier", "liability"]),
+            document("msa-2019", vec![0.9, 0.1, 0.0], &["supplier", "liability", "warranty"]),
+            document("nda", vec![0.2, 1.0, 0.0], &["confidential", "disclosure"]),
+            brief,
+        ];
+
+        let all = rank_similar(&documents[0], &documents, 10, &SimilarityFilter::default());
+        let ids: Vec<&str> = all.iter().map(|d| d.document_id.as_str()).collect();
+        assert_eq!(ids, vec!["brief", "msa-2019", "nda"]);
+        assert_eq!(all[0].shared_terms, vec!["indemnity", "supplier"]);
+
+        let contracts = SimilarityFilter {
+            collection: Some("legal_chunks".to_string()),
+            document_types: Some(vec![DocumentType::Contract]),
+        };
+        let precedent = rank_similar(&documents[0], &documents, 1, &contracts);
+        assert_eq!(precedent.len(), 1);
+        assert_eq!(precedent[0].document_id, "msa-2019");
+        assert_eq!(precedent[0].shared_terms, vec!["supplier", "liability"]);
+
+        let other_collection = SimilarityFilter {
+            collection: Some("matter_42".to_string()),
+            document_types: None,
+        };
+        assert!(rank_similar(&documents[0], &documents, 10, &other_collection).is_empty());
+    }
+
+    #[test]
+    fn test_vector_log_keeps_latest_entry() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = DocumentVectorLog::new(dir.path());
+        assert!(log.load().unwrap().is_empty());
+
+        log.append(&document("a", vec![1.0, 0.0], &["rent"])).unwrap();
+        log.append(&document("b", vec![0.0, 1.0], &["invoice"])).unwrap();
+        log.append(&document("a", vec![0.0, 1.0], &["payment"])).unwrap();
+
+        let loaded = log.load().unwrap();
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded[0].document_id, "a");
+        assert_eq!(loaded[0].terms[0].0, "payment");
+        assert_eq!(default_cluster_count(2), 1);
+        assert_eq!(default_cluster_count(5000), MAX_CLUSTERS);
+
+        log.remove("a").unwrap();
+        assert_eq!(log.load().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_mixed_embedding_models_are_refused() {
+        let mut documents = vec![
+            document("lease", vec![1.0, 0.0], &["rent"]),
+            document("invoice", vec![0.0, 1.0], &["payment"]),
+        ];
+        documents[1].embedding_model = String::new();
+        assert!(check_embeddings(&documents).is_ok());
+
+        documents[1].embedding_model = "bge-small".to_string();
+        assert!(check_embeddings(&documents).unwrap_err().to_string().contains("bge-small"));
+
+        documents[1] = document("invoice", vec![0.0, 1.0, 0.0], &["payment"]);
+        assert!(check_embeddings(&documents).unwrap_err().to_string().contains("dimensions"));
+    }
+
+    #[test]
+    fn test_removed_document_leaves_the_last_build() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = DocumentVectorLog::new(dir.path());
+        log.append(&document("lease", vec![1.0, 0.0], &["rent"])).unwrap();
+        log.append(&document("invoice", vec![0.0, 1.0], &["payment"])).unwrap();
+        let clustering = cluster_documents(&log.load().unwrap(), Some(2));
+        fs::write(dir.path().join("corpus_clusters.json"), serde_json::to_string(&clustering).unwrap()).unwrap();
+
+        let topics = CorpusTopics::new(dir.path());
+        let everyone = |_: &str| true;
+        assert_eq!(topics.list(&everyone).len(), 2);
+        topics.remove_document("lease").unwrap();
+        assert!(topics.cluster_of("lease", &everyone).is_none());
+        assert_eq!(topics.list(&everyone).len(), 1);
+        assert!(topics.similar_documents("lease", 5, &SimilarityFilter::default(), &everyone).is_err());
+
+        // The pruned build is what a restarted app loads
+        let reopened = CorpusTopics::new(dir.path());
+        assert_eq!(reopened.latest.lock().unwrap().as_ref().unwrap().documents, 1);
+    }
+}
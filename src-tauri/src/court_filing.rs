@@ -0,0 +1,401 @@
+use chrono::NaiveDate;
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::case_analytics::labelled_date;
+use crate::local_api::AnalyzerStorage;
+use crate::nemotron_rag::DocumentMetadata;
+
+/// Court Filing Metadata for BEAR AI
+/// Reads the caption block at the top of briefs, motions, orders and other filings: the court,
+/// the parties and their roles, the docket number, the kind of filing and the date it was filed.
+/// Indexing copies what is found into the document's metadata and onto every chunk, so retrieval
+/// can be narrowed to one court or one docket.
+// Captions sit at the top of the first page
+const CAPTION_LINES: usize = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FilingType {
+    Complaint,
+    Answer,
+    Motion,
+    Brief,
+    Memorandum,
+    Petition,
+    Order,
+    Judgment,
+    Opinion,
+    Notice,
+    Stipulation,
+    Affidavit,
+    Subpoena,
+}
+
+impl FilingType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FilingType::Complaint => "complaint",
+            FilingType::Answer => "answer",
+            FilingType::Motion => "motion",
+            FilingType::Brief => "brief",
+            FilingType::Memorandum => "memorandum",
+            FilingType::Petition => "petition",
+            FilingType::Order => "order",
+            FilingType::Judgment => "judgment",
+            FilingType::Opinion => "opinion",
+            FilingType::Notice => "notice",
+            FilingType::Stipulation => "stipulation",
+            FilingType::Affidavit => "affidavit",
+            FilingType::Subpoena => "subpoena",
+        }
+    }
+}
+
+// Title words and the filing they make; the earliest in the title wins, so "ORDER GRANTING
+// MOTION TO DISMISS" is an order and "OPPOSITION TO MOTION TO DISMISS" a brief. Longer phrases
+// come first so they win over their first word.
+const FILING_TITLES: &[(&str, FilingType)] = &[
+    ("memorandum of law", FilingType::Brief),
+    ("memorandum of points and authorities", FilingType::Brief),
+    ("memorandum opinion", FilingType::Opinion),
+    ("memorandum and order", FilingType::Order),
+    ("opinion and order", FilingType::Opinion),
+    ("brief", FilingType::Brief),
+    ("reply", FilingType::Brief),
+    ("opposition", FilingType::Brief),
+    ("complaint", FilingType::Complaint),
+    ("counterclaim", FilingType::Complaint),
+    ("answer", FilingType::Answer),
+    ("motion", FilingType::Motion),
+    ("application", FilingType::Motion),
+    ("petition", FilingType::Petition),
+    ("order", FilingType::Order),
+    ("judgment", FilingType::Judgment),
+    ("judgement", FilingType::Judgment),
+    ("decree", FilingType::Judgment),
+    ("opinion", FilingType::Opinion),
+    ("notice", FilingType::Notice),
+    ("stipulation", FilingType::Stipulation),
+    ("declaration", FilingType::Affidavit),
+    ("affidavit", FilingType::Affidavit),
+    ("subpoena", FilingType::Subpoena),
+    ("memorandum", FilingType::Memorandum),
+];
+
+lazy_static! {
+    static ref FILING_TITLE: Regex = Regex::new(&format!(
+        r"\b(?:{})\b",
+        FILING_TITLES.iter().map(|(word, _)| *word).collect::<Vec<_>>().join("|")
+    ))
+    .unwrap();
+}
+
+pub(crate) const CLAIMANT_ROLES: &[&str] = &["Plaintiff", "Claimant", "Petitioner", "Appellant"];
+pub(crate) const DEFENDANT_ROLES: &[&str] = &["Defendant", "Respondent", "Appellee"];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CaptionParty {
+    pub name: String,
+    pub role: Option<String>, // "Plaintiff", "Respondent"; None for a bare "A v. B" caption
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FilingMetadata {
+    pub court: Option<String>,
+    pub docket_number: Option<String>,
+    pub filing_type: Option<FilingType>,
+    pub filing_title: Option<String>, // the title line, e.g. "DEFENDANT'S MOTION TO DISMISS"
+    pub filing_date: Option<NaiveDate>,
+    pub caption: Option<String>,      // "Acme Ltd v. Beta Corp"
+    pub parties: Vec<CaptionParty>,
+}
+
+impl FilingMetadata {
+    /// Enough of a caption to treat the document as a filing; a court name or title word alone
+    /// also turns up in contracts and letters
+    pub fn is_filing(&self) -> bool {
+        self.docket_number.is_some() || (!self.parties.is_empty() && (self.court.is_some() || self.filing_type.is_some()))
+    }
+
+    /// Fill the court and parties of indexed-document metadata the caller left empty
+    pub fn apply_to(&self, metadata: &mut DocumentMetadata) {
+        if metadata.court.is_none() {
+            metadata.court = self.court.clone();
+        }
+        if metadata.parties.is_empty() {
+            metadata.parties = self.parties.iter().map(|p| p.name.clone()).collect();
+        }
+    }
+
+    /// Chunk metadata used by the court and docket filters of retrieval
+    pub fn chunk_metadata(&self) -> HashMap<String, String> {
+        let mut metadata = HashMap::new();
+        let mut put = |key: &str, value: Option<String>| {
+            if let Some(value) = value {
+                metadata.insert(key.to_string(), value);
+            }
+        };
+        put("court", self.court.clone());
+        put("docket_number", self.docket_number.clone());
+        put("filing_type", self.filing_type.map(|t| t.as_str().to_string()));
+        put("filing_date", self.filing_date.map(|d| d.format("%Y-%m-%d").to_string()));
+        put("caption", self.caption.clone());
+        metadata
+    }
+}
+
+/// Capitalised runs can start in a heading or the previous sentence; keep only the last part
+pub(crate) fn trim_to_sentence(name: &str) -> String {
+    let start = name.rfind(['\n', '\r']).map(|i| i + 1).unwrap_or(0);
+    let name = &name[start..];
+    let start = name.rfind(". ").map(|i| i + 2).unwrap_or(0);
+    name[start..].trim().trim_end_matches(',').to_string()
+}
+
+/// Acme Ltd, Plaintiff / Beta Corp., Defendants, as (role, name)
+pub(crate) fn caption_parties(text: &str) -> Vec<(String, String)> {
+    let caption = Regex::new(
+        r"([A-Z][\w&.'-]*(?:\s+[A-Z][\w&.'-]*){0,5}),\s+(Plaintiff|Claimant|Petitioner|Appellant|Defendant|Respondent|Appellee)s?\b",
+    )
+    .unwrap();
+    caption
+        .captures_iter(text)
+        .map(|caps| (caps[2].to_string(), trim_to_sentence(&caps[1])))
+        .collect()
+}
+
+fn collapse(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn mostly_uppercase(line: &str) -> bool {
+    let letters: Vec<char> = line.chars().filter(|c| c.is_alphabetic()).collect();
+    letters.len() >= 3 && letters.iter().filter(|c| c.is_uppercase()).count() * 10 >= letters.len() * 8
+}
+
+/// "UNITED STATES DISTRICT COURT" with the district or county lines that complete it
+fn extract_court(lines: &[&str]) -> Option<String> {
+    let court_word = Regex::new(r"(?i)\b(court|tribunal)\b").unwrap();
+    let continuation = Regex::new(
+        r"^(?:FOR|OF|IN AND FOR|AT|STATE OF|COUNTY OF|DISTRICT OF|(?:EASTERN|WESTERN|NORTHERN|SOUTHERN|CENTRAL|MIDDLE) DISTRICT|[A-Z ]+ DIVISION|[A-Z ]+ CIRCUIT)\b",
+    )
+    .unwrap();
+    let start = lines.iter().position(|line| {
+        line.len() < 120
+            && court_word.is_match(line)
+            && (mostly_uppercase(line) || line.to_lowercase().starts_with("in the "))
+            && filing_title(line).map_or(true, |(at, _)| at > 0)
+    })?;
+    let mut court = lines[start].to_string();
+    for line in lines[start + 1..].iter().take(3) {
+        if line.is_empty() || line.contains(',') || !mostly_uppercase(line) || !continuation.is_match(line) {
+            break;
+        }
+        let joiner = if ["FOR ", "OF ", "IN ", "AT "].iter().any(|p| line.starts_with(p)) { " " } else { ", " };
+        court.push_str(joiner);
+        court.push_str(line);
+    }
+    let court = Regex::new(r"(?i)^in the\s+").unwrap().replace(&court, "");
+    Some(collapse(&court).trim_end_matches(['.', ',', ';']).to_string())
+}
+
+fn extract_docket(head: &str) -> Option<String> {
+    // Electronic filing stamp: "Case 1:23-cv-04567-JMF Document 12 Filed 03/04/24 Page 1 of 20"
+    let stamp = Regex::new(r"(?m)^\s*Case\s+(\S*\d\S*)\s+Document\s+\d+").unwrap();
+    let labelled = Regex::new(
+        r"(?im)(?:\b(?:case|civil action|criminal action|docket|cause|index|appeal|claim)\s+(?:no\.?|number|#)|^\s*no\.)\s*:?\s*([A-Z0-9][\w:./-]*\d[\w:./-]*)",
+    )
+    .unwrap();
+    stamp
+        .captures(head)
+        .or_else(|| labelled.captures(head))
+        .map(|caps| caps[1].trim_end_matches(['.', ',', ';', ')']).to_string())
+}
+
+/// Position of the earliest title word in a line and the filing it makes
+fn filing_title(line: &str) -> Option<(usize, FilingType)> {
+    let lower = line.to_lowercase();
+    let found = FILING_TITLE.find(&lower)?;
+    FILING_TITLES
+        .iter()
+        .find(|(word, _)| *word == found.as_str())
+        .map(|(_, filing_type)| (found.start(), *filing_type))
+}
+
+fn extract_filing_type(lines: &[&str], court: Option<&str>) -> Option<(FilingType, String)> {
+    let role = Regex::new(r"(?i)\b(plaintiff|claimant|petitioner|appellant|defendant|respondent|appellee)s?\b\s*[,.]?\s*$").unwrap();
+    let docket = Regex::new(r"(?i)\b(case|docket|civil action)\s+(no|number)\b|^\s*no\.").unwrap();
+    lines
+        .iter()
+        .filter(|line| court.map_or(true, |court| !court.contains(*line)))
+        .filter(|line| !role.is_match(line) && !docket.is_match(line))
+        .find_map(|line| {
+            let (at, filing_type) = filing_title(line)?;
+            // A title is set in capitals, or is a short line that starts with the title word
+            let title = mostly_uppercase(line) || (line.len() <= 100 && !line.ends_with('.') && at < 20);
+            title.then(|| (filing_type, collapse(line)))
+        })
+}
+
+fn extract_parties(head: &str) -> Vec<CaptionParty> {
+    let mut parties: Vec<CaptionParty> = Vec::new();
+    for (role, name) in caption_parties(head) {
+        if !name.is_empty() && !parties.iter().any(|p| p.name.eq_ignore_ascii_case(&name)) {
+            parties.push(CaptionParty { name, role: Some(role) });
+        }
+    }
+    if parties.is_empty() {
+        // Appellate and short-form captions: "ACME LTD v. BETA CORP"
+        let versus = Regex::new(r"(?m)^\s*([A-Z][^\n]{1,80}?),?\s+(?:v\.?|vs\.?)\s+([A-Z][^\n]{1,80}?)[,.]?\s*$").unwrap();
+        if let Some(caps) = versus.captures(head) {
+            for name in [&caps[1], &caps[2]] {
+                parties.push(CaptionParty { name: collapse(name), role: None });
+            }
+        }
+    }
+    parties
+}
+
+fn caption(parties: &[CaptionParty]) -> Option<String> {
+    let with_role = |roles: &[&str]| parties.iter().find(|p| p.role.as_deref().is_some_and(|r| roles.contains(&r)));
+    match (with_role(CLAIMANT_ROLES), with_role(DEFENDANT_ROLES)) {
+        (Some(claimant), Some(defendant)) => Some(format!("{} v. {}", claimant.name, defendant.name)),
+        (None, None) if parties.len() > 1 => Some(format!("{} v. {}", parties[0].name, parties[1].name)),
+        _ => parties.first().map(|p| p.name.clone()),
+    }
+}
+
+fn extract_filing_date(head: &str, text: &str) -> Option<NaiveDate> {
+    let stamp = Regex::new(r"(?m)^\s*Case\s+\S+\s+Document\s+\d+\s+Filed\s+(\d{1,2}/\d{1,2}/\d{2,4})").unwrap();
+    if let Some(caps) = stamp.captures(head) {
+        let date = ["%m/%d/%y", "%m/%d/%Y"]
+            .iter()
+            .find_map(|format| NaiveDate::parse_from_str(&caps[1], format).ok());
+        if date.is_some() {
+            return date;
+        }
+    }
+    labelled_date(text, "filed|date filed|filing date|submitted")
+        .or_else(|| labelled_date(text, "dated|entered|signed"))
+}
+
+/// Caption metadata of a brief, motion, order or other filing; fields stay empty where the text
+/// does not state them
+pub fn extract_filing_metadata(text: &str) -> FilingMetadata {
+    let lines: Vec<&str> = text.lines().map(str::trim).take(CAPTION_LINES).collect();
+    let head = lines.join("\n");
+    let court = extract_court(&lines);
+    let filing = extract_filing_type(&lines, court.as_deref());
+    let parties = extract_parties(&head);
+    FilingMetadata {
+        docket_number: extract_docket(&head),
+        filing_type: filing.as_ref().map(|(filing_type, _)| *filing_type),
+        filing_title: filing.map(|(_, title)| title),
+        filing_date: extract_filing_date(&head, text),
+        caption: caption(&parties),
+        court,
+        parties,
+    }
+}
+
+fn docket_key(docket: &str) -> String {
+    docket.chars().filter(|c| c.is_alphanumeric()).collect::<String>().to_lowercase()
+}
+
+/// Whether chunk metadata matches the court and docket filters of a query. The court matches on
+/// any part of its name; a docket matches with or without the judge's initials after it.
+pub fn matches_filing_filter(metadata: &HashMap<String, String>, court: Option<&str>, docket_number: Option<&str>) -> bool {
+    let court_matches = court.map_or(true, |court| {
+        metadata
+            .get("court")
+            .is_some_and(|found| found.to_lowercase().contains(&court.trim().to_lowercase()))
+    });
+    let docket_matches = docket_number.map_or(true, |docket| {
+        metadata
+            .get("docket_number")
+            .is_some_and(|found| docket_key(found).starts_with(&docket_key(docket)))
+    });
+    court_matches && docket_matches
+}
+
+/// Caption metadata of a filing on disk
+#[tauri::command]
+pub async fn extract_filing_metadata_from_file(
+    path: String,
+    analyzer: tauri::State<'_, AnalyzerStorage>,
+) -> Result<FilingMetadata, String> {
+    let text = analyzer.extract_text(Path::new(&path)).await.map_err(|e| e.to_string())?;
+    Ok(extract_filing_metadata(&text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extracts_district_court_caption() {
+        let text = "Case 1:23-cv-04567-JMF Document 12 Filed 03/04/24 Page 1 of 20\n\
+                    UNITED STATES DISTRICT COURT\n\
+                    SOUTHERN DISTRICT OF NEW YORK\n\n\
+                    ACME LTD,\n\
+                    Plaintiff,\n\
+                    v.\n\
+                    BETA CORP.,\n\
+                    Defendant.\n\n\
+                    DEFENDANTS' MEMORANDUM OF LAW IN SUPPORT OF THEIR MOTION TO DISMISS\n\n\
+                    Defendants respectfully submit this memorandum. The order of events is set out below.";
+        let filing = extract_filing_metadata(text);
+
+        assert_eq!(filing.court.as_deref(), Some("UNITED STATES DISTRICT COURT, SOUTHERN DISTRICT OF NEW YORK"));
+        assert_eq!(filing.docket_number.as_deref(), Some("1:23-cv-04567-JMF"));
+        assert_eq!(filing.filing_type, Some(FilingType::Brief));
+        assert_eq!(filing.filing_date, NaiveDate::from_ymd_opt(2024, 3, 4));
+        assert_eq!(filing.caption.as_deref(), Some("ACME LTD v. BETA CORP."));
+        assert!(filing.is_filing());
+
+        let mut metadata = DocumentMetadata {
+            court: None,
+            judge: None,
+            parties: Vec::new(),
+            topics: Vec::new(),
+            precedential_value: crate::nemotron_rag::PrecedentialValue::NotPrecedential,
+            confidence: 1.0,
+        };
+        filing.apply_to(&mut metadata);
+        assert_eq!(metadata.parties, vec!["ACME LTD".to_string(), "BETA CORP.".to_string()]);
+
+        let chunk = filing.chunk_metadata();
+        assert!(matches_filing_filter(&chunk, Some("southern district of new york"), Some("1:23-cv-04567")));
+        assert!(!matches_filing_filter(&chunk, None, Some("1:23-cv-09999")));
+        assert!(!matches_filing_filter(&HashMap::new(), Some("district court"), None));
+    }
+
+    #[test]
+    fn test_state_court_order_and_non_filings() {
+        let text = "IN THE SUPERIOR COURT OF THE STATE OF CALIFORNIA\n\
+                    IN AND FOR THE COUNTY OF SAN FRANCISCO\n\
+                    Case No. CGC-22-601234\n\
+                    Jane Roe, Petitioner,\n\
+                    v.\n\
+                    City Transit Authority, Respondent.\n\
+                    ORDER GRANTING PETITION FOR WRIT OF MANDATE\n\
+                    Dated: June 5, 2023";
+        let filing = extract_filing_metadata(text);
+        assert_eq!(
+            filing.court.as_deref(),
+            Some("SUPERIOR COURT OF THE STATE OF CALIFORNIA IN AND FOR THE COUNTY OF SAN FRANCISCO")
+        );
+        assert_eq!(filing.docket_number.as_deref(), Some("CGC-22-601234"));
+        assert_eq!(filing.filing_type, Some(FilingType::Order));
+        assert_eq!(filing.filing_date, NaiveDate::from_ymd_opt(2023, 6, 5));
+        assert_eq!(filing.caption.as_deref(), Some("Jane Roe v. City Transit Authority"));
+
+        let contract = "SUPPLY AGREEMENT\nThis agreement is made between Acme Ltd and Beta Corp. \
+                        The courts of Amsterdam have exclusive jurisdiction.";
+        assert!(!extract_filing_metadata(contract).is_filing());
+    }
+}
@@ -0,0 +1,725 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+use crate::corpus_topics::{default_cluster_count, spherical_kmeans};
+use crate::docx_writer::{self, DocxBlock};
+use crate::llm_manager::{GenerateOptions, GenerateRequest, LLMManager};
+use crate::local_api::{authenticated_user, AnalyzerStorage, SessionStorage};
+use crate::matters::{Matter, MatterStorage};
+use crate::provenance::{self, ProvenanceClaim, ProvenanceSource, ProvenanceStorage};
+use crate::review_queue::{self, ReviewQueueStorage, Submission, WorkProductKind};
+use crate::security::SecurityManager;
+use crate::text_processing::{self, truncate_chars, LanguageTools};
+
+/// Deposition Preparation for BEAR AI
+/// Gathers the witness's documents and prior statements from the matter, numbers the documents
+/// as exhibits and reads transcripts line by line so prior testimony can be cited by page:line.
+/// Passages are clustered into topics on their terms, and the local LLM drafts the questions for
+/// each topic from that topic's excerpts. Every exhibit and page:line reference in the draft is
+/// checked against the material actually read; references that do not resolve are flagged.
+const LINES_PER_PAGE: u32 = 25;
+/// Transcript lines grouped into one passage for clustering and citing
+const PASSAGE_LINES: usize = 10;
+const MAX_PASSAGE_CHARS: usize = 1_200;
+const EXCERPTS_PER_TOPIC: usize = 12;
+const TERMS_PER_TOPIC: usize = 6;
+
+/// One numbered line of a transcript
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TranscriptLine {
+    pub page: u32,
+    pub line: u32,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Exhibit {
+    pub number: u32,
+    pub title: String,
+    pub path: String,
+}
+
+/// A prior statement with page:line numbering, cited as "T1 12:4-9"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriorTestimony {
+    pub label: String,
+    pub title: String,
+    pub path: String,
+    pub pages: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PassageSource {
+    Exhibit { number: u32 },
+    Testimony { transcript: usize, start: (u32, u32), end: (u32, u32) },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Passage {
+    pub source: PassageSource,
+    pub cite: String, // "Ex. 3" or "T1 12:4-13:2"
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutlineQuestion {
+    pub text: String,
+    pub exhibits: Vec<u32>,
+    pub testimony: Vec<String>,  // page:line cites that resolve to a transcript line
+    pub unverified: Vec<String>, // references that match no exhibit or transcript line
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutlineTopic {
+    pub id: usize,
+    pub title: String,
+    pub terms: Vec<String>,
+    pub exhibits: Vec<u32>,
+    pub testimony: Vec<String>, // the topic's transcript passages
+    pub questions: Vec<OutlineQuestion>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DepositionOutline {
+    pub id: String,
+    pub matter_id: String,
+    pub witness: String,
+    pub exhibits: Vec<Exhibit>,
+    pub prior_testimony: Vec<PriorTestimony>,
+    pub topics: Vec<OutlineTopic>,
+    pub unverified_references: usize,
+    pub generated_at: DateTime<Utc>,
+    pub path: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DepositionPrepRequest {
+    pub matter_id: String,
+    pub witness: String,
+    pub paths: Option<Vec<String>>, // defaults to the matter documents that mention the witness
+    pub topics: Option<usize>,
+    pub goals: Option<String>, // what the examining lawyer wants from the deposition
+    pub model: Option<String>,
+    #[serde(default)]
+    pub provenance_sidecar: bool, // also write a provenance manifest next to the outline
+}
+
+/// Read numbered transcript lines. Pages start at "Page N" markers, bare page numbers or form
+/// feeds, and a line number that restarts at or below the previous one begins the next page.
+pub fn parse_transcript(text: &str) -> Vec<TranscriptLine> {
+    let page_marker = Regex::new(r"^\s*(?i:page)\s+(\d+)(?:\s+(?i:of)\s+\d+)?\s*$").unwrap();
+    let numbered = Regex::new(r"^\s*(\d{1,2})(?:\s+(.*))?$").unwrap();
+
+    let mut lines = Vec::new();
+    let mut page = 1;
+    let mut last_line = 0;
+    for raw in text.lines() {
+        let mut raw = raw;
+        if raw.contains('\u{c}') {
+            if last_line > 0 {
+                page += 1;
+                last_line = 0;
+            }
+            raw = raw.rsplit('\u{c}').next().unwrap_or("");
+        }
+        if let Some(caps) = page_marker.captures(raw) {
+            page = caps[1].parse().unwrap_or(page);
+            last_line = 0;
+            continue;
+        }
+        let Some(caps) = numbered.captures(raw) else { continue };
+        let number: u32 = caps[1].parse().unwrap_or(0);
+        let text = caps.get(2).map(|m| m.as_str().trim()).unwrap_or("");
+        if text.is_empty() && (number == 0 || number > LINES_PER_PAGE || number != last_line + 1) {
+            // A bare number out of sequence is the page number in the header
+            page = number.max(1);
+            last_line = 0;
+            continue;
+        }
+        if number == 0 || number > LINES_PER_PAGE {
+            continue;
+        }
+        if number <= last_line {
+            page += 1;
+        }
+        last_line = number;
+        lines.push(TranscriptLine {
+            page,
+            line: number,
+            text: text.to_string(),
+        });
+    }
+    lines
+}
+
+/// Text reads as a transcript when most of its lines carry line numbers
+pub fn is_transcript(text: &str, lines: &[TranscriptLine]) -> bool {
+    let non_empty = text.lines().filter(|l| !l.trim().is_empty()).count();
+    lines.len() >= LINES_PER_PAGE as usize && lines.len() * 2 > non_empty
+}
+
+pub fn format_cite(label: &str, start: (u32, u32), end: (u32, u32)) -> String {
+    if start == end {
+        format!("{} {}:{}", label, start.0, start.1)
+    } else if start.0 == end.0 {
+        format!("{} {}:{}-{}", label, start.0, start.1, end.1)
+    } else {
+        format!("{} {}:{}-{}:{}", label, start.0, start.1, end.0, end.1)
+    }
+}
+
+fn testimony_passages(transcript: usize, label: &str, lines: &[TranscriptLine]) -> Vec<Passage> {
+    lines
+        .iter()
+        .filter(|l| !l.text.is_empty())
+        .collect::<Vec<_>>()
+        .chunks(PASSAGE_LINES)
+        .map(|chunk| {
+            let start = (chunk[0].page, chunk[0].line);
+            let end = (chunk[chunk.len() - 1].page, chunk[chunk.len() - 1].line);
+            Passage {
+                source: PassageSource::Testimony { transcript, start, end },
+                cite: format_cite(label, start, end),
+                text: chunk.iter().map(|l| l.text.as_str()).collect::<Vec<_>>().join("\n"),
+            }
+        })
+        .collect()
+}
+
+/// Paragraphs of an exhibit, merged until a passage is long enough to carry a topic
+fn exhibit_passages(number: u32, text: &str) -> Vec<Passage> {
+    let mut passages: Vec<Passage> = Vec::new();
+    let mut current = String::new();
+    for paragraph in text.split("\n\n").map(str::trim).filter(|p| !p.is_empty()) {
+        if !current.is_empty() && current.len() + paragraph.len() > MAX_PASSAGE_CHARS {
+            passages.push(exhibit_passage(number, std::mem::take(&mut current)));
+        }
+        if !current.is_empty() {
+            current.push_str("\n\n");
+        }
+        current.push_str(paragraph);
+    }
+    if !current.is_empty() {
+        passages.push(exhibit_passage(number, current));
+    }
+    passages
+}
+
+fn exhibit_passage(number: u32, text: String) -> Passage {
+    Passage {
+        source: PassageSource::Exhibit { number },
+        cite: format!("Ex. {}", number),
+        text,
+    }
+}
+
+/// Topic clusters over tf-idf term vectors: (member passage indices, distinctive terms), largest first
+pub fn cluster_passages(passages: &[Passage], k: usize) -> Vec<(Vec<usize>, Vec<String>)> {
+    let language = text_processing::detect_language(
+        &passages.iter().take(20).map(|p| p.text.as_str()).collect::<Vec<_>>().join("\n"),
+    );
+    let tools = LanguageTools::for_language(&language);
+
+    let mut surface_forms: HashMap<String, HashMap<String, u32>> = HashMap::new();
+    let counts: Vec<HashMap<String, f32>> = passages
+        .iter()
+        .map(|passage| {
+            let mut counts: HashMap<String, f32> = HashMap::new();
+            for word in text_processing::words(&passage.text) {
+                if word.chars().count() > 3 && !tools.is_stop_word(&word) {
+                    let stem = tools.stem(&word);
+                    *surface_forms.entry(stem.clone()).or_default().entry(word).or_default() += 1;
+                    *counts.entry(stem).or_default() += 1.0;
+                }
+            }
+            counts
+        })
+        .collect();
+
+    // Terms found in a single passage cannot tie passages together
+    let mut document_frequency: HashMap<&str, usize> = HashMap::new();
+    for passage in &counts {
+        for term in passage.keys() {
+            *document_frequency.entry(term.as_str()).or_default() += 1;
+        }
+    }
+    let mut vocabulary: Vec<&str> = document_frequency.iter().filter(|(_, df)| **df > 1).map(|(t, _)| *t).collect();
+    vocabulary.sort_unstable();
+    let index: HashMap<&str, usize> = vocabulary.iter().enumerate().map(|(i, t)| (*t, i)).collect();
+    let idf = |term: &str| (passages.len() as f32 / document_frequency[term] as f32).ln() + 1.0;
+
+    let vectors: Vec<Vec<f32>> = counts
+        .iter()
+        .map(|passage| {
+            let mut vector = vec![0.0f32; vocabulary.len()];
+            for (term, count) in passage {
+                if let Some(i) = index.get(term.as_str()) {
+                    vector[*i] = count * idf(term);
+                }
+            }
+            let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+            if norm > 0.0 {
+                vector.iter_mut().for_each(|v| *v /= norm);
+            }
+            vector
+        })
+        .collect();
+    let (assignments, centroids) = spherical_kmeans(&vectors, k);
+
+    let surface = |stem: &str| {
+        surface_forms[stem]
+            .iter()
+            .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)))
+            .map(|(word, _)| word.clone())
+            .unwrap_or_else(|| stem.to_string())
+    };
+    let mut clusters: Vec<(Vec<usize>, Vec<String>)> = centroids
+        .iter()
+        .enumerate()
+        .filter_map(|(cluster, centroid)| {
+            let members: Vec<usize> = (0..passages.len()).filter(|i| assignments[*i] == cluster).collect();
+            if members.is_empty() {
+                return None;
+            }
+            let mut weights: Vec<(usize, f32)> = centroid.iter().copied().enumerate().filter(|(_, w)| *w > 0.0).collect();
+            weights.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            let terms = weights.iter().take(TERMS_PER_TOPIC).map(|(i, _)| surface(vocabulary[*i])).collect();
+            Some((members, terms))
+        })
+        .collect();
+    clusters.sort_by(|a, b| b.0.len().cmp(&a.0.len()).then_with(|| a.0.cmp(&b.0)));
+    clusters
+}
+
+fn topic_prompt(
+    witness: &str,
+    matter: &Matter,
+    goals: Option<&str>,
+    terms: &[String],
+    excerpts: &[&Passage],
+    exhibits: &[Exhibit],
+) -> String {
+    let mut prompt = format!(
+        "You are preparing to depose {} in the matter {}.\n",
+        witness, matter.name
+    );
+    if let Some(description) = &matter.description {
+        prompt.push_str(&format!("Matter description: {}\n", description));
+    }
+    if let Some(goals) = goals {
+        prompt.push_str(&format!("Goals for the deposition: {}\n", goals.trim()));
+    }
+    prompt.push_str(&format!("\nThe excerpts below share these terms: {}\n", terms.join(", ")));
+    for excerpt in excerpts {
+        let title = match excerpt.source {
+            PassageSource::Exhibit { number } => exhibits
+                .iter()
+                .find(|e| e.number == number)
+                .map(|e| format!(" ({})", e.title))
+                .unwrap_or_default(),
+            PassageSource::Testimony { .. } => " (prior testimony)".to_string(),
+        };
+        prompt.push_str(&format!("\n[{}]{}\n{}\n", excerpt.cite, title, truncate_chars(&excerpt.text, MAX_PASSAGE_CHARS)));
+    }
+    prompt.push_str(
+        "\nStart with a line \"Topic: <title>\" naming the subject of these excerpts in a few words. Then write \
+         the examination questions for this topic as a numbered list, in the order you would ask them: lock in \
+         the witness's prior testimony, put the exhibits to the witness and probe inconsistencies. End each \
+         question with the exhibit or testimony it relies on in square brackets exactly as labelled above, \
+         for example [Ex. 2] or [T1 12:4-9]. Do not cite anything that is not labelled above.",
+    );
+    prompt
+}
+
+/// Resolves exhibit numbers and page:line references against the material that was read
+pub struct ReferenceIndex {
+    exhibits: BTreeSet<u32>,
+    transcripts: HashMap<String, BTreeSet<(u32, u32)>>, // label -> (page, line)
+}
+
+impl ReferenceIndex {
+    pub fn new(exhibits: &[Exhibit], transcripts: &[(String, Vec<TranscriptLine>)]) -> Self {
+        ReferenceIndex {
+            exhibits: exhibits.iter().map(|e| e.number).collect(),
+            transcripts: transcripts
+                .iter()
+                .map(|(label, lines)| (label.to_lowercase(), lines.iter().map(|l| (l.page, l.line)).collect()))
+                .collect(),
+        }
+    }
+
+    /// Split a question's bracketed references into exhibits, valid testimony cites and the rest
+    pub fn resolve(&self, question: &str) -> OutlineQuestion {
+        let bracket = Regex::new(r"\[([^\]]+)\]").unwrap();
+        let exhibit = Regex::new(r"^(?i:ex(?:hibit|\.)?)\s*(\d+)$").unwrap();
+        let testimony = Regex::new(r"^(\w+)\s+(\d+):(\d+)(?:\s*[-–]\s*(?:(\d+):)?(\d+))?$").unwrap();
+
+        let mut resolved = OutlineQuestion {
+            text: bracket.replace_all(question, "").trim().to_string(),
+            exhibits: Vec::new(),
+            testimony: Vec::new(),
+            unverified: Vec::new(),
+        };
+        for caps in bracket.captures_iter(question) {
+            for reference in caps[1].split(';').flat_map(|r| r.split(',')).map(str::trim).filter(|r| !r.is_empty()) {
+                if let Some(number) = exhibit.captures(reference).and_then(|c| c[1].parse::<u32>().ok()) {
+                    if self.exhibits.contains(&number) {
+                        if !resolved.exhibits.contains(&number) {
+                            resolved.exhibits.push(number);
+                        }
+                        continue;
+                    }
+                } else if let Some(c) = testimony.captures(reference) {
+                    let number = |i: usize| c.get(i).and_then(|m| m.as_str().parse::<u32>().ok());
+                    let start = (number(2).unwrap_or(0), number(3).unwrap_or(0));
+                    let end = match (number(4), number(5)) {
+                        (Some(page), Some(line)) => (page, line),
+                        (None, Some(line)) => (start.0, line),
+                        _ => start,
+                    };
+                    let lines = self.transcripts.get(&c[1].to_lowercase());
+                    let valid = start <= end && lines.map(|l| l.contains(&start) && l.contains(&end)).unwrap_or(false);
+                    if valid {
+                        resolved.testimony.push(format_cite(&c[1], start, end));
+                        continue;
+                    }
+                }
+                resolved.unverified.push(reference.to_string());
+            }
+        }
+        resolved
+    }
+}
+
+/// The model's "Topic:" title and its numbered questions
+pub fn parse_topic_draft(text: &str) -> (Option<String>, Vec<String>) {
+    let title_line = Regex::new(r"^\s*(?:\*\*)?(?i:topic)\s*:\s*(?:\*\*)?\s*(.+?)\s*(?:\*\*)?\s*$").unwrap();
+    let numbered = Regex::new(r"^\s*(?:\*\*)?\d+[.)]\s*(?:\*\*)?\s*(.*)$").unwrap();
+    let mut title = None;
+    let mut questions: Vec<String> = Vec::new();
+    for line in text.lines() {
+        if title.is_none() && questions.is_empty() {
+            if let Some(caps) = title_line.captures(line) {
+                title = Some(caps[1].trim_matches('*').trim().to_string());
+                continue;
+            }
+        }
+        match numbered.captures(line) {
+            Some(caps) => questions.push(caps[1].trim().to_string()),
+            None if !line.trim().is_empty() => {
+                if let Some(last) = questions.last_mut() {
+                    last.push(' ');
+                    last.push_str(line.trim());
+                }
+            }
+            None => {}
+        }
+    }
+    questions.retain(|q| !q.is_empty());
+    (title, questions)
+}
+
+fn render_blocks(outline: &DepositionOutline, matter: &Matter, generated_on: &str) -> Vec<DocxBlock> {
+    let mut blocks = vec![
+        DocxBlock::Title(format!("Deposition Outline: {}", outline.witness)),
+        DocxBlock::Paragraph(format!(
+            "Matter: {}\nWitness: {}\nDate: {}",
+            matter.name, outline.witness, generated_on
+        )),
+        DocxBlock::Heading(1, "Exhibits".to_string()),
+    ];
+    if outline.exhibits.is_empty() {
+        blocks.push(DocxBlock::Paragraph("No exhibits.".to_string()));
+    } else {
+        blocks.push(DocxBlock::Table {
+            header: vec!["Exhibit".to_string(), "Document".to_string()],
+            rows: outline
+                .exhibits
+                .iter()
+                .map(|e| vec![format!("Ex. {}", e.number), e.title.clone()])
+                .collect(),
+        });
+    }
+    if !outline.prior_testimony.is_empty() {
+        blocks.push(DocxBlock::Heading(1, "Prior testimony".to_string()));
+        blocks.push(DocxBlock::Table {
+            header: vec!["Cited as".to_string(), "Statement".to_string(), "Pages".to_string()],
+            rows: outline
+                .prior_testimony
+                .iter()
+                .map(|t| vec![t.label.clone(), t.title.clone(), t.pages.to_string()])
+                .collect(),
+        });
+    }
+
+    for topic in &outline.topics {
+        blocks.push(DocxBlock::Heading(1, format!("{}. {}", topic.id, topic.title)));
+        let mut sources: Vec<String> = topic.exhibits.iter().map(|n| format!("Ex. {}", n)).collect();
+        sources.extend(topic.testimony.iter().cloned());
+        if !sources.is_empty() {
+            blocks.push(DocxBlock::Paragraph(format!("Sources: {}", sources.join("; "))));
+        }
+        for question in &topic.questions {
+            let mut references: Vec<String> = question.exhibits.iter().map(|n| format!("Ex. {}", n)).collect();
+            references.extend(question.testimony.iter().cloned());
+            references.extend(question.unverified.iter().map(|r| format!("{} (unverified)", r)));
+            blocks.push(DocxBlock::Bullet(if references.is_empty() {
+                question.text.clone()
+            } else {
+                format!("{} [{}]", question.text, references.join("; "))
+            }));
+        }
+    }
+    if outline.unverified_references > 0 {
+        blocks.push(DocxBlock::Paragraph(format!(
+            "{} reference(s) marked unverified match no exhibit or transcript line and must be checked before the deposition.",
+            outline.unverified_references
+        )));
+    }
+    blocks
+}
+
+/// Where deposition outlines are written
+pub struct DepositionOutlines {
+    pub dir: PathBuf,
+}
+
+impl DepositionOutlines {
+    pub fn new(app_data_dir: &Path) -> Self {
+        DepositionOutlines {
+            dir: app_data_dir.join("deposition_outlines"),
+        }
+    }
+}
+
+pub type DepositionOutlineStorage = Arc<DepositionOutlines>;
+
+fn mentions_witness(text: &str, witness: &str) -> bool {
+    let text = text.to_lowercase();
+    let witness = witness.trim().to_lowercase();
+    let surname = witness.split_whitespace().last().unwrap_or("");
+    text.contains(&witness) || (surname.chars().count() > 2 && text.contains(surname))
+}
+
+fn file_title(path: &str) -> String {
+    Path::new(path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string())
+}
+
+async fn draft_topic(llm: &LLMManager, model: &str, prompt: String) -> Result<String> {
+    let request = GenerateRequest {
+        model: model.to_string(),
+        prompt,
+        stream: Some(false),
+        options: Some(GenerateOptions {
+            temperature: Some(0.2),
+            num_predict: Some(2000),
+            ..Default::default()
+        }),
+        system: Some("You are a litigator preparing a deposition outline. Ask short, single-fact questions.".to_string()),
+        template: None,
+        context: None,
+        raw: None,
+    };
+    Ok(llm.generate_response(request).await?.response)
+}
+
+/// Build a deposition outline for a witness from the matter's documents and write it as DOCX
+#[tauri::command]
+pub async fn deposition_prepare(
+    session_id: String,
+    request: DepositionPrepRequest,
+    outlines: tauri::State<'_, DepositionOutlineStorage>,
+    matters: tauri::State<'_, MatterStorage>,
+    analyzer: tauri::State<'_, AnalyzerStorage>,
+    llm: tauri::State<'_, Arc<LLMManager>>,
+    review: tauri::State<'_, ReviewQueueStorage>,
+    sessions: tauri::State<'_, SessionStorage>,
+    provenance: tauri::State<'_, ProvenanceStorage>,
+    security: tauri::State<'_, Arc<Mutex<SecurityManager>>>,
+) -> Result<DepositionOutline, String> {
+    let user = authenticated_user(&session_id, &sessions)?;
+    let matter = matters
+        .get(&request.matter_id)
+        .ok_or_else(|| format!("Matter {} not found", request.matter_id))?;
+    if request.witness.trim().is_empty() {
+        return Err("Name the witness to prepare for".to_string());
+    }
+    let model = match &request.model {
+        Some(model) => model.clone(),
+        None => llm
+            .default_generation_model()
+            .await
+            .ok_or_else(|| "No model is available to draft the outline".to_string())?,
+    };
+
+    let explicit = request.paths.is_some();
+    let paths = request.paths.clone().unwrap_or_else(|| matter.documents.clone());
+    let mut exhibits = Vec::new();
+    let mut prior_testimony = Vec::new();
+    let mut transcripts = Vec::new();
+    let mut passages = Vec::new();
+    let mut sources = Vec::new();
+    for path in &paths {
+        let text = analyzer.extract_text(Path::new(path)).await.map_err(|e| e.to_string())?;
+        if !explicit && !mentions_witness(&text, &request.witness) {
+            continue;
+        }
+        sources.push(ProvenanceSource::new(path, Some(&file_title(path)), None, &text));
+        let lines = parse_transcript(&text);
+        if is_transcript(&text, &lines) {
+            let label = format!("T{}", prior_testimony.len() + 1);
+            passages.extend(testimony_passages(transcripts.len(), &label, &lines));
+            prior_testimony.push(PriorTestimony {
+                label: label.clone(),
+                title: file_title(path),
+                path: path.clone(),
+                pages: lines.last().map(|l| l.page).unwrap_or(0),
+            });
+            transcripts.push((label, lines));
+        } else {
+            let number = exhibits.len() as u32 + 1;
+            passages.extend(exhibit_passages(number, &text));
+            exhibits.push(Exhibit {
+                number,
+                title: file_title(path),
+                path: path.clone(),
+            });
+        }
+    }
+    if passages.is_empty() {
+        return Err(format!("No documents in the matter mention {}", request.witness));
+    }
+
+    let references = ReferenceIndex::new(&exhibits, &transcripts);
+    let k = request.topics.unwrap_or_else(|| default_cluster_count(passages.len()));
+    let mut topics = Vec::new();
+    let mut prompts = Vec::new();
+    for (members, terms) in cluster_passages(&passages, k) {
+        let excerpts: Vec<&Passage> = members.iter().take(EXCERPTS_PER_TOPIC).map(|i| &passages[*i]).collect();
+        let prompt = topic_prompt(&request.witness, &matter, request.goals.as_deref(), &terms, &excerpts, &exhibits);
+        prompts.push(prompt.clone());
+        let draft = draft_topic(&llm, &model, prompt).await.map_err(|e| e.to_string())?;
+        let (title, questions) = parse_topic_draft(&draft);
+
+        let mut topic_exhibits = BTreeSet::new();
+        let mut testimony = Vec::new();
+        for passage in members.iter().map(|i| &passages[*i]) {
+            match passage.source {
+                PassageSource::Exhibit { number } => {
+                    topic_exhibits.insert(number);
+                }
+                PassageSource::Testimony { .. } => testimony.push(passage.cite.clone()),
+            }
+        }
+        topics.push(OutlineTopic {
+            id: topics.len() + 1,
+            title: title.unwrap_or_else(|| terms.iter().take(3).cloned().collect::<Vec<_>>().join(", ")),
+            terms,
+            exhibits: topic_exhibits.into_iter().collect(),
+            testimony,
+            questions: questions.iter().map(|q| references.resolve(q)).collect(),
+        });
+    }
+
+    let mut outline = DepositionOutline {
+        id: Uuid::new_v4().to_string(),
+        matter_id: matter.id.clone(),
+        witness: request.witness.trim().to_string(),
+        exhibits,
+        prior_testimony,
+        unverified_references: topics
+            .iter()
+            .flat_map(|t| &t.questions)
+            .map(|q| q.unverified.len())
+            .sum(),
+        topics,
+        generated_at: Utc::now(),
+        path: None,
+    };
+    let path = outlines.dir.join(format!("deposition_outline_{}.docx", outline.id));
+    docx_writer::write_docx(&path, &render_blocks(&outline, &matter, &outline.generated_at.format("%Y-%m-%d").to_string()))
+        .map_err(|e| e.to_string())?;
+    outline.path = Some(path.to_string_lossy().to_string());
+    let title = format!("Deposition outline for {}", outline.witness);
+    let claim = ProvenanceClaim {
+        title: title.clone(),
+        work_product: "deposition_prep".to_string(),
+        model,
+        prompts_sha256: provenance::prompts_sha256(&prompts),
+        sources,
+    };
+    provenance
+        .stamp(&path, claim, request.provenance_sidecar, &llm, &security.lock().unwrap())
+        .map_err(|e| format!("Failed to record the outline's provenance: {}", e))?;
+    let submission = Submission::for_file(
+        WorkProductKind::Draft,
+        title,
+        "deposition_prep",
+        &outline.id,
+        &path.to_string_lossy(),
+        Some(matter.id.clone()),
+    );
+    review_queue::submit_generated(&review, &user, submission)?;
+    Ok(outline)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_transcript_pages_and_lines() {
+        let text = "Page 12\n 1   Q. Where were you on March 3?\n 2   A. At the warehouse.\n 3\n 4   Q. Who else was there?\n\u{c}13\n 1   A. Mr. Jones.\n 2   Q. Did you sign the log?\n24   A. I did.\n 1   Q. When?";
+        let lines = parse_transcript(text);
+        let cites: Vec<(u32, u32)> = lines.iter().map(|l| (l.page, l.line)).collect();
+        assert_eq!(cites, vec![(12, 1), (12, 2), (12, 3), (12, 4), (13, 1), (13, 2), (13, 24), (14, 1)]);
+        assert_eq!(lines[4].text, "A. Mr. Jones.");
+        assert!(!is_transcript(text, &lines)); // too short to be sure
+        assert_eq!(format_cite("T1", (12, 1), (12, 4)), "T1 12:1-4");
+        assert_eq!(format_cite("T1", (12, 4), (13, 2)), "T1 12:4-13:2");
+        assert!(parse_transcript("The parties met in 2019.\n\n3 copies were made.").iter().all(|l| l.line == 3));
+    }
+
+    #[test]
+    fn test_references_resolve_and_topics_cluster() {
+        let exhibits = vec![Exhibit {
+            number: 1,
+            title: "Shipping log.pdf".to_string(),
+            path: "/m/log.pdf".to_string(),
+        }];
+        let lines = parse_transcript("Page 12\n1 Q. Did you sign?\n2 A. Yes.\n3 Q. When?\n4 A. Friday.");
+        let index = ReferenceIndex::new(&exhibits, &[("T1".to_string(), lines)]);
+        let question = index.resolve("You signed the log on Friday, correct? [Ex. 1; T1 12:1-4] [Ex. 7] [T1 12:1-9]");
+        assert_eq!(question.text, "You signed the log on Friday, correct?");
+        assert_eq!(question.exhibits, vec![1]);
+        assert_eq!(question.testimony, vec!["T1 12:1-4"]);
+        assert_eq!(question.unverified, vec!["Ex. 7", "T1 12:1-9"]);
+
+        let (title, questions) = parse_topic_draft("Topic: **The shipping log**\n1. Who keeps the log?\n2) Did you sign it\non March 3? [Ex. 1]");
+        assert_eq!(title.as_deref(), Some("The shipping log"));
+        assert_eq!(questions[1], "Did you sign it on March 3? [Ex. 1]");
+
+        let texts = [
+            "The forklift shipment left the warehouse dock late.",
+            "Warehouse dock records show the forklift shipment.",
+            "The invoice payment was wired by the bank.",
+            "Bank records show the invoice payment wire.",
+        ];
+        let passages: Vec<Passage> = texts.iter().enumerate().map(|(i, t)| exhibit_passage(i as u32 + 1, t.to_string())).collect();
+        let clusters = cluster_passages(&passages, 2);
+        let mut groups: Vec<Vec<usize>> = clusters.iter().map(|(members, _)| members.clone()).collect();
+        groups.sort();
+        assert_eq!(groups, vec![vec![0, 1], vec![2, 3]]);
+        assert!(clusters.iter().any(|(_, terms)| terms.contains(&"invoice".to_string())));
+    }
+}
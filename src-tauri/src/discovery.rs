@@ -0,0 +1,610 @@
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+use crate::docx_writer::{self, DocxBlock};
+use crate::enterprise_management::BarrierStorage;
+use crate::llm_manager::{GenerateOptions, GenerateRequest, LLMManager};
+use crate::local_api::{authenticated_user, AnalyzerStorage, SessionStorage};
+use crate::matters::{Matter, MatterStorage};
+use crate::review_queue::{self, ReviewQueueStorage, Submission, WorkProductKind};
+use crate::security::SecurityManager;
+use crate::text_processing::truncate_chars;
+
+/// Discovery Drafting for BEAR AI
+/// Drafts numbered interrogatories, requests for production and requests for admission from
+/// the pleading and the matter's facts. Each set is counted against the limits of the matter's
+/// jurisdiction, including what has already been served: requests that would exceed the limit
+/// are left out of the set and reported, never silently served. Limits are configurable per
+/// jurisdiction and the sets are exported as DOCX, one file per set.
+const MAX_PLEADING_CHARS: usize = 12_000;
+/// Requests drafted per set when the jurisdiction sets no limit
+const DEFAULT_SET_SIZE: u32 = 25;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiscoveryKind {
+    Interrogatories,
+    Production,
+    Admissions,
+}
+
+impl DiscoveryKind {
+    fn set_title(&self) -> &'static str {
+        match self {
+            DiscoveryKind::Interrogatories => "Interrogatories",
+            DiscoveryKind::Production => "Requests for Production of Documents",
+            DiscoveryKind::Admissions => "Requests for Admission",
+        }
+    }
+
+    fn item_label(&self) -> &'static str {
+        match self {
+            DiscoveryKind::Interrogatories => "INTERROGATORY NO.",
+            DiscoveryKind::Production => "REQUEST FOR PRODUCTION NO.",
+            DiscoveryKind::Admissions => "REQUEST FOR ADMISSION NO.",
+        }
+    }
+
+    fn instructions(&self) -> &'static str {
+        match self {
+            DiscoveryKind::Interrogatories => {
+                "Draft interrogatories. Each asks for the facts, witnesses or documents behind a specific \
+                 allegation or defense in the pleading. Avoid compound questions; do not use lettered subparts \
+                 unless they ask about one subject."
+            }
+            DiscoveryKind::Production => {
+                "Draft requests for production. Each describes one category of documents or electronically \
+                 stored information with reasonable particularity and ties it to an allegation or defense."
+            }
+            DiscoveryKind::Admissions => {
+                "Draft requests for admission. Each asks the responding party to admit one specific fact, \
+                 the application of law to one fact, or the genuineness of one described document, so that \
+                 it can be admitted or denied without qualification."
+            }
+        }
+    }
+}
+
+/// Count limit for one kind of request under a jurisdiction's rules
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiscoveryLimit {
+    pub max: Option<u32>, // None: no numerical limit
+    pub rule: String,     // cited in the count check
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JurisdictionRules {
+    pub jurisdiction: String,
+    pub aliases: Vec<String>, // matched case-insensitively against the matter's jurisdiction
+    pub interrogatories: DiscoveryLimit,
+    pub production: DiscoveryLimit,
+    pub admissions: DiscoveryLimit,
+    pub count_subparts: bool, // discrete subparts count as separate interrogatories
+}
+
+impl JurisdictionRules {
+    fn matches(&self, jurisdiction: &str) -> bool {
+        let jurisdiction = jurisdiction.trim();
+        self.jurisdiction.eq_ignore_ascii_case(jurisdiction)
+            || self.aliases.iter().any(|a| a.eq_ignore_ascii_case(jurisdiction))
+    }
+
+    fn limit(&self, kind: DiscoveryKind) -> &DiscoveryLimit {
+        match kind {
+            DiscoveryKind::Interrogatories => &self.interrogatories,
+            DiscoveryKind::Production => &self.production,
+            DiscoveryKind::Admissions => &self.admissions,
+        }
+    }
+}
+
+fn limit(max: Option<u32>, rule: &str) -> DiscoveryLimit {
+    DiscoveryLimit { max, rule: rule.to_string() }
+}
+
+pub fn default_rules() -> Vec<JurisdictionRules> {
+    vec![
+        JurisdictionRules {
+            jurisdiction: "US-FED".to_string(),
+            aliases: vec!["federal".to_string(), "frcp".to_string(), "united states district court".to_string()],
+            interrogatories: limit(Some(25), "Fed. R. Civ. P. 33(a)(1)"),
+            production: limit(None, "Fed. R. Civ. P. 34"),
+            admissions: limit(None, "Fed. R. Civ. P. 36"),
+            count_subparts: true,
+        },
+        JurisdictionRules {
+            jurisdiction: "US-CA".to_string(),
+            aliases: vec!["california".to_string(), "ca".to_string()],
+            interrogatories: limit(Some(35), "Cal. Civ. Proc. Code § 2030.030"),
+            production: limit(None, "Cal. Civ. Proc. Code § 2031.030"),
+            admissions: limit(Some(35), "Cal. Civ. Proc. Code § 2033.030"),
+            count_subparts: true,
+        },
+        JurisdictionRules {
+            jurisdiction: "US-TX".to_string(),
+            aliases: vec!["texas".to_string(), "tx".to_string()],
+            interrogatories: limit(Some(25), "Tex. R. Civ. P. 190.3(b)(3)"),
+            production: limit(None, "Tex. R. Civ. P. 196"),
+            admissions: limit(None, "Tex. R. Civ. P. 198"),
+            count_subparts: true,
+        },
+    ]
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiscoveryItem {
+    pub number: u32,
+    pub text: String,
+    pub counted_as: u32, // 1 plus any discrete subparts that count separately
+}
+
+/// How a set counts against the jurisdiction's limit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CountCheck {
+    pub limit: Option<u32>,
+    pub rule: Option<String>,
+    pub already_served: u32,
+    pub counted: u32,
+    pub within_limit: bool,
+    pub excluded: Vec<String>, // drafted requests left out to stay within the limit
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiscoverySet {
+    pub id: String,
+    pub matter_id: String,
+    pub kind: DiscoveryKind,
+    pub set_number: u32,
+    pub propounding_party: String,
+    pub responding_party: String,
+    pub jurisdiction: Option<String>,
+    pub items: Vec<DiscoveryItem>,
+    pub count_check: CountCheck,
+    pub path: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiscoveryDraftRequest {
+    pub matter_id: String,
+    pub pleading_path: String, // the complaint or answer
+    pub facts: Option<String>,
+    pub kinds: Vec<DiscoveryKind>,
+    pub propounding_party: String,
+    pub responding_party: String,
+    pub jurisdiction: Option<String>, // defaults to the matter's jurisdiction
+    #[serde(default)]
+    pub already_served: HashMap<DiscoveryKind, u32>,
+    pub set_number: Option<u32>, // defaults to the first set, or the second once requests were served
+    pub model: Option<String>,
+}
+
+/// Split the model's numbered list into requests; unnumbered lines continue the one before
+pub fn parse_items(text: &str) -> Vec<String> {
+    let numbered = Regex::new(r"^\s*(?:\*\*)?(?i:[a-z ]+no\.\s*)?\d+[.):]\s*(?:\*\*)?\s*(.*)$").unwrap();
+    let mut items: Vec<String> = Vec::new();
+    for line in text.lines() {
+        match numbered.captures(line) {
+            Some(caps) => items.push(caps[1].trim().to_string()),
+            None if !line.trim().is_empty() => {
+                if let Some(last) = items.last_mut() {
+                    last.push('\n');
+                    last.push_str(line.trim());
+                }
+            }
+            None => {}
+        }
+    }
+    items.retain(|item| !item.is_empty());
+    items
+}
+
+/// Interrogatories count once plus once per additional lettered subpart when subparts count
+pub fn counted_as(kind: DiscoveryKind, text: &str, rules: Option<&JurisdictionRules>) -> u32 {
+    if kind != DiscoveryKind::Interrogatories || !rules.map(|r| r.count_subparts).unwrap_or(false) {
+        return 1;
+    }
+    let subpart = Regex::new(r"\(([a-z])\)").unwrap();
+    let letters: HashSet<&str> = subpart.captures_iter(text).map(|c| c.get(1).unwrap().as_str()).collect();
+    letters.len().max(1) as u32
+}
+
+/// Number the requests and keep as many as the remaining allowance permits
+pub fn check_counts(
+    kind: DiscoveryKind,
+    drafted: Vec<String>,
+    rules: Option<&JurisdictionRules>,
+    already_served: u32,
+) -> (Vec<DiscoveryItem>, CountCheck) {
+    let limit = rules.map(|r| r.limit(kind));
+    let max = limit.and_then(|l| l.max);
+    let remaining = max.map(|m| m.saturating_sub(already_served));
+
+    let mut items = Vec::new();
+    let mut excluded = Vec::new();
+    let mut counted = 0;
+    for text in drafted {
+        let weight = counted_as(kind, &text, rules);
+        if remaining.map(|r| counted + weight > r).unwrap_or(false) {
+            excluded.push(text);
+            continue;
+        }
+        counted += weight;
+        items.push(DiscoveryItem {
+            number: items.len() as u32 + 1,
+            text,
+            counted_as: weight,
+        });
+    }
+
+    let check = CountCheck {
+        limit: max,
+        rule: limit.map(|l| l.rule.clone()),
+        already_served,
+        counted,
+        within_limit: max.map(|m| already_served + counted <= m).unwrap_or(true),
+        excluded,
+    };
+    (items, check)
+}
+
+fn draft_prompt(
+    kind: DiscoveryKind,
+    matter: &Matter,
+    request: &DiscoveryDraftRequest,
+    pleading: &str,
+    max_items: u32,
+) -> String {
+    let mut prompt = format!(
+        "{}\n\nPropounding party: {}\nResponding party: {}\nMatter: {}\n",
+        kind.instructions(),
+        request.propounding_party,
+        request.responding_party,
+        matter.name
+    );
+    if let Some(description) = &matter.description {
+        prompt.push_str(&format!("Matter description: {}\n", description));
+    }
+    if let Some(facts) = &request.facts {
+        prompt.push_str(&format!("Known facts:\n{}\n", facts.trim()));
+    }
+    prompt.push_str(&format!(
+        "\nPleading:\n{}\n\nWrite at most {} requests as a numbered list, one request per number, \
+         without definitions, instructions or commentary.",
+        truncate_chars(pleading, MAX_PLEADING_CHARS),
+        max_items
+    ));
+    prompt
+}
+
+fn render_blocks(set: &DiscoverySet, matter: &Matter, generated_on: &str) -> Vec<DocxBlock> {
+    let mut blocks = vec![
+        DocxBlock::Title(format!(
+            "{}'s {} Set of {} to {}",
+            set.propounding_party,
+            ordinal(set.set_number),
+            set.kind.set_title(),
+            set.responding_party
+        )),
+        DocxBlock::Paragraph(format!(
+            "Matter: {}\nPropounding party: {}\nResponding party: {}\nSet number: {}\nDate: {}",
+            matter.name,
+            set.propounding_party,
+            set.responding_party,
+            set.set_number,
+            generated_on
+        )),
+    ];
+    if let Some(rule) = &set.count_check.rule {
+        blocks.push(DocxBlock::Paragraph(format!(
+            "{} propounds the following {} on {} pursuant to {}, to be answered within the time the rules allow.",
+            set.propounding_party,
+            set.kind.set_title().to_lowercase(),
+            set.responding_party,
+            rule
+        )));
+    }
+    for item in &set.items {
+        blocks.push(DocxBlock::Heading(2, format!("{} {}:", set.kind.item_label(), item.number)));
+        blocks.push(DocxBlock::Paragraph(item.text.clone()));
+    }
+    blocks
+}
+
+fn ordinal(n: u32) -> String {
+    let words = ["First", "Second", "Third", "Fourth", "Fifth", "Sixth", "Seventh", "Eighth", "Ninth", "Tenth"];
+    match words.get(n.saturating_sub(1) as usize) {
+        Some(word) => word.to_string(),
+        None => format!("{}th", n),
+    }
+}
+
+/// Jurisdiction rules and where discovery sets are written
+pub struct DiscoveryDrafting {
+    rules_path: PathBuf,
+    pub dir: PathBuf,
+    rules: Mutex<Vec<JurisdictionRules>>,
+}
+
+impl DiscoveryDrafting {
+    pub fn new(app_data_dir: &Path) -> Self {
+        let rules_path = app_data_dir.join("discovery_rules.json");
+        let rules = fs::read_to_string(&rules_path)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_else(default_rules);
+        DiscoveryDrafting {
+            rules_path,
+            dir: app_data_dir.join("discovery"),
+            rules: Mutex::new(rules),
+        }
+    }
+
+    pub fn rules(&self) -> Vec<JurisdictionRules> {
+        self.rules.lock().unwrap().clone()
+    }
+
+    pub fn set_rules(&self, rules: Vec<JurisdictionRules>) -> Result<()> {
+        let mut seen = HashSet::new();
+        for rule in &rules {
+            if !seen.insert(rule.jurisdiction.to_lowercase()) {
+                return Err(anyhow!("Jurisdiction {} is configured twice", rule.jurisdiction));
+            }
+        }
+        fs::write(&self.rules_path, serde_json::to_string_pretty(&rules)?)?;
+        *self.rules.lock().unwrap() = rules;
+        Ok(())
+    }
+
+    pub fn rules_for(&self, jurisdiction: Option<&str>) -> Option<JurisdictionRules> {
+        let jurisdiction = jurisdiction?;
+        self.rules.lock().unwrap().iter().find(|r| r.matches(jurisdiction)).cloned()
+    }
+
+    /// Re-check an edited set against the limits and write it out
+    pub fn export(&self, mut set: DiscoverySet, matter: &Matter) -> Result<DiscoverySet> {
+        let rules = self.rules_for(set.jurisdiction.as_deref());
+        let drafted = set.items.into_iter().map(|item| item.text).collect();
+        let (items, check) = check_counts(set.kind, drafted, rules.as_ref(), set.count_check.already_served);
+        if !check.excluded.is_empty() {
+            return Err(anyhow!(
+                "The set exceeds the limit of {} under {}; remove {} request(s) before exporting",
+                check.limit.unwrap_or(0),
+                check.rule.clone().unwrap_or_default(),
+                check.excluded.len()
+            ));
+        }
+        set.items = items;
+        set.count_check = check;
+
+        let path = self.dir.join(format!("discovery_{}_{:?}.docx", set.id, set.kind).to_lowercase());
+        docx_writer::write_docx(&path, &render_blocks(&set, matter, &Utc::now().format("%Y-%m-%d").to_string()))?;
+        set.path = Some(path.to_string_lossy().to_string());
+        Ok(set)
+    }
+}
+
+pub type DiscoveryStorage = Arc<DiscoveryDrafting>;
+
+async fn draft_set(
+    llm: &LLMManager,
+    model: &str,
+    kind: DiscoveryKind,
+    matter: &Matter,
+    request: &DiscoveryDraftRequest,
+    pleading: &str,
+    max_items: u32,
+) -> Result<Vec<String>> {
+    let generate = GenerateRequest {
+        model: model.to_string(),
+        prompt: draft_prompt(kind, matter, request, pleading, max_items),
+        stream: Some(false),
+        options: Some(GenerateOptions {
+            temperature: Some(0.2),
+            num_predict: Some(3000),
+            ..Default::default()
+        }),
+        system: Some("You are a litigator drafting written discovery. Be precise and neutral in tone.".to_string()),
+        template: None,
+        context: None,
+        raw: None,
+    };
+    let response = llm.generate_response(generate).await?;
+    Ok(parse_items(&response.response))
+}
+
+/// What a reviewer approves of a set: its numbered requests and the parties they run between.
+/// The DOCX carries the date it was written, so the file itself changes with every export.
+fn set_sha256(set: &DiscoverySet) -> String {
+    let items: Vec<(u32, &str)> = set.items.iter().map(|item| (item.number, item.text.as_str())).collect();
+    let content = serde_json::json!({
+        "kind": set.kind,
+        "set_number": set.set_number,
+        "propounding_party": set.propounding_party,
+        "responding_party": set.responding_party,
+        "items": items,
+    });
+    review_queue::json_sha256(&content)
+}
+
+/// The review item of a written set
+fn review_submission(set: &DiscoverySet) -> Submission {
+    Submission {
+        kind: WorkProductKind::Draft,
+        title: format!("{} (set {})", set.kind.set_title(), set.set_number),
+        source: "discovery".to_string(),
+        reference: set.id.clone(),
+        path: set.path.clone(),
+        matter_id: Some(set.matter_id.clone()),
+        content_sha256: set_sha256(set),
+    }
+}
+
+/// Draft the requested discovery sets from a pleading and write each one as DOCX
+#[tauri::command]
+pub async fn discovery_draft(
+    session_id: String,
+    request: DiscoveryDraftRequest,
+    discovery: tauri::State<'_, DiscoveryStorage>,
+    matters: tauri::State<'_, MatterStorage>,
+    analyzer: tauri::State<'_, AnalyzerStorage>,
+    llm: tauri::State<'_, Arc<LLMManager>>,
+    review: tauri::State<'_, ReviewQueueStorage>,
+    sessions: tauri::State<'_, SessionStorage>,
+) -> Result<Vec<DiscoverySet>, String> {
+    let user = authenticated_user(&session_id, &sessions)?;
+    let matter = matters
+        .get(&request.matter_id)
+        .ok_or_else(|| format!("Matter {} not found", request.matter_id))?;
+    let pleading = analyzer
+        .extract_text(Path::new(&request.pleading_path))
+        .await
+        .map_err(|e| e.to_string())?;
+    let model = match &request.model {
+        Some(model) => model.clone(),
+        None => llm
+            .default_generation_model()
+            .await
+            .ok_or_else(|| "No model is available to draft discovery".to_string())?,
+    };
+    let jurisdiction = request.jurisdiction.clone().or_else(|| matter.jurisdiction.clone());
+    let rules = discovery.rules_for(jurisdiction.as_deref());
+
+    let mut sets = Vec::new();
+    for kind in &request.kinds {
+        let already_served = request.already_served.get(kind).copied().unwrap_or(0);
+        let max_items = match rules.as_ref().and_then(|r| r.limit(*kind).max) {
+            Some(max) if max <= already_served => {
+                return Err(format!(
+                    "The limit of {} {} is already used up",
+                    max,
+                    kind.set_title().to_lowercase()
+                ))
+            }
+            Some(max) => max - already_served,
+            None => DEFAULT_SET_SIZE,
+        };
+
+        let drafted = draft_set(&llm, &model, *kind, &matter, &request, &pleading, max_items)
+            .await
+            .map_err(|e| e.to_string())?;
+        let (items, count_check) = check_counts(*kind, drafted, rules.as_ref(), already_served);
+        let excluded = count_check.excluded.clone();
+        let set = DiscoverySet {
+            id: Uuid::new_v4().to_string(),
+            matter_id: matter.id.clone(),
+            kind: *kind,
+            set_number: request.set_number.unwrap_or(if already_served > 0 { 2 } else { 1 }),
+            propounding_party: request.propounding_party.clone(),
+            responding_party: request.responding_party.clone(),
+            jurisdiction: jurisdiction.clone(),
+            items,
+            count_check,
+            path: None,
+        };
+        let mut set = discovery.export(set, &matter).map_err(|e| e.to_string())?;
+        // Keep the requests dropped to meet the limit visible to the drafter
+        set.count_check.excluded = excluded;
+        review_queue::submit_generated(&review, &user, Ok(review_submission(&set)))?;
+        sets.push(set);
+    }
+    Ok(sets)
+}
+
+/// Write an edited set, refusing it if it no longer fits the jurisdiction's limit
+#[tauri::command]
+pub async fn discovery_export(
+    session_id: String,
+    set: DiscoverySet,
+    discovery: tauri::State<'_, DiscoveryStorage>,
+    matters: tauri::State<'_, MatterStorage>,
+    sessions: tauri::State<'_, SessionStorage>,
+    barriers: tauri::State<'_, BarrierStorage>,
+    security: tauri::State<'_, Arc<Mutex<SecurityManager>>>,
+    review: tauri::State<'_, ReviewQueueStorage>,
+) -> Result<DiscoverySet, String> {
+    let matter = matters
+        .get(&set.matter_id)
+        .ok_or_else(|| format!("Matter {} not found", set.matter_id))?;
+    let user = authenticated_user(&session_id, &sessions)?;
+    // The set is drawn from the matter, so a barrier around the matter covers it
+    let items: Vec<(String, Option<String>)> = std::iter::once(format!("discovery:{}", set.id))
+        .chain(matter.documents.iter().cloned())
+        .map(|document| (document, Some(matter.id.clone())))
+        .collect();
+    barriers.check_export(&security, &user, &items)?;
+    let set = discovery.export(set, &matter).map_err(|e| e.to_string())?;
+    // An edited set goes back to the reviewer and is released once approved as it now reads
+    let submission = review_submission(&set);
+    let content_sha256 = submission.content_sha256.clone();
+    if let Err(reason) = review_queue::release_or_submit(&review, &user, submission) {
+        review_queue::audit_withheld(&security, &format!("discovery:{}", set.id), &reason);
+        return Err(reason);
+    }
+    if let Some(path) = &set.path {
+        review
+            .record_export(&set.id, WorkProductKind::Draft, &content_sha256, Path::new(path))
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(set)
+}
+
+#[tauri::command]
+pub async fn get_discovery_rules(discovery: tauri::State<'_, DiscoveryStorage>) -> Result<Vec<JurisdictionRules>, String> {
+    Ok(discovery.rules())
+}
+
+#[tauri::command]
+pub async fn set_discovery_rules(
+    rules: Vec<JurisdictionRules>,
+    discovery: tauri::State<'_, DiscoveryStorage>,
+) -> Result<(), String> {
+    discovery.set_rules(rules).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_numbered_requests() {
+        let text = "Here are the interrogatories:\n\n1. Identify each person who negotiated the Agreement.\n2) State all facts supporting\nyour Third Affirmative Defense.\n**INTERROGATORY NO. 3:** Describe the damages you claim.";
+        let items = parse_items(text);
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[1], "State all facts supporting\nyour Third Affirmative Defense.");
+        assert_eq!(items[2], "Describe the damages you claim.");
+    }
+
+    #[test]
+    fn test_count_limits_include_subparts_and_served_requests() {
+        let rules = default_rules();
+        let federal = rules.iter().find(|r| r.matches("Federal")).unwrap();
+        let drafted = vec![
+            "Identify each witness.".to_string(),
+            "For each payment state (a) the date, (b) the amount and (c) the payee.".to_string(),
+            "Identify all documents concerning the invoice.".to_string(),
+        ];
+
+        // 20 already served leaves 5; the second interrogatory counts as 3
+        let (items, check) = check_counts(DiscoveryKind::Interrogatories, drafted.clone(), Some(federal), 20);
+        assert_eq!(items.iter().map(|i| i.counted_as).collect::<Vec<_>>(), vec![1, 3, 1]);
+        assert_eq!((check.counted, check.within_limit), (5, true));
+
+        let (items, check) = check_counts(DiscoveryKind::Interrogatories, drafted.clone(), Some(federal), 22);
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[1].number, 2);
+        assert_eq!(check.excluded, vec![drafted[1].clone()]);
+        assert_eq!(check.rule.as_deref(), Some("Fed. R. Civ. P. 33(a)(1)"));
+
+        // Requests for production have no federal limit
+        let (items, check) = check_counts(DiscoveryKind::Production, drafted, Some(federal), 100);
+        assert_eq!((items.len(), check.limit, check.within_limit), (3, None, true));
+        assert!(rules.iter().any(|r| r.matches("california") && r.admissions.max == Some(35)));
+    }
+}
@@ -0,0 +1,489 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::enterprise_management::{require_admin, EnterpriseManager};
+use crate::local_api::{authenticated_user, SessionStorage};
+use crate::security::{ActionOutcome, SecurityAction, SecurityManager};
+
+/// Document and Matter Access Control for BEAR AI
+/// Per-document and per-matter access lists on top of the role permissions. They are enforced
+/// when the workspace is shared (enterprise mode), where every signed-in user works from one
+/// document pool; a personal workspace only ever holds its own user's documents.
+///
+/// A user's access to a document is the highest of: ownership or a grant on the document, and
+/// ownership or a grant on its matter. The `*` principal grants everyone in the workspace. A
+/// privileged list ignores `*`, so a privileged matter hides its documents from everyone not on
+/// its list, and a privileged document is visible only to its owner and explicit grantees.
+/// Documents no list covers stay open, as they were before lists existed.
+pub const EVERYONE: &str = "*";
+pub const SHARED_WORKSPACE: &str = "shared";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum AccessLevel {
+    Read,
+    Annotate, // tags, notes and analysis on top of read
+    Export,   // take the document out of the workspace
+    Owner,    // rename, delete and manage the list
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum AclResource {
+    Document,
+    Matter,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccessControlList {
+    pub resource: AclResource,
+    pub resource_id: String,
+    pub owner: String,
+    pub grants: BTreeMap<String, AccessLevel>, // principal (user name or "*") -> level
+    pub privileged: bool,
+    pub matter_id: Option<String>, // documents: the matter whose list also applies
+    pub updated_at: DateTime<Utc>,
+    pub updated_by: String,
+}
+
+impl AccessControlList {
+    /// This list's contribution to a user's access; `open` is false when a privileged list
+    /// above this one rules out the everyone grant
+    fn level_for(&self, user: &str, open: bool) -> Option<AccessLevel> {
+        if self.owner == user {
+            return Some(AccessLevel::Owner);
+        }
+        let personal = self.grants.get(user).copied();
+        let everyone = self.grants.get(EVERYONE).copied().filter(|_| open && !self.privileged);
+        personal.max(everyone)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AclUpdate {
+    pub resource: AclResource,
+    pub resource_id: String,
+    pub owner: Option<String>, // transfer ownership; owners only
+    pub grants: BTreeMap<String, AccessLevel>,
+    pub privileged: bool,
+    pub matter_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceAccessSettings {
+    pub shared: bool,
+    pub default_access: Option<AccessLevel>, // what "*" gets on newly uploaded documents
+}
+
+impl Default for WorkspaceAccessSettings {
+    fn default() -> Self {
+        Self {
+            shared: false,
+            default_access: Some(AccessLevel::Read),
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct AclState {
+    settings: WorkspaceAccessSettings,
+    lists: HashMap<String, AccessControlList>, // "document:<id>" / "matter:<id>"
+    #[serde(default)]
+    uploaders: HashMap<String, String>, // document id -> who uploaded it, shared workspace or not
+}
+
+fn key(resource: AclResource, id: &str) -> String {
+    match resource {
+        AclResource::Document => format!("document:{}", id),
+        AclResource::Matter => format!("matter:{}", id),
+    }
+}
+
+/// Effective access of `user` to a document; None means the document is invisible to them
+fn effective_access(state: &AclState, user: &str, document_id: &str, matter_id: Option<&str>) -> Option<AccessLevel> {
+    if !state.settings.shared {
+        return Some(AccessLevel::Owner);
+    }
+    let document = state.lists.get(&key(AclResource::Document, document_id));
+    let matter_id = document.and_then(|d| d.matter_id.as_deref()).or(matter_id);
+    let matter = matter_id.and_then(|id| state.lists.get(&key(AclResource::Matter, id)));
+
+    match (document, matter) {
+        (None, None) => Some(AccessLevel::Owner),
+        (Some(document), _) if document.privileged => document.level_for(user, false),
+        (document, matter) => {
+            let open = !matter.is_some_and(|m| m.privileged);
+            document
+                .and_then(|d| d.level_for(user, open))
+                .max(matter.and_then(|m| m.level_for(user, true)))
+        }
+    }
+}
+
+pub struct DocumentAcl {
+    path: PathBuf,
+    state: Mutex<AclState>,
+}
+
+impl DocumentAcl {
+    pub fn new(app_data_dir: &Path) -> Result<Self> {
+        let path = app_data_dir.join("document_acls.json");
+        let state = if path.exists() {
+            serde_json::from_str(&fs::read_to_string(&path)?)?
+        } else {
+            AclState::default()
+        };
+        Ok(Self {
+            path,
+            state: Mutex::new(state),
+        })
+    }
+
+    fn persist(&self, state: &AclState) -> Result<()> {
+        fs::write(&self.path, serde_json::to_string_pretty(state)?)?;
+        Ok(())
+    }
+
+    pub fn settings(&self) -> WorkspaceAccessSettings {
+        self.state.lock().unwrap().settings.clone()
+    }
+
+    pub fn set_settings(&self, settings: WorkspaceAccessSettings) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        state.settings = settings;
+        self.persist(&state)
+    }
+
+    /// Key under which local_api keeps a session's documents: one pool when shared
+    pub fn workspace_key(&self, session_id: &str) -> String {
+        if self.state.lock().unwrap().settings.shared {
+            SHARED_WORKSPACE.to_string()
+        } else {
+            session_id.to_string()
+        }
+    }
+
+    pub fn access(&self, user: &str, document_id: &str, matter_id: Option<&str>) -> Option<AccessLevel> {
+        effective_access(&self.state.lock().unwrap(), user, document_id, matter_id)
+    }
+
+    pub fn allows(&self, user: &str, document_id: &str, required: AccessLevel) -> bool {
+        self.access(user, document_id, None).is_some_and(|level| level >= required)
+    }
+
+    /// Retrieval filter: may `user` see a chunk of this indexed document? An empty user (no
+    /// session) only passes documents open to everyone.
+    pub fn can_read_indexed(&self, user: &str, document_id: &str, matter_id: Option<&str>) -> bool {
+        self.access(user, document_id, matter_id).is_some()
+    }
+
+    /// Record the uploader of a new document, and make them its owner in a shared workspace
+    pub fn register_document(&self, document_id: &str, owner: &str) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        state.uploaders.insert(document_id.to_string(), owner.to_string());
+        if !state.settings.shared {
+            return self.persist(&state);
+        }
+        let grants = state
+            .settings
+            .default_access
+            .map(|level| BTreeMap::from([(EVERYONE.to_string(), level)]))
+            .unwrap_or_default();
+        state.lists.insert(
+            key(AclResource::Document, document_id),
+            AccessControlList {
+                resource: AclResource::Document,
+                resource_id: document_id.to_string(),
+                owner: owner.to_string(),
+                grants,
+                privileged: false,
+                matter_id: None,
+                updated_at: Utc::now(),
+                updated_by: owner.to_string(),
+            },
+        );
+        self.persist(&state)
+    }
+
+    pub fn remove_document(&self, document_id: &str) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        let listed = state.lists.remove(&key(AclResource::Document, document_id)).is_some();
+        if state.uploaders.remove(document_id).is_some() || listed {
+            self.persist(&state)?;
+        }
+        Ok(())
+    }
+
+    /// The matter a document's list files it under
+    pub fn matter_of(&self, document_id: &str) -> Option<String> {
+        self.state
+            .lock()
+            .unwrap()
+            .lists
+            .get(&key(AclResource::Document, document_id))
+            .and_then(|list| list.matter_id.clone())
+    }
+
+    pub fn get(&self, resource: AclResource, resource_id: &str) -> Option<AccessControlList> {
+        self.state.lock().unwrap().lists.get(&key(resource, resource_id)).cloned()
+    }
+
+    /// Create or replace a list. An existing list can only be changed by its owner. A new one
+    /// makes the caller its owner, and only a document's uploader or an administrator may start
+    /// one; lists for matters are started by administrators.
+    pub fn set(&self, update: AclUpdate, user: &str, is_admin: bool) -> Result<AccessControlList> {
+        let mut state = self.state.lock().unwrap();
+        let list_key = key(update.resource, &update.resource_id);
+        let owner = match state.lists.get(&list_key) {
+            Some(existing) if existing.owner != user => {
+                return Err(anyhow!("Only the owner ({}) can change this access list", existing.owner))
+            }
+            Some(existing) => update.owner.unwrap_or_else(|| existing.owner.clone()),
+            None if is_admin => update.owner.unwrap_or_else(|| user.to_string()),
+            None if update.resource == AclResource::Matter => {
+                return Err(anyhow!("Only an administrator can start an access list for a matter"))
+            }
+            None if state.uploaders.get(&update.resource_id).map(String::as_str) != Some(user) => {
+                return Err(anyhow!("Only the document's uploader or an administrator can start its access list"))
+            }
+            None => update.owner.unwrap_or_else(|| user.to_string()),
+        };
+        if update.resource == AclResource::Matter && update.matter_id.is_some() {
+            return Err(anyhow!("Only documents can inherit a matter's access list"));
+        }
+
+        let list = AccessControlList {
+            resource: update.resource,
+            resource_id: update.resource_id,
+            owner,
+            grants: update.grants.into_iter().filter(|(principal, _)| !principal.trim().is_empty()).collect(),
+            privileged: update.privileged,
+            matter_id: update.matter_id,
+            updated_at: Utc::now(),
+            updated_by: user.to_string(),
+        };
+        state.lists.insert(list_key, list.clone());
+        self.persist(&state)?;
+        Ok(list)
+    }
+
+    pub fn remove(&self, resource: AclResource, resource_id: &str, user: &str) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        let list_key = key(resource, resource_id);
+        match state.lists.get(&list_key) {
+            None => return Ok(()),
+            Some(existing) if existing.owner != user => {
+                return Err(anyhow!("Only the owner ({}) can remove this access list", existing.owner))
+            }
+            Some(_) => {}
+        }
+        state.lists.remove(&list_key);
+        self.persist(&state)
+    }
+}
+
+pub type DocumentAclStorage = Arc<DocumentAcl>;
+type SecurityStorage = Arc<Mutex<SecurityManager>>;
+
+/// The user the calling session signed in as
+fn require_user(session_id: &str, sessions: &SessionStorage) -> Result<String, String> {
+    authenticated_user(session_id, sessions).map_err(|_| "Sign in to manage access lists".to_string())
+}
+
+fn audit_change(security: &SecurityStorage, resource: AclResource, resource_id: &str, user: &str, change: &str) {
+    let details = HashMap::from([
+        ("resource".to_string(), format!("{:?}", resource)),
+        ("changed_by".to_string(), user.to_string()),
+        ("change".to_string(), change.to_string()),
+    ]);
+    let _ = security.lock().unwrap().write_audit_entry(
+        SecurityAction::SettingsChange,
+        &format!("acl:{}", resource_id),
+        ActionOutcome::Success,
+        Some(details),
+    );
+}
+
+#[tauri::command]
+pub async fn acl_get_settings(acl: tauri::State<'_, DocumentAclStorage>) -> Result<WorkspaceAccessSettings, String> {
+    Ok(acl.settings())
+}
+
+#[tauri::command]
+pub async fn acl_set_settings(
+    session_id: String,
+    settings: WorkspaceAccessSettings,
+    sessions: tauri::State<'_, SessionStorage>,
+    acl: tauri::State<'_, DocumentAclStorage>,
+    security: tauri::State<'_, SecurityStorage>,
+    enterprise: tauri::State<'_, Arc<Mutex<EnterpriseManager>>>,
+) -> Result<(), String> {
+    // Turning sharing off opens every document to everyone, so it is an administrator's call
+    let user = require_admin(&session_id, &sessions, &enterprise, "change workspace access settings")?;
+    acl.set_settings(settings.clone()).map_err(|e| e.to_string())?;
+    let change = if settings.shared { "shared workspace enabled" } else { "shared workspace disabled" };
+    audit_change(&security, AclResource::Document, "workspace", &user, change);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn acl_get(
+    session_id: String,
+    resource: AclResource,
+    resource_id: String,
+    sessions: tauri::State<'_, SessionStorage>,
+    acl: tauri::State<'_, DocumentAclStorage>,
+) -> Result<Option<AccessControlList>, String> {
+    let user = require_user(&session_id, &sessions)?;
+    if resource == AclResource::Document && !acl.allows(&user, &resource_id, AccessLevel::Read) {
+        return Ok(None); // invisible documents have no visible list either
+    }
+    Ok(acl.get(resource, &resource_id))
+}
+
+#[tauri::command]
+pub async fn acl_set(
+    session_id: String,
+    update: AclUpdate,
+    sessions: tauri::State<'_, SessionStorage>,
+    acl: tauri::State<'_, DocumentAclStorage>,
+    security: tauri::State<'_, SecurityStorage>,
+    enterprise: tauri::State<'_, Arc<Mutex<EnterpriseManager>>>,
+) -> Result<AccessControlList, String> {
+    let user = require_user(&session_id, &sessions)?;
+    let is_admin = enterprise.lock().unwrap().is_admin(&user);
+    let list = acl.set(update, &user, is_admin).map_err(|e| e.to_string())?;
+    let change = format!("{} grants, privileged: {}", list.grants.len(), list.privileged);
+    audit_change(&security, list.resource, &list.resource_id, &user, &change);
+    Ok(list)
+}
+
+#[tauri::command]
+pub async fn acl_remove(
+    session_id: String,
+    resource: AclResource,
+    resource_id: String,
+    sessions: tauri::State<'_, SessionStorage>,
+    acl: tauri::State<'_, DocumentAclStorage>,
+    security: tauri::State<'_, SecurityStorage>,
+) -> Result<(), String> {
+    let user = require_user(&session_id, &sessions)?;
+    acl.remove(resource, &resource_id, &user).map_err(|e| e.to_string())?;
+    audit_change(&security, resource, &resource_id, &user, "removed");
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn acl_my_access(
+    session_id: String,
+    document_id: String,
+    sessions: tauri::State<'_, SessionStorage>,
+    acl: tauri::State<'_, DocumentAclStorage>,
+) -> Result<Option<AccessLevel>, String> {
+    let user = require_user(&session_id, &sessions)?;
+    Ok(acl.access(&user, &document_id, None))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shared_acl(dir: &Path) -> DocumentAcl {
+        let acl = DocumentAcl::new(dir).unwrap();
+        acl.set_settings(WorkspaceAccessSettings {
+            shared: true,
+            default_access: Some(AccessLevel::Read),
+        })
+        .unwrap();
+        acl
+    }
+
+    fn update(resource: AclResource, id: &str, grants: &[(&str, AccessLevel)], privileged: bool) -> AclUpdate {
+        AclUpdate {
+            resource,
+            resource_id: id.to_string(),
+            owner: None,
+            grants: grants.iter().map(|(p, l)| (p.to_string(), *l)).collect(),
+            privileged,
+            matter_id: None,
+        }
+    }
+
+    #[test]
+    fn test_document_and_matter_access() {
+        let dir = tempfile::tempdir().unwrap();
+        let acl = shared_acl(dir.path());
+
+        // Uploads are owned by the uploader and readable by the workspace
+        acl.register_document("memo", "alice").unwrap();
+        assert_eq!(acl.access("alice", "memo", None), Some(AccessLevel::Owner));
+        assert_eq!(acl.access("bob", "memo", None), Some(AccessLevel::Read));
+        assert!(!acl.allows("bob", "memo", AccessLevel::Annotate));
+        assert_eq!(acl.access("bob", "unlisted", None), Some(AccessLevel::Owner));
+
+        // A privileged matter hides its documents, including indexed chunks tagged with it
+        acl.set(update(AclResource::Matter, "m1", &[("carol", AccessLevel::Export)], true), "alice", true).unwrap();
+        let mut filed = update(AclResource::Document, "memo", &[(EVERYONE, AccessLevel::Read)], false);
+        filed.matter_id = Some("m1".to_string());
+        acl.set(filed, "alice", false).unwrap();
+        assert_eq!(acl.access("bob", "memo", None), None);
+        assert_eq!(acl.access("carol", "memo", None), Some(AccessLevel::Export));
+        assert_eq!(acl.access("bob", "chunked", Some("m1")), None);
+        assert!(acl.can_read_indexed("carol", "chunked", Some("m1")));
+        assert!(!acl.can_read_indexed("", "chunked", Some("m1")));
+
+        // A privileged document narrows even matter members to its own list
+        let mut sealed = update(AclResource::Document, "memo", &[("dave", AccessLevel::Annotate)], true);
+        sealed.matter_id = Some("m1".to_string());
+        acl.set(sealed, "alice", false).unwrap();
+        assert_eq!(acl.access("carol", "memo", None), None);
+        assert_eq!(acl.access("dave", "memo", None), Some(AccessLevel::Annotate));
+
+        // Only the owner may change a list, and lists survive a restart
+        assert!(acl.set(update(AclResource::Document, "memo", &[], false), "dave", true).is_err());
+        let reloaded = DocumentAcl::new(dir.path()).unwrap();
+        assert_eq!(reloaded.access("dave", "memo", None), Some(AccessLevel::Annotate));
+    }
+
+    #[test]
+    fn test_personal_workspace_is_unrestricted() {
+        let dir = tempfile::tempdir().unwrap();
+        let acl = DocumentAcl::new(dir.path()).unwrap();
+        acl.register_document("memo", "alice").unwrap();
+        assert!(acl.get(AclResource::Document, "memo").is_none());
+        assert_eq!(acl.workspace_key("session-1"), "session-1");
+        assert!(acl.allows("bob", "memo", AccessLevel::Owner));
+
+        let acl = shared_acl(dir.path());
+        assert_eq!(acl.workspace_key("session-1"), SHARED_WORKSPACE);
+        acl.register_document("memo", "alice").unwrap();
+        acl.set(update(AclResource::Document, "memo", &[(EVERYONE, AccessLevel::Export)], true), "alice", false).unwrap();
+        assert_eq!(acl.access("bob", "memo", None), None, "privileged lists ignore the everyone grant");
+    }
+
+    #[test]
+    fn test_only_the_uploader_or_an_administrator_starts_a_list() {
+        let dir = tempfile::tempdir().unwrap();
+        let acl = DocumentAcl::new(dir.path()).unwrap();
+        // Uploaded before the workspace was shared, so the document has no list yet
+        acl.register_document("memo", "alice").unwrap();
+        let acl = shared_acl(dir.path());
+        assert!(acl.get(AclResource::Document, "memo").is_none());
+
+        let private = || update(AclResource::Document, "memo", &[], true);
+        assert!(acl.set(private(), "bob", false).is_err());
+        assert!(acl.set(update(AclResource::Document, "unknown", &[], true), "bob", false).is_err());
+        assert!(acl.set(update(AclResource::Matter, "m1", &[], true), "bob", false).is_err());
+        assert_eq!(acl.set(private(), "alice", false).unwrap().owner, "alice");
+
+        acl.register_document("brief", "alice").unwrap();
+        acl.remove_document("brief").unwrap();
+        assert!(acl.set(update(AclResource::Document, "brief", &[], true), "alice", false).is_err());
+        assert_eq!(acl.set(update(AclResource::Document, "brief", &[], true), "root", true).unwrap().owner, "root");
+    }
+}
@@ -9,11 +9,19 @@ use std::sync::Arc;
 use tokio::fs;
 use uuid::Uuid;
 use zip::ZipArchive;
-use whatlang::{detect, Lang};
 use lopdf::Document as PdfDocument;
+
+use crate::document_classifier::{self, DocumentTypeClassification, TypeCorrections};
+use crate::glossary;
+use crate::incremental_analysis::{self, AnalysisVersion, AnalysisVersions, IncrementalAnalysis, SectionFindings, TextDiff};
+use crate::local_api::{authenticated_user, SessionStorage};
+use crate::locale_formats::{self, DateOrder};
+use crate::output_language::{self, OutputLanguagePreference};
+use crate::reanalysis::AnalysisFingerprint;
+use crate::review_queue::{self, ReviewQueue, ReviewQueueStorage, Submission, WorkProductKind};
+use crate::text_processing::{self, LanguageTools};
+use crate::workflow_packages::{clause_rule_risks, WorkflowPackages};
 // use serde_xml_rs; // Not needed for current implementation
-// use stop_words::{get, LANGUAGE}; // Alternative implementation
-// use stemmer::Stemmer; // Using built-in implementation
 
 /// Document Analysis Engine for BEAR AI
 /// Provides comprehensive legal document processing and analysis
@@ -26,13 +34,15 @@ pub struct DocumentMetadata {
     pub uploaded_at: chrono::DateTime<chrono::Utc>,
     pub processed_at: Option<chrono::DateTime<chrono::Utc>>,
     pub document_type: Option<DocumentType>,
+    #[serde(default)]
+    pub document_type_confidence: Option<f32>,
     pub language: String,
     pub page_count: Option<u32>,
     pub word_count: Option<u32>,
     pub security_classification: SecurityLevel,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum DocumentType {
     Contract,
     LegalBrief,
@@ -83,6 +93,8 @@ pub struct DocumentAnalysis {
     pub summary: Option<String>,
     pub sentiment_analysis: Option<SentimentScore>,
     pub compliance_flags: Vec<ComplianceFlag>,
+    #[serde(default)]
+    pub output_language: Option<String>, // set when generated text was translated
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -93,6 +105,8 @@ pub struct LegalEntity {
     pub start_pos: usize,
     pub end_pos: usize,
     pub context: String,
+    #[serde(default)]
+    pub normalized_value: Option<String>, // ISO 8601 for dates, "EUR 1000.00" for amounts
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -213,6 +227,7 @@ pub enum CitationType {
     Statute,
     Regulation,
     Constitutional,
+    CourtRule,
     Secondary,
     Foreign,
 }
@@ -250,11 +265,19 @@ pub enum ComplianceStatus {
     NotApplicable,
 }
 
+/// Version of the compliance rules `check_compliance` applies. Raise it whenever the rules change
+/// so analyses made under earlier rules are reported as stale.
+pub const COMPLIANCE_RULE_PACK_VERSION: &str = "1";
+
 #[derive(Debug)]
 pub struct DocumentAnalyzer {
+    app_data_dir: PathBuf,
     documents_path: PathBuf,
     cache_path: PathBuf,
     llm_manager: Option<Arc<crate::llm_manager::LLMManager>>,
+    output_language: OutputLanguagePreference,
+    type_corrections: TypeCorrections,
+    analysis_versions: AnalysisVersions,
 }
 
 impl DocumentAnalyzer {
@@ -270,14 +293,185 @@ impl DocumentAnalyzer {
         std::fs::create_dir_all(&cache_path)?;
 
         Ok(Self {
+            app_data_dir: app_data_dir.to_path_buf(),
             documents_path,
             cache_path,
             llm_manager,
+            output_language: OutputLanguagePreference::new(app_data_dir),
+            type_corrections: TypeCorrections::new(app_data_dir),
+            analysis_versions: AnalysisVersions::new(app_data_dir),
         })
     }
 
+    /// Language summaries, risk descriptions and recommendations are rendered in
+    pub fn output_language(&self) -> &OutputLanguagePreference {
+        &self.output_language
+    }
+
     /// Process and analyze a document
     pub async fn analyze_document(&self, file_path: &Path) -> Result<DocumentAnalysis> {
+        let analysis = self.extract_analysis(file_path).await?;
+        self.record_version(file_path, &analysis).await;
+        self.finish_analysis(analysis).await
+    }
+
+    /// Stored analyses, each with the rule pack and model it was produced with
+    pub fn analysis_versions(&self) -> &AnalysisVersions {
+        &self.analysis_versions
+    }
+
+    /// The analysis last recorded for a document, rendered as `analyze_document` returns it,
+    /// so a delivery carries the analysis that was reviewed rather than a fresh run
+    pub async fn recorded_analysis(&self, file_path: &Path) -> Result<DocumentAnalysis> {
+        let version = self
+            .analysis_versions
+            .latest(file_path)
+            .ok_or_else(|| anyhow::anyhow!("{} has not been analyzed", file_path.display()))?;
+        self.finish_analysis(version.analysis).await
+    }
+
+    /// The rule pack and model analyses are produced with now
+    pub async fn fingerprint(&self) -> AnalysisFingerprint {
+        let model = match &self.llm_manager {
+            Some(llm) => llm.list_loaded_models().await.into_iter().next().map(|m| m.model_id),
+            None => None,
+        };
+        let model_sha256 = match (&self.llm_manager, &model) {
+            (Some(llm), Some(model)) => llm.get_model_provenance(model).ok().map(|p| p.sha256),
+            _ => None,
+        };
+        // Clause rules from installed workflow packages are part of the rule pack
+        let packages = WorkflowPackages::new(&self.app_data_dir).map(|p| p.rule_packages()).unwrap_or_default();
+        let rule_pack = if packages.is_empty() {
+            COMPLIANCE_RULE_PACK_VERSION.to_string()
+        } else {
+            format!("{}+{}", COMPLIANCE_RULE_PACK_VERSION, packages.join(","))
+        };
+        AnalysisFingerprint {
+            rule_pack,
+            model,
+            model_sha256,
+        }
+    }
+
+    /// Analyze a document again, re-running entity, clause and citation extraction only where its
+    /// text changed since the last analysis
+    pub async fn analyze_document_incremental(&self, file_path: &Path) -> Result<IncrementalAnalysis> {
+        self.analyze_versioned(file_path, false).await
+    }
+
+    /// Analyze a document again in full, as after a rule pack or model change, and list the
+    /// findings that changed since the last analysis
+    pub async fn reanalyze_document(&self, file_path: &Path) -> Result<IncrementalAnalysis> {
+        self.analyze_versioned(file_path, true).await
+    }
+
+    async fn analyze_versioned(&self, file_path: &Path, full: bool) -> Result<IncrementalAnalysis> {
+        let Some(previous) = self.analysis_versions.latest(file_path) else {
+            let analysis = self.extract_analysis(file_path).await?;
+            let version = self.record_version(file_path, &analysis).await;
+            return Ok(IncrementalAnalysis {
+                analysis: self.finish_analysis(analysis).await?,
+                version,
+                previous_version: None,
+                full_reanalysis: true,
+                changed_share: 1.0,
+                changed_sections: Vec::new(),
+                changes: Vec::new(),
+            });
+        };
+        log::info!("Starting incremental analysis of document: {:?}", file_path);
+
+        let text = self.extract_text(file_path).await?;
+        let mut diff = incremental_analysis::diff_text(&previous.analysis.extracted_text, &text);
+        let changed_share = diff.changed_share();
+        let full_reanalysis = full || changed_share > incremental_analysis::FULL_REANALYSIS_SHARE;
+
+        let (analysis, replaced, fresh) = if full_reanalysis {
+            // Most of the document changed; its type may have too
+            diff = TextDiff::whole(&previous.analysis.extracted_text, &text);
+            let analysis = self.extract_analysis(file_path).await?;
+            let fresh = SectionFindings::of(&analysis);
+            (analysis, vec![SectionFindings::of(&previous.analysis)], vec![fresh])
+        } else {
+            let (kept, replaced) = incremental_analysis::carry_over(&previous.analysis, &diff);
+            let document_type = previous.analysis.metadata.document_type.clone();
+            let language = self.detect_language_from_text(&text);
+            let date_order = locale_formats::detect_date_order(&language, &text);
+
+            let mut fresh = Vec::new();
+            for section in diff.sections() {
+                let section_text = &text[section.current.clone()];
+                let mut entities = self.extract_entities_in_order(section_text, date_order).await?;
+                for entity in &mut entities {
+                    entity.start_pos += section.current.start;
+                    entity.end_pos += section.current.start;
+                }
+                fresh.push(SectionFindings {
+                    entities,
+                    clauses: self.analyze_clauses(section_text, &document_type).await?,
+                    citations: self.extract_citations(section_text).await?,
+                });
+            }
+            let merged = incremental_analysis::merge(kept, &fresh);
+
+            // Document-wide findings depend on the whole text and are recomputed
+            let unchanged = diff.sections().is_empty();
+            let metadata = DocumentMetadata {
+                id: Uuid::new_v4().to_string(),
+                size: fs::metadata(file_path).await?.len(),
+                processed_at: Some(chrono::Utc::now()),
+                language,
+                page_count: self.extract_page_count(file_path).await,
+                word_count: Some(self.calculate_word_count(&text)),
+                ..previous.analysis.metadata.clone()
+            };
+            let analysis = DocumentAnalysis {
+                risks: self.assess_risks(&text, &merged.clauses).await?,
+                key_terms: self.extract_key_terms(&text).await?,
+                summary: if unchanged { previous.analysis.summary.clone() } else { self.generate_summary(&text).await? },
+                sentiment_analysis: self.analyze_sentiment(&text).await?,
+                compliance_flags: self.check_compliance(&text, &document_type).await?,
+                metadata,
+                entities: merged.entities,
+                clauses: merged.clauses,
+                citations: merged.citations,
+                extracted_text: text,
+                output_language: None,
+            };
+            (analysis, replaced, fresh)
+        };
+
+        let changes = incremental_analysis::compare(&previous.analysis, &analysis, &replaced, &fresh);
+        let version = self.record_version(file_path, &analysis).await;
+        log::info!(
+            "Incremental analysis completed: {} changed sections{}, {} finding changes",
+            diff.sections().len(),
+            if full_reanalysis { " (full reanalysis)" } else { "" },
+            changes.len()
+        );
+        Ok(IncrementalAnalysis {
+            analysis: self.finish_analysis(analysis).await?,
+            version,
+            previous_version: Some(previous.version),
+            full_reanalysis,
+            changed_share,
+            changed_sections: diff.sections().to_vec(),
+            changes,
+        })
+    }
+
+    /// Keep the analysis as the version the next incremental analysis compares against
+    async fn record_version(&self, file_path: &Path, analysis: &DocumentAnalysis) -> u32 {
+        let fingerprint = self.fingerprint().await;
+        self.analysis_versions.record(file_path, analysis, &fingerprint).unwrap_or_else(|e| {
+            log::warn!("Analysis version not recorded for {:?}: {}", file_path, e);
+            0
+        })
+    }
+
+    /// Run every analysis over a document, without translation or glossary terms
+    async fn extract_analysis(&self, file_path: &Path) -> Result<DocumentAnalysis> {
         log::info!("Starting analysis of document: {:?}", file_path);
 
         // Extract metadata
@@ -304,7 +498,7 @@ impl DocumentAnalyzer {
             .check_compliance(&extracted_text, &metadata.document_type)
             .await?;
 
-        let analysis = DocumentAnalysis {
+        Ok(DocumentAnalysis {
             metadata: updated_metadata,
             extracted_text,
             entities,
@@ -315,7 +509,20 @@ impl DocumentAnalyzer {
             summary,
             sentiment_analysis,
             compliance_flags,
-        };
+            output_language: None,
+        })
+    }
+
+    /// Localize and cache an analysis before it is returned
+    async fn finish_analysis(&self, mut analysis: DocumentAnalysis) -> Result<DocumentAnalysis> {
+        // Render generated text in the user's chosen language; quotes from the document stay as written
+        if let Some(llm_manager) = &self.llm_manager {
+            let settings = self.output_language.get();
+            if let Err(e) = output_language::localize_analysis(&mut analysis, &settings, llm_manager).await {
+                log::warn!("Analysis text left untranslated: {}", e);
+            }
+        }
+        glossary::enforce_in_analysis(&mut analysis);
 
         // Cache the analysis
         self.cache_analysis(&analysis).await?;
@@ -339,11 +546,9 @@ impl DocumentAnalyzer {
             .unwrap_or("unknown")
             .to_lowercase();
 
-        // Classify document type based on filename and content
-        let document_type = self.classify_document_type(&filename).await;
-
-        // Extract text to detect language and count words
+        // Extract text to classify the document, detect language and count words
         let extracted_text = self.extract_text(file_path).await.unwrap_or_default();
+        let classification = self.classify_content(&filename, &extracted_text).await;
         let language = self.detect_language_from_text(&extracted_text);
         let word_count = self.calculate_word_count(&extracted_text);
 
@@ -354,7 +559,8 @@ impl DocumentAnalyzer {
             size: file_metadata.len(),
             uploaded_at: chrono::Utc::now(),
             processed_at: Some(chrono::Utc::now()),
-            document_type,
+            document_type_confidence: classification.document_type.is_some().then_some(classification.confidence),
+            document_type: classification.document_type,
             language,
             page_count: self.extract_page_count(file_path).await,
             word_count: Some(word_count),
@@ -364,42 +570,12 @@ impl DocumentAnalyzer {
 
     /// Detect language from text content using whatlang
     fn detect_language_from_text(&self, text: &str) -> String {
-        if text.is_empty() {
-            return "en".to_string();
-        }
-
-        match detect(text) {
-            Some(info) => {
-                match info.lang() {
-                    Lang::Eng => "en".to_string(),
-                    Lang::Spa => "es".to_string(),
-                    Lang::Fra => "fr".to_string(),
-                    Lang::Deu => "de".to_string(),
-                    Lang::Ita => "it".to_string(),
-                    Lang::Por => "pt".to_string(),
-                    Lang::Rus => "ru".to_string(),
-                    Lang::Jpn => "ja".to_string(),
-                    Lang::Kor => "ko".to_string(),
-                    Lang::Cmn => "zh".to_string(),
-                    _ => "en".to_string(), // Default to English for other languages
-                }
-            }
-            None => "en".to_string(),
-        }
+        text_processing::detect_language(text)
     }
 
     /// Calculate word count from text
     fn calculate_word_count(&self, text: &str) -> u32 {
-        if text.is_empty() {
-            return 0;
-        }
-
-        text.split_whitespace()
-            .filter(|word| {
-                // Filter out very short words and non-alphabetic content
-                word.len() > 1 && word.chars().any(|c| c.is_alphabetic())
-            })
-            .count() as u32
+        text_processing::word_count(text)
     }
 
     /// Extract page count from document
@@ -462,7 +638,7 @@ impl DocumentAnalyzer {
     }
 
     /// Extract text from various document formats
-    async fn extract_text(&self, file_path: &Path) -> Result<String> {
+    pub(crate) async fn extract_text(&self, file_path: &Path) -> Result<String> {
         let extension = file_path
             .extension()
             .and_then(|ext| ext.to_str())
@@ -626,38 +802,39 @@ impl DocumentAnalyzer {
         result
     }
 
-    /// Classify document type based on content and filename
-    async fn classify_document_type(&self, filename: &str) -> Option<DocumentType> {
-        let filename_lower = filename.to_lowercase();
+    /// Classify a document's type from its content, learning from the user's corrections
+    pub async fn classify_content(&self, filename: &str, text: &str) -> DocumentTypeClassification {
+        document_classifier::classify(filename, text, &self.type_corrections, self.llm_manager.as_deref()).await
+    }
 
-        if filename_lower.contains("contract") || filename_lower.contains("agreement") {
-            Some(DocumentType::Contract)
-        } else if filename_lower.contains("brief") {
-            Some(DocumentType::LegalBrief)
-        } else if filename_lower.contains("memo") {
-            Some(DocumentType::LegalMemo)
-        } else if filename_lower.contains("lease") {
-            Some(DocumentType::Lease)
-        } else if filename_lower.contains("nda") || filename_lower.contains("confidentiality") {
-            Some(DocumentType::NDA)
-        } else if filename_lower.contains("employment") {
-            Some(DocumentType::EmploymentAgreement)
-        } else if filename_lower.contains("will") || filename_lower.contains("testament") {
-            Some(DocumentType::Will)
-        } else if filename_lower.contains("power") && filename_lower.contains("attorney") {
-            Some(DocumentType::PowerOfAttorney)
-        } else {
-            None
-        }
+    pub fn type_corrections(&self) -> &TypeCorrections {
+        &self.type_corrections
     }
 
     /// Extract legal entities using NLP
     async fn extract_entities(&self, text: &str) -> Result<Vec<LegalEntity>> {
+        let date_order = locale_formats::detect_date_order(&self.detect_language_from_text(text), text);
+        self.extract_entities_in_order(text, date_order).await
+    }
+
+    /// Organizations, people and contract parties named in `text`, for the matter party registry
+    pub(crate) async fn extract_party_entities(&self, text: &str) -> Result<Vec<LegalEntity>> {
+        Ok(self
+            .extract_entities(text)
+            .await?
+            .into_iter()
+            .filter(|e| matches!(e.entity_type, EntityType::Organization | EntityType::Person | EntityType::ContractParty))
+            .collect())
+    }
+
+    /// Extract legal entities, reading numeric dates in the given order. Part of a document is
+    /// read in the order detected for the whole of it.
+    async fn extract_entities_in_order(&self, text: &str, date_order: DateOrder) -> Result<Vec<LegalEntity>> {
         let mut entities = Vec::new();
 
         // Use regex patterns for basic entity extraction
         entities.extend(self.extract_monetary_amounts(text));
-        entities.extend(self.extract_dates(text));
+        entities.extend(self.extract_dates(text, date_order));
         entities.extend(self.extract_percentages(text));
         entities.extend(self.extract_case_numbers(text));
         entities.extend(self.extract_legal_terms(text));
@@ -673,55 +850,42 @@ impl DocumentAnalyzer {
         Ok(entities)
     }
 
-    /// Extract monetary amounts
+    /// Extract monetary amounts in US and European notation ($1,000.00, € 1.000,00, 1.000 EUR)
     fn extract_monetary_amounts(&self, text: &str) -> Vec<LegalEntity> {
-        let mut entities = Vec::new();
-        let re = regex::Regex::new(r"\$[\d,]+(?:\.\d{2})?").unwrap();
-
-        for mat in re.find_iter(text) {
-            entities.push(LegalEntity {
+        locale_formats::find_monetary_amounts(text)
+            .into_iter()
+            .map(|amount| LegalEntity {
                 entity_type: EntityType::MonetaryAmount,
-                text: mat.as_str().to_string(),
                 confidence: 0.9,
-                start_pos: mat.start(),
-                end_pos: mat.end(),
-                context: self.get_context(text, mat.start(), mat.end()),
-            });
-        }
-
-        entities
+                start_pos: amount.start,
+                end_pos: amount.end,
+                context: self.get_context(text, amount.start, amount.end),
+                text: amount.text,
+                normalized_value: Some(amount.normalized),
+            })
+            .collect()
     }
 
-    /// Extract dates
-    fn extract_dates(&self, text: &str) -> Vec<LegalEntity> {
-        let mut entities = Vec::new();
-        let patterns = vec![
-            r"\d{1,2}/\d{1,2}/\d{4}",
-            r"\d{1,2}-\d{1,2}-\d{4}",
-            r"[A-Za-z]+ \d{1,2}, \d{4}",
-        ];
-
-        for pattern in patterns {
-            let re = regex::Regex::new(pattern).unwrap();
-            for mat in re.find_iter(text) {
-                entities.push(LegalEntity {
-                    entity_type: EntityType::Date,
-                    text: mat.as_str().to_string(),
-                    confidence: 0.8,
-                    start_pos: mat.start(),
-                    end_pos: mat.end(),
-                    context: self.get_context(text, mat.start(), mat.end()),
-                });
-            }
-        }
-
-        entities
+    /// Extract dates, reading numeric dates in the document's day/month order
+    fn extract_dates(&self, text: &str, order: DateOrder) -> Vec<LegalEntity> {
+        locale_formats::find_dates(text, order)
+            .into_iter()
+            .map(|date| LegalEntity {
+                entity_type: EntityType::Date,
+                confidence: 0.8,
+                start_pos: date.start,
+                end_pos: date.end,
+                context: self.get_context(text, date.start, date.end),
+                text: date.text,
+                normalized_value: Some(date.normalized),
+            })
+            .collect()
     }
 
     /// Extract percentages
     fn extract_percentages(&self, text: &str) -> Vec<LegalEntity> {
         let mut entities = Vec::new();
-        let re = regex::Regex::new(r"\d+(?:\.\d+)?%").unwrap();
+        let re = regex::Regex::new(r"\d+(?:[.,]\d+)?\s?%").unwrap();
 
         for mat in re.find_iter(text) {
             entities.push(LegalEntity {
@@ -731,6 +895,7 @@ impl DocumentAnalyzer {
                 start_pos: mat.start(),
                 end_pos: mat.end(),
                 context: self.get_context(text, mat.start(), mat.end()),
+                normalized_value: None,
             });
         }
 
@@ -756,6 +921,7 @@ impl DocumentAnalyzer {
                     start_pos: mat.start(),
                     end_pos: mat.end(),
                     context: self.get_context(text, mat.start(), mat.end()),
+                    normalized_value: None,
                 });
             }
         }
@@ -798,6 +964,7 @@ impl DocumentAnalyzer {
                     start_pos: pos,
                     end_pos: pos + term.len(),
                     context: self.get_context(text, pos, pos + term.len()),
+                    normalized_value: None,
                 });
             }
         }
@@ -842,6 +1009,7 @@ impl DocumentAnalyzer {
                     start_pos: mat.start(),
                     end_pos: mat.end(),
                     context: self.get_context(text, mat.start(), mat.end()),
+                    normalized_value: None,
                 });
             }
         }
@@ -867,6 +1035,7 @@ impl DocumentAnalyzer {
                     start_pos: mat.start(),
                     end_pos: mat.end(),
                     context: self.get_context(text, mat.start(), mat.end()),
+                    normalized_value: None,
                 });
             }
         }
@@ -903,6 +1072,7 @@ impl DocumentAnalyzer {
                     start_pos: mat.start(),
                     end_pos: mat.end(),
                     context: self.get_context(text, mat.start(), mat.end()),
+                    normalized_value: None,
                 });
             }
         }
@@ -933,6 +1103,7 @@ impl DocumentAnalyzer {
                                 start_pos: party_match.start(),
                                 end_pos: party_match.end(),
                                 context: self.get_context(text, party_match.start(), party_match.end()),
+                                normalized_value: None,
                             });
                         }
                     }
@@ -990,6 +1161,7 @@ impl DocumentAnalyzer {
                             start_pos: pos,
                             end_pos: pos + name.len(),
                             context: self.get_context(original_text, pos, pos + name.len()),
+                            normalized_value: None,
                         });
                     }
                 }
@@ -1015,8 +1187,15 @@ impl DocumentAnalyzer {
     /// Get context around an entity
     fn get_context(&self, text: &str, start: usize, end: usize) -> String {
         let context_size = 50;
-        let context_start = start.saturating_sub(context_size);
-        let context_end = (end + context_size).min(text.len());
+        let mut context_start = start.saturating_sub(context_size);
+        let mut context_end = (end + context_size).min(text.len());
+        // Widen to character boundaries so accented text and "€" never split mid-character
+        while !text.is_char_boundary(context_start) {
+            context_start -= 1;
+        }
+        while !text.is_char_boundary(context_end) {
+            context_end += 1;
+        }
 
         text[context_start..context_end].to_string()
     }
@@ -1159,7 +1338,7 @@ impl DocumentAnalyzer {
     /// Assess document risks
     async fn assess_risks(
         &self,
-        _text: &str,
+        text: &str,
         clauses: &[ContractClause],
     ) -> Result<Vec<RiskAssessment>> {
         let mut risks = Vec::new();
@@ -1199,6 +1378,11 @@ impl DocumentAnalyzer {
             }
         }
 
+        // Read the installed packages afresh, so rules installed since startup apply
+        if let Ok(packages) = WorkflowPackages::new(&self.app_data_dir) {
+            risks.extend(clause_rule_risks(text, &packages.clause_rules()));
+        }
+
         Ok(risks)
     }
 
@@ -1208,24 +1392,37 @@ impl DocumentAnalyzer {
             return Ok(Vec::new());
         }
 
-        // Tokenize and clean the text
-        let tokens = self.tokenize_text(text);
+        // Stop words and stemming follow the document's language
+        let tools = LanguageTools::for_language(&self.detect_language_from_text(text));
+        let words = self.tokenize_text(text, &tools);
+        let stems: Vec<String> = words.iter().map(|word| tools.stem(word)).collect();
 
         // Calculate term frequencies
-        let term_frequencies = self.calculate_term_frequencies(&tokens);
+        let term_frequencies = self.calculate_term_frequencies(&stems);
+
+        // Inflections are counted under their stem but reported by their most common form
+        let mut surface_forms: HashMap<&str, HashMap<&str, u32>> = HashMap::new();
+        for (stem, word) in stems.iter().zip(&words) {
+            *surface_forms.entry(stem).or_default().entry(word).or_default() += 1;
+        }
 
         // For simplicity, we'll use a basic scoring approach
         // In a real implementation, you'd calculate IDF across a corpus
         let mut key_terms = Vec::new();
 
-        for (term, frequency) in term_frequencies {
-            // Skip very short terms and common words
-            if term.len() > 3 && frequency > 1 && !self.is_stop_word(&term) {
-                let importance = self.calculate_term_importance(&term, frequency, &tokens);
+        for (stem, frequency) in term_frequencies {
+            // Skip very short terms
+            if stem.chars().count() > 3 && frequency > 1 {
+                let term = surface_forms[stem.as_str()]
+                    .iter()
+                    .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)))
+                    .map(|(word, _)| word.to_string())
+                    .unwrap_or(stem);
+                let importance = self.calculate_term_importance(&term, frequency, &stems);
                 let category = self.categorize_term(&term);
 
                 key_terms.push(KeyTerm {
-                    term: term.clone(),
+                    term,
                     definition: None, // Could be populated from a legal dictionary
                     importance,
                     frequency,
@@ -1241,42 +1438,14 @@ impl DocumentAnalyzer {
         Ok(key_terms)
     }
 
-    /// Tokenize text into meaningful terms
-    fn tokenize_text(&self, text: &str) -> Vec<String> {
-        text.to_lowercase()
-            .split_whitespace()
-            .map(|word| {
-                // Remove punctuation
-                let cleaned: String = word.chars()
-                    .filter(|c| c.is_alphanumeric())
-                    .collect();
-
-                // Simple stemming (remove common suffixes)
-                if cleaned.len() > 2 {
-                    self.simple_stem(&cleaned)
-                } else {
-                    cleaned
-                }
-            })
-            .filter(|word| word.len() > 2)
+    /// Tokenize text into lowercase words on Unicode word boundaries, dropping stop words
+    fn tokenize_text(&self, text: &str, tools: &LanguageTools) -> Vec<String> {
+        text_processing::words(text)
+            .into_iter()
+            .filter(|word| word.chars().count() > 2 && !tools.is_stop_word(word))
             .collect()
     }
 
-    /// Simple stemming algorithm (basic suffix removal)
-    fn simple_stem(&self, word: &str) -> String {
-        let suffixes = vec![
-            "ing", "ed", "er", "est", "ly", "tion", "sion", "ness", "ment", "able", "ible"
-        ];
-
-        for suffix in suffixes {
-            if word.len() > suffix.len() + 2 && word.ends_with(suffix) {
-                return word[..word.len() - suffix.len()].to_string();
-            }
-        }
-
-        word.to_string()
-    }
-
     /// Calculate term frequencies
     fn calculate_term_frequencies(&self, tokens: &[String]) -> HashMap<String, u32> {
         let mut frequencies = HashMap::new();
@@ -1288,19 +1457,6 @@ impl DocumentAnalyzer {
         frequencies
     }
 
-    /// Check if a term is a stop word
-    fn is_stop_word(&self, term: &str) -> bool {
-        let stop_words = vec![
-            "a", "an", "and", "are", "as", "at", "be", "by", "for", "from",
-            "has", "he", "in", "is", "it", "its", "of", "on", "that", "the",
-            "to", "was", "will", "with", "would", "have", "had", "been", "not",
-            "but", "or", "can", "could", "should", "may", "might", "must",
-            "shall", "this", "these", "those", "they", "them", "their", "there",
-            "where", "when", "what", "who", "whom", "which", "how", "why",
-        ];
-        stop_words.contains(&term.to_lowercase().as_str())
-    }
-
     /// Calculate term importance (simplified TF-IDF-like score)
     fn calculate_term_importance(&self, term: &str, frequency: u32, tokens: &[String]) -> f32 {
         let tf = frequency as f32 / tokens.len() as f32;
@@ -1376,26 +1532,7 @@ impl DocumentAnalyzer {
 
     /// Extract legal citations
     async fn extract_citations(&self, text: &str) -> Result<Vec<LegalCitation>> {
-        let mut citations = Vec::new();
-
-        // Pattern for case citations (simplified)
-        let case_pattern = r"(\w+\s+v\.?\s+\w+),?\s*(\d+)\s+(\w+\.?\s*\d*)\s+(\d+)\s*\((\d{4})\)";
-        let re = regex::Regex::new(case_pattern).unwrap();
-
-        for cap in re.captures_iter(text) {
-            citations.push(LegalCitation {
-                citation_text: cap.get(0).unwrap().as_str().to_string(),
-                case_name: cap.get(1).map(|m| m.as_str().to_string()),
-                court: None,
-                year: cap.get(5).and_then(|m| m.as_str().parse().ok()),
-                volume: cap.get(2).map(|m| m.as_str().to_string()),
-                page: cap.get(4).map(|m| m.as_str().to_string()),
-                citation_type: CitationType::CaseLaw,
-                verification_status: CitationStatus::Unverified,
-            });
-        }
-
-        Ok(citations)
+        Ok(parse_citations(text).into_iter().map(|(_, citation)| citation).collect())
     }
 
     /// Generate document summary
@@ -1866,14 +2003,64 @@ impl DocumentAnalyzer {
 // Tauri commands for document analysis
 #[tauri::command]
 pub async fn analyze_document_file(
+    session_id: String,
     analyzer: tauri::State<'_, Arc<DocumentAnalyzer>>,
+    review: tauri::State<'_, ReviewQueueStorage>,
+    sessions: tauri::State<'_, SessionStorage>,
     file_path: String,
 ) -> Result<DocumentAnalysis, String> {
+    let user = authenticated_user(&session_id, &sessions)?;
     let path = Path::new(&file_path);
-    analyzer
+    let analysis = analyzer
         .analyze_document(path)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+    submit_analysis_for_review(path, &analyzer, &review, &user)?;
+    Ok(analysis)
+}
+
+/// What the review of an analysis covers: its findings and the rule pack and model that
+/// produced them. The run's id and timestamps are left out, so an unchanged re-run keeps its
+/// decision and a different result, from a changed document or engine, needs review again.
+pub fn analysis_sha256(version: &AnalysisVersion) -> String {
+    let mut analysis = version.analysis.clone();
+    analysis.metadata.id.clear();
+    analysis.metadata.uploaded_at = Default::default();
+    analysis.metadata.processed_at = None;
+    let content = serde_json::json!({ "analysis": analysis, "fingerprint": version.fingerprint });
+    review_queue::json_sha256(&content)
+}
+
+/// Queue the analysis last recorded for a document for attorney review
+pub(crate) fn submit_analysis_for_review(path: &Path, analyzer: &DocumentAnalyzer, review: &ReviewQueue, user: &str) -> Result<(), String> {
+    let reference = path.to_string_lossy().to_string();
+    let title = format!("Analysis of {}", path.file_name().and_then(|n| n.to_str()).unwrap_or(&reference));
+    let submission = analyzer
+        .analysis_versions()
+        .latest(path)
+        .ok_or_else(|| anyhow::anyhow!("no analysis of {} was recorded", reference))
+        .map(|version| Submission {
+            kind: WorkProductKind::Analysis,
+            title,
+            source: "document_analyzer".to_string(),
+            reference,
+            path: None,
+            matter_id: None,
+            content_sha256: analysis_sha256(&version),
+        });
+    review_queue::submit_generated(review, user, submission)?;
+    Ok(())
+}
+
+/// Delivery gate for a document that goes out with its AI analysis: the analysis last recorded
+/// for it must have been approved as it stands
+pub(crate) fn check_analysis_released(path: &Path, analyzer: &DocumentAnalyzer, review: &ReviewQueue) -> Result<(), String> {
+    let reference = path.to_string_lossy();
+    let version = analyzer
+        .analysis_versions()
+        .latest(path)
+        .ok_or_else(|| format!("{} has not been analyzed", reference))?;
+    review.check_released(&reference, Some(WorkProductKind::Analysis), &analysis_sha256(&version))
 }
 
 #[tauri::command]
@@ -1892,6 +2079,121 @@ pub async fn get_document_types() -> Result<Vec<String>, String> {
     ])
 }
 
+/// Words that can open a sentence before a case name but are not part of it
+const CASE_NAME_PREFIXES: &[&str] = &[
+    "See", "Cf.", "Accord", "E.g.,", "Compare", "But", "In", "The", "Under", "As", "And", "Although", "Because", "Unlike", "Like",
+];
+
+/// Reporter abbreviation such as "U.S.", "F.3d", "S. Ct." or "F. Supp. 2d"
+pub const REPORTER_PATTERN: &str = r"[A-Z][A-Za-z.]*(?:\s?(?:[A-Z][A-Za-z.]*|\d[a-z]{1,2}))*";
+
+fn case_name(raw: &str) -> String {
+    let mut name = raw.trim().trim_end_matches(',').trim();
+    // "In re" opens a case name; "In Smith v. Jones" does not
+    while let Some(prefix) = CASE_NAME_PREFIXES
+        .iter()
+        .find(|p| name.starts_with(&format!("{} ", p)) && !(**p == "In" && name.starts_with("In re ")))
+    {
+        name = name[prefix.len()..].trim_start();
+    }
+    name.to_string()
+}
+
+fn citation(text: &str, citation_type: CitationType) -> LegalCitation {
+    LegalCitation {
+        citation_text: text.to_string(),
+        case_name: None,
+        court: None,
+        year: None,
+        volume: None,
+        page: None,
+        citation_type,
+        verification_status: CitationStatus::Unverified,
+    }
+}
+
+/// Case, statute, regulation, constitutional, court rule and secondary citations with the byte
+/// offset of each in `text`, in document order. Short forms ("410 U.S. at 153") are not included.
+pub fn parse_citations(text: &str) -> Vec<(usize, LegalCitation)> {
+    let word = r"[A-Z][\w.'&-]*,?";
+    let connector = r"(?:of|the|and|for|de|ex rel\.|&)";
+    let name_words = format!(r"{w}(?:\s+(?:{w}|{c}))*", w = word, c = connector);
+    let case = regex::Regex::new(&format!(
+        r"(?P<name>(?:In re|Ex parte)\s+{n}|{n}\s+v\.?\s+{n}),?\s+(?P<volume>\d+)\s+(?P<reporter>{r})\s+(?P<page>\d+)(?:,\s*\d+(?:[-–]\d+)?)*\s*\((?P<court>[^()]*?)\s*(?P<year>\d{{4}})\)",
+        n = name_words,
+        r = REPORTER_PATTERN
+    ))
+    .unwrap();
+
+    let mut found: Vec<(usize, usize, LegalCitation)> = Vec::new();
+    for caps in case.captures_iter(text) {
+        let whole = caps.get(0).unwrap();
+        let name = case_name(&caps["name"]);
+        let name_start = whole.start() + caps["name"].rfind(&name).unwrap_or(0);
+        let court = caps["court"].trim();
+        found.push((
+            name_start,
+            whole.end(),
+            LegalCitation {
+                citation_text: text[name_start..whole.end()].to_string(),
+                case_name: Some(name),
+                court: (!court.is_empty()).then(|| court.to_string()),
+                year: caps["year"].parse().ok(),
+                volume: Some(caps["volume"].to_string()),
+                page: Some(caps["page"].to_string()),
+                citation_type: CitationType::CaseLaw,
+                verification_status: CitationStatus::Unverified,
+            },
+        ));
+    }
+
+    let others = [
+        (r"\b\d+\s+U\.S\.C\.?(?:\s?A\.)?\s*§§?\s*\d[\w.-]*(?:\([A-Za-z0-9]+\))*", CitationType::Statute),
+        (
+            r"\b(?:[A-Z][A-Za-z]*\.(?:[A-Z]\.)*\s+(?:&\s+)?){1,5}(?:Code|Stat\.|Law|Laws)(?:\s+Ann\.)?\s*§§?\s*\d[\w.:-]*(?:\([A-Za-z0-9]+\))*",
+            CitationType::Statute,
+        ),
+        (r"\b\d+\s+C\.F\.R\.\s*§§?\s*\d[\d.]*(?:\([A-Za-z0-9]+\))*", CitationType::Regulation),
+        (r"\b\d+\s+Fed\.\s?Reg\.\s+\d+", CitationType::Regulation),
+        (
+            r"\bU\.S\.\s+Const\.\s+(?:art\.\s+[IVX]+|amend\.\s+[IVXL]+)(?:,\s*§\s*\d+)?(?:,\s*cl\.\s*\d+)?",
+            CitationType::Constitutional,
+        ),
+        (
+            r"\bFed\.\s+R\.\s+(?:(?:Civ|Crim|App|Bankr)\.\s+P\.|Evid\.)\s+\d+(?:\.\d+)?(?:\([A-Za-z0-9]+\))*",
+            CitationType::CourtRule,
+        ),
+        (
+            r"\bRestatement\s+\((?:First|Second|Third|Fourth)\)\s+of\s+(?:[A-Z][a-z]*\s+|and\s+|of\s+)+§\s*\d+",
+            CitationType::Secondary,
+        ),
+        (r"\b\d+\s+(?:[A-Z][A-Za-z.]*\s+)*L\.\s?(?:Rev|J)\.\s+\d+(?:,\s*\d+)?\s*\(\d{4}\)", CitationType::Secondary),
+    ];
+    for (pattern, citation_type) in others {
+        let re = regex::Regex::new(pattern).unwrap();
+        for found_match in re.find_iter(text) {
+            // A sentence-ending period is not part of the section number
+            let matched = found_match.as_str().trim_end_matches(['.', ',']);
+            found.push((
+                found_match.start(),
+                found_match.start() + matched.len(),
+                citation(matched, citation_type.clone()),
+            ));
+        }
+    }
+
+    found.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| b.1.cmp(&a.1)));
+    let mut citations: Vec<(usize, LegalCitation)> = Vec::new();
+    let mut covered_to = 0;
+    for (start, end, citation) in found {
+        if start >= covered_to {
+            covered_to = end;
+            citations.push((start, citation));
+        }
+    }
+    citations
+}
+
 // Include tests
 #[cfg(test)]
 mod tests {
@@ -1989,10 +2291,45 @@ mod tests {
     async fn test_stemming() {
         let analyzer = create_test_analyzer().await;
 
-        assert_eq!(analyzer.simple_stem("running"), "runn");
-        assert_eq!(analyzer.simple_stem("beautiful"), "beauti");
-        assert_eq!(analyzer.simple_stem("agreement"), "agreement");
-        assert_eq!(analyzer.simple_stem("cat"), "cat");
+        let english = LanguageTools::for_language("en");
+        assert_eq!(english.stem("running"), "run");
+        assert_eq!(english.stem("agreements"), "agreement");
+        assert_eq!(english.stem("cat"), "cat");
+
+        let dutch = LanguageTools::for_language("nl");
+        assert_eq!(dutch.stem("overeenkomsten"), "overeenkomst");
+
+        // Inflected forms are grouped and reported in their most common form
+        let text = "Der Vertrag endet. Die Verträge enden. Der Vertrag gilt für alle Verträge und Vertrag.";
+        let key_terms = analyzer.extract_key_terms(text).await.unwrap();
+        let vertrag = key_terms.iter().find(|t| t.term == "vertrag").unwrap();
+        assert_eq!(vertrag.frequency, 5);
+    }
+
+    #[test]
+    fn test_parse_citations_by_type() {
+        let text = "See Bell Atl. Corp. v. Twombly, 550 U.S. 544, 570 (2007); In re Marriage of Smith, 123 Cal. App. 4th 1 (2004). \
+                    Under 42 U.S.C. § 1983 and Cal. Civ. Proc. Code § 2030.030, see also 21 C.F.R. § 314.50(d), \
+                    U.S. Const. amend. XIV, § 1, Fed. R. Civ. P. 12(b)(6) and 98 Harv. L. Rev. 1 (1984).";
+        let citations = parse_citations(text);
+        let types: Vec<(&str, String)> = citations
+            .iter()
+            .map(|(_, c)| (c.citation_text.as_str(), format!("{:?}", c.citation_type)))
+            .collect();
+        assert_eq!(types, vec![
+            ("Bell Atl. Corp. v. Twombly, 550 U.S. 544, 570 (2007)", "CaseLaw".to_string()),
+            ("In re Marriage of Smith, 123 Cal. App. 4th 1 (2004)", "CaseLaw".to_string()),
+            ("42 U.S.C. § 1983", "Statute".to_string()),
+            ("Cal. Civ. Proc. Code § 2030.030", "Statute".to_string()),
+            ("21 C.F.R. § 314.50(d)", "Regulation".to_string()),
+            ("U.S. Const. amend. XIV, § 1", "Constitutional".to_string()),
+            ("Fed. R. Civ. P. 12(b)(6)", "CourtRule".to_string()),
+            ("98 Harv. L. Rev. 1 (1984)", "Secondary".to_string()),
+        ]);
+        let twombly = &citations[0];
+        assert_eq!(twombly.0, text.find("Bell").unwrap());
+        assert_eq!(twombly.1.case_name.as_deref(), Some("Bell Atl. Corp. v. Twombly"));
+        assert_eq!((twombly.1.volume.as_deref(), twombly.1.page.as_deref(), twombly.1.year), (Some("550"), Some("544"), Some(2007)));
     }
 
     async fn create_test_analyzer() -> DocumentAnalyzer {
@@ -0,0 +1,575 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::document_analyzer::DocumentType;
+use crate::llm_manager::{GenerateOptions, GenerateRequest, LLMManager};
+use crate::local_api::AnalyzerStorage;
+use crate::review_queue;
+use crate::text_processing::{self, LanguageTools};
+
+/// Document Type Classification for BEAR AI
+/// Assigns a `DocumentType` from a document's content, with a confidence. A document that closely
+/// matches one the user has already corrected takes the corrected type; otherwise a loaded model
+/// classifies the opening of the document from a few-shot prompt whose examples include the most
+/// similar corrections, and indicator phrases (then the filename) are the fallback when no model is
+/// loaded or its answer cannot be read.
+// Indicators are matched against the lowercased opening of the document; the filename counts double
+const TYPE_INDICATORS: &[(&str, &[&str])] = &[
+    ("court_filing", &["plaintiff", "defendant", "claimant", "respondent", "case no", "complaint", "motion to", "petition"]),
+    ("legal_brief", &["brief in support", "memorandum of law", "statement of facts", "argument"]),
+    ("legal_memo", &["memorandum", "memo", "to:", "from:", "re:"]),
+    ("correspondence", &["dear ", "sincerely", "kind regards", "yours faithfully", "letter"]),
+    ("nda", &["non-disclosure", "confidentiality agreement", "confidential information", "nda"]),
+    ("employment", &["employment agreement", "employee", "employer", "salary"]),
+    ("lease", &["lease", "landlord", "tenant", "premises"]),
+    ("will", &["last will and testament", "executor", "bequeath"]),
+    ("power_of_attorney", &["power of attorney", "attorney-in-fact"]),
+    ("contract", &["agreement", "contract", "the parties", "hereby agree", "in witness whereof"]),
+    ("financial", &["invoice", "balance sheet", "amount due", "statement of account"]),
+];
+
+/// Types the model may choose from, as `DocumentType` variant names
+const CLASSIFIABLE_TYPES: &[&str] = &[
+    "Contract",
+    "NDA",
+    "EmploymentAgreement",
+    "Lease",
+    "Will",
+    "PowerOfAttorney",
+    "LegalBrief",
+    "LegalMemo",
+    "CourtFiling",
+    "CaseLaw",
+    "Statute",
+    "Regulation",
+    "Evidence",
+    "Correspondence",
+    "Patent",
+    "Trademark",
+    "Copyright",
+    "Financial",
+    "CorporateGovernance",
+    "Compliance",
+    "RegulatoryFiling",
+];
+
+// Built-in examples; corrections similar to the document are added after these
+const FEW_SHOT_EXAMPLES: &[(&str, &str)] = &[
+    ("IN THE UNITED STATES DISTRICT COURT ... ACME LTD, Plaintiff, v. BETA INC, Defendant. Case No. 1:23-cv-0456. COMPLAINT", "CourtFiling"),
+    ("This Agreement is made between Acme Ltd (the \"Supplier\") and Beta BV (the \"Customer\"). 1. Definitions ... IN WITNESS WHEREOF", "Contract"),
+    ("MEMORANDUM. To: Litigation team. From: J. Smith. Re: Limitation period for the Beta claim. Question presented ...", "LegalMemo"),
+    ("Dear Ms Jansen, Further to our call of 3 May, we write to confirm ... Yours sincerely", "Correspondence"),
+];
+
+const CLASSIFY_WINDOW: usize = 3000;
+/// A correction this similar to the document decides its type without asking the model
+const CORRECTION_MATCH: f32 = 0.85;
+/// Corrections at least this similar are shown to the model as examples
+const CORRECTION_EXAMPLE: f32 = 0.3;
+const MAX_CORRECTION_EXAMPLES: usize = 3;
+const PROFILE_TERMS: usize = 150;
+const EXCERPT_CHARS: usize = 400;
+const PROMPT_CHARS: usize = 1500;
+const MAX_CORRECTIONS: usize = 500;
+// Indicator phrases are weaker evidence than the model or a correction
+const MAX_KEYWORD_CONFIDENCE: f32 = 0.75;
+const FILENAME_CONFIDENCE: f32 = 0.3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClassificationMethod {
+    Correction, // a previously corrected document with near-identical content
+    Llm,
+    Keywords,
+    Filename,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TypeScore {
+    pub document_type: DocumentType,
+    pub confidence: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentTypeClassification {
+    pub document_type: Option<DocumentType>,
+    pub confidence: f32, // 0.0 to 1.0
+    pub method: Option<ClassificationMethod>,
+    pub alternatives: Vec<TypeScore>, // other types the indicator phrases point to
+    pub matched_correction: Option<String>, // id of the correction that decided or informed the type
+    pub model: Option<String>,
+}
+
+/// A user's correction, kept as a term profile so that similar documents are classified the same way
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TypeCorrection {
+    pub id: String,
+    pub filename: String,
+    pub document_type: DocumentType,
+    pub predicted: Option<DocumentType>,
+    pub content_sha256: String,
+    pub excerpt: String,
+    pub terms: HashMap<String, f32>, // unit-length weights of the most frequent stemmed terms
+    pub corrected_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TypeCorrectionInfo {
+    pub id: String,
+    pub filename: String,
+    pub document_type: DocumentType,
+    pub predicted: Option<DocumentType>,
+    pub corrected_at: DateTime<Utc>,
+}
+
+fn document_type(key: &str) -> DocumentType {
+    match key {
+        "court_filing" => DocumentType::CourtFiling,
+        "legal_brief" => DocumentType::LegalBrief,
+        "legal_memo" => DocumentType::LegalMemo,
+        "correspondence" => DocumentType::Correspondence,
+        "nda" => DocumentType::NDA,
+        "employment" => DocumentType::EmploymentAgreement,
+        "lease" => DocumentType::Lease,
+        "will" => DocumentType::Will,
+        "power_of_attorney" => DocumentType::PowerOfAttorney,
+        "financial" => DocumentType::Financial,
+        _ => DocumentType::Contract,
+    }
+}
+
+fn opening(text: &str) -> &str {
+    let mut end = text.len().min(CLASSIFY_WINDOW);
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
+}
+
+/// Indicator scores per type, in table order, for types with at least one indicator present
+fn keyword_scores(filename: &str, text: &str) -> Vec<(&'static str, usize)> {
+    let head = opening(text).to_lowercase();
+    let filename = filename.to_lowercase();
+    TYPE_INDICATORS
+        .iter()
+        .map(|(key, indicators)| {
+            let score = indicators
+                .iter()
+                .map(|indicator| head.matches(indicator).count().min(3) + 2 * usize::from(filename.contains(indicator)))
+                .sum();
+            (*key, score)
+        })
+        .filter(|(_, score)| *score > 0)
+        .collect()
+}
+
+/// Types the indicator phrases point to, best first. Confidence grows with the number of hits
+/// and shrinks as other types compete.
+pub fn keyword_classification(filename: &str, text: &str) -> Vec<TypeScore> {
+    let scores = keyword_scores(filename, text);
+    let total: usize = scores.iter().map(|(_, score)| score).sum();
+    let mut ranked = scores;
+    // The sort is stable, so earlier entries win ties: a complaint about a contract is a court filing
+    ranked.sort_by_key(|(_, score)| Reverse(*score));
+    ranked
+        .into_iter()
+        .map(|(key, score)| TypeScore {
+            document_type: document_type(key),
+            confidence: score as f32 / total as f32 * (score.min(6) as f32 / 6.0) * MAX_KEYWORD_CONFIDENCE,
+        })
+        .collect()
+}
+
+/// The type a filename suggests, when it names one
+pub fn filename_type(filename: &str) -> Option<DocumentType> {
+    let filename_lower = filename.to_lowercase();
+
+    if filename_lower.contains("contract") || filename_lower.contains("agreement") {
+        Some(DocumentType::Contract)
+    } else if filename_lower.contains("brief") {
+        Some(DocumentType::LegalBrief)
+    } else if filename_lower.contains("memo") {
+        Some(DocumentType::LegalMemo)
+    } else if filename_lower.contains("lease") {
+        Some(DocumentType::Lease)
+    } else if filename_lower.contains("nda") || filename_lower.contains("confidentiality") {
+        Some(DocumentType::NDA)
+    } else if filename_lower.contains("employment") {
+        Some(DocumentType::EmploymentAgreement)
+    } else if filename_lower.contains("will") || filename_lower.contains("testament") {
+        Some(DocumentType::Will)
+    } else if filename_lower.contains("power") && filename_lower.contains("attorney") {
+        Some(DocumentType::PowerOfAttorney)
+    } else {
+        None
+    }
+}
+
+/// A `DocumentType` from its variant name, ignoring case, spaces and underscores ("Legal Brief")
+pub fn parse_type(name: &str) -> Option<DocumentType> {
+    let normalized: String = name.chars().filter(|c| c.is_alphanumeric()).collect::<String>().to_lowercase();
+    let variant = CLASSIFIABLE_TYPES.iter().find(|t| t.to_lowercase() == normalized)?;
+    serde_json::from_value(serde_json::Value::String(variant.to_string())).ok()
+}
+
+/// The type and confidence from a model answer such as {"type": "Lease", "confidence": 0.9}
+pub fn parse_verdict(response: &str) -> Option<(DocumentType, f32)> {
+    let start = response.find('{')?;
+    let end = response.rfind('}')?;
+    let value: serde_json::Value = serde_json::from_str(response.get(start..=end)?).ok()?;
+    let document_type = parse_type(value["type"].as_str()?)?;
+    let confidence = value["confidence"].as_f64().unwrap_or(0.5) as f32;
+    Some((document_type, confidence.clamp(0.0, 1.0)))
+}
+
+/// Unit-length weights of the document's most frequent stemmed terms
+pub fn term_profile(text: &str) -> HashMap<String, f32> {
+    let head = opening(text);
+    let tools = LanguageTools::for_language(&text_processing::detect_language(head));
+    let mut counts: HashMap<String, f32> = HashMap::new();
+    for word in text_processing::words(head) {
+        if word.len() > 2 && !tools.is_stop_word(&word) && !word.chars().all(|c| c.is_ascii_digit()) {
+            *counts.entry(tools.stem(&word)).or_default() += 1.0;
+        }
+    }
+    let mut terms: Vec<(String, f32)> = counts.into_iter().collect();
+    terms.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    terms.truncate(PROFILE_TERMS);
+    let norm = terms.iter().map(|(_, w)| w * w).sum::<f32>().sqrt().max(f32::EPSILON);
+    terms.into_iter().map(|(term, weight)| (term, weight / norm)).collect()
+}
+
+fn similarity(a: &HashMap<String, f32>, b: &HashMap<String, f32>) -> f32 {
+    a.iter().filter_map(|(term, weight)| b.get(term).map(|other| weight * other)).sum()
+}
+
+fn excerpt(text: &str, chars: usize) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ").chars().take(chars).collect()
+}
+
+/// Corrections made by the user, stored next to the other settings files
+#[derive(Debug)]
+pub struct TypeCorrections {
+    path: PathBuf,
+    corrections: Mutex<Vec<TypeCorrection>>,
+}
+
+impl TypeCorrections {
+    pub fn new(app_data_dir: &Path) -> Self {
+        let path = app_data_dir.join("document_type_corrections.json");
+        let corrections = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+        TypeCorrections {
+            path,
+            corrections: Mutex::new(corrections),
+        }
+    }
+
+    fn save(&self, corrections: &[TypeCorrection]) -> Result<()> {
+        std::fs::write(&self.path, serde_json::to_string_pretty(corrections)?)?;
+        Ok(())
+    }
+
+    /// Record the correct type for a document; a later correction of the same content replaces it
+    pub fn record(
+        &self,
+        filename: &str,
+        text: &str,
+        document_type: DocumentType,
+        predicted: Option<DocumentType>,
+    ) -> Result<TypeCorrectionInfo> {
+        if text.trim().is_empty() {
+            return Err(anyhow!("{} has no text to learn from", filename));
+        }
+        let correction = TypeCorrection {
+            id: uuid::Uuid::new_v4().to_string(),
+            filename: filename.to_string(),
+            document_type,
+            predicted,
+            content_sha256: review_queue::sha256_hex(text.as_bytes()),
+            excerpt: excerpt(text, EXCERPT_CHARS),
+            terms: term_profile(text),
+            corrected_at: Utc::now(),
+        };
+        let mut corrections = self.corrections.lock().unwrap();
+        corrections.retain(|c| c.content_sha256 != correction.content_sha256);
+        corrections.push(correction.clone());
+        if corrections.len() > MAX_CORRECTIONS {
+            let excess = corrections.len() - MAX_CORRECTIONS;
+            corrections.drain(..excess);
+        }
+        self.save(&corrections)?;
+        Ok(correction.info())
+    }
+
+    pub fn remove(&self, id: &str) -> Result<bool> {
+        let mut corrections = self.corrections.lock().unwrap();
+        let before = corrections.len();
+        corrections.retain(|c| c.id != id);
+        if corrections.len() == before {
+            return Ok(false);
+        }
+        self.save(&corrections)?;
+        Ok(true)
+    }
+
+    /// Most recent first
+    pub fn list(&self) -> Vec<TypeCorrectionInfo> {
+        self.corrections.lock().unwrap().iter().rev().map(TypeCorrection::info).collect()
+    }
+
+    /// Corrections at least `threshold` similar to the profile, most similar first
+    fn similar(&self, profile: &HashMap<String, f32>, threshold: f32) -> Vec<(f32, TypeCorrection)> {
+        let mut similar: Vec<(f32, TypeCorrection)> = self
+            .corrections
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|c| (similarity(profile, &c.terms), c))
+            .filter(|(score, _)| *score >= threshold)
+            .map(|(score, c)| (score, c.clone()))
+            .collect();
+        similar.sort_by(|a, b| b.0.total_cmp(&a.0));
+        similar
+    }
+}
+
+impl TypeCorrection {
+    fn info(&self) -> TypeCorrectionInfo {
+        TypeCorrectionInfo {
+            id: self.id.clone(),
+            filename: self.filename.clone(),
+            document_type: self.document_type.clone(),
+            predicted: self.predicted.clone(),
+            corrected_at: self.corrected_at,
+        }
+    }
+}
+
+pub fn classification_prompt(text: &str, examples: &[(f32, TypeCorrection)]) -> String {
+    let mut prompt = format!(
+        "Classify the legal document by its content. Answer with JSON only: {{\"type\": \"<one of {}>\", \"confidence\": <0.0 to 1.0>}}.\n\n",
+        CLASSIFIABLE_TYPES.join(", ")
+    );
+    for (opening, label) in FEW_SHOT_EXAMPLES {
+        prompt.push_str(&format!("Document: {}\nAnswer: {{\"type\": \"{}\", \"confidence\": 0.95}}\n\n", opening, label));
+    }
+    for (_, correction) in examples {
+        let label = serde_json::to_value(&correction.document_type)
+            .ok()
+            .and_then(|v| v.as_str().map(str::to_string))
+            .unwrap_or_default();
+        prompt.push_str(&format!("Document: {}\nAnswer: {{\"type\": \"{}\", \"confidence\": 0.95}}\n\n", correction.excerpt, label));
+    }
+    prompt.push_str(&format!("Document: {}\nAnswer:", excerpt(opening(text), PROMPT_CHARS)));
+    prompt
+}
+
+async fn classify_with_llm(llm: &LLMManager, prompt: String) -> Result<Option<(DocumentType, f32, String)>> {
+    let model = match llm.default_generation_model().await {
+        Some(model) => model,
+        None => return Ok(None),
+    };
+    let request = GenerateRequest {
+        model: model.clone(),
+        prompt,
+        stream: Some(false),
+        options: Some(GenerateOptions {
+            num_predict: Some(60),
+            temperature: Some(0.0),
+            ..Default::default()
+        }),
+        system: Some("You classify legal documents and answer with JSON only.".to_string()),
+        template: None,
+        context: None,
+        raw: None,
+    };
+    let response = llm.generate_response(request).await?.response;
+    Ok(parse_verdict(&response).map(|(document_type, confidence)| (document_type, confidence, model)))
+}
+
+/// The final classification from the model's verdict (if any), the indicator phrases and the filename
+pub fn combine(
+    verdict: Option<(DocumentType, f32, String)>,
+    keywords: Vec<TypeScore>,
+    filename: &str,
+    informed_by: Option<String>,
+) -> DocumentTypeClassification {
+    if let Some((document_type, confidence, model)) = verdict {
+        // Agreement with the indicator phrases is worth a little extra confidence
+        let agrees = keywords.first().is_some_and(|k| k.document_type == document_type);
+        let confidence = if agrees { (confidence + 0.1).min(1.0) } else { confidence };
+        let alternatives = keywords.into_iter().filter(|k| k.document_type != document_type).collect();
+        return DocumentTypeClassification {
+            document_type: Some(document_type),
+            confidence,
+            method: Some(ClassificationMethod::Llm),
+            alternatives,
+            matched_correction: informed_by,
+            model: Some(model),
+        };
+    }
+    let mut keywords = keywords.into_iter();
+    if let Some(best) = keywords.next() {
+        return DocumentTypeClassification {
+            document_type: Some(best.document_type),
+            confidence: best.confidence,
+            method: Some(ClassificationMethod::Keywords),
+            alternatives: keywords.collect(),
+            matched_correction: None,
+            model: None,
+        };
+    }
+    let document_type = filename_type(filename);
+    DocumentTypeClassification {
+        confidence: if document_type.is_some() { FILENAME_CONFIDENCE } else { 0.0 },
+        method: document_type.is_some().then_some(ClassificationMethod::Filename),
+        document_type,
+        alternatives: vec![],
+        matched_correction: None,
+        model: None,
+    }
+}
+
+/// Classify a document from its text; `llm` is optional and any failure there falls back quietly
+pub async fn classify(
+    filename: &str,
+    text: &str,
+    corrections: &TypeCorrections,
+    llm: Option<&LLMManager>,
+) -> DocumentTypeClassification {
+    let keywords = keyword_classification(filename, text);
+    let similar = if text.trim().is_empty() {
+        vec![]
+    } else {
+        corrections.similar(&term_profile(text), CORRECTION_EXAMPLE)
+    };
+    if let Some((score, correction)) = similar.first().filter(|(score, _)| *score >= CORRECTION_MATCH) {
+        return DocumentTypeClassification {
+            document_type: Some(correction.document_type.clone()),
+            confidence: *score,
+            method: Some(ClassificationMethod::Correction),
+            alternatives: keywords.into_iter().filter(|k| k.document_type != correction.document_type).collect(),
+            matched_correction: Some(correction.id.clone()),
+            model: None,
+        };
+    }
+
+    let examples: Vec<(f32, TypeCorrection)> = similar.into_iter().take(MAX_CORRECTION_EXAMPLES).collect();
+    let verdict = match llm {
+        Some(llm) if !text.trim().is_empty() => classify_with_llm(llm, classification_prompt(text, &examples))
+            .await
+            .unwrap_or_else(|e| {
+                log::warn!("Model classification of {} failed, using indicator phrases: {}", filename, e);
+                None
+            }),
+        _ => None,
+    };
+    let informed_by = examples.first().map(|(_, c)| c.id.clone());
+    combine(verdict, keywords, filename, informed_by)
+}
+
+/// Classify a document's type from its content
+#[tauri::command]
+pub async fn classify_document_type(
+    path: String,
+    analyzer: tauri::State<'_, AnalyzerStorage>,
+) -> Result<DocumentTypeClassification, String> {
+    let path = Path::new(&path);
+    let text = analyzer.extract_text(path).await.map_err(|e| e.to_string())?;
+    let filename = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    Ok(analyzer.classify_content(&filename, &text).await)
+}
+
+/// Record the correct type of a document so similar documents are classified the same way
+#[tauri::command]
+pub async fn correct_document_type(
+    path: String,
+    document_type: DocumentType,
+    predicted: Option<DocumentType>,
+    analyzer: tauri::State<'_, AnalyzerStorage>,
+) -> Result<TypeCorrectionInfo, String> {
+    let path = Path::new(&path);
+    let text = analyzer.extract_text(path).await.map_err(|e| e.to_string())?;
+    let filename = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    analyzer
+        .type_corrections()
+        .record(&filename, &text, document_type, predicted)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn list_document_type_corrections(
+    analyzer: tauri::State<'_, AnalyzerStorage>,
+) -> Result<Vec<TypeCorrectionInfo>, String> {
+    Ok(analyzer.type_corrections().list())
+}
+
+#[tauri::command]
+pub async fn remove_document_type_correction(
+    id: String,
+    analyzer: tauri::State<'_, AnalyzerStorage>,
+) -> Result<bool, String> {
+    analyzer.type_corrections().remove(&id).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keywords_and_model_verdicts() {
+        let complaint = "ACME LTD, Plaintiff, v. BETA INC, Defendant. Case No. 4. COMPLAINT for breach of the supply agreement.";
+        let keywords = keyword_classification("complaint.pdf", complaint);
+        assert_eq!(keywords[0].document_type, DocumentType::CourtFiling);
+        assert!(keywords[0].confidence > keywords[1].confidence);
+        assert!(keywords[0].confidence <= MAX_KEYWORD_CONFIDENCE);
+
+        assert_eq!(parse_verdict("Sure: {\"type\": \"Legal Brief\", \"confidence\": 1.4}"), Some((DocumentType::LegalBrief, 1.0)));
+        assert_eq!(parse_verdict("{\"type\": \"Recipe\"}"), None);
+        assert_eq!(parse_type("power_of_attorney"), Some(DocumentType::PowerOfAttorney));
+
+        let verdict = Some((DocumentType::CourtFiling, 0.8, "local-model".to_string()));
+        let classification = combine(verdict, keywords.clone(), "complaint.pdf", None);
+        assert_eq!(classification.method, Some(ClassificationMethod::Llm));
+        assert!((classification.confidence - 0.9).abs() < 1e-6);
+        assert!(classification.alternatives.iter().all(|a| a.document_type != DocumentType::CourtFiling));
+
+        let fallback = combine(None, vec![], "Lease 2024.docx", None);
+        assert_eq!(fallback.document_type, Some(DocumentType::Lease));
+        assert_eq!(fallback.method, Some(ClassificationMethod::Filename));
+        assert_eq!(combine(None, vec![], "scan.pdf", None).document_type, None);
+    }
+
+    #[tokio::test]
+    async fn test_corrections_decide_similar_documents() {
+        let dir = tempfile::tempdir().unwrap();
+        let corrections = TypeCorrections::new(dir.path());
+        let policy = "Acme Group data retention policy. Records of customer accounts are retained for seven years. \
+                      Retention schedules are reviewed by the compliance officer every year.";
+        // The indicator phrases call it a contract because it mentions the customer agreement
+        let before = classify("policy.pdf", &format!("{} See the customer agreement.", policy), &corrections, None).await;
+        assert_eq!(before.method, Some(ClassificationMethod::Keywords));
+
+        corrections
+            .record("policy.pdf", policy, DocumentType::Compliance, before.document_type.clone())
+            .unwrap();
+        let after = classify("policy-v2.pdf", &format!("{} See the customer agreement.", policy), &corrections, None).await;
+        assert_eq!(after.document_type, Some(DocumentType::Compliance));
+        assert_eq!(after.method, Some(ClassificationMethod::Correction));
+        assert!(after.confidence >= CORRECTION_MATCH);
+
+        let reloaded = TypeCorrections::new(dir.path());
+        let listed = reloaded.list();
+        assert_eq!(listed.len(), 1);
+        assert!(reloaded.remove(&listed[0].id).unwrap());
+        assert!(reloaded.list().is_empty());
+    }
+}
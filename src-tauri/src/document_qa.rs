@@ -0,0 +1,444 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use crate::llm_manager::LLMManager;
+use crate::local_api::AnalyzerStorage;
+use crate::nemotron_rag::split_into_chunks;
+use crate::text_processing::{self, LanguageTools};
+
+/// Single-Document Q&A for BEAR AI
+/// Answers questions from one chosen document only. The document is split into pages (the form
+/// feeds pdftotext writes, or estimated pages for formats without them), each page is chunked,
+/// and the chunks are held in a BM25 index in memory for as long as the document stays open; the
+/// global RAG corpus is never consulted. The model sees only the best-matching excerpts, labelled
+/// with their pages, and every "[p. N]" it cites is checked against the pages it was shown.
+const CHUNK_WORDS: usize = 220;
+const CHUNK_OVERLAP_WORDS: usize = 40;
+const DEFAULT_TOP_K: usize = 6;
+const MAX_TOP_K: usize = 12;
+/// Page length assumed for documents without page breaks
+const WORDS_PER_ESTIMATED_PAGE: usize = 450;
+/// Open documents kept in memory; the least recently used is dropped beyond this
+const MAX_OPEN_DOCUMENTS: usize = 4;
+const QUOTE_CHARS: usize = 240;
+const BM25_K1: f32 = 1.2;
+const BM25_B: f32 = 0.75;
+const NOT_ADDRESSED: &str = "The document does not address this question.";
+const SYSTEM_PROMPT: &str = "You answer questions about a single legal document strictly from the excerpts you are given.";
+
+#[derive(Debug, Clone)]
+struct IndexedChunk {
+    page: u32,
+    text: String,
+    terms: HashMap<String, f32>,
+    length: f32,
+}
+
+/// The in-memory index of one document
+pub struct DocumentIndex {
+    path: String,
+    modified: Option<SystemTime>,
+    pages: usize,
+    pages_estimated: bool,
+    tools: LanguageTools,
+    chunks: Vec<IndexedChunk>,
+    document_frequency: HashMap<String, usize>,
+    average_length: f32,
+    last_used: Mutex<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentIndexInfo {
+    pub path: String,
+    pub pages: usize,
+    pub pages_estimated: bool, // no page breaks in the file; pages are counted every 450 words
+    pub chunks: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentQuestion {
+    pub path: String,
+    pub question: String,
+    pub model: Option<String>, // defaults to the model the generation server has ready
+    pub top_k: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PageExcerpt {
+    pub page: u32,
+    pub score: f32,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PageCitation {
+    pub page: u32,
+    pub quote: String, // the start of the best excerpt from that page
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentAnswer {
+    pub path: String,
+    pub question: String,
+    pub answer: String,
+    pub answered: bool, // false when nothing in the document matched the question
+    pub citations: Vec<PageCitation>,
+    pub unsupported_pages: Vec<u32>, // pages the answer cites that it was not shown
+    pub excerpts: Vec<PageExcerpt>,
+    pub pages_estimated: bool,
+    pub model: Option<String>,
+}
+
+/// Pages of pdftotext output; a text without form feeds is a single page. A blank last page is
+/// kept, as it may be a scan with no text layer.
+pub fn pdf_pages(text: &str) -> Vec<&str> {
+    let mut pages: Vec<&str> = text.split('\u{c}').collect();
+    // pdftotext ends every page, including the last, with a form feed
+    if pages.len() > 1 && pages.last().is_some_and(|page| page.trim().is_empty()) {
+        pages.pop();
+    }
+    pages
+}
+
+/// Pages of extracted text: split at form feeds, or every `WORDS_PER_ESTIMATED_PAGE` words when
+/// the text has none. The flag is true for estimated pages.
+pub fn split_pages(text: &str) -> (Vec<String>, bool) {
+    let pages: Vec<String> = pdf_pages(text).into_iter().map(str::to_string).collect();
+    if pages.len() > 1 {
+        return (pages, false);
+    }
+    let words: Vec<&str> = text.split_whitespace().collect();
+    if words.len() <= WORDS_PER_ESTIMATED_PAGE {
+        return (pages, false);
+    }
+    (words.chunks(WORDS_PER_ESTIMATED_PAGE).map(|page| page.join(" ")).collect(), true)
+}
+
+/// Pages cited as "[p. 4]", "[p 4]", "[pp. 4-5]" or "[page 4]", in order of first citation
+pub fn cited_pages(answer: &str) -> Vec<u32> {
+    let cite = Regex::new(r"(?i)\[(?:pp?\.?|pages?)\s*(\d+)(?:\s*[-–]\s*(\d+))?\]").unwrap();
+    let mut seen = BTreeSet::new();
+    let mut pages = Vec::new();
+    for captures in cite.captures_iter(answer) {
+        let first: u32 = captures[1].parse().unwrap_or(0);
+        let last: u32 = captures.get(2).and_then(|m| m.as_str().parse().ok()).unwrap_or(first);
+        for page in first..=last.min(first + 20) {
+            if seen.insert(page) {
+                pages.push(page);
+            }
+        }
+    }
+    pages
+}
+
+fn quote(text: &str) -> String {
+    match text.char_indices().nth(QUOTE_CHARS) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+impl DocumentIndex {
+    pub fn build(path: &str, text: &str, modified: Option<SystemTime>) -> Self {
+        let (pages, pages_estimated) = split_pages(text);
+        let language = text_processing::detect_language(&text.chars().take(5_000).collect::<String>());
+        let tools = LanguageTools::for_language(&language);
+
+        let mut chunks = Vec::new();
+        let mut document_frequency: HashMap<String, usize> = HashMap::new();
+        for (i, page) in pages.iter().enumerate() {
+            for chunk in split_into_chunks(page, CHUNK_WORDS, CHUNK_OVERLAP_WORDS) {
+                let terms = Self::terms(&tools, &chunk);
+                for term in terms.keys() {
+                    *document_frequency.entry(term.clone()).or_default() += 1;
+                }
+                chunks.push(IndexedChunk {
+                    page: i as u32 + 1,
+                    length: terms.values().sum(),
+                    text: chunk,
+                    terms,
+                });
+            }
+        }
+        let average_length = chunks.iter().map(|c| c.length).sum::<f32>() / chunks.len().max(1) as f32;
+
+        DocumentIndex {
+            path: path.to_string(),
+            modified,
+            pages: pages.len(),
+            pages_estimated,
+            tools,
+            chunks,
+            document_frequency,
+            average_length,
+            last_used: Mutex::new(Utc::now()),
+        }
+    }
+
+    fn terms(tools: &LanguageTools, text: &str) -> HashMap<String, f32> {
+        let mut terms = HashMap::new();
+        for word in text_processing::words(text) {
+            if !tools.is_stop_word(&word) {
+                *terms.entry(tools.stem(&word)).or_default() += 1.0;
+            }
+        }
+        terms
+    }
+
+    pub fn info(&self) -> DocumentIndexInfo {
+        DocumentIndexInfo {
+            path: self.path.clone(),
+            pages: self.pages,
+            pages_estimated: self.pages_estimated,
+            chunks: self.chunks.len(),
+        }
+    }
+
+    /// The best-matching excerpts by BM25, at most `top_k`, best first
+    pub fn search(&self, question: &str, top_k: usize) -> Vec<PageExcerpt> {
+        let query = Self::terms(&self.tools, question);
+        let total = self.chunks.len() as f32;
+        let mut scored: Vec<(f32, &IndexedChunk)> = self
+            .chunks
+            .iter()
+            .map(|chunk| {
+                let score = query
+                    .keys()
+                    .filter_map(|term| {
+                        let tf = *chunk.terms.get(term)?;
+                        let df = self.document_frequency[term] as f32;
+                        let idf = ((total - df + 0.5) / (df + 0.5) + 1.0).ln();
+                        let norm = BM25_K1 * (1.0 - BM25_B + BM25_B * chunk.length / self.average_length.max(1.0));
+                        Some(idf * tf * (BM25_K1 + 1.0) / (tf + norm))
+                    })
+                    .sum::<f32>();
+                (score, chunk)
+            })
+            .filter(|(score, _)| *score > 0.0)
+            .collect();
+        scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+        scored
+            .into_iter()
+            .take(top_k)
+            .map(|(score, chunk)| PageExcerpt {
+                page: chunk.page,
+                score,
+                text: chunk.text.clone(),
+            })
+            .collect()
+    }
+}
+
+pub fn answer_prompt(question: &str, excerpts: &[PageExcerpt]) -> String {
+    let mut ordered: Vec<&PageExcerpt> = excerpts.iter().collect();
+    ordered.sort_by_key(|e| e.page); // reading order helps the model follow the document
+    let mut prompt = String::from(
+        "Answer the question using only the excerpts below, which all come from one document. \
+         Cite the page of every statement as [p. N]. If the excerpts do not answer the question, say so \
+         and do not draw on anything else.\n\n",
+    );
+    for excerpt in ordered {
+        prompt.push_str(&format!("[p. {}]\n{}\n\n", excerpt.page, excerpt.text));
+    }
+    prompt.push_str(&format!("Question: {}\nAnswer:", question.trim()));
+    prompt
+}
+
+/// Cited pages that were among the excerpts, with a quote from each; and those that were not
+pub fn check_citations(answer: &str, excerpts: &[PageExcerpt]) -> (Vec<PageCitation>, Vec<u32>) {
+    let mut citations = Vec::new();
+    let mut unsupported = Vec::new();
+    for page in cited_pages(answer) {
+        // Excerpts are best first, so the first on a page is its best
+        match excerpts.iter().find(|e| e.page == page) {
+            Some(excerpt) => citations.push(PageCitation {
+                page,
+                quote: quote(&excerpt.text),
+            }),
+            None => unsupported.push(page),
+        }
+    }
+    (citations, unsupported)
+}
+
+/// Documents open for Q&A, indexed in memory only
+#[derive(Default)]
+pub struct DocumentQa {
+    indexes: Mutex<HashMap<String, Arc<DocumentIndex>>>,
+}
+
+impl DocumentQa {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The open index for a path, if the file has not changed since it was built
+    pub fn get(&self, path: &str) -> Option<Arc<DocumentIndex>> {
+        let modified = std::fs::metadata(path).and_then(|m| m.modified()).ok();
+        let indexes = self.indexes.lock().unwrap();
+        let index = indexes.get(path).filter(|i| i.modified == modified)?;
+        *index.last_used.lock().unwrap() = Utc::now();
+        Some(index.clone())
+    }
+
+    pub fn insert(&self, index: DocumentIndex) -> Arc<DocumentIndex> {
+        let index = Arc::new(index);
+        let mut indexes = self.indexes.lock().unwrap();
+        indexes.insert(index.path.clone(), index.clone());
+        while indexes.len() > MAX_OPEN_DOCUMENTS {
+            let oldest = indexes
+                .iter()
+                .min_by_key(|(_, i)| *i.last_used.lock().unwrap())
+                .map(|(path, _)| path.clone());
+            match oldest {
+                Some(path) => indexes.remove(&path),
+                None => break,
+            };
+        }
+        index
+    }
+
+    pub fn close(&self, path: &str) -> bool {
+        self.indexes.lock().unwrap().remove(path).is_some()
+    }
+
+    pub fn list(&self) -> Vec<DocumentIndexInfo> {
+        let mut open: Vec<DocumentIndexInfo> = self.indexes.lock().unwrap().values().map(|i| i.info()).collect();
+        open.sort_by(|a, b| a.path.cmp(&b.path));
+        open
+    }
+
+    async fn open(&self, path: &str, analyzer: &AnalyzerStorage) -> Result<Arc<DocumentIndex>> {
+        if let Some(index) = self.get(path) {
+            return Ok(index);
+        }
+        let modified = std::fs::metadata(path).and_then(|m| m.modified()).ok();
+        let text = analyzer.extract_text(Path::new(path)).await?;
+        if text.trim().is_empty() {
+            return Err(anyhow!("No text could be extracted from {}", path));
+        }
+        Ok(self.insert(DocumentIndex::build(path, &text, modified)))
+    }
+}
+
+pub type DocumentQaStorage = Arc<DocumentQa>;
+
+/// Index a document in memory for questions; reuses the open index while the file is unchanged
+#[tauri::command]
+pub async fn document_qa_open(
+    path: String,
+    qa: tauri::State<'_, DocumentQaStorage>,
+    analyzer: tauri::State<'_, AnalyzerStorage>,
+) -> Result<DocumentIndexInfo, String> {
+    qa.open(&path, &analyzer).await.map(|i| i.info()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn document_qa_ask(
+    request: DocumentQuestion,
+    qa: tauri::State<'_, DocumentQaStorage>,
+    analyzer: tauri::State<'_, AnalyzerStorage>,
+    llm: tauri::State<'_, Arc<LLMManager>>,
+) -> Result<DocumentAnswer, String> {
+    if request.question.trim().is_empty() {
+        return Err("Ask a question about the document".to_string());
+    }
+    let index = qa.open(&request.path, &analyzer).await.map_err(|e| e.to_string())?;
+    let excerpts = index.search(&request.question, request.top_k.unwrap_or(DEFAULT_TOP_K).clamp(1, MAX_TOP_K));
+    if excerpts.is_empty() {
+        return Ok(DocumentAnswer {
+            path: request.path,
+            question: request.question,
+            answer: NOT_ADDRESSED.to_string(),
+            answered: false,
+            citations: vec![],
+            unsupported_pages: vec![],
+            excerpts,
+            pages_estimated: index.pages_estimated,
+            model: None,
+        });
+    }
+
+    let model = match &request.model {
+        Some(model) => model.clone(),
+        None => llm
+            .default_generation_model()
+            .await
+            .ok_or_else(|| "No model is available to answer the question".to_string())?,
+    };
+    let answer = llm
+        .complete(&model, SYSTEM_PROMPT, answer_prompt(&request.question, &excerpts), 700, 0.1)
+        .await
+        .map_err(|e| e.to_string())?;
+    let (citations, unsupported_pages) = check_citations(&answer, &excerpts);
+    Ok(DocumentAnswer {
+        path: request.path,
+        question: request.question,
+        answer,
+        answered: true,
+        citations,
+        unsupported_pages,
+        excerpts,
+        pages_estimated: index.pages_estimated,
+        model: Some(model),
+    })
+}
+
+#[tauri::command]
+pub async fn document_qa_close(path: String, qa: tauri::State<'_, DocumentQaStorage>) -> Result<bool, String> {
+    Ok(qa.close(&path))
+}
+
+#[tauri::command]
+pub async fn document_qa_list(qa: tauri::State<'_, DocumentQaStorage>) -> Result<Vec<DocumentIndexInfo>, String> {
+    Ok(qa.list())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_pages_and_cited_pages() {
+        let (pages, estimated) = split_pages("Cover\u{c}Terms\u{c}Signatures\u{c}");
+        assert_eq!(pages, vec!["Cover", "Terms", "Signatures"]);
+        assert!(!estimated);
+
+        let long = vec!["word"; WORDS_PER_ESTIMATED_PAGE * 2 + 10].join(" ");
+        let (pages, estimated) = split_pages(&long);
+        assert_eq!(pages.len(), 3);
+        assert!(estimated);
+        assert_eq!(split_pages("Short letter").0.len(), 1);
+
+        assert_eq!(cited_pages("Rent is due monthly [p. 3], late fees apply [pp. 4-5] [P 3] [page 9]."), vec![3, 4, 5, 9]);
+        assert!(cited_pages("See section 3 [1].").is_empty());
+    }
+
+    #[test]
+    fn test_search_anchors_answers_to_pages() {
+        let text = "This lease is made between Acme and Beta.\u{c}\
+                    The tenant shall pay rent of 2,000 euros on the first day of each month.\u{c}\
+                    Either party may terminate this lease on ninety days written notice.\u{c}";
+        let index = DocumentIndex::build("lease.pdf", text, None);
+        assert_eq!(index.info().pages, 3);
+
+        let excerpts = index.search("How can the lease be terminated?", 2);
+        assert_eq!(excerpts[0].page, 3);
+        assert!(index.search("indemnification cap", 2).is_empty());
+
+        let (citations, unsupported) = check_citations("Ninety days notice [p. 3]; see also [p. 7].", &excerpts);
+        assert_eq!(citations.len(), 1);
+        assert_eq!(citations[0].page, 3);
+        assert!(citations[0].quote.contains("ninety days"));
+        assert_eq!(unsupported, vec![7]);
+
+        let prompt = answer_prompt("How can the lease be terminated?", &excerpts);
+        assert!(prompt.contains("[p. 3]\nEither party"));
+    }
+}
@@ -0,0 +1,702 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use walkdir::WalkDir;
+
+use crate::document_analyzer::DocumentAnalysis;
+use crate::local_api::AnalyzerStorage;
+
+/// Document Repository for BEAR AI
+/// The documents store is either the local app data folder or a mounted network share (an SMB
+/// drive letter or UNC path, or an NFS mount). On a share, files other users have open are
+/// recognized by their Word/Excel and LibreOffice owner files, extracted text is cached locally
+/// so documents stay readable while the share is unreachable, and periodic scans report files
+/// that were added, changed or removed since the last scan.
+const ONLINE_TIMEOUT_SECS: u64 = 5; // a hung mount must not hang the app
+const DEFAULT_SCAN_MINUTES: u32 = 5;
+
+pub const REPOSITORY_CHANGES_EVENT: &str = "repository-changes";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RepositoryMode {
+    Local,
+    NetworkShare,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepositorySettings {
+    pub mode: RepositoryMode,
+    pub network_path: Option<String>, // e.g. \\fileserver\matters, Z:\Matters or /mnt/matters
+    #[serde(default = "default_scan_minutes")]
+    pub scan_minutes: u32,
+    #[serde(default = "default_true")]
+    pub cache_text: bool,
+}
+
+fn default_scan_minutes() -> u32 {
+    DEFAULT_SCAN_MINUTES
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for RepositorySettings {
+    fn default() -> Self {
+        Self {
+            mode: RepositoryMode::Local,
+            network_path: None,
+            scan_minutes: DEFAULT_SCAN_MINUTES,
+            cache_text: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct FileStamp {
+    pub size: u64,
+    pub modified: DateTime<Utc>,
+}
+
+/// Someone else has the file open
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FileLock {
+    pub owner: Option<String>,
+    pub lock_file: Option<String>, // None: open in another program, which only Windows reports
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepositoryFile {
+    pub path: String, // relative to the repository root, '/'-separated
+    pub size: u64,
+    pub modified: DateTime<Utc>,
+    pub locked: Option<FileLock>,
+    pub cached: bool, // cached text matches the file's current size and modification time
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Added,
+    Modified,
+    Removed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepositoryChange {
+    pub path: String,
+    pub kind: ChangeKind,
+    pub stamp: Option<FileStamp>, // None for removed files
+    pub locked: Option<FileLock>, // still being edited
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepositoryScan {
+    pub root: String,
+    pub scanned_at: DateTime<Utc>,
+    pub baseline: bool, // first scan of this root; everything found is recorded, nothing reported
+    pub files: usize,
+    pub changes: Vec<RepositoryChange>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepositoryStatus {
+    pub mode: RepositoryMode,
+    pub root: String,
+    pub online: bool,
+    pub files_tracked: usize,
+    pub cached_texts: usize,
+    pub last_scan: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepositoryText {
+    pub path: String,
+    pub text: String,
+    pub from_cache: bool,
+    pub offline: bool,
+    pub stale: bool, // offline, and the last scan saw a newer version than the cached text
+    pub locked: Option<FileLock>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedText {
+    path: String,
+    stamp: FileStamp,
+    text: String,
+    cached_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct RepositoryState {
+    settings: RepositorySettings,
+    index: HashMap<String, FileStamp>, // files seen by the last scan
+    last_scan: Option<DateTime<Utc>>,
+}
+
+/// Word and Excel owner files, LibreOffice lock files and other editor droppings
+fn is_transient(name: &str) -> bool {
+    name.starts_with("~$")
+        || (name.starts_with(".~lock.") && name.ends_with('#'))
+        || (name.starts_with('~') && name.ends_with(".tmp"))
+        || name.eq_ignore_ascii_case("thumbs.db")
+        || name == ".DS_Store"
+}
+
+/// Office owner files start with the length of the user name followed by the name itself
+fn parse_office_owner(content: &[u8]) -> Option<String> {
+    let length = *content.first()? as usize;
+    let name: String = content.get(1..1 + length)?.iter().map(|&b| b as char).collect();
+    Some(name.trim().to_string()).filter(|n| !n.is_empty())
+}
+
+/// LibreOffice lock files are "Full Name,user,host,date,profile;"
+fn parse_libreoffice_owner(content: &str) -> Option<String> {
+    let mut fields = content.split(',').map(str::trim);
+    let full_name = fields.next().filter(|f| !f.is_empty());
+    let user = fields.next().filter(|f| !f.is_empty());
+    full_name.or(user).map(str::to_string)
+}
+
+/// Owner files next to the document, as Word, Excel and LibreOffice create them
+fn lock_file_candidates(name: &str) -> Vec<String> {
+    let mut candidates = vec![format!("~${}", name), format!(".~lock.{}#", name)];
+    // Word replaces the first two characters of longer names instead of prefixing them
+    let stem_length = Path::new(name).file_stem().map_or(0, |s| s.len());
+    if stem_length > 7 {
+        if let Some(rest) = name.get(2..) {
+            candidates.push(format!("~${}", rest));
+        }
+    }
+    candidates
+}
+
+pub fn lock_status(path: &Path) -> Option<FileLock> {
+    let name = path.file_name()?.to_str()?;
+    let dir = path.parent()?;
+    for candidate in lock_file_candidates(name) {
+        let lock_path = dir.join(&candidate);
+        let Ok(content) = fs::read(&lock_path) else { continue };
+        let owner = if candidate.starts_with("~$") {
+            parse_office_owner(&content)
+        } else {
+            parse_libreoffice_owner(&String::from_utf8_lossy(&content))
+        };
+        return Some(FileLock { owner, lock_file: Some(candidate) });
+    }
+    exclusively_open(path).then_some(FileLock { owner: None, lock_file: None })
+}
+
+#[cfg(windows)]
+fn exclusively_open(path: &Path) -> bool {
+    use std::os::windows::fs::OpenOptionsExt;
+    const ERROR_SHARING_VIOLATION: i32 = 32;
+    match fs::OpenOptions::new().read(true).share_mode(0).open(path) {
+        Ok(_) => false,
+        Err(e) => e.raw_os_error() == Some(ERROR_SHARING_VIOLATION),
+    }
+}
+
+#[cfg(not(windows))]
+fn exclusively_open(_path: &Path) -> bool {
+    false // SMB and NFS locks are advisory outside Windows; the owner files are all there is
+}
+
+fn stamp(metadata: &fs::Metadata) -> FileStamp {
+    FileStamp {
+        size: metadata.len(),
+        modified: metadata.modified().map(DateTime::<Utc>::from).unwrap_or_else(|_| Utc::now()),
+    }
+}
+
+fn relative_path(root: &Path, path: &Path) -> Option<String> {
+    Some(path.strip_prefix(root).ok()?.to_string_lossy().replace('\\', "/"))
+}
+
+/// Every document under `root`, keyed by relative path
+fn snapshot(root: &Path) -> Result<HashMap<String, FileStamp>> {
+    let mut files = HashMap::new();
+    let entries = WalkDir::new(root)
+        .into_iter()
+        .filter_entry(|e| e.depth() == 0 || !(e.file_type().is_dir() && e.file_name().to_string_lossy().starts_with('.')));
+    for entry in entries {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy();
+        if !entry.file_type().is_file() || is_transient(&name) {
+            continue;
+        }
+        if let Some(relative) = relative_path(root, entry.path()) {
+            files.insert(relative, stamp(&entry.metadata()?));
+        }
+    }
+    Ok(files)
+}
+
+/// Files added, modified or removed between two snapshots, sorted by path
+fn diff(previous: &HashMap<String, FileStamp>, current: &HashMap<String, FileStamp>) -> Vec<(String, ChangeKind)> {
+    let mut changes: Vec<(String, ChangeKind)> = current
+        .iter()
+        .filter_map(|(path, stamp)| match previous.get(path) {
+            None => Some((path.clone(), ChangeKind::Added)),
+            Some(before) if before != stamp => Some((path.clone(), ChangeKind::Modified)),
+            Some(_) => None,
+        })
+        .chain(
+            previous
+                .keys()
+                .filter(|path| !current.contains_key(*path))
+                .map(|path| (path.clone(), ChangeKind::Removed)),
+        )
+        .collect();
+    changes.sort_by(|a, b| a.0.cmp(&b.0));
+    changes
+}
+
+#[derive(Debug)]
+pub struct DocumentRepository {
+    path: PathBuf,
+    local_root: PathBuf,
+    cache_dir: PathBuf,
+    state: Mutex<RepositoryState>,
+}
+
+impl DocumentRepository {
+    pub fn new(app_data_dir: &Path) -> Result<Self> {
+        let path = app_data_dir.join("document_repository.json");
+        let state = if path.exists() {
+            serde_json::from_str(&fs::read_to_string(&path)?)?
+        } else {
+            RepositoryState::default()
+        };
+        let cache_dir = app_data_dir.join("repository_cache");
+        fs::create_dir_all(&cache_dir)?;
+
+        Ok(Self {
+            path,
+            local_root: app_data_dir.join("documents"), // the document analyzer's store
+            cache_dir,
+            state: Mutex::new(state),
+        })
+    }
+
+    fn persist(&self, state: &RepositoryState) -> Result<()> {
+        fs::write(&self.path, serde_json::to_string_pretty(state)?)?;
+        Ok(())
+    }
+
+    pub fn settings(&self) -> RepositorySettings {
+        self.state.lock().unwrap().settings.clone()
+    }
+
+    /// Switch modes or move the share. The new location must be reachable now; a different root
+    /// starts change detection and the text cache afresh.
+    pub fn set_settings(&self, mut settings: RepositorySettings) -> Result<RepositorySettings> {
+        settings.scan_minutes = settings.scan_minutes.max(1);
+        settings.network_path = settings.network_path.map(|p| p.trim().to_string()).filter(|p| !p.is_empty());
+        let root = match settings.mode {
+            RepositoryMode::Local => self.local_root.clone(),
+            RepositoryMode::NetworkShare => {
+                let path = settings
+                    .network_path
+                    .as_deref()
+                    .ok_or_else(|| anyhow!("Choose the mounted network folder"))?;
+                PathBuf::from(path)
+            }
+        };
+        if settings.mode == RepositoryMode::NetworkShare {
+            if !root.is_absolute() {
+                return Err(anyhow!("{} is not an absolute path", root.display()));
+            }
+            fs::read_dir(&root).map_err(|e| anyhow!("Cannot open {}: {}", root.display(), e))?;
+        }
+
+        let mut state = self.state.lock().unwrap();
+        if self.root_of(&state.settings) != root {
+            state.index.clear();
+            state.last_scan = None;
+            self.clear_cache()?;
+        }
+        state.settings = settings.clone();
+        self.persist(&state)?;
+        Ok(settings)
+    }
+
+    fn root_of(&self, settings: &RepositorySettings) -> PathBuf {
+        match (settings.mode, &settings.network_path) {
+            (RepositoryMode::NetworkShare, Some(path)) => PathBuf::from(path),
+            _ => self.local_root.clone(),
+        }
+    }
+
+    pub fn root(&self) -> PathBuf {
+        self.root_of(&self.state.lock().unwrap().settings)
+    }
+
+    /// Absolute path of a repository file; refuses anything that would leave the root
+    pub fn resolve(&self, relative: &str) -> Result<PathBuf> {
+        let relative = Path::new(relative);
+        if relative.components().any(|c| !matches!(c, Component::Normal(_) | Component::CurDir)) {
+            return Err(anyhow!("{} is not a path inside the repository", relative.display()));
+        }
+        Ok(self.root().join(relative))
+    }
+
+    /// Whether the root answers within a few seconds
+    pub async fn online(&self) -> bool {
+        let root = self.root();
+        let probe = tokio::task::spawn_blocking(move || fs::read_dir(root).is_ok());
+        matches!(
+            tokio::time::timeout(std::time::Duration::from_secs(ONLINE_TIMEOUT_SECS), probe).await,
+            Ok(Ok(true))
+        )
+    }
+
+    pub async fn status(&self) -> RepositoryStatus {
+        let online = self.online().await;
+        let cached_texts = fs::read_dir(&self.cache_dir).map(|d| d.count()).unwrap_or(0);
+        let state = self.state.lock().unwrap();
+        RepositoryStatus {
+            mode: state.settings.mode,
+            root: self.root_of(&state.settings).display().to_string(),
+            online,
+            files_tracked: state.index.len(),
+            cached_texts,
+            last_scan: state.last_scan,
+        }
+    }
+
+    /// Network shares whose last scan is older than their interval
+    pub fn scan_due(&self, now: DateTime<Utc>) -> bool {
+        let state = self.state.lock().unwrap();
+        state.settings.mode == RepositoryMode::NetworkShare
+            && !state
+                .last_scan
+                .is_some_and(|last| last + Duration::minutes(state.settings.scan_minutes as i64) > now)
+    }
+
+    /// Compare the share with the last scan; the first scan of a root only records a baseline
+    pub async fn scan(&self) -> Result<RepositoryScan> {
+        let root = self.root();
+        if !self.online().await {
+            return Err(anyhow!("{} is unreachable; cached text remains available", root.display()));
+        }
+        let walk_root = root.clone();
+        let current = tokio::task::spawn_blocking(move || snapshot(&walk_root)).await??;
+        let scanned_at = Utc::now();
+
+        let mut state = self.state.lock().unwrap();
+        let baseline = state.last_scan.is_none();
+        let changes: Vec<RepositoryChange> = if baseline {
+            Vec::new()
+        } else {
+            diff(&state.index, &current)
+                .into_iter()
+                .map(|(path, kind)| RepositoryChange {
+                    locked: (kind != ChangeKind::Removed).then(|| lock_status(&root.join(&path))).flatten(),
+                    stamp: current.get(&path).copied(),
+                    path,
+                    kind,
+                })
+                .collect()
+        };
+        for change in changes.iter().filter(|c| c.kind == ChangeKind::Removed) {
+            let _ = fs::remove_file(self.cache_file(&change.path));
+        }
+
+        state.index = current;
+        state.last_scan = Some(scanned_at);
+        self.persist(&state)?;
+        Ok(RepositoryScan {
+            root: root.display().to_string(),
+            scanned_at,
+            baseline,
+            files: state.index.len(),
+            changes,
+        })
+    }
+
+    /// Files in a folder and below; from the share when it answers, otherwise from the last scan
+    pub async fn list(&self, folder: Option<&str>) -> Result<Vec<RepositoryFile>> {
+        let folder = folder.map(|f| f.trim_matches('/')).filter(|f| !f.is_empty());
+        let online = self.online().await;
+        let stamps = if online {
+            let root = self.root();
+            let dir = match folder {
+                Some(folder) => self.resolve(folder)?,
+                None => root.clone(),
+            };
+            let found = tokio::task::spawn_blocking(move || snapshot(&dir)).await??;
+            let prefix = folder.map(|f| format!("{}/", f)).unwrap_or_default();
+            found.into_iter().map(|(path, stamp)| (format!("{}{}", prefix, path), stamp)).collect()
+        } else {
+            let prefix = folder.map(|f| format!("{}/", f));
+            let state = self.state.lock().unwrap();
+            state
+                .index
+                .iter()
+                .filter(|(path, _)| prefix.as_ref().map_or(true, |p| path.starts_with(p)))
+                .map(|(path, stamp)| (path.clone(), *stamp))
+                .collect::<Vec<_>>()
+        };
+
+        let root = self.root();
+        let mut files: Vec<RepositoryFile> = stamps
+            .into_iter()
+            .map(|(path, stamp)| RepositoryFile {
+                locked: if online { lock_status(&root.join(&path)) } else { None },
+                cached: self.read_cache(&path).is_some_and(|c| c.stamp == stamp),
+                size: stamp.size,
+                modified: stamp.modified,
+                path,
+            })
+            .collect();
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(files)
+    }
+
+    fn cache_file(&self, relative: &str) -> PathBuf {
+        self.cache_dir.join(format!("{:x}.json", Sha256::digest(relative.as_bytes())))
+    }
+
+    fn read_cache(&self, relative: &str) -> Option<CachedText> {
+        serde_json::from_str(&fs::read_to_string(self.cache_file(relative)).ok()?).ok()
+    }
+
+    fn write_cache(&self, relative: &str, stamp: FileStamp, text: &str) {
+        if !self.settings().cache_text {
+            return;
+        }
+        let cached = CachedText {
+            path: relative.to_string(),
+            stamp,
+            text: text.to_string(),
+            cached_at: Utc::now(),
+        };
+        let written = serde_json::to_string(&cached)
+            .map_err(anyhow::Error::from)
+            .and_then(|json| Ok(fs::write(self.cache_file(relative), json)?));
+        if let Err(e) = written {
+            log::warn!("Failed to cache text of {}: {}", relative, e);
+        }
+    }
+
+    fn clear_cache(&self) -> Result<()> {
+        for entry in fs::read_dir(&self.cache_dir)? {
+            fs::remove_file(entry?.path())?;
+        }
+        Ok(())
+    }
+
+    /// Text of a document: cached while the file is unchanged or the share is unreachable,
+    /// otherwise extracted afresh and cached
+    pub async fn document_text(&self, relative: &str, analyzer: &AnalyzerStorage) -> Result<RepositoryText> {
+        let path = self.resolve(relative)?;
+        let cached = self.read_cache(relative);
+        if !self.online().await {
+            let cached = cached.ok_or_else(|| anyhow!("{} is unavailable offline and has no cached text", relative))?;
+            let last_seen = self.state.lock().unwrap().index.get(relative).copied();
+            return Ok(RepositoryText {
+                path: relative.to_string(),
+                text: cached.text,
+                from_cache: true,
+                offline: true,
+                stale: last_seen.is_some_and(|seen| seen != cached.stamp),
+                locked: None,
+            });
+        }
+
+        let current = stamp(&fs::metadata(&path).map_err(|e| anyhow!("Cannot read {}: {}", relative, e))?);
+        let locked = lock_status(&path);
+        if let Some(cached) = cached.filter(|c| c.stamp == current) {
+            return Ok(RepositoryText {
+                path: relative.to_string(),
+                text: cached.text,
+                from_cache: true,
+                offline: false,
+                stale: false,
+                locked,
+            });
+        }
+
+        let text = analyzer.extract_text(&path).await?;
+        self.write_cache(relative, current, &text);
+        Ok(RepositoryText {
+            path: relative.to_string(),
+            text,
+            from_cache: false,
+            offline: false,
+            stale: false,
+            locked,
+        })
+    }
+
+    /// Analyze a repository document and cache its text; a file another user has open is
+    /// analyzed as last saved
+    pub async fn analyze(&self, relative: &str, analyzer: &AnalyzerStorage) -> Result<DocumentAnalysis> {
+        let path = self.resolve(relative)?;
+        if !self.online().await {
+            return Err(anyhow!("{} is unreachable; only cached text is available offline", self.root().display()));
+        }
+        if let Some(lock) = lock_status(&path) {
+            log::info!(
+                "Analyzing {} while it is open by {}",
+                relative,
+                lock.owner.as_deref().unwrap_or("another user")
+            );
+        }
+        let before = stamp(&fs::metadata(&path)?);
+        let analysis = analyzer.analyze_document(&path).await?;
+        // Only cache when nobody saved over the file while it was being read
+        if fs::metadata(&path).map(|m| stamp(&m)).ok() == Some(before) {
+            self.write_cache(relative, before, &analysis.extracted_text);
+        }
+        Ok(analysis)
+    }
+}
+
+pub type DocumentRepositoryStorage = Arc<DocumentRepository>;
+
+#[tauri::command]
+pub async fn repository_get_settings(
+    repository: tauri::State<'_, DocumentRepositoryStorage>,
+) -> Result<RepositorySettings, String> {
+    Ok(repository.settings())
+}
+
+#[tauri::command]
+pub async fn repository_set_settings(
+    settings: RepositorySettings,
+    repository: tauri::State<'_, DocumentRepositoryStorage>,
+) -> Result<RepositorySettings, String> {
+    repository.set_settings(settings).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn repository_status(
+    repository: tauri::State<'_, DocumentRepositoryStorage>,
+) -> Result<RepositoryStatus, String> {
+    Ok(repository.status().await)
+}
+
+#[tauri::command]
+pub async fn repository_scan(
+    repository: tauri::State<'_, DocumentRepositoryStorage>,
+) -> Result<RepositoryScan, String> {
+    repository.scan().await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn repository_list_files(
+    folder: Option<String>,
+    repository: tauri::State<'_, DocumentRepositoryStorage>,
+) -> Result<Vec<RepositoryFile>, String> {
+    repository.list(folder.as_deref()).await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn repository_document_text(
+    path: String,
+    repository: tauri::State<'_, DocumentRepositoryStorage>,
+    analyzer: tauri::State<'_, AnalyzerStorage>,
+) -> Result<RepositoryText, String> {
+    repository.document_text(&path, &analyzer).await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn repository_analyze_document(
+    path: String,
+    repository: tauri::State<'_, DocumentRepositoryStorage>,
+    analyzer: tauri::State<'_, AnalyzerStorage>,
+) -> Result<DocumentAnalysis, String> {
+    repository.analyze(&path, &analyzer).await.map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lock_files_identify_the_editor() {
+        let dir = tempfile::tempdir().unwrap();
+        let brief = dir.path().join("Reply Brief.docx");
+        fs::write(&brief, b"docx").unwrap();
+        assert!(lock_status(&brief).is_none());
+
+        // Word shortens long names: "Reply Brief.docx" -> "~$ply Brief.docx"
+        let mut owner = vec![8u8];
+        owner.extend_from_slice(b"J. Smith");
+        owner.resize(54, b' ');
+        fs::write(dir.path().join("~$ply Brief.docx"), &owner).unwrap();
+        let lock = lock_status(&brief).unwrap();
+        assert_eq!(lock.owner.as_deref(), Some("J. Smith"));
+        assert_eq!(lock.lock_file.as_deref(), Some("~$ply Brief.docx"));
+
+        let sheet = dir.path().join("costs.xlsx");
+        fs::write(&sheet, b"xlsx").unwrap();
+        fs::write(dir.path().join(".~lock.costs.xlsx#"), "Ana Lopez,alopez,LAPTOP-7,16.10.2026 09:12,file:///C:/Users/alopez;").unwrap();
+        assert_eq!(lock_status(&sheet).unwrap().owner.as_deref(), Some("Ana Lopez"));
+
+        assert!(is_transient("~$ply Brief.docx"));
+        assert!(is_transient(".~lock.costs.xlsx#"));
+        assert!(!is_transient("costs.xlsx"));
+    }
+
+    #[test]
+    fn test_change_detection_and_offline_cache() {
+        let app_dir = tempfile::tempdir().unwrap();
+        let share = tempfile::tempdir().unwrap();
+        fs::create_dir_all(share.path().join("M-1")).unwrap();
+        fs::write(share.path().join("M-1/contract.txt"), "First draft").unwrap();
+        fs::write(share.path().join("M-1/notes.txt"), "Call notes").unwrap();
+        fs::write(share.path().join("M-1/~$ntract.txt"), [0u8]).unwrap();
+
+        let repository = DocumentRepository::new(app_dir.path()).unwrap();
+        assert!(repository.resolve("../secrets.txt").is_err());
+        let settings = RepositorySettings {
+            mode: RepositoryMode::NetworkShare,
+            network_path: Some(share.path().display().to_string()),
+            ..RepositorySettings::default()
+        };
+        repository.set_settings(settings).unwrap();
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let first = runtime.block_on(repository.scan()).unwrap();
+        assert!(first.baseline);
+        assert_eq!(first.files, 2);
+
+        let before = repository.state.lock().unwrap().index["M-1/contract.txt"];
+        repository.write_cache("M-1/contract.txt", before, "First draft");
+        fs::write(share.path().join("M-1/contract.txt"), "Second draft, revised").unwrap();
+        fs::remove_file(share.path().join("M-1/notes.txt")).unwrap();
+        fs::write(share.path().join("M-1/letter.txt"), "Dear client").unwrap();
+
+        let second = runtime.block_on(repository.scan()).unwrap();
+        let changes: Vec<(&str, ChangeKind)> = second.changes.iter().map(|c| (c.path.as_str(), c.kind)).collect();
+        assert_eq!(
+            changes,
+            vec![
+                ("M-1/contract.txt", ChangeKind::Modified),
+                ("M-1/letter.txt", ChangeKind::Added),
+                ("M-1/notes.txt", ChangeKind::Removed),
+            ]
+        );
+        assert!(second.changes[0].locked.is_some());
+
+        // The cached text no longer matches the share, but is still there once it goes away
+        let files = runtime.block_on(repository.list(Some("M-1"))).unwrap();
+        assert!(!files.iter().find(|f| f.path == "M-1/contract.txt").unwrap().cached);
+        let offline_root = share.path().with_extension("offline");
+        fs::rename(share.path(), &offline_root).unwrap();
+        assert!(runtime.block_on(repository.scan()).is_err());
+        let offline = runtime.block_on(repository.list(None)).unwrap();
+        assert_eq!(offline.len(), 2);
+        assert_eq!(repository.read_cache("M-1/contract.txt").unwrap().text, "First draft");
+        fs::rename(&offline_root, share.path()).unwrap();
+    }
+}
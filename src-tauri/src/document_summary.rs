@@ -0,0 +1,349 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::ops::Range;
+use std::path::Path;
+use std::sync::Arc;
+use tauri::Manager;
+use uuid::Uuid;
+
+use crate::llm_manager::LLMManager;
+use crate::local_api::AnalyzerStorage;
+use crate::nemotron_rag::split_into_chunks;
+
+/// Long-Document Summaries for BEAR AI
+/// Documents too long for the model's context are summarized map-reduce style: the text is cut
+/// into overlapping chunks with the RAG chunker, each chunk is summarized, consecutive chunk
+/// summaries are combined into section summaries, and the section summaries are reduced (in
+/// further rounds when there are many) into one document summary. The detail level sets how
+/// long each stage's summaries are; progress is emitted after every model call.
+pub const SUMMARY_PROGRESS_EVENT: &str = "document-summary-progress";
+/// Words per chunk, well inside a 4k-token context with the prompt around it
+const CHUNK_WORDS: usize = 900;
+const CHUNK_OVERLAP_WORDS: usize = 60;
+/// Words of summaries combined in one reduce call
+const REDUCE_INPUT_WORDS: usize = 2_400;
+const MAX_REDUCE_ROUNDS: usize = 6;
+const SYSTEM_PROMPT: &str = "You summarize legal documents accurately. Keep parties, dates, amounts, \
+     obligations and defined terms exactly as written and never add facts that are not in the text.";
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DetailLevel {
+    Brief,
+    #[default]
+    Standard,
+    Detailed,
+}
+
+impl DetailLevel {
+    /// Target words for a chunk, section and document summary
+    fn target_words(self) -> (usize, usize, usize) {
+        match self {
+            DetailLevel::Brief => (60, 120, 200),
+            DetailLevel::Standard => (120, 250, 450),
+            DetailLevel::Detailed => (220, 400, 900),
+        }
+    }
+
+    fn instruction(self) -> &'static str {
+        match self {
+            DetailLevel::Brief => "Give only the essential points.",
+            DetailLevel::Standard => "Cover the main points and the key terms.",
+            DetailLevel::Detailed => "Cover every substantive point, including exceptions, deadlines and amounts.",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SummaryStage {
+    Chunks,
+    Sections,
+    Document,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SummaryProgress {
+    pub job_id: String,
+    pub stage: SummaryStage,
+    pub completed: usize,
+    pub total: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SectionSummary {
+    pub index: usize,
+    pub first_chunk: usize,
+    pub last_chunk: usize,
+    pub summary: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentSummary {
+    pub job_id: String,
+    pub path: String,
+    pub model: String,
+    pub detail: DetailLevel,
+    pub word_count: usize,
+    pub chunks: usize,
+    pub sections: Vec<SectionSummary>,
+    pub reduce_rounds: usize, // rounds after the sections, for very long documents
+    pub summary: String,
+    pub generated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SummarizeRequest {
+    pub path: String,
+    pub model: Option<String>, // defaults to the model the generation server has ready
+    pub detail: Option<DetailLevel>,
+    pub focus: Option<String>, // e.g. "termination rights"
+    pub job_id: Option<String>, // lets the frontend match progress events before the call returns
+}
+
+/// What the pipeline produced, before it is tied to a file and model
+#[derive(Debug, Clone)]
+pub struct SummaryOutline {
+    pub chunks: usize,
+    pub sections: Vec<SectionSummary>,
+    pub reduce_rounds: usize,
+    pub summary: String,
+}
+
+/// Consecutive runs of summaries that fit one reduce call; every run holds at least one
+pub fn group_for_reduce(summaries: &[String], budget_words: usize) -> Vec<Range<usize>> {
+    let mut groups = Vec::new();
+    let mut start = 0;
+    let mut words = 0;
+    for (i, summary) in summaries.iter().enumerate() {
+        let count = summary.split_whitespace().count();
+        if i > start && words + count > budget_words {
+            groups.push(start..i);
+            start = i;
+            words = 0;
+        }
+        words += count;
+    }
+    if start < summaries.len() {
+        groups.push(start..summaries.len());
+    }
+    groups
+}
+
+fn focus_line(focus: Option<&str>) -> String {
+    focus
+        .filter(|f| !f.trim().is_empty())
+        .map(|f| format!("Pay particular attention to: {}.\n", f.trim()))
+        .unwrap_or_default()
+}
+
+fn chunk_prompt(chunk: &str, index: usize, total: usize, detail: DetailLevel, focus: Option<&str>) -> String {
+    format!(
+        "Summarize part {} of {} of a longer document in at most {} words. {}\n{}\nText:\n{}",
+        index + 1,
+        total,
+        detail.target_words().0,
+        detail.instruction(),
+        focus_line(focus),
+        chunk
+    )
+}
+
+fn reduce_prompt(summaries: &[String], words: usize, whole: bool, detail: DetailLevel, focus: Option<&str>) -> String {
+    let what = if whole { "the whole document" } else { "this section of the document" };
+    let parts: Vec<String> = summaries.iter().enumerate().map(|(i, s)| format!("[{}] {}", i + 1, s)).collect();
+    format!(
+        "The following are summaries of consecutive parts of a document, in order. Combine them into one summary of {} in at most {} words, without repeating points. {}\n{}\nSummaries:\n{}",
+        what,
+        words,
+        detail.instruction(),
+        focus_line(focus),
+        parts.join("\n\n")
+    )
+}
+
+/// Run the map-reduce pipeline; `complete` sends a prompt to the model with a token budget
+pub async fn summarize_text<F, Fut, P>(
+    text: &str,
+    detail: DetailLevel,
+    focus: Option<&str>,
+    complete: F,
+    mut progress: P,
+) -> Result<SummaryOutline>
+where
+    F: Fn(String, i32) -> Fut,
+    Fut: Future<Output = Result<String>>,
+    P: FnMut(SummaryStage, usize, usize),
+{
+    let chunks = split_into_chunks(text, CHUNK_WORDS, CHUNK_OVERLAP_WORDS);
+    if chunks.is_empty() {
+        return Err(anyhow!("The document contains no text to summarize"));
+    }
+    let (chunk_words, section_words, document_words) = detail.target_words();
+    let tokens = |words: usize| (words * 2) as i32; // room for the model to run a little long
+
+    let mut chunk_summaries = Vec::with_capacity(chunks.len());
+    for (i, chunk) in chunks.iter().enumerate() {
+        chunk_summaries.push(complete(chunk_prompt(chunk, i, chunks.len(), detail, focus), tokens(chunk_words)).await?);
+        progress(SummaryStage::Chunks, i + 1, chunks.len());
+    }
+
+    // Chunk summaries that fit one call go straight to the document summary
+    let mut sections = Vec::new();
+    let mut level = chunk_summaries;
+    let groups = group_for_reduce(&level, REDUCE_INPUT_WORDS);
+    if groups.len() > 1 {
+        for (i, range) in groups.iter().enumerate() {
+            let prompt = reduce_prompt(&level[range.clone()], section_words, false, detail, focus);
+            sections.push(SectionSummary {
+                index: i + 1,
+                first_chunk: range.start + 1,
+                last_chunk: range.end,
+                summary: complete(prompt, tokens(section_words)).await?,
+            });
+            progress(SummaryStage::Sections, i + 1, groups.len());
+        }
+        level = sections.iter().map(|s| s.summary.clone()).collect();
+    }
+
+    // Very long documents leave more section summaries than one call can take
+    let mut reduce_rounds = 0;
+    loop {
+        let groups = group_for_reduce(&level, REDUCE_INPUT_WORDS);
+        if groups.len() <= 1 || reduce_rounds == MAX_REDUCE_ROUNDS {
+            break;
+        }
+        let mut next = Vec::with_capacity(groups.len());
+        for range in groups {
+            next.push(complete(reduce_prompt(&level[range], section_words, false, detail, focus), tokens(section_words)).await?);
+        }
+        level = next;
+        reduce_rounds += 1;
+    }
+
+    let summary = complete(reduce_prompt(&level, document_words, true, detail, focus), tokens(document_words)).await?;
+    progress(SummaryStage::Document, 1, 1);
+    Ok(SummaryOutline {
+        chunks: chunks.len(),
+        sections,
+        reduce_rounds,
+        summary,
+    })
+}
+
+/// Summarize a document of any length, emitting progress as each chunk and section is done
+#[tauri::command]
+pub async fn summarize_document(
+    request: SummarizeRequest,
+    app: tauri::AppHandle,
+    analyzer: tauri::State<'_, AnalyzerStorage>,
+    llm: tauri::State<'_, Arc<LLMManager>>,
+) -> Result<DocumentSummary, String> {
+    let model = match &request.model {
+        Some(model) => model.clone(),
+        None => llm
+            .default_generation_model()
+            .await
+            .ok_or_else(|| "No model is available to summarize the document".to_string())?,
+    };
+    let text = analyzer.extract_text(Path::new(&request.path)).await.map_err(|e| e.to_string())?;
+    let job_id = request.job_id.clone().unwrap_or_else(|| Uuid::new_v4().to_string());
+    let detail = request.detail.unwrap_or_default();
+
+    let llm = llm.inner().clone();
+    let outline = summarize_text(
+        &text,
+        detail,
+        request.focus.as_deref(),
+        |prompt, max_tokens| {
+            let llm = llm.clone();
+            let model = model.clone();
+            async move { llm.complete(&model, SYSTEM_PROMPT, prompt, max_tokens, 0.2).await }
+        },
+        |stage, completed, total| {
+            let _ = app.emit_all(SUMMARY_PROGRESS_EVENT, SummaryProgress {
+                job_id: job_id.clone(),
+                stage,
+                completed,
+                total,
+            });
+        },
+    )
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(DocumentSummary {
+        job_id,
+        path: request.path,
+        model,
+        detail,
+        word_count: text.split_whitespace().count(),
+        chunks: outline.chunks,
+        sections: outline.sections,
+        reduce_rounds: outline.reduce_rounds,
+        summary: outline.summary,
+        generated_at: Utc::now(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    fn words(n: usize) -> String {
+        vec!["clause"; n].join(" ")
+    }
+
+    #[test]
+    fn test_group_for_reduce_respects_budget() {
+        let summaries = vec![words(100), words(100), words(250), words(10)];
+        assert_eq!(group_for_reduce(&summaries, 200), vec![0..2, 2..3, 3..4]);
+        assert_eq!(group_for_reduce(&summaries, 1000), vec![0..4]);
+        assert_eq!(group_for_reduce(&[words(500)], 200), vec![0..1]); // never empty
+        assert!(group_for_reduce(&[], 200).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_long_document_reduces_through_sections() {
+        let calls = Mutex::new(0usize);
+        let events = Mutex::new(Vec::new());
+        // 40 chunks of 900 words; 120-word chunk summaries make 20 per section
+        let text = words(CHUNK_WORDS * 40 - CHUNK_OVERLAP_WORDS * 39);
+        let outline = summarize_text(
+            &text,
+            DetailLevel::Standard,
+            Some("indemnities"),
+            |prompt, _| {
+                *calls.lock().unwrap() += 1;
+                let focused = prompt.contains("indemnities");
+                async move {
+                    assert!(focused);
+                    Ok(words(120))
+                }
+            },
+            |stage, completed, total| events.lock().unwrap().push((stage, completed, total)),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(outline.chunks, 40);
+        assert_eq!(outline.sections.len(), 2);
+        assert_eq!((outline.sections[1].first_chunk, outline.sections[1].last_chunk), (21, 40));
+        assert_eq!(outline.reduce_rounds, 0);
+        assert_eq!(*calls.lock().unwrap(), 40 + 2 + 1);
+        let events = events.into_inner().unwrap();
+        assert_eq!(events[39], (SummaryStage::Chunks, 40, 40));
+        assert_eq!(events.last(), Some(&(SummaryStage::Document, 1, 1)));
+
+        let short = summarize_text("A short lease.", DetailLevel::Brief, None, |_, _| async { Ok(words(5)) }, |_, _, _| {})
+            .await
+            .unwrap();
+        assert!(short.sections.is_empty());
+        assert!(summarize_text(" ", DetailLevel::Brief, None, |_, _| async { Ok(String::new()) }, |_, _, _| {})
+            .await
+            .is_err());
+    }
+}
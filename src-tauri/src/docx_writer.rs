@@ -0,0 +1,154 @@
+use anyhow::Result;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+/// Minimal DOCX Writer for BEAR AI
+/// Generated reports are delivered as Word documents. Only what reports need is supported:
+/// a title, two heading levels, paragraphs, bullets and simple bordered tables, written as plain
+/// WordprocessingML with the built-in style names so the firm's template styles apply when
+/// the document is attached to it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DocxBlock {
+    Title(String),
+    Heading(u8, String), // level 1 or 2
+    Paragraph(String),
+    Bullet(String),
+    Table { header: Vec<String>, rows: Vec<Vec<String>> },
+}
+
+const CONTENT_TYPES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Override PartName="/word/document.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml"/><Override PartName="/word/styles.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.styles+xml"/></Types>"#;
+
+const PACKAGE_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="word/document.xml"/></Relationships>"#;
+
+const DOCUMENT_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles" Target="styles.xml"/></Relationships>"#;
+
+const STYLES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:styles xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main"><w:docDefaults><w:rPrDefault><w:rPr><w:rFonts w:ascii="Calibri" w:hAnsi="Calibri" w:cs="Calibri"/><w:sz w:val="22"/></w:rPr></w:rPrDefault><w:pPrDefault><w:pPr><w:spacing w:after="120"/></w:pPr></w:pPrDefault></w:docDefaults><w:style w:type="paragraph" w:default="1" w:styleId="Normal"><w:name w:val="Normal"/></w:style><w:style w:type="paragraph" w:styleId="Title"><w:name w:val="Title"/><w:basedOn w:val="Normal"/><w:pPr><w:spacing w:after="240"/></w:pPr><w:rPr><w:b/><w:sz w:val="40"/></w:rPr></w:style><w:style w:type="paragraph" w:styleId="Heading1"><w:name w:val="heading 1"/><w:basedOn w:val="Normal"/><w:pPr><w:keepNext/><w:spacing w:before="240"/><w:outlineLvl w:val="0"/></w:pPr><w:rPr><w:b/><w:sz w:val="30"/></w:rPr></w:style><w:style w:type="paragraph" w:styleId="Heading2"><w:name w:val="heading 2"/><w:basedOn w:val="Normal"/><w:pPr><w:keepNext/><w:spacing w:before="160"/><w:outlineLvl w:val="1"/></w:pPr><w:rPr><w:b/><w:sz w:val="24"/></w:rPr></w:style><w:style w:type="paragraph" w:styleId="ListBullet"><w:name w:val="List Bullet"/><w:basedOn w:val="Normal"/><w:pPr><w:ind w:left="360" w:hanging="360"/></w:pPr></w:style></w:styles>"#;
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn run(text: &str, bold: bool) -> String {
+    let properties = if bold { "<w:rPr><w:b/></w:rPr>" } else { "" };
+    // Line breaks inside a paragraph become <w:br/>
+    let lines: Vec<String> = text
+        .split('\n')
+        .map(|line| format!(r#"<w:t xml:space="preserve">{}</w:t>"#, escape(line)))
+        .collect();
+    format!("<w:r>{}{}</w:r>", properties, lines.join("<w:br/>"))
+}
+
+fn paragraph(style: Option<&str>, content: &str) -> String {
+    let properties = style
+        .map(|s| format!(r#"<w:pPr><w:pStyle w:val="{}"/></w:pPr>"#, s))
+        .unwrap_or_default();
+    format!("<w:p>{}{}</w:p>", properties, content)
+}
+
+fn table(header: &[String], rows: &[Vec<String>]) -> String {
+    let border = r#"w:val="single" w:sz="4" w:space="0" w:color="808080""#;
+    let mut xml = format!(
+        r#"<w:tbl><w:tblPr><w:tblW w:w="5000" w:type="pct"/><w:tblBorders><w:top {b}/><w:left {b}/><w:bottom {b}/><w:right {b}/><w:insideH {b}/><w:insideV {b}/></w:tblBorders></w:tblPr>"#,
+        b = border
+    );
+    let cells = |row: &[String], bold: bool| -> String {
+        row.iter()
+            .map(|cell| format!("<w:tc>{}</w:tc>", paragraph(None, &run(cell, bold))))
+            .collect()
+    };
+    xml.push_str(&format!(r#"<w:tr><w:trPr><w:tblHeader/></w:trPr>{}</w:tr>"#, cells(header, true)));
+    for row in rows {
+        xml.push_str(&format!("<w:tr>{}</w:tr>", cells(row, false)));
+    }
+    xml.push_str("</w:tbl>");
+    // Word needs a paragraph between adjacent tables and at the end of a cell or body
+    xml.push_str("<w:p/>");
+    xml
+}
+
+pub fn render_document_xml(blocks: &[DocxBlock]) -> String {
+    let body: String = blocks
+        .iter()
+        .map(|block| match block {
+            DocxBlock::Title(text) => paragraph(Some("Title"), &run(text, false)),
+            DocxBlock::Heading(level, text) => {
+                let style = if *level <= 1 { "Heading1" } else { "Heading2" };
+                paragraph(Some(style), &run(text, false))
+            }
+            DocxBlock::Paragraph(text) => paragraph(None, &run(text, false)),
+            DocxBlock::Bullet(text) => paragraph(Some("ListBullet"), &run(&format!("\u{2022}\t{}", text), false)),
+            DocxBlock::Table { header, rows } => table(header, rows),
+        })
+        .collect();
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main"><w:body>{}<w:sectPr><w:pgSz w:w="11906" w:h="16838"/><w:pgMar w:top="1440" w:right="1440" w:bottom="1440" w:left="1440" w:header="708" w:footer="708" w:gutter="0"/></w:sectPr></w:body></w:document>"#,
+        body
+    )
+}
+
+pub fn write_docx(path: &Path, blocks: &[DocxBlock]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut zip = ZipWriter::new(fs::File::create(path)?);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    let document = render_document_xml(blocks);
+    let parts: [(&str, &str); 5] = [
+        ("[Content_Types].xml", CONTENT_TYPES),
+        ("_rels/.rels", PACKAGE_RELS),
+        ("word/_rels/document.xml.rels", DOCUMENT_RELS),
+        ("word/styles.xml", STYLES),
+        ("word/document.xml", &document),
+    ];
+    for (name, content) in parts {
+        zip.start_file(name, options)?;
+        zip.write_all(content.as_bytes())?;
+    }
+    zip.finish()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn test_writes_readable_docx() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("reports").join("report.docx");
+        write_docx(
+            &path,
+            &[
+                DocxBlock::Title("Risk report".to_string()),
+                DocxBlock::Heading(1, "Findings & actions".to_string()),
+                DocxBlock::Bullet("Cap <liability>".to_string()),
+                DocxBlock::Table {
+                    header: vec!["Risk".to_string(), "Severity".to_string()],
+                    rows: vec![vec!["Transfers".to_string(), "High".to_string()]],
+                },
+            ],
+        )
+        .unwrap();
+
+        let mut archive = zip::ZipArchive::new(fs::File::open(&path).unwrap()).unwrap();
+        let mut document = String::new();
+        archive.by_name("word/document.xml").unwrap().read_to_string(&mut document).unwrap();
+        assert!(document.contains(r#"<w:pStyle w:val="Heading1"/></w:pPr><w:r><w:t xml:space="preserve">Findings &amp; actions</w:t>"#));
+        assert!(document.contains("Cap &lt;liability&gt;"));
+        assert!(document.contains("<w:tblHeader/>"));
+        assert!(archive.by_name("word/styles.xml").is_ok());
+        assert!(archive.by_name("[Content_Types].xml").is_ok());
+    }
+}
@@ -0,0 +1,460 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::document_analyzer::{ComplianceFlag, ComplianceStatus, DocumentAnalysis, RiskLevel};
+use crate::local_api::AnalyzerStorage;
+
+/// Data Processing Agreement Checker for BEAR AI
+/// A specialised analysis mode for DPAs: the agreement is checked for each element Art. 28 GDPR
+/// requires in a controller-processor contract and, when it relies on the 2021 Standard
+/// Contractual Clauses (Implementing Decision (EU) 2021/914), for the clauses and annexes of the
+/// modules it selects. Every element that cannot be found is reported with the exact provision
+/// that requires it. Matching is keyword-based, so "found" means the topic is addressed, not
+/// that the wording is adequate.
+struct Requirement {
+    reference: &'static str,
+    requirement: &'static str,
+    // Every group must match for the element to count as present; a regex alternation each
+    groups: &'static [&'static str],
+    recommendation: &'static str,
+    priority: RiskLevel,
+}
+
+const ARTICLE_28: &[Requirement] = &[
+    Requirement {
+        reference: "Art. 28(3) GDPR",
+        requirement: "Subject-matter and duration of the processing",
+        groups: &[r"subject[- ]matter", r"duration|term of (this|the) (agreement|dpa)"],
+        recommendation: "Describe the subject-matter of the processing and how long it will last",
+        priority: RiskLevel::Medium,
+    },
+    Requirement {
+        reference: "Art. 28(3) GDPR",
+        requirement: "Nature and purpose of the processing",
+        groups: &[r"nature", r"purposes? of (the )?processing|processing purposes?"],
+        recommendation: "State the nature of the processing operations and the purposes they serve",
+        priority: RiskLevel::Medium,
+    },
+    Requirement {
+        reference: "Art. 28(3) GDPR",
+        requirement: "Type of personal data and categories of data subjects",
+        groups: &[
+            r"(types?|categories) of personal data",
+            r"categories of data subjects",
+        ],
+        recommendation: "List the types of personal data and the categories of data subjects concerned",
+        priority: RiskLevel::Medium,
+    },
+    Requirement {
+        reference: "Art. 28(3)(a) GDPR",
+        requirement: "Processing only on documented instructions of the controller, including for transfers",
+        groups: &[r"documented instructions?|(only|solely) (on|in accordance with) (the )?(written )?instructions"],
+        recommendation: "Bind the processor to process personal data only on the controller's documented instructions, including with regard to transfers to third countries",
+        priority: RiskLevel::High,
+    },
+    Requirement {
+        reference: "Art. 28(3)(b) GDPR",
+        requirement: "Persons authorised to process the data are bound by confidentiality",
+        groups: &[r"confidentiality|duty of confidence|obligations? of secrecy"],
+        recommendation: "Require that staff authorised to process the data have committed to confidentiality or are under a statutory obligation of confidentiality",
+        priority: RiskLevel::High,
+    },
+    Requirement {
+        reference: "Art. 28(3)(c) GDPR",
+        requirement: "Security measures required by Art. 32",
+        groups: &[r"article 32|art\. ?32|technical and organi[sz]ational measures|security of (the )?processing"],
+        recommendation: "Require the processor to take all measures required under Art. 32 and describe them in an annex",
+        priority: RiskLevel::High,
+    },
+    Requirement {
+        reference: "Art. 28(2) and 28(3)(d) GDPR",
+        requirement: "Sub-processors only with the controller's prior authorisation, with notice of changes",
+        groups: &[
+            r"sub-?processors?|another processor",
+            r"prior [a-z ]{0,30}authori[sz]ation|prior (written )?consent",
+        ],
+        recommendation: "Allow sub-processors only with the controller's prior specific or general written authorisation and, for general authorisation, notice of intended changes with a right to object",
+        priority: RiskLevel::High,
+    },
+    Requirement {
+        reference: "Art. 28(4) GDPR",
+        requirement: "Same data protection obligations flowed down to sub-processors; processor remains liable",
+        groups: &[
+            r"same data protection obligations|equivalent (data protection )?obligations|obligations? (no less|not less) protective|flow[- ]down",
+            r"(remain|remains|shall remain) (fully )?liable",
+        ],
+        recommendation: "Impose the same data protection obligations on sub-processors by contract and keep the processor fully liable for their performance",
+        priority: RiskLevel::High,
+    },
+    Requirement {
+        reference: "Art. 28(3)(e) GDPR",
+        requirement: "Assistance with data subject rights requests",
+        groups: &[r"data subjects?'? rights|rights of (the )?data subjects?|requests? (from|by) (a )?data subjects?|exercise of (their|data subject) rights"],
+        recommendation: "Require the processor to assist the controller, by appropriate technical and organisational measures, in responding to data subject requests under Chapter III",
+        priority: RiskLevel::High,
+    },
+    Requirement {
+        reference: "Art. 28(3)(f) GDPR",
+        requirement: "Assistance with security, breach notification, DPIAs and prior consultation (Arts. 32 to 36)",
+        groups: &[
+            r"articles? 32 (to|-|–) 36|personal data breach|data breach|security incident",
+            r"impact assessment|prior consultation|articles? 32 (to|-|–) 36",
+        ],
+        recommendation: "Require the processor to assist with the obligations in Arts. 32 to 36, including notifying personal data breaches without undue delay and supporting DPIAs and prior consultation",
+        priority: RiskLevel::High,
+    },
+    Requirement {
+        reference: "Art. 28(3)(g) GDPR",
+        requirement: "Deletion or return of personal data at the end of the services",
+        groups: &[r"(delete|deletion|erase|erasure|destroy|destruction)\b.{0,40}\b(return|returned)|(return|returned)\b.{0,40}\b(delete|deletion|erase|erasure|destroy|destruction)"],
+        recommendation: "Require the processor, at the controller's choice, to delete or return all personal data after the end of the services and delete existing copies",
+        priority: RiskLevel::High,
+    },
+    Requirement {
+        reference: "Art. 28(3)(h) GDPR",
+        requirement: "Information to demonstrate compliance and audits, including inspections",
+        groups: &[
+            r"audits?|inspections?",
+            r"(all )?information necessary to demonstrate compliance|demonstrate compliance",
+        ],
+        recommendation: "Require the processor to make available all information necessary to demonstrate compliance and to allow for and contribute to audits, including inspections",
+        priority: RiskLevel::High,
+    },
+    Requirement {
+        reference: "Art. 28(3), second subparagraph, GDPR",
+        requirement: "Processor informs the controller if an instruction infringes data protection law",
+        groups: &[r"infring(e|es|ing|ement)"],
+        recommendation: "Require the processor to inform the controller immediately if, in its opinion, an instruction infringes the GDPR or other data protection law",
+        priority: RiskLevel::Medium,
+    },
+];
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SccModule {
+    ControllerToController,
+    ControllerToProcessor,
+    ProcessorToProcessor,
+    ProcessorToController,
+}
+
+impl SccModule {
+    const ALL: [SccModule; 4] = [
+        SccModule::ControllerToController,
+        SccModule::ControllerToProcessor,
+        SccModule::ProcessorToProcessor,
+        SccModule::ProcessorToController,
+    ];
+
+    pub fn number(&self) -> u8 {
+        match self {
+            SccModule::ControllerToController => 1,
+            SccModule::ControllerToProcessor => 2,
+            SccModule::ProcessorToProcessor => 3,
+            SccModule::ProcessorToController => 4,
+        }
+    }
+
+    fn pattern(&self) -> &'static str {
+        match self {
+            SccModule::ControllerToController => r"module (one|1)\b|controller[- ]to[- ]controller",
+            SccModule::ControllerToProcessor => r"module (two|2)\b|controller[- ]to[- ]processor",
+            SccModule::ProcessorToProcessor => r"module (three|3)\b|processor[- ]to[- ]processor",
+            SccModule::ProcessorToController => r"module (four|4)\b|processor[- ]to[- ]controller",
+        }
+    }
+}
+
+struct SccClause {
+    reference: &'static str,
+    requirement: &'static str,
+    modules: &'static [u8],
+    pattern: &'static str,
+}
+
+// Clause 7 (docking) is optional and therefore not checked
+const SCC_CLAUSES: &[SccClause] = &[
+    SccClause { reference: "SCC Clause 8", requirement: "Data protection safeguards", modules: &[1, 2, 3, 4], pattern: r"data protection safeguards" },
+    SccClause { reference: "SCC Clause 9", requirement: "Use of sub-processors (Option 1 or 2 selected)", modules: &[2, 3], pattern: r"use of sub-?processors" },
+    SccClause { reference: "SCC Clause 10", requirement: "Data subject rights", modules: &[1, 2, 3, 4], pattern: r"data subject rights" },
+    SccClause { reference: "SCC Clause 11", requirement: "Redress", modules: &[1, 2, 3, 4], pattern: r"\bredress\b" },
+    SccClause { reference: "SCC Clause 12", requirement: "Liability", modules: &[1, 2, 3, 4], pattern: r"\bliability\b" },
+    SccClause { reference: "SCC Clause 13", requirement: "Supervision (competent supervisory authority)", modules: &[1, 2, 3], pattern: r"\bsupervision\b|competent supervisory authority" },
+    SccClause { reference: "SCC Clause 14", requirement: "Local laws and practices affecting compliance", modules: &[1, 2, 3, 4], pattern: r"local laws and practices" },
+    SccClause { reference: "SCC Clause 15", requirement: "Obligations of the data importer in case of access by public authorities", modules: &[1, 2, 3, 4], pattern: r"access by public authorities" },
+    SccClause { reference: "SCC Clause 16", requirement: "Non-compliance with the Clauses and termination", modules: &[1, 2, 3, 4], pattern: r"non-?compliance with the clauses" },
+    SccClause { reference: "SCC Clause 17", requirement: "Governing law (an EU Member State's law selected)", modules: &[1, 2, 3, 4], pattern: r"governing law|governed by the law of" },
+    SccClause { reference: "SCC Clause 18", requirement: "Choice of forum and jurisdiction", modules: &[1, 2, 3, 4], pattern: r"choice of forum|courts of" },
+    SccClause { reference: "SCC Annex I", requirement: "List of parties, description of the transfer and competent supervisory authority", modules: &[1, 2, 3, 4], pattern: r"annex i\b|list of parties" },
+    SccClause { reference: "SCC Annex II", requirement: "Technical and organisational measures to ensure the security of the data", modules: &[1, 2, 3], pattern: r"annex ii\b" },
+    SccClause { reference: "SCC Annex III", requirement: "List of sub-processors (where specific authorisation is chosen)", modules: &[2, 3], pattern: r"annex iii\b|list of sub-?processors" },
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DpaFinding {
+    pub reference: String,
+    pub requirement: String,
+    pub status: ComplianceStatus,
+    pub evidence: Option<String>, // sentence where the element was found
+    pub recommendation: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SccModuleCheck {
+    pub module: SccModule,
+    pub findings: Vec<DpaFinding>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DpaCheck {
+    pub article_28: Vec<DpaFinding>,
+    pub scc_referenced: bool,
+    pub scc_incorporated_by_reference: bool,
+    pub scc_modules: Vec<SccModuleCheck>,
+    pub missing: Vec<ComplianceFlag>, // one flag per element that is not fully present
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DpaAnalysis {
+    pub analysis: DocumentAnalysis,
+    pub check: DpaCheck,
+}
+
+fn find(text: &str, pattern: &str) -> Option<usize> {
+    Regex::new(&format!("(?i){}", pattern))
+        .ok()
+        .and_then(|re| re.find(text).map(|m| m.start()))
+}
+
+/// The sentence around a match, capped so long clauses stay readable in the report
+fn sentence_at(text: &str, position: usize) -> String {
+    let start = text[..position]
+        .rfind(['.', ';', '\n'])
+        .map(|i| i + 1)
+        .unwrap_or(0);
+    let end = text[position..]
+        .find(['.', ';', '\n'])
+        .map(|i| position + i + 1)
+        .unwrap_or(text.len());
+    let sentence = text[start..end].trim();
+    if sentence.chars().count() > 240 {
+        format!("{}...", sentence.chars().take(240).collect::<String>())
+    } else {
+        sentence.to_string()
+    }
+}
+
+fn check_requirement(text: &str, requirement: &Requirement) -> DpaFinding {
+    let positions: Vec<Option<usize>> = requirement.groups.iter().map(|group| find(text, group)).collect();
+    let found = positions.iter().filter(|p| p.is_some()).count();
+    let status = if found == positions.len() {
+        ComplianceStatus::Compliant
+    } else if found > 0 {
+        ComplianceStatus::PartiallyCompliant
+    } else {
+        ComplianceStatus::NonCompliant
+    };
+    DpaFinding {
+        reference: requirement.reference.to_string(),
+        requirement: requirement.requirement.to_string(),
+        status,
+        evidence: positions.into_iter().flatten().next().map(|p| sentence_at(text, p)),
+        recommendation: requirement.recommendation.to_string(),
+    }
+}
+
+fn check_scc_module(text: &str, module: SccModule, incorporated: bool) -> SccModuleCheck {
+    let findings = SCC_CLAUSES
+        .iter()
+        .filter(|clause| clause.modules.contains(&module.number()))
+        .map(|clause| {
+            let position = find(text, clause.pattern);
+            // Clauses incorporated by reference are not reproduced, so their absence is only
+            // a prompt to confirm the options chosen; annexes must always be completed
+            let status = match position {
+                Some(_) => ComplianceStatus::Compliant,
+                None if incorporated && !clause.reference.contains("Annex") => ComplianceStatus::RequiresReview,
+                None => ComplianceStatus::NonCompliant,
+            };
+            DpaFinding {
+                reference: format!("{} (Module {})", clause.reference, module.number()),
+                requirement: clause.requirement.to_string(),
+                status,
+                evidence: position.map(|p| sentence_at(text, p)),
+                recommendation: format!(
+                    "Include or complete {} as required for Module {} of the Standard Contractual Clauses (Implementing Decision (EU) 2021/914)",
+                    clause.reference,
+                    module.number()
+                ),
+            }
+        })
+        .collect();
+    SccModuleCheck { module, findings }
+}
+
+fn to_flag(regulation: &str, finding: &DpaFinding, priority: RiskLevel) -> ComplianceFlag {
+    ComplianceFlag {
+        regulation: regulation.to_string(),
+        requirement: format!("{} - {}", finding.reference, finding.requirement),
+        compliance_status: finding.status.clone(),
+        recommendation: finding.recommendation.clone(),
+        priority,
+    }
+}
+
+/// Check a data processing agreement for the Art. 28 elements and the selected SCC modules
+pub fn check_dpa(text: &str) -> DpaCheck {
+    let mut missing = Vec::new();
+
+    let article_28: Vec<DpaFinding> = ARTICLE_28
+        .iter()
+        .map(|requirement| {
+            let finding = check_requirement(text, requirement);
+            match finding.status {
+                ComplianceStatus::NonCompliant => missing.push(to_flag("GDPR", &finding, requirement.priority.clone())),
+                ComplianceStatus::PartiallyCompliant => missing.push(to_flag("GDPR", &finding, RiskLevel::Medium)),
+                _ => {}
+            }
+            finding
+        })
+        .collect();
+
+    let scc_referenced = find(text, r"standard contractual clauses|2021/914").is_some();
+    let scc_incorporated_by_reference = scc_referenced
+        && find(text, r"incorporated (herein |into this [a-z]+ )?by reference|deemed (to be )?incorporated").is_some();
+
+    let scc_modules: Vec<SccModuleCheck> = if scc_referenced {
+        SccModule::ALL
+            .iter()
+            .filter(|module| find(text, module.pattern()).is_some())
+            .map(|module| check_scc_module(text, *module, scc_incorporated_by_reference))
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    if scc_referenced && scc_modules.is_empty() {
+        missing.push(ComplianceFlag {
+            regulation: "Standard Contractual Clauses".to_string(),
+            requirement: "SCC Section I - selection of the applicable module(s)".to_string(),
+            compliance_status: ComplianceStatus::NonCompliant,
+            recommendation: "State which SCC module applies (controller/processor roles of exporter and importer); the clauses cannot be completed without it".to_string(),
+            priority: RiskLevel::High,
+        });
+    }
+    for module in &scc_modules {
+        for finding in &module.findings {
+            match finding.status {
+                ComplianceStatus::NonCompliant => {
+                    missing.push(to_flag("Standard Contractual Clauses", finding, RiskLevel::High))
+                }
+                ComplianceStatus::RequiresReview => {
+                    missing.push(to_flag("Standard Contractual Clauses", finding, RiskLevel::Low))
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // Transfers outside the EEA need a Chapter V mechanism
+    let mentions_transfer = find(
+        text,
+        r"third countr(y|ies)|outside (of )?the (eea|european economic area|european union|eu)\b|international transfers?",
+    )
+    .is_some();
+    let other_mechanism = find(text, r"adequacy decision|binding corporate rules|data privacy framework|article 49|art\. ?49").is_some();
+    if mentions_transfer && !scc_referenced && !other_mechanism {
+        missing.push(ComplianceFlag {
+            regulation: "GDPR".to_string(),
+            requirement: "Arts. 44 and 46 GDPR - transfer mechanism for transfers to third countries".to_string(),
+            compliance_status: ComplianceStatus::NonCompliant,
+            recommendation: "Identify the transfer mechanism relied on (adequacy decision, Standard Contractual Clauses or binding corporate rules) for each third-country transfer".to_string(),
+            priority: RiskLevel::High,
+        });
+    }
+
+    DpaCheck {
+        article_28,
+        scc_referenced,
+        scc_incorporated_by_reference,
+        scc_modules,
+        missing,
+    }
+}
+
+/// Analyze a document in DPA mode: the regular analysis plus the Art. 28 / SCC check, with the
+/// missing provisions added to the analysis' compliance flags
+#[tauri::command]
+pub async fn analyze_dpa_file(
+    file_path: String,
+    analyzer: tauri::State<'_, AnalyzerStorage>,
+) -> Result<DpaAnalysis, String> {
+    let mut analysis = analyzer
+        .analyze_document(Path::new(&file_path))
+        .await
+        .map_err(|e| e.to_string())?;
+    let check = check_dpa(&analysis.extracted_text);
+    analysis.compliance_flags.extend(check.missing.iter().cloned());
+    Ok(DpaAnalysis { analysis, check })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COMPLETE_DPA: &str = "Data Processing Agreement. \
+        1. Subject-matter and duration: the processing of payroll data for the term of the Agreement. \
+        2. Nature and purpose of processing: storage and calculation for the purpose of payroll. \
+        Categories of personal data: identification and bank details; categories of data subjects: employees. \
+        3. The Processor shall process Personal Data only on documented instructions from the Controller, including with regard to transfers. \
+        The Processor shall immediately inform the Controller if an instruction infringes the GDPR. \
+        4. Persons authorised to process the data are bound by confidentiality. \
+        5. The Processor implements the technical and organisational measures set out in Annex II. \
+        6. The Processor shall not engage a sub-processor without prior specific or general written authorisation of the Controller. \
+        The same data protection obligations shall be imposed on each sub-processor and the Processor shall remain fully liable. \
+        7. The Processor assists the Controller with requests from data subjects. \
+        8. The Processor notifies any personal data breach without undue delay and assists with any data protection impact assessment. \
+        9. At the end of the services the Processor shall, at the Controller's choice, delete or return all personal data. \
+        10. The Processor makes available all information necessary to demonstrate compliance and allows for audits, including inspections.";
+
+    #[test]
+    fn test_complete_dpa_has_no_missing_article_28_elements() {
+        let check = check_dpa(COMPLETE_DPA);
+        assert_eq!(check.article_28.len(), ARTICLE_28.len());
+        assert!(
+            check.missing.is_empty(),
+            "unexpected findings: {:?}",
+            check.missing.iter().map(|f| &f.requirement).collect::<Vec<_>>()
+        );
+        assert!(!check.scc_referenced);
+    }
+
+    #[test]
+    fn test_reports_missing_provisions_and_scc_module_gaps() {
+        let text = "The Processor processes personal data on behalf of the Controller and keeps it confidential under a duty of confidence. \
+            For transfers to third countries the Standard Contractual Clauses, Module Two (controller to processor), \
+            are incorporated by reference. Clause 17: governed by the law of Ireland. Annex I lists the parties.";
+        let check = check_dpa(text);
+
+        let instructions = check.article_28.iter().find(|f| f.reference == "Art. 28(3)(a) GDPR").unwrap();
+        assert!(matches!(instructions.status, ComplianceStatus::NonCompliant));
+        assert!(check
+            .missing
+            .iter()
+            .any(|f| f.requirement.starts_with("Art. 28(3)(g) GDPR") && matches!(f.priority, RiskLevel::High)));
+
+        assert!(check.scc_incorporated_by_reference);
+        assert_eq!(check.scc_modules.len(), 1);
+        let module = &check.scc_modules[0];
+        assert_eq!(module.module, SccModule::ControllerToProcessor);
+        let status = |reference: &str| {
+            module.findings.iter().find(|f| f.reference.starts_with(reference)).unwrap().status.clone()
+        };
+        assert!(matches!(status("SCC Clause 17"), ComplianceStatus::Compliant));
+        // Incorporated clauses only need review, but annexes must be completed
+        assert!(matches!(status("SCC Clause 9"), ComplianceStatus::RequiresReview));
+        assert!(matches!(status("SCC Annex II"), ComplianceStatus::NonCompliant));
+        assert!(matches!(status("SCC Annex I "), ComplianceStatus::Compliant));
+    }
+}
@@ -0,0 +1,882 @@
+use anyhow::Result;
+use chrono::Utc;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::document_analyzer::{RiskAssessment, RiskLevel, RiskType};
+use crate::docx_writer::{self, DocxBlock};
+use crate::local_api::AnalyzerStorage;
+
+/// Data Protection Impact Assessments for BEAR AI
+/// A guided interview fills in the processing description one question at a time, skipping
+/// questions that do not apply; a processing-description document can pre-fill the answers
+/// first. The answers are screened against the EDPB high-risk criteria (WP248), turned into
+/// risks in the risk engine's terms, matched with the GDPR provisions they engage (quoted from
+/// the RAG index when the regulation has been indexed) and written out as a DOCX report.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LawfulBasis {
+    Consent,
+    Contract,
+    LegalObligation,
+    VitalInterests,
+    PublicTask,
+    LegitimateInterests,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ProcessingScale {
+    Small,
+    Medium,
+    Large,
+}
+
+/// Interview answers; a field stays None until its question has been answered
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DpiaInput {
+    pub processing_name: Option<String>,
+    pub controller: Option<String>,
+    pub description: Option<String>,
+    pub purposes: Option<Vec<String>>,
+    pub lawful_basis: Option<LawfulBasis>,
+    pub data_categories: Option<Vec<String>>,
+    pub special_categories: Option<Vec<String>>, // empty when none are processed
+    pub data_subjects: Option<Vec<String>>,
+    pub vulnerable_subjects: Option<bool>,
+    pub scale: Option<ProcessingScale>,
+    pub profiling: Option<bool>,
+    pub automated_decisions: Option<bool>,
+    pub systematic_monitoring: Option<bool>,
+    pub dataset_matching: Option<bool>,
+    pub innovative_technology: Option<bool>,
+    pub prevents_rights: Option<bool>,
+    pub processors: Option<Vec<String>>,
+    pub international_transfers: Option<Vec<String>>, // destination countries
+    pub transfer_mechanism: Option<String>,
+    pub retention_period: Option<String>,
+    pub security_measures: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum QuestionKind {
+    Text,
+    LongText,
+    List,
+    YesNo,
+    Choice,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DpiaQuestion {
+    pub id: &'static str, // the DpiaInput field the answer goes into
+    pub section: &'static str,
+    pub prompt: &'static str,
+    pub kind: QuestionKind,
+    pub options: &'static [&'static str],
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InterviewStep {
+    pub question: Option<DpiaQuestion>, // None when the interview is complete
+    pub answered: usize,
+    pub remaining: usize,
+}
+
+const fn question(id: &'static str, section: &'static str, prompt: &'static str, kind: QuestionKind) -> DpiaQuestion {
+    DpiaQuestion {
+        id,
+        section,
+        prompt,
+        kind,
+        options: &[],
+    }
+}
+
+const QUESTIONS: &[DpiaQuestion] = &[
+    question("processing_name", "Processing", "What is the processing operation called?", QuestionKind::Text),
+    question("controller", "Processing", "Who is the controller?", QuestionKind::Text),
+    question("description", "Processing", "Describe the processing: what happens to the data, by whom and with which systems.", QuestionKind::LongText),
+    question("purposes", "Processing", "For which purposes are the data processed?", QuestionKind::List),
+    DpiaQuestion {
+        options: &["consent", "contract", "legal_obligation", "vital_interests", "public_task", "legitimate_interests"],
+        ..question("lawful_basis", "Necessity", "On which lawful basis under Article 6 does the processing rest?", QuestionKind::Choice)
+    },
+    question("data_categories", "Data", "Which categories of personal data are processed?", QuestionKind::List),
+    question("special_categories", "Data", "Which special categories (Article 9) or criminal data (Article 10) are processed? Leave empty for none.", QuestionKind::List),
+    question("data_subjects", "Data", "Whose data is processed (employees, customers, patients, ...)?", QuestionKind::List),
+    question("vulnerable_subjects", "Data", "Are any data subjects vulnerable (children, patients, employees, asylum seekers)?", QuestionKind::YesNo),
+    DpiaQuestion {
+        options: &["small", "medium", "large"],
+        ..question("scale", "Data", "At what scale does the processing take place?", QuestionKind::Choice)
+    },
+    question("profiling", "Risk", "Does the processing evaluate or score people, including profiling?", QuestionKind::YesNo),
+    question("automated_decisions", "Risk", "Are decisions with legal or similarly significant effect taken without human involvement?", QuestionKind::YesNo),
+    question("systematic_monitoring", "Risk", "Are people systematically monitored, for example by CCTV, tracking or location data?", QuestionKind::YesNo),
+    question("dataset_matching", "Risk", "Are datasets from different sources matched or combined?", QuestionKind::YesNo),
+    question("innovative_technology", "Risk", "Does the processing use new technology such as AI, biometrics or IoT?", QuestionKind::YesNo),
+    question("prevents_rights", "Risk", "Does the processing prevent people from exercising a right or using a service or contract?", QuestionKind::YesNo),
+    question("processors", "Recipients", "Which processors handle the data on the controller's behalf?", QuestionKind::List),
+    question("international_transfers", "Recipients", "To which countries outside the EEA are the data transferred? Leave empty for none.", QuestionKind::List),
+    question("transfer_mechanism", "Recipients", "Which transfer mechanism covers those transfers (adequacy decision, SCCs, BCRs)?", QuestionKind::Text),
+    question("retention_period", "Necessity", "How long are the data kept?", QuestionKind::Text),
+    question("security_measures", "Security", "Which technical and organisational measures protect the data?", QuestionKind::List),
+];
+
+fn is_answered(input: &DpiaInput, id: &str) -> bool {
+    match id {
+        "processing_name" => input.processing_name.is_some(),
+        "controller" => input.controller.is_some(),
+        "description" => input.description.is_some(),
+        "purposes" => input.purposes.is_some(),
+        "lawful_basis" => input.lawful_basis.is_some(),
+        "data_categories" => input.data_categories.is_some(),
+        "special_categories" => input.special_categories.is_some(),
+        "data_subjects" => input.data_subjects.is_some(),
+        "vulnerable_subjects" => input.vulnerable_subjects.is_some(),
+        "scale" => input.scale.is_some(),
+        "profiling" => input.profiling.is_some(),
+        "automated_decisions" => input.automated_decisions.is_some(),
+        "systematic_monitoring" => input.systematic_monitoring.is_some(),
+        "dataset_matching" => input.dataset_matching.is_some(),
+        "innovative_technology" => input.innovative_technology.is_some(),
+        "prevents_rights" => input.prevents_rights.is_some(),
+        "processors" => input.processors.is_some(),
+        "international_transfers" => input.international_transfers.is_some(),
+        "transfer_mechanism" => input.transfer_mechanism.is_some(),
+        "retention_period" => input.retention_period.is_some(),
+        "security_measures" => input.security_measures.is_some(),
+        _ => true,
+    }
+}
+
+fn applies(input: &DpiaInput, id: &str) -> bool {
+    match id {
+        "transfer_mechanism" => input.international_transfers.as_ref().map(|t| !t.is_empty()).unwrap_or(false),
+        _ => true,
+    }
+}
+
+/// The next unanswered question that applies, with progress counts
+pub fn next_question(input: &DpiaInput) -> InterviewStep {
+    let applicable: Vec<&DpiaQuestion> = QUESTIONS.iter().filter(|q| applies(input, q.id)).collect();
+    let open: Vec<&DpiaQuestion> = applicable.iter().copied().filter(|q| !is_answered(input, q.id)).collect();
+    InterviewStep {
+        question: open.first().map(|q| (*q).clone()),
+        answered: applicable.len() - open.len(),
+        remaining: open.len(),
+    }
+}
+
+const SPECIAL_CATEGORY_TERMS: &[(&str, &str)] = &[
+    ("health", "health data"),
+    ("medical", "health data"),
+    ("biometric", "biometric data"),
+    ("fingerprint", "biometric data"),
+    ("facial recognition", "biometric data"),
+    ("genetic", "genetic data"),
+    ("racial", "racial or ethnic origin"),
+    ("ethnic", "racial or ethnic origin"),
+    ("political", "political opinions"),
+    ("religio", "religious or philosophical beliefs"),
+    ("trade union", "trade union membership"),
+    ("sexual orientation", "sex life or sexual orientation"),
+    ("sex life", "sex life or sexual orientation"),
+    ("criminal", "criminal convictions and offences"),
+];
+
+const DATA_CATEGORY_TERMS: &[(&str, &str)] = &[
+    ("name", "identification data"),
+    ("address", "contact details"),
+    ("email", "contact details"),
+    ("phone", "contact details"),
+    ("date of birth", "identification data"),
+    ("passport", "identification data"),
+    ("bank", "financial data"),
+    ("payment", "financial data"),
+    ("salary", "financial data"),
+    ("location", "location data"),
+    ("ip address", "online identifiers"),
+    ("cookie", "online identifiers"),
+];
+
+const SUBJECT_TERMS: &[(&str, &str)] = &[
+    ("employee", "employees"),
+    ("staff", "employees"),
+    ("applicant", "job applicants"),
+    ("customer", "customers"),
+    ("client", "clients"),
+    ("patient", "patients"),
+    ("child", "children"),
+    ("minor", "children"),
+    ("student", "students"),
+    ("visitor", "visitors"),
+    ("user", "users"),
+];
+
+const VULNERABLE_TERMS: &[&str] = &["child", "minor", "patient", "employee", "elderly", "asylum"];
+
+const THIRD_COUNTRIES: &[&str] = &[
+    "United States", "USA", "India", "China", "Russia", "Brazil", "Australia", "Singapore", "Philippines",
+    "Canada", "Japan", "United Kingdom", "Switzerland", "Israel", "South Africa", "Mexico",
+];
+
+const BASIS_TERMS: &[(&str, LawfulBasis)] = &[
+    ("legitimate interest", LawfulBasis::LegitimateInterests),
+    ("consent", LawfulBasis::Consent),
+    ("legal obligation", LawfulBasis::LegalObligation),
+    ("performance of a contract", LawfulBasis::Contract),
+    ("necessary for the contract", LawfulBasis::Contract),
+    ("vital interest", LawfulBasis::VitalInterests),
+    ("public task", LawfulBasis::PublicTask),
+    ("public interest", LawfulBasis::PublicTask),
+];
+
+fn found_labels(lower: &str, terms: &[(&str, &str)]) -> Vec<String> {
+    let mut labels: Vec<String> = Vec::new();
+    for (term, label) in terms {
+        if lower.contains(term) && !labels.iter().any(|l| l == label) {
+            labels.push(label.to_string());
+        }
+    }
+    labels
+}
+
+/// Pre-fill answers from a processing-description document. Only what the text states is
+/// filled in: a yes/no question is answered yes when its indicators appear and otherwise left
+/// for the interview, since silence in a description is not a no.
+pub fn input_from_description(text: &str) -> DpiaInput {
+    let lower = text.to_lowercase();
+    let any = |terms: &[&str]| terms.iter().any(|t| lower.contains(t));
+    let yes_if = |terms: &[&str]| if any(terms) { Some(true) } else { None };
+    let non_empty = |labels: Vec<String>| if labels.is_empty() { None } else { Some(labels) };
+
+    let mut input = DpiaInput {
+        description: Some(text.split_whitespace().collect::<Vec<_>>().join(" ").chars().take(2000).collect()),
+        data_categories: non_empty(found_labels(&lower, DATA_CATEGORY_TERMS)),
+        special_categories: non_empty(found_labels(&lower, SPECIAL_CATEGORY_TERMS)),
+        data_subjects: non_empty(found_labels(&lower, SUBJECT_TERMS)),
+        vulnerable_subjects: yes_if(VULNERABLE_TERMS),
+        profiling: yes_if(&["profil", "score", "scoring", "evaluat", "predict"]),
+        automated_decisions: yes_if(&["automated decision", "automatically decide", "automatically reject", "without human"]),
+        systematic_monitoring: yes_if(&["cctv", "camera", "monitor", "tracking", "geolocation"]),
+        dataset_matching: yes_if(&["combine", "match", "enrich", "linked with"]),
+        innovative_technology: yes_if(&["artificial intelligence", " ai ", "machine learning", "biometric", "facial recognition", "iot"]),
+        lawful_basis: BASIS_TERMS.iter().find(|(term, _)| lower.contains(term)).map(|(_, basis)| *basis),
+        ..DpiaInput::default()
+    };
+    if any(&["large scale", "large-scale", "nationwide", "millions"]) {
+        input.scale = Some(ProcessingScale::Large);
+    }
+
+    let transfers: Vec<String> = THIRD_COUNTRIES
+        .iter()
+        .filter(|country| Regex::new(&format!(r"\b{}\b", regex::escape(country))).unwrap().is_match(text))
+        .map(|country| country.to_string())
+        .collect();
+    if !transfers.is_empty() {
+        input.international_transfers = Some(transfers);
+        for mechanism in ["standard contractual clauses", "binding corporate rules", "adequacy decision", "data privacy framework"] {
+            if lower.contains(mechanism) {
+                input.transfer_mechanism = Some(mechanism.to_string());
+            }
+        }
+    }
+
+    let retention = Regex::new(r"(?i)(?:retained|kept|stored|deleted after)[^.]{0,40}?(\d+\s+(?:days|weeks|months|years))").unwrap();
+    input.retention_period = retention.captures(text).map(|caps| caps[1].to_string());
+    input
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScreeningCriterion {
+    pub criterion: String,
+    pub met: bool,
+}
+
+/// The nine EDPB criteria; two or more met means a DPIA is required
+pub fn screen(input: &DpiaInput) -> Vec<ScreeningCriterion> {
+    let yes = |answer: Option<bool>| answer.unwrap_or(false);
+    let criteria = [
+        ("Evaluation or scoring", yes(input.profiling)),
+        ("Automated decision-making with legal or similar effect", yes(input.automated_decisions)),
+        ("Systematic monitoring", yes(input.systematic_monitoring)),
+        ("Sensitive or highly personal data", input.special_categories.as_ref().map(|c| !c.is_empty()).unwrap_or(false)),
+        ("Data processed on a large scale", input.scale == Some(ProcessingScale::Large)),
+        ("Matching or combining datasets", yes(input.dataset_matching)),
+        ("Data concerning vulnerable data subjects", yes(input.vulnerable_subjects)),
+        ("Innovative use of new technology", yes(input.innovative_technology)),
+        ("Prevents exercising a right or using a service", yes(input.prevents_rights)),
+    ];
+    criteria
+        .into_iter()
+        .map(|(criterion, met)| ScreeningCriterion {
+            criterion: criterion.to_string(),
+            met,
+        })
+        .collect()
+}
+
+fn severity_rank(level: &RiskLevel) -> u8 {
+    match level {
+        RiskLevel::Low => 0,
+        RiskLevel::Medium => 1,
+        RiskLevel::High => 2,
+        RiskLevel::Critical => 3,
+    }
+}
+
+fn raised(level: RiskLevel) -> RiskLevel {
+    match level {
+        RiskLevel::Low => RiskLevel::Medium,
+        RiskLevel::Medium => RiskLevel::High,
+        _ => RiskLevel::Critical,
+    }
+}
+
+fn risk(risk_type: RiskType, description: &str, severity: RiskLevel, likelihood: f32, impact: &str, mitigations: &[&str], articles: &[&str]) -> RiskAssessment {
+    RiskAssessment {
+        risk_type,
+        description: description.to_string(),
+        severity,
+        likelihood,
+        impact: impact.to_string(),
+        mitigation_strategies: mitigations.iter().map(|m| m.to_string()).collect(),
+        related_clauses: articles.iter().map(|a| format!("{} GDPR", a)).collect(),
+    }
+}
+
+/// Risks to data subjects, in the risk engine's terms. Large-scale processing raises the
+/// severity of every risk by one level.
+pub fn assess_risks(input: &DpiaInput) -> Vec<RiskAssessment> {
+    let yes = |answer: Option<bool>| answer.unwrap_or(false);
+    let mut risks = Vec::new();
+
+    if let Some(special) = input.special_categories.as_ref().filter(|c| !c.is_empty()) {
+        risks.push(risk(
+            RiskType::Data_Privacy,
+            &format!("Processing of special category data: {}", special.join(", ")),
+            RiskLevel::High,
+            0.4,
+            "Disclosure could lead to discrimination, exclusion or distress",
+            &["Confirm an Article 9(2) condition", "Encrypt at rest and in transit", "Restrict access to named roles"],
+            &["Article 9", "Article 10"],
+        ));
+    }
+    if yes(input.automated_decisions) {
+        risks.push(risk(
+            RiskType::Legal,
+            "Decisions with legal or similarly significant effect are taken without human involvement",
+            RiskLevel::High,
+            0.5,
+            "Data subjects may be refused services or treated unfairly without recourse",
+            &["Add meaningful human review", "Explain the logic involved", "Offer a way to contest the decision"],
+            &["Article 22"],
+        ));
+    } else if yes(input.profiling) {
+        risks.push(risk(
+            RiskType::Data_Privacy,
+            "People are evaluated or scored",
+            RiskLevel::Medium,
+            0.4,
+            "Inaccurate profiles can lead to unfair treatment",
+            &["Test the model for accuracy and bias", "Inform data subjects of the profiling"],
+            &["Article 21", "Article 22"],
+        ));
+    }
+    if yes(input.systematic_monitoring) {
+        risks.push(risk(
+            RiskType::Data_Privacy,
+            "Systematic monitoring of individuals",
+            RiskLevel::Medium,
+            0.6,
+            "Chilling effect and loss of control over personal data",
+            &["Limit monitoring to what the purpose requires", "Post clear notices", "Shorten retention of recordings"],
+            &["Article 5(1)(c)", "Article 35(3)(c)"],
+        ));
+    }
+    if yes(input.vulnerable_subjects) {
+        risks.push(risk(
+            RiskType::Data_Privacy,
+            "Data subjects are vulnerable and may not be able to object",
+            RiskLevel::High,
+            0.4,
+            "Power imbalance makes consent and objection unreliable",
+            &["Do not rely on consent where there is a power imbalance", "Consult representatives of the data subjects"],
+            &["Article 35(9)", "Recital 75"],
+        ));
+    }
+    if yes(input.dataset_matching) {
+        risks.push(risk(
+            RiskType::Data_Privacy,
+            "Datasets from different sources are combined",
+            RiskLevel::Medium,
+            0.4,
+            "Combined data reveals more than data subjects expect",
+            &["Check each source's purpose for compatibility", "Minimise the fields combined"],
+            &["Article 5(1)(b)", "Article 6(4)"],
+        ));
+    }
+    if yes(input.innovative_technology) {
+        risks.push(risk(
+            RiskType::Operational,
+            "New technology with consequences that are not yet well understood",
+            RiskLevel::Medium,
+            0.3,
+            "Unforeseen effects on data subjects",
+            &["Pilot with a limited group", "Review the assessment after deployment"],
+            &["Article 25", "Article 35(1)"],
+        ));
+    }
+    if let Some(countries) = input.international_transfers.as_ref().filter(|t| !t.is_empty()) {
+        let covered = input.transfer_mechanism.as_ref().map(|m| !m.trim().is_empty()).unwrap_or(false);
+        risks.push(risk(
+            RiskType::Regulatory,
+            &format!(
+                "Transfers to {}{}",
+                countries.join(", "),
+                if covered { "" } else { " without a documented transfer mechanism" }
+            ),
+            if covered { RiskLevel::Medium } else { RiskLevel::High },
+            0.4,
+            "Foreign authorities may access the data without equivalent safeguards",
+            &["Document a transfer impact assessment", "Put SCCs or another Chapter V mechanism in place", "Encrypt with keys held in the EEA"],
+            &["Article 44", "Article 46"],
+        ));
+    }
+    if input.lawful_basis.is_none() {
+        risks.push(risk(
+            RiskType::Compliance,
+            "No lawful basis identified",
+            RiskLevel::High,
+            0.7,
+            "Processing without a lawful basis is unlawful",
+            &["Identify and document the Article 6 basis before processing starts"],
+            &["Article 6"],
+        ));
+    } else if input.lawful_basis == Some(LawfulBasis::LegitimateInterests) {
+        risks.push(risk(
+            RiskType::Compliance,
+            "Reliance on legitimate interests",
+            RiskLevel::Low,
+            0.3,
+            "The balancing test may come out in favour of the data subjects",
+            &["Record a legitimate interests assessment", "Offer an easy way to object"],
+            &["Article 6(1)(f)", "Article 21"],
+        ));
+    }
+    if input.retention_period.as_ref().map(|r| r.trim().is_empty()).unwrap_or(true) {
+        risks.push(risk(
+            RiskType::Compliance,
+            "No retention period defined",
+            RiskLevel::Medium,
+            0.6,
+            "Data is kept longer than necessary",
+            &["Set and enforce a retention schedule"],
+            &["Article 5(1)(e)"],
+        ));
+    }
+    if input.processors.as_ref().map(|p| !p.is_empty()).unwrap_or(false) {
+        risks.push(risk(
+            RiskType::Operational,
+            "Processors handle the data on the controller's behalf",
+            RiskLevel::Low,
+            0.3,
+            "A processor breach or misuse affects the data subjects",
+            &["Conclude Article 28 processing agreements", "Audit the processors' security"],
+            &["Article 28"],
+        ));
+    }
+    if input.security_measures.as_ref().map(|m| m.len()).unwrap_or(0) < 2 {
+        risks.push(risk(
+            RiskType::Data_Privacy,
+            "Security measures are missing or not documented",
+            RiskLevel::High,
+            0.5,
+            "Unauthorised access, loss or alteration of personal data",
+            &["Document access control, encryption, logging and backup measures"],
+            &["Article 32"],
+        ));
+    }
+
+    if input.scale == Some(ProcessingScale::Large) {
+        for risk in risks.iter_mut() {
+            risk.severity = raised(risk.severity.clone());
+        }
+    }
+    risks.sort_by_key(|r| std::cmp::Reverse(severity_rank(&r.severity)));
+    risks
+}
+
+/// A GDPR provision the processing engages, with the query used to find its text
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProvisionQuery {
+    pub article: String,
+    pub topic: String,
+    pub query: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProvisionExcerpt {
+    pub article: String,
+    pub topic: String,
+    pub excerpts: Vec<String>, // empty when the regulation is not in the index
+}
+
+fn provision(article: &str, topic: &str) -> ProvisionQuery {
+    ProvisionQuery {
+        article: article.to_string(),
+        topic: topic.to_string(),
+        query: format!("GDPR {} {}", article, topic),
+    }
+}
+
+pub fn relevant_provisions(input: &DpiaInput) -> Vec<ProvisionQuery> {
+    let yes = |answer: Option<bool>| answer.unwrap_or(false);
+    let mut provisions = vec![
+        provision("Article 5", "principles relating to processing of personal data"),
+        provision("Article 6", "lawfulness of processing"),
+    ];
+    if input.special_categories.as_ref().map(|c| !c.is_empty()).unwrap_or(false) {
+        provisions.push(provision("Article 9", "processing of special categories of personal data"));
+    }
+    if yes(input.automated_decisions) || yes(input.profiling) {
+        provisions.push(provision("Article 22", "automated individual decision-making, including profiling"));
+    }
+    if input.processors.as_ref().map(|p| !p.is_empty()).unwrap_or(false) {
+        provisions.push(provision("Article 28", "processor"));
+    }
+    provisions.push(provision("Article 32", "security of processing"));
+    provisions.push(provision("Article 35", "data protection impact assessment"));
+    if input.international_transfers.as_ref().map(|t| !t.is_empty()).unwrap_or(false) {
+        provisions.push(provision("Article 46", "transfers subject to appropriate safeguards"));
+    }
+    provisions
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DpiaReport {
+    pub id: String,
+    pub processing_name: String,
+    pub dpia_required: bool,
+    pub screening: Vec<ScreeningCriterion>,
+    pub risks: Vec<RiskAssessment>,
+    pub overall_risk: RiskLevel,
+    pub provisions: Vec<ProvisionExcerpt>,
+    pub unanswered: Vec<String>, // prompts of questions still open
+    pub path: String,
+    pub generated_at: String,
+}
+
+fn level_label(level: &RiskLevel) -> &'static str {
+    match level {
+        RiskLevel::Low => "Low",
+        RiskLevel::Medium => "Medium",
+        RiskLevel::High => "High",
+        RiskLevel::Critical => "Critical",
+    }
+}
+
+fn list_or(values: &Option<Vec<String>>, empty: &str) -> String {
+    match values {
+        Some(values) if !values.is_empty() => values.join(", "),
+        Some(_) => empty.to_string(),
+        None => "Not answered".to_string(),
+    }
+}
+
+fn yes_no(answer: Option<bool>) -> &'static str {
+    match answer {
+        Some(true) => "Yes",
+        Some(false) => "No",
+        None => "Not answered",
+    }
+}
+
+fn render_blocks(input: &DpiaInput, report: &DpiaReport, generated_on: &str) -> Vec<DocxBlock> {
+    let text = |value: &Option<String>| value.clone().unwrap_or_else(|| "Not answered".to_string());
+    let mut blocks = vec![
+        DocxBlock::Title(format!("Data Protection Impact Assessment: {}", report.processing_name)),
+        DocxBlock::Paragraph(format!("Controller: {}\nDate: {}", text(&input.controller), generated_on)),
+        DocxBlock::Heading(1, "1. Description of the processing".to_string()),
+        DocxBlock::Paragraph(text(&input.description)),
+        DocxBlock::Table {
+            header: vec!["Aspect".to_string(), "Answer".to_string()],
+            rows: vec![
+                vec!["Purposes".to_string(), list_or(&input.purposes, "None")],
+                vec!["Categories of personal data".to_string(), list_or(&input.data_categories, "None")],
+                vec!["Special categories".to_string(), list_or(&input.special_categories, "None")],
+                vec!["Data subjects".to_string(), list_or(&input.data_subjects, "None")],
+                vec![
+                    "Scale".to_string(),
+                    input.scale.map(|s| format!("{:?}", s)).unwrap_or_else(|| "Not answered".to_string()),
+                ],
+                vec!["Processors".to_string(), list_or(&input.processors, "None")],
+                vec!["Transfers outside the EEA".to_string(), list_or(&input.international_transfers, "None")],
+                vec!["Transfer mechanism".to_string(), text(&input.transfer_mechanism)],
+            ],
+        },
+        DocxBlock::Heading(1, "2. Necessity and proportionality".to_string()),
+        DocxBlock::Table {
+            header: vec!["Aspect".to_string(), "Answer".to_string()],
+            rows: vec![
+                vec![
+                    "Lawful basis".to_string(),
+                    input
+                        .lawful_basis
+                        .map(|b| format!("{:?}", b))
+                        .unwrap_or_else(|| "Not answered".to_string()),
+                ],
+                vec!["Retention period".to_string(), text(&input.retention_period)],
+                vec!["Security measures".to_string(), list_or(&input.security_measures, "None")],
+                vec!["Automated decisions".to_string(), yes_no(input.automated_decisions).to_string()],
+            ],
+        },
+        DocxBlock::Heading(1, "3. Screening".to_string()),
+        DocxBlock::Table {
+            header: vec!["EDPB criterion".to_string(), "Met".to_string()],
+            rows: report
+                .screening
+                .iter()
+                .map(|c| vec![c.criterion.clone(), if c.met { "Yes" } else { "No" }.to_string()])
+                .collect(),
+        },
+        DocxBlock::Paragraph(if report.dpia_required {
+            "Two or more criteria are met: a DPIA is required under Article 35 GDPR.".to_string()
+        } else {
+            "Fewer than two criteria are met: a DPIA is not strictly required but is recorded as good practice.".to_string()
+        }),
+        DocxBlock::Heading(1, "4. Risks to the rights and freedoms of data subjects".to_string()),
+    ];
+
+    if report.risks.is_empty() {
+        blocks.push(DocxBlock::Paragraph("No specific risks were identified.".to_string()));
+    } else {
+        blocks.push(DocxBlock::Table {
+            header: ["Risk", "Severity", "Likelihood", "Measures", "Provisions"]
+                .iter()
+                .map(|h| h.to_string())
+                .collect(),
+            rows: report
+                .risks
+                .iter()
+                .map(|r| {
+                    vec![
+                        format!("{}\n{}", r.description, r.impact),
+                        level_label(&r.severity).to_string(),
+                        format!("{:.0}%", r.likelihood * 100.0),
+                        r.mitigation_strategies.join("\n"),
+                        r.related_clauses.join(", "),
+                    ]
+                })
+                .collect(),
+        });
+    }
+    blocks.push(DocxBlock::Paragraph(format!(
+        "Overall risk before measures: {}.",
+        level_label(&report.overall_risk)
+    )));
+
+    blocks.push(DocxBlock::Heading(1, "5. Relevant provisions".to_string()));
+    for provision in &report.provisions {
+        blocks.push(DocxBlock::Heading(2, format!("{} GDPR: {}", provision.article, provision.topic)));
+        if provision.excerpts.is_empty() {
+            blocks.push(DocxBlock::Paragraph("Not found in the document index; consult the official text.".to_string()));
+        }
+        for excerpt in &provision.excerpts {
+            blocks.push(DocxBlock::Paragraph(excerpt.clone()));
+        }
+    }
+
+    if !report.unanswered.is_empty() {
+        blocks.push(DocxBlock::Heading(1, "6. Open questions".to_string()));
+        for prompt in &report.unanswered {
+            blocks.push(DocxBlock::Bullet(prompt.clone()));
+        }
+    }
+
+    blocks.push(DocxBlock::Heading(1, "Sign-off".to_string()));
+    blocks.push(DocxBlock::Table {
+        header: vec!["Item".to_string(), "Name / date".to_string(), "Notes".to_string()],
+        rows: ["Measures approved by", "Residual risks approved by", "DPO advice provided", "Prior consultation (Article 36) needed"]
+            .iter()
+            .map(|item| vec![item.to_string(), String::new(), String::new()])
+            .collect(),
+    });
+    blocks
+}
+
+/// Screen, assess and write the report; `excerpts` holds (article, quoted text) pairs found
+/// in the index for the provisions the processing engages
+pub fn generate_report(input: &DpiaInput, excerpts: &[(String, String)], reports_dir: &Path) -> Result<DpiaReport> {
+    let id = Uuid::new_v4().to_string();
+    let screening = screen(input);
+    let risks = assess_risks(input);
+    let overall_risk = risks
+        .iter()
+        .map(|r| r.severity.clone())
+        .max_by_key(severity_rank)
+        .unwrap_or(RiskLevel::Low);
+    let provisions = relevant_provisions(input)
+        .into_iter()
+        .map(|p| ProvisionExcerpt {
+            excerpts: excerpts
+                .iter()
+                .filter(|(article, _)| *article == p.article)
+                .map(|(_, text)| text.clone())
+                .collect(),
+            article: p.article,
+            topic: p.topic,
+        })
+        .collect();
+    let unanswered = QUESTIONS
+        .iter()
+        .filter(|q| applies(input, q.id) && !is_answered(input, q.id))
+        .map(|q| q.prompt.to_string())
+        .collect();
+
+    let processing_name = input
+        .processing_name
+        .clone()
+        .unwrap_or_else(|| "Unnamed processing".to_string());
+    let path: PathBuf = reports_dir.join(format!("dpia_{}.docx", id));
+    let now = Utc::now();
+    let report = DpiaReport {
+        id,
+        processing_name,
+        dpia_required: screening.iter().filter(|c| c.met).count() >= 2,
+        screening,
+        risks,
+        overall_risk,
+        provisions,
+        unanswered,
+        path: path.to_string_lossy().to_string(),
+        generated_at: now.to_rfc3339(),
+    };
+    docx_writer::write_docx(&path, &render_blocks(input, &report, &now.format("%Y-%m-%d").to_string()))?;
+    Ok(report)
+}
+
+/// Where generated DPIA reports are written
+pub struct DpiaReports {
+    pub dir: PathBuf,
+}
+
+impl DpiaReports {
+    pub fn new(app_data_dir: &Path) -> Self {
+        DpiaReports {
+            dir: app_data_dir.join("dpia_reports"),
+        }
+    }
+}
+
+pub type DpiaStorage = Arc<DpiaReports>;
+
+#[tauri::command]
+pub async fn dpia_next_question(input: DpiaInput) -> Result<InterviewStep, String> {
+    Ok(next_question(&input))
+}
+
+/// Pre-fill the interview from a processing-description document
+#[tauri::command]
+pub async fn dpia_prefill_from_document(
+    file_path: String,
+    analyzer: tauri::State<'_, AnalyzerStorage>,
+) -> Result<DpiaInput, String> {
+    let text = analyzer
+        .extract_text(Path::new(&file_path))
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(input_from_description(&text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interview_skips_questions_that_do_not_apply() {
+        let mut input = DpiaInput::default();
+        let step = next_question(&input);
+        assert_eq!(step.question.unwrap().id, "processing_name");
+        assert_eq!(step.remaining, QUESTIONS.len() - 1);
+
+        input.processing_name = Some("Payroll".to_string());
+        input.controller = Some("Acme BV".to_string());
+        input.description = Some("Monthly payroll run".to_string());
+        input.purposes = Some(vec!["salary payment".to_string()]);
+        input.lawful_basis = Some(LawfulBasis::Contract);
+        input.data_categories = Some(vec!["financial data".to_string()]);
+        input.special_categories = Some(Vec::new());
+        input.data_subjects = Some(vec!["employees".to_string()]);
+        input.vulnerable_subjects = Some(false);
+        input.scale = Some(ProcessingScale::Small);
+        input.profiling = Some(false);
+        input.automated_decisions = Some(false);
+        input.systematic_monitoring = Some(false);
+        input.dataset_matching = Some(false);
+        input.innovative_technology = Some(false);
+        input.prevents_rights = Some(false);
+        input.processors = Some(vec!["PayCo".to_string()]);
+        input.international_transfers = Some(Vec::new());
+
+        // No transfers, so the transfer mechanism is never asked
+        let step = next_question(&input);
+        assert_eq!(step.question.unwrap().id, "retention_period");
+        assert_eq!(step.remaining, 2);
+
+        input.international_transfers = Some(vec!["India".to_string()]);
+        assert_eq!(next_question(&input).question.unwrap().id, "transfer_mechanism");
+    }
+
+    #[test]
+    fn test_description_prefills_risks_and_report() {
+        let description = "The HR analytics tool combines CCTV footage with badge data to score employees on \
+            productivity using machine learning. Health data from sick notes is included. Data is hosted by \
+            CloudCo in the United States and retained for 5 years. Processing is based on legitimate interests.";
+        let mut input = input_from_description(description);
+        assert_eq!(input.special_categories, Some(vec!["health data".to_string()]));
+        assert_eq!(input.data_subjects, Some(vec!["employees".to_string()]));
+        assert_eq!(input.international_transfers, Some(vec!["United States".to_string()]));
+        assert_eq!(input.transfer_mechanism, None);
+        assert_eq!(input.retention_period.as_deref(), Some("5 years"));
+        assert_eq!(input.lawful_basis, Some(LawfulBasis::LegitimateInterests));
+        assert_eq!(input.systematic_monitoring, Some(true));
+        // Not stated either way, so left for the interview
+        assert_eq!(input.prevents_rights, None);
+
+        input.processing_name = Some("HR analytics".to_string());
+        let met: Vec<String> = screen(&input).into_iter().filter(|c| c.met).map(|c| c.criterion).collect();
+        assert_eq!(
+            met,
+            vec![
+                "Evaluation or scoring",
+                "Systematic monitoring",
+                "Sensitive or highly personal data",
+                "Matching or combining datasets",
+                "Data concerning vulnerable data subjects",
+                "Innovative use of new technology",
+            ]
+        );
+
+        let risks = assess_risks(&input);
+        assert!(matches!(risks[0].severity, RiskLevel::High));
+        let transfer = risks.iter().find(|r| matches!(r.risk_type, RiskType::Regulatory)).unwrap();
+        assert_eq!(transfer.description, "Transfers to United States without a documented transfer mechanism");
+
+        let dir = tempfile::tempdir().unwrap();
+        let excerpts = vec![("Article 9".to_string(), "Processing of data concerning health shall be prohibited.".to_string())];
+        let report = generate_report(&input, &excerpts, dir.path()).unwrap();
+        assert!(report.dpia_required);
+        assert!(matches!(report.overall_risk, RiskLevel::High));
+        let articles: Vec<&str> = report.provisions.iter().map(|p| p.article.as_str()).collect();
+        assert_eq!(articles, vec!["Article 5", "Article 6", "Article 9", "Article 22", "Article 32", "Article 35", "Article 46"]);
+        assert_eq!(report.provisions[2].excerpts.len(), 1);
+        assert!(report.unanswered.iter().any(|q| q.starts_with("Who is the controller")));
+        assert!(Path::new(&report.path).exists());
+    }
+}
@@ -1,12 +1,17 @@
 use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use tauri::{AppHandle, Manager, State};
 use chrono::{DateTime, Utc};
 use log::{error, info, warn, debug};
 use uuid::Uuid;
 
+use crate::local_api::{authenticated_user, SessionStorage};
+use crate::security::{ActionOutcome, SecurityAction, SecurityManager};
+
 // Enterprise account management structures
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EnterpriseAccount {
@@ -362,6 +367,17 @@ impl EnterpriseManager {
             .into()
     }
 
+    /// Whether a signed-in user name is an active administrator of an enterprise account
+    pub fn is_admin(&self, username: &str) -> bool {
+        let username = username.trim();
+        !username.is_empty()
+            && self.users.values().flatten().any(|u| {
+                u.is_active
+                    && matches!(u.role, UserRole::Admin)
+                    && (u.email.eq_ignore_ascii_case(username) || u.name.as_deref() == Some(username))
+            })
+    }
+
     pub fn update_user_role(&mut self, request: UpdateUserRoleRequest) -> Result<EnterpriseUser> {
         if let Some(users) = self.users.get_mut(&request.account_id) {
             if let Some(user) = users.iter_mut().find(|u| u.id == request.user_id) {
@@ -439,6 +455,253 @@ impl EnterpriseManager {
     }
 }
 
+
+// Information barriers (ethical walls)
+//
+// A barrier group is a set of people and the documents and matters tagged to them. A barrier
+// between two groups screens each side from the other's material: members of group A cannot
+// retrieve, search, open or export what is tagged to group B, and the other way round. Someone
+// who is a member of both groups keeps access to both. Users outside every group are not
+// walled off from anything.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BarrierGroup {
+    pub id: String,
+    pub name: String,
+    pub members: BTreeSet<String>,      // user names or emails, compared case-insensitively
+    pub document_ids: BTreeSet<String>, // document ids or file paths
+    pub matter_ids: BTreeSet<String>,
+    pub updated_at: DateTime<Utc>,
+    pub updated_by: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InformationBarrier {
+    pub id: String,
+    pub name: String,
+    pub group_a: String,
+    pub group_b: String,
+    pub reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub created_by: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BarrierState {
+    pub groups: Vec<BarrierGroup>,
+    pub barriers: Vec<InformationBarrier>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SaveBarrierGroupRequest {
+    pub id: Option<String>, // None creates a group
+    pub name: String,
+    pub members: BTreeSet<String>,
+    pub document_ids: BTreeSet<String>,
+    pub matter_ids: BTreeSet<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateBarrierRequest {
+    pub name: String,
+    pub group_a: String,
+    pub group_b: String,
+    pub reason: Option<String>,
+}
+
+/// Why a user is screened from a document
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BarrierBlock {
+    pub barrier_id: String,
+    pub barrier_name: String,
+    pub user_group: String,
+    pub document_group: String,
+}
+
+/// The barrier, if any, that screens `user` from a document filed under `matter_id`
+pub fn screen(state: &BarrierState, user: &str, document_id: &str, matter_id: Option<&str>) -> Option<BarrierBlock> {
+    let user = user.trim().to_lowercase();
+    if user.is_empty() {
+        return None;
+    }
+    let is_member = |group: &BarrierGroup| group.members.iter().any(|m| m.trim().to_lowercase() == user);
+    let is_tagged = |group: &BarrierGroup| {
+        group.document_ids.contains(document_id) || matter_id.is_some_and(|m| group.matter_ids.contains(m))
+    };
+    let group = |id: &str| state.groups.iter().find(|g| g.id == id);
+
+    for barrier in &state.barriers {
+        let (Some(a), Some(b)) = (group(&barrier.group_a), group(&barrier.group_b)) else {
+            continue;
+        };
+        for (mine, theirs) in [(a, b), (b, a)] {
+            if is_member(mine) && !is_member(theirs) && is_tagged(theirs) {
+                return Some(BarrierBlock {
+                    barrier_id: barrier.id.clone(),
+                    barrier_name: barrier.name.clone(),
+                    user_group: mine.name.clone(),
+                    document_group: theirs.name.clone(),
+                });
+            }
+        }
+    }
+    None
+}
+
+/// Barrier groups and barriers, persisted so walls survive restarts
+pub struct InformationBarriers {
+    path: PathBuf,
+    state: Mutex<BarrierState>,
+}
+
+impl InformationBarriers {
+    pub fn new(app_data_dir: &Path) -> Result<Self> {
+        let path = app_data_dir.join("information_barriers.json");
+        let state = if path.exists() {
+            serde_json::from_str(&fs::read_to_string(&path)?)?
+        } else {
+            BarrierState::default()
+        };
+        Ok(Self {
+            path,
+            state: Mutex::new(state),
+        })
+    }
+
+    fn persist(&self, state: &BarrierState) -> Result<()> {
+        fs::write(&self.path, serde_json::to_string_pretty(state)?)?;
+        Ok(())
+    }
+
+    pub fn list(&self) -> BarrierState {
+        self.state.lock().unwrap().clone()
+    }
+
+    pub fn screen(&self, user: &str, document_id: &str, matter_id: Option<&str>) -> Option<BarrierBlock> {
+        screen(&self.state.lock().unwrap(), user, document_id, matter_id)
+    }
+
+    pub fn save_group(&self, request: SaveBarrierGroupRequest, user: &str) -> Result<BarrierGroup> {
+        if request.name.trim().is_empty() {
+            return Err(anyhow!("Barrier group name is required"));
+        }
+        let mut state = self.state.lock().unwrap();
+        let id = match request.id {
+            Some(id) if state.groups.iter().any(|g| g.id == id) => id,
+            Some(id) => return Err(anyhow!("Barrier group not found: {}", id)),
+            None => Uuid::new_v4().to_string(),
+        };
+        let clean = |set: BTreeSet<String>| set.into_iter().map(|v| v.trim().to_string()).filter(|v| !v.is_empty()).collect();
+        let group = BarrierGroup {
+            id: id.clone(),
+            name: request.name.trim().to_string(),
+            members: clean(request.members),
+            document_ids: clean(request.document_ids),
+            matter_ids: clean(request.matter_ids),
+            updated_at: Utc::now(),
+            updated_by: user.to_string(),
+        };
+        state.groups.retain(|g| g.id != id);
+        state.groups.push(group.clone());
+        self.persist(&state)?;
+        info!("Saved barrier group {} ({} members)", group.name, group.members.len());
+        Ok(group)
+    }
+
+    /// Delete a group and every barrier it is part of
+    pub fn delete_group(&self, group_id: &str) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        state.groups.retain(|g| g.id != group_id);
+        state.barriers.retain(|b| b.group_a != group_id && b.group_b != group_id);
+        self.persist(&state)
+    }
+
+    pub fn create_barrier(&self, request: CreateBarrierRequest, user: &str) -> Result<InformationBarrier> {
+        let mut state = self.state.lock().unwrap();
+        if request.group_a == request.group_b {
+            return Err(anyhow!("A barrier needs two different groups"));
+        }
+        for id in [&request.group_a, &request.group_b] {
+            if !state.groups.iter().any(|g| &g.id == id) {
+                return Err(anyhow!("Barrier group not found: {}", id));
+            }
+        }
+        let barrier = InformationBarrier {
+            id: Uuid::new_v4().to_string(),
+            name: request.name.trim().to_string(),
+            group_a: request.group_a,
+            group_b: request.group_b,
+            reason: request.reason,
+            created_at: Utc::now(),
+            created_by: user.to_string(),
+        };
+        state.barriers.push(barrier.clone());
+        self.persist(&state)?;
+        info!("Created information barrier {}", barrier.name);
+        Ok(barrier)
+    }
+
+    pub fn remove_barrier(&self, barrier_id: &str) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        let before = state.barriers.len();
+        state.barriers.retain(|b| b.id != barrier_id);
+        if state.barriers.len() == before {
+            return Err(anyhow!("Barrier not found: {}", barrier_id));
+        }
+        self.persist(&state)
+    }
+
+    /// Audit an attempt to reach a document behind a barrier; `context` names what was tried
+    /// (retrieval, search, open, export)
+    pub fn record_blocked(&self, security: &SecurityStorage, user: &str, document_id: &str, block: &BarrierBlock, context: &str) {
+        warn!("Information barrier {} screened {} from {} ({})", block.barrier_name, user, document_id, context);
+        let details = HashMap::from([
+            ("user".to_string(), user.to_string()),
+            ("attempt".to_string(), context.to_string()),
+            ("barrier".to_string(), block.barrier_name.clone()),
+            ("barrier_id".to_string(), block.barrier_id.clone()),
+            ("user_group".to_string(), block.user_group.clone()),
+            ("document_group".to_string(), block.document_group.clone()),
+        ]);
+        if let Err(e) = security.lock().unwrap().write_audit_entry(
+            SecurityAction::DocumentAccess,
+            &format!("document:{}", document_id),
+            ActionOutcome::Blocked,
+            Some(details),
+        ) {
+            error!("Failed to audit information barrier block: {}", e);
+        }
+    }
+
+    /// Refuse an export that includes anything screened from `user`, auditing each document
+    pub fn check_export(
+        &self,
+        security: &SecurityStorage,
+        user: &str,
+        items: &[(String, Option<String>)], // (document id or path, matter it is filed under)
+    ) -> Result<(), String> {
+        let mut blocked: BTreeMap<&String, BarrierBlock> = BTreeMap::new();
+        for (document, matter) in items {
+            if let Some(block) = self.screen(user, document, matter.as_deref()) {
+                blocked.entry(document).or_insert(block);
+            }
+        }
+        let Some(first) = blocked.values().next().cloned() else {
+            return Ok(());
+        };
+        for (document, block) in &blocked {
+            self.record_blocked(security, user, document, block, "export");
+        }
+        Err(format!(
+            "Export refused: {} document(s) are behind the information barrier \"{}\"",
+            blocked.len(),
+            first.barrier_name
+        ))
+    }
+}
+
+pub type BarrierStorage = Arc<InformationBarriers>;
+type SecurityStorage = Arc<Mutex<SecurityManager>>;
+
 // Tauri command implementations
 #[tauri::command]
 pub async fn enterprise_create_account(
@@ -533,4 +796,184 @@ pub async fn enterprise_get_usage_stats(
 // Initialize enterprise manager
 pub fn create_enterprise_manager() -> Arc<Mutex<EnterpriseManager>> {
     Arc::new(Mutex::new(EnterpriseManager::new()))
-}
\ No newline at end of file
+}
+
+fn audit_barrier_change(security: &SecurityStorage, resource_id: &str, user: &str, change: &str) {
+    let details = HashMap::from([
+        ("changed_by".to_string(), user.to_string()),
+        ("change".to_string(), change.to_string()),
+    ]);
+    let _ = security.lock().unwrap().write_audit_entry(
+        SecurityAction::SettingsChange,
+        &format!("barrier:{}", resource_id),
+        ActionOutcome::Success,
+        Some(details),
+    );
+}
+
+/// The user a session signed in as, if that user is an administrator; `action` completes
+/// "Only an administrator can ..." in the refusal
+pub fn require_admin(
+    session_id: &str,
+    sessions: &SessionStorage,
+    enterprise: &Mutex<EnterpriseManager>,
+    action: &str,
+) -> Result<String, String> {
+    let user = authenticated_user(session_id, sessions)?;
+    if !enterprise.lock().unwrap().is_admin(&user) {
+        return Err(format!("Only an administrator can {}", action));
+    }
+    Ok(user)
+}
+
+/// Barriers name who is walled off from what, so only administrators see or change them
+#[tauri::command]
+pub async fn barrier_list(
+    session_id: String,
+    barriers: State<'_, BarrierStorage>,
+    sessions: State<'_, SessionStorage>,
+    enterprise: State<'_, Arc<Mutex<EnterpriseManager>>>,
+) -> Result<BarrierState, String> {
+    require_admin(&session_id, &sessions, &enterprise, "view information barriers")?;
+    Ok(barriers.list())
+}
+
+#[tauri::command]
+pub async fn barrier_save_group(
+    session_id: String,
+    request: SaveBarrierGroupRequest,
+    barriers: State<'_, BarrierStorage>,
+    sessions: State<'_, SessionStorage>,
+    security: State<'_, SecurityStorage>,
+    enterprise: State<'_, Arc<Mutex<EnterpriseManager>>>,
+) -> Result<BarrierGroup, String> {
+    let user = require_admin(&session_id, &sessions, &enterprise, "change barrier groups")?;
+    let group = barriers.save_group(request, &user)
+        .map_err(|e| format!("Failed to save barrier group: {}", e))?;
+    audit_barrier_change(&security, &group.id, &user, &format!("group {} saved", group.name));
+    Ok(group)
+}
+
+#[tauri::command]
+pub async fn barrier_delete_group(
+    session_id: String,
+    group_id: String,
+    barriers: State<'_, BarrierStorage>,
+    sessions: State<'_, SessionStorage>,
+    security: State<'_, SecurityStorage>,
+    enterprise: State<'_, Arc<Mutex<EnterpriseManager>>>,
+) -> Result<(), String> {
+    let user = require_admin(&session_id, &sessions, &enterprise, "change barrier groups")?;
+    barriers.delete_group(&group_id)
+        .map_err(|e| format!("Failed to delete barrier group: {}", e))?;
+    audit_barrier_change(&security, &group_id, &user, "group deleted");
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn barrier_create(
+    session_id: String,
+    request: CreateBarrierRequest,
+    barriers: State<'_, BarrierStorage>,
+    sessions: State<'_, SessionStorage>,
+    security: State<'_, SecurityStorage>,
+    enterprise: State<'_, Arc<Mutex<EnterpriseManager>>>,
+) -> Result<InformationBarrier, String> {
+    let user = require_admin(&session_id, &sessions, &enterprise, "change information barriers")?;
+    let barrier = barriers.create_barrier(request, &user)
+        .map_err(|e| format!("Failed to create barrier: {}", e))?;
+    audit_barrier_change(&security, &barrier.id, &user, &format!("barrier {} created", barrier.name));
+    Ok(barrier)
+}
+
+#[tauri::command]
+pub async fn barrier_remove(
+    session_id: String,
+    barrier_id: String,
+    barriers: State<'_, BarrierStorage>,
+    sessions: State<'_, SessionStorage>,
+    security: State<'_, SecurityStorage>,
+    enterprise: State<'_, Arc<Mutex<EnterpriseManager>>>,
+) -> Result<(), String> {
+    let user = require_admin(&session_id, &sessions, &enterprise, "change information barriers")?;
+    barriers.remove_barrier(&barrier_id)
+        .map_err(|e| format!("Failed to remove barrier: {}", e))?;
+    audit_barrier_change(&security, &barrier_id, &user, "barrier removed");
+    Ok(())
+}
+
+/// Whether a user is screened from a document, for conflict checks before staffing a matter.
+/// Users may check themselves; checking someone else takes an administrator.
+#[tauri::command]
+pub async fn barrier_check(
+    session_id: String,
+    user: String,
+    document_id: String,
+    matter_id: Option<String>,
+    barriers: State<'_, BarrierStorage>,
+    sessions: State<'_, SessionStorage>,
+    enterprise: State<'_, Arc<Mutex<EnterpriseManager>>>,
+) -> Result<Option<BarrierBlock>, String> {
+    let caller = authenticated_user(&session_id, &sessions)?;
+    if !caller.eq_ignore_ascii_case(user.trim()) {
+        require_admin(&session_id, &sessions, &enterprise, "check another user against information barriers")?;
+    }
+    Ok(barriers.screen(&user, &document_id, matter_id.as_deref()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn group(id: &str, members: &[&str], documents: &[&str], matters: &[&str]) -> BarrierGroup {
+        let set = |items: &[&str]| items.iter().map(|s| s.to_string()).collect();
+        BarrierGroup {
+            id: id.to_string(),
+            name: id.to_uppercase(),
+            members: set(members),
+            document_ids: set(documents),
+            matter_ids: set(matters),
+            updated_at: Utc::now(),
+            updated_by: "admin".to_string(),
+        }
+    }
+
+    fn walled() -> BarrierState {
+        BarrierState {
+            groups: vec![
+                group("buyer", &["alice@firm.com", "carol"], &["doc-a"], &["m-buyer"]),
+                group("seller", &["bob", "carol"], &["doc-b"], &["m-seller"]),
+            ],
+            barriers: vec![InformationBarrier {
+                id: "w1".to_string(),
+                name: "Acme deal".to_string(),
+                group_a: "buyer".to_string(),
+                group_b: "seller".to_string(),
+                reason: None,
+                created_at: Utc::now(),
+                created_by: "admin".to_string(),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_barrier_screens_both_sides() {
+        let state = walled();
+        let block = screen(&state, "Alice@Firm.com", "doc-b", None).unwrap();
+        assert_eq!(block.user_group, "BUYER");
+        assert_eq!(block.document_group, "SELLER");
+        assert!(screen(&state, "bob", "doc-a", None).is_some());
+        assert!(screen(&state, "bob", "other", Some("m-buyer")).is_some());
+        assert!(screen(&state, "alice@firm.com", "doc-a", None).is_none());
+    }
+
+    #[test]
+    fn test_barrier_spares_outsiders_and_dual_members() {
+        let state = walled();
+        assert!(screen(&state, "dave", "doc-a", Some("m-seller")).is_none());
+        assert!(screen(&state, "", "doc-a", None).is_none());
+        assert!(screen(&state, "carol", "doc-a", None).is_none());
+        assert!(screen(&state, "carol", "doc-b", None).is_none());
+        assert!(screen(&state, "alice@firm.com", "untagged", None).is_none());
+    }
+}
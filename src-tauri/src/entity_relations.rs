@@ -0,0 +1,530 @@
+use anyhow::Result;
+use chrono::Utc;
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+use crate::llm_manager::{GenerateOptions, GenerateRequest, LLMManager};
+use crate::local_api::AnalyzerStorage;
+use crate::matter_consistency::party_definitions;
+use crate::matters::name_tokens;
+
+/// Entity Relations for BEAR AI
+/// Reads who does what to whom from legal text: employment, indemnities, ownership, who acts as
+/// counsel for whom, guarantees and licences. Sentence patterns catch the standard drafting and
+/// a loaded model, when there is one, catches the rest. Defined roles ("the Supplier") are
+/// replaced by the party they stand for, so relations can be queried by party name across
+/// documents: every agreement where Acme indemnifies Beta.
+const PATTERN_CONFIDENCE: f32 = 0.75;
+const LLM_CONFIDENCE: f32 = 0.8;
+// Found by both the patterns and the model
+const CONFIRMED_CONFIDENCE: f32 = 0.95;
+// The model reads the document in windows of this many characters, at most this many of them
+const LLM_WINDOW_CHARS: usize = 3000;
+const MAX_LLM_WINDOWS: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RelationType {
+    Employs,
+    Indemnifies,
+    Owns,
+    CounselFor,
+    Guarantees,
+    Licenses,
+}
+
+impl RelationType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RelationType::Employs => "employs",
+            RelationType::Indemnifies => "indemnifies",
+            RelationType::Owns => "owns",
+            RelationType::CounselFor => "is counsel for",
+            RelationType::Guarantees => "guarantees",
+            RelationType::Licenses => "licenses",
+        }
+    }
+
+    /// Read a relation as the model names it: "indemnifies", "counsel_for", "is counsel for"
+    fn parse(value: &str) -> Option<Self> {
+        let value = value.trim().to_lowercase().replace(['_', '-'], " ");
+        let value = value.strip_prefix("is ").unwrap_or(&value);
+        match value {
+            "employs" | "employer of" => Some(RelationType::Employs),
+            "indemnifies" => Some(RelationType::Indemnifies),
+            "owns" | "owner of" => Some(RelationType::Owns),
+            "counsel for" | "counsel to" | "attorney for" => Some(RelationType::CounselFor),
+            "guarantees" | "guarantor of" => Some(RelationType::Guarantees),
+            "licenses" | "licences" | "licensor of" => Some(RelationType::Licenses),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RelationSource {
+    Pattern,
+    Llm,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntityRelation {
+    pub id: String,
+    pub relation: RelationType,
+    pub subject: String,
+    #[serde(default)]
+    pub subject_role: Option<String>, // the defined role the document used, e.g. "Supplier"
+    pub object: String,
+    #[serde(default)]
+    pub object_role: Option<String>,
+    pub document: String,
+    pub evidence: String, // the sentence the relation was read from
+    pub confidence: f32,
+    pub source: RelationSource,
+    pub extracted_at: String,
+}
+
+/// Relations matching every given field; names match ignoring case and corporate suffixes, and
+/// a name matches any party it is part of ("Acme" matches "Acme Holdings Ltd")
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RelationQuery {
+    pub subject: Option<String>,
+    pub relation: Option<RelationType>,
+    pub object: Option<String>,
+    pub document: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelationQueryResult {
+    pub relations: Vec<EntityRelation>,
+    pub documents: Vec<String>, // documents with at least one matching relation
+}
+
+// A party: up to six capitalised words, optionally after "the". Dots only inside a word
+// ("B.V"), so a name never runs on into the next sentence.
+const PARTY: &str = r"((?:[Tt]he\s+)?[A-Z][\w&'-]*(?:\.[A-Za-z]+)*(?:[ \t]+(?:[A-Z][\w&'-]*(?:\.[A-Za-z]+)*|of|and|&)){0,5})";
+// Capitalised words that open a sentence rather than a party name
+const LEADING_WORDS: &[&str] = &[
+    "accordingly", "additionally", "each", "further", "furthermore", "hereby", "however", "if", "in", "moreover",
+    "notwithstanding", "subject", "thereafter", "where", "whereas", "when",
+];
+
+lazy_static! {
+    // (relation, pattern, whether the first party is the object)
+    static ref RELATION_PATTERNS: Vec<(RelationType, Regex, bool)> = {
+        let pattern = |template: &str| Regex::new(&template.replace("{P}", PARTY)).unwrap();
+        vec![
+            (
+                RelationType::Indemnifies,
+                pattern(r"{P}\s+(?:(?:shall|will|must|hereby|agrees to|undertakes to|fully)\s+)*indemnif(?:y|ies)(?:,?\s+defend)?(?:,?\s+(?:and\s+)?(?:hold|keep)\s+harmless)?\s+{P}"),
+                false,
+            ),
+            (RelationType::Employs, pattern(r"{P}\s+(?:(?:shall|will|hereby|agrees to)\s+)*employs?\s+{P}"), false),
+            (RelationType::Employs, pattern(r"{P}\s+(?:is|shall be|will be|has been)\s+employed\s+by\s+{P}"), true),
+            (
+                RelationType::Owns,
+                pattern(r"{P}\s+(?:owns|holds)\s+(?:all\s+(?:of\s+)?|\d+(?:\.\d+)?\s?%\s+of\s+)?(?:the\s+)?(?:issued\s+)?(?:shares|share capital|equity|interests?)\s+(?:in|of)\s+{P}"),
+                false,
+            ),
+            (RelationType::Owns, pattern(r"{P}\s+(?:owns|is the (?:sole |legal and beneficial )?owner of)\s+{P}"), false),
+            (
+                RelationType::Owns,
+                pattern(r"{P}\s+is\s+(?:a\s+)?(?:wholly[- ]|majority[- ])?owned\s+(?:subsidiary\s+)?(?:of|by)\s+{P}"),
+                true,
+            ),
+            (
+                RelationType::CounselFor,
+                pattern(r"{P},?\s+(?:(?:is|are|acts as|act as|as)\s+)?(?:attorneys?|counsel|solicitors?|lawyers?)\s+(?:for|to)\s+(?:(?:the\s+)?(?:[Pp]laintiffs?|[Dd]efendants?|[Pp]etitioners?|[Rr]espondents?|[Aa]ppellants?|[Aa]ppellees?)\s+)?{P}"),
+                false,
+            ),
+            (
+                RelationType::Guarantees,
+                pattern(r"{P}\s+(?:(?:hereby|irrevocably|unconditionally|shall|and)\s+)*guarantees?\s+(?:(?:the\s+)?(?:due\s+)?(?:performance|obligations|payment)\s+(?:of|by)\s+)?{P}"),
+                false,
+            ),
+            (
+                RelationType::Licenses,
+                pattern(r"{P}\s+(?:(?:hereby|shall)\s+)*(?:grants?|licenses)\s+(?:to\s+)?{P}\s+(?:an?\s+)?(?:[\w-]+,?\s+){0,4}licen[cs]e"),
+                false,
+            ),
+        ]
+    };
+}
+
+/// Drop a leading "the", opening words like "Notwithstanding" and trailing punctuation
+fn clean_party(name: &str) -> String {
+    let mut words: Vec<&str> = name.split_whitespace().collect();
+    while words.len() > 1 && LEADING_WORDS.contains(&words[0].to_lowercase().as_str()) {
+        words.remove(0);
+    }
+    if words.len() > 1 && words[0].eq_ignore_ascii_case("the") {
+        words.remove(0);
+    }
+    while words.last().is_some_and(|w| matches!(*w, "of" | "and" | "&")) {
+        words.pop();
+    }
+    words.join(" ").trim_end_matches(['.', ',']).to_string()
+}
+
+/// The sentence around `start..end`
+fn sentence(text: &str, start: usize, end: usize) -> String {
+    let from = text[..start].rfind(['\n', ';']).map(|i| i + 1).unwrap_or(0);
+    let from = text[from..start].rfind(". ").map(|i| from + i + 2).unwrap_or(from);
+    let to = text[end..].find(['\n', ';']).map(|i| end + i).unwrap_or(text.len());
+    let to = text[end..to].find(". ").map(|i| end + i + 1).unwrap_or(to);
+    text[from..to].split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Replace a defined role by the party it stands for; returns (name, role)
+fn resolve_role(name: &str, roles: &HashMap<String, String>) -> (String, Option<String>) {
+    match roles.get(&name.to_lowercase()) {
+        Some(party) => (party.clone(), Some(name.to_string())),
+        None => (name.to_string(), None),
+    }
+}
+
+fn relation(
+    relation: RelationType,
+    subject: &str,
+    object: &str,
+    document: &str,
+    evidence: String,
+    roles: &HashMap<String, String>,
+    source: RelationSource,
+) -> Option<EntityRelation> {
+    let (subject, subject_role) = resolve_role(&clean_party(subject), roles);
+    let (object, object_role) = resolve_role(&clean_party(object), roles);
+    if name_tokens(&subject).is_empty() || name_tokens(&object).is_empty() || name_tokens(&subject) == name_tokens(&object) {
+        return None;
+    }
+    Some(EntityRelation {
+        id: Uuid::new_v4().to_string(),
+        relation,
+        subject,
+        subject_role,
+        object,
+        object_role,
+        document: document.to_string(),
+        evidence,
+        confidence: match source {
+            RelationSource::Pattern => PATTERN_CONFIDENCE,
+            RelationSource::Llm => LLM_CONFIDENCE,
+        },
+        source,
+        extracted_at: Utc::now().to_rfc3339(),
+    })
+}
+
+/// Defined roles in `text`, lowercased role -> party name
+fn defined_roles(text: &str) -> HashMap<String, String> {
+    party_definitions(text)
+        .into_iter()
+        .map(|(_, name, role)| (role.to_lowercase(), name))
+        .collect()
+}
+
+/// Relations the sentence patterns find in `text`
+pub fn extract_relations_with_patterns(text: &str, document: &str) -> Vec<EntityRelation> {
+    let roles = defined_roles(text);
+    let mut relations = Vec::new();
+    for (relation_type, pattern, reversed) in RELATION_PATTERNS.iter() {
+        for caps in pattern.captures_iter(text) {
+            let whole = caps.get(0).unwrap();
+            let (subject, object) = if *reversed { (&caps[2], &caps[1]) } else { (&caps[1], &caps[2]) };
+            let evidence = sentence(text, whole.start(), whole.end());
+            relations.extend(relation(*relation_type, subject, object, document, evidence, &roles, RelationSource::Pattern));
+        }
+    }
+    relations
+}
+
+#[derive(Debug, Deserialize)]
+struct LlmRelation {
+    subject: String,
+    relation: String,
+    object: String,
+    #[serde(default)]
+    evidence: Option<String>,
+}
+
+fn relations_prompt(window: &str) -> String {
+    format!(
+        "List the relationships between parties stated in the text below. Use only these relations: employs, indemnifies, owns, \
+         counsel_for, guarantees, licenses. Write one JSON object per line, for example\n\
+         {{\"subject\": \"Acme Ltd\", \"relation\": \"indemnifies\", \"object\": \"Beta BV\", \"evidence\": \"Acme Ltd shall indemnify Beta BV\"}}\n\
+         Write nothing else. If there are none, write nothing.\n\nText:\n{}",
+        window
+    )
+}
+
+/// Relations from the model's JSON lines; parties the window does not name are dropped
+fn parse_llm_relations(response: &str, window: &str, document: &str, roles: &HashMap<String, String>) -> Vec<EntityRelation> {
+    let window_lower = window.to_lowercase();
+    response
+        .lines()
+        .filter_map(|line| serde_json::from_str::<LlmRelation>(line.trim().trim_end_matches(',')).ok())
+        .filter(|r| {
+            window_lower.contains(&r.subject.trim().to_lowercase()) && window_lower.contains(&r.object.trim().to_lowercase())
+        })
+        .filter_map(|r| {
+            let relation_type = RelationType::parse(&r.relation)?;
+            let evidence = r
+                .evidence
+                .filter(|e| !e.trim().is_empty())
+                .unwrap_or_else(|| format!("{} {} {}", r.subject, relation_type.as_str(), r.object));
+            relation(relation_type, &r.subject, &r.object, document, evidence, roles, RelationSource::Llm)
+        })
+        .collect()
+}
+
+fn windows(text: &str) -> Vec<String> {
+    let chars: Vec<char> = text.chars().collect();
+    chars
+        .chunks(LLM_WINDOW_CHARS)
+        .take(MAX_LLM_WINDOWS)
+        .map(|c| c.iter().collect())
+        .collect()
+}
+
+async fn extract_relations_with_llm(llm: &LLMManager, text: &str, document: &str) -> Result<Vec<EntityRelation>> {
+    let model = match llm.default_generation_model().await {
+        Some(model) => model,
+        None => return Ok(Vec::new()),
+    };
+    let roles = defined_roles(text);
+    let mut relations = Vec::new();
+    for window in windows(text) {
+        let request = GenerateRequest {
+            model: model.clone(),
+            prompt: relations_prompt(&window),
+            stream: Some(false),
+            options: Some(GenerateOptions {
+                num_predict: Some(600),
+                temperature: Some(0.0),
+                ..Default::default()
+            }),
+            system: Some("You extract relationships between parties from legal documents and answer with JSON lines only.".to_string()),
+            template: None,
+            context: None,
+            raw: None,
+        };
+        let response = llm.generate_response(request).await?.response;
+        relations.extend(parse_llm_relations(&response, &window, document, &roles));
+    }
+    Ok(relations)
+}
+
+/// One relation per (relation, subject, object); one found both ways is confirmed
+fn merge_relations(relations: Vec<EntityRelation>) -> Vec<EntityRelation> {
+    let mut merged: Vec<EntityRelation> = Vec::new();
+    for relation in relations {
+        let key = |r: &EntityRelation| (r.relation, name_tokens(&r.subject), name_tokens(&r.object));
+        match merged.iter_mut().find(|m| key(m) == key(&relation)) {
+            Some(existing) if existing.source != relation.source => existing.confidence = CONFIRMED_CONFIDENCE,
+            Some(_) => {}
+            None => merged.push(relation),
+        }
+    }
+    merged
+}
+
+/// Relations in `text` from the patterns and, when a model is loaded, the model
+pub async fn extract_relations(text: &str, document: &str, llm: Option<&LLMManager>) -> Vec<EntityRelation> {
+    let mut relations = extract_relations_with_patterns(text, document);
+    if let Some(llm) = llm {
+        match extract_relations_with_llm(llm, text, document).await {
+            Ok(found) => relations.extend(found),
+            Err(e) => log::warn!("Relation extraction with the model failed for {}: {}", document, e),
+        }
+    }
+    merge_relations(relations)
+}
+
+/// Whether `name` names `party`, ignoring suffixes; a partial name matches
+fn names_party(name: &str, party: &str, role: Option<&str>) -> bool {
+    let wanted = name_tokens(name);
+    !wanted.is_empty()
+        && (wanted.is_subset(&name_tokens(party)) || role.is_some_and(|r| name_tokens(r) == wanted))
+}
+
+pub struct RelationStore {
+    path: PathBuf,
+    relations: Mutex<Vec<EntityRelation>>,
+}
+
+impl RelationStore {
+    pub fn new(app_data_dir: &Path) -> Result<Self> {
+        let path = app_data_dir.join("entity_relations.json");
+        let relations = if path.exists() {
+            serde_json::from_str(&fs::read_to_string(&path)?)?
+        } else {
+            Vec::new()
+        };
+        Ok(Self {
+            path,
+            relations: Mutex::new(relations),
+        })
+    }
+
+    /// Replace the relations stored for `document`
+    pub fn record(&self, document: &str, found: Vec<EntityRelation>) -> Result<()> {
+        let mut relations = self.relations.lock().unwrap();
+        relations.retain(|r| r.document != document);
+        relations.extend(found);
+        fs::write(&self.path, serde_json::to_string_pretty(&*relations)?)?;
+        Ok(())
+    }
+
+    pub fn query(&self, query: &RelationQuery) -> RelationQueryResult {
+        let mut relations: Vec<EntityRelation> = self
+            .relations
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|r| query.relation.map_or(true, |t| r.relation == t))
+            .filter(|r| query.document.as_ref().map_or(true, |d| &r.document == d))
+            .filter(|r| {
+                query
+                    .subject
+                    .as_ref()
+                    .map_or(true, |s| names_party(s, &r.subject, r.subject_role.as_deref()))
+            })
+            .filter(|r| {
+                query
+                    .object
+                    .as_ref()
+                    .map_or(true, |o| names_party(o, &r.object, r.object_role.as_deref()))
+            })
+            .cloned()
+            .collect();
+        relations.sort_by(|a, b| a.document.cmp(&b.document).then_with(|| a.relation.cmp(&b.relation)));
+
+        let mut documents: Vec<String> = relations.iter().map(|r| r.document.clone()).collect();
+        documents.dedup();
+        RelationQueryResult { relations, documents }
+    }
+}
+
+pub type RelationStorage = Arc<RelationStore>;
+
+/// Extract the relations in a document and store them in place of earlier ones
+#[tauri::command]
+pub async fn extract_entity_relations(
+    file_path: String,
+    analyzer: tauri::State<'_, AnalyzerStorage>,
+    llm: tauri::State<'_, Arc<LLMManager>>,
+    store: tauri::State<'_, RelationStorage>,
+) -> Result<Vec<EntityRelation>, String> {
+    let text = analyzer
+        .extract_text(Path::new(&file_path))
+        .await
+        .map_err(|e| format!("{}: {}", file_path, e))?;
+    let relations = extract_relations(&text, &file_path, Some(llm.inner().as_ref())).await;
+    store.record(&file_path, relations.clone()).map_err(|e| e.to_string())?;
+    Ok(relations)
+}
+
+/// Query stored relations, e.g. every document where one party indemnifies another
+#[tauri::command]
+pub async fn query_entity_relations(
+    query: RelationQuery,
+    store: tauri::State<'_, RelationStorage>,
+) -> Result<RelationQueryResult, String> {
+    Ok(store.query(&query))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MSA: &str = "This Agreement is made between Acme Ltd (the \"Supplier\") and Beta BV (the \"Customer\"). \
+        Notwithstanding Clause 4, the Supplier shall indemnify, defend and hold harmless the Customer against all third party claims. \
+        Acme Ltd is a wholly owned subsidiary of Acme Holdings Plc. \
+        The Supplier hereby grants the Customer a non-exclusive licence to use the Software.\n\
+        Smith & Jones LLP, counsel for Beta BV.";
+
+    fn triples(relations: &[EntityRelation]) -> Vec<(RelationType, &str, &str)> {
+        relations
+            .iter()
+            .map(|r| (r.relation, r.subject.as_str(), r.object.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_extracts_relations_with_defined_roles() {
+        let relations = extract_relations_with_patterns(MSA, "msa.docx");
+        assert_eq!(
+            triples(&relations),
+            vec![
+                (RelationType::Indemnifies, "Acme Ltd", "Beta BV"),
+                (RelationType::Owns, "Acme Holdings Plc", "Acme Ltd"),
+                (RelationType::CounselFor, "Smith & Jones LLP", "Beta BV"),
+                (RelationType::Licenses, "Acme Ltd", "Beta BV"),
+            ]
+        );
+        let indemnity = &relations[0];
+        assert_eq!(indemnity.subject_role.as_deref(), Some("Supplier"));
+        assert_eq!(indemnity.object_role.as_deref(), Some("Customer"));
+        assert_eq!(
+            indemnity.evidence,
+            "Notwithstanding Clause 4, the Supplier shall indemnify, defend and hold harmless the Customer against all third party claims."
+        );
+
+        // The model's lines are checked against the text and merged with the pattern finds
+        let roles = defined_roles(MSA);
+        let response = "{\"subject\": \"Supplier\", \"relation\": \"indemnifies\", \"object\": \"Customer\"}\n\
+                        {\"subject\": \"Beta BV\", \"relation\": \"employs\", \"object\": \"Jane Doe\"}\n\
+                        {\"subject\": \"Acme Ltd\", \"relation\": \"sues\", \"object\": \"Beta BV\"}\n\
+                        not json";
+        let from_llm = parse_llm_relations(response, MSA, "msa.docx", &roles);
+        assert_eq!(triples(&from_llm), vec![(RelationType::Indemnifies, "Acme Ltd", "Beta BV")]);
+        let merged = merge_relations(relations.into_iter().chain(from_llm).collect());
+        assert_eq!(merged.len(), 4);
+        assert_eq!(merged[0].confidence, CONFIRMED_CONFIDENCE);
+        assert_eq!(merged[1].confidence, PATTERN_CONFIDENCE);
+    }
+
+    #[test]
+    fn test_queries_relations_across_documents() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = RelationStore::new(dir.path()).unwrap();
+        store.record("msa.docx", extract_relations_with_patterns(MSA, "msa.docx")).unwrap();
+        store
+            .record(
+                "sow.docx",
+                extract_relations_with_patterns("Beta BV shall indemnify Acme Ltd against losses caused by its data.", "sow.docx"),
+            )
+            .unwrap();
+
+        let query = RelationQuery {
+            subject: Some("Acme".to_string()),
+            relation: Some(RelationType::Indemnifies),
+            object: Some("Beta".to_string()),
+            document: None,
+        };
+        let result = store.query(&query);
+        assert_eq!(result.documents, vec!["msa.docx"]);
+        assert_eq!(triples(&result.relations), vec![(RelationType::Indemnifies, "Acme Ltd", "Beta BV")]);
+
+        // Roles work as names, and re-recording a document replaces its relations
+        let by_role = RelationQuery {
+            subject: Some("Supplier".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(store.query(&by_role).relations.len(), 2);
+        store.record("msa.docx", Vec::new()).unwrap();
+        let reopened = RelationStore::new(dir.path()).unwrap();
+        let indemnities = RelationQuery {
+            relation: Some(RelationType::Indemnifies),
+            ..Default::default()
+        };
+        assert_eq!(reopened.query(&indemnities).documents, vec!["sow.docx"]);
+    }
+}
@@ -0,0 +1,392 @@
+use anyhow::Result;
+use chrono::Utc;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::docx_writer::{self, DocxBlock};
+use crate::local_api::AnalyzerStorage;
+use crate::matters::MatterStorage;
+use crate::table_of_authorities::{page_of, page_starts};
+
+/// Exhibit Lists for BEAR AI
+/// Reads the exhibit references in a filing ("Exhibit A", "Ex. 12", "Exhibits 3-5") and checks
+/// them against the attached documents, whose exhibit labels come from their file names
+/// ("Exhibit A - Lease.pdf", "ex_12_invoice.pdf"). References without an attachment, attachments
+/// nobody cites, two attachments with one label and gaps in the lettering or numbering are
+/// reported, and the exhibit list is written as DOCX.
+const MAX_RANGE: u32 = 50; // "Exhibits 1-500" is a typo, not five hundred exhibits
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(tag = "scheme", content = "value", rename_all = "snake_case")]
+pub enum ExhibitLabel {
+    Letter(u32), // A = 1, Z = 26, AA = 27
+    Number(u32),
+}
+
+impl ExhibitLabel {
+    pub fn parse(label: &str) -> Option<Self> {
+        let label = label.trim();
+        if let Ok(number) = label.parse::<u32>() {
+            return Some(ExhibitLabel::Number(number));
+        }
+        if label.is_empty() || label.len() > 2 || !label.chars().all(|c| c.is_ascii_alphabetic()) {
+            return None;
+        }
+        Some(ExhibitLabel::Letter(
+            label.to_ascii_uppercase().bytes().fold(0, |n, b| n * 26 + (b - b'A' + 1) as u32),
+        ))
+    }
+
+    fn same_scheme(&self, other: &ExhibitLabel) -> bool {
+        matches!(
+            (self, other),
+            (ExhibitLabel::Letter(_), ExhibitLabel::Letter(_)) | (ExhibitLabel::Number(_), ExhibitLabel::Number(_))
+        )
+    }
+
+    fn index(&self) -> u32 {
+        match self {
+            ExhibitLabel::Letter(n) | ExhibitLabel::Number(n) => *n,
+        }
+    }
+
+    fn with_index(&self, n: u32) -> Self {
+        match self {
+            ExhibitLabel::Letter(_) => ExhibitLabel::Letter(n),
+            ExhibitLabel::Number(_) => ExhibitLabel::Number(n),
+        }
+    }
+}
+
+impl std::fmt::Display for ExhibitLabel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExhibitLabel::Number(n) => write!(f, "{}", n),
+            ExhibitLabel::Letter(n) => {
+                let mut n = *n;
+                let mut letters = Vec::new();
+                while n > 0 {
+                    letters.push((b'A' + ((n - 1) % 26) as u8) as char);
+                    n = (n - 1) / 26;
+                }
+                write!(f, "{}", letters.iter().rev().collect::<String>())
+            }
+        }
+    }
+}
+
+/// Where a filing cites one exhibit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExhibitReference {
+    pub label: ExhibitLabel,
+    pub pages: Vec<u32>,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExhibitListEntry {
+    pub label: ExhibitLabel,
+    pub description: String,
+    pub path: String,
+    pub cited_on: Vec<u32>, // empty when the filing never cites it
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateExhibit {
+    pub label: ExhibitLabel,
+    pub paths: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExhibitCheck {
+    pub id: String,
+    pub filing_path: String,
+    pub references: Vec<ExhibitReference>,
+    pub exhibits: Vec<ExhibitListEntry>,
+    pub missing: Vec<ExhibitLabel>,      // cited but not attached
+    pub unreferenced: Vec<ExhibitLabel>, // attached but never cited
+    pub unlabelled: Vec<String>,         // attachments whose file name carries no exhibit label
+    pub duplicates: Vec<DuplicateExhibit>,
+    pub gaps: Vec<ExhibitLabel>, // labels skipped in the sequence
+    pub estimated_pages: bool,
+    pub generated_at: String,
+    pub path: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExhibitCheckRequest {
+    pub filing_path: String,
+    pub matter_id: Option<String>, // attachments default to the matter's documents
+    pub attachments: Option<Vec<String>>,
+    pub first_page: Option<u32>,
+}
+
+/// Exhibit references in a filing with their byte offsets; lists and ranges are expanded
+pub fn find_references(text: &str) -> Vec<(usize, ExhibitLabel)> {
+    let keyword = Regex::new(r"\b(?:Exhibits?|Exhs?\.|Exs?\.)\s+").unwrap();
+    let keyword_next = Regex::new(r"^(?:Exhibits?|Exhs?\.|Exs?\.)\s").unwrap();
+    let label = Regex::new(r"^([A-Z]{1,2}|\d{1,3})\b").unwrap();
+    let connector = Regex::new(r"^\s*(,|and\b|&|-|–|through\b|to\b)\s*").unwrap();
+
+    let mut found = Vec::new();
+    for reference in keyword.find_iter(text) {
+        let mut rest = &text[reference.end()..];
+        let mut previous: Option<ExhibitLabel> = None;
+        let mut range = false;
+        loop {
+            // "Exhibit A and Ex. C": the next keyword starts a reference of its own
+            if previous.is_some() && keyword_next.is_match(rest) {
+                break;
+            }
+            let Some(token) = label.find(rest) else { break };
+            let Some(current) = ExhibitLabel::parse(token.as_str()) else { break };
+            match &previous {
+                Some(first)
+                    if range && current.same_scheme(first) && current > *first && current.index() - first.index() <= MAX_RANGE =>
+                {
+                    for n in first.index() + 1..=current.index() {
+                        found.push((reference.start(), current.with_index(n)));
+                    }
+                }
+                _ => found.push((reference.start(), current.clone())),
+            }
+            rest = &rest[token.end()..];
+            previous = Some(current);
+            let Some(caps) = connector.captures(rest) else { break };
+            range = matches!(&caps[1], "-" | "–" | "through" | "to");
+            rest = &rest[caps.get(0).unwrap().end()..];
+        }
+    }
+    found
+}
+
+/// Exhibit label and description read from an attachment's file name
+pub fn attachment_label(path: &str) -> Option<(ExhibitLabel, String)> {
+    let stem = Path::new(path).file_stem()?.to_string_lossy().to_string();
+    let named = Regex::new(r"^(?i:exhibit|exh?\.?)[\s_.-]*([A-Za-z]{1,2}|\d{1,3})(?:[\s_.-]+(.*))?$").unwrap();
+    let caps = named.captures(stem.trim())?;
+    let label = ExhibitLabel::parse(&caps[1])?;
+    let description = caps
+        .get(2)
+        .map(|m| m.as_str().replace('_', " ").trim_matches(|c: char| c == '-' || c.is_whitespace()).to_string())
+        .filter(|d| !d.is_empty())
+        .unwrap_or(stem);
+    Some((label, description))
+}
+
+/// Labels missing between the first and the last one of each scheme
+pub fn sequence_gaps(labels: &BTreeSet<ExhibitLabel>) -> Vec<ExhibitLabel> {
+    let mut gaps = Vec::new();
+    for scheme in [ExhibitLabel::Letter(0), ExhibitLabel::Number(0)] {
+        let used: Vec<u32> = labels.iter().filter(|l| l.same_scheme(&scheme)).map(|l| l.index()).collect();
+        let (Some(first), Some(last)) = (used.first(), used.last()) else { continue };
+        gaps.extend((*first..*last).filter(|n| !used.contains(n)).map(|n| scheme.with_index(n)));
+    }
+    gaps
+}
+
+/// Cross-check a filing's references against its attachments
+pub fn check(text: &str, attachments: &[String], first_page: u32) -> ExhibitCheck {
+    let (starts, estimated_pages) = page_starts(text);
+
+    let mut references: Vec<ExhibitReference> = Vec::new();
+    for (offset, label) in find_references(text) {
+        let page = page_of(&starts, offset, first_page);
+        match references.iter_mut().find(|r| r.label == label) {
+            Some(reference) => {
+                if !reference.pages.contains(&page) {
+                    reference.pages.push(page);
+                }
+                reference.count += 1;
+            }
+            None => references.push(ExhibitReference { label, pages: vec![page], count: 1 }),
+        }
+    }
+    references.sort_by(|a, b| a.label.cmp(&b.label));
+
+    let mut exhibits: Vec<ExhibitListEntry> = Vec::new();
+    let mut unlabelled = Vec::new();
+    for path in attachments {
+        match attachment_label(path) {
+            Some((label, description)) => exhibits.push(ExhibitListEntry {
+                cited_on: references.iter().find(|r| r.label == label).map(|r| r.pages.clone()).unwrap_or_default(),
+                label,
+                description,
+                path: path.clone(),
+            }),
+            None => unlabelled.push(path.clone()),
+        }
+    }
+    exhibits.sort_by(|a, b| a.label.cmp(&b.label).then_with(|| a.path.cmp(&b.path)));
+
+    let mut duplicates: Vec<DuplicateExhibit> = Vec::new();
+    for pair in exhibits.windows(2).filter(|w| w[0].label == w[1].label) {
+        match duplicates.iter_mut().find(|d| d.label == pair[0].label) {
+            Some(duplicate) => duplicate.paths.push(pair[1].path.clone()),
+            None => duplicates.push(DuplicateExhibit {
+                label: pair[0].label.clone(),
+                paths: vec![pair[0].path.clone(), pair[1].path.clone()],
+            }),
+        }
+    }
+
+    let attached: BTreeSet<ExhibitLabel> = exhibits.iter().map(|e| e.label.clone()).collect();
+    let cited: BTreeSet<ExhibitLabel> = references.iter().map(|r| r.label.clone()).collect();
+    ExhibitCheck {
+        id: Uuid::new_v4().to_string(),
+        filing_path: String::new(),
+        missing: cited.difference(&attached).cloned().collect(),
+        unreferenced: attached.difference(&cited).cloned().collect(),
+        gaps: sequence_gaps(&attached.union(&cited).cloned().collect()),
+        references,
+        exhibits,
+        unlabelled,
+        duplicates,
+        estimated_pages,
+        generated_at: Utc::now().to_rfc3339(),
+        path: None,
+    }
+}
+
+fn labels(labels: &[ExhibitLabel]) -> String {
+    labels.iter().map(|l| l.to_string()).collect::<Vec<_>>().join(", ")
+}
+
+fn render_blocks(check: &ExhibitCheck) -> Vec<DocxBlock> {
+    let mut blocks = vec![
+        DocxBlock::Title("Exhibit List".to_string()),
+        DocxBlock::Table {
+            header: vec!["Exhibit".to_string(), "Description".to_string(), "Cited on page(s)".to_string()],
+            rows: check
+                .exhibits
+                .iter()
+                .map(|e| {
+                    let pages = e.cited_on.iter().map(|p| p.to_string()).collect::<Vec<_>>().join(", ");
+                    vec![e.label.to_string(), e.description.clone(), if pages.is_empty() { "Not cited".to_string() } else { pages }]
+                })
+                .collect(),
+        },
+        DocxBlock::Heading(1, "Cross-reference check".to_string()),
+    ];
+    let mut issues = Vec::new();
+    if !check.missing.is_empty() {
+        issues.push(format!("Cited but not attached: {}", labels(&check.missing)));
+    }
+    for duplicate in &check.duplicates {
+        issues.push(format!("Exhibit {} is attached {} times", duplicate.label, duplicate.paths.len()));
+    }
+    if !check.gaps.is_empty() {
+        issues.push(format!("Skipped in the sequence: {}", labels(&check.gaps)));
+    }
+    if !check.unreferenced.is_empty() {
+        issues.push(format!("Attached but never cited: {}", labels(&check.unreferenced)));
+    }
+    for path in &check.unlabelled {
+        issues.push(format!("No exhibit label in the file name: {}", path));
+    }
+    if issues.is_empty() {
+        blocks.push(DocxBlock::Paragraph("Every cited exhibit is attached once and the sequence has no gaps.".to_string()));
+    }
+    blocks.extend(issues.into_iter().map(DocxBlock::Bullet));
+    if check.estimated_pages {
+        blocks.push(DocxBlock::Paragraph(
+            "Page references are estimated from the word count because the filing's file carries no page breaks.".to_string(),
+        ));
+    }
+    blocks
+}
+
+/// Where exhibit lists are written
+pub struct ExhibitLists {
+    pub dir: PathBuf,
+}
+
+impl ExhibitLists {
+    pub fn new(app_data_dir: &Path) -> Self {
+        ExhibitLists {
+            dir: app_data_dir.join("exhibit_lists"),
+        }
+    }
+
+    pub fn export(&self, mut check: ExhibitCheck) -> Result<ExhibitCheck> {
+        let path = self.dir.join(format!("exhibit_list_{}.docx", check.id));
+        docx_writer::write_docx(&path, &render_blocks(&check))?;
+        check.path = Some(path.to_string_lossy().to_string());
+        Ok(check)
+    }
+}
+
+pub type ExhibitListStorage = Arc<ExhibitLists>;
+
+/// Check a filing's exhibit references against its attachments and write the exhibit list
+#[tauri::command]
+pub async fn check_exhibits(
+    request: ExhibitCheckRequest,
+    lists: tauri::State<'_, ExhibitListStorage>,
+    matters: tauri::State<'_, MatterStorage>,
+    analyzer: tauri::State<'_, AnalyzerStorage>,
+) -> Result<ExhibitCheck, String> {
+    let attachments = match (&request.attachments, &request.matter_id) {
+        (Some(attachments), _) => attachments.clone(),
+        (None, Some(matter_id)) => {
+            let matter = matters.get(matter_id).ok_or_else(|| format!("Matter {} not found", matter_id))?;
+            // The filing itself is usually among the matter's documents
+            matter.documents.into_iter().filter(|d| *d != request.filing_path).collect()
+        }
+        (None, None) => return Err("Attach the exhibits or choose the matter they belong to".to_string()),
+    };
+    let text = analyzer
+        .extract_text(Path::new(&request.filing_path))
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut result = check(&text, &attachments, request.first_page.unwrap_or(1));
+    result.filing_path = request.filing_path.clone();
+    lists.export(result).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_references_and_labels() {
+        let text = "As shown in Exhibit A and Ex. C, the lease ended. See Exhibits 3-5 and Exs. 7, 9 and 10 to the Smith Declaration.";
+        let found: Vec<String> = find_references(text).into_iter().map(|(_, l)| l.to_string()).collect();
+        assert_eq!(found, vec!["A", "C", "3", "4", "5", "7", "9", "10"]);
+        assert_eq!(ExhibitLabel::parse("AB").unwrap().to_string(), "AB");
+        assert_eq!(ExhibitLabel::parse("Z"), Some(ExhibitLabel::Letter(26)));
+        assert!(ExhibitLabel::Letter(26) < ExhibitLabel::Letter(27));
+
+        assert_eq!(attachment_label("/m/Exhibit A - Lease Agreement.pdf"), Some((ExhibitLabel::Letter(1), "Lease Agreement".to_string())));
+        assert_eq!(attachment_label("/m/ex_12_invoice.pdf"), Some((ExhibitLabel::Number(12), "invoice".to_string())));
+        assert_eq!(attachment_label("/m/Exhibit_Contract.pdf"), None);
+        assert_eq!(attachment_label("/m/complaint.pdf"), None);
+    }
+
+    #[test]
+    fn test_cross_reference_check() {
+        let text = "Exhibit A shows the notice.\u{c}Exhibit B and Exhibit D show the reply. Exhibit A again.";
+        let attachments = vec![
+            "/m/Exhibit A notice.pdf".to_string(),
+            "/m/Exhibit B reply.pdf".to_string(),
+            "/m/Exhibit B reply (copy).pdf".to_string(),
+            "/m/Exhibit E photo.jpg".to_string(),
+            "/m/cover letter.pdf".to_string(),
+        ];
+        let result = check(text, &attachments, 10);
+        assert_eq!(result.missing, vec![ExhibitLabel::Letter(4)]);
+        assert_eq!(result.unreferenced, vec![ExhibitLabel::Letter(5)]);
+        assert_eq!(result.gaps, vec![ExhibitLabel::Letter(3)]);
+        assert_eq!(result.duplicates.len(), 1);
+        assert_eq!(result.duplicates[0].label, ExhibitLabel::Letter(2));
+        assert_eq!(result.unlabelled, vec!["/m/cover letter.pdf"]);
+        assert_eq!(result.references[0].pages, vec![10, 11]);
+        assert_eq!(result.exhibits[0].cited_on, vec![10, 11]);
+        assert!(!result.estimated_pages);
+    }
+}
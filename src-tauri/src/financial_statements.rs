@@ -0,0 +1,737 @@
+use anyhow::{anyhow, Result};
+use calamine::{open_workbook_auto, Data, Reader};
+use chrono::{Duration, NaiveDate};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::document_analyzer::RiskLevel;
+use crate::local_api::AnalyzerStorage;
+use crate::locale_formats;
+
+/// Financial Statement Analysis for BEAR AI
+/// Spreadsheets are read cell by cell with their types intact instead of being flattened to
+/// text. Sheets laid out as a balance sheet, income statement or cash flow statement are
+/// recognised from their line-item labels, the standard line items are picked out per period,
+/// and the usual ratios are computed. Anomalies are flagged: negative equity, a balance sheet
+/// that does not balance, losses, falling revenue, and breaches of the financial covenants
+/// found in a credit agreement when one is supplied.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+pub enum CellValue {
+    Empty,
+    Number(f64),
+    Text(String),
+    Bool(bool),
+    Date(String), // ISO 8601
+    Error(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Sheet {
+    pub name: String,
+    pub rows: Vec<Vec<CellValue>>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StatementKind {
+    BalanceSheet,
+    IncomeStatement,
+    CashFlowStatement,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum FinancialMetric {
+    Revenue,
+    CostOfSales,
+    GrossProfit,
+    OperatingIncome,
+    Ebitda,
+    InterestExpense,
+    NetIncome,
+    TotalAssets,
+    CurrentAssets,
+    Cash,
+    TotalLiabilities,
+    CurrentLiabilities,
+    TotalEquity,
+    TotalDebt,
+    OperatingCashFlow,
+}
+
+// Normalised line-item labels (lowercase, punctuation removed) for each metric
+const METRIC_LABELS: &[(FinancialMetric, &[&str])] = &[
+    (FinancialMetric::Revenue, &["revenue", "revenues", "total revenue", "total revenues", "net revenue", "turnover", "sales", "net sales"]),
+    (FinancialMetric::CostOfSales, &["cost of sales", "cost of goods sold", "cost of revenue", "cost of revenues"]),
+    (FinancialMetric::GrossProfit, &["gross profit"]),
+    (FinancialMetric::OperatingIncome, &["operating income", "operating profit", "ebit", "operating result", "profit from operations", "results from operating activities"]),
+    (FinancialMetric::Ebitda, &["ebitda", "adjusted ebitda"]),
+    (FinancialMetric::InterestExpense, &["interest expense", "interest expenses", "finance costs", "net finance costs", "finance expense", "finance expenses"]),
+    (FinancialMetric::NetIncome, &["net income", "net profit", "net loss", "net income loss", "net profit loss", "profit for the year", "profit for the period", "profit loss for the year", "profit loss for the period", "result for the year"]),
+    (FinancialMetric::TotalAssets, &["total assets"]),
+    (FinancialMetric::CurrentAssets, &["total current assets", "current assets"]),
+    (FinancialMetric::Cash, &["cash and cash equivalents", "cash"]),
+    (FinancialMetric::TotalLiabilities, &["total liabilities"]),
+    (FinancialMetric::CurrentLiabilities, &["total current liabilities", "current liabilities"]),
+    (FinancialMetric::TotalEquity, &["total equity", "equity", "shareholders equity", "total shareholders equity", "stockholders equity", "total stockholders equity", "net assets", "equity attributable to owners of the parent"]),
+    (FinancialMetric::TotalDebt, &["total debt", "borrowings", "total borrowings", "loans and borrowings", "bank loans", "interest bearing debt"]),
+    (FinancialMetric::OperatingCashFlow, &["net cash from operating activities", "net cash generated from operating activities", "net cash provided by operating activities", "net cash flows from operating activities"]),
+];
+
+// Label keywords that give away each statement layout
+const LAYOUT_KEYWORDS: &[(StatementKind, &[&str])] = &[
+    (StatementKind::BalanceSheet, &["balance sheet", "financial position", "total assets", "total liabilities", "equity", "current assets"]),
+    (StatementKind::IncomeStatement, &["income statement", "profit or loss", "revenue", "turnover", "cost of sales", "gross profit", "operating", "net income", "profit for the"]),
+    (StatementKind::CashFlowStatement, &["cash flow", "operating activities", "investing activities", "financing activities"]),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LineItem {
+    pub label: String,
+    pub metric: Option<FinancialMetric>,
+    pub values: Vec<Option<f64>>, // one per period
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatementSheet {
+    pub name: String,
+    pub kind: Option<StatementKind>, // None when the layout is not recognised
+    pub periods: Vec<String>,
+    pub line_items: Vec<LineItem>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PeriodRatios {
+    pub period: String,
+    pub current_ratio: Option<f64>,
+    pub debt_to_equity: Option<f64>,
+    pub leverage: Option<f64>, // total debt / EBITDA
+    pub interest_coverage: Option<f64>, // EBITDA (or operating income) / interest
+    pub gross_margin: Option<f64>, // percentages
+    pub operating_margin: Option<f64>,
+    pub net_margin: Option<f64>,
+    pub return_on_equity: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CovenantMetric {
+    Leverage,
+    InterestCoverage,
+    CurrentRatio,
+    DebtToEquity,
+    MinimumEquity,
+}
+
+impl CovenantMetric {
+    fn label(&self) -> &'static str {
+        match self {
+            CovenantMetric::Leverage => "Leverage ratio",
+            CovenantMetric::InterestCoverage => "Interest cover",
+            CovenantMetric::CurrentRatio => "Current ratio",
+            CovenantMetric::DebtToEquity => "Debt to equity ratio",
+            CovenantMetric::MinimumEquity => "Equity",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CovenantLimit {
+    Maximum,
+    Minimum,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Covenant {
+    pub metric: CovenantMetric,
+    pub limit: CovenantLimit,
+    pub threshold: f64,
+    pub excerpt: String, // wording in the credit agreement
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CovenantTest {
+    pub covenant: Covenant,
+    pub period: Option<String>, // None when no period had the figures to test it
+    pub actual: Option<f64>,
+    pub breached: Option<bool>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AnomalyKind {
+    NegativeEquity,
+    BalanceMismatch,
+    NetLoss,
+    RevenueDecline,
+    NegativeOperatingCashFlow,
+    LowLiquidity,
+    CovenantBreach,
+    CovenantUntested,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FinancialAnomaly {
+    pub kind: AnomalyKind,
+    pub period: Option<String>,
+    pub description: String,
+    pub severity: RiskLevel,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FinancialAnalysis {
+    pub file: String,
+    pub statements: Vec<StatementSheet>,
+    pub ratios: Vec<PeriodRatios>,
+    pub covenant_tests: Vec<CovenantTest>,
+    pub anomalies: Vec<FinancialAnomaly>,
+}
+
+/// Excel stores dates as days since 1899-12-30
+fn excel_serial_to_iso(serial: f64) -> String {
+    let epoch = NaiveDate::from_ymd_opt(1899, 12, 30).unwrap();
+    (epoch + Duration::days(serial.floor() as i64)).format("%Y-%m-%d").to_string()
+}
+
+fn cell_value(cell: &Data) -> CellValue {
+    match cell {
+        Data::Empty => CellValue::Empty,
+        Data::Int(value) => CellValue::Number(*value as f64),
+        Data::Float(value) => CellValue::Number(*value),
+        Data::String(value) if value.trim().is_empty() => CellValue::Empty,
+        Data::String(value) => CellValue::Text(value.trim().to_string()),
+        Data::Bool(value) => CellValue::Bool(*value),
+        Data::DateTime(value) => CellValue::Date(excel_serial_to_iso(value.as_f64())),
+        Data::DateTimeIso(value) | Data::DurationIso(value) => CellValue::Date(value.clone()),
+        Data::Error(error) => CellValue::Error(error.to_string()),
+    }
+}
+
+/// Read every sheet of a workbook (xlsx, xls, ods) with cell types preserved
+pub fn read_workbook(path: &Path) -> Result<Vec<Sheet>> {
+    let mut workbook = open_workbook_auto(path).map_err(|e| anyhow!("Failed to read spreadsheet: {}", e))?;
+    let mut sheets = Vec::new();
+    for name in workbook.sheet_names() {
+        if let Ok(range) = workbook.worksheet_range(&name) {
+            sheets.push(Sheet {
+                rows: range.rows().map(|row| row.iter().map(cell_value).collect()).collect(),
+                name,
+            });
+        }
+    }
+    Ok(sheets)
+}
+
+fn normalize_label(label: &str) -> String {
+    label
+        .to_lowercase()
+        .replace('&', " and ")
+        .replace('\'', "")
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn metric_for(label: &str) -> Option<FinancialMetric> {
+    let label = normalize_label(label);
+    METRIC_LABELS
+        .iter()
+        .find(|(_, labels)| labels.contains(&label.as_str()))
+        .map(|(metric, _)| *metric)
+}
+
+/// Numbers typed as text: "1.234,5", "(1,200)" and "-300" included
+fn numeric(cell: &CellValue) -> Option<f64> {
+    lazy_static! {
+        static ref NUMERIC_TEXT: Regex = Regex::new(r"^[-(]?\s*[€$£]?\s*\d[\d.,\s\u{a0}]*\)?$").unwrap();
+    }
+    match cell {
+        CellValue::Number(value) => Some(*value),
+        CellValue::Text(text) if NUMERIC_TEXT.is_match(text) => {
+            // The sign and parentheses go before parsing, or "56)" would not read as decimals
+            let negative = text.starts_with('-') || text.starts_with('(');
+            let digits = text.trim_start_matches(['-', '(']).trim_end_matches(')').trim();
+            let value = locale_formats::parse_localized_number(digits.trim_start_matches(['€', '$', '£']))?;
+            Some(if negative { -value } else { value })
+        }
+        _ => None,
+    }
+}
+
+fn period_label(cell: &CellValue) -> Option<String> {
+    lazy_static! {
+        static ref YEAR: Regex = Regex::new(r"\b(19|20)\d{2}\b").unwrap();
+    }
+    match cell {
+        CellValue::Number(value) if value.fract() == 0.0 && (1990.0..=2100.0).contains(value) => Some(format!("{:.0}", value)),
+        CellValue::Text(text) if YEAR.is_match(text) => Some(text.clone()),
+        CellValue::Date(date) => Some(date.clone()),
+        _ => None,
+    }
+}
+
+fn period_year(period: &str) -> Option<i32> {
+    lazy_static! {
+        static ref YEAR: Regex = Regex::new(r"(19|20)\d{2}").unwrap();
+    }
+    YEAR.find(period).and_then(|m| m.as_str().parse().ok())
+}
+
+fn column_name(index: usize) -> String {
+    let mut name = String::new();
+    let mut index = index + 1;
+    while index > 0 {
+        let remainder = (index - 1) % 26;
+        name.insert(0, (b'A' + remainder as u8) as char);
+        index = (index - 1) / 26;
+    }
+    format!("Column {}", name)
+}
+
+/// Pick the label column, period header and line items out of a sheet
+pub fn parse_statement(sheet: &Sheet) -> StatementSheet {
+    let mut header: Option<Vec<(usize, String)>> = None;
+    let mut rows: Vec<(String, Vec<(usize, f64)>)> = Vec::new();
+
+    for row in &sheet.rows {
+        let label_at = row.iter().position(|c| matches!(c, CellValue::Text(_)) && numeric(c).is_none());
+        let is_line_item = matches!(label_at.map(|i| &row[i]), Some(CellValue::Text(t)) if metric_for(t).is_some());
+        // The header is the first row, before any line item, that names periods
+        if header.is_none() && rows.is_empty() && !is_line_item {
+            let periods: Vec<(usize, String)> = row
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| Some(*i) != label_at)
+                .filter_map(|(i, cell)| period_label(cell).map(|p| (i, p)))
+                .collect();
+            if !periods.is_empty() {
+                header = Some(periods);
+                continue;
+            }
+        }
+        let Some(label_at) = label_at else { continue };
+        let CellValue::Text(label) = &row[label_at] else { continue };
+        let numbers: Vec<(usize, f64)> = row
+            .iter()
+            .enumerate()
+            .skip(label_at + 1)
+            .filter_map(|(i, cell)| numeric(cell).map(|n| (i, n)))
+            .collect();
+        if !numbers.is_empty() {
+            rows.push((label.clone(), numbers));
+        }
+    }
+
+    // Without a header every column holding figures is its own period
+    let periods = header.unwrap_or_else(|| {
+        let mut columns: Vec<usize> = rows.iter().flat_map(|(_, n)| n.iter().map(|(i, _)| *i)).collect();
+        columns.sort_unstable();
+        columns.dedup();
+        columns.into_iter().map(|i| (i, column_name(i))).collect()
+    });
+
+    let line_items: Vec<LineItem> = rows
+        .into_iter()
+        .map(|(label, numbers)| LineItem {
+            metric: metric_for(&label),
+            values: periods
+                .iter()
+                .map(|(column, _)| numbers.iter().find(|(i, _)| i == column).map(|(_, n)| *n))
+                .collect(),
+            label,
+        })
+        .collect();
+
+    let haystack = format!(
+        "{} {}",
+        sheet.name.to_lowercase(),
+        line_items.iter().map(|l| normalize_label(&l.label)).collect::<Vec<_>>().join(" | ")
+    );
+    let kind = LAYOUT_KEYWORDS
+        .iter()
+        .map(|(kind, keywords)| (*kind, keywords.iter().filter(|k| haystack.contains(*k)).count()))
+        .filter(|(_, score)| *score >= 2)
+        .max_by_key(|(_, score)| *score)
+        .map(|(kind, _)| kind);
+
+    StatementSheet {
+        name: sheet.name.clone(),
+        kind,
+        periods: periods.into_iter().map(|(_, p)| p).collect(),
+        line_items,
+    }
+}
+
+fn round(value: f64, decimals: i32) -> f64 {
+    let factor = 10f64.powi(decimals);
+    (value * factor).round() / factor
+}
+
+fn ratio(numerator: Option<f64>, denominator: Option<f64>) -> Option<f64> {
+    match (numerator, denominator) {
+        (Some(n), Some(d)) if d != 0.0 => Some(round(n / d, 2)),
+        _ => None,
+    }
+}
+
+fn margin(numerator: Option<f64>, revenue: Option<f64>) -> Option<f64> {
+    ratio(numerator.map(|n| n * 100.0), revenue)
+}
+
+/// Covenants in a credit agreement, e.g. "the Leverage Ratio shall not exceed 3.50:1"
+pub fn extract_covenants(text: &str) -> Vec<Covenant> {
+    const MAXIMUM: &str = r"(?:shall\s+)?not\s+(?:to\s+)?exceed|(?:shall\s+)?not\s+be\s+(?:greater|more|higher)\s+than|no\s+(?:greater|more|higher)\s+than|(?:less|lower)\s+than\s+or\s+equal\s+to|at\s+most|maximum\s+of";
+    const MINIMUM: &str = r"(?:shall\s+)?not\s+be\s+(?:less|lower)\s+than|no\s+(?:less|lower)\s+than|(?:greater|more|higher)\s+than\s+or\s+equal\s+to|at\s+least|minimum\s+of";
+    const METRICS: &[(CovenantMetric, &str)] = &[
+        (CovenantMetric::Leverage, r"(?:net\s+)?leverage\s+ratio|(?:total\s+)?(?:net\s+)?(?:debt|indebtedness)\s+to\s+(?:consolidated\s+)?ebitda"),
+        (CovenantMetric::InterestCoverage, r"interest\s+cover(?:age)?(?:\s+ratio)?|ebitda?\s+to\s+(?:net\s+)?(?:interest|finance)\s+(?:expense|charges|costs)"),
+        (CovenantMetric::CurrentRatio, r"current\s+ratio"),
+        (CovenantMetric::DebtToEquity, r"(?:debt|gearing)\s+to\s+equity(?:\s+ratio)?|gearing\s+ratio"),
+        (CovenantMetric::MinimumEquity, r"(?:tangible\s+)?net\s+worth|(?:total\s+)?shareholders'?\s+equity|total\s+equity"),
+    ];
+
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let mut covenants = Vec::new();
+    for (metric, pattern) in METRICS {
+        let regex = Regex::new(&format!(
+            r"(?i)(?:{})[^;]{{0,150}}?(?:(?P<max>{})|(?P<min>{}))\s+(?:[€$£]\s*|(?:EUR|USD|GBP)\s+)?(?P<value>\d[\d,]*(?:\.\d+)?)(?:\s*(?P<scale>million|billion|m\b|bn\b))?",
+            pattern, MAXIMUM, MINIMUM
+        ))
+        .unwrap();
+        for captures in regex.captures_iter(&text) {
+            let Ok(mut threshold) = captures["value"].replace(',', "").parse::<f64>() else { continue };
+            threshold *= match captures.name("scale").map(|s| s.as_str().to_lowercase()) {
+                Some(scale) if scale.starts_with('b') => 1_000_000_000.0,
+                Some(_) => 1_000_000.0,
+                None => 1.0,
+            };
+            covenants.push(Covenant {
+                metric: *metric,
+                limit: if captures.name("max").is_some() { CovenantLimit::Maximum } else { CovenantLimit::Minimum },
+                threshold,
+                excerpt: captures[0].to_string(),
+            });
+        }
+    }
+    covenants
+}
+
+/// Ratios, covenant tests and anomalies over the parsed statements
+pub fn analyze_statements(file: &str, statements: Vec<StatementSheet>, covenants: &[Covenant]) -> FinancialAnalysis {
+    // First value found per metric and period across all recognised sheets; sheets headed
+    // "FY 2023" and "2023" describe the same period, which keeps the first label seen
+    let mut periods: Vec<String> = Vec::new();
+    let mut figures: BTreeMap<(String, FinancialMetric), f64> = BTreeMap::new();
+    for statement in statements.iter().filter(|s| s.kind.is_some()) {
+        for (index, label) in statement.periods.iter().enumerate() {
+            let year = period_year(label);
+            let period = match periods.iter().find(|p| *p == label || (year.is_some() && period_year(p) == year)) {
+                Some(existing) => existing.clone(),
+                None => {
+                    periods.push(label.clone());
+                    label.clone()
+                }
+            };
+            for item in &statement.line_items {
+                if let (Some(metric), Some(Some(value))) = (item.metric, item.values.get(index)) {
+                    figures.entry((period.clone(), metric)).or_insert(*value);
+                }
+            }
+        }
+    }
+    // Compare periods oldest first when they carry years
+    if periods.iter().all(|p| period_year(p).is_some()) {
+        periods.sort_by_key(|p| period_year(p));
+    }
+    let get = |period: &str, metric: FinancialMetric| figures.get(&(period.to_string(), metric)).copied();
+
+    let mut anomalies = Vec::new();
+    let mut ratios = Vec::new();
+    let mut previous_revenue: Option<(String, f64)> = None;
+    for period in &periods {
+        let revenue = get(period, FinancialMetric::Revenue);
+        let gross_profit = get(period, FinancialMetric::GrossProfit).or_else(|| {
+            revenue.zip(get(period, FinancialMetric::CostOfSales)).map(|(r, c)| r - c.abs())
+        });
+        let operating_income = get(period, FinancialMetric::OperatingIncome);
+        let interest = get(period, FinancialMetric::InterestExpense).map(f64::abs);
+        let net_income = get(period, FinancialMetric::NetIncome);
+        let equity = get(period, FinancialMetric::TotalEquity);
+        let debt = get(period, FinancialMetric::TotalDebt);
+        let ebitda = get(period, FinancialMetric::Ebitda);
+
+        ratios.push(PeriodRatios {
+            period: period.clone(),
+            current_ratio: ratio(get(period, FinancialMetric::CurrentAssets), get(period, FinancialMetric::CurrentLiabilities)),
+            debt_to_equity: ratio(debt.or_else(|| get(period, FinancialMetric::TotalLiabilities)), equity),
+            leverage: ratio(debt, ebitda),
+            interest_coverage: ratio(ebitda.or(operating_income), interest),
+            gross_margin: margin(gross_profit, revenue),
+            operating_margin: margin(operating_income, revenue),
+            net_margin: margin(net_income, revenue),
+            return_on_equity: margin(net_income, equity.filter(|e| *e > 0.0)),
+        });
+
+        let mut flag = |kind: AnomalyKind, description: String, severity: RiskLevel| {
+            anomalies.push(FinancialAnomaly { kind, period: Some(period.clone()), description, severity });
+        };
+        if let Some(equity) = equity.filter(|e| *e < 0.0) {
+            flag(
+                AnomalyKind::NegativeEquity,
+                format!("Equity is negative ({:.2}); liabilities exceed assets", equity),
+                RiskLevel::High,
+            );
+        }
+        if let (Some(assets), Some(liabilities), Some(equity)) =
+            (get(period, FinancialMetric::TotalAssets), get(period, FinancialMetric::TotalLiabilities), equity)
+        {
+            if (assets - liabilities - equity).abs() > assets.abs() * 0.01 {
+                flag(
+                    AnomalyKind::BalanceMismatch,
+                    format!("Total assets ({:.2}) differ from liabilities plus equity ({:.2})", assets, liabilities + equity),
+                    RiskLevel::Medium,
+                );
+            }
+        }
+        if let Some(loss) = net_income.filter(|n| *n < 0.0) {
+            flag(AnomalyKind::NetLoss, format!("Net loss of {:.2}", loss.abs()), RiskLevel::Medium);
+        }
+        if let Some(cash_flow) = get(period, FinancialMetric::OperatingCashFlow).filter(|c| *c < 0.0) {
+            flag(
+                AnomalyKind::NegativeOperatingCashFlow,
+                format!("Operating activities consumed {:.2} of cash", cash_flow.abs()),
+                RiskLevel::Medium,
+            );
+        }
+        if let Some(current) = ratios.last().and_then(|r| r.current_ratio).filter(|r| *r < 1.0) {
+            flag(
+                AnomalyKind::LowLiquidity,
+                format!("Current ratio of {:.2}; current liabilities exceed current assets", current),
+                RiskLevel::Low,
+            );
+        }
+        if let (Some((previous_period, previous)), Some(revenue)) = (&previous_revenue, revenue) {
+            if *previous > 0.0 && revenue < previous * 0.8 {
+                flag(
+                    AnomalyKind::RevenueDecline,
+                    format!(
+                        "Revenue fell {:.1}% from {} to {}",
+                        (1.0 - revenue / previous) * 100.0,
+                        previous_period,
+                        period
+                    ),
+                    RiskLevel::Medium,
+                );
+            }
+        }
+        if let Some(revenue) = revenue {
+            previous_revenue = Some((period.clone(), revenue));
+        }
+    }
+
+    let mut covenant_tests = Vec::new();
+    for covenant in covenants {
+        let mut tested = false;
+        for (period, period_ratios) in periods.iter().zip(&ratios) {
+            let actual = match covenant.metric {
+                CovenantMetric::Leverage => period_ratios.leverage,
+                CovenantMetric::InterestCoverage => period_ratios.interest_coverage,
+                CovenantMetric::CurrentRatio => period_ratios.current_ratio,
+                CovenantMetric::DebtToEquity => period_ratios.debt_to_equity,
+                CovenantMetric::MinimumEquity => get(period, FinancialMetric::TotalEquity),
+            };
+            let Some(actual) = actual else { continue };
+            tested = true;
+            let breached = match covenant.limit {
+                CovenantLimit::Maximum => actual > covenant.threshold,
+                CovenantLimit::Minimum => actual < covenant.threshold,
+            };
+            if breached {
+                anomalies.push(FinancialAnomaly {
+                    kind: AnomalyKind::CovenantBreach,
+                    period: Some(period.clone()),
+                    description: format!(
+                        "{} of {:.2} breaches the {} of {:.2} in the credit agreement (\"{}\")",
+                        covenant.metric.label(),
+                        actual,
+                        if covenant.limit == CovenantLimit::Maximum { "maximum" } else { "minimum" },
+                        covenant.threshold,
+                        covenant.excerpt
+                    ),
+                    severity: RiskLevel::High,
+                });
+            }
+            covenant_tests.push(CovenantTest {
+                covenant: covenant.clone(),
+                period: Some(period.clone()),
+                actual: Some(actual),
+                breached: Some(breached),
+            });
+        }
+        if !tested {
+            anomalies.push(FinancialAnomaly {
+                kind: AnomalyKind::CovenantUntested,
+                period: None,
+                description: format!("The statements do not contain the figures to test \"{}\"", covenant.excerpt),
+                severity: RiskLevel::Low,
+            });
+            covenant_tests.push(CovenantTest {
+                covenant: covenant.clone(),
+                period: None,
+                actual: None,
+                breached: None,
+            });
+        }
+    }
+
+    FinancialAnalysis {
+        file: file.to_string(),
+        statements,
+        ratios,
+        covenant_tests,
+        anomalies,
+    }
+}
+
+/// Analyze a spreadsheet of financial statements, testing covenants from a credit agreement
+#[tauri::command]
+pub async fn analyze_financial_statements(
+    file_path: String,
+    credit_agreement_path: Option<String>,
+    analyzer: tauri::State<'_, AnalyzerStorage>,
+) -> Result<FinancialAnalysis, String> {
+    let sheets = read_workbook(Path::new(&file_path)).map_err(|e| e.to_string())?;
+    let statements = sheets.iter().map(parse_statement).collect();
+    let covenants = match credit_agreement_path {
+        Some(path) => extract_covenants(
+            &analyzer
+                .extract_text(Path::new(&path))
+                .await
+                .map_err(|e| e.to_string())?,
+        ),
+        None => Vec::new(),
+    };
+    Ok(analyze_statements(&file_path, statements, &covenants))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(value: &str) -> CellValue {
+        CellValue::Text(value.to_string())
+    }
+
+    fn sheet(name: &str, rows: Vec<Vec<CellValue>>) -> Sheet {
+        Sheet { name: name.to_string(), rows }
+    }
+
+    #[test]
+    fn test_parses_statement_layout_and_computes_ratios() {
+        let balance = sheet(
+            "Balance sheet",
+            vec![
+                vec![text("EUR thousands"), CellValue::Number(2024.0), text("FY 2023")],
+                vec![text("Current assets"), CellValue::Empty, CellValue::Empty],
+                vec![text("Total current assets"), CellValue::Number(800.0), CellValue::Number(900.0)],
+                vec![text("Total assets"), CellValue::Number(2000.0), CellValue::Number(2100.0)],
+                vec![text("Total current liabilities"), CellValue::Number(1000.0), CellValue::Number(600.0)],
+                vec![text("Borrowings"), CellValue::Number(1500.0), CellValue::Number(1000.0)],
+                vec![text("Total liabilities"), CellValue::Number(2100.0), CellValue::Number(1600.0)],
+                vec![text("Total equity"), text("(100)"), CellValue::Number(500.0)],
+            ],
+        );
+        let income = sheet(
+            "P&L",
+            vec![
+                vec![CellValue::Empty, text("2024"), text("2023")],
+                vec![text("Revenue"), text("1.200,0"), CellValue::Number(2000.0)],
+                vec![text("Cost of sales"), CellValue::Number(-700.0), CellValue::Number(-1000.0)],
+                vec![text("EBITDA"), CellValue::Number(300.0), CellValue::Number(500.0)],
+                vec![text("Finance costs"), CellValue::Number(-100.0), CellValue::Number(-50.0)],
+                vec![text("Net loss"), CellValue::Number(-150.0), CellValue::Number(120.0)],
+            ],
+        );
+
+        let statements: Vec<StatementSheet> = [balance, income].iter().map(parse_statement).collect();
+        assert_eq!(statements[0].kind, Some(StatementKind::BalanceSheet));
+        assert_eq!(statements[0].periods, vec!["2024".to_string(), "FY 2023".to_string()]);
+        assert_eq!(statements[0].line_items.len(), 6); // the "Current assets" heading has no figures
+        assert_eq!(statements[1].kind, Some(StatementKind::IncomeStatement));
+        assert_eq!(statements[1].line_items[0].values, vec![Some(1200.0), Some(2000.0)]);
+
+        let analysis = analyze_statements("accounts.xlsx", statements, &[]);
+        // Periods are compared oldest first
+        assert_eq!(analysis.ratios.iter().map(|r| r.period.as_str()).collect::<Vec<_>>(), vec!["FY 2023", "2024"]);
+        let latest = &analysis.ratios[1];
+        assert_eq!(latest.current_ratio, Some(0.8));
+        assert_eq!(latest.leverage, Some(5.0));
+        assert_eq!(latest.interest_coverage, Some(3.0));
+        assert_eq!(latest.gross_margin, Some(41.67));
+
+        let kinds: Vec<AnomalyKind> = analysis.anomalies.iter().map(|a| a.kind).collect();
+        assert!(kinds.contains(&AnomalyKind::NegativeEquity));
+        assert!(kinds.contains(&AnomalyKind::NetLoss));
+        assert!(kinds.contains(&AnomalyKind::RevenueDecline));
+        assert!(kinds.contains(&AnomalyKind::LowLiquidity));
+        assert!(!kinds.contains(&AnomalyKind::BalanceMismatch));
+    }
+
+    #[test]
+    fn test_numeric_text_keeps_decimals_of_negative_amounts() {
+        let text = |t: &str| numeric(&CellValue::Text(t.to_string()));
+        assert_eq!(text("(1,234.56)"), Some(-1234.56));
+        assert_eq!(text("(12.5)"), Some(-12.5));
+        assert_eq!(text("-1.234,5"), Some(-1234.5));
+        assert_eq!(text("( € 1.200 )"), Some(-1200.0));
+        assert_eq!(text("300"), Some(300.0));
+        assert_eq!(text("n/a"), None);
+    }
+
+    #[test]
+    fn test_extracts_covenants_and_flags_breaches() {
+        let agreement = "23.2 Financial condition. The Borrower shall ensure that: (a) the Leverage Ratio \
+            in respect of any Relevant Period shall not exceed 3.50:1; (b) the Interest Cover in respect of any \
+            Relevant Period shall not be less than 4.00:1; and (c) Tangible Net Worth is at all times at least EUR 1 million; \
+            (d) the Current Ratio shall be no less than 1.20 to 1.";
+        let covenants = extract_covenants(agreement);
+        assert_eq!(covenants.len(), 4);
+        let leverage = covenants.iter().find(|c| c.metric == CovenantMetric::Leverage).unwrap();
+        assert_eq!((leverage.limit, leverage.threshold), (CovenantLimit::Maximum, 3.5));
+        let net_worth = covenants.iter().find(|c| c.metric == CovenantMetric::MinimumEquity).unwrap();
+        assert_eq!((net_worth.limit, net_worth.threshold), (CovenantLimit::Minimum, 1_000_000.0));
+
+        let statement = parse_statement(&sheet(
+            "Income statement",
+            vec![
+                vec![text("Year"), CellValue::Number(2024.0)],
+                vec![text("Revenue"), CellValue::Number(5000.0)],
+                vec![text("EBITDA"), CellValue::Number(1000.0)],
+                vec![text("Interest expense"), CellValue::Number(200.0)],
+                vec![text("Total debt"), CellValue::Number(4000.0)],
+            ],
+        ));
+        let analysis = analyze_statements("accounts.xlsx", vec![statement], &covenants);
+
+        let test_for = |metric: CovenantMetric| analysis.covenant_tests.iter().find(|t| t.covenant.metric == metric).unwrap();
+        assert_eq!(test_for(CovenantMetric::Leverage).breached, Some(true)); // 4.0x against 3.5x
+        assert_eq!(test_for(CovenantMetric::InterestCoverage).breached, Some(false)); // 5.0x against 4.0x
+        assert_eq!(test_for(CovenantMetric::CurrentRatio).breached, None);
+        assert_eq!(
+            analysis.anomalies.iter().filter(|a| a.kind == AnomalyKind::CovenantBreach).count(),
+            1
+        );
+        assert_eq!(
+            analysis.anomalies.iter().filter(|a| a.kind == AnomalyKind::CovenantUntested).count(),
+            2
+        );
+    }
+}
@@ -0,0 +1,312 @@
+use anyhow::{anyhow, Result};
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+
+use crate::document_analyzer::DocumentAnalysis;
+use crate::output_language::{protect_quotes, restore_quotes};
+
+/// Terminology Glossary for BEAR AI
+/// Firms keep preferred terms ("Client", not "Customer"). Generated drafts, summaries and
+/// analysis text are checked against the glossary: entries marked `enforce` are replaced in
+/// place, keeping capitalisation and plural "s"; the rest are only reported. Quoted passages are
+/// left alone since they reproduce someone else's wording.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GlossaryEntry {
+    pub id: String,
+    pub preferred: String,
+    pub avoid: Vec<String>,
+    pub enforce: bool, // replace automatically; otherwise report only
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GlossaryViolation {
+    pub found: String,
+    pub preferred: String,
+    pub occurrences: usize,
+    pub replaced: bool,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GlossaryReview {
+    pub text: String,
+    pub violations: Vec<GlossaryViolation>,
+}
+
+/// Give the replacement the casing of the word it replaces: "CUSTOMER" -> "CLIENT",
+/// "Customer" -> "Client", "customer" -> "client"
+fn match_case(found: &str, preferred: &str) -> String {
+    let letters: Vec<char> = found.chars().filter(|c| c.is_alphabetic()).collect();
+    if letters.len() > 1 && letters.iter().all(|c| c.is_uppercase()) {
+        preferred.to_uppercase()
+    } else if letters.first().is_some_and(|c| c.is_uppercase()) {
+        let mut chars = preferred.chars();
+        chars
+            .next()
+            .map(|first| first.to_uppercase().chain(chars).collect())
+            .unwrap_or_default()
+    } else {
+        preferred.to_lowercase()
+    }
+}
+
+/// Check text against the glossary; with `apply`, enforced entries are replaced
+pub fn review_text(entries: &[GlossaryEntry], text: &str, apply: bool) -> GlossaryReview {
+    let (mut working, quotes) = protect_quotes(text);
+
+    // Longer phrases first so "customer account" wins over "customer"
+    let mut terms: Vec<(&GlossaryEntry, &str)> = entries
+        .iter()
+        .flat_map(|entry| entry.avoid.iter().map(move |avoid| (entry, avoid.trim())))
+        .filter(|(entry, avoid)| !avoid.is_empty() && !avoid.eq_ignore_ascii_case(&entry.preferred))
+        .collect();
+    terms.sort_by_key(|(_, avoid)| std::cmp::Reverse(avoid.len()));
+
+    let mut violations = Vec::new();
+    for (entry, avoid) in terms {
+        let re = Regex::new(&format!(r"(?i)\b{}(s?)\b", regex::escape(avoid))).unwrap();
+        let occurrences = re.find_iter(&working).count();
+        if occurrences == 0 {
+            continue;
+        }
+        let replaced = apply && entry.enforce;
+        if replaced {
+            working = re
+                .replace_all(&working, |caps: &Captures| {
+                    format!("{}{}", match_case(&caps[0], &entry.preferred), &caps[1])
+                })
+                .into_owned();
+        }
+        violations.push(GlossaryViolation {
+            found: avoid.to_string(),
+            preferred: entry.preferred.clone(),
+            occurrences,
+            replaced,
+            note: entry.note.clone(),
+        });
+    }
+
+    GlossaryReview {
+        text: restore_quotes(&working, &quotes).unwrap_or(working),
+        violations,
+    }
+}
+
+pub struct Glossary {
+    path: PathBuf,
+    entries: Mutex<Vec<GlossaryEntry>>,
+}
+
+impl Glossary {
+    pub fn new(app_data_dir: &Path) -> Result<Self> {
+        let path = app_data_dir.join("glossary.json");
+        let entries = if path.exists() {
+            serde_json::from_str(&fs::read_to_string(&path)?)?
+        } else {
+            Vec::new()
+        };
+        Ok(Glossary {
+            path,
+            entries: Mutex::new(entries),
+        })
+    }
+
+    fn persist(&self, entries: &[GlossaryEntry]) -> Result<()> {
+        fs::write(&self.path, serde_json::to_string_pretty(entries)?)?;
+        Ok(())
+    }
+
+    pub fn list_entries(&self) -> Vec<GlossaryEntry> {
+        self.entries.lock().unwrap().clone()
+    }
+
+    /// Add an entry, or update the one with the same preferred term
+    pub fn save_entry(&self, preferred: &str, avoid: Vec<String>, enforce: bool, note: Option<String>) -> Result<GlossaryEntry> {
+        let preferred = preferred.trim();
+        if preferred.is_empty() {
+            return Err(anyhow!("Preferred term is required"));
+        }
+        let avoid: Vec<String> = avoid
+            .into_iter()
+            .map(|a| a.trim().to_string())
+            .filter(|a| !a.is_empty())
+            .collect();
+        if avoid.is_empty() {
+            return Err(anyhow!("List at least one term to avoid"));
+        }
+
+        let mut entries = self.entries.lock().unwrap();
+        let entry = match entries.iter_mut().find(|e| e.preferred.eq_ignore_ascii_case(preferred)) {
+            Some(existing) => {
+                existing.preferred = preferred.to_string();
+                existing.avoid = avoid;
+                existing.enforce = enforce;
+                existing.note = note;
+                existing.clone()
+            }
+            None => {
+                let entry = GlossaryEntry {
+                    id: uuid::Uuid::new_v4().to_string(),
+                    preferred: preferred.to_string(),
+                    avoid,
+                    enforce,
+                    note,
+                };
+                entries.push(entry.clone());
+                entry
+            }
+        };
+        self.persist(&entries)?;
+        Ok(entry)
+    }
+
+    pub fn remove_entry(&self, id: &str) -> Result<()> {
+        let mut entries = self.entries.lock().unwrap();
+        let before = entries.len();
+        entries.retain(|e| e.id != id);
+        if entries.len() == before {
+            return Err(anyhow!("Glossary entry {} not found", id));
+        }
+        self.persist(&entries)
+    }
+
+    pub fn review(&self, text: &str, apply: bool) -> GlossaryReview {
+        review_text(&self.entries.lock().unwrap(), text, apply)
+    }
+}
+
+lazy_static! {
+    static ref GLOBAL_GLOSSARY: RwLock<Option<Arc<Glossary>>> = RwLock::new(None);
+}
+
+/// Initialize the global glossary
+pub fn initialize_glossary(app_data_dir: &Path) -> Result<Arc<Glossary>> {
+    let glossary = Arc::new(Glossary::new(app_data_dir)?);
+    *GLOBAL_GLOSSARY.write().unwrap() = Some(glossary.clone());
+    Ok(glossary)
+}
+
+/// Get the global glossary
+pub fn get_glossary() -> Option<Arc<Glossary>> {
+    GLOBAL_GLOSSARY.read().unwrap().clone()
+}
+
+/// Apply enforced glossary terms to generated text; unchanged when no glossary is set up
+pub fn enforce_glossary(text: &str) -> String {
+    match get_glossary() {
+        Some(glossary) => glossary.review(text, true).text,
+        None => text.to_string(),
+    }
+}
+
+/// Apply enforced glossary terms to the generated text of an analysis
+pub fn enforce_in_analysis(analysis: &mut DocumentAnalysis) {
+    if get_glossary().is_none() {
+        return;
+    }
+    let enforce = |text: &mut String| *text = enforce_glossary(text);
+    if let Some(summary) = analysis.summary.as_mut() {
+        enforce(summary);
+    }
+    for risk in analysis.risks.iter_mut() {
+        enforce(&mut risk.description);
+        enforce(&mut risk.impact);
+        risk.mitigation_strategies.iter_mut().for_each(enforce);
+    }
+    for flag in analysis.compliance_flags.iter_mut() {
+        enforce(&mut flag.requirement);
+        enforce(&mut flag.recommendation);
+    }
+}
+
+pub type GlossaryStorage = Arc<Glossary>;
+
+#[tauri::command]
+pub async fn glossary_list_entries(glossary: tauri::State<'_, GlossaryStorage>) -> Result<Vec<GlossaryEntry>, String> {
+    Ok(glossary.list_entries())
+}
+
+#[tauri::command]
+pub async fn glossary_save_entry(
+    preferred: String,
+    avoid: Vec<String>,
+    enforce: bool,
+    note: Option<String>,
+    glossary: tauri::State<'_, GlossaryStorage>,
+) -> Result<GlossaryEntry, String> {
+    glossary
+        .save_entry(&preferred, avoid, enforce, note)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn glossary_remove_entry(id: String, glossary: tauri::State<'_, GlossaryStorage>) -> Result<(), String> {
+    glossary.remove_entry(&id).map_err(|e| e.to_string())
+}
+
+/// Review a draft; with `apply` (the default) enforced terms are replaced in the returned text
+#[tauri::command]
+pub async fn glossary_review_text(
+    text: String,
+    apply: Option<bool>,
+    glossary: tauri::State<'_, GlossaryStorage>,
+) -> Result<GlossaryReview, String> {
+    Ok(glossary.review(&text, apply.unwrap_or(true)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(preferred: &str, avoid: &[&str], enforce: bool) -> GlossaryEntry {
+        GlossaryEntry {
+            id: preferred.to_lowercase(),
+            preferred: preferred.to_string(),
+            avoid: avoid.iter().map(|a| a.to_string()).collect(),
+            enforce,
+            note: None,
+        }
+    }
+
+    #[test]
+    fn test_enforced_terms_keep_case_and_plural() {
+        let entries = vec![entry("Client", &["Customer"], true)];
+        let review = review_text(
+            &entries,
+            r#"The Customer pays. CUSTOMERS are invoiced monthly; each customer may object. Clause 4 reads "the Customer indemnifies"."#,
+            true,
+        );
+
+        assert_eq!(
+            review.text,
+            r#"The Client pays. CLIENTS are invoiced monthly; each client may object. Clause 4 reads "the Customer indemnifies"."#
+        );
+        assert_eq!(review.violations.len(), 1);
+        assert_eq!(review.violations[0].occurrences, 3);
+        assert!(review.violations[0].replaced);
+    }
+
+    #[test]
+    fn test_report_only_entries_are_not_replaced() {
+        let entries = vec![
+            entry("Client", &["Customer"], true),
+            entry("Agreement", &["Contract"], false),
+        ];
+        let text = "This Contract binds the Customer.";
+
+        let review = review_text(&entries, text, true);
+        assert_eq!(review.text, "This Contract binds the Client.");
+        let contract = review.violations.iter().find(|v| v.found == "Contract").unwrap();
+        assert!(!contract.replaced);
+
+        // Without apply nothing changes but every violation is still reported
+        let dry_run = review_text(&entries, text, false);
+        assert_eq!(dry_run.text, text);
+        assert_eq!(dry_run.violations.len(), 2);
+    }
+}
@@ -0,0 +1,264 @@
+use anyhow::Result;
+use chrono::Utc;
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::future::Future;
+use uuid::Uuid;
+
+use crate::nemotron_rag::{LegalDocument, RAGChunk};
+
+/// Grounded Answer Generation for BEAR AI
+/// Answers a question from retrieved passages with the model citing them inline as [1], [2, 3].
+/// Every marker is mapped back to the chunk and document it names, with a pin cite (page or
+/// section) so the attorney can go straight to the supporting text. Markers naming no passage
+/// are reported rather than dropped.
+// Passages shown to the model
+pub const MAX_SOURCES: usize = 8;
+// Passages are cut to this many characters in the prompt; the answer keeps a shorter excerpt
+const PROMPT_EXCERPT_CHARS: usize = 1500;
+const SOURCE_EXCERPT_CHARS: usize = 300;
+// Longest section heading used as a pin cite
+const MAX_HEADING_CHARS: usize = 80;
+const NO_SOURCES_ANSWER: &str = "No indexed material addresses the question.";
+
+lazy_static! {
+    // [1], [2, 3] or [4; 5]
+    static ref MARKER: Regex = Regex::new(r"\[(\d+(?:\s*[,;]\s*\d+)*)\]").unwrap();
+}
+
+/// A passage the model was given, numbered as it saw it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnswerSource {
+    pub marker: usize,
+    pub chunk_id: String,
+    pub document_id: String,
+    pub document_title: Option<String>,
+    pub pin_cite: String, // page and/or section within the document, else the chunk position
+    pub excerpt: String,
+    pub cited: bool,
+}
+
+/// One marker in the answer; `start..end` is the byte range of its brackets
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InlineCitation {
+    pub marker: usize,
+    pub start: usize,
+    pub end: usize,
+    pub chunk_id: String,
+    pub document_id: String,
+    pub pin_cite: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroundedAnswer {
+    pub id: String,
+    pub query: String,
+    pub answer: String,
+    pub citations: Vec<InlineCitation>,
+    pub sources: Vec<AnswerSource>,
+    pub unsupported_markers: Vec<usize>, // cited numbers that match no passage
+    pub grounded: bool, // cites at least one passage and nothing that was not given
+    pub model: String,
+    pub created_at: String,
+}
+
+fn truncate(text: &str, chars: usize) -> String {
+    let text = text.trim();
+    if text.chars().count() <= chars {
+        return text.to_string();
+    }
+    format!("{}...", text.chars().take(chars).collect::<String>().trim_end())
+}
+
+/// The heading a chunk opens with, when it opens a section
+fn section_heading(content: &str) -> Option<String> {
+    let first = content.lines().map(str::trim).find(|l| !l.is_empty())?;
+    let first = first.split(". ").next().unwrap_or(first); // a heading run into its first sentence
+    let lower = first.to_lowercase();
+    let is_heading = ["section ", "article ", "clause ", "§"].iter().any(|p| lower.starts_with(p));
+    if !is_heading {
+        return None;
+    }
+    let heading = truncate(first, MAX_HEADING_CHARS);
+    Some(heading.trim_end_matches(['.', ':', ';']).to_string())
+}
+
+/// Where in its document a chunk sits: page and section when known, else its position
+fn pin_cite(chunk: &RAGChunk) -> String {
+    let page = chunk.metadata.get("page").filter(|p| !p.trim().is_empty()).map(|p| format!("p. {}", p.trim()));
+    match (page, section_heading(&chunk.content)) {
+        (Some(page), Some(section)) => format!("{}, {}", page, section),
+        (Some(page), None) => page,
+        (None, Some(section)) => section,
+        (None, None) => format!("passage {}", chunk.chunk_index + 1),
+    }
+}
+
+fn answer_prompt(query: &str, chunks: &[RAGChunk], sources: &[AnswerSource]) -> String {
+    let mut prompt = String::from(
+        "Answer the question using only the numbered sources below. After every statement, cite the sources \
+         that support it in square brackets, e.g. [1] or [2, 3]. Do not cite a number that is not listed. \
+         If the sources do not answer the question, say so and do not draw on anything else.\n\n",
+    );
+    for (chunk, source) in chunks.iter().zip(sources) {
+        let title = source.document_title.as_deref().unwrap_or(&source.document_id);
+        prompt.push_str(&format!(
+            "[{}] {} ({})\n{}\n\n",
+            source.marker,
+            title,
+            source.pin_cite,
+            truncate(&chunk.content, PROMPT_EXCERPT_CHARS)
+        ));
+    }
+    prompt.push_str(&format!("Question: {}\nAnswer:", query.trim()));
+    prompt
+}
+
+/// Map the answer's markers onto the sources, marking those cited; returns the citations and
+/// the numbers that match no source
+fn resolve_markers(answer: &str, sources: &mut [AnswerSource]) -> (Vec<InlineCitation>, Vec<usize>) {
+    let mut citations = Vec::new();
+    let mut unsupported = Vec::new();
+    for captures in MARKER.captures_iter(answer) {
+        let whole = captures.get(0).unwrap();
+        for number in captures[1].split([',', ';']) {
+            let Ok(marker) = number.trim().parse::<usize>() else {
+                continue;
+            };
+            match sources.iter_mut().find(|s| s.marker == marker) {
+                Some(source) => {
+                    source.cited = true;
+                    citations.push(InlineCitation {
+                        marker,
+                        start: whole.start(),
+                        end: whole.end(),
+                        chunk_id: source.chunk_id.clone(),
+                        document_id: source.document_id.clone(),
+                        pin_cite: source.pin_cite.clone(),
+                    });
+                }
+                None if !unsupported.contains(&marker) => unsupported.push(marker),
+                None => {}
+            }
+        }
+    }
+    (citations, unsupported)
+}
+
+/// Answer `query` from `chunks` (best first, already limited to what the user may see), with
+/// `generate` completing the prompt with `model`. `documents` supplies source titles.
+pub async fn generate_grounded_answer<G, F>(
+    query: &str,
+    model: &str,
+    chunks: Vec<RAGChunk>,
+    documents: &[LegalDocument],
+    generate: G,
+) -> Result<GroundedAnswer>
+where
+    G: FnOnce(String) -> F,
+    F: Future<Output = Result<String>>,
+{
+    let titles: HashMap<&str, &str> = documents.iter().map(|d| (d.id.as_str(), d.title.as_str())).collect();
+    let chunks: Vec<RAGChunk> = chunks.into_iter().take(MAX_SOURCES).collect();
+    let mut sources: Vec<AnswerSource> = chunks
+        .iter()
+        .enumerate()
+        .map(|(index, chunk)| AnswerSource {
+            marker: index + 1,
+            chunk_id: chunk.id.clone(),
+            document_id: chunk.document_id.clone(),
+            document_title: titles.get(chunk.document_id.as_str()).map(|t| t.to_string()),
+            pin_cite: pin_cite(chunk),
+            excerpt: truncate(&chunk.content, SOURCE_EXCERPT_CHARS),
+            cited: false,
+        })
+        .collect();
+
+    let answer = if sources.is_empty() {
+        NO_SOURCES_ANSWER.to_string()
+    } else {
+        generate(answer_prompt(query, &chunks, &sources)).await?.trim().to_string()
+    };
+    let (citations, unsupported_markers) = resolve_markers(&answer, &mut sources);
+
+    Ok(GroundedAnswer {
+        id: Uuid::new_v4().to_string(),
+        query: query.to_string(),
+        grounded: !citations.is_empty() && unsupported_markers.is_empty(),
+        answer,
+        citations,
+        sources,
+        unsupported_markers,
+        model: model.to_string(),
+        created_at: Utc::now().to_rfc3339(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(id: &str, document_id: &str, content: &str, chunk_index: usize) -> RAGChunk {
+        RAGChunk {
+            id: id.to_string(),
+            document_id: document_id.to_string(),
+            content: content.to_string(),
+            embedding: Vec::new(),
+            chunk_index,
+            tokens: 0,
+            overlap: 0,
+            legal_concepts: Vec::new(),
+            cited_authorities: Vec::new(),
+            confidence: 0.8,
+            temporal_relevance: 1.0,
+            created_at: Utc::now(),
+            metadata: HashMap::new(),
+            rerank: None,
+            authority: None,
+        }
+    }
+
+    #[test]
+    fn test_pin_cites_prefer_page_and_section() {
+        let mut paged = chunk("a", "lease", "Section 4.2 Termination.\nEither party may terminate.", 3);
+        paged.metadata.insert("page".to_string(), "7".to_string());
+        assert_eq!(pin_cite(&paged), "p. 7, Section 4.2 Termination");
+        assert_eq!(pin_cite(&chunk("b", "lease", "§ 12: Notices\nAll notices in writing.", 0)), "§ 12: Notices");
+        assert_eq!(pin_cite(&chunk("c", "lease", "Rent is due monthly.", 2)), "passage 3");
+    }
+
+    #[tokio::test]
+    async fn test_maps_inline_markers_to_chunks() {
+        let chunks = vec![
+            chunk("lease-chunk-0", "lease", "Section 2 Term. The lease runs for five years.", 0),
+            chunk("lease-chunk-4", "lease", "Rent is 1,000 per month.", 4),
+            chunk("memo-chunk-1", "memo", "The tenant paid late twice.", 1),
+        ];
+        let reply = "The lease runs five years [1] at 1,000 a month [2; 3]. It renews automatically [4].";
+        let answer = generate_grounded_answer("What are the lease terms?", "local-model", chunks, &[], |prompt| {
+            assert!(prompt.contains("[2] lease (passage 5)\nRent is 1,000 per month."));
+            async move { Ok(reply.to_string()) }
+        })
+        .await
+        .unwrap();
+
+        let cited: Vec<(usize, &str)> = answer.citations.iter().map(|c| (c.marker, c.chunk_id.as_str())).collect();
+        assert_eq!(cited, vec![(1, "lease-chunk-0"), (2, "lease-chunk-4"), (3, "memo-chunk-1")]);
+        assert_eq!(&answer.answer[answer.citations[1].start..answer.citations[1].end], "[2; 3]");
+        assert_eq!(answer.citations[0].pin_cite, "Section 2 Term");
+        assert_eq!(answer.unsupported_markers, vec![4]);
+        assert!(!answer.grounded);
+        assert!(answer.sources.iter().all(|s| s.cited));
+
+        // Nothing retrieved: no model call and nothing cited
+        let empty = generate_grounded_answer("Anything?", "local-model", Vec::new(), &[], |_| async {
+            panic!("no sources to answer from")
+        })
+        .await
+        .unwrap();
+        assert_eq!(empty.answer, NO_SOURCES_ANSWER);
+        assert!(empty.citations.is_empty() && !empty.grounded);
+    }
+}
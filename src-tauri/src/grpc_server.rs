@@ -0,0 +1,254 @@
+use anyhow::{anyhow, Context, Result};
+use serde::Serialize;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tonic::transport::{Certificate, Identity, Server, ServerTlsConfig};
+use tonic::{Request, Response, Status};
+
+use crate::document_analyzer::DocumentAnalyzer;
+use crate::jurisdiction::JurisdictionScope;
+use crate::nemotron_rag::{NemotronRAG, QueryContext};
+use crate::pii_detector::PIIDetector;
+use crate::retrieval_scope::RetrievalScope;
+use crate::SCOPED_OVERFETCH;
+
+pub mod pb {
+    tonic::include_proto!("bear_ai.v1");
+}
+
+use pb::bear_ai_engine_server::{BearAiEngine, BearAiEngineServer};
+
+/// gRPC server for BEAR AI
+/// Exposes document analysis, PII detection and RAG retrieval to other services in the
+/// firm's infrastructure. Clients must present a certificate signed by the configured CA.
+/// Retrieval answers with what the user the server runs as may read, and documents are only
+/// read by path from under the document roots the server was started with.
+#[derive(Debug, Clone)]
+pub struct GrpcServerConfig {
+    pub listen_addr: SocketAddr,
+    pub cert_path: PathBuf,
+    pub key_path: PathBuf,
+    pub client_ca_path: PathBuf,
+}
+
+// Analysis requests may carry whole documents
+const MAX_MESSAGE_BYTES: usize = 64 * 1024 * 1024;
+
+pub struct BearAiEngineService {
+    analyzer: Arc<DocumentAnalyzer>,
+    rag: Option<Arc<NemotronRAG>>,
+    scope: RetrievalScope,
+    document_roots: Vec<PathBuf>, // canonical; empty refuses every path
+}
+
+impl BearAiEngineService {
+    pub fn new(
+        analyzer: Arc<DocumentAnalyzer>,
+        rag: Option<Arc<NemotronRAG>>,
+        scope: RetrievalScope,
+        document_roots: &[PathBuf],
+    ) -> Result<Self> {
+        let document_roots = document_roots
+            .iter()
+            .map(|root| root.canonicalize().with_context(|| format!("Cannot open document root {}", root.display())))
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            analyzer,
+            rag,
+            scope,
+            document_roots,
+        })
+    }
+
+    /// A requested path, resolved through links and `..`, if it lies under a document root
+    fn readable_path(&self, path: &str) -> Result<PathBuf, Status> {
+        if self.document_roots.is_empty() {
+            return Err(Status::permission_denied(
+                "This server reads no paths; send the document content instead",
+            ));
+        }
+        let resolved = Path::new(path)
+            .canonicalize()
+            .map_err(|_| Status::not_found(format!("{} not found", path)))?;
+        if !self.document_roots.iter().any(|root| resolved.starts_with(root)) {
+            return Err(Status::permission_denied(format!("{} is outside the server's document roots", path)));
+        }
+        Ok(resolved)
+    }
+}
+
+// Serde name of an enum variant, so gRPC and JSON clients see the same values
+fn enum_name<T: Serialize>(value: &T) -> String {
+    serde_json::to_value(value)
+        .ok()
+        .and_then(|v| v.as_str().map(String::from))
+        .unwrap_or_default()
+}
+
+fn internal(e: anyhow::Error) -> Status {
+    log::error!("gRPC request failed: {}", e);
+    Status::internal(e.to_string())
+}
+
+#[tonic::async_trait]
+impl BearAiEngine for BearAiEngineService {
+    async fn analyze_document(
+        &self,
+        request: Request<pb::AnalyzeDocumentRequest>,
+    ) -> Result<Response<pb::AnalyzeDocumentResponse>, Status> {
+        let request = request.into_inner();
+
+        // Uploaded bytes are analyzed from a temporary file with the original extension
+        let mut upload = None;
+        let path = match request.source {
+            Some(pb::analyze_document_request::Source::Path(path)) => self.readable_path(&path)?,
+            Some(pb::analyze_document_request::Source::Content(content)) => {
+                let extension = Path::new(&request.filename)
+                    .extension()
+                    .and_then(|e| e.to_str())
+                    .ok_or_else(|| Status::invalid_argument("filename with an extension is required for uploaded content"))?;
+                let file = tempfile::Builder::new()
+                    .suffix(&format!(".{}", extension))
+                    .tempfile()
+                    .map_err(|e| Status::internal(e.to_string()))?;
+                std::fs::write(file.path(), content).map_err(|e| Status::internal(e.to_string()))?;
+                upload.insert(file).path().to_path_buf()
+            }
+            None => return Err(Status::invalid_argument("path or content is required")),
+        };
+
+        let analysis = self.analyzer.analyze_document(&path).await.map_err(internal)?;
+
+        Ok(Response::new(pb::AnalyzeDocumentResponse {
+            document_type: analysis
+                .metadata
+                .document_type
+                .as_ref()
+                .map(enum_name)
+                .unwrap_or_default(),
+            language: analysis.metadata.language.clone(),
+            summary: analysis.summary.clone(),
+            entities: analysis
+                .entities
+                .iter()
+                .map(|e| pb::Entity {
+                    entity_type: enum_name(&e.entity_type),
+                    text: e.text.clone(),
+                    confidence: e.confidence,
+                })
+                .collect(),
+            risks: analysis
+                .risks
+                .iter()
+                .map(|r| pb::Risk {
+                    risk_type: enum_name(&r.risk_type),
+                    severity: enum_name(&r.severity),
+                    description: r.description.clone(),
+                    likelihood: r.likelihood,
+                })
+                .collect(),
+            analysis_json: serde_json::to_string(&analysis).map_err(|e| Status::internal(e.to_string()))?,
+        }))
+    }
+
+    async fn detect_pii(
+        &self,
+        request: Request<pb::DetectPiiRequest>,
+    ) -> Result<Response<pb::DetectPiiResponse>, Status> {
+        let detection = PIIDetector::new(None).detect_pii(&request.into_inner().text);
+
+        Ok(Response::new(pb::DetectPiiResponse {
+            has_pii: detection.has_pii,
+            risk_level: enum_name(&detection.risk_level),
+            matches: detection
+                .matches
+                .iter()
+                .map(|m| pb::PiiMatch {
+                    pii_type: enum_name(&m.pii_type),
+                    text: m.text.clone(),
+                    start: m.start as u64,
+                    end: m.end as u64,
+                    confidence: m.confidence,
+                })
+                .collect(),
+            suggestions: detection.suggestions,
+        }))
+    }
+
+    async fn retrieve(
+        &self,
+        request: Request<pb::RetrieveRequest>,
+    ) -> Result<Response<pb::RetrieveResponse>, Status> {
+        let rag = self
+            .rag
+            .as_ref()
+            .ok_or_else(|| Status::unavailable("RAG system is not configured on this server"))?;
+        let request = request.into_inner();
+        if request.query.trim().is_empty() {
+            return Err(Status::invalid_argument("query is required"));
+        }
+
+        let max_results = request.max_results.map(|n| n as usize).unwrap_or(rag.config().max_results);
+        let result = rag
+            .retrieve(QueryContext {
+                query: request.query,
+                jurisdiction: request.jurisdiction,
+                document_types: None,
+                time_range: None,
+                precedential_only: None,
+                require_citations: None,
+                max_results: Some(max_results * SCOPED_OVERFETCH),
+                confidence_threshold: None,
+                court: None,
+                docket_number: None,
+                jurisdiction_scope: JurisdictionScope::Prefer,
+                latency_budget_ms: None,
+            })
+            .await
+            .map_err(internal)?;
+        let result = self.scope.filter_top(result, "retrieval", max_results);
+
+        Ok(Response::new(pb::RetrieveResponse {
+            chunks: result
+                .chunks
+                .iter()
+                .map(|c| pb::RetrievedChunk {
+                    chunk_id: c.id.clone(),
+                    document_id: c.document_id.clone(),
+                    chunk_index: c.chunk_index as u64,
+                    content: c.content.clone(),
+                    confidence: c.confidence,
+                })
+                .collect(),
+            confidence: result.confidence,
+        }))
+    }
+}
+
+fn tls_config(config: &GrpcServerConfig) -> Result<ServerTlsConfig> {
+    let read = |path: &Path| std::fs::read(path).with_context(|| format!("Cannot read {}", path.display()));
+    let identity = Identity::from_pem(read(&config.cert_path)?, read(&config.key_path)?);
+    let client_ca = Certificate::from_pem(read(&config.client_ca_path)?);
+
+    Ok(ServerTlsConfig::new().identity(identity).client_ca_root(client_ca))
+}
+
+/// Serve the engine API with mutual TLS until the process is stopped
+pub async fn serve(config: GrpcServerConfig, service: BearAiEngineService) -> Result<()> {
+    let tls = tls_config(&config)?;
+    log::info!("BEAR AI gRPC server listening on {} (mTLS)", config.listen_addr);
+
+    Server::builder()
+        .tls_config(tls)
+        .map_err(|e| anyhow!("Invalid TLS configuration: {}", e))?
+        .add_service(
+            BearAiEngineServer::new(service)
+                .max_decoding_message_size(MAX_MESSAGE_BYTES)
+                .max_encoding_message_size(MAX_MESSAGE_BYTES),
+        )
+        .serve(config.listen_addr)
+        .await?;
+
+    Ok(())
+}
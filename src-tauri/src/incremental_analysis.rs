@@ -0,0 +1,670 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use crate::document_analyzer::{
+    self, ContractClause, DocumentAnalysis, EntityType, LegalCitation, LegalEntity,
+};
+use crate::local_api::{authenticated_user, AnalyzerStorage, SessionStorage};
+use crate::reanalysis::AnalysisFingerprint;
+use crate::review_queue::ReviewQueueStorage;
+
+/// Incremental Document Analysis for BEAR AI
+/// Re-analyzing an edited document compares its text with the version analyzed last. Lines and
+/// sentences are diffed; entities, clauses and citations are extracted again only in the changed
+/// regions (with a sentence of context on either side) and carried over, shifted to their new
+/// positions, everywhere else. Document-wide findings (risks, compliance flags, key terms, the
+/// summary) are recomputed from the merged result. Every finding that appeared, disappeared or
+/// changed since the previous version is listed.
+// Above this share of changed text the whole document is analyzed again, classification included
+pub const FULL_REANALYSIS_SHARE: f32 = 0.5;
+
+// Unchanged sentences around a change that are analyzed with it
+const CONTEXT_UNITS: usize = 1;
+
+// The diff table is bounded; larger rewrites count as one changed region
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+/// Changed region of a document: byte ranges in the previous and the current text
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChangedSection {
+    pub previous: Range<usize>,
+    pub current: Range<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TextDiff {
+    sections: Vec<ChangedSection>,
+    old_units: Vec<Range<usize>>,
+    new_units: Vec<Range<usize>>,
+    unit_map: Vec<Option<usize>>, // previous unit -> current unit, for unchanged units
+    changed_bytes: usize,
+    new_len: usize,
+}
+
+/// Byte ranges of the lines and sentences of a text; together they cover it exactly
+fn units(text: &str) -> Vec<Range<usize>> {
+    let boundary = Regex::new(r"\n|[.;:?!]\s+").unwrap();
+    let mut units = Vec::new();
+    let mut start = 0;
+    for found in boundary.find_iter(text) {
+        if found.end() > start {
+            units.push(start..found.end());
+            start = found.end();
+        }
+    }
+    if start < text.len() {
+        units.push(start..text.len());
+    }
+    units
+}
+
+fn unit_hash(text: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for word in text.split_whitespace() {
+        word.hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+/// Byte range covered by units `range`; an empty range sits where the units would be
+fn byte_range(units: &[Range<usize>], range: Range<usize>, len: usize) -> Range<usize> {
+    if range.is_empty() {
+        let at = units.get(range.start).map_or(len, |u| u.start);
+        return at..at;
+    }
+    units[range.start].start..units[range.end - 1].end
+}
+
+/// Longest common subsequence of two hash sequences, as matched index pairs
+fn matching_units(old: &[u64], new: &[u64]) -> Option<Vec<(usize, usize)>> {
+    let (n, m) = (old.len(), new.len());
+    if (n + 1) * (m + 1) > MAX_DIFF_CELLS {
+        return None;
+    }
+    let mut table = vec![0u32; (n + 1) * (m + 1)];
+    let at = |i: usize, j: usize| i * (m + 1) + j;
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            table[at(i, j)] = if old[i] == new[j] {
+                table[at(i + 1, j + 1)] + 1
+            } else {
+                table[at(i + 1, j)].max(table[at(i, j + 1)])
+            };
+        }
+    }
+    let (mut i, mut j) = (0, 0);
+    let mut pairs = Vec::new();
+    while i < n && j < m {
+        if old[i] == new[j] {
+            pairs.push((i, j));
+            i += 1;
+            j += 1;
+        } else if table[at(i + 1, j)] >= table[at(i, j + 1)] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    Some(pairs)
+}
+
+/// Diff two versions of a document's text
+pub fn diff_text(old: &str, new: &str) -> TextDiff {
+    let old_units = units(old);
+    let new_units = units(new);
+    let old_hashes: Vec<u64> = old_units.iter().map(|u| unit_hash(&old[u.clone()])).collect();
+    let new_hashes: Vec<u64> = new_units.iter().map(|u| unit_hash(&new[u.clone()])).collect();
+
+    // Edits are usually local: match the common head and tail before diffing the middle
+    let prefix = old_hashes.iter().zip(&new_hashes).take_while(|(a, b)| a == b).count();
+    let suffix = old_hashes[prefix..]
+        .iter()
+        .rev()
+        .zip(new_hashes[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let (old_end, new_end) = (old_hashes.len() - suffix, new_hashes.len() - suffix);
+
+    let mut pairs: Vec<(usize, usize)> = (0..prefix).map(|i| (i, i)).collect();
+    let middle = matching_units(&old_hashes[prefix..old_end], &new_hashes[prefix..new_end]).unwrap_or_default();
+    pairs.extend(middle.into_iter().map(|(i, j)| (i + prefix, j + prefix)));
+    pairs.extend((0..suffix).map(|k| (old_end + k, new_end + k)));
+
+    // Runs between matched units are the changes
+    let mut changes: Vec<(Range<usize>, Range<usize>)> = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    for &(pi, pj) in pairs.iter().chain([(old_units.len(), new_units.len())].iter()) {
+        if pi > i || pj > j {
+            changes.push((i..pi, j..pj));
+        }
+        i = pi + 1;
+        j = pj + 1;
+    }
+    let changed_bytes = changes
+        .iter()
+        .map(|(_, new_range)| byte_range(&new_units, new_range.clone(), new.len()).len())
+        .sum();
+
+    // Widen by the context units and merge changes that now touch
+    let mut widened: Vec<(Range<usize>, Range<usize>)> = Vec::new();
+    for (old_range, new_range) in changes {
+        let old_range = old_range.start.saturating_sub(CONTEXT_UNITS)..(old_range.end + CONTEXT_UNITS).min(old_units.len());
+        let new_range = new_range.start.saturating_sub(CONTEXT_UNITS)..(new_range.end + CONTEXT_UNITS).min(new_units.len());
+        match widened.last_mut() {
+            Some((last_old, last_new)) if old_range.start <= last_old.end || new_range.start <= last_new.end => {
+                last_old.end = last_old.end.max(old_range.end);
+                last_new.end = last_new.end.max(new_range.end);
+            }
+            _ => widened.push((old_range, new_range)),
+        }
+    }
+
+    let mut unit_map = vec![None; old_units.len()];
+    for (pi, pj) in pairs {
+        unit_map[pi] = Some(pj);
+    }
+    TextDiff {
+        sections: widened
+            .into_iter()
+            .map(|(old_range, new_range)| ChangedSection {
+                previous: byte_range(&old_units, old_range, old.len()),
+                current: byte_range(&new_units, new_range, new.len()),
+            })
+            .collect(),
+        old_units,
+        new_units,
+        unit_map,
+        changed_bytes,
+        new_len: new.len(),
+    }
+}
+
+impl TextDiff {
+    /// A diff that treats the whole document as changed
+    pub fn whole(old: &str, new: &str) -> Self {
+        TextDiff {
+            sections: vec![ChangedSection {
+                previous: 0..old.len(),
+                current: 0..new.len(),
+            }],
+            old_units: Vec::new(),
+            new_units: Vec::new(),
+            unit_map: Vec::new(),
+            changed_bytes: new.len(),
+            new_len: new.len(),
+        }
+    }
+
+    pub fn sections(&self) -> &[ChangedSection] {
+        &self.sections
+    }
+
+    /// Share of the current text that is new or edited
+    pub fn changed_share(&self) -> f32 {
+        if self.new_len == 0 {
+            return if self.sections.is_empty() { 0.0 } else { 1.0 };
+        }
+        self.changed_bytes as f32 / self.new_len as f32
+    }
+
+    /// Changed section a span of the previous text overlaps
+    fn section_of(&self, span: Range<usize>) -> Option<usize> {
+        self.sections.iter().position(|s| {
+            if span.is_empty() || s.previous.is_empty() {
+                s.previous.contains(&span.start)
+            } else {
+                span.start < s.previous.end && s.previous.start < span.end
+            }
+        })
+    }
+
+    /// Position in the current text of an unchanged position in the previous one
+    fn map_offset(&self, offset: usize) -> Option<usize> {
+        let unit = self.old_units.iter().position(|u| u.contains(&offset))?;
+        let target = self.unit_map[unit]?;
+        Some(self.new_units[target].start + (offset - self.old_units[unit].start))
+    }
+}
+
+/// Findings tied to a place in the text
+#[derive(Debug, Clone, Default)]
+pub struct SectionFindings {
+    pub entities: Vec<LegalEntity>,
+    pub clauses: Vec<ContractClause>,
+    pub citations: Vec<LegalCitation>,
+}
+
+impl SectionFindings {
+    /// All located findings of an analysis, as one section
+    pub fn of(analysis: &DocumentAnalysis) -> Self {
+        SectionFindings {
+            entities: analysis.entities.clone(),
+            clauses: analysis.clauses.clone(),
+            citations: analysis.citations.clone(),
+        }
+    }
+}
+
+/// Split the previous analysis into findings outside the changed sections, moved to their new
+/// positions, and the findings of each changed section, which are replaced
+pub fn carry_over(previous: &DocumentAnalysis, diff: &TextDiff) -> (SectionFindings, Vec<SectionFindings>) {
+    let text = &previous.extracted_text;
+    let mut kept = SectionFindings::default();
+    let mut replaced = vec![SectionFindings::default(); diff.sections.len()];
+
+    for entity in &previous.entities {
+        let section = diff.section_of(entity.start_pos..entity.end_pos);
+        let moved = diff.map_offset(entity.start_pos);
+        match (section, moved) {
+            (None, Some(start)) => kept.entities.push(LegalEntity {
+                start_pos: start,
+                end_pos: start + (entity.end_pos - entity.start_pos),
+                ..entity.clone()
+            }),
+            (Some(section), _) => replaced[section].entities.push(entity.clone()),
+            (None, None) => {}
+        }
+    }
+    // Clauses and citations are located by their text
+    let span = |needle: &str| text.find(needle).map(|at| at..at + needle.len());
+    for clause in &previous.clauses {
+        match span(&clause.text).and_then(|s| diff.section_of(s)) {
+            Some(section) => replaced[section].clauses.push(clause.clone()),
+            None => kept.clauses.push(clause.clone()),
+        }
+    }
+    for citation in &previous.citations {
+        match span(&citation.citation_text).and_then(|s| diff.section_of(s)) {
+            Some(section) => replaced[section].citations.push(citation.clone()),
+            None => kept.citations.push(citation.clone()),
+        }
+    }
+    (kept, replaced)
+}
+
+/// Carried-over and fresh findings together. Legal terms are reported once per document, at
+/// their first occurrence, as a full analysis does.
+pub fn merge(kept: SectionFindings, fresh: &[SectionFindings]) -> SectionFindings {
+    let mut merged = kept;
+    for findings in fresh {
+        merged.entities.extend(findings.entities.iter().cloned());
+        merged.clauses.extend(findings.clauses.iter().cloned());
+        merged.citations.extend(findings.citations.iter().cloned());
+    }
+    merged.entities.sort_by_key(|e| e.start_pos);
+    let mut seen_terms = HashSet::new();
+    merged
+        .entities
+        .retain(|e| !matches!(e.entity_type, EntityType::LegalTerm) || seen_terms.insert(e.text.to_lowercase()));
+    merged
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FindingKind {
+    Entity,
+    Clause,
+    Citation,
+    Risk,
+    ComplianceFlag,
+    KeyTerm,
+    Summary,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeStatus {
+    New,
+    Removed,
+    Changed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FindingChange {
+    pub kind: FindingKind,
+    pub status: ChangeStatus,
+    pub label: String,
+    pub section: Option<usize>, // index into `changed_sections`; None for document-wide findings
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+// (label, fingerprint, excerpt); findings with the same label are paired in order
+type Finding = (String, String, String);
+type Occurrences = Vec<(String, String)>;
+
+fn collapse(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn compare_findings(kind: FindingKind, section: Option<usize>, before: Vec<Finding>, after: Vec<Finding>, changes: &mut Vec<FindingChange>) {
+    let mut grouped: BTreeMap<String, (Occurrences, Occurrences)> = BTreeMap::new();
+    for (label, fingerprint, excerpt) in before {
+        grouped.entry(label).or_default().0.push((fingerprint, excerpt));
+    }
+    for (label, fingerprint, excerpt) in after {
+        grouped.entry(label).or_default().1.push((fingerprint, excerpt));
+    }
+    for (label, (mut before, mut after)) in grouped {
+        // Occurrences present in both versions are not changes
+        before.retain(|b| match after.iter().position(|a| a.0 == b.0) {
+            Some(i) => {
+                after.remove(i);
+                false
+            }
+            None => true,
+        });
+        let paired = before.len().max(after.len());
+        for i in 0..paired {
+            let (old, new) = (before.get(i), after.get(i));
+            changes.push(FindingChange {
+                kind,
+                status: match (old, new) {
+                    (Some(_), Some(_)) => ChangeStatus::Changed,
+                    (None, _) => ChangeStatus::New,
+                    (_, None) => ChangeStatus::Removed,
+                },
+                label: label.clone(),
+                section,
+                before: old.map(|(_, excerpt)| excerpt.clone()),
+                after: new.map(|(_, excerpt)| excerpt.clone()),
+            });
+        }
+    }
+}
+
+fn entity_findings(entities: &[LegalEntity]) -> Vec<Finding> {
+    entities
+        .iter()
+        .map(|e| {
+            let label = format!("{:?}: {}", e.entity_type, e.text);
+            (label, e.normalized_value.clone().unwrap_or_default(), collapse(&e.context))
+        })
+        .collect()
+}
+
+fn clause_findings(clauses: &[ContractClause]) -> Vec<Finding> {
+    clauses
+        .iter()
+        .map(|c| (format!("{:?}", c.clause_type), collapse(&c.text), collapse(&c.text)))
+        .collect()
+}
+
+fn citation_findings(citations: &[LegalCitation]) -> Vec<Finding> {
+    citations
+        .iter()
+        .map(|c| {
+            let label = c.case_name.clone().unwrap_or_else(|| collapse(&c.citation_text));
+            (label, collapse(&c.citation_text), c.citation_text.clone())
+        })
+        .collect()
+}
+
+/// Findings that appeared, disappeared or changed between two versions. `replaced` and `fresh`
+/// hold the section findings of each changed section before and after the edit.
+pub fn compare(previous: &DocumentAnalysis, current: &DocumentAnalysis, replaced: &[SectionFindings], fresh: &[SectionFindings]) -> Vec<FindingChange> {
+    let mut changes = Vec::new();
+    for (section, (before, after)) in replaced.iter().zip(fresh).enumerate() {
+        let section = Some(section);
+        compare_findings(FindingKind::Entity, section, entity_findings(&before.entities), entity_findings(&after.entities), &mut changes);
+        compare_findings(FindingKind::Clause, section, clause_findings(&before.clauses), clause_findings(&after.clauses), &mut changes);
+        compare_findings(FindingKind::Citation, section, citation_findings(&before.citations), citation_findings(&after.citations), &mut changes);
+    }
+
+    let risks = |analysis: &DocumentAnalysis| -> Vec<Finding> {
+        analysis
+            .risks
+            .iter()
+            .map(|r| {
+                let mut assessment = format!("{:?} severity, {:.0}% likelihood", r.severity, r.likelihood * 100.0);
+                if !r.related_clauses.is_empty() {
+                    assessment.push_str(&format!(": {}", collapse(&r.related_clauses.join(" | "))));
+                }
+                (format!("{:?}: {}", r.risk_type, r.description), assessment.clone(), assessment)
+            })
+            .collect()
+    };
+    compare_findings(FindingKind::Risk, None, risks(previous), risks(current), &mut changes);
+
+    let flags = |analysis: &DocumentAnalysis| -> Vec<Finding> {
+        analysis
+            .compliance_flags
+            .iter()
+            .map(|f| {
+                let state = format!("{:?} ({:?})", f.compliance_status, f.priority);
+                (format!("{}: {}", f.regulation, f.requirement), state.clone(), state)
+            })
+            .collect()
+    };
+    compare_findings(FindingKind::ComplianceFlag, None, flags(previous), flags(current), &mut changes);
+
+    let terms = |analysis: &DocumentAnalysis| -> Vec<Finding> {
+        analysis.key_terms.iter().map(|t| (t.term.clone(), String::new(), t.term.clone())).collect()
+    };
+    compare_findings(FindingKind::KeyTerm, None, terms(previous), terms(current), &mut changes);
+
+    if previous.summary != current.summary {
+        changes.push(FindingChange {
+            kind: FindingKind::Summary,
+            status: match (&previous.summary, &current.summary) {
+                (None, _) => ChangeStatus::New,
+                (_, None) => ChangeStatus::Removed,
+                _ => ChangeStatus::Changed,
+            },
+            label: "Summary".to_string(),
+            section: None,
+            before: previous.summary.clone(),
+            after: current.summary.clone(),
+        });
+    }
+    changes
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnalysisVersion {
+    pub path: String,
+    pub version: u32,
+    pub analyzed_at: DateTime<Utc>,
+    pub analysis: DocumentAnalysis, // as extracted, before translation and glossary terms
+    #[serde(default)]
+    pub fingerprint: Option<AnalysisFingerprint>, // None for versions recorded before it was tracked
+}
+
+/// The latest analysis of each file, which the next incremental analysis diffs against
+#[derive(Debug)]
+pub struct AnalysisVersions {
+    dir: PathBuf,
+}
+
+impl AnalysisVersions {
+    pub fn new(app_data_dir: &Path) -> Self {
+        AnalysisVersions {
+            dir: app_data_dir.join("analysis_versions"),
+        }
+    }
+
+    fn file(&self, document: &Path) -> PathBuf {
+        let document = document.canonicalize().unwrap_or_else(|_| document.to_path_buf());
+        let digest = Sha256::digest(document.to_string_lossy().as_bytes());
+        self.dir.join(format!("{:x}.json", digest))
+    }
+
+    pub fn latest(&self, document: &Path) -> Option<AnalysisVersion> {
+        let json = fs::read_to_string(self.file(document)).ok()?;
+        serde_json::from_str(&json).ok()
+    }
+
+    /// The latest version of every analyzed file
+    pub fn list(&self) -> Vec<AnalysisVersion> {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+        entries
+            .filter_map(|entry| fs::read_to_string(entry.ok()?.path()).ok())
+            .filter_map(|json| serde_json::from_str(&json).ok())
+            .collect()
+    }
+
+    /// Store an analysis as the document's latest version and return its version number
+    pub fn record(&self, document: &Path, analysis: &DocumentAnalysis, fingerprint: &AnalysisFingerprint) -> Result<u32> {
+        let version = self.latest(document).map_or(1, |v| v.version + 1);
+        fs::create_dir_all(&self.dir)?;
+        let entry = AnalysisVersion {
+            path: document.to_string_lossy().to_string(),
+            version,
+            analyzed_at: Utc::now(),
+            analysis: analysis.clone(),
+            fingerprint: Some(fingerprint.clone()),
+        };
+        fs::write(self.file(document), serde_json::to_string(&entry)?)?;
+        Ok(version)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IncrementalAnalysis {
+    pub analysis: DocumentAnalysis,
+    pub version: u32,
+    pub previous_version: Option<u32>,   // None the first time a file is analyzed
+    pub full_reanalysis: bool,           // no previous version, or too much of the text changed
+    pub changed_share: f32,
+    pub changed_sections: Vec<ChangedSection>,
+    pub changes: Vec<FindingChange>,
+}
+
+/// Analyze a document again, re-running analysis only where it changed since the last analysis
+#[tauri::command]
+pub async fn analyze_document_incremental(
+    session_id: String,
+    analyzer: tauri::State<'_, AnalyzerStorage>,
+    review: tauri::State<'_, ReviewQueueStorage>,
+    sessions: tauri::State<'_, SessionStorage>,
+    file_path: String,
+) -> Result<IncrementalAnalysis, String> {
+    let user = authenticated_user(&session_id, &sessions)?;
+    let path = Path::new(&file_path);
+    let result = analyzer
+        .analyze_document_incremental(path)
+        .await
+        .map_err(|e| e.to_string())?;
+    document_analyzer::submit_analysis_for_review(path, &analyzer, &review, &user)?;
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document_analyzer::{ClauseType, RiskLevel};
+
+    fn entity(text: &str, source: &str) -> LegalEntity {
+        let start = source.find(text).unwrap();
+        LegalEntity {
+            entity_type: EntityType::Date,
+            text: text.to_string(),
+            confidence: 0.8,
+            start_pos: start,
+            end_pos: start + text.len(),
+            context: text.to_string(),
+            normalized_value: Some(text.to_string()),
+        }
+    }
+
+    fn clause(text: &str) -> ContractClause {
+        ContractClause {
+            clause_type: ClauseType::PaymentTerms,
+            text: text.to_string(),
+            risk_level: RiskLevel::Low,
+            suggestions: Vec::new(),
+            standard_language: None,
+            page_number: None,
+            section: None,
+        }
+    }
+
+    #[test]
+    fn test_diff_finds_local_edits() {
+        let old = "1. Term. This agreement starts on 1 March 2024.\n2. Payment. Invoices are due in 30 days.\n3. Notices. Notices are in writing.\n4. Law. Dutch law applies.\n";
+        let new = "1. Term. This agreement starts on 1 March 2024.\n2. Payment. Invoices are due in 60 days.\n3. Notices. Notices are in writing.\n4. Law. Dutch law applies.\n5. Audit. Records are kept.\n";
+        let diff = diff_text(old, new);
+
+        assert_eq!(diff.sections().len(), 2);
+        let edited = &diff.sections()[0];
+        assert!(new[edited.current.clone()].contains("60 days"));
+        assert!(!new[edited.current.clone()].contains("1 March"));
+        assert!(!new[edited.current.clone()].contains("Dutch"));
+        assert!(new[diff.sections()[1].current.clone()].contains("Audit"));
+        assert!(diff.changed_share() < FULL_REANALYSIS_SHARE);
+
+        let unchanged = old.find("Dutch").unwrap();
+        assert_eq!(diff.map_offset(unchanged), new.find("Dutch"));
+        assert_eq!(diff_text(old, old).sections().len(), 0);
+        assert_eq!(TextDiff::whole(old, new).changed_share(), 1.0);
+    }
+
+    #[test]
+    fn test_carry_over_and_compare() {
+        let old = "The term starts on 1 March 2024.\nInvoices are due in 30 days.\nNotices are in writing.\nPayment is due monthly.\n";
+        let new = "Preamble added here.\nThe term starts on 1 March 2024.\nInvoices are due in 60 days.\nNotices are in writing.\nPayment is due monthly.\n";
+        let previous = DocumentAnalysis {
+            metadata: serde_json::from_value(serde_json::json!({
+                "id": "a", "filename": "a.txt", "file_type": "txt", "size": 1,
+                "uploaded_at": "2024-01-01T00:00:00Z", "processed_at": null, "document_type": null,
+                "language": "en", "page_count": null, "word_count": null,
+                "security_classification": "Confidential"
+            }))
+            .unwrap(),
+            extracted_text: old.to_string(),
+            entities: vec![entity("1 March 2024", old)],
+            clauses: vec![clause("Invoices are due in 30 days"), clause("Payment is due monthly")],
+            risks: Vec::new(),
+            key_terms: Vec::new(),
+            citations: Vec::new(),
+            summary: None,
+            sentiment_analysis: None,
+            compliance_flags: Vec::new(),
+            output_language: None,
+        };
+        let diff = diff_text(old, new);
+        let (kept, replaced) = carry_over(&previous, &diff);
+
+        // The inserted preamble and the edit share a line of context and form one section
+        assert_eq!(diff.sections().len(), 1);
+        assert_eq!(kept.clauses.len(), 1);
+        assert_eq!(replaced[0].entities.len(), 1);
+        assert_eq!(replaced[0].clauses[0].text, "Invoices are due in 30 days");
+
+        let fresh = vec![SectionFindings {
+            entities: vec![entity("1 March 2024", new)],
+            clauses: vec![clause("Invoices are due in 60 days")],
+            citations: Vec::new(),
+        }];
+
+        let merged = merge(kept, &fresh);
+        assert_eq!(merged.clauses.len(), 2);
+        assert_eq!(merged.entities[0].start_pos, new.find("1 March").unwrap());
+
+        let current = DocumentAnalysis {
+            extracted_text: new.to_string(),
+            entities: merged.entities,
+            clauses: merged.clauses,
+            summary: Some("Payment terms".to_string()),
+            ..previous.clone()
+        };
+        let changes = compare(&previous, &current, &replaced, &fresh);
+        let statuses: Vec<(FindingKind, ChangeStatus)> = changes.iter().map(|c| (c.kind, c.status)).collect();
+        assert_eq!(
+            statuses,
+            vec![(FindingKind::Clause, ChangeStatus::Changed), (FindingKind::Summary, ChangeStatus::New)]
+        );
+        assert_eq!(changes[0].before.as_deref(), Some("Invoices are due in 30 days"));
+    }
+}
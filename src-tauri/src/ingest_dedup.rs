@@ -0,0 +1,459 @@
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+
+use crate::nemotron_rag::LegalDocument;
+
+/// Ingest Deduplication for BEAR AI
+/// Every document indexed into a collection leaves a MinHash signature of its word shingles.
+/// A document whose estimated similarity to one already in the collection reaches the threshold
+/// (the same brief saved twice, a copy with a changed date) is indexed with a `duplicate_of` link
+/// to the original. Retrieval keeps the most recently indexed of an original and its copies
+/// among those a reader may see, and copies can be listed and purged. Signatures use
+/// fixed hash functions so they stay comparable across versions.
+pub const DEFAULT_THRESHOLD: f32 = 0.9;
+// Thresholds below this would link documents that merely share boilerplate
+const MIN_THRESHOLD: f32 = 0.5;
+const SHINGLE_WORDS: usize = 5;
+const SIGNATURE_SIZE: u64 = 64;
+
+fn default_threshold() -> f32 {
+    DEFAULT_THRESHOLD
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentFingerprint {
+    pub document_id: String,
+    pub title: String,
+    pub collection: String,
+    pub content_sha256: String,
+    pub signature: Vec<u64>,
+    pub chunk_count: usize,
+    pub duplicate_of: Option<String>, // the original's document id
+    pub similarity: Option<f32>, // estimated similarity to the original
+    pub indexed_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct DedupState {
+    #[serde(default = "default_threshold")]
+    threshold: f32,
+    #[serde(default)]
+    documents: Vec<DocumentFingerprint>,
+}
+
+impl Default for DedupState {
+    fn default() -> Self {
+        Self {
+            threshold: DEFAULT_THRESHOLD,
+            documents: Vec::new(),
+        }
+    }
+}
+
+/// A document indexed as a duplicate, with the original it was linked to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateDocument {
+    pub document_id: String,
+    pub title: String,
+    pub collection: String,
+    pub chunk_count: usize,
+    pub duplicate_of: String,
+    pub original_title: Option<String>,
+    pub similarity: f32,
+    pub indexed_at: String,
+}
+
+fn fnv1a(text: &str) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in text.bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash
+}
+
+// The splitmix64 finalizer; xoring a shingle hash with mix(i) gives the i-th hash function
+fn mix(mut x: u64) -> u64 {
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+/// MinHash signature over the text's word shingles, ignoring case and punctuation; empty for
+/// a text without words
+pub fn minhash(text: &str) -> Vec<u64> {
+    let words: Vec<String> = text
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(String::from)
+        .collect();
+    if words.is_empty() {
+        return Vec::new();
+    }
+    let shingles: HashSet<u64> = words
+        .windows(SHINGLE_WORDS.min(words.len()))
+        .map(|shingle| fnv1a(&shingle.join(" ")))
+        .collect();
+    (1..=SIGNATURE_SIZE)
+        .map(|i| {
+            let seed = mix(i);
+            shingles.iter().map(|h| mix(h ^ seed)).min().unwrap_or(u64::MAX)
+        })
+        .collect()
+}
+
+/// Estimated Jaccard similarity of the texts two signatures were taken from
+pub fn signature_similarity(a: &[u64], b: &[u64]) -> f32 {
+    if a.is_empty() || a.len() != b.len() {
+        return 0.0;
+    }
+    a.iter().zip(b).filter(|(x, y)| x == y).count() as f32 / a.len() as f32
+}
+
+/// Fingerprint of a document about to be indexed into `collection` from its cleaned content
+pub fn fingerprint(collection: &str, document: &LegalDocument, content: &str, chunk_count: usize) -> DocumentFingerprint {
+    use sha2::{Digest, Sha256};
+
+    DocumentFingerprint {
+        document_id: document.id.clone(),
+        title: document.title.clone(),
+        collection: collection.to_string(),
+        content_sha256: format!("{:x}", Sha256::digest(content.as_bytes())),
+        signature: minhash(content),
+        chunk_count,
+        duplicate_of: None,
+        similarity: None,
+        indexed_at: Utc::now().to_rfc3339(),
+    }
+}
+
+/// Signatures are re-read from disk for every operation: the desktop commands and the RAG index
+/// in the library crate each hold an index over the same file
+pub struct DedupIndex {
+    path: PathBuf,
+    lock: Mutex<()>,
+}
+
+impl DedupIndex {
+    pub fn new(app_data_dir: &Path) -> Self {
+        Self {
+            path: app_data_dir.join("ingest_dedup.json"),
+            lock: Mutex::new(()),
+        }
+    }
+
+    fn load(&self) -> Result<DedupState> {
+        if !self.path.exists() {
+            return Ok(DedupState::default());
+        }
+        Ok(serde_json::from_str(&fs::read_to_string(&self.path)?)?)
+    }
+
+    fn update<T>(&self, update: impl FnOnce(&mut DedupState) -> Result<T>) -> Result<T> {
+        let _guard = self.lock.lock().unwrap();
+        let mut state = self.load()?;
+        let result = update(&mut state)?;
+        fs::write(&self.path, serde_json::to_string_pretty(&state)?)?;
+        Ok(result)
+    }
+
+    pub fn threshold(&self) -> Result<f32> {
+        let _guard = self.lock.lock().unwrap();
+        Ok(self.load()?.threshold)
+    }
+
+    /// Applies to documents indexed from now on; links already made are kept
+    pub fn set_threshold(&self, threshold: f32) -> Result<f32> {
+        if !(MIN_THRESHOLD..=1.0).contains(&threshold) {
+            return Err(anyhow!("The similarity threshold must be between {} and 1", MIN_THRESHOLD));
+        }
+        self.update(|state| {
+            state.threshold = threshold;
+            Ok(threshold)
+        })
+    }
+
+    /// Link `fingerprint` to the most similar other document of its collection at or above the
+    /// threshold, or to that document's original when it is itself a duplicate
+    pub fn link_duplicate(&self, fingerprint: &mut DocumentFingerprint) -> Result<()> {
+        let state = {
+            let _guard = self.lock.lock().unwrap();
+            self.load()?
+        };
+        let best = state
+            .documents
+            .iter()
+            .filter(|d| d.collection == fingerprint.collection && d.document_id != fingerprint.document_id)
+            .map(|d| {
+                let similarity = if d.content_sha256 == fingerprint.content_sha256 {
+                    1.0
+                } else {
+                    signature_similarity(&d.signature, &fingerprint.signature)
+                };
+                (d, similarity)
+            })
+            .filter(|(_, similarity)| *similarity >= state.threshold)
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+        if let Some((original, similarity)) = best {
+            let original_id = original.duplicate_of.clone().unwrap_or_else(|| original.document_id.clone());
+            // Re-indexing the original of a chain must not link it to its own copy
+            if original_id != fingerprint.document_id {
+                fingerprint.duplicate_of = Some(original_id);
+                fingerprint.similarity = Some(similarity);
+            }
+        }
+        Ok(())
+    }
+
+    /// Record an indexed document, replacing its earlier fingerprint in the collection
+    pub fn record(&self, fingerprint: DocumentFingerprint) -> Result<()> {
+        self.update(|state| {
+            state
+                .documents
+                .retain(|d| !(d.collection == fingerprint.collection && d.document_id == fingerprint.document_id));
+            state.documents.push(fingerprint);
+            Ok(())
+        })
+    }
+
+    /// Documents indexed as duplicates, in `collection` or all collections, most similar first
+    pub fn duplicates(&self, collection: Option<&str>) -> Result<Vec<DuplicateDocument>> {
+        let documents = self.documents()?;
+        let mut duplicates: Vec<DuplicateDocument> = documents
+            .iter()
+            .filter(|d| collection.map_or(true, |c| d.collection == c))
+            .filter_map(|d| {
+                let original = d.duplicate_of.as_ref()?;
+                Some(DuplicateDocument {
+                    document_id: d.document_id.clone(),
+                    title: d.title.clone(),
+                    collection: d.collection.clone(),
+                    chunk_count: d.chunk_count,
+                    duplicate_of: original.clone(),
+                    original_title: documents
+                        .iter()
+                        .find(|o| o.collection == d.collection && &o.document_id == original)
+                        .map(|o| o.title.clone()),
+                    similarity: d.similarity.unwrap_or(1.0),
+                    indexed_at: d.indexed_at.clone(),
+                })
+            })
+            .collect();
+        duplicates.sort_by(|a, b| b.similarity.total_cmp(&a.similarity).then_with(|| a.title.cmp(&b.title)));
+        Ok(duplicates)
+    }
+
+    /// The recorded fingerprint of a document in a collection
+    pub fn fingerprint(&self, collection: &str, document_id: &str) -> Result<Option<DocumentFingerprint>> {
+        Ok(self
+            .documents()?
+            .into_iter()
+            .find(|d| d.collection == collection && d.document_id == document_id))
+    }
+
+    /// Fingerprints of the indexed documents, optionally in one collection
+    pub fn indexed_documents(&self, collection: Option<&str>) -> Result<Vec<DocumentFingerprint>> {
+        let mut documents = self.documents()?;
+        documents.retain(|d| collection.map_or(true, |c| d.collection == c));
+        Ok(documents)
+    }
+
+    fn documents(&self) -> Result<Vec<DocumentFingerprint>> {
+        let _guard = self.lock.lock().unwrap();
+        Ok(self.load()?.documents)
+    }
+
+    /// Forget a document; when it was an original, its most recently indexed copy takes its
+    /// place and the other copies are linked to that one
+    pub fn remove(&self, collection: &str, document_id: &str) -> Result<()> {
+        self.update(|state| {
+            state
+                .documents
+                .retain(|d| !(d.collection == collection && d.document_id == document_id));
+            let copies = |d: &&mut DocumentFingerprint| d.collection == collection && d.duplicate_of.as_deref() == Some(document_id);
+            let promoted = state
+                .documents
+                .iter_mut()
+                .filter(copies)
+                .max_by(|a, b| a.indexed_at.cmp(&b.indexed_at))
+                .map(|copy| {
+                    copy.duplicate_of = None;
+                    copy.similarity = None;
+                    copy.document_id.clone()
+                });
+            if let Some(promoted) = promoted {
+                for copy in state.documents.iter_mut().filter(copies) {
+                    copy.duplicate_of = Some(promoted.clone());
+                }
+            }
+            Ok(())
+        })
+    }
+}
+
+lazy_static! {
+    static ref GLOBAL_DEDUP_INDEX: RwLock<Option<Arc<DedupIndex>>> = RwLock::new(None);
+}
+
+/// Initialize the global deduplication index consulted during indexing
+pub fn initialize_dedup_index(app_data_dir: &Path) -> Arc<DedupIndex> {
+    let index = Arc::new(DedupIndex::new(app_data_dir));
+    *GLOBAL_DEDUP_INDEX.write().unwrap() = Some(index.clone());
+    index
+}
+
+pub fn dedup_index() -> Option<Arc<DedupIndex>> {
+    GLOBAL_DEDUP_INDEX.read().unwrap().clone()
+}
+
+/// Link a document about to be indexed to the one it duplicates; unlinked when the index is not
+/// initialized or cannot be read
+pub fn check_duplicate(fingerprint: &mut DocumentFingerprint) {
+    if let Some(index) = dedup_index() {
+        if let Err(e) = index.link_duplicate(fingerprint) {
+            log::warn!("Duplicate check failed for {}: {}", fingerprint.document_id, e);
+        }
+    }
+}
+
+/// Record an indexed document; ignored when the index is not initialized
+pub fn record_indexed_document(fingerprint: DocumentFingerprint) -> Result<()> {
+    match dedup_index() {
+        Some(index) => index.record(fingerprint),
+        None => Ok(()),
+    }
+}
+
+pub type DedupIndexStorage = Arc<DedupIndex>;
+
+#[tauri::command]
+pub async fn list_duplicate_documents(
+    collection: Option<String>,
+    index: tauri::State<'_, DedupIndexStorage>,
+) -> Result<Vec<DuplicateDocument>, String> {
+    index.duplicates(collection.as_deref()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_dedup_threshold(index: tauri::State<'_, DedupIndexStorage>) -> Result<f32, String> {
+    index.threshold().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn set_dedup_threshold(
+    threshold: f32,
+    index: tauri::State<'_, DedupIndexStorage>,
+) -> Result<f32, String> {
+    index.set_threshold(threshold).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nemotron_rag::{DocumentMetadata, DocumentType, PrecedentialValue};
+
+    const BRIEF: &str = "The plaintiff moves for summary judgment on the breach of contract claim. The supply \
+        agreement required delivery of two thousand units by the first of March, and the defendant delivered \
+        nothing until late April. The defendant does not dispute the delivery dates but argues that a force \
+        majeure event excused performance. The clause it relies on covers only acts of government and natural \
+        disasters, and the shortage of components it cites is neither. Because no genuine dispute of material \
+        fact remains as to breach, the court should enter judgment for the plaintiff on liability and set the \
+        question of damages for trial.";
+
+    fn document(id: &str) -> LegalDocument {
+        LegalDocument {
+            id: id.to_string(),
+            title: format!("{}.docx", id),
+            content: String::new(),
+            jurisdiction: "General".to_string(),
+            document_type: DocumentType::Brief,
+            last_updated: Utc::now(),
+            citations: vec![],
+            metadata: DocumentMetadata {
+                court: None,
+                judge: None,
+                parties: vec![],
+                topics: vec![],
+                precedential_value: PrecedentialValue::NotPrecedential,
+                confidence: 1.0,
+            },
+        }
+    }
+
+    #[test]
+    fn test_minhash_estimates_similarity() {
+        let revised = BRIEF.replace("for trial", "at trial");
+        let similar = signature_similarity(&minhash(BRIEF), &minhash(&revised));
+        assert!((DEFAULT_THRESHOLD..1.0).contains(&similar), "{}", similar);
+        assert_eq!(signature_similarity(&minhash(BRIEF), &minhash(&BRIEF.to_uppercase())), 1.0);
+
+        let unrelated = "The tenant shall keep the premises in good repair and return them at the end of the term.";
+        assert!(signature_similarity(&minhash(BRIEF), &minhash(unrelated)) < 0.2);
+        assert!(minhash("").is_empty());
+        assert_eq!(signature_similarity(&[], &[]), 0.0);
+    }
+
+    #[test]
+    fn test_links_and_lists_duplicates_per_collection() {
+        let dir = tempfile::tempdir().unwrap();
+        let index = DedupIndex::new(dir.path());
+        let original = fingerprint("briefs", &document("original"), BRIEF, 3);
+        index.record(original).unwrap();
+
+        // A copy and a copy of the copy both link to the original
+        let mut copy = fingerprint("briefs", &document("copy"), &BRIEF.replace("for trial", "at trial"), 3);
+        index.link_duplicate(&mut copy).unwrap();
+        assert_eq!(copy.duplicate_of.as_deref(), Some("original"));
+        index.record(copy).unwrap();
+        let mut exact = fingerprint("briefs", &document("exact"), &BRIEF.replace("for trial", "at trial"), 3);
+        index.link_duplicate(&mut exact).unwrap();
+        assert_eq!((exact.duplicate_of.as_deref(), exact.similarity), (Some("original"), Some(1.0)));
+        index.record(exact).unwrap();
+
+        // Other collections and re-indexing the original are left alone
+        let mut elsewhere = fingerprint("archive", &document("elsewhere"), BRIEF, 3);
+        index.link_duplicate(&mut elsewhere).unwrap();
+        assert_eq!(elsewhere.duplicate_of, None);
+        let mut reindexed = fingerprint("briefs", &document("original"), BRIEF, 3);
+        index.link_duplicate(&mut reindexed).unwrap();
+        assert_eq!(reindexed.duplicate_of, None);
+
+        let listed = index.duplicates(Some("briefs")).unwrap();
+        let ids: Vec<&str> = listed.iter().map(|d| d.document_id.as_str()).collect();
+        assert_eq!(ids, vec!["exact", "copy"]);
+        assert_eq!(listed[0].original_title.as_deref(), Some("original.docx"));
+        assert!(index.duplicates(Some("archive")).unwrap().is_empty());
+
+        assert_eq!(index.fingerprint("briefs", "copy").unwrap().unwrap().chunk_count, 3);
+        index.remove("briefs", "copy").unwrap();
+        assert_eq!(index.duplicates(None).unwrap().len(), 1);
+        assert!(index.fingerprint("briefs", "copy").unwrap().is_none());
+        assert_eq!(index.indexed_documents(Some("briefs")).unwrap().len(), 2);
+        assert!(index.indexed_documents(Some("archive")).unwrap().is_empty());
+
+        // Removing the original promotes its latest copy
+        let mut later = fingerprint("briefs", &document("later"), BRIEF, 3);
+        index.link_duplicate(&mut later).unwrap();
+        later.indexed_at = "9999-01-01T00:00:00Z".to_string();
+        index.record(later).unwrap();
+        index.remove("briefs", "original").unwrap();
+        let listed = index.duplicates(Some("briefs")).unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!((listed[0].document_id.as_str(), listed[0].duplicate_of.as_str()), ("exact", "later"));
+        assert_eq!(listed[0].original_title.as_deref(), Some("later.docx"));
+
+        // A strict threshold stops near copies from linking
+        index.set_threshold(1.0).unwrap();
+        let mut near = fingerprint("briefs", &document("near"), &BRIEF.replace("the first of March", "1 March"), 3);
+        index.link_duplicate(&mut near).unwrap();
+        assert_eq!(near.duplicate_of, None);
+        assert!(index.set_threshold(0.2).is_err());
+    }
+}
@@ -0,0 +1,851 @@
+use anyhow::{anyhow, Result};
+use base64::Engine;
+use chrono::{DateTime, Duration, Utc};
+use regex::Regex;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE, ETAG, LAST_MODIFIED};
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::nemotron_rag::{DocumentMetadata, DocumentType, LegalDocument, PrecedentialValue};
+use crate::security::SecurityManager;
+
+/// Intranet Crawler for BEAR AI
+/// Indexes firm wikis and knowledge bases behind the firewall into a collection of their own.
+/// Crawls stay on the configured hosts, honour robots.txt and robots meta tags, and every chunk
+/// records the page it came from so answers can point back to the source.
+const CRAWLER_USER_AGENT: &str = "BEAR-AI-Crawler/1.0";
+const MAX_PAGE_BYTES: u64 = 5 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrawlSite {
+    pub id: String,
+    pub name: String,
+    pub seed_urls: Vec<String>,
+    pub allowed_hosts: Vec<String>,
+    pub max_depth: u32,
+    pub max_pages: usize,
+    pub respect_robots: bool,
+    pub collection: String,
+    pub refresh_hours: Option<u32>, // None: only crawled on request
+    pub header_names: Vec<String>,
+    headers_encrypted: Option<String>, // base64 of the SecurityManager ciphertext of the header map
+    pub last_crawled: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CrawlSiteRequest {
+    pub id: Option<String>,
+    pub name: String,
+    pub seed_urls: Vec<String>,
+    #[serde(default)]
+    pub allowed_hosts: Vec<String>, // defaults to the seed hosts
+    pub max_depth: Option<u32>,
+    pub max_pages: Option<usize>,
+    pub respect_robots: Option<bool>,
+    pub collection: Option<String>,
+    pub refresh_hours: Option<u32>,
+    pub headers: Option<HashMap<String, String>>, // None keeps the stored headers when updating
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrawledPage {
+    pub url: String,
+    pub title: String,
+    pub document_id: String,
+    pub content_sha256: String,
+    pub depth: u32,
+    pub chunks: usize,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub fetched_at: DateTime<Utc>,
+    pub indexed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrawlReport {
+    pub site_id: String,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub fetched: usize,
+    pub indexed: usize,
+    pub unchanged: usize,
+    pub blocked_by_robots: usize,
+    pub truncated: bool, // stopped at max_pages
+    pub removed: Vec<String>, // previously indexed pages no longer reachable
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CrawlerState {
+    sites: HashMap<String, CrawlSite>,
+    pages: HashMap<String, HashMap<String, CrawledPage>>, // site id -> url -> page
+    reports: HashMap<String, CrawlReport>, // last crawl per site
+}
+
+type RobotsGroup = (Vec<String>, Vec<(bool, String)>); // (user agents, (allow, pattern))
+
+/// robots.txt rules that apply to our user agent
+#[derive(Debug, Clone, Default)]
+pub struct RobotsRules {
+    rules: Vec<(bool, usize, Regex)>, // (allow, pattern length, matcher)
+    disallow_all: bool,
+}
+
+impl RobotsRules {
+    /// Rules from the most specific group naming `user_agent`, else the `*` group
+    pub fn parse(text: &str, user_agent: &str) -> Self {
+        let agent = user_agent.split('/').next().unwrap_or(user_agent).to_lowercase();
+        let mut groups: Vec<RobotsGroup> = Vec::new();
+        let mut in_agents = false;
+
+        for line in text.lines() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+            match key.trim().to_lowercase().as_str() {
+                "user-agent" => {
+                    if !in_agents {
+                        groups.push((Vec::new(), Vec::new()));
+                        in_agents = true;
+                    }
+                    if let Some(group) = groups.last_mut() {
+                        group.0.push(value.to_lowercase());
+                    }
+                }
+                key @ ("allow" | "disallow") => {
+                    in_agents = false;
+                    if let Some(group) = groups.last_mut() {
+                        group.1.push((key == "allow", value.to_string()));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let named: Vec<_> = groups
+            .iter()
+            .filter(|(agents, _)| agents.iter().any(|a| a != "*" && agent.contains(a.as_str())))
+            .collect();
+        let applicable = if named.is_empty() {
+            groups.iter().filter(|(agents, _)| agents.iter().any(|a| a == "*")).collect()
+        } else {
+            named
+        };
+
+        let rules = applicable
+            .into_iter()
+            .flat_map(|(_, rules)| rules.iter())
+            .filter(|(_, pattern)| !pattern.is_empty()) // an empty Disallow allows everything
+            .filter_map(|(allow, pattern)| Some((*allow, pattern.len(), robots_pattern(pattern)?)))
+            .collect();
+        Self { rules, disallow_all: false }
+    }
+
+    /// Used when robots.txt exists but could not be read
+    pub fn disallow_all() -> Self {
+        Self { rules: Vec::new(), disallow_all: true }
+    }
+
+    /// The longest matching rule decides; Allow wins a tie
+    pub fn allows(&self, path: &str) -> bool {
+        if self.disallow_all {
+            return false;
+        }
+        let decisive = self
+            .rules
+            .iter()
+            .filter(|(_, _, matcher)| matcher.is_match(path))
+            .max_by_key(|(allow, len, _)| (*len, *allow));
+        match decisive {
+            Some((allow, _, _)) => *allow,
+            None => true,
+        }
+    }
+}
+
+fn robots_pattern(pattern: &str) -> Option<Regex> {
+    let (body, anchored) = match pattern.strip_suffix('$') {
+        Some(body) => (body, true),
+        None => (pattern, false),
+    };
+    let body = body.split('*').map(regex::escape).collect::<Vec<_>>().join(".*");
+    Regex::new(&format!("^{}{}", body, if anchored { "$" } else { "" })).ok()
+}
+
+/// Text and links of one HTML page
+#[derive(Debug, Clone, Default)]
+pub struct HtmlPage {
+    pub title: Option<String>,
+    pub text: String,
+    pub links: Vec<String>,
+    pub base: Option<String>,
+    pub noindex: bool,
+    pub nofollow: bool,
+}
+
+pub fn parse_html(html: &str) -> HtmlPage {
+    let mut page = HtmlPage::default();
+
+    let meta = Regex::new(r"(?is)<meta\s[^>]*>").unwrap();
+    let attribute = Regex::new(r#"(?is)\b(name|content)\s*=\s*(?:"([^"]*)"|'([^']*)')"#).unwrap();
+    for tag in meta.find_iter(html) {
+        let mut name = String::new();
+        let mut content = String::new();
+        for cap in attribute.captures_iter(tag.as_str()) {
+            let value = cap.get(2).or_else(|| cap.get(3)).map_or("", |m| m.as_str()).to_lowercase();
+            if cap[1].eq_ignore_ascii_case("name") {
+                name = value;
+            } else {
+                content = value;
+            }
+        }
+        if name == "robots" || name == CRAWLER_USER_AGENT.split('/').next().unwrap_or("").to_lowercase() {
+            page.noindex |= content.contains("noindex") || content.contains("none");
+            page.nofollow |= content.contains("nofollow") || content.contains("none");
+        }
+    }
+
+    let mut body = Regex::new(r"(?s)<!--.*?-->").unwrap().replace_all(html, " ").to_string();
+    for tag in ["script", "style", "noscript", "template", "svg"] {
+        let element = Regex::new(&format!(r"(?is)<{}\b.*?</{}\s*>", tag, tag)).unwrap();
+        body = element.replace_all(&body, " ").to_string();
+    }
+
+    page.title = Regex::new(r"(?is)<title[^>]*>(.*?)</title\s*>")
+        .unwrap()
+        .captures(&body)
+        .map(|cap| collapse_whitespace(&decode_entities(&cap[1])))
+        .filter(|title| !title.is_empty());
+    page.base = Regex::new(r#"(?is)<base\s[^>]*?\bhref\s*=\s*["']([^"']+)["']"#)
+        .unwrap()
+        .captures(&body)
+        .map(|cap| decode_entities(&cap[1]));
+
+    let anchor = Regex::new(r#"(?is)<a\s[^>]*?\bhref\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s>]+))"#).unwrap();
+    page.links = anchor
+        .captures_iter(&body)
+        .filter_map(|cap| cap.get(1).or_else(|| cap.get(2)).or_else(|| cap.get(3)))
+        .map(|m| decode_entities(m.as_str().trim()))
+        .filter(|href| !href.is_empty())
+        .collect();
+
+    let body = Regex::new(r"(?is)<head\b.*?</head\s*>").unwrap().replace_all(&body, " ");
+    let body = Regex::new(r"(?i)</?(p|div|br|li|ul|ol|h[1-6]|tr|table|section|article|header|footer|blockquote|pre|dt|dd)\b[^>]*>")
+        .unwrap()
+        .replace_all(&body, "\n");
+    let body = Regex::new(r"(?i)</?(td|th)\b[^>]*>").unwrap().replace_all(&body, " ");
+    let body = Regex::new(r"<[^>]*>").unwrap().replace_all(&body, "");
+    page.text = decode_entities(&body)
+        .lines()
+        .map(collapse_whitespace)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n");
+    page
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn decode_entities(text: &str) -> String {
+    let entity = Regex::new(r"&(#[0-9]+|#[xX][0-9a-fA-F]+|[a-zA-Z]+);").unwrap();
+    entity
+        .replace_all(text, |cap: &regex::Captures| {
+            let name = &cap[1];
+            let decoded = if let Some(hex) = name.strip_prefix("#x").or_else(|| name.strip_prefix("#X")) {
+                u32::from_str_radix(hex, 16).ok().and_then(char::from_u32)
+            } else if let Some(dec) = name.strip_prefix('#') {
+                dec.parse().ok().and_then(char::from_u32)
+            } else {
+                match name {
+                    "amp" => Some('&'),
+                    "lt" => Some('<'),
+                    "gt" => Some('>'),
+                    "quot" => Some('"'),
+                    "apos" => Some('\''),
+                    "nbsp" => Some(' '),
+                    "ndash" => Some('–'),
+                    "mdash" => Some('—'),
+                    "sect" => Some('§'),
+                    "hellip" => Some('…'),
+                    _ => None,
+                }
+            };
+            decoded.map_or_else(|| cap[0].to_string(), |c| c.to_string())
+        })
+        .to_string()
+}
+
+/// Absolute http(s) URLs without fragments, in document order
+pub fn resolve_links(base: &Url, hrefs: &[String]) -> Vec<Url> {
+    hrefs
+        .iter()
+        .filter_map(|href| base.join(href).ok())
+        .filter(|url| matches!(url.scheme(), "http" | "https"))
+        .map(|mut url| {
+            url.set_fragment(None);
+            url
+        })
+        .collect()
+}
+
+fn in_scope(url: &Url, allowed_hosts: &[String]) -> bool {
+    url.host_str()
+        .map(|host| allowed_hosts.iter().any(|allowed| allowed.eq_ignore_ascii_case(host)))
+        .unwrap_or(false)
+}
+
+fn path_and_query(url: &Url) -> String {
+    match url.query() {
+        Some(query) => format!("{}?{}", url.path(), query),
+        None => url.path().to_string(),
+    }
+}
+
+fn sha256_hex(text: &str) -> String {
+    format!("{:x}", Sha256::digest(text.as_bytes()))
+}
+
+fn header_map(headers: &HashMap<String, String>) -> Result<HeaderMap> {
+    let mut map = HeaderMap::new();
+    for (name, value) in headers {
+        let name = HeaderName::from_bytes(name.trim().as_bytes()).map_err(|_| anyhow!("Invalid header name: {}", name))?;
+        let mut value = HeaderValue::from_str(value.trim()).map_err(|_| anyhow!("Invalid value for header {}", name))?;
+        value.set_sensitive(true);
+        map.insert(name, value);
+    }
+    Ok(map)
+}
+
+struct FetchedPage {
+    url: Url,
+    body: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+pub struct IntranetCrawler {
+    path: PathBuf,
+    state: Mutex<CrawlerState>,
+    running: Mutex<HashSet<String>>,
+    client: reqwest::Client,
+}
+
+impl IntranetCrawler {
+    pub fn new(app_data_dir: &Path) -> Result<Self> {
+        let path = app_data_dir.join("intranet_crawler.json");
+        let state = if path.exists() {
+            serde_json::from_str(&fs::read_to_string(&path)?)?
+        } else {
+            CrawlerState::default()
+        };
+
+        Ok(Self {
+            path,
+            state: Mutex::new(state),
+            running: Mutex::new(HashSet::new()),
+            client: reqwest::Client::builder()
+                .user_agent(CRAWLER_USER_AGENT)
+                .timeout(std::time::Duration::from_secs(30))
+                .build()?,
+        })
+    }
+
+    fn persist(&self, state: &CrawlerState) -> Result<()> {
+        fs::write(&self.path, serde_json::to_string_pretty(state)?)?;
+        Ok(())
+    }
+
+    /// Add or update a site; moving it to another collection forgets what was indexed before
+    pub fn save_site(&self, request: CrawlSiteRequest, security: &SecurityManager) -> Result<CrawlSite> {
+        let mut seeds = Vec::new();
+        for seed in &request.seed_urls {
+            let url = Url::parse(seed.trim()).map_err(|e| anyhow!("Invalid seed URL {}: {}", seed, e))?;
+            if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none() {
+                return Err(anyhow!("Seed URL must be http or https: {}", seed));
+            }
+            seeds.push(url);
+        }
+        if seeds.is_empty() {
+            return Err(anyhow!("At least one seed URL is required"));
+        }
+
+        let mut allowed_hosts: Vec<String> = if request.allowed_hosts.is_empty() {
+            seeds.iter().filter_map(|u| u.host_str()).map(str::to_string).collect()
+        } else {
+            request.allowed_hosts.iter().map(|h| h.trim().to_string()).collect()
+        };
+        allowed_hosts.iter_mut().for_each(|h| *h = h.to_lowercase());
+        allowed_hosts.sort();
+        allowed_hosts.dedup();
+        if let Some(seed) = seeds.iter().find(|u| !in_scope(u, &allowed_hosts)) {
+            return Err(anyhow!("Seed URL {} is outside the allowed hosts", seed));
+        }
+
+        let id = request.id.clone().unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+        let collection = request.collection.clone().unwrap_or_else(|| {
+            let slug: String = request
+                .name
+                .to_lowercase()
+                .chars()
+                .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+                .collect();
+            format!("intranet_{}", slug.trim_matches('_'))
+        });
+        if collection.is_empty() || !collection.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+            return Err(anyhow!("Collection names may only contain letters, digits, '_' and '-'"));
+        }
+        if collection == "legal_chunks" || collection == "legal_documents" {
+            return Err(anyhow!("Crawled pages need their own collection, not {}", collection));
+        }
+
+        let mut state = self.state.lock().unwrap();
+        let existing = state.sites.get(&id).cloned();
+        if request.id.is_some() && existing.is_none() {
+            return Err(anyhow!("Crawl site not found: {}", id));
+        }
+
+        let (header_names, headers_encrypted) = match &request.headers {
+            Some(headers) => {
+                header_map(headers)?;
+                let mut names: Vec<String> = headers.keys().cloned().collect();
+                names.sort();
+                let encrypted = if headers.is_empty() {
+                    None
+                } else {
+                    let ciphertext = security.encrypt_data(serde_json::to_string(headers)?.as_bytes())?;
+                    Some(base64::engine::general_purpose::STANDARD.encode(ciphertext))
+                };
+                (names, encrypted)
+            }
+            None => existing
+                .as_ref()
+                .map(|s| (s.header_names.clone(), s.headers_encrypted.clone()))
+                .unwrap_or_default(),
+        };
+
+        if existing.as_ref().is_some_and(|s| s.collection != collection) {
+            state.pages.remove(&id);
+        }
+
+        let site = CrawlSite {
+            id: id.clone(),
+            name: request.name.trim().to_string(),
+            seed_urls: seeds.iter().map(Url::to_string).collect(),
+            allowed_hosts,
+            max_depth: request.max_depth.unwrap_or(3),
+            max_pages: request.max_pages.unwrap_or(500).max(1),
+            respect_robots: request.respect_robots.unwrap_or(true),
+            collection,
+            refresh_hours: request.refresh_hours.filter(|h| *h > 0),
+            header_names,
+            headers_encrypted,
+            last_crawled: existing.and_then(|s| s.last_crawled),
+        };
+        state.sites.insert(id, site.clone());
+        self.persist(&state)?;
+        Ok(site)
+    }
+
+    pub fn site(&self, site_id: &str) -> Option<CrawlSite> {
+        self.state.lock().unwrap().sites.get(site_id).cloned()
+    }
+
+    pub fn sites(&self) -> Vec<CrawlSite> {
+        let mut sites: Vec<CrawlSite> = self.state.lock().unwrap().sites.values().cloned().collect();
+        sites.sort_by(|a, b| a.name.cmp(&b.name));
+        sites
+    }
+
+    /// Stop crawling a site; chunks already in its collection stay searchable until it is rebuilt
+    pub fn remove_site(&self, site_id: &str) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        if state.sites.remove(site_id).is_none() {
+            return Err(anyhow!("Crawl site not found: {}", site_id));
+        }
+        state.pages.remove(site_id);
+        state.reports.remove(site_id);
+        self.persist(&state)
+    }
+
+    pub fn pages(&self, site_id: &str) -> Vec<CrawledPage> {
+        let state = self.state.lock().unwrap();
+        let mut pages: Vec<CrawledPage> = state.pages.get(site_id).map(|p| p.values().cloned().collect()).unwrap_or_default();
+        pages.sort_by(|a, b| a.url.cmp(&b.url));
+        pages
+    }
+
+    pub fn last_report(&self, site_id: &str) -> Option<CrawlReport> {
+        self.state.lock().unwrap().reports.get(site_id).cloned()
+    }
+
+    /// Sites with a refresh interval whose last crawl is older than it
+    pub fn due_sites(&self, now: DateTime<Utc>) -> Vec<String> {
+        let running = self.running.lock().unwrap();
+        self.state
+            .lock()
+            .unwrap()
+            .sites
+            .values()
+            .filter(|site| !running.contains(&site.id))
+            .filter(|site| match (site.refresh_hours, site.last_crawled) {
+                (Some(_), None) => true,
+                (Some(hours), Some(last)) => last + Duration::hours(hours as i64) <= now,
+                (None, _) => false,
+            })
+            .map(|site| site.id.clone())
+            .collect()
+    }
+
+    pub fn headers(&self, site_id: &str, security: &SecurityManager) -> Result<HashMap<String, String>> {
+        let site = self.site(site_id).ok_or_else(|| anyhow!("Crawl site not found: {}", site_id))?;
+        let Some(encrypted) = site.headers_encrypted else {
+            return Ok(HashMap::new());
+        };
+        let ciphertext = base64::engine::general_purpose::STANDARD.decode(encrypted)?;
+        Ok(serde_json::from_slice(&security.decrypt_data(&ciphertext)?)?)
+    }
+
+    /// Crawl a site breadth first and hand new or changed pages to `index`, which stores the
+    /// document with the given chunk metadata in the site's collection and returns the chunk count
+    pub async fn crawl<F, Fut>(&self, site_id: &str, headers: &HashMap<String, String>, mut index: F) -> Result<CrawlReport>
+    where
+        F: FnMut(LegalDocument, HashMap<String, String>) -> Fut,
+        Fut: Future<Output = Result<usize>>,
+    {
+        let site = self.site(site_id).ok_or_else(|| anyhow!("Crawl site not found: {}", site_id))?;
+        if !self.running.lock().unwrap().insert(site.id.clone()) {
+            return Err(anyhow!("{} is already being crawled", site.name));
+        }
+        let _running = scopeguard::guard((), |_| {
+            self.running.lock().unwrap().remove(&site.id);
+        });
+
+        let header_map = header_map(headers)?;
+        let previous = self.state.lock().unwrap().pages.get(site_id).cloned().unwrap_or_default();
+        let mut report = CrawlReport {
+            site_id: site.id.clone(),
+            started_at: Utc::now(),
+            finished_at: Utc::now(),
+            fetched: 0,
+            indexed: 0,
+            unchanged: 0,
+            blocked_by_robots: 0,
+            truncated: false,
+            removed: Vec::new(),
+            errors: Vec::new(),
+        };
+
+        let mut robots: HashMap<String, RobotsRules> = HashMap::new();
+        let mut queue: VecDeque<(Url, u32)> = VecDeque::new();
+        let mut seen: HashSet<String> = HashSet::new();
+        for seed in site.seed_urls.iter().filter_map(|s| Url::parse(s).ok()) {
+            if seen.insert(seed.to_string()) {
+                queue.push_back((seed, 0));
+            }
+        }
+
+        let mut pages: HashMap<String, CrawledPage> = HashMap::new();
+        let mut failed: HashSet<String> = HashSet::new();
+        while let Some((url, depth)) = queue.pop_front() {
+            if report.fetched >= site.max_pages {
+                report.truncated = true;
+                break;
+            }
+            if site.respect_robots && !self.robots_allow(&url, &header_map, &mut robots).await {
+                report.blocked_by_robots += 1;
+                continue;
+            }
+
+            let fetched = match self.fetch_page(&url, &header_map).await {
+                Ok(Some(fetched)) => fetched,
+                Ok(None) => continue, // not HTML
+                Err(e) => {
+                    report.errors.push(format!("{}: {}", url, e));
+                    failed.insert(url.to_string());
+                    continue;
+                }
+            };
+            report.fetched += 1;
+
+            // Redirects may leave the site; the final URL is the page's identity
+            let mut page_url = fetched.url.clone();
+            page_url.set_fragment(None);
+            if !in_scope(&page_url, &site.allowed_hosts) {
+                continue;
+            }
+            seen.insert(page_url.to_string());
+
+            let html = parse_html(&fetched.body);
+            if depth < site.max_depth && !html.nofollow {
+                let base = html
+                    .base
+                    .as_deref()
+                    .and_then(|b| page_url.join(b).ok())
+                    .unwrap_or_else(|| page_url.clone());
+                for link in resolve_links(&base, &html.links) {
+                    if in_scope(&link, &site.allowed_hosts) && seen.insert(link.to_string()) {
+                        queue.push_back((link, depth + 1));
+                    }
+                }
+            }
+            if html.noindex || html.text.is_empty() {
+                continue;
+            }
+
+            let key = page_url.to_string();
+            let title = html.title.clone().unwrap_or_else(|| key.clone());
+            let content = format!("{}\n\n{}", title, html.text);
+            let content_sha256 = sha256_hex(&content);
+            let fetched_at = Utc::now();
+            if let Some(page) = previous.get(&key).filter(|p| p.content_sha256 == content_sha256) {
+                report.unchanged += 1;
+                pages.insert(key, CrawledPage { fetched_at, depth, ..page.clone() });
+                continue;
+            }
+
+            let document_id = format!("intranet-{}", &sha256_hex(&key)[..16]);
+            let mut metadata = HashMap::new();
+            metadata.insert("source".to_string(), "intranet_crawler".to_string());
+            metadata.insert("crawl_site".to_string(), site.id.clone());
+            metadata.insert("source_url".to_string(), key.clone());
+            metadata.insert("page_title".to_string(), title.clone());
+            metadata.insert("fetched_at".to_string(), fetched_at.to_rfc3339());
+            metadata.insert("content_sha256".to_string(), content_sha256.clone());
+            metadata.insert("crawl_depth".to_string(), depth.to_string());
+            if let Some(etag) = &fetched.etag {
+                metadata.insert("etag".to_string(), etag.clone());
+            }
+            if let Some(last_modified) = &fetched.last_modified {
+                metadata.insert("last_modified".to_string(), last_modified.clone());
+            }
+
+            let document = LegalDocument {
+                id: document_id.clone(),
+                title: title.clone(),
+                content,
+                jurisdiction: "Internal".to_string(),
+                document_type: DocumentType::Brief,
+                last_updated: fetched_at,
+                citations: Vec::new(),
+                metadata: DocumentMetadata {
+                    court: None,
+                    judge: None,
+                    parties: Vec::new(),
+                    topics: vec![site.name.clone()],
+                    precedential_value: PrecedentialValue::NotPrecedential,
+                    confidence: 1.0,
+                },
+            };
+
+            match index(document, metadata).await {
+                Ok(chunks) => {
+                    report.indexed += 1;
+                    pages.insert(
+                        key.clone(),
+                        CrawledPage {
+                            url: key,
+                            title,
+                            document_id,
+                            content_sha256,
+                            depth,
+                            chunks,
+                            etag: fetched.etag,
+                            last_modified: fetched.last_modified,
+                            fetched_at,
+                            indexed_at: fetched_at,
+                        },
+                    );
+                }
+                Err(e) => {
+                    report.errors.push(format!("{}: indexing failed: {}", key, e));
+                    failed.insert(key);
+                }
+            }
+        }
+
+        // Pages we could not reach this time, or never got to, keep their last indexed version
+        for (url, page) in previous {
+            if pages.contains_key(&url) {
+                continue;
+            }
+            if report.truncated || failed.contains(&url) {
+                pages.insert(url, page);
+            } else {
+                report.removed.push(url);
+            }
+        }
+        report.removed.sort();
+        report.finished_at = Utc::now();
+
+        let mut state = self.state.lock().unwrap();
+        if let Some(site) = state.sites.get_mut(site_id) {
+            site.last_crawled = Some(report.started_at);
+        }
+        state.pages.insert(site_id.to_string(), pages);
+        state.reports.insert(site_id.to_string(), report.clone());
+        self.persist(&state)?;
+        Ok(report)
+    }
+
+    async fn robots_allow(&self, url: &Url, headers: &HeaderMap, cache: &mut HashMap<String, RobotsRules>) -> bool {
+        let origin = url.origin().ascii_serialization();
+        if !cache.contains_key(&origin) {
+            let rules = self.fetch_robots(&origin, headers).await;
+            cache.insert(origin.clone(), rules);
+        }
+        cache[&origin].allows(&path_and_query(url))
+    }
+
+    /// A missing robots.txt allows everything; one that errors or cannot be reached allows nothing
+    async fn fetch_robots(&self, origin: &str, headers: &HeaderMap) -> RobotsRules {
+        let response = self.client.get(format!("{}/robots.txt", origin)).headers(headers.clone()).send().await;
+        match response {
+            Ok(response) if response.status().is_success() => match response.text().await {
+                Ok(text) => RobotsRules::parse(&text, CRAWLER_USER_AGENT),
+                Err(_) => RobotsRules::disallow_all(),
+            },
+            Ok(response) if response.status().is_client_error() => RobotsRules::default(),
+            Ok(response) => {
+                log::warn!("robots.txt for {} returned {}, not crawling it", origin, response.status());
+                RobotsRules::disallow_all()
+            }
+            Err(e) => {
+                log::warn!("Could not fetch robots.txt for {}: {}", origin, e);
+                RobotsRules::disallow_all()
+            }
+        }
+    }
+
+    async fn fetch_page(&self, url: &Url, headers: &HeaderMap) -> Result<Option<FetchedPage>> {
+        let response = self.client.get(url.clone()).headers(headers.clone()).send().await?;
+        if !response.status().is_success() {
+            return Err(anyhow!("HTTP {}", response.status()));
+        }
+        let header = |name: HeaderName| response.headers().get(name).and_then(|v| v.to_str().ok()).map(str::to_string);
+        let content_type = header(CONTENT_TYPE).unwrap_or_default().to_lowercase();
+        if !content_type.contains("text/html") && !content_type.contains("application/xhtml") {
+            return Ok(None);
+        }
+        if response.content_length().is_some_and(|len| len > MAX_PAGE_BYTES) {
+            return Err(anyhow!("page larger than {} bytes", MAX_PAGE_BYTES));
+        }
+
+        let etag = header(ETAG);
+        let last_modified = header(LAST_MODIFIED);
+        let url = response.url().clone();
+        let body = response.text().await?;
+        Ok(Some(FetchedPage { url, body, etag, last_modified }))
+    }
+}
+
+pub type CrawlerStorage = Arc<IntranetCrawler>;
+type SecurityStorage = Arc<Mutex<SecurityManager>>;
+
+#[tauri::command]
+pub async fn crawler_save_site(
+    request: CrawlSiteRequest,
+    crawler: tauri::State<'_, CrawlerStorage>,
+    security: tauri::State<'_, SecurityStorage>,
+) -> Result<CrawlSite, String> {
+    let security = security.lock().unwrap();
+    crawler.save_site(request, &security).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn crawler_list_sites(crawler: tauri::State<'_, CrawlerStorage>) -> Result<Vec<CrawlSite>, String> {
+    Ok(crawler.sites())
+}
+
+#[tauri::command]
+pub async fn crawler_remove_site(site_id: String, crawler: tauri::State<'_, CrawlerStorage>) -> Result<(), String> {
+    crawler.remove_site(&site_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn crawler_list_pages(
+    site_id: String,
+    crawler: tauri::State<'_, CrawlerStorage>,
+) -> Result<Vec<CrawledPage>, String> {
+    Ok(crawler.pages(&site_id))
+}
+
+#[tauri::command]
+pub async fn crawler_last_report(
+    site_id: String,
+    crawler: tauri::State<'_, CrawlerStorage>,
+) -> Result<Option<CrawlReport>, String> {
+    Ok(crawler.last_report(&site_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_robots_rules_pick_group_and_longest_match() {
+        let robots = "\
+User-agent: *
+Disallow: /
+
+User-agent: googlebot
+User-agent: bear-ai-crawler # our own group
+Disallow: /private/
+Allow: /private/handbook
+Disallow: /*.pdf$
+Disallow:
+";
+        let rules = RobotsRules::parse(robots, CRAWLER_USER_AGENT);
+        assert!(rules.allows("/wiki/Engagement_letters"));
+        assert!(!rules.allows("/private/salaries"));
+        assert!(rules.allows("/private/handbook/chapter-2"));
+        assert!(!rules.allows("/files/precedent.pdf"));
+        assert!(rules.allows("/files/precedent.pdf?download=1"));
+
+        let generic = RobotsRules::parse(robots, "SomeOtherBot/2.0");
+        assert!(!generic.allows("/wiki/Engagement_letters"));
+        assert!(RobotsRules::parse("", CRAWLER_USER_AGENT).allows("/anything"));
+        assert!(!RobotsRules::disallow_all().allows("/"));
+    }
+
+    #[test]
+    fn test_html_text_links_and_scope() {
+        let html = r#"<html><head><title>Know-how &amp; Precedents</title>
+<meta name="robots" content="NOFOLLOW"><style>body { color: red }</style></head>
+<body><h1>Limitation periods</h1><script>var x = "<a href='/hidden'>";</script>
+<p>Claims under &sect; 3 expire after&nbsp;six years.</p><!-- <a href="/draft">draft</a> -->
+<ul><li><a href="/wiki/Notice#clauses">Notice</a></li><li><a href='https://kb.firm.local/wiki/Costs'>Costs</a></li>
+<li><a href=../people>People</a></li><li><a href="https://example.com/out">Out</a></li><li><a href="mailto:kb@firm.local">Mail</a></li></ul>
+</body></html>"#;
+        let page = parse_html(html);
+        assert_eq!(page.title.as_deref(), Some("Know-how & Precedents"));
+        assert!(page.nofollow && !page.noindex);
+        assert_eq!(page.text, "Limitation periods\nClaims under § 3 expire after six years.\nNotice\nCosts\nPeople\nOut\nMail");
+        assert!(!page.links.iter().any(|l| l.contains("hidden") || l.contains("draft")));
+
+        let base = Url::parse("https://kb.firm.local/wiki/Limitation").unwrap();
+        let allowed = vec!["kb.firm.local".to_string()];
+        let in_site: Vec<String> = resolve_links(&base, &page.links)
+            .into_iter()
+            .filter(|u| in_scope(u, &allowed))
+            .map(|u| u.to_string())
+            .collect();
+        assert_eq!(
+            in_site,
+            vec![
+                "https://kb.firm.local/wiki/Notice",
+                "https://kb.firm.local/wiki/Costs",
+                "https://kb.firm.local/people",
+            ]
+        );
+    }
+}
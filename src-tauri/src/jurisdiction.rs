@@ -0,0 +1,474 @@
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+use crate::nemotron_rag::RAGChunk;
+
+/// Jurisdiction-Aware Retrieval for BEAR AI
+/// A configurable tree of jurisdictions (federal > circuit > state by default) used to narrow
+/// retrieval to authority that binds the asked-about jurisdiction and to move same-court and
+/// same-circuit material up the ranking. Chunks carry their jurisdiction in metadata, recorded
+/// at indexing from the document or, for filings, read from the court named in the caption.
+/// Federal trial courts resolve to the state they sit in, which places them in their circuit.
+// Aliases shorter than this only match a whole label, so "CA" does not match inside "Cal. App."
+const MIN_CONTAINED_ALIAS: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JurisdictionLevel {
+    Federal,
+    Circuit,
+    State,
+    Local,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JurisdictionNode {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    #[serde(default)]
+    pub parent: Option<String>, // None only for the root
+    pub level: JurisdictionLevel,
+}
+
+/// How a chunk's jurisdiction stands to the one asked about
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JurisdictionRelation {
+    Same,
+    Superior,    // an ancestor, e.g. the circuit above a state: binding authority
+    Sibling,     // shares a parent, e.g. another state in the same circuit
+    Subordinate, // a descendant, e.g. a state within the circuit asked about
+    Unrelated,
+    Unknown, // no jurisdiction recorded, or one the hierarchy does not know
+}
+
+/// Hard filter applied before ranking
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JurisdictionScope {
+    #[default]
+    Prefer, // keep everything, only boost
+    Binding, // the jurisdiction itself and those above it
+    Within,  // the jurisdiction itself and those below it
+    Exact,
+}
+
+impl JurisdictionScope {
+    pub fn parse(value: &str) -> Result<Self> {
+        match value.to_lowercase().as_str() {
+            "prefer" => Ok(Self::Prefer),
+            "binding" => Ok(Self::Binding),
+            "within" => Ok(Self::Within),
+            "exact" => Ok(Self::Exact),
+            other => Err(anyhow!("Unknown jurisdiction scope: {}", other)),
+        }
+    }
+
+    pub fn admits(&self, relation: JurisdictionRelation) -> bool {
+        use JurisdictionRelation::*;
+        match self {
+            Self::Prefer => true,
+            Self::Binding => matches!(relation, Same | Superior),
+            Self::Within => matches!(relation, Same | Subordinate),
+            Self::Exact => relation == Same,
+        }
+    }
+}
+
+/// Added to a chunk's relevance score by relation; unrelated and unknown chunks get nothing
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JurisdictionBoosts {
+    pub same: f32,
+    pub superior: f32,
+    pub sibling: f32,
+    pub subordinate: f32,
+}
+
+impl Default for JurisdictionBoosts {
+    fn default() -> Self {
+        Self {
+            same: 0.3,
+            superior: 0.2,
+            sibling: 0.1,
+            subordinate: 0.05,
+        }
+    }
+}
+
+impl JurisdictionBoosts {
+    pub fn boost(&self, relation: JurisdictionRelation) -> f32 {
+        match relation {
+            JurisdictionRelation::Same => self.same,
+            JurisdictionRelation::Superior => self.superior,
+            JurisdictionRelation::Sibling => self.sibling,
+            JurisdictionRelation::Subordinate => self.subordinate,
+            JurisdictionRelation::Unrelated | JurisdictionRelation::Unknown => 0.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JurisdictionHierarchy {
+    pub nodes: Vec<JurisdictionNode>,
+    #[serde(default)]
+    pub boosts: JurisdictionBoosts,
+}
+
+// Circuit id, ordinal, and the states in the circuit as (postal code, name)
+type Circuit = (&'static str, &'static str, &'static [(&'static str, &'static str)]);
+
+const US_CIRCUITS: &[Circuit] = &[
+    ("ca1", "1st", &[("ME", "Maine"), ("MA", "Massachusetts"), ("NH", "New Hampshire"), ("RI", "Rhode Island"), ("PR", "Puerto Rico")]),
+    ("ca2", "2nd", &[("CT", "Connecticut"), ("NY", "New York"), ("VT", "Vermont")]),
+    ("ca3", "3rd", &[("DE", "Delaware"), ("NJ", "New Jersey"), ("PA", "Pennsylvania")]),
+    ("ca4", "4th", &[("MD", "Maryland"), ("NC", "North Carolina"), ("SC", "South Carolina"), ("VA", "Virginia"), ("WV", "West Virginia")]),
+    ("ca5", "5th", &[("LA", "Louisiana"), ("MS", "Mississippi"), ("TX", "Texas")]),
+    ("ca6", "6th", &[("KY", "Kentucky"), ("MI", "Michigan"), ("OH", "Ohio"), ("TN", "Tennessee")]),
+    ("ca7", "7th", &[("IL", "Illinois"), ("IN", "Indiana"), ("WI", "Wisconsin")]),
+    (
+        "ca8",
+        "8th",
+        &[
+            ("AR", "Arkansas"),
+            ("IA", "Iowa"),
+            ("MN", "Minnesota"),
+            ("MO", "Missouri"),
+            ("NE", "Nebraska"),
+            ("ND", "North Dakota"),
+            ("SD", "South Dakota"),
+        ],
+    ),
+    (
+        "ca9",
+        "9th",
+        &[
+            ("AK", "Alaska"),
+            ("AZ", "Arizona"),
+            ("CA", "California"),
+            ("HI", "Hawaii"),
+            ("ID", "Idaho"),
+            ("MT", "Montana"),
+            ("NV", "Nevada"),
+            ("OR", "Oregon"),
+            ("WA", "Washington"),
+        ],
+    ),
+    ("ca10", "10th", &[("CO", "Colorado"), ("KS", "Kansas"), ("NM", "New Mexico"), ("OK", "Oklahoma"), ("UT", "Utah"), ("WY", "Wyoming")]),
+    ("ca11", "11th", &[("AL", "Alabama"), ("FL", "Florida"), ("GA", "Georgia")]),
+    ("cadc", "D.C.", &[("DC", "District of Columbia")]),
+    ("cafc", "Federal", &[]),
+];
+
+const ORDINAL_WORDS: &[&str] = &[
+    "First", "Second", "Third", "Fourth", "Fifth", "Sixth", "Seventh", "Eighth", "Ninth", "Tenth", "Eleventh",
+];
+
+impl Default for JurisdictionHierarchy {
+    /// United States federal courts, the thirteen circuits and the states each one covers
+    fn default() -> Self {
+        let mut nodes = vec![JurisdictionNode {
+            id: "us".to_string(),
+            name: "United States".to_string(),
+            aliases: vec![
+                "Federal".to_string(),
+                "U.S.".to_string(),
+                "US".to_string(),
+                "Supreme Court of the United States".to_string(),
+                "SCOTUS".to_string(),
+            ],
+            parent: None,
+            level: JurisdictionLevel::Federal,
+        }];
+
+        for (index, (id, ordinal, states)) in US_CIRCUITS.iter().enumerate() {
+            let mut aliases = vec![format!("{} Cir.", ordinal)];
+            if let Some(word) = ORDINAL_WORDS.get(index) {
+                aliases.push(format!("{} Circuit", word));
+            }
+            nodes.push(JurisdictionNode {
+                id: id.to_string(),
+                name: format!("{} Circuit", ordinal),
+                aliases,
+                parent: Some("us".to_string()),
+                level: JurisdictionLevel::Circuit,
+            });
+            for (code, name) in states.iter() {
+                nodes.push(JurisdictionNode {
+                    id: code.to_lowercase(),
+                    name: name.to_string(),
+                    aliases: vec![code.to_string()],
+                    parent: Some(id.to_string()),
+                    level: JurisdictionLevel::State,
+                });
+            }
+        }
+
+        Self {
+            nodes,
+            boosts: JurisdictionBoosts::default(),
+        }
+    }
+}
+
+impl JurisdictionHierarchy {
+    /// Ids are unique, parents exist and sit at a higher level than their children, which
+    /// also rules out cycles
+    pub fn validate(&self) -> Result<()> {
+        let mut seen = HashSet::new();
+        for node in &self.nodes {
+            if !seen.insert(node.id.as_str()) {
+                return Err(anyhow!("Jurisdiction {} is defined twice", node.id));
+            }
+        }
+        for node in &self.nodes {
+            let Some(parent_id) = &node.parent else {
+                continue;
+            };
+            let parent = self
+                .node(parent_id)
+                .ok_or_else(|| anyhow!("Jurisdiction {} has unknown parent {}", node.id, parent_id))?;
+            if parent.level >= node.level {
+                return Err(anyhow!(
+                    "Jurisdiction {} ({:?}) cannot sit below {} ({:?})",
+                    node.id,
+                    node.level,
+                    parent.id,
+                    parent.level
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn node(&self, id: &str) -> Option<&JurisdictionNode> {
+        self.nodes.iter().find(|n| n.id == id)
+    }
+
+    /// Find the jurisdiction a label names: an exact id, name or alias first, otherwise the
+    /// most specific one whose name appears in it, so a court name like "Court of Appeals of
+    /// California" resolves to California rather than to a circuit
+    pub fn resolve(&self, label: &str) -> Option<&JurisdictionNode> {
+        let label = normalize(label);
+        if label.is_empty() {
+            return None;
+        }
+        let labels = |node: &'_ JurisdictionNode| {
+            std::iter::once(node.id.clone())
+                .chain(std::iter::once(node.name.clone()))
+                .chain(node.aliases.iter().cloned())
+                .map(|l| normalize(&l))
+                .collect::<Vec<_>>()
+        };
+
+        if let Some(node) = self.nodes.iter().find(|n| labels(n).contains(&label)) {
+            return Some(node);
+        }
+
+        let padded = format!(" {} ", label);
+        self.nodes
+            .iter()
+            .filter_map(|node| {
+                labels(node)
+                    .into_iter()
+                    .filter(|l| l.len() >= MIN_CONTAINED_ALIAS && padded.contains(&format!(" {} ", l)))
+                    .map(|l| l.len())
+                    .max()
+                    .map(|length| (node, length))
+            })
+            .max_by_key(|(node, length)| (node.level, *length))
+            .map(|(node, _)| node)
+    }
+
+    /// Ids of the jurisdictions above `id`, nearest first
+    fn ancestors(&self, id: &str) -> Vec<&str> {
+        let mut ancestors = Vec::new();
+        let mut current = self.node(id).and_then(|n| n.parent.as_deref());
+        while let Some(parent) = current {
+            // Guards against hierarchies that skipped validation
+            if ancestors.contains(&parent) || ancestors.len() > self.nodes.len() {
+                break;
+            }
+            ancestors.push(parent);
+            current = self.node(parent).and_then(|n| n.parent.as_deref());
+        }
+        ancestors
+    }
+
+    pub fn relation(&self, query: &str, chunk: &str) -> JurisdictionRelation {
+        let (Some(asked), Some(found)) = (self.resolve(query), self.resolve(chunk)) else {
+            // Jurisdictions outside the hierarchy still match themselves
+            if !normalize(query).is_empty() && normalize(query) == normalize(chunk) {
+                return JurisdictionRelation::Same;
+            }
+            return JurisdictionRelation::Unknown;
+        };
+
+        if asked.id == found.id {
+            JurisdictionRelation::Same
+        } else if self.ancestors(&asked.id).contains(&found.id.as_str()) {
+            JurisdictionRelation::Superior
+        } else if self.ancestors(&found.id).contains(&asked.id.as_str()) {
+            JurisdictionRelation::Subordinate
+        } else if asked.parent.is_some() && asked.parent == found.parent {
+            JurisdictionRelation::Sibling
+        } else {
+            JurisdictionRelation::Unrelated
+        }
+    }
+}
+
+/// The jurisdiction recorded on a chunk, falling back to the court of a filing
+pub fn chunk_jurisdiction(metadata: &HashMap<String, String>) -> Option<&str> {
+    metadata
+        .get("jurisdiction")
+        .or_else(|| metadata.get("court"))
+        .map(String::as_str)
+        .filter(|j| !j.trim().is_empty())
+}
+
+/// Drop the chunks `scope` excludes and reorder the rest by relevance plus the boost for their
+/// relation to `jurisdiction`. Relevance is the rerank score when there is one, otherwise the
+/// chunk's confidence, plus any authority boost; chunks with equal scores keep their order.
+pub fn apply_jurisdiction(
+    chunks: Vec<RAGChunk>,
+    jurisdiction: &str,
+    scope: JurisdictionScope,
+    hierarchy: &JurisdictionHierarchy,
+) -> Vec<RAGChunk> {
+    let mut scored: Vec<(f32, RAGChunk)> = chunks
+        .into_iter()
+        .filter_map(|chunk| {
+            let relation = chunk_jurisdiction(&chunk.metadata)
+                .map(|found| hierarchy.relation(jurisdiction, found))
+                .unwrap_or(JurisdictionRelation::Unknown);
+            if !scope.admits(relation) {
+                return None;
+            }
+            let relevance = chunk.rerank.as_ref().map(|r| r.score).unwrap_or(chunk.confidence)
+                + chunk.authority.as_ref().map_or(0.0, |a| a.total);
+            Some((relevance + hierarchy.boosts.boost(relation), chunk))
+        })
+        .collect();
+    scored.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+    scored.into_iter().map(|(_, chunk)| chunk).collect()
+}
+
+fn normalize(label: &str) -> String {
+    label
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn chunk(id: &str, jurisdiction: Option<&str>, confidence: f32) -> RAGChunk {
+        RAGChunk {
+            id: id.to_string(),
+            document_id: id.to_string(),
+            content: String::new(),
+            embedding: Vec::new(),
+            chunk_index: 0,
+            tokens: 0,
+            overlap: 0,
+            legal_concepts: Vec::new(),
+            cited_authorities: Vec::new(),
+            confidence,
+            temporal_relevance: 1.0,
+            created_at: Utc::now(),
+            metadata: jurisdiction
+                .map(|j| HashMap::from([("jurisdiction".to_string(), j.to_string())]))
+                .unwrap_or_default(),
+            rerank: None,
+            authority: None,
+        }
+    }
+
+    #[test]
+    fn test_resolves_and_relates_us_jurisdictions() {
+        let hierarchy = JurisdictionHierarchy::default();
+        hierarchy.validate().unwrap();
+
+        assert_eq!(hierarchy.resolve("California").unwrap().id, "ca");
+        assert_eq!(hierarchy.resolve("9th Cir.").unwrap().id, "ca9");
+        assert_eq!(hierarchy.resolve("Court of Appeals for the Ninth Circuit").unwrap().id, "ca9");
+        assert_eq!(hierarchy.resolve("Supreme Court of Arkansas").unwrap().id, "ar");
+        assert_eq!(hierarchy.resolve("West Virginia").unwrap().id, "wv");
+        assert_eq!(
+            hierarchy.resolve("UNITED STATES DISTRICT COURT, SOUTHERN DISTRICT OF NEW YORK").unwrap().id,
+            "ny"
+        );
+        assert!(hierarchy.resolve("High Court of Narnia").is_none());
+
+        assert_eq!(hierarchy.relation("California", "Cal"), JurisdictionRelation::Unknown);
+        assert_eq!(hierarchy.relation("California", "CA"), JurisdictionRelation::Same);
+        assert_eq!(hierarchy.relation("California", "Ninth Circuit"), JurisdictionRelation::Superior);
+        assert_eq!(hierarchy.relation("California", "Federal"), JurisdictionRelation::Superior);
+        assert_eq!(hierarchy.relation("California", "Oregon"), JurisdictionRelation::Sibling);
+        assert_eq!(hierarchy.relation("California", "Texas"), JurisdictionRelation::Unrelated);
+        assert_eq!(hierarchy.relation("9th Circuit", "Nevada"), JurisdictionRelation::Subordinate);
+        assert_eq!(hierarchy.relation("Narnia", "narnia"), JurisdictionRelation::Same);
+
+        let mut broken = hierarchy.clone();
+        broken.nodes.push(JurisdictionNode {
+            id: "sf".to_string(),
+            name: "San Francisco".to_string(),
+            aliases: Vec::new(),
+            parent: Some("ca".to_string()),
+            level: JurisdictionLevel::Circuit,
+        });
+        assert!(broken.validate().is_err());
+    }
+
+    #[test]
+    fn test_filters_and_boosts_mixed_jurisdiction_corpus() {
+        let hierarchy = JurisdictionHierarchy::default();
+        let corpus = || {
+            vec![
+                chunk("texas", Some("Texas"), 0.9),
+                chunk("untagged", None, 0.85),
+                chunk("oregon", Some("Oregon"), 0.78),
+                chunk("ninth", Some("9th Cir."), 0.75),
+                chunk("california", Some("California"), 0.7),
+                chunk("fifth", Some("5th Cir."), 0.7),
+            ]
+        };
+        let ids = |chunks: Vec<RAGChunk>| chunks.into_iter().map(|c| c.id).collect::<Vec<_>>();
+
+        // Same state and binding circuit overtake more confident out-of-circuit material
+        assert_eq!(
+            ids(apply_jurisdiction(corpus(), "California", JurisdictionScope::Prefer, &hierarchy)),
+            vec!["california", "ninth", "texas", "oregon", "untagged", "fifth"]
+        );
+        assert_eq!(
+            ids(apply_jurisdiction(corpus(), "California", JurisdictionScope::Binding, &hierarchy)),
+            vec!["california", "ninth"]
+        );
+        assert_eq!(
+            ids(apply_jurisdiction(corpus(), "Ninth Circuit", JurisdictionScope::Within, &hierarchy)),
+            vec!["ninth", "oregon", "california"]
+        );
+        assert_eq!(
+            ids(apply_jurisdiction(corpus(), "CA", JurisdictionScope::Exact, &hierarchy)),
+            vec!["california"]
+        );
+
+        // A filing's court stands in when no jurisdiction was recorded
+        let mut filing = chunk("filing", None, 0.1);
+        filing
+            .metadata
+            .insert("court".to_string(), "SUPERIOR COURT OF THE STATE OF CALIFORNIA".to_string());
+        assert_eq!(
+            ids(apply_jurisdiction(vec![filing], "California", JurisdictionScope::Exact, &hierarchy)),
+            vec!["filing"]
+        );
+    }
+}
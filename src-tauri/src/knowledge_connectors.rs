@@ -0,0 +1,970 @@
+use anyhow::{anyhow, Result};
+use base64::Engine;
+use chrono::{DateTime, Duration, Utc};
+use reqwest::{RequestBuilder, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::intranet_crawler::parse_html;
+use crate::nemotron_rag::{DocumentMetadata, DocumentType, LegalDocument, PrecedentialValue};
+use crate::security::SecurityManager;
+
+/// Knowledge Connectors for BEAR AI
+/// Imports Confluence Cloud spaces and Notion workspaces into RAG collections. Syncs are
+/// incremental on the pages' last-edited time, every chunk carries the page's place in the
+/// space hierarchy, and API tokens are stored encrypted like the CalDAV password.
+pub(crate) const NOTION_API: &str = "https://api.notion.com/v1";
+const NOTION_VERSION: &str = "2022-06-28";
+const MAX_HIERARCHY_DEPTH: usize = 32;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ConnectorKind {
+    Confluence,
+    Notion,
+}
+
+impl ConnectorKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ConnectorKind::Confluence => "confluence",
+            ConnectorKind::Notion => "notion",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KnowledgeSource {
+    pub id: String,
+    pub name: String,
+    pub kind: ConnectorKind,
+    pub base_url: Option<String>, // Confluence site, e.g. https://firm.atlassian.net
+    pub username: Option<String>, // Confluence account email
+    token_encrypted: String,      // base64 of the SecurityManager ciphertext of the API token
+    pub scopes: Vec<String>,      // Confluence space keys, or Notion root page ids (empty: everything shared)
+    pub collection: String,
+    pub refresh_hours: Option<u32>,
+    pub last_synced: Option<DateTime<Utc>>,
+    pub last_edited_cursor: Option<DateTime<Utc>>, // newest edit already imported
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct KnowledgeSourceRequest {
+    pub id: Option<String>,
+    pub name: String,
+    pub kind: ConnectorKind,
+    pub base_url: Option<String>,
+    pub username: Option<String>,
+    pub token: Option<String>, // None keeps the stored token when updating
+    #[serde(default)]
+    pub scopes: Vec<String>,
+    pub collection: Option<String>,
+    pub refresh_hours: Option<u32>,
+}
+
+/// A page as the remote API describes it
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemotePage {
+    pub id: String,
+    pub title: String,
+    pub parent_id: Option<String>,
+    pub ancestors: Vec<(String, String)>, // (id, title), root first, when the API returns them
+    pub space: Option<String>,
+    pub url: Option<String>,
+    pub last_edited: DateTime<Utc>,
+    pub version: Option<i64>,
+    pub body: Option<String>, // plain text, when it came with the listing
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportedPage {
+    pub id: String,
+    pub title: String,
+    pub parent_id: Option<String>,
+    pub hierarchy: Vec<String>, // ancestor titles, root first
+    pub space: Option<String>,
+    pub url: Option<String>,
+    pub last_edited: DateTime<Utc>,
+    pub version: Option<i64>,
+    pub document_id: String,
+    pub content_sha256: String,
+    pub chunks: usize,
+    pub imported_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectorSyncReport {
+    pub source_id: String,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub full: bool,
+    pub changed: usize, // pages the API reported as edited since the cursor
+    pub imported: usize,
+    pub unchanged: usize,
+    pub out_of_scope: usize,
+    pub errors: Vec<String>,
+}
+
+enum PageOutcome {
+    Imported,
+    Unchanged,
+    OutOfScope,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ConnectorState {
+    sources: HashMap<String, KnowledgeSource>,
+    pages: HashMap<String, HashMap<String, ImportedPage>>, // source id -> page id -> page
+    reports: HashMap<String, ConnectorSyncReport>,
+}
+
+fn sha256_hex(text: &str) -> String {
+    format!("{:x}", Sha256::digest(text.as_bytes()))
+}
+
+fn parse_time(value: &Value) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value.as_str()?).ok().map(|t| t.with_timezone(&Utc))
+}
+
+/// Notion ids appear with and without dashes
+fn notion_id(id: &str) -> String {
+    id.trim().replace('-', "").to_lowercase()
+}
+
+/// CQL selecting pages in the given spaces edited since `since`. CQL compares in the
+/// account's time zone at minute precision, so callers pass a cursor with a safety margin.
+pub fn confluence_cql(spaces: &[String], since: Option<DateTime<Utc>>) -> String {
+    let spaces = spaces
+        .iter()
+        .map(|key| format!("\"{}\"", key.replace('"', "")))
+        .collect::<Vec<_>>()
+        .join(",");
+    let mut cql = format!("type = page and space in ({})", spaces);
+    if let Some(since) = since {
+        cql.push_str(&format!(" and lastmodified >= \"{}\"", since.format("%Y/%m/%d %H:%M")));
+    }
+    cql.push_str(" order by lastmodified asc");
+    cql
+}
+
+/// Pages and the absolute next-page URL from a Confluence content search response
+pub fn parse_confluence_search(response: &Value) -> (Vec<RemotePage>, Option<String>) {
+    let base = response["_links"]["base"].as_str().unwrap_or("").trim_end_matches('/');
+    let pages = response["results"]
+        .as_array()
+        .map(|results| {
+            results
+                .iter()
+                .filter_map(|result| {
+                    let id = result["id"].as_str()?.to_string();
+                    let ancestors: Vec<(String, String)> = result["ancestors"]
+                        .as_array()
+                        .map(|a| {
+                            a.iter()
+                                .filter_map(|p| Some((p["id"].as_str()?.to_string(), p["title"].as_str().unwrap_or("").to_string())))
+                                .collect()
+                        })
+                        .unwrap_or_default();
+                    Some(RemotePage {
+                        title: result["title"].as_str().unwrap_or(&id).to_string(),
+                        parent_id: ancestors.last().map(|(id, _)| id.clone()),
+                        ancestors,
+                        space: result["space"]["key"].as_str().map(str::to_string),
+                        url: result["_links"]["webui"].as_str().map(|webui| format!("{}{}", base, webui)),
+                        last_edited: parse_time(&result["version"]["when"]).unwrap_or_else(Utc::now),
+                        version: result["version"]["number"].as_i64(),
+                        body: result["body"]["storage"]["value"].as_str().map(|storage| parse_html(storage).text),
+                        id,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let next = response["_links"]["next"].as_str().map(|next| format!("{}{}", base, next));
+    (pages, next)
+}
+
+fn notion_title(page: &Value) -> Option<String> {
+    let properties = page["properties"].as_object()?;
+    let title = properties.values().find(|p| p["type"] == "title")?;
+    let text: String = title["title"]
+        .as_array()?
+        .iter()
+        .filter_map(|t| t["plain_text"].as_str())
+        .collect();
+    Some(text).filter(|t| !t.trim().is_empty())
+}
+
+/// Pages and the next cursor from a Notion search response
+pub fn parse_notion_search(response: &Value) -> (Vec<RemotePage>, Option<String>) {
+    let pages = response["results"]
+        .as_array()
+        .map(|results| {
+            results
+                .iter()
+                .filter(|r| r["object"] == "page" && r["archived"] != true && r["in_trash"] != true)
+                .filter_map(|result| {
+                    let id = notion_id(result["id"].as_str()?);
+                    let parent = &result["parent"];
+                    let parent_id = parent["type"]
+                        .as_str()
+                        .and_then(|kind| parent[kind].as_str())
+                        .map(notion_id);
+                    Some(RemotePage {
+                        title: notion_title(result).unwrap_or_else(|| "Untitled".to_string()),
+                        parent_id,
+                        ancestors: Vec::new(),
+                        space: None,
+                        url: result["url"].as_str().map(str::to_string),
+                        last_edited: parse_time(&result["last_edited_time"])?,
+                        version: None,
+                        body: None,
+                        id,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let next = if response["has_more"] == true {
+        response["next_cursor"].as_str().map(str::to_string)
+    } else {
+        None
+    };
+    (pages, next)
+}
+
+/// Text lines of a page of Notion blocks, and the blocks whose children hold more text.
+/// Child pages are imported as pages of their own.
+pub fn notion_block_text(response: &Value) -> (Vec<String>, Vec<String>) {
+    let mut lines = Vec::new();
+    let mut nested = Vec::new();
+    let rich_text = |value: &Value| -> String {
+        value
+            .as_array()
+            .map(|parts| parts.iter().filter_map(|p| p["plain_text"].as_str()).collect())
+            .unwrap_or_default()
+    };
+
+    for block in response["results"].as_array().into_iter().flatten() {
+        let Some(kind) = block["type"].as_str() else {
+            continue;
+        };
+        if kind == "child_page" || kind == "child_database" {
+            continue;
+        }
+        let content = &block[kind];
+        let text = match kind {
+            "table_row" => content["cells"]
+                .as_array()
+                .map(|cells| cells.iter().map(rich_text).collect::<Vec<_>>().join(" | "))
+                .unwrap_or_default(),
+            "bulleted_list_item" | "numbered_list_item" => format!("- {}", rich_text(&content["rich_text"])),
+            "to_do" => format!(
+                "[{}] {}",
+                if content["checked"] == true { "x" } else { " " },
+                rich_text(&content["rich_text"])
+            ),
+            _ => rich_text(&content["rich_text"]),
+        };
+        if !text.trim().is_empty() && text.trim() != "-" {
+            lines.push(text);
+        }
+        if block["has_children"] == true {
+            if let Some(id) = block["id"].as_str() {
+                nested.push(id.to_string());
+            }
+        }
+    }
+    (lines, nested)
+}
+
+/// Ancestor titles of a page, root first, from the ids and titles seen so far
+pub fn hierarchy_path(page_id: &str, parents: &HashMap<String, (String, Option<String>)>) -> Vec<String> {
+    let mut path = Vec::new();
+    let mut seen = HashSet::from([page_id.to_string()]);
+    let mut current = parents.get(page_id).and_then(|(_, parent)| parent.clone());
+    while let Some(id) = current {
+        if path.len() >= MAX_HIERARCHY_DEPTH || !seen.insert(id.clone()) {
+            break;
+        }
+        match parents.get(&id) {
+            Some((title, parent)) => {
+                path.push(title.clone());
+                current = parent.clone();
+            }
+            None => break, // a database, the workspace or a page not shared with us
+        }
+    }
+    path.reverse();
+    path
+}
+
+fn in_scope(page_id: &str, roots: &HashSet<String>, parents: &HashMap<String, (String, Option<String>)>) -> bool {
+    if roots.is_empty() || roots.contains(page_id) {
+        return true;
+    }
+    let mut seen = HashSet::new();
+    let mut current = parents.get(page_id).and_then(|(_, parent)| parent.clone());
+    while let Some(id) = current {
+        if roots.contains(&id) {
+            return true;
+        }
+        if !seen.insert(id.clone()) || seen.len() > MAX_HIERARCHY_DEPTH {
+            return false;
+        }
+        current = parents.get(&id).and_then(|(_, parent)| parent.clone());
+    }
+    false
+}
+
+/// Sends a request, waiting out one rate limit response
+async fn send(request: RequestBuilder) -> Result<Value> {
+    let retry = request.try_clone();
+    let mut response = request.send().await?;
+    if response.status() == StatusCode::TOO_MANY_REQUESTS {
+        if let Some(retry) = retry {
+            let wait = response
+                .headers()
+                .get("retry-after")
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.parse::<u64>().ok())
+                .unwrap_or(2)
+                .min(60);
+            tokio::time::sleep(std::time::Duration::from_secs(wait)).await;
+            response = retry.send().await?;
+        }
+    }
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(anyhow!("HTTP {}: {}", status, body.chars().take(200).collect::<String>()));
+    }
+    Ok(response.json().await?)
+}
+
+pub struct KnowledgeConnectors {
+    path: PathBuf,
+    state: Mutex<ConnectorState>,
+    running: Mutex<HashSet<String>>,
+    client: reqwest::Client,
+}
+
+impl KnowledgeConnectors {
+    pub fn new(app_data_dir: &Path) -> Result<Self> {
+        let path = app_data_dir.join("knowledge_connectors.json");
+        let state = if path.exists() {
+            serde_json::from_str(&fs::read_to_string(&path)?)?
+        } else {
+            ConnectorState::default()
+        };
+
+        Ok(Self {
+            path,
+            state: Mutex::new(state),
+            running: Mutex::new(HashSet::new()),
+            client: reqwest::Client::builder()
+                .timeout(std::time::Duration::from_secs(60))
+                .build()?,
+        })
+    }
+
+    fn persist(&self, state: &ConnectorState) -> Result<()> {
+        fs::write(&self.path, serde_json::to_string_pretty(state)?)?;
+        Ok(())
+    }
+
+    /// Add or update a source; changing what it points at starts the next sync from scratch
+    pub fn save_source(&self, request: KnowledgeSourceRequest, security: &SecurityManager) -> Result<KnowledgeSource> {
+        let (base_url, username) = match request.kind {
+            ConnectorKind::Confluence => {
+                let base_url = request
+                    .base_url
+                    .as_deref()
+                    .map(|u| u.trim().trim_end_matches('/').trim_end_matches("/wiki").to_string())
+                    .ok_or_else(|| anyhow!("Confluence needs the site URL"))?;
+                if !base_url.starts_with("https://") {
+                    return Err(anyhow!("Confluence site URL must use https"));
+                }
+                let username = request
+                    .username
+                    .as_deref()
+                    .map(str::trim)
+                    .filter(|u| !u.is_empty())
+                    .ok_or_else(|| anyhow!("Confluence needs the account email the API token belongs to"))?;
+                if request.scopes.is_empty() {
+                    return Err(anyhow!("Choose at least one Confluence space key"));
+                }
+                (Some(base_url), Some(username.to_string()))
+            }
+            ConnectorKind::Notion => (None, None),
+        };
+        let scopes: Vec<String> = request
+            .scopes
+            .iter()
+            .map(|s| match request.kind {
+                ConnectorKind::Confluence => s.trim().to_string(),
+                ConnectorKind::Notion => notion_id(s),
+            })
+            .filter(|s| !s.is_empty())
+            .collect();
+
+        let id = request.id.clone().unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+        let collection = request.collection.clone().unwrap_or_else(|| {
+            let slug: String = request
+                .name
+                .to_lowercase()
+                .chars()
+                .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+                .collect();
+            format!("{}_{}", request.kind.as_str(), slug.trim_matches('_'))
+        });
+        if !collection.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+            return Err(anyhow!("Collection names may only contain letters, digits, '_' and '-'"));
+        }
+        if collection == "legal_chunks" || collection == "legal_documents" {
+            return Err(anyhow!("Imported pages need their own collection, not {}", collection));
+        }
+
+        let mut state = self.state.lock().unwrap();
+        let existing = state.sources.get(&id).cloned();
+        if request.id.is_some() && existing.is_none() {
+            return Err(anyhow!("Knowledge source not found: {}", id));
+        }
+        if existing.as_ref().is_some_and(|s| s.kind != request.kind) {
+            return Err(anyhow!("A source cannot change between Confluence and Notion"));
+        }
+
+        let token_encrypted = match request.token.as_deref().map(str::trim).filter(|t| !t.is_empty()) {
+            Some(token) => base64::engine::general_purpose::STANDARD.encode(security.encrypt_data(token.as_bytes())?),
+            None => existing
+                .as_ref()
+                .map(|s| s.token_encrypted.clone())
+                .ok_or_else(|| anyhow!("An API token is required"))?,
+        };
+
+        let retarget = existing
+            .as_ref()
+            .is_some_and(|s| s.base_url != base_url || s.scopes != scopes || s.collection != collection);
+        if retarget {
+            state.pages.remove(&id);
+        }
+
+        let source = KnowledgeSource {
+            id: id.clone(),
+            name: request.name.trim().to_string(),
+            kind: request.kind,
+            base_url,
+            username,
+            token_encrypted,
+            scopes,
+            collection,
+            refresh_hours: request.refresh_hours.filter(|h| *h > 0),
+            last_synced: existing.as_ref().and_then(|s| s.last_synced),
+            last_edited_cursor: if retarget { None } else { existing.and_then(|s| s.last_edited_cursor) },
+        };
+        state.sources.insert(id, source.clone());
+        self.persist(&state)?;
+        Ok(source)
+    }
+
+    pub fn source(&self, source_id: &str) -> Option<KnowledgeSource> {
+        self.state.lock().unwrap().sources.get(source_id).cloned()
+    }
+
+    pub fn sources(&self) -> Vec<KnowledgeSource> {
+        let mut sources: Vec<KnowledgeSource> = self.state.lock().unwrap().sources.values().cloned().collect();
+        sources.sort_by(|a, b| a.name.cmp(&b.name));
+        sources
+    }
+
+    /// Stop syncing a source; chunks already imported stay in its collection
+    pub fn remove_source(&self, source_id: &str) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        if state.sources.remove(source_id).is_none() {
+            return Err(anyhow!("Knowledge source not found: {}", source_id));
+        }
+        state.pages.remove(source_id);
+        state.reports.remove(source_id);
+        self.persist(&state)
+    }
+
+    pub fn pages(&self, source_id: &str) -> Vec<ImportedPage> {
+        let state = self.state.lock().unwrap();
+        let mut pages: Vec<ImportedPage> = state.pages.get(source_id).map(|p| p.values().cloned().collect()).unwrap_or_default();
+        pages.sort_by(|a, b| a.hierarchy.cmp(&b.hierarchy).then_with(|| a.title.cmp(&b.title)));
+        pages
+    }
+
+    pub fn last_report(&self, source_id: &str) -> Option<ConnectorSyncReport> {
+        self.state.lock().unwrap().reports.get(source_id).cloned()
+    }
+
+    /// Sources with a refresh interval whose last sync is older than it
+    pub fn due_sources(&self, now: DateTime<Utc>) -> Vec<String> {
+        let running = self.running.lock().unwrap();
+        self.state
+            .lock()
+            .unwrap()
+            .sources
+            .values()
+            .filter(|source| !running.contains(&source.id))
+            .filter(|source| match (source.refresh_hours, source.last_synced) {
+                (Some(_), None) => true,
+                (Some(hours), Some(last)) => last + Duration::hours(hours as i64) <= now,
+                (None, _) => false,
+            })
+            .map(|source| source.id.clone())
+            .collect()
+    }
+
+    pub fn token(&self, source_id: &str, security: &SecurityManager) -> Result<String> {
+        let source = self.source(source_id).ok_or_else(|| anyhow!("Knowledge source not found: {}", source_id))?;
+        let ciphertext = base64::engine::general_purpose::STANDARD.decode(&source.token_encrypted)?;
+        Ok(String::from_utf8(security.decrypt_data(&ciphertext)?)?)
+    }
+
+    /// Import pages edited since the last sync (all pages when `full`) and hand each new or
+    /// changed one to `index`, which stores it with the given chunk metadata in the source's
+    /// collection and returns the chunk count
+    pub async fn sync<F, Fut>(&self, source_id: &str, token: &str, full: bool, mut index: F) -> Result<ConnectorSyncReport>
+    where
+        F: FnMut(LegalDocument, HashMap<String, String>) -> Fut,
+        Fut: Future<Output = Result<usize>>,
+    {
+        let source = self.source(source_id).ok_or_else(|| anyhow!("Knowledge source not found: {}", source_id))?;
+        if !self.running.lock().unwrap().insert(source.id.clone()) {
+            return Err(anyhow!("{} is already syncing", source.name));
+        }
+        let _running = scopeguard::guard((), |_| {
+            self.running.lock().unwrap().remove(&source.id);
+        });
+
+        let started_at = Utc::now();
+        let cursor = if full { None } else { source.last_edited_cursor };
+        let mut changed = match source.kind {
+            ConnectorKind::Confluence => self.confluence_changes(&source, token, cursor).await?,
+            ConnectorKind::Notion => self.notion_changes(token, cursor).await?,
+        };
+        changed.sort_by_key(|page| page.last_edited);
+
+        let mut pages = self.state.lock().unwrap().pages.get(source_id).cloned().unwrap_or_default();
+        let mut report = ConnectorSyncReport {
+            source_id: source.id.clone(),
+            started_at,
+            finished_at: started_at,
+            full,
+            changed: changed.len(),
+            imported: 0,
+            unchanged: 0,
+            out_of_scope: 0,
+            errors: Vec::new(),
+        };
+
+        // Titles and parents of everything known, so children edited in this batch see moved or renamed ancestors
+        let mut parents: HashMap<String, (String, Option<String>)> = pages
+            .values()
+            .map(|p| (p.id.clone(), (p.title.clone(), p.parent_id.clone())))
+            .collect();
+        for page in &changed {
+            for (i, (id, title)) in page.ancestors.iter().enumerate() {
+                let parent = if i == 0 { None } else { Some(page.ancestors[i - 1].0.clone()) };
+                parents.insert(id.clone(), (title.clone(), parent));
+            }
+            parents.insert(page.id.clone(), (page.title.clone(), page.parent_id.clone()));
+        }
+        let roots: HashSet<String> = match source.kind {
+            ConnectorKind::Notion => source.scopes.iter().cloned().collect(),
+            ConnectorKind::Confluence => HashSet::new(), // already limited to the spaces by CQL
+        };
+
+        let mut new_cursor = source.last_edited_cursor;
+        let mut failed = false;
+        for page in changed {
+            // Pages are handled oldest first; the cursor stops before the first failure so it is retried
+            let edited = page.last_edited;
+            match self.sync_page(&source, token, page, &roots, &parents, &mut pages, &mut index).await {
+                Ok(PageOutcome::Imported) => report.imported += 1,
+                Ok(PageOutcome::Unchanged) => report.unchanged += 1,
+                Ok(PageOutcome::OutOfScope) => report.out_of_scope += 1,
+                Err(e) => {
+                    report.errors.push(e.to_string());
+                    failed = true;
+                }
+            }
+            if !failed {
+                new_cursor = new_cursor.max(Some(edited));
+            }
+        }
+        report.finished_at = Utc::now();
+
+        let mut state = self.state.lock().unwrap();
+        if let Some(source) = state.sources.get_mut(source_id) {
+            source.last_synced = Some(started_at);
+            source.last_edited_cursor = new_cursor;
+        }
+        state.pages.insert(source_id.to_string(), pages);
+        state.reports.insert(source_id.to_string(), report.clone());
+        self.persist(&state)?;
+        Ok(report)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn sync_page<F, Fut>(
+        &self,
+        source: &KnowledgeSource,
+        token: &str,
+        page: RemotePage,
+        roots: &HashSet<String>,
+        parents: &HashMap<String, (String, Option<String>)>,
+        pages: &mut HashMap<String, ImportedPage>,
+        index: &mut F,
+    ) -> Result<PageOutcome>
+    where
+        F: FnMut(LegalDocument, HashMap<String, String>) -> Fut,
+        Fut: Future<Output = Result<usize>>,
+    {
+        if !in_scope(&page.id, roots, parents) {
+            return Ok(PageOutcome::OutOfScope);
+        }
+        let hierarchy = hierarchy_path(&page.id, parents);
+        let previous = pages.get(&page.id).filter(|p| p.hierarchy == hierarchy && p.title == page.title);
+        if previous.is_some_and(|p| p.version.is_some() && p.version == page.version) {
+            return Ok(PageOutcome::Unchanged);
+        }
+
+        let imported = self
+            .import_page(source, token, &page, &hierarchy, previous, index)
+            .await
+            .map_err(|e| anyhow!("{} ({}): {}", page.title, page.id, e))?;
+        match imported {
+            Some(imported) => {
+                pages.insert(page.id.clone(), imported);
+                Ok(PageOutcome::Imported)
+            }
+            None => {
+                if let Some(known) = pages.get_mut(&page.id) {
+                    known.last_edited = page.last_edited;
+                    known.version = page.version;
+                }
+                Ok(PageOutcome::Unchanged)
+            }
+        }
+    }
+
+    /// Index one page; None when its text and place in the hierarchy are what we already have
+    async fn import_page<F, Fut>(
+        &self,
+        source: &KnowledgeSource,
+        token: &str,
+        page: &RemotePage,
+        hierarchy: &[String],
+        previous: Option<&ImportedPage>,
+        index: &mut F,
+    ) -> Result<Option<ImportedPage>>
+    where
+        F: FnMut(LegalDocument, HashMap<String, String>) -> Fut,
+        Fut: Future<Output = Result<usize>>,
+    {
+        let body = match &page.body {
+            Some(body) => body.clone(),
+            None => self.notion_page_text(token, &page.id).await?,
+        };
+        let content = format!("{}\n\n{}", page.title, body);
+        let content_sha256 = sha256_hex(&content);
+        if previous.is_some_and(|p| p.content_sha256 == content_sha256) {
+            return Ok(None);
+        }
+        let document_id = format!("{}-{}", source.kind.as_str(), page.id);
+
+        let mut metadata = HashMap::new();
+        metadata.insert("source".to_string(), source.kind.as_str().to_string());
+        metadata.insert("knowledge_source".to_string(), source.id.clone());
+        metadata.insert("page_id".to_string(), page.id.clone());
+        metadata.insert("page_title".to_string(), page.title.clone());
+        metadata.insert("hierarchy".to_string(), hierarchy.join(" / "));
+        metadata.insert("last_edited".to_string(), page.last_edited.to_rfc3339());
+        metadata.insert("content_sha256".to_string(), content_sha256.clone());
+        if let Some(parent_id) = &page.parent_id {
+            metadata.insert("parent_id".to_string(), parent_id.clone());
+        }
+        if let Some(space) = &page.space {
+            metadata.insert("space".to_string(), space.clone());
+        }
+        if let Some(url) = &page.url {
+            metadata.insert("page_url".to_string(), url.clone());
+        }
+        if let Some(version) = page.version {
+            metadata.insert("version".to_string(), version.to_string());
+        }
+
+        let mut topics = hierarchy.to_vec();
+        topics.insert(0, source.name.clone());
+        let document = LegalDocument {
+            id: document_id.clone(),
+            title: page.title.clone(),
+            content,
+            jurisdiction: "Internal".to_string(),
+            document_type: DocumentType::Brief,
+            last_updated: page.last_edited,
+            citations: Vec::new(),
+            metadata: DocumentMetadata {
+                court: None,
+                judge: None,
+                parties: Vec::new(),
+                topics,
+                precedential_value: PrecedentialValue::NotPrecedential,
+                confidence: 1.0,
+            },
+        };
+        let chunks = index(document, metadata).await?;
+
+        Ok(Some(ImportedPage {
+            id: page.id.clone(),
+            title: page.title.clone(),
+            parent_id: page.parent_id.clone(),
+            hierarchy: hierarchy.to_vec(),
+            space: page.space.clone(),
+            url: page.url.clone(),
+            last_edited: page.last_edited,
+            version: page.version,
+            document_id,
+            content_sha256,
+            chunks,
+            imported_at: Utc::now(),
+        }))
+    }
+
+    async fn confluence_changes(&self, source: &KnowledgeSource, token: &str, cursor: Option<DateTime<Utc>>) -> Result<Vec<RemotePage>> {
+        let base_url = source.base_url.as_deref().ok_or_else(|| anyhow!("Confluence site URL is missing"))?;
+        let username = source.username.as_deref().unwrap_or_default();
+        // CQL works in the account's time zone; a day of margin covers any offset and the
+        // stored versions filter out pages seen before
+        let cql = confluence_cql(&source.scopes, cursor.map(|c| c - Duration::days(1)));
+
+        let mut pages = Vec::new();
+        let mut request = self
+            .client
+            .get(format!("{}/wiki/rest/api/content/search", base_url))
+            .query(&[("cql", cql.as_str()), ("expand", "body.storage,version,ancestors,space"), ("limit", "50")]);
+        loop {
+            let response = send(request.basic_auth(username, Some(token))).await?;
+            let (batch, next) = parse_confluence_search(&response);
+            pages.extend(batch);
+            match next {
+                Some(next) => request = self.client.get(next),
+                None => break,
+            }
+        }
+        Ok(pages)
+    }
+
+    async fn notion_changes(&self, token: &str, cursor: Option<DateTime<Utc>>) -> Result<Vec<RemotePage>> {
+        let mut pages = Vec::new();
+        let mut start_cursor: Option<String> = None;
+        'search: loop {
+            let mut body = json!({
+                "filter": { "property": "object", "value": "page" },
+                "sort": { "timestamp": "last_edited_time", "direction": "descending" },
+                "page_size": 100,
+            });
+            if let Some(start) = &start_cursor {
+                body["start_cursor"] = json!(start);
+            }
+            let response = send(
+                self.client
+                    .post(format!("{}/search", NOTION_API))
+                    .bearer_auth(token)
+                    .header("Notion-Version", NOTION_VERSION)
+                    .json(&body),
+            )
+            .await?;
+            let (batch, next) = parse_notion_search(&response);
+            for page in batch {
+                // Newest first, so everything after the cursor has been imported already
+                if cursor.is_some_and(|c| page.last_edited <= c) {
+                    break 'search;
+                }
+                pages.push(page);
+            }
+            match next {
+                Some(next) => start_cursor = Some(next),
+                None => break,
+            }
+        }
+        Ok(pages)
+    }
+
+    async fn notion_page_text(&self, token: &str, page_id: &str) -> Result<String> {
+        let mut lines = Vec::new();
+        let mut pending = vec![page_id.to_string()];
+        let mut fetched = 0;
+        while let Some(block_id) = pending.pop() {
+            fetched += 1;
+            if fetched > 200 {
+                log::warn!("Notion page {} has more nested blocks than we import", page_id);
+                break;
+            }
+            let mut start_cursor: Option<String> = None;
+            let mut block_lines = Vec::new();
+            let mut nested = Vec::new();
+            loop {
+                let mut request = self
+                    .client
+                    .get(format!("{}/blocks/{}/children", NOTION_API, block_id))
+                    .bearer_auth(token)
+                    .header("Notion-Version", NOTION_VERSION)
+                    .query(&[("page_size", "100")]);
+                if let Some(start) = &start_cursor {
+                    request = request.query(&[("start_cursor", start.as_str())]);
+                }
+                let response = send(request).await?;
+                let (text, children) = notion_block_text(&response);
+                block_lines.extend(text);
+                nested.extend(children);
+                match response["next_cursor"].as_str().filter(|_| response["has_more"] == true) {
+                    Some(next) => start_cursor = Some(next.to_string()),
+                    None => break,
+                }
+            }
+            lines.extend(block_lines);
+            // Depth first keeps nested text near its parent block
+            pending.extend(nested.into_iter().rev());
+        }
+        Ok(lines.join("\n"))
+    }
+}
+
+pub type KnowledgeConnectorStorage = Arc<KnowledgeConnectors>;
+type SecurityStorage = Arc<Mutex<SecurityManager>>;
+
+#[tauri::command]
+pub async fn connector_save_source(
+    request: KnowledgeSourceRequest,
+    connectors: tauri::State<'_, KnowledgeConnectorStorage>,
+    security: tauri::State<'_, SecurityStorage>,
+) -> Result<KnowledgeSource, String> {
+    let security = security.lock().unwrap();
+    connectors.save_source(request, &security).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn connector_list_sources(
+    connectors: tauri::State<'_, KnowledgeConnectorStorage>,
+) -> Result<Vec<KnowledgeSource>, String> {
+    Ok(connectors.sources())
+}
+
+#[tauri::command]
+pub async fn connector_remove_source(
+    source_id: String,
+    connectors: tauri::State<'_, KnowledgeConnectorStorage>,
+) -> Result<(), String> {
+    connectors.remove_source(&source_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn connector_list_pages(
+    source_id: String,
+    connectors: tauri::State<'_, KnowledgeConnectorStorage>,
+) -> Result<Vec<ImportedPage>, String> {
+    Ok(connectors.pages(&source_id))
+}
+
+#[tauri::command]
+pub async fn connector_last_report(
+    source_id: String,
+    connectors: tauri::State<'_, KnowledgeConnectorStorage>,
+) -> Result<Option<ConnectorSyncReport>, String> {
+    Ok(connectors.last_report(&source_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_confluence_search_and_hierarchy() {
+        let response = json!({
+            "results": [{
+                "id": "300", "type": "page", "title": "Limitation periods",
+                "space": { "key": "LIT" },
+                "version": { "number": 4, "when": "2026-03-02T10:15:00.000Z" },
+                "ancestors": [{ "id": "100", "title": "Litigation" }, { "id": "200", "title": "Procedure" }],
+                "body": { "storage": { "value": "<p>Contract claims: <strong>6 years</strong>.</p><ul><li>Tort: 6 years</li></ul>" } },
+                "_links": { "webui": "/spaces/LIT/pages/300/Limitation+periods" }
+            }],
+            "_links": { "base": "https://firm.atlassian.net/wiki", "next": "/rest/api/content/search?cursor=abc" }
+        });
+        let (pages, next) = parse_confluence_search(&response);
+        assert_eq!(next.as_deref(), Some("https://firm.atlassian.net/wiki/rest/api/content/search?cursor=abc"));
+        let page = &pages[0];
+        assert_eq!(page.parent_id.as_deref(), Some("200"));
+        assert_eq!(page.space.as_deref(), Some("LIT"));
+        assert_eq!(page.version, Some(4));
+        assert_eq!(page.url.as_deref(), Some("https://firm.atlassian.net/wiki/spaces/LIT/pages/300/Limitation+periods"));
+        assert_eq!(page.body.as_deref(), Some("Contract claims: 6 years.\nTort: 6 years"));
+
+        let mut parents = HashMap::new();
+        for (i, (id, title)) in page.ancestors.iter().enumerate() {
+            parents.insert(id.clone(), (title.clone(), (i > 0).then(|| page.ancestors[i - 1].0.clone())));
+        }
+        parents.insert(page.id.clone(), (page.title.clone(), page.parent_id.clone()));
+        assert_eq!(hierarchy_path("300", &parents), vec!["Litigation", "Procedure"]);
+
+        let since = DateTime::parse_from_rfc3339("2026-03-01T09:05:00Z").unwrap().with_timezone(&Utc);
+        assert_eq!(
+            confluence_cql(&["LIT".to_string(), "KM".to_string()], Some(since)),
+            "type = page and space in (\"LIT\",\"KM\") and lastmodified >= \"2026/03/01 09:05\" order by lastmodified asc"
+        );
+    }
+
+    #[test]
+    fn test_notion_pages_blocks_and_scope() {
+        let search = json!({
+            "results": [
+                { "object": "page", "id": "aaaa-1111", "last_edited_time": "2026-04-01T08:00:00.000Z",
+                  "parent": { "type": "workspace", "workspace": true },
+                  "properties": { "title": { "type": "title", "title": [{ "plain_text": "Know-how" }] } } },
+                { "object": "page", "id": "bbbb-2222", "last_edited_time": "2026-04-02T08:00:00.000Z",
+                  "parent": { "type": "page_id", "page_id": "aaaa-1111" }, "url": "https://www.notion.so/bbbb2222",
+                  "properties": { "Name": { "type": "title", "title": [{ "plain_text": "Notice " }, { "plain_text": "clauses" }] } } },
+                { "object": "page", "id": "cccc-3333", "last_edited_time": "2026-04-03T08:00:00.000Z", "archived": true,
+                  "parent": { "type": "page_id", "page_id": "aaaa-1111" }, "properties": {} }
+            ],
+            "has_more": true, "next_cursor": "cursor-2"
+        });
+        let (pages, next) = parse_notion_search(&search);
+        assert_eq!(next.as_deref(), Some("cursor-2"));
+        assert_eq!(pages.len(), 2);
+        assert_eq!(pages[1].id, "bbbb2222");
+        assert_eq!(pages[1].title, "Notice clauses");
+        assert_eq!(pages[1].parent_id.as_deref(), Some("aaaa1111"));
+        assert_eq!(pages[0].parent_id, None);
+
+        let parents: HashMap<String, (String, Option<String>)> =
+            pages.iter().map(|p| (p.id.clone(), (p.title.clone(), p.parent_id.clone()))).collect();
+        assert_eq!(hierarchy_path("bbbb2222", &parents), vec!["Know-how"]);
+        assert!(in_scope("bbbb2222", &HashSet::from(["aaaa1111".to_string()]), &parents));
+        assert!(!in_scope("bbbb2222", &HashSet::from(["dddd4444".to_string()]), &parents));
+
+        let blocks = json!({
+            "results": [
+                { "id": "b1", "type": "heading_2", "has_children": false, "heading_2": { "rich_text": [{ "plain_text": "Notice" }] } },
+                { "id": "b2", "type": "bulleted_list_item", "has_children": true, "bulleted_list_item": { "rich_text": [{ "plain_text": "By email" }] } },
+                { "id": "b3", "type": "to_do", "has_children": false, "to_do": { "rich_text": [{ "plain_text": "Check address" }], "checked": true } },
+                { "id": "b4", "type": "child_page", "has_children": true, "child_page": { "title": "Sub page" } },
+                { "id": "b5", "type": "divider", "has_children": false, "divider": {} }
+            ],
+            "has_more": false, "next_cursor": null
+        });
+        let (lines, nested) = notion_block_text(&blocks);
+        assert_eq!(lines, vec!["Notice", "- By email", "[x] Check address"]);
+        assert_eq!(nested, vec!["b2"]);
+    }
+}
@@ -2,24 +2,97 @@
 //! Enhanced with NVIDIA Nemotron RAG capabilities
 
 // Existing modules that actually exist
+pub mod ai_disclosure;
+pub mod analysis_comparison;
+pub mod analytics_export;
+pub mod audio_evidence;
+pub mod authority_ranking;
+pub mod automation_api;
+pub mod brief_checker;
+pub mod calendar_sync;
+pub mod case_analytics;
+pub mod channel_ingestion;
+pub mod charts;
 pub mod chat_export;
+pub mod chunking_profiles;
+pub mod citation_graph;
+pub mod clause_search;
+pub mod cli;
+pub mod client_bundle;
+pub mod contract_execution;
+pub mod coordination;
+pub mod corporate_structure;
+pub mod corpus_topics;
+pub mod court_filing;
+pub mod deposition_prep;
+pub mod discovery;
+pub mod document_acl;
 pub mod document_analyzer;
+pub mod document_classifier;
+pub mod document_qa;
+pub mod document_repository;
+pub mod document_summary;
+pub mod docx_writer;
+pub mod dpa_checker;
+pub mod dpia;
 pub mod enterprise_management;
+pub mod entity_relations;
+pub mod exhibit_list;
+pub mod financial_statements;
+pub mod glossary;
+pub mod grounded_answer;
+#[cfg(feature = "grpc")]
+pub mod grpc_server;
 pub mod hardware_detection;
 pub mod huggingface;
+pub mod incremental_analysis;
+pub mod ingest_dedup;
+pub mod intranet_crawler;
+pub mod jurisdiction;
+pub mod knowledge_connectors;
+pub mod license_attribution;
 pub mod licensing;
 pub mod llm_commands;
 pub mod llm_manager;
 pub mod local_api;
+pub mod local_vector_store;
+pub mod locale_formats;
+pub mod matter_consistency;
+pub mod matter_graph;
+pub mod matter_intake;
+pub mod matters;
 pub mod mcp_server;
 pub mod model_commands;
 pub mod mollie_integration;
+pub mod multi_hop;
 pub mod nemotron_rag;
+pub mod network_attestation;
+pub mod object_storage;
+pub mod ocr_ingest;
 pub mod ocr_processor;
+pub mod output_language;
+pub mod party_registry;
 pub mod performance_tracker;
+pub mod provenance;
+pub mod reanalysis;
+pub mod regulatory_monitor;
+pub mod request_tracing;
+pub mod research_memo;
+pub mod retrieval_scope;
+pub mod review_queue;
+pub mod sanctions_screening;
 pub mod pii_detector;
 pub mod security;
+pub mod session_summary;
+pub mod speech_to_text;
 pub mod stripe_integration_v2;
+pub mod table_of_authorities;
+pub mod text_processing;
+pub mod timekeeping;
+pub mod webhooks;
+pub mod workflow_budget;
+pub mod workflow_packages;
+pub mod workspace_stats;
 
 use tauri::State;
 use std::sync::Arc;
@@ -27,6 +100,9 @@ use std::sync::Arc;
 // Re-export core types
 pub use nemotron_rag::{NemotronRAG, NemotronConfig};
 
+/// How many times the wanted number of passages a scoped retrieval fetches before filtering
+pub(crate) const SCOPED_OVERFETCH: usize = 5;
+
 /// Application state with RAG system
 #[derive(Clone)]
 pub struct AppState {
@@ -63,6 +139,7 @@ pub async fn initialize_rag_system(
 /// Process a legal document
 pub async fn process_legal_document(
     document: String,
+    document_type: Option<nemotron_rag::DocumentType>,
     state: State<'_, Arc<tokio::sync::RwLock<AppState>>>,
 ) -> Result<String, String> {
     let app_state = state.read().await;
@@ -75,7 +152,7 @@ pub async fn process_legal_document(
         title: "Document".to_string(),
         content: document,
         jurisdiction: "General".to_string(),
-        document_type: nemotron_rag::DocumentType::Brief,
+        document_type: document_type.unwrap_or(nemotron_rag::DocumentType::Brief),
         last_updated: chrono::Utc::now(),
         citations: Vec::new(),
         metadata: nemotron_rag::DocumentMetadata {
@@ -88,9 +165,7 @@ pub async fn process_legal_document(
         },
     };
 
-    let mut rag_mut = rag_system.clone();
-    Arc::get_mut(&mut rag_mut)
-        .ok_or_else(|| "Cannot get mutable reference to RAG system".to_string())?
+    rag_system
         .process_document(legal_doc)
         .await
         .map_err(|e| format!("Failed to process document: {}", e))?;
@@ -98,9 +173,46 @@ pub async fn process_legal_document(
     Ok("Document processed successfully".to_string())
 }
 
-/// Retrieve legal information
+/// Index a document into a dedicated collection with per-chunk metadata; returns the chunk count
+pub async fn index_document_into(
+    collection: &str,
+    document: nemotron_rag::LegalDocument,
+    metadata: std::collections::HashMap<String, String>,
+    state: &tokio::sync::RwLock<AppState>,
+) -> Result<usize, String> {
+    let app_state = state.read().await;
+    let rag_system = app_state.rag_system.as_ref()
+        .ok_or_else(|| "RAG system not initialized".to_string())?;
+
+    let chunks = rag_system
+        .process_document_into(collection, document, metadata)
+        .await
+        .map_err(|e| format!("Failed to process document: {}", e))?;
+
+    Ok(chunks.len())
+}
+
+/// Search a single collection, e.g. a crawled intranet
+pub async fn search_collection(
+    collection: String,
+    query: String,
+    max_results: usize,
+    state: State<'_, Arc<tokio::sync::RwLock<AppState>>>,
+) -> Result<Vec<nemotron_rag::RAGChunk>, String> {
+    let app_state = state.read().await;
+    let rag_system = app_state.rag_system.as_ref()
+        .ok_or_else(|| "RAG system not initialized".to_string())?;
+
+    rag_system.search_collection(&collection, &query, max_results)
+        .await
+        .map_err(|e| format!("Failed to search {}: {}", collection, e))
+}
+
+/// Retrieve legal information; with `latency_budget_ms`, optional stages are skipped to answer
+/// within it, e.g. for search-as-you-type
 pub async fn retrieve_legal_info(
     query: String,
+    latency_budget_ms: Option<u64>,
     state: State<'_, Arc<tokio::sync::RwLock<AppState>>>,
 ) -> Result<nemotron_rag::RetrievalResult, String> {
     let app_state = state.read().await;
@@ -117,6 +229,10 @@ pub async fn retrieve_legal_info(
         require_citations: None,
         max_results: None,
         confidence_threshold: None,
+        court: None,
+        docket_number: None,
+        jurisdiction_scope: jurisdiction::JurisdictionScope::Prefer,
+        latency_budget_ms,
     };
 
     rag_system.retrieve(context)
@@ -124,11 +240,59 @@ pub async fn retrieve_legal_info(
         .map_err(|e| format!("Failed to retrieve information: {}", e))
 }
 
-/// Generate an agentic response
-pub async fn generate_agentic_response(
+/// Retrieve up to `max_results` passages from the indexed statutes and regulations `visible`
+/// lets through. More are fetched than needed, so passages the reader may not see do not crowd
+/// out the ones they may.
+pub async fn retrieve_legislation(
     query: String,
+    max_results: usize,
     state: State<'_, Arc<tokio::sync::RwLock<AppState>>>,
-) -> Result<String, String> {
+    visible: impl Fn(&nemotron_rag::RAGChunk) -> bool,
+) -> Result<nemotron_rag::RetrievalResult, String> {
+    let app_state = state.read().await;
+    let rag_system = app_state.rag_system.as_ref()
+        .ok_or_else(|| "RAG system not initialized".to_string())?;
+
+    let context = nemotron_rag::QueryContext {
+        query,
+        jurisdiction: None,
+        document_types: Some(vec![nemotron_rag::DocumentType::Regulation, nemotron_rag::DocumentType::Statute]),
+        time_range: None,
+        precedential_only: None,
+        require_citations: None,
+        max_results: Some(max_results * SCOPED_OVERFETCH),
+        confidence_threshold: None,
+        court: None,
+        docket_number: None,
+        jurisdiction_scope: jurisdiction::JurisdictionScope::Prefer,
+        latency_budget_ms: None,
+    };
+
+    let mut result = rag_system.retrieve(context)
+        .await
+        .map_err(|e| format!("Failed to retrieve legislation: {}", e))?;
+    result.chunks.retain(|c| visible(c));
+    nemotron_rag::collapse_duplicates(&mut result.chunks);
+    result.chunks.truncate(max_results);
+    let chunks = &result.chunks;
+    result.documents.retain(|d| chunks.iter().any(|c| c.document_id == d.id));
+    Ok(result)
+}
+
+/// Answer `query` from the chunks `visible` lets through (document access lists), with `generate`
+/// completing the prompt with `model`; the answer cites its chunks inline, and is withheld as
+/// insufficient evidence when they do not support it
+pub async fn generate_agentic_response<G, F>(
+    query: String,
+    model: String,
+    state: State<'_, Arc<tokio::sync::RwLock<AppState>>>,
+    visible: impl Fn(&nemotron_rag::RAGChunk) -> bool,
+    generate: G,
+) -> Result<nemotron_rag::VerifiedAnswer<grounded_answer::GroundedAnswer>, String>
+where
+    G: FnOnce(String) -> F,
+    F: std::future::Future<Output = anyhow::Result<String>>,
+{
     let app_state = state.read().await;
     let rag_system = app_state.rag_system.as_ref()
         .ok_or_else(|| "RAG system not initialized".to_string())?;
@@ -143,39 +307,59 @@ pub async fn generate_agentic_response(
         require_citations: None,
         max_results: None,
         confidence_threshold: None,
+        court: None,
+        docket_number: None,
+        jurisdiction_scope: jurisdiction::JurisdictionScope::Prefer,
+        latency_budget_ms: None,
     };
 
-    let retrieval_results = rag_system.retrieve(context)
+    let mut retrieval_results = rag_system.retrieve(context)
         .await
         .map_err(|e| format!("Failed to retrieve information: {}", e))?;
-
-    // Generate response based on retrieval
-    let context = retrieval_results.chunks.iter()
-        .map(|c| c.content.clone())
-        .collect::<Vec<_>>()
-        .join("\n\n");
-
-    Ok(format!("Based on the retrieved context:\n\n{}\n\nResponse: {}", context, query))
+    retrieval_results.chunks.retain(|c| visible(c));
+    nemotron_rag::collapse_duplicates(&mut retrieval_results.chunks);
+    retrieval_results.chunks.truncate(grounded_answer::MAX_SOURCES);
+
+    let answer = grounded_answer::generate_grounded_answer(
+        &query,
+        &model,
+        retrieval_results.chunks.clone(),
+        &retrieval_results.documents,
+        generate,
+    )
+    .await
+    .map_err(|e| format!("Failed to generate answer: {}", e))?;
+
+    // Only answers the retrieved chunks bear out are returned
+    let text = answer.answer.clone();
+    Ok(rag_system.verify_answer(answer, &text, &retrieval_results, None))
 }
 
-/// Perform multi-hop reasoning
-pub async fn multi_hop_reasoning(
+/// Answer `query` in retrieval hops over the chunks `visible` lets through, with `generate`
+/// completing each hop's prompt with `model`; returns the trace of every hop
+pub async fn multi_hop_reasoning<G, F>(
     query: String,
     max_hops: Option<usize>,
+    model: String,
     state: State<'_, Arc<tokio::sync::RwLock<AppState>>>,
-) -> Result<String, String> {
+    visible: impl Fn(&nemotron_rag::RAGChunk) -> bool,
+    generate: G,
+) -> Result<multi_hop::ReasoningTrace, String>
+where
+    G: Fn(String) -> F,
+    F: std::future::Future<Output = anyhow::Result<String>>,
+{
     let app_state = state.read().await;
     let rag_system = app_state.rag_system.as_ref()
         .ok_or_else(|| "RAG system not initialized".to_string())?;
+    let visible = &visible;
+    // Titles and verified citations of what was retrieved, for the trace's sources
+    let titles = &std::sync::Mutex::new(std::collections::HashMap::new());
+    let verified = &std::sync::Mutex::new(std::collections::HashSet::new());
 
-    let mut current_query = query.clone();
-    let mut all_results = Vec::new();
-    let hops = max_hops.unwrap_or(3);
-
-    for hop in 0..hops {
-        // Create QueryContext from the current query
+    let retrieve = |hop_query: String| async move {
         let context = nemotron_rag::QueryContext {
-            query: current_query.clone(),
+            query: hop_query,
             jurisdiction: None,
             document_types: None,
             time_range: None,
@@ -183,24 +367,36 @@ pub async fn multi_hop_reasoning(
             require_citations: None,
             max_results: None,
             confidence_threshold: None,
+            court: None,
+            docket_number: None,
+            jurisdiction_scope: jurisdiction::JurisdictionScope::Prefer,
+            latency_budget_ms: None,
         };
+        let mut results = rag_system.retrieve(context).await?;
+        results.chunks.retain(visible);
+        nemotron_rag::collapse_duplicates(&mut results.chunks);
+        titles.lock().unwrap().extend(results.documents.into_iter().map(|d| (d.id, d.title)));
+        verified.lock().unwrap().extend(results.citations.into_iter().filter(|c| c.verified).map(|c| c.text));
+        Ok(results.chunks)
+    };
 
-        let results = rag_system.retrieve(context)
-            .await
-            .map_err(|e| format!("Failed at hop {}: {}", hop, e))?;
-
-        if results.chunks.is_empty() {
-            break;
-        }
-
-        all_results.push(results.clone());
-
-        // Generate next query based on results
-        current_query = format!("Based on: {}, what about: {}",
-            results.chunks[0].content, query);
+    let mut trace = multi_hop::reason(
+        &query,
+        max_hops.unwrap_or(multi_hop::DEFAULT_MAX_HOPS),
+        &model,
+        retrieve,
+        generate,
+    )
+    .await
+    .map_err(|e| format!("Multi-hop reasoning failed: {}", e))?;
+
+    let titles = titles.lock().unwrap();
+    let verified = verified.lock().unwrap();
+    for source in trace.hops.iter_mut().flat_map(|hop| hop.sources.iter_mut()) {
+        source.title = titles.get(&source.document_id).cloned();
+        source.verified_citations = source.cited_authorities.iter().filter(|c| verified.contains(*c)).cloned().collect();
     }
-
-    Ok(format!("Multi-hop reasoning completed with {} hops", all_results.len()))
+    Ok(trace)
 }
 
 /// Get RAG health status
@@ -216,6 +412,192 @@ pub async fn get_rag_health(
         .map_err(|e| format!("Failed to get health status: {}", e))
 }
 
+/// The local vector store with the embedding model its collections are expected to hold
+fn local_store(app_state: &AppState) -> Result<(Arc<local_vector_store::LocalVectorStore>, String), String> {
+    let rag_system = app_state.rag_system.as_ref()
+        .ok_or_else(|| "RAG system not initialized".to_string())?;
+    let store = rag_system.local_vector_store()
+        .ok_or_else(|| "The RAG system is not using the local vector store".to_string())?;
+    Ok((store, rag_system.config().embedding_model.clone()))
+}
+
+/// Report the size of the local vector store and of each collection's index
+pub async fn get_vector_store_stats(
+    state: State<'_, Arc<tokio::sync::RwLock<AppState>>>,
+) -> Result<local_vector_store::VectorStoreStats, String> {
+    let (store, _) = local_store(&*state.read().await)?;
+    tokio::task::spawn_blocking(move || store.stats())
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| format!("Failed to read vector store stats: {}", e))
+}
+
+/// Rebuild the HNSW graph of one collection, or of all collections when none is given
+pub async fn rebuild_vector_index(
+    collection: Option<String>,
+    state: State<'_, Arc<tokio::sync::RwLock<AppState>>>,
+) -> Result<local_vector_store::VectorStoreStats, String> {
+    let (store, model) = local_store(&*state.read().await)?;
+    tokio::task::spawn_blocking(move || store.rebuild(collection.as_deref(), &model))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| format!("Failed to rebuild vector index: {}", e))
+}
+
+/// Drop replaced chunks from the local vector store and reclaim disk space
+pub async fn compact_vector_store(
+    state: State<'_, Arc<tokio::sync::RwLock<AppState>>>,
+) -> Result<local_vector_store::VectorStoreStats, String> {
+    let (store, model) = local_store(&*state.read().await)?;
+    tokio::task::spawn_blocking(move || store.compact(&model))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| format!("Failed to compact vector store: {}", e))
+}
+
+/// Remove documents indexed as duplicates of others, in one collection or all, from the vector
+/// store, the deduplication index and everything else indexing recorded for them; returns the
+/// documents purged. Only duplicates whose original is still indexed and that `purge` lets
+/// through are removed.
+pub async fn purge_duplicate_documents(
+    collection: Option<String>,
+    state: State<'_, Arc<tokio::sync::RwLock<AppState>>>,
+    purge: impl Fn(&ingest_dedup::DuplicateDocument) -> bool,
+) -> Result<Vec<ingest_dedup::DuplicateDocument>, String> {
+    let index = ingest_dedup::dedup_index()
+        .ok_or_else(|| "Deduplication index not initialized".to_string())?;
+    let app_state = state.read().await;
+    let rag_system = app_state.rag_system.as_ref()
+        .ok_or_else(|| "RAG system not initialized".to_string())?;
+
+    let mut duplicates = index.duplicates(collection.as_deref()).map_err(|e| e.to_string())?;
+    duplicates.retain(|duplicate| duplicate.original_title.is_some() && purge(duplicate));
+    for duplicate in &duplicates {
+        forget_indexed_document(rag_system, &index, &duplicate.collection, &duplicate.document_id, duplicate.chunk_count)
+            .await
+            .map_err(|e| format!("Failed to purge {}: {}", duplicate.title, e))?;
+    }
+    Ok(duplicates)
+}
+
+/// Delete an indexed document from the vector store, the deduplication index and everything else
+/// indexing recorded for it, such as the case corpus; returns the fingerprint it was indexed
+/// with. Copies of the document stay indexed, relinked to the newest of them.
+pub async fn delete_indexed_document(
+    collection: &str,
+    document_id: &str,
+    state: State<'_, Arc<tokio::sync::RwLock<AppState>>>,
+) -> Result<ingest_dedup::DocumentFingerprint, String> {
+    let index = ingest_dedup::dedup_index()
+        .ok_or_else(|| "Deduplication index not initialized".to_string())?;
+    let app_state = state.read().await;
+    let rag_system = app_state.rag_system.as_ref()
+        .ok_or_else(|| "RAG system not initialized".to_string())?;
+
+    let fingerprint = index.fingerprint(collection, document_id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Document {} is not indexed in {}", document_id, collection))?;
+    forget_indexed_document(rag_system, &index, collection, document_id, fingerprint.chunk_count)
+        .await
+        .map_err(|e| format!("Failed to delete {}: {}", fingerprint.title, e))?;
+    Ok(fingerprint)
+}
+
+/// Delete the documents indexed more than `retention_days` ago, optionally in one collection, as
+/// `delete_indexed_document` would; returns their fingerprints
+pub async fn purge_expired_documents(
+    retention_days: u32,
+    collection: Option<String>,
+    state: State<'_, Arc<tokio::sync::RwLock<AppState>>>,
+) -> Result<Vec<ingest_dedup::DocumentFingerprint>, String> {
+    let index = ingest_dedup::dedup_index()
+        .ok_or_else(|| "Deduplication index not initialized".to_string())?;
+    let app_state = state.read().await;
+    let rag_system = app_state.rag_system.as_ref()
+        .ok_or_else(|| "RAG system not initialized".to_string())?;
+
+    let cutoff = chrono::Utc::now() - chrono::Duration::days(i64::from(retention_days));
+    let mut expired = index.indexed_documents(collection.as_deref()).map_err(|e| e.to_string())?;
+    expired.retain(|document| {
+        chrono::DateTime::parse_from_rfc3339(&document.indexed_at).is_ok_and(|indexed_at| indexed_at < cutoff)
+    });
+    for document in &expired {
+        forget_indexed_document(rag_system, &index, &document.collection, &document.document_id, document.chunk_count)
+            .await
+            .map_err(|e| format!("Failed to purge {}: {}", document.title, e))?;
+    }
+    Ok(expired)
+}
+
+async fn forget_indexed_document(
+    rag_system: &NemotronRAG,
+    index: &ingest_dedup::DedupIndex,
+    collection: &str,
+    document_id: &str,
+    chunk_count: usize,
+) -> anyhow::Result<()> {
+    rag_system.delete_document_chunks(collection, document_id, chunk_count).await?;
+    rag_system.forget_document(document_id).await?;
+    index.remove(collection, document_id)
+}
+
+/// Copy an existing Qdrant index into the local vector store
+pub async fn migrate_vector_store(
+    qdrant_url: Option<String>,
+    collections: Option<Vec<String>>,
+    state: State<'_, Arc<tokio::sync::RwLock<AppState>>>,
+) -> Result<Vec<nemotron_rag::MigratedCollection>, String> {
+    let app_state = state.read().await;
+    let rag_system = app_state.rag_system.as_ref()
+        .ok_or_else(|| "RAG system not initialized".to_string())?;
+    let qdrant_url = qdrant_url
+        .or_else(|| std::env::var("VECTOR_DB_URL").ok())
+        .unwrap_or_else(|| "http://localhost:6333".to_string());
+    let collections = collections
+        .unwrap_or_else(|| vec!["legal_chunks".to_string(), "legal_documents".to_string()]);
+
+    rag_system.migrate_from_qdrant(&qdrant_url, &collections)
+        .await
+        .map_err(|e| format!("Failed to migrate vector store: {}", e))
+}
+
+/// Indexed documents that cite a document, given by id or by a citation of it
+pub async fn get_citing_documents(
+    document: String,
+    state: State<'_, Arc<tokio::sync::RwLock<AppState>>>,
+) -> Result<Vec<citation_graph::CitationLink>, String> {
+    let app_state = state.read().await;
+    let rag_system = app_state.rag_system.as_ref()
+        .ok_or_else(|| "RAG system not initialized".to_string())?;
+    let links = rag_system.citation_graph().read().await.citing_documents(&document);
+    Ok(links)
+}
+
+/// Authorities an indexed document cites, linked to the indexed documents they refer to
+pub async fn get_cited_by(
+    document: String,
+    state: State<'_, Arc<tokio::sync::RwLock<AppState>>>,
+) -> Result<Vec<citation_graph::CitationLink>, String> {
+    let app_state = state.read().await;
+    let rag_system = app_state.rag_system.as_ref()
+        .ok_or_else(|| "RAG system not initialized".to_string())?;
+    let links = rag_system.citation_graph().read().await.cited_by(&document);
+    Ok(links)
+}
+
+/// Shortest chain of citations between two documents or authorities; None when they are not connected
+pub async fn shortest_citation_path(
+    from: String,
+    to: String,
+    state: State<'_, Arc<tokio::sync::RwLock<AppState>>>,
+) -> Result<Option<citation_graph::CitationPath>, String> {
+    let app_state = state.read().await;
+    let rag_system = app_state.rag_system.as_ref()
+        .ok_or_else(|| "RAG system not initialized".to_string())?;
+    let path = rag_system.citation_graph().read().await.shortest_path(&from, &to);
+    Ok(path)
+}
+
 /// Create default Nemotron configuration
 pub fn create_default_nemotron_config() -> nemotron_rag::NemotronConfig {
     nemotron_rag::NemotronConfig {
@@ -236,7 +618,82 @@ pub fn create_default_nemotron_config() -> nemotron_rag::NemotronConfig {
         enable_gpu_acceleration: true,
         cache_ttl: 3600,
         lance_db_path: None,
+        local_store_path: None,
+        citation_graph_path: None,
         max_results: 25,
         embedding_dimension: 768,
+        embedding_batch_size: 32,
+        embedding_parallel_requests: 2,
+        embedding_backend: nemotron_rag::EmbeddingBackend::NemoCloud,
+        local_embedding_url: None,
+        rerank_backend: nemotron_rag::RerankBackend::Disabled,
+        local_reranker_url: None,
+        jurisdictions: None,
+        authority_weights: authority_ranking::AuthorityWeights::default(),
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::future::BoxFuture;
+
+    /// Embeds every text as the same unit vector, so indexing needs no model server
+    struct FixedEmbedder;
+
+    impl nemotron_rag::EmbeddingProvider for FixedEmbedder {
+        fn name(&self) -> &'static str {
+            "fixed"
+        }
+
+        fn is_local(&self) -> bool {
+            true
+        }
+
+        fn embed<'a>(&'a self, _text: &'a str) -> BoxFuture<'a, anyhow::Result<Vec<f32>>> {
+            Box::pin(async { Ok(vec![1.0, 0.0, 0.0, 0.0]) })
+        }
+
+        fn embed_batch<'a>(&'a self, texts: &'a [String]) -> BoxFuture<'a, anyhow::Result<Vec<Vec<f32>>>> {
+            Box::pin(async move { Ok(texts.iter().map(|_| vec![1.0, 0.0, 0.0, 0.0]).collect()) })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_index_document_into_a_collection_of_the_shared_rag_system() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = create_default_nemotron_config();
+        config.vector_db_type = nemotron_rag::VectorDbType::SqliteLocal;
+        config.local_store_path = Some(dir.path().join("vector_store.sqlite3").to_string_lossy().into_owned());
+        config.redis_url = None;
+        config.embedding_dimension = 4;
+        let mut rag = NemotronRAG::new(config)
+            .await
+            .unwrap()
+            .with_embedding_provider(Arc::new(FixedEmbedder));
+        rag.initialize().await.unwrap();
+
+        // The state holds the system behind an Arc, as the app does, and the command reads it
+        // through a clone, so indexing must not need the only reference
+        let state = tokio::sync::RwLock::new(AppState {
+            rag_system: Some(Arc::new(rag)),
+        });
+        let held = state.read().await.rag_system.clone();
+
+        let path = dir.path().join("handbook.txt");
+        let request: nemotron_rag::BulkIngestRequest =
+            serde_json::from_value(serde_json::json!({ "directory": dir.path() })).unwrap();
+        let document = nemotron_rag::legal_document_from_file(
+            &path,
+            "Employees accrue paid leave monthly. Leave requests go to the practice manager.".to_string(),
+            &request,
+        );
+        let metadata = std::collections::HashMap::from([("source_url".to_string(), "https://intranet/handbook".to_string())]);
+
+        let chunks = index_document_into("intranet", document, metadata, &state).await.unwrap();
+        assert!(chunks > 0);
+
+        let found = held.unwrap().search_collection("intranet", "paid leave", 5).await.unwrap();
+        assert_eq!(found.len(), chunks);
+        assert_eq!(found[0].metadata["source_url"], "https://intranet/handbook");
+    }
+}
@@ -0,0 +1,659 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use printpdf::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::llm_manager::LLMManager;
+
+/// Third-Party License Attribution for BEAR AI
+/// Builds the attribution document procurement reviews ask for from the CycloneDX SBOM that
+/// build.rs embeds (shipped Rust dependencies for this build's target and features, with their
+/// license files), plus a summary of the licenses of the installed and catalogued models.
+/// Licenses that need a closer look (copyleft, unknown, non-commercial models) are called out.
+const SBOM_JSON: &str = include_str!(concat!(env!("OUT_DIR"), "/sbom.json"));
+const LICENSE_TEXTS_JSON: &str = include_str!(concat!(env!("OUT_DIR"), "/license-texts.json"));
+const NO_ASSERTION: &str = "NOASSERTION";
+const COPYLEFT_MARKERS: [&str; 6] = ["GPL", "MPL", "EPL", "CDDL", "OSL", "EUPL"];
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AttributionFormat {
+    Pdf,
+    Markdown,
+    CycloneDx, // the embedded SBOM as built
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DependencyAttribution {
+    pub name: String,
+    pub version: String,
+    pub license: String, // SPDX expression, or NOASSERTION
+    pub author: Option<String>,
+    pub copyright: Vec<String>,
+    pub repository: Option<String>,
+    pub license_texts: Vec<String>, // ids into AttributionReport::license_texts
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LicenseText {
+    pub id: String,
+    pub file: String,
+    pub text: String,
+    pub used_by: Vec<String>, // "name version"
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LicenseTally {
+    pub license: String,
+    pub components: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelLicenseSummary {
+    pub model_id: String,
+    pub name: String,
+    pub installed: bool,
+    pub license: Option<String>,
+    pub commercial_use_allowed: bool,
+    pub warning: Option<String>,
+    pub source: Option<String>, // Hugging Face repo or download URL
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttributionReport {
+    pub generated_at: DateTime<Utc>,
+    pub application: String,
+    pub version: String,
+    pub dependencies: Vec<DependencyAttribution>,
+    pub license_tally: Vec<LicenseTally>,
+    pub license_texts: Vec<LicenseText>,
+    pub models: Vec<ModelLicenseSummary>,
+    pub review_notes: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttributionExport {
+    pub path: String,
+    pub format: AttributionFormat,
+    pub dependencies: usize,
+    pub models: usize,
+    pub review_notes: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct CycloneDxBom {
+    metadata: CdxMetadata,
+    #[serde(default)]
+    components: Vec<CdxComponent>,
+}
+
+#[derive(Deserialize)]
+struct CdxMetadata {
+    component: CdxApplication,
+    #[serde(default)]
+    properties: Vec<CdxProperty>,
+}
+
+#[derive(Deserialize)]
+struct CdxApplication {
+    name: String,
+    version: String,
+}
+
+#[derive(Deserialize)]
+struct CdxComponent {
+    name: String,
+    version: String,
+    author: Option<String>,
+    copyright: Option<String>,
+    #[serde(default)]
+    licenses: Vec<CdxLicense>,
+    #[serde(default, rename = "externalReferences")]
+    external_references: Vec<CdxReference>,
+    #[serde(default)]
+    properties: Vec<CdxProperty>,
+}
+
+#[derive(Deserialize)]
+struct CdxLicense {
+    expression: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct CdxReference {
+    #[serde(rename = "type")]
+    kind: String,
+    url: String,
+}
+
+#[derive(Deserialize)]
+struct CdxProperty {
+    name: String,
+    value: String,
+}
+
+#[derive(Deserialize)]
+struct StoredLicenseText {
+    file: String,
+    text: String,
+}
+
+fn is_copyleft(license: &str) -> bool {
+    // Dual-licensed components can be taken under the permissive option
+    license
+        .split(" OR ")
+        .flat_map(|option| option.split('/'))
+        .all(|option| COPYLEFT_MARKERS.iter().any(|marker| option.to_uppercase().contains(marker)))
+}
+
+/// Assemble the report from an SBOM and its license texts
+pub fn build_report(
+    sbom_json: &str,
+    texts_json: &str,
+    models: Vec<ModelLicenseSummary>,
+    now: DateTime<Utc>,
+) -> Result<AttributionReport> {
+    let sbom: CycloneDxBom = serde_json::from_str(sbom_json).context("Embedded SBOM is not valid CycloneDX")?;
+    let mut stored: HashMap<String, StoredLicenseText> =
+        serde_json::from_str(texts_json).context("Embedded license texts are invalid")?;
+
+    let mut review_notes: Vec<String> = sbom
+        .metadata
+        .properties
+        .iter()
+        .filter(|p| p.name == "bear-ai:sbom-error")
+        .map(|p| format!("The SBOM could not be generated at build time: {}", p.value))
+        .collect();
+
+    let mut dependencies: Vec<DependencyAttribution> = sbom
+        .components
+        .into_iter()
+        .map(|component| DependencyAttribution {
+            license: component
+                .licenses
+                .into_iter()
+                .find_map(|l| l.expression)
+                .unwrap_or_else(|| NO_ASSERTION.to_string()),
+            copyright: component.copyright.map(|c| c.lines().map(str::to_string).collect()).unwrap_or_default(),
+            repository: component.external_references.into_iter().find(|r| r.kind == "vcs").map(|r| r.url),
+            license_texts: component
+                .properties
+                .into_iter()
+                .filter(|p| p.name == "bear-ai:license-text")
+                .map(|p| p.value)
+                .collect(),
+            name: component.name,
+            version: component.version,
+            author: component.author.filter(|a| !a.is_empty()),
+        })
+        .collect();
+    dependencies.sort_by(|a, b| (a.name.to_lowercase(), &a.version).cmp(&(b.name.to_lowercase(), &b.version)));
+
+    let mut tally: BTreeMap<&str, usize> = BTreeMap::new();
+    let mut used_by: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+    for dependency in &dependencies {
+        *tally.entry(&dependency.license).or_default() += 1;
+        for id in &dependency.license_texts {
+            used_by
+                .entry(id)
+                .or_default()
+                .push(format!("{} {}", dependency.name, dependency.version));
+        }
+        if dependency.license == NO_ASSERTION && dependency.license_texts.is_empty() {
+            review_notes.push(format!(
+                "{} {} declares no license and ships no license file",
+                dependency.name, dependency.version
+            ));
+        } else if is_copyleft(&dependency.license) {
+            review_notes.push(format!(
+                "{} {} is under a copyleft license ({})",
+                dependency.name, dependency.version, dependency.license
+            ));
+        }
+    }
+    let mut license_tally: Vec<LicenseTally> = tally
+        .into_iter()
+        .map(|(license, components)| LicenseTally {
+            license: license.to_string(),
+            components,
+        })
+        .collect();
+    license_tally.sort_by(|a, b| b.components.cmp(&a.components).then_with(|| a.license.cmp(&b.license)));
+
+    let license_texts = used_by
+        .into_iter()
+        .filter_map(|(id, used_by)| {
+            let stored = stored.remove(id)?;
+            Some(LicenseText {
+                id: id.to_string(),
+                file: stored.file,
+                text: stored.text,
+                used_by,
+            })
+        })
+        .collect();
+
+    for model in &models {
+        if let Some(warning) = model.warning.as_ref().filter(|_| !model.commercial_use_allowed) {
+            review_notes.push(format!("Model {}: {}", model.name, warning));
+        }
+    }
+
+    Ok(AttributionReport {
+        generated_at: now,
+        application: sbom.metadata.component.name,
+        version: sbom.metadata.component.version,
+        dependencies,
+        license_tally,
+        license_texts,
+        models,
+        review_notes,
+    })
+}
+
+/// License of every installed and catalogued model; installed models use their download provenance
+pub async fn model_licenses(llm: &LLMManager) -> Result<Vec<ModelLicenseSummary>> {
+    let mut summaries: Vec<ModelLicenseSummary> = llm
+        .list_models()
+        .await?
+        .into_iter()
+        .map(|model| {
+            let provenance = llm.get_model_provenance(&model.id).ok().filter(|_| model.installed);
+            let (commercial_use_allowed, warning) = match &provenance {
+                Some(provenance) => (provenance.commercial_use_allowed, provenance.license_warning.clone()),
+                None => LLMManager::check_commercial_license(model.license.as_deref()),
+            };
+            ModelLicenseSummary {
+                source: provenance
+                    .as_ref()
+                    .and_then(|p| p.hf_repo.clone())
+                    .or_else(|| model.download_url.clone()),
+                license: provenance.and_then(|p| p.license).or(model.license),
+                model_id: model.id,
+                name: model.name,
+                installed: model.installed,
+                commercial_use_allowed,
+                warning,
+            }
+        })
+        .collect();
+    summaries.sort_by(|a, b| b.installed.cmp(&a.installed).then_with(|| a.name.cmp(&b.name)));
+    Ok(summaries)
+}
+
+pub fn render_markdown(report: &AttributionReport) -> String {
+    let mut out = format!(
+        "# Third-Party Licenses: {} {}\n\nGenerated {}. {} bundled components, {} models.\n\n",
+        report.application,
+        report.version,
+        report.generated_at.format("%Y-%m-%d %H:%M UTC"),
+        report.dependencies.len(),
+        report.models.len()
+    );
+
+    if !report.review_notes.is_empty() {
+        out.push_str("## Items for review\n\n");
+        for note in &report.review_notes {
+            out.push_str(&format!("- {}\n", note));
+        }
+        out.push('\n');
+    }
+
+    out.push_str("## Model licenses\n\n| Model | Installed | License | Commercial use | Notes |\n|---|---|---|---|---|\n");
+    for model in &report.models {
+        out.push_str(&format!(
+            "| {} | {} | {} | {} | {} |\n",
+            model.name,
+            if model.installed { "yes" } else { "no" },
+            model.license.as_deref().unwrap_or("unknown"),
+            if model.commercial_use_allowed { "permitted" } else { "not permitted" },
+            model.warning.as_deref().unwrap_or("")
+        ));
+    }
+
+    out.push_str("\n## License summary\n\n| License | Components |\n|---|---|\n");
+    for tally in &report.license_tally {
+        out.push_str(&format!("| {} | {} |\n", tally.license, tally.components));
+    }
+
+    out.push_str("\n## Components\n\n");
+    for dependency in &report.dependencies {
+        out.push_str(&format!("### {} {}\n\n- License: {}\n", dependency.name, dependency.version, dependency.license));
+        if let Some(author) = &dependency.author {
+            out.push_str(&format!("- Authors: {}\n", author));
+        }
+        for copyright in &dependency.copyright {
+            out.push_str(&format!("- {}\n", copyright));
+        }
+        if let Some(repository) = &dependency.repository {
+            out.push_str(&format!("- Source: {}\n", repository));
+        }
+        out.push('\n');
+    }
+
+    out.push_str("## License texts\n\n");
+    for text in &report.license_texts {
+        out.push_str(&format!(
+            "### {} ({})\n\nUsed by: {}\n\n```text\n{}\n```\n\n",
+            text.file,
+            text.id,
+            text.used_by.join(", "),
+            text.text.trim_end()
+        ));
+    }
+    out
+}
+
+/// Simple A4 text flow for PDF exports: wraps long lines and starts new pages as needed
+pub(crate) struct PdfFlow {
+    doc: PdfDocumentReference,
+    layer: PdfLayerReference,
+    regular: IndirectFontRef,
+    bold: IndirectFontRef,
+    mono: IndirectFontRef,
+    y: f32,
+}
+
+impl PdfFlow {
+    const TOP: f32 = 277.0;
+    const BOTTOM: f32 = 20.0;
+    const LEFT: f32 = 18.0;
+
+    pub(crate) fn new(title: &str) -> Result<Self> {
+        let (doc, page, layer) = PdfDocument::new(title, Mm(210.0), Mm(297.0), "Layer 1");
+        let layer = doc.get_page(page).get_layer(layer);
+        Ok(Self {
+            regular: doc.add_builtin_font(BuiltinFont::Helvetica)?,
+            bold: doc.add_builtin_font(BuiltinFont::HelveticaBold)?,
+            mono: doc.add_builtin_font(BuiltinFont::Courier)?,
+            doc,
+            layer,
+            y: Self::TOP,
+        })
+    }
+
+    fn new_page(&mut self) {
+        let (page, layer) = self.doc.add_page(Mm(210.0), Mm(297.0), "Layer 1");
+        self.layer = self.doc.get_page(page).get_layer(layer);
+        self.y = Self::TOP;
+    }
+
+    /// Write text wrapped to `width` characters; `font` is 0 regular, 1 bold, 2 monospace
+    pub(crate) fn text(&mut self, text: &str, size: f32, font: u8, width: usize) {
+        let line_height = size * 0.45;
+        for line in wrap(&pdf_safe(text), width) {
+            if self.y - line_height < Self::BOTTOM {
+                self.new_page();
+            }
+            self.y -= line_height;
+            let font = match font {
+                1 => &self.bold,
+                2 => &self.mono,
+                _ => &self.regular,
+            };
+            self.layer.use_text(line, size, Mm(Self::LEFT), Mm(self.y), font);
+        }
+    }
+
+    pub(crate) fn gap(&mut self, mm: f32) {
+        self.y -= mm;
+    }
+
+    pub(crate) fn save(self, path: &Path) -> Result<()> {
+        self.doc.save(&mut BufWriter::new(fs::File::create(path)?))?;
+        Ok(())
+    }
+}
+
+/// The built-in PDF fonts only cover Latin-1
+fn pdf_safe(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '\t' => ' ',
+            '\u{2018}' | '\u{2019}' => '\'',
+            '\u{201c}' | '\u{201d}' => '"',
+            '\u{2013}' | '\u{2014}' => '-',
+            '\n' => '\n',
+            c if (c as u32) < 0x20 || (c as u32) > 0xff => '?',
+            c => c,
+        })
+        .collect()
+}
+
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    for source in text.lines() {
+        let mut line = String::new();
+        for word in source.split(' ') {
+            if !line.is_empty() && line.chars().count() + word.chars().count() + 1 > width {
+                lines.push(std::mem::take(&mut line));
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(word);
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+pub fn render_pdf(report: &AttributionReport, path: &Path) -> Result<()> {
+    let mut pdf = PdfFlow::new(&format!("{} third-party licenses", report.application))?;
+    pdf.text(&format!("Third-Party Licenses: {} {}", report.application, report.version), 16.0, 1, 60);
+    pdf.text(
+        &format!(
+            "Generated {}. {} bundled components, {} models.",
+            report.generated_at.format("%Y-%m-%d %H:%M UTC"),
+            report.dependencies.len(),
+            report.models.len()
+        ),
+        9.0,
+        0,
+        110,
+    );
+
+    if !report.review_notes.is_empty() {
+        pdf.gap(4.0);
+        pdf.text("Items for review", 12.0, 1, 80);
+        for note in &report.review_notes {
+            pdf.text(&format!("- {}", note), 9.0, 0, 110);
+        }
+    }
+
+    pdf.gap(4.0);
+    pdf.text("Model licenses", 12.0, 1, 80);
+    for model in &report.models {
+        pdf.text(
+            &format!(
+                "{} ({}): {}; commercial use {}",
+                model.name,
+                if model.installed { "installed" } else { "not installed" },
+                model.license.as_deref().unwrap_or("license unknown"),
+                if model.commercial_use_allowed { "permitted" } else { "not permitted" }
+            ),
+            9.0,
+            0,
+            110,
+        );
+        if let Some(warning) = &model.warning {
+            pdf.text(&format!("    {}", warning), 8.0, 0, 120);
+        }
+    }
+
+    pdf.gap(4.0);
+    pdf.text("License summary", 12.0, 1, 80);
+    for tally in &report.license_tally {
+        pdf.text(&format!("{}: {} components", tally.license, tally.components), 9.0, 0, 110);
+    }
+
+    pdf.new_page();
+    pdf.text("Components", 12.0, 1, 80);
+    for dependency in &report.dependencies {
+        pdf.gap(1.5);
+        pdf.text(&format!("{} {} - {}", dependency.name, dependency.version, dependency.license), 9.0, 1, 100);
+        for copyright in &dependency.copyright {
+            pdf.text(copyright, 8.0, 0, 120);
+        }
+        if let Some(repository) = &dependency.repository {
+            pdf.text(repository, 8.0, 0, 120);
+        }
+    }
+
+    for text in &report.license_texts {
+        pdf.new_page();
+        pdf.text(&format!("{} ({})", text.file, text.id), 12.0, 1, 80);
+        pdf.text(&format!("Used by: {}", text.used_by.join(", ")), 8.0, 0, 120);
+        pdf.gap(2.0);
+        pdf.text(text.text.trim_end(), 7.0, 2, 105);
+    }
+    pdf.save(path)
+}
+
+pub struct LicenseAttribution {
+    reports_dir: PathBuf,
+}
+
+impl LicenseAttribution {
+    pub fn new(app_data_dir: &Path) -> Result<Self> {
+        let reports_dir = app_data_dir.join("license_reports");
+        fs::create_dir_all(&reports_dir)?;
+        Ok(Self { reports_dir })
+    }
+
+    pub async fn report(&self, llm: &LLMManager) -> Result<AttributionReport> {
+        build_report(SBOM_JSON, LICENSE_TEXTS_JSON, model_licenses(llm).await?, Utc::now())
+    }
+
+    pub async fn export(&self, format: AttributionFormat, llm: &LLMManager) -> Result<AttributionExport> {
+        let report = self.report(llm).await?;
+        let stem = format!(
+            "third-party-licenses-{}-{}",
+            report.version,
+            report.generated_at.format("%Y%m%d-%H%M%S")
+        );
+        let path = match format {
+            AttributionFormat::Pdf => {
+                let path = self.reports_dir.join(format!("{}.pdf", stem));
+                render_pdf(&report, &path)?;
+                path
+            }
+            AttributionFormat::Markdown => {
+                let path = self.reports_dir.join(format!("{}.md", stem));
+                fs::write(&path, render_markdown(&report))?;
+                path
+            }
+            AttributionFormat::CycloneDx => {
+                let path = self.reports_dir.join(format!("{}.cdx.json", stem));
+                fs::write(&path, SBOM_JSON)?;
+                path
+            }
+        };
+        Ok(AttributionExport {
+            path: path.display().to_string(),
+            format,
+            dependencies: report.dependencies.len(),
+            models: report.models.len(),
+            review_notes: report.review_notes,
+        })
+    }
+}
+
+pub type LicenseAttributionStorage = Arc<LicenseAttribution>;
+
+#[tauri::command]
+pub async fn get_license_attribution(
+    attribution: tauri::State<'_, LicenseAttributionStorage>,
+    llm: tauri::State<'_, Arc<LLMManager>>,
+) -> Result<AttributionReport, String> {
+    attribution.report(&llm).await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn export_license_attribution(
+    format: AttributionFormat,
+    attribution: tauri::State<'_, LicenseAttributionStorage>,
+    llm: tauri::State<'_, Arc<LLMManager>>,
+) -> Result<AttributionExport, String> {
+    attribution.export(format, &llm).await.map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SBOM: &str = r#"{
+        "bomFormat": "CycloneDX", "specVersion": "1.5", "version": 1,
+        "metadata": { "component": { "type": "application", "name": "bear-ai", "version": "1.0.0" }, "properties": [] },
+        "components": [
+            { "type": "library", "name": "serde", "version": "1.0.200", "licenses": [{ "expression": "MIT OR Apache-2.0" }],
+              "copyright": "Copyright (c) 2014 The Rust Project Developers",
+              "externalReferences": [{ "type": "vcs", "url": "https://github.com/serde-rs/serde" }],
+              "properties": [{ "name": "bear-ai:license-text", "value": "aaaa" }, { "name": "bear-ai:license-text", "value": "bbbb" }] },
+            { "type": "library", "name": "Inline", "version": "0.1.0", "licenses": [{ "expression": "LGPL-2.1-only" }],
+              "properties": [{ "name": "bear-ai:license-text", "value": "bbbb" }] },
+            { "type": "library", "name": "mystery", "version": "0.0.1" }
+        ]
+    }"#;
+    const TEXTS: &str = r#"{ "aaaa": { "file": "LICENSE-MIT", "text": "MIT text" }, "bbbb": { "file": "LICENSE-APACHE", "text": "Apache text" } }"#;
+
+    fn model(name: &str, license: &str) -> ModelLicenseSummary {
+        let (commercial_use_allowed, warning) = LLMManager::check_commercial_license(Some(license));
+        ModelLicenseSummary {
+            model_id: name.to_lowercase(),
+            name: name.to_string(),
+            installed: true,
+            license: Some(license.to_string()),
+            commercial_use_allowed,
+            warning,
+            source: None,
+        }
+    }
+
+    #[test]
+    fn test_report_groups_licenses_and_flags_review_items() {
+        let models = vec![model("Phi", "MIT"), model("Research", "CC-BY-NC-4.0")];
+        let report = build_report(SBOM, TEXTS, models, Utc::now()).unwrap();
+
+        let names: Vec<&str> = report.dependencies.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, vec!["Inline", "mystery", "serde"]);
+        assert_eq!(report.dependencies[1].license, NO_ASSERTION);
+        assert_eq!(report.dependencies[2].repository.as_deref(), Some("https://github.com/serde-rs/serde"));
+        assert_eq!(report.license_tally.len(), 3);
+
+        // Shared license files appear once, listing every component that ships them
+        let apache = report.license_texts.iter().find(|t| t.id == "bbbb").unwrap();
+        assert_eq!(apache.used_by, vec!["Inline 0.1.0", "serde 1.0.200"]);
+
+        assert_eq!(report.review_notes.len(), 3, "{:?}", report.review_notes);
+        assert!(report.review_notes.iter().any(|n| n.contains("Inline 0.1.0 is under a copyleft")));
+        assert!(report.review_notes.iter().any(|n| n.contains("mystery 0.0.1 declares no license")));
+        assert!(report.review_notes.iter().any(|n| n.starts_with("Model Research")));
+        assert!(!is_copyleft("MPL-2.0 OR MIT"));
+    }
+
+    #[test]
+    fn test_markdown_and_pdf_rendering() {
+        let report = build_report(SBOM, TEXTS, vec![model("Phi", "MIT")], Utc::now()).unwrap();
+        let markdown = render_markdown(&report);
+        assert!(markdown.contains("### serde 1.0.200"));
+        assert!(markdown.contains("- Copyright (c) 2014 The Rust Project Developers"));
+        assert!(markdown.contains("| Phi | yes | MIT | permitted |  |"));
+
+        assert_eq!(wrap("one two three", 7), vec!["one two", "three"]);
+        assert_eq!(pdf_safe("\u{201c}ok\u{201d} \u{4e2d}"), "\"ok\" ?");
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("licenses.pdf");
+        render_pdf(&report, &path).unwrap();
+        assert!(fs::read(&path).unwrap().starts_with(b"%PDF"));
+
+        // The SBOM embedded in this build parses
+        assert!(build_report(SBOM_JSON, LICENSE_TEXTS_JSON, Vec::new(), Utc::now()).is_ok());
+    }
+}
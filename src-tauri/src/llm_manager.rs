@@ -21,6 +21,7 @@ use chrono::{DateTime, Utc};
 // scopeguard will be used with macro syntax below
 use scopeguard;
 use sysinfo::System;
+use crate::request_tracing::{self, StageTimer};
 
 /// Local LLM Management System for BEAR AI
 /// Provides Ollama-style model management with HuggingFace integration
@@ -38,6 +39,8 @@ pub struct ModelInfo {
     pub installed: bool,
     pub version: String,
     pub created_at: u64, // Unix timestamp
+    #[serde(default)]
+    pub license: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -45,6 +48,485 @@ pub struct ModelRegistry {
     pub models: HashMap<String, ModelInfo>,
     pub model_path: PathBuf,
     pub cache_path: PathBuf,
+    #[serde(default)]
+    pub draft_pairs: HashMap<String, DraftModelConfig>, // keyed by target model id
+    #[serde(default)]
+    pub provenance: HashMap<String, ModelProvenance>,
+    #[serde(default)]
+    pub fallback_models: HashMap<String, Vec<String>>, // model id -> ordered fallbacks
+}
+
+/// Where a downloaded model came from, recorded at download time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelProvenance {
+    pub model_id: String,
+    pub source_url: String,
+    pub hf_repo: Option<String>,
+    pub hf_revision: Option<String>,
+    pub sha256: String,
+    pub file_size: u64,
+    pub downloaded_at: String, // RFC 3339
+    pub license: Option<String>,
+    pub commercial_use_allowed: bool,
+    pub license_warning: Option<String>,
+}
+
+/// Speculative decoding pairing: a small draft model proposes tokens the target model verifies
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DraftModelConfig {
+    pub draft_model_id: String,
+    pub draft_max: u32,   // max tokens drafted per step
+    pub draft_min: u32,   // min tokens drafted per step
+    pub draft_p_min: f32, // min probability for a drafted token to be kept
+    pub enabled: bool,
+}
+
+impl Default for DraftModelConfig {
+    fn default() -> Self {
+        Self {
+            draft_model_id: String::new(),
+            draft_max: 16,
+            draft_min: 4,
+            draft_p_min: 0.8,
+            enabled: true,
+        }
+    }
+}
+
+/// Progress of a model warm-up, emitted as `model-warmup-status` events
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelWarmupStatus {
+    pub model_id: Option<String>,
+    pub state: String, // "warming", "ready", "skipped", "failed"
+    pub message: Option<String>,
+    pub duration_ms: u64,
+}
+
+pub const MODEL_WARMUP_EVENT: &str = "model-warmup-status";
+
+/// A saved llama.cpp KV-cache slot for a prompt prefix (system prompt + document context)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptCacheEntry {
+    pub prompt_hash: String,
+    pub model_id: String,
+    pub filename: String,
+    pub prefix_chars: usize,
+    pub created_at: u64,
+    pub last_used: u64,
+    pub hits: u64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct PromptCacheState {
+    entries: HashMap<String, PromptCacheEntry>,
+    #[serde(skip)]
+    resident: HashMap<String, String>, // model id -> prompt hash currently held in slot 0
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrefixCachedCompletion {
+    pub content: String,
+    pub prompt_hash: String,
+    pub cache_hit: bool,
+    pub tokens_cached: Option<u32>,
+    pub tokens_evaluated: Option<u32>,
+}
+
+const MAX_PROMPT_CACHE_ENTRIES: usize = 32;
+
+/// Signed curated-model manifest published with each release
+pub(crate) const MODEL_MANIFEST_URL: &str = "https://github.com/KingOfTheAce2/BEAR_AI/releases/latest/download/model-manifest.json";
+
+/// Base64 Ed25519 public key for the manifest signature, injected at build time.
+/// Without it the remote manifest is never trusted and the embedded list is used.
+pub(crate) const MODEL_MANIFEST_PUBLIC_KEY: Option<&str> = option_env!("BEAR_AI_MANIFEST_PUBLIC_KEY");
+
+/// Curated legal model list, served remotely so new models ship without a new binary
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CuratedModelManifest {
+    pub version: u64,
+    pub issued_at: String,
+    pub models: Vec<ModelInfo>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestStatus {
+    pub source: String, // "remote", "cache" or "embedded"
+    pub version: Option<u64>,
+    pub issued_at: Option<String>,
+    pub model_count: usize,
+    pub error: Option<String>,
+}
+
+/// A llama-server instance spawned for a model, tracked so several can run side by side
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoadedModel {
+    pub model_id: String,
+    pub endpoint: String,
+    pub port: u16,
+    pub pid: Option<u32>,
+    pub capabilities: Vec<String>, // "chat", "completion", "embed"
+    pub loaded_at: String,         // RFC 3339
+    pub last_used: String,         // RFC 3339
+    pub memory_mb: u64,
+    pub vram_mb: u64,
+    pub healthy: bool,
+    pub last_health_check: Option<String>,
+}
+
+/// Output of a streaming generate or chat request, emitted as the model produces it
+pub const GENERATION_CHUNK_EVENT: &str = "llm-generation-chunk";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenerationChunk {
+    pub stream_id: String,
+    pub model: String,
+    pub index: usize,
+    pub content: String, // the new text; empty on the final event
+    pub done: bool,
+}
+
+/// Why a request to the model server failed, so failover can tell a backend that is down
+/// from a request that was turned away
+#[derive(Debug)]
+pub enum ModelBackendError {
+    /// A resource guard refused the request before it reached a server
+    Denied(String),
+    /// The server answered with an error status
+    Status { status: u16, message: String },
+    /// The server reported an error partway through a streamed response
+    Stream(String),
+}
+
+impl ModelBackendError {
+    /// Whether another attempt, on a restarted server or a fallback model, could succeed.
+    /// A 404 means the server does not have the model.
+    fn is_backend_failure(&self) -> bool {
+        match self {
+            ModelBackendError::Denied(_) => false,
+            ModelBackendError::Status { status, .. } => *status >= 500 || *status == 404,
+            ModelBackendError::Stream(_) => true,
+        }
+    }
+}
+
+impl std::fmt::Display for ModelBackendError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ModelBackendError::Denied(message)
+            | ModelBackendError::Status { message, .. }
+            | ModelBackendError::Stream(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for ModelBackendError {}
+
+/// Receives each piece of streamed output
+pub type ChunkSink<'a> = &'a (dyn Fn(&str) + Send + Sync);
+
+/// Splits a streamed body into its newline-delimited JSON records, holding back a partial
+/// record (or a UTF-8 sequence cut between network chunks) until the rest arrives
+#[derive(Default)]
+struct NdjsonLines {
+    buffer: Vec<u8>,
+}
+
+impl NdjsonLines {
+    fn push(&mut self, bytes: &[u8]) -> Vec<String> {
+        self.buffer.extend_from_slice(bytes);
+        let mut lines = Vec::new();
+        while let Some(end) = self.buffer.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line).trim().to_string();
+            if !line.is_empty() {
+                lines.push(line);
+            }
+        }
+        lines
+    }
+
+    fn finish(&mut self) -> Option<String> {
+        let line = String::from_utf8_lossy(&std::mem::take(&mut self.buffer)).trim().to_string();
+        (!line.is_empty()).then_some(line)
+    }
+}
+
+/// Read an Ollama streaming response, passing each piece of output to `on_chunk`. Returns the
+/// final record with the full output assembled, as a non-streaming request would have.
+async fn read_stream<T: serde::de::DeserializeOwned>(
+    response: reqwest::Response,
+    kind: &str,
+    text_of: fn(&mut T) -> &mut String,
+    on_chunk: ChunkSink<'_>,
+) -> Result<T> {
+    let mut lines = NdjsonLines::default();
+    let mut output = String::new();
+    let mut handle = |line: String| -> Result<Option<T>> {
+        let value: Value = serde_json::from_str(&line).with_context(|| format!("Failed to parse {} stream", kind))?;
+        if let Some(error) = value.get("error").and_then(Value::as_str) {
+            return Err(ModelBackendError::Stream(format!("{} request failed: {}", kind, error)).into());
+        }
+        let done = value.get("done").and_then(Value::as_bool).unwrap_or(false);
+        let mut record: T = serde_json::from_value(value).with_context(|| format!("Failed to parse {} stream", kind))?;
+        let piece = std::mem::take(text_of(&mut record));
+        if !piece.is_empty() {
+            on_chunk(&piece);
+            output.push_str(&piece);
+        }
+        if !done {
+            return Ok(None);
+        }
+        *text_of(&mut record) = std::mem::take(&mut output);
+        Ok(Some(record))
+    };
+
+    let mut stream = response.bytes_stream();
+    while let Some(bytes) = stream.next().await {
+        let bytes = bytes.with_context(|| format!("{} stream interrupted", kind))?;
+        for line in lines.push(&bytes) {
+            if let Some(record) = handle(line)? {
+                return Ok(record);
+            }
+        }
+    }
+    if let Some(record) = lines.finish().map(&mut handle).transpose()?.flatten() {
+        return Ok(record);
+    }
+    Err(anyhow::anyhow!("{} request failed: the stream ended before the model finished", kind))
+}
+
+/// Read a llama-server chat completion streamed as server-sent events, passing each piece of
+/// the reply to `on_chunk`. Returns the full reply and the timings sent with the last event.
+async fn read_completion_stream(response: reqwest::Response, on_chunk: ChunkSink<'_>) -> Result<(String, Value)> {
+    let mut lines = NdjsonLines::default();
+    let mut output = String::new();
+    let mut last = Value::Null;
+    let mut handle = |line: String| -> Result<bool> {
+        let Some(data) = line.strip_prefix("data:").map(str::trim) else {
+            return Ok(false); // comments and other SSE fields
+        };
+        if data == "[DONE]" {
+            return Ok(true);
+        }
+        let value: Value = serde_json::from_str(data).context("Failed to parse chat stream")?;
+        if let Some(error) = value.get("error") {
+            let message = error.get("message").and_then(Value::as_str).map(str::to_string).unwrap_or_else(|| error.to_string());
+            return Err(ModelBackendError::Stream(format!("Chat request failed: {}", message)).into());
+        }
+        if let Some(piece) = value["choices"][0]["delta"]["content"].as_str().filter(|p| !p.is_empty()) {
+            on_chunk(piece);
+            output.push_str(piece);
+        }
+        last = value;
+        Ok(false)
+    };
+
+    let mut stream = response.bytes_stream();
+    'read: while let Some(bytes) = stream.next().await {
+        let bytes = bytes.context("Chat stream interrupted")?;
+        for line in lines.push(&bytes) {
+            if handle(line)? {
+                break 'read;
+            }
+        }
+    }
+    if let Some(line) = lines.finish() {
+        handle(line)?;
+    }
+    if last["choices"][0]["finish_reason"].as_str().is_none() {
+        return Err(ModelBackendError::Stream("Chat request failed: the stream ended before the model finished".to_string()).into());
+    }
+    Ok((output, last))
+}
+
+/// Supervisor events for spawned llama-server processes
+pub const MODEL_PROCESS_EVENT: &str = "model-process-status";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelProcessEvent {
+    pub model_id: String,
+    pub event: String, // "crashed", "unhealthy", "restarting", "restarted", "restart_failed", "gave_up"
+    pub message: Option<String>,
+    pub restart_attempt: u32,
+    pub timestamp: String,
+}
+
+/// Local model server the manager talks to; other modules use it to reach the same models
+pub const DEFAULT_MODEL_SERVER_URL: &str = "http://127.0.0.1:11434";
+
+const SUPERVISOR_INTERVAL: Duration = Duration::from_secs(10);
+const MAX_RESTART_ATTEMPTS: u32 = 5;
+const RESTART_BACKOFF_BASE_SECS: u64 = 5;
+const UNHEALTHY_PING_THRESHOLD: u32 = 3;
+
+#[derive(Debug, Default)]
+struct SupervisorState {
+    failed_pings: u32,
+    restart_attempts: u32,
+    next_restart: Option<std::time::Instant>,
+    restart_pending: bool,
+    gave_up: bool,
+    loaded_at: Option<String>, // of the server last seen for the model
+}
+
+/// Fixed prompt and output length used for inference benchmarks
+const BENCHMARK_PROMPT: &str =
+    "Summarize the key obligations of a non-disclosure agreement between two companies in plain language.";
+const BENCHMARK_TOKENS: i32 = 128;
+
+/// Context size passed to llama-server when loading a model
+const DEFAULT_CONTEXT_LENGTH: u32 = 4096;
+
+/// GPU memory totals with the share attributed to each loaded model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VramUsage {
+    pub total_mb: u64,
+    pub free_mb: u64,
+    pub attributed_mb: u64,
+    pub models: Vec<LoadedModel>,
+}
+
+/// Estimated memory footprint of a model load compared against live availability
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoadPreflight {
+    pub model_id: String,
+    pub quantization: String,
+    pub context_length: u32,
+    pub estimated_params_billions: f32,
+    pub weights_mb: u64,
+    pub kv_cache_mb: u64,
+    pub overhead_mb: u64,
+    pub required_mb: u64,
+    pub available_ram_mb: u64,
+    pub free_vram_mb: u64,
+    pub layers: u64,
+    pub gpu_layers: u64, // layers that fit in free VRAM, passed to llama-server as --n-gpu-layers
+    pub fits: bool,
+    pub recommendation: Option<String>,
+}
+
+/// Size of a model file that is a complete GGUF; a truncated or non-GGUF file fails inside
+/// llama-server after a long wait, so it is caught here
+fn check_gguf_file(model_file: &Path) -> Result<u64> {
+    let file_size = fs::metadata(model_file)
+        .with_context(|| format!("Model file not found: {:?}", model_file))?
+        .len();
+
+    let mut magic = [0u8; 4];
+    {
+        use std::io::Read;
+        fs::File::open(model_file)?
+            .read_exact(&mut magic)
+            .context("Model file is too small to be a valid GGUF file")?;
+    }
+    if &magic != b"GGUF" {
+        return Err(anyhow::anyhow!("Model file {:?} is not a GGUF file", model_file));
+    }
+
+    Ok(file_size)
+}
+
+/// Estimate the memory a model load needs from its file size and GGUF header, and compare it
+/// against the RAM and VRAM available
+fn estimate_load(
+    model_id: &str,
+    quantization: String,
+    file_size: u64,
+    metadata: Option<&crate::model_commands::GgufMetadata>,
+    context_length: u32,
+    available_ram_mb: u64,
+    free_vram_mb: u64,
+) -> LoadPreflight {
+    let quantization = metadata
+        .and_then(|m| m.quantization.clone())
+        .unwrap_or(quantization);
+
+    let bits = LLMManager::quantization_bits(&quantization);
+    let params_billions = match metadata.map(|m| m.parameter_count) {
+        Some(count) if count > 0 => count as f32 / 1e9,
+        _ => (file_size as f64 * 8.0 / bits as f64 / 1e9) as f32,
+    };
+
+    // f16 K and V for every layer; shape is inferred from the parameter class if unknown
+    let (estimated_layers, estimated_embedding) = match params_billions {
+        p if p <= 2.0 => (24u64, 2048u64),
+        p if p <= 4.5 => (32, 3072),
+        p if p <= 9.0 => (32, 4096),
+        p if p <= 15.0 => (40, 5120),
+        p if p <= 35.0 => (60, 6656),
+        _ => (80, 8192),
+    };
+    let layers = metadata.and_then(|m| m.block_count).map_or(estimated_layers, |v| v as u64);
+    let embedding = metadata.and_then(|m| m.embedding_length).map_or(estimated_embedding, |v| v as u64);
+    // Grouped-query attention shrinks the KV cache by head_count / head_count_kv
+    let kv_embedding = match metadata.and_then(|m| Some((m.head_count?, m.head_count_kv?))) {
+        Some((heads, kv_heads)) if heads > 0 && kv_heads > 0 => embedding * kv_heads as u64 / heads as u64,
+        _ => embedding,
+    };
+    let kv_cache_bytes = 2 * 2 * layers * kv_embedding * context_length as u64;
+
+    let weights_mb = file_size / 1024 / 1024;
+    let kv_cache_mb = kv_cache_bytes / 1024 / 1024;
+    let overhead_mb = 256 + weights_mb / 10; // compute buffers and runtime
+    let required_mb = weights_mb + kv_cache_mb + overhead_mb;
+
+    // Offload every layer when the whole model fits, otherwise as many as free VRAM holds
+    let gpu_layers = if required_mb <= free_vram_mb * 9 / 10 {
+        layers
+    } else {
+        let per_layer_mb = ((weights_mb + kv_cache_mb) / layers.max(1)).max(1);
+        (free_vram_mb * 9 / 10 / per_layer_mb).min(layers)
+    };
+
+    // Keep 10% headroom for the rest of the application
+    let fits = required_mb <= available_ram_mb * 9 / 10;
+
+    let recommendation = if fits {
+        None
+    } else {
+        let budget_mb = (available_ram_mb * 9 / 10).saturating_sub(kv_cache_mb + 256);
+        let smaller_quant = [("Q5_K_M", 5.7f32), ("Q4_K_M", 4.85), ("Q3_K_M", 3.9), ("Q2_K", 2.6)]
+            .iter()
+            .filter(|(_, q_bits)| *q_bits < bits)
+            .find(|(_, q_bits)| {
+                let size_mb = (params_billions as f64 * 1e9 * *q_bits as f64 / 8.0 / 1024.0 / 1024.0) as u64;
+                size_mb + size_mb / 10 <= budget_mb
+            })
+            .map(|(name, _)| *name);
+
+        let mut advice = Vec::new();
+        if let Some(quant) = smaller_quant {
+            advice.push(format!("use a {} quantization of this model", quant));
+        }
+        if gpu_layers > 0 {
+            advice.push(format!("offload {} layers to the GPU (--n-gpu-layers {})", gpu_layers, gpu_layers));
+        }
+        if context_length > 2048 {
+            advice.push("reduce the context length to 2048".to_string());
+        }
+        if advice.is_empty() {
+            advice.push("choose a smaller model".to_string());
+        }
+        Some(format!("Suggestion: {}", advice.join(", or ")))
+    };
+
+    LoadPreflight {
+        model_id: model_id.to_string(),
+        quantization,
+        context_length,
+        estimated_params_billions: params_billions,
+        weights_mb,
+        kv_cache_mb,
+        overhead_mb,
+        required_mb,
+        available_ram_mb,
+        free_vram_mb,
+        layers,
+        gpu_layers,
+        fits,
+        recommendation,
+    }
 }
 
 #[derive(Debug)]
@@ -53,6 +535,13 @@ pub struct LLMManager {
     model_path: PathBuf,
     cache_path: PathBuf,
     running_models: Arc<Mutex<HashMap<String, tokio::process::Child>>>,
+    loaded_models: Arc<Mutex<HashMap<String, LoadedModel>>>,
+    // GPU buffer sizes reported in each llama-server's log, keyed by model id (MiB)
+    logged_gpu_buffers: Arc<Mutex<HashMap<String, u64>>>,
+    // Held while a model's server starts, so concurrent loads of one model start one server
+    loading_locks: Arc<Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>>,
+    prompt_cache: Arc<Mutex<PromptCacheState>>,
+    curated_manifest: Arc<Mutex<Option<CuratedModelManifest>>>,
     http_client: Client,
     ollama_base_url: String,
 }
@@ -74,15 +563,31 @@ impl LLMManager {
                 models: HashMap::new(),
                 model_path: model_path.clone(),
                 cache_path: cache_path.clone(),
+                draft_pairs: HashMap::new(),
+                provenance: HashMap::new(),
+                fallback_models: HashMap::new(),
             })
         } else {
             ModelRegistry {
                 models: HashMap::new(),
                 model_path: model_path.clone(),
                 cache_path: cache_path.clone(),
+                draft_pairs: HashMap::new(),
+                provenance: HashMap::new(),
+                fallback_models: HashMap::new(),
             }
         };
 
+        // Prompt-prefix cache index; the KV slot files themselves live in cache/kv_slots
+        fs::create_dir_all(cache_path.join("kv_slots"))?;
+        let prompt_cache: PromptCacheState = fs::read_to_string(cache_path.join("prompt_cache.json"))
+            .ok()
+            .and_then(|data| serde_json::from_str(&data).ok())
+            .unwrap_or_default();
+
+        // A previously fetched manifest is only used if its signature still verifies
+        let curated_manifest = Self::load_cached_manifest(&cache_path);
+
         let http_client = Client::builder()
             .timeout(Duration::from_secs(300))
             .build()
@@ -93,12 +598,142 @@ impl LLMManager {
             model_path,
             cache_path,
             running_models: Arc::new(Mutex::new(HashMap::new())),
+            loaded_models: Arc::new(Mutex::new(HashMap::new())),
+            logged_gpu_buffers: Arc::new(Mutex::new(HashMap::new())),
+            loading_locks: Arc::new(Mutex::new(HashMap::new())),
+            prompt_cache: Arc::new(Mutex::new(prompt_cache)),
+            curated_manifest: Arc::new(Mutex::new(curated_manifest)),
             http_client,
-            ollama_base_url: "http://127.0.0.1:11434".to_string(),
+            ollama_base_url: DEFAULT_MODEL_SERVER_URL.to_string(),
         })
     }
 
-    /// Get list of available legal-focused models (curated list)
+    /// Where Ollama-served models are reached
+    pub fn ollama_base_url(&self) -> &str {
+        &self.ollama_base_url
+    }
+
+    /// Curated models from the signed manifest, falling back to the embedded list
+    pub fn curated_models(&self) -> Vec<ModelInfo> {
+        match self.curated_manifest.lock().unwrap().as_ref() {
+            Some(manifest) if !manifest.models.is_empty() => manifest.models.clone(),
+            _ => Self::get_curated_legal_models(),
+        }
+    }
+
+    /// Fetch the signed curated-model manifest, verify it and cache it for offline use
+    pub async fn refresh_curated_manifest(&self) -> Result<ManifestStatus> {
+        let public_key = MODEL_MANIFEST_PUBLIC_KEY
+            .context("No manifest public key configured; using the embedded model list")?;
+
+        let manifest_bytes = self
+            .http_client
+            .get(MODEL_MANIFEST_URL)
+            .timeout(Duration::from_secs(30))
+            .send()
+            .await
+            .context("Failed to fetch model manifest")?
+            .error_for_status()?
+            .bytes()
+            .await?;
+        let signature = self
+            .http_client
+            .get(format!("{}.sig", MODEL_MANIFEST_URL))
+            .timeout(Duration::from_secs(30))
+            .send()
+            .await
+            .context("Failed to fetch model manifest signature")?
+            .error_for_status()?
+            .text()
+            .await?;
+
+        let manifest = Self::verify_manifest(&manifest_bytes, signature.trim(), public_key)?;
+
+        // Reject rollbacks to an older (possibly vulnerable) manifest
+        let current_version = self.curated_manifest.lock().unwrap().as_ref().map(|m| m.version);
+        if let Some(current) = current_version {
+            if manifest.version < current {
+                return Err(anyhow::anyhow!(
+                    "Remote manifest version {} is older than cached version {}",
+                    manifest.version,
+                    current
+                ));
+            }
+        }
+
+        fs::write(self.cache_path.join("model_manifest.json"), &manifest_bytes)?;
+        fs::write(self.cache_path.join("model_manifest.json.sig"), signature.trim())?;
+
+        let status = ManifestStatus {
+            source: "remote".to_string(),
+            version: Some(manifest.version),
+            issued_at: Some(manifest.issued_at.clone()),
+            model_count: manifest.models.len(),
+            error: None,
+        };
+        *self.curated_manifest.lock().unwrap() = Some(manifest);
+
+        log::info!("Curated model manifest v{} loaded ({} models)", status.version.unwrap_or(0), status.model_count);
+        Ok(status)
+    }
+
+    /// Describe where the curated model list currently comes from
+    pub fn manifest_status(&self) -> ManifestStatus {
+        match self.curated_manifest.lock().unwrap().as_ref() {
+            Some(manifest) => ManifestStatus {
+                source: "cache".to_string(),
+                version: Some(manifest.version),
+                issued_at: Some(manifest.issued_at.clone()),
+                model_count: manifest.models.len(),
+                error: None,
+            },
+            None => ManifestStatus {
+                source: "embedded".to_string(),
+                version: None,
+                issued_at: None,
+                model_count: Self::get_curated_legal_models().len(),
+                error: None,
+            },
+        }
+    }
+
+    fn load_cached_manifest(cache_path: &Path) -> Option<CuratedModelManifest> {
+        let public_key = MODEL_MANIFEST_PUBLIC_KEY?;
+        let manifest_bytes = fs::read(cache_path.join("model_manifest.json")).ok()?;
+        let signature = fs::read_to_string(cache_path.join("model_manifest.json.sig")).ok()?;
+
+        match Self::verify_manifest(&manifest_bytes, signature.trim(), public_key) {
+            Ok(manifest) => Some(manifest),
+            Err(e) => {
+                log::warn!("Ignoring cached model manifest: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Verify the Ed25519 signature over the exact manifest bytes, then parse it
+    fn verify_manifest(manifest_bytes: &[u8], signature_b64: &str, public_key_b64: &str) -> Result<CuratedModelManifest> {
+        use base64::Engine;
+        use ring::signature::{UnparsedPublicKey, ED25519};
+
+        let engine = base64::engine::general_purpose::STANDARD;
+        let public_key = engine.decode(public_key_b64).context("Invalid manifest public key")?;
+        let signature = engine.decode(signature_b64).context("Invalid manifest signature encoding")?;
+
+        UnparsedPublicKey::new(&ED25519, &public_key)
+            .verify(manifest_bytes, &signature)
+            .map_err(|_| anyhow::anyhow!("Model manifest signature verification failed"))?;
+
+        let mut manifest: CuratedModelManifest =
+            serde_json::from_slice(manifest_bytes).context("Invalid model manifest")?;
+        for model in &mut manifest.models {
+            model.installed = false;
+        }
+
+        Ok(manifest)
+    }
+
+    /// Embedded curated list of legal-focused models (offline fallback for the manifest)
     pub fn get_curated_legal_models() -> Vec<ModelInfo> {
         vec![
             ModelInfo {
@@ -114,6 +749,7 @@ impl LLMManager {
                 installed: false,
                 version: "3.0.0".to_string(),
                 created_at: chrono::Utc::now().timestamp() as u64,
+                license: Some("Llama 3 Community License".to_string()),
             },
             ModelInfo {
                 id: "phi3-mini-legal".to_string(),
@@ -128,6 +764,7 @@ impl LLMManager {
                 installed: false,
                 version: "3.0.0".to_string(),
                 created_at: chrono::Utc::now().timestamp() as u64,
+                license: Some("MIT".to_string()),
             },
             ModelInfo {
                 id: "codellama-7b-legal".to_string(),
@@ -142,6 +779,7 @@ impl LLMManager {
                 installed: false,
                 version: "2.0.0".to_string(),
                 created_at: chrono::Utc::now().timestamp() as u64,
+                license: Some("Llama 2 Community License".to_string()),
             },
             ModelInfo {
                 id: "mistral-7b-legal".to_string(),
@@ -156,6 +794,7 @@ impl LLMManager {
                 installed: false,
                 version: "0.2.0".to_string(),
                 created_at: chrono::Utc::now().timestamp() as u64,
+                license: Some("Apache-2.0".to_string()),
             },
             ModelInfo {
                 id: "llama3-70b-legal".to_string(),
@@ -170,6 +809,53 @@ impl LLMManager {
                 installed: false,
                 version: "3.0.0".to_string(),
                 created_at: chrono::Utc::now().timestamp() as u64,
+                license: Some("Llama 3 Community License".to_string()),
+            },
+            // Speech-to-text models for dictation, run by whisper.cpp rather than llama-server
+            ModelInfo {
+                id: "whisper-base-en".to_string(),
+                name: "Whisper Base (English)".to_string(),
+                description: "Fast English dictation model for everyday use".to_string(),
+                size: 148_000_000, // ~148MB
+                quantization: "F16".to_string(),
+                format: "GGML".to_string(),
+                path: PathBuf::from("ggml-base.en.bin"),
+                download_url: Some("https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-base.en.bin".to_string()),
+                legal_optimized: false,
+                installed: false,
+                version: "1.0.0".to_string(),
+                created_at: chrono::Utc::now().timestamp() as u64,
+                license: Some("MIT".to_string()),
+            },
+            ModelInfo {
+                id: "whisper-small-en-tdrz".to_string(),
+                name: "Whisper Small (English, speaker turns)".to_string(),
+                description: "Marks speaker changes, for dictated interviews and meetings".to_string(),
+                size: 488_000_000, // ~488MB
+                quantization: "F16".to_string(),
+                format: "GGML".to_string(),
+                path: PathBuf::from("ggml-small.en-tdrz.bin"),
+                download_url: Some("https://huggingface.co/akashmjn/tinydiarize-whisper.cpp/resolve/main/ggml-small.en-tdrz.bin".to_string()),
+                legal_optimized: false,
+                installed: false,
+                version: "1.0.0".to_string(),
+                created_at: chrono::Utc::now().timestamp() as u64,
+                license: Some("MIT".to_string()),
+            },
+            ModelInfo {
+                id: "whisper-large-v3-turbo".to_string(),
+                name: "Whisper Large v3 Turbo (multilingual)".to_string(),
+                description: "Accurate multilingual dictation for Dutch, German, French and more".to_string(),
+                size: 574_000_000, // ~574MB
+                quantization: "Q5_0".to_string(),
+                format: "GGML".to_string(),
+                path: PathBuf::from("ggml-large-v3-turbo-q5_0.bin"),
+                download_url: Some("https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-large-v3-turbo-q5_0.bin".to_string()),
+                legal_optimized: false,
+                installed: false,
+                version: "3.0.0".to_string(),
+                created_at: chrono::Utc::now().timestamp() as u64,
+                license: Some("MIT".to_string()),
             },
         ]
     }
@@ -180,7 +866,7 @@ impl LLMManager {
         let mut models: Vec<ModelInfo> = registry.models.values().cloned().collect();
 
         // Add curated models that aren't installed
-        for curated in Self::get_curated_legal_models() {
+        for curated in self.curated_models() {
             if !registry.models.contains_key(&curated.id) {
                 models.push(curated);
             }
@@ -201,7 +887,7 @@ impl LLMManager {
         model_id: &str,
         progress_callback: Option<Box<dyn Fn(u64, u64) + Send>>,
     ) -> Result<()> {
-        let curated_models = Self::get_curated_legal_models();
+        let curated_models = self.curated_models();
         let model = curated_models
             .iter()
             .find(|m| m.id == model_id)
@@ -234,6 +920,8 @@ impl LLMManager {
 
         let mut stream = response.bytes_stream();
         use tokio_stream::StreamExt;
+        use sha2::{Digest, Sha256};
+        let mut hasher = Sha256::new();
 
         while let Some(chunk) = stream.next().await {
             let chunk = chunk.context("Failed to read chunk")?;
@@ -241,99 +929,1185 @@ impl LLMManager {
                 .await
                 .context("Failed to write chunk")?;
 
-            downloaded += chunk.len() as u64;
+            hasher.update(&chunk);
+            downloaded += chunk.len() as u64;
+
+            if let Some(ref callback) = progress_callback {
+                callback(downloaded, total_size);
+            }
+        }
+
+        // Verify download
+        let file_size = async_fs::metadata(&model_file).await?.len();
+        if total_size > 0 && file_size != total_size {
+            return Err(anyhow::anyhow!(
+                "Download verification failed: expected {} bytes, got {}",
+                total_size,
+                file_size
+            ));
+        }
+
+        // Record provenance alongside the registry entry
+        let (hf_repo, hf_revision) = Self::parse_huggingface_url(download_url);
+        let (commercial_use_allowed, license_warning) = Self::check_commercial_license(model.license.as_deref());
+        if let Some(warning) = &license_warning {
+            log::warn!("Model {}: {}", model.name, warning);
+        }
+        let provenance = ModelProvenance {
+            model_id: model_id.to_string(),
+            source_url: download_url.clone(),
+            hf_repo,
+            hf_revision,
+            sha256: format!("{:x}", hasher.finalize()),
+            file_size,
+            downloaded_at: chrono::Utc::now().to_rfc3339(),
+            license: model.license.clone(),
+            commercial_use_allowed,
+            license_warning,
+        };
+
+        // Update registry
+        let mut registry = self.registry.lock().unwrap();
+        let mut updated_model = model.clone();
+        updated_model.installed = true;
+        registry.models.insert(model_id.to_string(), updated_model);
+        registry.provenance.insert(model_id.to_string(), provenance);
+
+        self.save_registry(&registry)?;
+
+        log::info!("Model {} downloaded successfully", model.name);
+        Ok(())
+    }
+
+    /// Load a model for inference with resource guards
+    pub async fn load_model(&self, model_id: &str) -> Result<String> {
+        if Self::model_capabilities(model_id).iter().any(|c| c == "transcribe") {
+            return Err(anyhow::anyhow!(
+                "{} is a speech model; use the transcription commands instead of loading it",
+                model_id
+            ));
+        }
+
+        // CHECK RESOURCE GUARDS BEFORE LOADING MODEL
+        if let Some(tracker) = crate::performance_tracker::get_performance_tracker() {
+            // Acquire operation permit with resource checking
+            tracker.acquire_operation_permit().await
+                .map_err(|e| ModelBackendError::Denied(format!("Resource guard denied model loading: {}", e)))?;
+
+            // Ensure permit is released on function exit using scopeguard
+            let _permit_guard = scopeguard::defer! {
+                if let Some(tracker) = crate::performance_tracker::get_performance_tracker() {
+                    tracker.release_operation_permit();
+                }
+            };
+        }
+
+        // Each model gets exactly one server; concurrent callers wait for the first to start
+        // it and then share it
+        let loading = self
+            .loading_locks
+            .lock()
+            .unwrap()
+            .entry(model_id.to_string())
+            .or_default()
+            .clone();
+        let _loading = loading.lock().await;
+        if let Some(loaded) = self.loaded_models.lock().unwrap().get(model_id) {
+            return Ok(loaded.endpoint.clone());
+        }
+
+        // Fail fast instead of letting the OS kill an oversized model mid-load
+        let mut preflight = self.preflight_model_load(model_id, DEFAULT_CONTEXT_LENGTH)?;
+
+        // Make room on the GPU by evicting idle models rather than partially offloading; the
+        // server is then given as many layers as the freed VRAM holds
+        if preflight.free_vram_mb > 0 && preflight.required_mb > preflight.free_vram_mb {
+            if self.evict_for_vram(model_id, preflight.required_mb - preflight.free_vram_mb).await > 0 {
+                preflight = self.preflight_model_load(model_id, DEFAULT_CONTEXT_LENGTH)?;
+            }
+        }
+
+        if !preflight.fits {
+            return Err(anyhow::anyhow!(
+                "Not enough memory to load {}: needs ~{} MB, {} MB available. {}",
+                model_id,
+                preflight.required_mb,
+                preflight.available_ram_mb,
+                preflight.recommendation.unwrap_or_default()
+            ));
+        }
+
+        let registry = self.registry.lock().unwrap();
+        let model = registry.models.get(model_id).context("Model not found")?;
+
+        if !model.installed {
+            return Err(anyhow::anyhow!("Model {} is not installed", model_id));
+        }
+
+        let model_file = self.model_path.join(&model.path);
+        if !model_file.exists() {
+            return Err(anyhow::anyhow!("Model file not found: {:?}", model_file));
+        }
+
+        // Resolve the draft model for speculative decoding, if one is paired and installed
+        let draft = registry
+            .draft_pairs
+            .get(model_id)
+            .filter(|pair| pair.enabled)
+            .and_then(|pair| {
+                let draft_model = registry.models.get(&pair.draft_model_id)?;
+                let draft_file = self.model_path.join(&draft_model.path);
+                if draft_model.installed && draft_file.exists() {
+                    Some((pair.clone(), draft_file))
+                } else {
+                    log::warn!(
+                        "Draft model '{}' for '{}' is not installed, loading without speculative decoding",
+                        pair.draft_model_id,
+                        model_id
+                    );
+                    None
+                }
+            });
+
+        // Start llama.cpp server for this model
+        let server_port = self.get_available_port().await?;
+        let mut cmd = AsyncCommand::new("llama-server");
+        cmd.arg("--model")
+            .arg(&model_file)
+            .arg("--port")
+            .arg(server_port.to_string())
+            .arg("--host")
+            .arg("127.0.0.1")
+            .arg("--ctx-size")
+            .arg(DEFAULT_CONTEXT_LENGTH.to_string())
+            .arg("--threads")
+            .arg("8")
+            .arg("--n-gpu-layers")
+            .arg(preflight.gpu_layers.to_string())
+            .arg("--slot-save-path")
+            .arg(self.cache_path.join("kv_slots"))
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+
+        if let Some((pair, draft_file)) = &draft {
+            cmd.arg("--model-draft")
+                .arg(draft_file)
+                .arg("--draft-max")
+                .arg(pair.draft_max.to_string())
+                .arg("--draft-min")
+                .arg(pair.draft_min.to_string())
+                .arg("--draft-p-min")
+                .arg(pair.draft_p_min.to_string());
+            log::info!("Loading {} with draft model {}", model_id, pair.draft_model_id);
+        }
+
+        let capabilities = Self::model_capabilities(model_id);
+        if capabilities.iter().any(|c| c == "embed") {
+            cmd.arg("--embedding");
+        }
+
+        let mut child = cmd.spawn().context("Failed to start llama-server")?;
+
+        // Drain the server's output so the pipes never fill, noting GPU buffer allocations
+        self.logged_gpu_buffers.lock().unwrap().remove(model_id);
+        if let Some(stdout) = child.stdout.take() {
+            tokio::spawn(async move {
+                let mut lines = BufReader::new(stdout).lines();
+                while let Ok(Some(_)) = lines.next_line().await {}
+            });
+        }
+        if let Some(stderr) = child.stderr.take() {
+            let gpu_buffers = Arc::clone(&self.logged_gpu_buffers);
+            let log_model_id = model_id.to_string();
+            tokio::spawn(async move {
+                let mut lines = BufReader::new(stderr).lines();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    if let Some(mib) = Self::parse_gpu_buffer_line(&line) {
+                        *gpu_buffers.lock().unwrap().entry(log_model_id.clone()).or_insert(0) += mib;
+                    }
+                }
+            });
+        }
+
+        // Wait for server to be ready
+        tokio::time::sleep(tokio::time::Duration::from_secs(3)).await;
+
+        let url = format!("http://127.0.0.1:{}", server_port);
+
+        let loaded = LoadedModel {
+            model_id: model_id.to_string(),
+            endpoint: url.clone(),
+            port: server_port,
+            pid: child.id(),
+            capabilities,
+            loaded_at: chrono::Utc::now().to_rfc3339(),
+            last_used: chrono::Utc::now().to_rfc3339(),
+            memory_mb: 0,
+            vram_mb: 0,
+            healthy: true,
+            last_health_check: None,
+        };
+
+        // Store running model
+        {
+            let mut running_models = self.running_models.lock().unwrap();
+            running_models.insert(model_id.to_string(), child);
+        }
+        self.loaded_models.lock().unwrap().insert(model_id.to_string(), loaded);
+        self.prompt_cache.lock().unwrap().resident.remove(model_id);
+        self.save_server_pids();
+
+        if let Some(tracker) = crate::performance_tracker::get_performance_tracker() {
+            tracker.record_model_load(model_id).await;
+        }
+
+        Ok(url)
+    }
+
+    /// File of an installed model, for backends other than llama-server (e.g. Whisper)
+    pub fn installed_model_file(&self, model_id: &str) -> Result<PathBuf> {
+        let registry = self.registry.lock().unwrap();
+        let model = registry.models.get(model_id).context("Model not found")?;
+        if !model.installed {
+            return Err(anyhow::anyhow!("Model {} is not installed", model_id));
+        }
+
+        let model_file = self.model_path.join(&model.path);
+        if !model_file.exists() {
+            return Err(anyhow::anyhow!("Model file not found: {:?}", model_file));
+        }
+        Ok(model_file)
+    }
+
+    /// Embedding models are started in embedding mode; whisper models are run by the
+    /// transcription module; everything else serves chat/completion
+    fn model_capabilities(model_id: &str) -> Vec<String> {
+        let id = model_id.to_lowercase();
+        if id.contains("embed") || id.contains("bge") || id.contains("minilm") || id.contains("e5-") {
+            vec!["embed".to_string()]
+        } else if id.contains("whisper") {
+            vec!["transcribe".to_string()]
+        } else {
+            vec!["chat".to_string(), "completion".to_string()]
+        }
+    }
+
+    /// List loaded models with a fresh health ping and resident memory for each
+    pub async fn list_loaded_models(&self) -> Vec<LoadedModel> {
+        let snapshot: Vec<LoadedModel> = self.loaded_models.lock().unwrap().values().cloned().collect();
+
+        let mut sys = System::new();
+        sys.refresh_processes(sysinfo::ProcessesToUpdate::All, true);
+
+        let vram_by_model = self.vram_by_model(&snapshot);
+
+        let mut refreshed = Vec::with_capacity(snapshot.len());
+        for mut model in snapshot {
+            model.healthy = self.ping_endpoint(&model.endpoint).await;
+            model.last_health_check = Some(chrono::Utc::now().to_rfc3339());
+            model.memory_mb = model
+                .pid
+                .and_then(|pid| sys.process(sysinfo::Pid::from_u32(pid)))
+                .map(|process| process.memory() / 1024 / 1024)
+                .unwrap_or(0);
+            model.vram_mb = vram_by_model.get(&model.model_id).copied().unwrap_or(0);
+            refreshed.push(model);
+        }
+
+        // Write the results back so other callers see the latest health state
+        {
+            let mut loaded = self.loaded_models.lock().unwrap();
+            for model in &refreshed {
+                if let Some(entry) = loaded.get_mut(&model.model_id) {
+                    entry.healthy = model.healthy;
+                    entry.last_health_check = model.last_health_check.clone();
+                    entry.memory_mb = model.memory_mb;
+                    entry.vram_mb = model.vram_mb;
+                }
+            }
+        }
+
+        refreshed.sort_by(|a, b| a.model_id.cmp(&b.model_id));
+        refreshed
+    }
+
+    /// VRAM attributed to each loaded model: per-process NVML/nvidia-smi figures where the
+    /// driver reports them, otherwise the buffer sizes the server logged (Metal, WDDM, ROCm)
+    fn vram_by_model(&self, models: &[LoadedModel]) -> HashMap<String, u64> {
+        let per_process = Self::gpu_memory_by_pid();
+        let logged = self.logged_gpu_buffers.lock().unwrap().clone();
+
+        models
+            .iter()
+            .map(|model| {
+                let vram_mb = model
+                    .pid
+                    .and_then(|pid| per_process.get(&pid).copied())
+                    .map(|bytes| bytes / 1024 / 1024)
+                    .or_else(|| logged.get(&model.model_id).copied())
+                    .unwrap_or(0);
+                (model.model_id.clone(), vram_mb)
+            })
+            .collect()
+    }
+
+    /// GPU memory in bytes per process id, from NVML
+    #[cfg(feature = "gpu-detection")]
+    fn gpu_memory_by_pid() -> HashMap<u32, u64> {
+        use nvml_wrapper::enums::device::UsedGpuMemory;
+        use nvml_wrapper::Nvml;
+
+        let mut usage = HashMap::new();
+        if let Ok(nvml) = Nvml::init() {
+            let device_count = nvml.device_count().unwrap_or(0);
+            for i in 0..device_count {
+                if let Ok(device) = nvml.device_by_index(i) {
+                    for process in device.running_compute_processes().unwrap_or_default() {
+                        if let UsedGpuMemory::Used(bytes) = process.used_gpu_memory {
+                            *usage.entry(process.pid).or_insert(0) += bytes;
+                        }
+                    }
+                }
+            }
+        }
+        usage
+    }
+
+    /// GPU memory in bytes per process id, from nvidia-smi
+    #[cfg(not(feature = "gpu-detection"))]
+    fn gpu_memory_by_pid() -> HashMap<u32, u64> {
+        let mut usage = HashMap::new();
+        if let Ok(output) = Command::new("nvidia-smi")
+            .arg("--query-compute-apps=pid,used_memory")
+            .arg("--format=csv,noheader,nounits")
+            .output()
+        {
+            if output.status.success() {
+                for line in String::from_utf8_lossy(&output.stdout).lines() {
+                    let parts: Vec<&str> = line.split(',').map(|s| s.trim()).collect();
+                    if let (Some(pid), Some(mib)) = (
+                        parts.first().and_then(|p| p.parse::<u32>().ok()),
+                        parts.get(1).and_then(|m| m.parse::<u64>().ok()),
+                    ) {
+                        *usage.entry(pid).or_insert(0) += mib * 1024 * 1024;
+                    }
+                }
+            }
+        }
+        usage
+    }
+
+    /// Parse llama.cpp allocation lines such as
+    /// `llm_load_tensors:      CUDA0 buffer size =  3577.56 MiB` or
+    /// `llama_kv_cache_init:      Metal KV buffer size =   512.00 MiB`
+    fn parse_gpu_buffer_line(line: &str) -> Option<u64> {
+        if !line.contains("buffer size") {
+            return None;
+        }
+        let on_gpu = ["CUDA", "Metal", "ROCm", "Vulkan", "SYCL"].iter().any(|backend| line.contains(backend));
+        if !on_gpu {
+            return None;
+        }
+
+        let value = line.split('=').nth(1)?.trim();
+        let number: f64 = value.split_whitespace().next()?.parse().ok()?;
+        if value.contains("GiB") {
+            Some((number * 1024.0) as u64)
+        } else {
+            Some(number as u64)
+        }
+    }
+
+    /// Unload least recently used GPU-resident models until `needed_mb` of VRAM is freed
+    /// Returns the VRAM freed in MB
+    async fn evict_for_vram(&self, loading_model_id: &str, needed_mb: u64) -> u64 {
+        let snapshot: Vec<LoadedModel> = self.loaded_models.lock().unwrap().values().cloned().collect();
+        let vram_by_model = self.vram_by_model(&snapshot);
+
+        let mut candidates: Vec<(LoadedModel, u64)> = snapshot
+            .into_iter()
+            .filter(|m| m.model_id != loading_model_id)
+            .filter_map(|m| {
+                let vram = vram_by_model.get(&m.model_id).copied().unwrap_or(0);
+                (vram > 0).then_some((m, vram))
+            })
+            .collect();
+        candidates.sort_by(|a, b| a.0.last_used.cmp(&b.0.last_used));
+
+        let mut freed_mb = 0;
+        for (model, vram_mb) in candidates {
+            if freed_mb >= needed_mb {
+                break;
+            }
+            log::info!(
+                "Evicting model {} ({} MB VRAM) to make room for {}",
+                model.model_id,
+                vram_mb,
+                loading_model_id
+            );
+            if self.unload_model(&model.model_id).await.is_ok() {
+                freed_mb += vram_mb;
+            }
+        }
+        freed_mb
+    }
+
+    /// GPU memory totals alongside what each loaded model is holding
+    pub async fn vram_usage(&self) -> VramUsage {
+        let gpu_info = self.detect_gpu_info();
+        let read_mb = |key: &str| gpu_info.get(key).and_then(|v| v.parse::<u64>().ok()).unwrap_or(0);
+
+        let models = self.list_loaded_models().await;
+        VramUsage {
+            total_mb: read_mb("gpu_memory_total"),
+            free_mb: read_mb("gpu_memory_free"),
+            attributed_mb: models.iter().map(|m| m.vram_mb).sum(),
+            models,
+        }
+    }
+
+    /// Run a fixed, deterministic generation and measure throughput and latency.
+    /// The GPU and system state are captured so regressions can be explained later.
+    pub async fn run_benchmark(&self, model_id: &str) -> Result<crate::performance_tracker::BenchmarkResult> {
+        let request = GenerateRequest {
+            model: model_id.to_string(),
+            prompt: BENCHMARK_PROMPT.to_string(),
+            stream: Some(false),
+            options: Some(GenerateOptions {
+                num_predict: Some(BENCHMARK_TOKENS),
+                temperature: Some(0.0),
+                seed: Some(42),
+                ..Default::default()
+            }),
+            system: None,
+            template: None,
+            context: None,
+            raw: None,
+        };
+
+        // Warm-up pass so model loading is not part of the measurement
+        self.generate_response(request.clone()).await?;
+
+        let started = std::time::Instant::now();
+        let response = self.generate_response(request).await?;
+        let wall_clock_ms = started.elapsed().as_millis() as u64;
+
+        let completion_tokens = response.eval_count.unwrap_or(0);
+        // Ollama reports durations in nanoseconds
+        let tokens_per_second = match response.eval_duration {
+            Some(ns) if ns > 0 => completion_tokens as f32 / (ns as f32 / 1_000_000_000.0),
+            _ if wall_clock_ms > 0 => completion_tokens as f32 / (wall_clock_ms as f32 / 1000.0),
+            _ => 0.0,
+        };
+        let time_to_first_token_ms = response
+            .prompt_eval_duration
+            .map(|ns| ns / 1_000_000)
+            .unwrap_or(wall_clock_ms);
+
+        let gpu_info = self.detect_gpu_info();
+        let known = |key: &str| gpu_info.get(key).filter(|v| v.as_str() != "none" && v.as_str() != "0").cloned();
+
+        let (cpu_usage_percent, memory_usage_percent) = match crate::performance_tracker::get_performance_tracker() {
+            Some(tracker) => {
+                let system = tracker.get_system_metrics().await;
+                (system.cpu_usage_percent, system.memory_usage_percent)
+            }
+            None => (0.0, 0.0),
+        };
+
+        Ok(crate::performance_tracker::BenchmarkResult {
+            model_name: model_id.to_string(),
+            timestamp: chrono::Utc::now().timestamp() as u64,
+            tokens_per_second,
+            time_to_first_token_ms,
+            total_latency_ms: wall_clock_ms,
+            prompt_tokens: response.prompt_eval_count.unwrap_or(0),
+            completion_tokens,
+            gpu_models: known("gpu_models"),
+            gpu_driver_version: known("gpu_driver_version"),
+            gpu_memory_free_mb: known("gpu_memory_free").and_then(|v| v.parse().ok()).unwrap_or(0),
+            cpu_usage_percent,
+            memory_usage_percent,
+        })
+    }
+
+    /// Record that a loaded model served a request (drives LRU eviction)
+    fn touch_loaded_model(&self, model_id: &str) {
+        if let Some(model) = self.loaded_models.lock().unwrap().get_mut(model_id) {
+            model.last_used = chrono::Utc::now().to_rfc3339();
+        }
+    }
+
+    /// Find a loaded model offering a capability ("chat" or "embed"), preferring healthy ones
+    pub fn find_loaded_model(&self, capability: &str) -> Option<LoadedModel> {
+        let loaded = self.loaded_models.lock().unwrap();
+        let mut candidates: Vec<&LoadedModel> = loaded
+            .values()
+            .filter(|m| m.capabilities.iter().any(|c| c == capability))
+            .collect();
+        candidates.sort_by_key(|m| !m.healthy);
+        candidates.first().map(|m| (*m).clone())
+    }
+
+    /// llama-server answers 200 on /health once the model is ready
+    async fn ping_endpoint(&self, endpoint: &str) -> bool {
+        self.http_client
+            .get(format!("{}/health", endpoint))
+            .timeout(Duration::from_secs(2))
+            .send()
+            .await
+            .map(|r| r.status().is_success())
+            .unwrap_or(false)
+    }
+
+    /// Generate with a reusable prompt prefix (system prompt + document context).
+    /// The KV cache for the prefix is saved per prompt hash and restored on follow-up
+    /// questions, so only the new suffix has to be evaluated.
+    pub async fn generate_with_prefix_cache(
+        &self,
+        model_id: &str,
+        prefix: &str,
+        suffix: &str,
+        n_predict: Option<i32>,
+    ) -> Result<PrefixCachedCompletion> {
+        let existing_url = self.loaded_models.lock().unwrap().get(model_id).map(|m| m.endpoint.clone());
+        self.touch_loaded_model(model_id);
+        let url = match existing_url {
+            Some(url) => url,
+            None => self.load_model(model_id).await?,
+        };
+
+        let prompt_hash = Self::prompt_hash(model_id, prefix);
+        let filename = format!("{}.bin", prompt_hash);
+
+        let (resident, known) = {
+            let cache = self.prompt_cache.lock().unwrap();
+            (
+                cache.resident.get(model_id) == Some(&prompt_hash),
+                cache.entries.contains_key(&prompt_hash),
+            )
+        };
+
+        // Restore the saved slot unless the prefix is already resident in the server
+        let mut cache_hit = resident;
+        if !resident && known && self.cache_path.join("kv_slots").join(&filename).exists() {
+            let restore = self
+                .http_client
+                .post(format!("{}/slots/0?action=restore", url))
+                .json(&serde_json::json!({ "filename": filename }))
+                .send()
+                .await;
+            match restore {
+                Ok(response) if response.status().is_success() => cache_hit = true,
+                Ok(response) => log::warn!("KV slot restore failed: {}", response.status()),
+                Err(e) => log::warn!("KV slot restore failed: {}", e),
+            }
+        }
+
+        let response = self
+            .http_client
+            .post(format!("{}/completion", url))
+            .json(&serde_json::json!({
+                "prompt": format!("{}{}", prefix, suffix),
+                "n_predict": n_predict.unwrap_or(-1),
+                "cache_prompt": true,
+                "id_slot": 0
+            }))
+            .send()
+            .await
+            .context("Failed to send completion request")?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(anyhow::anyhow!("Completion request failed: {}", error_text));
+        }
+
+        let body: Value = response.json().await.context("Failed to parse completion response")?;
+
+        // Save the slot the first time this prefix is seen
+        if !known {
+            let saved = self
+                .http_client
+                .post(format!("{}/slots/0?action=save", url))
+                .json(&serde_json::json!({ "filename": filename }))
+                .send()
+                .await
+                .map(|r| r.status().is_success())
+                .unwrap_or(false);
+            if !saved {
+                log::warn!("Failed to save KV slot for prompt prefix {}", prompt_hash);
+            }
+        }
+
+        self.update_prompt_cache(model_id, &prompt_hash, &filename, prefix.len())?;
+
+        Ok(PrefixCachedCompletion {
+            content: body["content"].as_str().unwrap_or_default().to_string(),
+            prompt_hash,
+            cache_hit,
+            tokens_cached: body["tokens_cached"].as_u64().map(|t| t as u32),
+            tokens_evaluated: body["tokens_evaluated"].as_u64().map(|t| t as u32),
+        })
+    }
+
+    /// Hash of the model and prompt prefix used as the KV-cache key
+    fn prompt_hash(model_id: &str, prefix: &str) -> String {
+        use sha2::{Digest, Sha256};
+
+        let mut hasher = Sha256::new();
+        hasher.update(model_id.as_bytes());
+        hasher.update([0u8]);
+        hasher.update(prefix.as_bytes());
+        format!("{:x}", hasher.finalize())[..32].to_string()
+    }
+
+    /// Record a prefix cache use, evict the least recently used entries and persist the index
+    fn update_prompt_cache(&self, model_id: &str, prompt_hash: &str, filename: &str, prefix_chars: usize) -> Result<()> {
+        let now = chrono::Utc::now().timestamp() as u64;
+        let mut cache = self.prompt_cache.lock().unwrap();
+
+        let entry = cache
+            .entries
+            .entry(prompt_hash.to_string())
+            .or_insert_with(|| PromptCacheEntry {
+                prompt_hash: prompt_hash.to_string(),
+                model_id: model_id.to_string(),
+                filename: filename.to_string(),
+                prefix_chars,
+                created_at: now,
+                last_used: now,
+                hits: 0,
+            });
+        entry.hits += 1;
+        entry.last_used = now;
+        cache.resident.insert(model_id.to_string(), prompt_hash.to_string());
+
+        while cache.entries.len() > MAX_PROMPT_CACHE_ENTRIES {
+            let oldest = cache
+                .entries
+                .values()
+                .min_by_key(|e| e.last_used)
+                .map(|e| e.prompt_hash.clone());
+            match oldest.and_then(|hash| cache.entries.remove(&hash)) {
+                Some(evicted) => {
+                    let _ = fs::remove_file(self.cache_path.join("kv_slots").join(&evicted.filename));
+                }
+                None => break,
+            }
+        }
+
+        fs::write(self.cache_path.join("prompt_cache.json"), serde_json::to_string_pretty(&*cache)?)?;
+        Ok(())
+    }
+
+    /// List saved prompt-prefix cache entries
+    pub fn list_prompt_cache(&self) -> Vec<PromptCacheEntry> {
+        self.prompt_cache.lock().unwrap().entries.values().cloned().collect()
+    }
+
+    /// Drop all saved prompt-prefix caches (or only those for one model)
+    pub fn clear_prompt_cache(&self, model_id: Option<&str>) -> Result<usize> {
+        let mut cache = self.prompt_cache.lock().unwrap();
+        let removed: Vec<PromptCacheEntry> = cache
+            .entries
+            .values()
+            .filter(|e| model_id.map_or(true, |id| e.model_id == id))
+            .cloned()
+            .collect();
+
+        for entry in &removed {
+            cache.entries.remove(&entry.prompt_hash);
+            let _ = fs::remove_file(self.cache_path.join("kv_slots").join(&entry.filename));
+        }
+        match model_id {
+            Some(id) => {
+                cache.resident.remove(id);
+            }
+            None => cache.resident.clear(),
+        }
+
+        fs::write(self.cache_path.join("prompt_cache.json"), serde_json::to_string_pretty(&*cache)?)?;
+        Ok(removed.len())
+    }
+
+    /// The model `generate_response` and `chat` answer with when the caller names none: the first
+    /// one the Ollama-compatible server has in memory, else the first it has installed. Models
+    /// running as llama-server instances are not served there, so they are not candidates.
+    pub async fn default_generation_model(&self) -> Option<String> {
+        for endpoint in ["api/ps", "api/tags"] {
+            if let Some(model) = self.served_model_names(endpoint).await.into_iter().next() {
+                return Some(model);
+            }
+        }
+        None
+    }
+
+    async fn served_model_names(&self, endpoint: &str) -> Vec<String> {
+        let response = match self
+            .http_client
+            .get(format!("{}/{}", self.ollama_base_url, endpoint))
+            .timeout(Duration::from_secs(5))
+            .send()
+            .await
+        {
+            Ok(response) if response.status().is_success() => response,
+            _ => return Vec::new(),
+        };
+        let body: Value = response.json().await.unwrap_or_default();
+        body["models"]
+            .as_array()
+            .map(|models| models.iter().filter_map(|m| m["name"].as_str().map(String::from)).collect())
+            .unwrap_or_default()
+    }
+
+    /// Pick the most frequently used installed model, based on tracked usage history
+    pub async fn most_used_installed_model(&self) -> Option<String> {
+        let tracker = crate::performance_tracker::get_performance_tracker()?;
+        let candidates = tracker.get_most_used_models(10).await;
+
+        let registry = self.registry.lock().unwrap();
+        candidates
+            .into_iter()
+            .map(|stats| stats.model_name)
+            .find(|name| registry.models.get(name).map(|m| m.installed).unwrap_or(false))
+    }
+
+    /// Load a model ahead of use and run a one-token completion so the first request is fast
+    pub async fn warmup_model(&self, model_id: &str) -> Result<ModelWarmupStatus> {
+        let start = std::time::Instant::now();
+        let skipped = |message: String| ModelWarmupStatus {
+            model_id: Some(model_id.to_string()),
+            state: "skipped".to_string(),
+            message: Some(message),
+            duration_ms: 0,
+        };
+
+        if self.running_models.lock().unwrap().contains_key(model_id) {
+            return Ok(skipped("Model is already loaded".to_string()));
+        }
+
+        // Warm-up is opportunistic: never compete with real work for resources
+        if let Some(tracker) = crate::performance_tracker::get_performance_tracker() {
+            let guard = tracker.check_resource_guards().await?;
+            if !guard.allowed {
+                return Ok(skipped(guard.reason.unwrap_or_else(|| "Insufficient resources".to_string())));
+            }
+        }
+
+        let url = self.load_model(model_id).await?;
+
+        // A failed priming request is not fatal, the server itself is up
+        let prime = self
+            .http_client
+            .post(format!("{}/completion", url))
+            .json(&serde_json::json!({ "prompt": "Hello", "n_predict": 1 }))
+            .timeout(Duration::from_secs(60))
+            .send()
+            .await;
+        if let Err(e) = prime {
+            log::warn!("Warm-up completion for model '{}' failed: {}", model_id, e);
+        }
+
+        log::info!("Model {} warmed up in {}ms", model_id, start.elapsed().as_millis());
+        Ok(ModelWarmupStatus {
+            model_id: Some(model_id.to_string()),
+            state: "ready".to_string(),
+            message: None,
+            duration_ms: start.elapsed().as_millis() as u64,
+        })
+    }
+
+    /// Estimate RAM needed to load a model and check it against what is available right now.
+    /// Also verifies the file is a complete GGUF that can be memory-mapped.
+    pub fn preflight_model_load(&self, model_id: &str, context_length: u32) -> Result<LoadPreflight> {
+        let (model_file, quantization) = {
+            let registry = self.registry.lock().unwrap();
+            let model = registry.models.get(model_id).context("Model not found")?;
+            (self.model_path.join(&model.path), model.quantization.clone())
+        };
+
+        let file_size = check_gguf_file(&model_file)?;
+
+        // Use the real shape from the GGUF header when it can be read, otherwise estimate
+        let metadata = crate::model_commands::read_gguf_metadata(&model_file).ok();
+
+        let mut sys = System::new_all();
+        sys.refresh_memory();
+        let available_ram_mb = sys.available_memory() / 1024 / 1024;
+        let free_vram_mb = self
+            .detect_gpu_info()
+            .get("gpu_memory_free")
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(0);
+
+        Ok(estimate_load(
+            model_id,
+            quantization,
+            file_size,
+            metadata.as_ref(),
+            context_length,
+            available_ram_mb,
+            free_vram_mb,
+        ))
+    }
+
+    /// Approximate bits per weight for common GGUF quantizations
+    fn quantization_bits(quantization: &str) -> f32 {
+        let q = quantization.to_uppercase();
+        if q.starts_with("Q2") {
+            2.6
+        } else if q.starts_with("Q3") {
+            3.9
+        } else if q.starts_with("Q4") {
+            4.85
+        } else if q.starts_with("Q5") {
+            5.7
+        } else if q.starts_with("Q6") {
+            6.6
+        } else if q.starts_with("Q8") {
+            8.5
+        } else if q == "F32" {
+            32.0
+        } else {
+            16.0
+        }
+    }
+
+    /// Get the recorded provenance of a downloaded model
+    pub fn get_model_provenance(&self, model_id: &str) -> Result<ModelProvenance> {
+        let registry = self.registry.lock().unwrap();
+        if let Some(provenance) = registry.provenance.get(model_id) {
+            return Ok(provenance.clone());
+        }
+
+        // Models installed before provenance tracking only have what the registry knows
+        let model = registry.models.get(model_id).context("Model not found")?;
+        let (commercial_use_allowed, license_warning) = Self::check_commercial_license(model.license.as_deref());
+        let (hf_repo, hf_revision) = model
+            .download_url
+            .as_deref()
+            .map(Self::parse_huggingface_url)
+            .unwrap_or((None, None));
+
+        Ok(ModelProvenance {
+            model_id: model_id.to_string(),
+            source_url: model.download_url.clone().unwrap_or_default(),
+            hf_repo,
+            hf_revision,
+            sha256: String::new(),
+            file_size: model.size,
+            downloaded_at: String::new(),
+            license: model.license.clone(),
+            commercial_use_allowed,
+            license_warning,
+        })
+    }
+
+    /// Extract repo and revision from a HuggingFace `resolve` URL
+    fn parse_huggingface_url(url: &str) -> (Option<String>, Option<String>) {
+        let path = match url.strip_prefix("https://huggingface.co/") {
+            Some(path) => path,
+            None => return (None, None),
+        };
+
+        let parts: Vec<&str> = path.split('/').collect();
+        let repo = if parts.len() >= 2 {
+            Some(format!("{}/{}", parts[0], parts[1]))
+        } else {
+            None
+        };
+        let revision = match parts.get(2) {
+            Some(&"resolve") | Some(&"blob") => parts.get(3).map(|r| r.to_string()),
+            _ => None,
+        };
 
-            if let Some(ref callback) = progress_callback {
-                callback(downloaded, total_size);
+        (repo, revision)
+    }
+
+    /// Decide whether a license permits commercial use (legal practice counts as commercial)
+    pub(crate) fn check_commercial_license(license: Option<&str>) -> (bool, Option<String>) {
+        let license = match license {
+            Some(license) if !license.trim().is_empty() => license,
+            _ => {
+                return (
+                    false,
+                    Some("License unknown; verify the model card before commercial use".to_string()),
+                )
             }
+        };
+
+        let normalized = license.to_lowercase();
+        let non_commercial = ["-nc", "non-commercial", "noncommercial", "research only", "research-only"];
+        if non_commercial.iter().any(|marker| normalized.contains(marker)) {
+            return (
+                false,
+                Some(format!("License '{}' does not permit commercial use", license)),
+            );
         }
 
-        // Verify download
-        let file_size = async_fs::metadata(&model_file).await?.len();
-        if total_size > 0 && file_size != total_size {
-            return Err(anyhow::anyhow!(
-                "Download verification failed: expected {} bytes, got {}",
-                total_size,
-                file_size
-            ));
+        if normalized.contains("llama") || normalized.contains("gemma") {
+            return (
+                true,
+                Some(format!("License '{}' permits commercial use subject to its acceptable use policy", license)),
+            );
+        }
+
+        (true, None)
+    }
+
+    /// Pair a draft model with a target model for speculative decoding
+    pub fn set_draft_model(&self, target_model_id: &str, config: DraftModelConfig) -> Result<()> {
+        if target_model_id == config.draft_model_id {
+            return Err(anyhow::anyhow!("A model cannot be its own draft model"));
+        }
+        if config.draft_min > config.draft_max {
+            return Err(anyhow::anyhow!("draft_min must not exceed draft_max"));
+        }
+        if !(0.0..=1.0).contains(&config.draft_p_min) {
+            return Err(anyhow::anyhow!("draft_p_min must be between 0 and 1"));
         }
 
-        // Update registry
         let mut registry = self.registry.lock().unwrap();
-        let mut updated_model = model.clone();
-        updated_model.installed = true;
-        registry.models.insert(model_id.to_string(), updated_model);
+        let target = registry.models.get(target_model_id).context("Target model not found")?;
+        let draft = registry.models.get(&config.draft_model_id).context("Draft model not found")?;
+
+        // Draft and target must share a vocabulary; a smaller sibling of the same family is expected
+        if draft.size >= target.size {
+            log::warn!(
+                "Draft model '{}' is not smaller than target '{}', speculative decoding may be slower",
+                draft.id,
+                target.id
+            );
+        }
 
+        registry.draft_pairs.insert(target_model_id.to_string(), config);
         self.save_registry(&registry)?;
 
-        log::info!("Model {} downloaded successfully", model.name);
+        log::info!("Draft model configured for {} (takes effect on next load)", target_model_id);
         Ok(())
     }
 
-    /// Load a model for inference with resource guards
-    pub async fn load_model(&self, model_id: &str) -> Result<String> {
-        // CHECK RESOURCE GUARDS BEFORE LOADING MODEL
-        if let Some(tracker) = crate::performance_tracker::get_performance_tracker() {
-            // Acquire operation permit with resource checking
-            tracker.acquire_operation_permit().await
-                .map_err(|e| anyhow::anyhow!("Resource guard denied model loading: {}", e))?;
+    /// Remove the draft model pairing for a target model
+    pub fn remove_draft_model(&self, target_model_id: &str) -> Result<()> {
+        let mut registry = self.registry.lock().unwrap();
+        if registry.draft_pairs.remove(target_model_id).is_some() {
+            self.save_registry(&registry)?;
+        }
+        Ok(())
+    }
 
-            // Ensure permit is released on function exit using scopeguard
-            let _permit_guard = scopeguard::defer! {
-                if let Some(tracker) = crate::performance_tracker::get_performance_tracker() {
-                    tracker.release_operation_permit();
+    /// List configured draft model pairings
+    pub fn list_draft_models(&self) -> HashMap<String, DraftModelConfig> {
+        self.registry.lock().unwrap().draft_pairs.clone()
+    }
+
+    /// Unload a running model
+    pub async fn unload_model(&self, model_id: &str) -> Result<()> {
+        let child = self.running_models.lock().unwrap().remove(model_id);
+        self.loaded_models.lock().unwrap().remove(model_id);
+        self.logged_gpu_buffers.lock().unwrap().remove(model_id);
+        self.prompt_cache.lock().unwrap().resident.remove(model_id);
+        if let Some(mut child) = child {
+            child.kill().await?;
+            log::info!("Model {} unloaded", model_id);
+        }
+        self.save_server_pids();
+        Ok(())
+    }
+
+    /// Start the background supervisor that health-checks spawned servers and restarts
+    /// crashed ones with exponential backoff
+    pub fn start_supervisor(self: Arc<Self>, app: tauri::AppHandle) {
+        tauri::async_runtime::spawn(async move {
+            let mut states: HashMap<String, SupervisorState> = HashMap::new();
+
+            loop {
+                tokio::time::sleep(SUPERVISOR_INTERVAL).await;
+                self.supervise_once(&app, &mut states).await;
+            }
+        });
+    }
+
+    async fn supervise_once(&self, app: &tauri::AppHandle, states: &mut HashMap<String, SupervisorState>) {
+        // Processes that have exited since the last tick
+        let exited: HashMap<String, Option<i32>> = {
+            let mut running = self.running_models.lock().unwrap();
+            running
+                .iter_mut()
+                .filter_map(|(id, child)| match child.try_wait() {
+                    Ok(Some(status)) => Some((id.clone(), status.code())),
+                    _ => None,
+                })
+                .collect()
+        };
+
+        let loaded: Vec<LoadedModel> = self.loaded_models.lock().unwrap().values().cloned().collect();
+
+        // Forget models that were unloaded on purpose, keep those waiting for a restart
+        states.retain(|id, state| state.restart_pending || loaded.iter().any(|m| &m.model_id == id));
+
+        let mut model_ids: Vec<String> = loaded.iter().map(|m| m.model_id.clone()).collect();
+        for (id, state) in states.iter() {
+            if state.restart_pending && !model_ids.contains(id) {
+                model_ids.push(id.clone());
+            }
+        }
+
+        for model_id in model_ids {
+            let loaded_at = loaded.iter().find(|m| m.model_id == model_id).map(|m| m.loaded_at.clone());
+            let state = states.entry(model_id.clone()).or_default();
+            // A server started or reloaded after the supervisor gave up is supervised afresh
+            if state.gave_up && loaded_at.is_some() && loaded_at != state.loaded_at {
+                *state = SupervisorState::default();
+            }
+            if loaded_at.is_some() {
+                state.loaded_at = loaded_at;
+            }
+            if state.gave_up {
+                continue;
+            }
+
+            let needs_restart = if state.restart_pending {
+                true
+            } else if let Some(code) = exited.get(&model_id) {
+                let message = format!("llama-server exited with code {:?}", code);
+                Self::emit_process_event(app, &model_id, "crashed", Some(message), state.restart_attempts);
+                true
+            } else {
+                let endpoint = loaded.iter().find(|m| m.model_id == model_id).map(|m| m.endpoint.clone());
+                let healthy = match endpoint {
+                    Some(endpoint) => self.ping_endpoint(&endpoint).await,
+                    None => false,
+                };
+
+                if healthy {
+                    state.failed_pings = 0;
+                    state.restart_attempts = 0;
+                    false
+                } else {
+                    state.failed_pings += 1;
+                    if state.failed_pings >= UNHEALTHY_PING_THRESHOLD {
+                        let message = format!("{} consecutive health checks failed", state.failed_pings);
+                        Self::emit_process_event(app, &model_id, "unhealthy", Some(message), state.restart_attempts);
+                        true
+                    } else {
+                        false
+                    }
                 }
             };
+
+            if !needs_restart {
+                continue;
+            }
+            state.restart_pending = true;
+
+            if state.restart_attempts >= MAX_RESTART_ATTEMPTS {
+                state.gave_up = true;
+                state.restart_pending = false;
+                let _ = self.unload_model(&model_id).await;
+                let message = format!("Stopped restarting after {} attempts", state.restart_attempts);
+                Self::emit_process_event(app, &model_id, "gave_up", Some(message), state.restart_attempts);
+                continue;
+            }
+
+            if state.next_restart.map_or(false, |at| std::time::Instant::now() < at) {
+                continue;
+            }
+
+            state.restart_attempts += 1;
+            let backoff = RESTART_BACKOFF_BASE_SECS * 2u64.pow(state.restart_attempts - 1);
+            state.next_restart = Some(std::time::Instant::now() + Duration::from_secs(backoff));
+            Self::emit_process_event(app, &model_id, "restarting", None, state.restart_attempts);
+
+            let _ = self.unload_model(&model_id).await;
+            match self.load_model(&model_id).await {
+                Ok(_) => {
+                    state.restart_pending = false;
+                    state.failed_pings = 0;
+                    Self::emit_process_event(app, &model_id, "restarted", None, state.restart_attempts);
+                }
+                Err(e) => {
+                    Self::emit_process_event(app, &model_id, "restart_failed", Some(e.to_string()), state.restart_attempts);
+                }
+            }
         }
+    }
 
-        let registry = self.registry.lock().unwrap();
-        let model = registry.models.get(model_id).context("Model not found")?;
+    /// Emit a supervisor event and raise a desktop notification for failures
+    fn emit_process_event(app: &tauri::AppHandle, model_id: &str, event: &str, message: Option<String>, restart_attempt: u32) {
+        use tauri::Manager;
 
-        if !model.installed {
-            return Err(anyhow::anyhow!("Model {} is not installed", model_id));
+        match event {
+            "restarted" => log::info!("Model {} restarted (attempt {})", model_id, restart_attempt),
+            _ => log::warn!("Model {} {}: {}", model_id, event, message.as_deref().unwrap_or("")),
         }
 
-        let model_file = self.model_path.join(&model.path);
-        if !model_file.exists() {
-            return Err(anyhow::anyhow!("Model file not found: {:?}", model_file));
+        if matches!(event, "crashed" | "gave_up") {
+            let body = match event {
+                "crashed" => format!("Model {} stopped unexpectedly and is being restarted.", model_id),
+                _ => format!("Model {} keeps failing and has been stopped.", model_id),
+            };
+            let _ = tauri::api::notification::Notification::new(&app.config().tauri.bundle.identifier)
+                .title("BEAR AI model process")
+                .body(body)
+                .show();
         }
 
-        // Start llama.cpp server for this model
-        let server_port = self.get_available_port().await?;
-        let mut cmd = AsyncCommand::new("llama-server");
-        cmd.arg("--model")
-            .arg(&model_file)
-            .arg("--port")
-            .arg(server_port.to_string())
-            .arg("--host")
-            .arg("127.0.0.1")
-            .arg("--ctx-size")
-            .arg("4096")
-            .arg("--threads")
-            .arg("8")
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped());
+        let _ = app.emit_all(MODEL_PROCESS_EVENT, ModelProcessEvent {
+            model_id: model_id.to_string(),
+            event: event.to_string(),
+            message,
+            restart_attempt,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        });
+    }
 
-        let mut child = cmd.spawn().context("Failed to start llama-server")?;
+    /// Kill every spawned llama-server; called on app shutdown
+    pub fn shutdown_all_models(&self) {
+        let children: Vec<(String, tokio::process::Child)> = self.running_models.lock().unwrap().drain().collect();
+        for (model_id, mut child) in children {
+            if let Err(e) = child.start_kill() {
+                log::warn!("Failed to stop model {}: {}", model_id, e);
+            }
+        }
+        self.loaded_models.lock().unwrap().clear();
+        let _ = fs::remove_file(self.cache_path.join("llama_server_pids.json"));
+    }
 
-        // Wait for server to be ready
-        tokio::time::sleep(tokio::time::Duration::from_secs(3)).await;
+    /// Kill llama-server processes left behind by a previous run that did not shut down cleanly
+    pub fn cleanup_orphaned_processes(&self) -> usize {
+        let pids_file = self.cache_path.join("llama_server_pids.json");
+        let pids: Vec<u32> = match fs::read_to_string(&pids_file).ok().and_then(|d| serde_json::from_str(&d).ok()) {
+            Some(pids) => pids,
+            None => return 0,
+        };
 
-        // Store running model
-        let mut running_models = self.running_models.lock().unwrap();
-        running_models.insert(model_id.to_string(), child);
+        let mut sys = System::new();
+        sys.refresh_processes(sysinfo::ProcessesToUpdate::All, true);
+
+        let mut killed = 0;
+        for pid in pids {
+            // Only kill if the PID still belongs to a llama-server (PIDs get reused)
+            if let Some(process) = sys.process(sysinfo::Pid::from_u32(pid)) {
+                if process.name().to_string_lossy().contains("llama-server") && process.kill() {
+                    killed += 1;
+                }
+            }
+        }
 
-        Ok(format!("http://127.0.0.1:{}", server_port))
+        let _ = fs::remove_file(&pids_file);
+        if killed > 0 {
+            log::warn!("Killed {} orphaned llama-server process(es)", killed);
+        }
+        killed
     }
 
-    /// Unload a running model
-    pub async fn unload_model(&self, model_id: &str) -> Result<()> {
-        let mut running_models = self.running_models.lock().unwrap();
-        if let Some(mut child) = running_models.remove(model_id) {
-            child.kill().await?;
-            log::info!("Model {} unloaded", model_id);
+    fn save_server_pids(&self) {
+        let pids: Vec<u32> = self.loaded_models.lock().unwrap().values().filter_map(|m| m.pid).collect();
+        if let Ok(json) = serde_json::to_string(&pids) {
+            let _ = fs::write(self.cache_path.join("llama_server_pids.json"), json);
         }
-        Ok(())
     }
 
     /// Remove a model from local storage
@@ -506,12 +2280,24 @@ impl LLMManager {
             gpu_info.insert("vulkan_available".to_string(), "true".to_string());
         }
 
-        // 6. Fallback: Use sysinfo for basic GPU detection
-        if detected_gpus.is_empty() {
-            if let Some(sysinfo_gpus) = self.detect_sysinfo_gpu() {
-                detected_gpus.extend(sysinfo_gpus);
+        // 6. Windows: DXGI sees every vendor's adapters, add those the vendor tools missed
+        let mut driver_versions = Vec::new();
+        for adapter in self.detect_windows_dxgi_gpus() {
+            if let Some(version) = &adapter.driver_version {
+                driver_versions.push(format!("{}: {}", adapter.name, version));
+            }
+            if detected_gpus.iter().any(|gpu| gpu.eq_ignore_ascii_case(&adapter.name)) {
+                continue;
+            }
+            detected_gpus.push(adapter.name);
+            total_memory += adapter.dedicated_memory;
+            if !vendors.contains(&adapter.vendor) {
+                vendors.push(adapter.vendor);
             }
         }
+        if !driver_versions.is_empty() {
+            gpu_info.insert("gpu_driver_version".to_string(), driver_versions.join(", "));
+        }
 
         // Update final results
         if !detected_gpus.is_empty() {
@@ -776,12 +2562,7 @@ impl LLMManager {
             }
         }
 
-        #[cfg(target_os = "windows")]
-        {
-            // Windows DirectX detection would go here
-            // For now, use sysinfo fallback
-        }
-
+        // On Windows, Intel adapters are picked up by DXGI enumeration
         None
     }
 
@@ -802,59 +2583,122 @@ impl LLMManager {
         false
     }
 
-    /// Fallback GPU detection using sysinfo
-    fn detect_sysinfo_gpu(&self) -> Option<Vec<String>> {
-        use sysinfo::System;
+    /// Windows GPU enumeration through DXGI: adapter name, vendor, dedicated VRAM and driver version
+    #[cfg(target_os = "windows")]
+    fn detect_windows_dxgi_gpus(&self) -> Vec<DxgiAdapterInfo> {
+        use windows::core::ComInterface;
+        use windows::Win32::Graphics::Dxgi::{
+            CreateDXGIFactory1, IDXGIDevice, IDXGIFactory1, DXGI_ADAPTER_DESC1, DXGI_ADAPTER_FLAG_SOFTWARE,
+        };
 
-        let mut sys = System::new_all();
-        sys.refresh_all();
+        let mut adapters = Vec::new();
+        let factory: IDXGIFactory1 = match unsafe { CreateDXGIFactory1() } {
+            Ok(factory) => factory,
+            Err(e) => {
+                log::warn!("DXGI factory creation failed: {}", e);
+                return adapters;
+            }
+        };
 
-        // Use system info to detect GPUs
-        let mut gpu_models = Vec::new();
+        // EnumAdapters1 returns DXGI_ERROR_NOT_FOUND past the last adapter
+        let mut index = 0;
+        while let Ok(adapter) = unsafe { factory.EnumAdapters1(index) } {
+            index += 1;
 
-        // On Windows, check for GPU via system name
-        #[cfg(target_os = "windows")]
-        {
-            if let Some(name) = sys.name() {
-                if name.to_lowercase().contains("nvidia") ||
-                   name.to_lowercase().contains("amd") ||
-                   name.to_lowercase().contains("intel") {
-                    gpu_models.push(format!("Detected GPU: {}", name));
-                }
+            let mut desc = DXGI_ADAPTER_DESC1::default();
+            if unsafe { adapter.GetDesc1(&mut desc) }.is_err() {
+                continue;
             }
-        }
 
-        // Generic fallback
-        if gpu_models.is_empty() {
-            // Try to detect via system host name or other properties
-            if sys.total_memory() > 8_000_000_000 { // More than 8GB RAM suggests possible dedicated GPU
-                gpu_models.push("GPU detected (generic)".to_string());
+            // Skip the Microsoft Basic Render Driver and other software adapters
+            if desc.Flags & DXGI_ADAPTER_FLAG_SOFTWARE.0 != 0 {
+                continue;
             }
-        }
 
-        if !gpu_models.is_empty() {
-            Some(gpu_models)
-        } else {
-            None
+            let name_len = desc.Description.iter().position(|&c| c == 0).unwrap_or(desc.Description.len());
+            let vendor = match desc.VendorId {
+                0x10DE => "NVIDIA",
+                0x1002 | 0x1022 => "AMD",
+                0x8086 => "Intel",
+                0x5143 => "Qualcomm",
+                _ => "Unknown",
+            };
+
+            // The user-mode driver version is packed as four 16-bit parts
+            let driver_version = unsafe { adapter.CheckInterfaceSupport(&IDXGIDevice::IID) }
+                .ok()
+                .map(|v| {
+                    let v = v as u64;
+                    format!("{}.{}.{}.{}", (v >> 48) & 0xFFFF, (v >> 32) & 0xFFFF, (v >> 16) & 0xFFFF, v & 0xFFFF)
+                });
+
+            adapters.push(DxgiAdapterInfo {
+                name: String::from_utf16_lossy(&desc.Description[..name_len]).trim().to_string(),
+                vendor: vendor.to_string(),
+                dedicated_memory: desc.DedicatedVideoMemory as u64,
+                driver_version,
+            });
         }
+
+        adapters
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    fn detect_windows_dxgi_gpus(&self) -> Vec<DxgiAdapterInfo> {
+        Vec::new()
     }
 
     /// Generate text response using Ollama-compatible API
     pub async fn generate_response(&self, request: GenerateRequest) -> Result<GenerateResponse> {
+        let model = request.model.clone();
+        request_tracing::in_trace("generate", Some(&model), self.generate_response_traced(request, None)).await
+    }
+
+    /// One non-streaming completion of `prompt` under `system`, trimmed
+    pub async fn complete(&self, model: &str, system: &str, prompt: String, max_tokens: i32, temperature: f32) -> Result<String> {
+        let request = GenerateRequest {
+            model: model.to_string(),
+            prompt,
+            stream: Some(false),
+            options: Some(GenerateOptions {
+                num_predict: Some(max_tokens),
+                temperature: Some(temperature),
+                ..Default::default()
+            }),
+            system: Some(system.to_string()),
+            template: None,
+            context: None,
+            raw: None,
+        };
+        Ok(self.generate_response(request).await?.response.trim().to_string())
+    }
+
+    /// Generate, passing each piece of output to `on_chunk` as the model produces it; the
+    /// response holds the full text as `generate_response` would return it
+    pub async fn generate_response_streaming(&self, request: GenerateRequest, on_chunk: ChunkSink<'_>) -> Result<GenerateResponse> {
+        let model = request.model.clone();
+        request_tracing::in_trace("generate", Some(&model), self.generate_response_traced(request, Some(on_chunk))).await
+    }
+
+    async fn generate_response_traced(&self, request: GenerateRequest, on_chunk: Option<ChunkSink<'_>>) -> Result<GenerateResponse> {
         // Check resource guards before generating
         if let Some(tracker) = crate::performance_tracker::get_performance_tracker() {
+            let _queue_wait = StageTimer::start("llm_manager", "queue_wait");
             tracker.acquire_operation_permit().await
-                .map_err(|e| anyhow::anyhow!("Resource guard denied generation: {}", e))?;
+                .map_err(|e| ModelBackendError::Denied(format!("Resource guard denied generation: {}", e)))?;
         }
 
         // Ensure model is loaded
-        let model_url = self.ensure_model_loaded(&request.model).await?;
+        let model_url = {
+            let _model_load = StageTimer::start("llm_manager", "model_load");
+            self.ensure_model_loaded(&request.model).await?
+        };
 
         // Prepare request body
         let mut request_body = serde_json::json!({
             "model": request.model,
             "prompt": request.prompt,
-            "stream": request.stream.unwrap_or(false)
+            "stream": on_chunk.is_some()
         });
 
         if let Some(options) = request.options {
@@ -886,30 +2730,82 @@ impl LLMManager {
             return Err(anyhow::anyhow!("Generate request failed: {}", error_text));
         }
 
-        let generate_response: GenerateResponse = response
-            .json()
-            .await
-            .context("Failed to parse generate response")?;
+        let mut generate_response: GenerateResponse = match on_chunk {
+            Some(on_chunk) => read_stream(response, "Generate", |r: &mut GenerateResponse| &mut r.response, on_chunk).await?,
+            None => {
+                let _post_processing = StageTimer::start("llm_manager", "post_processing");
+                response
+                    .json()
+                    .await
+                    .context("Failed to parse generate response")?
+            }
+        };
+
+        // Ollama reports durations in nanoseconds
+        request_tracing::record_model_timings(
+            "llm_manager",
+            generate_response.prompt_eval_duration.unwrap_or(0) / 1_000_000,
+            generate_response.eval_duration.unwrap_or(0) / 1_000_000,
+        );
+        generate_response.trace_id = request_tracing::current_trace_id();
 
         Ok(generate_response)
     }
 
     /// Chat with model using conversation context
     pub async fn chat(&self, request: ChatRequest) -> Result<ChatResponse> {
+        self.chat_once(request, None).await
+    }
+
+    async fn chat_once(&self, request: ChatRequest, on_chunk: Option<ChunkSink<'_>>) -> Result<ChatResponse> {
+        let model = request.model.clone();
+        request_tracing::in_trace("chat", Some(&model), self.chat_traced(request, on_chunk)).await
+    }
+
+    async fn chat_traced(&self, request: ChatRequest, on_chunk: Option<ChunkSink<'_>>) -> Result<ChatResponse> {
         // Check resource guards
         if let Some(tracker) = crate::performance_tracker::get_performance_tracker() {
+            let _queue_wait = StageTimer::start("llm_manager", "queue_wait");
             tracker.acquire_operation_permit().await
-                .map_err(|e| anyhow::anyhow!("Resource guard denied chat: {}", e))?;
+                .map_err(|e| ModelBackendError::Denied(format!("Resource guard denied chat: {}", e)))?;
         }
 
         // Ensure model is loaded
-        let _model_url = self.ensure_model_loaded(&request.model).await?;
+        {
+            let _model_load = StageTimer::start("llm_manager", "model_load");
+            self.ensure_model_loaded(&request.model).await?;
+        }
 
+        // A model we spawned a llama-server for is answered there; anything else goes to Ollama
+        let mut chat_response = match self.llama_server_endpoint(&request.model) {
+            Some(endpoint) => self.chat_llama_server(&endpoint, request, on_chunk).await?,
+            None => self.chat_ollama(request, on_chunk).await?,
+        };
+
+        request_tracing::record_model_timings(
+            "llm_manager",
+            chat_response.prompt_eval_duration.unwrap_or(0) / 1_000_000,
+            chat_response.eval_duration.unwrap_or(0) / 1_000_000,
+        );
+        chat_response.trace_id = request_tracing::current_trace_id();
+
+        Ok(chat_response)
+    }
+
+    /// Endpoint of the llama-server instance running `model_id`, if we spawned one
+    fn llama_server_endpoint(&self, model_id: &str) -> Option<String> {
+        if !self.running_models.lock().unwrap().contains_key(model_id) {
+            return None;
+        }
+        self.loaded_models.lock().unwrap().get(model_id).map(|m| m.endpoint.clone())
+    }
+
+    async fn chat_ollama(&self, request: ChatRequest, on_chunk: Option<ChunkSink<'_>>) -> Result<ChatResponse> {
         // Prepare request body
         let mut request_body = serde_json::json!({
             "model": request.model,
             "messages": request.messages,
-            "stream": request.stream.unwrap_or(false)
+            "stream": on_chunk.is_some()
         });
 
         if let Some(options) = request.options {
@@ -924,17 +2820,252 @@ impl LLMManager {
             .await
             .context("Failed to send chat request")?;
 
-        if !response.status().is_success() {
-            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(anyhow::anyhow!("Chat request failed: {}", error_text));
+        if !response.status().is_success() {
+            let status = response.status().as_u16();
+            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(ModelBackendError::Status { status, message: format!("Chat request failed: {}", error_text) }.into());
+        }
+
+        match on_chunk {
+            Some(on_chunk) => read_stream(response, "Chat", |r: &mut ChatResponse| &mut r.message.content, on_chunk).await,
+            None => {
+                let _post_processing = StageTimer::start("llm_manager", "post_processing");
+                response
+                    .json()
+                    .await
+                    .context("Failed to parse chat response")
+            }
+        }
+    }
+
+    /// Chat through llama-server's OpenAI-compatible endpoint, answered in Ollama's shape with
+    /// durations in nanoseconds
+    async fn chat_llama_server(&self, endpoint: &str, request: ChatRequest, on_chunk: Option<ChunkSink<'_>>) -> Result<ChatResponse> {
+        let messages: Vec<Value> = request
+            .messages
+            .iter()
+            .map(|m| serde_json::json!({"role": m.role, "content": m.content}))
+            .collect();
+        let mut request_body = serde_json::json!({
+            "model": request.model,
+            "messages": messages,
+            "stream": on_chunk.is_some()
+        });
+
+        if let Some(options) = &request.options {
+            let fields = [
+                ("max_tokens", options.num_predict.map(Value::from)),
+                ("temperature", options.temperature.map(Value::from)),
+                ("top_p", options.top_p.map(Value::from)),
+                ("top_k", options.top_k.map(Value::from)),
+                ("seed", options.seed.map(Value::from)),
+                ("repeat_penalty", options.repeat_penalty.map(Value::from)),
+                ("presence_penalty", options.presence_penalty.map(Value::from)),
+                ("frequency_penalty", options.frequency_penalty.map(Value::from)),
+                ("stop", options.stop.clone().map(Value::from)),
+            ];
+            for (name, value) in fields {
+                if let Some(value) = value {
+                    request_body[name] = value;
+                }
+            }
+        }
+
+        let response = self.http_client
+            .post(&format!("{}/v1/chat/completions", endpoint))
+            .json(&request_body)
+            .send()
+            .await
+            .context("Failed to send chat request")?;
+
+        if !response.status().is_success() {
+            let status = response.status().as_u16();
+            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(ModelBackendError::Status { status, message: format!("Chat request failed: {}", error_text) }.into());
+        }
+
+        let (content, completion) = match on_chunk {
+            Some(on_chunk) => read_completion_stream(response, on_chunk).await?,
+            None => {
+                let _post_processing = StageTimer::start("llm_manager", "post_processing");
+                let completion: Value = response.json().await.context("Failed to parse chat response")?;
+                let content = completion["choices"][0]["message"]["content"].as_str().unwrap_or_default().to_string();
+                (content, completion)
+            }
+        };
+
+        let count = |field: &str| completion["usage"][field].as_u64().map(|n| n as u32);
+        let nanos = |field: &str| completion["timings"][field].as_f64().map(|ms| (ms * 1_000_000.0) as u64);
+        Ok(ChatResponse {
+            model: request.model,
+            created_at: Utc::now().to_rfc3339(),
+            message: ChatMessage {
+                role: "assistant".to_string(),
+                content,
+                images: None,
+            },
+            done: true,
+            total_duration: None,
+            load_duration: None,
+            prompt_eval_count: count("prompt_tokens"),
+            prompt_eval_duration: nanos("prompt_ms"),
+            eval_count: count("completion_tokens"),
+            eval_duration: nanos("predicted_ms"),
+            trace_id: None,
+            stream_id: None,
+            answered_by: None,
+            failover_from: None,
+        })
+    }
+
+    /// Chat, recovering from a crashed backend by restarting the model or switching to a
+    /// configured fallback. The response records which model answered.
+    pub async fn chat_with_failover(&self, request: ChatRequest) -> Result<ChatResponse> {
+        let primary = request.model.clone();
+        request_tracing::in_trace("chat", Some(&primary), self.chat_with_failover_traced(request, None)).await
+    }
+
+    /// `chat_with_failover` with streamed output. Once output has reached `on_chunk` a failure
+    /// is returned as is, since a restarted or fallback model would answer from the beginning.
+    pub async fn chat_with_failover_streaming(&self, request: ChatRequest, on_chunk: ChunkSink<'_>) -> Result<ChatResponse> {
+        let primary = request.model.clone();
+        request_tracing::in_trace("chat", Some(&primary), self.chat_with_failover_traced(request, Some(on_chunk))).await
+    }
+
+    async fn chat_with_failover_traced(&self, request: ChatRequest, on_chunk: Option<ChunkSink<'_>>) -> Result<ChatResponse> {
+        let primary = request.model.clone();
+        let streamed = std::sync::atomic::AtomicBool::new(false);
+        let forward = |piece: &str| {
+            streamed.store(true, std::sync::atomic::Ordering::Relaxed);
+            if let Some(on_chunk) = on_chunk {
+                on_chunk(piece);
+            }
+        };
+        let sink = on_chunk.map(|_| &forward as ChunkSink<'_>);
+
+        let first_error = match self.chat_once(request.clone(), sink).await {
+            Ok(mut response) => {
+                response.answered_by = Some(primary);
+                return Ok(response);
+            }
+            Err(e) if !Self::is_backend_failure(&e) => return Err(e),
+            Err(e) if streamed.load(std::sync::atomic::Ordering::Relaxed) => return Err(e),
+            Err(e) => e,
+        };
+        log::warn!("Model '{}' backend failed during chat: {}", primary, first_error);
+
+        // A llama-server we spawned may simply have died; restart it and retry once
+        let spawned = self.running_models.lock().unwrap().contains_key(&primary);
+        if spawned {
+            let _ = self.unload_model(&primary).await;
+            match self.load_model(&primary).await {
+                Ok(_) => match self.chat_once(request.clone(), sink).await {
+                    Ok(mut response) => {
+                        log::info!("Model '{}' recovered after restart", primary);
+                        response.answered_by = Some(primary);
+                        return Ok(response);
+                    }
+                    Err(e) if streamed.load(std::sync::atomic::Ordering::Relaxed) => return Err(e),
+                    Err(e) => log::warn!("Model '{}' failed again after restart: {}", primary, e),
+                },
+                Err(e) => log::warn!("Failed to restart model '{}': {}", primary, e),
+            }
+        }
+
+        let fallbacks = self
+            .registry
+            .lock()
+            .unwrap()
+            .fallback_models
+            .get(&primary)
+            .cloned()
+            .unwrap_or_default();
+
+        for fallback in fallbacks {
+            // The conversation is replayed in full, trimmed to what the fallback can hold
+            let mut fallback_request = request.clone();
+            fallback_request.model = fallback.clone();
+            fallback_request.messages = Self::replay_messages(&request.messages, DEFAULT_CONTEXT_LENGTH as usize * 3);
+
+            match self.chat_once(fallback_request, sink).await {
+                Ok(mut response) => {
+                    log::info!("Fallback model '{}' answered for '{}'", fallback, primary);
+                    response.answered_by = Some(fallback);
+                    response.failover_from = Some(primary);
+                    return Ok(response);
+                }
+                Err(e) if streamed.load(std::sync::atomic::Ordering::Relaxed) => return Err(e),
+                Err(e) => log::warn!("Fallback model '{}' failed: {}", fallback, e),
+            }
+        }
+
+        Err(anyhow::anyhow!(
+            "Model '{}' failed and no fallback model could answer: {}",
+            primary,
+            first_error
+        ))
+    }
+
+    /// Whether an error means the model backend is down, as opposed to a rejected request
+    fn is_backend_failure(error: &anyhow::Error) -> bool {
+        if let Some(e) = error.chain().find_map(|cause| cause.downcast_ref::<ModelBackendError>()) {
+            return e.is_backend_failure();
+        }
+
+        error.chain().any(|cause| match cause.downcast_ref::<reqwest::Error>() {
+            Some(e) => e.is_connect() || e.is_timeout() || e.is_request() || e.is_body(),
+            None => false,
+        })
+    }
+
+    /// Keep system messages and as many of the latest messages as fit in the character budget
+    fn replay_messages(messages: &[ChatMessage], max_chars: usize) -> Vec<ChatMessage> {
+        let mut budget = max_chars.saturating_sub(
+            messages.iter().filter(|m| m.role == "system").map(|m| m.content.len()).sum(),
+        );
+
+        let mut keep = vec![false; messages.len()];
+        for (i, message) in messages.iter().enumerate().rev() {
+            if message.role == "system" {
+                keep[i] = true;
+            } else if message.content.len() <= budget {
+                budget -= message.content.len();
+                keep[i] = true;
+            } else {
+                budget = 0;
+            }
+        }
+
+        messages
+            .iter()
+            .zip(keep)
+            .filter(|(_, keep)| *keep)
+            .map(|(message, _)| message.clone())
+            .collect()
+    }
+
+    /// Configure the ordered fallback models used when a model fails mid-conversation
+    pub fn set_fallback_models(&self, model_id: &str, fallbacks: Vec<String>) -> Result<()> {
+        if fallbacks.iter().any(|f| f == model_id) {
+            return Err(anyhow::anyhow!("A model cannot be its own fallback"));
         }
 
-        let chat_response: ChatResponse = response
-            .json()
-            .await
-            .context("Failed to parse chat response")?;
+        let mut registry = self.registry.lock().unwrap();
+        if fallbacks.is_empty() {
+            registry.fallback_models.remove(model_id);
+        } else {
+            registry.fallback_models.insert(model_id.to_string(), fallbacks);
+        }
+        self.save_registry(&registry)
+    }
 
-        Ok(chat_response)
+    pub fn get_fallback_models(&self) -> HashMap<String, Vec<String>> {
+        self.registry.lock().unwrap().fallback_models.clone()
+    }
+
+    /// Score documents against a query with a cross-encoder on the local model server
+    pub async fn rerank(&self, request: RerankRequest) -> Result<RerankResponse> {
+        request_rerank(&self.http_client, &self.ollama_base_url, &request).await
     }
 
     /// Generate embeddings for text
@@ -942,7 +3073,7 @@ impl LLMManager {
         // Check resource guards
         if let Some(tracker) = crate::performance_tracker::get_performance_tracker() {
             tracker.acquire_operation_permit().await
-                .map_err(|e| anyhow::anyhow!("Resource guard denied embeddings: {}", e))?;
+                .map_err(|e| ModelBackendError::Denied(format!("Resource guard denied embeddings: {}", e)))?;
         }
 
         // Ensure model is loaded
@@ -979,6 +3110,119 @@ impl LLMManager {
         Ok(embedding_response)
     }
 
+    /// Generate embeddings for many inputs, coalesced into batches sent with bounded parallelism
+    pub async fn embeddings_batch(&self, request: EmbeddingBatchRequest) -> Result<EmbeddingBatchResponse> {
+        use futures::stream::{self, StreamExt, TryStreamExt};
+
+        if request.inputs.is_empty() {
+            return Ok(EmbeddingBatchResponse {
+                embeddings: Vec::new(),
+                batches: 0,
+                elapsed_ms: 0,
+                inputs_per_second: 0.0,
+            });
+        }
+
+        let batch_size = request.batch_size.unwrap_or(32).max(1);
+        let max_parallel = request.max_parallel.unwrap_or(2).max(1);
+
+        // One permit covers the whole batch job
+        let permit_tracker = crate::performance_tracker::get_performance_tracker();
+        if let Some(tracker) = &permit_tracker {
+            tracker.acquire_operation_permit().await
+                .map_err(|e| ModelBackendError::Denied(format!("Resource guard denied embeddings: {}", e)))?;
+        }
+        let _permit_guard = scopeguard::guard(permit_tracker, |tracker| {
+            if let Some(tracker) = tracker {
+                tracker.release_operation_permit();
+            }
+        });
+
+        let _model_url = self.ensure_model_loaded(&request.model).await?;
+
+        let timer = crate::performance_tracker::PerformanceTimer::new(
+            request.model.clone(),
+            "embeddings_batch".to_string(),
+        );
+        let batches: Vec<Vec<String>> = request.inputs.chunks(batch_size).map(|c| c.to_vec()).collect();
+        let batch_count = batches.len();
+
+        // `buffered` keeps results in input order while running up to `max_parallel` requests
+        let results: Vec<Vec<Vec<f32>>> = stream::iter(batches.into_iter().map(|batch| {
+            let mut request_body = serde_json::json!({
+                "model": request.model,
+                "input": batch
+            });
+            if let Some(options) = &request.options {
+                request_body["options"] = serde_json::to_value(options).unwrap_or(Value::Null);
+            }
+            let expected = batch.len();
+
+            async move {
+                let response = self.http_client
+                    .post(&format!("{}/api/embed", self.ollama_base_url))
+                    .json(&request_body)
+                    .send()
+                    .await
+                    .context("Failed to send batch embeddings request")?;
+
+                if !response.status().is_success() {
+                    let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+                    return Err(anyhow::anyhow!("Batch embeddings request failed: {}", error_text));
+                }
+
+                let body: Value = response.json().await.context("Failed to parse batch embeddings response")?;
+                let embeddings: Vec<Vec<f32>> = serde_json::from_value(body["embeddings"].clone())
+                    .context("Invalid batch embeddings response")?;
+                if embeddings.len() != expected {
+                    return Err(anyhow::anyhow!(
+                        "Batch embeddings response had {} vectors for {} inputs",
+                        embeddings.len(),
+                        expected
+                    ));
+                }
+                Ok(embeddings)
+            }
+        }))
+        .buffered(max_parallel)
+        .try_collect()
+        .await?;
+
+        let embeddings: Vec<Vec<f32>> = results.into_iter().flatten().collect();
+
+        // Report throughput; token counts are estimated at ~4 characters per token
+        let input_bytes: usize = request.inputs.iter().map(|i| i.len()).sum();
+        let elapsed_ms = timer.elapsed_ms();
+        let inputs_per_second = if elapsed_ms > 0 {
+            embeddings.len() as f32 * 1000.0 / elapsed_ms as f32
+        } else {
+            0.0
+        };
+        if let Some(tracker) = crate::performance_tracker::get_performance_tracker() {
+            let estimated_tokens = (input_bytes / 4) as u32;
+            let mut metrics = timer.finish_with_tokens(estimated_tokens, estimated_tokens, 0);
+            if elapsed_ms > 0 {
+                metrics.document_processing_speed_mb_per_sec =
+                    (input_bytes as f32 / (1024.0 * 1024.0)) / (elapsed_ms as f32 / 1000.0);
+            }
+            tracker.record_metric(metrics).await;
+        }
+
+        log::info!(
+            "Embedded {} inputs in {} batches ({:.1} inputs/s)",
+            embeddings.len(),
+            batch_count,
+            inputs_per_second
+        );
+
+        Ok(EmbeddingBatchResponse {
+            embeddings,
+            batches: batch_count,
+            elapsed_ms,
+            inputs_per_second,
+        })
+    }
+
     /// Show detailed information about a model
     pub async fn show_model(&self, model_name: &str) -> Result<OllamaModelInfo> {
         // First check our local registry
@@ -1033,7 +3277,7 @@ impl LLMManager {
         log::info!("Pulling model: {}", request.name);
 
         // Check if this is one of our curated models first
-        let curated_models = Self::get_curated_legal_models();
+        let curated_models = self.curated_models();
         if let Some(curated_model) = curated_models.iter().find(|m| m.id == request.name || m.name == request.name) {
             // Use our download system for curated models
             self.download_model(&curated_model.id, None).await?;
@@ -1134,6 +3378,7 @@ impl LLMManager {
             installed: true,
             version: "1.0.0".to_string(),
             created_at: chrono::Utc::now().timestamp() as u64,
+            license: None,
         };
 
         registry.models.insert(request.name.clone(), new_model);
@@ -1241,6 +3486,8 @@ impl LLMManager {
         {
             let running_models = self.running_models.lock().unwrap();
             if running_models.contains_key(model_id) {
+                drop(running_models);
+                self.touch_loaded_model(model_id);
                 return Ok(self.ollama_base_url.clone());
             }
         }
@@ -1280,6 +3527,15 @@ impl LLMManager {
     }
 }
 
+/// A hardware adapter reported by DXGI on Windows
+#[derive(Debug, Clone)]
+struct DxgiAdapterInfo {
+    name: String,
+    vendor: String,
+    dedicated_memory: u64, // bytes
+    driver_version: Option<String>,
+}
+
 /// Helper struct for GPU detection results
 #[derive(Debug, Default)]
 struct GpuDetectionResult {
@@ -1321,6 +3577,10 @@ pub struct GenerateResponse {
     pub prompt_eval_duration: Option<u64>,
     pub eval_count: Option<u32>,
     pub eval_duration: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stream_id: Option<String>, // set when the output was also emitted as chunk events
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -1350,6 +3610,14 @@ pub struct ChatResponse {
     pub prompt_eval_duration: Option<u64>,
     pub eval_count: Option<u32>,
     pub eval_duration: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stream_id: Option<String>, // set when the output was also emitted as chunk events
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub answered_by: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failover_from: Option<String>, // set when a fallback model answered
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -1364,6 +3632,60 @@ pub struct EmbeddingResponse {
     pub embedding: Vec<f32>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RerankRequest {
+    pub model: String,
+    pub query: String,
+    pub documents: Vec<String>,
+    pub top_n: Option<usize>, // the server scores every document when unset
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RerankResult {
+    pub index: usize, // position in `RerankRequest::documents`
+    pub relevance_score: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RerankResponse {
+    pub results: Vec<RerankResult>,
+}
+
+/// Send a rerank request to the model server at `base_url`
+pub async fn request_rerank(http_client: &Client, base_url: &str, request: &RerankRequest) -> Result<RerankResponse> {
+    let base_url = base_url.trim_end_matches('/');
+    let response = http_client
+        .post(format!("{}/api/rerank", base_url))
+        .json(request)
+        .send()
+        .await
+        .with_context(|| format!("Local model server at {} is not reachable", base_url))?;
+
+    if !response.status().is_success() {
+        let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+        return Err(anyhow::anyhow!("Rerank request failed: {}", error_text));
+    }
+
+    response.json().await.context("Failed to parse rerank response")
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmbeddingBatchRequest {
+    pub model: String,
+    pub inputs: Vec<String>,
+    pub batch_size: Option<usize>,   // inputs per request (default 32)
+    pub max_parallel: Option<usize>, // concurrent requests (default 2)
+    pub options: Option<GenerateOptions>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmbeddingBatchResponse {
+    pub embeddings: Vec<Vec<f32>>, // same order as the inputs
+    pub batches: usize,
+    pub elapsed_ms: u64,
+    pub inputs_per_second: f32,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PullRequest {
     pub name: String,
@@ -1397,7 +3719,7 @@ pub struct CopyRequest {
     pub destination: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct GenerateOptions {
     pub num_keep: Option<i32>,
     pub seed: Option<i32>,
@@ -1510,6 +3832,181 @@ pub async fn load_model(
         .map_err(|e| e.to_string())
 }
 
+/// Warm up a model (the most used one when none is given) and emit status events
+pub async fn run_model_warmup(
+    app: &tauri::AppHandle,
+    manager: Arc<LLMManager>,
+    model_id: Option<String>,
+) -> ModelWarmupStatus {
+    use tauri::Manager;
+
+    let model_id = match model_id {
+        Some(id) => Some(id),
+        None => manager.most_used_installed_model().await,
+    };
+
+    let status = match model_id {
+        None => ModelWarmupStatus {
+            model_id: None,
+            state: "skipped".to_string(),
+            message: Some("No usage history for any installed model".to_string()),
+            duration_ms: 0,
+        },
+        Some(id) => {
+            let _ = app.emit_all(MODEL_WARMUP_EVENT, ModelWarmupStatus {
+                model_id: Some(id.clone()),
+                state: "warming".to_string(),
+                message: None,
+                duration_ms: 0,
+            });
+
+            manager.warmup_model(&id).await.unwrap_or_else(|e| ModelWarmupStatus {
+                model_id: Some(id.clone()),
+                state: "failed".to_string(),
+                message: Some(e.to_string()),
+                duration_ms: 0,
+            })
+        }
+    };
+
+    let _ = app.emit_all(MODEL_WARMUP_EVENT, status.clone());
+    status
+}
+
+#[tauri::command]
+pub async fn warmup_model(
+    app: tauri::AppHandle,
+    manager: tauri::State<'_, Arc<LLMManager>>,
+    model_id: Option<String>,
+) -> Result<ModelWarmupStatus, String> {
+    let status = run_model_warmup(&app, manager.inner().clone(), model_id).await;
+    if status.state == "failed" {
+        return Err(status.message.unwrap_or_else(|| "Model warm-up failed".to_string()));
+    }
+    Ok(status)
+}
+
+#[tauri::command]
+pub async fn generate_with_prefix_cache(
+    manager: tauri::State<'_, Arc<LLMManager>>,
+    model_id: String,
+    prefix: String,
+    suffix: String,
+    n_predict: Option<i32>,
+) -> Result<PrefixCachedCompletion, String> {
+    manager
+        .generate_with_prefix_cache(&model_id, &prefix, &suffix, n_predict)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn list_prompt_cache(
+    manager: tauri::State<'_, Arc<LLMManager>>,
+) -> Result<Vec<PromptCacheEntry>, String> {
+    Ok(manager.list_prompt_cache())
+}
+
+#[tauri::command]
+pub async fn clear_prompt_cache(
+    manager: tauri::State<'_, Arc<LLMManager>>,
+    model_id: Option<String>,
+) -> Result<usize, String> {
+    manager
+        .clear_prompt_cache(model_id.as_deref())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_embeddings_batch(
+    manager: tauri::State<'_, Arc<LLMManager>>,
+    request: EmbeddingBatchRequest,
+) -> Result<EmbeddingBatchResponse, String> {
+    manager
+        .embeddings_batch(request)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn preflight_model_load(
+    manager: tauri::State<'_, Arc<LLMManager>>,
+    model_id: String,
+    context_length: Option<u32>,
+) -> Result<LoadPreflight, String> {
+    manager
+        .preflight_model_load(&model_id, context_length.unwrap_or(DEFAULT_CONTEXT_LENGTH))
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_model_provenance(
+    manager: tauri::State<'_, Arc<LLMManager>>,
+    model_id: String,
+) -> Result<ModelProvenance, String> {
+    manager
+        .get_model_provenance(&model_id)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn refresh_model_manifest(
+    manager: tauri::State<'_, Arc<LLMManager>>,
+) -> Result<ManifestStatus, String> {
+    match manager.refresh_curated_manifest().await {
+        Ok(status) => Ok(status),
+        Err(e) => {
+            // Fall back to whatever list is in use, reporting why the refresh failed
+            let mut status = manager.manifest_status();
+            status.error = Some(e.to_string());
+            Ok(status)
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn list_loaded_models(
+    manager: tauri::State<'_, Arc<LLMManager>>,
+) -> Result<Vec<LoadedModel>, String> {
+    Ok(manager.list_loaded_models().await)
+}
+
+#[tauri::command]
+pub async fn get_model_endpoint(
+    manager: tauri::State<'_, Arc<LLMManager>>,
+    capability: String,
+) -> Result<Option<LoadedModel>, String> {
+    Ok(manager.find_loaded_model(&capability))
+}
+
+#[tauri::command]
+pub async fn set_draft_model(
+    manager: tauri::State<'_, Arc<LLMManager>>,
+    target_model_id: String,
+    config: DraftModelConfig,
+) -> Result<(), String> {
+    manager
+        .set_draft_model(&target_model_id, config)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn remove_draft_model(
+    manager: tauri::State<'_, Arc<LLMManager>>,
+    target_model_id: String,
+) -> Result<(), String> {
+    manager
+        .remove_draft_model(&target_model_id)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn list_draft_models(
+    manager: tauri::State<'_, Arc<LLMManager>>,
+) -> Result<HashMap<String, DraftModelConfig>, String> {
+    Ok(manager.list_draft_models())
+}
+
 #[tauri::command]
 pub async fn unload_model(
     manager: tauri::State<'_, Arc<LLMManager>>,
@@ -1548,20 +4045,97 @@ pub async fn get_system_info(
 
 // Additional Tauri commands for the new methods
 
+/// Emits each piece of streamed output as a `GENERATION_CHUNK_EVENT`
+struct ChunkEmitter {
+    app: tauri::AppHandle,
+    stream_id: String,
+    model: String,
+    index: std::sync::atomic::AtomicUsize,
+}
+
+impl ChunkEmitter {
+    fn new(app: tauri::AppHandle, stream_id: Option<String>, model: &str) -> Self {
+        Self {
+            app,
+            stream_id: stream_id.unwrap_or_else(|| Uuid::new_v4().to_string()),
+            model: model.to_string(),
+            index: std::sync::atomic::AtomicUsize::new(0),
+        }
+    }
+
+    fn emit(&self, content: &str, done: bool) {
+        use tauri::Manager;
+        let _ = self.app.emit_all(GENERATION_CHUNK_EVENT, GenerationChunk {
+            stream_id: self.stream_id.clone(),
+            model: self.model.clone(),
+            index: self.index.fetch_add(1, std::sync::atomic::Ordering::Relaxed),
+            content: content.to_string(),
+            done,
+        });
+    }
+}
+
+/// Generate a response. With `stream: true` the output is also emitted as it is produced;
+/// `stream_id` tells concurrent streams apart and is returned with the response, generated
+/// when the caller gives none.
 #[tauri::command]
 pub async fn generate_response(
     manager: tauri::State<'_, Arc<LLMManager>>,
+    app: tauri::AppHandle,
     request: GenerateRequest,
+    stream_id: Option<String>,
 ) -> Result<GenerateResponse, String> {
-    manager.generate_response(request).await.map_err(|e| e.to_string())
+    if request.stream != Some(true) {
+        return manager.generate_response(request).await.map_err(|e| e.to_string());
+    }
+    let emitter = ChunkEmitter::new(app, stream_id, &request.model);
+    let response = manager
+        .generate_response_streaming(request, &|piece: &str| emitter.emit(piece, false))
+        .await
+        .map_err(|e| e.to_string());
+    emitter.emit("", true);
+    let mut response = response?;
+    response.stream_id = Some(emitter.stream_id);
+    Ok(response)
 }
 
 #[tauri::command]
 pub async fn chat_with_model(
     manager: tauri::State<'_, Arc<LLMManager>>,
+    app: tauri::AppHandle,
     request: ChatRequest,
+    stream_id: Option<String>,
 ) -> Result<ChatResponse, String> {
-    manager.chat(request).await.map_err(|e| e.to_string())
+    if request.stream != Some(true) {
+        return manager.chat_with_failover(request).await.map_err(|e| e.to_string());
+    }
+    let emitter = ChunkEmitter::new(app, stream_id, &request.model);
+    let response = manager
+        .chat_with_failover_streaming(request, &|piece: &str| emitter.emit(piece, false))
+        .await
+        .map_err(|e| e.to_string());
+    emitter.emit("", true);
+    let mut response = response?;
+    response.stream_id = Some(emitter.stream_id);
+    Ok(response)
+}
+
+#[tauri::command]
+pub async fn set_fallback_models(
+    manager: tauri::State<'_, Arc<LLMManager>>,
+    model_id: String,
+    fallbacks: Vec<String>,
+) -> Result<(), String> {
+    manager
+        .set_fallback_models(&model_id, fallbacks)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_fallback_models(
+    manager: tauri::State<'_, Arc<LLMManager>>,
+) -> Result<HashMap<String, Vec<String>>, String> {
+    Ok(manager.get_fallback_models())
 }
 
 #[tauri::command]
@@ -1604,3 +4178,337 @@ pub async fn copy_model(
 ) -> Result<(), String> {
     manager.copy_model(&source, &destination).await.map_err(|e| e.to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    /// Stand-in model server that writes its streamed body in the given pieces, pausing
+    /// between them so each arrives as its own network chunk
+    async fn serve_in_pieces(content_type: &'static str, pieces: Vec<Vec<u8>>) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = vec![0u8; 4096];
+            let _ = socket.read(&mut request).await.unwrap();
+            let head = format!("HTTP/1.1 200 OK\r\ncontent-type: {}\r\nconnection: close\r\n\r\n", content_type);
+            socket.write_all(head.as_bytes()).await.unwrap();
+            for piece in pieces {
+                socket.write_all(&piece).await.unwrap();
+                socket.flush().await.unwrap();
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+        });
+        format!("http://{}", address)
+    }
+
+    async fn get(url: &str) -> reqwest::Response {
+        Client::new().get(url).send().await.unwrap()
+    }
+
+    #[test]
+    fn test_ndjson_lines_hold_back_partial_records() {
+        let mut lines = NdjsonLines::default();
+        assert_eq!(lines.push(b"{\"a\":1}\n\n{\"b\""), vec!["{\"a\":1}".to_string()]);
+        assert!(lines.push(b":2").is_empty());
+        assert_eq!(lines.push(b"}\n{\"c\":3}"), vec!["{\"b\":2}".to_string()]);
+        assert_eq!(lines.finish(), Some("{\"c\":3}".to_string()));
+        assert_eq!(lines.finish(), None);
+    }
+
+    #[test]
+    fn test_ndjson_lines_join_characters_split_between_chunks() {
+        let record = "{\"content\":\"résilié\"}\n".as_bytes();
+        let split = record.iter().position(|b| *b == 0xC3).unwrap() + 1; // inside the first é
+
+        let mut lines = NdjsonLines::default();
+        assert!(lines.push(&record[..split]).is_empty());
+        assert_eq!(lines.push(&record[split..]), vec!["{\"content\":\"résilié\"}".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_read_stream_assembles_output_across_network_chunks() {
+        let body = concat!(
+            "{\"model\":\"m\",\"created_at\":\"t\",\"response\":\"Contrat \",\"done\":false}\n",
+            "{\"model\":\"m\",\"created_at\":\"t\",\"response\":\"résilié\",\"done\":false}\n",
+            "{\"model\":\"m\",\"created_at\":\"t\",\"response\":\"\",\"done\":true,\"eval_count\":2}\n",
+        )
+        .as_bytes();
+        // Cut inside a record and inside the two bytes of an é
+        let first_cut = 30;
+        let second_cut = body.iter().position(|b| *b == 0xC3).unwrap() + 1;
+        let url = serve_in_pieces(
+            "application/x-ndjson",
+            vec![body[..first_cut].to_vec(), body[first_cut..second_cut].to_vec(), body[second_cut..].to_vec()],
+        )
+        .await;
+
+        let pieces = Mutex::new(Vec::new());
+        let on_chunk = |piece: &str| pieces.lock().unwrap().push(piece.to_string());
+        let response: GenerateResponse =
+            read_stream(get(&url).await, "Generate", |r: &mut GenerateResponse| &mut r.response, &on_chunk)
+                .await
+                .unwrap();
+
+        assert_eq!(response.response, "Contrat résilié");
+        assert_eq!(response.eval_count, Some(2));
+        assert!(response.done);
+        assert_eq!(pieces.into_inner().unwrap(), vec!["Contrat ", "résilié"]);
+    }
+
+    #[tokio::test]
+    async fn test_read_stream_reports_unfinished_and_failed_streams() {
+        let unfinished = "{\"model\":\"m\",\"created_at\":\"t\",\"response\":\"Partial\",\"done\":false}\n";
+        let url = serve_in_pieces("application/x-ndjson", vec![unfinished.as_bytes().to_vec()]).await;
+        let error = read_stream(get(&url).await, "Generate", |r: &mut GenerateResponse| &mut r.response, &|_: &str| {})
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("ended before the model finished"));
+
+        let failed = "{\"error\":\"model crashed\"}";
+        let url = serve_in_pieces("application/x-ndjson", vec![failed.as_bytes().to_vec()]).await;
+        let error = read_stream(get(&url).await, "Generate", |r: &mut GenerateResponse| &mut r.response, &|_: &str| {})
+            .await
+            .unwrap_err();
+        assert_eq!(error.to_string(), "Generate request failed: model crashed");
+        assert!(LLMManager::is_backend_failure(&error));
+    }
+
+    #[tokio::test]
+    async fn test_read_completion_stream_collects_server_sent_events() {
+        let events = concat!(
+            "data: {\"choices\":[{\"delta\":{\"content\":\"Clause \"},\"finish_reason\":null}]}\n\n",
+            "data: {\"choices\":[{\"delta\":{\"content\":\"12\"},\"finish_reason\":null}]}\n\n",
+            "data: {\"choices\":[{\"delta\":{},\"finish_reason\":\"stop\"}],\"timings\":{\"predicted_ms\":4.5}}\n\n",
+            "data: [DONE]\n\n",
+        );
+        let (head, tail) = events.split_at(40);
+        let url = serve_in_pieces("text/event-stream", vec![head.as_bytes().to_vec(), tail.as_bytes().to_vec()]).await;
+
+        let pieces = Mutex::new(Vec::new());
+        let on_chunk = |piece: &str| pieces.lock().unwrap().push(piece.to_string());
+        let (content, last) = read_completion_stream(get(&url).await, &on_chunk).await.unwrap();
+
+        assert_eq!(content, "Clause 12");
+        assert_eq!(last["timings"]["predicted_ms"].as_f64(), Some(4.5));
+        assert_eq!(pieces.into_inner().unwrap(), vec!["Clause ", "12"]);
+    }
+
+    #[test]
+    fn test_resource_guard_denials_are_not_backend_failures() {
+        let denied: anyhow::Error = ModelBackendError::Denied("Resource guard denied chat: busy".to_string()).into();
+        assert!(!LLMManager::is_backend_failure(&denied));
+
+        let missing: anyhow::Error = ModelBackendError::Status { status: 404, message: "Chat request failed: no model".to_string() }.into();
+        assert!(LLMManager::is_backend_failure(&missing));
+
+        let rejected: anyhow::Error = ModelBackendError::Status { status: 400, message: "Chat request failed: bad".to_string() }.into();
+        assert!(!LLMManager::is_backend_failure(&rejected));
+    }
+
+    const GIB: u64 = 1024 * 1024 * 1024;
+
+    #[test]
+    fn test_estimate_load_infers_shape_from_file_size() {
+        // A 4 GiB Q4 file is a 7B-class model: 32 layers of 4096 wide f16 K and V
+        let preflight = estimate_load("model", "Q4_K_M".to_string(), 4 * GIB, None, 4096, 16_000, 0);
+
+        assert_eq!(preflight.weights_mb, 4096);
+        assert_eq!(preflight.layers, 32);
+        assert_eq!(preflight.kv_cache_mb, 2048);
+        assert_eq!(preflight.overhead_mb, 256 + 409);
+        assert_eq!(preflight.required_mb, 4096 + 2048 + 665);
+        assert!(preflight.fits);
+        assert_eq!(preflight.gpu_layers, 0);
+        assert!(preflight.recommendation.is_none());
+    }
+
+    #[test]
+    fn test_estimate_load_reads_shape_from_gguf_header() {
+        let metadata = crate::model_commands::GgufMetadata {
+            parameter_count: 8_000_000_000,
+            block_count: Some(32),
+            embedding_length: Some(4096),
+            head_count: Some(32),
+            head_count_kv: Some(8),
+            quantization: Some("Q8_0".to_string()),
+            ..Default::default()
+        };
+        let preflight = estimate_load("model", "unknown".to_string(), 8 * GIB, Some(&metadata), 4096, 16_000, 0);
+
+        assert_eq!(preflight.quantization, "Q8_0");
+        assert!((preflight.estimated_params_billions - 8.0).abs() < 0.01);
+        // Grouped-query attention: 8 of 32 heads carry K and V
+        assert_eq!(preflight.kv_cache_mb, 512);
+    }
+
+    #[test]
+    fn test_estimate_load_offloads_the_layers_free_vram_holds() {
+        // 192 MB per layer; 90% of 4000 MB holds 18 of them
+        let partial = estimate_load("model", "Q4_K_M".to_string(), 4 * GIB, None, 4096, 16_000, 4000);
+        assert_eq!(partial.gpu_layers, 18);
+
+        let full = estimate_load("model", "Q4_K_M".to_string(), 4 * GIB, None, 4096, 16_000, 8000);
+        assert_eq!(full.gpu_layers, 32);
+    }
+
+    #[test]
+    fn test_estimate_load_recommends_a_smaller_quantization_that_fits() {
+        let metadata = crate::model_commands::GgufMetadata {
+            parameter_count: 8_000_000_000,
+            ..Default::default()
+        };
+        let preflight = estimate_load("model", "Q8_0".to_string(), 8 * GIB, Some(&metadata), 2048, 9000, 0);
+
+        assert!(!preflight.fits);
+        let recommendation = preflight.recommendation.unwrap();
+        assert!(recommendation.contains("Q5_K_M"), "{}", recommendation);
+        assert!(!recommendation.contains("context length"), "{}", recommendation);
+    }
+
+    #[test]
+    fn test_estimate_load_suggests_a_shorter_context_when_nothing_else_helps() {
+        let preflight = estimate_load("model", "Q4_K_M".to_string(), 4 * GIB, None, 4096, 4000, 0);
+
+        assert!(!preflight.fits);
+        assert_eq!(
+            preflight.recommendation.as_deref(),
+            Some("Suggestion: reduce the context length to 2048")
+        );
+    }
+
+    #[test]
+    fn test_check_gguf_file_rejects_truncated_and_foreign_files() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let model = dir.path().join("model.gguf");
+        fs::write(&model, b"GGUF\x03\x00\x00\x00").unwrap();
+        assert_eq!(check_gguf_file(&model).unwrap(), 8);
+
+        let truncated = dir.path().join("truncated.gguf");
+        fs::write(&truncated, b"GG").unwrap();
+        assert!(check_gguf_file(&truncated).unwrap_err().to_string().contains("too small"));
+
+        let foreign = dir.path().join("model.bin");
+        fs::write(&foreign, b"GGML\x00\x00\x00\x00").unwrap();
+        assert!(check_gguf_file(&foreign).unwrap_err().to_string().contains("not a GGUF file"));
+
+        assert!(check_gguf_file(&dir.path().join("missing.gguf")).is_err());
+    }
+
+    /// Stand-in `/api/embed` endpoint: every input is a number and embeds as `[number]`. It
+    /// answers the first batch last, and records the size of each batch it was sent. With
+    /// `short_by_one` it leaves out one vector per batch.
+    async fn serve_embeddings(short_by_one: bool) -> (String, Arc<Mutex<Vec<usize>>>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let batch_sizes = Arc::new(Mutex::new(Vec::new()));
+        let sizes = batch_sizes.clone();
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let sizes = sizes.clone();
+                tokio::spawn(async move {
+                    let mut request = Vec::new();
+                    let mut buffer = [0u8; 4096];
+                    let body = loop {
+                        let read = socket.read(&mut buffer).await.unwrap();
+                        request.extend_from_slice(&buffer[..read]);
+                        let text = String::from_utf8_lossy(&request).to_string();
+                        if let Some(head_end) = text.find("\r\n\r\n") {
+                            let length = text[..head_end]
+                                .lines()
+                                .find_map(|l| l.to_lowercase().strip_prefix("content-length:").map(|v| v.trim().parse::<usize>().unwrap()))
+                                .unwrap_or(0);
+                            if request.len() >= head_end + 4 + length {
+                                break request[head_end + 4..head_end + 4 + length].to_vec();
+                            }
+                        }
+                    };
+
+                    let body: Value = serde_json::from_slice(&body).unwrap();
+                    let inputs: Vec<f32> = body["input"]
+                        .as_array()
+                        .unwrap()
+                        .iter()
+                        .map(|i| i.as_str().unwrap().parse().unwrap())
+                        .collect();
+                    sizes.lock().unwrap().push(inputs.len());
+                    if inputs[0] == 0.0 {
+                        tokio::time::sleep(Duration::from_millis(100)).await;
+                    }
+
+                    let keep = if short_by_one { inputs.len() - 1 } else { inputs.len() };
+                    let embeddings: Vec<Vec<f32>> = inputs.iter().take(keep).map(|i| vec![*i]).collect();
+                    let body = serde_json::json!({ "embeddings": embeddings }).to_string();
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                        body.len(),
+                        body
+                    );
+                    socket.write_all(response.as_bytes()).await.unwrap();
+                });
+            }
+        });
+        (format!("http://{}", address), batch_sizes)
+    }
+
+    fn embedding_manager(dir: &Path, url: &str) -> LLMManager {
+        let mut manager = LLMManager::new(dir).unwrap();
+        manager.ollama_base_url = url.to_string();
+        manager
+    }
+
+    fn batch_request(inputs: usize, batch_size: usize) -> EmbeddingBatchRequest {
+        EmbeddingBatchRequest {
+            model: "embedder".to_string(),
+            inputs: (0..inputs).map(|i| i.to_string()).collect(),
+            batch_size: Some(batch_size),
+            max_parallel: Some(3),
+            options: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_embeddings_batch_splits_inputs_and_keeps_their_order() {
+        let (url, batch_sizes) = serve_embeddings(false).await;
+        let dir = tempfile::tempdir().unwrap();
+        let manager = embedding_manager(dir.path(), &url);
+
+        let response = manager.embeddings_batch(batch_request(7, 3)).await.unwrap();
+
+        assert_eq!(response.batches, 3);
+        let mut sizes = batch_sizes.lock().unwrap().clone();
+        sizes.sort();
+        assert_eq!(sizes, vec![1, 3, 3]);
+        // The first batch is answered last, yet its vectors still come first
+        let expected: Vec<Vec<f32>> = (0..7).map(|i| vec![i as f32]).collect();
+        assert_eq!(response.embeddings, expected);
+    }
+
+    #[tokio::test]
+    async fn test_embeddings_batch_without_inputs_sends_nothing() {
+        let (url, batch_sizes) = serve_embeddings(false).await;
+        let dir = tempfile::tempdir().unwrap();
+        let manager = embedding_manager(dir.path(), &url);
+
+        let response = manager.embeddings_batch(batch_request(0, 3)).await.unwrap();
+
+        assert_eq!(response.batches, 0);
+        assert!(response.embeddings.is_empty());
+        assert!(batch_sizes.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_embeddings_batch_rejects_a_batch_missing_vectors() {
+        let (url, _) = serve_embeddings(true).await;
+        let dir = tempfile::tempdir().unwrap();
+        let manager = embedding_manager(dir.path(), &url);
+
+        let error = manager.embeddings_batch(batch_request(4, 2)).await.unwrap_err();
+
+        assert!(error.to_string().contains("had 1 vectors for 2 inputs"), "{}", error);
+    }
+}
@@ -5,12 +5,24 @@ use std::sync::{Arc, Mutex};
 use std::time::{SystemTime, UNIX_EPOCH};
 use std::path::Path;
 use tauri::State;
-use crate::document_analyzer::{DocumentAnalyzer, DocumentAnalysis};
+use crate::calendar_sync::{self, CalendarSyncStorage};
+use crate::document_acl::{AccessLevel, DocumentAcl, DocumentAclStorage};
+use crate::document_analyzer::{self, DocumentAnalyzer, DocumentAnalysis};
+use crate::enterprise_management::{BarrierStorage, InformationBarriers};
+use crate::security::SecurityManager;
+use crate::workspace_stats::{in_workspace, WorkspaceStatsSnapshot, WorkspaceStatsStorage};
+use crate::llm_manager::LLMManager;
+use crate::nemotron_rag::RetrievalProvenance;
+use crate::review_queue::ReviewQueueStorage;
+use crate::session_summary;
+use crate::timekeeping::{ActivityKind, TimekeeperStorage};
 
 // Local API types for Tauri commands
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct LocalSession {
     pub id: String,
+    #[serde(default)]
+    pub username: String,
     pub authenticated: bool,
     pub created_at: u64,
     pub last_activity: u64,
@@ -41,6 +53,8 @@ pub struct ChatSession {
     pub created_at: String,
     pub message_count: u32,
     pub last_activity: String,
+    #[serde(default)]
+    pub metadata: Option<HashMap<String, String>>, // summary, title source (see session_summary)
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -51,6 +65,8 @@ pub struct ChatMessage {
     pub role: String, // "user" or "assistant"
     pub timestamp: String,
     pub metadata: Option<HashMap<String, String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retrieval_provenance: Option<RetrievalProvenance>, // chunks that informed an assistant answer
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -105,6 +121,23 @@ pub type DocumentStorage = Arc<Mutex<HashMap<String, Vec<Document>>>>;
 pub type MessageStorage = Arc<Mutex<HashMap<String, Vec<ChatMessage>>>>;
 pub type RateLimitStorage = Arc<Mutex<HashMap<String, RateLimitConfig>>>;
 pub type AnalyzerStorage = Arc<DocumentAnalyzer>;
+pub type RetrievalLog = Arc<Mutex<HashMap<String, Vec<(String, RetrievalProvenance)>>>>;
+
+// Retrievals kept per session for answers to cite; older ones are dropped
+const MAX_RECORDED_RETRIEVALS: usize = 32;
+
+/// Keep the provenance of a retrieval the session ran, computed from what retrieval returned,
+/// and give back the id an assistant message cites it by
+pub fn record_retrieval(log: &RetrievalLog, session_id: &str, provenance: RetrievalProvenance) -> String {
+    let retrieval_id = generate_uuid();
+    let mut log = log.lock().unwrap();
+    let retrievals = log.entry(session_id.to_string()).or_default();
+    retrievals.push((retrieval_id.clone(), provenance));
+    if retrievals.len() > MAX_RECORDED_RETRIEVALS {
+        retrievals.remove(0);
+    }
+    retrieval_id
+}
 
 // Utility functions
 fn get_current_timestamp() -> Result<u64, String> {
@@ -114,7 +147,7 @@ fn get_current_timestamp() -> Result<u64, String> {
         .map_err(|e| e.to_string())
 }
 
-fn generate_uuid() -> String {
+pub(crate) fn generate_uuid() -> String {
     use std::collections::hash_map::DefaultHasher;
     use std::hash::{Hash, Hasher};
 
@@ -123,7 +156,7 @@ fn generate_uuid() -> String {
     format!("local_{:x}", hasher.finish())
 }
 
-fn validate_session(session_id: &str, sessions: &SessionStorage) -> Result<bool, String> {
+pub(crate) fn validate_session(session_id: &str, sessions: &SessionStorage) -> Result<bool, String> {
     let sessions_guard = sessions.lock().unwrap();
     match sessions_guard.get(session_id) {
         Some(session) => {
@@ -170,6 +203,108 @@ fn check_rate_limit(session_id: &str, sessions: &SessionStorage) -> Result<bool,
     Ok(true)
 }
 
+/// User name a session signed in with
+pub(crate) fn session_user(session_id: &str, sessions: &SessionStorage) -> String {
+    sessions
+        .lock()
+        .unwrap()
+        .get(session_id)
+        .map(|s| s.username.clone())
+        .unwrap_or_default()
+}
+
+/// The user a valid session signed in as. Commands acting for a user derive it from the
+/// caller's session, never from whoever signed in last.
+pub(crate) fn authenticated_user(session_id: &str, sessions: &SessionStorage) -> Result<String, String> {
+    if validate_session(session_id, sessions)? {
+        Ok(session_user(session_id, sessions))
+    } else {
+        Err("Sign in to continue".to_string())
+    }
+}
+
+/// Add a document to the session's workspace, owned by the session's user
+pub(crate) fn store_document(
+    document: Document,
+    session_id: &str,
+    sessions: &SessionStorage,
+    document_storage: &DocumentStorage,
+    acl: &DocumentAcl,
+) -> Result<(), String> {
+    acl.register_document(&document.id, &session_user(session_id, sessions))
+        .map_err(|e| e.to_string())?;
+    document_storage
+        .lock()
+        .unwrap()
+        .entry(acl.workspace_key(session_id))
+        .or_insert_with(Vec::new)
+        .push(document);
+    Ok(())
+}
+
+/// The workspace documents the session's user holds at least `required` access to; in a
+/// shared workspace the others are invisible, as are documents behind an information barrier
+fn visible_documents(
+    session_id: &str,
+    required: AccessLevel,
+    sessions: &SessionStorage,
+    document_storage: &DocumentStorage,
+    acl: &DocumentAcl,
+    barriers: &InformationBarriers,
+) -> Vec<Document> {
+    let user = session_user(session_id, sessions);
+    workspace_documents(session_id, document_storage, acl)
+        .into_iter()
+        .filter(|d| acl.allows(&user, &d.id, required))
+        .filter(|d| barriers.screen(&user, &d.id, acl.matter_of(&d.id).as_deref()).is_none())
+        .collect()
+}
+
+fn workspace_documents(session_id: &str, document_storage: &DocumentStorage, acl: &DocumentAcl) -> Vec<Document> {
+    document_storage
+        .lock()
+        .unwrap()
+        .get(&acl.workspace_key(session_id))
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// Audit an attempt to reach a document behind an information barrier; true when the user is
+/// screened from it
+fn barrier_blocked(
+    session_id: &str,
+    document_id: &str,
+    attempt: &str,
+    sessions: &SessionStorage,
+    acl: &DocumentAcl,
+    barriers: &InformationBarriers,
+    security: &Arc<Mutex<SecurityManager>>,
+) -> bool {
+    let user = session_user(session_id, sessions);
+    match barriers.screen(&user, document_id, acl.matter_of(document_id).as_deref()) {
+        Some(block) => {
+            barriers.record_blocked(security, &user, document_id, &block, attempt);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Access check for changing a document the user can see
+fn require_access(
+    session_id: &str,
+    document_id: &str,
+    required: AccessLevel,
+    sessions: &SessionStorage,
+    acl: &DocumentAcl,
+) -> Result<(), String> {
+    if acl.allows(&session_user(session_id, sessions), document_id, required) {
+        Ok(())
+    } else {
+        Err("You do not have permission to change this document".to_string())
+    }
+}
+
 // Authentication commands
 #[tauri::command]
 pub async fn local_auth_login(
@@ -194,6 +329,7 @@ pub async fn local_auth_login(
 
         let session = LocalSession {
             id: session_id.clone(),
+            username: credentials.username.clone(),
             authenticated: true,
             created_at: now,
             last_activity: now,
@@ -307,6 +443,9 @@ pub async fn local_chat_create(
         return Err("Rate limit exceeded".to_string());
     }
 
+    // Placeholder titles are replaced by a generated one after the first exchange
+    let title_source = if session_summary::is_placeholder_title(&title) { None } else { Some("user") };
+
     let now = chrono::Utc::now().to_rfc3339();
     let chat_session = ChatSession {
         id: generate_uuid(),
@@ -315,6 +454,9 @@ pub async fn local_chat_create(
         created_at: now.clone(),
         message_count: 0,
         last_activity: now,
+        metadata: title_source.map(|source| {
+            HashMap::from([(session_summary::TITLE_SOURCE_KEY.to_string(), source.to_string())])
+        }),
     };
 
     let mut chat_guard = chat_storage.lock().unwrap();
@@ -326,13 +468,17 @@ pub async fn local_chat_create(
 
 #[tauri::command]
 pub async fn local_chat_send_message(
+    app: tauri::AppHandle,
     session_id: String,
     chat_session_id: String,
     content: String,
     role: Option<String>,
+    retrieval_id: Option<String>,
     sessions: State<'_, SessionStorage>,
     message_storage: State<'_, MessageStorage>,
     chat_storage: State<'_, ChatStorage>,
+    retrieval_log: State<'_, RetrievalLog>,
+    manager: State<'_, Arc<LLMManager>>,
 ) -> Result<ChatMessage, String> {
     if !validate_session(&session_id, &sessions)? {
         return Err("Unauthorized".to_string());
@@ -342,32 +488,137 @@ pub async fn local_chat_send_message(
         return Err("Rate limit exceeded".to_string());
     }
 
+    // Provenance comes from the session's own recorded retrievals, never from the client
+    let role = role.unwrap_or("user".to_string());
+    let retrieval_provenance = match retrieval_id {
+        Some(_) if role != "assistant" => return Err("Only assistant messages cite a retrieval".to_string()),
+        Some(retrieval_id) => {
+            let log = retrieval_log.lock().unwrap();
+            let provenance = log
+                .get(&session_id)
+                .and_then(|retrievals| retrievals.iter().find(|(id, _)| *id == retrieval_id))
+                .map(|(_, provenance)| provenance.clone())
+                .ok_or_else(|| "Retrieval not found".to_string())?;
+            Some(provenance)
+        }
+        None => None,
+    };
+
     let message = ChatMessage {
         id: generate_uuid(),
         session_id: chat_session_id.clone(),
         content,
-        role: role.unwrap_or("user".to_string()),
+        role,
         timestamp: chrono::Utc::now().to_rfc3339(),
         metadata: None,
+        retrieval_provenance,
     };
 
+    let updated_chat = append_chat_message(&chat_storage, &message_storage, &session_id, message.clone());
+
+    // Name the chat after its first answer and keep the rolling summary current, in the background
+    if let Some(chat) = updated_chat {
+        let title_due = message.role == "assistant" && session_summary::needs_title(&chat);
+        if title_due || session_summary::summary_due(&chat) {
+            spawn_session_update(
+                app,
+                manager.inner().clone(),
+                chat_storage.inner().clone(),
+                message_storage.inner().clone(),
+                session_id,
+                chat_session_id,
+            );
+        }
+    }
+
+    Ok(message)
+}
+
+/// Store a message and bump its chat's activity; returns the updated chat if it exists
+pub(crate) fn append_chat_message(
+    chat_storage: &ChatStorage,
+    message_storage: &MessageStorage,
+    session_id: &str,
+    message: ChatMessage,
+) -> Option<ChatSession> {
+    let chat_session_id = message.session_id.clone();
+    let timestamp = message.timestamp.clone();
+
     // Store message
-    let mut message_guard = message_storage.lock().unwrap();
-    let chat_messages = message_guard
+    message_storage
+        .lock()
+        .unwrap()
         .entry(chat_session_id.clone())
-        .or_insert_with(Vec::new);
-    chat_messages.push(message.clone());
+        .or_insert_with(Vec::new)
+        .push(message);
 
     // Update chat session
     let mut chat_guard = chat_storage.lock().unwrap();
-    if let Some(user_chats) = chat_guard.get_mut(&session_id) {
-        if let Some(chat) = user_chats.iter_mut().find(|c| c.id == chat_session_id) {
+    chat_guard
+        .get_mut(session_id)
+        .and_then(|user_chats| user_chats.iter_mut().find(|c| c.id == chat_session_id))
+        .map(|chat| {
             chat.message_count += 1;
-            chat.last_activity = message.timestamp.clone();
+            chat.last_activity = timestamp;
+            chat.clone()
+        })
+}
+
+fn spawn_session_update(
+    app: tauri::AppHandle,
+    manager: Arc<LLMManager>,
+    chat_storage: ChatStorage,
+    message_storage: MessageStorage,
+    session_id: String,
+    chat_session_id: String,
+) {
+    use tauri::Manager;
+
+    tauri::async_runtime::spawn(async move {
+        match session_summary::update_session(manager, chat_storage, message_storage, &session_id, &chat_session_id, false).await {
+            Ok(Some(chat)) => {
+                let _ = app.emit_all(session_summary::SESSION_UPDATED_EVENT, &chat);
+            }
+            Ok(None) => {}
+            Err(e) => log::warn!("Failed to update chat session {}: {}", chat_session_id, e),
         }
+    });
+}
+
+#[tauri::command]
+pub async fn regenerate_session_summary(
+    app: tauri::AppHandle,
+    session_id: String,
+    chat_session_id: String,
+    sessions: State<'_, SessionStorage>,
+    chat_storage: State<'_, ChatStorage>,
+    message_storage: State<'_, MessageStorage>,
+    manager: State<'_, Arc<LLMManager>>,
+) -> Result<ChatSession, String> {
+    use tauri::Manager;
+
+    if !validate_session(&session_id, &sessions)? {
+        return Err("Unauthorized".to_string());
     }
 
-    Ok(message)
+    if !check_rate_limit(&session_id, &sessions)? {
+        return Err("Rate limit exceeded".to_string());
+    }
+
+    let updated = session_summary::update_session(
+        manager.inner().clone(),
+        chat_storage.inner().clone(),
+        message_storage.inner().clone(),
+        &session_id,
+        &chat_session_id,
+        true,
+    )
+    .await
+    .map_err(|e| e.to_string())?
+    .ok_or_else(|| "Chat session has no messages to summarize".to_string())?;
+
+    let _ = app.emit_all(session_summary::SESSION_UPDATED_EVENT, &updated);
+    Ok(updated)
 }
 
 #[tauri::command]
@@ -400,6 +651,27 @@ pub async fn local_chat_get_messages(
     Ok(messages.into_iter().skip(start).take(end - start).collect())
 }
 
+#[tauri::command]
+pub async fn local_chat_get_message_provenance(
+    session_id: String,
+    chat_session_id: String,
+    message_id: String,
+    sessions: State<'_, SessionStorage>,
+    message_storage: State<'_, MessageStorage>,
+) -> Result<Option<RetrievalProvenance>, String> {
+    if !validate_session(&session_id, &sessions)? {
+        return Err("Unauthorized".to_string());
+    }
+
+    let message_guard = message_storage.lock().unwrap();
+    let message = message_guard
+        .get(&chat_session_id)
+        .and_then(|messages| messages.iter().find(|m| m.id == message_id))
+        .ok_or_else(|| "Message not found".to_string())?;
+
+    Ok(message.retrieval_provenance.clone())
+}
+
 #[tauri::command]
 pub async fn local_chat_delete_session(
     session_id: String,
@@ -438,6 +710,8 @@ pub async fn local_documents_list(
     offset: Option<u32>,
     sessions: State<'_, SessionStorage>,
     document_storage: State<'_, DocumentStorage>,
+    acl: State<'_, DocumentAclStorage>,
+    barriers: State<'_, BarrierStorage>,
 ) -> Result<Vec<Document>, String> {
     if !validate_session(&session_id, &sessions)? {
         return Err("Unauthorized".to_string());
@@ -447,8 +721,7 @@ pub async fn local_documents_list(
         return Err("Rate limit exceeded".to_string());
     }
 
-    let doc_guard = document_storage.lock().unwrap();
-    let mut documents = doc_guard.get(&session_id).cloned().unwrap_or_default();
+    let mut documents = visible_documents(&session_id, AccessLevel::Read, &sessions, &document_storage, &acl, &barriers);
 
     // Filter by category if specified
     if let Some(cat) = category {
@@ -479,6 +752,8 @@ pub async fn local_document_upload(
     tags: Vec<String>,
     sessions: State<'_, SessionStorage>,
     document_storage: State<'_, DocumentStorage>,
+    workspace_stats: State<'_, WorkspaceStatsStorage>,
+    acl: State<'_, DocumentAclStorage>,
 ) -> Result<Document, String> {
     if !validate_session(&session_id, &sessions)? {
         return Err("Unauthorized".to_string());
@@ -499,9 +774,8 @@ pub async fn local_document_upload(
         content_type,
     };
 
-    let mut doc_guard = document_storage.lock().unwrap();
-    let user_docs = doc_guard.entry(session_id).or_insert_with(Vec::new);
-    user_docs.push(document.clone());
+    store_document(document.clone(), &session_id, &sessions, &document_storage, &acl)?;
+    workspace_stats.invalidate();
 
     Ok(document)
 }
@@ -512,6 +786,9 @@ pub async fn local_document_get(
     document_id: String,
     sessions: State<'_, SessionStorage>,
     document_storage: State<'_, DocumentStorage>,
+    acl: State<'_, DocumentAclStorage>,
+    barriers: State<'_, BarrierStorage>,
+    security: State<'_, Arc<Mutex<SecurityManager>>>,
 ) -> Result<Option<Document>, String> {
     if !validate_session(&session_id, &sessions)? {
         return Err("Unauthorized".to_string());
@@ -521,13 +798,12 @@ pub async fn local_document_get(
         return Err("Rate limit exceeded".to_string());
     }
 
-    let doc_guard = document_storage.lock().unwrap();
-    if let Some(user_docs) = doc_guard.get(&session_id) {
-        let document = user_docs.iter().find(|d| d.id == document_id).cloned();
-        Ok(document)
-    } else {
-        Ok(None)
+    if barrier_blocked(&session_id, &document_id, "open", &sessions, &acl, &barriers, &security) {
+        return Ok(None);
     }
+    Ok(visible_documents(&session_id, AccessLevel::Read, &sessions, &document_storage, &acl, &barriers)
+        .into_iter()
+        .find(|d| d.id == document_id))
 }
 
 #[tauri::command]
@@ -536,6 +812,11 @@ pub async fn local_document_delete(
     document_id: String,
     sessions: State<'_, SessionStorage>,
     document_storage: State<'_, DocumentStorage>,
+    workspace_stats: State<'_, WorkspaceStatsStorage>,
+    acl: State<'_, DocumentAclStorage>,
+    barriers: State<'_, BarrierStorage>,
+    calendar: State<'_, CalendarSyncStorage>,
+    security: State<'_, Arc<Mutex<SecurityManager>>>,
 ) -> Result<bool, String> {
     if !validate_session(&session_id, &sessions)? {
         return Err("Unauthorized".to_string());
@@ -545,15 +826,34 @@ pub async fn local_document_delete(
         return Err("Rate limit exceeded".to_string());
     }
 
-    let mut doc_guard = document_storage.lock().unwrap();
-    if let Some(user_docs) = doc_guard.get_mut(&session_id) {
-        if let Some(pos) = user_docs.iter().position(|d| d.id == document_id) {
-            user_docs.remove(pos);
-            return Ok(true);
+    if !acl.allows(&session_user(&session_id, &sessions), &document_id, AccessLevel::Read)
+        || barrier_blocked(&session_id, &document_id, "delete", &sessions, &acl, &barriers, &security)
+    {
+        return Ok(false);
+    }
+    require_access(&session_id, &document_id, AccessLevel::Owner, &sessions, &acl)?;
+
+    let removed = {
+        let mut doc_guard = document_storage.lock().unwrap();
+        match doc_guard.get_mut(&acl.workspace_key(&session_id)) {
+            Some(user_docs) => match user_docs.iter().position(|d| d.id == document_id) {
+                Some(pos) => {
+                    user_docs.remove(pos);
+                    true
+                }
+                None => false,
+            },
+            None => false,
         }
+    };
+    if !removed {
+        return Ok(false);
     }
 
-    Ok(false)
+    acl.remove_document(&document_id).map_err(|e| e.to_string())?;
+    workspace_stats.invalidate();
+    calendar_sync::remove_deleted_document(&calendar, &security, &document_id).await;
+    Ok(true)
 }
 
 #[tauri::command]
@@ -565,6 +865,9 @@ pub async fn local_document_update(
     tags: Option<Vec<String>>,
     sessions: State<'_, SessionStorage>,
     document_storage: State<'_, DocumentStorage>,
+    acl: State<'_, DocumentAclStorage>,
+    barriers: State<'_, BarrierStorage>,
+    security: State<'_, Arc<Mutex<SecurityManager>>>,
 ) -> Result<Option<Document>, String> {
     if !validate_session(&session_id, &sessions)? {
         return Err("Unauthorized".to_string());
@@ -574,8 +877,17 @@ pub async fn local_document_update(
         return Err("Rate limit exceeded".to_string());
     }
 
+    // Renaming and refiling belong to the owner; tagging is annotation
+    if !acl.allows(&session_user(&session_id, &sessions), &document_id, AccessLevel::Read)
+        || barrier_blocked(&session_id, &document_id, "update", &sessions, &acl, &barriers, &security)
+    {
+        return Ok(None);
+    }
+    let required = if name.is_some() || category.is_some() { AccessLevel::Owner } else { AccessLevel::Annotate };
+    require_access(&session_id, &document_id, required, &sessions, &acl)?;
+
     let mut doc_guard = document_storage.lock().unwrap();
-    if let Some(user_docs) = doc_guard.get_mut(&session_id) {
+    if let Some(user_docs) = doc_guard.get_mut(&acl.workspace_key(&session_id)) {
         if let Some(document) = user_docs.iter_mut().find(|d| d.id == document_id) {
             if let Some(new_name) = name {
                 document.name = new_name;
@@ -601,6 +913,10 @@ pub async fn local_research_search(
     sessions: State<'_, SessionStorage>,
     document_storage: State<'_, DocumentStorage>,
     analyzer: State<'_, AnalyzerStorage>,
+    acl: State<'_, DocumentAclStorage>,
+    barriers: State<'_, BarrierStorage>,
+    security: State<'_, Arc<Mutex<SecurityManager>>>,
+    review: State<'_, ReviewQueueStorage>,
 ) -> Result<HashMap<String, serde_json::Value>, String> {
     if !validate_session(&session_id, &sessions)? {
         return Err("Unauthorized".to_string());
@@ -612,14 +928,25 @@ pub async fn local_research_search(
 
     let start_time = std::time::Instant::now();
 
-    // Get user documents
-    let doc_guard = document_storage.lock().unwrap();
-    let user_documents = doc_guard.get(&session_id).cloned().unwrap_or_default();
-    drop(doc_guard);
+    // Get the documents this user can see
+    let user_documents = visible_documents(&session_id, AccessLevel::Read, &sessions, &document_storage, &acl, &barriers);
 
     // Perform comprehensive search
     let search_results = perform_document_search(&query, &user_documents, &analyzer).await?;
 
+    // Documents behind an information barrier that the query would have found are attempts
+    // worth auditing; they stay out of the results
+    let user = session_user(&session_id, &sessions);
+    let screened: Vec<Document> = workspace_documents(&session_id, &document_storage, &acl)
+        .into_iter()
+        .filter(|d| acl.allows(&user, &d.id, AccessLevel::Read) && !user_documents.iter().any(|v| v.id == d.id))
+        .collect();
+    if !screened.is_empty() {
+        for hit in perform_document_search(&query, &screened, &analyzer).await? {
+            barrier_blocked(&session_id, &hit.id, "search", &sessions, &acl, &barriers, &security);
+        }
+    }
+
     // Apply pagination
     let limit = query.limit.unwrap_or(20) as usize;
     let offset = query.offset.unwrap_or(0) as usize;
@@ -652,6 +979,12 @@ pub async fn local_analysis_analyze(
     sessions: State<'_, SessionStorage>,
     document_storage: State<'_, DocumentStorage>,
     analyzer: State<'_, AnalyzerStorage>,
+    workspace_stats: State<'_, WorkspaceStatsStorage>,
+    timekeeper: State<'_, TimekeeperStorage>,
+    acl: State<'_, DocumentAclStorage>,
+    barriers: State<'_, BarrierStorage>,
+    security: State<'_, Arc<Mutex<SecurityManager>>>,
+    review: State<'_, ReviewQueueStorage>,
 ) -> Result<HashMap<String, serde_json::Value>, String> {
     if !validate_session(&session_id, &sessions)? {
         return Err("Unauthorized".to_string());
@@ -664,17 +997,66 @@ pub async fn local_analysis_analyze(
     let start_time = std::time::Instant::now();
 
     // Find the document to analyze
-    let doc_guard = document_storage.lock().unwrap();
-    let user_documents = doc_guard.get(&session_id).cloned().unwrap_or_default();
-    drop(doc_guard);
+    let user_documents = visible_documents(&session_id, AccessLevel::Read, &sessions, &document_storage, &acl, &barriers);
 
+    if barrier_blocked(&session_id, &request.document_id, "analyze", &sessions, &acl, &barriers, &security) {
+        return Err("Document not found".to_string());
+    }
     let document = user_documents
         .iter()
         .find(|doc| doc.id == request.document_id)
         .ok_or_else(|| "Document not found".to_string())?;
 
-    // Perform real document analysis
-    let analysis_result = perform_document_analysis(&request, document, &analyzer).await?;
+    // Perform real document analysis; the model usage it records counts towards this workspace
+    let workspace = acl.workspace_key(&session_id);
+    let analysis_result =
+        in_workspace(&workspace, perform_document_analysis(&request, document, &analyzer)).await?;
+    // Like any analysis it goes out of the app only once an attorney approved it
+    document_analyzer::submit_analysis_for_review(
+        Path::new(&analysis_path(document)),
+        &analyzer,
+        &review,
+        &session_user(&session_id, &sessions),
+    )?;
+    workspace_stats.record_analysis(&workspace, &request.analysis_type);
+    // Captured time is per matter; the run only joins a segment on the document's matter
+    if let Some(matter_id) = acl.matter_of(&document.id) {
+        timekeeper.note_activity(
+            &matter_id,
+            ActivityKind::AnalysisRun,
+            &format!("{} on {}", request.analysis_type.replace('_', " "), document.name),
+        );
+    }
+
+    // Events carry identifiers and counts only, never document content
+    crate::webhooks::publish_event(
+        crate::webhooks::EVENT_ANALYSIS_COMPLETED,
+        serde_json::json!({
+            "document_id": document.id,
+            "document_name": document.name,
+            "analysis_type": request.analysis_type,
+        }),
+    );
+    let high_risks = analysis_result["risks"]
+        .as_array()
+        .map(|risks| {
+            risks
+                .iter()
+                .filter(|r| matches!(r["severity"].as_str(), Some("High") | Some("Critical")))
+                .count()
+        })
+        .unwrap_or(0);
+    if high_risks > 0 {
+        crate::webhooks::publish_event(
+            crate::webhooks::EVENT_HIGH_RISK_CONTRACT,
+            serde_json::json!({
+                "document_id": document.id,
+                "document_name": document.name,
+                "category": document.category,
+                "high_risks": high_risks,
+            }),
+        );
+    }
 
     let processing_time = start_time.elapsed().as_millis();
 
@@ -737,6 +1119,8 @@ pub async fn local_system_stats(
     chat_storage: State<'_, ChatStorage>,
     document_storage: State<'_, DocumentStorage>,
     message_storage: State<'_, MessageStorage>,
+    acl: State<'_, DocumentAclStorage>,
+    barriers: State<'_, BarrierStorage>,
 ) -> Result<HashMap<String, serde_json::Value>, String> {
     if !validate_session(&session_id, &sessions)? {
         return Err("Unauthorized".to_string());
@@ -749,12 +1133,7 @@ pub async fn local_system_stats(
         .map(|v| v.len())
         .unwrap_or(0);
 
-    let user_documents = document_storage
-        .lock()
-        .unwrap()
-        .get(&session_id)
-        .map(|v| v.len())
-        .unwrap_or(0);
+    let user_documents = visible_documents(&session_id, AccessLevel::Read, &sessions, &document_storage, &acl, &barriers).len();
 
     let user_messages: usize = message_storage
         .lock()
@@ -795,6 +1174,32 @@ pub async fn local_system_stats(
     Ok(stats)
 }
 
+// Workspace statistics dashboard
+#[tauri::command]
+pub async fn local_workspace_stats(
+    session_id: String,
+    force_refresh: Option<bool>,
+    sessions: State<'_, SessionStorage>,
+    document_storage: State<'_, DocumentStorage>,
+    workspace_stats: State<'_, WorkspaceStatsStorage>,
+    acl: State<'_, DocumentAclStorage>,
+    barriers: State<'_, BarrierStorage>,
+) -> Result<WorkspaceStatsSnapshot, String> {
+    if !validate_session(&session_id, &sessions)? {
+        return Err("Unauthorized".to_string());
+    }
+
+    if !check_rate_limit(&session_id, &sessions)? {
+        return Err("Rate limit exceeded".to_string());
+    }
+
+    let user_documents = visible_documents(&session_id, AccessLevel::Read, &sessions, &document_storage, &acl, &barriers);
+
+    Ok(workspace_stats
+        .snapshot(&acl.workspace_key(&session_id), &user_documents, force_refresh.unwrap_or(false))
+        .await)
+}
+
 // Helper functions for real search and analysis functionality
 
 /// Perform comprehensive document search with full-text, semantic, and metadata filtering
@@ -1000,6 +1405,11 @@ fn generate_document_snippet(document: &Document, query_terms: &[&str]) -> Strin
     snippet_parts.join(" | ")
 }
 
+/// Where a workspace document is written out to be analysed; its analyses are recorded under it
+fn analysis_path(document: &Document) -> String {
+    format!("/tmp/analysis_{}.txt", document.id)
+}
+
 /// Perform real document analysis using the DocumentAnalyzer
 async fn perform_document_analysis(
     request: &AnalysisRequest,
@@ -1008,7 +1418,7 @@ async fn perform_document_analysis(
 ) -> Result<serde_json::Value, String> {
     // For now, we'll create a mock file path since we don't have actual file storage
     // In a real implementation, you'd have the actual file path stored with the document
-    let temp_file_path = format!("/tmp/analysis_{}.txt", document.id);
+    let temp_file_path = analysis_path(document);
 
     // Create a temporary file with document content (simplified approach)
     // In practice, you'd retrieve the actual file content from storage
@@ -1026,64 +1436,43 @@ async fn perform_document_analysis(
         return Err(format!("Failed to create temporary file: {}", e));
     }
 
-    // Perform analysis based on the requested type
-    let analysis_result = match request.analysis_type.as_str() {
-        "full_analysis" => {
-            match analyzer.analyze_document(Path::new(&temp_file_path)).await {
-                Ok(analysis) => create_full_analysis_result(analysis),
-                Err(e) => {
-                    // Clean up temporary file
-                    let _ = tokio::fs::remove_file(&temp_file_path).await;
-                    return Err(format!("Full analysis failed: {}", e));
-                }
-            }
-        }
-        "entity_extraction" => {
-            match analyzer.analyze_document(Path::new(&temp_file_path)).await {
-                Ok(analysis) => create_entity_analysis_result(analysis),
-                Err(e) => {
-                    let _ = tokio::fs::remove_file(&temp_file_path).await;
-                    return Err(format!("Entity extraction failed: {}", e));
-                }
-            }
-        }
-        "risk_assessment" => {
-            match analyzer.analyze_document(Path::new(&temp_file_path)).await {
-                Ok(analysis) => create_risk_analysis_result(analysis),
-                Err(e) => {
-                    let _ = tokio::fs::remove_file(&temp_file_path).await;
-                    return Err(format!("Risk assessment failed: {}", e));
-                }
-            }
-        }
-        "compliance_check" => {
-            match analyzer.analyze_document(Path::new(&temp_file_path)).await {
-                Ok(analysis) => create_compliance_analysis_result(analysis),
-                Err(e) => {
-                    let _ = tokio::fs::remove_file(&temp_file_path).await;
-                    return Err(format!("Compliance check failed: {}", e));
-                }
-            }
-        }
-        "key_terms" => {
-            match analyzer.analyze_document(Path::new(&temp_file_path)).await {
-                Ok(analysis) => create_key_terms_analysis_result(analysis),
-                Err(e) => {
-                    let _ = tokio::fs::remove_file(&temp_file_path).await;
-                    return Err(format!("Key terms extraction failed: {}", e));
-                }
-            }
-        }
-        _ => {
-            let _ = tokio::fs::remove_file(&temp_file_path).await;
-            return Err(format!("Unknown analysis type: {}", request.analysis_type));
-        }
-    };
+    let analysis_result = perform_file_analysis(&request.analysis_type, Path::new(&temp_file_path), analyzer).await;
 
     // Clean up temporary file
     let _ = tokio::fs::remove_file(&temp_file_path).await;
 
-    Ok(analysis_result)
+    analysis_result
+}
+
+/// Analysis types accepted by `local_analysis_analyze` and the automation API
+pub(crate) const ANALYSIS_TYPES: &[&str] = &[
+    "full_analysis",
+    "entity_extraction",
+    "risk_assessment",
+    "compliance_check",
+    "key_terms",
+];
+
+/// Analyse a file and shape the result for the requested analysis type
+pub(crate) async fn perform_file_analysis(
+    analysis_type: &str,
+    path: &Path,
+    analyzer: &DocumentAnalyzer,
+) -> Result<serde_json::Value, String> {
+    if !ANALYSIS_TYPES.contains(&analysis_type) {
+        return Err(format!("Unknown analysis type: {}", analysis_type));
+    }
+    let analysis = analyzer
+        .analyze_document(path)
+        .await
+        .map_err(|e| format!("{} failed: {}", analysis_type.replace('_', " "), e))?;
+    Ok(match analysis_type {
+        "full_analysis" => create_full_analysis_result(analysis),
+        "entity_extraction" => create_entity_analysis_result(analysis),
+        "risk_assessment" => create_risk_analysis_result(analysis),
+        "compliance_check" => create_compliance_analysis_result(analysis),
+        _ => create_key_terms_analysis_result(analysis),
+    })
 }
 
 /// Create full analysis result from DocumentAnalysis
@@ -0,0 +1,842 @@
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BTreeSet, BinaryHeap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, RwLock};
+
+use crate::nemotron_rag::RAGChunk;
+
+/// Local Vector Store for BEAR AI
+/// An embedded alternative to Qdrant for air-gapped installs: chunks, their embeddings and an HNSW
+/// graph over them live in one SQLite file under the app data directory. The graph is kept in
+/// memory and every insert writes the rows it changed, so opening the store does not rebuild it.
+/// Re-indexed chunks leave a deleted node behind until the collection is rebuilt or compacted.
+/// Each collection records the embedding model and dimension its vectors were made with; a store
+/// opened with another model refuses the collection instead of mixing vectors that do not compare.
+const M: usize = 16;
+const M0: usize = 32; // neighbours on the bottom layer
+const EF_CONSTRUCTION: usize = 100;
+const MIN_EF_SEARCH: usize = 64;
+const MAX_LEVEL: usize = 16;
+
+// Each entry upgrades the schema by one version (PRAGMA user_version)
+const MIGRATIONS: &[&str] = &[
+    "CREATE TABLE collections (
+        name TEXT PRIMARY KEY,
+        dimension INTEGER NOT NULL,
+        entry INTEGER
+     );
+     CREATE TABLE chunks (
+        collection TEXT NOT NULL,
+        node INTEGER NOT NULL,
+        chunk_id TEXT NOT NULL,
+        payload TEXT NOT NULL,
+        embedding BLOB NOT NULL,
+        neighbors TEXT NOT NULL,
+        deleted INTEGER NOT NULL DEFAULT 0,
+        PRIMARY KEY (collection, node)
+     );
+     CREATE INDEX chunks_by_chunk_id ON chunks (collection, chunk_id);",
+    // Collections created before this record their model on first use
+    "ALTER TABLE collections ADD COLUMN embedding_model TEXT;",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Scored {
+    distance: f32,
+    node: u32,
+}
+
+impl Eq for Scored {}
+
+impl Ord for Scored {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.distance.total_cmp(&other.distance).then(self.node.cmp(&other.node))
+    }
+}
+
+impl PartialOrd for Scored {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+fn normalized(mut vector: Vec<f32>) -> Vec<f32> {
+    let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|x| *x /= norm);
+    }
+    vector
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+/// Layer of a node, drawn from the HNSW distribution by hashing its number so that rebuilding
+/// the same vectors gives the same graph
+fn level_for(node: u32) -> usize {
+    let mut z = (node as u64).wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^= z >> 31;
+    let uniform = ((z >> 11) as f64 + 1.0) / (1u64 << 53) as f64;
+    ((-uniform.ln() / (M as f64).ln()) as usize).min(MAX_LEVEL)
+}
+
+/// Hierarchical navigable small world graph over unit vectors, by cosine distance
+#[derive(Debug, Default)]
+pub struct Hnsw {
+    vectors: Vec<Vec<f32>>,
+    neighbors: Vec<Vec<Vec<u32>>>, // node, then layer
+    deleted: Vec<bool>,
+    entry: Option<u32>,
+}
+
+impl Hnsw {
+    pub fn len(&self) -> usize {
+        self.vectors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vectors.is_empty()
+    }
+
+    pub fn live(&self) -> usize {
+        self.deleted.iter().filter(|d| !**d).count()
+    }
+
+    pub fn vector(&self, node: u32) -> &[f32] {
+        &self.vectors[node as usize]
+    }
+
+    pub fn mark_deleted(&mut self, node: u32) {
+        self.deleted[node as usize] = true;
+    }
+
+    fn distance(&self, query: &[f32], node: u32) -> f32 {
+        1.0 - dot(query, &self.vectors[node as usize])
+    }
+
+    fn top_layer(&self, node: u32) -> usize {
+        self.neighbors[node as usize].len() - 1
+    }
+
+    /// The `ef` nodes nearest the query on one layer, nearest first
+    fn search_layer(&self, query: &[f32], entries: &[u32], ef: usize, layer: usize) -> Vec<Scored> {
+        let mut visited: HashSet<u32> = entries.iter().copied().collect();
+        let mut candidates: BinaryHeap<Reverse<Scored>> = BinaryHeap::new();
+        let mut nearest: BinaryHeap<Scored> = BinaryHeap::new();
+        for &node in entries {
+            let scored = Scored {
+                distance: self.distance(query, node),
+                node,
+            };
+            candidates.push(Reverse(scored));
+            nearest.push(scored);
+        }
+        while let Some(Reverse(current)) = candidates.pop() {
+            if nearest.len() >= ef && nearest.peek().is_some_and(|far| current.distance > far.distance) {
+                break;
+            }
+            let Some(links) = self.neighbors[current.node as usize].get(layer) else {
+                continue;
+            };
+            for &next in links {
+                if !visited.insert(next) {
+                    continue;
+                }
+                let scored = Scored {
+                    distance: self.distance(query, next),
+                    node: next,
+                };
+                if nearest.len() < ef || nearest.peek().is_some_and(|far| scored.distance < far.distance) {
+                    candidates.push(Reverse(scored));
+                    nearest.push(scored);
+                    if nearest.len() > ef {
+                        nearest.pop();
+                    }
+                }
+            }
+        }
+        nearest.into_sorted_vec()
+    }
+
+    /// Greedy descent from the entry point to the given layer
+    fn descend(&self, query: &[f32], to_layer: usize) -> Option<u32> {
+        let mut current = self.entry?;
+        for layer in (to_layer + 1..=self.top_layer(current)).rev() {
+            current = self.search_layer(query, &[current], 1, layer)[0].node;
+        }
+        Some(current)
+    }
+
+    /// Add a vector; returns its node and the existing nodes whose neighbour lists changed
+    pub fn insert(&mut self, vector: Vec<f32>) -> (u32, Vec<u32>) {
+        let node = self.vectors.len() as u32;
+        let level = level_for(node);
+        self.vectors.push(normalized(vector));
+        self.neighbors.push(vec![Vec::new(); level + 1]);
+        self.deleted.push(false);
+
+        let Some(entry) = self.entry else {
+            self.entry = Some(node);
+            return (node, vec![]);
+        };
+        let top = self.top_layer(entry);
+        let query = self.vectors[node as usize].clone();
+        let mut entries = vec![self.descend(&query, level).unwrap_or(entry)];
+        let mut changed = BTreeSet::new();
+        for layer in (0..=level.min(top)).rev() {
+            let found = self.search_layer(&query, &entries, EF_CONSTRUCTION, layer);
+            let selected: Vec<u32> = found.iter().take(M).map(|s| s.node).collect();
+            let capacity = if layer == 0 { M0 } else { M };
+            for &neighbor in &selected {
+                let mut links = std::mem::take(&mut self.neighbors[neighbor as usize][layer]);
+                links.push(node);
+                if links.len() > capacity {
+                    let base = &self.vectors[neighbor as usize];
+                    links.sort_by(|a, b| {
+                        let da = 1.0 - dot(base, &self.vectors[*a as usize]);
+                        let db = 1.0 - dot(base, &self.vectors[*b as usize]);
+                        da.total_cmp(&db)
+                    });
+                    links.truncate(capacity);
+                }
+                self.neighbors[neighbor as usize][layer] = links;
+                changed.insert(neighbor);
+            }
+            self.neighbors[node as usize][layer] = selected;
+            entries = found.into_iter().map(|s| s.node).collect();
+        }
+        if level > top {
+            self.entry = Some(node);
+        }
+        (node, changed.into_iter().collect())
+    }
+
+    /// The `k` live nodes most similar to the query, with their cosine similarity, best first
+    pub fn search(&self, query: &[f32], k: usize) -> Vec<(u32, f32)> {
+        let query = normalized(query.to_vec());
+        let Some(entry) = self.descend(&query, 0) else {
+            return vec![];
+        };
+        // Deleted nodes still route the search but are not returned
+        let ef = (k + self.len() - self.live()).max(k * 2).max(MIN_EF_SEARCH);
+        self.search_layer(&query, &[entry], ef, 0)
+            .into_iter()
+            .filter(|s| !self.deleted[s.node as usize])
+            .take(k)
+            .map(|s| (s.node, 1.0 - s.distance))
+            .collect()
+    }
+}
+
+#[derive(Debug)]
+struct CollectionIndex {
+    dimension: usize,
+    embedding_model: Option<String>,
+    graph: Hnsw,
+    nodes_by_chunk: HashMap<String, u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CollectionStats {
+    pub name: String,
+    pub dimension: usize,
+    pub embedding_model: Option<String>,
+    pub chunks: usize,
+    pub deleted_nodes: usize, // left by re-indexed chunks until the next rebuild
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorStoreStats {
+    pub path: String,
+    pub size_bytes: u64, // database file and its write-ahead log
+    pub collections: Vec<CollectionStats>,
+}
+
+/// A collection holds vectors from another embedding model or of another dimension than the
+/// configured one; its chunks have to be re-indexed before it can be used
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmbeddingMismatch {
+    pub collection: String,
+    pub stored_model: Option<String>,
+    pub stored_dimension: usize,
+    pub model: String,
+    pub dimension: usize,
+}
+
+impl std::fmt::Display for EmbeddingMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Collection {} holds {}-dimensional vectors from {}, not {}-dimensional vectors from {}; re-index it",
+            self.collection,
+            self.stored_dimension,
+            self.stored_model.as_deref().unwrap_or("an unrecorded model"),
+            self.dimension,
+            self.model
+        )
+    }
+}
+
+impl std::error::Error for EmbeddingMismatch {}
+
+impl CollectionIndex {
+    fn check_embeddings(&self, collection: &str, model: &str, dimension: usize) -> Result<()> {
+        if self.dimension == dimension && self.embedding_model.as_deref().map_or(true, |stored| stored == model) {
+            return Ok(());
+        }
+        Err(EmbeddingMismatch {
+            collection: collection.to_string(),
+            stored_model: self.embedding_model.clone(),
+            stored_dimension: self.dimension,
+            model: model.to_string(),
+            dimension,
+        }
+        .into())
+    }
+}
+
+fn embedding_blob(vector: &[f32]) -> Vec<u8> {
+    vector.iter().flat_map(|x| x.to_le_bytes()).collect()
+}
+
+fn embedding_from_blob(blob: &[u8]) -> Vec<f32> {
+    blob.chunks_exact(4).map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]])).collect()
+}
+
+fn in_transaction<T>(connection: &sqlite::Connection, write: impl FnOnce() -> Result<T>) -> Result<T> {
+    connection.execute("BEGIN IMMEDIATE")?;
+    match write() {
+        Ok(value) => {
+            connection.execute("COMMIT")?;
+            Ok(value)
+        }
+        Err(e) => {
+            let _ = connection.execute("ROLLBACK");
+            Err(e)
+        }
+    }
+}
+
+/// Chunks and HNSW graphs of every collection, persisted in one SQLite file
+pub struct LocalVectorStore {
+    path: PathBuf,
+    connection: Mutex<sqlite::ConnectionThreadSafe>,
+    collections: RwLock<HashMap<String, CollectionIndex>>,
+}
+
+impl LocalVectorStore {
+    pub fn open(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let connection = sqlite::Connection::open_thread_safe(path)
+            .with_context(|| format!("Cannot open the vector store at {}", path.display()))?;
+        connection.execute("PRAGMA journal_mode = WAL")?;
+        Self::migrate(&connection)?;
+
+        let mut collections = HashMap::new();
+        let mut statement = connection.prepare("SELECT name, dimension, entry, embedding_model FROM collections")?;
+        let mut rows = Vec::new();
+        while let sqlite::State::Row = statement.next()? {
+            rows.push((
+                statement.read::<String, _>(0)?,
+                statement.read::<i64, _>(1)? as usize,
+                statement.read::<Option<i64>, _>(2)?.map(|e| e as u32),
+                statement.read::<Option<String>, _>(3)?,
+            ));
+        }
+        drop(statement);
+        for (name, dimension, entry, embedding_model) in rows {
+            let mut index = Self::load_collection(&connection, &name, dimension, entry)?;
+            index.embedding_model = embedding_model;
+            collections.insert(name, index);
+        }
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            connection: Mutex::new(connection),
+            collections: RwLock::new(collections),
+        })
+    }
+
+    fn migrate(connection: &sqlite::Connection) -> Result<()> {
+        let mut statement = connection.prepare("PRAGMA user_version")?;
+        statement.next()?;
+        let version = statement.read::<i64, _>(0)? as usize;
+        drop(statement);
+        if version > MIGRATIONS.len() {
+            return Err(anyhow!(
+                "The vector store was created by a newer version of BEAR AI (schema {})",
+                version
+            ));
+        }
+        for (i, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+            in_transaction(connection, || {
+                connection.execute(migration)?;
+                connection.execute(format!("PRAGMA user_version = {}", i + 1))?;
+                Ok(())
+            })?;
+            log::info!("Vector store schema migrated to version {}", i + 1);
+        }
+        Ok(())
+    }
+
+    fn load_collection(connection: &sqlite::Connection, name: &str, dimension: usize, entry: Option<u32>) -> Result<CollectionIndex> {
+        let mut statement = connection.prepare(
+            "SELECT node, chunk_id, embedding, neighbors, deleted FROM chunks WHERE collection = ? ORDER BY node",
+        )?;
+        statement.bind((1, name))?;
+        let mut graph = Hnsw {
+            entry,
+            ..Default::default()
+        };
+        let mut nodes_by_chunk = HashMap::new();
+        while let sqlite::State::Row = statement.next()? {
+            let node = statement.read::<i64, _>(0)? as u32;
+            if node as usize != graph.len() {
+                return Err(anyhow!("Vector store collection {} is damaged; rebuild it", name));
+            }
+            let deleted = statement.read::<i64, _>(4)? != 0;
+            if !deleted {
+                nodes_by_chunk.insert(statement.read::<String, _>(1)?, node);
+            }
+            graph.vectors.push(embedding_from_blob(&statement.read::<Vec<u8>, _>(2)?));
+            graph.neighbors.push(serde_json::from_str(&statement.read::<String, _>(3)?)?);
+            graph.deleted.push(deleted);
+        }
+        Ok(CollectionIndex {
+            dimension,
+            embedding_model: None,
+            graph,
+            nodes_by_chunk,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Create a collection for vectors of `dimension` from `embedding_model`; an existing one must
+    /// hold vectors from the same model, and fails with `EmbeddingMismatch` otherwise
+    pub fn create_collection(&self, name: &str, dimension: usize, embedding_model: &str) -> Result<()> {
+        let mut collections = self.collections.write().unwrap();
+        if let Some(existing) = collections.get_mut(name) {
+            existing.check_embeddings(name, embedding_model, dimension)?;
+            if existing.embedding_model.is_none() {
+                let connection = self.connection.lock().unwrap();
+                let mut statement = connection.prepare("UPDATE collections SET embedding_model = ? WHERE name = ?")?;
+                statement.bind((1, embedding_model))?;
+                statement.bind((2, name))?;
+                statement.next()?;
+                existing.embedding_model = Some(embedding_model.to_string());
+            }
+            return Ok(());
+        }
+        let connection = self.connection.lock().unwrap();
+        let mut statement = connection
+            .prepare("INSERT INTO collections (name, dimension, entry, embedding_model) VALUES (?, ?, NULL, ?)")?;
+        statement.bind((1, name))?;
+        statement.bind((2, dimension as i64))?;
+        statement.bind((3, embedding_model))?;
+        statement.next()?;
+        collections.insert(
+            name.to_string(),
+            CollectionIndex {
+                dimension,
+                embedding_model: Some(embedding_model.to_string()),
+                graph: Hnsw::default(),
+                nodes_by_chunk: HashMap::new(),
+            },
+        );
+        Ok(())
+    }
+
+    fn write_node(connection: &sqlite::Connection, collection: &str, node: u32, chunk: &RAGChunk, graph: &Hnsw) -> Result<()> {
+        let payload = RAGChunk {
+            embedding: Vec::new(), // stored once, in the blob
+            ..chunk.clone()
+        };
+        let mut statement = connection.prepare(
+            "INSERT INTO chunks (collection, node, chunk_id, payload, embedding, neighbors, deleted) VALUES (?, ?, ?, ?, ?, ?, 0)",
+        )?;
+        statement.bind((1, collection))?;
+        statement.bind((2, node as i64))?;
+        statement.bind((3, chunk.id.as_str()))?;
+        statement.bind((4, serde_json::to_string(&payload)?.as_str()))?;
+        statement.bind((5, embedding_blob(graph.vector(node)).as_slice()))?;
+        statement.bind((6, serde_json::to_string(&graph.neighbors[node as usize])?.as_str()))?;
+        statement.next()?;
+        Ok(())
+    }
+
+    fn write_entry(connection: &sqlite::Connection, collection: &str, graph: &Hnsw) -> Result<()> {
+        let mut statement = connection.prepare("UPDATE collections SET entry = ? WHERE name = ?")?;
+        statement.bind((1, graph.entry.map(|e| e as i64)))?;
+        statement.bind((2, collection))?;
+        statement.next()?;
+        Ok(())
+    }
+
+    /// Insert chunks, replacing any already stored under the same id
+    pub fn upsert(&self, collection: &str, chunks: &[RAGChunk]) -> Result<()> {
+        let mut collections = self.collections.write().unwrap();
+        let index = collections
+            .get_mut(collection)
+            .ok_or_else(|| anyhow!("Collection {} does not exist", collection))?;
+        if let Some(chunk) = chunks.iter().find(|c| c.embedding.len() != index.dimension) {
+            return Err(anyhow!(
+                "Chunk {} has a {}-dimensional embedding; collection {} expects {}",
+                chunk.id,
+                chunk.embedding.len(),
+                collection,
+                index.dimension
+            ));
+        }
+
+        let first_new = index.graph.len() as u32;
+        let mut replaced = Vec::new();
+        let mut changed = BTreeSet::new();
+        for chunk in chunks {
+            if let Some(old) = index.nodes_by_chunk.get(&chunk.id) {
+                index.graph.mark_deleted(*old);
+                replaced.push(*old);
+            }
+            let (node, neighbors) = index.graph.insert(chunk.embedding.clone());
+            index.nodes_by_chunk.insert(chunk.id.clone(), node);
+            changed.extend(neighbors.into_iter().filter(|n| *n < first_new));
+        }
+
+        let connection = self.connection.lock().unwrap();
+        let written = in_transaction(&connection, || {
+            for (offset, chunk) in chunks.iter().enumerate() {
+                Self::write_node(&connection, collection, first_new + offset as u32, chunk, &index.graph)?;
+            }
+            let mut update = connection.prepare("UPDATE chunks SET neighbors = ? WHERE collection = ? AND node = ?")?;
+            for node in &changed {
+                update.reset()?;
+                update.bind((1, serde_json::to_string(&index.graph.neighbors[*node as usize])?.as_str()))?;
+                update.bind((2, collection))?;
+                update.bind((3, *node as i64))?;
+                update.next()?;
+            }
+            let mut delete = connection.prepare("UPDATE chunks SET deleted = 1 WHERE collection = ? AND node = ?")?;
+            for node in &replaced {
+                delete.reset()?;
+                delete.bind((1, collection))?;
+                delete.bind((2, *node as i64))?;
+                delete.next()?;
+            }
+            Self::write_entry(&connection, collection, &index.graph)
+        });
+        if let Err(e) = written {
+            // Put the in-memory graph back in step with what is stored
+            let (dimension, embedding_model) = (index.dimension, index.embedding_model.take());
+            let entry = Self::stored_entry(&connection, collection)?;
+            *index = Self::load_collection(&connection, collection, dimension, entry)?;
+            index.embedding_model = embedding_model;
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Delete the chunks stored under these ids; returns how many there were. Their nodes stay in
+    /// the graph, marked deleted, until the collection is compacted.
+    pub fn delete(&self, collection: &str, chunk_ids: &[String]) -> Result<usize> {
+        let mut collections = self.collections.write().unwrap();
+        let index = collections
+            .get_mut(collection)
+            .ok_or_else(|| anyhow!("Collection {} does not exist", collection))?;
+        let nodes: Vec<u32> = chunk_ids.iter().filter_map(|id| index.nodes_by_chunk.get(id).copied()).collect();
+
+        let connection = self.connection.lock().unwrap();
+        in_transaction(&connection, || {
+            let mut delete = connection.prepare("UPDATE chunks SET deleted = 1 WHERE collection = ? AND node = ?")?;
+            for node in &nodes {
+                delete.reset()?;
+                delete.bind((1, collection))?;
+                delete.bind((2, *node as i64))?;
+                delete.next()?;
+            }
+            Ok(())
+        })?;
+        for node in &nodes {
+            index.graph.mark_deleted(*node);
+        }
+        index.nodes_by_chunk.retain(|_, node| !nodes.contains(node));
+        Ok(nodes.len())
+    }
+
+    fn stored_entry(connection: &sqlite::Connection, collection: &str) -> Result<Option<u32>> {
+        let mut statement = connection.prepare("SELECT entry FROM collections WHERE name = ?")?;
+        statement.bind((1, collection))?;
+        statement.next()?;
+        Ok(statement.read::<Option<i64>, _>(0)?.map(|e| e as u32))
+    }
+
+    /// The stored chunks nearest the query, best first
+    pub fn search(&self, collection: &str, query: &[f32], limit: usize) -> Result<Vec<RAGChunk>> {
+        let collections = self.collections.read().unwrap();
+        let index = collections
+            .get(collection)
+            .ok_or_else(|| anyhow!("Collection {} does not exist", collection))?;
+        if query.len() != index.dimension {
+            return Err(anyhow!(
+                "The query has a {}-dimensional embedding; collection {} expects {}",
+                query.len(),
+                collection,
+                index.dimension
+            ));
+        }
+        let hits = index.graph.search(query, limit);
+
+        let connection = self.connection.lock().unwrap();
+        let mut statement = connection.prepare("SELECT payload FROM chunks WHERE collection = ? AND node = ?")?;
+        let mut chunks = Vec::with_capacity(hits.len());
+        for (node, _) in hits {
+            statement.reset()?;
+            statement.bind((1, collection))?;
+            statement.bind((2, node as i64))?;
+            if let sqlite::State::Row = statement.next()? {
+                let mut chunk: RAGChunk = serde_json::from_str(&statement.read::<String, _>(0)?)?;
+                chunk.embedding = index.graph.vector(node).to_vec();
+                chunks.push(chunk);
+            }
+        }
+        Ok(chunks)
+    }
+
+    /// Every live chunk of a collection in node order, with its stored embedding
+    fn live_chunks(&self, connection: &sqlite::Connection, collection: &str, graph: &Hnsw) -> Result<Vec<RAGChunk>> {
+        let mut statement =
+            connection.prepare("SELECT node, payload FROM chunks WHERE collection = ? AND deleted = 0 ORDER BY node")?;
+        statement.bind((1, collection))?;
+        let mut chunks = Vec::new();
+        while let sqlite::State::Row = statement.next()? {
+            let node = statement.read::<i64, _>(0)? as u32;
+            let mut chunk: RAGChunk = serde_json::from_str(&statement.read::<String, _>(1)?)?;
+            chunk.embedding = graph.vector(node).to_vec();
+            chunks.push(chunk);
+        }
+        Ok(chunks)
+    }
+
+    /// Rebuild the HNSW graph of one collection, or all, from its live chunks; deleted nodes are
+    /// dropped. A collection whose vectors come from another model than `embedding_model`
+    /// is refused: its graph would be rebuilt over vectors queries cannot be compared with.
+    pub fn rebuild(&self, collection: Option<&str>, embedding_model: &str) -> Result<VectorStoreStats> {
+        let mut collections = self.collections.write().unwrap();
+        let names: Vec<String> = match collection {
+            Some(name) if collections.contains_key(name) => vec![name.to_string()],
+            Some(name) => return Err(anyhow!("Collection {} does not exist", name)),
+            None => collections.keys().cloned().collect(),
+        };
+        for name in &names {
+            let index = &collections[name];
+            index.check_embeddings(name, embedding_model, index.dimension)?;
+        }
+        let connection = self.connection.lock().unwrap();
+        for name in names {
+            let index = collections.get_mut(&name).expect("collection listed above");
+            let chunks = self.live_chunks(&connection, &name, &index.graph)?;
+            if chunks.iter().any(|c| c.embedding.len() != index.dimension) {
+                return Err(anyhow!("Vector store collection {} holds vectors of mixed dimensions; re-index it", name));
+            }
+            let mut graph = Hnsw::default();
+            let mut nodes_by_chunk = HashMap::new();
+            for chunk in &chunks {
+                let (node, _) = graph.insert(chunk.embedding.clone());
+                nodes_by_chunk.insert(chunk.id.clone(), node);
+            }
+            in_transaction(&connection, || {
+                let mut clear = connection.prepare("DELETE FROM chunks WHERE collection = ?")?;
+                clear.bind((1, name.as_str()))?;
+                clear.next()?;
+                for (node, chunk) in chunks.iter().enumerate() {
+                    Self::write_node(&connection, &name, node as u32, chunk, &graph)?;
+                }
+                Self::write_entry(&connection, &name, &graph)
+            })?;
+            log::info!("Rebuilt vector index {} with {} chunks", name, chunks.len());
+            index.graph = graph;
+            index.nodes_by_chunk = nodes_by_chunk;
+        }
+        drop(connection);
+        drop(collections);
+        self.stats()
+    }
+
+    /// Rebuild every collection that has deleted nodes, then reclaim the file space they used
+    pub fn compact(&self, embedding_model: &str) -> Result<VectorStoreStats> {
+        let fragmented: Vec<String> = self
+            .collections
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|(_, index)| index.graph.live() < index.graph.len())
+            .map(|(name, _)| name.clone())
+            .collect();
+        for name in fragmented {
+            self.rebuild(Some(&name), embedding_model)?;
+        }
+        let connection = self.connection.lock().unwrap();
+        connection.execute("VACUUM")?;
+        connection.execute("PRAGMA wal_checkpoint(TRUNCATE)")?;
+        drop(connection);
+        self.stats()
+    }
+
+    pub fn stats(&self) -> Result<VectorStoreStats> {
+        let wal = PathBuf::from(format!("{}-wal", self.path.display()));
+        let size_bytes = [&self.path, &wal]
+            .iter()
+            .filter_map(|p| std::fs::metadata(p).ok())
+            .map(|m| m.len())
+            .sum();
+        let mut collections: Vec<CollectionStats> = self
+            .collections
+            .read()
+            .unwrap()
+            .iter()
+            .map(|(name, index)| CollectionStats {
+                name: name.clone(),
+                dimension: index.dimension,
+                embedding_model: index.embedding_model.clone(),
+                chunks: index.graph.live(),
+                deleted_nodes: index.graph.len() - index.graph.live(),
+            })
+            .collect();
+        collections.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(VectorStoreStats {
+            path: self.path.to_string_lossy().to_string(),
+            size_bytes,
+            collections,
+        })
+    }
+}
+
+/// Where the store lives when the configuration does not say
+pub fn default_store_path() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("bear-ai")
+        .join("vector_store.sqlite3")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn vector(seed: u32, dimension: usize) -> Vec<f32> {
+        (0..dimension)
+            .map(|i| ((seed as f32 + 1.0) * (i as f32 + 1.0) * 12.9898).sin() * 43.758)
+            .map(|x| x - x.floor() - 0.5)
+            .collect()
+    }
+
+    fn chunk(id: &str, content: &str, embedding: Vec<f32>) -> RAGChunk {
+        RAGChunk {
+            id: id.to_string(),
+            document_id: "doc-1".to_string(),
+            content: content.to_string(),
+            embedding,
+            chunk_index: 0,
+            tokens: 3,
+            overlap: 0,
+            legal_concepts: vec![],
+            cited_authorities: vec![],
+            confidence: 1.0,
+            temporal_relevance: 1.0,
+            created_at: Utc::now(),
+            metadata: HashMap::new(),
+            rerank: None,
+            authority: None,
+        }
+    }
+
+    #[test]
+    fn test_hnsw_finds_exact_neighbours() {
+        let mut graph = Hnsw::default();
+        let vectors: Vec<Vec<f32>> = (0..500).map(|i| vector(i, 24)).collect();
+        for v in &vectors {
+            graph.insert(v.clone());
+        }
+
+        let mut recalled = 0;
+        for query in vectors.iter().step_by(25) {
+            let query = normalized(query.clone());
+            let mut exact: Vec<(u32, f32)> = graph
+                .vectors
+                .iter()
+                .enumerate()
+                .map(|(i, v)| (i as u32, dot(&query, v)))
+                .collect();
+            exact.sort_by(|a, b| b.1.total_cmp(&a.1));
+            let found: HashSet<u32> = graph.search(&query, 10).into_iter().map(|(node, _)| node).collect();
+            recalled += exact.iter().take(10).filter(|(node, _)| found.contains(node)).count();
+        }
+        assert!(recalled >= 190, "recall {} of 200", recalled);
+
+        graph.mark_deleted(0);
+        assert!(graph.search(&vectors[0], 3).iter().all(|(node, _)| *node != 0));
+        assert_eq!(graph.live(), 499);
+        assert_eq!(level_for(42), level_for(42));
+    }
+
+    #[test]
+    fn test_store_persists_replaces_and_compacts() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("vector_store.sqlite3");
+        {
+            let store = LocalVectorStore::open(&path).unwrap();
+            store.create_collection("legal_chunks", 8, "nomic-embed-text").unwrap();
+            assert!(store.create_collection("legal_chunks", 16, "nomic-embed-text").is_err());
+            let chunks: Vec<RAGChunk> = (0..40).map(|i| chunk(&format!("c{}", i), &format!("clause {}", i), vector(i, 8))).collect();
+            store.upsert("legal_chunks", &chunks).unwrap();
+            store.upsert("legal_chunks", &[chunk("c7", "clause 7, amended", vector(7, 8))]).unwrap();
+        }
+
+        let store = LocalVectorStore::open(&path).unwrap();
+        let hits = store.search("legal_chunks", &vector(7, 8), 1).unwrap();
+        assert_eq!(hits[0].id, "c7");
+        assert_eq!(hits[0].content, "clause 7, amended");
+        let stats = store.stats().unwrap();
+        assert_eq!((stats.collections[0].chunks, stats.collections[0].deleted_nodes), (40, 1));
+
+        let stats = store.compact("nomic-embed-text").unwrap();
+        assert_eq!((stats.collections[0].chunks, stats.collections[0].deleted_nodes), (40, 0));
+        assert_eq!(store.search("legal_chunks", &vector(12, 8), 1).unwrap()[0].id, "c12");
+        assert!(store.search("missing", &vector(1, 8), 1).is_err());
+        assert!(store.search("legal_chunks", &vector(1, 16), 1).is_err());
+
+        assert_eq!(store.delete("legal_chunks", &["c12".to_string(), "c99".to_string()]).unwrap(), 1);
+        assert_ne!(store.search("legal_chunks", &vector(12, 8), 1).unwrap()[0].id, "c12");
+        assert_eq!(store.stats().unwrap().collections[0].chunks, 39);
+    }
+
+    #[test]
+    fn test_collection_keeps_its_embedding_model() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("vector_store.sqlite3");
+        {
+            let store = LocalVectorStore::open(&path).unwrap();
+            store.create_collection("legal_chunks", 8, "nomic-embed-text").unwrap();
+            store.upsert("legal_chunks", &[chunk("c1", "clause 1", vector(1, 8))]).unwrap();
+        }
+
+        let store = LocalVectorStore::open(&path).unwrap();
+        assert_eq!(store.stats().unwrap().collections[0].embedding_model.as_deref(), Some("nomic-embed-text"));
+        store.create_collection("legal_chunks", 8, "nomic-embed-text").unwrap();
+        // Same dimension, different model: the vectors do not compare
+        let refused = store.create_collection("legal_chunks", 8, "bge-small").unwrap_err();
+        let mismatch = refused.downcast_ref::<EmbeddingMismatch>().unwrap();
+        assert_eq!(mismatch.stored_model.as_deref(), Some("nomic-embed-text"));
+        assert!(store.rebuild(Some("legal_chunks"), "bge-small").is_err());
+        assert!(store.rebuild(None, "nomic-embed-text").is_ok());
+    }
+}
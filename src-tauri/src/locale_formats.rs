@@ -0,0 +1,238 @@
+use chrono::NaiveDate;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// Locale-aware Date and Amount Parsing for BEAR AI
+/// Entity extraction used to assume US conventions ($1,000.00, MM/DD/YYYY). EU contracts write
+/// "€ 1.000,00" and "01-03-2024" for the first of March. Amounts are unambiguous once grouping
+/// and decimal separators are told apart; numeric dates are not, so the date order is picked per
+/// document: from the governing-law jurisdiction for English documents, from the detected
+/// language for everything else.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DateOrder {
+    MonthFirst,
+    DayFirst,
+}
+
+/// Jurisdictions outside the US that write dates day first
+const DAY_FIRST_JURISDICTIONS: &[&str] = &[
+    "england", "wales", "scotland", "northern ireland", "ireland", "united kingdom",
+    "netherlands", "germany", "france", "belgium", "luxembourg", "spain", "italy", "portugal",
+    "austria", "switzerland", "sweden", "denmark", "norway", "finland", "poland",
+    "australia", "new zealand", "india", "singapore", "hong kong", "south africa",
+];
+
+const DAY_FIRST_LAW_ADJECTIVES: &[&str] = &[
+    "english", "scots", "irish", "dutch", "german", "french", "belgian", "spanish", "italian",
+    "portuguese", "austrian", "swiss", "swedish", "danish", "australian",
+];
+
+/// Month names in the languages we detect, lowercase, with common abbreviations
+const MONTH_NAMES: &[(&str, u32)] = &[
+    ("january", 1), ("jan", 1), ("januar", 1), ("janvier", 1), ("januari", 1), ("enero", 1), ("gennaio", 1), ("janeiro", 1),
+    ("february", 2), ("feb", 2), ("februar", 2), ("février", 2), ("fevrier", 2), ("februari", 2), ("febrero", 2), ("febbraio", 2), ("fevereiro", 2),
+    ("march", 3), ("mar", 3), ("märz", 3), ("maerz", 3), ("mars", 3), ("maart", 3), ("marzo", 3), ("março", 3),
+    ("april", 4), ("apr", 4), ("avril", 4), ("abril", 4), ("aprile", 4),
+    ("may", 5), ("mai", 5), ("mei", 5), ("mayo", 5), ("maggio", 5), ("maio", 5),
+    ("june", 6), ("jun", 6), ("juni", 6), ("juin", 6), ("junio", 6), ("giugno", 6), ("junho", 6),
+    ("july", 7), ("jul", 7), ("juli", 7), ("juillet", 7), ("julio", 7), ("luglio", 7), ("julho", 7),
+    ("august", 8), ("aug", 8), ("août", 8), ("aout", 8), ("augustus", 8), ("agosto", 8),
+    ("september", 9), ("sep", 9), ("sept", 9), ("septembre", 9), ("septiembre", 9), ("settembre", 9), ("setembro", 9),
+    ("october", 10), ("oct", 10), ("oktober", 10), ("octobre", 10), ("octubre", 10), ("ottobre", 10), ("outubro", 10),
+    ("november", 11), ("nov", 11), ("novembre", 11), ("noviembre", 11), ("novembro", 11),
+    ("december", 12), ("dec", 12), ("dezember", 12), ("décembre", 12), ("decembre", 12), ("diciembre", 12), ("dicembre", 12), ("dezembro", 12),
+];
+
+/// Date order from the detected language and, for English documents, the governing-law clause
+pub fn detect_date_order(language: &str, text: &str) -> DateOrder {
+    match language {
+        "de" | "nl" | "fr" | "es" | "it" | "pt" | "ru" => DateOrder::DayFirst,
+        "en" if governed_by_day_first_jurisdiction(text) => DateOrder::DayFirst,
+        _ => DateOrder::MonthFirst,
+    }
+}
+
+fn governed_by_day_first_jurisdiction(text: &str) -> bool {
+    let lower = text.to_lowercase();
+    let places = DAY_FIRST_JURISDICTIONS.join("|");
+    let adjectives = DAY_FIRST_LAW_ADJECTIVES.join("|");
+    let re = Regex::new(&format!(
+        r"(?:laws|courts) of (?:the )?(?:{})\b|\b(?:{}) law\b",
+        places, adjectives
+    ))
+    .unwrap();
+    re.is_match(&lower)
+}
+
+fn month_number(name: &str) -> Option<u32> {
+    let name = name.trim_end_matches('.').to_lowercase();
+    MONTH_NAMES.iter().find(|(n, _)| *n == name).map(|(_, m)| *m)
+}
+
+/// A date or amount found in text, with its byte span and normalized value
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocalizedMatch {
+    pub start: usize,
+    pub end: usize,
+    pub text: String,
+    pub normalized: String, // ISO 8601 date, or "EUR 1000.00"
+}
+
+fn overlaps(found: &[LocalizedMatch], start: usize, end: usize) -> bool {
+    found.iter().any(|m| start < m.end && m.start < end)
+}
+
+/// Dates in numeric (25/12/2023, 25.12.2023, 2023-12-25) and written forms (December 25, 2023;
+/// 25 December 2023; 25. Dezember 2023; 25 de diciembre de 2023)
+pub fn find_dates(text: &str, order: DateOrder) -> Vec<LocalizedMatch> {
+    let mut found: Vec<LocalizedMatch> = Vec::new();
+    let push = |found: &mut Vec<LocalizedMatch>, start: usize, end: usize, date: Option<NaiveDate>| {
+        if let Some(date) = date {
+            if !overlaps(found, start, end) {
+                found.push(LocalizedMatch {
+                    start,
+                    end,
+                    text: text[start..end].to_string(),
+                    normalized: date.format("%Y-%m-%d").to_string(),
+                });
+            }
+        }
+    };
+
+    // Both separators must agree ("2023-12-25", not "2023-12.25")
+    let iso = Regex::new(r"\b(\d{4})([-/.])(\d{1,2})([-/.])(\d{1,2})\b").unwrap();
+    for caps in iso.captures_iter(text).filter(|caps| caps[2] == caps[4]) {
+        let whole = caps.get(0).unwrap();
+        let date = NaiveDate::from_ymd_opt(caps[1].parse().unwrap(), caps[3].parse().unwrap(), caps[5].parse().unwrap());
+        push(&mut found, whole.start(), whole.end(), date);
+    }
+
+    let numeric = Regex::new(r"\b(\d{1,2})([-/.])(\d{1,2})([-/.])(\d{4})\b").unwrap();
+    for caps in numeric.captures_iter(text).filter(|caps| caps[2] == caps[4]) {
+        let whole = caps.get(0).unwrap();
+        let (a, b, year): (u32, u32, i32) = (caps[1].parse().unwrap(), caps[3].parse().unwrap(), caps[5].parse().unwrap());
+        let (month, day) = match order {
+            DateOrder::MonthFirst => (a, b),
+            DateOrder::DayFirst => (b, a),
+        };
+        // "25/12/2023" in a US document can only be day first
+        let date = NaiveDate::from_ymd_opt(year, month, day).or_else(|| NaiveDate::from_ymd_opt(year, day, month));
+        push(&mut found, whole.start(), whole.end(), date);
+    }
+
+    let day_month_year = Regex::new(r"\b(\d{1,2})(?:\.|er|st|nd|rd|th)?\s+(?:de\s+)?(\p{L}+\.?)\s+(?:de\s+)?(\d{4})\b").unwrap();
+    for caps in day_month_year.captures_iter(text) {
+        let whole = caps.get(0).unwrap();
+        let date = month_number(&caps[2])
+            .and_then(|month| NaiveDate::from_ymd_opt(caps[3].parse().unwrap(), month, caps[1].parse().unwrap()));
+        push(&mut found, whole.start(), whole.end(), date);
+    }
+
+    let month_day_year = Regex::new(r"\b(\p{L}+\.?)\s+(\d{1,2})(?:st|nd|rd|th)?,?\s+(\d{4})\b").unwrap();
+    for caps in month_day_year.captures_iter(text) {
+        let whole = caps.get(0).unwrap();
+        let date = month_number(&caps[1])
+            .and_then(|month| NaiveDate::from_ymd_opt(caps[3].parse().unwrap(), month, caps[2].parse().unwrap()));
+        push(&mut found, whole.start(), whole.end(), date);
+    }
+
+    found.sort_by_key(|m| m.start);
+    found
+}
+
+fn currency_code(symbol: &str) -> &'static str {
+    match symbol.to_lowercase().as_str() {
+        "€" | "eur" | "euro" | "euros" => "EUR",
+        "£" | "gbp" => "GBP",
+        "chf" => "CHF",
+        _ => "USD",
+    }
+}
+
+/// Parse "1.000,00", "1,000.00", "1 000,50" or "12,5" into a number. The last separator is
+/// decimal when one or two digits follow it; three digits after a separator are a thousands group
+/// in every locale, since amounts are never written to three decimals.
+pub fn parse_localized_number(number: &str) -> Option<f64> {
+    let number: String = number
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '\u{a0}' && *c != '\u{202f}')
+        .collect();
+    let decimal_at = number
+        .rfind(['.', ','])
+        .filter(|&i| (1..=2).contains(&(number.len() - i - 1)));
+
+    let (integer, fraction) = match decimal_at {
+        Some(i) => (&number[..i], &number[i + 1..]),
+        None => (number.as_str(), ""),
+    };
+    let integer: String = integer.chars().filter(|c| c.is_ascii_digit()).collect();
+    if integer.is_empty() {
+        return None;
+    }
+    format!("{}.{}", integer, if fraction.is_empty() { "0" } else { fraction })
+        .parse()
+        .ok()
+}
+
+/// Amounts with a currency symbol or code before ("€ 1.000,00", "$10,000") or after
+/// ("1.000,00 EUR", "250 euro")
+pub fn find_monetary_amounts(text: &str) -> Vec<LocalizedMatch> {
+    const NUMBER: &str = r"\d{1,3}(?:[.,\u{a0}\u{202f} ]\d{3})+(?:[.,]\d{1,2})?|\d+(?:[.,]\d{1,2})?";
+    let prefixed = Regex::new(&format!(r"([$€£]|\b(?:USD|EUR|GBP|CHF)\b)\s?({})(?:,-)?", NUMBER)).unwrap();
+    let suffixed = Regex::new(&format!(r"\b({})\s?(€|\b(?:EUR|USD|GBP|CHF|[Ee]uros?)\b)", NUMBER)).unwrap();
+
+    let mut found: Vec<LocalizedMatch> = Vec::new();
+    for (re, currency_group, number_group) in [(&prefixed, 1, 2), (&suffixed, 2, 1)] {
+        for caps in re.captures_iter(text) {
+            let whole = caps.get(0).unwrap();
+            if overlaps(&found, whole.start(), whole.end()) {
+                continue;
+            }
+            if let Some(value) = parse_localized_number(&caps[number_group]) {
+                found.push(LocalizedMatch {
+                    start: whole.start(),
+                    end: whole.end(),
+                    text: whole.as_str().to_string(),
+                    normalized: format!("{} {:.2}", currency_code(&caps[currency_group]), value),
+                });
+            }
+        }
+    }
+
+    found.sort_by_key(|m| m.start);
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_date_order_and_dates() {
+        let uk_contract = "This Agreement is governed by the laws of England and Wales.";
+        assert_eq!(detect_date_order("en", uk_contract), DateOrder::DayFirst);
+        assert_eq!(detect_date_order("en", "Governed by the laws of the State of New York."), DateOrder::MonthFirst);
+        assert_eq!(detect_date_order("nl", ""), DateOrder::DayFirst);
+
+        let normalized = |text: &str, order| -> Vec<String> {
+            find_dates(text, order).into_iter().map(|m| m.normalized).collect()
+        };
+        assert_eq!(normalized("due 03/01/2024", DateOrder::MonthFirst), vec!["2024-03-01"]);
+        assert_eq!(normalized("due 03/01/2024", DateOrder::DayFirst), vec!["2024-01-03"]);
+        assert_eq!(normalized("fällig am 1. März 2024", DateOrder::DayFirst), vec!["2024-03-01"]);
+        assert_eq!(normalized("el 15 de enero de 2025", DateOrder::DayFirst), vec!["2025-01-15"]);
+        assert_eq!(normalized("on December 25, 2023 and 2024-02-29", DateOrder::MonthFirst), vec!["2023-12-25", "2024-02-29"]);
+    }
+
+    #[test]
+    fn test_monetary_amounts_in_any_locale() {
+        let amounts = |text: &str| -> Vec<String> {
+            find_monetary_amounts(text).into_iter().map(|m| m.normalized).collect()
+        };
+        assert_eq!(amounts("a fee of $10,000.50"), vec!["USD 10000.50"]);
+        assert_eq!(amounts("een vergoeding van € 1.250.000,00 per jaar"), vec!["EUR 1250000.00"]);
+        assert_eq!(amounts("huur € 950,- per maand"), vec!["EUR 950.00"]);
+        assert_eq!(amounts("Betrag von 2.500 EUR"), vec!["EUR 2500.00"]);
+        assert_eq!(amounts("a cap of £1,000"), vec!["GBP 1000.00"]);
+    }
+}
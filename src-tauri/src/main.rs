@@ -11,13 +11,93 @@ use tauri::{
     Window,
 };
 
+#[cfg(feature = "desktop")]
+mod ai_disclosure;
+#[cfg(feature = "desktop")]
+mod analysis_comparison;
+#[cfg(feature = "desktop")]
+mod analytics_export;
+#[cfg(feature = "desktop")]
+mod audio_evidence;
+#[cfg(feature = "desktop")]
+mod authority_ranking;
+#[cfg(feature = "desktop")]
+mod automation_api;
+#[cfg(feature = "desktop")]
+mod brief_checker;
+#[cfg(feature = "desktop")]
+mod calendar_sync;
+#[cfg(feature = "desktop")]
+mod case_analytics;
+#[cfg(feature = "desktop")]
+mod channel_ingestion;
+#[cfg(feature = "desktop")]
+mod charts;
 #[cfg(feature = "desktop")]
 mod chat_export;
 #[cfg(feature = "desktop")]
+mod chunking_profiles;
+#[cfg(feature = "desktop")]
+mod citation_graph;
+#[cfg(feature = "desktop")]
+mod clause_search;
+#[cfg(feature = "desktop")]
+mod client_bundle;
+#[cfg(feature = "desktop")]
+mod contract_execution;
+#[cfg(feature = "desktop")]
+mod coordination;
+#[cfg(feature = "desktop")]
+mod corporate_structure;
+#[cfg(feature = "desktop")]
+mod corpus_topics;
+#[cfg(feature = "desktop")]
+mod court_filing;
+#[cfg(feature = "desktop")]
+mod deposition_prep;
+#[cfg(feature = "desktop")]
+mod discovery;
+#[cfg(feature = "desktop")]
+mod document_acl;
+#[cfg(feature = "desktop")]
 mod document_analyzer;
 #[cfg(feature = "desktop")]
+mod document_classifier;
+#[cfg(feature = "desktop")]
+mod document_qa;
+#[cfg(feature = "desktop")]
+mod document_repository;
+#[cfg(feature = "desktop")]
+mod document_summary;
+#[cfg(feature = "desktop")]
+mod docx_writer;
+#[cfg(feature = "desktop")]
+mod dpa_checker;
+#[cfg(feature = "desktop")]
+mod dpia;
+#[cfg(feature = "desktop")]
+mod entity_relations;
+#[cfg(feature = "desktop")]
+mod exhibit_list;
+#[cfg(feature = "desktop")]
+mod financial_statements;
+#[cfg(feature = "desktop")]
+mod glossary;
+#[cfg(feature = "desktop")]
 mod huggingface;
 #[cfg(feature = "desktop")]
+mod incremental_analysis;
+#[cfg(feature = "desktop")]
+mod ingest_dedup;
+#[cfg(feature = "desktop")]
+mod intranet_crawler;
+#[cfg(feature = "desktop")]
+mod jurisdiction;
+#[cfg(feature = "desktop")]
+mod knowledge_connectors;
+#[cfg(feature = "desktop")]
+mod license_attribution;
+#[cfg(feature = "desktop")]
 mod licensing;
 #[cfg(feature = "desktop")]
 mod llm_commands;
@@ -26,6 +106,18 @@ mod llm_manager;
 #[cfg(feature = "desktop")]
 mod local_api;
 #[cfg(feature = "desktop")]
+mod local_vector_store;
+#[cfg(feature = "desktop")]
+mod locale_formats;
+#[cfg(feature = "desktop")]
+mod matter_consistency;
+#[cfg(feature = "desktop")]
+mod matter_graph;
+#[cfg(feature = "desktop")]
+mod matter_intake;
+#[cfg(feature = "desktop")]
+mod matters;
+#[cfg(feature = "desktop")]
 mod mcp_server;
 #[cfg(feature = "desktop")]
 mod security;
@@ -42,16 +134,60 @@ mod hardware_detection;
 #[cfg(feature = "desktop")]
 mod model_commands;
 #[cfg(feature = "desktop")]
+mod object_storage;
+#[cfg(feature = "desktop")]
+mod ocr_ingest;
+#[cfg(feature = "desktop")]
 mod ocr_processor;
 #[cfg(feature = "desktop")]
+mod output_language;
+#[cfg(feature = "desktop")]
+mod party_registry;
+#[cfg(feature = "desktop")]
 mod performance_tracker;
 #[cfg(feature = "desktop")]
 mod nemotron_rag;
+#[cfg(feature = "desktop")]
+mod network_attestation;
+#[cfg(feature = "desktop")]
+mod provenance;
+#[cfg(feature = "desktop")]
+mod reanalysis;
+#[cfg(feature = "desktop")]
+mod regulatory_monitor;
+#[cfg(feature = "desktop")]
+mod request_tracing;
+#[cfg(feature = "desktop")]
+mod research_memo;
+#[cfg(feature = "desktop")]
+mod retrieval_scope;
+#[cfg(feature = "desktop")]
+mod review_queue;
+#[cfg(feature = "desktop")]
+mod sanctions_screening;
+#[cfg(feature = "desktop")]
+mod session_summary;
+#[cfg(feature = "desktop")]
+mod speech_to_text;
+#[cfg(feature = "desktop")]
+mod table_of_authorities;
+#[cfg(feature = "desktop")]
+mod text_processing;
+#[cfg(feature = "desktop")]
+mod timekeeping;
+#[cfg(feature = "desktop")]
+mod webhooks;
+#[cfg(feature = "desktop")]
+mod workflow_budget;
+#[cfg(feature = "desktop")]
+mod workflow_packages;
+#[cfg(feature = "desktop")]
+mod workspace_stats;
 
 #[cfg(feature = "desktop")]
 use llm_commands::*;
 #[cfg(feature = "desktop")]
-use llm_manager::{LLMManager, list_models, download_model, load_model, unload_model, remove_model, get_recommended_models, get_system_info as llm_get_system_info, generate_response, chat_with_model, get_embeddings, show_model_info, pull_model, create_model, copy_model};
+use llm_manager::{LLMManager, list_models, download_model, load_model, unload_model, warmup_model, preflight_model_load, get_model_provenance, refresh_model_manifest, set_fallback_models, get_fallback_models, list_loaded_models, get_model_endpoint, set_draft_model, remove_draft_model, list_draft_models, generate_with_prefix_cache, list_prompt_cache, clear_prompt_cache, remove_model, get_recommended_models, get_system_info as llm_get_system_info, generate_response, chat_with_model, get_embeddings, get_embeddings_batch, show_model_info, pull_model, create_model, copy_model};
 #[cfg(feature = "desktop")]
 use local_api::*;
 #[cfg(feature = "desktop")]
@@ -62,6 +198,8 @@ use stripe_integration_v2::*;
 use pii_detector::*;
 #[cfg(feature = "desktop")]
 use ocr_processor::*;
+#[cfg(feature = "desktop")]
+use retrieval_scope::{RetrievalScope, ScreenedDocuments};
 
 // Learn more about Tauri commands at https://tauri.app/v1/guides/features/command
 #[cfg(feature = "desktop")]
@@ -101,9 +239,21 @@ async fn hide_window(window: Window) -> Result<(), String> {
 #[cfg(feature = "desktop")]
 #[tauri::command]
 async fn initialize_rag_system(
-    config: bear_ai_legal_assistant::nemotron_rag::NemotronConfig,
+    mut config: bear_ai_legal_assistant::nemotron_rag::NemotronConfig,
+    app: tauri::AppHandle,
     state: tauri::State<'_, Arc<tokio::sync::RwLock<bear_ai_legal_assistant::AppState>>>,
 ) -> Result<String, String> {
+    let app_data_dir = app.path_resolver().app_data_dir();
+    if config.local_store_path.is_none() {
+        config.local_store_path = app_data_dir
+            .as_ref()
+            .map(|dir| dir.join("vector_store.sqlite3").to_string_lossy().into_owned());
+    }
+    if config.citation_graph_path.is_none() {
+        config.citation_graph_path = app_data_dir
+            .as_ref()
+            .map(|dir| dir.join("citation_graph.jsonl").to_string_lossy().into_owned());
+    }
     bear_ai_legal_assistant::initialize_rag_system(config, state).await
 }
 
@@ -111,37 +261,188 @@ async fn initialize_rag_system(
 #[tauri::command]
 async fn process_legal_document(
     document: String,
+    document_type: Option<bear_ai_legal_assistant::nemotron_rag::DocumentType>,
     state: tauri::State<'_, Arc<tokio::sync::RwLock<bear_ai_legal_assistant::AppState>>>,
 ) -> Result<String, String> {
-    bear_ai_legal_assistant::process_legal_document(document, state).await
+    bear_ai_legal_assistant::process_legal_document(document, document_type, state).await
+}
+
+/// The retrieval scope over the library crate's RAG index and citation graph, whose types are
+/// the library's own
+#[cfg(feature = "desktop")]
+impl RetrievalScope {
+    /// Whether a chunk of the library index may be used
+    fn admit_indexed(&self, chunk: &bear_ai_legal_assistant::nemotron_rag::RAGChunk, screened: &ScreenedDocuments) -> bool {
+        let matter_id = chunk.metadata.get("matter_id").map(String::as_str);
+        self.admit_document(&chunk.document_id, matter_id, screened)
+    }
+
+    /// Whether a citation graph node may be shown; authorities outside the index always may
+    fn admit_node(&self, node: &bear_ai_legal_assistant::citation_graph::CitationNode, screened: &ScreenedDocuments) -> bool {
+        match &node.document_id {
+            Some(document_id) => self.admit_document(document_id, node.matter_id.as_deref(), screened),
+            None => true,
+        }
+    }
+
+    /// Drop citation links to documents the user may not see
+    fn filter_links(
+        &self,
+        mut links: Vec<bear_ai_legal_assistant::citation_graph::CitationLink>,
+    ) -> Vec<bear_ai_legal_assistant::citation_graph::CitationLink> {
+        let screened = ScreenedDocuments::default();
+        links.retain(|link| self.admit_node(&link.node, &screened));
+        self.audit(screened, "citation graph");
+        links
+    }
+
+    /// Drop chunks of the library index the user may not see, copies of documents kept in a
+    /// newer version, and documents left without chunks
+    fn filter_indexed(
+        &self,
+        mut result: bear_ai_legal_assistant::nemotron_rag::RetrievalResult,
+    ) -> bear_ai_legal_assistant::nemotron_rag::RetrievalResult {
+        let screened = ScreenedDocuments::default();
+        result.chunks.retain(|chunk| self.admit_indexed(chunk, &screened));
+        self.audit(screened, "retrieval");
+        bear_ai_legal_assistant::nemotron_rag::collapse_duplicates(&mut result.chunks);
+        let chunks = &result.chunks;
+        result.documents.retain(|d| chunks.iter().any(|c| c.document_id == d.id));
+        result
+    }
 }
 
 #[cfg(feature = "desktop")]
 #[tauri::command]
 async fn retrieve_legal_info(
+    session_id: String,
     query: String,
+    latency_budget_ms: Option<u64>,
+    acl: tauri::State<'_, document_acl::DocumentAclStorage>,
+    sessions: tauri::State<'_, local_api::SessionStorage>,
+    barriers: tauri::State<'_, enterprise_management::BarrierStorage>,
+    security: tauri::State<'_, Arc<Mutex<security::SecurityManager>>>,
+    retrieval_log: tauri::State<'_, local_api::RetrievalLog>,
     state: tauri::State<'_, Arc<tokio::sync::RwLock<bear_ai_legal_assistant::AppState>>>,
 ) -> Result<bear_ai_legal_assistant::nemotron_rag::RetrievalResult, String> {
-    bear_ai_legal_assistant::retrieve_legal_info(query, state).await
+    let scope = RetrievalScope::for_session(&session_id, &sessions, &acl, &barriers, &security)?;
+    let result = bear_ai_legal_assistant::retrieve_legal_info(query.clone(), latency_budget_ms, state).await?;
+    let mut result = scope.filter_indexed(result);
+
+    // The answer this retrieval informs cites it by id, so its provenance is what was returned here
+    let provenance = serde_json::to_value(result.provenance(&query))
+        .and_then(serde_json::from_value)
+        .map_err(|e| e.to_string())?;
+    result.retrieval_id = Some(local_api::record_retrieval(&retrieval_log, &session_id, provenance));
+    Ok(result)
 }
 
 #[cfg(feature = "desktop")]
 #[tauri::command]
 async fn generate_agentic_response(
+    session_id: String,
     query: String,
+    model: Option<String>,
+    acl: tauri::State<'_, document_acl::DocumentAclStorage>,
+    sessions: tauri::State<'_, local_api::SessionStorage>,
+    barriers: tauri::State<'_, enterprise_management::BarrierStorage>,
+    security: tauri::State<'_, Arc<Mutex<security::SecurityManager>>>,
+    llm: tauri::State<'_, Arc<LLMManager>>,
     state: tauri::State<'_, Arc<tokio::sync::RwLock<bear_ai_legal_assistant::AppState>>>,
-) -> Result<String, String> {
-    bear_ai_legal_assistant::generate_agentic_response(query, state).await
+) -> Result<bear_ai_legal_assistant::nemotron_rag::VerifiedAnswer<bear_ai_legal_assistant::grounded_answer::GroundedAnswer>, String> {
+    let model = match model {
+        Some(model) => model,
+        None => llm
+            .default_generation_model()
+            .await
+            .ok_or_else(|| "No model is available to answer with".to_string())?,
+    };
+    let generate = |prompt: String| {
+        let request = llm_manager::GenerateRequest {
+            model: model.clone(),
+            prompt,
+            stream: Some(false),
+            options: Some(llm_manager::GenerateOptions {
+                num_predict: Some(900),
+                temperature: Some(0.1),
+                ..Default::default()
+            }),
+            system: Some("You are a legal research assistant. Answer only from the numbered sources and cite them.".to_string()),
+            template: None,
+            context: None,
+            raw: None,
+        };
+        let llm = llm.inner().clone();
+        async move { llm.generate_response(request).await.map(|r| r.response) }
+    };
+
+    let scope = RetrievalScope::for_session(&session_id, &sessions, &acl, &barriers, &security)?;
+    let screened = ScreenedDocuments::default();
+    let response = bear_ai_legal_assistant::generate_agentic_response(
+        query,
+        model.clone(),
+        state,
+        |chunk| scope.admit_indexed(chunk, &screened),
+        generate,
+    )
+    .await;
+    scope.audit(screened, "retrieval");
+    response
 }
 
 #[cfg(feature = "desktop")]
 #[tauri::command]
 async fn multi_hop_reasoning(
+    session_id: String,
     query: String,
     max_hops: Option<usize>,
+    model: Option<String>,
+    acl: tauri::State<'_, document_acl::DocumentAclStorage>,
+    sessions: tauri::State<'_, local_api::SessionStorage>,
+    barriers: tauri::State<'_, enterprise_management::BarrierStorage>,
+    security: tauri::State<'_, Arc<Mutex<security::SecurityManager>>>,
+    llm: tauri::State<'_, Arc<LLMManager>>,
     state: tauri::State<'_, Arc<tokio::sync::RwLock<bear_ai_legal_assistant::AppState>>>,
-) -> Result<String, String> {
-    bear_ai_legal_assistant::multi_hop_reasoning(query, max_hops, state).await
+) -> Result<bear_ai_legal_assistant::multi_hop::ReasoningTrace, String> {
+    let model = match model {
+        Some(model) => model,
+        None => llm
+            .default_generation_model()
+            .await
+            .ok_or_else(|| "No model is available to reason with".to_string())?,
+    };
+    let generate = |prompt: String| {
+        let request = llm_manager::GenerateRequest {
+            model: model.clone(),
+            prompt,
+            stream: Some(false),
+            options: Some(llm_manager::GenerateOptions {
+                num_predict: Some(700),
+                temperature: Some(0.1),
+                ..Default::default()
+            }),
+            system: Some("You are a legal research assistant. Reason strictly from the passages and findings you are given.".to_string()),
+            template: None,
+            context: None,
+            raw: None,
+        };
+        let llm = llm.inner().clone();
+        async move { llm.generate_response(request).await.map(|r| r.response.trim().to_string()) }
+    };
+
+    let scope = RetrievalScope::for_session(&session_id, &sessions, &acl, &barriers, &security)?;
+    let screened = ScreenedDocuments::default();
+    let trace = bear_ai_legal_assistant::multi_hop_reasoning(
+        query,
+        max_hops,
+        model.clone(),
+        state,
+        |chunk| scope.admit_indexed(chunk, &screened),
+        generate,
+    )
+    .await;
+    scope.audit(screened, "retrieval");
+    trace
 }
 
 #[cfg(feature = "desktop")]
@@ -152,6 +453,646 @@ async fn get_rag_health(
     bear_ai_legal_assistant::get_rag_health(state).await
 }
 
+#[cfg(feature = "desktop")]
+#[tauri::command]
+async fn get_citing_documents(
+    session_id: String,
+    document: String,
+    acl: tauri::State<'_, document_acl::DocumentAclStorage>,
+    sessions: tauri::State<'_, local_api::SessionStorage>,
+    barriers: tauri::State<'_, enterprise_management::BarrierStorage>,
+    security: tauri::State<'_, Arc<Mutex<security::SecurityManager>>>,
+    state: tauri::State<'_, Arc<tokio::sync::RwLock<bear_ai_legal_assistant::AppState>>>,
+) -> Result<Vec<bear_ai_legal_assistant::citation_graph::CitationLink>, String> {
+    let links = bear_ai_legal_assistant::get_citing_documents(document, state).await?;
+    Ok(RetrievalScope::for_session(&session_id, &sessions, &acl, &barriers, &security)?.filter_links(links))
+}
+
+#[cfg(feature = "desktop")]
+#[tauri::command]
+async fn get_cited_by(
+    session_id: String,
+    document: String,
+    acl: tauri::State<'_, document_acl::DocumentAclStorage>,
+    sessions: tauri::State<'_, local_api::SessionStorage>,
+    barriers: tauri::State<'_, enterprise_management::BarrierStorage>,
+    security: tauri::State<'_, Arc<Mutex<security::SecurityManager>>>,
+    state: tauri::State<'_, Arc<tokio::sync::RwLock<bear_ai_legal_assistant::AppState>>>,
+) -> Result<Vec<bear_ai_legal_assistant::citation_graph::CitationLink>, String> {
+    let links = bear_ai_legal_assistant::get_cited_by(document, state).await?;
+    Ok(RetrievalScope::for_session(&session_id, &sessions, &acl, &barriers, &security)?.filter_links(links))
+}
+
+/// A chain through a document the user may not see is reported as no chain
+#[cfg(feature = "desktop")]
+#[tauri::command]
+async fn shortest_citation_path(
+    session_id: String,
+    from: String,
+    to: String,
+    acl: tauri::State<'_, document_acl::DocumentAclStorage>,
+    sessions: tauri::State<'_, local_api::SessionStorage>,
+    barriers: tauri::State<'_, enterprise_management::BarrierStorage>,
+    security: tauri::State<'_, Arc<Mutex<security::SecurityManager>>>,
+    state: tauri::State<'_, Arc<tokio::sync::RwLock<bear_ai_legal_assistant::AppState>>>,
+) -> Result<Option<bear_ai_legal_assistant::citation_graph::CitationPath>, String> {
+    let path = bear_ai_legal_assistant::shortest_citation_path(from, to, state).await?;
+    let scope = RetrievalScope::for_session(&session_id, &sessions, &acl, &barriers, &security)?;
+    let screened = ScreenedDocuments::default();
+    let path = path.filter(|path| path.nodes.iter().all(|node| scope.admit_node(node, &screened)));
+    scope.audit(screened, "citation graph");
+    Ok(path)
+}
+
+#[cfg(feature = "desktop")]
+#[tauri::command]
+async fn get_vector_store_stats(
+    state: tauri::State<'_, Arc<tokio::sync::RwLock<bear_ai_legal_assistant::AppState>>>,
+) -> Result<bear_ai_legal_assistant::local_vector_store::VectorStoreStats, String> {
+    bear_ai_legal_assistant::get_vector_store_stats(state).await
+}
+
+#[cfg(feature = "desktop")]
+#[tauri::command]
+async fn rebuild_vector_index(
+    collection: Option<String>,
+    state: tauri::State<'_, Arc<tokio::sync::RwLock<bear_ai_legal_assistant::AppState>>>,
+) -> Result<bear_ai_legal_assistant::local_vector_store::VectorStoreStats, String> {
+    bear_ai_legal_assistant::rebuild_vector_index(collection, state).await
+}
+
+#[cfg(feature = "desktop")]
+#[tauri::command]
+async fn compact_vector_store(
+    state: tauri::State<'_, Arc<tokio::sync::RwLock<bear_ai_legal_assistant::AppState>>>,
+) -> Result<bear_ai_legal_assistant::local_vector_store::VectorStoreStats, String> {
+    bear_ai_legal_assistant::compact_vector_store(state).await
+}
+
+#[cfg(feature = "desktop")]
+#[tauri::command]
+async fn purge_duplicate_documents(
+    session_id: String,
+    collection: Option<String>,
+    acl: tauri::State<'_, document_acl::DocumentAclStorage>,
+    calendar: tauri::State<'_, calendar_sync::CalendarSyncStorage>,
+    topics: tauri::State<'_, corpus_topics::CorpusTopicsStorage>,
+    sessions: tauri::State<'_, local_api::SessionStorage>,
+    security: tauri::State<'_, Arc<Mutex<security::SecurityManager>>>,
+    state: tauri::State<'_, Arc<tokio::sync::RwLock<bear_ai_legal_assistant::AppState>>>,
+) -> Result<Vec<bear_ai_legal_assistant::ingest_dedup::DuplicateDocument>, String> {
+    let user = local_api::authenticated_user(&session_id, &sessions)?;
+    if !security.lock().unwrap().check_permission(&user, security::Permission::DocumentDelete) {
+        return Err("Purging duplicates needs permission to delete documents".to_string());
+    }
+    // A copy filed under another matter is that matter's document, not a duplicate to purge
+    let purged = bear_ai_legal_assistant::purge_duplicate_documents(collection, state, |duplicate| {
+        acl.matter_of(&duplicate.document_id) == acl.matter_of(&duplicate.duplicate_of)
+    })
+    .await?;
+
+    for duplicate in &purged {
+        topics.remove_document(&duplicate.document_id).map_err(|e| e.to_string())?;
+        acl.remove_document(&duplicate.document_id).map_err(|e| e.to_string())?;
+        calendar_sync::remove_deleted_document(&calendar, &security, &duplicate.document_id).await;
+        let details = HashMap::from([
+            ("purged_by".to_string(), user.clone()),
+            ("collection".to_string(), duplicate.collection.clone()),
+            ("duplicate_of".to_string(), duplicate.duplicate_of.clone()),
+        ]);
+        let _ = security.lock().unwrap().write_audit_entry(
+            security::SecurityAction::DocumentDelete,
+            &duplicate.document_id,
+            security::ActionOutcome::Success,
+            Some(details),
+        );
+    }
+    Ok(purged)
+}
+
+#[cfg(feature = "desktop")]
+#[tauri::command]
+async fn delete_indexed_document(
+    session_id: String,
+    collection: String,
+    document_id: String,
+    acl: tauri::State<'_, document_acl::DocumentAclStorage>,
+    calendar: tauri::State<'_, calendar_sync::CalendarSyncStorage>,
+    topics: tauri::State<'_, corpus_topics::CorpusTopicsStorage>,
+    sessions: tauri::State<'_, local_api::SessionStorage>,
+    security: tauri::State<'_, Arc<Mutex<security::SecurityManager>>>,
+    state: tauri::State<'_, Arc<tokio::sync::RwLock<bear_ai_legal_assistant::AppState>>>,
+) -> Result<(), String> {
+    let user = local_api::authenticated_user(&session_id, &sessions)?;
+    if !security.lock().unwrap().check_permission(&user, security::Permission::DocumentDelete)
+        || !acl.allows(&user, &document_id, document_acl::AccessLevel::Owner)
+    {
+        return Err("Deleting this document needs permission to delete documents and ownership of it".to_string());
+    }
+    let deleted = bear_ai_legal_assistant::delete_indexed_document(&collection, &document_id, state).await?;
+
+    topics.remove_document(&document_id).map_err(|e| e.to_string())?;
+    acl.remove_document(&document_id).map_err(|e| e.to_string())?;
+    calendar_sync::remove_deleted_document(&calendar, &security, &document_id).await;
+    let details = HashMap::from([
+        ("deleted_by".to_string(), user),
+        ("collection".to_string(), collection),
+        ("title".to_string(), deleted.title),
+    ]);
+    let _ = security.lock().unwrap().write_audit_entry(
+        security::SecurityAction::DocumentDelete,
+        &document_id,
+        security::ActionOutcome::Success,
+        Some(details),
+    );
+    Ok(())
+}
+
+/// Delete the indexed documents kept longer than the retention period, and notify the
+/// subscribed webhook endpoints of the purge
+#[cfg(feature = "desktop")]
+#[tauri::command]
+async fn purge_expired_documents(
+    session_id: String,
+    retention_days: u32,
+    collection: Option<String>,
+    acl: tauri::State<'_, document_acl::DocumentAclStorage>,
+    calendar: tauri::State<'_, calendar_sync::CalendarSyncStorage>,
+    topics: tauri::State<'_, corpus_topics::CorpusTopicsStorage>,
+    sessions: tauri::State<'_, local_api::SessionStorage>,
+    security: tauri::State<'_, Arc<Mutex<security::SecurityManager>>>,
+    state: tauri::State<'_, Arc<tokio::sync::RwLock<bear_ai_legal_assistant::AppState>>>,
+) -> Result<Vec<bear_ai_legal_assistant::ingest_dedup::DocumentFingerprint>, String> {
+    let user = local_api::authenticated_user(&session_id, &sessions)?;
+    {
+        let security = security.lock().unwrap();
+        if !security.check_permission(&user, security::Permission::DocumentDelete)
+            || !security.check_permission(&user, security::Permission::SystemConfiguration)
+        {
+            return Err("A retention purge needs permission to delete documents and to configure the system".to_string());
+        }
+    }
+    if retention_days == 0 {
+        return Err("The retention period must be at least one day".to_string());
+    }
+    let purged =
+        bear_ai_legal_assistant::purge_expired_documents(retention_days, collection.clone(), state).await?;
+
+    for document in &purged {
+        topics.remove_document(&document.document_id).map_err(|e| e.to_string())?;
+        acl.remove_document(&document.document_id).map_err(|e| e.to_string())?;
+        calendar_sync::remove_deleted_document(&calendar, &security, &document.document_id).await;
+        let details = HashMap::from([
+            ("purged_by".to_string(), user.clone()),
+            ("collection".to_string(), document.collection.clone()),
+            ("retention_days".to_string(), retention_days.to_string()),
+        ]);
+        let _ = security.lock().unwrap().write_audit_entry(
+            security::SecurityAction::DocumentDelete,
+            &document.document_id,
+            security::ActionOutcome::Success,
+            Some(details),
+        );
+    }
+    webhooks::publish_event(
+        webhooks::EVENT_RETENTION_PURGE,
+        serde_json::json!({
+            "retention_days": retention_days,
+            "collection": collection,
+            "documents_purged": purged.len(),
+            "document_ids": purged.iter().map(|d| d.document_id.as_str()).collect::<Vec<_>>(),
+        }),
+    );
+    Ok(purged)
+}
+
+#[cfg(feature = "desktop")]
+#[tauri::command]
+async fn migrate_vector_store(
+    qdrant_url: Option<String>,
+    collections: Option<Vec<String>>,
+    state: tauri::State<'_, Arc<tokio::sync::RwLock<bear_ai_legal_assistant::AppState>>>,
+) -> Result<Vec<bear_ai_legal_assistant::nemotron_rag::MigratedCollection>, String> {
+    bear_ai_legal_assistant::migrate_vector_store(qdrant_url, collections, state).await
+}
+
+#[cfg(feature = "desktop")]
+#[tauri::command]
+async fn get_judge_analytics(
+    session_id: String,
+    judge: Option<String>,
+    acl: tauri::State<'_, document_acl::DocumentAclStorage>,
+    sessions: tauri::State<'_, local_api::SessionStorage>,
+    barriers: tauri::State<'_, enterprise_management::BarrierStorage>,
+    security: tauri::State<'_, Arc<Mutex<security::SecurityManager>>>,
+) -> Result<bear_ai_legal_assistant::case_analytics::JudgeAnalyticsReport, String> {
+    let scope = RetrievalScope::for_session(&session_id, &sessions, &acl, &barriers, &security)?;
+    let screened = ScreenedDocuments::default();
+    let report = bear_ai_legal_assistant::case_analytics::judge_analytics(judge, |case| {
+        scope.admit_document(&case.document_id, None, &screened)
+    });
+    scope.audit(screened, "judge analytics");
+    report
+}
+
+/// Generate a DPIA report, quoting the GDPR from the RAG index in the library crate when it
+/// has been indexed
+#[cfg(feature = "desktop")]
+#[tauri::command]
+async fn dpia_generate(
+    session_id: String,
+    input: dpia::DpiaInput,
+    reports: tauri::State<'_, dpia::DpiaStorage>,
+    acl: tauri::State<'_, document_acl::DocumentAclStorage>,
+    sessions: tauri::State<'_, local_api::SessionStorage>,
+    barriers: tauri::State<'_, enterprise_management::BarrierStorage>,
+    security: tauri::State<'_, Arc<Mutex<security::SecurityManager>>>,
+    state: tauri::State<'_, Arc<tokio::sync::RwLock<bear_ai_legal_assistant::AppState>>>,
+) -> Result<dpia::DpiaReport, String> {
+    let scope = RetrievalScope::for_session(&session_id, &sessions, &acl, &barriers, &security)?;
+    let screened = ScreenedDocuments::default();
+    let mut excerpts = Vec::new();
+    for provision in dpia::relevant_provisions(&input) {
+        let visible = |chunk: &bear_ai_legal_assistant::nemotron_rag::RAGChunk| scope.admit_indexed(chunk, &screened);
+        match bear_ai_legal_assistant::retrieve_legislation(provision.query, 2, state.clone(), visible).await {
+            Ok(result) => excerpts.extend(result.chunks.into_iter().map(|chunk| (provision.article.clone(), chunk.content))),
+            Err(e) => log::warn!("No GDPR text for {}: {}", provision.article, e),
+        }
+    }
+    scope.audit(screened, "retrieval");
+    dpia::generate_report(&input, &excerpts, &reports.dir).map_err(|e| e.to_string())
+}
+
+/// Export a research memo over the passages retrieved here for its question in the session's
+/// scope; a retrieval sent by the client could quote documents the user may not see
+#[cfg(feature = "desktop")]
+#[tauri::command]
+async fn export_research_memo(
+    session_id: String,
+    mut memo: chat_export::ResearchMemoExport,
+    format: Option<String>,
+    exporter: tauri::State<'_, Arc<Mutex<chat_export::ChatExporter>>>,
+    disclosures: tauri::State<'_, ai_disclosure::DisclosureStorage>,
+    provenance: tauri::State<'_, provenance::ProvenanceStorage>,
+    llm: tauri::State<'_, Arc<LLMManager>>,
+    acl: tauri::State<'_, document_acl::DocumentAclStorage>,
+    sessions: tauri::State<'_, local_api::SessionStorage>,
+    barriers: tauri::State<'_, enterprise_management::BarrierStorage>,
+    security: tauri::State<'_, Arc<Mutex<security::SecurityManager>>>,
+    review: tauri::State<'_, review_queue::ReviewQueueStorage>,
+    state: tauri::State<'_, Arc<tokio::sync::RwLock<bear_ai_legal_assistant::AppState>>>,
+) -> Result<String, String> {
+    let scope = RetrievalScope::for_session(&session_id, &sessions, &acl, &barriers, &security)?;
+    let result = bear_ai_legal_assistant::retrieve_legal_info(memo.question.clone(), None, state).await?;
+    // The library crate compiles its own copy of the RAG types
+    memo.result = serde_json::to_value(scope.filter_indexed(result))
+        .and_then(serde_json::from_value)
+        .map_err(|e| e.to_string())?;
+    chat_export::export_research_memo(
+        session_id, exporter, disclosures, provenance, llm, security, sessions, barriers, review, memo, format,
+    )
+    .await
+}
+
+/// RAG passages for a query from the index in the library crate, as the drafting workflows take them
+#[cfg(feature = "desktop")]
+async fn retrieve_passages(
+    query: String,
+    state: tauri::State<'_, Arc<tokio::sync::RwLock<bear_ai_legal_assistant::AppState>>>,
+    scope: RetrievalScope,
+) -> anyhow::Result<Vec<research_memo::RetrievedPassage>> {
+    let result = bear_ai_legal_assistant::retrieve_legal_info(query, None, state)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    let result = scope.filter_indexed(result);
+    let verified: Vec<String> = result.citations.iter().filter(|c| c.verified).map(|c| c.text.clone()).collect();
+    Ok(result
+        .chunks
+        .iter()
+        .map(|chunk| research_memo::RetrievedPassage {
+            chunk_id: chunk.id.clone(),
+            document_id: chunk.document_id.clone(),
+            title: result.documents.iter().find(|d| d.id == chunk.document_id).map(|d| d.title.clone()),
+            content: chunk.content.clone(),
+            cited_authorities: chunk.cited_authorities.clone(),
+            verified_citations: verified
+                .iter()
+                .filter(|c| chunk.cited_authorities.contains(*c))
+                .cloned()
+                .collect(),
+        })
+        .collect())
+}
+
+/// Research a question against the RAG index in the library crate and write an IRAC memo
+#[cfg(feature = "desktop")]
+#[tauri::command]
+async fn run_research_memo(
+    session_id: String,
+    request: research_memo::ResearchMemoRequest,
+    memos: tauri::State<'_, research_memo::ResearchMemoStorage>,
+    llm: tauri::State<'_, Arc<LLMManager>>,
+    acl: tauri::State<'_, document_acl::DocumentAclStorage>,
+    sessions: tauri::State<'_, local_api::SessionStorage>,
+    barriers: tauri::State<'_, enterprise_management::BarrierStorage>,
+    security: tauri::State<'_, Arc<Mutex<security::SecurityManager>>>,
+    review: tauri::State<'_, review_queue::ReviewQueueStorage>,
+    disclosures: tauri::State<'_, ai_disclosure::DisclosureStorage>,
+    provenance: tauri::State<'_, provenance::ProvenanceStorage>,
+    state: tauri::State<'_, Arc<tokio::sync::RwLock<bear_ai_legal_assistant::AppState>>>,
+) -> Result<research_memo::ResearchMemo, String> {
+    let scope = RetrievalScope::for_session(&session_id, &sessions, &acl, &barriers, &security)?;
+    let (llm_manager, scope_ref, reason_state) = (llm.inner().as_ref(), &scope, state.clone());
+    let reason = |question: String, max_hops: usize, model: String| async move {
+        let generate = |prompt: String| {
+            llm_manager.complete(
+                &model,
+                "You are a legal research assistant. Reason strictly from the passages and findings you are given.",
+                prompt,
+                700,
+                0.2,
+            )
+        };
+        let screened = ScreenedDocuments::default();
+        let trace = bear_ai_legal_assistant::multi_hop_reasoning(
+            question,
+            Some(max_hops),
+            model.clone(),
+            reason_state,
+            |chunk| scope_ref.admit_indexed(chunk, &screened),
+            generate,
+        )
+        .await;
+        scope_ref.audit(screened, "retrieval");
+        let hops = trace.map_err(|e| anyhow::anyhow!(e))?.hops;
+        Ok(hops
+            .into_iter()
+            .map(|hop| {
+                let passages = hop
+                    .sources
+                    .into_iter()
+                    .map(|source| research_memo::RetrievedPassage {
+                        chunk_id: source.chunk_id,
+                        document_id: source.document_id,
+                        title: source.title,
+                        content: source.content,
+                        cited_authorities: source.cited_authorities,
+                        verified_citations: source.verified_citations,
+                    })
+                    .collect();
+                (hop.query, passages)
+            })
+            .collect())
+    };
+    let memo = research_memo::run_research_memo(&request, &llm, &memos.dir, &disclosures, reason)
+        .await
+        .map_err(|e| e.to_string())?;
+    provenance
+        .stamp(
+            std::path::Path::new(&memo.path),
+            memo.provenance_claim(),
+            request.provenance_sidecar,
+            &llm,
+            &security.lock().unwrap(),
+        )
+        .map_err(|e| format!("Failed to record the memo's provenance: {}", e))?;
+    let submission = review_queue::Submission::for_file(
+        review_queue::WorkProductKind::Memo,
+        format!("Research memo: {}", memo.question),
+        "research_memo",
+        &memo.id,
+        &memo.path,
+        None,
+    );
+    review_queue::submit_generated(&review, &scope.user, submission)?;
+    Ok(memo)
+}
+
+/// Check a draft brief's citations and look for adverse authority in the RAG index
+#[cfg(feature = "desktop")]
+#[tauri::command]
+async fn review_brief(
+    session_id: String,
+    request: brief_checker::BriefCheckRequest,
+    analyzer: tauri::State<'_, local_api::AnalyzerStorage>,
+    llm: tauri::State<'_, Arc<LLMManager>>,
+    acl: tauri::State<'_, document_acl::DocumentAclStorage>,
+    sessions: tauri::State<'_, local_api::SessionStorage>,
+    barriers: tauri::State<'_, enterprise_management::BarrierStorage>,
+    security: tauri::State<'_, Arc<Mutex<security::SecurityManager>>>,
+    state: tauri::State<'_, Arc<tokio::sync::RwLock<bear_ai_legal_assistant::AppState>>>,
+) -> Result<brief_checker::BriefReview, String> {
+    let text = match (&request.path, &request.text) {
+        (Some(path), _) => analyzer
+            .extract_text(std::path::Path::new(path))
+            .await
+            .map_err(|e| e.to_string())?,
+        (None, Some(text)) => text.clone(),
+        (None, None) => return Err("Provide the brief as a file or as text".to_string()),
+    };
+    let scope = RetrievalScope::for_session(&session_id, &sessions, &acl, &barriers, &security)?;
+    let retrieve = |query: String| retrieve_passages(query, state.clone(), scope.clone());
+    brief_checker::review_brief(&request, &text, &llm, retrieve)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Index a crawled or imported page into a collection of the RAG index in the library crate
+#[cfg(feature = "desktop")]
+async fn index_into_collection(
+    collection: String,
+    document: nemotron_rag::LegalDocument,
+    metadata: HashMap<String, String>,
+    state: tauri::State<'_, Arc<tokio::sync::RwLock<bear_ai_legal_assistant::AppState>>>,
+) -> anyhow::Result<usize> {
+    // The library crate compiles its own copy of the RAG types
+    let document = serde_json::from_value(serde_json::to_value(document)?)?;
+    bear_ai_legal_assistant::index_document_into(&collection, document, metadata, &state)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))
+}
+
+/// Crawl an intranet site into its collection in the RAG index of the library crate
+#[cfg(feature = "desktop")]
+async fn crawl_site(
+    site_id: &str,
+    crawler: &intranet_crawler::IntranetCrawler,
+    security: &Arc<Mutex<security::SecurityManager>>,
+    state: tauri::State<'_, Arc<tokio::sync::RwLock<bear_ai_legal_assistant::AppState>>>,
+) -> anyhow::Result<intranet_crawler::CrawlReport> {
+    if state.read().await.rag_system.is_none() {
+        return Err(anyhow::anyhow!("RAG system not initialized"));
+    }
+    let site = crawler
+        .site(site_id)
+        .ok_or_else(|| anyhow::anyhow!("Crawl site not found: {}", site_id))?;
+    let headers = crawler.headers(site_id, &security.lock().unwrap())?;
+    let index = |document, metadata| index_into_collection(site.collection.clone(), document, metadata, state.clone());
+    crawler.crawl(site_id, &headers, index).await
+}
+
+/// Import changed Confluence or Notion pages into the source's collection
+#[cfg(feature = "desktop")]
+async fn sync_knowledge_source(
+    source_id: &str,
+    full: bool,
+    connectors: &knowledge_connectors::KnowledgeConnectors,
+    security: &Arc<Mutex<security::SecurityManager>>,
+    state: tauri::State<'_, Arc<tokio::sync::RwLock<bear_ai_legal_assistant::AppState>>>,
+) -> anyhow::Result<knowledge_connectors::ConnectorSyncReport> {
+    if state.read().await.rag_system.is_none() {
+        return Err(anyhow::anyhow!("RAG system not initialized"));
+    }
+    let source = connectors
+        .source(source_id)
+        .ok_or_else(|| anyhow::anyhow!("Knowledge source not found: {}", source_id))?;
+    let token = connectors.token(source_id, &security.lock().unwrap())?;
+    let index = |document, metadata| index_into_collection(source.collection.clone(), document, metadata, state.clone());
+    connectors.sync(source_id, &token, full, index).await
+}
+
+#[cfg(feature = "desktop")]
+#[tauri::command]
+async fn crawler_run_site(
+    site_id: String,
+    crawler: tauri::State<'_, intranet_crawler::CrawlerStorage>,
+    security: tauri::State<'_, Arc<Mutex<security::SecurityManager>>>,
+    state: tauri::State<'_, Arc<tokio::sync::RwLock<bear_ai_legal_assistant::AppState>>>,
+) -> Result<intranet_crawler::CrawlReport, String> {
+    crawl_site(&site_id, &crawler, &security, state)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(feature = "desktop")]
+#[tauri::command]
+async fn connector_sync_source(
+    source_id: String,
+    full_resync: Option<bool>,
+    connectors: tauri::State<'_, knowledge_connectors::KnowledgeConnectorStorage>,
+    security: tauri::State<'_, Arc<Mutex<security::SecurityManager>>>,
+    state: tauri::State<'_, Arc<tokio::sync::RwLock<bear_ai_legal_assistant::AppState>>>,
+) -> Result<knowledge_connectors::ConnectorSyncReport, String> {
+    sync_knowledge_source(&source_id, full_resync.unwrap_or(false), &connectors, &security, state)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// File Slack/Teams channel exports under their matters and index them with the legal corpus
+#[cfg(feature = "desktop")]
+#[tauri::command]
+async fn ingest_channel_archive(
+    request: channel_ingestion::ChannelIngestRequest,
+    archives: tauri::State<'_, channel_ingestion::ChannelArchiveStorage>,
+    matters: tauri::State<'_, matters::MatterStorage>,
+    state: tauri::State<'_, Arc<tokio::sync::RwLock<bear_ai_legal_assistant::AppState>>>,
+) -> Result<channel_ingestion::ChannelIngestReport, String> {
+    let index = |document, metadata| index_into_collection("legal_chunks".to_string(), document, metadata, state.clone());
+    archives
+        .ingest(&request, &matters, index)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Index every supported document under a folder into the RAG index of the library crate,
+/// emitting progress per file
+#[cfg(feature = "desktop")]
+#[tauri::command]
+async fn bulk_ingest_directory(
+    request: nemotron_rag::BulkIngestRequest,
+    app: tauri::AppHandle,
+    analyzer: tauri::State<'_, local_api::AnalyzerStorage>,
+    calendar: tauri::State<'_, calendar_sync::CalendarSyncStorage>,
+    security: tauri::State<'_, Arc<Mutex<security::SecurityManager>>>,
+    state: tauri::State<'_, Arc<tokio::sync::RwLock<bear_ai_legal_assistant::AppState>>>,
+) -> Result<nemotron_rag::BulkIngestSummary, String> {
+    if state.read().await.rag_system.is_none() {
+        return Err("RAG system not initialized".to_string());
+    }
+    let files = nemotron_rag::collect_ingestible_files(std::path::Path::new(&request.directory), request.recursive.unwrap_or(true))
+        .map_err(|e| e.to_string())?;
+    let collection = request.collection.clone().unwrap_or_else(|| "legal_chunks".to_string());
+    // Image-only pages of scanned PDFs are read by OCR when Tesseract is installed
+    let mut ocr_config = ocr_processor::OcrConfiguration::default();
+    if let Some(languages) = &request.ocr_languages {
+        let languages: Vec<String> = languages.iter().map(|l| l.trim().to_string()).filter(|l| !l.is_empty()).collect();
+        if !languages.is_empty() {
+            ocr_config.languages = languages;
+        }
+    }
+    let ocr = Arc::new(ocr_processor::OcrProcessor::new(ocr_config));
+    let extract = |path: std::path::PathBuf| {
+        let analyzer = analyzer.inner().clone();
+        let ocr = ocr.clone();
+        async move { ocr_ingest::extract_ingest_text(&analyzer, Some(&ocr), &path).await }
+    };
+    let index = |document, metadata| index_into_collection(collection.clone(), document, metadata, state.clone());
+    let mut indexed = Vec::new();
+    let progress = |update: &nemotron_rag::IngestFileProgress| {
+        if update.status == nemotron_rag::IngestFileStatus::Indexed {
+            indexed.push(std::path::PathBuf::from(&update.path));
+        }
+        let _ = app.emit_all(nemotron_rag::INGEST_PROGRESS_EVENT, update);
+    };
+    let summary = nemotron_rag::bulk_ingest(&request, files, extract, index, progress).await;
+
+    // Deadlines in the indexed files go to the configured calendar
+    for path in indexed {
+        let document_id = nemotron_rag::file_document_id(&path);
+        calendar_sync::sync_ingested_document(&calendar, &analyzer, &security, &document_id, &path).await;
+    }
+    Ok(summary)
+}
+
+/// Sign a report of every endpoint the current configuration can contact, RAG services included
+#[cfg(feature = "desktop")]
+#[tauri::command]
+async fn generate_network_attestation(
+    app: tauri::AppHandle,
+    attestations: tauri::State<'_, network_attestation::NetworkAttestationStorage>,
+    security: tauri::State<'_, Arc<Mutex<security::SecurityManager>>>,
+    state: tauri::State<'_, Arc<tokio::sync::RwLock<bear_ai_legal_assistant::AppState>>>,
+) -> Result<network_attestation::AttestationExport, String> {
+    use network_attestation::ContactTrigger::UserAction;
+    let mut inventory = network_attestation::collect_inventory(&app);
+    if let Some(rag) = &state.read().await.rag_system {
+        let config = rag.config();
+        inventory.endpoint("RAG", &config.nemo_retriever_url, UserAction, "NeMo Retriever embeddings");
+        inventory.endpoint("RAG", &config.vector_db_url, UserAction, "Vector database");
+        if let Some(redis_url) = &config.redis_url {
+            inventory.endpoint("RAG", redis_url, UserAction, "Query cache");
+        }
+    }
+    network_attestation::attest_inventory(inventory, &attestations, &security)
+}
+
+/// Search one crawled site; each chunk's metadata carries the page it came from
+#[cfg(feature = "desktop")]
+#[tauri::command]
+async fn crawler_search(
+    session_id: String,
+    site_id: String,
+    query: String,
+    max_results: Option<usize>,
+    crawler: tauri::State<'_, intranet_crawler::CrawlerStorage>,
+    acl: tauri::State<'_, document_acl::DocumentAclStorage>,
+    sessions: tauri::State<'_, local_api::SessionStorage>,
+    barriers: tauri::State<'_, enterprise_management::BarrierStorage>,
+    security: tauri::State<'_, Arc<Mutex<security::SecurityManager>>>,
+    state: tauri::State<'_, Arc<tokio::sync::RwLock<bear_ai_legal_assistant::AppState>>>,
+) -> Result<Vec<bear_ai_legal_assistant::nemotron_rag::RAGChunk>, String> {
+    let site = crawler
+        .site(&site_id)
+        .ok_or_else(|| format!("Crawl site not found: {}", site_id))?;
+    let mut chunks = bear_ai_legal_assistant::search_collection(site.collection, query, max_results.unwrap_or(10), state).await?;
+    let scope = RetrievalScope::for_session(&session_id, &sessions, &acl, &barriers, &security)?;
+    let screened = ScreenedDocuments::default();
+    chunks.retain(|chunk| scope.admit_indexed(chunk, &screened));
+    scope.audit(screened, "search");
+    bear_ai_legal_assistant::nemotron_rag::collapse_duplicates(&mut chunks);
+    Ok(chunks)
+}
+
 #[cfg(feature = "desktop")]
 #[tauri::command]
 fn create_default_nemotron_config() -> bear_ai_legal_assistant::nemotron_rag::NemotronConfig {
@@ -228,6 +1169,7 @@ fn handle_tray_event(app: &tauri::AppHandle, event: SystemTrayEvent) {
         }
         SystemTrayEvent::MenuItemClick { id, .. } => match id.as_str() {
             "quit" => {
+                app.state::<Arc<LLMManager>>().shutdown_all_models();
                 std::process::exit(0);
             }
             "show" => {
@@ -260,10 +1202,41 @@ fn main() {
             // NVIDIA Nemotron RAG commands
             initialize_rag_system,
             process_legal_document,
+            bulk_ingest_directory,
             retrieve_legal_info,
             generate_agentic_response,
             multi_hop_reasoning,
             get_rag_health,
+            get_citing_documents,
+            get_cited_by,
+            shortest_citation_path,
+            get_vector_store_stats,
+            rebuild_vector_index,
+            compact_vector_store,
+            purge_duplicate_documents,
+            delete_indexed_document,
+            purge_expired_documents,
+            migrate_vector_store,
+            get_judge_analytics,
+            dpa_checker::analyze_dpa_file,
+            financial_statements::analyze_financial_statements,
+            dpia_generate,
+            dpia::dpia_next_question,
+            dpia::dpia_prefill_from_document,
+            run_research_memo,
+            review_brief,
+            crawler_run_site,
+            crawler_search,
+            connector_sync_source,
+            ingest_channel_archive,
+            channel_ingestion::channel_archive_preview,
+            table_of_authorities::generate_table_of_authorities,
+            exhibit_list::check_exhibits,
+            discovery::discovery_draft,
+            discovery::discovery_export,
+            discovery::get_discovery_rules,
+            discovery::set_discovery_rules,
+            deposition_prep::deposition_prepare,
             create_default_nemotron_config,
             // Local API Authentication commands
             local_auth_login,
@@ -276,6 +1249,8 @@ fn main() {
             local_chat_send_message,
             local_chat_get_messages,
             local_chat_delete_session,
+            local_chat_get_message_provenance,
+            regenerate_session_summary,
             // Local API Document commands
             local_documents_list,
             local_document_upload,
@@ -289,6 +1264,7 @@ fn main() {
             // Local API System commands
             local_system_health,
             local_system_stats,
+            local_workspace_stats,
             // LLM Management commands
             llm_initialize,
             llm_list_models,
@@ -316,6 +1292,7 @@ fn main() {
             chat_export::export_chat_session,
             chat_export::get_export_formats,
             chat_export::create_export_options,
+            export_research_memo,
             // Security management commands
             security::encrypt_document,
             security::decrypt_document,
@@ -405,18 +1382,221 @@ fn main() {
             process_document_ocr,
             extract_legal_entities_from_ocr,
             get_ocr_capabilities,
+            ocr_extract_handwriting,
+            ocr_detect_signatures,
+            contract_execution::contract_execution_register,
+            contract_execution::contract_execution_set_status,
+            contract_execution::contract_execution_scan,
+            contract_execution::contract_execution_list,
+            contract_execution::contract_execution_unexecuted,
+            webhooks::webhook_register_endpoint,
+            webhooks::webhook_remove_endpoint,
+            webhooks::webhook_set_endpoint_enabled,
+            webhooks::webhook_list_endpoints,
+            webhooks::webhook_delivery_log,
+            webhooks::webhook_send_test,
+            workflow_packages::export_workflow_package,
+            workflow_packages::import_workflow_package,
+            workflow_packages::list_workflow_packages,
+            workflow_packages::get_workflow_package,
+            automation_api::automation_api_start,
+            automation_api::automation_api_stop,
+            automation_api::automation_api_status,
+            automation_api::automation_create_key,
+            automation_api::automation_list_keys,
+            automation_api::automation_revoke_key,
+            calendar_sync::calendar_extract_deadlines,
+            calendar_sync::calendar_export_ics,
+            calendar_sync::calendar_configure_caldav,
+            calendar_sync::calendar_sync_document,
+            calendar_sync::calendar_remove_document,
+            calendar_sync::calendar_list_synced_events,
+            calendar_sync::calendar_resolve_conflict,
+            intranet_crawler::crawler_save_site,
+            intranet_crawler::crawler_list_sites,
+            intranet_crawler::crawler_remove_site,
+            intranet_crawler::crawler_list_pages,
+            intranet_crawler::crawler_last_report,
+            knowledge_connectors::connector_save_source,
+            knowledge_connectors::connector_list_sources,
+            knowledge_connectors::connector_remove_source,
+            knowledge_connectors::connector_list_pages,
+            knowledge_connectors::connector_last_report,
+            object_storage::storage_save_target,
+            object_storage::storage_list_targets,
+            object_storage::storage_remove_target,
+            object_storage::storage_test_connection,
+            object_storage::storage_list_objects,
+            object_storage::storage_upload,
+            object_storage::storage_backup,
+            object_storage::storage_prune,
+            object_storage::storage_download,
+            document_repository::repository_get_settings,
+            document_repository::repository_set_settings,
+            document_repository::repository_status,
+            document_repository::repository_scan,
+            document_repository::repository_list_files,
+            document_repository::repository_document_text,
+            document_repository::repository_analyze_document,
+            generate_network_attestation,
+            network_attestation::verify_network_attestation,
+            license_attribution::get_license_attribution,
+            license_attribution::export_license_attribution,
+            document_acl::acl_get_settings,
+            document_acl::acl_set_settings,
+            document_acl::acl_get,
+            document_acl::acl_set,
+            document_acl::acl_remove,
+            document_acl::acl_my_access,
+            enterprise_management::barrier_list,
+            enterprise_management::barrier_save_group,
+            enterprise_management::barrier_delete_group,
+            enterprise_management::barrier_create,
+            enterprise_management::barrier_remove,
+            enterprise_management::barrier_check,
+            review_queue::review_list,
+            review_queue::review_decide,
+            review_queue::review_history,
+            review_queue::review_verify_log,
+            review_queue::review_get_settings,
+            review_queue::review_set_settings,
+            document_analyzer::analyze_document_file,
+            incremental_analysis::analyze_document_incremental,
+            analysis_comparison::compare_analyses,
+            reanalysis::list_stale_analyses,
+            reanalysis::start_reanalysis,
+            reanalysis::pause_reanalysis,
+            reanalysis::resume_reanalysis,
+            reanalysis::get_reanalysis_job,
+            reanalysis::get_reanalysis_summary,
+            document_classifier::classify_document_type,
+            document_classifier::correct_document_type,
+            document_classifier::list_document_type_corrections,
+            document_classifier::remove_document_type_correction,
+            court_filing::extract_filing_metadata_from_file,
+            ai_disclosure::disclosure_get_settings,
+            ai_disclosure::disclosure_set_settings,
+            ai_disclosure::disclosure_get_policy,
+            ai_disclosure::disclosure_set_policy,
+            ai_disclosure::disclosure_preview,
+            provenance::verify_provenance,
+            provenance::get_provenance_fingerprint,
+            document_summary::summarize_document,
+            document_qa::document_qa_open,
+            document_qa::document_qa_ask,
+            document_qa::document_qa_close,
+            document_qa::document_qa_list,
+            entity_relations::extract_entity_relations,
+            entity_relations::query_entity_relations,
+            matter_graph::export_matter_graph,
+            matter_graph::query_matter_graph,
+            clause_search::search_within_document,
+            timekeeping::timekeeping_focus,
+            timekeeping::timekeeping_blur,
+            timekeeping::timekeeping_add_entry,
+            timekeeping::timekeeping_update_entry,
+            timekeeping::timekeeping_delete_entry,
+            timekeeping::timekeeping_list_entries,
+            timekeeping::timekeeping_get_profile,
+            timekeeping::timekeeping_set_profile,
+            timekeeping::timekeeping_export,
+            matters::matter_create_intake,
+            matters::matter_list,
+            matters::matter_run_conflict_check,
+            matters::matter_record_waiver,
+            matters::matter_generate_engagement_letter,
+            matters::matter_intake_status,
+            matters::matter_activate,
+            matters::matter_close,
+            matters::matter_attach_documents,
+            matters::matter_detach_document,
+            matter_consistency::matter_check_consistency,
+            party_registry::matter_resolve_parties,
+            party_registry::matter_list_parties,
+            party_registry::matter_party_documents,
+            party_registry::matter_add_party_alias,
+            party_registry::matter_rename_party,
+            party_registry::matter_merge_parties,
+            matter_intake::matter_propose_intake,
+            matter_intake::matter_confirm_intake,
+            client_bundle::export_client_bundle,
+            charts::get_chart_theme,
+            charts::set_chart_theme,
+            charts::render_chart,
+            analytics_export::export_anonymized_analytics,
+            output_language::get_output_language,
+            output_language::set_output_language,
+            glossary::glossary_list_entries,
+            glossary::glossary_save_entry,
+            glossary::glossary_remove_entry,
+            glossary::glossary_review_text,
+            corpus_topics::corpus_build_clusters,
+            corpus_topics::corpus_list_clusters,
+            corpus_topics::corpus_get_cluster,
+            corpus_topics::corpus_document_cluster,
+            corpus_topics::find_similar_documents,
+            chunking_profiles::get_chunking_profiles,
+            chunking_profiles::set_chunking_profile,
+            chunking_profiles::clear_chunking_profile,
+            chunking_profiles::preview_chunking,
+            ingest_dedup::list_duplicate_documents,
+            ingest_dedup::get_dedup_threshold,
+            ingest_dedup::set_dedup_threshold,
+            corporate_structure::corporate_structure_extract,
+            corporate_structure::corporate_structure_export,
+            regulatory_monitor::regulatory_check_update,
+            regulatory_monitor::regulatory_list_regulations,
+            regulatory_monitor::regulatory_list_tasks,
+            regulatory_monitor::regulatory_update_task,
+            regulatory_monitor::regulatory_watch_regulation,
+            regulatory_monitor::regulatory_unwatch_regulation,
+            regulatory_monitor::regulatory_list_watched,
+            regulatory_monitor::regulatory_fetch_now,
+            sanctions_screening::sanctions_update_lists,
+            sanctions_screening::sanctions_list_status,
+            sanctions_screening::sanctions_screen_parties,
+            sanctions_screening::sanctions_screen_matter,
+            sanctions_screening::sanctions_screen_document,
+            sanctions_screening::sanctions_list_runs,
             model_commands::unload_model,
             model_commands::benchmark_inference,
+            model_commands::get_benchmark_history,
+            model_commands::reset_benchmark_baseline,
+            model_commands::set_regression_thresholds,
             model_commands::get_process_memory_usage,
             model_commands::get_vram_usage,
             model_commands::get_power_consumption,
             model_commands::get_cpu_temperature,
             model_commands::detect_model_quantization,
+            model_commands::inspect_gguf_metadata,
+            request_tracing::get_request_trace,
+            request_tracing::list_request_traces,
+            speech_to_text::transcribe_audio_file,
+            speech_to_text::start_dictation,
+            speech_to_text::push_dictation_audio,
+            speech_to_text::stop_dictation,
+            speech_to_text::send_transcript_to_chat,
+            speech_to_text::ingest_transcript_as_document,
+            audio_evidence::ingest_audio_evidence,
             // Local LLM Manager commands (with GPU detection)
             list_models,
             download_model,
             load_model,
             unload_model,
+            warmup_model,
+            preflight_model_load,
+            get_model_provenance,
+            refresh_model_manifest,
+            set_fallback_models,
+            get_fallback_models,
+            list_loaded_models,
+            get_model_endpoint,
+            set_draft_model,
+            remove_draft_model,
+            list_draft_models,
+            generate_with_prefix_cache,
+            list_prompt_cache,
+            clear_prompt_cache,
             remove_model,
             get_recommended_models,
             llm_get_system_info,
@@ -424,6 +1604,7 @@ fn main() {
             generate_response,
             chat_with_model,
             get_embeddings,
+            get_embeddings_batch,
             show_model_info,
             pull_model,
             create_model,
@@ -433,6 +1614,8 @@ fn main() {
         .manage(ChatStorage::new(Mutex::new(HashMap::new())))
         .manage(DocumentStorage::new(Mutex::new(HashMap::new())))
         .manage(MessageStorage::new(Mutex::new(HashMap::new())))
+        .manage(RetrievalLog::new(Mutex::new(HashMap::new())))
+        .manage(workspace_stats::create_workspace_stats_service())
         .manage(create_local_llm_manager().unwrap_or_else(|e| {
             log::error!("Failed to create LLM manager: {}", e);
             Arc::new(LLMManager::new(&std::path::PathBuf::from(".")).unwrap())
@@ -481,6 +1664,249 @@ fn main() {
                 }
             }
 
+            // Initialize dictation, which runs Whisper models from the model registry
+            let stt_manager = app.state::<Arc<LLMManager>>().inner().clone();
+            let speech_to_text = speech_to_text::SpeechToTextService::new(stt_manager, &app_data_dir).unwrap();
+            app.manage(Arc::new(speech_to_text));
+
+            // Initialize contract execution tracking
+            let contract_execution = contract_execution::ContractExecutionTracker::new(&app_data_dir).unwrap();
+            app.manage(Arc::new(contract_execution));
+
+            // Initialize the terminology glossary used on generated text
+            let glossary = glossary::initialize_glossary(&app_data_dir).unwrap();
+            app.manage(glossary);
+
+            // Initialize the case corpus behind judge analytics; the RAG index runs from the library crate
+            bear_ai_legal_assistant::case_analytics::initialize_case_corpus(&app_data_dir).unwrap();
+
+            // Initialize corpus topic clustering; indexing appends document vectors from the library crate
+            bear_ai_legal_assistant::corpus_topics::initialize_document_vectors(&app_data_dir);
+            app.manage(Arc::new(corpus_topics::CorpusTopics::new(&app_data_dir)));
+
+            // Initialize per-document-type chunking profiles; indexing pins them to collections from the library crate
+            bear_ai_legal_assistant::chunking_profiles::initialize_chunking_profiles(&app_data_dir);
+            app.manage(Arc::new(chunking_profiles::ChunkingProfileStore::new(&app_data_dir)));
+
+            // Initialize ingest deduplication; indexing records document signatures from the library crate
+            bear_ai_legal_assistant::ingest_dedup::initialize_dedup_index(&app_data_dir);
+            app.manage(Arc::new(ingest_dedup::DedupIndex::new(&app_data_dir)));
+
+            // DPIA reports are written as DOCX under the app data directory
+            app.manage(Arc::new(dpia::DpiaReports::new(&app_data_dir)));
+
+            // Research memos are written as DOCX alongside them
+            app.manage(Arc::new(research_memo::ResearchMemos::new(&app_data_dir)));
+            app.manage(Arc::new(table_of_authorities::AuthorityTables::new(&app_data_dir)));
+            app.manage(Arc::new(exhibit_list::ExhibitLists::new(&app_data_dir)));
+
+            // Discovery sets are drafted against configurable jurisdiction count limits
+            app.manage(Arc::new(discovery::DiscoveryDrafting::new(&app_data_dir)));
+
+            // Deposition outlines cite exhibits and prior testimony by page:line
+            app.manage(Arc::new(deposition_prep::DepositionOutlines::new(&app_data_dir)));
+
+            // Slack/Teams channel transcripts are filed per matter under the app data directory
+            app.manage(Arc::new(channel_ingestion::ChannelArchives::new(&app_data_dir)));
+
+            // Firm branding for charts in reports and client bundles
+            app.manage(Arc::new(charts::ChartThemePreference::new(&app_data_dir)));
+
+            // Initialize regulatory change monitoring; indexing records citing passages from the library crate
+            bear_ai_legal_assistant::regulatory_monitor::initialize_reference_log(&app_data_dir);
+            let regulatory_monitor = Arc::new(regulatory_monitor::RegulatoryMonitor::new(&app_data_dir).unwrap());
+            app.manage(regulatory_monitor.clone());
+            // Watched regulations are fetched from their official source when due
+            let statute_handle = app.handle();
+            tauri::async_runtime::spawn(async move {
+                let mut ticker = tokio::time::interval(std::time::Duration::from_secs(60 * 60));
+                loop {
+                    ticker.tick().await;
+                    for regulation_id in regulatory_monitor.due_regulations(chrono::Utc::now()) {
+                        match regulatory_monitor.fetch_and_check(&regulation_id).await {
+                            Ok(check) => regulatory_monitor::announce_check(&statute_handle, &check),
+                            Err(e) => log::warn!("Scheduled fetch of {} skipped: {}", regulation_id, e),
+                        }
+                    }
+                }
+            });
+
+            // Initialize outbound webhooks
+            let security_storage = app.state::<Arc<Mutex<security::SecurityManager>>>().inner().clone();
+            let webhook_publisher = webhooks::initialize_webhook_publisher(&app_data_dir, security_storage).unwrap();
+            app.manage(webhook_publisher);
+
+            // Agent workflows, with the runs waiting on a reviewer kept across restarts
+            let llm_manager = app.state::<Arc<LLMManager>>().inner().clone();
+            let mcp_server = mcp_server::MCPServer::new(llm_manager, mcp_server::DEFAULT_PORT)
+                .with_run_storage(&app_data_dir)
+                .unwrap();
+
+            // Signed workflow packages shared between firms; installed ones add their workflows
+            let workflow_packages = workflow_packages::WorkflowPackages::new(&app_data_dir).unwrap();
+            workflow_packages.install_all(&mcp_server);
+            app.manage(Arc::new(workflow_packages));
+            app.manage(Arc::new(mcp_server));
+
+            // Initialize calendar sync
+            let calendar_sync = calendar_sync::CalendarSync::new(&app_data_dir).unwrap();
+            app.manage(Arc::new(calendar_sync));
+
+            // Documents store on local disk or a network share; shares are rescanned for changes by other users
+            let document_repository = Arc::new(document_repository::DocumentRepository::new(&app_data_dir).unwrap());
+            app.manage(document_repository.clone());
+            let repository_handle = app.handle();
+            tauri::async_runtime::spawn(async move {
+                let mut ticker = tokio::time::interval(std::time::Duration::from_secs(60));
+                loop {
+                    ticker.tick().await;
+                    if !document_repository.scan_due(chrono::Utc::now()) {
+                        continue;
+                    }
+                    match document_repository.scan().await {
+                        Ok(scan) if !scan.changes.is_empty() => {
+                            let _ = repository_handle.emit_all(document_repository::REPOSITORY_CHANGES_EVENT, &scan);
+                        }
+                        Ok(_) => {}
+                        Err(e) => log::warn!("Repository scan skipped: {}", e),
+                    }
+                }
+            });
+
+            // Signed reports of the endpoints the current configuration can contact
+            let network_attestations = network_attestation::NetworkAttestations::new(&app_data_dir).unwrap();
+            app.manage(Arc::new(network_attestations));
+
+            // Per-document and per-matter access lists, enforced in a shared workspace
+            let document_acl = document_acl::DocumentAcl::new(&app_data_dir).unwrap();
+            app.manage(Arc::new(document_acl));
+
+            // Information barriers between groups of users and the matters tagged to them
+            let barriers = enterprise_management::InformationBarriers::new(&app_data_dir).unwrap();
+            app.manage(Arc::new(barriers));
+
+            // AI-generated drafts, memos and analyses wait here for attorney review
+            let review_queue = Arc::new(review_queue::ReviewQueue::new(&app_data_dir).unwrap());
+            app.manage(review_queue.clone());
+
+            // Initialize automation API (started on demand); its results go out once reviewed
+            let analyzer = app.state::<local_api::AnalyzerStorage>().inner().clone();
+            let automation = automation_api::AutomationApi::new(&app_data_dir, analyzer, review_queue).unwrap();
+            app.manage(Arc::new(automation));
+
+            // Analyses made with an older rule pack or model are re-run in the background; a job
+            // interrupted by a restart carries on
+            let reanalysis = Arc::new(reanalysis::Reanalysis::new(&app_data_dir).unwrap());
+            let resume_reanalysis = reanalysis.should_resume();
+            app.manage(reanalysis);
+            if resume_reanalysis {
+                tauri::async_runtime::spawn(reanalysis::run_job(app.handle()));
+            }
+
+            // AI-use disclosure blocks for generated memos, chat exports and reports
+            let disclosures = ai_disclosure::Disclosures::new(&app_data_dir).unwrap();
+            app.manage(Arc::new(disclosures));
+
+            // Signed provenance manifests for exported AI-generated documents
+            app.manage(Arc::new(provenance::Provenance::new(&app_data_dir)));
+
+            // Single-document Q&A indexes, held in memory only
+            app.manage(Arc::new(document_qa::DocumentQa::new()));
+            app.manage(Arc::new(clause_search::ClauseSearch::new()));
+
+            // Relations between parties read from documents, queried across the library
+            let relation_store = entity_relations::RelationStore::new(&app_data_dir).unwrap();
+            app.manage(Arc::new(relation_store));
+
+            // Third-party license attribution from the SBOM embedded at build time
+            let license_attribution = license_attribution::LicenseAttribution::new(&app_data_dir).unwrap();
+            app.manage(Arc::new(license_attribution));
+
+            // Off-machine storage for backups, exports and productions; objects are encrypted before upload
+            let object_storage = object_storage::ObjectStorage::new(&app_data_dir).unwrap();
+            app.manage(Arc::new(object_storage));
+
+            // Initialize the intranet crawler and Confluence/Notion connectors; sources with a
+            // refresh interval are re-synced in the background
+            let intranet_crawler = intranet_crawler::IntranetCrawler::new(&app_data_dir).unwrap();
+            app.manage(Arc::new(intranet_crawler));
+            let knowledge_connectors = knowledge_connectors::KnowledgeConnectors::new(&app_data_dir).unwrap();
+            app.manage(Arc::new(knowledge_connectors));
+            let crawl_handle = app.handle();
+            tauri::async_runtime::spawn(async move {
+                let mut ticker = tokio::time::interval(std::time::Duration::from_secs(15 * 60));
+                loop {
+                    ticker.tick().await;
+                    let crawler = crawl_handle.state::<intranet_crawler::CrawlerStorage>().inner().clone();
+                    let security = crawl_handle.state::<Arc<Mutex<security::SecurityManager>>>().inner().clone();
+                    for site_id in crawler.due_sites(chrono::Utc::now()) {
+                        match crawl_site(&site_id, &crawler, &security, crawl_handle.state()).await {
+                            Ok(report) => log::info!(
+                                "Refreshed intranet site {}: {} indexed, {} unchanged, {} errors",
+                                site_id,
+                                report.indexed,
+                                report.unchanged,
+                                report.errors.len()
+                            ),
+                            Err(e) => log::warn!("Scheduled crawl of {} skipped: {}", site_id, e),
+                        }
+                    }
+                    let connectors = crawl_handle.state::<knowledge_connectors::KnowledgeConnectorStorage>().inner().clone();
+                    for source_id in connectors.due_sources(chrono::Utc::now()) {
+                        match sync_knowledge_source(&source_id, false, &connectors, &security, crawl_handle.state()).await {
+                            Ok(report) => log::info!(
+                                "Synced knowledge source {}: {} imported, {} unchanged, {} errors",
+                                source_id,
+                                report.imported,
+                                report.unchanged,
+                                report.errors.len()
+                            ),
+                            Err(e) => log::warn!("Scheduled sync of {} skipped: {}", source_id, e),
+                        }
+                    }
+                }
+            });
+
+            // Initialize timekeeping
+            let timekeeper = timekeeping::Timekeeper::new(&app_data_dir).unwrap();
+            app.manage(Arc::new(timekeeper));
+
+            // Initialize matters and client intake
+            let matter_registry = matters::MatterRegistry::new(&app_data_dir).unwrap();
+            app.manage(Arc::new(matter_registry));
+
+            // Canonical parties resolved from each matter's documents
+            let party_registry = party_registry::PartyRegistry::new(&app_data_dir).unwrap();
+            app.manage(Arc::new(party_registry));
+
+            // Initialize sanctions screening against locally stored lists
+            let sanctions_screener = sanctions_screening::SanctionsScreener::new(&app_data_dir).unwrap();
+            app.manage(Arc::new(sanctions_screener));
+
+            // Clean up servers orphaned by a previous crash, then supervise new ones
+            let supervised_manager = app.state::<Arc<LLMManager>>().inner().clone();
+            supervised_manager.cleanup_orphaned_processes();
+            supervised_manager.start_supervisor(app.handle());
+
+            // Refresh the signed curated model manifest in the background
+            let manifest_manager = app.state::<Arc<LLMManager>>().inner().clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = manifest_manager.refresh_curated_manifest().await {
+                    log::warn!("Using cached or embedded model list: {}", e);
+                }
+            });
+
+            // Optionally pre-load the most used model in the background
+            let preload = std::env::var("PRELOAD_MODEL").unwrap_or_else(|_| "false".to_string());
+            if preload == "true" || preload == "1" {
+                let manager = app.state::<Arc<LLMManager>>().inner().clone();
+                let handle = app.handle();
+                tauri::async_runtime::spawn(async move {
+                    let status = llm_manager::run_model_warmup(&handle, manager, None).await;
+                    log::info!("Startup model preload: {} ({:?})", status.state, status.message);
+                });
+            }
+
             // Initialize additional managers later
             // let license_manager = licensing::LicenseManager::new(&app_data_dir).unwrap();
             // app.manage(Arc::new(Mutex::new(license_manager)));
@@ -516,13 +1942,23 @@ fn main() {
             log::info!("BEAR AI Legal Assistant started successfully");
             Ok(())
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app_handle, event| {
+            if let tauri::RunEvent::Exit = event {
+                app_handle.state::<Arc<LLMManager>>().shutdown_all_models();
+            }
+        });
 }
 
+// Headless builds run the batch command line instead of the desktop app
 #[cfg(not(feature = "desktop"))]
 fn main() {
-    println!(
-        "BEAR AI headless build: desktop features are disabled. Enable the `desktop` feature to build the full Tauri application."
-    );
+    env_logger::Builder::from_default_env()
+        .filter_level(log::LevelFilter::Warn)
+        .init();
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let runtime = tokio::runtime::Runtime::new().expect("Failed to start async runtime");
+    std::process::exit(runtime.block_on(bear_ai_legal_assistant::cli::run(args)));
 }
@@ -0,0 +1,376 @@
+use chrono::Utc;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use crate::locale_formats::{self, detect_date_order};
+use crate::local_api::AnalyzerStorage;
+use crate::matters::{name_similarity, MatterStorage};
+use crate::text_processing;
+
+/// Cross-document Consistency for BEAR AI
+/// Documents in one matter should agree on the key facts. Dates and amounts are labelled by the
+/// nearest keyword before them in the same sentence ("terminates on", "liability cap"), parties
+/// by the role they are defined as (Acme Ltd (the "Supplier")) and defined terms by their
+/// definition. A fact is flagged when two documents give it values with nothing in common.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FactKind {
+    Date,
+    Amount,
+    PartyName,
+    DefinedTerm,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiscrepancySeverity {
+    High,
+    Medium,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FactOccurrence {
+    pub document: String,
+    pub value: String, // normalized: ISO date, "EUR 1000.00", party name, lowercased definition
+    pub text: String,  // as written
+    pub context: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Discrepancy {
+    pub kind: FactKind,
+    pub key: String, // "termination date", "Supplier", "Services"
+    pub severity: DiscrepancySeverity,
+    pub description: String,
+    pub occurrences: Vec<FactOccurrence>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsistencyReport {
+    pub matter_id: String,
+    pub documents: Vec<String>,
+    pub facts_compared: usize, // facts found in at least two documents
+    pub discrepancies: Vec<Discrepancy>,
+    pub checked_at: String,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Fact {
+    pub(crate) kind: FactKind,
+    pub(crate) key: String,
+    pub(crate) value: String,
+    pub(crate) text: String,
+    pub(crate) context: String,
+}
+
+const DATE_LABELS: &[(&str, &[&str])] = &[
+    ("effective date", &["effective", "commencement", "commence", "start date"]),
+    ("termination date", &["terminat", "expir", "end date", "ends on", "end on", "until"]),
+    ("closing date", &["closing", "completion"]),
+    ("delivery date", &["deliver"]),
+    ("payment due date", &["due", "payable"]),
+];
+
+const AMOUNT_LABELS: &[(&str, &[&str])] = &[
+    ("liability cap", &["liability", "cap", "aggregate"]),
+    ("contract value", &["contract value", "total value", "contract price", "purchase price"]),
+    ("fees", &["fee", "charges", "price"]),
+    ("deposit", &["deposit"]),
+    ("rent", &["rent"]),
+];
+
+// How far back in the sentence a label may appear
+const LABEL_WINDOW: usize = 80;
+
+fn floor_boundary(text: &str, mut index: usize) -> usize {
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+fn ceil_boundary(text: &str, mut index: usize) -> usize {
+    while index < text.len() && !text.is_char_boundary(index) {
+        index += 1;
+    }
+    index
+}
+
+fn context(text: &str, start: usize, end: usize) -> String {
+    let from = floor_boundary(text, start.saturating_sub(60));
+    let to = ceil_boundary(text, (end + 60).min(text.len()));
+    text[from..to].split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// The label whose keyword appears closest before `start` in the same sentence
+fn label_before(text: &str, start: usize, labels: &[(&'static str, &[&str])]) -> Option<&'static str> {
+    let from = floor_boundary(text, start.saturating_sub(LABEL_WINDOW));
+    let window = text[from..start].to_lowercase();
+    let sentence = window.rsplit([';', '\n']).next().unwrap_or("");
+    let sentence = match sentence.rfind(". ") {
+        Some(i) => &sentence[i + 2..],
+        None => sentence,
+    };
+    labels
+        .iter()
+        .filter_map(|(label, keywords)| {
+            keywords
+                .iter()
+                .filter_map(|keyword| sentence.rfind(keyword))
+                .max()
+                .map(|position| (*label, position))
+        })
+        .max_by_key(|(_, position)| *position)
+        .map(|(label, _)| label)
+}
+
+/// Parties introduced with a defined role, as (span of the definition, name, role):
+/// Acme Ltd (the "Supplier"), Beta B.V. (hereinafter "Customer")
+pub(crate) fn party_definitions(text: &str) -> Vec<(std::ops::Range<usize>, String, String)> {
+    let party = Regex::new(
+        r#"((?:[A-Z][\w&.'-]*,?\s+){0,5}[A-Z][\w&.'-]*)\s*\(\s*(?:(?:the|hereinafter(?:\s+the)?)\s+)?["“]([A-Z][\w ]{1,30})["”]\s*\)"#,
+    )
+    .unwrap();
+    party
+        .captures_iter(text)
+        .map(|caps| {
+            let whole = caps.get(0).unwrap();
+            let name = caps[1].trim().trim_end_matches(',').to_string();
+            (whole.range(), name, caps[2].trim().to_string())
+        })
+        .collect()
+}
+
+pub(crate) fn extract_facts(text: &str) -> Vec<Fact> {
+    let mut facts = Vec::new();
+    let order = detect_date_order(&text_processing::detect_language(text), text);
+
+    for date in locale_formats::find_dates(text, order) {
+        if let Some(label) = label_before(text, date.start, DATE_LABELS) {
+            facts.push(Fact {
+                kind: FactKind::Date,
+                key: label.to_string(),
+                value: date.normalized.clone(),
+                text: date.text.clone(),
+                context: context(text, date.start, date.end),
+            });
+        }
+    }
+    for amount in locale_formats::find_monetary_amounts(text) {
+        if let Some(label) = label_before(text, amount.start, AMOUNT_LABELS) {
+            facts.push(Fact {
+                kind: FactKind::Amount,
+                key: label.to_string(),
+                value: amount.normalized.clone(),
+                text: amount.text.clone(),
+                context: context(text, amount.start, amount.end),
+            });
+        }
+    }
+
+    for (range, name, role) in party_definitions(text) {
+        facts.push(Fact {
+            kind: FactKind::PartyName,
+            key: role,
+            value: name.clone(),
+            text: name,
+            context: context(text, range.start, range.end),
+        });
+    }
+
+    // "Services" means the services described in Schedule 1
+    let definition = Regex::new(r#"["“]([A-Z][\w -]{1,40})["”]\s+(?:means|shall mean)\s+([^.;]{3,200})"#).unwrap();
+    for caps in definition.captures_iter(text) {
+        let whole = caps.get(0).unwrap();
+        facts.push(Fact {
+            kind: FactKind::DefinedTerm,
+            key: caps[1].trim().to_string(),
+            value: caps[2].split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase(),
+            text: caps[2].trim().to_string(),
+            context: context(text, whole.start(), whole.end()),
+        });
+    }
+    facts
+}
+
+/// Two documents disagree when their values for a fact have nothing in common. Party names are
+/// compared ignoring corporate suffixes, so "Acme Ltd" and "ACME Limited" agree.
+fn values_agree(kind: FactKind, a: &BTreeSet<String>, b: &BTreeSet<String>) -> bool {
+    match kind {
+        FactKind::PartyName => a.iter().any(|x| b.iter().any(|y| name_similarity(x, y) >= 1.0)),
+        _ => !a.is_disjoint(b),
+    }
+}
+
+/// Compare the facts of (document name, text) pairs; returns the number of facts found in at
+/// least two documents and the discrepancies among them
+pub fn find_discrepancies(documents: &[(String, String)]) -> (usize, Vec<Discrepancy>) {
+    // (kind, key) -> document -> facts
+    let mut grouped: BTreeMap<(FactKind, String), BTreeMap<&str, Vec<Fact>>> = BTreeMap::new();
+    for (document, text) in documents {
+        for fact in extract_facts(text) {
+            grouped
+                .entry((fact.kind, fact.key.clone()))
+                .or_default()
+                .entry(document.as_str())
+                .or_default()
+                .push(fact);
+        }
+    }
+
+    let mut compared = 0;
+    let mut discrepancies = Vec::new();
+    for ((kind, key), by_document) in grouped {
+        if by_document.len() < 2 {
+            continue;
+        }
+        compared += 1;
+
+        let values: Vec<(&str, BTreeSet<String>)> = by_document
+            .iter()
+            .map(|(document, facts)| (*document, facts.iter().map(|f| f.value.clone()).collect()))
+            .collect();
+        let conflicting = values.iter().enumerate().any(|(i, (_, a))| {
+            values[i + 1..].iter().any(|(_, b)| !values_agree(kind, a, b))
+        });
+        if !conflicting {
+            continue;
+        }
+
+        let summary: Vec<String> = values
+            .iter()
+            .map(|(document, values)| {
+                format!("{} in {}", values.iter().cloned().collect::<Vec<_>>().join(" / "), document)
+            })
+            .collect();
+        discrepancies.push(Discrepancy {
+            kind,
+            key: key.clone(),
+            severity: match kind {
+                FactKind::DefinedTerm => DiscrepancySeverity::Medium,
+                _ => DiscrepancySeverity::High,
+            },
+            description: format!("{} differs: {}", key, summary.join("; ")),
+            occurrences: by_document
+                .into_iter()
+                .flat_map(|(document, facts)| {
+                    facts.into_iter().map(move |f| FactOccurrence {
+                        document: document.to_string(),
+                        value: f.value,
+                        text: f.text,
+                        context: f.context,
+                    })
+                })
+                .collect(),
+        });
+    }
+    (compared, discrepancies)
+}
+
+/// Check the matter's attached documents, or the given files, against each other
+#[tauri::command]
+pub async fn matter_check_consistency(
+    matter_id: String,
+    file_paths: Option<Vec<String>>,
+    matters: tauri::State<'_, MatterStorage>,
+    analyzer: tauri::State<'_, AnalyzerStorage>,
+) -> Result<ConsistencyReport, String> {
+    let matter = matters
+        .get(&matter_id)
+        .ok_or_else(|| format!("Matter {} not found", matter_id))?;
+    let paths = file_paths.unwrap_or(matter.documents);
+    if paths.len() < 2 {
+        return Err("At least two documents are needed for a consistency check".to_string());
+    }
+
+    let mut documents = Vec::new();
+    for path in &paths {
+        let text = analyzer
+            .extract_text(Path::new(path))
+            .await
+            .map_err(|e| format!("{}: {}", path, e))?;
+        let name = Path::new(path)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.clone());
+        documents.push((name, text));
+    }
+
+    let (facts_compared, discrepancies) = find_discrepancies(&documents);
+    Ok(ConsistencyReport {
+        matter_id,
+        documents: paths,
+        facts_compared,
+        discrepancies,
+        checked_at: Utc::now().to_rfc3339(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn docs(items: &[(&str, &str)]) -> Vec<(String, String)> {
+        items.iter().map(|(n, t)| (n.to_string(), t.to_string())).collect()
+    }
+
+    #[test]
+    fn test_flags_differing_dates_and_amounts() {
+        let documents = docs(&[
+            (
+                "MSA.txt",
+                "This Agreement is made between Acme Ltd (the \"Supplier\") and Beta BV (the \"Customer\"). \
+                 This Agreement terminates on 31 December 2025. The aggregate liability of the Supplier shall not exceed EUR 50.000,00.",
+            ),
+            (
+                "SOW.txt",
+                "This SOW is issued under the agreement between ACME Limited (the \"Supplier\") and Beta BV (the \"Customer\"). \
+                 The Services end on 30 June 2026. Fees are EUR 12.000,00. Liability is capped at EUR 50.000,00.",
+            ),
+        ]);
+        let (compared, discrepancies) = find_discrepancies(&documents);
+
+        // termination date, liability cap, Supplier and Customer appear in both
+        assert_eq!(compared, 4);
+        assert_eq!(discrepancies.len(), 1);
+        let termination = &discrepancies[0];
+        assert_eq!(termination.kind, FactKind::Date);
+        assert_eq!(termination.key, "termination date");
+        assert_eq!(termination.severity, DiscrepancySeverity::High);
+        assert_eq!(
+            termination.description,
+            "termination date differs: 2025-12-31 in MSA.txt; 2026-06-30 in SOW.txt"
+        );
+        assert_eq!(termination.occurrences.len(), 2);
+        assert!(termination.occurrences[0].context.contains("terminates on 31 December 2025"));
+    }
+
+    #[test]
+    fn test_flags_party_and_definition_changes() {
+        let documents = docs(&[
+            (
+                "MSA.txt",
+                "Acme Ltd (the \"Supplier\") will provide the Services. \"Services\" means the hosting services in Schedule 1.",
+            ),
+            (
+                "Amendment.txt",
+                "Acme Cloud Holdings Inc (the \"Supplier\") will provide the Services. \"Services\" means the hosting and support services in Schedule 1.",
+            ),
+        ]);
+        let (_, discrepancies) = find_discrepancies(&documents);
+
+        let kinds: Vec<(FactKind, &str, DiscrepancySeverity)> = discrepancies
+            .iter()
+            .map(|d| (d.kind, d.key.as_str(), d.severity))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (FactKind::PartyName, "Supplier", DiscrepancySeverity::High),
+                (FactKind::DefinedTerm, "Services", DiscrepancySeverity::Medium),
+            ]
+        );
+    }
+}
@@ -0,0 +1,519 @@
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, VecDeque};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use crate::enterprise_management::BarrierStorage;
+use crate::entity_relations::{EntityRelation, RelationQuery, RelationStorage};
+use crate::local_api::{authenticated_user, SessionStorage};
+use crate::matters::{name_tokens, MatterStorage};
+use crate::party_registry::{Party, PartyRegistryStorage};
+use crate::security::SecurityManager;
+
+/// Matter Graph for BEAR AI
+/// Assembles a matter's parties, documents and the relations read from them into one graph and
+/// exports it as GraphML (yEd, Gephi, Cytoscape) or JSON-LD, so the matter can be drawn as a map
+/// of who is connected to whom and through which documents. Relation parties are resolved to the
+/// matter's canonical parties; names the registry does not know become plain entity nodes.
+pub const DEFAULT_PATH_LENGTH: usize = 3;
+pub const MAX_PATH_LENGTH: usize = 5;
+// Path queries stop after this many paths
+const MAX_PATHS: usize = 50;
+const JSON_LD_VOCAB: &str = "https://bear-ai.app/ns/matter#";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GraphNodeKind {
+    Party,
+    Entity, // named in a relation but not in the party registry
+    Document,
+}
+
+impl GraphNodeKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            GraphNodeKind::Party => "party",
+            GraphNodeKind::Entity => "entity",
+            GraphNodeKind::Document => "document",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphNode {
+    pub id: String, // "party:<id>", "entity:<name>" or "document:<path>"
+    pub kind: GraphNodeKind,
+    pub label: String,
+    pub role: Option<String>, // the party's role in the matter
+    pub aliases: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphEdge {
+    pub source: String,
+    pub target: String,
+    pub label: String, // a relation ("indemnifies") or "mentioned in"
+    pub document: Option<String>, // where a relation was read
+    pub evidence: Option<String>,
+    pub weight: f32, // a relation's confidence, or a party's mentions in the document
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatterGraph {
+    pub matter_id: String,
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum GraphFormat {
+    Graphml,
+    JsonLd,
+}
+
+/// Paths from one node to another; `from` and `to` are node ids or party names. Edges are
+/// followed both ways unless `directed`, and only those labelled `relation` when it is given.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GraphPathQuery {
+    pub from: String,
+    pub to: String,
+    pub relation: Option<String>,
+    pub max_length: Option<usize>,
+    #[serde(default)]
+    pub directed: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphPath {
+    pub nodes: Vec<String>, // node labels, from first to last
+    pub edges: Vec<GraphEdge>,
+}
+
+fn party_node_id(party: &Party) -> String {
+    format!("party:{}", party.id)
+}
+
+fn document_label(path: &str) -> String {
+    Path::new(path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string())
+}
+
+/// The node a relation's party name stands for, adding an entity node when no party matches
+fn resolve_node(graph: &mut MatterGraph, parties: &[Party], name: &str) -> String {
+    let tokens = name_tokens(name);
+    if let Some(party) = parties.iter().find(|p| p.aliases.iter().any(|a| name_tokens(a) == tokens)) {
+        return party_node_id(party);
+    }
+    let id = format!("entity:{}", tokens.into_iter().collect::<Vec<_>>().join(" "));
+    if !graph.nodes.iter().any(|n| n.id == id) {
+        graph.nodes.push(GraphNode {
+            id: id.clone(),
+            kind: GraphNodeKind::Entity,
+            label: name.to_string(),
+            role: None,
+            aliases: vec![name.to_string()],
+        });
+    }
+    id
+}
+
+pub fn build_graph(matter_id: &str, documents: &[String], parties: &[Party], relations: &[EntityRelation]) -> MatterGraph {
+    let mut graph = MatterGraph {
+        matter_id: matter_id.to_string(),
+        nodes: Vec::new(),
+        edges: Vec::new(),
+    };
+    for document in documents {
+        graph.nodes.push(GraphNode {
+            id: format!("document:{}", document),
+            kind: GraphNodeKind::Document,
+            label: document_label(document),
+            role: None,
+            aliases: Vec::new(),
+        });
+    }
+    for party in parties {
+        graph.nodes.push(GraphNode {
+            id: party_node_id(party),
+            kind: GraphNodeKind::Party,
+            label: party.canonical_name.clone(),
+            role: party.role.map(|r| format!("{:?}", r).to_lowercase()),
+            aliases: party.aliases.clone(),
+        });
+        let mut mentions: BTreeMap<&str, usize> = BTreeMap::new();
+        for mention in party.mentions.iter().filter(|m| documents.contains(&m.document)) {
+            *mentions.entry(mention.document.as_str()).or_default() += mention.count;
+        }
+        for (document, count) in mentions {
+            graph.edges.push(GraphEdge {
+                source: party_node_id(party),
+                target: format!("document:{}", document),
+                label: "mentioned in".to_string(),
+                document: Some(document.to_string()),
+                evidence: None,
+                weight: count as f32,
+            });
+        }
+    }
+    for relation in relations.iter().filter(|r| documents.contains(&r.document)) {
+        let source = resolve_node(&mut graph, parties, &relation.subject);
+        let target = resolve_node(&mut graph, parties, &relation.object);
+        graph.edges.push(GraphEdge {
+            source,
+            target,
+            label: relation.relation.as_str().to_string(),
+            document: Some(relation.document.clone()),
+            evidence: Some(relation.evidence.clone()),
+            weight: relation.confidence,
+        });
+    }
+    graph
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+pub fn to_graphml(graph: &MatterGraph) -> String {
+    let data = |key: &str, value: &str| format!("      <data key=\"{}\">{}</data>\n", key, escape(value));
+    let mut xml = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n\
+         \x20 <key id=\"label\" for=\"all\" attr.name=\"label\" attr.type=\"string\"/>\n\
+         \x20 <key id=\"kind\" for=\"node\" attr.name=\"kind\" attr.type=\"string\"/>\n\
+         \x20 <key id=\"role\" for=\"node\" attr.name=\"role\" attr.type=\"string\"/>\n\
+         \x20 <key id=\"aliases\" for=\"node\" attr.name=\"aliases\" attr.type=\"string\"/>\n\
+         \x20 <key id=\"document\" for=\"edge\" attr.name=\"document\" attr.type=\"string\"/>\n\
+         \x20 <key id=\"evidence\" for=\"edge\" attr.name=\"evidence\" attr.type=\"string\"/>\n\
+         \x20 <key id=\"weight\" for=\"edge\" attr.name=\"weight\" attr.type=\"double\"/>\n",
+    );
+    xml.push_str(&format!("  <graph id=\"{}\" edgedefault=\"directed\">\n", escape(&graph.matter_id)));
+    for node in &graph.nodes {
+        xml.push_str(&format!("    <node id=\"{}\">\n", escape(&node.id)));
+        xml.push_str(&data("label", &node.label));
+        xml.push_str(&data("kind", node.kind.as_str()));
+        if let Some(role) = &node.role {
+            xml.push_str(&data("role", role));
+        }
+        if !node.aliases.is_empty() {
+            xml.push_str(&data("aliases", &node.aliases.join("; ")));
+        }
+        xml.push_str("    </node>\n");
+    }
+    for (index, edge) in graph.edges.iter().enumerate() {
+        xml.push_str(&format!(
+            "    <edge id=\"e{}\" source=\"{}\" target=\"{}\">\n",
+            index,
+            escape(&edge.source),
+            escape(&edge.target)
+        ));
+        xml.push_str(&data("label", &edge.label));
+        if let Some(document) = &edge.document {
+            xml.push_str(&data("document", document));
+        }
+        if let Some(evidence) = &edge.evidence {
+            xml.push_str(&data("evidence", evidence));
+        }
+        xml.push_str(&data("weight", &edge.weight.to_string()));
+        xml.push_str("    </edge>\n");
+    }
+    xml.push_str("  </graph>\n</graphml>\n");
+    xml
+}
+
+/// Nodes as resources and each edge as a relationship resource, so the evidence and document
+/// of every relation survive the export
+pub fn to_json_ld(graph: &MatterGraph) -> Value {
+    let mut resources: Vec<Value> = graph
+        .nodes
+        .iter()
+        .map(|node| {
+            let mut resource = json!({
+                "@id": format!("urn:bear-ai:{}", node.id),
+                "@type": node.kind.as_str(),
+                "name": node.label,
+            });
+            if let Some(role) = &node.role {
+                resource["role"] = json!(role);
+            }
+            if !node.aliases.is_empty() {
+                resource["alias"] = json!(node.aliases);
+            }
+            resource
+        })
+        .collect();
+    resources.extend(graph.edges.iter().map(|edge| {
+        json!({
+            "@type": "relationship",
+            "name": edge.label,
+            "source": { "@id": format!("urn:bear-ai:{}", edge.source) },
+            "target": { "@id": format!("urn:bear-ai:{}", edge.target) },
+            "document": edge.document,
+            "evidence": edge.evidence,
+            "weight": edge.weight,
+        })
+    }));
+    json!({
+        "@context": {
+            "@vocab": JSON_LD_VOCAB,
+            "name": "http://schema.org/name",
+            "alias": "http://schema.org/alternateName",
+            "source": { "@type": "@id" },
+            "target": { "@type": "@id" },
+        },
+        "@id": format!("urn:bear-ai:matter:{}", graph.matter_id),
+        "@graph": resources,
+    })
+}
+
+/// The node a query names: its id, else a node whose label or alias is that name
+fn find_node<'a>(graph: &'a MatterGraph, name: &str) -> Option<&'a GraphNode> {
+    let tokens = name_tokens(name);
+    graph.nodes.iter().find(|n| n.id == name).or_else(|| {
+        graph.nodes.iter().find(|n| {
+            !tokens.is_empty()
+                && (name_tokens(&n.label) == tokens || n.aliases.iter().any(|a| name_tokens(a) == tokens))
+        })
+    })
+}
+
+/// Every path without repeated nodes from `query.from` to `query.to`, shortest first
+pub fn find_paths(graph: &MatterGraph, query: &GraphPathQuery) -> Result<Vec<GraphPath>> {
+    let from = find_node(graph, &query.from).ok_or_else(|| anyhow!("No node named {}", query.from))?;
+    let to = find_node(graph, &query.to).ok_or_else(|| anyhow!("No node named {}", query.to))?;
+    let max_length = query.max_length.unwrap_or(DEFAULT_PATH_LENGTH).clamp(1, MAX_PATH_LENGTH);
+    let labels: BTreeMap<&str, &str> = graph.nodes.iter().map(|n| (n.id.as_str(), n.label.as_str())).collect();
+
+    // Breadth first, so paths come out shortest first
+    let mut paths = Vec::new();
+    let mut queue: VecDeque<(Vec<&str>, Vec<&GraphEdge>)> = VecDeque::from([(vec![from.id.as_str()], Vec::new())]);
+    while let Some((nodes, edges)) = queue.pop_front() {
+        let last = *nodes.last().unwrap();
+        if last == to.id {
+            paths.push(GraphPath {
+                nodes: nodes.iter().map(|id| labels[id].to_string()).collect(),
+                edges: edges.into_iter().cloned().collect(),
+            });
+            if paths.len() >= MAX_PATHS {
+                break;
+            }
+            continue;
+        }
+        if edges.len() >= max_length {
+            continue;
+        }
+        for edge in &graph.edges {
+            if query.relation.as_ref().is_some_and(|r| !edge.label.eq_ignore_ascii_case(r)) {
+                continue;
+            }
+            let next = if edge.source == last {
+                edge.target.as_str()
+            } else if !query.directed && edge.target == last {
+                edge.source.as_str()
+            } else {
+                continue;
+            };
+            if nodes.contains(&next) {
+                continue;
+            }
+            let mut nodes = nodes.clone();
+            nodes.push(next);
+            let mut edges = edges.clone();
+            edges.push(edge);
+            queue.push_back((nodes, edges));
+        }
+    }
+    Ok(paths)
+}
+
+fn matter_graph(
+    matter_id: &str,
+    matters: &MatterStorage,
+    registry: &PartyRegistryStorage,
+    relations: &RelationStorage,
+) -> Result<MatterGraph, String> {
+    let matter = matters
+        .get(matter_id)
+        .ok_or_else(|| format!("Matter {} not found", matter_id))?;
+    let found: Vec<EntityRelation> = matter
+        .documents
+        .iter()
+        .flat_map(|document| {
+            relations
+                .query(&RelationQuery {
+                    document: Some(document.clone()),
+                    ..Default::default()
+                })
+                .relations
+        })
+        .collect();
+    Ok(build_graph(matter_id, &matter.documents, &registry.list(matter_id), &found))
+}
+
+/// Write the matter's graph to `output_path` as GraphML or JSON-LD
+#[tauri::command]
+pub async fn export_matter_graph(
+    session_id: String,
+    matter_id: String,
+    format: GraphFormat,
+    output_path: String,
+    matters: tauri::State<'_, MatterStorage>,
+    registry: tauri::State<'_, PartyRegistryStorage>,
+    relations: tauri::State<'_, RelationStorage>,
+    sessions: tauri::State<'_, SessionStorage>,
+    barriers: tauri::State<'_, BarrierStorage>,
+    security: tauri::State<'_, Arc<Mutex<SecurityManager>>>,
+) -> Result<MatterGraph, String> {
+    let graph = matter_graph(&matter_id, &matters, &registry, &relations)?;
+    // The graph is drawn from the matter's documents, so a barrier around the matter covers it
+    let user = authenticated_user(&session_id, &sessions)?;
+    let items: Vec<(String, Option<String>)> = std::iter::once(format!("matter_graph:{}", matter_id))
+        .chain(matters.get(&matter_id).map(|m| m.documents).unwrap_or_default())
+        .map(|document| (document, Some(matter_id.clone())))
+        .collect();
+    barriers.check_export(&security, &user, &items)?;
+    let content = match format {
+        GraphFormat::Graphml => to_graphml(&graph),
+        GraphFormat::JsonLd => serde_json::to_string_pretty(&to_json_ld(&graph)).map_err(|e| e.to_string())?,
+    };
+    std::fs::write(&output_path, content).map_err(|e| e.to_string())?;
+    Ok(graph)
+}
+
+/// How two parties (or a party and a document) of the matter are connected
+#[tauri::command]
+pub async fn query_matter_graph(
+    matter_id: String,
+    query: GraphPathQuery,
+    matters: tauri::State<'_, MatterStorage>,
+    registry: tauri::State<'_, PartyRegistryStorage>,
+    relations: tauri::State<'_, RelationStorage>,
+) -> Result<Vec<GraphPath>, String> {
+    let graph = matter_graph(&matter_id, &matters, &registry, &relations)?;
+    find_paths(&graph, &query).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity_relations::{RelationSource, RelationType};
+    use crate::party_registry::{PartyMention, PartyRole};
+
+    fn party(id: &str, name: &str, role: Option<PartyRole>, mentions: &[(&str, usize)]) -> Party {
+        Party {
+            id: id.to_string(),
+            canonical_name: name.to_string(),
+            aliases: vec![name.to_string()],
+            role,
+            confirmed: false,
+            mentions: mentions
+                .iter()
+                .map(|(document, count)| PartyMention {
+                    document: document.to_string(),
+                    alias: name.to_string(),
+                    count: *count,
+                })
+                .collect(),
+            updated_at: String::new(),
+        }
+    }
+
+    fn relation(relation: RelationType, subject: &str, object: &str, document: &str) -> EntityRelation {
+        EntityRelation {
+            id: String::new(),
+            relation,
+            subject: subject.to_string(),
+            subject_role: None,
+            object: object.to_string(),
+            object_role: None,
+            document: document.to_string(),
+            evidence: format!("{} {} {} & more.", subject, relation.as_str(), object),
+            confidence: 0.75,
+            source: RelationSource::Pattern,
+            extracted_at: String::new(),
+        }
+    }
+
+    fn sample() -> MatterGraph {
+        let documents = vec!["/m/msa.pdf".to_string(), "/m/guarantee.pdf".to_string()];
+        let parties = vec![
+            party("acme", "Acme Ltd", Some(PartyRole::Client), &[("/m/msa.pdf", 4), ("/m/guarantee.pdf", 1)]),
+            party("beta", "Beta BV", Some(PartyRole::Adverse), &[("/m/msa.pdf", 3), ("/other/x.pdf", 2)]),
+        ];
+        let relations = vec![
+            relation(RelationType::Indemnifies, "Acme Limited", "Beta BV", "/m/msa.pdf"),
+            relation(RelationType::Guarantees, "Acme Holdings Plc", "Acme Ltd", "/m/guarantee.pdf"),
+            relation(RelationType::Owns, "Gamma", "Beta", "/other/x.pdf"), // not a document of the matter
+        ];
+        build_graph("matter-1", &documents, &parties, &relations)
+    }
+
+    #[test]
+    fn test_builds_and_exports_the_matter_graph() {
+        let graph = sample();
+        let kinds: Vec<(&str, GraphNodeKind)> = graph.nodes.iter().map(|n| (n.id.as_str(), n.kind)).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ("document:/m/msa.pdf", GraphNodeKind::Document),
+                ("document:/m/guarantee.pdf", GraphNodeKind::Document),
+                ("party:acme", GraphNodeKind::Party),
+                ("party:beta", GraphNodeKind::Party),
+                ("entity:acme holdings", GraphNodeKind::Entity),
+            ]
+        );
+        // Three mentions in the matter's documents and two relations; nothing from other matters
+        assert_eq!(graph.edges.len(), 5);
+        let indemnity = graph.edges.iter().find(|e| e.label == "indemnifies").unwrap();
+        assert_eq!((indemnity.source.as_str(), indemnity.target.as_str()), ("party:acme", "party:beta"));
+
+        let graphml = to_graphml(&graph);
+        assert!(graphml.contains("<node id=\"party:acme\">"));
+        assert!(graphml.contains("<data key=\"role\">client</data>"));
+        assert!(graphml.contains("Acme Limited indemnifies Beta BV &amp; more."));
+
+        let json_ld = to_json_ld(&graph);
+        let resources = json_ld["@graph"].as_array().unwrap();
+        assert_eq!(resources.len(), 10);
+        assert_eq!(resources[2]["@id"], "urn:bear-ai:party:acme");
+        assert_eq!(resources[9]["source"]["@id"], "urn:bear-ai:entity:acme holdings");
+    }
+
+    #[test]
+    fn test_finds_paths_between_parties() {
+        let graph = sample();
+        let query = GraphPathQuery {
+            from: "Acme Holdings".to_string(),
+            to: "Beta".to_string(),
+            ..Default::default()
+        };
+        let paths = find_paths(&graph, &query).unwrap();
+        assert_eq!(paths[0].nodes, vec!["Acme Holdings Plc", "Acme Ltd", "Beta BV"]);
+        assert_eq!(paths[0].edges.iter().map(|e| e.label.as_str()).collect::<Vec<_>>(), vec!["guarantees", "indemnifies"]);
+        // Also through the document both are mentioned in
+        assert!(paths.iter().any(|p| p.nodes == vec!["Acme Holdings Plc", "Acme Ltd", "msa.pdf", "Beta BV"]));
+
+        // Only relations, followed the way they point
+        let directed = GraphPathQuery {
+            from: "Beta BV".to_string(),
+            to: "Acme Holdings".to_string(),
+            directed: true,
+            ..Default::default()
+        };
+        assert!(find_paths(&graph, &directed).unwrap().is_empty());
+        let relation = GraphPathQuery {
+            relation: Some("indemnifies".to_string()),
+            ..query
+        };
+        assert!(find_paths(&graph, &relation).unwrap().is_empty());
+        assert!(find_paths(&graph, &GraphPathQuery { from: "Nobody".to_string(), ..directed }).is_err());
+    }
+}
@@ -0,0 +1,504 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use crate::court_filing::{caption_parties, trim_to_sentence, CLAIMANT_ROLES, DEFENDANT_ROLES};
+use crate::document_analyzer::DocumentType;
+use crate::document_classifier;
+use crate::local_api::AnalyzerStorage;
+use crate::matter_consistency::{self, FactKind};
+use crate::matters::{name_similarity, Matter, MatterStorage, MatterType};
+use crate::text_processing;
+
+/// Smart Matter Intake for BEAR AI
+/// Turns a folder of documents into a proposed matter record: every file is classified from its
+/// content, parties come from their definitions (Acme Ltd (the "Supplier")) and litigation
+/// captions (Acme Ltd, Plaintiff), key dates from the labels before them and the jurisdiction from
+/// governing-law clauses and court names. Nothing is stored until the reviewed proposal is
+/// confirmed, which opens the matter in intake with its conflict check as usual.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntakeDocument {
+    pub path: String,
+    pub document_type: Option<DocumentType>,
+    pub language: Option<String>,
+    pub error: Option<String>, // text could not be extracted; the file is not attached
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProposedParty {
+    pub name: String,
+    pub roles: BTreeSet<String>, // "Supplier", "Plaintiff"
+    pub documents: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProposedDate {
+    pub label: String,
+    pub date: String, // ISO 8601
+    pub document: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JurisdictionCandidate {
+    pub jurisdiction: String,
+    pub documents: usize,
+    pub evidence: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntakeProposal {
+    pub folder: String,
+    pub name: String,
+    pub matter_type: MatterType,
+    pub jurisdiction: Option<String>,
+    pub client: Option<String>,
+    pub adverse_parties: Vec<String>,
+    pub related_parties: Vec<String>,
+    pub description: String,
+    pub parties: Vec<ProposedParty>,
+    pub key_dates: Vec<ProposedDate>,
+    pub jurisdiction_candidates: Vec<JurisdictionCandidate>,
+    pub documents: Vec<IntakeDocument>,
+}
+
+/// The document type with the most indicators in the filename and opening text
+pub fn classify_document(filename: &str, text: &str) -> Option<DocumentType> {
+    document_classifier::keyword_classification(filename, text)
+        .into_iter()
+        .next()
+        .map(|best| best.document_type)
+}
+
+fn matter_type_for(document_type: &DocumentType) -> MatterType {
+    match document_type {
+        DocumentType::CourtFiling | DocumentType::LegalBrief | DocumentType::CaseLaw | DocumentType::Evidence => {
+            MatterType::Litigation
+        }
+        DocumentType::Contract | DocumentType::NDA | DocumentType::CorporateGovernance => MatterType::Transactional,
+        DocumentType::EmploymentAgreement => MatterType::Employment,
+        DocumentType::Lease => MatterType::RealEstate,
+        DocumentType::Will | DocumentType::PowerOfAttorney => MatterType::EstatePlanning,
+        DocumentType::LegalMemo | DocumentType::Compliance | DocumentType::Regulation | DocumentType::Statute => {
+            MatterType::Advisory
+        }
+        _ => MatterType::General,
+    }
+}
+
+/// Any court filing makes it a litigation matter, however many contracts come with it;
+/// otherwise the most common type among the classified documents
+fn propose_matter_type(types: &[DocumentType]) -> MatterType {
+    let matter_types: Vec<MatterType> = types
+        .iter()
+        .map(matter_type_for)
+        .filter(|t| *t != MatterType::General)
+        .collect();
+    if matter_types.contains(&MatterType::Litigation) {
+        return MatterType::Litigation;
+    }
+    let mut best = (MatterType::General, 0);
+    for candidate in &matter_types {
+        let count = matter_types.iter().filter(|t| *t == candidate).count();
+        if count > best.1 {
+            best = (*candidate, count);
+        }
+    }
+    best.0
+}
+
+// "English law", "Dutch law" name the jurisdiction by its adjective
+const LAW_ADJECTIVES: &[(&str, &str)] = &[
+    ("English", "England and Wales"),
+    ("Scots", "Scotland"),
+    ("Irish", "Ireland"),
+    ("Dutch", "Netherlands"),
+    ("German", "Germany"),
+    ("French", "France"),
+    ("Belgian", "Belgium"),
+    ("Spanish", "Spain"),
+    ("Italian", "Italy"),
+    ("Swiss", "Switzerland"),
+];
+
+const PLACE: &str = r"([A-Z][a-z]+(?:\s+(?:and\s+)?[A-Z][a-z]+){0,3})";
+
+/// Jurisdictions named by the governing-law clause or the court, with the matching text
+fn find_jurisdictions(text: &str) -> Vec<(String, String)> {
+    let patterns = [
+        format!(
+            r"(?i:governed by|construed in accordance with|subject to)[^.;]{{0,40}}?\b(?i:laws?) of (?:the )?(?:(?i:state|commonwealth|province|kingdom) of )?{}",
+            PLACE
+        ),
+        format!(r"(?i:courts?) of (?:the )?(?:(?i:state|commonwealth|province) of )?{}", PLACE),
+        format!(r"(?i:district court) for the (?:[A-Z][a-z]+ )?(?i:district) of {}", PLACE),
+    ];
+
+    let mut found = Vec::new();
+    for pattern in &patterns {
+        let re = Regex::new(pattern).unwrap();
+        for caps in re.captures_iter(text) {
+            found.push((caps[1].to_string(), caps[0].to_string()));
+        }
+    }
+    let adjective = Regex::new(r"\b(English|Scots|Irish|Dutch|German|French|Belgian|Spanish|Italian|Swiss) law\b").unwrap();
+    for caps in adjective.captures_iter(text) {
+        if let Some((_, place)) = LAW_ADJECTIVES.iter().find(|(a, _)| *a == &caps[1]) {
+            found.push((place.to_string(), caps[0].to_string()));
+        }
+    }
+    found
+}
+
+fn side(roles: &BTreeSet<String>) -> Option<bool> {
+    if roles.iter().any(|r| CLAIMANT_ROLES.contains(&r.as_str())) {
+        Some(true)
+    } else if roles.iter().any(|r| DEFENDANT_ROLES.contains(&r.as_str())) {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+fn type_label(matter_type: MatterType) -> &'static str {
+    match matter_type {
+        MatterType::Litigation => "Litigation",
+        MatterType::Transactional => "Transaction",
+        MatterType::Employment => "Employment",
+        MatterType::RealEstate => "Real Estate",
+        MatterType::EstatePlanning => "Estate Planning",
+        MatterType::Advisory => "Advice",
+        MatterType::General => "General",
+    }
+}
+
+/// Build the proposal from (path, text) pairs. With a client hint the matching party becomes the
+/// client; without one the party found in the most documents is proposed.
+pub fn propose_intake(
+    folder: &str,
+    documents: &[(String, String)],
+    failed: Vec<(String, String)>,
+    client_hint: Option<&str>,
+) -> IntakeProposal {
+    let mut intake_documents = Vec::new();
+    let mut types = Vec::new();
+    let mut parties: Vec<(ProposedParty, BTreeSet<&str>)> = Vec::new();
+    let mut key_dates: Vec<ProposedDate> = Vec::new();
+    let mut jurisdictions: BTreeMap<String, (BTreeSet<&str>, Vec<String>)> = BTreeMap::new();
+
+    for (path, text) in documents {
+        let name = Path::new(path)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.clone());
+        let document_type = classify_document(&name, text);
+        if let Some(document_type) = &document_type {
+            types.push(document_type.clone());
+        }
+        intake_documents.push(IntakeDocument {
+            path: path.clone(),
+            document_type,
+            language: Some(text_processing::detect_language(text)),
+            error: None,
+        });
+
+        let facts = matter_consistency::extract_facts(text);
+        let mut found: Vec<(String, String)> = facts
+            .iter()
+            .filter(|f| f.kind == FactKind::PartyName)
+            .map(|f| (f.key.clone(), trim_to_sentence(&f.value)))
+            .collect();
+        found.extend(caption_parties(text));
+        for (role, party) in found {
+            match parties.iter_mut().find(|(p, _)| name_similarity(&p.name, &party) >= 1.0) {
+                Some((existing, seen_in)) => {
+                    existing.roles.insert(role);
+                    seen_in.insert(path.as_str());
+                }
+                None => parties.push((
+                    ProposedParty {
+                        name: party,
+                        roles: BTreeSet::from([role]),
+                        documents: 0,
+                    },
+                    BTreeSet::from([path.as_str()]),
+                )),
+            }
+        }
+
+        for fact in facts.iter().filter(|f| f.kind == FactKind::Date) {
+            let date = ProposedDate {
+                label: fact.key.clone(),
+                date: fact.value.clone(),
+                document: name.clone(),
+            };
+            if !key_dates.iter().any(|d| d.label == date.label && d.date == date.date) {
+                key_dates.push(date);
+            }
+        }
+
+        for (jurisdiction, evidence) in find_jurisdictions(text) {
+            let entry = jurisdictions.entry(jurisdiction).or_default();
+            entry.0.insert(path.as_str());
+            entry.1.push(format!("{}: {}", name, evidence));
+        }
+    }
+    for (path, error) in failed {
+        intake_documents.push(IntakeDocument {
+            path,
+            document_type: None,
+            language: None,
+            error: Some(error),
+        });
+    }
+
+    let mut parties: Vec<ProposedParty> = parties
+        .into_iter()
+        .map(|(mut party, seen_in)| {
+            party.documents = seen_in.len();
+            party
+        })
+        .collect();
+    // Stable sort keeps first-seen order among parties found equally often
+    parties.sort_by_key(|p| Reverse(p.documents));
+    key_dates.sort_by(|a, b| a.date.cmp(&b.date));
+
+    let mut jurisdiction_candidates: Vec<JurisdictionCandidate> = jurisdictions
+        .into_iter()
+        .map(|(jurisdiction, (seen_in, evidence))| JurisdictionCandidate {
+            jurisdiction,
+            documents: seen_in.len(),
+            evidence,
+        })
+        .collect();
+    jurisdiction_candidates.sort_by(|a, b| b.documents.cmp(&a.documents).then(b.evidence.len().cmp(&a.evidence.len())));
+
+    let matter_type = propose_matter_type(&types);
+    let client = match client_hint {
+        Some(hint) => parties
+            .iter()
+            .find(|p| name_similarity(&p.name, hint) >= 1.0)
+            .cloned(),
+        None => parties.first().cloned(),
+    };
+    let client_name = client.as_ref().map(|c| c.name.clone()).or_else(|| client_hint.map(String::from));
+
+    // Parties on the client's side of a caption, or sharing its role, are related; the rest adverse
+    let mut adverse_parties = Vec::new();
+    let mut related_parties = Vec::new();
+    for party in &parties {
+        let Some(client) = &client else {
+            adverse_parties.push(party.name.clone());
+            continue;
+        };
+        if party.name == client.name {
+            continue;
+        }
+        let same_side = match (side(&client.roles), side(&party.roles)) {
+            (Some(a), Some(b)) => a == b,
+            _ => !client.roles.is_disjoint(&party.roles),
+        };
+        if same_side {
+            related_parties.push(party.name.clone());
+        } else {
+            adverse_parties.push(party.name.clone());
+        }
+    }
+
+    let folder_name = Path::new(folder)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| folder.to_string());
+    let claimant = parties.iter().find(|p| side(&p.roles) == Some(true));
+    let defendant = parties.iter().find(|p| side(&p.roles) == Some(false));
+    let name = match (claimant, defendant, &client_name, adverse_parties.first()) {
+        (Some(claimant), Some(defendant), _, _) => format!("{} v. {}", claimant.name, defendant.name),
+        (_, _, Some(client), Some(other)) => format!("{} / {} ({})", client, other, type_label(matter_type)),
+        (_, _, Some(client), None) => format!("{} ({})", client, type_label(matter_type)),
+        _ => folder_name,
+    };
+
+    let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+    for document in &intake_documents {
+        let label = match &document.document_type {
+            Some(DocumentType::Other(other)) => other.clone(),
+            Some(document_type) => format!("{:?}", document_type),
+            None if document.error.is_some() => "unreadable".to_string(),
+            None => "unclassified".to_string(),
+        };
+        *counts.entry(label).or_default() += 1;
+    }
+    let description = format!(
+        "Proposed from {} documents in {}: {}",
+        intake_documents.len(),
+        folder,
+        counts
+            .iter()
+            .map(|(label, count)| format!("{} {}", count, label))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+
+    IntakeProposal {
+        folder: folder.to_string(),
+        name,
+        matter_type,
+        jurisdiction: jurisdiction_candidates.first().map(|c| c.jurisdiction.clone()),
+        client: client_name,
+        adverse_parties,
+        related_parties,
+        description,
+        parties,
+        key_dates,
+        jurisdiction_candidates,
+        documents: intake_documents,
+    }
+}
+
+/// Read every file below the folder and propose a matter for it
+#[tauri::command]
+pub async fn matter_propose_intake(
+    folder: String,
+    client: Option<String>,
+    analyzer: tauri::State<'_, AnalyzerStorage>,
+) -> Result<IntakeProposal, String> {
+    let root = Path::new(&folder);
+    if !root.is_dir() {
+        return Err(format!("{} is not a folder", folder));
+    }
+    let mut paths: Vec<PathBuf> = walkdir::WalkDir::new(root)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| entry.into_path())
+        .collect();
+    paths.sort();
+
+    let mut documents = Vec::new();
+    let mut failed = Vec::new();
+    for path in paths {
+        let display = path.to_string_lossy().to_string();
+        match analyzer.extract_text(&path).await {
+            Ok(text) if !text.trim().is_empty() => documents.push((display, text)),
+            Ok(_) => failed.push((display, "No text could be extracted".to_string())),
+            Err(e) => failed.push((display, e.to_string())),
+        }
+    }
+    if documents.is_empty() {
+        return Err(format!("No readable documents in {}", folder));
+    }
+
+    Ok(propose_intake(&folder, &documents, failed, client.as_deref()))
+}
+
+/// Open the reviewed proposal as a matter in intake and attach its readable documents
+#[tauri::command]
+pub async fn matter_confirm_intake(
+    proposal: IntakeProposal,
+    matters: tauri::State<'_, MatterStorage>,
+) -> Result<Matter, String> {
+    let client = proposal
+        .client
+        .filter(|c| !c.trim().is_empty())
+        .ok_or_else(|| "Choose the client before confirming the matter".to_string())?;
+    let matter = matters
+        .create_intake(
+            &proposal.name,
+            &client,
+            proposal.adverse_parties,
+            proposal.related_parties,
+            Some(proposal.description),
+        )
+        .map_err(|e| e.to_string())?;
+    matters
+        .set_profile(&matter.id, Some(proposal.matter_type), proposal.jurisdiction)
+        .map_err(|e| e.to_string())?;
+    let readable = proposal
+        .documents
+        .into_iter()
+        .filter(|d| d.error.is_none())
+        .map(|d| d.path)
+        .collect();
+    matters.attach_documents(&matter.id, readable).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn docs(items: &[(&str, &str)]) -> Vec<(String, String)> {
+        items.iter().map(|(n, t)| (n.to_string(), t.to_string())).collect()
+    }
+
+    #[test]
+    fn test_proposes_litigation_matter_from_dump() {
+        let documents = docs(&[
+            (
+                "dump/complaint.txt",
+                "IN THE SUPERIOR COURT OF THE STATE OF CALIFORNIA. Acme Ltd, Plaintiff, v. Beta Corp, Defendant. \
+                 Case No. 24-1234. COMPLAINT FOR BREACH OF CONTRACT. Acme Holdings, Plaintiff, joins this complaint.",
+            ),
+            (
+                "dump/supply_agreement.txt",
+                "This Supply Agreement is made between Acme Ltd (the \"Supplier\") and Beta Corp (the \"Customer\"). \
+                 This Agreement terminates on 31 December 2025. This Agreement is governed by the laws of the State of California.",
+            ),
+            ("dump/scan.pdf", ""),
+        ]);
+        let proposal = propose_intake(
+            "dump",
+            &documents[..2],
+            vec![("dump/scan.pdf".to_string(), "Unsupported".to_string())],
+            None,
+        );
+
+        assert_eq!(proposal.matter_type, MatterType::Litigation);
+        assert_eq!(proposal.name, "Acme Ltd v. Beta Corp");
+        assert_eq!(proposal.client.as_deref(), Some("Acme Ltd"));
+        assert_eq!(proposal.adverse_parties, vec!["Beta Corp".to_string()]);
+        assert_eq!(proposal.related_parties, vec!["Acme Holdings".to_string()]);
+        assert_eq!(proposal.jurisdiction.as_deref(), Some("California"));
+        assert_eq!(
+            proposal.key_dates,
+            vec![ProposedDate {
+                label: "termination date".to_string(),
+                date: "2025-12-31".to_string(),
+                document: "supply_agreement.txt".to_string(),
+            }]
+        );
+        assert!(matches!(proposal.documents[0].document_type, Some(DocumentType::CourtFiling)));
+        assert!(matches!(proposal.documents[1].document_type, Some(DocumentType::Contract)));
+        assert!(proposal.documents[2].error.is_some());
+        assert_eq!(
+            proposal.description,
+            "Proposed from 3 documents in dump: 1 Contract, 1 CourtFiling, 1 unreadable"
+        );
+    }
+
+    #[test]
+    fn test_client_hint_and_transactional_type() {
+        let documents = docs(&[
+            (
+                "deal/nda.txt",
+                "MUTUAL NON-DISCLOSURE AGREEMENT between Gamma BV (the \"Discloser\") and Delta GmbH (the \"Recipient\"). \
+                 Confidential Information shall be kept secret. This Agreement is governed by Dutch law.",
+            ),
+            (
+                "deal/spa.txt",
+                "Share Purchase Agreement. The parties hereby agree that Gamma BV (the \"Buyer\") acquires the shares from \
+                 Delta GmbH (the \"Seller\"). Closing shall occur on 1 March 2026. The courts of the Netherlands have jurisdiction.",
+            ),
+        ]);
+        let proposal = propose_intake("/cases/deal", &documents, Vec::new(), Some("Delta GmbH"));
+
+        assert_eq!(proposal.matter_type, MatterType::Transactional);
+        assert!(matches!(proposal.documents[0].document_type, Some(DocumentType::NDA)));
+        assert_eq!(proposal.client.as_deref(), Some("Delta GmbH"));
+        assert_eq!(proposal.adverse_parties, vec!["Gamma BV".to_string()]);
+        assert_eq!(proposal.name, "Delta GmbH / Gamma BV (Transaction)");
+        assert_eq!(proposal.jurisdiction.as_deref(), Some("Netherlands"));
+        assert_eq!(proposal.key_dates[0].label, "closing date");
+        assert_eq!(proposal.key_dates[0].date, "2026-03-01");
+    }
+}
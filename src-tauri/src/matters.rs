@@ -0,0 +1,816 @@
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+use crate::contract_execution::{ContractExecutionStorage, ExecutionStatus};
+use crate::glossary;
+use crate::pii_detector::PIIDetector;
+
+/// Matters and client intake for BEAR AI
+/// A matter starts in intake and can only be activated once the guided workflow is complete:
+/// a conflict check against every other matter, written waivers for waivable conflicts, an
+/// engagement letter generated from the firm template and a fully signed copy of that letter.
+/// Signature status comes from the contract execution tracker.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MatterStatus {
+    Intake,
+    Active,
+    Closed,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MatterType {
+    Litigation,
+    Transactional,
+    Employment,
+    RealEstate,
+    EstatePlanning,
+    Advisory,
+    General,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Matter {
+    pub id: String,
+    pub name: String,
+    pub client: String,
+    pub adverse_parties: Vec<String>,
+    pub related_parties: Vec<String>,
+    pub description: Option<String>,
+    pub status: MatterStatus,
+    pub created_at: String,
+    pub activated_at: Option<String>,
+    #[serde(default)]
+    pub documents: Vec<String>, // file paths of the matter's documents
+    #[serde(default)]
+    pub matter_type: Option<MatterType>,
+    #[serde(default)]
+    pub jurisdiction: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ConflictType {
+    AdverseParty,
+    FormerClient,
+    BusinessRelationship,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictSeverity {
+    High,
+    Medium,
+    Low,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ConflictIssue {
+    pub conflict_type: ConflictType,
+    pub party: String,
+    pub other_matter_id: String,
+    pub description: String,
+    pub severity: ConflictSeverity,
+    pub waivable: bool,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictStatus {
+    Clear,
+    Potential,
+    Conflict,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConflictCheckResult {
+    pub status: ConflictStatus,
+    pub conflicts: Vec<ConflictIssue>,
+    pub checked_against: usize,
+    pub checked_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConflictWaiver {
+    pub party: String,
+    pub waived_by: String,
+    pub consent_document: String, // signed informed-consent letter
+    pub recorded_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EngagementDetails {
+    pub firm_name: String,
+    pub responsible_attorney: String,
+    pub scope: String,
+    pub fee_arrangement: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EngagementLetter {
+    pub document_id: String, // tracked in contract execution under this id
+    pub path: String,
+    pub generated_at: String,
+    pub pii_warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IntakeWorkflow {
+    pub conflict_check: Option<ConflictCheckResult>,
+    pub waivers: Vec<ConflictWaiver>,
+    pub engagement_letter: Option<EngagementLetter>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntakeStatus {
+    pub matter: Matter,
+    pub workflow: IntakeWorkflow,
+    pub signature_status: Option<ExecutionStatus>,
+    pub blockers: Vec<String>, // empty when the matter can be activated
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct MatterState {
+    matters: HashMap<String, Matter>,
+    workflows: HashMap<String, IntakeWorkflow>,
+}
+
+const ENGAGEMENT_LETTER_TEMPLATE: &str = "{{date}}
+
+{{client}}
+
+Re: Engagement of {{firm_name}} - {{matter_name}}
+
+Dear {{client}},
+
+Thank you for selecting {{firm_name}} to represent you. This letter sets out the terms of our engagement.
+
+1. Scope of engagement
+{{scope}}
+
+2. Responsible attorney
+{{responsible_attorney}} will have primary responsibility for this matter.
+
+3. Fees
+{{fee_arrangement}}
+
+{{waiver_section}}Please confirm your agreement to these terms by signing below.
+
+{{firm_name}}
+
+By: ______________________________
+{{responsible_attorney}}
+
+Agreed and accepted:
+
+______________________________
+{{client}}
+";
+
+// Corporate suffixes ignored when comparing party names
+pub(crate) const ENTITY_SUFFIXES: &[&str] = &[
+    "inc", "incorporated", "llc", "llp", "ltd", "limited", "corp", "corporation", "co", "company", "plc", "bv", "nv",
+    "gmbh", "ag", "sa", "sarl", "the",
+];
+
+// Identifiers that do not belong in an engagement letter
+const SENSITIVE_PII: &[&str] = &["ssn", "credit_card", "bsn", "iban", "bank_account", "patient_id", "medical_record", "dutch_passport", "dutch_id"];
+
+pub(crate) fn name_tokens(name: &str) -> BTreeSet<String> {
+    name.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty() && !ENTITY_SUFFIXES.contains(t))
+        .map(String::from)
+        .collect()
+}
+
+/// 1.0 for the same party, a token-overlap score in between for similar names
+pub(crate) fn name_similarity(a: &str, b: &str) -> f64 {
+    let (a, b) = (name_tokens(a), name_tokens(b));
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    a.intersection(&b).count() as f64 / a.union(&b).count() as f64
+}
+
+const SAME_PARTY: f64 = 1.0;
+const SIMILAR_PARTY: f64 = 0.6;
+
+/// Check a matter's parties against all other matters
+fn check_conflicts(matter: &Matter, others: &[&Matter]) -> ConflictCheckResult {
+    let mut conflicts = Vec::new();
+    let mut exact = false;
+
+    for other in others.iter().filter(|o| o.id != matter.id) {
+        let mut compare = |ours: &str, theirs: &str, conflict_type: ConflictType, severity: ConflictSeverity, description: String| {
+            let score = name_similarity(ours, theirs);
+            if score >= SIMILAR_PARTY {
+                exact |= score >= SAME_PARTY;
+                conflicts.push(ConflictIssue {
+                    conflict_type,
+                    party: theirs.to_string(),
+                    other_matter_id: other.id.clone(),
+                    description: if score >= SAME_PARTY { description } else { format!("Possible match: {}", description) },
+                    severity: if score >= SAME_PARTY { severity } else { ConflictSeverity::Low },
+                    waivable: true,
+                });
+            }
+        };
+
+        // Acting against someone the firm represents or represented
+        for adverse in &matter.adverse_parties {
+            let (conflict_type, severity, relation) = match other.status {
+                MatterStatus::Closed => (ConflictType::FormerClient, ConflictSeverity::Medium, "a former client"),
+                _ => (ConflictType::AdverseParty, ConflictSeverity::High, "a current client"),
+            };
+            compare(
+                adverse,
+                &other.client,
+                conflict_type,
+                severity,
+                format!("Adverse party {} is {} in matter {}", adverse, relation, other.name),
+            );
+        }
+        // Representing someone the firm is acting against elsewhere
+        if other.status != MatterStatus::Closed {
+            for adverse in &other.adverse_parties {
+                compare(
+                    &matter.client,
+                    adverse,
+                    ConflictType::AdverseParty,
+                    ConflictSeverity::High,
+                    format!("Client {} is an adverse party in matter {}", matter.client, other.name),
+                );
+            }
+        }
+        for related in &matter.related_parties {
+            compare(
+                related,
+                &other.client,
+                ConflictType::BusinessRelationship,
+                ConflictSeverity::Low,
+                format!("Related party {} is a client in matter {}", related, other.name),
+            );
+        }
+    }
+
+    // The same client cannot be on both sides of this matter
+    for adverse in &matter.adverse_parties {
+        if name_similarity(adverse, &matter.client) >= SAME_PARTY {
+            exact = true;
+            conflicts.push(ConflictIssue {
+                conflict_type: ConflictType::AdverseParty,
+                party: adverse.clone(),
+                other_matter_id: matter.id.clone(),
+                description: format!("{} is both client and adverse party", adverse),
+                severity: ConflictSeverity::High,
+                waivable: false,
+            });
+        }
+    }
+
+    let status = if exact {
+        ConflictStatus::Conflict
+    } else if conflicts.is_empty() {
+        ConflictStatus::Clear
+    } else {
+        ConflictStatus::Potential
+    };
+    ConflictCheckResult {
+        status,
+        conflicts,
+        checked_against: others.iter().filter(|o| o.id != matter.id).count(),
+        checked_at: Utc::now().to_rfc3339(),
+    }
+}
+
+fn render_engagement_letter(matter: &Matter, details: &EngagementDetails, waivers: &[ConflictWaiver]) -> String {
+    let waiver_section = if waivers.is_empty() {
+        String::new()
+    } else {
+        let parties: Vec<&str> = waivers.iter().map(|w| w.party.as_str()).collect();
+        format!(
+            "4. Conflicts of interest\nWe disclosed our relationship with {} and you have given informed consent in writing to our acting for you notwithstanding that relationship.\n\n",
+            parties.join(", ")
+        )
+    };
+
+    [
+        ("{{date}}", Utc::now().format("%B %-d, %Y").to_string()),
+        ("{{client}}", matter.client.clone()),
+        ("{{matter_name}}", matter.name.clone()),
+        ("{{firm_name}}", details.firm_name.clone()),
+        ("{{responsible_attorney}}", details.responsible_attorney.clone()),
+        ("{{scope}}", details.scope.clone()),
+        ("{{fee_arrangement}}", details.fee_arrangement.clone()),
+        ("{{waiver_section}}", waiver_section),
+    ]
+    .iter()
+    .fold(ENGAGEMENT_LETTER_TEMPLATE.to_string(), |letter, (placeholder, value)| letter.replace(placeholder, value))
+}
+
+/// Reasons the matter cannot be activated yet
+fn intake_blockers(workflow: &IntakeWorkflow, signature_status: Option<ExecutionStatus>) -> Vec<String> {
+    let mut blockers = Vec::new();
+    match &workflow.conflict_check {
+        None => blockers.push("Conflict check has not been run".to_string()),
+        Some(check) => {
+            for conflict in &check.conflicts {
+                if !conflict.waivable {
+                    blockers.push(format!("Non-waivable conflict: {}", conflict.description));
+                } else if !workflow.waivers.iter().any(|w| w.party == conflict.party) {
+                    blockers.push(format!("Waiver required: {}", conflict.description));
+                }
+            }
+        }
+    }
+    match (&workflow.engagement_letter, signature_status) {
+        (None, _) => blockers.push("Engagement letter has not been generated".to_string()),
+        (Some(_), Some(ExecutionStatus::FullyExecuted)) => {}
+        (Some(_), _) => blockers.push("Engagement letter is not fully signed".to_string()),
+    }
+    blockers
+}
+
+pub struct MatterRegistry {
+    path: PathBuf,
+    letters_dir: PathBuf,
+    state: Mutex<MatterState>,
+}
+
+impl MatterRegistry {
+    pub fn new(app_data_dir: &Path) -> Result<Self> {
+        let path = app_data_dir.join("matters.json");
+        let letters_dir = app_data_dir.join("engagement_letters");
+        fs::create_dir_all(&letters_dir)?;
+        let state = if path.exists() {
+            serde_json::from_str(&fs::read_to_string(&path)?)?
+        } else {
+            MatterState::default()
+        };
+
+        Ok(Self {
+            path,
+            letters_dir,
+            state: Mutex::new(state),
+        })
+    }
+
+    fn persist(&self, state: &MatterState) -> Result<()> {
+        fs::write(&self.path, serde_json::to_string_pretty(state)?)?;
+        Ok(())
+    }
+
+    /// Open a matter in intake and run the first conflict check
+    pub fn create_intake(
+        &self,
+        name: &str,
+        client: &str,
+        adverse_parties: Vec<String>,
+        related_parties: Vec<String>,
+        description: Option<String>,
+    ) -> Result<Matter> {
+        let matter = Matter {
+            id: Uuid::new_v4().to_string(),
+            name: name.to_string(),
+            client: client.to_string(),
+            adverse_parties,
+            related_parties,
+            description,
+            status: MatterStatus::Intake,
+            created_at: Utc::now().to_rfc3339(),
+            activated_at: None,
+            documents: Vec::new(),
+            matter_type: None,
+            jurisdiction: None,
+        };
+
+        let mut state = self.state.lock().unwrap();
+        state.matters.insert(matter.id.clone(), matter.clone());
+        state.workflows.insert(matter.id.clone(), IntakeWorkflow::default());
+        self.persist(&state)?;
+        drop(state);
+
+        self.run_conflict_check(&matter.id)?;
+        Ok(matter)
+    }
+
+    pub fn get(&self, matter_id: &str) -> Option<Matter> {
+        self.state.lock().unwrap().matters.get(matter_id).cloned()
+    }
+
+    pub fn list(&self) -> Vec<Matter> {
+        let mut matters: Vec<Matter> = self.state.lock().unwrap().matters.values().cloned().collect();
+        matters.sort_by(|a, b| a.name.cmp(&b.name));
+        matters
+    }
+
+    /// What an export of these documents must clear the information barriers for: each document
+    /// on its own and under every matter it is filed in
+    pub fn export_items(&self, documents: &[String]) -> Vec<(String, Option<String>)> {
+        let all_matters = self.list();
+        documents
+            .iter()
+            .flat_map(|path| {
+                let filed_in = all_matters.iter().filter(|m| m.documents.contains(path)).map(|m| Some(m.id.clone()));
+                std::iter::once(None).chain(filed_in).map(move |matter| (path.clone(), matter))
+            })
+            .collect()
+    }
+
+    pub fn run_conflict_check(&self, matter_id: &str) -> Result<ConflictCheckResult> {
+        let mut state = self.state.lock().unwrap();
+        let matter = state
+            .matters
+            .get(matter_id)
+            .ok_or_else(|| anyhow!("Matter {} not found", matter_id))?;
+        let others: Vec<&Matter> = state.matters.values().collect();
+        let result = check_conflicts(matter, &others);
+
+        state.workflows.entry(matter_id.to_string()).or_default().conflict_check = Some(result.clone());
+        self.persist(&state)?;
+        Ok(result)
+    }
+
+    pub fn record_waiver(&self, matter_id: &str, party: &str, waived_by: &str, consent_document: &str) -> Result<IntakeWorkflow> {
+        let mut state = self.state.lock().unwrap();
+        let workflow = state
+            .workflows
+            .get_mut(matter_id)
+            .ok_or_else(|| anyhow!("Matter {} not found", matter_id))?;
+        let conflict = workflow
+            .conflict_check
+            .as_ref()
+            .and_then(|c| c.conflicts.iter().find(|c| c.party == party))
+            .ok_or_else(|| anyhow!("No conflict with {} to waive", party))?;
+        if !conflict.waivable {
+            return Err(anyhow!("The conflict with {} cannot be waived", party));
+        }
+
+        workflow.waivers.retain(|w| w.party != party);
+        workflow.waivers.push(ConflictWaiver {
+            party: party.to_string(),
+            waived_by: waived_by.to_string(),
+            consent_document: consent_document.to_string(),
+            recorded_at: Utc::now().to_rfc3339(),
+        });
+        let workflow = workflow.clone();
+        self.persist(&state)?;
+        Ok(workflow)
+    }
+
+    /// Write the engagement letter and flag sensitive identifiers that ended up in it
+    pub fn generate_engagement_letter(&self, matter_id: &str, details: &EngagementDetails) -> Result<EngagementLetter> {
+        let mut state = self.state.lock().unwrap();
+        let matter = state
+            .matters
+            .get(matter_id)
+            .cloned()
+            .ok_or_else(|| anyhow!("Matter {} not found", matter_id))?;
+        let workflow = state.workflows.entry(matter_id.to_string()).or_default();
+        if workflow.conflict_check.is_none() {
+            return Err(anyhow!("Run the conflict check before generating the engagement letter"));
+        }
+
+        let letter = glossary::enforce_glossary(&render_engagement_letter(&matter, details, &workflow.waivers));
+        let detection = PIIDetector::new(None).detect_pii(&letter);
+        let pii_warnings = detection
+            .matches
+            .iter()
+            .filter_map(|m| {
+                let pii_type = serde_json::to_value(&m.pii_type).ok()?.as_str()?.to_string();
+                SENSITIVE_PII
+                    .contains(&pii_type.as_str())
+                    .then(|| format!("Letter contains a {} at position {}", pii_type.replace('_', " "), m.start))
+            })
+            .collect();
+
+        let path = self.letters_dir.join(format!("{}.md", matter_id));
+        fs::write(&path, &letter)?;
+        let engagement_letter = EngagementLetter {
+            document_id: format!("engagement:{}", matter_id),
+            path: path.to_string_lossy().to_string(),
+            generated_at: Utc::now().to_rfc3339(),
+            pii_warnings,
+        };
+        workflow.engagement_letter = Some(engagement_letter.clone());
+        self.persist(&state)?;
+        Ok(engagement_letter)
+    }
+
+    pub fn intake_status(&self, matter_id: &str, signature_status: Option<ExecutionStatus>) -> Result<IntakeStatus> {
+        let state = self.state.lock().unwrap();
+        let matter = state
+            .matters
+            .get(matter_id)
+            .cloned()
+            .ok_or_else(|| anyhow!("Matter {} not found", matter_id))?;
+        let workflow = state.workflows.get(matter_id).cloned().unwrap_or_default();
+        let blockers = if matter.status == MatterStatus::Intake {
+            intake_blockers(&workflow, signature_status)
+        } else {
+            Vec::new()
+        };
+
+        Ok(IntakeStatus {
+            matter,
+            workflow,
+            signature_status,
+            blockers,
+        })
+    }
+
+    /// Move a matter out of intake once nothing blocks it. The conflict check runs again first,
+    /// since matters opened after it may conflict.
+    pub fn activate(&self, matter_id: &str, signature_status: Option<ExecutionStatus>) -> Result<Matter> {
+        if self.get(matter_id).is_some_and(|m| m.status == MatterStatus::Intake) {
+            self.run_conflict_check(matter_id)?;
+        }
+        let status = self.intake_status(matter_id, signature_status)?;
+        if status.matter.status != MatterStatus::Intake {
+            return Err(anyhow!("Matter {} is not in intake", matter_id));
+        }
+        if !status.blockers.is_empty() {
+            return Err(anyhow!("Matter cannot be activated: {}", status.blockers.join("; ")));
+        }
+
+        let mut state = self.state.lock().unwrap();
+        let matter = state
+            .matters
+            .get_mut(matter_id)
+            .ok_or_else(|| anyhow!("Matter {} not found", matter_id))?;
+        matter.status = MatterStatus::Active;
+        matter.activated_at = Some(Utc::now().to_rfc3339());
+        let matter = matter.clone();
+        self.persist(&state)?;
+        Ok(matter)
+    }
+
+    /// Attach document files to a matter; paths already attached are skipped
+    pub fn attach_documents(&self, matter_id: &str, file_paths: Vec<String>) -> Result<Matter> {
+        let mut state = self.state.lock().unwrap();
+        let matter = state
+            .matters
+            .get_mut(matter_id)
+            .ok_or_else(|| anyhow!("Matter {} not found", matter_id))?;
+        for path in file_paths {
+            if !matter.documents.contains(&path) {
+                matter.documents.push(path);
+            }
+        }
+        let matter = matter.clone();
+        self.persist(&state)?;
+        Ok(matter)
+    }
+
+    pub fn set_profile(&self, matter_id: &str, matter_type: Option<MatterType>, jurisdiction: Option<String>) -> Result<Matter> {
+        let mut state = self.state.lock().unwrap();
+        let matter = state
+            .matters
+            .get_mut(matter_id)
+            .ok_or_else(|| anyhow!("Matter {} not found", matter_id))?;
+        matter.matter_type = matter_type;
+        matter.jurisdiction = jurisdiction;
+        let matter = matter.clone();
+        self.persist(&state)?;
+        Ok(matter)
+    }
+
+    pub fn detach_document(&self, matter_id: &str, file_path: &str) -> Result<Matter> {
+        let mut state = self.state.lock().unwrap();
+        let matter = state
+            .matters
+            .get_mut(matter_id)
+            .ok_or_else(|| anyhow!("Matter {} not found", matter_id))?;
+        matter.documents.retain(|d| d != file_path);
+        let matter = matter.clone();
+        self.persist(&state)?;
+        Ok(matter)
+    }
+
+    pub fn close(&self, matter_id: &str) -> Result<Matter> {
+        let mut state = self.state.lock().unwrap();
+        let matter = state
+            .matters
+            .get_mut(matter_id)
+            .ok_or_else(|| anyhow!("Matter {} not found", matter_id))?;
+        matter.status = MatterStatus::Closed;
+        let matter = matter.clone();
+        self.persist(&state)?;
+        Ok(matter)
+    }
+}
+
+pub type MatterStorage = Arc<MatterRegistry>;
+
+fn letter_signature_status(matter_id: &str, tracker: &ContractExecutionStorage) -> Option<ExecutionStatus> {
+    tracker.get(&format!("engagement:{}", matter_id)).map(|c| c.status)
+}
+
+#[tauri::command]
+pub async fn matter_create_intake(
+    name: String,
+    client: String,
+    adverse_parties: Option<Vec<String>>,
+    related_parties: Option<Vec<String>>,
+    description: Option<String>,
+    matters: tauri::State<'_, MatterStorage>,
+) -> Result<Matter, String> {
+    matters
+        .create_intake(
+            &name,
+            &client,
+            adverse_parties.unwrap_or_default(),
+            related_parties.unwrap_or_default(),
+            description,
+        )
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn matter_list(matters: tauri::State<'_, MatterStorage>) -> Result<Vec<Matter>, String> {
+    Ok(matters.list())
+}
+
+#[tauri::command]
+pub async fn matter_run_conflict_check(
+    matter_id: String,
+    matters: tauri::State<'_, MatterStorage>,
+) -> Result<ConflictCheckResult, String> {
+    matters.run_conflict_check(&matter_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn matter_record_waiver(
+    matter_id: String,
+    party: String,
+    waived_by: String,
+    consent_document: String,
+    matters: tauri::State<'_, MatterStorage>,
+) -> Result<IntakeWorkflow, String> {
+    matters
+        .record_waiver(&matter_id, &party, &waived_by, &consent_document)
+        .map_err(|e| e.to_string())
+}
+
+/// Generate the letter and track its signatures as a contract; a regenerated letter has to be
+/// signed again
+#[tauri::command]
+pub async fn matter_generate_engagement_letter(
+    matter_id: String,
+    details: EngagementDetails,
+    matters: tauri::State<'_, MatterStorage>,
+    tracker: tauri::State<'_, ContractExecutionStorage>,
+) -> Result<EngagementLetter, String> {
+    let letter = matters
+        .generate_engagement_letter(&matter_id, &details)
+        .map_err(|e| e.to_string())?;
+    let matter = matters.get(&matter_id).ok_or_else(|| "Matter not found".to_string())?;
+    tracker
+        .register_revision(
+            &letter.document_id,
+            &format!("Engagement letter - {}", matter.name),
+            vec![details.firm_name.clone(), matter.client.clone()],
+        )
+        .map_err(|e| e.to_string())?;
+    Ok(letter)
+}
+
+#[tauri::command]
+pub async fn matter_intake_status(
+    matter_id: String,
+    matters: tauri::State<'_, MatterStorage>,
+    tracker: tauri::State<'_, ContractExecutionStorage>,
+) -> Result<IntakeStatus, String> {
+    matters
+        .intake_status(&matter_id, letter_signature_status(&matter_id, &tracker))
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn matter_activate(
+    matter_id: String,
+    matters: tauri::State<'_, MatterStorage>,
+    tracker: tauri::State<'_, ContractExecutionStorage>,
+) -> Result<Matter, String> {
+    matters
+        .activate(&matter_id, letter_signature_status(&matter_id, &tracker))
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn matter_attach_documents(
+    matter_id: String,
+    file_paths: Vec<String>,
+    matters: tauri::State<'_, MatterStorage>,
+) -> Result<Matter, String> {
+    matters.attach_documents(&matter_id, file_paths).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn matter_detach_document(
+    matter_id: String,
+    file_path: String,
+    matters: tauri::State<'_, MatterStorage>,
+) -> Result<Matter, String> {
+    matters.detach_document(&matter_id, &file_path).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn matter_close(matter_id: String, matters: tauri::State<'_, MatterStorage>) -> Result<Matter, String> {
+    matters.close(&matter_id).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matter(id: &str, client: &str, adverse: &[&str], status: MatterStatus) -> Matter {
+        Matter {
+            id: id.to_string(),
+            name: format!("Matter {}", id),
+            client: client.to_string(),
+            adverse_parties: adverse.iter().map(|s| s.to_string()).collect(),
+            related_parties: Vec::new(),
+            description: None,
+            status,
+            created_at: String::new(),
+            activated_at: None,
+            documents: Vec::new(),
+            matter_type: None,
+            jurisdiction: None,
+        }
+    }
+
+    #[test]
+    fn test_check_conflicts() {
+        let existing_active = matter("a", "Acme Corp.", &["Globex"], MatterStatus::Active);
+        let existing_closed = matter("b", "Initech LLC", &[], MatterStatus::Closed);
+        let new = matter("n", "Globex Inc", &["ACME Corporation", "Initech"], MatterStatus::Intake);
+
+        let result = check_conflicts(&new, &[&existing_active, &existing_closed, &new]);
+        assert_eq!(result.status, ConflictStatus::Conflict);
+        let types: Vec<ConflictType> = result.conflicts.iter().map(|c| c.conflict_type).collect();
+        assert_eq!(types.iter().filter(|t| **t == ConflictType::AdverseParty).count(), 2);
+        assert!(types.contains(&ConflictType::FormerClient));
+
+        let unrelated = matter("u", "Umbrella BV", &["Wayne Enterprises"], MatterStatus::Intake);
+        assert_eq!(check_conflicts(&unrelated, &[&existing_active, &unrelated]).status, ConflictStatus::Clear);
+    }
+
+    #[test]
+    fn test_intake_blockers() {
+        let mut workflow = IntakeWorkflow::default();
+        assert_eq!(intake_blockers(&workflow, None).len(), 2);
+
+        let new = matter("n", "Globex", &["Acme"], MatterStatus::Intake);
+        workflow.conflict_check = Some(check_conflicts(&new, &[&matter("a", "Acme", &[], MatterStatus::Active)]));
+        workflow.engagement_letter = Some(EngagementLetter {
+            document_id: "engagement:n".to_string(),
+            path: String::new(),
+            generated_at: String::new(),
+            pii_warnings: Vec::new(),
+        });
+        assert_eq!(
+            intake_blockers(&workflow, Some(ExecutionStatus::Sent)),
+            vec![
+                "Waiver required: Adverse party Acme is a current client in matter Matter a".to_string(),
+                "Engagement letter is not fully signed".to_string(),
+            ]
+        );
+
+        workflow.waivers.push(ConflictWaiver {
+            party: "Acme".to_string(),
+            waived_by: "Globex GC".to_string(),
+            consent_document: "waiver.pdf".to_string(),
+            recorded_at: String::new(),
+        });
+        assert!(intake_blockers(&workflow, Some(ExecutionStatus::FullyExecuted)).is_empty());
+    }
+
+    #[test]
+    fn test_activation_rechecks_conflicts() {
+        let dir = tempfile::tempdir().unwrap();
+        let registry = MatterRegistry::new(dir.path()).unwrap();
+        let matter = registry.create_intake("Supply dispute", "Globex Inc", vec!["Acme Corp".to_string()], Vec::new(), None).unwrap();
+        let details = EngagementDetails {
+            firm_name: "Firm LLP".to_string(),
+            responsible_attorney: "J. Doe".to_string(),
+            scope: "Litigation".to_string(),
+            fee_arrangement: "Hourly".to_string(),
+        };
+        registry.generate_engagement_letter(&matter.id, &details).unwrap();
+
+        // A matter for the adverse party opened after the first check blocks activation
+        registry.create_intake("Acme general", "Acme Corp", Vec::new(), Vec::new(), None).unwrap();
+        let error = registry.activate(&matter.id, Some(ExecutionStatus::FullyExecuted)).unwrap_err();
+        assert!(error.to_string().contains("Waiver required"), "{}", error);
+    }
+}
@@ -1,12 +1,21 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::Path;
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
+use tauri::Manager;
 use tokio::net::TcpListener;
 use tokio::sync::mpsc;
 use uuid::Uuid;
 
+use crate::coordination::{self, CoordinationRole, CoordinationStrategy, IntermediateOutput};
+use crate::request_tracing::{self, StageTimer};
+use crate::local_api::{authenticated_user, SessionStorage};
+use crate::security::{ActionOutcome, Permission, SecurityAction, SecurityManager};
+use crate::workflow_budget::{self, BudgetConsumption, BudgetExceeded, BudgetMeter, TokenBudget};
+use crate::workflow_packages::PromptTemplate;
+
 /// MCP Protocol Structures following Anthropic's MCP specification
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MCPRequest {
@@ -248,6 +257,9 @@ pub struct AgentResponse {
     pub follow_up_questions: Vec<String>,
     pub completion_time: chrono::DateTime<chrono::Utc>,
     pub status: TaskStatus,
+    /// Panel answers, debate rounds and the judge's verdict behind `output`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub intermediate_outputs: Vec<IntermediateOutput>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -266,15 +278,83 @@ pub struct WorkflowDefinition {
     pub description: String,
     pub steps: Vec<WorkflowStep>,
     pub legal_domain: LegalDomain,
+    #[serde(default)]
+    pub coordination: CoordinationStrategy,
+    #[serde(default)]
+    pub budget: TokenBudget,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorkflowStep {
     pub step_id: String,
-    pub agent_type: AgentType,
+    pub agent_type: AgentType, // ignored for approval steps
     pub input_mapping: HashMap<String, String>,
     pub dependencies: Vec<String>,
     pub timeout_seconds: u64,
+    #[serde(default)]
+    pub kind: StepKind,
+    #[serde(default)]
+    pub budget: TokenBudget,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StepKind {
+    #[default]
+    Agent,
+    /// Pause until a reviewer approves the output of the steps it depends on
+    Approval { instructions: Option<String> },
+}
+
+pub const WORKFLOW_REVIEW_EVENT: &str = "workflow-review-required";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum WorkflowRunStatus {
+    Running,
+    AwaitingApproval,
+    Completed,
+    Rejected,
+    BudgetExceeded,
+    /// A step failed; the error is kept on the run
+    Failed,
+}
+
+/// Emitted when a run reaches an approval step
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewRequest {
+    pub run_id: String,
+    pub workflow_id: String,
+    pub workflow_name: String,
+    pub step_id: String,
+    pub instructions: Option<String>,
+    pub outputs: HashMap<String, String>, // step id -> output under review
+    pub requested_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewDecision {
+    pub step_id: String,
+    pub reviewer: String,
+    pub approved: bool,
+    pub comment: Option<String>,
+    pub decided_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowRun {
+    pub run_id: String,
+    pub workflow_id: String,
+    pub status: WorkflowRunStatus,
+    pub input: HashMap<String, String>,
+    pub step_outputs: HashMap<String, String>,
+    pub results: HashMap<String, AgentResponse>,
+    pub pending_review: Option<ReviewRequest>,
+    pub reviews: Vec<ReviewDecision>,
+    #[serde(default)]
+    pub budget: BudgetConsumption,
+    #[serde(default)]
+    pub error: Option<String>,
+    pub started_at: chrono::DateTime<chrono::Utc>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -295,12 +375,21 @@ pub enum LegalDomain {
     General,
 }
 
+/// Port the MCP server listens on when started
+pub const DEFAULT_PORT: u16 = 3001;
+/// Completion cap per model call, lowered further by a workflow budget
+const MAX_COMPLETION_TOKENS: u64 = 2048;
+/// A budgeted call is not started unless at least this much output fits
+const MIN_COMPLETION_TOKENS: u64 = 64;
+
 #[derive(Debug)]
 pub struct MCPServer {
     agents: Arc<Mutex<HashMap<String, AgentDefinition>>>,
     workflows: Arc<Mutex<HashMap<String, WorkflowDefinition>>>,
     active_tasks: Arc<Mutex<HashMap<String, AgentTask>>>,
     task_results: Arc<Mutex<HashMap<String, AgentResponse>>>,
+    workflow_runs: Arc<Mutex<HashMap<String, WorkflowRun>>>,
+    runs_path: Option<PathBuf>,
     llm_manager: Arc<crate::llm_manager::LLMManager>,
     port: u16,
 }
@@ -380,11 +469,50 @@ impl MCPServer {
             workflows: Arc::new(Mutex::new(HashMap::new())),
             active_tasks: Arc::new(Mutex::new(HashMap::new())),
             task_results: Arc::new(Mutex::new(HashMap::new())),
+            workflow_runs: Arc::new(Mutex::new(HashMap::new())),
+            runs_path: None,
             llm_manager,
             port,
         }
     }
 
+    /// Keep workflow runs in the app data directory, so runs waiting on a reviewer survive a
+    /// restart. A run that was still executing when the app stopped cannot pick up where it
+    /// was and is marked failed.
+    pub fn with_run_storage(mut self, app_data_dir: &Path) -> Result<Self> {
+        let path = app_data_dir.join("workflow_runs.json");
+        let mut runs: HashMap<String, WorkflowRun> = if path.exists() {
+            serde_json::from_str(&fs::read_to_string(&path)?)?
+        } else {
+            HashMap::new()
+        };
+        for run in runs.values_mut().filter(|run| run.status == WorkflowRunStatus::Running) {
+            run.status = WorkflowRunStatus::Failed;
+            run.error = Some("Interrupted by an application restart".to_string());
+        }
+        self.workflow_runs = Arc::new(Mutex::new(runs));
+        self.runs_path = Some(path);
+        Ok(self)
+    }
+
+    fn persist_runs(&self, runs: &HashMap<String, WorkflowRun>) {
+        let Some(path) = &self.runs_path else {
+            return;
+        };
+        let written = serde_json::to_string_pretty(runs)
+            .map_err(anyhow::Error::from)
+            .and_then(|json| fs::write(path, json).map_err(anyhow::Error::from));
+        if let Err(e) = written {
+            log::error!("Failed to save workflow runs: {}", e);
+        }
+    }
+
+    fn store_run(&self, run: &WorkflowRun) {
+        let mut runs = self.workflow_runs.lock().unwrap();
+        runs.insert(run.run_id.clone(), run.clone());
+        self.persist_runs(&runs);
+    }
+
     /// Start the MCP server
     pub async fn start(&self) -> Result<()> {
         let listener = TcpListener::bind(format!("127.0.0.1:{}", self.port))
@@ -1221,12 +1349,28 @@ impl MCPServer {
             .filter_map(|(k, v)| v.as_str().map(|s| (k.clone(), s.to_string())))
             .collect();
 
-        let results = self.execute_workflow(workflow_id, input_map).await?;
+        let run = self.execute_workflow(workflow_id, input_map).await?;
 
         // Format workflow results
-        let mut output = format!("Workflow '{}' completed successfully:\n\n", workflow_id);
-        for (step_id, response) in results {
+        let mut output = match (&run.pending_review, &run.budget.exceeded) {
+            (Some(review), _) => format!(
+                "Workflow '{}' is waiting for approval of step '{}' (run {}):\n\n",
+                workflow_id, review.step_id, run.run_id
+            ),
+            (None, Some(exceeded)) => format!("Workflow '{}' stopped early. {}\n\n", workflow_id, exceeded),
+            (None, None) => format!("Workflow '{}' completed successfully:\n\n", workflow_id),
+        };
+        for (step_id, response) in run.results {
             output.push_str(&format!("Step '{}' (Agent: {}):\n", step_id, response.agent_id));
+            for entry in response.intermediate_outputs.iter().filter(|e| e.role != CoordinationRole::Verdict) {
+                output.push_str(&format!(
+                    "  [{:?}, round {}, {}]\n  {}\n",
+                    entry.role,
+                    entry.round,
+                    entry.agent_id.as_deref().unwrap_or("judge"),
+                    entry.output.trim().replace('\n', "\n  ")
+                ));
+            }
             output.push_str(&format!("{}\n\n", response.output));
         }
 
@@ -1235,6 +1379,10 @@ impl MCPServer {
 
     /// Execute an agent task
     pub async fn execute_task(&self, task: AgentTask) -> Result<AgentResponse> {
+        request_tracing::in_trace("agent_task", None, self.execute_task_traced(task)).await
+    }
+
+    async fn execute_task_traced(&self, task: AgentTask) -> Result<AgentResponse> {
         let agents = self.agents.lock().unwrap();
         let agent = agents
             .get(&task.agent_id)
@@ -1250,8 +1398,14 @@ impl MCPServer {
             active_tasks.insert(task.task_id.clone(), task.clone());
         }
 
-        // Prepare the prompt
-        let prompt = agent.prompt_template.replace("{input}", &task.input);
+        // Prepare the prompt, with the challenger template if the task is sampled into an A/B experiment
+        let tracker = crate::performance_tracker::get_performance_tracker();
+        let assignment = match &tracker {
+            Some(tracker) => tracker.assign_prompt_variant(&agent.id).await,
+            None => None,
+        };
+        let template = assignment.as_ref().map(|(_, t)| t.as_str()).unwrap_or(&agent.prompt_template);
+        let prompt = template.replace("{input}", &task.input);
 
         // Execute with the assigned model
         let default_model = "phi3-mini-legal".to_string();
@@ -1260,9 +1414,23 @@ impl MCPServer {
             .as_ref()
             .unwrap_or(&default_model);
 
-        let response_text = self.execute_with_model(model_id, &prompt).await?;
+        let started = std::time::Instant::now();
+        let result = self.execute_with_model(model_id, &prompt).await;
+        if let Some(tracker) = &tracker {
+            tracker
+                .record_agent_task(
+                    &task.task_id,
+                    &agent.id,
+                    started.elapsed().as_millis() as u64,
+                    result.is_ok(),
+                    assignment.map(|(a, _)| a),
+                )
+                .await;
+        }
+        let response_text = result?;
 
         // Create response
+        let _post_processing = StageTimer::start("mcp_server", "post_processing");
         let response = AgentResponse {
             task_id: task.task_id.clone(),
             agent_id: agent.id,
@@ -1273,6 +1441,7 @@ impl MCPServer {
             follow_up_questions: self.generate_follow_up_questions(&response_text, &task.input),
             completion_time: chrono::Utc::now(),
             status: TaskStatus::Completed,
+            intermediate_outputs: Vec::new(),
         };
 
         // Store result and remove from active tasks
@@ -1289,29 +1458,66 @@ impl MCPServer {
         Ok(response)
     }
 
-    /// Execute a workflow
+    /// Execute a workflow. The run pauses at the first approval step until a reviewer decides.
     pub async fn execute_workflow(
         &self,
         workflow_id: &str,
         input: HashMap<String, String>,
-    ) -> Result<HashMap<String, AgentResponse>> {
-        let workflows = self.workflows.lock().unwrap();
-        let workflow = workflows
-            .get(workflow_id)
-            .context("Workflow not found")?
-            .clone();
-        drop(workflows);
+    ) -> Result<WorkflowRun> {
+        let run = WorkflowRun {
+            run_id: Uuid::new_v4().to_string(),
+            workflow_id: workflow_id.to_string(),
+            status: WorkflowRunStatus::Running,
+            input,
+            step_outputs: HashMap::new(),
+            results: HashMap::new(),
+            pending_review: None,
+            reviews: Vec::new(),
+            budget: BudgetConsumption::default(),
+            error: None,
+            started_at: chrono::Utc::now(),
+        };
+        // All steps of a workflow share one trace
+        request_tracing::in_trace("workflow", None, self.run_workflow_steps(run)).await
+    }
 
-        log::info!("Executing workflow: {}", workflow.name);
+    /// Run the steps that have not run yet, stopping at an undecided approval step. A stored run
+    /// that fails, such as one resumed after an approval, is kept as failed.
+    async fn run_workflow_steps(&self, run: WorkflowRun) -> Result<WorkflowRun> {
+        let run_id = run.run_id.clone();
+        let result = self.advance_run(run).await;
+        if let Err(e) = &result {
+            let mut runs = self.workflow_runs.lock().unwrap();
+            if let Some(run) = runs.get_mut(&run_id) {
+                run.status = WorkflowRunStatus::Failed;
+                run.error = Some(e.to_string());
+                self.persist_runs(&runs);
+            }
+        }
+        result
+    }
+
+    async fn advance_run(&self, mut run: WorkflowRun) -> Result<WorkflowRun> {
+        let workflow = self
+            .workflows
+            .lock()
+            .unwrap()
+            .get(&run.workflow_id)
+            .cloned()
+            .context("Workflow not found")?;
 
-        let mut results = HashMap::new();
-        let mut step_outputs = HashMap::new();
+        log::info!("Executing workflow: {}", workflow.name);
+        let meter = BudgetMeter::new(workflow.budget.clone(), run.budget.clone());
 
         // Execute workflow steps
         for step in &workflow.steps {
+            if run.step_outputs.contains_key(&step.step_id) {
+                continue;
+            }
+
             // Check dependencies
             for dep in &step.dependencies {
-                if !step_outputs.contains_key(dep) {
+                if !run.step_outputs.contains_key(dep) {
                     return Err(anyhow::anyhow!(
                         "Dependency {} not satisfied for step {}",
                         dep,
@@ -1320,57 +1526,323 @@ impl MCPServer {
                 }
             }
 
+            if let StepKind::Approval { instructions } = &step.kind {
+                // Reviewers see the outputs the gate depends on, or everything so far
+                let outputs = run
+                    .step_outputs
+                    .iter()
+                    .filter(|(id, _)| step.dependencies.is_empty() || step.dependencies.contains(id))
+                    .map(|(id, output)| (id.clone(), output.clone()))
+                    .collect();
+                run.status = WorkflowRunStatus::AwaitingApproval;
+                run.pending_review = Some(ReviewRequest {
+                    run_id: run.run_id.clone(),
+                    workflow_id: workflow.id.clone(),
+                    workflow_name: workflow.name.clone(),
+                    step_id: step.step_id.clone(),
+                    instructions: instructions.clone(),
+                    outputs,
+                    requested_at: chrono::Utc::now(),
+                });
+                log::info!("Workflow {} paused for approval at step {}", workflow.name, step.step_id);
+                self.store_run(&run);
+                return Ok(run);
+            }
+
             // Prepare input for this step
             let mut step_input = String::new();
             for (key, mapping) in &step.input_mapping {
-                if let Some(value) = input.get(key) {
+                if let Some(value) = run.input.get(key) {
                     step_input.push_str(&format!("{}: {}\n", key, value));
-                } else if let Some(prev_output) = step_outputs.get(key) {
+                } else if let Some(prev_output) = run.step_outputs.get(key) {
                     step_input.push_str(&format!("{}: {}\n", key, prev_output));
                 }
             }
 
-            // Find agent for this step
-            let agents = self.agents.lock().unwrap();
-            let agent = agents
-                .values()
-                .find(|a| a.agent_type == step.agent_type)
-                .context("No agent found for step")?
-                .clone();
-            drop(agents);
-
-            // Create and execute task
-            let task = AgentTask {
-                task_id: Uuid::new_v4().to_string(),
-                agent_id: agent.id,
-                input: step_input,
-                context: input.clone(),
-                priority: TaskPriority::Normal,
-                created_at: chrono::Utc::now(),
-                deadline: Some(
-                    chrono::Utc::now() + chrono::Duration::seconds(step.timeout_seconds as i64),
-                ),
+            // Find agent for this step and run it under the workflow's coordination strategy
+            let agent = self.agent_for(&step.agent_type).context("No agent found for step")?;
+            let deadline = chrono::Utc::now() + chrono::Duration::seconds(step.timeout_seconds as i64);
+
+            // Skip a step whose model calls cannot fit the remaining budget even before output
+            meter.begin_step(&step.step_id, step.budget.clone());
+            let model_id = agent.model_id.clone().unwrap_or_else(|| "phi3-mini-legal".to_string());
+            let estimate = self.count_tokens(&model_id, &step_input).await
+                * coordination::model_calls(&workflow.coordination);
+            let result = match meter.check(estimate) {
+                Ok(_) => {
+                    workflow_budget::metered(
+                        meter.clone(),
+                        self.execute_step(&workflow.coordination, &agent, &step_input, &run.input, deadline),
+                    )
+                    .await
+                }
+                Err(exceeded) => Err(exceeded.into()),
             };
-
-            let response = self.execute_task(task).await?;
-            step_outputs.insert(step.step_id.clone(), response.output.clone());
-            results.insert(step.step_id.clone(), response);
+            run.budget = meter.consumption();
+
+            let response = match result {
+                Ok(response) => response,
+                Err(e) if e.downcast_ref::<BudgetExceeded>().is_some() => {
+                    log::warn!("Workflow {} stopped: {}", workflow.name, e);
+                    run.status = WorkflowRunStatus::BudgetExceeded;
+                    self.store_run(&run);
+                    return Ok(run);
+                }
+                Err(e) => return Err(e),
+            };
+            run.step_outputs.insert(step.step_id.clone(), response.output.clone());
+            run.results.insert(step.step_id.clone(), response);
         }
 
+        run.status = WorkflowRunStatus::Completed;
+        self.store_run(&run);
         log::info!("Workflow {} completed successfully", workflow.name);
-        Ok(results)
+        Ok(run)
+    }
+
+    /// Record a reviewer's decision on the approval step a run is waiting on
+    fn decide_step(&self, run_id: &str, step_id: &str, reviewer: &str, approved: bool, comment: Option<String>) -> Result<WorkflowRun> {
+        if reviewer.trim().is_empty() {
+            return Err(anyhow::anyhow!("A reviewer is required"));
+        }
+        let mut runs = self.workflow_runs.lock().unwrap();
+        let run = runs.get_mut(run_id).context("Workflow run not found")?;
+        match &run.pending_review {
+            Some(review) if review.step_id == step_id => {}
+            Some(review) => {
+                return Err(anyhow::anyhow!("Run {} is waiting on step {}, not {}", run_id, review.step_id, step_id))
+            }
+            None => return Err(anyhow::anyhow!("Run {} is not waiting for approval", run_id)),
+        }
+
+        run.reviews.push(ReviewDecision {
+            step_id: step_id.to_string(),
+            reviewer: reviewer.trim().to_string(),
+            approved,
+            comment: comment.clone(),
+            decided_at: chrono::Utc::now(),
+        });
+        run.pending_review = None;
+        if approved {
+            // Later steps can map the approval note as their input
+            let note = comment.unwrap_or_else(|| format!("Approved by {}", reviewer.trim()));
+            run.step_outputs.insert(step_id.to_string(), note);
+            run.status = WorkflowRunStatus::Running;
+        } else {
+            run.status = WorkflowRunStatus::Rejected;
+        }
+        let decided = run.clone();
+        self.persist_runs(&runs);
+        Ok(decided)
+    }
+
+    /// Runs waiting on a reviewer
+    pub fn pending_reviews(&self) -> Vec<ReviewRequest> {
+        self.workflow_runs
+            .lock()
+            .unwrap()
+            .values()
+            .filter_map(|run| run.pending_review.clone())
+            .collect()
+    }
+
+    fn agent_for(&self, agent_type: &AgentType) -> Result<AgentDefinition> {
+        self.agents
+            .lock()
+            .unwrap()
+            .values()
+            .find(|a| &a.agent_type == agent_type)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("No agent registered for {:?}", agent_type))
+    }
+
+    fn task_for(
+        agent: &AgentDefinition,
+        input: String,
+        context: &HashMap<String, String>,
+        deadline: chrono::DateTime<chrono::Utc>,
+    ) -> AgentTask {
+        AgentTask {
+            task_id: Uuid::new_v4().to_string(),
+            agent_id: agent.id.clone(),
+            input,
+            context: context.clone(),
+            priority: TaskPriority::Normal,
+            created_at: chrono::Utc::now(),
+            deadline: Some(deadline),
+        }
+    }
+
+    /// Run one workflow step under a coordination strategy
+    async fn execute_step(
+        &self,
+        strategy: &CoordinationStrategy,
+        agent: &AgentDefinition,
+        step_input: &str,
+        context: &HashMap<String, String>,
+        deadline: chrono::DateTime<chrono::Utc>,
+    ) -> Result<AgentResponse> {
+        match strategy {
+            CoordinationStrategy::Hierarchical => {
+                self.execute_task(Self::task_for(agent, step_input.to_string(), context, deadline)).await
+            }
+            CoordinationStrategy::ParallelConsensus { panel, judge_model } => {
+                let mut members = vec![agent.clone()];
+                for agent_type in panel {
+                    members.push(self.agent_for(agent_type)?);
+                }
+
+                // Panel members answer independently and concurrently; every call runs to its end, so
+                // the usage of all of them is recorded before a failure ends the step
+                let answers = futures::future::join_all(members.iter().map(|member| {
+                    self.execute_task(Self::task_for(member, step_input.to_string(), context, deadline))
+                }))
+                .await
+                .into_iter()
+                .collect::<Result<Vec<_>>>()?;
+                let intermediate: Vec<IntermediateOutput> = answers
+                    .iter()
+                    .zip(&members)
+                    .map(|(answer, member)| IntermediateOutput {
+                        role: CoordinationRole::PanelAnswer,
+                        agent_id: Some(answer.agent_id.clone()),
+                        model_id: member.model_id.clone(),
+                        round: 0,
+                        output: answer.output.clone(),
+                    })
+                    .collect();
+
+                let judge_prompt = coordination::consensus_prompt(step_input, &intermediate);
+                let reasoning = format!("Reconciled {} independent agent answers", answers.len());
+                let model_id = Self::judge_model_for(agent, judge_model);
+                self.judge(&model_id, &judge_prompt, step_input, answers[0].clone(), intermediate, reasoning)
+                    .await
+            }
+            CoordinationStrategy::Debate { challenger, rounds, judge_model } => {
+                strategy.validate()?;
+                let challenger = self.agent_for(challenger)?;
+
+                let opening = self
+                    .execute_task(Self::task_for(agent, step_input.to_string(), context, deadline))
+                    .await?;
+                let mut position = opening.output.clone();
+                let mut transcript = vec![IntermediateOutput {
+                    role: CoordinationRole::Proposal,
+                    agent_id: Some(agent.id.clone()),
+                    model_id: agent.model_id.clone(),
+                    round: 0,
+                    output: position.clone(),
+                }];
+
+                for round in 1..=*rounds {
+                    let critique = self
+                        .execute_task(Self::task_for(
+                            &challenger,
+                            coordination::challenge_input(step_input, &position),
+                            context,
+                            deadline,
+                        ))
+                        .await?
+                        .output;
+                    let revision = self
+                        .execute_task(Self::task_for(
+                            agent,
+                            coordination::revision_input(step_input, &position, &critique),
+                            context,
+                            deadline,
+                        ))
+                        .await?
+                        .output;
+                    transcript.push(IntermediateOutput {
+                        role: CoordinationRole::Challenge,
+                        agent_id: Some(challenger.id.clone()),
+                        model_id: challenger.model_id.clone(),
+                        round,
+                        output: critique,
+                    });
+                    transcript.push(IntermediateOutput {
+                        role: CoordinationRole::Revision,
+                        agent_id: Some(agent.id.clone()),
+                        model_id: agent.model_id.clone(),
+                        round,
+                        output: revision.clone(),
+                    });
+                    position = revision;
+                }
+
+                let judge_prompt = coordination::debate_verdict_prompt(step_input, &transcript);
+                let reasoning = format!("Judged a {}-round debate with {}", rounds, challenger.name);
+                let model_id = Self::judge_model_for(agent, judge_model);
+                self.judge(&model_id, &judge_prompt, step_input, opening, transcript, reasoning)
+                    .await
+            }
+        }
+    }
+
+    /// The judge runs on the configured model, else on the step agent's model
+    fn judge_model_for(agent: &AgentDefinition, judge_model: &Option<String>) -> String {
+        judge_model
+            .clone()
+            .or_else(|| agent.model_id.clone())
+            .unwrap_or_else(|| "phi3-mini-legal".to_string())
+    }
+
+    /// Ask the judge model for the final answer and attach the intermediate outputs to it
+    async fn judge(
+        &self,
+        model_id: &str,
+        judge_prompt: &str,
+        step_input: &str,
+        base: AgentResponse,
+        mut intermediate: Vec<IntermediateOutput>,
+        reasoning: String,
+    ) -> Result<AgentResponse> {
+        let verdict = self.execute_with_model(model_id, judge_prompt).await?;
+
+        intermediate.push(IntermediateOutput {
+            role: CoordinationRole::Verdict,
+            agent_id: None,
+            model_id: Some(model_id.to_string()),
+            round: 0,
+            output: verdict.clone(),
+        });
+        Ok(AgentResponse {
+            confidence: self.calculate_confidence(&verdict, step_input),
+            reasoning,
+            citations: self.extract_citations(&verdict),
+            follow_up_questions: self.generate_follow_up_questions(&verdict, step_input),
+            completion_time: chrono::Utc::now(),
+            output: verdict,
+            intermediate_outputs: intermediate,
+            ..base
+        })
     }
 
     /// Execute prompt with a specific model
     async fn execute_with_model(&self, model_id: &str, prompt: &str) -> Result<String> {
         // Load the model if not already loaded
-        let endpoint = self.llm_manager.load_model(model_id).await?;
+        let endpoint = {
+            let _model_load = StageTimer::start("mcp_server", "model_load");
+            self.llm_manager.load_model(model_id).await?
+        };
+
+        // Inside a budgeted workflow step, reserve the prompt and a completion capped to what the
+        // budget has left until the call's usage is known
+        let mut max_tokens = MAX_COMPLETION_TOKENS;
+        let mut prompt_tokens = 0;
+        let mut reservation = None;
+        if let Some(meter) = workflow_budget::current_meter() {
+            prompt_tokens = self.count_tokens_at(&endpoint, prompt).await;
+            let reserved = meter.authorize(prompt_tokens, MIN_COMPLETION_TOKENS, MAX_COMPLETION_TOKENS)?;
+            max_tokens = reserved.completion_tokens;
+            reservation = Some(reserved);
+        }
 
         // Make API call to the local model
         let client = reqwest::Client::new();
         let request_body = serde_json::json!({
             "prompt": prompt,
-            "max_tokens": 2048,
+            "max_tokens": max_tokens,
             "temperature": 0.1,
             "stop": ["</s>", "[INST]", "[/INST]"]
         });
@@ -1387,14 +1859,54 @@ impl MCPServer {
             .await
             .context("Failed to parse model response")?;
 
+        // llama-server reports its own prompt/decode split in milliseconds
+        let timings = &response_json["timings"];
+        if let (Some(prompt_ms), Some(predicted_ms)) = (timings["prompt_ms"].as_f64(), timings["predicted_ms"].as_f64()) {
+            request_tracing::record_model_timings("mcp_server", prompt_ms as u64, predicted_ms as u64);
+        }
+
         let text = response_json["choices"][0]["text"]
             .as_str()
             .unwrap_or("No response generated")
             .to_string();
 
+        if let Some(reservation) = reservation {
+            let usage = &response_json["usage"];
+            reservation.record(
+                usage["prompt_tokens"].as_u64().unwrap_or(prompt_tokens),
+                usage["completion_tokens"]
+                    .as_u64()
+                    .unwrap_or_else(|| workflow_budget::estimate_tokens(&text)),
+            );
+        }
+
         Ok(text)
     }
 
+    /// Count tokens with the model's own tokenizer
+    async fn count_tokens(&self, model_id: &str, text: &str) -> u64 {
+        match self.llm_manager.load_model(model_id).await {
+            Ok(endpoint) => self.count_tokens_at(&endpoint, text).await,
+            Err(_) => workflow_budget::estimate_tokens(text),
+        }
+    }
+
+    /// llama-server's /tokenize endpoint, falling back to an estimate
+    async fn count_tokens_at(&self, endpoint: &str, text: &str) -> u64 {
+        let response = reqwest::Client::new()
+            .post(format!("{}/tokenize", endpoint))
+            .json(&serde_json::json!({ "content": text }))
+            .send()
+            .await;
+        let tokens = match response {
+            Ok(response) => response.json::<serde_json::Value>().await.ok(),
+            Err(_) => None,
+        };
+        tokens
+            .and_then(|json| json["tokens"].as_array().map(|tokens| tokens.len() as u64))
+            .unwrap_or_else(|| workflow_budget::estimate_tokens(text))
+    }
+
     /// Initialize default workflows
     async fn initialize_workflows(&self) -> Result<()> {
         let mut workflows = self.workflows.lock().unwrap();
@@ -1407,6 +1919,8 @@ impl MCPServer {
                 name: "Comprehensive Contract Review".to_string(),
                 description: "Multi-agent workflow for thorough contract analysis".to_string(),
                 legal_domain: LegalDomain::ContractLaw,
+                coordination: CoordinationStrategy::Hierarchical,
+                budget: TokenBudget::default(),
                 steps: vec![
                     WorkflowStep {
                         step_id: "initial_analysis".to_string(),
@@ -1417,6 +1931,8 @@ impl MCPServer {
                         )]),
                         dependencies: Vec::new(),
                         timeout_seconds: 300,
+                        kind: StepKind::Agent,
+                        budget: TokenBudget::default(),
                     },
                     WorkflowStep {
                         step_id: "risk_assessment".to_string(),
@@ -1427,6 +1943,8 @@ impl MCPServer {
                         )]),
                         dependencies: vec!["initial_analysis".to_string()],
                         timeout_seconds: 240,
+                        kind: StepKind::Agent,
+                        budget: TokenBudget::default(),
                     },
                     WorkflowStep {
                         step_id: "compliance_check".to_string(),
@@ -1437,6 +1955,8 @@ impl MCPServer {
                         )]),
                         dependencies: vec!["initial_analysis".to_string()],
                         timeout_seconds: 180,
+                        kind: StepKind::Agent,
+                        budget: TokenBudget::default(),
                     },
                 ],
             },
@@ -1450,6 +1970,8 @@ impl MCPServer {
                 name: "Comprehensive Legal Research".to_string(),
                 description: "Multi-agent workflow for in-depth legal research".to_string(),
                 legal_domain: LegalDomain::General,
+                coordination: CoordinationStrategy::Hierarchical,
+                budget: TokenBudget::default(),
                 steps: vec![
                     WorkflowStep {
                         step_id: "case_research".to_string(),
@@ -1460,6 +1982,8 @@ impl MCPServer {
                         )]),
                         dependencies: Vec::new(),
                         timeout_seconds: 600,
+                        kind: StepKind::Agent,
+                        budget: TokenBudget::default(),
                     },
                     WorkflowStep {
                         step_id: "risk_analysis".to_string(),
@@ -1470,6 +1994,8 @@ impl MCPServer {
                         )]),
                         dependencies: vec!["case_research".to_string()],
                         timeout_seconds: 300,
+                        kind: StepKind::Agent,
+                        budget: TokenBudget::default(),
                     },
                 ],
             },
@@ -1483,6 +2009,8 @@ impl MCPServer {
                 name: "Legal Due Diligence".to_string(),
                 description: "Comprehensive legal due diligence workflow for transactions".to_string(),
                 legal_domain: LegalDomain::CorporateLaw,
+                coordination: CoordinationStrategy::Hierarchical,
+                budget: TokenBudget::default(),
                 steps: vec![
                     WorkflowStep {
                         step_id: "document_review".to_string(),
@@ -1493,6 +2021,8 @@ impl MCPServer {
                         )]),
                         dependencies: Vec::new(),
                         timeout_seconds: 900,
+                        kind: StepKind::Agent,
+                        budget: TokenBudget::default(),
                     },
                     WorkflowStep {
                         step_id: "risk_assessment".to_string(),
@@ -1503,6 +2033,8 @@ impl MCPServer {
                         )]),
                         dependencies: vec!["document_review".to_string()],
                         timeout_seconds: 600,
+                        kind: StepKind::Agent,
+                        budget: TokenBudget::default(),
                     },
                     WorkflowStep {
                         step_id: "compliance_verification".to_string(),
@@ -1513,6 +2045,8 @@ impl MCPServer {
                         )]),
                         dependencies: vec!["document_review".to_string()],
                         timeout_seconds: 480,
+                        kind: StepKind::Agent,
+                        budget: TokenBudget::default(),
                     },
                     WorkflowStep {
                         step_id: "legal_research".to_string(),
@@ -1523,6 +2057,8 @@ impl MCPServer {
                         )]),
                         dependencies: vec!["risk_assessment".to_string(), "compliance_verification".to_string()],
                         timeout_seconds: 720,
+                        kind: StepKind::Agent,
+                        budget: TokenBudget::default(),
                     },
                 ],
             },
@@ -1537,11 +2073,112 @@ impl MCPServer {
         self.agents.lock().unwrap().values().cloned().collect()
     }
 
+    /// A/B test a new prompt template against the agent's current one on a sample of its tasks
+    pub async fn start_prompt_experiment(
+        &self,
+        name: String,
+        agent_id: &str,
+        template_b: String,
+        sample_rate: f32,
+        min_rated_trials: usize,
+    ) -> Result<crate::performance_tracker::PromptExperiment> {
+        let template_a = self
+            .agents
+            .lock()
+            .unwrap()
+            .get(agent_id)
+            .map(|agent| agent.prompt_template.clone())
+            .context("Agent not found")?;
+        let tracker = crate::performance_tracker::get_performance_tracker()
+            .context("Performance tracker not initialized")?;
+        tracker
+            .start_prompt_experiment(name, agent_id, template_a, template_b, sample_rate, min_rated_trials)
+            .await
+    }
+
     /// Get available workflows
     pub fn get_workflows(&self) -> Vec<WorkflowDefinition> {
         self.workflows.lock().unwrap().values().cloned().collect()
     }
 
+    /// Register the workflows of an installed package, and make each of its prompt templates
+    /// the prompt of the agent serving the template's agent type
+    pub fn install_package(&self, workflows: &[WorkflowDefinition], templates: &[PromptTemplate]) {
+        let mut registered = self.workflows.lock().unwrap();
+        for workflow in workflows {
+            registered.insert(workflow.id.clone(), workflow.clone());
+        }
+
+        let mut agents = self.agents.lock().unwrap();
+        for template in templates {
+            match agents.values_mut().find(|a| a.agent_type == template.agent_type) {
+                Some(agent) => {
+                    agent.prompt_template = template.template.clone();
+                    if template.model_id.is_some() {
+                        agent.model_id = template.model_id.clone();
+                    }
+                }
+                None => {
+                    agents.insert(template.id.clone(), AgentDefinition {
+                        id: template.id.clone(),
+                        name: template.name.clone(),
+                        agent_type: template.agent_type.clone(),
+                        capabilities: Vec::new(),
+                        prompt_template: template.template.clone(),
+                        model_id: template.model_id.clone(),
+                        memory_limit: 4096,
+                        max_iterations: 3,
+                    });
+                }
+            }
+        }
+    }
+
+    /// Choose how the agents of a workflow coordinate
+    pub fn set_workflow_coordination(&self, workflow_id: &str, strategy: CoordinationStrategy) -> Result<WorkflowDefinition> {
+        strategy.validate()?;
+        let agents = self.get_agents();
+        let required = match &strategy {
+            CoordinationStrategy::Hierarchical => Vec::new(),
+            CoordinationStrategy::ParallelConsensus { panel, .. } => panel.clone(),
+            CoordinationStrategy::Debate { challenger, .. } => vec![challenger.clone()],
+        };
+        if let Some(missing) = required.iter().find(|t| !agents.iter().any(|a| &a.agent_type == *t)) {
+            return Err(anyhow::anyhow!("No agent registered for {:?}", missing));
+        }
+
+        let mut workflows = self.workflows.lock().unwrap();
+        let workflow = workflows.get_mut(workflow_id).context("Workflow not found")?;
+        workflow.coordination = strategy;
+        Ok(workflow.clone())
+    }
+
+    /// Set the token budget of a workflow and, optionally, of its steps
+    pub fn set_workflow_budget(
+        &self,
+        workflow_id: &str,
+        budget: TokenBudget,
+        step_budgets: HashMap<String, TokenBudget>,
+    ) -> Result<WorkflowDefinition> {
+        budget.validate()?;
+        for step_budget in step_budgets.values() {
+            step_budget.validate()?;
+        }
+
+        let mut workflows = self.workflows.lock().unwrap();
+        let workflow = workflows.get_mut(workflow_id).context("Workflow not found")?;
+        if let Some(unknown) = step_budgets.keys().find(|id| !workflow.steps.iter().any(|s| &s.step_id == *id)) {
+            return Err(anyhow::anyhow!("Workflow {} has no step {}", workflow_id, unknown));
+        }
+        workflow.budget = budget;
+        for step in &mut workflow.steps {
+            if let Some(step_budget) = step_budgets.get(&step.step_id) {
+                step.budget = step_budget.clone();
+            }
+        }
+        Ok(workflow.clone())
+    }
+
     /// Get task status
     pub fn get_task_status(&self, task_id: &str) -> Option<TaskStatus> {
         if let Some(result) = self.task_results.lock().unwrap().get(task_id) {
@@ -1738,6 +2375,8 @@ impl Clone for MCPServer {
             workflows: self.workflows.clone(),
             active_tasks: self.active_tasks.clone(),
             task_results: self.task_results.clone(),
+            workflow_runs: self.workflow_runs.clone(),
+            runs_path: self.runs_path.clone(),
             llm_manager: self.llm_manager.clone(),
             port: self.port,
         }
@@ -1767,14 +2406,102 @@ pub async fn execute_agent_task(
 
 #[tauri::command]
 pub async fn execute_workflow(
+    app: tauri::AppHandle,
     server: tauri::State<'_, Arc<MCPServer>>,
     workflow_id: String,
     input: HashMap<String, String>,
-) -> Result<HashMap<String, AgentResponse>, String> {
-    server
+) -> Result<WorkflowRun, String> {
+    let run = server
         .execute_workflow(&workflow_id, input)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+    announce_review(&app, &run);
+    Ok(run)
+}
+
+fn announce_review(app: &tauri::AppHandle, run: &WorkflowRun) {
+    if let Some(review) = &run.pending_review {
+        let _ = app.emit_all(WORKFLOW_REVIEW_EVENT, review);
+    }
+}
+
+fn audit_review(security: &Mutex<SecurityManager>, run: &WorkflowRun, step_id: &str) {
+    let Some(decision) = run.reviews.iter().rev().find(|d| d.step_id == step_id) else {
+        return;
+    };
+    let mut details = HashMap::new();
+    details.insert("workflow".to_string(), run.workflow_id.clone());
+    details.insert("reviewer".to_string(), decision.reviewer.clone());
+    details.insert("decision".to_string(), if decision.approved { "approved" } else { "rejected" }.to_string());
+    details.insert("comment".to_string(), decision.comment.clone().unwrap_or_default());
+    let outcome = if decision.approved { ActionOutcome::Success } else { ActionOutcome::Blocked };
+    let resource = format!("workflow_run/{}/{}", run.run_id, step_id);
+    if let Err(e) = security
+        .lock()
+        .unwrap()
+        .write_audit_entry(SecurityAction::AgentExecution, &resource, outcome, Some(details))
+    {
+        log::error!("Failed to audit workflow review: {}", e);
+    }
+}
+
+/// The signed-in user deciding on a workflow step; the decision is recorded under this name
+fn reviewer_for(session_id: &str, sessions: &SessionStorage, security: &Mutex<SecurityManager>) -> Result<String, String> {
+    let user = authenticated_user(session_id, sessions)?;
+    if !security.lock().unwrap().check_permission(&user, Permission::WorkflowExecution) {
+        return Err("Reviewing workflow steps needs permission to run workflows".to_string());
+    }
+    Ok(user)
+}
+
+#[tauri::command]
+pub async fn approve_workflow_step(
+    app: tauri::AppHandle,
+    server: tauri::State<'_, Arc<MCPServer>>,
+    sessions: tauri::State<'_, SessionStorage>,
+    security: tauri::State<'_, Arc<Mutex<SecurityManager>>>,
+    session_id: String,
+    run_id: String,
+    step_id: String,
+    comment: Option<String>,
+) -> Result<WorkflowRun, String> {
+    let reviewer = reviewer_for(&session_id, &sessions, &security)?;
+    // Audit the approval before resuming, so it is recorded even if a later step fails
+    let approved = server
+        .decide_step(&run_id, &step_id, &reviewer, true, comment)
+        .map_err(|e| e.to_string())?;
+    audit_review(&security, &approved, &step_id);
+
+    let run = request_tracing::in_trace("workflow", None, server.run_workflow_steps(approved))
+        .await
+        .map_err(|e| e.to_string())?;
+    announce_review(&app, &run);
+    Ok(run)
+}
+
+#[tauri::command]
+pub async fn reject_workflow_step(
+    server: tauri::State<'_, Arc<MCPServer>>,
+    sessions: tauri::State<'_, SessionStorage>,
+    security: tauri::State<'_, Arc<Mutex<SecurityManager>>>,
+    session_id: String,
+    run_id: String,
+    step_id: String,
+    reason: Option<String>,
+) -> Result<WorkflowRun, String> {
+    let reviewer = reviewer_for(&session_id, &sessions, &security)?;
+    let run = server
+        .decide_step(&run_id, &step_id, &reviewer, false, reason)
+        .map_err(|e| e.to_string())?;
+    audit_review(&security, &run, &step_id);
+    Ok(run)
+}
+
+#[tauri::command]
+pub async fn get_pending_workflow_reviews(
+    server: tauri::State<'_, Arc<MCPServer>>,
+) -> Result<Vec<ReviewRequest>, String> {
+    Ok(server.pending_reviews())
 }
 
 #[tauri::command]
@@ -1791,6 +2518,29 @@ pub async fn get_available_workflows(
     Ok(server.get_workflows())
 }
 
+#[tauri::command]
+pub async fn set_workflow_coordination(
+    server: tauri::State<'_, Arc<MCPServer>>,
+    workflow_id: String,
+    strategy: CoordinationStrategy,
+) -> Result<WorkflowDefinition, String> {
+    server
+        .set_workflow_coordination(&workflow_id, strategy)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn set_workflow_budget(
+    server: tauri::State<'_, Arc<MCPServer>>,
+    workflow_id: String,
+    budget: TokenBudget,
+    step_budgets: Option<HashMap<String, TokenBudget>>,
+) -> Result<WorkflowDefinition, String> {
+    server
+        .set_workflow_budget(&workflow_id, budget, step_budgets.unwrap_or_default())
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn get_task_status(
     server: tauri::State<'_, Arc<MCPServer>>,
@@ -1806,3 +2556,177 @@ pub async fn get_task_result(
 ) -> Result<Option<AgentResponse>, String> {
     Ok(server.get_task_result(&task_id))
 }
+
+#[tauri::command]
+pub async fn start_prompt_experiment(
+    server: tauri::State<'_, Arc<MCPServer>>,
+    name: String,
+    agent_id: String,
+    template: String,
+    sample_rate: f32,
+    min_rated_trials: Option<usize>,
+) -> Result<crate::performance_tracker::PromptExperiment, String> {
+    server
+        .start_prompt_experiment(name, &agent_id, template, sample_rate, min_rated_trials.unwrap_or(10))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn stop_prompt_experiment(
+    experiment_id: String,
+) -> Result<crate::performance_tracker::ExperimentReport, String> {
+    let tracker = crate::performance_tracker::get_performance_tracker()
+        .ok_or_else(|| "Performance tracker not initialized".to_string())?;
+    tracker.stop_prompt_experiment(&experiment_id).await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_prompt_experiment_reports() -> Result<Vec<crate::performance_tracker::ExperimentReport>, String> {
+    let tracker = crate::performance_tracker::get_performance_tracker()
+        .ok_or_else(|| "Performance tracker not initialized".to_string())?;
+    Ok(tracker.get_experiment_reports().await)
+}
+
+#[tauri::command]
+pub async fn submit_agent_feedback(
+    task_id: String,
+    score: u8,
+    revision_requested: bool,
+) -> Result<(), String> {
+    let tracker = crate::performance_tracker::get_performance_tracker()
+        .ok_or_else(|| "Performance tracker not initialized".to_string())?;
+    tracker
+        .record_agent_feedback(&task_id, score, revision_requested)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_agent_performance_metrics() -> Result<Vec<crate::performance_tracker::AgentPerformanceMetrics>, String> {
+    let tracker = crate::performance_tracker::get_performance_tracker()
+        .ok_or_else(|| "Performance tracker not initialized".to_string())?;
+    Ok(tracker.get_agent_metrics().await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gate(step_id: &str, dependencies: &[&str]) -> WorkflowStep {
+        WorkflowStep {
+            step_id: step_id.to_string(),
+            agent_type: AgentType::LegalAdvisor,
+            input_mapping: HashMap::new(),
+            dependencies: dependencies.iter().map(|d| d.to_string()).collect(),
+            timeout_seconds: 0,
+            kind: StepKind::Approval {
+                instructions: Some("Partner sign-off".to_string()),
+            },
+            budget: TokenBudget::default(),
+        }
+    }
+
+    fn server_with_gates(dir: &Path) -> MCPServer {
+        let llm_manager = Arc::new(crate::llm_manager::LLMManager::new(dir).unwrap());
+        let server = MCPServer::new(llm_manager, 0);
+        server.workflows.lock().unwrap().insert(
+            "sign_off".to_string(),
+            WorkflowDefinition {
+                id: "sign_off".to_string(),
+                name: "Sign-off".to_string(),
+                description: String::new(),
+                steps: vec![gate("first_review", &[]), gate("final_review", &["first_review"])],
+                legal_domain: LegalDomain::General,
+                coordination: CoordinationStrategy::Hierarchical,
+                budget: TokenBudget::default(),
+            },
+        );
+        server
+    }
+
+    #[tokio::test]
+    async fn test_approval_gates_pause_and_resume() {
+        let dir = tempfile::tempdir().unwrap();
+        let server = server_with_gates(dir.path());
+
+        let run = server.execute_workflow("sign_off", HashMap::new()).await.unwrap();
+        assert_eq!(run.status, WorkflowRunStatus::AwaitingApproval);
+        assert_eq!(run.pending_review.as_ref().unwrap().step_id, "first_review");
+        assert_eq!(server.pending_reviews().len(), 1);
+        assert!(server.decide_step(&run.run_id, "final_review", "A. Jansen", true, None).is_err());
+        assert!(server.decide_step(&run.run_id, "first_review", " ", true, None).is_err());
+
+        let approved = server
+            .decide_step(&run.run_id, "first_review", "A. Jansen", true, Some("Looks right".to_string()))
+            .unwrap();
+        let run = server.run_workflow_steps(approved).await.unwrap();
+        assert_eq!(run.pending_review.as_ref().unwrap().step_id, "final_review");
+        assert_eq!(run.pending_review.as_ref().unwrap().outputs["first_review"], "Looks right");
+
+        let approved = server.decide_step(&run.run_id, "final_review", "B. de Vries", true, None).unwrap();
+        let run = server.run_workflow_steps(approved).await.unwrap();
+        assert_eq!(run.status, WorkflowRunStatus::Completed);
+        assert_eq!(run.step_outputs["final_review"], "Approved by B. de Vries");
+        assert!(server.pending_reviews().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_budget_stops_run_before_an_oversized_step() {
+        let dir = tempfile::tempdir().unwrap();
+        let server = server_with_gates(dir.path());
+        server.initialize_workflows().await.unwrap();
+        let budget = TokenBudget { max_tokens: Some(50), ..TokenBudget::default() };
+        let unknown_step = HashMap::from([("drafting".to_string(), budget.clone())]);
+        assert!(server.set_workflow_budget("contract_review", budget.clone(), unknown_step).is_err());
+        server.set_workflow_budget("contract_review", budget, HashMap::new()).unwrap();
+
+        // The contract is far larger than the budget, so no model is called
+        let input = HashMap::from([("contract".to_string(), "The Supplier shall indemnify the Customer. ".repeat(40))]);
+        let run = server.execute_workflow("contract_review", input).await.unwrap();
+        assert_eq!(run.status, WorkflowRunStatus::BudgetExceeded);
+        let exceeded = run.budget.exceeded.unwrap();
+        assert_eq!((exceeded.scope.as_str(), exceeded.step_id.as_str()), ("workflow", "initial_analysis"));
+        assert_eq!(exceeded.remaining_tokens, 50);
+        assert!(run.results.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_runs_survive_a_restart_and_a_failed_resume_is_kept() {
+        let dir = tempfile::tempdir().unwrap();
+        let server = server_with_gates(dir.path()).with_run_storage(dir.path()).unwrap();
+        let mut failing = server.workflows.lock().unwrap()["sign_off"].clone();
+        failing.id = "unstaffed".to_string();
+        // No agent is registered for the step after the gate
+        failing.steps[1].kind = StepKind::Agent;
+        server.workflows.lock().unwrap().insert(failing.id.clone(), failing);
+
+        let waiting = server.execute_workflow("sign_off", HashMap::new()).await.unwrap();
+        let run = server.execute_workflow("unstaffed", HashMap::new()).await.unwrap();
+        let approved = server.decide_step(&run.run_id, "first_review", "A. Jansen", true, None).unwrap();
+        assert!(server.run_workflow_steps(approved).await.is_err());
+
+        let llm_manager = Arc::new(crate::llm_manager::LLMManager::new(dir.path()).unwrap());
+        let restarted = MCPServer::new(llm_manager, 0).with_run_storage(dir.path()).unwrap();
+        let runs = restarted.workflow_runs.lock().unwrap();
+        assert_eq!(runs[&waiting.run_id].status, WorkflowRunStatus::AwaitingApproval);
+        assert_eq!(runs[&run.run_id].status, WorkflowRunStatus::Failed);
+        assert_eq!(runs[&run.run_id].reviews[0].reviewer, "A. Jansen");
+        assert!(runs[&run.run_id].error.as_deref().unwrap().contains("No agent found"));
+    }
+
+    #[tokio::test]
+    async fn test_rejection_stops_the_run() {
+        let dir = tempfile::tempdir().unwrap();
+        let server = server_with_gates(dir.path());
+
+        let run = server.execute_workflow("sign_off", HashMap::new()).await.unwrap();
+        let rejected = server
+            .decide_step(&run.run_id, "first_review", "A. Jansen", false, Some("Wrong governing law".to_string()))
+            .unwrap();
+        assert_eq!(rejected.status, WorkflowRunStatus::Rejected);
+        assert_eq!(rejected.reviews[0].reviewer, "A. Jansen");
+        assert!(!rejected.reviews[0].approved);
+        assert!(server.decide_step(&run.run_id, "first_review", "A. Jansen", true, None).is_err());
+    }
+}
@@ -4,6 +4,7 @@ use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::process::Stdio;
 use std::fs;
+use std::io::Read;
 use log::{info, warn, error, debug};
 use tokio::process::Command as TokioCommand;
 use reqwest::Client;
@@ -122,6 +123,235 @@ impl Default for ModelConfig {
     }
 }
 
+/// Metadata read from a GGUF file header, without loading the model
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct GgufMetadata {
+    pub version: u32,
+    pub name: Option<String>,
+    pub architecture: Option<String>,
+    pub parameter_count: u64,
+    pub context_length: Option<u32>,
+    pub block_count: Option<u32>,
+    pub embedding_length: Option<u32>,
+    pub head_count: Option<u32>,
+    pub head_count_kv: Option<u32>,
+    pub quantization: Option<String>,
+    pub chat_template: Option<String>,
+    pub tensor_count: u64,
+}
+
+// Guards against corrupt headers causing huge allocations
+const GGUF_MAX_STRING_LEN: u64 = 16 * 1024 * 1024;
+const GGUF_MAX_TENSOR_DIMS: u32 = 8;
+
+fn gguf_read_u32<R: Read>(reader: &mut R) -> Result<u32> {
+    let mut buf = [0u8; 4];
+    reader.read_exact(&mut buf)?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+fn gguf_read_u64<R: Read>(reader: &mut R) -> Result<u64> {
+    let mut buf = [0u8; 8];
+    reader.read_exact(&mut buf)?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+// GGUF v1 used 32-bit lengths and counts, v2+ use 64-bit
+fn gguf_read_len<R: Read>(reader: &mut R, version: u32) -> Result<u64> {
+    if version == 1 {
+        Ok(gguf_read_u32(reader)? as u64)
+    } else {
+        gguf_read_u64(reader)
+    }
+}
+
+fn gguf_read_string<R: Read>(reader: &mut R, version: u32) -> Result<String> {
+    let len = gguf_read_len(reader, version)?;
+    if len > GGUF_MAX_STRING_LEN {
+        return Err(anyhow!("GGUF string too long: {} bytes", len));
+    }
+    let mut buf = vec![0u8; len as usize];
+    reader.read_exact(&mut buf)?;
+    Ok(String::from_utf8_lossy(&buf).to_string())
+}
+
+fn gguf_skip<R: Read>(reader: &mut R, bytes: u64) -> Result<()> {
+    let skipped = std::io::copy(&mut reader.take(bytes), &mut std::io::sink())?;
+    if skipped != bytes {
+        return Err(anyhow!("Unexpected end of GGUF header"));
+    }
+    Ok(())
+}
+
+/// Read a metadata value; scalars are returned as JSON, arrays are skipped (returned as Null)
+fn gguf_read_value<R: Read>(reader: &mut R, value_type: u32, version: u32) -> Result<serde_json::Value> {
+    use serde_json::Value;
+
+    let mut buf8 = [0u8; 8];
+    Ok(match value_type {
+        0 | 1 | 7 => {
+            reader.read_exact(&mut buf8[..1])?;
+            match value_type {
+                0 => Value::from(buf8[0]),
+                1 => Value::from(buf8[0] as i8),
+                _ => Value::from(buf8[0] != 0),
+            }
+        }
+        2 | 3 => {
+            reader.read_exact(&mut buf8[..2])?;
+            let raw = [buf8[0], buf8[1]];
+            if value_type == 2 { Value::from(u16::from_le_bytes(raw)) } else { Value::from(i16::from_le_bytes(raw)) }
+        }
+        4 => Value::from(gguf_read_u32(reader)?),
+        5 => Value::from(gguf_read_u32(reader)? as i32),
+        6 => Value::from(f32::from_bits(gguf_read_u32(reader)?)),
+        8 => Value::from(gguf_read_string(reader, version)?),
+        9 => {
+            let item_type = gguf_read_u32(reader)?;
+            let len = gguf_read_len(reader, version)?;
+            match gguf_scalar_size(item_type) {
+                Some(size) => gguf_skip(reader, len.saturating_mul(size))?,
+                None => {
+                    for _ in 0..len {
+                        gguf_read_value(reader, item_type, version)?;
+                    }
+                }
+            }
+            Value::Null
+        }
+        10 => Value::from(gguf_read_u64(reader)?),
+        11 => Value::from(gguf_read_u64(reader)? as i64),
+        12 => Value::from(f64::from_bits(gguf_read_u64(reader)?)),
+        other => return Err(anyhow!("Unknown GGUF value type: {}", other)),
+    })
+}
+
+fn gguf_scalar_size(value_type: u32) -> Option<u64> {
+    match value_type {
+        0 | 1 | 7 => Some(1),
+        2 | 3 => Some(2),
+        4 | 5 | 6 => Some(4),
+        10 | 11 | 12 => Some(8),
+        _ => None,
+    }
+}
+
+/// Map `general.file_type` to the llama.cpp quantization name
+fn gguf_file_type_name(file_type: u64) -> Option<&'static str> {
+    Some(match file_type {
+        0 => "F32",
+        1 => "F16",
+        2 => "Q4_0",
+        3 => "Q4_1",
+        7 => "Q8_0",
+        8 => "Q5_0",
+        9 => "Q5_1",
+        10 => "Q2_K",
+        11 => "Q3_K_S",
+        12 => "Q3_K_M",
+        13 => "Q3_K_L",
+        14 => "Q4_K_S",
+        15 => "Q4_K_M",
+        16 => "Q5_K_S",
+        17 => "Q5_K_M",
+        18 => "Q6_K",
+        32 => "BF16",
+        _ => return None,
+    })
+}
+
+/// Parse the GGUF header: metadata key/values and tensor shapes (for the parameter count)
+pub fn parse_gguf_metadata<R: Read>(reader: &mut R) -> Result<GgufMetadata> {
+    let mut magic = [0u8; 4];
+    reader.read_exact(&mut magic)?;
+    if &magic != b"GGUF" {
+        return Err(anyhow!("Not a GGUF file"));
+    }
+
+    let version = gguf_read_u32(reader)?;
+    if !(1..=3).contains(&version) {
+        return Err(anyhow!("Unsupported GGUF version: {}", version));
+    }
+
+    let tensor_count = gguf_read_len(reader, version)?;
+    let kv_count = gguf_read_len(reader, version)?;
+
+    let mut values = HashMap::new();
+    for _ in 0..kv_count {
+        let key = gguf_read_string(reader, version)?;
+        let value_type = gguf_read_u32(reader)?;
+        let value = gguf_read_value(reader, value_type, version)?;
+        if !value.is_null() {
+            values.insert(key, value);
+        }
+    }
+
+    let mut parameter_count = 0u64;
+    for _ in 0..tensor_count {
+        let _name = gguf_read_string(reader, version)?;
+        let n_dims = gguf_read_u32(reader)?;
+        if n_dims > GGUF_MAX_TENSOR_DIMS {
+            return Err(anyhow!("Invalid GGUF tensor dimensions: {}", n_dims));
+        }
+        let mut elements = 1u64;
+        for _ in 0..n_dims {
+            elements = elements.saturating_mul(gguf_read_len(reader, version)?);
+        }
+        let _tensor_type = gguf_read_u32(reader)?;
+        let _offset = gguf_read_u64(reader)?;
+        parameter_count = parameter_count.saturating_add(elements);
+    }
+
+    let architecture = values.get("general.architecture").and_then(|v| v.as_str()).map(String::from);
+    let arch_u32 = |suffix: &str| {
+        architecture
+            .as_ref()
+            .and_then(|arch| values.get(&format!("{}.{}", arch, suffix)))
+            .and_then(|v| v.as_u64())
+            .map(|v| v as u32)
+    };
+
+    Ok(GgufMetadata {
+        version,
+        name: values.get("general.name").and_then(|v| v.as_str()).map(String::from),
+        context_length: arch_u32("context_length"),
+        block_count: arch_u32("block_count"),
+        embedding_length: arch_u32("embedding_length"),
+        head_count: arch_u32("attention.head_count"),
+        head_count_kv: arch_u32("attention.head_count_kv"),
+        quantization: values
+            .get("general.file_type")
+            .and_then(|v| v.as_u64())
+            .and_then(gguf_file_type_name)
+            .map(String::from),
+        chat_template: values.get("tokenizer.chat_template").and_then(|v| v.as_str()).map(String::from),
+        architecture,
+        parameter_count,
+        tensor_count,
+    })
+}
+
+/// Read GGUF metadata from a model file on disk
+pub fn read_gguf_metadata(path: &Path) -> Result<GgufMetadata> {
+    let file = fs::File::open(path)?;
+    let mut reader = std::io::BufReader::new(file);
+    parse_gguf_metadata(&mut reader)
+}
+
+/// Format a parameter count the way the catalog does ("7B", "2.7B", "350M")
+fn format_parameter_count(count: u64) -> String {
+    if count >= 1_000_000_000 {
+        let billions = count as f64 / 1e9;
+        if billions >= 10.0 || (billions - billions.round()).abs() < 0.05 {
+            format!("{}B", billions.round() as u64)
+        } else {
+            format!("{:.1}B", billions)
+        }
+    } else {
+        format!("{}M", (count as f64 / 1e6).round() as u64)
+    }
+}
+
 pub struct ModelManager {
     models: Arc<Mutex<HashMap<String, ModelInfo>>>,
     models_directory: PathBuf,
@@ -300,6 +530,15 @@ impl ModelManager {
             if model_path.exists() {
                 updated_model.status = ModelStatus::Installed;
                 updated_model.local_path = Some(model_path.to_string_lossy().to_string());
+
+                // Prefer what the file says over the catalog's estimates
+                let gguf_path = model_path.join("model.gguf");
+                if gguf_path.exists() {
+                    match read_gguf_metadata(&gguf_path) {
+                        Ok(metadata) => Self::apply_gguf_metadata(&mut updated_model, &metadata, &gguf_path),
+                        Err(e) => warn!("Failed to read GGUF metadata for {}: {}", model.id, e),
+                    }
+                }
             }
 
             models.insert(model.id.clone(), updated_model);
@@ -309,6 +548,29 @@ impl ModelManager {
         Ok(())
     }
 
+    // Overwrite catalog values with those read from the GGUF header
+    fn apply_gguf_metadata(model: &mut ModelInfo, metadata: &GgufMetadata, path: &Path) {
+        if metadata.parameter_count > 0 {
+            model.size.parameters = format_parameter_count(metadata.parameter_count);
+        }
+        if let Ok(file_meta) = fs::metadata(path) {
+            model.size.file_size = file_meta.len();
+        }
+        if let Some(context_length) = metadata.context_length {
+            model.capabilities.context_length = context_length;
+        }
+        if let Some(quantization) = &metadata.quantization {
+            model.requirements.quantization_levels = vec![quantization.to_lowercase()];
+        }
+        if metadata.chat_template.is_some() {
+            model.capabilities.instruction_following = true;
+            if !model.capabilities.tasks.iter().any(|t| t == "chat") {
+                model.capabilities.tasks.push("chat".to_string());
+            }
+        }
+        debug!("Applied GGUF metadata to {}: {:?}", model.id, metadata.architecture);
+    }
+
     // List all available models
     pub fn list_models(&self) -> Vec<ModelInfo> {
         let models = self.models.lock().unwrap();
@@ -676,6 +938,89 @@ pub async fn delete_model(model_id: String) -> Result<(), String> {
         .map_err(|e| format!("Failed to delete model: {}", e))
 }
 
+#[tauri::command]
+pub async fn get_vram_usage(
+    manager: tauri::State<'_, Arc<crate::llm_manager::LLMManager>>,
+) -> Result<crate::llm_manager::VramUsage, String> {
+    Ok(manager.vram_usage().await)
+}
+
+/// Event emitted to the frontend when a benchmark falls behind its baseline
+pub const PERFORMANCE_REGRESSION_EVENT: &str = "performance-regression";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BenchmarkReport {
+    pub result: crate::performance_tracker::BenchmarkResult,
+    pub baseline: Option<crate::performance_tracker::BenchmarkResult>,
+    pub alerts: Vec<crate::performance_tracker::RegressionAlert>,
+}
+
+#[tauri::command]
+pub async fn benchmark_inference(
+    app: tauri::AppHandle,
+    manager: tauri::State<'_, Arc<crate::llm_manager::LLMManager>>,
+    model_id: String,
+) -> Result<BenchmarkReport, String> {
+    use tauri::Manager;
+
+    let tracker = crate::performance_tracker::get_performance_tracker()
+        .ok_or_else(|| "Performance tracker not initialized".to_string())?;
+
+    let result = manager.run_benchmark(&model_id).await.map_err(|e| e.to_string())?;
+    let alerts = tracker.record_benchmark(result.clone()).await;
+    let (baseline, _) = tracker.get_benchmark_history(&model_id).await;
+
+    if !alerts.is_empty() {
+        let summary = alerts
+            .iter()
+            .map(|a| format!("{} {:+.0}%", a.metric.replace('_', " "), a.change_percent))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let body = format!(
+            "{} is slower than its baseline ({}). Likely cause: {}",
+            model_id, summary, alerts[0].suspected_causes[0]
+        );
+        let _ = tauri::api::notification::Notification::new(&app.config().tauri.bundle.identifier)
+            .title("BEAR AI performance regression")
+            .body(body)
+            .show();
+        let _ = app.emit_all(PERFORMANCE_REGRESSION_EVENT, &alerts);
+    }
+
+    Ok(BenchmarkReport { result, baseline, alerts })
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BenchmarkHistory {
+    pub baseline: Option<crate::performance_tracker::BenchmarkResult>,
+    pub runs: Vec<crate::performance_tracker::BenchmarkResult>,
+}
+
+#[tauri::command]
+pub async fn get_benchmark_history(model_id: String) -> Result<BenchmarkHistory, String> {
+    let tracker = crate::performance_tracker::get_performance_tracker()
+        .ok_or_else(|| "Performance tracker not initialized".to_string())?;
+    let (baseline, runs) = tracker.get_benchmark_history(&model_id).await;
+    Ok(BenchmarkHistory { baseline, runs })
+}
+
+#[tauri::command]
+pub async fn reset_benchmark_baseline(model_id: String) -> Result<crate::performance_tracker::BenchmarkResult, String> {
+    let tracker = crate::performance_tracker::get_performance_tracker()
+        .ok_or_else(|| "Performance tracker not initialized".to_string())?;
+    tracker.reset_benchmark_baseline(&model_id).await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn set_regression_thresholds(
+    thresholds: crate::performance_tracker::RegressionThresholds,
+) -> Result<(), String> {
+    let tracker = crate::performance_tracker::get_performance_tracker()
+        .ok_or_else(|| "Performance tracker not initialized".to_string())?;
+    tracker.set_regression_thresholds(thresholds).await;
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn get_running_models() -> Result<Vec<String>, String> {
     let models_dir = dirs::data_dir()
@@ -703,6 +1048,12 @@ pub async fn check_model_requirements(model_id: String) -> Result<HashMap<String
         .map_err(|e| format!("Failed to check requirements: {}", e))
 }
 
+#[tauri::command]
+pub async fn inspect_gguf_metadata(path: String) -> Result<GgufMetadata, String> {
+    read_gguf_metadata(Path::new(&path))
+        .map_err(|e| format!("Failed to read GGUF metadata: {}", e))
+}
+
 // Initialize model management system
 pub fn init_model_management() -> Result<ModelManager> {
     let models_dir = dirs::data_dir()
@@ -711,4 +1062,76 @@ pub fn init_model_management() -> Result<ModelManager> {
         .join("models");
 
     ModelManager::new(models_dir)
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn push_string(buf: &mut Vec<u8>, value: &str) {
+        buf.extend_from_slice(&(value.len() as u64).to_le_bytes());
+        buf.extend_from_slice(value.as_bytes());
+    }
+
+    #[test]
+    fn test_parse_gguf_metadata() {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(b"GGUF");
+        buf.extend_from_slice(&3u32.to_le_bytes());
+        buf.extend_from_slice(&1u64.to_le_bytes()); // tensors
+        buf.extend_from_slice(&5u64.to_le_bytes()); // metadata entries
+
+        push_string(&mut buf, "general.architecture");
+        buf.extend_from_slice(&8u32.to_le_bytes());
+        push_string(&mut buf, "llama");
+
+        push_string(&mut buf, "llama.context_length");
+        buf.extend_from_slice(&4u32.to_le_bytes());
+        buf.extend_from_slice(&8192u32.to_le_bytes());
+
+        push_string(&mut buf, "general.file_type");
+        buf.extend_from_slice(&4u32.to_le_bytes());
+        buf.extend_from_slice(&15u32.to_le_bytes());
+
+        // Arrays are skipped without being stored
+        push_string(&mut buf, "tokenizer.ggml.scores");
+        buf.extend_from_slice(&9u32.to_le_bytes());
+        buf.extend_from_slice(&6u32.to_le_bytes());
+        buf.extend_from_slice(&2u64.to_le_bytes());
+        buf.extend_from_slice(&0.5f32.to_le_bytes());
+        buf.extend_from_slice(&0.25f32.to_le_bytes());
+
+        push_string(&mut buf, "tokenizer.chat_template");
+        buf.extend_from_slice(&8u32.to_le_bytes());
+        push_string(&mut buf, "{{ messages }}");
+
+        push_string(&mut buf, "token_embd.weight");
+        buf.extend_from_slice(&2u32.to_le_bytes());
+        buf.extend_from_slice(&4096u64.to_le_bytes());
+        buf.extend_from_slice(&32000u64.to_le_bytes());
+        buf.extend_from_slice(&12u32.to_le_bytes());
+        buf.extend_from_slice(&0u64.to_le_bytes());
+
+        let metadata = parse_gguf_metadata(&mut buf.as_slice()).unwrap();
+
+        assert_eq!(metadata.architecture.as_deref(), Some("llama"));
+        assert_eq!(metadata.context_length, Some(8192));
+        assert_eq!(metadata.quantization.as_deref(), Some("Q4_K_M"));
+        assert_eq!(metadata.chat_template.as_deref(), Some("{{ messages }}"));
+        assert_eq!(metadata.parameter_count, 4096 * 32000);
+    }
+
+    #[test]
+    fn test_parse_gguf_rejects_other_formats() {
+        let data = b"GGML\x01\x00\x00\x00";
+        assert!(parse_gguf_metadata(&mut data.as_slice()).is_err());
+    }
+
+    #[test]
+    fn test_format_parameter_count() {
+        assert_eq!(format_parameter_count(7_000_559_616), "7B");
+        assert_eq!(format_parameter_count(2_779_683_840), "2.8B");
+        assert_eq!(format_parameter_count(13_015_864_320), "13B");
+        assert_eq!(format_parameter_count(350_000_000), "350M");
+    }
+}
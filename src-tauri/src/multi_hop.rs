@@ -0,0 +1,386 @@
+use anyhow::Result;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use uuid::Uuid;
+
+use crate::nemotron_rag::RAGChunk;
+
+/// Multi-hop Reasoning for BEAR AI
+/// Answers a question in hops: each hop retrieves passages for its query, has the model answer
+/// what it can from them and either conclude or name the next thing to look up. The trace keeps
+/// every hop's query, the passages it saw, its sub-answer and confidence, so an attorney can
+/// check how the final answer was reached.
+pub const DEFAULT_MAX_HOPS: usize = 3;
+pub const MAX_HOPS: usize = 6;
+// Passages shown to the model per hop
+const SOURCES_PER_HOP: usize = 5;
+// Excerpts are cut to this many characters in prompts; the trace keeps the full text
+const PROMPT_EXCERPT_CHARS: usize = 1200;
+// Sub-answers that cite none of the passages are trusted half as much
+const UNCITED_PENALTY: f32 = 0.5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StopReason {
+    Answered, // the model concluded the question was answered
+    MaxHops, // ran out of hops with a follow-up still open
+    NoResults, // a hop's query retrieved nothing the user may see
+    RepeatedQuery, // the model asked for something already looked up
+}
+
+/// A passage a hop retrieved, numbered as the model saw it ([S1], [S2], ...)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HopSource {
+    pub label: String,
+    pub chunk_id: String,
+    pub document_id: String,
+    pub content: String,
+    pub retrieval_confidence: f32,
+    pub cited: bool, // referenced by the hop's sub-answer
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub cited_authorities: Vec<String>,
+    #[serde(default)]
+    pub verified_citations: Vec<String>, // cited authorities the retriever verified
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReasoningHop {
+    pub hop: usize, // 1-based
+    pub query: String,
+    pub sources: Vec<HopSource>,
+    pub sub_answer: String,
+    pub confidence: f32,
+    pub follow_up: Option<String>, // the next hop's query, None when this hop concluded
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReasoningTrace {
+    pub id: String,
+    pub question: String,
+    pub model: String,
+    pub hops: Vec<ReasoningHop>,
+    pub final_answer: String,
+    pub confidence: f32, // the weakest hop's confidence; a chain holds no better than that
+    pub stop_reason: StopReason,
+    pub created_at: String,
+}
+
+/// What the model said at one hop
+#[derive(Debug, Clone, PartialEq)]
+struct HopResponse {
+    answer: String,
+    complete: bool,
+    next: Option<String>,
+}
+
+fn excerpt(text: &str) -> String {
+    if text.chars().count() <= PROMPT_EXCERPT_CHARS {
+        return text.trim().to_string();
+    }
+    format!("{}...", text.chars().take(PROMPT_EXCERPT_CHARS).collect::<String>().trim())
+}
+
+fn hop_prompt(question: &str, earlier: &[ReasoningHop], query: &str, sources: &[HopSource]) -> String {
+    let mut prompt = format!("Question: {}\n\n", question);
+    if !earlier.is_empty() {
+        prompt.push_str("Established so far:\n");
+        for hop in earlier {
+            prompt.push_str(&format!("- Step {} ({}): {}\n", hop.hop, hop.query, hop.sub_answer));
+        }
+        prompt.push('\n');
+    }
+    prompt.push_str(&format!("Passages retrieved for \"{}\":\n", query));
+    for source in sources {
+        prompt.push_str(&format!("[{}] {}\n", source.label, excerpt(&source.content)));
+    }
+    prompt.push_str(
+        "\nUsing only these passages and what is established so far, answer as much of the question as you can, \
+         citing passages as [S1], [S2]. Reply in exactly this format:\n\
+         ANSWER: <your answer>\n\
+         COMPLETE: <yes if the question is fully answered, otherwise no>\n\
+         NEXT: <if not complete, one search query for the missing information>",
+    );
+    prompt
+}
+
+fn final_prompt(question: &str, hops: &[ReasoningHop]) -> String {
+    let mut prompt = format!("Question: {}\n\nFindings from each research step:\n", question);
+    for hop in hops {
+        prompt.push_str(&format!("Step {} ({}): {}\n", hop.hop, hop.query, hop.sub_answer));
+    }
+    prompt.push_str(
+        "\nCombine the findings into one answer to the question. Use only the findings, keep their passage \
+         citations with the step they came from (e.g. step 2 [S1]), and say what remains unanswered.",
+    );
+    prompt
+}
+
+fn strip_marker<'a>(line: &'a str, marker: &str) -> Option<&'a str> {
+    line.get(..marker.len())
+        .filter(|prefix| prefix.eq_ignore_ascii_case(marker))
+        .map(|_| line[marker.len()..].trim())
+}
+
+/// Read the ANSWER / COMPLETE / NEXT reply; text without the markers is taken as a complete answer
+fn parse_hop_response(text: &str) -> HopResponse {
+    let mut answer: Vec<&str> = Vec::new();
+    let mut complete = None;
+    let mut next = None;
+    let mut in_answer = false;
+    for line in text.lines() {
+        let line = line.trim();
+        if let Some(rest) = strip_marker(line, "ANSWER:") {
+            in_answer = true;
+            answer.push(rest);
+        } else if let Some(rest) = strip_marker(line, "COMPLETE:") {
+            in_answer = false;
+            complete = Some(rest.to_lowercase().starts_with('y'));
+        } else if let Some(rest) = strip_marker(line, "NEXT:") {
+            in_answer = false;
+            let query = rest.trim_matches('"');
+            if !query.is_empty() && !matches!(query.to_lowercase().as_str(), "none" | "n/a" | "-") {
+                next = Some(query.to_string());
+            }
+        } else if in_answer {
+            answer.push(line);
+        }
+    }
+
+    let answer = if answer.is_empty() {
+        text.trim().to_string()
+    } else {
+        answer.join("\n").trim().to_string()
+    };
+    let complete = complete.unwrap_or(next.is_none());
+    HopResponse {
+        answer,
+        complete,
+        next: if complete { None } else { next },
+    }
+}
+
+/// Mark the sources the answer cites and score the hop: the mean retrieval confidence of the
+/// cited sources, or half that of all sources when none is cited
+fn score_hop(answer: &str, sources: &mut [HopSource]) -> f32 {
+    for source in sources.iter_mut() {
+        source.cited = answer.contains(&format!("[{}]", source.label));
+    }
+    let mean = |scores: Vec<f32>| {
+        if scores.is_empty() {
+            0.0
+        } else {
+            scores.iter().sum::<f32>() / scores.len() as f32
+        }
+    };
+    let cited: Vec<f32> = sources.iter().filter(|s| s.cited).map(|s| s.retrieval_confidence).collect();
+    let score = if cited.is_empty() {
+        mean(sources.iter().map(|s| s.retrieval_confidence).collect()) * UNCITED_PENALTY
+    } else {
+        mean(cited)
+    };
+    score.clamp(0.0, 1.0)
+}
+
+fn normalize_query(query: &str) -> String {
+    query
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Reason over up to `max_hops` retrievals. `retrieve` returns the passages for a query that the
+/// user may see, best first; `generate` completes a prompt with `model`.
+pub async fn reason<R, RF, G, GF>(
+    question: &str,
+    max_hops: usize,
+    model: &str,
+    retrieve: R,
+    generate: G,
+) -> Result<ReasoningTrace>
+where
+    R: Fn(String) -> RF,
+    RF: Future<Output = Result<Vec<RAGChunk>>>,
+    G: Fn(String) -> GF,
+    GF: Future<Output = Result<String>>,
+{
+    let max_hops = max_hops.clamp(1, MAX_HOPS);
+    let mut hops: Vec<ReasoningHop> = Vec::new();
+    let mut asked = vec![normalize_query(question)];
+    let mut query = question.to_string();
+    let mut stop_reason = StopReason::MaxHops;
+
+    for hop in 1..=max_hops {
+        let chunks = retrieve(query.clone()).await?;
+        if chunks.is_empty() {
+            stop_reason = StopReason::NoResults;
+            break;
+        }
+        let mut sources: Vec<HopSource> = chunks
+            .into_iter()
+            .take(SOURCES_PER_HOP)
+            .enumerate()
+            .map(|(index, chunk)| HopSource {
+                label: format!("S{}", index + 1),
+                chunk_id: chunk.id,
+                document_id: chunk.document_id,
+                content: chunk.content,
+                retrieval_confidence: chunk.rerank.map(|r| r.score.clamp(0.0, 1.0)).unwrap_or(chunk.confidence),
+                cited: false,
+                title: None,
+                cited_authorities: chunk.cited_authorities,
+                verified_citations: Vec::new(),
+            })
+            .collect();
+
+        let response = parse_hop_response(&generate(hop_prompt(question, &hops, &query, &sources)).await?);
+        let confidence = score_hop(&response.answer, &mut sources);
+        hops.push(ReasoningHop {
+            hop,
+            query: query.clone(),
+            sources,
+            sub_answer: response.answer,
+            confidence,
+            follow_up: response.next.clone(),
+        });
+
+        match response.next {
+            None => {
+                stop_reason = StopReason::Answered;
+                break;
+            }
+            Some(next) if asked.contains(&normalize_query(&next)) => {
+                stop_reason = StopReason::RepeatedQuery;
+                break;
+            }
+            Some(next) => {
+                asked.push(normalize_query(&next));
+                query = next;
+            }
+        }
+    }
+
+    let final_answer = match hops.as_slice() {
+        [] => "No indexed material addresses the question.".to_string(),
+        [only] if stop_reason == StopReason::Answered => only.sub_answer.clone(),
+        _ => generate(final_prompt(question, &hops)).await?.trim().to_string(),
+    };
+    let confidence = hops.iter().map(|h| h.confidence).fold(None, |weakest: Option<f32>, c| {
+        Some(weakest.map_or(c, |w| w.min(c)))
+    });
+
+    Ok(ReasoningTrace {
+        id: Uuid::new_v4().to_string(),
+        question: question.to_string(),
+        model: model.to_string(),
+        hops,
+        final_answer,
+        confidence: confidence.unwrap_or(0.0),
+        stop_reason,
+        created_at: Utc::now().to_rfc3339(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    fn chunk(id: &str, content: &str, confidence: f32) -> RAGChunk {
+        RAGChunk {
+            id: id.to_string(),
+            document_id: format!("doc-{}", id),
+            content: content.to_string(),
+            embedding: Vec::new(),
+            chunk_index: 0,
+            tokens: 0,
+            overlap: 0,
+            legal_concepts: Vec::new(),
+            cited_authorities: Vec::new(),
+            confidence,
+            temporal_relevance: 1.0,
+            created_at: Utc::now(),
+            metadata: HashMap::new(),
+            rerank: None,
+            authority: None,
+        }
+    }
+
+    #[test]
+    fn test_parses_hop_responses() {
+        let response = parse_hop_response(
+            "ANSWER: The lease ends in 2026 [S1].\nThe landlord may extend it.\nCOMPLETE: no\nNEXT: \"extension notice period\"",
+        );
+        assert_eq!(response.answer, "The lease ends in 2026 [S1].\nThe landlord may extend it.");
+        assert!(!response.complete);
+        assert_eq!(response.next.as_deref(), Some("extension notice period"));
+
+        let response = parse_hop_response("ANSWER: Yes [S2].\nCOMPLETE: yes\nNEXT: none");
+        assert!(response.complete);
+        assert_eq!(response.next, None);
+
+        let response = parse_hop_response("The clause is void.");
+        assert_eq!(response.answer, "The clause is void.");
+        assert!(response.complete);
+    }
+
+    #[tokio::test]
+    async fn test_traces_each_hop_to_the_final_answer() {
+        let corpus = HashMap::from([
+            ("who is the landlord", vec![chunk("a", "The landlord is Acme Ltd.", 0.9), chunk("b", "Rent is due monthly.", 0.4)]),
+            ("acme ltd notice address", vec![chunk("c", "Notices to Acme Ltd go to 1 Main St.", 0.7)]),
+        ]);
+        let replies = Mutex::new(vec![
+            "ANSWER: The landlord is Acme Ltd [S1].\nCOMPLETE: no\nNEXT: Acme Ltd notice address",
+            "ANSWER: Notices go to 1 Main St [S1].\nCOMPLETE: yes",
+            "Notices go to Acme Ltd (step 1 [S1]) at 1 Main St (step 2 [S1]).",
+        ]);
+        let prompts = Mutex::new(Vec::new());
+
+        let trace = reason(
+            "Who is the landlord",
+            3,
+            "local-model",
+            |query| {
+                let chunks = corpus.get(normalize_query(&query).as_str()).cloned().unwrap_or_default();
+                async move { Ok(chunks) }
+            },
+            |prompt| {
+                prompts.lock().unwrap().push(prompt);
+                let reply = replies.lock().unwrap().remove(0).to_string();
+                async move { Ok(reply) }
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(trace.stop_reason, StopReason::Answered);
+        assert_eq!(trace.hops.len(), 2);
+        let first = &trace.hops[0];
+        assert_eq!(first.query, "Who is the landlord");
+        assert_eq!(first.sources.iter().map(|s| s.cited).collect::<Vec<_>>(), vec![true, false]);
+        assert!((first.confidence - 0.9).abs() < 1e-6);
+        assert_eq!(first.follow_up.as_deref(), Some("Acme Ltd notice address"));
+        assert_eq!(trace.hops[1].query, "Acme Ltd notice address");
+        assert_eq!(trace.final_answer, "Notices go to Acme Ltd (step 1 [S1]) at 1 Main St (step 2 [S1]).");
+        assert!((trace.confidence - 0.7).abs() < 1e-6);
+
+        // The second hop builds on what the first established
+        let prompts = prompts.into_inner().unwrap();
+        assert!(prompts[1].contains("Step 1 (Who is the landlord): The landlord is Acme Ltd [S1]."));
+        assert!(prompts[2].contains("Combine the findings"));
+
+        // A question nothing answers ends without hops
+        let empty = reason("Unknown", 3, "local-model", |_| async { Ok(Vec::new()) }, |_| async { Ok(String::new()) })
+            .await
+            .unwrap();
+        assert_eq!(empty.stop_reason, StopReason::NoResults);
+        assert!(empty.hops.is_empty());
+        assert_eq!(empty.confidence, 0.0);
+    }
+}
@@ -4,17 +4,33 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 use anyhow::{Result, Context};
 use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
+use crate::authority_ranking::{self, AuthorityBoost, AuthorityWeights};
+use crate::case_analytics;
+use crate::chunking_profiles::{self, ChunkingProfile};
+use crate::citation_graph::CitationGraph;
+use crate::corpus_topics;
+use crate::court_filing;
+use crate::document_analyzer;
+use crate::ingest_dedup;
+use crate::jurisdiction::{self, JurisdictionHierarchy, JurisdictionScope};
+use crate::local_vector_store::{self, LocalVectorStore};
+use crate::ocr_ingest::{self, IngestText};
+use crate::regulatory_monitor;
+use crate::request_tracing::{self, StageTimer};
+
 // Vector database clients
 use qdrant_client::{
     client::QdrantClient,
     qdrant::{
-        vectors_config::Config, CreateCollection, Distance, PointStruct, SearchPoints,
-        VectorParams, VectorsConfig, UpsertPoints
+        points_selector::PointsSelectorOneOf, vectors::VectorsOptions, vectors_config::Config, CreateCollection,
+        DeletePoints, Distance, PointStruct, PointsIdsList, PointsSelector, ScrollPoints, SearchPoints,
+        Value as QdrantValue, VectorParams, VectorsConfig, UpsertPoints
     },
 };
 // Lance DB imports - disabled due to protobuf requirement
@@ -37,6 +53,7 @@ use rayon::prelude::*;
 
 // HTTP client for NVIDIA APIs
 use reqwest::Client;
+use futures::future::BoxFuture;
 
 /// Configuration for the Nemotron RAG system
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -55,8 +72,57 @@ pub struct NemotronConfig {
     pub enable_gpu_acceleration: bool,
     pub cache_ttl: u64,
     pub lance_db_path: Option<String>,
+    #[serde(default)]
+    pub local_store_path: Option<String>, // SqliteLocal store file; defaults under the app data directory
+    #[serde(default)]
+    pub citation_graph_path: Option<String>, // citation graph log; kept in memory only when unset
     pub max_results: usize,
     pub embedding_dimension: usize,
+    #[serde(default = "default_embedding_batch_size")]
+    pub embedding_batch_size: usize,
+    #[serde(default = "default_embedding_parallel_requests")]
+    pub embedding_parallel_requests: usize,
+    #[serde(default)]
+    pub embedding_backend: EmbeddingBackend,
+    #[serde(default)]
+    pub local_embedding_url: Option<String>, // defaults to the local model server llm_manager uses
+    #[serde(default)]
+    pub rerank_backend: RerankBackend,
+    #[serde(default)]
+    pub local_reranker_url: Option<String>, // defaults to the local model server llm_manager uses
+    #[serde(default)]
+    pub jurisdictions: Option<JurisdictionHierarchy>, // defaults to US federal > circuit > state
+    #[serde(default)]
+    pub authority_weights: AuthorityWeights, // ranking boosts for precedential value, court level and recency
+}
+
+fn default_embedding_batch_size() -> usize {
+    32
+}
+
+fn default_embedding_parallel_requests() -> usize {
+    2
+}
+
+/// Where chunk and query embeddings are computed. `Local` keeps document text on this machine;
+/// `embedding_model` then names a model on the local model server and `embedding_dimension` must
+/// match it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EmbeddingBackend {
+    #[default]
+    NemoCloud,
+    Local,
+}
+
+/// How fused retrieval results are reordered. `Local` scores every chunk against the query with
+/// the cross-encoder named by `reranking_model` on the local model server.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RerankBackend {
+    #[default]
+    Disabled,
+    Local,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -64,6 +130,7 @@ pub enum VectorDbType {
     Qdrant,
     // LanceDB,  // Disabled due to protobuf requirement
     Hybrid,
+    SqliteLocal, // embedded SQLite + HNSW store; no Qdrant or Redis needed
 }
 
 /// Legal document representation
@@ -79,7 +146,7 @@ pub struct LegalDocument {
     pub metadata: DocumentMetadata,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum DocumentType {
     Statute,
     CaseLaw,
@@ -99,7 +166,7 @@ pub struct DocumentMetadata {
     pub confidence: f32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PrecedentialValue {
     Binding,
     Persuasive,
@@ -121,6 +188,20 @@ pub struct RAGChunk {
     pub confidence: f32,
     pub temporal_relevance: f32,
     pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub metadata: HashMap<String, String>, // provenance supplied by the ingesting source
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rerank: Option<RerankScore>, // set on retrieved chunks when a reranker ordered them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub authority: Option<AuthorityBoost>, // set on retrieved chunks when authority weighting ranked them
+}
+
+/// Why a retrieved chunk sits where it does after reranking
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RerankScore {
+    pub model: String,
+    pub score: f32,            // cross-encoder relevance to the query; higher ranks first
+    pub retrieval_rank: usize, // position before reranking, 0 = first
 }
 
 /// RAG system health status
@@ -129,6 +210,8 @@ pub struct RAGHealth {
     pub status: String,
     pub vector_db_connected: bool,
     pub embeddings_available: bool,
+    pub embedding_provider: String,
+    pub embeddings_local: bool, // document text is embedded without leaving this machine
     pub cache_enabled: bool,
     pub gpu_available: bool,
     pub total_documents: usize,
@@ -146,6 +229,14 @@ pub struct QueryContext {
     pub require_citations: Option<bool>,
     pub max_results: Option<usize>,
     pub confidence_threshold: Option<f32>,
+    #[serde(default)]
+    pub court: Option<String>, // only chunks of filings in this court, matched on part of its name
+    #[serde(default)]
+    pub docket_number: Option<String>,
+    #[serde(default)]
+    pub jurisdiction_scope: JurisdictionScope, // how strictly `jurisdiction` narrows the results
+    #[serde(default)]
+    pub latency_budget_ms: Option<u64>, // optional stages are skipped to answer within this
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -155,7 +246,7 @@ pub struct TimeRange {
 }
 
 /// Retrieval results
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct RetrievalResult {
     pub chunks: Vec<RAGChunk>,
     pub documents: Vec<LegalDocument>,
@@ -164,6 +255,418 @@ pub struct RetrievalResult {
     pub reasoning: Vec<String>,
     pub contradictions: Vec<ContradictionInfo>,
     pub graph_relations: Vec<GraphRelation>,
+    #[serde(default)]
+    pub skipped_stages: Vec<String>, // optional stages left out to meet the latency budget
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retrieval_id: Option<String>, // under which the app recorded the result's provenance
+}
+
+/// What a retrieval handed to the model, kept with the answer it informed so the
+/// exact context can be reproduced for exports and audits
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetrievalProvenance {
+    pub query: String,
+    pub retrieved_at: DateTime<Utc>,
+    pub confidence: f32,
+    pub chunks: Vec<ChunkProvenance>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChunkProvenance {
+    pub chunk_id: String,
+    pub document_id: String,
+    pub document_title: Option<String>,
+    pub chunk_index: usize,
+    pub score: f32,
+    pub content: String,
+    pub content_sha256: String,
+}
+
+/// How well one sentence of a generated answer is backed by the retrieved chunks
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SentenceSupport {
+    pub sentence: String,
+    pub support: f32, // share of the sentence's content words found in its best chunk
+    pub chunk_id: Option<String>, // that chunk, when any word was found
+}
+
+/// The verification pass over a generated answer: `confidence` weighs how well the chunks
+/// support the answer against how confident retrieval was in the chunks
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnswerVerification {
+    pub confidence: f32,
+    pub threshold: f32,
+    pub support: f32, // mean support of the answer's sentences
+    pub retrieval_confidence: f32,
+    pub sentences: Vec<SentenceSupport>,
+}
+
+/// A generated answer, or in its place the reason the evidence does not carry one
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum VerifiedAnswer<T> {
+    Answered { answer: T, verification: AnswerVerification },
+    InsufficientEvidence { reason: String, verification: AnswerVerification },
+}
+
+/// Split text into chunks of up to `max_size` words, each repeating the last `overlap` words of
+/// the one before
+pub fn split_into_chunks(content: &str, max_size: usize, overlap: usize) -> Vec<String> {
+    let words: Vec<&str> = content.split_whitespace().collect();
+    let overlap = overlap.min(max_size.saturating_sub(1));
+    let mut chunks = Vec::new();
+    let mut start = 0;
+
+    while start < words.len() {
+        let end = std::cmp::min(start + max_size.max(1), words.len());
+        let chunk = words[start..end].join(" ");
+        chunks.push(chunk);
+
+        if end >= words.len() {
+            break;
+        }
+
+        start = end - overlap;
+    }
+
+    chunks
+}
+
+/// Byte ranges of a legal text's sections, each starting at a heading such as "Section 4",
+/// "§ 12" or "Article VII"; a text without headings is a single section
+pub fn legal_section_boundaries(content: &str) -> Vec<(usize, usize)> {
+    let heading = regex::Regex::new(r"(?:\b(?:Section|SECTION|Article|ARTICLE|Chapter|CHAPTER|Part|PART)|§)\s*[0-9IVXLC]+\b").unwrap();
+    let mut starts: Vec<usize> = heading.find_iter(content).map(|m| m.start()).filter(|start| *start > 0).collect();
+    starts.insert(0, 0);
+    starts.dedup();
+
+    starts
+        .iter()
+        .enumerate()
+        .map(|(i, start)| (*start, starts.get(i + 1).copied().unwrap_or(content.len())))
+        .filter(|(start, end)| !content[*start..*end].trim().is_empty())
+        .collect()
+}
+
+/// Collapse a legal text's whitespace before chunking
+pub fn clean_legal_text(content: &str) -> String {
+    // Remove excessive whitespace
+    let cleaned = content.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    // Remove common legal document artifacts
+    let cleaned = cleaned.replace("\\n", " ");
+    let cleaned = cleaned.replace("\\t", " ");
+
+    // Normalize legal citations
+    // This would include more sophisticated citation normalization
+
+    cleaned
+}
+
+fn estimate_tokens(text: &str) -> usize {
+    // Rough estimation: 1 token ≈ 0.75 words
+    (text.split_whitespace().count() as f32 * 1.33) as usize
+}
+
+/// Legal-aware chunking of `document`'s cleaned `content` with `profile`: sections that fit are
+/// one chunk each, longer ones are split with the profile's overlap
+pub fn legal_chunks(content: &str, document: &LegalDocument, profile: &ChunkingProfile) -> Vec<RAGChunk> {
+    let mut chunks = Vec::new();
+    let max_chunk_size = profile.max_chunk_size;
+    let overlap = profile.chunk_overlap;
+
+    // Split by legal sections first (e.g., "Section", "§", "Article")
+    let section_boundaries = if profile.split_on_sections {
+        legal_section_boundaries(content)
+    } else {
+        vec![(0, content.len())]
+    };
+
+    let mut chunk_index = 0;
+    let mut push = |text: &str, overlap: usize| {
+        chunks.push(RAGChunk {
+            id: format!("{}-chunk-{}", document.id, chunk_index),
+            document_id: document.id.clone(),
+            content: text.to_string(),
+            embedding: vec![], // Will be filled later
+            chunk_index,
+            tokens: estimate_tokens(text),
+            overlap,
+            legal_concepts: vec![],
+            cited_authorities: vec![],
+            confidence: 1.0,
+            temporal_relevance: 1.0,
+            created_at: Utc::now(),
+            metadata: HashMap::new(),
+            rerank: None,
+            authority: None,
+        });
+        chunk_index += 1;
+    };
+
+    for (section_start, section_end) in section_boundaries {
+        let section_content = &content[section_start..section_end];
+
+        if section_content.split_whitespace().count() <= max_chunk_size {
+            // Section fits in one chunk
+            push(section_content, 0);
+        } else {
+            // Split section into smaller chunks
+            for chunk_content in split_into_chunks(section_content, max_chunk_size, overlap) {
+                push(&chunk_content, overlap);
+            }
+        }
+    }
+
+    chunks
+}
+
+/// Event emitted as each file of a folder ingestion is indexed, skipped or fails
+pub const INGEST_PROGRESS_EVENT: &str = "rag-ingest-progress";
+
+/// File types document_analyzer can extract text from
+pub const INGESTIBLE_EXTENSIONS: &[&str] = &["pdf", "docx", "txt", "rtf", "xlsx", "xls", "csv", "pptx", "ppt"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkIngestRequest {
+    pub directory: String,
+    pub recursive: Option<bool>,    // defaults to true
+    pub collection: Option<String>, // defaults to legal_chunks
+    pub document_type: Option<DocumentType>,
+    pub jurisdiction: Option<String>,
+    pub matter_id: Option<String>, // files every chunk under the matter for access checks
+    pub ocr_languages: Option<Vec<String>>, // Tesseract codes for scanned pages, e.g. ["eng", "nld"]; defaults to English
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IngestFileStatus {
+    Indexed,
+    Skipped, // no text could be extracted
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IngestFileProgress {
+    pub job_id: String,
+    pub path: String,
+    pub file_index: usize, // 1-based
+    pub total_files: usize,
+    pub status: IngestFileStatus,
+    pub chunks: usize,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IngestFailure {
+    pub path: String,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkIngestSummary {
+    pub job_id: String,
+    pub directory: String,
+    pub collection: String,
+    pub files_found: usize,
+    pub indexed: usize,
+    pub skipped: Vec<String>,
+    pub failed: Vec<IngestFailure>,
+    pub chunks: usize,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+}
+
+/// Supported files under a folder, hidden files and folders excluded, in a stable order
+pub fn collect_ingestible_files(directory: &std::path::Path, recursive: bool) -> Result<Vec<std::path::PathBuf>> {
+    if !directory.is_dir() {
+        return Err(anyhow::anyhow!("{} is not a folder", directory.display()));
+    }
+    let mut files: Vec<std::path::PathBuf> = walkdir::WalkDir::new(directory)
+        .max_depth(if recursive { usize::MAX } else { 1 })
+        .into_iter()
+        .filter_entry(|entry| entry.depth() == 0 || !entry.file_name().to_string_lossy().starts_with('.'))
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| entry.into_path())
+        .filter(|path| {
+            path.extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| INGESTIBLE_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+        })
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// Id of the document a file is indexed as. It is derived from the path, so ingesting the
+/// folder again replaces the file's chunks instead of duplicating them.
+pub fn file_document_id(path: &std::path::Path) -> String {
+    use sha2::{Digest, Sha256};
+
+    let digest = Sha256::digest(path.to_string_lossy().as_bytes());
+    Uuid::from_slice(&digest[..16]).map(|id| id.to_string()).unwrap_or_else(|_| Uuid::new_v4().to_string())
+}
+
+/// A file as a document for the index, under its `file_document_id`
+pub fn legal_document_from_file(path: &std::path::Path, content: String, request: &BulkIngestRequest) -> LegalDocument {
+    let last_updated = std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .map(DateTime::<Utc>::from)
+        .unwrap_or_else(|_| Utc::now());
+    LegalDocument {
+        id: file_document_id(path),
+        title: path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default(),
+        content,
+        jurisdiction: request.jurisdiction.clone().unwrap_or_else(|| "General".to_string()),
+        document_type: request.document_type.clone().unwrap_or(DocumentType::Brief),
+        last_updated,
+        citations: Vec::new(),
+        metadata: DocumentMetadata {
+            court: None,
+            judge: None,
+            parties: Vec::new(),
+            topics: Vec::new(),
+            precedential_value: PrecedentialValue::NotPrecedential,
+            confidence: 1.0,
+        },
+    }
+}
+
+/// Extract, chunk and index each file in turn; one file failing does not stop the rest.
+/// `extract` reads a file's text, OCR page confidences included, `index` stores a document with chunk metadata and returns its
+/// chunk count, and `progress` hears about every file as it finishes.
+pub async fn bulk_ingest<E, EF, I, IF, P>(
+    request: &BulkIngestRequest,
+    files: Vec<std::path::PathBuf>,
+    extract: E,
+    index: I,
+    mut progress: P,
+) -> BulkIngestSummary
+where
+    E: Fn(std::path::PathBuf) -> EF,
+    EF: std::future::Future<Output = Result<IngestText>>,
+    I: Fn(LegalDocument, HashMap<String, String>) -> IF,
+    IF: std::future::Future<Output = Result<usize>>,
+    P: FnMut(&IngestFileProgress),
+{
+    let job_id = Uuid::new_v4().to_string();
+    let mut summary = BulkIngestSummary {
+        job_id: job_id.clone(),
+        directory: request.directory.clone(),
+        collection: request.collection.clone().unwrap_or_else(|| "legal_chunks".to_string()),
+        files_found: files.len(),
+        indexed: 0,
+        skipped: Vec::new(),
+        failed: Vec::new(),
+        chunks: 0,
+        started_at: Utc::now(),
+        finished_at: Utc::now(),
+    };
+
+    for (i, path) in files.iter().enumerate() {
+        let display = path.to_string_lossy().to_string();
+        let outcome = match extract(path.clone()).await {
+            Ok(text) if text.text.trim().is_empty() => Ok(None),
+            Ok(text) => {
+                let mut metadata = text.metadata();
+                metadata.insert("source_path".to_string(), display.clone());
+                metadata.insert("ingest_job_id".to_string(), job_id.clone());
+                if let Some(matter_id) = &request.matter_id {
+                    metadata.insert("matter_id".to_string(), matter_id.clone());
+                }
+                index(legal_document_from_file(path, text.text, request), metadata).await.map(Some)
+            }
+            Err(e) => Err(e),
+        };
+
+        let (status, chunks, error) = match outcome {
+            Ok(Some(chunks)) => {
+                summary.indexed += 1;
+                summary.chunks += chunks;
+                (IngestFileStatus::Indexed, chunks, None)
+            }
+            Ok(None) => {
+                summary.skipped.push(display.clone());
+                (IngestFileStatus::Skipped, 0, Some("No text could be extracted".to_string()))
+            }
+            Err(e) => {
+                log::warn!("Failed to ingest {}: {}", display, e);
+                summary.failed.push(IngestFailure {
+                    path: display.clone(),
+                    error: e.to_string(),
+                });
+                (IngestFileStatus::Failed, 0, Some(e.to_string()))
+            }
+        };
+        progress(&IngestFileProgress {
+            job_id: job_id.clone(),
+            path: display,
+            file_index: i + 1,
+            total_files: files.len(),
+            status,
+            chunks,
+            error,
+        });
+    }
+
+    summary.finished_at = Utc::now();
+    summary
+}
+
+/// Keep one document of each set of near copies among chunks already admitted for a reader:
+/// the most recently indexed of an original and its copies filed under the same matter. A copy
+/// stands in for an original that was deleted or that the reader may not see.
+pub fn collapse_duplicates(chunks: &mut Vec<RAGChunk>) {
+    let group = |chunk: &RAGChunk| {
+        let original = chunk.metadata.get("duplicate_of").unwrap_or(&chunk.document_id).clone();
+        (chunk.metadata.get("matter_id").cloned(), original)
+    };
+    let mut newest: HashMap<(Option<String>, String), (DateTime<Utc>, String)> = HashMap::new();
+    for chunk in chunks.iter() {
+        let candidate = (chunk.created_at, chunk.document_id.clone());
+        newest
+            .entry(group(chunk))
+            .and_modify(|kept| {
+                if candidate > *kept {
+                    *kept = candidate.clone();
+                }
+            })
+            .or_insert(candidate);
+    }
+    chunks.retain(|chunk| newest[&group(chunk)].1 == chunk.document_id);
+}
+
+impl RetrievalResult {
+    /// Provenance record for the chunks in this result, in the order they were ranked
+    pub fn provenance(&self, query: &str) -> RetrievalProvenance {
+        use sha2::{Digest, Sha256};
+
+        let chunks = self
+            .chunks
+            .iter()
+            .map(|chunk| ChunkProvenance {
+                chunk_id: chunk.id.clone(),
+                document_id: chunk.document_id.clone(),
+                document_title: self
+                    .documents
+                    .iter()
+                    .find(|d| d.id == chunk.document_id)
+                    .map(|d| d.title.clone()),
+                chunk_index: chunk.chunk_index,
+                score: chunk.confidence,
+                content: chunk.content.clone(),
+                content_sha256: format!("{:x}", Sha256::digest(chunk.content.as_bytes())),
+            })
+            .collect();
+
+        RetrievalProvenance {
+            query: query.to_string(),
+            retrieved_at: Utc::now(),
+            confidence: self.confidence,
+            chunks,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -277,6 +780,7 @@ pub enum RelationType {
 pub enum VectorDatabase {
     Qdrant(Arc<QdrantClient>),
     // Lance(Arc<RwLock<Option<Dataset>>>),  // Disabled due to protobuf requirement
+    SqliteLocal(Arc<LocalVectorStore>),
 }
 
 impl VectorDatabase {
@@ -299,10 +803,21 @@ impl VectorDatabase {
                 let client = QdrantClient::from_url(&config.vector_db_url).build()?;
                 Ok(VectorDatabase::Qdrant(Arc::new(client)))
             }
+            VectorDbType::SqliteLocal => {
+                let path = config
+                    .local_store_path
+                    .as_ref()
+                    .map(std::path::PathBuf::from)
+                    .unwrap_or_else(local_vector_store::default_store_path);
+                let store = tokio::task::spawn_blocking(move || LocalVectorStore::open(&path)).await??;
+                Ok(VectorDatabase::SqliteLocal(Arc::new(store)))
+            }
         }
     }
 
-    pub async fn create_collection(&self, collection_name: &str, dimension: usize) -> Result<()> {
+    /// Create a collection for vectors from `embedding_model`; the local store also records the
+    /// model and refuses an existing collection built with another one
+    pub async fn create_collection(&self, collection_name: &str, dimension: usize, embedding_model: &str) -> Result<()> {
         match self {
             VectorDatabase::Qdrant(client) => {
                 let config = VectorsConfig {
@@ -325,6 +840,10 @@ impl VectorDatabase {
             //     // Lance DB collection creation is handled during first insert
             //     Ok(())
             // }
+            VectorDatabase::SqliteLocal(store) => {
+                let (store, name, model) = (store.clone(), collection_name.to_string(), embedding_model.to_string());
+                tokio::task::spawn_blocking(move || store.create_collection(&name, dimension, &model)).await?
+            }
         }
     }
 
@@ -340,6 +859,9 @@ impl VectorDatabase {
                     payload.insert("temporal_relevance".to_string(), chunk.temporal_relevance.into());
                     payload.insert("legal_concepts".to_string(), chunk.legal_concepts.join(",").into());
                     payload.insert("cited_authorities".to_string(), chunk.cited_authorities.join(",").into());
+                    if !chunk.metadata.is_empty() {
+                        payload.insert("metadata".to_string(), serde_json::to_string(&chunk.metadata).unwrap_or_default().into());
+                    }
 
                     PointStruct {
                         id: Some(chunk.id.clone().into()),
@@ -396,6 +918,33 @@ impl VectorDatabase {
             //
             //     Ok(())
             // }
+            VectorDatabase::SqliteLocal(store) => {
+                let (store, name, chunks) = (store.clone(), collection_name.to_string(), chunks.to_vec());
+                tokio::task::spawn_blocking(move || store.upsert(&name, &chunks)).await?
+            }
+        }
+    }
+
+    /// Delete chunks by id, e.g. every chunk of a document being purged
+    pub async fn delete_chunks(&self, collection_name: &str, chunk_ids: &[String]) -> Result<()> {
+        match self {
+            VectorDatabase::Qdrant(client) => {
+                client.delete_points(DeletePoints {
+                    collection_name: collection_name.to_string(),
+                    points: Some(PointsSelector {
+                        points_selector_one_of: Some(PointsSelectorOneOf::Points(PointsIdsList {
+                            ids: chunk_ids.iter().map(|id| id.clone().into()).collect(),
+                        })),
+                    }),
+                    ..Default::default()
+                }).await?;
+                Ok(())
+            }
+            VectorDatabase::SqliteLocal(store) => {
+                let (store, name, ids) = (store.clone(), collection_name.to_string(), chunk_ids.to_vec());
+                tokio::task::spawn_blocking(move || store.delete(&name, &ids)).await??;
+                Ok(())
+            }
         }
     }
 
@@ -414,40 +963,8 @@ impl VectorDatabase {
                 }).await?;
 
                 let chunks: Vec<RAGChunk> = search_result.result.into_iter().map(|point| {
-                    let payload = point.payload;
-
-                    RAGChunk {
-                        id: point.id.unwrap().to_string(),
-                        document_id: payload.get("document_id")
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("")
-                            .to_string(),
-                        content: payload.get("content")
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("")
-                            .to_string(),
-                        embedding: query_vector.to_vec(), // Simplified
-                        chunk_index: payload.get("chunk_index")
-                            .and_then(|v| v.as_integer())
-                            .unwrap_or(0) as usize,
-                        tokens: 0, // Would be computed
-                        overlap: 0,
-                        legal_concepts: payload.get("legal_concepts")
-                            .and_then(|v| v.as_str())
-                            .map(|s| s.split(',').map(String::from).collect())
-                            .unwrap_or_default(),
-                        cited_authorities: payload.get("cited_authorities")
-                            .and_then(|v| v.as_str())
-                            .map(|s| s.split(',').map(String::from).collect())
-                            .unwrap_or_default(),
-                        confidence: payload.get("confidence")
-                            .and_then(|v| v.as_double())
-                            .unwrap_or(0.0) as f32,
-                        temporal_relevance: payload.get("temporal_relevance")
-                            .and_then(|v| v.as_double())
-                            .unwrap_or(1.0) as f32,
-                        created_at: Utc::now(),
-                    }
+                    // Simplified: the query vector stands in for the stored one
+                    chunk_from_qdrant(point.id.unwrap().to_string(), &point.payload, query_vector.to_vec())
                 }).collect();
 
                 Ok(chunks)
@@ -457,7 +974,462 @@ impl VectorDatabase {
             //     // This is a simplified placeholder
             //     Ok(vec![])
             // }
+            VectorDatabase::SqliteLocal(store) => {
+                let (store, name, query) = (store.clone(), collection_name.to_string(), query_vector.to_vec());
+                tokio::task::spawn_blocking(move || store.search(&name, &query, limit)).await?
+            }
+        }
+    }
+}
+
+fn chunk_from_qdrant(id: String, payload: &HashMap<String, QdrantValue>, embedding: Vec<f32>) -> RAGChunk {
+    RAGChunk {
+        id,
+        document_id: payload.get("document_id")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string(),
+        content: payload.get("content")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string(),
+        embedding,
+        chunk_index: payload.get("chunk_index")
+            .and_then(|v| v.as_integer())
+            .unwrap_or(0) as usize,
+        tokens: 0, // Would be computed
+        overlap: 0,
+        legal_concepts: payload.get("legal_concepts")
+            .and_then(|v| v.as_str())
+            .map(|s| s.split(',').map(String::from).collect())
+            .unwrap_or_default(),
+        cited_authorities: payload.get("cited_authorities")
+            .and_then(|v| v.as_str())
+            .map(|s| s.split(',').map(String::from).collect())
+            .unwrap_or_default(),
+        confidence: payload.get("confidence")
+            .and_then(|v| v.as_double())
+            .unwrap_or(0.0) as f32,
+        temporal_relevance: payload.get("temporal_relevance")
+            .and_then(|v| v.as_double())
+            .unwrap_or(1.0) as f32,
+        created_at: Utc::now(),
+        metadata: payload.get("metadata")
+            .and_then(|v| v.as_str())
+            .and_then(|s| serde_json::from_str(s).ok())
+            .unwrap_or_default(),
+        rerank: None,
+        authority: None,
+    }
+}
+
+/// Every point of a Qdrant collection with its vector, for moving an index to the local store
+async fn export_qdrant_collection(client: &QdrantClient, collection_name: &str) -> Result<Vec<RAGChunk>> {
+    let mut chunks = Vec::new();
+    let mut offset = None;
+    loop {
+        let page = client.scroll(&ScrollPoints {
+            collection_name: collection_name.to_string(),
+            offset: offset.take(),
+            limit: Some(256),
+            with_payload: Some(true.into()),
+            with_vectors: Some(true.into()),
+            ..Default::default()
+        }).await?;
+        for point in page.result {
+            let embedding = match point.vectors.and_then(|v| v.vectors_options) {
+                Some(VectorsOptions::Vector(vector)) => vector.data,
+                _ => continue, // named vectors are not used by this index
+            };
+            let id = point.id.map(|id| id.to_string()).unwrap_or_default();
+            chunks.push(chunk_from_qdrant(id, &point.payload, embedding));
+        }
+        match page.next_page_offset {
+            Some(next) => offset = Some(next),
+            None => break,
+        }
+    }
+    Ok(chunks)
+}
+
+/// Chunks copied into the local store from one Qdrant collection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MigratedCollection {
+    pub collection: String,
+    pub chunks: usize,
+}
+
+/// Source of embedding vectors for chunks and queries
+pub trait EmbeddingProvider: Send + Sync {
+    /// Short name for logs and health reports
+    fn name(&self) -> &'static str;
+
+    /// Whether text stays on this machine
+    fn is_local(&self) -> bool;
+
+    fn embed<'a>(&'a self, text: &'a str) -> BoxFuture<'a, Result<Vec<f32>>>;
+
+    /// One vector per text, in the order given
+    fn embed_batch<'a>(&'a self, texts: &'a [String]) -> BoxFuture<'a, Result<Vec<Vec<f32>>>>;
+}
+
+fn json_vector(value: &serde_json::Value) -> Option<Vec<f32>> {
+    value.as_array().map(|values| values.iter().map(|v| v.as_f64().unwrap_or(0.0) as f32).collect())
+}
+
+fn check_batch_len(embeddings: &[Vec<f32>], texts: &[String]) -> Result<()> {
+    if embeddings.len() != texts.len() {
+        return Err(anyhow::anyhow!(
+            "Batch embedding response had {} vectors for {} inputs",
+            embeddings.len(),
+            texts.len()
+        ));
+    }
+    Ok(())
+}
+
+/// NVIDIA NeMo Retriever embedding endpoint
+pub struct NemoEmbeddingProvider {
+    http_client: Client,
+    url: String,
+    api_key: String,
+    model: String,
+}
+
+impl NemoEmbeddingProvider {
+    pub fn new(config: &NemotronConfig, http_client: Client) -> Self {
+        Self {
+            http_client,
+            url: format!("{}/embed", config.nemo_retriever_url.trim_end_matches('/')),
+            api_key: config.nemotron_api_key.clone(),
+            model: config.embedding_model.clone(),
+        }
+    }
+
+    async fn post(&self, body: serde_json::Value) -> Result<serde_json::Value> {
+        let response = self.http_client
+            .post(&self.url)
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .json(&body)
+            .send()
+            .await?;
+        Ok(response.json().await?)
+    }
+}
+
+impl EmbeddingProvider for NemoEmbeddingProvider {
+    fn name(&self) -> &'static str {
+        "nemo_cloud"
+    }
+
+    fn is_local(&self) -> bool {
+        false
+    }
+
+    fn embed<'a>(&'a self, text: &'a str) -> BoxFuture<'a, Result<Vec<f32>>> {
+        Box::pin(async move {
+            let result = self.post(serde_json::json!({ "text": text, "model": self.model })).await?;
+            json_vector(&result["embedding"]).ok_or_else(|| anyhow::anyhow!("Invalid embedding response"))
+        })
+    }
+
+    fn embed_batch<'a>(&'a self, texts: &'a [String]) -> BoxFuture<'a, Result<Vec<Vec<f32>>>> {
+        Box::pin(async move {
+            let result = self.post(serde_json::json!({ "input": texts, "model": self.model })).await?;
+            let embeddings: Vec<Vec<f32>> = result["embeddings"]
+                .as_array()
+                .ok_or_else(|| anyhow::anyhow!("Invalid batch embedding response"))?
+                .iter()
+                .map(|embedding| json_vector(embedding).unwrap_or_default())
+                .collect();
+            check_batch_len(&embeddings, texts)?;
+            Ok(embeddings)
+        })
+    }
+}
+
+/// The local model server behind llm_manager: `/api/embeddings` for single texts and `/api/embed`
+/// for batches. Nothing leaves the machine and no API key is needed.
+pub struct LocalEmbeddingProvider {
+    http_client: Client,
+    base_url: String,
+    model: String,
+}
+
+impl LocalEmbeddingProvider {
+    pub fn new(config: &NemotronConfig, http_client: Client) -> Self {
+        let base_url = config
+            .local_embedding_url
+            .clone()
+            .unwrap_or_else(|| crate::llm_manager::DEFAULT_MODEL_SERVER_URL.to_string());
+        Self {
+            http_client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            model: config.embedding_model.clone(),
+        }
+    }
+
+    async fn post(&self, endpoint: &str, body: serde_json::Value) -> Result<serde_json::Value> {
+        let response = self.http_client
+            .post(format!("{}{}", self.base_url, endpoint))
+            .json(&body)
+            .send()
+            .await
+            .with_context(|| format!("Local model server at {} is not reachable", self.base_url))?;
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(anyhow::anyhow!("Local embeddings request failed: {}", error_text));
         }
+        Ok(response.json().await?)
+    }
+}
+
+impl EmbeddingProvider for LocalEmbeddingProvider {
+    fn name(&self) -> &'static str {
+        "local"
+    }
+
+    fn is_local(&self) -> bool {
+        true
+    }
+
+    fn embed<'a>(&'a self, text: &'a str) -> BoxFuture<'a, Result<Vec<f32>>> {
+        Box::pin(async move {
+            let request = crate::llm_manager::EmbeddingRequest {
+                model: self.model.clone(),
+                prompt: text.to_string(),
+                options: None,
+            };
+            let result = self.post("/api/embeddings", serde_json::to_value(request)?).await?;
+            let response: crate::llm_manager::EmbeddingResponse =
+                serde_json::from_value(result).context("Invalid local embedding response")?;
+            Ok(response.embedding)
+        })
+    }
+
+    fn embed_batch<'a>(&'a self, texts: &'a [String]) -> BoxFuture<'a, Result<Vec<Vec<f32>>>> {
+        Box::pin(async move {
+            let result = self.post("/api/embed", serde_json::json!({ "model": self.model, "input": texts })).await?;
+            let embeddings: Vec<Vec<f32>> = serde_json::from_value(result["embeddings"].clone())
+                .context("Invalid local batch embedding response")?;
+            check_batch_len(&embeddings, texts)?;
+            Ok(embeddings)
+        })
+    }
+}
+
+/// Order chunks by their rerank scores, highest first, recording each score and the chunk's
+/// prior position. Chunks the reranker did not score follow in their prior order.
+pub fn apply_rerank_scores(chunks: Vec<RAGChunk>, scores: &[crate::llm_manager::RerankResult], model: &str) -> Vec<RAGChunk> {
+    let mut ranked: Vec<RAGChunk> = chunks
+        .into_iter()
+        .enumerate()
+        .map(|(retrieval_rank, mut chunk)| {
+            chunk.rerank = scores.iter().find(|s| s.index == retrieval_rank).map(|s| RerankScore {
+                model: model.to_string(),
+                score: s.relevance_score,
+                retrieval_rank,
+            });
+            chunk
+        })
+        .collect();
+    ranked.sort_by(|a, b| match (&a.rerank, &b.rerank) {
+        (Some(a), Some(b)) => b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => std::cmp::Ordering::Equal,
+    });
+    ranked
+}
+
+// Words that say nothing about whether a sentence is supported; negations and modals change
+// what a sentence says and are kept
+const SUPPORT_STOP_WORDS: &[&str] = &[
+    "the", "and", "for", "that", "this", "with", "from", "are", "was", "were", "has", "have", "had", "but", "its",
+    "any", "all", "such", "which", "under", "into", "upon", "their", "there", "been", "would", "can", "also",
+    "other", "than", "then", "these", "those", "who", "whom", "what",
+];
+// A sentence negated by one of these is only supported by a chunk that has it too
+const NEGATIONS: &[&str] = &["not", "no", "never", "neither", "nor", "none", "without"];
+// Support counts for this much of the calibrated confidence, retrieval confidence for the rest
+const SUPPORT_WEIGHT: f32 = 0.7;
+// Sentences below this support are reported as unsupported
+const UNSUPPORTED_SENTENCE: f32 = 0.4;
+
+fn negations(text: &str) -> std::collections::HashSet<String> {
+    text.to_lowercase()
+        .replace("n't", " not")
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| NEGATIONS.contains(w))
+        .map(String::from)
+        .collect()
+}
+
+fn support_terms(text: &str) -> std::collections::HashSet<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| (w.len() >= 3 || w.chars().any(|c| c.is_ascii_digit())) && !SUPPORT_STOP_WORDS.contains(w))
+        .map(String::from)
+        .collect()
+}
+
+/// Score each sentence of `answer` by the chunk that covers most of its content words, among the
+/// chunks that have its negations. Citation markers ([1], [p. 4]) are left out, and so are
+/// sentences with no content words.
+pub fn sentence_support(answer: &str, chunks: &[RAGChunk]) -> Vec<SentenceSupport> {
+    let markers = regex::Regex::new(r"\[[^\]]{0,20}\]").unwrap();
+    type Terms = std::collections::HashSet<String>;
+    let chunk_terms: Vec<(&str, Terms, Terms)> = chunks
+        .iter()
+        .map(|c| (c.id.as_str(), support_terms(&c.content), negations(&c.content)))
+        .collect();
+
+    let text = markers.replace_all(answer, "");
+    let mut sentences = Vec::new();
+    for sentence in text.split_inclusive(['.', '?', '!', '\n']) {
+        let terms = support_terms(sentence);
+        if terms.is_empty() {
+            continue;
+        }
+        let negated = negations(sentence);
+        let best = chunk_terms
+            .iter()
+            .filter(|(_, _, chunk_negations)| negated.is_subset(chunk_negations))
+            .map(|(id, words, _)| (*id, terms.iter().filter(|t| words.contains(*t)).count()))
+            .max_by_key(|(_, found)| *found)
+            .filter(|(_, found)| *found > 0);
+        sentences.push(SentenceSupport {
+            sentence: sentence.trim().to_string(),
+            support: best.map_or(0.0, |(_, found)| found as f32 / terms.len() as f32),
+            chunk_id: best.map(|(id, _)| id.to_string()),
+        });
+    }
+    sentences
+}
+
+/// Verify `answer` (whose text is `text`) against the chunks it was generated from. Answers whose
+/// calibrated confidence falls below `threshold` come back as `InsufficientEvidence`.
+pub fn verify_answer<T>(
+    answer: T,
+    text: &str,
+    chunks: &[RAGChunk],
+    retrieval_confidence: f32,
+    threshold: f32,
+) -> VerifiedAnswer<T> {
+    let sentences = sentence_support(text, chunks);
+    let support = if sentences.is_empty() {
+        0.0
+    } else {
+        sentences.iter().map(|s| s.support).sum::<f32>() / sentences.len() as f32
+    };
+    // Retrieval confidence is NaN when nothing was retrieved
+    let retrieval_confidence = if retrieval_confidence.is_finite() { retrieval_confidence.clamp(0.0, 1.0) } else { 0.0 };
+    let confidence = if chunks.is_empty() {
+        0.0
+    } else {
+        SUPPORT_WEIGHT * support + (1.0 - SUPPORT_WEIGHT) * retrieval_confidence
+    };
+    let unsupported = sentences.iter().filter(|s| s.support < UNSUPPORTED_SENTENCE).count();
+    let verification = AnswerVerification {
+        confidence,
+        threshold,
+        support,
+        retrieval_confidence,
+        sentences,
+    };
+
+    if !chunks.is_empty() && confidence >= threshold {
+        return VerifiedAnswer::Answered { answer, verification };
+    }
+    let reason = if chunks.is_empty() {
+        "No retrieved material addresses the question".to_string()
+    } else {
+        format!(
+            "The retrieved material does not support an answer: confidence {:.2} is below {:.2}, {} of {} statements unsupported",
+            confidence,
+            threshold,
+            unsupported,
+            verification.sentences.len()
+        )
+    };
+    VerifiedAnswer::InsufficientEvidence { reason, verification }
+}
+
+/// The embedding provider a configuration selects
+pub fn embedding_provider(config: &NemotronConfig, http_client: Client) -> Arc<dyn EmbeddingProvider> {
+    match config.embedding_backend {
+        EmbeddingBackend::NemoCloud => Arc::new(NemoEmbeddingProvider::new(config, http_client)),
+        EmbeddingBackend::Local => Arc::new(LocalEmbeddingProvider::new(config, http_client)),
+    }
+}
+
+// Weight of the latest run in a stage's latency estimate
+const STAGE_LATENCY_SMOOTHING: f64 = 0.3;
+// Share of a stage's estimate kept each time a budget leaves it out, so a stage that ran slow once
+// is tried again after a few skips instead of being left out for good
+const SKIPPED_STAGE_DECAY: f64 = 0.7;
+
+/// Recent latency of each retrieval stage, to judge which optional stages fit a budget
+#[derive(Default)]
+struct StageLatencies {
+    estimates_ms: std::sync::Mutex<HashMap<&'static str, f64>>,
+}
+
+impl StageLatencies {
+    /// Expected time for `stages` together; stages not yet run count as instant
+    fn estimate(&self, stages: &[&str]) -> Duration {
+        let estimates = self.estimates_ms.lock().unwrap();
+        let ms: f64 = stages.iter().filter_map(|stage| estimates.get(stage)).sum();
+        Duration::from_secs_f64(ms / 1000.0)
+    }
+
+    fn record(&self, stage: &'static str, elapsed: Duration) {
+        let ms = elapsed.as_secs_f64() * 1000.0;
+        let mut estimates = self.estimates_ms.lock().unwrap();
+        let estimate = estimates.entry(stage).or_insert(ms);
+        *estimate += STAGE_LATENCY_SMOOTHING * (ms - *estimate);
+    }
+
+    /// Lower the estimate of a stage left out of a query; skipped stages get no new timings
+    fn decay(&self, stage: &str) {
+        if let Some(estimate) = self.estimates_ms.lock().unwrap().get_mut(stage) {
+            *estimate *= SKIPPED_STAGE_DECAY;
+        }
+    }
+}
+
+/// The time a query has left, and the optional stages left out to stay within it
+struct LatencyBudget {
+    deadline: Option<Instant>,
+    skipped: Vec<String>,
+}
+
+impl LatencyBudget {
+    fn new(budget_ms: Option<u64>) -> Self {
+        Self {
+            deadline: budget_ms.map(|ms| Instant::now() + Duration::from_millis(ms)),
+            skipped: Vec::new(),
+        }
+    }
+
+    fn remaining(&self) -> Option<Duration> {
+        self.deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()))
+    }
+
+    /// Whether an optional stage expected to take `estimate` fits, leaving `reserve` for the
+    /// stages after it; recorded as skipped when it does not
+    fn allows(&mut self, stage: &str, estimate: Duration, reserve: Duration) -> bool {
+        match self.remaining() {
+            Some(remaining) if estimate + reserve >= remaining => {
+                self.skip(stage);
+                false
+            }
+            _ => true,
+        }
+    }
+
+    fn skip(&mut self, stage: &str) {
+        self.skipped.push(stage.to_string());
     }
 }
 
@@ -467,10 +1439,13 @@ pub struct NemotronRAG {
     vector_db: VectorDatabase,
     // embedding_model: Option<EmbeddingModel>,  // Disabled due to candle conflicts
     redis_client: Option<redis::Client>,
+    embedder: Arc<dyn EmbeddingProvider>,
     http_client: Client,
+    jurisdictions: JurisdictionHierarchy,
     embedding_cache: Arc<RwLock<LruCache<String, Vec<f32>>>>,
-    document_graph: Arc<RwLock<HashMap<String, Vec<GraphRelation>>>>,
+    citation_graph: Arc<RwLock<CitationGraph>>,
     legal_terminology: Arc<RwLock<std::collections::HashSet<String>>>,
+    stage_latencies: StageLatencies,
 }
 
 impl NemotronRAG {
@@ -491,27 +1466,109 @@ impl NemotronRAG {
         };
 
         let http_client = Client::new();
+        let embedder = embedding_provider(&config, http_client.clone());
         let embedding_cache = Arc::new(RwLock::new(LruCache::new(std::num::NonZeroUsize::new(10000).unwrap())));
-        let document_graph = Arc::new(RwLock::new(HashMap::new()));
+        let citation_graph = match &config.citation_graph_path {
+            Some(path) => CitationGraph::open(std::path::Path::new(path))
+                .with_context(|| format!("Cannot load citation graph from {}", path))?,
+            None => CitationGraph::new(),
+        };
+        let citation_graph = Arc::new(RwLock::new(citation_graph));
         let legal_terminology = Arc::new(RwLock::new(std::collections::HashSet::new()));
+        let jurisdictions = config.jurisdictions.clone().unwrap_or_default();
+        jurisdictions.validate().context("Invalid jurisdiction hierarchy")?;
+        config.authority_weights.validate().context("Invalid authority weights")?;
 
         Ok(Self {
             config,
             vector_db,
             // embedding_model,  // Disabled due to candle conflicts
             redis_client,
+            embedder,
             http_client,
+            jurisdictions,
             embedding_cache,
-            document_graph,
+            citation_graph,
             legal_terminology,
+            stage_latencies: StageLatencies::default(),
         })
     }
 
+    pub fn config(&self) -> &NemotronConfig {
+        &self.config
+    }
+
+    /// Replace the embedding provider the configuration selected
+    pub fn with_embedding_provider(mut self, embedder: Arc<dyn EmbeddingProvider>) -> Self {
+        self.embedder = embedder;
+        self
+    }
+
+    /// Verify a generated answer against `results`, at the query's confidence threshold or else
+    /// the configured one
+    pub fn verify_answer<T>(&self, answer: T, text: &str, results: &RetrievalResult, threshold: Option<f32>) -> VerifiedAnswer<T> {
+        verify_answer(
+            answer,
+            text,
+            &results.chunks,
+            results.confidence,
+            threshold.unwrap_or(self.config.confidence_threshold),
+        )
+    }
+
+    pub fn embedding_provider(&self) -> &Arc<dyn EmbeddingProvider> {
+        &self.embedder
+    }
+
+    /// Citations between indexed documents
+    pub fn citation_graph(&self) -> &Arc<RwLock<CitationGraph>> {
+        &self.citation_graph
+    }
+
+    /// The embedded store, when the configuration selects `VectorDbType::SqliteLocal`
+    pub fn local_vector_store(&self) -> Option<Arc<LocalVectorStore>> {
+        match &self.vector_db {
+            VectorDatabase::SqliteLocal(store) => Some(store.clone()),
+            _ => None,
+        }
+    }
+
+    /// Copy collections from a Qdrant server into the local store, replacing chunks with the same ids
+    pub async fn migrate_from_qdrant(&self, qdrant_url: &str, collections: &[String]) -> Result<Vec<MigratedCollection>> {
+        let store = self
+            .local_vector_store()
+            .ok_or_else(|| anyhow::anyhow!("The RAG system is not using the local vector store"))?;
+        let client = QdrantClient::from_url(qdrant_url).build()?;
+        let mut migrated = Vec::new();
+        for collection in collections {
+            let chunks = export_qdrant_collection(&client, collection)
+                .await
+                .with_context(|| format!("Cannot read collection {} from {}", collection, qdrant_url))?;
+            let count = chunks.len();
+            if let Some(first) = chunks.first() {
+                let (store, name, dimension) = (store.clone(), collection.clone(), first.embedding.len());
+                let model = self.config.embedding_model.clone();
+                tokio::task::spawn_blocking(move || {
+                    store.create_collection(&name, dimension, &model)?;
+                    chunks.chunks(256).try_for_each(|batch| store.upsert(&name, batch))
+                })
+                .await??;
+            }
+            log::info!("Migrated {} chunks of {} from Qdrant", count, collection);
+            migrated.push(MigratedCollection {
+                collection: collection.clone(),
+                chunks: count,
+            });
+        }
+        Ok(migrated)
+    }
+
     /// Initialize the RAG system
     pub async fn initialize(&mut self) -> Result<()> {
         // Create vector database collections
-        self.vector_db.create_collection("legal_chunks", self.config.embedding_dimension).await?;
-        self.vector_db.create_collection("legal_documents", self.config.embedding_dimension).await?;
+        let (dimension, model) = (self.config.embedding_dimension, &self.config.embedding_model);
+        self.vector_db.create_collection("legal_chunks", dimension, model).await?;
+        self.vector_db.create_collection("legal_documents", dimension, model).await?;
 
         // Load legal terminology
         self.load_legal_terminology().await?;
@@ -523,77 +1580,267 @@ impl NemotronRAG {
     }
 
     /// Process and store a legal document with resource guards
-    pub async fn process_document(&mut self, document: LegalDocument) -> Result<Vec<RAGChunk>> {
+    pub async fn process_document(&self, document: LegalDocument) -> Result<Vec<RAGChunk>> {
+        self.process_document_into("legal_chunks", document, HashMap::new()).await
+    }
+
+    /// Process a document into a dedicated collection, attaching `metadata` to every chunk
+    pub async fn process_document_into(
+        &self,
+        collection: &str,
+        mut document: LegalDocument,
+        metadata: HashMap<String, String>,
+    ) -> Result<Vec<RAGChunk>> {
         // Note: Resource guards would be implemented here if performance tracker is available
         // For now, proceed with document processing
 
+        // Captions name the court, parties and docket; read them before cleaning joins the lines
+        let filing = court_filing::extract_filing_metadata(&document.content);
+        let mut filing_metadata = HashMap::new();
+        if filing.is_filing() {
+            filing.apply_to(&mut document.metadata);
+            filing_metadata = filing.chunk_metadata();
+        }
+        if document.citations.is_empty() {
+            document.citations = document_analyzer::parse_citations(&document.content)
+                .into_iter()
+                .map(|(_, citation)| citation.citation_text)
+                .collect();
+        }
+
         // Clean and preprocess the document
         let cleaned_content = self.clean_legal_text(&document.content);
 
         // Chunk the document using legal-aware chunking
-        let chunks = self.legal_chunk_document(&cleaned_content, &document).await?;
+        let chunks = self.legal_chunk_document(&cleaned_content, &document, collection).await?;
+
+        // A near copy of a document already in the collection is indexed linked to the original
+        let mut fingerprint = ingest_dedup::fingerprint(collection, &document, &cleaned_content, chunks.len());
+        ingest_dedup::check_duplicate(&mut fingerprint);
 
         // Generate embeddings for chunks
         let embedded_chunks = self.generate_embeddings_for_chunks(chunks).await?;
 
         // Extract legal concepts and citations
-        let enriched_chunks = self.enrich_chunks_with_legal_data(embedded_chunks, &document).await?;
+        let mut enriched_chunks = self.enrich_chunks_with_legal_data(embedded_chunks, &document).await?;
+        for chunk in &mut enriched_chunks {
+            if !document.jurisdiction.trim().is_empty() && document.jurisdiction != "General" {
+                chunk.metadata.insert("jurisdiction".to_string(), document.jurisdiction.clone());
+            }
+            chunk.metadata.extend(authority_ranking::chunk_metadata(&document));
+            chunk.metadata.extend(filing_metadata.iter().map(|(k, v)| (k.clone(), v.clone())));
+            chunk.metadata.extend(metadata.iter().map(|(k, v)| (k.clone(), v.clone())));
+            if let Some(original) = &fingerprint.duplicate_of {
+                chunk.metadata.insert("duplicate_of".to_string(), original.clone());
+            }
+        }
+        // Pages read by OCR: each chunk keeps the confidence of the pages it was cut from
+        if let Some(page_confidence) = metadata.get(ocr_ingest::OCR_PAGE_CONFIDENCE_KEY) {
+            ocr_ingest::annotate_chunks(&document.content, &cleaned_content, page_confidence, &mut enriched_chunks);
+        }
 
-        // Store in vector database
-        self.vector_db.upsert_chunks("legal_chunks", &enriched_chunks).await?;
+        // Store in vector database; collections other than the built-in ones are created on first use
+        if collection != "legal_chunks" {
+            let (dimension, model) = (self.config.embedding_dimension, &self.config.embedding_model);
+            match self.vector_db.create_collection(collection, dimension, model).await {
+                Err(e) if e.downcast_ref::<local_vector_store::EmbeddingMismatch>().is_some() => return Err(e),
+                Err(e) => log::debug!("Collection {} not created, assuming it exists: {}", collection, e),
+                Ok(()) => {}
+            }
+        }
+        self.vector_db.upsert_chunks(collection, &enriched_chunks).await?;
 
         // Update document graph
-        self.update_document_graph(&document, &enriched_chunks).await?;
+        self.update_document_graph(&document, metadata.get("matter_id").map(String::as_str)).await?;
+
+        // Case law feeds the judge analytics; a failure there must not fail indexing
+        if let Err(e) = case_analytics::record_indexed_document(&document) {
+            log::warn!("Failed to record case {} for analytics: {}", document.id, e);
+        }
+        if let Err(e) = corpus_topics::record_indexed_document(collection, &self.config.embedding_model, &document, &enriched_chunks) {
+            log::warn!("Failed to record document vector for {}: {}", document.id, e);
+        }
+        if let Err(e) = regulatory_monitor::record_indexed_document(&document) {
+            log::warn!("Failed to record regulatory references for {}: {}", document.id, e);
+        }
+        if let Err(e) = ingest_dedup::record_indexed_document(fingerprint) {
+            log::warn!("Failed to record fingerprint for {}: {}", document.id, e);
+        }
 
         Ok(enriched_chunks)
     }
 
+    /// Remove a document's `chunk_count` chunks from `collection`
+    pub async fn delete_document_chunks(&self, collection: &str, document_id: &str, chunk_count: usize) -> Result<()> {
+        let chunk_ids: Vec<String> = (0..chunk_count).map(|i| format!("{}-chunk-{}", document_id, i)).collect();
+        self.vector_db.delete_chunks(collection, &chunk_ids).await
+    }
+
+    /// Forget a deleted document in what indexing it recorded besides its chunks: the citation
+    /// graph, the document vectors, the regulatory references and the case corpus
+    pub async fn forget_document(&self, document_id: &str) -> Result<()> {
+        self.citation_graph.write().await.remove_document(document_id)?;
+        corpus_topics::remove_indexed_document(document_id)?;
+        regulatory_monitor::remove_indexed_document(document_id)?;
+        case_analytics::remove_indexed_document(document_id)
+    }
+
+    /// Dense search in a single collection, e.g. a crawled knowledge base
+    pub async fn search_collection(&self, collection: &str, query: &str, limit: usize) -> Result<Vec<RAGChunk>> {
+        let query_embedding = self.generate_embedding(query).await?;
+        self.vector_db.search(collection, &query_embedding, limit, None).await
+    }
+
     /// Multi-stage retrieval pipeline with resource guards
     pub async fn retrieve(&self, context: QueryContext) -> Result<RetrievalResult> {
+        request_tracing::in_trace("retrieve", None, self.retrieve_traced(context)).await
+    }
+
+    async fn retrieve_traced(&self, context: QueryContext) -> Result<RetrievalResult> {
         // Note: Resource guards would be implemented here if performance tracker is available
         // For now, proceed with retrieval
-
-        // Stage 1: Query expansion and understanding
-        let expanded_query = self.expand_query(&context).await?;
+        let mut budget = LatencyBudget::new(context.latency_budget_ms);
+
+        // Stage 1: Query expansion and understanding; optional under a latency budget
+        let reserve = self.stage_latencies.estimate(&[
+            "sparse_retrieval",
+            "dense_retrieval",
+            "graph_retrieval",
+            "citation_verification",
+        ]);
+        let expanded_query = if self.fits(&mut budget, "query_expansion", reserve) {
+            let _stage = StageTimer::start("nemotron_rag", "query_expansion");
+            self.timed("query_expansion", self.expand_query(&context)).await?
+        } else {
+            context.clone()
+        };
 
         // Stage 2: Sparse retrieval (BM25-style)
-        let sparse_results = self.sparse_retrieval(&expanded_query).await?;
+        let sparse_results = {
+            let _stage = StageTimer::start("nemotron_rag", "sparse_retrieval");
+            self.timed("sparse_retrieval", self.sparse_retrieval(&expanded_query)).await?
+        };
 
         // Stage 3: Dense retrieval (semantic)
-        let dense_results = self.dense_retrieval(&expanded_query).await?;
+        let dense_results = {
+            let _stage = StageTimer::start("nemotron_rag", "dense_retrieval");
+            self.timed("dense_retrieval", self.dense_retrieval(&expanded_query)).await?
+        };
 
         // Stage 4: Graph-based retrieval
-        let graph_results = self.graph_retrieval(&expanded_query).await?;
+        let graph_results = {
+            let _stage = StageTimer::start("nemotron_rag", "graph_retrieval");
+            self.timed("graph_retrieval", self.graph_retrieval(&expanded_query)).await?
+        };
 
         // Stage 5: Result fusion
-        let fused_results = self.fuse_retrieval_results(sparse_results, dense_results, graph_results).await?;
+        let mut fused_results = self.fuse_retrieval_results(sparse_results, dense_results, graph_results).await?;
+        if context.court.is_some() || context.docket_number.is_some() {
+            fused_results.chunks.retain(|chunk| {
+                court_filing::matches_filing_filter(&chunk.metadata, context.court.as_deref(), context.docket_number.as_deref())
+            });
+        }
 
-        // Stage 6: Reranking with Nemotron
-        let reranked_results = self.rerank_with_nemotron(&fused_results, &context).await?;
+        // Stage 6: Cross-encoder reranking; optional under a latency budget, and cut off when it
+        // would overrun it, keeping retrieval order
+        let reranks = self.config.rerank_backend != RerankBackend::Disabled && !fused_results.chunks.is_empty();
+        let reserve = self.stage_latencies.estimate(&["citation_verification"]);
+        let reranked_results = if !reranks || !self.fits(&mut budget, "rerank", reserve) {
+            fused_results
+        } else {
+            let _stage = StageTimer::start("nemotron_rag", "rerank");
+            match budget.remaining() {
+                None => self.timed("rerank", self.rerank(fused_results, &context)).await,
+                Some(remaining) => {
+                    let limit = remaining.saturating_sub(reserve);
+                    let retrieval_order = fused_results.clone();
+                    let started = Instant::now();
+                    match tokio::time::timeout(limit, self.timed("rerank", self.rerank(fused_results, &context))).await {
+                        Ok(reranked) => reranked,
+                        Err(_) => {
+                            self.stage_latencies.record("rerank", started.elapsed());
+                            budget.skip("rerank");
+                            retrieval_order
+                        }
+                    }
+                }
+            }
+        };
+        let reranked_results = self.apply_authority(reranked_results);
+        let reranked_results = match context.jurisdiction.as_deref() {
+            Some(jurisdiction) if !jurisdiction.trim().is_empty() => {
+                self.apply_jurisdiction(reranked_results, jurisdiction, context.jurisdiction_scope)
+            }
+            _ => reranked_results,
+        };
+
+        // Stages 7-9 post-process the ranked results
+        let _post_processing = StageTimer::start("nemotron_rag", "post_processing");
 
         // Stage 7: Citation verification
-        let verified_citations = self.verify_citations(&reranked_results).await?;
+        let verified_citations = self.timed("citation_verification", self.verify_citations(&reranked_results)).await?;
 
-        // Stage 8: Contradiction detection
-        let contradictions = self.detect_contradictions(&reranked_results).await?;
+        // Stage 8: Contradiction detection; optional under a latency budget
+        let contradictions = if self.fits(&mut budget, "contradiction_detection", Duration::ZERO) {
+            self.timed("contradiction_detection", self.detect_contradictions(&reranked_results)).await?
+        } else {
+            Vec::new()
+        };
 
         // Stage 9: Confidence scoring
         let confidence = self.calculate_confidence(&reranked_results, &context).await?;
 
+        // Citations between the retrieved documents and the rest of the corpus, to explore from
+        let mut graph_relations = reranked_results.graph_relations;
+        let mut document_ids: Vec<String> = reranked_results.chunks.iter().map(|c| c.document_id.clone()).collect();
+        document_ids.sort();
+        document_ids.dedup();
+        graph_relations.extend(self.citation_graph.read().await.relations(&document_ids));
+
+        let mut reasoning = reranked_results.reasoning;
+        if !budget.skipped.is_empty() {
+            reasoning.push(format!(
+                "Skipped {} to stay within the {}ms latency budget",
+                budget.skipped.join(", "),
+                context.latency_budget_ms.unwrap_or_default()
+            ));
+        }
+        reasoning.push("Multi-stage retrieval completed".to_string());
         let result = RetrievalResult {
             chunks: reranked_results.chunks,
             documents: reranked_results.documents,
             citations: verified_citations,
             confidence,
-            reasoning: vec!["Multi-stage retrieval completed".to_string()],
+            reasoning,
             contradictions,
-            graph_relations: reranked_results.graph_relations,
+            graph_relations,
+            skipped_stages: budget.skipped,
+            retrieval_id: None,
         };
 
         Ok(result)
     }
 
-    /// Generate embeddings using local model or NVIDIA API
+    /// Whether an optional stage fits the budget by its recent latency; a stage left out has its
+    /// estimate lowered, so it is probed again on a later query
+    fn fits(&self, budget: &mut LatencyBudget, stage: &str, reserve: Duration) -> bool {
+        let fits = budget.allows(stage, self.stage_latencies.estimate(&[stage]), reserve);
+        if !fits {
+            self.stage_latencies.decay(stage);
+        }
+        fits
+    }
+
+    /// Run a retrieval stage, folding its duration into the estimate latency budgets are
+    /// checked against
+    async fn timed<T>(&self, stage: &'static str, stage_future: impl std::future::Future<Output = T>) -> T {
+        let started = Instant::now();
+        let output = stage_future.await;
+        self.stage_latencies.record(stage, started.elapsed());
+        output
+    }
+
+    /// Generate an embedding with the configured provider, cached by text
     pub async fn generate_embedding(&self, text: &str) -> Result<Vec<f32>> {
         // Check cache first
         {
@@ -603,13 +1850,7 @@ impl NemotronRAG {
             }
         }
 
-        let embedding = if let Some(model) = &self.embedding_model {
-            // Use local embedding model
-            model.encode(text).await?
-        } else {
-            // Use NVIDIA NeMo API
-            self.generate_embedding_via_api(text).await?
-        };
+        let embedding = self.embedder.embed(text).await?;
 
         // Cache the result
         {
@@ -622,92 +1863,95 @@ impl NemotronRAG {
 
     /// Clean legal text for processing
     fn clean_legal_text(&self, content: &str) -> String {
-        // Remove excessive whitespace
-        let cleaned = content.trim().split_whitespace().collect::<Vec<_>>().join(" ");
-
-        // Remove common legal document artifacts
-        let cleaned = cleaned.replace("\\n", " ");
-        let cleaned = cleaned.replace("\\t", " ");
-
-        // Normalize legal citations
-        // This would include more sophisticated citation normalization
-
-        cleaned
-    }
-
-    /// Legal-aware document chunking
-    async fn legal_chunk_document(&self, content: &str, document: &LegalDocument) -> Result<Vec<RAGChunk>> {
-        let mut chunks = Vec::new();
-        let max_chunk_size = self.config.max_chunk_size;
-        let overlap = self.config.chunk_overlap;
-
-        // Split by legal sections first (e.g., "Section", "§", "Article")
-        let section_boundaries = self.find_legal_section_boundaries(content);
-
-        let mut current_pos = 0;
-        let mut chunk_index = 0;
-
-        for (section_start, section_end) in section_boundaries {
-            let section_content = &content[section_start..section_end];
-
-            if section_content.len() <= max_chunk_size {
-                // Section fits in one chunk
-                chunks.push(RAGChunk {
-                    id: format!("{}-chunk-{}", document.id, chunk_index),
-                    document_id: document.id.clone(),
-                    content: section_content.to_string(),
-                    embedding: vec![], // Will be filled later
-                    chunk_index,
-                    tokens: self.estimate_tokens(section_content),
-                    overlap: 0,
-                    legal_concepts: vec![],
-                    cited_authorities: vec![],
-                    confidence: 1.0,
-                    temporal_relevance: 1.0,
-                    created_at: Utc::now(),
-                });
-                chunk_index += 1;
-            } else {
-                // Split section into smaller chunks
-                let section_chunks = self.split_section_into_chunks(section_content, max_chunk_size, overlap);
-                for (i, chunk_content) in section_chunks.iter().enumerate() {
-                    chunks.push(RAGChunk {
-                        id: format!("{}-chunk-{}", document.id, chunk_index),
-                        document_id: document.id.clone(),
-                        content: chunk_content.clone(),
-                        embedding: vec![],
-                        chunk_index,
-                        tokens: self.estimate_tokens(chunk_content),
-                        overlap,
-                        legal_concepts: vec![],
-                        cited_authorities: vec![],
-                        confidence: 1.0,
-                        temporal_relevance: 1.0,
-                        created_at: Utc::now(),
-                    });
-                    chunk_index += 1;
+        clean_legal_text(content)
+    }
+
+    /// Legal-aware document chunking with the profile for the document's type in `collection`
+    async fn legal_chunk_document(&self, content: &str, document: &LegalDocument, collection: &str) -> Result<Vec<RAGChunk>> {
+        let configured = ChunkingProfile {
+            max_chunk_size: self.config.max_chunk_size,
+            chunk_overlap: self.config.chunk_overlap,
+            split_on_sections: true,
+        };
+        let profile = chunking_profiles::indexing_profile(collection, &document.document_type, configured);
+        Ok(legal_chunks(content, document, &profile))
+    }
+
+    /// Generate embeddings for chunks, coalescing uncached chunks into batched requests
+    async fn generate_embeddings_for_chunks(&self, mut chunks: Vec<RAGChunk>) -> Result<Vec<RAGChunk>> {
+        use futures::stream::{self, StreamExt, TryStreamExt};
+
+        let start = std::time::Instant::now();
+
+        // Serve cached chunks first, collect the rest for batching
+        let mut pending = Vec::new();
+        {
+            let cache = self.embedding_cache.read().await;
+            for (i, chunk) in chunks.iter_mut().enumerate() {
+                match cache.peek(&chunk.content) {
+                    Some(embedding) => chunk.embedding = embedding.clone(),
+                    None => pending.push(i),
                 }
             }
+        }
 
-            current_pos = section_end;
+        if pending.is_empty() {
+            return Ok(chunks);
         }
 
-        Ok(chunks)
-    }
+        let batch_size = self.config.embedding_batch_size.max(1);
+        let parallel = self.config.embedding_parallel_requests.max(1);
+        let batches: Vec<Vec<usize>> = pending.chunks(batch_size).map(|c| c.to_vec()).collect();
+        let batch_count = batches.len();
 
-    /// Generate embeddings for chunks
-    async fn generate_embeddings_for_chunks(&self, mut chunks: Vec<RAGChunk>) -> Result<Vec<RAGChunk>> {
-        // Process chunks in parallel for better performance
-        let embeddings: Result<Vec<_>> = futures::future::try_join_all(
-            chunks.iter().map(|chunk| self.generate_embedding(&chunk.content))
-        ).await;
+        let results: Vec<(Vec<usize>, Vec<Vec<f32>>)> = stream::iter(batches.into_iter().map(|indices| {
+            let texts: Vec<String> = indices.iter().map(|&i| chunks[i].content.clone()).collect();
+            async move {
+                let embeddings = self.embedder.embed_batch(&texts).await?;
+                Ok::<_, anyhow::Error>((indices, embeddings))
+            }
+        }))
+        .buffer_unordered(parallel)
+        .try_collect()
+        .await?;
 
-        let embeddings = embeddings?;
+        {
+            let mut cache = self.embedding_cache.write().await;
+            for (indices, embeddings) in results {
+                for (i, embedding) in indices.into_iter().zip(embeddings.into_iter()) {
+                    cache.put(chunks[i].content.clone(), embedding.clone());
+                    chunks[i].embedding = embedding;
+                }
+            }
+        }
 
-        for (chunk, embedding) in chunks.iter_mut().zip(embeddings.into_iter()) {
-            chunk.embedding = embedding;
+        // Report ingestion throughput (tokens are the chunker's estimates)
+        let elapsed_ms = start.elapsed().as_millis() as u64;
+        let tokens: u32 = pending.iter().map(|&i| chunks[i].tokens as u32).sum();
+        let bytes: usize = pending.iter().map(|&i| chunks[i].content.len()).sum();
+        if let Some(tracker) = crate::performance_tracker::get_performance_tracker() {
+            let timer = crate::performance_tracker::PerformanceTimer::new(
+                self.config.embedding_model.clone(),
+                "rag_embedding_batch".to_string(),
+            );
+            let mut metrics = timer.finish_with_tokens(tokens, tokens, 0);
+            metrics.response_time_ms = elapsed_ms;
+            if elapsed_ms > 0 {
+                metrics.tokens_per_second = tokens as f32 * 1000.0 / elapsed_ms as f32;
+                metrics.document_processing_speed_mb_per_sec =
+                    (bytes as f32 / (1024.0 * 1024.0)) / (elapsed_ms as f32 / 1000.0);
+            }
+            tracker.record_metric(metrics).await;
         }
 
+        log::info!(
+            "Embedded {} chunks in {} batches ({} from cache) in {}ms",
+            pending.len(),
+            batch_count,
+            chunks.len() - pending.len(),
+            elapsed_ms
+        );
+
         Ok(chunks)
     }
 
@@ -756,29 +2000,8 @@ impl NemotronRAG {
     }
 
     /// Update document graph with new document
-    async fn update_document_graph(&self, document: &LegalDocument, chunks: &[RAGChunk]) -> Result<()> {
-        let mut graph = self.document_graph.write().await;
-
-        // Extract citations and create graph relationships
-        for chunk in chunks {
-            for citation in &chunk.cited_authorities {
-                // Find the document being cited
-                if let Some(cited_doc_id) = self.find_document_by_citation(citation).await? {
-                    let relation = GraphRelation {
-                        source_doc: document.id.clone(),
-                        target_doc: cited_doc_id,
-                        relation_type: RelationType::Cites,
-                        strength: 1.0,
-                    };
-
-                    graph.entry(document.id.clone())
-                        .or_insert_with(Vec::new)
-                        .push(relation);
-                }
-            }
-        }
-
-        Ok(())
+    async fn update_document_graph(&self, document: &LegalDocument, matter_id: Option<&str>) -> Result<()> {
+        self.citation_graph.write().await.add_document(document, matter_id)
     }
 
     // Helper methods
@@ -805,6 +2028,8 @@ impl NemotronRAG {
             reasoning: vec![],
             contradictions: vec![],
             graph_relations: vec![],
+            skipped_stages: vec![],
+            retrieval_id: None,
         })
     }
 
@@ -828,6 +2053,8 @@ impl NemotronRAG {
             reasoning: vec![],
             contradictions: vec![],
             graph_relations: vec![],
+            skipped_stages: vec![],
+            retrieval_id: None,
         })
     }
 
@@ -842,6 +2069,8 @@ impl NemotronRAG {
             reasoning: vec![],
             contradictions: vec![],
             graph_relations: vec![],
+            skipped_stages: vec![],
+            retrieval_id: None,
         })
     }
 
@@ -873,13 +2102,71 @@ impl NemotronRAG {
             reasoning: vec![],
             contradictions: vec![],
             graph_relations: vec![],
+            skipped_stages: vec![],
+            retrieval_id: None,
         })
     }
 
-    async fn rerank_with_nemotron(&self, results: &RetrievalResult, _context: &QueryContext) -> Result<RetrievalResult> {
-        // Use NVIDIA Nemotron for reranking
-        // This would call the Nemotron API with the query and candidate chunks
-        Ok(results.clone())
+    /// Reorder chunks by cross-encoder relevance to the query. Retrieval order is kept when
+    /// reranking is disabled or the reranker cannot be reached.
+    async fn rerank(&self, mut results: RetrievalResult, context: &QueryContext) -> RetrievalResult {
+        if self.config.rerank_backend == RerankBackend::Disabled || results.chunks.is_empty() {
+            return results;
+        }
+        let base_url = self
+            .config
+            .local_reranker_url
+            .clone()
+            .unwrap_or_else(|| crate::llm_manager::DEFAULT_MODEL_SERVER_URL.to_string());
+        let request = crate::llm_manager::RerankRequest {
+            model: self.config.reranking_model.clone(),
+            query: context.query.clone(),
+            documents: results.chunks.iter().map(|c| c.content.clone()).collect(),
+            top_n: None,
+        };
+        match crate::llm_manager::request_rerank(&self.http_client, &base_url, &request).await {
+            Ok(response) => {
+                results.chunks = apply_rerank_scores(results.chunks, &response.results, &request.model);
+                results
+                    .reasoning
+                    .push(format!("Reranked {} chunks with {}", request.documents.len(), request.model));
+            }
+            Err(e) => {
+                log::warn!("Reranking skipped, keeping retrieval order: {}", e);
+                results.reasoning.push(format!("Reranking with {} unavailable; retrieval order kept", request.model));
+            }
+        }
+        results
+    }
+
+    /// Move binding authority, higher courts and recent documents up by the configured weights
+    fn apply_authority(&self, mut results: RetrievalResult) -> RetrievalResult {
+        let weights = &self.config.authority_weights;
+        if !weights.is_enabled() || results.chunks.is_empty() {
+            return results;
+        }
+        results.chunks = authority_ranking::apply_authority_weights(results.chunks, weights, Utc::now());
+        let boosted = results.chunks.iter().filter(|c| c.authority.as_ref().is_some_and(|a| a.total > 0.0)).count();
+        results.reasoning.push(format!(
+            "Weighted by authority (precedential value, court level, recency): boosted {} of {} chunks",
+            boosted,
+            results.chunks.len()
+        ));
+        results
+    }
+
+    /// Narrow the results to `scope` around `jurisdiction` and move the closest authority up
+    fn apply_jurisdiction(&self, mut results: RetrievalResult, jurisdiction: &str, scope: JurisdictionScope) -> RetrievalResult {
+        let retrieved = results.chunks.len();
+        results.chunks = jurisdiction::apply_jurisdiction(results.chunks, jurisdiction, scope, &self.jurisdictions);
+        results.reasoning.push(format!(
+            "Ranked by jurisdiction {} ({:?}): kept {} of {} chunks",
+            jurisdiction,
+            scope,
+            results.chunks.len(),
+            retrieved
+        ));
+        results
     }
 
     async fn verify_citations(&self, results: &RetrievalResult) -> Result<Vec<CitationInfo>> {
@@ -956,75 +2243,8 @@ impl NemotronRAG {
         Ok(confidence)
     }
 
-    async fn generate_embedding_via_api(&self, text: &str) -> Result<Vec<f32>> {
-        let request_body = serde_json::json!({
-            "text": text,
-            "model": self.config.embedding_model
-        });
-
-        let response = self.http_client
-            .post(&format!("{}/embed", self.config.nemo_retriever_url))
-            .header("Authorization", format!("Bearer {}", self.config.nemotron_api_key))
-            .json(&request_body)
-            .send()
-            .await?;
-
-        let result: serde_json::Value = response.json().await?;
-        let embedding: Vec<f32> = result["embedding"]
-            .as_array()
-            .ok_or_else(|| anyhow::anyhow!("Invalid embedding response"))?
-            .iter()
-            .map(|v| v.as_f64().unwrap_or(0.0) as f32)
-            .collect();
-
-        Ok(embedding)
-    }
-
     // Additional helper methods with simplified implementations
 
-    fn find_legal_section_boundaries(&self, content: &str) -> Vec<(usize, usize)> {
-        // Find section boundaries based on legal document structure
-        let section_markers = ["Section", "§", "Article", "Chapter", "Part"];
-        let mut boundaries = Vec::new();
-
-        // Simplified implementation
-        let sentences: Vec<&str> = content.split('.').collect();
-        let chunk_size = 5; // Sentences per chunk
-
-        for (i, chunk) in sentences.chunks(chunk_size).enumerate() {
-            let start = i * chunk_size;
-            let end = std::cmp::min(start + chunk_size, sentences.len());
-            boundaries.push((start, end));
-        }
-
-        boundaries
-    }
-
-    fn split_section_into_chunks(&self, content: &str, max_size: usize, overlap: usize) -> Vec<String> {
-        let words: Vec<&str> = content.split_whitespace().collect();
-        let mut chunks = Vec::new();
-        let mut start = 0;
-
-        while start < words.len() {
-            let end = std::cmp::min(start + max_size, words.len());
-            let chunk = words[start..end].join(" ");
-            chunks.push(chunk);
-
-            if end >= words.len() {
-                break;
-            }
-
-            start = end - overlap;
-        }
-
-        chunks
-    }
-
-    fn estimate_tokens(&self, text: &str) -> usize {
-        // Rough estimation: 1 token ≈ 0.75 words
-        (text.split_whitespace().count() as f32 * 1.33) as usize
-    }
-
     async fn extract_legal_concepts(&self, content: &str) -> Vec<String> {
         let terminology = self.legal_terminology.read().await;
         let mut concepts = Vec::new();
@@ -1068,12 +2288,6 @@ impl NemotronRAG {
         (-days_old / max_age_days).exp().max(0.1)
     }
 
-    async fn find_document_by_citation(&self, _citation: &str) -> Result<Option<String>> {
-        // This would search for documents that match the citation
-        // Simplified placeholder
-        Ok(None)
-    }
-
     async fn get_legal_synonyms(&self, _query: &str) -> Result<Vec<String>> {
         // Get legal synonyms and related terms
         // This could use a legal thesaurus or API
@@ -1098,10 +2312,12 @@ impl NemotronRAG {
     pub async fn get_health(&self) -> Result<RAGHealth> {
         // Check vector database connection
         let vector_db_connected = match &self.config.vector_db_type {
-            VectorDbType::Qdrant => {
+            VectorDbType::Qdrant | VectorDbType::Hybrid => {
                 // Vector DB is always created during initialization
                 true
             }
+            // The store file was opened during initialization
+            VectorDbType::SqliteLocal => self.local_vector_store().is_some(),
         };
 
         // Check if embeddings are available
@@ -1121,6 +2337,8 @@ impl NemotronRAG {
             status: "operational".to_string(),
             vector_db_connected,
             embeddings_available,
+            embedding_provider: self.embedder.name().to_string(),
+            embeddings_local: self.embedder.is_local(),
             cache_enabled,
             gpu_available,
             total_documents,
@@ -1158,12 +2376,354 @@ mod tests {
             enable_gpu_acceleration: false,
             cache_ttl: 3600,
             lance_db_path: None,
+            local_store_path: None,
+            citation_graph_path: None,
             max_results: 10,
             embedding_dimension: 768,
+            embedding_batch_size: 32,
+            embedding_parallel_requests: 2,
+            embedding_backend: EmbeddingBackend::NemoCloud,
+            local_embedding_url: None,
+            rerank_backend: RerankBackend::Disabled,
+            local_reranker_url: None,
+            jurisdictions: None,
+            authority_weights: AuthorityWeights::default(),
         };
 
         // This test would require actual services running
         // For now, just test the struct creation
         assert!(true);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_legal_section_boundaries() {
+        let text = "Recitals. Section 1 Definitions apply. Section 2 Payment is due. § 3 Notices in writing.";
+        let sections: Vec<&str> = legal_section_boundaries(text).iter().map(|(s, e)| &text[*s..*e]).collect();
+        assert_eq!(sections, vec!["Recitals. ", "Section 1 Definitions apply. ", "Section 2 Payment is due. ", "§ 3 Notices in writing."]);
+        assert_eq!(legal_section_boundaries("Plain letter"), vec![(0, 12)]);
+        assert_eq!(split_into_chunks("a b c d e", 3, 1), vec!["a b c", "c d e"]);
+    }
+
+    #[tokio::test]
+    async fn test_bulk_ingest_reports_each_file() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("sub")).unwrap();
+        std::fs::create_dir(dir.path().join(".git")).unwrap();
+        for name in ["a.pdf", "b.docx", "sub/c.xlsx", "notes.md", ".git/d.pdf", "empty.txt"] {
+            std::fs::write(dir.path().join(name), "x").unwrap();
+        }
+        let files = collect_ingestible_files(dir.path(), true).unwrap();
+        let names: Vec<String> = files
+            .iter()
+            .map(|f| f.strip_prefix(dir.path()).unwrap().to_string_lossy().replace('\\', "/"))
+            .collect();
+        assert_eq!(names, vec!["a.pdf", "b.docx", "empty.txt", "sub/c.xlsx"]);
+        assert_eq!(collect_ingestible_files(dir.path(), false).unwrap().len(), 3);
+
+        let request = BulkIngestRequest {
+            directory: dir.path().to_string_lossy().to_string(),
+            recursive: None,
+            collection: None,
+            document_type: None,
+            jurisdiction: None,
+            matter_id: Some("m-1".to_string()),
+            ocr_languages: None,
+        };
+        let mut events = Vec::new();
+        let summary = bulk_ingest(
+            &request,
+            files.clone(),
+            |path| async move {
+                match path.extension().and_then(|e| e.to_str()) {
+                    Some("docx") => Err(anyhow::anyhow!("corrupt archive")),
+                    Some("txt") => Ok(IngestText::plain("  ".to_string())),
+                    _ => Ok(IngestText::plain("Section 1 The parties agree.".to_string())),
+                }
+            },
+            |document, metadata| async move {
+                assert_eq!(metadata["matter_id"], "m-1");
+                assert!(!document.title.is_empty());
+                Ok(2)
+            },
+            |progress| events.push((progress.file_index, progress.status)),
+        )
+        .await;
+
+        assert_eq!((summary.files_found, summary.indexed, summary.chunks), (4, 2, 4));
+        assert_eq!(summary.failed.len(), 1);
+        assert!(summary.failed[0].path.ends_with("b.docx"));
+        assert_eq!(summary.skipped.len(), 1);
+        assert_eq!(events[1], (2, IngestFileStatus::Failed));
+        assert_eq!(events.len(), 4);
+
+        let id = legal_document_from_file(&files[0], String::new(), &request).id;
+        assert_eq!(id, legal_document_from_file(&files[0], String::new(), &request).id);
+    }
+
+    #[tokio::test]
+    async fn test_local_embedding_backend_stays_on_the_local_server() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let config: NemotronConfig = serde_json::from_value(serde_json::json!({
+            "nemotron_api_key": "",
+            "nemo_retriever_url": "https://api.nemo.nvidia.com",
+            "embedding_model": "nomic-embed-text",
+            "generation_model": "local",
+            "vector_db_type": "Qdrant",
+            "vector_db_url": "http://localhost:6333",
+            "redis_url": null,
+            "max_chunk_size": 512,
+            "chunk_overlap": 50,
+            "reranking_model": "none",
+            "confidence_threshold": 0.7,
+            "enable_gpu_acceleration": false,
+            "cache_ttl": 3600,
+            "lance_db_path": null,
+            "max_results": 10,
+            "embedding_dimension": 3
+        }))
+        .unwrap();
+        assert_eq!(config.embedding_backend, EmbeddingBackend::NemoCloud);
+        assert!(!embedding_provider(&config, Client::new()).is_local());
+
+        // Stand-in for the local model server; answers one /api/embeddings request
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = vec![0u8; 4096];
+            let read = socket.read(&mut request).await.unwrap();
+            let body = r#"{"embedding":[0.5,0.25,0.0]}"#;
+            let response = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+            String::from_utf8_lossy(&request[..read]).to_string()
+        });
+
+        let config = NemotronConfig {
+            embedding_backend: EmbeddingBackend::Local,
+            local_embedding_url: Some(format!("http://{}/", address)),
+            ..config
+        };
+        let provider = embedding_provider(&config, Client::new());
+        assert!(provider.is_local());
+        assert_eq!(provider.embed("indemnification").await.unwrap(), vec![0.5, 0.25, 0.0]);
+
+        let request = server.await.unwrap();
+        assert!(request.starts_with("POST /api/embeddings "));
+        assert!(!request.to_lowercase().contains("authorization"));
+        assert!(request.contains(r#""model":"nomic-embed-text""#));
+    }
+
+    #[tokio::test]
+    async fn test_rerank_orders_chunks_by_local_cross_encoder() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // Stand-in for the local model server; answers one /api/rerank request
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = vec![0u8; 4096];
+            let read = socket.read(&mut request).await.unwrap();
+            let body = r#"{"results":[{"index":2,"relevance_score":0.91},{"index":0,"relevance_score":0.12}]}"#;
+            let response = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+            String::from_utf8_lossy(&request[..read]).to_string()
+        });
+
+        let chunk = |id: &str| RAGChunk {
+            id: id.to_string(),
+            document_id: "doc-1".to_string(),
+            content: format!("Passage {}", id),
+            embedding: vec![],
+            chunk_index: 0,
+            tokens: 2,
+            overlap: 0,
+            legal_concepts: vec![],
+            cited_authorities: vec![],
+            confidence: 1.0,
+            temporal_relevance: 1.0,
+            created_at: Utc::now(),
+            metadata: HashMap::new(),
+            rerank: None,
+            authority: None,
+        };
+        let chunks = vec![chunk("a"), chunk("b"), chunk("c")];
+        let request = crate::llm_manager::RerankRequest {
+            model: "bge-reranker-v2-m3".to_string(),
+            query: "limitation of liability".to_string(),
+            documents: chunks.iter().map(|c| c.content.clone()).collect(),
+            top_n: None,
+        };
+        let response = crate::llm_manager::request_rerank(&Client::new(), &format!("http://{}/", address), &request)
+            .await
+            .unwrap();
+        let ranked = apply_rerank_scores(chunks, &response.results, &request.model);
+
+        // Scored chunks first by score; the unscored one keeps its place after them
+        let order: Vec<&str> = ranked.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(order, vec!["c", "a", "b"]);
+        assert_eq!(
+            ranked[0].rerank,
+            Some(RerankScore {
+                model: "bge-reranker-v2-m3".to_string(),
+                score: 0.91,
+                retrieval_rank: 2,
+            })
+        );
+        assert!(ranked[2].rerank.is_none());
+
+        let request = server.await.unwrap();
+        assert!(request.starts_with("POST /api/rerank "));
+        assert!(request.contains(r#""query":"limitation of liability""#));
+    }
+
+    #[test]
+    fn test_unsupported_answers_are_insufficient_evidence() {
+        let chunk = |id: &str, content: &str| RAGChunk {
+            id: id.to_string(),
+            document_id: "lease".to_string(),
+            content: content.to_string(),
+            embedding: vec![],
+            chunk_index: 0,
+            tokens: 0,
+            overlap: 0,
+            legal_concepts: vec![],
+            cited_authorities: vec![],
+            confidence: 0.9,
+            temporal_relevance: 1.0,
+            created_at: Utc::now(),
+            metadata: HashMap::new(),
+            rerank: None,
+            authority: None,
+        };
+        let chunks = vec![
+            chunk("c1", "The tenant shall pay rent of 2,500 EUR on the first day of each month."),
+            chunk("c2", "Either party may terminate this lease on three months written notice."),
+        ];
+
+        let supported = "Rent of 2,500 EUR is due on the first day of each month [1]. Either party may terminate on three months notice [2].";
+        match verify_answer("answer", supported, &chunks, 0.9, 0.7) {
+            VerifiedAnswer::Answered { answer, verification } => {
+                assert_eq!(answer, "answer");
+                assert_eq!(verification.sentences.len(), 2);
+                assert_eq!(verification.sentences[1].chunk_id.as_deref(), Some("c2"));
+                assert!(verification.confidence >= 0.7);
+            }
+            other => panic!("expected an answer, got {:?}", other),
+        }
+
+        // Negating what the chunk says leaves the sentence unsupported, however many words it shares
+        let negated = sentence_support("The tenant shall not pay rent of 2,500 EUR on the first day of each month.", &chunks);
+        assert_eq!(negated.len(), 1);
+        assert!(negated[0].support < UNSUPPORTED_SENTENCE, "{:?}", negated);
+        assert!(negated[0].chunk_id.is_none());
+        let contracted = sentence_support("Either party can't terminate this lease on three months written notice.", &chunks);
+        assert!(contracted[0].support < UNSUPPORTED_SENTENCE, "{:?}", contracted);
+
+        let invented = "The landlord must repaint the premises every five years. Pets are prohibited.";
+        match verify_answer("answer", invented, &chunks, 0.9, 0.7) {
+            VerifiedAnswer::InsufficientEvidence { reason, verification } => {
+                assert!(verification.confidence < 0.7);
+                assert!(reason.contains("2 of 2 statements unsupported"), "{}", reason);
+            }
+            other => panic!("expected insufficient evidence, got {:?}", other),
+        }
+
+        // Nothing retrieved: never an answer, whatever the retrieval score
+        assert!(matches!(
+            verify_answer((), "Anything.", &[], f32::NAN, 0.0),
+            VerifiedAnswer::InsufficientEvidence { .. }
+        ));
+    }
+
+    #[test]
+    fn test_collapse_duplicates_keeps_newest_admitted_version_per_matter() {
+        let chunk = |document_id: &str, minutes: i64, metadata: &[(&str, &str)]| RAGChunk {
+            id: format!("{}-chunk-0", document_id),
+            document_id: document_id.to_string(),
+            content: String::new(),
+            embedding: vec![],
+            chunk_index: 0,
+            tokens: 0,
+            overlap: 0,
+            legal_concepts: vec![],
+            cited_authorities: vec![],
+            confidence: 0.9,
+            temporal_relevance: 1.0,
+            created_at: DateTime::<Utc>::UNIX_EPOCH + chrono::Duration::minutes(minutes),
+            metadata: metadata.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+            rerank: None,
+            authority: None,
+        };
+        let ids = |chunks: &[RAGChunk]| chunks.iter().map(|c| c.document_id.clone()).collect::<Vec<_>>();
+
+        // The newer copy replaces its original; a copy under another matter stays
+        let mut chunks = vec![
+            chunk("brief", 1, &[]),
+            chunk("brief-v2", 5, &[("duplicate_of", "brief")]),
+            chunk("brief-other", 9, &[("duplicate_of", "brief"), ("matter_id", "m2")]),
+            chunk("lease", 2, &[]),
+        ];
+        collapse_duplicates(&mut chunks);
+        assert_eq!(ids(&chunks), vec!["brief-v2", "brief-other", "lease"]);
+
+        // An original that was deleted or screened out leaves its copy standing in for it
+        let mut chunks = vec![chunk("brief-v2", 5, &[("duplicate_of", "brief")])];
+        collapse_duplicates(&mut chunks);
+        assert_eq!(ids(&chunks), vec!["brief-v2"]);
+
+        // An original re-indexed after its copy is the newer version
+        let mut chunks = vec![chunk("brief", 7, &[]), chunk("brief-v2", 5, &[("duplicate_of", "brief")])];
+        collapse_duplicates(&mut chunks);
+        assert_eq!(ids(&chunks), vec!["brief"]);
+    }
+
+    #[test]
+    fn test_latency_budget_skips_stages_that_do_not_fit() {
+        let latencies = StageLatencies::default();
+        assert_eq!(latencies.estimate(&["rerank"]), Duration::ZERO);
+        latencies.record("rerank", Duration::from_millis(400));
+        latencies.record("rerank", Duration::from_millis(200));
+        latencies.record("dense_retrieval", Duration::from_millis(100));
+        assert!((latencies.estimate(&["rerank"]).as_secs_f64() - 0.34).abs() < 1e-6);
+        assert!((latencies.estimate(&["rerank", "dense_retrieval", "query_expansion"]).as_secs_f64() - 0.44).abs() < 1e-6);
+
+        let mut unbounded = LatencyBudget::new(None);
+        assert!(unbounded.allows("rerank", Duration::from_secs(60), Duration::ZERO));
+        assert!(unbounded.skipped.is_empty());
+
+        let mut budget = LatencyBudget::new(Some(800));
+        assert!(budget.allows("query_expansion", Duration::from_millis(50), Duration::from_millis(300)));
+        assert!(!budget.allows("rerank", latencies.estimate(&["rerank"]), Duration::from_millis(500)));
+        assert!(budget.remaining().unwrap() <= Duration::from_millis(800));
+
+        let mut spent = LatencyBudget::new(Some(0));
+        assert!(!spent.allows("contradiction_detection", Duration::ZERO, Duration::ZERO));
+        assert_eq!(budget.skipped, vec!["rerank"]);
+        assert_eq!(spent.skipped, vec!["contradiction_detection"]);
+
+        // A stage kept out by one slow run is probed again once enough skips lowered its estimate
+        latencies.record("contradiction_detection", Duration::from_millis(1000));
+        let mut skips = 0;
+        while !LatencyBudget::new(Some(300)).allows(
+            "contradiction_detection",
+            latencies.estimate(&["contradiction_detection"]),
+            Duration::ZERO,
+        ) {
+            latencies.decay("contradiction_detection");
+            skips += 1;
+        }
+        assert_eq!(skips, 4);
+        latencies.decay("query_expansion");
+        assert_eq!(latencies.estimate(&["query_expansion"]), Duration::ZERO);
+    }
+}
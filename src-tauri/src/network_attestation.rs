@@ -0,0 +1,431 @@
+use anyhow::{anyhow, Context, Result};
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use ring::signature::{Ed25519KeyPair, KeyPair, UnparsedPublicKey, ED25519};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::Manager;
+
+use crate::security::SecurityManager;
+use crate::{
+    automation_api, calendar_sync, intranet_crawler, knowledge_connectors, llm_manager, object_storage,
+    sanctions_screening, webhooks,
+};
+
+/// Network Attestation for BEAR AI
+/// Lists every network endpoint the running configuration can contact and every port it
+/// listens on, and signs the list with the firm's attestation key so it can be handed to
+/// clients as evidence of a local-only deployment. An installation counts as local-only when no
+/// internet endpoint is contacted without a user starting it and nothing listens beyond
+/// loopback; on-demand endpoints such as model downloads are listed with their purpose.
+const ATTESTATION_FORMAT: &str = "bear-ai-network-attestation";
+const ATTESTATION_FORMAT_VERSION: u32 = 1;
+const PRIVATE_SUFFIXES: [&str; 6] = [".local", ".lan", ".internal", ".corp", ".intranet", ".home.arpa"];
+const FEATURES: [(&str, bool); 12] = [
+    ("desktop", cfg!(feature = "desktop")),
+    ("headless", cfg!(feature = "headless")),
+    ("custom-protocol", cfg!(feature = "custom-protocol")),
+    ("full-rag", cfg!(feature = "full-rag")),
+    ("nemotron-rag", cfg!(feature = "nemotron-rag")),
+    ("qdrant", cfg!(feature = "qdrant")),
+    ("lance", cfg!(feature = "lance")),
+    ("gpu", cfg!(feature = "gpu")),
+    ("gpu-detection", cfg!(feature = "gpu-detection")),
+    ("embedding", cfg!(feature = "embedding")),
+    ("cache", cfg!(feature = "cache")),
+    ("grpc", cfg!(feature = "grpc")),
+];
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum EndpointScope {
+    Loopback,
+    PrivateNetwork,
+    Internet,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ContactTrigger {
+    Background, // contacted on a schedule, at startup or on an event, without a user action
+    UserAction,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NetworkEndpoint {
+    pub subsystem: String,
+    pub url: String, // "*" when any URL supplied at run time can be fetched
+    pub host: String,
+    pub scope: EndpointScope,
+    pub trigger: ContactTrigger,
+    pub purpose: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LocalListener {
+    pub subsystem: String,
+    pub address: String,
+    pub scope: EndpointScope,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttestationReport {
+    pub generated_at: DateTime<Utc>,
+    pub app_version: String,
+    pub features: Vec<String>, // enabled build features
+    pub endpoints: Vec<NetworkEndpoint>,
+    pub listeners: Vec<LocalListener>,
+    pub local_only: bool,
+    pub findings: Vec<String>, // why the installation is not local-only
+}
+
+/// On-disk form of an attestation. The signature covers the exact bytes of `report`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedAttestation {
+    pub format: String,
+    pub format_version: u32,
+    pub report: String,
+    pub signer_key: String, // base64 Ed25519 public key
+    pub signature: String,  // base64
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttestationVerification {
+    pub report: AttestationReport,
+    pub signer_fingerprint: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttestationExport {
+    pub attestation: SignedAttestation,
+    pub path: String,
+    pub signer_fingerprint: String,
+}
+
+pub fn signer_fingerprint(public_key: &[u8]) -> String {
+    hex::encode(&Sha256::digest(public_key)[..16])
+}
+
+/// Where a host lives, judged from its name or address without resolving it
+pub fn classify_host(host: &str) -> EndpointScope {
+    let host = host.trim_start_matches('[').trim_end_matches(']').to_lowercase();
+    if host == "localhost" || host.ends_with(".localhost") {
+        return EndpointScope::Loopback;
+    }
+    if let Ok(ip) = host.parse::<IpAddr>() {
+        return match ip {
+            ip if ip.is_loopback() => EndpointScope::Loopback,
+            IpAddr::V4(v4) if v4.is_private() || v4.is_link_local() => EndpointScope::PrivateNetwork,
+            IpAddr::V6(v6) if (v6.segments()[0] & 0xfe00) == 0xfc00 => EndpointScope::PrivateNetwork, // unique local
+            _ => EndpointScope::Internet, // includes 0.0.0.0, i.e. every interface
+        };
+    }
+    if !host.contains('.') || PRIVATE_SUFFIXES.iter().any(|suffix| host.ends_with(suffix)) {
+        return EndpointScope::PrivateNetwork;
+    }
+    EndpointScope::Internet
+}
+
+fn url_host(url: &str) -> Option<String> {
+    reqwest::Url::parse(url).ok()?.host_str().map(str::to_string)
+}
+
+/// Endpoints and listeners gathered from the running configuration
+#[derive(Debug, Default)]
+pub struct NetworkInventory {
+    endpoints: Vec<NetworkEndpoint>,
+    listeners: Vec<LocalListener>,
+}
+
+impl NetworkInventory {
+    pub fn endpoint(&mut self, subsystem: &str, url: &str, trigger: ContactTrigger, purpose: &str) {
+        let (host, scope) = match url_host(url) {
+            Some(host) => {
+                let scope = classify_host(&host);
+                (host, scope)
+            }
+            None => ("*".to_string(), EndpointScope::Internet), // unknown until run time: assume the worst
+        };
+        self.endpoints.push(NetworkEndpoint {
+            subsystem: subsystem.to_string(),
+            url: url.to_string(),
+            host,
+            scope,
+            trigger,
+            purpose: purpose.to_string(),
+        });
+    }
+
+    pub fn listener(&mut self, subsystem: &str, address: &str) {
+        let host = address.rsplit_once(':').map_or(address, |(host, _)| host);
+        self.listeners.push(LocalListener {
+            subsystem: subsystem.to_string(),
+            address: address.to_string(),
+            scope: classify_host(host),
+        });
+    }
+
+    pub fn into_report(mut self, features: Vec<String>, now: DateTime<Utc>) -> AttestationReport {
+        self.endpoints.sort_by(|a, b| (&a.subsystem, &a.url).cmp(&(&b.subsystem, &b.url)));
+        self.endpoints.dedup_by(|a, b| a.subsystem == b.subsystem && a.url == b.url);
+        self.listeners.sort_by(|a, b| (&a.subsystem, &a.address).cmp(&(&b.subsystem, &b.address)));
+
+        let mut findings: Vec<String> = self
+            .endpoints
+            .iter()
+            .filter(|e| e.scope == EndpointScope::Internet && e.trigger == ContactTrigger::Background)
+            .map(|e| format!("{} contacts {} without a user action ({})", e.subsystem, e.host, e.purpose))
+            .collect();
+        findings.extend(
+            self.listeners
+                .iter()
+                .filter(|l| l.scope != EndpointScope::Loopback)
+                .map(|l| format!("{} accepts connections on {}", l.subsystem, l.address)),
+        );
+
+        AttestationReport {
+            generated_at: now,
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            features,
+            endpoints: self.endpoints,
+            listeners: self.listeners,
+            local_only: findings.is_empty(),
+            findings,
+        }
+    }
+}
+
+pub fn enabled_features() -> Vec<String> {
+    FEATURES.iter().filter(|(_, on)| *on).map(|(name, _)| name.to_string()).collect()
+}
+
+fn scheduled(refresh_hours: Option<u32>) -> ContactTrigger {
+    if refresh_hours.is_some() {
+        ContactTrigger::Background
+    } else {
+        ContactTrigger::UserAction
+    }
+}
+
+/// Everything the managed subsystems are configured to reach. The RAG services are added by
+/// the caller, which owns the RAG state.
+pub fn collect_inventory(app: &tauri::AppHandle) -> NetworkInventory {
+    use ContactTrigger::{Background, UserAction};
+    let mut inventory = NetworkInventory::default();
+
+    let updater = &app.config().tauri.updater;
+    if updater.active {
+        for endpoint in updater.endpoints.iter().flatten() {
+            inventory.endpoint("Updater", &endpoint.0.to_string(), Background, "Update check at startup");
+        }
+    }
+
+    if let Some(llm) = app.try_state::<Arc<llm_manager::LLMManager>>() {
+        inventory.endpoint("Local models", llm.ollama_base_url(), UserAction, "Ollama inference");
+        inventory.listener("Local models", "127.0.0.1:*"); // llama-server processes on ephemeral ports
+    }
+    if llm_manager::MODEL_MANIFEST_PUBLIC_KEY.is_some() {
+        inventory.endpoint("Model catalogue", llm_manager::MODEL_MANIFEST_URL, Background, "Signed model list at startup");
+    }
+    inventory.endpoint("Model downloads", "https://huggingface.co", UserAction, "Model search and downloads");
+
+    for url in [
+        sanctions_screening::EU_CONSOLIDATED_URL,
+        sanctions_screening::OFAC_SDN_URL,
+        sanctions_screening::OFAC_ALT_URL,
+    ] {
+        inventory.endpoint("Sanctions screening", url, UserAction, "Sanctions list updates");
+    }
+    inventory.endpoint("Billing", "https://api.stripe.com/v1", UserAction, "Subscription payments");
+    inventory.endpoint("Billing", "https://api.mollie.com/v2", UserAction, "Subscription payments");
+
+    if let Some(publisher) = webhooks::get_webhook_publisher() {
+        for endpoint in publisher.list_endpoints().into_iter().filter(|e| e.enabled) {
+            inventory.endpoint("Webhooks", &endpoint.url, Background, "Event notifications");
+        }
+    }
+    if let Some(calendar) = app.try_state::<calendar_sync::CalendarSyncStorage>() {
+        if let Some(config) = calendar.config() {
+            inventory.endpoint("Calendar sync", &config.calendar_url, UserAction, "CalDAV deadlines");
+        }
+    }
+    if let Some(crawler) = app.try_state::<intranet_crawler::CrawlerStorage>() {
+        for site in crawler.sites() {
+            for url in &site.seed_urls {
+                inventory.endpoint("Intranet crawler", url, scheduled(site.refresh_hours), &site.name);
+            }
+        }
+    }
+    if let Some(connectors) = app.try_state::<knowledge_connectors::KnowledgeConnectorStorage>() {
+        for source in connectors.sources() {
+            let url = match source.kind {
+                knowledge_connectors::ConnectorKind::Confluence => source.base_url.clone().unwrap_or_default(),
+                knowledge_connectors::ConnectorKind::Notion => knowledge_connectors::NOTION_API.to_string(),
+            };
+            inventory.endpoint("Knowledge connectors", &url, scheduled(source.refresh_hours), &source.name);
+        }
+    }
+    if let Some(storage) = app.try_state::<object_storage::ObjectStorageStorage>() {
+        for target in storage.targets() {
+            inventory.endpoint("Object storage", &target.endpoint_url(), UserAction, &target.name);
+        }
+    }
+    if let Some(automation) = app.try_state::<automation_api::AutomationStorage>() {
+        if let Some(address) = automation.status().address {
+            inventory.listener("Automation API", &address);
+            inventory.endpoint("Automation API", "*", UserAction, "Documents submitted by URL");
+        }
+    }
+    inventory
+}
+
+pub fn sign_report(report: &AttestationReport, key_pair: &Ed25519KeyPair) -> Result<SignedAttestation> {
+    let engine = base64::engine::general_purpose::STANDARD;
+    let report = serde_json::to_string_pretty(report)?;
+    Ok(SignedAttestation {
+        format: ATTESTATION_FORMAT.to_string(),
+        format_version: ATTESTATION_FORMAT_VERSION,
+        signature: engine.encode(key_pair.sign(report.as_bytes())),
+        signer_key: engine.encode(key_pair.public_key()),
+        report,
+    })
+}
+
+/// Check the format and signature; returns the report with the signer's fingerprint, which the
+/// recipient compares with the one the firm gave them
+pub fn verify_attestation(attestation: &SignedAttestation) -> Result<AttestationVerification> {
+    if attestation.format != ATTESTATION_FORMAT {
+        return Err(anyhow!("Not a BEAR AI network attestation"));
+    }
+    if attestation.format_version > ATTESTATION_FORMAT_VERSION {
+        return Err(anyhow!(
+            "Attestation format version {} is newer than supported ({})",
+            attestation.format_version,
+            ATTESTATION_FORMAT_VERSION
+        ));
+    }
+
+    let engine = base64::engine::general_purpose::STANDARD;
+    let public_key = engine.decode(&attestation.signer_key).context("Invalid signer key encoding")?;
+    let signature = engine.decode(&attestation.signature).context("Invalid signature encoding")?;
+    UnparsedPublicKey::new(&ED25519, &public_key)
+        .verify(attestation.report.as_bytes(), &signature)
+        .map_err(|_| anyhow!("Attestation signature verification failed; the report was modified"))?;
+
+    Ok(AttestationVerification {
+        report: serde_json::from_str(&attestation.report).context("Invalid attestation report")?,
+        signer_fingerprint: signer_fingerprint(&public_key),
+    })
+}
+
+pub struct NetworkAttestations {
+    dir: PathBuf,
+    key_path: PathBuf,
+}
+
+impl NetworkAttestations {
+    pub fn new(app_data_dir: &Path) -> Result<Self> {
+        let dir = app_data_dir.join("attestations");
+        fs::create_dir_all(&dir)?;
+        Ok(Self {
+            dir,
+            key_path: app_data_dir.join("attestation_signing_key.enc"),
+        })
+    }
+
+    /// The firm's attestation key, generated on first use and stored encrypted
+    fn signing_key(&self, security: &SecurityManager) -> Result<Ed25519KeyPair> {
+        security.signing_key(&self.key_path, "attestation key")
+    }
+
+    /// Sign a report and keep a copy with the firm's records
+    pub fn attest(&self, report: &AttestationReport, security: &SecurityManager) -> Result<AttestationExport> {
+        let key_pair = self.signing_key(security)?;
+        let attestation = sign_report(report, &key_pair)?;
+        let path = self
+            .dir
+            .join(format!("attestation-{}.json", report.generated_at.format("%Y%m%dT%H%M%SZ")));
+        fs::write(&path, serde_json::to_string_pretty(&attestation)?)?;
+        Ok(AttestationExport {
+            attestation,
+            path: path.display().to_string(),
+            signer_fingerprint: signer_fingerprint(key_pair.public_key().as_ref()),
+        })
+    }
+}
+
+pub type NetworkAttestationStorage = Arc<NetworkAttestations>;
+type SecurityStorage = Arc<Mutex<SecurityManager>>;
+
+/// Sign a report of the current configuration; main adds the RAG services before calling this
+pub fn attest_inventory(
+    inventory: NetworkInventory,
+    attestations: &NetworkAttestations,
+    security: &SecurityStorage,
+) -> Result<AttestationExport, String> {
+    let report = inventory.into_report(enabled_features(), Utc::now());
+    let security = security.lock().unwrap();
+    attestations.attest(&report, &security).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn verify_network_attestation(attestation: SignedAttestation) -> Result<AttestationVerification, String> {
+    verify_attestation(&attestation).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::rand::SystemRandom;
+
+    #[test]
+    fn test_host_classification() {
+        assert_eq!(classify_host("localhost"), EndpointScope::Loopback);
+        assert_eq!(classify_host("127.0.0.1"), EndpointScope::Loopback);
+        assert_eq!(classify_host("[::1]"), EndpointScope::Loopback);
+        assert_eq!(classify_host("10.4.0.12"), EndpointScope::PrivateNetwork);
+        assert_eq!(classify_host("fd12:3456::1"), EndpointScope::PrivateNetwork);
+        assert_eq!(classify_host("kb.firm.local"), EndpointScope::PrivateNetwork);
+        assert_eq!(classify_host("fileserver"), EndpointScope::PrivateNetwork);
+        assert_eq!(classify_host("0.0.0.0"), EndpointScope::Internet);
+        assert_eq!(classify_host("api.notion.com"), EndpointScope::Internet);
+    }
+
+    #[test]
+    fn test_report_findings_and_signature() {
+        let mut inventory = NetworkInventory::default();
+        inventory.endpoint("Local models", "http://127.0.0.1:11434", ContactTrigger::UserAction, "Ollama inference");
+        inventory.endpoint("Model downloads", "https://huggingface.co", ContactTrigger::UserAction, "Downloads");
+        inventory.endpoint("Intranet crawler", "https://kb.firm.local/wiki", ContactTrigger::Background, "Wiki");
+        inventory.listener("Automation API", "127.0.0.1:8787");
+        let now = Utc::now();
+        let report = inventory.into_report(vec!["desktop".to_string()], now);
+        assert!(report.local_only, "{:?}", report.findings);
+        assert_eq!(report.endpoints[0].subsystem, "Intranet crawler");
+
+        let mut inventory = NetworkInventory::default();
+        inventory.endpoint("Updater", "https://github.com/x/latest.json", ContactTrigger::Background, "Update check");
+        inventory.listener("gRPC engine", "0.0.0.0:50051");
+        let report = inventory.into_report(Vec::new(), now);
+        assert!(!report.local_only);
+        assert_eq!(report.findings.len(), 2);
+        assert!(report.findings[0].contains("github.com"));
+
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+        let key_pair = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+        let signed = sign_report(&report, &key_pair).unwrap();
+        let verified = verify_attestation(&signed).unwrap();
+        assert_eq!(verified.signer_fingerprint, signer_fingerprint(key_pair.public_key().as_ref()));
+        assert_eq!(verified.report.findings, report.findings);
+
+        // Claiming local-only after the fact breaks the signature
+        let mut tampered = signed.clone();
+        tampered.report = tampered.report.replace("\"local_only\": false", "\"local_only\": true");
+        assert_ne!(tampered.report, signed.report);
+        assert!(verify_attestation(&tampered).is_err());
+    }
+}
@@ -0,0 +1,272 @@
+use anyhow::Result;
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::document_analyzer::DocumentAnalyzer;
+use crate::document_qa::pdf_pages;
+use crate::nemotron_rag::{clean_legal_text, RAGChunk};
+use crate::ocr_processor::{OcrProcessor, OcrResult};
+
+/// OCR Fallback at Ingest for BEAR AI
+/// A scanned PDF has no text layer, so `pdftotext` gives its pages back empty. Before such a
+/// document is indexed, the pages with next to no text are rendered and read by OCR. The text
+/// keeps the form feed pdftotext puts after every page, which lets each chunk record the OCR
+/// confidence of the pages it was cut from.
+pub const OCR_PAGE_CONFIDENCE_KEY: &str = "ocr_page_confidence";
+const OCR_CONFIDENCE_KEY: &str = "ocr_confidence";
+// A text layer with fewer words on a page is taken for a scan with a stray page number or stamp
+const MIN_PAGE_WORDS: usize = 3;
+const PAGE_BREAK: char = '\u{c}';
+
+/// A file's text for indexing, with the pages that were read by OCR
+#[derive(Debug, Clone, Default)]
+pub struct IngestText {
+    pub text: String,
+    pub ocr_pages: Vec<(usize, f32)>, // 1-based page number, OCR confidence
+}
+
+impl IngestText {
+    pub fn plain(text: String) -> Self {
+        Self {
+            text,
+            ocr_pages: Vec::new(),
+        }
+    }
+
+    /// Document metadata for the index; empty when no page was read by OCR
+    pub fn metadata(&self) -> HashMap<String, String> {
+        if self.ocr_pages.is_empty() {
+            return HashMap::new();
+        }
+        HashMap::from([(OCR_PAGE_CONFIDENCE_KEY.to_string(), format_page_confidence(&self.ocr_pages))])
+    }
+}
+
+/// "3:81.5,4:90.0" for pages 3 and 4
+pub fn format_page_confidence(pages: &[(usize, f32)]) -> String {
+    pages
+        .iter()
+        .map(|(page, confidence)| format!("{}:{:.1}", page, confidence))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+pub fn parse_page_confidence(value: &str) -> Vec<(usize, f32)> {
+    value
+        .split(',')
+        .filter_map(|entry| {
+            let (page, confidence) = entry.split_once(':')?;
+            Some((page.trim().parse().ok()?, confidence.trim().parse().ok()?))
+        })
+        .collect()
+}
+
+/// Indexes of the pages with too little text to have come from a text layer
+pub fn image_only_pages(pages: &[&str]) -> Vec<usize> {
+    pages
+        .iter()
+        .enumerate()
+        .filter(|(_, page)| {
+            page.split_whitespace()
+                .filter(|word| word.chars().any(char::is_alphanumeric))
+                .count()
+                < MIN_PAGE_WORDS
+        })
+        .map(|(index, _)| index)
+        .collect()
+}
+
+/// Put the OCR text of each page (0-based) in place of its extracted text; pages OCR read
+/// nothing from keep what they had
+pub fn merge_ocr_pages(text: &str, ocr: &[(usize, OcrResult)]) -> IngestText {
+    let mut pages: Vec<String> = pdf_pages(text).into_iter().map(String::from).collect();
+    let mut ocr_pages = Vec::new();
+    for (index, result) in ocr {
+        if result.text.trim().is_empty() {
+            continue;
+        }
+        if *index >= pages.len() {
+            pages.resize(index + 1, String::new());
+        }
+        pages[*index] = result.text.replace(PAGE_BREAK, " ");
+        ocr_pages.push((index + 1, result.confidence));
+    }
+    ocr_pages.sort_by_key(|(page, _)| *page);
+    IngestText {
+        text: pages.join(&PAGE_BREAK.to_string()),
+        ocr_pages,
+    }
+}
+
+/// A file's text for indexing. The image-only pages of a PDF are read by OCR when `ocr` is
+/// available; without it, or when OCR fails, they stay empty as before.
+pub async fn extract_ingest_text(analyzer: &DocumentAnalyzer, ocr: Option<&OcrProcessor>, path: &Path) -> Result<IngestText> {
+    let text = analyzer.extract_text(path).await?;
+    let is_pdf = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("pdf"));
+    let ocr = match ocr {
+        Some(ocr) if is_pdf && ocr.is_available() => ocr,
+        _ => return Ok(IngestText::plain(text)),
+    };
+
+    let blank_pages = image_only_pages(&pdf_pages(&text));
+    if blank_pages.is_empty() {
+        return Ok(IngestText::plain(text));
+    }
+    // Without form feeds pdftotext did not run and the page count is unknown, so every page is read
+    let requested = if text.contains(PAGE_BREAK) { Some(blank_pages.as_slice()) } else { None };
+    match ocr.extract_text_from_pdf_pages(&path.to_string_lossy(), requested).await {
+        Ok(results) => {
+            let merged = merge_ocr_pages(&text, &results);
+            log::info!("Read {} image-only pages of {} by OCR", merged.ocr_pages.len(), path.display());
+            Ok(merged)
+        }
+        Err(e) => {
+            log::warn!("OCR of {} failed, indexing its text layer only: {}", path.display(), e);
+            Ok(IngestText::plain(text))
+        }
+    }
+}
+
+/// Record on each chunk the confidence of the OCR-read pages it was cut from. `content` is the
+/// document text with its page breaks, `cleaned` the cleaned text the chunks were cut from, and
+/// `page_confidence` the document's `ocr_page_confidence` metadata, which chunks cut from text
+/// layer pages only lose.
+pub fn annotate_chunks(content: &str, cleaned: &str, page_confidence: &str, chunks: &mut [RAGChunk]) {
+    let ocr_pages = parse_page_confidence(page_confidence);
+
+    // Word ranges of the pages in the cleaned text
+    let mut page_ranges = Vec::new();
+    let mut words = 0;
+    for page in pdf_pages(content) {
+        let start = words;
+        words += clean_legal_text(page).split_whitespace().count();
+        page_ranges.push((start, words));
+    }
+
+    // Chunks come in document order; each is found after where the one before it starts
+    let mut previous: Option<(usize, usize)> = None; // byte offset, word offset
+    for chunk in chunks.iter_mut() {
+        let text = chunk.content.trim();
+        let search_from = match previous {
+            Some((offset, _)) => offset + cleaned[offset..].chars().next().map_or(1, char::len_utf8),
+            None => 0,
+        };
+        let Some(position) = cleaned.get(search_from..).and_then(|rest| rest.find(text)) else {
+            chunk.metadata.remove(OCR_PAGE_CONFIDENCE_KEY);
+            continue;
+        };
+        let offset = search_from + position;
+        let start_word = match previous {
+            Some((previous_offset, previous_word)) => previous_word + cleaned[previous_offset..offset].split_whitespace().count(),
+            None => cleaned[..offset].split_whitespace().count(),
+        };
+        previous = Some((offset, start_word));
+        let end_word = start_word + text.split_whitespace().count();
+
+        let spanned: Vec<(usize, f32)> = ocr_pages
+            .iter()
+            .filter(|(page, _)| {
+                page.checked_sub(1)
+                    .and_then(|index| page_ranges.get(index))
+                    .is_some_and(|(start, end)| *start < end_word && *end > start_word)
+            })
+            .copied()
+            .collect();
+        if spanned.is_empty() {
+            chunk.metadata.remove(OCR_PAGE_CONFIDENCE_KEY);
+        } else {
+            let lowest = spanned.iter().map(|(_, confidence)| *confidence).fold(f32::INFINITY, f32::min);
+            chunk.metadata.insert(OCR_PAGE_CONFIDENCE_KEY.to_string(), format_page_confidence(&spanned));
+            chunk.metadata.insert(OCR_CONFIDENCE_KEY.to_string(), format!("{:.1}", lowest));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nemotron_rag::split_into_chunks;
+    use chrono::Utc;
+
+    fn ocr_result(text: &str, confidence: f32) -> OcrResult {
+        OcrResult {
+            text: text.to_string(),
+            confidence,
+            language: "eng".to_string(),
+            word_count: text.split_whitespace().count(),
+            processing_time_ms: 0,
+            source_file: String::new(),
+        }
+    }
+
+    fn chunk(index: usize, content: &str, metadata: &HashMap<String, String>) -> RAGChunk {
+        RAGChunk {
+            id: format!("doc-chunk-{}", index),
+            document_id: "doc".to_string(),
+            content: content.to_string(),
+            embedding: Vec::new(),
+            chunk_index: index,
+            tokens: 0,
+            overlap: 0,
+            legal_concepts: Vec::new(),
+            cited_authorities: Vec::new(),
+            confidence: 1.0,
+            temporal_relevance: 1.0,
+            created_at: Utc::now(),
+            metadata: metadata.clone(),
+            rerank: None,
+            authority: None,
+        }
+    }
+
+    #[test]
+    fn test_finds_image_only_pages_and_merges_ocr_text() {
+        let extracted = "The parties agree as follows.\n\x0c  12 \n\x0c\x0cSigned by both parties.\n\x0c";
+        let pages = pdf_pages(extracted);
+        assert_eq!(pages.len(), 4);
+        assert_eq!(image_only_pages(&pages), vec![1, 2]);
+        assert_eq!(image_only_pages(&pdf_pages("")), vec![0]);
+
+        let merged = merge_ocr_pages(
+            extracted,
+            &[(1, ocr_result("Payment is due within thirty days.", 82.0)), (2, ocr_result("  ", 0.0))],
+        );
+        let merged_pages = pdf_pages(&merged.text);
+        assert_eq!(merged_pages.len(), 4);
+        assert_eq!(merged_pages[1], "Payment is due within thirty days.");
+        assert_eq!(merged_pages[2], "");
+        assert_eq!(merged.ocr_pages, vec![(2, 82.0)]);
+        assert_eq!(merged.metadata()[OCR_PAGE_CONFIDENCE_KEY], "2:82.0");
+        assert!(IngestText::plain(extracted.to_string()).metadata().is_empty());
+
+        // Pages read from a PDF pdftotext could not open are added in page order
+        let unread = merge_ocr_pages("", &[(1, ocr_result("Second page.", 75.5)), (0, ocr_result("First page.", 90.0))]);
+        assert_eq!(unread.text, "First page.\x0cSecond page.");
+        assert_eq!(parse_page_confidence(&format_page_confidence(&unread.ocr_pages)), vec![(1, 90.0), (2, 75.5)]);
+    }
+
+    #[test]
+    fn test_annotates_chunks_with_confidence_of_their_pages() {
+        let content = "one two three four\x0cfive six seven eight\x0cnine ten eleven twelve\x0c";
+        let cleaned = clean_legal_text(content);
+        let metadata = HashMap::from([(OCR_PAGE_CONFIDENCE_KEY.to_string(), "2:81.0,3:64.5".to_string())]);
+        let mut chunks: Vec<RAGChunk> = split_into_chunks(&cleaned, 4, 1)
+            .iter()
+            .enumerate()
+            .map(|(i, text)| chunk(i, text, &metadata))
+            .collect();
+        assert_eq!(chunks.len(), 4);
+
+        annotate_chunks(content, &cleaned, &metadata[OCR_PAGE_CONFIDENCE_KEY], &mut chunks);
+        // "one..four" is from the text layer, "four..seven" crosses into page 2, and so on
+        assert!(!chunks[0].metadata.contains_key(OCR_PAGE_CONFIDENCE_KEY));
+        assert!(!chunks[0].metadata.contains_key(OCR_CONFIDENCE_KEY));
+        assert_eq!(chunks[1].metadata[OCR_PAGE_CONFIDENCE_KEY], "2:81.0");
+        assert_eq!(chunks[2].metadata[OCR_PAGE_CONFIDENCE_KEY], "2:81.0,3:64.5");
+        assert_eq!(chunks[2].metadata[OCR_CONFIDENCE_KEY], "64.5");
+        assert_eq!(chunks[3].metadata[OCR_PAGE_CONFIDENCE_KEY], "3:64.5");
+    }
+}
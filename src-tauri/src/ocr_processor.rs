@@ -25,6 +25,119 @@ pub struct OcrConfiguration {
     pub confidence_threshold: f32,
     pub preprocessing_enabled: bool,
     pub output_format: String,
+    #[serde(default)]
+    pub recognition_mode: RecognitionMode,
+    // Tesseract traineddata used for handwriting, e.g. "eng_handwritten"; falls back to
+    // `languages` with the LSTM engine when it is not installed
+    #[serde(default)]
+    pub handwriting_model: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum RecognitionMode {
+    #[default]
+    Printed,
+    Handwriting,
+}
+
+/// Pixel rectangle on a page image
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct BoundingBox {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl BoundingBox {
+    fn right(&self) -> u32 {
+        self.x + self.width
+    }
+
+    fn bottom(&self) -> u32 {
+        self.y + self.height
+    }
+
+    fn contains(&self, x: u32, y: u32) -> bool {
+        x >= self.x && x < self.right() && y >= self.y && y < self.bottom()
+    }
+
+    // Gap between two boxes, 0 when they overlap
+    fn distance_to(&self, other: &BoundingBox) -> u32 {
+        let dx = other.x.saturating_sub(self.right()).max(self.x.saturating_sub(other.right()));
+        let dy = other.y.saturating_sub(self.bottom()).max(self.y.saturating_sub(other.bottom()));
+        dx.max(dy)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum MarkKind {
+    SignatureBlock,
+    HandwrittenSignature,
+    NotaryStamp,
+    Seal,
+    Stamp,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DetectedMark {
+    pub page: u32,
+    pub kind: MarkKind,
+    pub bbox: BoundingBox,
+    pub confidence: f32,
+    pub label: Option<String>, // anchor text for signature blocks
+}
+
+/// A signature line or "By:" label and whether ink was found on it
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SignatureBlockStatus {
+    pub page: u32,
+    pub label: String,
+    pub bbox: BoundingBox,
+    pub signed: bool,
+    pub signature_bbox: Option<BoundingBox>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PageSignatureScan {
+    pub page: u32, // 1-based
+    pub width: u32,
+    pub height: u32,
+    pub marks: Vec<DetectedMark>,
+    pub signature_blocks: Vec<SignatureBlockStatus>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SignatureDetectionReport {
+    pub source_file: String,
+    pub pages: Vec<PageSignatureScan>,
+    pub unsigned_blocks: Vec<SignatureBlockStatus>,
+    pub fully_signed: bool, // signature blocks were found and all carry ink
+}
+
+// Share of non-text dark pixels in a signature area above which it counts as signed
+const SIGNATURE_INK_RATIO: f32 = 0.01;
+
+/// A segmented line of handwriting with its own recognition confidence
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HandwritingLine {
+    pub line_index: usize,
+    pub top: u32,
+    pub bottom: u32,
+    pub text: String,
+    pub confidence: f32,
+    pub needs_review: bool, // confidence below the configured threshold
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HandwritingResult {
+    pub ocr: OcrResult,
+    pub lines: Vec<HandwritingLine>,
+    pub flagged_lines: usize,
+    pub needs_review: bool,
+    pub engine: String, // traineddata actually used
 }
 
 impl Default for OcrConfiguration {
@@ -35,6 +148,8 @@ impl Default for OcrConfiguration {
             confidence_threshold: 70.0,
             preprocessing_enabled: true,
             output_format: "txt".to_string(),
+            recognition_mode: RecognitionMode::Printed,
+            handwriting_model: None,
         }
     }
 }
@@ -101,6 +216,10 @@ impl OcrProcessor {
 
     // Extract text from image using Tesseract
     pub async fn extract_text_from_image(&self, image_path: &str) -> Result<OcrResult> {
+        if self.config.recognition_mode == RecognitionMode::Handwriting {
+            return Ok(self.extract_handwriting(image_path).await?.ocr);
+        }
+
         if !self.tesseract_available {
             return Err(anyhow!("Tesseract OCR not available"));
         }
@@ -179,8 +298,66 @@ impl OcrProcessor {
             return Err(anyhow!("Tesseract OCR not available"));
         }
 
-        let path = Path::new(pdf_path);
-        if !path.exists() {
+        let temp_dir = tempfile::tempdir()
+            .map_err(|e| anyhow!("Failed to create temp directory: {}", e))?;
+        let pages = Self::render_pdf_pages(pdf_path, temp_dir.path())?;
+
+        // Process each page image
+        let results = self.ocr_page_images(pdf_path, pages.into_iter().enumerate().collect()).await;
+
+        info!("OCR completed for PDF {}: {} pages processed", pdf_path, results.len());
+        Ok(results.into_iter().map(|(_, result)| result).collect())
+    }
+
+    // OCR selected pages of a PDF (0-based), each rendered on its own, e.g. the pages a text
+    // layer is missing from; all pages when `pages` is None. Results carry their page index;
+    // pages that fail are skipped.
+    pub async fn extract_text_from_pdf_pages(&self, pdf_path: &str, pages: Option<&[usize]>) -> Result<Vec<(usize, OcrResult)>> {
+        if !self.tesseract_available {
+            return Err(anyhow!("Tesseract OCR not available"));
+        }
+
+        let temp_dir = tempfile::tempdir()
+            .map_err(|e| anyhow!("Failed to create temp directory: {}", e))?;
+        let page_images = match pages {
+            Some(pages) => {
+                let mut page_images = Vec::new();
+                for &page in pages {
+                    match Self::render_pdf_page(pdf_path, page, temp_dir.path()) {
+                        Ok(page_image) => page_images.push((page, page_image)),
+                        Err(e) => warn!("Failed to render PDF page {}: {}", page + 1, e),
+                    }
+                }
+                page_images
+            }
+            None => Self::render_pdf_pages(pdf_path, temp_dir.path())?.into_iter().enumerate().collect(),
+        };
+
+        let results = self.ocr_page_images(pdf_path, page_images).await;
+        info!("OCR completed for PDF {}: {} pages processed", pdf_path, results.len());
+        Ok(results)
+    }
+
+    async fn ocr_page_images(&self, pdf_path: &str, page_images: Vec<(usize, std::path::PathBuf)>) -> Vec<(usize, OcrResult)> {
+        let mut results = Vec::new();
+
+        for (page, page_image) in page_images {
+            match self.extract_text_from_image(&page_image.to_string_lossy()).await {
+                Ok(mut result) => {
+                    result.source_file = format!("{}#page-{}", pdf_path, page + 1);
+                    results.push((page, result));
+                }
+                Err(e) => {
+                    warn!("Failed to process PDF page {}: {}", page + 1, e);
+                }
+            }
+        }
+        results
+    }
+
+    // Render PDF pages to PNG images at 300 DPI, in page order
+    fn render_pdf_pages(pdf_path: &str, output_dir: &Path) -> Result<Vec<std::path::PathBuf>> {
+        if !Path::new(pdf_path).exists() {
             return Err(anyhow!("PDF file not found: {}", pdf_path));
         }
 
@@ -189,17 +366,13 @@ impl OcrProcessor {
             return Err(anyhow!("ImageMagick not available for PDF processing"));
         }
 
-        // Create temporary directory for page images
-        let temp_dir = tempfile::tempdir()
-            .map_err(|e| anyhow!("Failed to create temp directory: {}", e))?;
-
         // Convert PDF pages to images using ImageMagick
         let convert_output = Command::new("magick")
             .arg("convert")
             .arg("-density")
             .arg("300") // High DPI for better OCR
             .arg(pdf_path)
-            .arg(temp_dir.path().join("page-%03d.png"))
+            .arg(output_dir.join("page-%03d.png"))
             .output()
             .map_err(|e| anyhow!("Failed to execute ImageMagick: {}", e))?;
 
@@ -208,31 +381,365 @@ impl OcrProcessor {
             return Err(anyhow!("ImageMagick conversion failed: {}", error_message));
         }
 
-        // Process each page image
-        let mut results = Vec::new();
-        let mut page_num = 0;
-
+        let mut pages = Vec::new();
         loop {
-            let page_image = temp_dir.path().join(format!("page-{:03}.png", page_num));
+            let page_image = output_dir.join(format!("page-{:03}.png", pages.len()));
             if !page_image.exists() {
                 break;
             }
+            pages.push(page_image);
+        }
+        Ok(pages)
+    }
 
-            match self.extract_text_from_image(&page_image.to_string_lossy()).await {
-                Ok(mut result) => {
-                    result.source_file = format!("{}#page-{}", pdf_path, page_num + 1);
-                    results.push(result);
-                }
-                Err(e) => {
-                    warn!("Failed to process PDF page {}: {}", page_num + 1, e);
+    // Render one PDF page (0-based) to a PNG image at 300 DPI
+    fn render_pdf_page(pdf_path: &str, page: usize, output_dir: &Path) -> Result<std::path::PathBuf> {
+        if !Path::new(pdf_path).exists() {
+            return Err(anyhow!("PDF file not found: {}", pdf_path));
+        }
+
+        if !Self::check_imagemagick_availability() {
+            return Err(anyhow!("ImageMagick not available for PDF processing"));
+        }
+
+        // ImageMagick selects a single page with a "[index]" suffix on the input file
+        let page_image = output_dir.join(format!("page-{:03}.png", page));
+        let convert_output = Command::new("magick")
+            .arg("convert")
+            .arg("-density")
+            .arg("300")
+            .arg(format!("{}[{}]", pdf_path, page))
+            .arg(&page_image)
+            .output()
+            .map_err(|e| anyhow!("Failed to execute ImageMagick: {}", e))?;
+
+        if !convert_output.status.success() || !page_image.exists() {
+            let error_message = String::from_utf8_lossy(&convert_output.stderr);
+            return Err(anyhow!("ImageMagick conversion failed: {}", error_message));
+        }
+        Ok(page_image)
+    }
+
+    // Locate signature blocks, handwritten signatures, notary stamps and seals on a scanned
+    // page. Coordinates are pixels of the page image (300 DPI for PDF pages).
+    pub async fn detect_signatures_in_image(&self, image_path: &str, page: u32) -> Result<PageSignatureScan> {
+        if !self.tesseract_available {
+            return Err(anyhow!("Tesseract OCR not available"));
+        }
+
+        let path = Path::new(image_path);
+        if !path.exists() {
+            return Err(anyhow!("Image file not found: {}", image_path));
+        }
+
+        let img = image::open(path)
+            .map_err(|e| anyhow!("Failed to load image: {}", e))?;
+        let rgb = img.to_rgb8();
+        let gray = img.to_luma8();
+        let (width, height) = gray.dimensions();
+
+        // Printed words with their boxes, to find the anchors and to tell print from ink
+        let output = Command::new("tesseract")
+            .arg(path)
+            .arg("stdout")
+            .args(["-l", &self.config.languages.join("+"), "--psm", "3", "tsv"])
+            .output()
+            .map_err(|e| anyhow!("Failed to execute Tesseract: {}", e))?;
+        if !output.status.success() {
+            return Err(anyhow!("Tesseract failed: {}", String::from_utf8_lossy(&output.stderr)));
+        }
+        let words = parse_tsv_words(&String::from_utf8_lossy(&output.stdout));
+
+        let threshold = otsu_threshold(&gray);
+        let mut marks = Vec::new();
+        let mut signature_blocks = Vec::new();
+
+        for anchor in find_signature_anchors(&words) {
+            // Signatures sit on or just above the signature line, starting at the label
+            let line_height = anchor.bbox.height.max(20);
+            let region = BoundingBox {
+                x: anchor.bbox.x,
+                y: anchor.bbox.y.saturating_sub(line_height * 5 / 2),
+                width: anchor.bbox.width.max(width / 3).min(width - anchor.bbox.x),
+                height: (anchor.bbox.bottom() + line_height / 2).min(height) - anchor.bbox.y.saturating_sub(line_height * 5 / 2),
+            };
+
+            // Ink that is not part of any recognized word (underscores excluded)
+            let printed: Vec<&BoundingBox> = words
+                .iter()
+                .filter(|w| !w.text.contains("__") && w.bbox.distance_to(&region) == 0)
+                .map(|w| &w.bbox)
+                .collect();
+            let mut ink = 0u32;
+            let (mut min_x, mut min_y, mut max_x, mut max_y) = (u32::MAX, u32::MAX, 0, 0);
+            for y in region.y..region.bottom() {
+                for x in region.x..region.right() {
+                    if gray.get_pixel(x, y)[0] > threshold
+                        || printed.iter().any(|bbox| bbox.contains(x, y))
+                        || is_rule_row(&gray, threshold, x, y)
+                    {
+                        continue;
+                    }
+                    ink += 1;
+                    min_x = min_x.min(x);
+                    min_y = min_y.min(y);
+                    max_x = max_x.max(x);
+                    max_y = max_y.max(y);
                 }
             }
 
-            page_num += 1;
+            let ink_ratio = ink as f32 / (region.width * region.height).max(1) as f32;
+            let signed = ink_ratio >= SIGNATURE_INK_RATIO;
+            let signature_bbox = signed.then(|| BoundingBox {
+                x: min_x,
+                y: min_y,
+                width: max_x - min_x + 1,
+                height: max_y - min_y + 1,
+            });
+
+            marks.push(DetectedMark {
+                page,
+                kind: MarkKind::SignatureBlock,
+                bbox: anchor.bbox.clone(),
+                confidence: anchor.confidence,
+                label: Some(anchor.text.clone()),
+            });
+            if let Some(bbox) = &signature_bbox {
+                marks.push(DetectedMark {
+                    page,
+                    kind: MarkKind::HandwrittenSignature,
+                    bbox: bbox.clone(),
+                    confidence: (ink_ratio / (SIGNATURE_INK_RATIO * 3.0)).min(1.0),
+                    label: None,
+                });
+            }
+            signature_blocks.push(SignatureBlockStatus {
+                page,
+                label: anchor.text,
+                bbox: anchor.bbox,
+                signed,
+                signature_bbox,
+            });
         }
 
-        info!("OCR completed for PDF {}: {} pages processed", pdf_path, results.len());
-        Ok(results)
+        // Stamps and seals are usually printed in colored ink
+        let cell_size = 16;
+        let cells = colored_cell_mask(&rgb, cell_size);
+        let min_cells = ((width / 40) * (height / 40) / (cell_size * cell_size)).max(4) as usize;
+        let notary_words: Vec<&OcrWord> = words
+            .iter()
+            .filter(|w| {
+                let text = w.text.to_lowercase();
+                text.contains("notary") || text.contains("seal") || text == "l.s."
+            })
+            .collect();
+
+        for (cells_in_region, bbox) in connected_regions(&cells.0, cells.1, cells.2) {
+            if cells_in_region < min_cells {
+                continue;
+            }
+            let bbox = BoundingBox {
+                x: bbox.x * cell_size,
+                y: bbox.y * cell_size,
+                width: (bbox.width * cell_size).min(width - bbox.x * cell_size),
+                height: (bbox.height * cell_size).min(height - bbox.y * cell_size),
+            };
+            let near_notary = notary_words.iter().any(|w| w.bbox.distance_to(&bbox) < width / 8);
+            let aspect = bbox.width as f32 / bbox.height.max(1) as f32;
+            let kind = if near_notary {
+                MarkKind::NotaryStamp
+            } else if (0.75..=1.33).contains(&aspect) {
+                MarkKind::Seal
+            } else {
+                MarkKind::Stamp
+            };
+            let fill = cells_in_region as f32 / ((bbox.width / cell_size).max(1) * (bbox.height / cell_size).max(1)) as f32;
+
+            marks.push(DetectedMark {
+                page,
+                kind,
+                bbox,
+                confidence: (0.5 + fill / 2.0).min(1.0),
+                label: None,
+            });
+        }
+
+        Ok(PageSignatureScan {
+            page,
+            width,
+            height,
+            marks,
+            signature_blocks,
+        })
+    }
+
+    // Scan every page of a PDF, or a single image, for signatures and stamps
+    pub async fn detect_signatures(&self, file_path: &str) -> Result<SignatureDetectionReport> {
+        let is_pdf = Path::new(file_path)
+            .extension()
+            .map(|e| e.eq_ignore_ascii_case("pdf"))
+            .unwrap_or(false);
+
+        let mut pages = Vec::new();
+        if is_pdf {
+            let temp_dir = tempfile::tempdir()
+                .map_err(|e| anyhow!("Failed to create temp directory: {}", e))?;
+            for (index, page_image) in Self::render_pdf_pages(file_path, temp_dir.path())?.iter().enumerate() {
+                pages.push(self.detect_signatures_in_image(&page_image.to_string_lossy(), index as u32 + 1).await?);
+            }
+        } else {
+            pages.push(self.detect_signatures_in_image(file_path, 1).await?);
+        }
+
+        let unsigned_blocks: Vec<SignatureBlockStatus> = pages
+            .iter()
+            .flat_map(|p| p.signature_blocks.iter().filter(|b| !b.signed).cloned())
+            .collect();
+
+        info!("Signature scan of {}: {} pages, {} signature blocks, {} unsigned",
+              file_path,
+              pages.len(),
+              pages.iter().map(|p| p.signature_blocks.len()).sum::<usize>(),
+              unsigned_blocks.len());
+
+        Ok(SignatureDetectionReport {
+            source_file: file_path.to_string(),
+            fully_signed: unsigned_blocks.is_empty() && pages.iter().any(|p| !p.signature_blocks.is_empty()),
+            unsigned_blocks,
+            pages,
+        })
+    }
+
+    // Recognize handwritten notes: binarize, split the page into text lines and recognize
+    // each line on its own with the LSTM engine, flagging lines with low confidence
+    pub async fn extract_handwriting(&self, image_path: &str) -> Result<HandwritingResult> {
+        if !self.tesseract_available {
+            return Err(anyhow!("Tesseract OCR not available"));
+        }
+
+        let start_time = std::time::Instant::now();
+        let path = Path::new(image_path);
+        if !path.exists() {
+            return Err(anyhow!("Image file not found: {}", image_path));
+        }
+
+        let img = image::open(path)
+            .map_err(|e| anyhow!("Failed to load image: {}", e))?;
+        let mut gray = img.to_luma8();
+        let page_height = gray.height();
+
+        // Handwriting strokes are thin; upscale low resolution scans before thresholding
+        if gray.height() < 1500 {
+            let scale = 1500.0 / gray.height() as f32;
+            gray = image::imageops::resize(
+                &gray,
+                (gray.width() as f32 * scale) as u32,
+                1500,
+                image::imageops::FilterType::CatmullRom,
+            );
+        }
+
+        let threshold = otsu_threshold(&gray);
+        let binary = image::GrayImage::from_fn(gray.width(), gray.height(), |x, y| {
+            if gray.get_pixel(x, y)[0] > threshold { image::Luma([255]) } else { image::Luma([0]) }
+        });
+
+        let ink_per_row: Vec<u32> = (0..binary.height())
+            .map(|y| (0..binary.width()).filter(|&x| binary.get_pixel(x, y)[0] == 0).count() as u32)
+            .collect();
+        let min_ink = (binary.width() / 200).max(2);
+        let bands = segment_lines(&ink_per_row, min_ink, 12, 8);
+
+        let engine = self.handwriting_engine();
+        let temp_dir = tempfile::tempdir()
+            .map_err(|e| anyhow!("Failed to create temp directory: {}", e))?;
+
+        let mut lines = Vec::new();
+        for (line_index, (top, bottom)) in bands.into_iter().enumerate() {
+            // A little padding keeps ascenders and descenders that cross the band edge
+            let top = top.saturating_sub(4);
+            let bottom = (bottom + 4).min(binary.height());
+            let crop = image::imageops::crop_imm(&binary, 0, top, binary.width(), bottom - top).to_image();
+            let line_path = temp_dir.path().join(format!("line-{:03}.png", line_index));
+            crop.save(&line_path)
+                .map_err(|e| anyhow!("Failed to save line image: {}", e))?;
+
+            // --psm 7: treat the image as a single text line
+            let output = Command::new("tesseract")
+                .arg(&line_path)
+                .arg("stdout")
+                .args(["-l", &engine, "--oem", "1", "--psm", "7", "tsv"])
+                .output()
+                .map_err(|e| anyhow!("Failed to execute Tesseract: {}", e))?;
+            if !output.status.success() {
+                warn!("Tesseract failed on handwriting line {}: {}", line_index + 1, String::from_utf8_lossy(&output.stderr));
+                continue;
+            }
+
+            let (text, confidence) = parse_tsv_line(&String::from_utf8_lossy(&output.stdout));
+            if text.is_empty() {
+                continue;
+            }
+            // Lines are reported in the coordinates of the page as scanned, not the upscaled copy
+            lines.push(HandwritingLine {
+                line_index,
+                top: page_row(top, binary.height(), page_height),
+                bottom: page_row(bottom, binary.height(), page_height),
+                needs_review: confidence < self.config.confidence_threshold,
+                text,
+                confidence,
+            });
+        }
+
+        let text = lines.iter().map(|l| l.text.as_str()).collect::<Vec<_>>().join("\n");
+        // Mean confidence weighted by line length, so short scribbles do not dominate
+        let total_chars: usize = lines.iter().map(|l| l.text.len()).sum();
+        let confidence = if total_chars == 0 {
+            0.0
+        } else {
+            lines.iter().map(|l| l.confidence * l.text.len() as f32).sum::<f32>() / total_chars as f32
+        };
+        let flagged_lines = lines.iter().filter(|l| l.needs_review).count();
+        let processing_time = start_time.elapsed().as_millis() as u64;
+
+        info!("Handwriting OCR completed for {}: {} lines, {} flagged for review in {}ms",
+              image_path, lines.len(), flagged_lines, processing_time);
+
+        Ok(HandwritingResult {
+            ocr: OcrResult {
+                word_count: text.split_whitespace().count(),
+                text,
+                confidence,
+                language: engine.clone(),
+                processing_time_ms: processing_time,
+                source_file: image_path.to_string(),
+            },
+            needs_review: flagged_lines > 0 || lines.is_empty(),
+            flagged_lines,
+            lines,
+            engine,
+        })
+    }
+
+    // Use the handwriting traineddata when it is installed, otherwise the configured languages
+    fn handwriting_engine(&self) -> String {
+        let languages = self.config.languages.join("+");
+        let Some(model) = &self.config.handwriting_model else {
+            return languages;
+        };
+
+        let installed = Command::new("tesseract")
+            .arg("--list-langs")
+            .output()
+            .map(|o| String::from_utf8_lossy(&o.stdout).lines().any(|l| l.trim() == model))
+            .unwrap_or(false);
+
+        if installed {
+            model.clone()
+        } else {
+            warn!("Handwriting model {} not installed, using {}", model, languages);
+            languages
+        }
     }
 
     // Check if ImageMagick is available
@@ -315,6 +822,30 @@ pub async fn ocr_batch_process_images(
         .map_err(|e| format!("Batch OCR failed: {}", e))
 }
 
+#[tauri::command]
+pub async fn ocr_extract_handwriting(
+    image_path: String,
+    config: Option<OcrConfiguration>
+) -> Result<HandwritingResult, String> {
+    let mut ocr_config = config.unwrap_or_default();
+    ocr_config.recognition_mode = RecognitionMode::Handwriting;
+    let processor = OcrProcessor::new(ocr_config);
+
+    processor.extract_handwriting(&image_path).await
+        .map_err(|e| format!("Handwriting OCR failed: {}", e))
+}
+
+#[tauri::command]
+pub async fn ocr_detect_signatures(
+    file_path: String,
+    config: Option<OcrConfiguration>
+) -> Result<SignatureDetectionReport, String> {
+    let processor = OcrProcessor::new(config.unwrap_or_default());
+
+    processor.detect_signatures(&file_path).await
+        .map_err(|e| format!("Signature detection failed: {}", e))
+}
+
 #[tauri::command]
 pub async fn ocr_check_availability() -> Result<bool, String> {
     Ok(OcrProcessor::check_tesseract_availability())
@@ -329,4 +860,335 @@ pub async fn ocr_get_default_config() -> Result<OcrConfiguration, String> {
 pub fn init_ocr_system() -> OcrProcessor {
     let config = OcrConfiguration::default();
     OcrProcessor::new(config)
-}
\ No newline at end of file
+}
+
+// Otsu's method: the gray level that best separates ink from paper
+fn otsu_threshold(image: &image::GrayImage) -> u8 {
+    let mut histogram = [0u64; 256];
+    for pixel in image.pixels() {
+        histogram[pixel[0] as usize] += 1;
+    }
+
+    let total: u64 = histogram.iter().sum();
+    let sum_all: f64 = histogram.iter().enumerate().map(|(i, &c)| i as f64 * c as f64).sum();
+
+    let (mut weight_bg, mut sum_bg) = (0u64, 0f64);
+    let (mut best_threshold, mut best_variance) = (127u8, 0f64);
+    for (level, &count) in histogram.iter().enumerate() {
+        weight_bg += count;
+        if weight_bg == 0 {
+            continue;
+        }
+        let weight_fg = total - weight_bg;
+        if weight_fg == 0 {
+            break;
+        }
+        sum_bg += level as f64 * count as f64;
+        let mean_bg = sum_bg / weight_bg as f64;
+        let mean_fg = (sum_all - sum_bg) / weight_fg as f64;
+        let variance = weight_bg as f64 * weight_fg as f64 * (mean_bg - mean_fg).powi(2);
+        if variance > best_variance {
+            best_variance = variance;
+            best_threshold = level as u8;
+        }
+    }
+    best_threshold
+}
+
+// Split a page into text lines from its horizontal projection profile (ink pixels per row).
+// Rows with less than `min_ink` count as gaps; bands shorter than `min_height` are specks,
+// and bands separated by less than `min_gap` rows are merged (handwriting lines wander).
+/// Map a row of an image resized to `scaled_height` back to the page it was resized from
+fn page_row(row: u32, scaled_height: u32, page_height: u32) -> u32 {
+    if scaled_height == 0 {
+        return row;
+    }
+    ((row as u64 * page_height as u64 + scaled_height as u64 / 2) / scaled_height as u64).min(page_height as u64) as u32
+}
+
+fn segment_lines(ink_per_row: &[u32], min_ink: u32, min_height: u32, min_gap: u32) -> Vec<(u32, u32)> {
+    let mut bands: Vec<(u32, u32)> = Vec::new();
+    let mut start = None;
+
+    for (row, &ink) in ink_per_row.iter().enumerate() {
+        let row = row as u32;
+        match (start, ink >= min_ink) {
+            (None, true) => start = Some(row),
+            (Some(top), false) => {
+                bands.push((top, row));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(top) = start {
+        bands.push((top, ink_per_row.len() as u32));
+    }
+
+    let mut merged: Vec<(u32, u32)> = Vec::new();
+    for band in bands {
+        match merged.last_mut() {
+            Some(last) if band.0 - last.1 < min_gap => last.1 = band.1,
+            _ => merged.push(band),
+        }
+    }
+
+    merged.into_iter().filter(|(top, bottom)| bottom - top >= min_height).collect()
+}
+
+// Text and mean word confidence from Tesseract TSV output for a single line
+fn parse_tsv_line(tsv: &str) -> (String, f32) {
+    let mut words = Vec::new();
+    let mut confidences = Vec::new();
+
+    // Columns: level page block par line word left top width height conf text
+    for row in tsv.lines().skip(1) {
+        let columns: Vec<&str> = row.split('\t').collect();
+        if columns.len() < 12 || columns[0] != "5" {
+            continue;
+        }
+        let word = columns[11].trim();
+        let confidence: f32 = columns[10].parse().unwrap_or(-1.0);
+        if word.is_empty() || confidence < 0.0 {
+            continue;
+        }
+        words.push(word.to_string());
+        confidences.push(confidence);
+    }
+
+    let confidence = if confidences.is_empty() {
+        0.0
+    } else {
+        confidences.iter().sum::<f32>() / confidences.len() as f32
+    };
+    (words.join(" "), confidence)
+}
+
+#[derive(Debug, Clone)]
+struct OcrWord {
+    text: String,
+    confidence: f32,
+    bbox: BoundingBox,
+    line_key: (u32, u32, u32), // block, paragraph, line
+}
+
+// Words with their boxes from Tesseract TSV output
+fn parse_tsv_words(tsv: &str) -> Vec<OcrWord> {
+    tsv.lines()
+        .skip(1)
+        .filter_map(|row| {
+            let columns: Vec<&str> = row.split('\t').collect();
+            if columns.len() < 12 || columns[0] != "5" || columns[11].trim().is_empty() {
+                return None;
+            }
+            let number = |i: usize| columns[i].parse::<u32>().unwrap_or(0);
+            Some(OcrWord {
+                text: columns[11].trim().to_string(),
+                confidence: columns[10].parse().unwrap_or(0.0),
+                bbox: BoundingBox {
+                    x: number(6),
+                    y: number(7),
+                    width: number(8),
+                    height: number(9),
+                },
+                line_key: (number(2), number(3), number(4)),
+            })
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone)]
+struct SignatureAnchor {
+    text: String,
+    bbox: BoundingBox,
+    confidence: f32,
+}
+
+// Lines that call for a signature: "Signature", "Signed", "By:", "Sign here", or a run of
+// underscores. Name/Title/Date lines of the same block are not separate anchors.
+fn find_signature_anchors(words: &[OcrWord]) -> Vec<SignatureAnchor> {
+    let mut lines: Vec<((u32, u32, u32), Vec<&OcrWord>)> = Vec::new();
+    for word in words {
+        match lines.iter_mut().find(|(key, _)| *key == word.line_key) {
+            Some((_, line)) => line.push(word),
+            None => lines.push((word.line_key, vec![word])),
+        }
+    }
+
+    lines
+        .into_iter()
+        .filter_map(|(_, line)| {
+            let text = line.iter().map(|w| w.text.as_str()).collect::<Vec<_>>().join(" ");
+            let lower = text.to_lowercase();
+            let labelled = lower.starts_with("by:")
+                || lower.starts_with("by _")
+                || lower.contains("signature")
+                || lower.starts_with("signed")
+                || lower.contains("sign here");
+            let rule = text.contains("_____");
+            if !labelled && !rule {
+                return None;
+            }
+
+            let x = line.iter().map(|w| w.bbox.x).min()?;
+            let y = line.iter().map(|w| w.bbox.y).min()?;
+            let right = line.iter().map(|w| w.bbox.right()).max()?;
+            let bottom = line.iter().map(|w| w.bbox.bottom()).max()?;
+            Some(SignatureAnchor {
+                text,
+                bbox: BoundingBox {
+                    x,
+                    y,
+                    width: right - x,
+                    height: bottom - y,
+                },
+                // Both a label and a line make a stronger case than either alone
+                confidence: if labelled && rule { 0.95 } else { 0.75 },
+            })
+        })
+        .collect()
+}
+
+// Whether (x, y) lies on a long horizontal rule (the printed signature line itself)
+fn is_rule_row(gray: &image::GrayImage, threshold: u8, x: u32, y: u32) -> bool {
+    let span = 40;
+    let from = x.saturating_sub(span / 2);
+    let to = (from + span).min(gray.width());
+    to - from == span && (from..to).all(|rx| gray.get_pixel(rx, y)[0] <= threshold)
+}
+
+// Grid of cells, row-major, where a noticeable share of pixels is saturated color
+#[allow(clippy::manual_div_ceil)]
+fn colored_cell_mask(rgb: &image::RgbImage, cell_size: u32) -> (Vec<bool>, u32, u32) {
+    let columns = (rgb.width() + cell_size - 1) / cell_size;
+    let rows = (rgb.height() + cell_size - 1) / cell_size;
+    let mut colored = vec![0u32; (columns * rows) as usize];
+
+    for (x, y, pixel) in rgb.enumerate_pixels() {
+        let max = pixel.0.iter().copied().max().unwrap_or(0);
+        let min = pixel.0.iter().copied().min().unwrap_or(0);
+        if max - min > 60 && max < 240 {
+            colored[((y / cell_size) * columns + x / cell_size) as usize] += 1;
+        }
+    }
+
+    let min_pixels = cell_size * cell_size / 12;
+    (colored.into_iter().map(|c| c >= min_pixels).collect(), columns, rows)
+}
+
+// 4-connected regions of set cells, as (cell count, bounding box in cell units)
+fn connected_regions(mask: &[bool], columns: u32, rows: u32) -> Vec<(usize, BoundingBox)> {
+    let mut seen = vec![false; mask.len()];
+    let mut regions = Vec::new();
+
+    for start in 0..mask.len() {
+        if !mask[start] || seen[start] {
+            continue;
+        }
+        seen[start] = true;
+        let mut stack = vec![start];
+        let mut count = 0;
+        let (mut min_x, mut min_y, mut max_x, mut max_y) = (u32::MAX, u32::MAX, 0, 0);
+
+        while let Some(index) = stack.pop() {
+            count += 1;
+            let (x, y) = (index as u32 % columns, index as u32 / columns);
+            min_x = min_x.min(x);
+            min_y = min_y.min(y);
+            max_x = max_x.max(x);
+            max_y = max_y.max(y);
+
+            let neighbours = [
+                (x > 0).then(|| index - 1),
+                (x + 1 < columns).then(|| index + 1),
+                (y > 0).then(|| index - columns as usize),
+                (y + 1 < rows).then(|| index + columns as usize),
+            ];
+            for neighbour in neighbours.into_iter().flatten() {
+                if mask[neighbour] && !seen[neighbour] {
+                    seen[neighbour] = true;
+                    stack.push(neighbour);
+                }
+            }
+        }
+
+        regions.push((
+            count,
+            BoundingBox {
+                x: min_x,
+                y: min_y,
+                width: max_x - min_x + 1,
+                height: max_y - min_y + 1,
+            },
+        ));
+    }
+    regions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_segment_lines_merges_small_gaps_and_drops_specks() {
+        let mut profile = vec![0u32; 100];
+        profile[10..30].iter_mut().for_each(|v| *v = 20);
+        profile[33..40].iter_mut().for_each(|v| *v = 20); // descender gap of 3 rows
+        profile[60..63].iter_mut().for_each(|v| *v = 20); // speck
+        profile[80..98].iter_mut().for_each(|v| *v = 20);
+
+        assert_eq!(segment_lines(&profile, 2, 12, 8), vec![(10, 40), (80, 98)]);
+    }
+
+    #[test]
+    fn test_page_row_maps_upscaled_rows_back_to_the_page() {
+        // A 500 row scan upscaled to 1500 rows
+        assert_eq!(page_row(300, 1500, 500), 100);
+        assert_eq!(page_row(1500, 1500, 500), 500);
+        assert_eq!(page_row(0, 1500, 500), 0);
+        // Scans tall enough are not resized
+        assert_eq!(page_row(1234, 2000, 2000), 1234);
+    }
+
+    #[test]
+    fn test_parse_tsv_line() {
+        let tsv = "level\tpage_num\tblock_num\tpar_num\tline_num\tword_num\tleft\ttop\twidth\theight\tconf\ttext\n\
+                   4\t1\t1\t1\t1\t0\t0\t0\t500\t40\t-1\t\n\
+                   5\t1\t1\t1\t1\t1\t0\t0\t100\t40\t90.5\tCall\n\
+                   5\t1\t1\t1\t1\t2\t110\t0\t100\t40\t45.5\tclient\n";
+        let (text, confidence) = parse_tsv_line(tsv);
+        assert_eq!(text, "Call client");
+        assert!((confidence - 68.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_find_signature_anchors() {
+        let tsv = "level\tpage_num\tblock_num\tpar_num\tline_num\tword_num\tleft\ttop\twidth\theight\tconf\ttext\n\
+                   5\t1\t1\t1\t1\t1\t100\t900\t40\t30\t95\tBy:\n\
+                   5\t1\t1\t1\t1\t2\t150\t900\t400\t30\t60\t____________\n\
+                   5\t1\t1\t1\t2\t1\t100\t950\t80\t30\t95\tName:\n\
+                   5\t1\t2\t1\t1\t1\t100\t100\t300\t30\t95\tAgreement\n";
+        let words = parse_tsv_words(tsv);
+        assert_eq!(words.len(), 4);
+
+        let anchors = find_signature_anchors(&words);
+        assert_eq!(anchors.len(), 1);
+        assert_eq!(anchors[0].bbox, BoundingBox { x: 100, y: 900, width: 450, height: 30 });
+        assert!(anchors[0].confidence > 0.9);
+    }
+
+    #[test]
+    fn test_connected_regions() {
+        // 4x3 grid with an L-shaped region and a single cell
+        let mask = vec![
+            true, false, false, true,
+            true, true, false, false,
+            false, false, false, false,
+        ];
+        let mut regions = connected_regions(&mask, 4, 3);
+        regions.sort_by_key(|(count, _)| *count);
+        assert_eq!(regions.len(), 2);
+        assert_eq!(regions[0], (1, BoundingBox { x: 3, y: 0, width: 1, height: 1 }));
+        assert_eq!(regions[1], (3, BoundingBox { x: 0, y: 0, width: 2, height: 2 }));
+    }
+}
@@ -0,0 +1,258 @@
+use anyhow::{anyhow, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::document_analyzer::DocumentAnalysis;
+use crate::llm_manager::{GenerateOptions, GenerateRequest, LLMManager};
+use crate::local_api::AnalyzerStorage;
+
+/// Output Language for BEAR AI
+/// Risk descriptions and compliance recommendations are written in English and summaries in the
+/// document's own language. When the user picks an output language, that text is translated by
+/// the local LLM after analysis. Quoted passages are swapped for placeholders before translation
+/// so wording taken from the document comes back exactly as written.
+pub const OUTPUT_LANGUAGES: &[(&str, &str)] = &[
+    ("en", "English"),
+    ("nl", "Dutch"),
+    ("de", "German"),
+    ("fr", "French"),
+    ("es", "Spanish"),
+    ("it", "Italian"),
+    ("pt", "Portuguese"),
+];
+
+// Languages the analyzer can detect but we do not offer as output
+const SOURCE_ONLY_LANGUAGES: &[(&str, &str)] = &[
+    ("ru", "Russian"),
+    ("ja", "Japanese"),
+    ("ko", "Korean"),
+    ("zh", "Chinese"),
+];
+
+/// Language generated analysis text is written in before translation
+const GENERATED_TEXT_LANGUAGE: &str = "en";
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OutputLanguageSettings {
+    pub language: Option<String>, // None leaves analysis text as generated
+    pub model: Option<String>,    // None uses the model the generation server has ready
+}
+
+#[derive(Debug)]
+pub struct OutputLanguagePreference {
+    path: PathBuf,
+    settings: Mutex<OutputLanguageSettings>,
+}
+
+impl OutputLanguagePreference {
+    pub fn new(app_data_dir: &Path) -> Self {
+        let path = app_data_dir.join("output_language.json");
+        let settings = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+        OutputLanguagePreference {
+            path,
+            settings: Mutex::new(settings),
+        }
+    }
+
+    pub fn get(&self) -> OutputLanguageSettings {
+        self.settings.lock().unwrap().clone()
+    }
+
+    pub fn set(&self, settings: OutputLanguageSettings) -> Result<()> {
+        if let Some(language) = &settings.language {
+            if !OUTPUT_LANGUAGES.iter().any(|(code, _)| code == language) {
+                return Err(anyhow!("Unsupported output language: {}", language));
+            }
+        }
+        std::fs::write(&self.path, serde_json::to_string_pretty(&settings)?)?;
+        *self.settings.lock().unwrap() = settings;
+        Ok(())
+    }
+}
+
+fn language_name(code: &str) -> &str {
+    OUTPUT_LANGUAGES
+        .iter()
+        .chain(SOURCE_ONLY_LANGUAGES)
+        .find(|(c, _)| *c == code)
+        .map(|(_, name)| *name)
+        .unwrap_or(code)
+}
+
+/// Replace quoted passages with [[Q1]], [[Q2]], ... and return them in order
+pub(crate) fn protect_quotes(text: &str) -> (String, Vec<String>) {
+    let re = Regex::new(r#""[^"\n]+"|“[^”\n]+”|„[^“”\n]+[“”]|«[^»\n]+»"#).unwrap();
+    let mut quotes = Vec::new();
+    let protected = re
+        .replace_all(text, |caps: &regex::Captures| {
+            quotes.push(caps[0].to_string());
+            format!("[[Q{}]]", quotes.len())
+        })
+        .into_owned();
+    (protected, quotes)
+}
+
+/// Put the original quotes back; None if the translation lost a placeholder
+pub(crate) fn restore_quotes(text: &str, quotes: &[String]) -> Option<String> {
+    let mut restored = text.to_string();
+    for (i, quote) in quotes.iter().enumerate() {
+        let placeholder = format!("[[Q{}]]", i + 1);
+        if !restored.contains(&placeholder) {
+            return None;
+        }
+        restored = restored.replacen(&placeholder, quote, 1);
+    }
+    Some(restored)
+}
+
+async fn translate(llm: &LLMManager, model: &str, text: &str, source: &str, target: &str) -> Result<String> {
+    let (protected, quotes) = protect_quotes(text);
+    let request = GenerateRequest {
+        model: model.to_string(),
+        prompt: format!(
+            "Translate the following text from {} into {}. Keep every placeholder such as [[Q1]] exactly as written. Reply with the translation only.\n\n{}",
+            language_name(source),
+            language_name(target),
+            protected
+        ),
+        stream: Some(false),
+        options: Some(GenerateOptions {
+            temperature: Some(0.1),
+            ..Default::default()
+        }),
+        system: Some("You are a legal translator. Preserve the legal meaning and keep defined terms consistent.".to_string()),
+        template: None,
+        context: None,
+        raw: None,
+    };
+    let response = llm.generate_response(request).await?;
+    restore_quotes(response.response.trim(), &quotes)
+        .ok_or_else(|| anyhow!("Translation dropped a quoted passage"))
+}
+
+/// Translate the generated text of an analysis into the configured output language. Either every
+/// field is translated or the analysis is left untouched.
+pub async fn localize_analysis(
+    analysis: &mut DocumentAnalysis,
+    settings: &OutputLanguageSettings,
+    llm: &LLMManager,
+) -> Result<()> {
+    let Some(target) = settings.language.as_deref() else {
+        return Ok(());
+    };
+    let model = match &settings.model {
+        Some(model) => model.clone(),
+        None => llm
+            .default_generation_model()
+            .await
+            .ok_or_else(|| anyhow!("No model is available to translate analysis output"))?,
+    };
+
+    let document_language = analysis.metadata.language.clone();
+    let mut pending: Vec<(&str, String)> = Vec::new();
+    if let Some(summary) = &analysis.summary {
+        pending.push((document_language.as_str(), summary.clone()));
+    }
+    for risk in &analysis.risks {
+        pending.push((GENERATED_TEXT_LANGUAGE, risk.description.clone()));
+        pending.push((GENERATED_TEXT_LANGUAGE, risk.impact.clone()));
+        for strategy in &risk.mitigation_strategies {
+            pending.push((GENERATED_TEXT_LANGUAGE, strategy.clone()));
+        }
+    }
+    for flag in &analysis.compliance_flags {
+        pending.push((GENERATED_TEXT_LANGUAGE, flag.requirement.clone()));
+        pending.push((GENERATED_TEXT_LANGUAGE, flag.recommendation.clone()));
+    }
+
+    // Recommendations repeat across documents and flags; translate each text once
+    let mut translations: HashMap<String, String> = HashMap::new();
+    for (source, text) in pending {
+        if source == target || text.trim().is_empty() || translations.contains_key(&text) {
+            continue;
+        }
+        let translated = translate(llm, &model, &text, source, target).await?;
+        translations.insert(text, translated);
+    }
+
+    let localize = |text: &mut String| {
+        if let Some(translated) = translations.get(text.as_str()) {
+            *text = translated.clone();
+        }
+    };
+    if let Some(summary) = analysis.summary.as_mut() {
+        localize(summary);
+    }
+    for risk in analysis.risks.iter_mut() {
+        localize(&mut risk.description);
+        localize(&mut risk.impact);
+        risk.mitigation_strategies.iter_mut().for_each(localize);
+    }
+    for flag in analysis.compliance_flags.iter_mut() {
+        localize(&mut flag.requirement);
+        localize(&mut flag.recommendation);
+    }
+    analysis.output_language = Some(target.to_string());
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_output_language(
+    analyzer: tauri::State<'_, AnalyzerStorage>,
+) -> Result<OutputLanguageSettings, String> {
+    Ok(analyzer.output_language().get())
+}
+
+#[tauri::command]
+pub async fn set_output_language(
+    settings: OutputLanguageSettings,
+    analyzer: tauri::State<'_, AnalyzerStorage>,
+) -> Result<(), String> {
+    analyzer.output_language().set(settings).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quotes_survive_translation() {
+        let text = r#"The clause "de huurder is aansprakelijk" shifts liability; see also „Haftung“."#;
+        let (protected, quotes) = protect_quotes(text);
+        assert_eq!(protected, "The clause [[Q1]] shifts liability; see also [[Q2]].");
+        assert_eq!(quotes.len(), 2);
+
+        let translated = "De clausule [[Q1]] verschuift de aansprakelijkheid; zie ook [[Q2]].";
+        assert_eq!(
+            restore_quotes(translated, &quotes).unwrap(),
+            r#"De clausule "de huurder is aansprakelijk" verschuift de aansprakelijkheid; zie ook „Haftung“."#
+        );
+        assert_eq!(restore_quotes("De clausule verschuift de aansprakelijkheid.", &quotes), None);
+    }
+
+    #[test]
+    fn test_settings_persist_and_validate() {
+        let dir = tempfile::tempdir().unwrap();
+        let preference = OutputLanguagePreference::new(dir.path());
+        assert_eq!(preference.get(), OutputLanguageSettings::default());
+
+        let dutch = OutputLanguageSettings {
+            language: Some("nl".to_string()),
+            model: None,
+        };
+        preference.set(dutch.clone()).unwrap();
+        assert!(preference
+            .set(OutputLanguageSettings {
+                language: Some("tlh".to_string()),
+                model: None,
+            })
+            .is_err());
+        assert_eq!(OutputLanguagePreference::new(dir.path()).get(), dutch);
+    }
+}
@@ -0,0 +1,570 @@
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+use crate::local_api::AnalyzerStorage;
+use crate::matters::{name_tokens, MatterStorage, ENTITY_SUFFIXES};
+
+/// Party Registry for BEAR AI
+/// Clusters the organizations and people named across a matter's documents into canonical
+/// parties. Spellings that differ only in case, punctuation or corporate suffix ("ABC Corp",
+/// "ABC Corporation") resolve to one party, and the bare name ("ABC") is counted as a mention
+/// of it too. Aliases are kept between runs, so parties merged or renamed by hand stay that way.
+// Pattern-matched person names (0.6) are too noisy to open a party on their own
+const MIN_PARTY_CONFIDENCE: f32 = 0.7;
+// Bare names shorter than this are not searched for, e.g. "AB" of "AB Ltd"
+const MIN_SHORT_NAME: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PartyRole {
+    Client,
+    Adverse,
+    Related,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PartyMention {
+    pub document: String, // file path
+    pub alias: String,    // spelling as it appears in the document
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Party {
+    pub id: String,
+    pub canonical_name: String,
+    pub aliases: Vec<String>, // every spelling seen or added, the canonical name included
+    #[serde(default)]
+    pub role: Option<PartyRole>, // set for the client and the parties named at intake
+    #[serde(default)]
+    pub confirmed: bool, // renamed or merged by hand; the canonical name is kept
+    #[serde(default)]
+    pub mentions: Vec<PartyMention>,
+    pub updated_at: String,
+}
+
+impl Party {
+    fn new(name: &str) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            canonical_name: name.to_string(),
+            aliases: vec![name.to_string()],
+            role: None,
+            confirmed: false,
+            mentions: Vec::new(),
+            updated_at: Utc::now().to_rfc3339(),
+        }
+    }
+
+    /// Whether `name` is a spelling of this party, ignoring case, punctuation and corporate suffixes
+    fn matches(&self, name: &str) -> bool {
+        let tokens = name_tokens(name);
+        !tokens.is_empty() && self.aliases.iter().any(|alias| name_tokens(alias) == tokens)
+    }
+
+    fn add_alias(&mut self, alias: &str) {
+        if !self.aliases.iter().any(|a| a.eq_ignore_ascii_case(alias)) {
+            self.aliases.push(alias.to_string());
+        }
+    }
+
+    pub fn total_mentions(&self) -> usize {
+        self.mentions.iter().map(|m| m.count).sum()
+    }
+}
+
+/// One document mentioning a party
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PartyDocument {
+    pub document: String,
+    pub mentions: usize,
+    pub aliases: Vec<String>, // spellings used in this document
+}
+
+/// A party with every document of the matter that mentions it, most mentions first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PartyView {
+    pub matter_id: String,
+    pub party: Party,
+    pub documents: Vec<PartyDocument>,
+}
+
+/// "ABC Corp" -> "ABC", "The Coca-Cola Company" -> "Coca-Cola"; None when there is no suffix
+fn short_name(name: &str) -> Option<String> {
+    let words: Vec<&str> = name.split_whitespace().collect();
+    let is_suffix = |word: &&str| {
+        let word: String = word.chars().filter(|c| c.is_alphanumeric()).collect();
+        ENTITY_SUFFIXES.contains(&word.to_lowercase().as_str())
+    };
+    let start = words.iter().position(|w| !is_suffix(w))?;
+    let end = words.iter().rposition(|w| !is_suffix(w))? + 1;
+    if start == 0 && end == words.len() {
+        return None;
+    }
+    let short = words[start..end].join(" ").trim_end_matches(',').to_string();
+    (short.len() >= MIN_SHORT_NAME).then_some(short)
+}
+
+/// Add `name` to the party it is a spelling of, or open a new party for it
+fn resolve_name(parties: &mut Vec<Party>, name: &str) -> Option<usize> {
+    let name = name.trim().trim_end_matches(',').trim();
+    if name_tokens(name).is_empty() {
+        return None;
+    }
+    match parties.iter().position(|p| p.matches(name)) {
+        Some(index) => {
+            parties[index].add_alias(name);
+            Some(index)
+        }
+        None => {
+            parties.push(Party::new(name));
+            Some(parties.len() - 1)
+        }
+    }
+}
+
+fn at_word_boundary(text: &str, start: usize, end: usize) -> bool {
+    let before = text[..start].chars().next_back();
+    let after = text[end..].chars().next();
+    !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+}
+
+/// Count each party's spellings in each (path, text) document. Overlapping spellings count once,
+/// for the longest, so "ABC Corp" is not also a mention of "ABC".
+fn index_mentions(parties: &mut [Party], documents: &[(String, String)]) {
+    let mut spellings: Vec<(usize, String)> = parties
+        .iter()
+        .enumerate()
+        .flat_map(|(index, party)| {
+            let shorts = party.aliases.iter().filter_map(|a| short_name(a)).collect::<Vec<_>>();
+            party.aliases.iter().cloned().chain(shorts).map(move |s| (index, s.to_ascii_lowercase()))
+        })
+        .collect();
+    spellings.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then_with(|| a.1.cmp(&b.1)).then_with(|| a.0.cmp(&b.0)));
+    // A spelling shared by two parties belongs to the first
+    spellings.dedup_by(|a, b| a.1 == b.1);
+
+    for party in parties.iter_mut() {
+        party.mentions.clear();
+    }
+    for (document, text) in documents {
+        // ASCII lowercasing keeps byte offsets, so matches slice the original text
+        let lower = text.to_ascii_lowercase();
+        let mut taken: Vec<(usize, usize)> = Vec::new();
+        let mut counts: BTreeMap<(usize, String), usize> = BTreeMap::new();
+        for (party, spelling) in &spellings {
+            for (start, _) in lower.match_indices(spelling.as_str()) {
+                let end = start + spelling.len();
+                if !at_word_boundary(&lower, start, end) || taken.iter().any(|&(s, e)| start < e && s < end) {
+                    continue;
+                }
+                taken.push((start, end));
+                *counts.entry((*party, text[start..end].to_string())).or_default() += 1;
+            }
+        }
+        for ((party, alias), count) in counts {
+            parties[party].mentions.push(PartyMention {
+                document: document.clone(),
+                alias,
+                count,
+            });
+        }
+    }
+}
+
+/// The most mentioned full spelling, the longest on ties; bare names are only used when there
+/// is nothing else
+fn choose_canonical(party: &Party) -> String {
+    let shorts: BTreeSet<String> = party.aliases.iter().filter_map(|a| short_name(a)).collect();
+    let mentions = |alias: &str| {
+        party
+            .mentions
+            .iter()
+            .filter(|m| m.alias.eq_ignore_ascii_case(alias))
+            .map(|m| m.count)
+            .sum::<usize>()
+    };
+    party
+        .aliases
+        .iter()
+        .max_by_key(|alias| (!shorts.contains(alias.as_str()), mentions(alias), alias.len()))
+        .cloned()
+        .unwrap_or_else(|| party.canonical_name.clone())
+}
+
+/// Resolve a matter's parties from the names given at intake, the party names extracted from
+/// its documents and the (path, text) of each document. Existing parties keep their id and
+/// aliases; those no longer mentioned anywhere are dropped unless named at intake or edited.
+pub fn resolve_parties(
+    existing: Vec<Party>,
+    intake: &[(String, PartyRole)],
+    extracted: &[String],
+    documents: &[(String, String)],
+) -> Vec<Party> {
+    let mut parties = existing;
+    for party in parties.iter_mut() {
+        party.role = None;
+    }
+    for (name, role) in intake {
+        if let Some(index) = resolve_name(&mut parties, name) {
+            let party = &mut parties[index];
+            if party.role.is_none() {
+                party.role = Some(*role);
+                if !party.confirmed {
+                    party.canonical_name = name.trim().to_string();
+                }
+            }
+        }
+    }
+    for name in extracted {
+        resolve_name(&mut parties, name);
+    }
+
+    index_mentions(&mut parties, documents);
+    parties.retain(|p| !p.mentions.is_empty() || p.role.is_some() || p.confirmed);
+
+    let now = Utc::now().to_rfc3339();
+    for party in parties.iter_mut() {
+        let found: Vec<String> = party.mentions.iter().map(|m| m.alias.clone()).collect();
+        for alias in found {
+            party.add_alias(&alias);
+        }
+        if party.role.is_none() && !party.confirmed {
+            party.canonical_name = choose_canonical(party);
+        }
+        party.updated_at = now.clone();
+    }
+    parties.sort_by(|a, b| {
+        b.role
+            .is_some()
+            .cmp(&a.role.is_some())
+            .then_with(|| b.total_mentions().cmp(&a.total_mentions()))
+            .then_with(|| a.canonical_name.cmp(&b.canonical_name))
+    });
+    parties
+}
+
+pub struct PartyRegistry {
+    path: PathBuf,
+    parties: Mutex<BTreeMap<String, Vec<Party>>>, // matter id -> parties
+}
+
+impl PartyRegistry {
+    pub fn new(app_data_dir: &Path) -> Result<Self> {
+        let path = app_data_dir.join("party_registry.json");
+        let parties = if path.exists() {
+            serde_json::from_str(&fs::read_to_string(&path)?)?
+        } else {
+            BTreeMap::new()
+        };
+        Ok(Self {
+            path,
+            parties: Mutex::new(parties),
+        })
+    }
+
+    fn persist(&self, parties: &BTreeMap<String, Vec<Party>>) -> Result<()> {
+        fs::write(&self.path, serde_json::to_string_pretty(parties)?)?;
+        Ok(())
+    }
+
+    pub fn list(&self, matter_id: &str) -> Vec<Party> {
+        self.parties.lock().unwrap().get(matter_id).cloned().unwrap_or_default()
+    }
+
+    pub fn replace(&self, matter_id: &str, parties: Vec<Party>) -> Result<()> {
+        let mut all = self.parties.lock().unwrap();
+        all.insert(matter_id.to_string(), parties);
+        self.persist(&all)
+    }
+
+    fn edit<T>(&self, matter_id: &str, edit: impl FnOnce(&mut Vec<Party>) -> Result<T>) -> Result<T> {
+        let mut all = self.parties.lock().unwrap();
+        let parties = all
+            .get_mut(matter_id)
+            .ok_or_else(|| anyhow!("No parties resolved for matter {}", matter_id))?;
+        let result = edit(parties)?;
+        self.persist(&all)?;
+        Ok(result)
+    }
+
+    /// Record another spelling of a party; spellings of a different party need a merge instead
+    pub fn add_alias(&self, matter_id: &str, party_id: &str, alias: &str) -> Result<Party> {
+        let alias = alias.trim();
+        if name_tokens(alias).is_empty() {
+            return Err(anyhow!("Alias must contain a name"));
+        }
+        self.edit(matter_id, |parties| {
+            if let Some(other) = parties.iter().find(|p| p.id != party_id && p.matches(alias)) {
+                return Err(anyhow!("{} is a spelling of {}; merge the parties instead", alias, other.canonical_name));
+            }
+            let party = find_mut(parties, party_id)?;
+            party.add_alias(alias);
+            party.updated_at = Utc::now().to_rfc3339();
+            Ok(party.clone())
+        })
+    }
+
+    pub fn rename(&self, matter_id: &str, party_id: &str, canonical_name: &str) -> Result<Party> {
+        let canonical_name = canonical_name.trim();
+        if canonical_name.is_empty() {
+            return Err(anyhow!("Party name cannot be empty"));
+        }
+        self.edit(matter_id, |parties| {
+            let party = find_mut(parties, party_id)?;
+            party.canonical_name = canonical_name.to_string();
+            party.add_alias(canonical_name);
+            party.confirmed = true;
+            party.updated_at = Utc::now().to_rfc3339();
+            Ok(party.clone())
+        })
+    }
+
+    /// Fold `merge_id` into `keep_id`, which keeps its name and takes the other's aliases and mentions
+    pub fn merge(&self, matter_id: &str, keep_id: &str, merge_id: &str) -> Result<Party> {
+        if keep_id == merge_id {
+            return Err(anyhow!("Cannot merge a party into itself"));
+        }
+        self.edit(matter_id, |parties| {
+            find_mut(parties, keep_id)?;
+            let index = parties
+                .iter()
+                .position(|p| p.id == merge_id)
+                .ok_or_else(|| anyhow!("Party {} not found", merge_id))?;
+            let merged = parties.remove(index);
+
+            let party = find_mut(parties, keep_id)?;
+            for alias in &merged.aliases {
+                party.add_alias(alias);
+            }
+            for mention in merged.mentions {
+                match party
+                    .mentions
+                    .iter_mut()
+                    .find(|m| m.document == mention.document && m.alias == mention.alias)
+                {
+                    Some(existing) => existing.count += mention.count,
+                    None => party.mentions.push(mention),
+                }
+            }
+            party.role = party.role.or(merged.role);
+            party.confirmed = true;
+            party.updated_at = Utc::now().to_rfc3339();
+            Ok(party.clone())
+        })
+    }
+
+    pub fn view(&self, matter_id: &str, party_id: &str) -> Result<PartyView> {
+        let party = self
+            .list(matter_id)
+            .into_iter()
+            .find(|p| p.id == party_id)
+            .ok_or_else(|| anyhow!("Party {} not found", party_id))?;
+
+        let mut documents: BTreeMap<&str, PartyDocument> = BTreeMap::new();
+        for mention in &party.mentions {
+            let document = documents.entry(mention.document.as_str()).or_insert_with(|| PartyDocument {
+                document: mention.document.clone(),
+                mentions: 0,
+                aliases: Vec::new(),
+            });
+            document.mentions += mention.count;
+            document.aliases.push(mention.alias.clone());
+        }
+        let mut documents: Vec<PartyDocument> = documents.into_values().collect();
+        documents.sort_by(|a, b| b.mentions.cmp(&a.mentions).then_with(|| a.document.cmp(&b.document)));
+
+        Ok(PartyView {
+            matter_id: matter_id.to_string(),
+            party,
+            documents,
+        })
+    }
+}
+
+fn find_mut<'a>(parties: &'a mut [Party], party_id: &str) -> Result<&'a mut Party> {
+    parties
+        .iter_mut()
+        .find(|p| p.id == party_id)
+        .ok_or_else(|| anyhow!("Party {} not found", party_id))
+}
+
+pub type PartyRegistryStorage = Arc<PartyRegistry>;
+
+/// Resolve the parties of a matter's attached documents into the registry
+#[tauri::command]
+pub async fn matter_resolve_parties(
+    matter_id: String,
+    matters: tauri::State<'_, MatterStorage>,
+    analyzer: tauri::State<'_, AnalyzerStorage>,
+    registry: tauri::State<'_, PartyRegistryStorage>,
+) -> Result<Vec<Party>, String> {
+    let matter = matters
+        .get(&matter_id)
+        .ok_or_else(|| format!("Matter {} not found", matter_id))?;
+
+    let mut intake = vec![(matter.client.clone(), PartyRole::Client)];
+    intake.extend(matter.adverse_parties.iter().map(|p| (p.clone(), PartyRole::Adverse)));
+    intake.extend(matter.related_parties.iter().map(|p| (p.clone(), PartyRole::Related)));
+
+    let mut extracted = Vec::new();
+    let mut documents = Vec::new();
+    for path in &matter.documents {
+        let text = analyzer
+            .extract_text(Path::new(path))
+            .await
+            .map_err(|e| format!("{}: {}", path, e))?;
+        let entities = analyzer
+            .extract_party_entities(&text)
+            .await
+            .map_err(|e| format!("{}: {}", path, e))?;
+        extracted.extend(
+            entities
+                .into_iter()
+                .filter(|e| e.confidence >= MIN_PARTY_CONFIDENCE)
+                .map(|e| e.text),
+        );
+        documents.push((path.clone(), text));
+    }
+
+    let parties = resolve_parties(registry.list(&matter_id), &intake, &extracted, &documents);
+    registry.replace(&matter_id, parties.clone()).map_err(|e| e.to_string())?;
+    Ok(parties)
+}
+
+#[tauri::command]
+pub async fn matter_list_parties(
+    matter_id: String,
+    registry: tauri::State<'_, PartyRegistryStorage>,
+) -> Result<Vec<Party>, String> {
+    Ok(registry.list(&matter_id))
+}
+
+#[tauri::command]
+pub async fn matter_party_documents(
+    matter_id: String,
+    party_id: String,
+    registry: tauri::State<'_, PartyRegistryStorage>,
+) -> Result<PartyView, String> {
+    registry.view(&matter_id, &party_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn matter_add_party_alias(
+    matter_id: String,
+    party_id: String,
+    alias: String,
+    registry: tauri::State<'_, PartyRegistryStorage>,
+) -> Result<Party, String> {
+    registry.add_alias(&matter_id, &party_id, &alias).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn matter_rename_party(
+    matter_id: String,
+    party_id: String,
+    canonical_name: String,
+    registry: tauri::State<'_, PartyRegistryStorage>,
+) -> Result<Party, String> {
+    registry.rename(&matter_id, &party_id, &canonical_name).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn matter_merge_parties(
+    matter_id: String,
+    keep_party_id: String,
+    merge_party_id: String,
+    registry: tauri::State<'_, PartyRegistryStorage>,
+) -> Result<Party, String> {
+    registry
+        .merge(&matter_id, &keep_party_id, &merge_party_id)
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn docs(items: &[(&str, &str)]) -> Vec<(String, String)> {
+        items.iter().map(|(n, t)| (n.to_string(), t.to_string())).collect()
+    }
+
+    #[test]
+    fn test_clusters_spellings_into_one_party() {
+        let documents = docs(&[
+            ("MSA.txt", "This Agreement is between ABC Corp and Beta Ltd. ABC shall deliver the goods to Beta."),
+            ("Letter.txt", "ABC Corporation hereby notifies Beta Limited that ABC Corporation terminates the Agreement."),
+        ]);
+        let extracted = vec!["ABC Corp".to_string(), "ABC Corporation".to_string(), "Beta Limited".to_string()];
+        let intake = vec![("Beta Ltd".to_string(), PartyRole::Client)];
+
+        let parties = resolve_parties(Vec::new(), &intake, &extracted, &documents);
+        assert_eq!(parties.len(), 2);
+
+        let beta = &parties[0];
+        assert_eq!(beta.canonical_name, "Beta Ltd");
+        assert_eq!(beta.role, Some(PartyRole::Client));
+        assert_eq!(beta.aliases, vec!["Beta Ltd", "Beta Limited", "Beta"]);
+        assert_eq!(beta.total_mentions(), 3);
+
+        let abc = &parties[1];
+        assert_eq!(abc.canonical_name, "ABC Corporation");
+        assert_eq!(abc.aliases, vec!["ABC Corp", "ABC Corporation", "ABC"]);
+        assert_eq!(
+            abc.mentions,
+            vec![
+                PartyMention { document: "MSA.txt".to_string(), alias: "ABC".to_string(), count: 1 },
+                PartyMention { document: "MSA.txt".to_string(), alias: "ABC Corp".to_string(), count: 1 },
+                PartyMention { document: "Letter.txt".to_string(), alias: "ABC Corporation".to_string(), count: 2 },
+            ]
+        );
+
+        // Re-resolving keeps ids and drops parties no longer mentioned
+        let ids: Vec<String> = parties.iter().map(|p| p.id.clone()).collect();
+        let again = resolve_parties(parties, &intake, &[], &documents[1..]);
+        assert_eq!(again.iter().map(|p| p.id.clone()).collect::<Vec<_>>(), ids);
+        assert_eq!(again[1].total_mentions(), 2);
+        let gone = resolve_parties(again, &intake, &[], &docs(&[("Other.txt", "Beta Ltd only.")]));
+        assert_eq!(gone.len(), 1);
+    }
+
+    #[test]
+    fn test_merges_aliases_and_lists_documents_by_party() {
+        let dir = tempfile::tempdir().unwrap();
+        let registry = PartyRegistry::new(dir.path()).unwrap();
+        let documents = docs(&[
+            ("Complaint.txt", "Alpha Beta Capital LLC sued Gamma Inc. Gamma Inc denies the claims."),
+            ("Answer.txt", "ABC Holdings answers on behalf of ABC Holdings. Gamma Inc is the defendant."),
+        ]);
+        let extracted = vec!["Alpha Beta Capital LLC".to_string(), "ABC Holdings".to_string(), "Gamma Inc".to_string()];
+        registry
+            .replace("m-1", resolve_parties(Vec::new(), &[], &extracted, &documents))
+            .unwrap();
+        let parties = registry.list("m-1");
+        let id = |name: &str| parties.iter().find(|p| p.canonical_name == name).unwrap().id.clone();
+
+        assert!(registry.add_alias("m-1", &id("Gamma Inc"), "ABC Holdings Inc").is_err());
+        let merged = registry.merge("m-1", &id("Alpha Beta Capital LLC"), &id("ABC Holdings")).unwrap();
+        assert_eq!(merged.aliases, vec!["Alpha Beta Capital LLC", "ABC Holdings"]);
+        assert!(merged.confirmed);
+        registry.rename("m-1", &merged.id, "Alpha Beta Capital").unwrap();
+
+        let reopened = PartyRegistry::new(dir.path()).unwrap();
+        assert_eq!(reopened.list("m-1").len(), 2);
+        let view = reopened.view("m-1", &merged.id).unwrap();
+        assert_eq!(view.party.canonical_name, "Alpha Beta Capital");
+        let documents: Vec<(&str, usize)> = view.documents.iter().map(|d| (d.document.as_str(), d.mentions)).collect();
+        assert_eq!(documents, vec![("Answer.txt", 2), ("Complaint.txt", 1)]);
+
+        // A later run keeps the merge and the chosen name
+        let rerun = resolve_parties(reopened.list("m-1"), &[], &extracted, &docs(&[("Answer.txt", "ABC Holdings replies.")]));
+        assert_eq!(rerun.len(), 1);
+        assert_eq!(rerun[0].canonical_name, "Alpha Beta Capital");
+    }
+}
@@ -8,7 +8,7 @@ use sysinfo::System;
 use anyhow::{Result, anyhow};
 use std::fs;
 use std::path::PathBuf;
-use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use log;
 use lazy_static::lazy_static;
 
@@ -17,6 +17,12 @@ use lazy_static::lazy_static;
 pub struct PerformanceMetrics {
     pub model_name: String,
     pub timestamp: u64,
+    /// Order of recording, to tell apart metrics recorded within the same second
+    #[serde(default)]
+    pub sequence: u64,
+    /// Workspace the metric was recorded for; unattributed metrics count towards the shared one
+    #[serde(default)]
+    pub workspace: Option<String>,
 
     // Response metrics
     pub response_time_ms: u64,
@@ -157,6 +163,15 @@ pub struct ModelPerformanceMetrics {
     pub thread_efficiency_percent: f32,
 }
 
+/// Model usage frequency, used to decide which model to warm up at startup
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModelUsageStats {
+    pub model_name: String,
+    pub request_count: u64,
+    pub load_count: u64,
+    pub last_used: u64,
+}
+
 /// Resource guard thresholds
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ResourceThresholds {
@@ -197,6 +212,140 @@ pub struct ResourceGuardStatus {
     pub suggested_delay_ms: Option<u64>,
 }
 
+/// A single inference benchmark run and the environment it ran in
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchmarkResult {
+    pub model_name: String,
+    pub timestamp: u64,
+    pub tokens_per_second: f32,
+    pub time_to_first_token_ms: u64,
+    pub total_latency_ms: u64,
+    pub prompt_tokens: u32,
+    pub completion_tokens: u32,
+
+    // Environment snapshot, used to explain regressions
+    pub gpu_models: Option<String>,
+    pub gpu_driver_version: Option<String>,
+    pub gpu_memory_free_mb: u64,
+    pub cpu_usage_percent: f32,
+    pub memory_usage_percent: f32,
+}
+
+/// How far a benchmark may fall behind its baseline before an alert is raised
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegressionThresholds {
+    pub max_tokens_per_second_drop_percent: f32,
+    pub max_latency_increase_percent: f32,
+}
+
+impl Default for RegressionThresholds {
+    fn default() -> Self {
+        Self {
+            max_tokens_per_second_drop_percent: 15.0,
+            max_latency_increase_percent: 25.0,
+        }
+    }
+}
+
+/// A detected performance regression against the stored baseline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegressionAlert {
+    pub model_name: String,
+    pub metric: String, // "tokens_per_second" | "time_to_first_token_ms" | "total_latency_ms"
+    pub baseline_value: f32,
+    pub current_value: f32,
+    pub change_percent: f32,
+    pub baseline_timestamp: u64,
+    pub detected_at: u64,
+    pub suspected_causes: Vec<String>,
+}
+
+/// One agent task, with the prompt variant it ran under and the feedback it received
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentTaskRecord {
+    pub task_id: String,
+    pub agent_id: String,
+    pub timestamp: u64,
+    pub completion_time_ms: u64,
+    pub success: bool,
+    pub experiment: Option<PromptAssignment>,
+    pub feedback_score: Option<u8>, // 1-5
+    pub revision_requested: bool,
+}
+
+/// Aggregated metrics for one agent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentPerformanceMetrics {
+    pub agent_id: String,
+    pub total_tasks: u64,
+    pub failed_tasks: u64,
+    pub avg_completion_time_ms: u64,
+    pub p95_completion_time_ms: u64,
+    pub revision_rate_percent: f32,
+    pub feedback_count: u64,
+    pub avg_feedback_score: Option<f32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PromptVariant {
+    A,
+    B,
+}
+
+/// The experiment and variant a task was sampled into
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PromptAssignment {
+    pub experiment_id: String,
+    pub variant: PromptVariant,
+}
+
+/// A/B comparison of two prompt templates for one agent.
+/// Variant A is the template the agent used when the experiment started.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptExperiment {
+    pub id: String,
+    pub name: String,
+    pub agent_id: String,
+    pub template_a: String,
+    pub template_b: String,
+    pub sample_rate: f32, // share of the agent's tasks that join the experiment
+    pub min_rated_trials: usize,
+    pub started_at: u64,
+    pub ended_at: Option<u64>,
+    pub trials: Vec<AgentTaskRecord>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VariantStats {
+    pub variant: PromptVariant,
+    pub trials: usize,
+    pub rated_trials: usize,
+    pub avg_feedback_score: Option<f32>,
+    pub revision_rate_percent: f32,
+    pub avg_completion_time_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExperimentReport {
+    pub experiment_id: String,
+    pub name: String,
+    pub agent_id: String,
+    pub active: bool,
+    pub variants: Vec<VariantStats>,
+    pub winner: Option<PromptVariant>,
+    pub conclusion: String,
+}
+
+/// Agent task history and prompt experiments, persisted with the other metrics
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AgentAnalytics {
+    pub tasks: VecDeque<AgentTaskRecord>,
+    pub experiments: HashMap<String, PromptExperiment>,
+}
+
+/// Benchmark runs kept per model
+const MAX_BENCHMARK_HISTORY: usize = 50;
+
 /// Thread-safe performance tracking system with resource guards
 pub struct PerformanceTracker {
     // Real-time metrics storage (last 1000 entries per model)
@@ -208,6 +357,17 @@ pub struct PerformanceTracker {
     // Model-specific metrics
     model_metrics: Arc<RwLock<HashMap<String, ModelPerformanceMetrics>>>,
 
+    // Model usage history (not bounded by the metrics buffer)
+    model_usage: Arc<RwLock<HashMap<String, ModelUsageStats>>>,
+
+    // Benchmark runs and the baseline each model is compared against
+    benchmark_history: Arc<RwLock<HashMap<String, VecDeque<BenchmarkResult>>>>,
+    benchmark_baselines: Arc<RwLock<HashMap<String, BenchmarkResult>>>,
+    regression_thresholds: Arc<RwLock<RegressionThresholds>>,
+
+    // Agent task outcomes, user feedback and prompt experiments
+    agent_analytics: Arc<RwLock<AgentAnalytics>>,
+
     // Configuration
     max_buffer_size: usize,
     persistence_path: PathBuf,
@@ -235,6 +395,11 @@ impl PerformanceTracker {
             metrics_buffer: Arc::new(RwLock::new(HashMap::new())),
             system_metrics: Arc::new(RwLock::new(VecDeque::new())),
             model_metrics: Arc::new(RwLock::new(HashMap::new())),
+            model_usage: Arc::new(RwLock::new(HashMap::new())),
+            benchmark_history: Arc::new(RwLock::new(HashMap::new())),
+            benchmark_baselines: Arc::new(RwLock::new(HashMap::new())),
+            regression_thresholds: Arc::new(RwLock::new(RegressionThresholds::default())),
+            agent_analytics: Arc::new(RwLock::new(AgentAnalytics::default())),
             max_buffer_size,
             persistence_path,
             system: Arc::new(Mutex::new(System::new_all())),
@@ -264,7 +429,22 @@ impl PerformanceTracker {
     }
 
     /// Record a new performance metric
-    pub async fn record_metric(&self, metric: PerformanceMetrics) {
+    pub async fn record_metric(&self, mut metric: PerformanceMetrics) {
+        metric.sequence = METRIC_SEQUENCE.fetch_add(1, Ordering::Relaxed) + 1;
+        if metric.workspace.is_none() {
+            metric.workspace = crate::workspace_stats::current_workspace();
+        }
+
+        {
+            let mut usage = self.model_usage.write().unwrap();
+            let stats = usage.entry(metric.model_name.clone()).or_insert_with(|| ModelUsageStats {
+                model_name: metric.model_name.clone(),
+                ..Default::default()
+            });
+            stats.request_count += 1;
+            stats.last_used = metric.timestamp;
+        }
+
         let mut buffer = self.metrics_buffer.write().unwrap();
         let model_buffer = buffer.entry(metric.model_name.clone()).or_insert_with(VecDeque::new);
 
@@ -282,6 +462,110 @@ impl PerformanceTracker {
         model_metrics.insert(metric.model_name.clone(), metric);
     }
 
+    /// Record that a model was loaded into memory
+    pub async fn record_model_load(&self, model_name: &str) {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let mut usage = self.model_usage.write().unwrap();
+        let stats = usage.entry(model_name.to_string()).or_insert_with(|| ModelUsageStats {
+            model_name: model_name.to_string(),
+            ..Default::default()
+        });
+        stats.load_count += 1;
+        stats.last_used = now;
+    }
+
+    /// Get models ordered by usage frequency (most used first)
+    pub async fn get_most_used_models(&self, limit: usize) -> Vec<ModelUsageStats> {
+        let usage = self.model_usage.read().unwrap();
+        let mut models: Vec<ModelUsageStats> = usage.values().cloned().collect();
+
+        models.sort_by(|a, b| {
+            b.request_count
+                .cmp(&a.request_count)
+                .then(b.load_count.cmp(&a.load_count))
+                .then(b.last_used.cmp(&a.last_used))
+        });
+        models.truncate(limit);
+
+        models
+    }
+
+    /// Store a benchmark run and compare it against the model's baseline.
+    /// The first run for a model becomes its baseline.
+    pub async fn record_benchmark(&self, result: BenchmarkResult) -> Vec<RegressionAlert> {
+        {
+            let mut history = self.benchmark_history.write().unwrap();
+            let runs = history.entry(result.model_name.clone()).or_insert_with(VecDeque::new);
+            runs.push_back(result.clone());
+            if runs.len() > MAX_BENCHMARK_HISTORY {
+                runs.pop_front();
+            }
+        }
+
+        let baseline = {
+            let mut baselines = self.benchmark_baselines.write().unwrap();
+            match baselines.get(&result.model_name) {
+                Some(baseline) => baseline.clone(),
+                None => {
+                    baselines.insert(result.model_name.clone(), result);
+                    return Vec::new();
+                }
+            }
+        };
+
+        let thresholds = self.regression_thresholds.read().unwrap().clone();
+        let alerts = detect_regressions(&baseline, &result, &thresholds);
+        for alert in &alerts {
+            log::warn!(
+                "Performance regression for {}: {} {:.1} -> {:.1} ({:+.1}%)",
+                alert.model_name,
+                alert.metric,
+                alert.baseline_value,
+                alert.current_value,
+                alert.change_percent
+            );
+        }
+
+        alerts
+    }
+
+    /// Make the latest benchmark run the baseline (e.g. after accepting a hardware change)
+    pub async fn reset_benchmark_baseline(&self, model_name: &str) -> Result<BenchmarkResult> {
+        let latest = self
+            .benchmark_history
+            .read()
+            .unwrap()
+            .get(model_name)
+            .and_then(|runs| runs.back().cloned())
+            .ok_or_else(|| anyhow!("No benchmark results for model {}", model_name))?;
+
+        self.benchmark_baselines
+            .write()
+            .unwrap()
+            .insert(model_name.to_string(), latest.clone());
+
+        Ok(latest)
+    }
+
+    /// Get the stored baseline and benchmark runs for a model (oldest first)
+    pub async fn get_benchmark_history(&self, model_name: &str) -> (Option<BenchmarkResult>, Vec<BenchmarkResult>) {
+        let baseline = self.benchmark_baselines.read().unwrap().get(model_name).cloned();
+        let runs = self
+            .benchmark_history
+            .read()
+            .unwrap()
+            .get(model_name)
+            .map(|runs| runs.iter().cloned().collect())
+            .unwrap_or_default();
+
+        (baseline, runs)
+    }
+
+    /// Update the regression alert thresholds
+    pub async fn set_regression_thresholds(&self, thresholds: RegressionThresholds) {
+        *self.regression_thresholds.write().unwrap() = thresholds;
+    }
+
     /// Get current performance metrics for a model
     pub async fn get_current_metrics(&self, model_name: &str) -> Option<PerformanceMetrics> {
         let buffer = self.metrics_buffer.read().unwrap();
@@ -471,6 +755,85 @@ impl PerformanceTracker {
         result
     }
 
+    /// Get all buffered metrics recorded in or after the given unix second; callers tell the ones
+    /// they already saw apart by `sequence`
+    pub async fn get_metrics_since(&self, since: u64) -> Vec<PerformanceMetrics> {
+        let buffer = self.metrics_buffer.read().unwrap();
+
+        buffer
+            .values()
+            .flat_map(|metrics| metrics.iter())
+            .filter(|m| m.timestamp >= since)
+            .cloned()
+            .collect()
+    }
+
+    /// Record how an agent task went; `experiment` is the prompt variant it ran under
+    pub async fn record_agent_task(
+        &self,
+        task_id: &str,
+        agent_id: &str,
+        completion_time_ms: u64,
+        success: bool,
+        experiment: Option<PromptAssignment>,
+    ) {
+        let record = AgentTaskRecord {
+            task_id: task_id.to_string(),
+            agent_id: agent_id.to_string(),
+            timestamp: SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs(),
+            completion_time_ms,
+            success,
+            experiment,
+            feedback_score: None,
+            revision_requested: false,
+        };
+        let mut analytics = self.agent_analytics.write().unwrap();
+        analytics.record_task(record, self.max_buffer_size);
+    }
+
+    /// Record the user's rating of an agent's output
+    pub async fn record_agent_feedback(&self, task_id: &str, score: u8, revision_requested: bool) -> Result<()> {
+        let mut analytics = self.agent_analytics.write().unwrap();
+        analytics.record_feedback(task_id, score, revision_requested)
+    }
+
+    pub async fn get_agent_metrics(&self) -> Vec<AgentPerformanceMetrics> {
+        self.agent_analytics.read().unwrap().agent_metrics()
+    }
+
+    /// Pick the prompt template for a task, sampling it into the agent's experiment if one runs
+    pub async fn assign_prompt_variant(&self, agent_id: &str) -> Option<(PromptAssignment, String)> {
+        let analytics = self.agent_analytics.read().unwrap();
+        analytics.assign_variant(agent_id, rand::random::<f32>(), rand::random::<bool>())
+    }
+
+    pub async fn start_prompt_experiment(
+        &self,
+        name: String,
+        agent_id: &str,
+        template_a: String,
+        template_b: String,
+        sample_rate: f32,
+        min_rated_trials: usize,
+    ) -> Result<PromptExperiment> {
+        let mut analytics = self.agent_analytics.write().unwrap();
+        analytics.start_experiment(name, agent_id, template_a, template_b, sample_rate, min_rated_trials)
+    }
+
+    pub async fn stop_prompt_experiment(&self, experiment_id: &str) -> Result<ExperimentReport> {
+        self.agent_analytics.write().unwrap().stop_experiment(experiment_id)
+    }
+
+    pub async fn get_experiment_reports(&self) -> Vec<ExperimentReport> {
+        let analytics = self.agent_analytics.read().unwrap();
+        let mut experiments: Vec<&PromptExperiment> = analytics.experiments.values().collect();
+        experiments.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+        experiments
+            .into_iter()
+            .filter_map(|e| analytics.experiment_report(&e.id).ok())
+            .collect()
+    }
+
     /// Update cost per token for a specific model
     pub async fn set_cost_per_token(&self, model_name: &str, cost: f32) {
         let mut costs = self.cost_per_token_by_model.write().unwrap();
@@ -541,6 +904,10 @@ impl PerformanceTracker {
         let metrics_buffer = Arc::clone(&self.metrics_buffer);
         let system_metrics = Arc::clone(&self.system_metrics);
         let model_metrics = Arc::clone(&self.model_metrics);
+        let model_usage = Arc::clone(&self.model_usage);
+        let benchmark_history = Arc::clone(&self.benchmark_history);
+        let benchmark_baselines = Arc::clone(&self.benchmark_baselines);
+        let agent_analytics = Arc::clone(&self.agent_analytics);
         let persistence_path = self.persistence_path.clone();
 
         tokio::spawn(async move {
@@ -554,6 +921,10 @@ impl PerformanceTracker {
                     &metrics_buffer,
                     &system_metrics,
                     &model_metrics,
+                    &model_usage,
+                    &benchmark_history,
+                    &benchmark_baselines,
+                    &agent_analytics,
                     &persistence_path,
                 ).await {
                     eprintln!("Failed to persist performance metrics: {}", e);
@@ -563,10 +934,15 @@ impl PerformanceTracker {
     }
 
     /// Persist metrics to disk
+    #[allow(clippy::too_many_arguments)]
     async fn persist_to_disk(
         metrics_buffer: &Arc<RwLock<HashMap<String, VecDeque<PerformanceMetrics>>>>,
         system_metrics: &Arc<RwLock<VecDeque<SystemResourceMetrics>>>,
         model_metrics: &Arc<RwLock<HashMap<String, ModelPerformanceMetrics>>>,
+        model_usage: &Arc<RwLock<HashMap<String, ModelUsageStats>>>,
+        benchmark_history: &Arc<RwLock<HashMap<String, VecDeque<BenchmarkResult>>>>,
+        benchmark_baselines: &Arc<RwLock<HashMap<String, BenchmarkResult>>>,
+        agent_analytics: &Arc<RwLock<AgentAnalytics>>,
         persistence_path: &PathBuf,
     ) -> Result<()> {
         let metrics_data = {
@@ -584,6 +960,26 @@ impl PerformanceTracker {
             mod_metrics.clone()
         };
 
+        let usage_data = {
+            let usage = model_usage.read().unwrap();
+            usage.clone()
+        };
+
+        let history_data = {
+            let history = benchmark_history.read().unwrap();
+            history.clone()
+        };
+
+        let baseline_data = {
+            let baselines = benchmark_baselines.read().unwrap();
+            baselines.clone()
+        };
+
+        let analytics_data = {
+            let analytics = agent_analytics.read().unwrap();
+            analytics.clone()
+        };
+
         // Create persistence directory if it doesn't exist
         if let Some(parent) = persistence_path.parent() {
             fs::create_dir_all(parent)?;
@@ -594,6 +990,10 @@ impl PerformanceTracker {
             "metrics_buffer": metrics_data,
             "system_metrics": system_data,
             "model_metrics": model_data,
+            "model_usage": usage_data,
+            "benchmark_history": history_data,
+            "benchmark_baselines": baseline_data,
+            "agent_analytics": analytics_data,
             "timestamp": SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs()
         });
 
@@ -635,10 +1035,415 @@ impl PerformanceTracker {
             }
         }
 
+        // Load model usage history
+        if let Some(usage_data) = parsed.get("model_usage") {
+            if let Ok(usage) = serde_json::from_value::<HashMap<String, ModelUsageStats>>(usage_data.clone()) {
+                let mut model_usage = self.model_usage.write().unwrap();
+                *model_usage = usage;
+            }
+        }
+
+        // Load benchmark history and baselines
+        if let Some(history_data) = parsed.get("benchmark_history") {
+            if let Ok(history) = serde_json::from_value::<HashMap<String, VecDeque<BenchmarkResult>>>(history_data.clone()) {
+                let mut benchmark_history = self.benchmark_history.write().unwrap();
+                *benchmark_history = history;
+            }
+        }
+
+        if let Some(baseline_data) = parsed.get("benchmark_baselines") {
+            if let Ok(baselines) = serde_json::from_value::<HashMap<String, BenchmarkResult>>(baseline_data.clone()) {
+                let mut benchmark_baselines = self.benchmark_baselines.write().unwrap();
+                *benchmark_baselines = baselines;
+            }
+        }
+
+        // Load agent analytics and prompt experiments
+        if let Some(analytics_data) = parsed.get("agent_analytics") {
+            if let Ok(analytics) = serde_json::from_value::<AgentAnalytics>(analytics_data.clone()) {
+                let mut agent_analytics = self.agent_analytics.write().unwrap();
+                *agent_analytics = analytics;
+            }
+        }
+
         Ok(())
     }
 }
 
+/// Compare a benchmark run against its baseline and explain any regressions found
+pub fn detect_regressions(
+    baseline: &BenchmarkResult,
+    current: &BenchmarkResult,
+    thresholds: &RegressionThresholds,
+) -> Vec<RegressionAlert> {
+    let change = |before: f32, after: f32| {
+        if before > 0.0 {
+            (after - before) / before * 100.0
+        } else {
+            0.0
+        }
+    };
+
+    // (metric, baseline, current, change %, regressed)
+    let tps_change = change(baseline.tokens_per_second, current.tokens_per_second);
+    let ttft_change = change(baseline.time_to_first_token_ms as f32, current.time_to_first_token_ms as f32);
+    let latency_change = change(baseline.total_latency_ms as f32, current.total_latency_ms as f32);
+    let checks = [
+        (
+            "tokens_per_second",
+            baseline.tokens_per_second,
+            current.tokens_per_second,
+            tps_change,
+            -tps_change > thresholds.max_tokens_per_second_drop_percent,
+        ),
+        (
+            "time_to_first_token_ms",
+            baseline.time_to_first_token_ms as f32,
+            current.time_to_first_token_ms as f32,
+            ttft_change,
+            ttft_change > thresholds.max_latency_increase_percent,
+        ),
+        (
+            "total_latency_ms",
+            baseline.total_latency_ms as f32,
+            current.total_latency_ms as f32,
+            latency_change,
+            latency_change > thresholds.max_latency_increase_percent,
+        ),
+    ];
+
+    let detected_at = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+    let mut alerts = Vec::new();
+    for (metric, baseline_value, current_value, change_percent, regressed) in checks {
+        if !regressed {
+            continue;
+        }
+        alerts.push(RegressionAlert {
+            model_name: current.model_name.clone(),
+            metric: metric.to_string(),
+            baseline_value,
+            current_value,
+            change_percent,
+            baseline_timestamp: baseline.timestamp,
+            detected_at,
+            suspected_causes: suspected_regression_causes(baseline, current),
+        });
+    }
+
+    alerts
+}
+
+/// Environment differences between two benchmark runs that could explain a slowdown
+fn suspected_regression_causes(baseline: &BenchmarkResult, current: &BenchmarkResult) -> Vec<String> {
+    let mut causes = Vec::new();
+
+    if baseline.gpu_driver_version != current.gpu_driver_version {
+        causes.push(format!(
+            "GPU driver changed from {} to {}",
+            baseline.gpu_driver_version.as_deref().unwrap_or("unknown"),
+            current.gpu_driver_version.as_deref().unwrap_or("unknown")
+        ));
+    }
+
+    if baseline.gpu_models != current.gpu_models {
+        causes.push(format!(
+            "GPU changed from {} to {}",
+            baseline.gpu_models.as_deref().unwrap_or("none"),
+            current.gpu_models.as_deref().unwrap_or("none")
+        ));
+    }
+
+    // Less free VRAM means fewer layers offloaded to the GPU
+    if baseline.gpu_memory_free_mb > 0 && current.gpu_memory_free_mb < baseline.gpu_memory_free_mb * 3 / 4 {
+        causes.push(format!(
+            "Free GPU memory dropped from {} MB to {} MB; other applications or models may be using the GPU",
+            baseline.gpu_memory_free_mb, current.gpu_memory_free_mb
+        ));
+    }
+
+    if current.cpu_usage_percent > baseline.cpu_usage_percent + 25.0 {
+        causes.push(format!(
+            "CPU was busier during the run ({:.0}% vs {:.0}%)",
+            current.cpu_usage_percent, baseline.cpu_usage_percent
+        ));
+    }
+
+    if current.memory_usage_percent > 90.0 && current.memory_usage_percent > baseline.memory_usage_percent {
+        causes.push(format!(
+            "System memory was under pressure ({:.0}% used); the model may be swapping",
+            current.memory_usage_percent
+        ));
+    }
+
+    if causes.is_empty() {
+        causes.push("No environment change detected; check for thermal throttling or power-saving mode".to_string());
+    }
+
+    causes
+}
+
+/// Feedback scores range from 1 (unusable) to 5 (used as-is)
+const MAX_FEEDBACK_SCORE: u8 = 5;
+
+impl AgentAnalytics {
+    /// Store a finished task; tasks run under an experiment are also kept as its trials
+    pub fn record_task(&mut self, record: AgentTaskRecord, max_tasks: usize) {
+        if let Some(assignment) = &record.experiment {
+            if let Some(experiment) = self.experiments.get_mut(&assignment.experiment_id) {
+                experiment.trials.push(record.clone());
+            }
+        }
+
+        self.tasks.push_back(record);
+        if self.tasks.len() > max_tasks {
+            self.tasks.pop_front();
+        }
+    }
+
+    /// Attach user feedback to a task and to its experiment trial
+    pub fn record_feedback(&mut self, task_id: &str, score: u8, revision_requested: bool) -> Result<()> {
+        if score == 0 || score > MAX_FEEDBACK_SCORE {
+            return Err(anyhow!("Feedback score must be between 1 and {}", MAX_FEEDBACK_SCORE));
+        }
+
+        let mut found = false;
+        let trials = self.experiments.values_mut().flat_map(|e| e.trials.iter_mut());
+        for record in self.tasks.iter_mut().chain(trials).filter(|r| r.task_id == task_id) {
+            record.feedback_score = Some(score);
+            record.revision_requested = revision_requested;
+            found = true;
+        }
+
+        if found {
+            Ok(())
+        } else {
+            Err(anyhow!("No recorded agent task with id {}", task_id))
+        }
+    }
+
+    /// Metrics per agent over the recorded task history
+    pub fn agent_metrics(&self) -> Vec<AgentPerformanceMetrics> {
+        let mut by_agent: HashMap<&str, Vec<&AgentTaskRecord>> = HashMap::new();
+        for record in &self.tasks {
+            by_agent.entry(record.agent_id.as_str()).or_default().push(record);
+        }
+
+        let mut metrics: Vec<AgentPerformanceMetrics> = by_agent
+            .into_iter()
+            .map(|(agent_id, records)| {
+                let completed: Vec<&AgentTaskRecord> = records.iter().copied().filter(|r| r.success).collect();
+                let mut times: Vec<u64> = completed.iter().map(|r| r.completion_time_ms).collect();
+                times.sort_unstable();
+                let p95_index = (times.len() * 95 / 100).min(times.len().saturating_sub(1));
+                let scores: Vec<f32> = records.iter().filter_map(|r| r.feedback_score).map(f32::from).collect();
+
+                AgentPerformanceMetrics {
+                    agent_id: agent_id.to_string(),
+                    total_tasks: records.len() as u64,
+                    failed_tasks: (records.len() - completed.len()) as u64,
+                    avg_completion_time_ms: mean_ms(&times),
+                    p95_completion_time_ms: times.get(p95_index).copied().unwrap_or(0),
+                    revision_rate_percent: revision_rate(&completed),
+                    feedback_count: scores.len() as u64,
+                    avg_feedback_score: mean(&scores),
+                }
+            })
+            .collect();
+        metrics.sort_by(|a, b| a.agent_id.cmp(&b.agent_id));
+        metrics
+    }
+
+    /// Start comparing `template_b` against the agent's current template
+    pub fn start_experiment(
+        &mut self,
+        name: String,
+        agent_id: &str,
+        template_a: String,
+        template_b: String,
+        sample_rate: f32,
+        min_rated_trials: usize,
+    ) -> Result<PromptExperiment> {
+        if !template_b.contains("{input}") {
+            return Err(anyhow!("The challenger template must contain an {{input}} placeholder"));
+        }
+        if template_b == template_a {
+            return Err(anyhow!("The challenger template is identical to the current one"));
+        }
+        if !(sample_rate > 0.0 && sample_rate <= 1.0) {
+            return Err(anyhow!("Sample rate must be greater than 0 and at most 1"));
+        }
+        if self.active_experiment(agent_id).is_some() {
+            return Err(anyhow!("Agent {} already has a running prompt experiment", agent_id));
+        }
+
+        let experiment = PromptExperiment {
+            id: uuid::Uuid::new_v4().to_string(),
+            name,
+            agent_id: agent_id.to_string(),
+            template_a,
+            template_b,
+            sample_rate,
+            min_rated_trials: min_rated_trials.max(1),
+            started_at: SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs(),
+            ended_at: None,
+            trials: Vec::new(),
+        };
+        self.experiments.insert(experiment.id.clone(), experiment.clone());
+        Ok(experiment)
+    }
+
+    pub fn stop_experiment(&mut self, experiment_id: &str) -> Result<ExperimentReport> {
+        let experiment = self
+            .experiments
+            .get_mut(experiment_id)
+            .ok_or_else(|| anyhow!("Prompt experiment {} not found", experiment_id))?;
+        if experiment.ended_at.is_none() {
+            experiment.ended_at = Some(SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs());
+        }
+        self.experiment_report(experiment_id)
+    }
+
+    fn active_experiment(&self, agent_id: &str) -> Option<&PromptExperiment> {
+        self.experiments
+            .values()
+            .find(|e| e.agent_id == agent_id && e.ended_at.is_none())
+    }
+
+    /// Decide whether a task joins the agent's running experiment and with which template.
+    /// `sample` (0..1) decides participation; `coin` breaks ties so the variants stay balanced.
+    pub fn assign_variant(&self, agent_id: &str, sample: f32, coin: bool) -> Option<(PromptAssignment, String)> {
+        let experiment = self.active_experiment(agent_id)?;
+        if sample >= experiment.sample_rate {
+            return None;
+        }
+
+        let count = |variant| experiment.trials.iter().filter(|t| trial_variant(t) == Some(variant)).count();
+        let (a, b) = (count(PromptVariant::A), count(PromptVariant::B));
+        let variant = match a.cmp(&b) {
+            std::cmp::Ordering::Less => PromptVariant::A,
+            std::cmp::Ordering::Greater => PromptVariant::B,
+            std::cmp::Ordering::Equal if coin => PromptVariant::B,
+            std::cmp::Ordering::Equal => PromptVariant::A,
+        };
+        let template = match variant {
+            PromptVariant::A => experiment.template_a.clone(),
+            PromptVariant::B => experiment.template_b.clone(),
+        };
+
+        Some((PromptAssignment { experiment_id: experiment.id.clone(), variant }, template))
+    }
+
+    /// Compare the variants on feedback score. A winner is only declared once both have
+    /// enough rated trials and the gap in mean score is larger than twice its standard error.
+    pub fn experiment_report(&self, experiment_id: &str) -> Result<ExperimentReport> {
+        let experiment = self
+            .experiments
+            .get(experiment_id)
+            .ok_or_else(|| anyhow!("Prompt experiment {} not found", experiment_id))?;
+
+        let mut variants = Vec::new();
+        let mut scores = Vec::new();
+        for variant in [PromptVariant::A, PromptVariant::B] {
+            let trials: Vec<&AgentTaskRecord> = experiment
+                .trials
+                .iter()
+                .filter(|t| trial_variant(t) == Some(variant))
+                .collect();
+            let completed: Vec<&AgentTaskRecord> = trials.iter().copied().filter(|t| t.success).collect();
+            let rated: Vec<f32> = trials.iter().filter_map(|t| t.feedback_score).map(f32::from).collect();
+            let times: Vec<u64> = completed.iter().map(|t| t.completion_time_ms).collect();
+
+            variants.push(VariantStats {
+                variant,
+                trials: trials.len(),
+                rated_trials: rated.len(),
+                avg_feedback_score: mean(&rated),
+                revision_rate_percent: revision_rate(&completed),
+                avg_completion_time_ms: mean_ms(&times),
+            });
+            scores.push(rated);
+        }
+
+        let min = experiment.min_rated_trials;
+        let missing: usize = scores.iter().map(|s| min.saturating_sub(s.len())).sum();
+        let (winner, conclusion) = if missing > 0 {
+            (None, format!("{} more rated tasks needed before the variants can be compared", missing))
+        } else {
+            let (mean_a, mean_b) = (mean(&scores[0]).unwrap_or(0.0), mean(&scores[1]).unwrap_or(0.0));
+            let standard_error = (variance(&scores[0]) / scores[0].len() as f32
+                + variance(&scores[1]) / scores[1].len() as f32)
+                .sqrt();
+            let gap = mean_b - mean_a;
+            if gap.abs() > 2.0 * standard_error {
+                let winner = if gap > 0.0 { PromptVariant::B } else { PromptVariant::A };
+                (
+                    Some(winner),
+                    format!(
+                        "Variant {:?} scores {:.2} against {:.2} on user feedback",
+                        winner,
+                        mean_a.max(mean_b),
+                        mean_a.min(mean_b)
+                    ),
+                )
+            } else {
+                (
+                    None,
+                    format!("No clear difference yet ({:.2} vs {:.2} on user feedback)", mean_a, mean_b),
+                )
+            }
+        };
+
+        Ok(ExperimentReport {
+            experiment_id: experiment.id.clone(),
+            name: experiment.name.clone(),
+            agent_id: experiment.agent_id.clone(),
+            active: experiment.ended_at.is_none(),
+            variants,
+            winner,
+            conclusion,
+        })
+    }
+}
+
+fn trial_variant(record: &AgentTaskRecord) -> Option<PromptVariant> {
+    record.experiment.as_ref().map(|a| a.variant)
+}
+
+fn mean(values: &[f32]) -> Option<f32> {
+    if values.is_empty() {
+        None
+    } else {
+        Some(values.iter().sum::<f32>() / values.len() as f32)
+    }
+}
+
+fn mean_ms(values: &[u64]) -> u64 {
+    if values.is_empty() {
+        0
+    } else {
+        values.iter().sum::<u64>() / values.len() as u64
+    }
+}
+
+/// Sample variance; zero with fewer than two values
+fn variance(values: &[f32]) -> f32 {
+    match mean(values) {
+        Some(m) if values.len() > 1 => {
+            values.iter().map(|v| (v - m).powi(2)).sum::<f32>() / (values.len() - 1) as f32
+        }
+        _ => 0.0,
+    }
+}
+
+/// Share of completed tasks the user sent back for revision
+fn revision_rate(completed: &[&AgentTaskRecord]) -> f32 {
+    if completed.is_empty() {
+        return 0.0;
+    }
+    let revised = completed.iter().filter(|r| r.revision_requested).count();
+    revised as f32 / completed.len() as f32 * 100.0
+}
+
 /// Helper struct for timing operations
 pub struct PerformanceTimer {
     start_time: Instant,
@@ -670,6 +1475,8 @@ impl PerformanceTimer {
         PerformanceMetrics {
             model_name: self.model_name.clone(),
             timestamp: SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs(),
+            sequence: 0, // Assigned when recorded
+            workspace: None, // Attributed when recorded
             response_time_ms: elapsed_ms,
             tokens_per_second,
             total_tokens,
@@ -1207,6 +2014,9 @@ impl PerformanceTracker {
 }
 
 // Global performance tracker instance
+/// Last sequence number handed to a recorded metric
+static METRIC_SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
 lazy_static! {
     pub static ref GLOBAL_PERFORMANCE_TRACKER: Arc<RwLock<Option<Arc<PerformanceTracker>>>> =
         Arc::new(RwLock::new(None));
@@ -1225,4 +2035,138 @@ pub async fn initialize_performance_tracker(persistence_path: PathBuf) -> Result
 /// Get the global performance tracker
 pub fn get_performance_tracker() -> Option<Arc<PerformanceTracker>> {
     GLOBAL_PERFORMANCE_TRACKER.read().unwrap().clone()
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn benchmark(tokens_per_second: f32, total_latency_ms: u64, driver: &str) -> BenchmarkResult {
+        BenchmarkResult {
+            model_name: "llama3.2:3b".to_string(),
+            timestamp: 1_700_000_000,
+            tokens_per_second,
+            time_to_first_token_ms: 200,
+            total_latency_ms,
+            prompt_tokens: 32,
+            completion_tokens: 128,
+            gpu_models: Some("NVIDIA GeForce RTX 4070".to_string()),
+            gpu_driver_version: Some(driver.to_string()),
+            gpu_memory_free_mb: 10_000,
+            cpu_usage_percent: 10.0,
+            memory_usage_percent: 50.0,
+        }
+    }
+
+    #[test]
+    fn test_no_alert_within_threshold() {
+        let baseline = benchmark(50.0, 3000, "551.23");
+        let current = benchmark(46.0, 3300, "551.23");
+
+        assert!(detect_regressions(&baseline, &current, &RegressionThresholds::default()).is_empty());
+    }
+
+    #[test]
+    fn test_driver_update_regression() {
+        let baseline = benchmark(50.0, 3000, "551.23");
+        let current = benchmark(30.0, 4500, "560.70");
+
+        let alerts = detect_regressions(&baseline, &current, &RegressionThresholds::default());
+        let metrics: Vec<&str> = alerts.iter().map(|a| a.metric.as_str()).collect();
+
+        assert_eq!(metrics, vec!["tokens_per_second", "total_latency_ms"]);
+        assert!((alerts[0].change_percent + 40.0).abs() < 0.01);
+        assert!(alerts[0].suspected_causes[0].contains("560.70"));
+    }
+
+    fn agent_task(
+        task_id: &str,
+        agent_id: &str,
+        ms: u64,
+        success: bool,
+        experiment: Option<PromptAssignment>,
+    ) -> AgentTaskRecord {
+        AgentTaskRecord {
+            task_id: task_id.to_string(),
+            agent_id: agent_id.to_string(),
+            timestamp: 1_700_000_000,
+            completion_time_ms: ms,
+            success,
+            experiment,
+            feedback_score: None,
+            revision_requested: false,
+        }
+    }
+
+    #[test]
+    fn test_agent_metrics_from_tasks_and_feedback() {
+        let mut analytics = AgentAnalytics::default();
+        analytics.record_task(agent_task("t1", "contract_analyzer", 1_000, true, None), 1000);
+        analytics.record_task(agent_task("t2", "contract_analyzer", 3_000, true, None), 1000);
+        analytics.record_task(agent_task("t3", "contract_analyzer", 9_000, false, None), 1000);
+        analytics.record_task(agent_task("t4", "risk_assessor", 2_000, true, None), 1000);
+
+        analytics.record_feedback("t1", 5, false).unwrap();
+        analytics.record_feedback("t2", 2, true).unwrap();
+        assert!(analytics.record_feedback("t2", 6, false).is_err());
+        assert!(analytics.record_feedback("missing", 3, false).is_err());
+
+        let metrics = analytics.agent_metrics();
+        let contracts = &metrics[0];
+        assert_eq!(contracts.agent_id, "contract_analyzer");
+        assert_eq!((contracts.total_tasks, contracts.failed_tasks), (3, 1));
+        assert_eq!(contracts.avg_completion_time_ms, 2_000);
+        assert!((contracts.revision_rate_percent - 50.0).abs() < 0.01);
+        assert_eq!(contracts.avg_feedback_score, Some(3.5));
+        assert_eq!(metrics[1].avg_feedback_score, None);
+
+        // The task log stays bounded
+        analytics.record_task(agent_task("t5", "risk_assessor", 2_000, true, None), 4);
+        assert_eq!(analytics.tasks.front().unwrap().task_id, "t2");
+    }
+
+    #[test]
+    fn test_prompt_experiment_declares_winner_on_feedback() {
+        let mut analytics = AgentAnalytics::default();
+        let current = "Analyze:\n\n{input}".to_string();
+        assert!(analytics
+            .start_experiment("no placeholder".into(), "contract_analyzer", current.clone(), "Analyze".into(), 0.5, 3)
+            .is_err());
+        let experiment = analytics
+            .start_experiment(
+                "Clause-by-clause".into(),
+                "contract_analyzer",
+                current.clone(),
+                "Go clause by clause:\n\n{input}".into(),
+                0.5,
+                3,
+            )
+            .unwrap();
+        assert!(analytics
+            .start_experiment("second".into(), "contract_analyzer", current, "{input}".into(), 0.5, 3)
+            .is_err());
+
+        // Tasks above the sample rate, or for other agents, keep the current template
+        assert!(analytics.assign_variant("contract_analyzer", 0.7, false).is_none());
+        assert!(analytics.assign_variant("risk_assessor", 0.1, false).is_none());
+
+        for i in 0..6 {
+            let (assignment, template) = analytics.assign_variant("contract_analyzer", 0.1, true).unwrap();
+            // Assignment alternates to keep the variants balanced
+            let expected = if i % 2 == 0 { PromptVariant::B } else { PromptVariant::A };
+            assert_eq!(assignment.variant, expected);
+            assert_eq!(template.starts_with("Go clause"), expected == PromptVariant::B);
+
+            let task_id = format!("task-{}", i);
+            analytics.record_task(agent_task(&task_id, "contract_analyzer", 1_500, true, Some(assignment)), 1000);
+            let score = if expected == PromptVariant::B { 5 } else { 3 };
+            analytics.record_feedback(&task_id, score, expected == PromptVariant::A).unwrap();
+        }
+
+        let report = analytics.stop_experiment(&experiment.id).unwrap();
+        assert!(!report.active);
+        assert_eq!(report.winner, Some(PromptVariant::B));
+        assert_eq!(report.variants[0].rated_trials, 3);
+        assert!((report.variants[0].revision_rate_percent - 100.0).abs() < 0.01);
+        assert!(analytics.assign_variant("contract_analyzer", 0.1, false).is_none());
+    }
+}
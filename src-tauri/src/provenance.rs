@@ -0,0 +1,411 @@
+use anyhow::{anyhow, Context, Result};
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use ring::signature::{Ed25519KeyPair, KeyPair, UnparsedPublicKey, ED25519};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::llm_manager::LLMManager;
+use crate::network_attestation::signer_fingerprint;
+use crate::security::SecurityManager;
+
+/// Provenance Manifests for BEAR AI
+/// Exported AI-generated documents carry a signed manifest of how they were produced: the
+/// model and its weights, a hash of the prompts, the retrieval sources and the time. The
+/// manifest is embedded in the document (a package part of a DOCX, a trailing comment in
+/// Markdown) and can also be written as a C2PA-style sidecar next to the file. Each manifest
+/// binds to the content it describes by SHA-256, so a recipient can tell whether the document
+/// was changed after export; the signer fingerprint identifies the firm's installation.
+pub const MANIFEST_FORMAT: &str = "bear-ai-provenance";
+pub const MANIFEST_FORMAT_VERSION: u32 = 1;
+pub const SIDECAR_SUFFIX: &str = ".provenance.json";
+const DOCX_MANIFEST_PART: &str = "bear-ai/provenance.json";
+const DOCX_BODY_PART: &str = "word/document.xml";
+const DOCX_RELATIONSHIP: &str = r#"<Relationship Id="rIdBearAiProvenance" Type="https://bear-ai.app/relationships/provenance" Target="bear-ai/provenance.json"/>"#;
+const MARKDOWN_MARKER: &str = "\n<!-- bear-ai-provenance ";
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProvenanceSource {
+    pub document_id: String,
+    pub title: Option<String>,
+    pub chunk_id: Option<String>,
+    pub content_sha256: Option<String>,
+}
+
+impl ProvenanceSource {
+    pub fn new(document_id: &str, title: Option<&str>, chunk_id: Option<&str>, content: &str) -> Self {
+        Self {
+            document_id: document_id.to_string(),
+            title: title.map(str::to_string),
+            chunk_id: chunk_id.map(str::to_string),
+            content_sha256: Some(sha256_hex(content.as_bytes())).filter(|_| !content.is_empty()),
+        }
+    }
+}
+
+/// What a manifest's content hash covers
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BindingScope {
+    File,         // every byte of the file (sidecars)
+    DocxBody,     // word/document.xml of a DOCX
+    MarkdownBody, // the Markdown before the embedded manifest
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProvenanceManifest {
+    pub manifest_id: String,
+    pub claim_generator: String,
+    pub title: String,
+    pub work_product: String, // the feature that produced it, e.g. "research_memo"
+    pub model: String,
+    pub model_sha256: Option<String>,
+    pub model_revision: Option<String>,
+    pub prompts_sha256: String,
+    pub sources: Vec<ProvenanceSource>,
+    pub created_at: DateTime<Utc>,
+    pub binding: BindingScope,
+    pub content_sha256: String,
+}
+
+/// What a feature knows about the document it generated
+#[derive(Debug, Clone)]
+pub struct ProvenanceClaim {
+    pub title: String,
+    pub work_product: String,
+    pub model: String,
+    pub prompts_sha256: String, // see prompts_sha256()
+    pub sources: Vec<ProvenanceSource>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedManifest {
+    pub format: String,
+    pub format_version: u32,
+    pub manifest: String, // the manifest JSON exactly as signed
+    pub signer_key: String,
+    pub signature: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProvenanceVerification {
+    pub manifest: ProvenanceManifest,
+    pub signer_fingerprint: String,
+    pub content_unchanged: bool,
+    pub found_in: String, // "sidecar" or "embedded"
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+/// One hash over every prompt, so a recipient can check a prompt log without seeing it here
+pub fn prompts_sha256(prompts: &[String]) -> String {
+    sha256_hex(prompts.join("\u{0}").as_bytes())
+}
+
+pub fn sign_manifest(manifest: &ProvenanceManifest, key_pair: &Ed25519KeyPair) -> Result<SignedManifest> {
+    let engine = base64::engine::general_purpose::STANDARD;
+    let manifest = serde_json::to_string_pretty(manifest)?;
+    Ok(SignedManifest {
+        format: MANIFEST_FORMAT.to_string(),
+        format_version: MANIFEST_FORMAT_VERSION,
+        signature: engine.encode(key_pair.sign(manifest.as_bytes())),
+        signer_key: engine.encode(key_pair.public_key()),
+        manifest,
+    })
+}
+
+/// Check the signature; returns the manifest and the signer's fingerprint
+pub fn verify_signed(signed: &SignedManifest) -> Result<(ProvenanceManifest, String)> {
+    if signed.format != MANIFEST_FORMAT {
+        return Err(anyhow!("Not a BEAR AI provenance manifest"));
+    }
+    if signed.format_version > MANIFEST_FORMAT_VERSION {
+        return Err(anyhow!("Manifest format version {} is newer than supported", signed.format_version));
+    }
+    let engine = base64::engine::general_purpose::STANDARD;
+    let public_key = engine.decode(&signed.signer_key).context("Invalid signer key encoding")?;
+    let signature = engine.decode(&signed.signature).context("Invalid signature encoding")?;
+    UnparsedPublicKey::new(&ED25519, &public_key)
+        .verify(signed.manifest.as_bytes(), &signature)
+        .map_err(|_| anyhow!("Manifest signature verification failed; the manifest was modified"))?;
+    let manifest = serde_json::from_str(&signed.manifest).context("Invalid provenance manifest")?;
+    Ok((manifest, signer_fingerprint(&public_key)))
+}
+
+fn read_docx_part(path: &Path, name: &str) -> Result<Option<Vec<u8>>> {
+    let mut archive = ZipArchive::new(fs::File::open(path)?)?;
+    let mut part = match archive.by_name(name) {
+        Ok(part) => part,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let mut content = Vec::new();
+    part.read_to_end(&mut content)?;
+    Ok(Some(content))
+}
+
+/// Add the manifest to a DOCX as its own package part, with the content type and package
+/// relationship Word needs to leave it alone
+pub fn embed_in_docx(path: &Path, signed: &SignedManifest) -> Result<()> {
+    let mut archive = ZipArchive::new(fs::File::open(path)?)?;
+    let mut parts = Vec::new();
+    for i in 0..archive.len() {
+        let mut part = archive.by_index(i)?;
+        if part.name() == DOCX_MANIFEST_PART {
+            continue;
+        }
+        let mut content = Vec::new();
+        part.read_to_end(&mut content)?;
+        parts.push((part.name().to_string(), content));
+    }
+    drop(archive);
+
+    for (name, content) in parts.iter_mut() {
+        let xml = String::from_utf8_lossy(content).to_string();
+        if name == "[Content_Types].xml" && !xml.contains(r#"Extension="json""#) {
+            *content = xml
+                .replacen("<Default ", r#"<Default Extension="json" ContentType="application/json"/><Default "#, 1)
+                .into_bytes();
+        } else if name == "_rels/.rels" && !xml.contains("rIdBearAiProvenance") {
+            *content = xml.replace("</Relationships>", &format!("{}</Relationships>", DOCX_RELATIONSHIP)).into_bytes();
+        }
+    }
+    parts.push((DOCX_MANIFEST_PART.to_string(), serde_json::to_vec_pretty(signed)?));
+
+    let mut zip = ZipWriter::new(fs::File::create(path)?);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    for (name, content) in parts {
+        zip.start_file(name, options)?;
+        zip.write_all(&content)?;
+    }
+    zip.finish()?;
+    Ok(())
+}
+
+/// Markdown with the manifest appended as a comment, which renderers do not display
+pub fn embed_in_markdown(content: &str, signed: &SignedManifest) -> Result<String> {
+    let encoded = base64::engine::general_purpose::STANDARD.encode(serde_json::to_vec(signed)?);
+    Ok(format!("{}{}{} -->\n", content, MARKDOWN_MARKER, encoded))
+}
+
+fn split_markdown(content: &str) -> Option<(&str, SignedManifest)> {
+    let start = content.rfind(MARKDOWN_MARKER)?;
+    let encoded = content[start + MARKDOWN_MARKER.len()..].trim().trim_end_matches("-->").trim();
+    let json = base64::engine::general_purpose::STANDARD.decode(encoded).ok()?;
+    Some((&content[..start], serde_json::from_slice(&json).ok()?))
+}
+
+pub fn sidecar_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(SIDECAR_SUFFIX);
+    PathBuf::from(name)
+}
+
+fn is_markdown(path: &Path) -> bool {
+    path.extension().is_some_and(|e| e.eq_ignore_ascii_case("md"))
+}
+
+fn is_docx(path: &Path) -> bool {
+    path.extension().is_some_and(|e| e.eq_ignore_ascii_case("docx"))
+}
+
+/// Verify a document's provenance: its sidecar when there is one, else the embedded manifest
+pub fn verify_file(path: &Path) -> Result<ProvenanceVerification> {
+    let sidecar = sidecar_path(path);
+    let (signed, found_in) = if sidecar.exists() {
+        (serde_json::from_str(&fs::read_to_string(&sidecar)?)?, "sidecar")
+    } else if is_docx(path) {
+        let part = read_docx_part(path, DOCX_MANIFEST_PART)?.ok_or_else(|| anyhow!("The document carries no provenance manifest"))?;
+        (serde_json::from_slice(&part)?, "embedded")
+    } else if is_markdown(path) {
+        let content = fs::read_to_string(path)?;
+        let (_, signed) = split_markdown(&content).ok_or_else(|| anyhow!("The document carries no provenance manifest"))?;
+        (signed, "embedded")
+    } else {
+        return Err(anyhow!("No provenance sidecar found at {}", sidecar.display()));
+    };
+
+    let (manifest, signer_fingerprint) = verify_signed(&signed)?;
+    let current = match manifest.binding {
+        BindingScope::File => sha256_hex(&fs::read(path)?),
+        BindingScope::DocxBody => sha256_hex(&read_docx_part(path, DOCX_BODY_PART)?.unwrap_or_default()),
+        BindingScope::MarkdownBody => {
+            let content = fs::read_to_string(path)?;
+            sha256_hex(split_markdown(&content).map(|(body, _)| body).unwrap_or(&content).as_bytes())
+        }
+    };
+    Ok(ProvenanceVerification {
+        content_unchanged: current == manifest.content_sha256,
+        manifest,
+        signer_fingerprint,
+        found_in: found_in.to_string(),
+    })
+}
+
+pub struct Provenance {
+    key_path: PathBuf,
+}
+
+impl Provenance {
+    pub fn new(app_data_dir: &Path) -> Self {
+        Self {
+            key_path: app_data_dir.join("provenance_signing_key.enc"),
+        }
+    }
+
+    fn signing_key(&self, security: &SecurityManager) -> Result<Ed25519KeyPair> {
+        security.signing_key(&self.key_path, "provenance key")
+    }
+
+    pub fn signer_fingerprint(&self, security: &SecurityManager) -> Result<String> {
+        Ok(signer_fingerprint(self.signing_key(security)?.public_key().as_ref()))
+    }
+
+    /// Embed a signed manifest in a DOCX or Markdown export and, when asked (or when the format
+    /// cannot carry one), write a sidecar bound to the finished file
+    pub fn stamp(
+        &self,
+        path: &Path,
+        claim: ProvenanceClaim,
+        sidecar: bool,
+        llm: &LLMManager,
+        security: &SecurityManager,
+    ) -> Result<ProvenanceManifest> {
+        let key_pair = self.signing_key(security)?;
+        let model = llm.get_model_provenance(&claim.model).ok();
+        let manifest = |binding: BindingScope, content_sha256: String| ProvenanceManifest {
+            manifest_id: Uuid::new_v4().to_string(),
+            claim_generator: format!("BEAR AI Legal Assistant/{}", env!("CARGO_PKG_VERSION")),
+            title: claim.title.clone(),
+            work_product: claim.work_product.clone(),
+            model: claim.model.clone(),
+            model_sha256: model.as_ref().map(|m| m.sha256.clone()).filter(|s| !s.is_empty()),
+            model_revision: model.as_ref().and_then(|m| m.hf_revision.clone()),
+            prompts_sha256: claim.prompts_sha256.clone(),
+            sources: claim.sources.clone(),
+            created_at: Utc::now(),
+            binding,
+            content_sha256,
+        };
+
+        let mut stamped = None;
+        if is_docx(path) {
+            let body = read_docx_part(path, DOCX_BODY_PART)?.ok_or_else(|| anyhow!("Not a Word document"))?;
+            let embedded = manifest(BindingScope::DocxBody, sha256_hex(&body));
+            embed_in_docx(path, &sign_manifest(&embedded, &key_pair)?)?;
+            stamped = Some(embedded);
+        } else if is_markdown(path) {
+            let content = fs::read_to_string(path)?;
+            let embedded = manifest(BindingScope::MarkdownBody, sha256_hex(content.as_bytes()));
+            fs::write(path, embed_in_markdown(&content, &sign_manifest(&embedded, &key_pair)?)?)?;
+            stamped = Some(embedded);
+        }
+        if sidecar || stamped.is_none() {
+            let whole = manifest(BindingScope::File, sha256_hex(&fs::read(path)?));
+            fs::write(sidecar_path(path), serde_json::to_string_pretty(&sign_manifest(&whole, &key_pair)?)?)?;
+            stamped = stamped.or(Some(whole));
+        }
+        Ok(stamped.expect("a manifest is always written"))
+    }
+}
+
+pub type ProvenanceStorage = Arc<Provenance>;
+type SecurityStorage = Arc<Mutex<SecurityManager>>;
+
+#[tauri::command]
+pub async fn verify_provenance(path: String) -> Result<ProvenanceVerification, String> {
+    verify_file(Path::new(&path)).map_err(|e| e.to_string())
+}
+
+/// The fingerprint recipients compare verified manifests against
+#[tauri::command]
+pub async fn get_provenance_fingerprint(
+    provenance: tauri::State<'_, ProvenanceStorage>,
+    security: tauri::State<'_, SecurityStorage>,
+) -> Result<String, String> {
+    let security = security.lock().unwrap();
+    provenance.signer_fingerprint(&security).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::rand::SystemRandom;
+    use crate::docx_writer::{self, DocxBlock};
+
+    fn signed(binding: BindingScope, content_sha256: String, key_pair: &Ed25519KeyPair) -> SignedManifest {
+        let manifest = ProvenanceManifest {
+            manifest_id: "m1".to_string(),
+            claim_generator: "test".to_string(),
+            title: "Memo".to_string(),
+            work_product: "research_memo".to_string(),
+            model: "llama3".to_string(),
+            model_sha256: None,
+            model_revision: None,
+            prompts_sha256: prompts_sha256(&["p1".to_string()]),
+            sources: vec![],
+            created_at: Utc::now(),
+            binding,
+            content_sha256,
+        };
+        sign_manifest(&manifest, key_pair).unwrap()
+    }
+
+    fn key_pair() -> Ed25519KeyPair {
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+        Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap()
+    }
+
+    #[test]
+    fn test_docx_manifest_detects_edits() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("memo.docx");
+        docx_writer::write_docx(&path, &[DocxBlock::Paragraph("Holding".to_string())]).unwrap();
+        let body = read_docx_part(&path, DOCX_BODY_PART).unwrap().unwrap();
+        let keys = key_pair();
+        embed_in_docx(&path, &signed(BindingScope::DocxBody, sha256_hex(&body), &keys)).unwrap();
+
+        let verified = verify_file(&path).unwrap();
+        assert!(verified.content_unchanged);
+        assert_eq!(verified.found_in, "embedded");
+        assert_eq!(verified.signer_fingerprint, signer_fingerprint(keys.public_key().as_ref()));
+        let types = String::from_utf8(read_docx_part(&path, "[Content_Types].xml").unwrap().unwrap()).unwrap();
+        assert!(types.contains(r#"Extension="json""#));
+
+        docx_writer::write_docx(&path, &[DocxBlock::Paragraph("Edited holding".to_string())]).unwrap();
+        assert!(verify_file(&path).is_err()); // rewritten without a manifest
+    }
+
+    #[test]
+    fn test_markdown_and_sidecar_manifests() {
+        let dir = tempfile::tempdir().unwrap();
+        let keys = key_pair();
+        let path = dir.path().join("chat.md");
+        let body = "# Chat\n\nAnswer\n";
+        let content = embed_in_markdown(body, &signed(BindingScope::MarkdownBody, sha256_hex(body.as_bytes()), &keys)).unwrap();
+        fs::write(&path, &content).unwrap();
+        assert!(verify_file(&path).unwrap().content_unchanged);
+        fs::write(&path, content.replace("Answer", "Other answer")).unwrap();
+        assert!(!verify_file(&path).unwrap().content_unchanged);
+
+        let pdf = dir.path().join("chat.pdf");
+        fs::write(&pdf, b"%PDF-1.4").unwrap();
+        let sidecar = signed(BindingScope::File, sha256_hex(b"%PDF-1.4"), &keys);
+        fs::write(sidecar_path(&pdf), serde_json::to_string(&sidecar).unwrap()).unwrap();
+        assert_eq!(verify_file(&pdf).unwrap().found_in, "sidecar");
+
+        let mut forged = sidecar;
+        forged.manifest = forged.manifest.replace("llama3", "gpt");
+        fs::write(sidecar_path(&pdf), serde_json::to_string(&forged).unwrap()).unwrap();
+        assert!(verify_file(&pdf).is_err());
+    }
+}
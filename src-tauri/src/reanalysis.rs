@@ -0,0 +1,521 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::Manager;
+use uuid::Uuid;
+
+use crate::document_analyzer;
+use crate::incremental_analysis::{AnalysisVersion, ChangeStatus, FindingChange, FindingKind};
+use crate::local_api::{authenticated_user, AnalyzerStorage, SessionStorage};
+use crate::performance_tracker;
+use crate::review_queue::ReviewQueueStorage;
+
+/// Bulk Re-analysis for BEAR AI
+/// Every stored analysis records the compliance rule pack and model it was produced with. When
+/// either changes, the analyses made with the old ones are stale. A re-analysis job works through
+/// them one document at a time in the background, pausing between documents and while the
+/// resource guards report the machine is busy. The job is saved after every document, so it
+/// resumes where it stopped after a pause or a restart. Re-analyses with changed findings are
+/// queued for attorney review.
+pub const REANALYSIS_PROGRESS_EVENT: &str = "reanalysis-progress";
+
+// Pause between documents unless the job sets its own
+const DEFAULT_PAUSE_MS: u64 = 2_000;
+
+// Wait while the resource guards block work without suggesting a delay
+const BUSY_WAIT_MS: u64 = 5_000;
+
+/// What an analysis was produced with; a change in either part makes it stale
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnalysisFingerprint {
+    pub rule_pack: String,
+    pub model: Option<String>, // the loaded model that classifies and translates; None without one
+    pub model_sha256: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StaleReason {
+    RulePackUpdated,
+    ModelChanged,
+    NotRecorded, // analyzed before fingerprints were recorded
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StaleAnalysis {
+    pub path: String,
+    pub version: u32,
+    pub analyzed_at: DateTime<Utc>,
+    pub fingerprint: Option<AnalysisFingerprint>,
+    pub reasons: Vec<StaleReason>,
+}
+
+/// Why an analysis made with `recorded` differs from one made with `current`; empty when current
+pub fn stale_reasons(recorded: Option<&AnalysisFingerprint>, current: &AnalysisFingerprint) -> Vec<StaleReason> {
+    let Some(recorded) = recorded else {
+        return vec![StaleReason::NotRecorded];
+    };
+    let mut reasons = Vec::new();
+    if recorded.rule_pack != current.rule_pack {
+        reasons.push(StaleReason::RulePackUpdated);
+    }
+    let new_weights = matches!((&recorded.model_sha256, &current.model_sha256), (Some(a), Some(b)) if a != b);
+    if recorded.model != current.model || new_weights {
+        reasons.push(StaleReason::ModelChanged);
+    }
+    reasons
+}
+
+/// Stale analyses, oldest first
+pub fn find_stale(versions: Vec<AnalysisVersion>, current: &AnalysisFingerprint) -> Vec<StaleAnalysis> {
+    let mut stale: Vec<StaleAnalysis> = versions
+        .into_iter()
+        .filter_map(|v| {
+            let reasons = stale_reasons(v.fingerprint.as_ref(), current);
+            (!reasons.is_empty()).then_some(StaleAnalysis {
+                path: v.path,
+                version: v.version,
+                analyzed_at: v.analyzed_at,
+                fingerprint: v.fingerprint,
+                reasons,
+            })
+        })
+        .collect();
+    stale.sort_by_key(|s| s.analyzed_at);
+    stale
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DocumentStatus {
+    Pending,
+    Done,
+    Failed,
+    Skipped, // the file is gone
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobDocument {
+    pub path: String,
+    pub reasons: Vec<StaleReason>,
+    pub status: DocumentStatus,
+    pub error: Option<String>,
+    pub previous_version: u32,
+    pub version: Option<u32>,
+    pub changes: Vec<FindingChange>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobState {
+    Running,
+    Paused,
+    Completed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReanalysisJob {
+    pub id: String,
+    pub created_at: DateTime<Utc>,
+    pub target: AnalysisFingerprint,
+    pub state: JobState,
+    pub pause_ms: u64,
+    #[serde(default)]
+    pub started_by: String, // re-analyses with changed findings are submitted for review as this user
+    pub documents: Vec<JobDocument>,
+}
+
+/// Finding changes of one kind across the job's documents
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KindChanges {
+    pub kind: FindingKind,
+    pub new: usize,
+    pub removed: usize,
+    pub changed: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReanalysisSummary {
+    pub job_id: String,
+    pub state: JobState,
+    pub total: usize,
+    pub pending: usize,
+    pub done: usize,
+    pub failed: usize,
+    pub skipped: usize,
+    pub documents_with_changes: usize,
+    pub changes: Vec<KindChanges>,
+}
+
+/// Progress and the findings that changed so far
+pub fn summarize(job: &ReanalysisJob) -> ReanalysisSummary {
+    let count = |status: DocumentStatus| job.documents.iter().filter(|d| d.status == status).count();
+    let mut changes: Vec<KindChanges> = Vec::new();
+    for change in job.documents.iter().flat_map(|d| &d.changes) {
+        let index = match changes.iter().position(|c| c.kind == change.kind) {
+            Some(index) => index,
+            None => {
+                changes.push(KindChanges {
+                    kind: change.kind,
+                    new: 0,
+                    removed: 0,
+                    changed: 0,
+                });
+                changes.len() - 1
+            }
+        };
+        match change.status {
+            ChangeStatus::New => changes[index].new += 1,
+            ChangeStatus::Removed => changes[index].removed += 1,
+            ChangeStatus::Changed => changes[index].changed += 1,
+        }
+    }
+    ReanalysisSummary {
+        job_id: job.id.clone(),
+        state: job.state,
+        total: job.documents.len(),
+        pending: count(DocumentStatus::Pending),
+        done: count(DocumentStatus::Done),
+        failed: count(DocumentStatus::Failed),
+        skipped: count(DocumentStatus::Skipped),
+        documents_with_changes: job.documents.iter().filter(|d| !d.changes.is_empty()).count(),
+        changes,
+    }
+}
+
+/// The current re-analysis job, saved after every change
+pub struct Reanalysis {
+    path: PathBuf,
+    job: Mutex<Option<ReanalysisJob>>,
+    running: AtomicBool, // a runner task is working through the job
+}
+
+pub type ReanalysisStorage = Arc<Reanalysis>;
+
+impl Reanalysis {
+    pub fn new(app_data_dir: &Path) -> Result<Self> {
+        let path = app_data_dir.join("reanalysis_job.json");
+        let job = match fs::read_to_string(&path) {
+            Ok(json) => Some(serde_json::from_str(&json)?),
+            Err(_) => None,
+        };
+        Ok(Reanalysis {
+            path,
+            job: Mutex::new(job),
+            running: AtomicBool::new(false),
+        })
+    }
+
+    fn save(&self, job: &ReanalysisJob) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.path, serde_json::to_string_pretty(job)?)?;
+        Ok(())
+    }
+
+    pub fn current(&self) -> Option<ReanalysisJob> {
+        self.job.lock().unwrap().clone()
+    }
+
+    /// Whether a saved job was still running when the app stopped
+    pub fn should_resume(&self) -> bool {
+        self.job.lock().unwrap().as_ref().is_some_and(|j| j.state == JobState::Running)
+    }
+
+    /// Replace any finished or paused job with one over `stale`
+    pub fn start(&self, stale: Vec<StaleAnalysis>, target: AnalysisFingerprint, pause_ms: Option<u64>, started_by: &str) -> Result<ReanalysisJob> {
+        let mut current = self.job.lock().unwrap();
+        if current.as_ref().is_some_and(|j| j.state == JobState::Running) {
+            return Err(anyhow!("A re-analysis job is already running"));
+        }
+        let job = ReanalysisJob {
+            id: Uuid::new_v4().to_string(),
+            created_at: Utc::now(),
+            target,
+            state: if stale.is_empty() { JobState::Completed } else { JobState::Running },
+            pause_ms: pause_ms.unwrap_or(DEFAULT_PAUSE_MS),
+            started_by: started_by.to_string(),
+            documents: stale
+                .into_iter()
+                .map(|s| JobDocument {
+                    path: s.path,
+                    reasons: s.reasons,
+                    status: DocumentStatus::Pending,
+                    error: None,
+                    previous_version: s.version,
+                    version: None,
+                    changes: Vec::new(),
+                    finished_at: None,
+                })
+                .collect(),
+        };
+        self.save(&job)?;
+        *current = Some(job.clone());
+        Ok(job)
+    }
+
+    /// Pause or resume the job; completed jobs stay completed
+    pub fn set_state(&self, state: JobState) -> Result<ReanalysisJob> {
+        let mut current = self.job.lock().unwrap();
+        let job = current.as_mut().ok_or_else(|| anyhow!("No re-analysis job"))?;
+        if job.state != JobState::Completed {
+            job.state = state;
+            self.save(job)?;
+        }
+        Ok(job.clone())
+    }
+
+    /// The next pending document of a running job; completes the job when none is left
+    fn next_pending(&self) -> Result<Option<(usize, String, u64)>> {
+        let mut current = self.job.lock().unwrap();
+        let Some(job) = current.as_mut().filter(|j| j.state == JobState::Running) else {
+            return Ok(None);
+        };
+        match job.documents.iter().position(|d| d.status == DocumentStatus::Pending) {
+            Some(index) => Ok(Some((index, job.documents[index].path.clone(), job.pause_ms))),
+            None => {
+                job.state = JobState::Completed;
+                self.save(job)?;
+                Ok(None)
+            }
+        }
+    }
+
+    fn finish(&self, index: usize, status: DocumentStatus, error: Option<String>, version: Option<u32>, changes: Vec<FindingChange>) -> Result<ReanalysisSummary> {
+        let mut current = self.job.lock().unwrap();
+        let job = current.as_mut().ok_or_else(|| anyhow!("No re-analysis job"))?;
+        let document = &mut job.documents[index];
+        document.status = status;
+        document.error = error;
+        document.version = version;
+        document.changes = changes;
+        document.finished_at = Some(Utc::now());
+        self.save(job)?;
+        Ok(summarize(job))
+    }
+}
+
+/// Wait until the resource guards allow more work
+async fn wait_for_resources() {
+    let Some(tracker) = performance_tracker::get_performance_tracker() else {
+        return;
+    };
+    loop {
+        match tracker.check_resource_guards().await {
+            Ok(status) if !status.allowed => {
+                log::info!("Re-analysis waiting: {}", status.reason.unwrap_or_default());
+                tokio::time::sleep(Duration::from_millis(status.suggested_delay_ms.unwrap_or(BUSY_WAIT_MS))).await;
+            }
+            _ => return,
+        }
+    }
+}
+
+/// Work through the running job's pending documents. Only one runner works at a time.
+pub async fn run_job(app: tauri::AppHandle) {
+    let jobs = app.state::<ReanalysisStorage>().inner().clone();
+    if jobs.running.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let analyzer = app.state::<AnalyzerStorage>().inner().clone();
+
+    loop {
+        let (index, path, pause_ms) = match jobs.next_pending() {
+            Ok(Some(next)) => next,
+            Ok(None) => break,
+            Err(e) => {
+                log::warn!("Re-analysis job could not be saved: {}", e);
+                break;
+            }
+        };
+        tokio::time::sleep(Duration::from_millis(pause_ms)).await;
+        wait_for_resources().await;
+
+        let file = Path::new(&path);
+        let finished = if !file.exists() {
+            jobs.finish(index, DocumentStatus::Skipped, Some("File no longer exists".to_string()), None, Vec::new())
+        } else {
+            match analyzer.reanalyze_document(file).await {
+                Ok(result) => {
+                    // A new rule pack or model makes a new analysis even when no finding changed
+                    let review = app.state::<ReviewQueueStorage>();
+                    let started_by = jobs.current().map(|job| job.started_by).unwrap_or_default();
+                    if let Err(e) = document_analyzer::submit_analysis_for_review(file, &analyzer, &review, &started_by) {
+                        log::warn!("Re-analysis of {} not queued for review: {}", path, e);
+                    }
+                    jobs.finish(index, DocumentStatus::Done, None, Some(result.version), result.changes)
+                }
+                Err(e) => jobs.finish(index, DocumentStatus::Failed, Some(e.to_string()), None, Vec::new()),
+            }
+        };
+        match finished {
+            Ok(summary) => {
+                let _ = app.emit_all(REANALYSIS_PROGRESS_EVENT, &summary);
+            }
+            Err(e) => {
+                log::warn!("Re-analysis job could not be saved: {}", e);
+                break;
+            }
+        }
+    }
+
+    jobs.running.store(false, Ordering::SeqCst);
+    if let Some(job) = jobs.current() {
+        let _ = app.emit_all(REANALYSIS_PROGRESS_EVENT, &summarize(&job));
+    }
+}
+
+/// Analyses made with an older rule pack or a different model
+#[tauri::command]
+pub async fn list_stale_analyses(analyzer: tauri::State<'_, AnalyzerStorage>) -> Result<Vec<StaleAnalysis>, String> {
+    let current = analyzer.fingerprint().await;
+    Ok(find_stale(analyzer.analysis_versions().list(), &current))
+}
+
+/// Re-analyze stale analyses in the background; `paths` limits the job to some of them
+#[tauri::command]
+pub async fn start_reanalysis(
+    app: tauri::AppHandle,
+    session_id: String,
+    paths: Option<Vec<String>>,
+    pause_ms: Option<u64>,
+    analyzer: tauri::State<'_, AnalyzerStorage>,
+    jobs: tauri::State<'_, ReanalysisStorage>,
+    sessions: tauri::State<'_, SessionStorage>,
+) -> Result<ReanalysisJob, String> {
+    let user = authenticated_user(&session_id, &sessions)?;
+    let current = analyzer.fingerprint().await;
+    let mut stale = find_stale(analyzer.analysis_versions().list(), &current);
+    if let Some(paths) = paths {
+        stale.retain(|s| paths.contains(&s.path));
+    }
+    let job = jobs.start(stale, current, pause_ms, &user).map_err(|e| e.to_string())?;
+    tauri::async_runtime::spawn(run_job(app));
+    Ok(job)
+}
+
+#[tauri::command]
+pub async fn pause_reanalysis(jobs: tauri::State<'_, ReanalysisStorage>) -> Result<ReanalysisJob, String> {
+    jobs.set_state(JobState::Paused).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn resume_reanalysis(app: tauri::AppHandle, jobs: tauri::State<'_, ReanalysisStorage>) -> Result<ReanalysisJob, String> {
+    let job = jobs.set_state(JobState::Running).map_err(|e| e.to_string())?;
+    tauri::async_runtime::spawn(run_job(app));
+    Ok(job)
+}
+
+/// The current job with each document's status and changed findings
+#[tauri::command]
+pub async fn get_reanalysis_job(jobs: tauri::State<'_, ReanalysisStorage>) -> Result<Option<ReanalysisJob>, String> {
+    Ok(jobs.current())
+}
+
+#[tauri::command]
+pub async fn get_reanalysis_summary(jobs: tauri::State<'_, ReanalysisStorage>) -> Result<Option<ReanalysisSummary>, String> {
+    Ok(jobs.current().as_ref().map(summarize))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn fingerprint(rule_pack: &str, model: Option<&str>, sha: Option<&str>) -> AnalysisFingerprint {
+        AnalysisFingerprint {
+            rule_pack: rule_pack.to_string(),
+            model: model.map(String::from),
+            model_sha256: sha.map(String::from),
+        }
+    }
+
+    fn stale(path: &str) -> StaleAnalysis {
+        StaleAnalysis {
+            path: path.to_string(),
+            version: 1,
+            analyzed_at: Utc::now(),
+            fingerprint: None,
+            reasons: vec![StaleReason::RulePackUpdated],
+        }
+    }
+
+    fn change(kind: FindingKind, status: ChangeStatus) -> FindingChange {
+        FindingChange {
+            kind,
+            status,
+            label: "GDPR: Consent".to_string(),
+            section: None,
+            before: None,
+            after: None,
+        }
+    }
+
+    #[test]
+    fn test_stale_reasons() {
+        let current = fingerprint("2", Some("llama3.1:8b"), Some("abc"));
+        assert!(stale_reasons(Some(&current), &current).is_empty());
+        assert_eq!(stale_reasons(None, &current), vec![StaleReason::NotRecorded]);
+        assert_eq!(
+            stale_reasons(Some(&fingerprint("1", Some("llama3.1:8b"), Some("abc"))), &current),
+            vec![StaleReason::RulePackUpdated]
+        );
+        // Same model name with new weights
+        assert_eq!(
+            stale_reasons(Some(&fingerprint("2", Some("llama3.1:8b"), Some("old"))), &current),
+            vec![StaleReason::ModelChanged]
+        );
+        assert_eq!(
+            stale_reasons(Some(&fingerprint("1", None, None)), &current),
+            vec![StaleReason::RulePackUpdated, StaleReason::ModelChanged]
+        );
+        // A missing checksum is not evidence of new weights
+        assert!(stale_reasons(Some(&fingerprint("2", Some("llama3.1:8b"), None)), &current).is_empty());
+    }
+
+    #[test]
+    fn test_job_resumes_after_restart_and_summarizes_changes() {
+        let dir = tempdir().unwrap();
+        let jobs = Reanalysis::new(dir.path()).unwrap();
+        let target = fingerprint("2", None, None);
+        let job = jobs.start(vec![stale("/a.docx"), stale("/b.docx"), stale("/c.docx")], target.clone(), Some(0), "alice").unwrap();
+        assert_eq!(job.state, JobState::Running);
+        assert!(jobs.start(Vec::new(), target, None, "alice").is_err());
+
+        let (index, path, _) = jobs.next_pending().unwrap().unwrap();
+        assert_eq!(path, "/a.docx");
+        let changes = vec![
+            change(FindingKind::ComplianceFlag, ChangeStatus::New),
+            change(FindingKind::ComplianceFlag, ChangeStatus::Changed),
+            change(FindingKind::Risk, ChangeStatus::Removed),
+        ];
+        jobs.finish(index, DocumentStatus::Done, None, Some(2), changes).unwrap();
+
+        // A restart picks the job up at the next pending document
+        let jobs = Reanalysis::new(dir.path()).unwrap();
+        assert!(jobs.should_resume());
+        jobs.set_state(JobState::Paused).unwrap();
+        assert!(jobs.next_pending().unwrap().is_none());
+        jobs.set_state(JobState::Running).unwrap();
+        let (index, path, _) = jobs.next_pending().unwrap().unwrap();
+        assert_eq!(path, "/b.docx");
+        jobs.finish(index, DocumentStatus::Failed, Some("unreadable".to_string()), None, Vec::new()).unwrap();
+        let (index, _, _) = jobs.next_pending().unwrap().unwrap();
+        let summary = jobs.finish(index, DocumentStatus::Skipped, None, None, Vec::new()).unwrap();
+        assert!(jobs.next_pending().unwrap().is_none());
+        assert_eq!(jobs.current().unwrap().state, JobState::Completed);
+
+        assert_eq!((summary.done, summary.failed, summary.skipped, summary.pending), (1, 1, 1, 0));
+        assert_eq!(summary.documents_with_changes, 1);
+        let flags = &summary.changes[0];
+        assert_eq!((flags.kind, flags.new, flags.changed, flags.removed), (FindingKind::ComplianceFlag, 1, 1, 0));
+        assert_eq!((summary.changes[1].kind, summary.changes[1].removed), (FindingKind::Risk, 1));
+    }
+}
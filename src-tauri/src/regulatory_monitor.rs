@@ -0,0 +1,903 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+use tauri::Manager;
+use uuid::Uuid;
+
+use crate::intranet_crawler::parse_html;
+use crate::nemotron_rag::{DocumentType, LegalDocument};
+
+/// Regulatory Change Monitoring for BEAR AI
+/// Indexing keeps every passage of an internal policy or contract that cites legislation
+/// ("Article 28 GDPR", "§ 1798.150 of the Cal. Civ. Code"). When a statute fetcher hands in a
+/// newly fetched regulation, its provisions are compared with the previous version by content
+/// hash. Documents whose passages cite the regulation together with an amended or repealed
+/// provision get a high-priority review task; documents that only name the regulation get a
+/// low-priority one. The first version of a regulation is the baseline and opens no tasks.
+/// The built-in fetcher downloads watched regulations from their official source on a schedule.
+const MAX_PASSAGES_PER_DOCUMENT: usize = 200;
+const MAX_PASSAGE_CHARS: usize = 500;
+const FETCHER_USER_AGENT: &str = "BEAR-AI-Statute-Fetcher/1.0";
+const MAX_REGULATION_BYTES: u64 = 20 * 1024 * 1024;
+const DEFAULT_REFRESH_HOURS: u32 = 24;
+
+pub const REGULATORY_REVIEW_EVENT: &str = "regulatory-review-tasks";
+
+/// Passage of an indexed document that cites legislation
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CitingPassage {
+    pub text: String,
+    pub provisions: Vec<String>, // "28", "1798.150"
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentReferences {
+    pub document_id: String,
+    pub title: String,
+    pub document_type: DocumentType,
+    pub passages: Vec<CitingPassage>,
+    pub indexed_at: DateTime<Utc>,
+}
+
+/// A regulation as delivered by a statute fetcher
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FetchedRegulation {
+    pub id: String,       // stable across versions, e.g. "eu-2016-679"
+    pub citation: String, // "Regulation (EU) 2016/679"
+    #[serde(default)]
+    pub aliases: Vec<String>, // "GDPR", "General Data Protection Regulation"
+    pub title: String,
+    pub jurisdiction: String,
+    pub text: String,
+    pub source_url: Option<String>,
+}
+
+/// A regulation the statute fetcher downloads from its official text on a schedule
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchedRegulation {
+    pub id: String,
+    pub citation: String,
+    pub aliases: Vec<String>,
+    pub title: String,
+    pub jurisdiction: String,
+    pub source_url: String, // HTML or plain text
+    pub refresh_hours: u32,
+    pub last_fetched: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct WatchRegulationRequest {
+    pub id: String,
+    pub citation: String,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    pub title: String,
+    pub jurisdiction: String,
+    pub source_url: String,
+    pub refresh_hours: Option<u32>, // defaults to daily
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegulationSnapshot {
+    pub id: String,
+    pub citation: String,
+    pub aliases: Vec<String>,
+    pub title: String,
+    pub jurisdiction: String,
+    pub source_url: Option<String>,
+    pub provisions: BTreeMap<String, String>, // provision number -> content hash
+    pub fetched_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Added,
+    Amended,
+    Repealed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProvisionChange {
+    pub provision: String,
+    pub kind: ChangeKind,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewPriority {
+    High, // cites a changed provision
+    Low,  // names the regulation without a provision
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewTaskStatus {
+    Open,
+    Resolved,
+    Dismissed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewTask {
+    pub id: String,
+    pub regulation_id: String,
+    pub citation: String,
+    pub document_id: String,
+    pub document_title: String,
+    pub provisions: Vec<String>, // changed provisions the document cites
+    pub passages: Vec<String>,
+    pub priority: ReviewPriority,
+    pub status: ReviewTaskStatus,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegulatoryCheck {
+    pub regulation_id: String,
+    pub citation: String,
+    pub baseline: bool, // first version seen; nothing to compare against
+    pub changes: Vec<ProvisionChange>,
+    pub tasks: Vec<ReviewTask>, // opened or updated by this check
+    pub documents_checked: usize,
+    pub checked_at: DateTime<Utc>,
+}
+
+fn provision_heading() -> Regex {
+    Regex::new(r"(?m)^[ \t]*(?:Article|ARTICLE|Art\.|Section|SECTION|Sec\.|§)[ \t]*(\d+[a-z]?(?:\.\d+)*)").unwrap()
+}
+
+fn provision_reference() -> Regex {
+    Regex::new(r"(?:\b(?i:articles?|art\.|sections?|sec\.)|§§?)\s*(\d+[a-z]?(?:\.\d+)*)").unwrap()
+}
+
+fn content_hash(text: &str) -> String {
+    let normalized = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    hex::encode(Sha256::digest(normalized.as_bytes()))
+}
+
+/// Provision number -> content hash. Text before the first heading is the preamble and is not
+/// compared; a regulation without headings is hashed as a single provision "*".
+pub fn split_provisions(text: &str) -> BTreeMap<String, String> {
+    let headings: Vec<(usize, String)> = provision_heading()
+        .captures_iter(text)
+        .map(|caps| (caps.get(0).unwrap().start(), caps[1].to_string()))
+        .collect();
+    if headings.is_empty() {
+        return BTreeMap::from([("*".to_string(), content_hash(text))]);
+    }
+
+    let mut provisions = BTreeMap::new();
+    for (i, (start, number)) in headings.iter().enumerate() {
+        let end = headings.get(i + 1).map(|(next, _)| *next).unwrap_or(text.len());
+        // A repeated heading (a cross-reference at a line start) extends the provision
+        let hash = content_hash(&text[*start..end]);
+        provisions
+            .entry(number.clone())
+            .and_modify(|existing: &mut String| *existing = content_hash(&format!("{}{}", existing, hash)))
+            .or_insert(hash);
+    }
+    provisions
+}
+
+pub fn diff_provisions(previous: &BTreeMap<String, String>, current: &BTreeMap<String, String>) -> Vec<ProvisionChange> {
+    let mut changes = Vec::new();
+    for (provision, hash) in current {
+        match previous.get(provision) {
+            None => changes.push(ProvisionChange {
+                provision: provision.clone(),
+                kind: ChangeKind::Added,
+            }),
+            Some(old) if old != hash => changes.push(ProvisionChange {
+                provision: provision.clone(),
+                kind: ChangeKind::Amended,
+            }),
+            Some(_) => {}
+        }
+    }
+    for provision in previous.keys().filter(|p| !current.contains_key(*p)) {
+        changes.push(ProvisionChange {
+            provision: provision.clone(),
+            kind: ChangeKind::Repealed,
+        });
+    }
+    changes
+}
+
+/// Sentences naming an instrument ("the GDPR", "Data Protection Act"), with the provisions they cite
+pub fn citing_passages(text: &str) -> Vec<CitingPassage> {
+    let reference = provision_reference();
+    let instrument = Regex::new(r"\b(?:Regulation|Directive|Act|Code|Law|Ordinance)\b|\b[A-Z]{3,6}\b").unwrap();
+    let sentence_end = Regex::new(r"[.;](?:\s+[A-Z(]|\s*\n)|\n\s*\n").unwrap();
+
+    let mut passages = Vec::new();
+    let mut start = 0;
+    let mut ends: Vec<usize> = sentence_end.find_iter(text).map(|m| m.start() + 1).collect();
+    ends.push(text.len());
+    for end in ends {
+        if end <= start {
+            continue;
+        }
+        let sentence = text[start..end].split_whitespace().collect::<Vec<_>>().join(" ");
+        start = end;
+        if sentence.len() > MAX_PASSAGE_CHARS || !instrument.is_match(&sentence) {
+            continue;
+        }
+        let provisions: BTreeSet<String> = reference.captures_iter(&sentence).map(|caps| caps[1].to_string()).collect();
+        passages.push(CitingPassage {
+            text: sentence,
+            provisions: provisions.into_iter().collect(),
+        });
+        if passages.len() == MAX_PASSAGES_PER_DOCUMENT {
+            break;
+        }
+    }
+    passages
+}
+
+fn names_regulation(passage: &str, snapshot: &RegulationSnapshot) -> bool {
+    let passage = passage.to_lowercase();
+    std::iter::once(&snapshot.citation)
+        .chain(snapshot.aliases.iter())
+        .filter(|name| !name.trim().is_empty())
+        .any(|name| passage.contains(&name.to_lowercase()))
+}
+
+/// Review tasks for the documents affected by the changes, before merging with open tasks
+pub fn affected_documents(
+    snapshot: &RegulationSnapshot,
+    changes: &[ProvisionChange],
+    documents: &[DocumentReferences],
+) -> Vec<ReviewTask> {
+    if changes.is_empty() {
+        return Vec::new();
+    }
+    let changed: BTreeSet<&str> = changes.iter().map(|c| c.provision.as_str()).collect();
+    let whole_text_changed = changed.contains("*");
+    let now = Utc::now();
+
+    let mut tasks = Vec::new();
+    for document in documents {
+        let mut provisions = BTreeSet::new();
+        let mut passages = Vec::new();
+        let mut general = false;
+        for passage in document.passages.iter().filter(|p| names_regulation(&p.text, snapshot)) {
+            let cited: Vec<&String> = passage
+                .provisions
+                .iter()
+                .filter(|p| whole_text_changed || changed.contains(p.as_str()))
+                .collect();
+            if !cited.is_empty() {
+                provisions.extend(cited.into_iter().cloned());
+                passages.push(passage.text.clone());
+            } else if passage.provisions.is_empty() {
+                general = true;
+                passages.push(passage.text.clone());
+            }
+        }
+        if passages.is_empty() {
+            continue;
+        }
+        tasks.push(ReviewTask {
+            id: Uuid::new_v4().to_string(),
+            regulation_id: snapshot.id.clone(),
+            citation: snapshot.citation.clone(),
+            document_id: document.document_id.clone(),
+            document_title: document.title.clone(),
+            priority: if provisions.is_empty() && general {
+                ReviewPriority::Low
+            } else {
+                ReviewPriority::High
+            },
+            provisions: provisions.into_iter().collect(),
+            passages,
+            status: ReviewTaskStatus::Open,
+            created_at: now,
+            updated_at: now,
+            note: None,
+        });
+    }
+    tasks
+}
+
+/// Append-only log of citing passages per indexed document; the last entry for a document wins
+pub struct ReferenceLog {
+    path: PathBuf,
+}
+
+impl ReferenceLog {
+    pub fn new(app_data_dir: &Path) -> Self {
+        ReferenceLog {
+            path: app_data_dir.join("regulatory_references.jsonl"),
+        }
+    }
+
+    pub fn append(&self, references: &DocumentReferences) -> Result<()> {
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(references)?)?;
+        Ok(())
+    }
+
+    pub fn load(&self) -> Result<Vec<DocumentReferences>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let mut latest: Vec<DocumentReferences> = Vec::new();
+        let mut position: HashMap<String, usize> = HashMap::new();
+        for line in fs::read_to_string(&self.path)?.lines().filter(|l| !l.trim().is_empty()) {
+            let references: DocumentReferences = serde_json::from_str(line)?;
+            match position.get(&references.document_id) {
+                Some(&i) => latest[i] = references,
+                None => {
+                    position.insert(references.document_id.clone(), latest.len());
+                    latest.push(references);
+                }
+            }
+        }
+        Ok(latest)
+    }
+
+    /// Rewrite the log without a deleted document
+    pub fn remove(&self, document_id: &str) -> Result<()> {
+        let entries = self.load()?;
+        if !entries.iter().any(|e| e.document_id == document_id) {
+            return Ok(());
+        }
+        let mut log = String::new();
+        for entry in entries.iter().filter(|e| e.document_id != document_id) {
+            log.push_str(&serde_json::to_string(entry)?);
+            log.push('\n');
+        }
+        fs::write(&self.path, log)?;
+        Ok(())
+    }
+}
+
+lazy_static! {
+    static ref GLOBAL_REFERENCE_LOG: RwLock<Option<Arc<ReferenceLog>>> = RwLock::new(None);
+}
+
+/// Initialize the global reference log written during indexing
+pub fn initialize_reference_log(app_data_dir: &Path) -> Arc<ReferenceLog> {
+    let log = Arc::new(ReferenceLog::new(app_data_dir));
+    *GLOBAL_REFERENCE_LOG.write().unwrap() = Some(log.clone());
+    log
+}
+
+/// Record the citing passages of an indexed policy or contract. Legislation and case law are
+/// the sources being monitored, not documents to review, and are skipped.
+pub fn record_indexed_document(document: &LegalDocument) -> Result<()> {
+    if matches!(
+        document.document_type,
+        DocumentType::Statute | DocumentType::Regulation | DocumentType::CaseLaw | DocumentType::Opinion
+    ) {
+        return Ok(());
+    }
+    let Some(log) = GLOBAL_REFERENCE_LOG.read().unwrap().clone() else {
+        return Ok(());
+    };
+    log.append(&DocumentReferences {
+        document_id: document.id.clone(),
+        title: document.title.clone(),
+        document_type: document.document_type.clone(),
+        passages: citing_passages(&document.content),
+        indexed_at: Utc::now(),
+    })
+}
+
+/// Drop a deleted document from the log; ignored when the log is not initialized
+pub fn remove_indexed_document(document_id: &str) -> Result<()> {
+    match GLOBAL_REFERENCE_LOG.read().unwrap().clone() {
+        Some(log) => log.remove(document_id),
+        None => Ok(()),
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct MonitorState {
+    regulations: HashMap<String, RegulationSnapshot>,
+    tasks: Vec<ReviewTask>,
+    #[serde(default)]
+    watched: BTreeMap<String, WatchedRegulation>,
+}
+
+pub struct RegulatoryMonitor {
+    path: PathBuf,
+    references: ReferenceLog,
+    state: Mutex<MonitorState>,
+    client: reqwest::Client,
+}
+
+impl RegulatoryMonitor {
+    pub fn new(app_data_dir: &Path) -> Result<Self> {
+        let path = app_data_dir.join("regulatory_monitor.json");
+        let state = if path.exists() {
+            serde_json::from_str(&fs::read_to_string(&path)?)?
+        } else {
+            MonitorState::default()
+        };
+        Ok(Self {
+            path,
+            references: ReferenceLog::new(app_data_dir),
+            state: Mutex::new(state),
+            client: reqwest::Client::builder()
+                .user_agent(FETCHER_USER_AGENT)
+                .timeout(std::time::Duration::from_secs(60))
+                .build()?,
+        })
+    }
+
+    fn persist(&self, state: &MonitorState) -> Result<()> {
+        fs::write(&self.path, serde_json::to_string_pretty(state)?)?;
+        Ok(())
+    }
+
+    /// Compare a fetched regulation with the stored version and open review tasks. An open task
+    /// for the same document and regulation is updated instead of duplicated.
+    pub fn check(&self, regulation: &FetchedRegulation) -> Result<RegulatoryCheck> {
+        let snapshot = RegulationSnapshot {
+            id: regulation.id.clone(),
+            citation: regulation.citation.clone(),
+            aliases: regulation.aliases.clone(),
+            title: regulation.title.clone(),
+            jurisdiction: regulation.jurisdiction.clone(),
+            source_url: regulation.source_url.clone(),
+            provisions: split_provisions(&regulation.text),
+            fetched_at: Utc::now(),
+        };
+        let documents = self.references.load()?;
+
+        let mut state = self.state.lock().unwrap();
+        let previous = state.regulations.get(&regulation.id);
+        let baseline = previous.is_none();
+        let changes = previous
+            .map(|p| diff_provisions(&p.provisions, &snapshot.provisions))
+            .unwrap_or_default();
+
+        let mut tasks = Vec::new();
+        for task in affected_documents(&snapshot, &changes, &documents) {
+            let open = state.tasks.iter_mut().find(|t| {
+                t.status == ReviewTaskStatus::Open
+                    && t.regulation_id == task.regulation_id
+                    && t.document_id == task.document_id
+            });
+            match open {
+                Some(existing) => {
+                    for provision in task.provisions {
+                        if !existing.provisions.contains(&provision) {
+                            existing.provisions.push(provision);
+                        }
+                    }
+                    for passage in task.passages {
+                        if !existing.passages.contains(&passage) {
+                            existing.passages.push(passage);
+                        }
+                    }
+                    if task.priority == ReviewPriority::High {
+                        existing.priority = ReviewPriority::High;
+                    }
+                    existing.updated_at = task.updated_at;
+                    tasks.push(existing.clone());
+                }
+                None => {
+                    state.tasks.push(task.clone());
+                    tasks.push(task);
+                }
+            }
+        }
+
+        state.regulations.insert(regulation.id.clone(), snapshot);
+        self.persist(&state)?;
+        Ok(RegulatoryCheck {
+            regulation_id: regulation.id.clone(),
+            citation: regulation.citation.clone(),
+            baseline,
+            changes,
+            tasks,
+            documents_checked: documents.len(),
+            checked_at: Utc::now(),
+        })
+    }
+
+    pub fn list_regulations(&self) -> Vec<RegulationSnapshot> {
+        let mut regulations: Vec<RegulationSnapshot> =
+            self.state.lock().unwrap().regulations.values().cloned().collect();
+        regulations.sort_by(|a, b| a.citation.cmp(&b.citation));
+        regulations
+    }
+
+    /// Tasks, newest first, optionally filtered by status
+    pub fn list_tasks(&self, status: Option<ReviewTaskStatus>) -> Vec<ReviewTask> {
+        let mut tasks: Vec<ReviewTask> = self
+            .state
+            .lock()
+            .unwrap()
+            .tasks
+            .iter()
+            .filter(|t| status.iter().all(|s| t.status == *s))
+            .cloned()
+            .collect();
+        tasks.sort_by_key(|t| Reverse(t.updated_at));
+        tasks
+    }
+
+    pub fn update_task(&self, task_id: &str, status: ReviewTaskStatus, note: Option<String>) -> Result<ReviewTask> {
+        let mut state = self.state.lock().unwrap();
+        let task = state
+            .tasks
+            .iter_mut()
+            .find(|t| t.id == task_id)
+            .ok_or_else(|| anyhow!("Review task {} not found", task_id))?;
+        task.status = status;
+        if note.is_some() {
+            task.note = note;
+        }
+        task.updated_at = Utc::now();
+        let task = task.clone();
+        self.persist(&state)?;
+        Ok(task)
+    }
+
+    /// Watch a regulation, or change how an already watched one is fetched
+    pub fn watch(&self, request: WatchRegulationRequest) -> Result<WatchedRegulation> {
+        let id = request.id.trim().to_string();
+        if id.is_empty() || request.citation.trim().is_empty() {
+            return Err(anyhow!("A watched regulation needs an id and a citation"));
+        }
+        let source_url = reqwest::Url::parse(request.source_url.trim())
+            .map_err(|e| anyhow!("Invalid source URL: {}", e))?;
+        if source_url.scheme() != "https" {
+            return Err(anyhow!("The regulation source must use https"));
+        }
+        let refresh_hours = request.refresh_hours.unwrap_or(DEFAULT_REFRESH_HOURS);
+        if refresh_hours == 0 {
+            return Err(anyhow!("The refresh interval must be at least one hour"));
+        }
+
+        let mut state = self.state.lock().unwrap();
+        let existing = state.watched.get(&id);
+        let watched = WatchedRegulation {
+            id: id.clone(),
+            citation: request.citation.trim().to_string(),
+            aliases: request.aliases.iter().map(|a| a.trim().to_string()).filter(|a| !a.is_empty()).collect(),
+            title: request.title,
+            jurisdiction: request.jurisdiction,
+            source_url: source_url.to_string(),
+            refresh_hours,
+            last_fetched: existing.and_then(|w| w.last_fetched),
+            last_error: existing.and_then(|w| w.last_error.clone()),
+        };
+        state.watched.insert(id, watched.clone());
+        self.persist(&state)?;
+        Ok(watched)
+    }
+
+    /// Stop fetching a regulation; its snapshot and review tasks are kept
+    pub fn unwatch(&self, regulation_id: &str) -> Result<bool> {
+        let mut state = self.state.lock().unwrap();
+        let removed = state.watched.remove(regulation_id).is_some();
+        if removed {
+            self.persist(&state)?;
+        }
+        Ok(removed)
+    }
+
+    pub fn list_watched(&self) -> Vec<WatchedRegulation> {
+        self.state.lock().unwrap().watched.values().cloned().collect()
+    }
+
+    /// Watched regulations not fetched within their refresh interval
+    pub fn due_regulations(&self, now: DateTime<Utc>) -> Vec<String> {
+        self.state
+            .lock()
+            .unwrap()
+            .watched
+            .values()
+            .filter(|w| w.last_fetched.map_or(true, |last| last + chrono::Duration::hours(w.refresh_hours as i64) <= now))
+            .map(|w| w.id.clone())
+            .collect()
+    }
+
+    /// Download the current text of a watched regulation
+    async fn fetch(&self, watched: &WatchedRegulation) -> Result<FetchedRegulation> {
+        let response = self.client.get(&watched.source_url).send().await?;
+        if !response.status().is_success() {
+            return Err(anyhow!("HTTP {}", response.status()));
+        }
+        if response.content_length().is_some_and(|len| len > MAX_REGULATION_BYTES) {
+            return Err(anyhow!("regulation text larger than {} bytes", MAX_REGULATION_BYTES));
+        }
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .to_lowercase();
+        let body = response.text().await?;
+        let text = if content_type.contains("html") { parse_html(&body).text } else { body };
+        if text.trim().is_empty() {
+            return Err(anyhow!("the source returned no text"));
+        }
+        Ok(FetchedRegulation {
+            id: watched.id.clone(),
+            citation: watched.citation.clone(),
+            aliases: watched.aliases.clone(),
+            title: watched.title.clone(),
+            jurisdiction: watched.jurisdiction.clone(),
+            text,
+            source_url: Some(watched.source_url.clone()),
+        })
+    }
+
+    /// Fetch a watched regulation and check it against the stored version. A failed fetch is
+    /// recorded on the watch and leaves the stored version alone.
+    pub async fn fetch_and_check(&self, regulation_id: &str) -> Result<RegulatoryCheck> {
+        let watched = self
+            .state
+            .lock()
+            .unwrap()
+            .watched
+            .get(regulation_id)
+            .cloned()
+            .ok_or_else(|| anyhow!("Regulation {} is not watched", regulation_id))?;
+
+        let fetched = self.fetch(&watched).await;
+        {
+            let mut state = self.state.lock().unwrap();
+            if let Some(w) = state.watched.get_mut(regulation_id) {
+                w.last_fetched = Some(Utc::now());
+                w.last_error = fetched.as_ref().err().map(|e| e.to_string());
+            }
+            self.persist(&state)?;
+        }
+        self.check(&fetched.map_err(|e| anyhow!("Fetching {} failed: {}", watched.citation, e))?)
+    }
+}
+
+pub type RegulatoryMonitorStorage = Arc<RegulatoryMonitor>;
+
+/// Announce the review tasks a check opened to the notification center
+pub fn announce_check(app: &tauri::AppHandle, check: &RegulatoryCheck) {
+    if !check.tasks.is_empty() {
+        let _ = app.emit_all(REGULATORY_REVIEW_EVENT, check);
+    }
+}
+
+/// Entry point for external statute fetchers: check a newly fetched regulation and announce
+/// the review tasks it opens
+#[tauri::command]
+pub async fn regulatory_check_update(
+    regulation: FetchedRegulation,
+    app: tauri::AppHandle,
+    monitor: tauri::State<'_, RegulatoryMonitorStorage>,
+) -> Result<RegulatoryCheck, String> {
+    let check = monitor.check(&regulation).map_err(|e| e.to_string())?;
+    announce_check(&app, &check);
+    Ok(check)
+}
+
+#[tauri::command]
+pub async fn regulatory_watch_regulation(
+    request: WatchRegulationRequest,
+    monitor: tauri::State<'_, RegulatoryMonitorStorage>,
+) -> Result<WatchedRegulation, String> {
+    monitor.watch(request).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn regulatory_unwatch_regulation(
+    regulation_id: String,
+    monitor: tauri::State<'_, RegulatoryMonitorStorage>,
+) -> Result<bool, String> {
+    monitor.unwatch(&regulation_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn regulatory_list_watched(
+    monitor: tauri::State<'_, RegulatoryMonitorStorage>,
+) -> Result<Vec<WatchedRegulation>, String> {
+    Ok(monitor.list_watched())
+}
+
+/// Fetch one watched regulation, or all of them, now instead of on schedule
+#[tauri::command]
+pub async fn regulatory_fetch_now(
+    regulation_id: Option<String>,
+    app: tauri::AppHandle,
+    monitor: tauri::State<'_, RegulatoryMonitorStorage>,
+) -> Result<Vec<RegulatoryCheck>, String> {
+    let ids = match regulation_id {
+        Some(id) => vec![id],
+        None => monitor.list_watched().into_iter().map(|w| w.id).collect(),
+    };
+    let mut checks = Vec::new();
+    for id in ids {
+        let check = monitor.fetch_and_check(&id).await.map_err(|e| e.to_string())?;
+        announce_check(&app, &check);
+        checks.push(check);
+    }
+    Ok(checks)
+}
+
+#[tauri::command]
+pub async fn regulatory_list_regulations(
+    monitor: tauri::State<'_, RegulatoryMonitorStorage>,
+) -> Result<Vec<RegulationSnapshot>, String> {
+    Ok(monitor.list_regulations())
+}
+
+#[tauri::command]
+pub async fn regulatory_list_tasks(
+    status: Option<ReviewTaskStatus>,
+    monitor: tauri::State<'_, RegulatoryMonitorStorage>,
+) -> Result<Vec<ReviewTask>, String> {
+    Ok(monitor.list_tasks(status))
+}
+
+#[tauri::command]
+pub async fn regulatory_update_task(
+    task_id: String,
+    status: ReviewTaskStatus,
+    note: Option<String>,
+    monitor: tauri::State<'_, RegulatoryMonitorStorage>,
+) -> Result<ReviewTask, String> {
+    monitor.update_task(&task_id, status, note).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn regulation(text: &str) -> FetchedRegulation {
+        FetchedRegulation {
+            id: "eu-2016-679".to_string(),
+            citation: "Regulation (EU) 2016/679".to_string(),
+            aliases: vec!["GDPR".to_string()],
+            title: "General Data Protection Regulation".to_string(),
+            jurisdiction: "EU".to_string(),
+            text: text.to_string(),
+            source_url: None,
+        }
+    }
+
+    fn references(id: &str, text: &str) -> DocumentReferences {
+        DocumentReferences {
+            document_id: id.to_string(),
+            title: format!("{} policy", id),
+            document_type: DocumentType::Contract,
+            passages: citing_passages(text),
+            indexed_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_changed_provisions_open_tasks_for_citing_documents() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = ReferenceLog::new(dir.path());
+        log.append(&references(
+            "dpa",
+            "The Processor shall assist the Controller as required by Article 28 GDPR. Fees are due monthly.",
+        ))
+        .unwrap();
+        log.append(&references("retention", "Records are kept in line with Art. 5 GDPR; see also Article 28 of the DPA."))
+            .unwrap();
+        log.append(&references("privacy", "We process personal data in accordance with the GDPR."))
+            .unwrap();
+        log.append(&references("lease", "The Tenant shall pay rent under Section 28 of the Lease."))
+            .unwrap();
+
+        let monitor = RegulatoryMonitor::new(dir.path()).unwrap();
+        let v1 = "Preamble\nArticle 5\nPrinciples of processing.\nArticle 28\nProcessor duties.\nArticle 99\nEntry into force.";
+        let baseline = monitor.check(&regulation(v1)).unwrap();
+        assert!(baseline.baseline);
+        assert!(baseline.tasks.is_empty());
+
+        let v2 = "Preamble\nArticle 5\nPrinciples of processing.\nArticle 28\nProcessor duties, amended.\nArticle 28a\nSub-processors.";
+        let check = monitor.check(&regulation(v2)).unwrap();
+        assert!(!check.baseline);
+        assert_eq!(
+            check.changes,
+            vec![
+                ProvisionChange { provision: "28".to_string(), kind: ChangeKind::Amended },
+                ProvisionChange { provision: "28a".to_string(), kind: ChangeKind::Added },
+                ProvisionChange { provision: "99".to_string(), kind: ChangeKind::Repealed },
+            ]
+        );
+
+        let flagged: Vec<(&str, ReviewPriority, Vec<String>)> = check
+            .tasks
+            .iter()
+            .map(|t| (t.document_id.as_str(), t.priority, t.provisions.clone()))
+            .collect();
+        assert_eq!(
+            flagged,
+            vec![
+                ("dpa", ReviewPriority::High, vec!["28".to_string()]),
+                // cites Article 28 in the same sentence as the GDPR, even though it means the DPA's
+                ("retention", ReviewPriority::High, vec!["28".to_string()]),
+                ("privacy", ReviewPriority::Low, Vec::new()),
+            ]
+        );
+        assert_eq!(check.tasks[0].passages, vec!["The Processor shall assist the Controller as required by Article 28 GDPR.".to_string()]);
+        assert_eq!(monitor.list_tasks(Some(ReviewTaskStatus::Open)).len(), 3);
+    }
+
+    #[test]
+    fn test_open_tasks_are_updated_not_duplicated() {
+        let dir = tempfile::tempdir().unwrap();
+        ReferenceLog::new(dir.path())
+            .append(&references("dpa", "Sub-processing follows Article 28 GDPR and Article 32 GDPR."))
+            .unwrap();
+        let monitor = RegulatoryMonitor::new(dir.path()).unwrap();
+
+        monitor.check(&regulation("Article 28\nDuties.\nArticle 32\nSecurity.")).unwrap();
+        let first = monitor.check(&regulation("Article 28\nDuties, amended.\nArticle 32\nSecurity.")).unwrap();
+        assert_eq!(first.tasks[0].provisions, vec!["28".to_string()]);
+
+        let second = monitor
+            .check(&regulation("Article 28\nDuties, amended.\nArticle 32\nSecurity, amended."))
+            .unwrap();
+        assert_eq!(second.tasks[0].id, first.tasks[0].id);
+        assert_eq!(second.tasks[0].provisions, vec!["28".to_string(), "32".to_string()]);
+        assert_eq!(monitor.list_tasks(None).len(), 1);
+
+        let resolved = monitor
+            .update_task(&first.tasks[0].id, ReviewTaskStatus::Resolved, Some("DPA updated".to_string()))
+            .unwrap();
+        assert_eq!(resolved.status, ReviewTaskStatus::Resolved);
+        assert!(monitor.list_tasks(Some(ReviewTaskStatus::Open)).is_empty());
+
+        // No change since the last fetch: nothing new to review
+        let unchanged = monitor
+            .check(&regulation("Article 28\nDuties, amended.\nArticle 32\nSecurity, amended."))
+            .unwrap();
+        assert!(unchanged.changes.is_empty());
+        assert!(unchanged.tasks.is_empty());
+    }
+
+    #[test]
+    fn test_watched_regulations_fall_due_after_their_refresh_interval() {
+        let dir = tempfile::tempdir().unwrap();
+        let monitor = RegulatoryMonitor::new(dir.path()).unwrap();
+        let request = |url: &str, hours: Option<u32>| WatchRegulationRequest {
+            id: "eu-2016-679".to_string(),
+            citation: "Regulation (EU) 2016/679".to_string(),
+            aliases: vec!["GDPR".to_string(), " ".to_string()],
+            title: "General Data Protection Regulation".to_string(),
+            jurisdiction: "EU".to_string(),
+            source_url: url.to_string(),
+            refresh_hours: hours,
+        };
+
+        assert!(monitor.watch(request("http://eur-lex.europa.eu/eli/reg/2016/679/oj", None)).is_err());
+        assert!(monitor.watch(request("https://eur-lex.europa.eu/eli/reg/2016/679/oj", Some(0))).is_err());
+        let watched = monitor.watch(request("https://eur-lex.europa.eu/eli/reg/2016/679/oj", None)).unwrap();
+        assert_eq!(watched.refresh_hours, 24);
+        assert_eq!(watched.aliases, vec!["GDPR".to_string()]);
+
+        // Never fetched, so due at once; then due again a day after the last fetch
+        let now = Utc::now();
+        assert_eq!(monitor.due_regulations(now), vec!["eu-2016-679".to_string()]);
+        monitor.state.lock().unwrap().watched.get_mut("eu-2016-679").unwrap().last_fetched = Some(now);
+        assert!(monitor.due_regulations(now + chrono::Duration::hours(23)).is_empty());
+        assert_eq!(monitor.due_regulations(now + chrono::Duration::hours(24)).len(), 1);
+
+        // The watch survives a restart and can be dropped
+        let reopened = RegulatoryMonitor::new(dir.path()).unwrap();
+        assert_eq!(reopened.list_watched().len(), 1);
+        assert!(reopened.unwatch("eu-2016-679").unwrap());
+        assert!(reopened.list_watched().is_empty());
+    }
+}
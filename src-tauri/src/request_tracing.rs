@@ -0,0 +1,220 @@
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::Instant;
+use lazy_static::lazy_static;
+use uuid::Uuid;
+
+/// Inference request tracing for BEAR AI
+/// Records per-stage latency (queue wait, model load, prompt eval, decode, post-processing)
+/// under a trace ID shared by every component that handles the request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RequestTrace {
+    pub trace_id: String,
+    pub operation: String,
+    pub model: Option<String>,
+    pub started_at: String, // RFC 3339
+    pub total_ms: Option<u64>,
+    pub status: TraceStatus,
+    pub error: Option<String>,
+    pub stages: Vec<TraceStage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraceStage {
+    pub component: String, // "llm_manager" | "nemotron_rag" | "mcp_server"
+    pub stage: String,
+    pub offset_ms: u64, // start, relative to the trace start
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum TraceStatus {
+    Running,
+    Completed,
+    Failed,
+}
+
+/// Completed traces kept for inspection
+const MAX_TRACES: usize = 200;
+
+struct TraceEntry {
+    trace: RequestTrace,
+    started: Instant,
+}
+
+lazy_static! {
+    static ref TRACES: Mutex<VecDeque<TraceEntry>> = Mutex::new(VecDeque::new());
+}
+
+tokio::task_local! {
+    static CURRENT_TRACE: String;
+}
+
+/// The trace ID of the request being handled on this task, if any
+pub fn current_trace_id() -> Option<String> {
+    CURRENT_TRACE.try_with(|id| id.clone()).ok()
+}
+
+/// Run `fut` as part of a traced request. Nested calls (e.g. the MCP server calling into
+/// the LLM manager) join the caller's trace instead of starting a new one.
+pub async fn in_trace<F, T>(operation: &str, model: Option<&str>, fut: F) -> anyhow::Result<T>
+where
+    F: Future<Output = anyhow::Result<T>>,
+{
+    if current_trace_id().is_some() {
+        return fut.await;
+    }
+
+    let trace_id = start_trace(operation, model);
+    let result = CURRENT_TRACE.scope(trace_id.clone(), fut).await;
+    finish_trace(&trace_id, result.as_ref().err().map(|e| e.to_string()));
+    result
+}
+
+fn start_trace(operation: &str, model: Option<&str>) -> String {
+    let trace_id = Uuid::new_v4().to_string();
+    let mut traces = TRACES.lock().unwrap();
+    traces.push_back(TraceEntry {
+        trace: RequestTrace {
+            trace_id: trace_id.clone(),
+            operation: operation.to_string(),
+            model: model.map(|m| m.to_string()),
+            started_at: chrono::Utc::now().to_rfc3339(),
+            total_ms: None,
+            status: TraceStatus::Running,
+            error: None,
+            stages: Vec::new(),
+        },
+        started: Instant::now(),
+    });
+    if traces.len() > MAX_TRACES {
+        traces.pop_front();
+    }
+    trace_id
+}
+
+fn finish_trace(trace_id: &str, error: Option<String>) {
+    let mut traces = TRACES.lock().unwrap();
+    if let Some(entry) = traces.iter_mut().find(|e| e.trace.trace_id == trace_id) {
+        entry.trace.total_ms = Some(entry.started.elapsed().as_millis() as u64);
+        entry.trace.status = if error.is_some() { TraceStatus::Failed } else { TraceStatus::Completed };
+        entry.trace.error = error;
+    }
+}
+
+/// Record a stage that ended just now and took `duration_ms`.
+/// No-op outside a traced request.
+pub fn record_stage(component: &str, stage: &str, duration_ms: u64) {
+    push_stage(component, stage, duration_ms, 0);
+}
+
+/// Record the prompt evaluation and decode times reported by the model server,
+/// which ran back to back and finished just now
+pub fn record_model_timings(component: &str, prompt_eval_ms: u64, decode_ms: u64) {
+    push_stage(component, "prompt_eval", prompt_eval_ms, decode_ms);
+    push_stage(component, "decode", decode_ms, 0);
+}
+
+fn push_stage(component: &str, stage: &str, duration_ms: u64, ended_ms_ago: u64) {
+    let Some(trace_id) = current_trace_id() else {
+        return;
+    };
+
+    let mut traces = TRACES.lock().unwrap();
+    if let Some(entry) = traces.iter_mut().find(|e| e.trace.trace_id == trace_id) {
+        let elapsed_ms = entry.started.elapsed().as_millis() as u64;
+        entry.trace.stages.push(TraceStage {
+            component: component.to_string(),
+            stage: stage.to_string(),
+            offset_ms: elapsed_ms.saturating_sub(ended_ms_ago + duration_ms),
+            duration_ms,
+        });
+    }
+}
+
+/// Times a stage and records it when dropped, so early returns are still traced
+pub struct StageTimer {
+    component: &'static str,
+    stage: &'static str,
+    started: Instant,
+}
+
+impl StageTimer {
+    pub fn start(component: &'static str, stage: &'static str) -> Self {
+        Self {
+            component,
+            stage,
+            started: Instant::now(),
+        }
+    }
+}
+
+impl Drop for StageTimer {
+    fn drop(&mut self) {
+        record_stage(self.component, self.stage, self.started.elapsed().as_millis() as u64);
+    }
+}
+
+pub fn get_trace(trace_id: &str) -> Option<RequestTrace> {
+    TRACES
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|e| e.trace.trace_id == trace_id)
+        .map(|e| e.trace.clone())
+}
+
+/// Most recent traces first
+pub fn recent_traces(limit: usize) -> Vec<RequestTrace> {
+    TRACES
+        .lock()
+        .unwrap()
+        .iter()
+        .rev()
+        .take(limit)
+        .map(|e| e.trace.clone())
+        .collect()
+}
+
+#[tauri::command]
+pub async fn get_request_trace(trace_id: String) -> Result<RequestTrace, String> {
+    get_trace(&trace_id).ok_or_else(|| format!("Trace {} not found", trace_id))
+}
+
+#[tauri::command]
+pub async fn list_request_traces(limit: Option<usize>) -> Result<Vec<RequestTrace>, String> {
+    Ok(recent_traces(limit.unwrap_or(50)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_nested_calls_share_trace() {
+        let (outer, inner) = in_trace("agent_task", Some("phi3"), async {
+            let _timer = StageTimer::start("mcp_server", "post_processing");
+            let outer = current_trace_id();
+            let inner = in_trace("generate", None, async { Ok(current_trace_id()) }).await?;
+            record_model_timings("llm_manager", 3, 5);
+            Ok((outer, inner))
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(outer, inner);
+        let trace = get_trace(&outer.unwrap()).unwrap();
+        assert_eq!(trace.status, TraceStatus::Completed);
+        assert_eq!(trace.operation, "agent_task");
+        let stages: Vec<&str> = trace.stages.iter().map(|s| s.stage.as_str()).collect();
+        assert_eq!(stages, vec!["prompt_eval", "decode", "post_processing"]);
+    }
+
+    #[test]
+    fn test_record_stage_outside_trace_is_noop() {
+        record_model_timings("llm_manager", 3, 5);
+        assert!(current_trace_id().is_none());
+    }
+}
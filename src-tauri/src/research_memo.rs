@@ -0,0 +1,581 @@
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::ai_disclosure::{Disclosure, Disclosures};
+use crate::docx_writer::{self, DocxBlock};
+use crate::llm_manager::LLMManager;
+use crate::provenance::{self, ProvenanceClaim, ProvenanceSource};
+use crate::text_processing::elide_chars;
+
+/// Legal Research Memos for BEAR AI
+/// One command runs the whole research workflow: the question is researched by multi-hop
+/// reasoning over the RAG index, whose retrieval expands every query, and the model drafts an
+/// IRAC memo from the passages the hops found, numbered as sources. Every citation in the draft
+/// is checked against what was retrieved before the memo is written out as a DOCX with an
+/// outline and a citation table.
+pub const DEFAULT_MAX_HOPS: usize = 2;
+const MAX_SOURCES: usize = 20;
+const MAX_SOURCE_CHARS: usize = 1500;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResearchMemoRequest {
+    pub question: String,
+    pub jurisdiction: Option<String>,
+    pub max_hops: Option<usize>,
+    pub model: Option<String>, // defaults to the model the generation server has ready
+    #[serde(default)]
+    pub include_disclosure: Option<bool>, // None follows the disclosure settings
+    #[serde(default)]
+    pub provenance_sidecar: bool, // also write a provenance manifest next to the memo
+}
+
+/// A passage returned by the RAG index for one query
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetrievedPassage {
+    pub chunk_id: String,
+    pub document_id: String,
+    pub title: Option<String>,
+    pub content: String,
+    pub cited_authorities: Vec<String>,
+    pub verified_citations: Vec<String>, // authorities the retriever verified
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResearchSource {
+    pub label: String, // "S1", as cited in the memo
+    pub hop: usize,
+    pub query: String,
+    pub passage: RetrievedPassage,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResearchHop {
+    pub hop: usize,
+    pub queries: Vec<String>,
+    pub new_sources: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IracSection {
+    Issue,
+    Rule,
+    Application,
+    Conclusion,
+}
+
+impl IracSection {
+    const ALL: [IracSection; 4] = [
+        IracSection::Issue,
+        IracSection::Rule,
+        IracSection::Application,
+        IracSection::Conclusion,
+    ];
+
+    pub fn heading(&self) -> &'static str {
+        match self {
+            IracSection::Issue => "Issue",
+            IracSection::Rule => "Rule",
+            IracSection::Application => "Application",
+            IracSection::Conclusion => "Conclusion",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoSection {
+    pub section: IracSection,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutlineEntry {
+    pub heading: String,
+    pub points: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CitationStatus {
+    Verified,      // verified by the retriever
+    InSources,     // quoted by a retrieved passage but not verified
+    NotInSources,  // only the model cites it: check before relying on it
+    UnknownSource, // a [S#] marker with no such source
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CitationRow {
+    pub citation: String,
+    pub sources: Vec<String>, // labels of the sources it appears in
+    pub sections: Vec<IracSection>,
+    pub status: CitationStatus,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResearchMemo {
+    pub id: String,
+    pub question: String,
+    pub jurisdiction: Option<String>,
+    pub model: String,
+    pub hops: Vec<ResearchHop>,
+    pub sources: Vec<ResearchSource>,
+    pub sections: Vec<MemoSection>,
+    pub outline: Vec<OutlineEntry>,
+    pub citations: Vec<CitationRow>,
+    pub unverified_citations: usize,
+    pub prompts_sha256: String, // for the provenance manifest
+    pub path: String,
+    pub generated_at: String,
+}
+
+/// Number the passages of each hop's query as the memo's sources, each passage once
+pub fn collect_sources(hop_passages: Vec<(String, Vec<RetrievedPassage>)>) -> (Vec<ResearchHop>, Vec<ResearchSource>) {
+    let mut seen_chunks = HashSet::new();
+    let mut hops = Vec::new();
+    let mut sources: Vec<ResearchSource> = Vec::new();
+
+    for (index, (query, passages)) in hop_passages.into_iter().enumerate() {
+        let first_new = sources.len();
+        for passage in passages {
+            if sources.len() >= MAX_SOURCES || !seen_chunks.insert(passage.chunk_id.clone()) {
+                continue;
+            }
+            sources.push(ResearchSource {
+                label: format!("S{}", sources.len() + 1),
+                hop: index + 1,
+                query: query.clone(),
+                passage,
+            });
+        }
+        hops.push(ResearchHop {
+            hop: index + 1,
+            queries: vec![query],
+            new_sources: sources.len() - first_new,
+        });
+    }
+
+    (hops, sources)
+}
+
+pub fn memo_prompt(question: &str, jurisdiction: Option<&str>, sources: &[ResearchSource]) -> String {
+    let mut prompt = format!("Question: {}\n", question.trim());
+    if let Some(jurisdiction) = jurisdiction {
+        prompt.push_str(&format!("Jurisdiction: {}\n", jurisdiction));
+    }
+    prompt.push_str("\nSources:\n");
+    for source in sources {
+        prompt.push_str(&format!(
+            "[{}] {}\n{}\n\n",
+            source.label,
+            source.passage.title.as_deref().unwrap_or(&source.passage.document_id),
+            elide_chars(source.passage.content.trim(), MAX_SOURCE_CHARS)
+        ));
+    }
+    prompt.push_str(
+        "Write a research memo answering the question in IRAC form, with the headings Issue, Rule, \
+         Application and Conclusion on their own lines. Support every statement of law with the \
+         source it comes from, as [S1], and give case and statute citations exactly as they appear \
+         in the sources. If the sources do not answer part of the question, say so instead of \
+         relying on outside knowledge.",
+    );
+    prompt
+}
+
+/// Split the draft at its IRAC headings. A draft without them is kept whole as the analysis.
+pub fn parse_irac(question: &str, text: &str) -> Vec<MemoSection> {
+    let heading = Regex::new(
+        r"(?i)^\s*(?:#{1,6}\s*|\d+[.)]\s*|[IVX]+[.)]\s*)?\**\s*(issues?|questions? presented|rules?|applicable law|application|analysis|conclusions?)\b\s*\**\s*(:)?\s*\**\s*(.*)$",
+    )
+    .unwrap();
+
+    let mut texts: Vec<(IracSection, Vec<String>)> = Vec::new();
+    for line in text.lines() {
+        if let Some(caps) = heading.captures(line) {
+            let rest = caps[3].trim();
+            if rest.is_empty() || caps.get(2).is_some() {
+                let section = match caps[1].to_lowercase().chars().next() {
+                    Some('i') | Some('q') => IracSection::Issue,
+                    Some('r') => IracSection::Rule,
+                    Some('c') => IracSection::Conclusion,
+                    _ => IracSection::Application,
+                };
+                texts.push((section, vec![rest.to_string()]));
+                continue;
+            }
+        }
+        match texts.last_mut() {
+            Some((_, lines)) => lines.push(line.to_string()),
+            None => texts.push((IracSection::Application, vec![line.to_string()])),
+        }
+    }
+
+    IracSection::ALL
+        .iter()
+        .map(|section| {
+            let text = texts
+                .iter()
+                .filter(|(s, _)| s == section)
+                .map(|(_, lines)| lines.join("\n").trim().to_string())
+                .filter(|t| !t.is_empty())
+                .collect::<Vec<_>>()
+                .join("\n\n");
+            let text = match section {
+                IracSection::Issue if text.is_empty() => question.trim().to_string(),
+                _ if text.is_empty() => "Not addressed in the draft.".to_string(),
+                _ => text,
+            };
+            MemoSection { section: *section, text }
+        })
+        .collect()
+}
+
+/// One point per paragraph: its first sentence
+pub fn build_outline(sections: &[MemoSection]) -> Vec<OutlineEntry> {
+    sections
+        .iter()
+        .map(|section| OutlineEntry {
+            heading: section.section.heading().to_string(),
+            points: section
+                .text
+                .split("\n\n")
+                .map(str::trim)
+                .filter(|p| !p.is_empty())
+                .map(|p| {
+                    let end = p.find(". ").map(|i| i + 1).unwrap_or(p.len());
+                    elide_chars(p[..end].trim_start_matches(['-', '*', '•', ' ']), 160)
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+/// Case, statute and ECLI citation formats recognised in drafts
+pub fn authority_patterns() -> Vec<Regex> {
+    [
+        r"ECLI:[A-Z]{2}:[A-Z0-9]+:\d{4}:[A-Z0-9.]*[A-Z0-9]",
+        r"\bCase\s+[CT]-\d+/\d+",
+        r"\[\d{4}\]\s+[A-Z][A-Za-z]*\s+(?:[A-Z][a-z]*\s+)?\d+",
+        r"\b\d+\s+(?:U\.S\.|S\.\s?Ct\.|F\.\s?(?:2d|3d|4th)|F\.\s?Supp\.(?:\s?[23]d)?)\s+\d+",
+        r"\b\d+\s+U\.S\.C\.?\s*§+\s*\d+[a-z]?",
+    ]
+    .iter()
+    .map(|p| Regex::new(p).unwrap())
+    .collect()
+}
+
+/// Citation compared without case or spacing differences
+pub fn normalize_citation(citation: &str) -> String {
+    citation.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+/// Check every authority and source marker in the memo against the retrieved sources
+pub fn citation_table(sections: &[MemoSection], sources: &[ResearchSource]) -> Vec<CitationRow> {
+    let mut rows: Vec<CitationRow> = Vec::new();
+    let mut add = |citation: String, section: IracSection, status: CitationStatus, labels: Vec<String>| {
+        match rows.iter_mut().find(|r| normalize_citation(&r.citation) == normalize_citation(&citation)) {
+            Some(row) if !row.sections.contains(&section) => row.sections.push(section),
+            Some(_) => {}
+            None => rows.push(CitationRow { citation, sources: labels, sections: vec![section], status }),
+        }
+    };
+
+    let marker = Regex::new(r"\[S(\d+)\]").unwrap();
+    let patterns = authority_patterns();
+    for section in sections {
+        for caps in marker.captures_iter(&section.text) {
+            let index: usize = caps[1].parse().unwrap_or(0);
+            if index == 0 || index > sources.len() {
+                add(caps[0].to_string(), section.section, CitationStatus::UnknownSource, Vec::new());
+            }
+        }
+
+        for pattern in &patterns {
+            for found in pattern.find_iter(&section.text) {
+                let citation = found.as_str().to_string();
+                let key = normalize_citation(&citation);
+                let verified = sources.iter().any(|s| s.passage.verified_citations.iter().any(|c| normalize_citation(c) == key));
+                let labels: Vec<String> = sources
+                    .iter()
+                    .filter(|s| {
+                        normalize_citation(&s.passage.content).contains(&key)
+                            || s.passage.cited_authorities.iter().any(|c| normalize_citation(c) == key)
+                    })
+                    .map(|s| s.label.clone())
+                    .collect();
+                let status = if verified {
+                    CitationStatus::Verified
+                } else if !labels.is_empty() {
+                    CitationStatus::InSources
+                } else {
+                    CitationStatus::NotInSources
+                };
+                add(citation, section.section, status, labels);
+            }
+        }
+    }
+    rows
+}
+
+fn status_label(status: CitationStatus) -> &'static str {
+    match status {
+        CitationStatus::Verified => "Verified",
+        CitationStatus::InSources => "In sources, not verified",
+        CitationStatus::NotInSources => "Not in sources: check before relying",
+        CitationStatus::UnknownSource => "No such source",
+    }
+}
+
+fn render_blocks(memo: &ResearchMemo, generated_on: &str, disclosure: Option<&Disclosure>) -> Vec<DocxBlock> {
+    let mut blocks = vec![
+        DocxBlock::Title("Research Memorandum".to_string()),
+        DocxBlock::Paragraph(format!(
+            "Question: {}\nJurisdiction: {}\nDate: {}",
+            memo.question,
+            memo.jurisdiction.as_deref().unwrap_or("Not specified"),
+            generated_on
+        )),
+        DocxBlock::Heading(1, "Outline".to_string()),
+    ];
+    for entry in &memo.outline {
+        blocks.push(DocxBlock::Heading(2, entry.heading.clone()));
+        for point in &entry.points {
+            blocks.push(DocxBlock::Bullet(point.clone()));
+        }
+    }
+
+    for section in &memo.sections {
+        blocks.push(DocxBlock::Heading(1, section.section.heading().to_string()));
+        for paragraph in section.text.split("\n\n").map(str::trim).filter(|p| !p.is_empty()) {
+            blocks.push(DocxBlock::Paragraph(paragraph.to_string()));
+        }
+    }
+
+    blocks.push(DocxBlock::Heading(1, "Citation table".to_string()));
+    if memo.citations.is_empty() {
+        blocks.push(DocxBlock::Paragraph("The memo cites no case law or statutes directly.".to_string()));
+    } else {
+        blocks.push(DocxBlock::Table {
+            header: ["Citation", "Cited in", "Sources", "Status"].iter().map(|h| h.to_string()).collect(),
+            rows: memo
+                .citations
+                .iter()
+                .map(|row| {
+                    vec![
+                        row.citation.clone(),
+                        row.sections.iter().map(|s| s.heading()).collect::<Vec<_>>().join(", "),
+                        row.sources.join(", "),
+                        status_label(row.status).to_string(),
+                    ]
+                })
+                .collect(),
+        });
+    }
+    if memo.unverified_citations > 0 {
+        blocks.push(DocxBlock::Paragraph(format!(
+            "{} citation(s) could not be matched to a retrieved source and must be checked before the memo is relied on.",
+            memo.unverified_citations
+        )));
+    }
+
+    blocks.push(DocxBlock::Heading(1, "Sources".to_string()));
+    blocks.push(DocxBlock::Table {
+        header: ["Ref", "Document", "Found by"].iter().map(|h| h.to_string()).collect(),
+        rows: memo
+            .sources
+            .iter()
+            .map(|s| {
+                vec![
+                    s.label.clone(),
+                    s.passage.title.clone().unwrap_or_else(|| s.passage.document_id.clone()),
+                    format!("Hop {}: {}", s.hop, s.query),
+                ]
+            })
+            .collect(),
+    });
+    if let Some(disclosure) = disclosure {
+        blocks.extend(disclosure.to_blocks());
+    }
+    blocks
+}
+
+/// Run the research workflow and write the memo. `reason` runs multi-hop reasoning over the RAG
+/// index for the question, with a number of hops and a model, and gives back each hop's query
+/// with the passages it retrieved.
+pub async fn run_research_memo<F, Fut>(
+    request: &ResearchMemoRequest,
+    llm: &LLMManager,
+    memos_dir: &Path,
+    disclosures: &Disclosures,
+    reason: F,
+) -> Result<ResearchMemo>
+where
+    F: FnOnce(String, usize, String) -> Fut,
+    Fut: Future<Output = Result<Vec<(String, Vec<RetrievedPassage>)>>>,
+{
+    let question = request.question.trim();
+    if question.is_empty() {
+        return Err(anyhow!("A research question is required"));
+    }
+    let model = match &request.model {
+        Some(model) => model.clone(),
+        None => llm
+            .default_generation_model()
+            .await
+            .ok_or_else(|| anyhow!("No model is available to draft the memo"))?,
+    };
+    let system = "You are a careful legal research assistant. You never invent authorities.";
+
+    let hop_passages = reason(question.to_string(), request.max_hops.unwrap_or(DEFAULT_MAX_HOPS), model.clone()).await?;
+    let (hops, sources) = collect_sources(hop_passages);
+    if sources.is_empty() {
+        return Err(anyhow!("Nothing in the document index addresses this question"));
+    }
+
+    let draft_prompt = memo_prompt(question, request.jurisdiction.as_deref(), &sources);
+    let draft = llm.complete(&model, system, draft_prompt.clone(), 2048, 0.2).await?;
+    let sections = parse_irac(question, &draft);
+    let outline = build_outline(&sections);
+    let citations = citation_table(&sections, &sources);
+    let unverified_citations = citations
+        .iter()
+        .filter(|c| matches!(c.status, CitationStatus::NotInSources | CitationStatus::UnknownSource))
+        .count();
+
+    let id = Uuid::new_v4().to_string();
+    let path: PathBuf = memos_dir.join(format!("research_memo_{}.docx", id));
+    let now = Utc::now();
+    let memo = ResearchMemo {
+        id,
+        question: question.to_string(),
+        jurisdiction: request.jurisdiction.clone(),
+        model,
+        hops,
+        sources,
+        sections,
+        outline,
+        citations,
+        unverified_citations,
+        prompts_sha256: provenance::prompts_sha256(&[system.to_string(), draft_prompt]),
+        path: path.to_string_lossy().to_string(),
+        generated_at: now.to_rfc3339(),
+    };
+    let disclosure = disclosures.resolve(memo.jurisdiction.as_deref(), Some(&memo.model), request.include_disclosure);
+    docx_writer::write_docx(&path, &render_blocks(&memo, &now.format("%Y-%m-%d").to_string(), disclosure.as_ref()))?;
+    Ok(memo)
+}
+
+impl ResearchMemo {
+    /// What the memo's provenance manifest records
+    pub fn provenance_claim(&self) -> ProvenanceClaim {
+        ProvenanceClaim {
+            title: format!("Research memo: {}", self.question),
+            work_product: "research_memo".to_string(),
+            model: self.model.clone(),
+            prompts_sha256: self.prompts_sha256.clone(),
+            sources: self
+                .sources
+                .iter()
+                .map(|s| {
+                    let p = &s.passage;
+                    ProvenanceSource::new(&p.document_id, p.title.as_deref(), Some(&p.chunk_id), &p.content)
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Where generated research memos are written
+pub struct ResearchMemos {
+    pub dir: PathBuf,
+}
+
+impl ResearchMemos {
+    pub fn new(app_data_dir: &Path) -> Self {
+        ResearchMemos {
+            dir: app_data_dir.join("research_memos"),
+        }
+    }
+}
+
+pub type ResearchMemoStorage = Arc<ResearchMemos>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn passage(chunk_id: &str, content: &str, cites: &[&str]) -> RetrievedPassage {
+        RetrievedPassage {
+            chunk_id: chunk_id.to_string(),
+            document_id: format!("doc-{}", chunk_id),
+            title: None,
+            content: content.to_string(),
+            cited_authorities: cites.iter().map(|c| c.to_string()).collect(),
+            verified_citations: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_sources_are_numbered_across_hops() {
+        let (hops, sources) = collect_sources(vec![
+            (
+                "Is a liability cap enforceable?".to_string(),
+                vec![passage("a", "Caps are upheld between businesses.", &["[2001] EWCA Civ 317"]), passage("b", "Section 11 test.", &[])],
+            ),
+            (
+                "[2001] EWCA Civ 317".to_string(),
+                vec![passage("a", "duplicate", &[]), passage("c", "Watford Electronics v Sanderson.", &[])],
+            ),
+        ]);
+
+        assert_eq!(hops.len(), 2);
+        assert_eq!(hops[0].new_sources, 2);
+        assert_eq!(hops[1].queries, vec!["[2001] EWCA Civ 317"]);
+        assert_eq!(hops[1].new_sources, 1);
+        let labels: Vec<(&str, usize)> = sources.iter().map(|s| (s.label.as_str(), s.hop)).collect();
+        assert_eq!(labels, vec![("S1", 1), ("S2", 1), ("S3", 2)]);
+        assert_eq!(sources[2].passage.content, "Watford Electronics v Sanderson.");
+    }
+
+    #[test]
+    fn test_irac_memo_with_checked_citations() {
+        let draft = "## Issue\nWhether the cap binds the supplier.\n\n**Rule:** A cap must be reasonable [S1], see [2001] EWCA Civ 317.\n\nApplication\nThe parties negotiated at arm's length [S2]. Compare 550 U.S. 544.\n\nConclusion: The cap is likely enforceable [S7].";
+        let sections = parse_irac("Is the cap enforceable?", draft);
+        assert_eq!(sections[0].text, "Whether the cap binds the supplier.");
+        assert!(sections[1].text.starts_with("A cap must be reasonable"));
+        assert!(sections[2].text.starts_with("The parties negotiated"));
+        assert_eq!(sections[3].section, IracSection::Conclusion);
+
+        let outline = build_outline(&sections);
+        assert_eq!(outline[2].points, vec!["The parties negotiated at arm's length [S2]."]);
+
+        let mut verified = passage("a", "Reasonableness was considered in [2001] EWCA Civ 317.", &["[2001] EWCA Civ 317"]);
+        verified.verified_citations = vec!["[2001] EWCA Civ 317".to_string()];
+        let sources = vec![
+            ResearchSource { label: "S1".to_string(), hop: 1, query: "q".to_string(), passage: verified },
+            ResearchSource { label: "S2".to_string(), hop: 1, query: "q".to_string(), passage: passage("b", "Negotiated terms.", &[]) },
+        ];
+        let table = citation_table(&sections, &sources);
+        let statuses: Vec<(&str, CitationStatus)> = table.iter().map(|r| (r.citation.as_str(), r.status)).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                ("[2001] EWCA Civ 317", CitationStatus::Verified),
+                ("550 U.S. 544", CitationStatus::NotInSources),
+                ("[S7]", CitationStatus::UnknownSource),
+            ]
+        );
+        assert_eq!(table[0].sources, vec!["S1"]);
+
+        // An unstructured draft is kept as the analysis
+        let sections = parse_irac("Is the cap enforceable?", "The cap is probably fine.");
+        assert_eq!(sections[0].text, "Is the cap enforceable?");
+        assert_eq!(sections[2].text, "The cap is probably fine.");
+    }
+}
@@ -0,0 +1,105 @@
+use anyhow::Result;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use crate::document_acl::{DocumentAcl, DocumentAclStorage};
+use crate::enterprise_management::{BarrierBlock, BarrierStorage, InformationBarriers};
+use crate::local_api::{authenticated_user, SessionStorage};
+use crate::nemotron_rag::{collapse_duplicates, RAGChunk, RetrievalResult};
+use crate::security::SecurityManager;
+
+/// Retrieval Scope for BEAR AI
+/// Who may see which indexed documents: the reader's document access lists and the information
+/// barriers. Everything that hands out indexed content admits documents through a scope, from the
+/// desktop commands (scoped to the calling session) to the CLI and the gRPC server (scoped to the
+/// user they run as). Chunks filed under a matter carry its id in their metadata.
+pub type ScreenedDocuments = Mutex<BTreeMap<String, BarrierBlock>>;
+type SecurityStorage = Arc<Mutex<SecurityManager>>;
+
+#[derive(Clone)]
+pub struct RetrievalScope {
+    pub user: String,
+    acl: DocumentAclStorage,
+    barriers: BarrierStorage,
+    security: SecurityStorage,
+}
+
+impl RetrievalScope {
+    /// Scope of `user`; an empty user only sees documents open to everyone
+    pub fn new(user: &str, acl: &DocumentAclStorage, barriers: &BarrierStorage, security: &SecurityStorage) -> Self {
+        Self {
+            user: user.trim().to_string(),
+            acl: acl.clone(),
+            barriers: barriers.clone(),
+            security: security.clone(),
+        }
+    }
+
+    /// Scope of the user the session signed in as; fails without a valid session
+    pub fn for_session(
+        session_id: &str,
+        sessions: &SessionStorage,
+        acl: &DocumentAclStorage,
+        barriers: &BarrierStorage,
+        security: &SecurityStorage,
+    ) -> Result<Self, String> {
+        let user = authenticated_user(session_id, sessions)?;
+        Ok(Self::new(&user, acl, barriers, security))
+    }
+
+    /// Scope of `user` over the access lists and barriers kept in an app data directory, for
+    /// the headless entry points that run outside the desktop app
+    pub fn open(app_data_dir: &Path, user: &str) -> Result<Self> {
+        Ok(Self::new(
+            user,
+            &Arc::new(DocumentAcl::new(app_data_dir)?),
+            &Arc::new(InformationBarriers::new(app_data_dir)?),
+            &Arc::new(Mutex::new(SecurityManager::new(app_data_dir)?)),
+        ))
+    }
+
+    /// Whether a document may be used; documents held back by a barrier are noted in `screened`.
+    /// Without a matter from the caller, the one the document's access list files it under counts.
+    pub fn admit_document(&self, document_id: &str, matter_id: Option<&str>, screened: &ScreenedDocuments) -> bool {
+        let listed_matter = matter_id.is_none().then(|| self.acl.matter_of(document_id)).flatten();
+        let matter_id = matter_id.or(listed_matter.as_deref());
+        if let Some(block) = self.barriers.screen(&self.user, document_id, matter_id) {
+            screened.lock().unwrap().insert(document_id.to_string(), block);
+            return false;
+        }
+        self.acl.can_read_indexed(&self.user, document_id, matter_id)
+    }
+
+    /// Whether a chunk may be used
+    pub fn admit(&self, chunk: &RAGChunk, screened: &ScreenedDocuments) -> bool {
+        let matter_id = chunk.metadata.get("matter_id").map(String::as_str);
+        self.admit_document(&chunk.document_id, matter_id, screened)
+    }
+
+    /// One audit entry per document a barrier held back, however many of its chunks matched
+    pub fn audit(&self, screened: ScreenedDocuments, attempt: &str) {
+        for (document_id, block) in screened.into_inner().unwrap() {
+            self.barriers.record_blocked(&self.security, &self.user, &document_id, &block, attempt);
+        }
+    }
+
+    /// Drop chunks the user may not see, copies of documents kept in a newer version, and
+    /// documents left without chunks
+    pub fn filter(&self, result: RetrievalResult, attempt: &str) -> RetrievalResult {
+        self.filter_top(result, attempt, usize::MAX)
+    }
+
+    /// `filter`, keeping at most the `max_results` best chunks that remain; callers over-fetch
+    /// so that screened hits do not leave the user short
+    pub fn filter_top(&self, mut result: RetrievalResult, attempt: &str, max_results: usize) -> RetrievalResult {
+        let screened = ScreenedDocuments::default();
+        result.chunks.retain(|chunk| self.admit(chunk, &screened));
+        self.audit(screened, attempt);
+        collapse_duplicates(&mut result.chunks);
+        result.chunks.truncate(max_results);
+        let chunks = &result.chunks;
+        result.documents.retain(|d| chunks.iter().any(|c| c.document_id == d.id));
+        result
+    }
+}
@@ -0,0 +1,585 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeSet, HashMap};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+use crate::automation_api::AutomationStorage;
+use crate::enterprise_management::EnterpriseManager;
+use crate::local_api::{authenticated_user, SessionStorage};
+use crate::security::{ActionOutcome, SecurityAction, SecurityManager};
+
+/// Supervisory Review for BEAR AI
+/// Drafts, memos and analyses the AI generates enter a queue as pending attorney review, and
+/// cannot be exported or delivered until a reviewer approves them. An approval covers the
+/// content as it was reviewed (the SHA-256 of the file, or of the analysis with the rule pack
+/// and model behind it): if the content changes afterwards the item goes back to pending, and
+/// content the queue never approved is not released at all. Every submission and decision is
+/// appended to a hash-chained log, so the review history cannot be edited without it showing.
+const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkProductKind {
+    Draft,
+    Memo,
+    Analysis,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewStatus {
+    Pending,
+    Approved,
+    Rejected,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewItem {
+    pub id: String,
+    pub kind: WorkProductKind,
+    pub title: String,
+    pub source: String,    // the feature that generated it, e.g. "research_memo"
+    pub reference: String, // memo, set or outline id; the document path for analyses
+    pub path: Option<String>,
+    pub matter_id: Option<String>,
+    pub content_sha256: String,
+    pub status: ReviewStatus,
+    pub submitted_at: DateTime<Utc>,
+    pub submitted_by: String,
+    pub decided_at: Option<DateTime<Utc>>,
+    pub decided_by: Option<String>,
+    pub comment: Option<String>,
+    #[serde(default)]
+    pub exported_sha256: Vec<String>, // files written from the content once it was approved
+}
+
+/// What a feature hands the queue when it generates something
+#[derive(Debug, Clone)]
+pub struct Submission {
+    pub kind: WorkProductKind,
+    pub title: String,
+    pub source: String,
+    pub reference: String,
+    pub path: Option<String>,
+    pub matter_id: Option<String>,
+    pub content_sha256: String,
+}
+
+impl Submission {
+    /// A generated file, hashed as it was written
+    pub fn for_file(
+        kind: WorkProductKind,
+        title: String,
+        source: &str,
+        reference: &str,
+        path: &str,
+        matter_id: Option<String>,
+    ) -> Result<Self> {
+        Ok(Self {
+            kind,
+            title,
+            source: source.to_string(),
+            reference: reference.to_string(),
+            path: Some(path.to_string()),
+            matter_id,
+            content_sha256: file_sha256(Path::new(path))?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewEventKind {
+    Submitted,
+    Resubmitted, // the content changed, so any earlier decision no longer applies
+    Approved,
+    Rejected,
+}
+
+/// One line of the review log; `hash` covers the event and the hash before it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewEvent {
+    pub sequence: u64,
+    pub item_id: String,
+    pub event: ReviewEventKind,
+    pub user: String,
+    pub content_sha256: String,
+    pub comment: Option<String>,
+    pub timestamp: DateTime<Utc>,
+    pub previous_hash: String,
+    pub hash: String,
+}
+
+impl ReviewEvent {
+    fn compute_hash(&self) -> String {
+        let line = format!(
+            "{}|{}|{:?}|{}|{}|{}|{}|{}",
+            self.sequence,
+            self.item_id,
+            self.event,
+            self.user,
+            self.content_sha256,
+            self.comment.as_deref().unwrap_or(""),
+            self.timestamp.to_rfc3339(),
+            self.previous_hash
+        );
+        hex::encode(Sha256::digest(line.as_bytes()))
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReviewSettings {
+    pub reviewers: BTreeSet<String>, // user names allowed to approve or reject
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct QueueState {
+    settings: ReviewSettings,
+    items: Vec<ReviewItem>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogVerification {
+    pub events: u64,
+    pub intact: bool,
+    pub first_broken_sequence: Option<u64>,
+}
+
+pub fn sha256_hex(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+/// Hash of a generated file, or of the document an analysis was made from
+// Object keys in a stable order; serde_json keeps the order of the HashMaps it was built from
+fn sorted_keys(value: &serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::Object(map) => {
+            let mut entries: Vec<(&String, serde_json::Value)> = map.iter().map(|(k, v)| (k, sorted_keys(v))).collect();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+            serde_json::Value::Object(entries.into_iter().map(|(k, v)| (k.clone(), v)).collect())
+        }
+        serde_json::Value::Array(items) => serde_json::Value::Array(items.iter().map(sorted_keys).collect()),
+        other => other.clone(),
+    }
+}
+
+/// SHA-256 of generated content held as JSON, independent of the order its maps were built in
+pub fn json_sha256(value: &serde_json::Value) -> String {
+    sha256_hex(sorted_keys(value).to_string().as_bytes())
+}
+
+pub fn file_sha256(path: &Path) -> Result<String> {
+    Ok(sha256_hex(&fs::read(path)?))
+}
+
+/// Check a chain of events; returns the sequence number of the first event that does not
+/// follow from the one before it
+pub fn verify_chain(events: &[ReviewEvent]) -> Option<u64> {
+    let mut previous = GENESIS_HASH.to_string();
+    for event in events {
+        if event.previous_hash != previous || event.compute_hash() != event.hash {
+            return Some(event.sequence);
+        }
+        previous = event.hash.clone();
+    }
+    None
+}
+
+pub struct ReviewQueue {
+    path: PathBuf,
+    log_path: PathBuf,
+    state: Mutex<QueueState>,
+    tail: Mutex<(u64, String)>, // next sequence number and the hash of the last event
+}
+
+impl ReviewQueue {
+    pub fn new(app_data_dir: &Path) -> Result<Self> {
+        let path = app_data_dir.join("review_queue.json");
+        let log_path = app_data_dir.join("review_log.jsonl");
+        let state = if path.exists() {
+            serde_json::from_str(&fs::read_to_string(&path)?)?
+        } else {
+            QueueState::default()
+        };
+        let tail = match read_log(&log_path)?.last() {
+            Some(last) => (last.sequence + 1, last.hash.clone()),
+            None => (0, GENESIS_HASH.to_string()),
+        };
+        Ok(Self {
+            path,
+            log_path,
+            state: Mutex::new(state),
+            tail: Mutex::new(tail),
+        })
+    }
+
+    fn persist(&self, state: &QueueState) -> Result<()> {
+        fs::write(&self.path, serde_json::to_string_pretty(state)?)?;
+        Ok(())
+    }
+
+    fn append_event(&self, item: &ReviewItem, event: ReviewEventKind, user: &str) -> Result<()> {
+        let mut tail = self.tail.lock().unwrap();
+        let mut entry = ReviewEvent {
+            sequence: tail.0,
+            item_id: item.id.clone(),
+            event,
+            user: user.to_string(),
+            content_sha256: item.content_sha256.clone(),
+            comment: item.comment.clone().filter(|_| event != ReviewEventKind::Submitted),
+            timestamp: Utc::now(),
+            previous_hash: tail.1.clone(),
+            hash: String::new(),
+        };
+        entry.hash = entry.compute_hash();
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.log_path)?;
+        writeln!(file, "{}", serde_json::to_string(&entry)?)?;
+        *tail = (entry.sequence + 1, entry.hash);
+        Ok(())
+    }
+
+    pub fn settings(&self) -> ReviewSettings {
+        self.state.lock().unwrap().settings.clone()
+    }
+
+    /// Replace the reviewer list; callers check that the user is an administrator
+    pub fn set_settings(&self, settings: ReviewSettings) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        state.settings = settings;
+        self.persist(&state)
+    }
+
+    /// Queue generated work product as pending review. Submitting the same reference again
+    /// with unchanged content keeps its decision; changed content sends it back to pending.
+    pub fn submit(&self, submission: Submission, user: &str) -> Result<ReviewItem> {
+        let mut state = self.state.lock().unwrap();
+        let now = Utc::now();
+        let existing = state
+            .items
+            .iter_mut()
+            .find(|i| i.reference == submission.reference && i.kind == submission.kind);
+        let (item, event) = match existing {
+            Some(item) if item.content_sha256 == submission.content_sha256 => return Ok(item.clone()),
+            Some(item) => {
+                item.title = submission.title;
+                item.path = submission.path;
+                item.content_sha256 = submission.content_sha256;
+                item.status = ReviewStatus::Pending;
+                item.submitted_at = now;
+                item.submitted_by = user.to_string();
+                item.decided_at = None;
+                item.decided_by = None;
+                item.comment = None;
+                item.exported_sha256.clear();
+                (item.clone(), ReviewEventKind::Resubmitted)
+            }
+            None => {
+                let item = ReviewItem {
+                    id: Uuid::new_v4().to_string(),
+                    kind: submission.kind,
+                    title: submission.title,
+                    source: submission.source,
+                    reference: submission.reference,
+                    path: submission.path,
+                    matter_id: submission.matter_id,
+                    content_sha256: submission.content_sha256,
+                    status: ReviewStatus::Pending,
+                    submitted_at: now,
+                    submitted_by: user.to_string(),
+                    decided_at: None,
+                    decided_by: None,
+                    comment: None,
+                    exported_sha256: Vec::new(),
+                };
+                state.items.push(item.clone());
+                (item, ReviewEventKind::Submitted)
+            }
+        };
+        self.persist(&state)?;
+        self.append_event(&item, event, user)?;
+        Ok(item)
+    }
+
+    /// Approve or reject a pending item; the caller must be on the reviewer list
+    pub fn decide(&self, item_id: &str, approve: bool, comment: Option<String>, user: &str) -> Result<ReviewItem> {
+        let mut state = self.state.lock().unwrap();
+        if !state.settings.reviewers.contains(user) {
+            return Err(anyhow!("{} is not a reviewer", if user.is_empty() { "Nobody signed in" } else { user }));
+        }
+        let item = state
+            .items
+            .iter_mut()
+            .find(|i| i.id == item_id)
+            .ok_or_else(|| anyhow!("Review item not found: {}", item_id))?;
+        if item.status != ReviewStatus::Pending {
+            return Err(anyhow!("{} has already been decided", item.title));
+        }
+        item.status = if approve { ReviewStatus::Approved } else { ReviewStatus::Rejected };
+        item.decided_at = Some(Utc::now());
+        item.decided_by = Some(user.to_string());
+        item.comment = comment.filter(|c| !c.trim().is_empty());
+        let item = item.clone();
+        self.persist(&state)?;
+        let event = if approve { ReviewEventKind::Approved } else { ReviewEventKind::Rejected };
+        self.append_event(&item, event, user)?;
+        Ok(item)
+    }
+
+    pub fn list(&self, status: Option<ReviewStatus>) -> Vec<ReviewItem> {
+        let state = self.state.lock().unwrap();
+        let mut items: Vec<ReviewItem> = state
+            .items
+            .iter()
+            .filter(|i| status.is_none() || status == Some(i.status))
+            .cloned()
+            .collect();
+        items.sort_by_key(|i| std::cmp::Reverse(i.submitted_at));
+        items
+    }
+
+    pub fn history(&self, item_id: &str) -> Result<Vec<ReviewEvent>> {
+        Ok(read_log(&self.log_path)?.into_iter().filter(|e| e.item_id == item_id).collect())
+    }
+
+    pub fn verify_log(&self) -> Result<LogVerification> {
+        let events = read_log(&self.log_path)?;
+        let broken = verify_chain(&events);
+        Ok(LogVerification {
+            events: events.len() as u64,
+            intact: broken.is_none(),
+            first_broken_sequence: broken,
+        })
+    }
+
+    /// Note a file written from approved content, such as a DOCX rendered from an approved set,
+    /// so the file is released with the content it was written from
+    pub fn record_export(&self, reference: &str, kind: WorkProductKind, content_sha256: &str, file: &Path) -> Result<()> {
+        let file_sha256 = file_sha256(file)?;
+        let mut state = self.state.lock().unwrap();
+        let item = state
+            .items
+            .iter_mut()
+            .find(|i| i.kind == kind && i.status == ReviewStatus::Approved && i.content_sha256 == content_sha256)
+            .ok_or_else(|| anyhow!("{} has not been approved in attorney review", reference))?;
+        if !item.exported_sha256.contains(&file_sha256) {
+            item.exported_sha256.push(file_sha256);
+        }
+        self.persist(&state)
+    }
+
+    /// Export gate: content is released once a reviewer approved it, of `kind`, exactly as it
+    /// now reads (or it is a file written from approved content), wherever it was approved
+    /// from, so a copy or a renamed file stays released and an edited one does not. Content
+    /// the queue has not approved is held back.
+    pub fn check_released(&self, reference: &str, kind: Option<WorkProductKind>, current_sha256: &str) -> Result<(), String> {
+        let state = self.state.lock().unwrap();
+        let of_kind = |i: &&ReviewItem| kind.is_none() || kind == Some(i.kind);
+        if state
+            .items
+            .iter()
+            .filter(of_kind)
+            .any(|i| {
+                i.status == ReviewStatus::Approved
+                    && (i.content_sha256 == current_sha256 || i.exported_sha256.iter().any(|sha| sha == current_sha256))
+            })
+        {
+            return Ok(());
+        }
+        let known = state
+            .items
+            .iter()
+            .filter(of_kind)
+            .find(|i| i.reference == reference || i.path.as_deref() == Some(reference));
+        match known {
+            Some(item) => match item.status {
+                ReviewStatus::Approved => Err(format!("{} changed after it was approved and needs review again", item.title)),
+                ReviewStatus::Pending => Err(format!("{} is pending attorney review", item.title)),
+                ReviewStatus::Rejected => Err(format!("{} was rejected in review", item.title)),
+            },
+            None => Err(format!("{} has not been approved in attorney review", reference)),
+        }
+    }
+}
+
+fn read_log(path: &Path) -> Result<Vec<ReviewEvent>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    fs::read_to_string(path)?
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| serde_json::from_str(line).map_err(|e| anyhow!("Corrupt review log entry: {}", e)))
+        .collect()
+}
+
+pub type ReviewQueueStorage = Arc<ReviewQueue>;
+type SecurityStorage = Arc<Mutex<SecurityManager>>;
+
+/// Queue generated work product from a feature, submitted by `user`. Failing to queue fails the
+/// feature: content the queue never saw would not be held back at export.
+pub(crate) fn submit_generated(queue: &ReviewQueue, user: &str, submission: Result<Submission>) -> Result<ReviewItem, String> {
+    submission
+        .and_then(|submission| queue.submit(submission, user))
+        .map_err(|e| format!("Failed to queue for attorney review: {}", e))
+}
+
+/// Export gate for work product generated at export time, such as a chat transcript: released
+/// when a reviewer approved it as it now reads; otherwise it is queued for review and refused
+pub(crate) fn release_or_submit(queue: &ReviewQueue, user: &str, submission: Submission) -> Result<(), String> {
+    if queue
+        .check_released(&submission.reference, Some(submission.kind), &submission.content_sha256)
+        .is_ok()
+    {
+        return Ok(());
+    }
+    let item = submit_generated(queue, user, Ok(submission))?;
+    match item.status {
+        ReviewStatus::Rejected => Err(format!("{} was rejected in review", item.title)),
+        _ => Err(format!("{} is pending attorney review; export it again once it is approved", item.title)),
+    }
+}
+
+/// Audit an export or delivery refused because its content has not been released
+pub(crate) fn audit_withheld(security: &SecurityStorage, resource: &str, reason: &str) {
+    let details = HashMap::from([("reason".to_string(), reason.to_string())]);
+    let _ = security.lock().unwrap().write_audit_entry(
+        SecurityAction::DataExport,
+        resource,
+        ActionOutcome::Blocked,
+        Some(details),
+    );
+}
+
+#[tauri::command]
+pub async fn review_list(
+    status: Option<ReviewStatus>,
+    queue: tauri::State<'_, ReviewQueueStorage>,
+) -> Result<Vec<ReviewItem>, String> {
+    Ok(queue.list(status))
+}
+
+#[tauri::command]
+pub async fn review_decide(
+    session_id: String,
+    item_id: String,
+    approve: bool,
+    comment: Option<String>,
+    queue: tauri::State<'_, ReviewQueueStorage>,
+    sessions: tauri::State<'_, SessionStorage>,
+    automation: tauri::State<'_, AutomationStorage>,
+) -> Result<ReviewItem, String> {
+    let user = authenticated_user(&session_id, &sessions)?;
+    let item = queue.decide(&item_id, approve, comment, &user).map_err(|e| e.to_string())?;
+    automation.review_decided(&item);
+    Ok(item)
+}
+
+#[tauri::command]
+pub async fn review_history(item_id: String, queue: tauri::State<'_, ReviewQueueStorage>) -> Result<Vec<ReviewEvent>, String> {
+    queue.history(&item_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn review_verify_log(queue: tauri::State<'_, ReviewQueueStorage>) -> Result<LogVerification, String> {
+    queue.verify_log().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn review_get_settings(queue: tauri::State<'_, ReviewQueueStorage>) -> Result<ReviewSettings, String> {
+    Ok(queue.settings())
+}
+
+#[tauri::command]
+pub async fn review_set_settings(
+    session_id: String,
+    settings: ReviewSettings,
+    queue: tauri::State<'_, ReviewQueueStorage>,
+    sessions: tauri::State<'_, SessionStorage>,
+    enterprise: tauri::State<'_, Arc<Mutex<EnterpriseManager>>>,
+) -> Result<(), String> {
+    let user = authenticated_user(&session_id, &sessions)?;
+    if !enterprise.lock().unwrap().is_admin(&user) {
+        return Err("Only an administrator can change the reviewer list".to_string());
+    }
+    queue.set_settings(settings).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn memo(sha: &str) -> Submission {
+        Submission {
+            kind: WorkProductKind::Memo,
+            title: "Research memo".to_string(),
+            source: "research_memo".to_string(),
+            reference: "memo-1".to_string(),
+            path: Some("/memos/memo-1.docx".to_string()),
+            matter_id: None,
+            content_sha256: sha.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_approval_gates_release_until_content_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let queue = ReviewQueue::new(dir.path()).unwrap();
+        queue
+            .set_settings(ReviewSettings { reviewers: BTreeSet::from(["partner".to_string()]) })
+            .unwrap();
+
+        let item = queue.submit(memo("aaa"), "associate").unwrap();
+        assert!(queue.check_released("/memos/memo-1.docx", None, "aaa").is_err());
+        assert!(queue.decide(&item.id, true, None, "associate").is_err());
+        queue.decide(&item.id, true, Some("Checked".to_string()), "partner").unwrap();
+        assert!(queue.check_released("memo-1", None, "aaa").is_ok());
+        assert!(queue.check_released("memo-1", None, "bbb").is_err());
+        // A copy of the approved memo is released; content never approved is not
+        assert!(queue.check_released("/exports/copy.docx", None, "aaa").is_ok());
+        assert!(queue.check_released("/exports/copy.docx", Some(WorkProductKind::Analysis), "aaa").is_err());
+        assert!(queue.check_released("unqueued.docx", None, "x").is_err());
+
+        // A file written from the approved memo is released with it
+        let exported = dir.path().join("memo-1.pdf");
+        fs::write(&exported, b"rendered memo").unwrap();
+        let exported_sha = file_sha256(&exported).unwrap();
+        assert!(queue.check_released("/exports/memo-1.pdf", None, &exported_sha).is_err());
+        assert!(queue.record_export("memo-1", WorkProductKind::Memo, "zzz", &exported).is_err());
+        queue.record_export("memo-1", WorkProductKind::Memo, "aaa", &exported).unwrap();
+        assert!(queue.check_released("/exports/memo-1.pdf", None, &exported_sha).is_ok());
+
+        // Regenerated content goes back to pending
+        queue.submit(memo("bbb"), "associate").unwrap();
+        assert_eq!(queue.list(Some(ReviewStatus::Pending)).len(), 1);
+        assert!(queue.check_released("memo-1", None, "bbb").is_err());
+        assert!(queue.check_released("/docs/lease.pdf", Some(WorkProductKind::Analysis), "x").is_err());
+    }
+
+    #[test]
+    fn test_review_log_detects_tampering() {
+        let dir = tempfile::tempdir().unwrap();
+        let queue = ReviewQueue::new(dir.path()).unwrap();
+        queue
+            .set_settings(ReviewSettings { reviewers: BTreeSet::from(["partner".to_string()]) })
+            .unwrap();
+        let item = queue.submit(memo("aaa"), "associate").unwrap();
+        queue.decide(&item.id, false, Some("Wrong standard".to_string()), "partner").unwrap();
+
+        let reopened = ReviewQueue::new(dir.path()).unwrap();
+        assert!(reopened.verify_log().unwrap().intact);
+        assert_eq!(reopened.history(&item.id).unwrap().len(), 2);
+
+        let log = dir.path().join("review_log.jsonl");
+        let tampered = fs::read_to_string(&log).unwrap().replace("\"rejected\"", "\"approved\"");
+        fs::write(&log, tampered).unwrap();
+        let check = reopened.verify_log().unwrap();
+        assert!(!check.intact);
+        assert_eq!(check.first_broken_sequence, Some(1));
+    }
+}
@@ -0,0 +1,622 @@
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use csv::ReaderBuilder;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use uuid::Uuid;
+
+use crate::document_analyzer::EntityType;
+use crate::local_api::AnalyzerStorage;
+use crate::matters::{self, MatterStorage};
+
+/// Sanctions and Adverse-Party Screening for BEAR AI
+/// The EU consolidated financial sanctions list and the OFAC SDN list are downloaded into local
+/// storage, so screening itself never sends a party name off the machine. Party names from a
+/// matter, a document or an ad hoc list are fuzzy-matched against every listed name and alias;
+/// each run is appended to an audit trail together with the list versions it was run against.
+pub(crate) const EU_CONSOLIDATED_URL: &str =
+    "https://webgate.ec.europa.eu/fsd/fsf/public/files/csvFullSanctionsList_1_1/content?token=dG9rZW4tMjAxNw";
+pub(crate) const OFAC_SDN_URL: &str = "https://www.treasury.gov/ofac/downloads/sdn.csv";
+pub(crate) const OFAC_ALT_URL: &str = "https://www.treasury.gov/ofac/downloads/alt.csv";
+
+pub const DEFAULT_THRESHOLD: f64 = 0.85;
+const MAX_MATCHES_PER_PARTY: usize = 10;
+// Share of the confidence given to how much of the candidate the query covers
+const EXTRA_TOKEN_WEIGHT: f64 = 0.1;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum SanctionsList {
+    EuConsolidated,
+    OfacSdn,
+}
+
+impl SanctionsList {
+    pub const ALL: [SanctionsList; 2] = [SanctionsList::EuConsolidated, SanctionsList::OfacSdn];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SanctionsList::EuConsolidated => "EU consolidated financial sanctions list",
+            SanctionsList::OfacSdn => "OFAC Specially Designated Nationals list",
+        }
+    }
+
+    fn file_name(&self) -> &'static str {
+        match self {
+            SanctionsList::EuConsolidated => "eu_consolidated.json",
+            SanctionsList::OfacSdn => "ofac_sdn.json",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SanctionsEntry {
+    pub list: SanctionsList,
+    pub id: String,
+    pub name: String,
+    pub aliases: Vec<String>,
+    pub subject_type: Option<String>, // "individual", "entity", "vessel", ...
+    pub programs: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredList {
+    list: SanctionsList,
+    source_url: String,
+    downloaded_at: String,
+    entries: Vec<SanctionsEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListStatus {
+    pub list: SanctionsList,
+    pub source_url: Option<String>,
+    pub downloaded_at: Option<String>, // None until the list has been downloaded
+    pub entries: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SanctionsMatch {
+    pub list: SanctionsList,
+    pub entry_id: String,
+    pub listed_name: String,  // primary name of the entry
+    pub matched_name: String, // the name or alias that matched
+    pub subject_type: Option<String>,
+    pub programs: Vec<String>,
+    pub confidence: f64, // 0.0 - 1.0
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PartyScreening {
+    pub party: String,
+    pub role: Option<String>, // "client", "adverse party", "person" ...
+    pub matches: Vec<SanctionsMatch>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ScreeningSubject {
+    Matter { matter_id: String, name: String },
+    Document { path: String },
+    Parties,
+}
+
+/// One audited screening run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScreeningRun {
+    pub id: String,
+    pub run_at: String,
+    pub subject: ScreeningSubject,
+    pub threshold: f64,
+    pub lists: Vec<ListStatus>, // list versions screened against
+    pub parties: Vec<PartyScreening>,
+    pub hits: usize, // parties with at least one match
+}
+
+fn clean(value: &str) -> Option<String> {
+    let value = value.trim();
+    if value.is_empty() || value == "-0-" {
+        None
+    } else {
+        Some(value.to_string())
+    }
+}
+
+/// Parse the OFAC SDN file and its alternate-names file (both header-less CSV)
+pub fn parse_ofac_sdn(sdn_csv: &str, alt_csv: &str) -> Result<Vec<SanctionsEntry>> {
+    let mut entries: BTreeMap<String, SanctionsEntry> = BTreeMap::new();
+    let mut reader = ReaderBuilder::new().has_headers(false).flexible(true).from_reader(sdn_csv.as_bytes());
+    for record in reader.records() {
+        let record = record?;
+        let (Some(id), Some(name)) = (record.get(0).and_then(clean), record.get(1).and_then(clean)) else {
+            continue;
+        };
+        entries.insert(
+            id.clone(),
+            SanctionsEntry {
+                list: SanctionsList::OfacSdn,
+                id,
+                name,
+                aliases: Vec::new(),
+                // OFAC leaves the type empty for entities
+                subject_type: Some(record.get(2).and_then(clean).unwrap_or_else(|| "entity".to_string())),
+                programs: record
+                    .get(3)
+                    .and_then(clean)
+                    .map(|p| p.split(']').map(|s| s.trim_matches(|c| c == '[' || c == ' ').to_string()).filter(|s| !s.is_empty()).collect())
+                    .unwrap_or_default(),
+            },
+        );
+    }
+
+    let mut reader = ReaderBuilder::new().has_headers(false).flexible(true).from_reader(alt_csv.as_bytes());
+    for record in reader.records() {
+        let record = record?;
+        if let (Some(id), Some(alias)) = (record.get(0).and_then(clean), record.get(3).and_then(clean)) {
+            if let Some(entry) = entries.get_mut(&id) {
+                entry.aliases.push(alias);
+            }
+        }
+    }
+    Ok(entries.into_values().collect())
+}
+
+/// Parse the EU consolidated list CSV (semicolon-separated, one row per name alias)
+pub fn parse_eu_consolidated(csv_text: &str) -> Result<Vec<SanctionsEntry>> {
+    let mut reader = ReaderBuilder::new().delimiter(b';').flexible(true).from_reader(csv_text.as_bytes());
+    let headers = reader.headers()?.clone();
+    let column = |name: &str| headers.iter().position(|h| h.trim() == name);
+    let id_column = column("Entity_LogicalId").ok_or_else(|| anyhow!("EU list has no Entity_LogicalId column"))?;
+    let name_column = column("NameAlias_WholeName").ok_or_else(|| anyhow!("EU list has no NameAlias_WholeName column"))?;
+    let type_column = column("Entity_SubjectType_ClassificationCode").or_else(|| column("Entity_SubjectType"));
+    let programme_column = column("Entity_Regulation_Programme");
+
+    let mut entries: BTreeMap<String, SanctionsEntry> = BTreeMap::new();
+    for record in reader.records() {
+        let record = record?;
+        let (Some(id), Some(name)) = (record.get(id_column).and_then(clean), record.get(name_column).and_then(clean)) else {
+            continue;
+        };
+        let entry = entries.entry(id.clone()).or_insert_with(|| SanctionsEntry {
+            list: SanctionsList::EuConsolidated,
+            id,
+            name: name.clone(),
+            aliases: Vec::new(),
+            subject_type: type_column.and_then(|c| record.get(c)).and_then(clean),
+            programs: Vec::new(),
+        });
+        if entry.name != name && !entry.aliases.contains(&name) {
+            entry.aliases.push(name);
+        }
+        if let Some(programme) = programme_column.and_then(|c| record.get(c)).and_then(clean) {
+            if !entry.programs.contains(&programme) {
+                entry.programs.push(programme);
+            }
+        }
+    }
+    Ok(entries.into_values().collect())
+}
+
+/// Normalised edit-distance similarity of two tokens
+fn token_similarity(a: &str, b: &str) -> f64 {
+    if a == b {
+        return 1.0;
+    }
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    1.0 - previous[b.len()] as f64 / a.len().max(b.len()) as f64
+}
+
+/// Order-insensitive fuzzy match, scored by how much of the queried name the candidate covers:
+/// every query token is paired with its closest candidate token, so "Vladimir Putin" matches
+/// "PUTIN, Vladimir Vladimirovich" although the list adds a patronymic, and a misspelt token
+/// costs only part of the score. Candidate tokens the query lacks only break ties.
+pub fn match_confidence(query: &[String], candidate: &[String]) -> f64 {
+    if query.is_empty() || candidate.is_empty() {
+        return 0.0;
+    }
+    let coverage = |from: &[String], to: &[String]| -> f64 {
+        from.iter()
+            .map(|t| to.iter().map(|u| token_similarity(t, u)).fold(0.0, f64::max))
+            .sum::<f64>()
+            / from.len() as f64
+    };
+    (1.0 - EXTRA_TOKEN_WEIGHT) * coverage(query, candidate) + EXTRA_TOKEN_WEIGHT * coverage(candidate, query)
+}
+
+fn tokens(name: &str) -> Vec<String> {
+    matters::name_tokens(name).into_iter().collect()
+}
+
+struct IndexedList {
+    stored: StoredList,
+    names: Vec<(usize, String, Vec<String>)>, // entry index, name, tokens
+}
+
+impl IndexedList {
+    fn new(stored: StoredList) -> Self {
+        let names = stored
+            .entries
+            .iter()
+            .enumerate()
+            .flat_map(|(index, entry)| {
+                std::iter::once(&entry.name)
+                    .chain(entry.aliases.iter())
+                    .map(move |name| (index, name.clone(), tokens(name)))
+            })
+            .collect();
+        Self { stored, names }
+    }
+
+    fn status(&self) -> ListStatus {
+        ListStatus {
+            list: self.stored.list,
+            source_url: Some(self.stored.source_url.clone()),
+            downloaded_at: Some(self.stored.downloaded_at.clone()),
+            entries: self.stored.entries.len(),
+        }
+    }
+
+    fn screen(&self, party: &[String], threshold: f64) -> Vec<SanctionsMatch> {
+        let mut best: HashMap<usize, (f64, &str)> = HashMap::new();
+        for (index, name, name_tokens) in &self.names {
+            let confidence = match_confidence(party, name_tokens);
+            if confidence >= threshold && best.get(index).map(|(c, _)| confidence > *c).unwrap_or(true) {
+                best.insert(*index, (confidence, name.as_str()));
+            }
+        }
+        best.into_iter()
+            .map(|(index, (confidence, matched_name))| {
+                let entry = &self.stored.entries[index];
+                SanctionsMatch {
+                    list: entry.list,
+                    entry_id: entry.id.clone(),
+                    listed_name: entry.name.clone(),
+                    matched_name: matched_name.to_string(),
+                    subject_type: entry.subject_type.clone(),
+                    programs: entry.programs.clone(),
+                    confidence: (confidence * 1000.0).round() / 1000.0,
+                }
+            })
+            .collect()
+    }
+}
+
+pub struct SanctionsScreener {
+    dir: PathBuf,
+    runs_path: PathBuf,
+    client: reqwest::Client,
+    lists: RwLock<HashMap<SanctionsList, IndexedList>>,
+}
+
+impl SanctionsScreener {
+    pub fn new(app_data_dir: &Path) -> Result<Self> {
+        let dir = app_data_dir.join("sanctions");
+        fs::create_dir_all(&dir)?;
+        let mut lists = HashMap::new();
+        for list in SanctionsList::ALL {
+            let path = dir.join(list.file_name());
+            if !path.exists() {
+                continue;
+            }
+            match fs::read_to_string(&path)
+                .map_err(anyhow::Error::from)
+                .and_then(|content| Ok(serde_json::from_str::<StoredList>(&content)?))
+            {
+                Ok(stored) => {
+                    lists.insert(list, IndexedList::new(stored));
+                }
+                Err(e) => log::warn!("Ignoring unreadable sanctions list {}: {}", path.display(), e),
+            }
+        }
+
+        Ok(Self {
+            runs_path: dir.join("screening_runs.jsonl"),
+            dir,
+            client: reqwest::Client::new(),
+            lists: RwLock::new(lists),
+        })
+    }
+
+    async fn download(&self, url: &str) -> Result<String> {
+        let response = self.client.get(url).send().await?;
+        if !response.status().is_success() {
+            return Err(anyhow!("Download of {} failed with status {}", url, response.status()));
+        }
+        Ok(response.text().await?)
+    }
+
+    /// Download a list and replace the local copy
+    pub async fn update(&self, list: SanctionsList) -> Result<ListStatus> {
+        let entries = match list {
+            SanctionsList::EuConsolidated => parse_eu_consolidated(&self.download(EU_CONSOLIDATED_URL).await?)?,
+            SanctionsList::OfacSdn => {
+                let sdn = self.download(OFAC_SDN_URL).await?;
+                let alt = self.download(OFAC_ALT_URL).await?;
+                parse_ofac_sdn(&sdn, &alt)?
+            }
+        };
+        let source_url = match list {
+            SanctionsList::EuConsolidated => EU_CONSOLIDATED_URL,
+            SanctionsList::OfacSdn => OFAC_SDN_URL,
+        };
+        self.store(list, source_url, entries)
+    }
+
+    fn store(&self, list: SanctionsList, source_url: &str, entries: Vec<SanctionsEntry>) -> Result<ListStatus> {
+        // An empty parse means the format changed; keep the last good copy
+        if entries.is_empty() {
+            return Err(anyhow!("{} contained no entries", list.label()));
+        }
+        let stored = StoredList {
+            list,
+            source_url: source_url.to_string(),
+            downloaded_at: Utc::now().to_rfc3339(),
+            entries,
+        };
+        fs::write(self.dir.join(list.file_name()), serde_json::to_string(&stored)?)?;
+        let indexed = IndexedList::new(stored);
+        let status = indexed.status();
+        self.lists.write().unwrap().insert(list, indexed);
+        Ok(status)
+    }
+
+    pub fn status(&self) -> Vec<ListStatus> {
+        let lists = self.lists.read().unwrap();
+        SanctionsList::ALL
+            .iter()
+            .map(|list| {
+                lists.get(list).map(|l| l.status()).unwrap_or(ListStatus {
+                    list: *list,
+                    source_url: None,
+                    downloaded_at: None,
+                    entries: 0,
+                })
+            })
+            .collect()
+    }
+
+    /// Screen (party, role) pairs against every local list and record the run
+    pub fn screen(&self, subject: ScreeningSubject, parties: Vec<(String, Option<String>)>, threshold: f64) -> Result<ScreeningRun> {
+        let lists = self.lists.read().unwrap();
+        if lists.is_empty() {
+            return Err(anyhow!("No sanctions lists have been downloaded yet"));
+        }
+
+        let mut seen = BTreeSet::new();
+        let parties: Vec<PartyScreening> = parties
+            .into_iter()
+            .filter(|(party, _)| seen.insert(party.trim().to_lowercase()))
+            .map(|(party, role)| {
+                let party_tokens = tokens(&party);
+                let mut matches: Vec<SanctionsMatch> =
+                    lists.values().flat_map(|list| list.screen(&party_tokens, threshold)).collect();
+                matches.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+                matches.truncate(MAX_MATCHES_PER_PARTY);
+                PartyScreening { party, role, matches }
+            })
+            .collect();
+
+        let mut statuses: Vec<ListStatus> = lists.values().map(|l| l.status()).collect();
+        statuses.sort_by_key(|s| s.list);
+        let run = ScreeningRun {
+            id: Uuid::new_v4().to_string(),
+            run_at: Utc::now().to_rfc3339(),
+            subject,
+            threshold,
+            lists: statuses,
+            hits: parties.iter().filter(|p| !p.matches.is_empty()).count(),
+            parties,
+        };
+
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.runs_path)?;
+        writeln!(file, "{}", serde_json::to_string(&run)?)?;
+        Ok(run)
+    }
+
+    /// Past screening runs, newest first
+    pub fn runs(&self, limit: usize) -> Result<Vec<ScreeningRun>> {
+        if !self.runs_path.exists() {
+            return Ok(Vec::new());
+        }
+        let mut runs = Vec::new();
+        for line in fs::read_to_string(&self.runs_path)?.lines().filter(|l| !l.trim().is_empty()) {
+            match serde_json::from_str::<ScreeningRun>(line) {
+                Ok(run) => runs.push(run),
+                Err(e) => log::warn!("Skipping unreadable screening run: {}", e),
+            }
+        }
+        runs.sort_by_key(|r| Reverse(r.run_at.clone()));
+        runs.truncate(limit);
+        Ok(runs)
+    }
+}
+
+pub type SanctionsStorage = Arc<SanctionsScreener>;
+
+#[tauri::command]
+pub async fn sanctions_update_lists(
+    lists: Option<Vec<SanctionsList>>,
+    screener: tauri::State<'_, SanctionsStorage>,
+) -> Result<Vec<ListStatus>, String> {
+    let mut updated = Vec::new();
+    for list in lists.unwrap_or_else(|| SanctionsList::ALL.to_vec()) {
+        updated.push(
+            screener
+                .update(list)
+                .await
+                .map_err(|e| format!("{}: {}", list.label(), e))?,
+        );
+    }
+    Ok(updated)
+}
+
+#[tauri::command]
+pub async fn sanctions_list_status(screener: tauri::State<'_, SanctionsStorage>) -> Result<Vec<ListStatus>, String> {
+    Ok(screener.status())
+}
+
+#[tauri::command]
+pub async fn sanctions_screen_parties(
+    parties: Vec<String>,
+    threshold: Option<f64>,
+    screener: tauri::State<'_, SanctionsStorage>,
+) -> Result<ScreeningRun, String> {
+    screener
+        .screen(
+            ScreeningSubject::Parties,
+            parties.into_iter().map(|p| (p, None)).collect(),
+            threshold.unwrap_or(DEFAULT_THRESHOLD),
+        )
+        .map_err(|e| e.to_string())
+}
+
+/// Screen the client, adverse and related parties of a matter
+#[tauri::command]
+pub async fn sanctions_screen_matter(
+    matter_id: String,
+    threshold: Option<f64>,
+    screener: tauri::State<'_, SanctionsStorage>,
+    matters: tauri::State<'_, MatterStorage>,
+) -> Result<ScreeningRun, String> {
+    let matter = matters.get(&matter_id).ok_or_else(|| "Matter not found".to_string())?;
+    let mut parties = vec![(matter.client.clone(), Some("client".to_string()))];
+    parties.extend(matter.adverse_parties.iter().map(|p| (p.clone(), Some("adverse party".to_string()))));
+    parties.extend(matter.related_parties.iter().map(|p| (p.clone(), Some("related party".to_string()))));
+    screener
+        .screen(
+            ScreeningSubject::Matter { matter_id, name: matter.name },
+            parties,
+            threshold.unwrap_or(DEFAULT_THRESHOLD),
+        )
+        .map_err(|e| e.to_string())
+}
+
+/// Screen the people, organisations and contract parties extracted from a document
+#[tauri::command]
+pub async fn sanctions_screen_document(
+    file_path: String,
+    threshold: Option<f64>,
+    screener: tauri::State<'_, SanctionsStorage>,
+    analyzer: tauri::State<'_, AnalyzerStorage>,
+) -> Result<ScreeningRun, String> {
+    let analysis = analyzer
+        .analyze_document(Path::new(&file_path))
+        .await
+        .map_err(|e| e.to_string())?;
+    let parties = analysis
+        .entities
+        .iter()
+        .filter_map(|entity| {
+            let role = match entity.entity_type {
+                EntityType::Person => "person",
+                EntityType::Organization => "organization",
+                EntityType::ContractParty => "contract party",
+                _ => return None,
+            };
+            Some((entity.text.trim().to_string(), Some(role.to_string())))
+        })
+        .collect();
+    screener
+        .screen(
+            ScreeningSubject::Document { path: file_path },
+            parties,
+            threshold.unwrap_or(DEFAULT_THRESHOLD),
+        )
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn sanctions_list_runs(
+    limit: Option<usize>,
+    screener: tauri::State<'_, SanctionsStorage>,
+) -> Result<Vec<ScreeningRun>, String> {
+    screener.runs(limit.unwrap_or(50)).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SDN: &str = "36,\"AEROCARIBBEAN AIRLINES\",-0- ,\"CUBA\",-0- ,-0- ,-0- ,-0- ,-0- ,-0- ,-0- ,\"Havana, Cuba.\"\n\
+        173,\"ANGLO-CARIBBEAN CO., LTD.\",-0- ,\"CUBA\",-0- ,-0- ,-0- ,-0- ,-0- ,-0- ,-0- ,-0- \n\
+        9999,\"PETROV, Ivan Sergeyevich\",\"individual\",\"RUSSIA-EO14024] [UKRAINE-EO13660\",-0- ,-0- ,-0- ,-0- ,-0- ,-0- ,-0- ,-0- \n";
+    const ALT: &str = "36,12,\"aka\",\"AERO-CARIBBEAN\",-0- \n9999,13,\"aka\",\"PETROFF, Ivan\",-0- \n";
+    const EU: &str = "fileGenerationDate;Entity_LogicalId;Entity_SubjectType_ClassificationCode;Entity_Regulation_Programme;NameAlias_WholeName\n\
+        2024-01-01;13;person;UKR;Ivan Sergeyevich Petrov\n\
+        2024-01-01;13;person;UKR;Иван Сергеевич Петров\n\
+        2024-01-01;20;enterprise;BLR;Belaruskali OAO\n";
+
+    #[test]
+    fn test_parses_ofac_and_eu_lists() {
+        let ofac = parse_ofac_sdn(SDN, ALT).unwrap();
+        assert_eq!(ofac.len(), 3);
+        let petrov = ofac.iter().find(|e| e.id == "9999").unwrap();
+        assert_eq!(petrov.aliases, vec!["PETROFF, Ivan".to_string()]);
+        assert_eq!(petrov.programs, vec!["RUSSIA-EO14024".to_string(), "UKRAINE-EO13660".to_string()]);
+        assert_eq!(ofac[0].subject_type.as_deref(), Some("entity"));
+
+        let eu = parse_eu_consolidated(EU).unwrap();
+        assert_eq!(eu.len(), 2);
+        assert_eq!(eu[0].name, "Ivan Sergeyevich Petrov");
+        assert_eq!(eu[0].aliases.len(), 1);
+        assert_eq!(eu[0].programs, vec!["UKR".to_string()]);
+    }
+
+    #[test]
+    fn test_fuzzy_screening_is_recorded_in_audit_trail() {
+        let dir = tempfile::tempdir().unwrap();
+        let screener = SanctionsScreener::new(dir.path()).unwrap();
+        assert!(screener.screen(ScreeningSubject::Parties, vec![("Acme".to_string(), None)], DEFAULT_THRESHOLD).is_err());
+
+        screener.store(SanctionsList::OfacSdn, OFAC_SDN_URL, parse_ofac_sdn(SDN, ALT).unwrap()).unwrap();
+        screener.store(SanctionsList::EuConsolidated, EU_CONSOLIDATED_URL, parse_eu_consolidated(EU).unwrap()).unwrap();
+
+        let run = screener
+            .screen(
+                ScreeningSubject::Parties,
+                vec![
+                    ("Ivan Petrov".to_string(), Some("adverse party".to_string())),
+                    ("Aerocaribean Airlines".to_string(), None), // misspelt
+                    ("Acme Holdings BV".to_string(), Some("client".to_string())),
+                ],
+                DEFAULT_THRESHOLD,
+            )
+            .unwrap();
+        assert_eq!(run.hits, 2);
+        let petrov = &run.parties[0];
+        assert!(petrov.matches.iter().any(|m| m.list == SanctionsList::OfacSdn && m.entry_id == "9999"));
+        assert!(petrov.matches.iter().any(|m| m.list == SanctionsList::EuConsolidated && m.entry_id == "13"));
+        let airline = &run.parties[1].matches[0];
+        assert_eq!(airline.entry_id, "36");
+        assert!(airline.confidence > 0.9 && airline.confidence < 1.0);
+        assert!(run.parties[2].matches.is_empty());
+        assert!(petrov.matches.iter().all(|m| m.confidence >= DEFAULT_THRESHOLD));
+        // The list's extra names cost little; an exact name still ranks first
+        let query = tokens("Ivan Petrov");
+        assert!(match_confidence(&query, &tokens("PETROV, Ivan Sergeyevich")) >= DEFAULT_THRESHOLD);
+        assert!(match_confidence(&query, &tokens("Petrov Ivan")) > match_confidence(&query, &tokens("PETROV, Ivan Sergeyevich")));
+
+        // Lists and runs survive a restart
+        let reopened = SanctionsScreener::new(dir.path()).unwrap();
+        assert_eq!(reopened.status().iter().map(|s| s.entries).collect::<Vec<_>>(), vec![2, 3]);
+        let runs = reopened.runs(10).unwrap();
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].id, run.id);
+        assert_eq!(runs[0].lists.len(), 2);
+    }
+}
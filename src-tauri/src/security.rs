@@ -4,6 +4,8 @@ use aes_gcm::{
 };
 use anyhow::{Context, Result};
 use ring::digest;
+use ring::rand::SystemRandom;
+use ring::signature::Ed25519KeyPair;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
@@ -341,6 +343,19 @@ impl SecurityManager {
             .map_err(|e| anyhow::anyhow!("Decryption failed: {}", e))
     }
 
+    /// The Ed25519 key kept encrypted at `key_path`, generated on first use; `label` names it
+    /// in errors
+    pub fn signing_key(&self, key_path: &Path, label: &str) -> Result<Ed25519KeyPair> {
+        if key_path.exists() {
+            let pkcs8 = self.decrypt_data(&fs::read(key_path)?)?;
+            return Ed25519KeyPair::from_pkcs8(&pkcs8).map_err(|_| anyhow::anyhow!("Stored {} is invalid", label));
+        }
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new())
+            .map_err(|_| anyhow::anyhow!("Failed to generate a {}", label))?;
+        fs::write(key_path, self.encrypt_data(pkcs8.as_ref())?)?;
+        Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).map_err(|_| anyhow::anyhow!("Generated {} is invalid", label))
+    }
+
     /// Secure document storage
     pub async fn secure_document_store(&self, document_path: &Path, content: &[u8]) -> Result<()> {
         // Encrypt content
@@ -465,6 +480,17 @@ impl SecurityManager {
         resource: &str,
         outcome: ActionOutcome,
         details: Option<HashMap<String, String>>,
+    ) -> Result<()> {
+        self.write_audit_entry(action, resource, outcome, details)
+    }
+
+    /// Append an entry to the audit log; usable while the manager is behind a std mutex
+    pub fn write_audit_entry(
+        &self,
+        action: SecurityAction,
+        resource: &str,
+        outcome: ActionOutcome,
+        details: Option<HashMap<String, String>>,
     ) -> Result<()> {
         if !self.config.audit_logging {
             return Ok(());
@@ -0,0 +1,317 @@
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::glossary;
+use crate::llm_manager::{ChatMessage as LlmChatMessage, ChatRequest, GenerateOptions, LLMManager};
+use crate::local_api::{ChatMessage, ChatSession, ChatStorage, MessageStorage};
+use crate::text_processing::truncate_chars;
+
+/// Chat session titles and rolling summaries for BEAR AI
+/// Titles come from the first exchange; summaries are refreshed as the conversation grows
+/// and kept in the session metadata under the keys below
+pub const SUMMARY_KEY: &str = "summary";
+pub const SUMMARY_MESSAGE_COUNT_KEY: &str = "summary_message_count";
+pub const SUMMARY_UPDATED_AT_KEY: &str = "summary_updated_at";
+pub const TITLE_SOURCE_KEY: &str = "title_source"; // "user" | "auto" | "fallback"
+
+/// Event emitted when a session's title or summary changes
+pub const SESSION_UPDATED_EVENT: &str = "chat-session-updated";
+
+/// Placeholder titles the frontend uses for new chats
+const PLACEHOLDER_TITLES: &[&str] = &["", "New Chat", "New chat", "Untitled"];
+
+/// New messages needed before the rolling summary is refreshed
+const SUMMARY_INTERVAL: usize = 10;
+
+/// Longest title kept, in characters
+const MAX_TITLE_CHARS: usize = 60;
+
+/// Transcript characters sent to the model per summary refresh
+const MAX_TRANSCRIPT_CHARS: usize = 12_000;
+
+pub fn is_placeholder_title(title: &str) -> bool {
+    PLACEHOLDER_TITLES.contains(&title.trim())
+}
+
+/// Whether the session still needs a generated title
+pub fn needs_title(session: &ChatSession) -> bool {
+    let source = session
+        .metadata
+        .as_ref()
+        .and_then(|m| m.get(TITLE_SOURCE_KEY))
+        .map(|s| s.as_str());
+
+    match source {
+        Some("user") | Some("auto") => false,
+        Some(_) => true, // fallback titles are replaced once a model is available
+        None => is_placeholder_title(&session.title),
+    }
+}
+
+/// Whether enough messages arrived since the last summary to refresh it
+pub fn summary_due(session: &ChatSession) -> bool {
+    let summarized = session
+        .metadata
+        .as_ref()
+        .and_then(|m| m.get(SUMMARY_MESSAGE_COUNT_KEY))
+        .and_then(|c| c.parse::<usize>().ok())
+        .unwrap_or(0);
+
+    session.message_count as usize >= summarized + SUMMARY_INTERVAL
+}
+
+/// Title from the first user message, used when no model is loaded
+pub fn fallback_title(first_user_message: &str) -> String {
+    let first_line = first_user_message.lines().find(|l| !l.trim().is_empty()).unwrap_or("");
+    let title = clean_title(first_line);
+    if title.is_empty() {
+        "Untitled conversation".to_string()
+    } else {
+        title
+    }
+}
+
+/// Strip the quoting, labels and trailing punctuation models tend to add around a title
+fn clean_title(raw: &str) -> String {
+    let line = raw.lines().find(|l| !l.trim().is_empty()).unwrap_or("").trim();
+    let line = line
+        .strip_prefix("Title:")
+        .or_else(|| line.strip_prefix("title:"))
+        .unwrap_or(line)
+        .trim_start_matches(|c| matches!(c, '"' | '\'' | '*' | '#' | ' '))
+        .trim_end_matches(|c| matches!(c, '"' | '\'' | '*' | '#' | '.' | ':' | ' '));
+
+    if line.chars().count() <= MAX_TITLE_CHARS {
+        return line.to_string();
+    }
+
+    // Cut on a word boundary
+    let truncated: String = line.chars().take(MAX_TITLE_CHARS).collect();
+    match truncated.rfind(' ') {
+        Some(pos) if pos > MAX_TITLE_CHARS / 2 => format!("{}…", &truncated[..pos]),
+        _ => format!("{}…", truncated),
+    }
+}
+
+/// Model used for background summarization: only one that is already loaded, so
+/// naming a chat never triggers a model load
+fn summarization_model(manager: &LLMManager) -> Option<String> {
+    manager.find_loaded_model("chat").map(|m| m.model_id)
+}
+
+async fn complete(manager: &LLMManager, model: &str, system: &str, user: String, max_tokens: i32) -> Result<String> {
+    let request = ChatRequest {
+        model: model.to_string(),
+        messages: vec![
+            LlmChatMessage {
+                role: "system".to_string(),
+                content: system.to_string(),
+                images: None,
+            },
+            LlmChatMessage {
+                role: "user".to_string(),
+                content: user,
+                images: None,
+            },
+        ],
+        stream: Some(false),
+        options: Some(GenerateOptions {
+            num_predict: Some(max_tokens),
+            temperature: Some(0.2),
+            ..Default::default()
+        }),
+    };
+
+    let response = manager.chat(request).await?;
+    Ok(response.message.content.trim().to_string())
+}
+
+/// Short title for a conversation from its first exchange
+pub async fn generate_title(manager: &LLMManager, model: &str, user_message: &str, assistant_message: &str) -> Result<String> {
+    let prompt = format!(
+        "User: {}\n\nAssistant: {}",
+        truncate_chars(user_message, 2000),
+        truncate_chars(assistant_message, 2000)
+    );
+    let raw = complete(
+        manager,
+        model,
+        "Write a title of at most six words for this legal conversation. Reply with the title only.",
+        prompt,
+        24,
+    )
+    .await?;
+
+    let title = clean_title(&raw);
+    if title.is_empty() {
+        return Err(anyhow!("Model returned an empty title"));
+    }
+    Ok(title)
+}
+
+/// Fold new messages into the previous summary
+pub async fn generate_summary(
+    manager: &LLMManager,
+    model: &str,
+    previous_summary: Option<&str>,
+    messages: &[ChatMessage],
+) -> Result<String> {
+    let transcript = messages
+        .iter()
+        .map(|m| format!("{}: {}", m.role, m.content))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    // Keep the most recent part of the transcript when it is too long
+    let skip = transcript.chars().count().saturating_sub(MAX_TRANSCRIPT_CHARS);
+    let transcript: String = transcript.chars().skip(skip).collect();
+
+    let prompt = match previous_summary {
+        Some(summary) => format!("Summary so far:\n{}\n\nNew messages:\n{}", summary, transcript),
+        None => format!("Conversation:\n{}", transcript),
+    };
+
+    complete(
+        manager,
+        model,
+        "Summarize this legal conversation in at most five sentences. Keep parties, dates, \
+         jurisdictions and open questions. Reply with the summary only.",
+        prompt,
+        256,
+    )
+    .await
+}
+
+fn find_session(chat_storage: &ChatStorage, owner_session_id: &str, chat_session_id: &str) -> Option<ChatSession> {
+    chat_storage
+        .lock()
+        .unwrap()
+        .get(owner_session_id)?
+        .iter()
+        .find(|c| c.id == chat_session_id)
+        .cloned()
+}
+
+fn store_session_metadata(
+    chat_storage: &ChatStorage,
+    owner_session_id: &str,
+    chat_session_id: &str,
+    title: Option<String>,
+    metadata: HashMap<String, String>,
+) -> Option<ChatSession> {
+    let mut chat_guard = chat_storage.lock().unwrap();
+    let chat = chat_guard
+        .get_mut(owner_session_id)?
+        .iter_mut()
+        .find(|c| c.id == chat_session_id)?;
+
+    if let Some(title) = title {
+        chat.title = title;
+    }
+    chat.metadata.get_or_insert_with(HashMap::new).extend(metadata);
+    Some(chat.clone())
+}
+
+/// Generate the title and/or refresh the summary of a session when due (or when forced).
+/// Returns the updated session, or `None` when nothing changed.
+pub async fn update_session(
+    manager: Arc<LLMManager>,
+    chat_storage: ChatStorage,
+    message_storage: MessageStorage,
+    owner_session_id: &str,
+    chat_session_id: &str,
+    force_summary: bool,
+) -> Result<Option<ChatSession>> {
+    let session = find_session(&chat_storage, owner_session_id, chat_session_id)
+        .ok_or_else(|| anyhow!("Chat session {} not found", chat_session_id))?;
+    let messages = message_storage
+        .lock()
+        .unwrap()
+        .get(chat_session_id)
+        .cloned()
+        .unwrap_or_default();
+
+    let want_title = needs_title(&session);
+    let want_summary = force_summary || summary_due(&session);
+    if (!want_title && !want_summary) || messages.is_empty() {
+        return Ok(None);
+    }
+
+    let model = summarization_model(&manager);
+    let mut title = None;
+    let mut metadata = HashMap::new();
+
+    if want_title {
+        let first_user = messages.iter().find(|m| m.role == "user");
+        let first_assistant = messages.iter().find(|m| m.role == "assistant");
+
+        match (&model, first_user, first_assistant) {
+            (Some(model), Some(user), Some(assistant)) => {
+                match generate_title(&manager, model, &user.content, &assistant.content).await {
+                    Ok(generated) => {
+                        title = Some(generated);
+                        metadata.insert(TITLE_SOURCE_KEY.to_string(), "auto".to_string());
+                    }
+                    Err(e) => log::warn!("Title generation failed for {}: {}", chat_session_id, e),
+                }
+            }
+            (_, Some(user), _) if is_placeholder_title(&session.title) => {
+                title = Some(fallback_title(&user.content));
+                metadata.insert(TITLE_SOURCE_KEY.to_string(), "fallback".to_string());
+            }
+            _ => {}
+        }
+    }
+
+    if want_summary {
+        match &model {
+            Some(model) => {
+                let meta = session.metadata.clone().unwrap_or_default();
+                let summarized = meta
+                    .get(SUMMARY_MESSAGE_COUNT_KEY)
+                    .and_then(|c| c.parse::<usize>().ok())
+                    .unwrap_or(0)
+                    .min(messages.len());
+
+                // A forced regeneration starts over from the full conversation
+                let (previous, new_messages) = if force_summary {
+                    (None, &messages[..])
+                } else {
+                    (meta.get(SUMMARY_KEY).map(|s| s.as_str()), &messages[summarized..])
+                };
+
+                let summary = generate_summary(&manager, model, previous, new_messages).await?;
+                metadata.insert(SUMMARY_KEY.to_string(), glossary::enforce_glossary(&summary));
+                metadata.insert(SUMMARY_MESSAGE_COUNT_KEY.to_string(), messages.len().to_string());
+                metadata.insert(SUMMARY_UPDATED_AT_KEY.to_string(), chrono::Utc::now().to_rfc3339());
+            }
+            None if force_summary => return Err(anyhow!("No chat model is loaded to summarize the session")),
+            None => {}
+        }
+    }
+
+    if title.is_none() && metadata.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(store_session_metadata(&chat_storage, owner_session_id, chat_session_id, title, metadata))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clean_title() {
+        assert_eq!(clean_title("Title: \"NDA Breach Remedies\"."), "NDA Breach Remedies");
+        assert_eq!(clean_title("\n**Lease Termination Notice**\nExtra line"), "Lease Termination Notice");
+    }
+
+    #[test]
+    fn test_fallback_title_truncates_on_word_boundary() {
+        let title = fallback_title("Can you review the indemnification clause in the attached master services agreement for us?");
+        assert!(title.ends_with('…'));
+        assert!(title.chars().count() <= MAX_TITLE_CHARS + 1);
+        assert!(title.starts_with("Can you review the indemnification clause"));
+    }
+}
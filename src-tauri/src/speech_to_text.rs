@@ -0,0 +1,640 @@
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::process::Command as AsyncCommand;
+use uuid::Uuid;
+
+use crate::document_acl::DocumentAclStorage;
+use crate::llm_manager::LLMManager;
+use crate::local_api::{
+    append_chat_message, generate_uuid, store_document, validate_session, ChatMessage, ChatStorage, Document,
+    DocumentStorage, MessageStorage, SessionStorage,
+};
+
+/// Speech-to-Text for BEAR AI
+/// Transcribes dictation with whisper.cpp using Whisper models installed through the
+/// model registry, from audio files or microphone audio streamed in by the frontend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptSegment {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub text: String,
+    // Increments at each speaker change detected by tinydiarize ("-tdrz") models;
+    // it separates turns but does not identify who is speaking
+    pub speaker_turn: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Transcript {
+    pub id: String,
+    pub model_id: String,
+    pub language: Option<String>,
+    pub source: String, // file path or "microphone"
+    pub duration_ms: u64,
+    pub segments: Vec<TranscriptSegment>,
+    pub created_at: String,
+}
+
+impl Transcript {
+    /// Plain text, one paragraph per speaker turn
+    pub fn text(&self) -> String {
+        let mut paragraphs: Vec<String> = Vec::new();
+        let mut current_turn = None;
+        for segment in &self.segments {
+            let text = segment.text.trim();
+            if text.is_empty() {
+                continue;
+            }
+            match paragraphs.last_mut() {
+                Some(paragraph) if current_turn == Some(segment.speaker_turn) => {
+                    paragraph.push(' ');
+                    paragraph.push_str(text);
+                }
+                _ => paragraphs.push(text.to_string()),
+            }
+            current_turn = Some(segment.speaker_turn);
+        }
+        paragraphs.join("\n\n")
+    }
+
+    /// Text with a timestamp and speaker label per segment, e.g. "[00:01:05] Speaker 2: ..."
+    pub fn text_with_timestamps(&self) -> String {
+        self.segments
+            .iter()
+            .filter(|s| !s.text.trim().is_empty())
+            .map(|s| format!("[{}] Speaker {}: {}", format_timestamp(s.start_ms), s.speaker_turn + 1, s.text.trim()))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TranscriptionOptions {
+    pub language: Option<String>, // ISO 639-1, or None to auto-detect
+    pub translate_to_english: bool,
+    pub initial_prompt: Option<String>, // names and terms to bias recognition towards
+}
+
+/// Event emitted with newly transcribed segments while dictating
+pub const DICTATION_EVENT: &str = "dictation-transcript";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DictationUpdate {
+    pub stream_id: String,
+    pub segments: Vec<TranscriptSegment>,
+}
+
+/// Sample rate whisper.cpp expects
+pub const WHISPER_SAMPLE_RATE: u32 = 16_000;
+
+/// Microphone audio is transcribed in windows of this length while dictating
+const DICTATION_WINDOW_MS: u64 = 5_000;
+
+/// Characters of earlier transcript passed as context to the next window
+const DICTATION_PROMPT_CHARS: usize = 200;
+
+struct DictationStream {
+    model_id: String,
+    options: TranscriptionOptions,
+    pending: Vec<f32>,
+    pending_offset_ms: u64,
+    segments: Vec<TranscriptSegment>,
+    busy: bool,
+}
+
+pub struct SpeechToTextService {
+    manager: Arc<LLMManager>,
+    work_dir: PathBuf,
+    streams: Mutex<HashMap<String, DictationStream>>,
+    transcripts: Mutex<HashMap<String, Transcript>>,
+}
+
+pub type SpeechToTextStorage = Arc<SpeechToTextService>;
+
+impl SpeechToTextService {
+    pub fn new(manager: Arc<LLMManager>, app_data_dir: &Path) -> Result<Self> {
+        let work_dir = app_data_dir.join("transcripts");
+        std::fs::create_dir_all(&work_dir)?;
+
+        Ok(Self {
+            manager,
+            work_dir,
+            streams: Mutex::new(HashMap::new()),
+            transcripts: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Transcribe an audio file. WAV is read directly; other formats are converted with ffmpeg.
+    pub async fn transcribe_file(&self, model_id: &str, audio_path: &Path, options: &TranscriptionOptions) -> Result<Transcript> {
+        if !audio_path.exists() {
+            return Err(anyhow!("Audio file not found: {:?}", audio_path));
+        }
+
+        let is_wav = audio_path
+            .extension()
+            .map(|e| e.eq_ignore_ascii_case("wav"))
+            .unwrap_or(false);
+        let (wav_path, converted) = if is_wav {
+            (audio_path.to_path_buf(), false)
+        } else {
+            (self.convert_to_wav(audio_path).await?, true)
+        };
+
+        let result = self.run_whisper(model_id, &wav_path, options).await;
+        if converted {
+            let _ = tokio::fs::remove_file(&wav_path).await;
+        }
+        let segments = result?;
+
+        let transcript = Transcript {
+            id: Uuid::new_v4().to_string(),
+            model_id: model_id.to_string(),
+            language: options.language.clone(),
+            source: audio_path.to_string_lossy().to_string(),
+            duration_ms: segments.last().map(|s| s.end_ms).unwrap_or(0),
+            segments,
+            created_at: chrono::Utc::now().to_rfc3339(),
+        };
+        self.store_transcript(&transcript);
+
+        Ok(transcript)
+    }
+
+    /// Begin a microphone dictation; audio arrives through `push_audio`
+    pub fn start_dictation(&self, model_id: &str, options: TranscriptionOptions) -> Result<String> {
+        // Fail now rather than after the user has been speaking for a while
+        self.manager.installed_model_file(model_id)?;
+
+        let stream_id = Uuid::new_v4().to_string();
+        self.streams.lock().unwrap().insert(
+            stream_id.clone(),
+            DictationStream {
+                model_id: model_id.to_string(),
+                options,
+                pending: Vec::new(),
+                pending_offset_ms: 0,
+                segments: Vec::new(),
+                busy: false,
+            },
+        );
+        Ok(stream_id)
+    }
+
+    /// Buffer 16 kHz mono samples. Once a full window is buffered it is transcribed in the
+    /// background and the new segments are emitted as a `DICTATION_EVENT`.
+    pub fn push_audio(self: &Arc<Self>, app: &tauri::AppHandle, stream_id: &str, samples: &[f32]) -> Result<()> {
+        let window = {
+            let mut streams = self.streams.lock().unwrap();
+            let stream = streams
+                .get_mut(stream_id)
+                .ok_or_else(|| anyhow!("Dictation {} not found", stream_id))?;
+            stream.pending.extend_from_slice(samples);
+
+            if stream.busy || samples_to_ms(stream.pending.len()) < DICTATION_WINDOW_MS {
+                return Ok(());
+            }
+            stream.busy = true;
+            Self::take_window(stream)
+        };
+
+        let service = Arc::clone(self);
+        let app = app.clone();
+        let stream_id = stream_id.to_string();
+        tauri::async_runtime::spawn(async move {
+            use tauri::Manager;
+
+            let (model_id, options, samples, offset_ms) = window;
+            let segments = match service.transcribe_samples(&model_id, &samples, offset_ms, &options).await {
+                Ok(segments) => segments,
+                Err(e) => {
+                    log::warn!("Dictation {} window failed: {}", stream_id, e);
+                    Vec::new()
+                }
+            };
+
+            if let Some(stream) = service.streams.lock().unwrap().get_mut(&stream_id) {
+                stream.segments.extend(segments.iter().cloned());
+                stream.busy = false;
+            }
+            if !segments.is_empty() {
+                let _ = app.emit_all(DICTATION_EVENT, &DictationUpdate { stream_id, segments });
+            }
+        });
+
+        Ok(())
+    }
+
+    /// Finish a dictation: transcribe what is still buffered and return the full transcript
+    pub async fn stop_dictation(&self, stream_id: &str) -> Result<Transcript> {
+        // Let an in-flight window finish so segments stay in order
+        loop {
+            let busy = self
+                .streams
+                .lock()
+                .unwrap()
+                .get(stream_id)
+                .map(|s| s.busy)
+                .ok_or_else(|| anyhow!("Dictation {} not found", stream_id))?;
+            if !busy {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+
+        let mut stream = self
+            .streams
+            .lock()
+            .unwrap()
+            .remove(stream_id)
+            .ok_or_else(|| anyhow!("Dictation {} not found", stream_id))?;
+
+        let (model_id, options, samples, offset_ms) = Self::take_window(&mut stream);
+        if !samples.is_empty() {
+            let tail = self.transcribe_samples(&model_id, &samples, offset_ms, &options).await?;
+            stream.segments.extend(tail);
+        }
+
+        let transcript = Transcript {
+            id: Uuid::new_v4().to_string(),
+            model_id: stream.model_id,
+            language: stream.options.language,
+            source: "microphone".to_string(),
+            duration_ms: offset_ms + samples_to_ms(samples.len()),
+            segments: stream.segments,
+            created_at: chrono::Utc::now().to_rfc3339(),
+        };
+        self.store_transcript(&transcript);
+
+        Ok(transcript)
+    }
+
+    /// Write the timestamped transcript text next to its JSON, for document ingestion
+    pub async fn save_transcript_text(&self, transcript: &Transcript) -> Result<PathBuf> {
+        let text_path = self.work_dir.join(format!("{}.txt", transcript.id));
+        tokio::fs::write(&text_path, transcript.text_with_timestamps()).await?;
+        Ok(text_path)
+    }
+
+    pub fn get_transcript(&self, transcript_id: &str) -> Option<Transcript> {
+        self.transcripts.lock().unwrap().get(transcript_id).cloned()
+    }
+
+    /// Move the buffered audio out of a stream, with the context needed to transcribe it
+    fn take_window(stream: &mut DictationStream) -> (String, TranscriptionOptions, Vec<f32>, u64) {
+        let samples = std::mem::take(&mut stream.pending);
+        let offset_ms = stream.pending_offset_ms;
+        stream.pending_offset_ms += samples_to_ms(samples.len());
+
+        // Earlier text keeps names and spelling consistent across windows
+        let mut options = stream.options.clone();
+        let previous: String = stream.segments.iter().map(|s| s.text.as_str()).collect();
+        if !previous.is_empty() {
+            let skip = previous.chars().count().saturating_sub(DICTATION_PROMPT_CHARS);
+            options.initial_prompt = Some(previous.chars().skip(skip).collect());
+        }
+
+        (stream.model_id.clone(), options, samples, offset_ms)
+    }
+
+    async fn transcribe_samples(
+        &self,
+        model_id: &str,
+        samples: &[f32],
+        offset_ms: u64,
+        options: &TranscriptionOptions,
+    ) -> Result<Vec<TranscriptSegment>> {
+        let wav_path = self.work_dir.join(format!("{}.wav", Uuid::new_v4()));
+        tokio::fs::write(&wav_path, encode_wav(samples, WHISPER_SAMPLE_RATE)).await?;
+
+        let result = self.run_whisper(model_id, &wav_path, options).await;
+        let _ = tokio::fs::remove_file(&wav_path).await;
+
+        let mut segments = result?;
+        for segment in &mut segments {
+            segment.start_ms += offset_ms;
+            segment.end_ms += offset_ms;
+        }
+        Ok(segments)
+    }
+
+    async fn run_whisper(&self, model_id: &str, wav_path: &Path, options: &TranscriptionOptions) -> Result<Vec<TranscriptSegment>> {
+        let model_file = self.manager.installed_model_file(model_id)?;
+        let output_base = self.work_dir.join(Uuid::new_v4().to_string());
+        let threads = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(4);
+
+        let mut cmd = AsyncCommand::new(whisper_binary());
+        cmd.arg("-m")
+            .arg(&model_file)
+            .arg("-f")
+            .arg(wav_path)
+            .arg("-l")
+            .arg(options.language.as_deref().unwrap_or("auto"))
+            .arg("-t")
+            .arg(threads.to_string())
+            .arg("-oj")
+            .arg("-of")
+            .arg(&output_base)
+            .arg("-np");
+
+        if options.translate_to_english {
+            cmd.arg("-tr");
+        }
+        if let Some(prompt) = &options.initial_prompt {
+            cmd.arg("--prompt").arg(prompt);
+        }
+        // tinydiarize models mark speaker turns
+        if model_id.contains("tdrz") {
+            cmd.arg("-tdrz");
+        }
+
+        let output = cmd.output().await.context("Failed to start whisper-cli")?;
+        if !output.status.success() {
+            return Err(anyhow!(
+                "Transcription failed: {}",
+                String::from_utf8_lossy(&output.stderr).lines().last().unwrap_or("unknown error")
+            ));
+        }
+
+        let json_path = output_base.with_extension("json");
+        let json = tokio::fs::read_to_string(&json_path)
+            .await
+            .context("whisper-cli produced no transcript")?;
+        let _ = tokio::fs::remove_file(&json_path).await;
+
+        parse_whisper_json(&json)
+    }
+
+    async fn convert_to_wav(&self, audio_path: &Path) -> Result<PathBuf> {
+        let wav_path = self.work_dir.join(format!("{}.wav", Uuid::new_v4()));
+        let output = AsyncCommand::new("ffmpeg")
+            .arg("-y")
+            .arg("-i")
+            .arg(audio_path)
+            .arg("-ar")
+            .arg(WHISPER_SAMPLE_RATE.to_string())
+            .arg("-ac")
+            .arg("1")
+            .arg("-c:a")
+            .arg("pcm_s16le")
+            .arg(&wav_path)
+            .output()
+            .await
+            .context("Only WAV audio can be transcribed without ffmpeg installed")?;
+
+        if !output.status.success() {
+            return Err(anyhow!("Could not convert {:?} to WAV", audio_path));
+        }
+        Ok(wav_path)
+    }
+
+    /// Keep the transcript in memory and save it next to the dictation work files
+    fn store_transcript(&self, transcript: &Transcript) {
+        let path = self.work_dir.join(format!("{}.json", transcript.id));
+        if let Ok(json) = serde_json::to_string_pretty(transcript) {
+            if let Err(e) = std::fs::write(&path, json) {
+                log::warn!("Failed to save transcript {}: {}", transcript.id, e);
+            }
+        }
+        self.transcripts
+            .lock()
+            .unwrap()
+            .insert(transcript.id.clone(), transcript.clone());
+    }
+}
+
+/// whisper.cpp CLI, overridable for custom builds
+fn whisper_binary() -> String {
+    std::env::var("WHISPER_CLI_PATH").unwrap_or_else(|_| "whisper-cli".to_string())
+}
+
+fn samples_to_ms(samples: usize) -> u64 {
+    samples as u64 * 1000 / WHISPER_SAMPLE_RATE as u64
+}
+
+fn format_timestamp(ms: u64) -> String {
+    let seconds = ms / 1000;
+    format!("{:02}:{:02}:{:02}", seconds / 3600, (seconds / 60) % 60, seconds % 60)
+}
+
+/// 16-bit PCM mono WAV from float samples in [-1, 1]
+pub fn encode_wav(samples: &[f32], sample_rate: u32) -> Vec<u8> {
+    let data_len = (samples.len() * 2) as u32;
+    let mut wav = Vec::with_capacity(44 + data_len as usize);
+
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+    wav.extend_from_slice(b"WAVE");
+    wav.extend_from_slice(b"fmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes()); // fmt chunk size
+    wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    wav.extend_from_slice(&1u16.to_le_bytes()); // mono
+    wav.extend_from_slice(&sample_rate.to_le_bytes());
+    wav.extend_from_slice(&(sample_rate * 2).to_le_bytes()); // byte rate
+    wav.extend_from_slice(&2u16.to_le_bytes()); // block align
+    wav.extend_from_slice(&16u16.to_le_bytes()); // bits per sample
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_len.to_le_bytes());
+
+    for sample in samples {
+        let value = (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
+        wav.extend_from_slice(&value.to_le_bytes());
+    }
+
+    wav
+}
+
+/// Parse the `-oj` output of whisper-cli
+pub fn parse_whisper_json(json: &str) -> Result<Vec<TranscriptSegment>> {
+    let parsed: serde_json::Value = serde_json::from_str(json).context("Invalid whisper output")?;
+    let entries = parsed["transcription"]
+        .as_array()
+        .ok_or_else(|| anyhow!("Whisper output has no transcription"))?;
+
+    let mut speaker_turn = 0;
+    let mut segments = Vec::with_capacity(entries.len());
+    for entry in entries {
+        segments.push(TranscriptSegment {
+            start_ms: entry["offsets"]["from"].as_u64().unwrap_or(0),
+            end_ms: entry["offsets"]["to"].as_u64().unwrap_or(0),
+            text: entry["text"].as_str().unwrap_or("").trim().to_string(),
+            speaker_turn,
+        });
+        if entry["speaker_turn_next"].as_bool().unwrap_or(false) {
+            speaker_turn += 1;
+        }
+    }
+
+    Ok(segments)
+}
+
+// Tauri commands for dictation
+#[tauri::command]
+pub async fn transcribe_audio_file(
+    stt: tauri::State<'_, SpeechToTextStorage>,
+    model_id: String,
+    audio_path: String,
+    options: Option<TranscriptionOptions>,
+) -> Result<Transcript, String> {
+    stt.transcribe_file(&model_id, Path::new(&audio_path), &options.unwrap_or_default())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn start_dictation(
+    stt: tauri::State<'_, SpeechToTextStorage>,
+    model_id: String,
+    options: Option<TranscriptionOptions>,
+) -> Result<String, String> {
+    stt.start_dictation(&model_id, options.unwrap_or_default())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn push_dictation_audio(
+    app: tauri::AppHandle,
+    stt: tauri::State<'_, SpeechToTextStorage>,
+    stream_id: String,
+    samples: Vec<f32>, // 16 kHz mono
+) -> Result<(), String> {
+    stt.inner()
+        .push_audio(&app, &stream_id, &samples)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn stop_dictation(
+    stt: tauri::State<'_, SpeechToTextStorage>,
+    stream_id: String,
+) -> Result<Transcript, String> {
+    stt.stop_dictation(&stream_id).await.map_err(|e| e.to_string())
+}
+
+/// Post a transcript into a chat as a user message
+#[tauri::command]
+pub async fn send_transcript_to_chat(
+    stt: tauri::State<'_, SpeechToTextStorage>,
+    session_id: String,
+    chat_session_id: String,
+    transcript_id: String,
+    include_timestamps: Option<bool>,
+    sessions: tauri::State<'_, SessionStorage>,
+    chat_storage: tauri::State<'_, ChatStorage>,
+    message_storage: tauri::State<'_, MessageStorage>,
+) -> Result<ChatMessage, String> {
+    if !validate_session(&session_id, &sessions)? {
+        return Err("Unauthorized".to_string());
+    }
+
+    let transcript = stt
+        .get_transcript(&transcript_id)
+        .ok_or_else(|| "Transcript not found".to_string())?;
+    let content = if include_timestamps.unwrap_or(false) {
+        transcript.text_with_timestamps()
+    } else {
+        transcript.text()
+    };
+
+    let message = ChatMessage {
+        id: generate_uuid(),
+        session_id: chat_session_id,
+        content,
+        role: "user".to_string(),
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        metadata: Some(HashMap::from([
+            ("source".to_string(), "dictation".to_string()),
+            ("transcript_id".to_string(), transcript.id.clone()),
+        ])),
+        retrieval_provenance: None,
+    };
+    append_chat_message(&chat_storage, &message_storage, &session_id, message.clone());
+
+    Ok(message)
+}
+
+/// Save a transcript as a text document in the workspace
+#[tauri::command]
+pub async fn ingest_transcript_as_document(
+    stt: tauri::State<'_, SpeechToTextStorage>,
+    session_id: String,
+    transcript_id: String,
+    name: Option<String>,
+    category: Option<String>,
+    sessions: tauri::State<'_, SessionStorage>,
+    document_storage: tauri::State<'_, DocumentStorage>,
+    workspace_stats: tauri::State<'_, crate::workspace_stats::WorkspaceStatsStorage>,
+    acl: tauri::State<'_, DocumentAclStorage>,
+) -> Result<Document, String> {
+    if !validate_session(&session_id, &sessions)? {
+        return Err("Unauthorized".to_string());
+    }
+
+    let transcript = stt
+        .get_transcript(&transcript_id)
+        .ok_or_else(|| "Transcript not found".to_string())?;
+
+    let text_path = stt.save_transcript_text(&transcript).await.map_err(|e| e.to_string())?;
+    let file_size = std::fs::metadata(&text_path).map(|m| m.len()).unwrap_or(0);
+
+    let document = Document {
+        id: generate_uuid(),
+        name: name.unwrap_or_else(|| format!("Dictation {}", &transcript.created_at[..10.min(transcript.created_at.len())])),
+        category: category.unwrap_or_else(|| "dictation".to_string()),
+        file_size,
+        created_at: chrono::Utc::now().to_rfc3339(),
+        tags: vec!["dictation".to_string(), format!("transcript:{}", transcript.id)],
+        status: "uploaded".to_string(),
+        content_type: "text/plain".to_string(),
+    };
+
+    store_document(document.clone(), &session_id, &sessions, &document_storage, &acl)?;
+    workspace_stats.invalidate();
+
+    Ok(document)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_whisper_json_speaker_turns() {
+        let json = r#"{
+            "result": {"language": "en"},
+            "transcription": [
+                {"offsets": {"from": 0, "to": 2400}, "text": " Please state your name.", "speaker_turn_next": true},
+                {"offsets": {"from": 2400, "to": 4100}, "text": " Jane Doe."},
+                {"offsets": {"from": 4100, "to": 6000}, "text": " I work at Acme."}
+            ]
+        }"#;
+
+        let segments = parse_whisper_json(json).unwrap();
+        assert_eq!(segments.len(), 3);
+        assert_eq!(segments[0].speaker_turn, 0);
+        assert_eq!(segments[1].speaker_turn, 1);
+        assert_eq!(segments[2].end_ms, 6000);
+
+        let transcript = Transcript {
+            id: "t".to_string(),
+            model_id: "whisper-small-en-tdrz".to_string(),
+            language: Some("en".to_string()),
+            source: "microphone".to_string(),
+            duration_ms: 6000,
+            segments,
+            created_at: String::new(),
+        };
+        assert_eq!(transcript.text(), "Please state your name.\n\nJane Doe. I work at Acme.");
+        assert!(transcript.text_with_timestamps().starts_with("[00:00:00] Speaker 1: Please"));
+    }
+
+    #[test]
+    fn test_encode_wav_header() {
+        let wav = encode_wav(&[0.0, 1.0, -1.0], WHISPER_SAMPLE_RATE);
+        assert_eq!(&wav[0..4], b"RIFF");
+        assert_eq!(wav.len(), 44 + 6);
+        assert_eq!(i16::from_le_bytes([wav[46], wav[47]]), i16::MAX);
+    }
+}
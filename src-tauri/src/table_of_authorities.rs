@@ -0,0 +1,353 @@
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::document_analyzer::{parse_citations, CitationType, LegalCitation, REPORTER_PATTERN};
+use crate::docx_writer::{self, DocxBlock};
+use crate::local_api::AnalyzerStorage;
+
+/// Table of Authorities for BEAR AI
+/// Builds the table from the document analyzer's citation parser: every authority the brief
+/// cites is grouped as a case, statute, regulation or other authority, listed once in its full
+/// form and followed by the pages it appears on, including short-form case cites. Pages come from
+/// the page breaks in the extracted text; when the format has none, they are estimated from the
+/// word count and the table says so.
+const WORDS_PER_PAGE: usize = 275; // a double-spaced brief page
+/// Authorities cited on this many pages or more are listed "passim"
+const DEFAULT_PASSIM_PAGES: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthorityGroup {
+    Cases,
+    Statutes,
+    Regulations,
+    Other,
+}
+
+impl AuthorityGroup {
+    fn heading(&self) -> &'static str {
+        match self {
+            AuthorityGroup::Cases => "Cases",
+            AuthorityGroup::Statutes => "Statutes",
+            AuthorityGroup::Regulations => "Regulations",
+            AuthorityGroup::Other => "Other Authorities",
+        }
+    }
+
+    fn of(citation_type: &CitationType) -> Self {
+        match citation_type {
+            CitationType::CaseLaw => AuthorityGroup::Cases,
+            CitationType::Statute => AuthorityGroup::Statutes,
+            CitationType::Regulation => AuthorityGroup::Regulations,
+            _ => AuthorityGroup::Other,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthorityEntry {
+    pub authority: String,
+    pub pages: Vec<u32>,
+    pub passim: bool,
+    pub citations: usize, // full and short-form cites counted
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthorityTableGroup {
+    pub group: AuthorityGroup,
+    pub entries: Vec<AuthorityEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableOfAuthorities {
+    pub id: String,
+    pub brief_path: String,
+    pub groups: Vec<AuthorityTableGroup>,
+    pub estimated_pages: bool,
+    pub generated_at: String,
+    pub path: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableOfAuthoritiesRequest {
+    pub brief_path: String,
+    pub first_page: Option<u32>, // number printed on the first page of the text, default 1
+    pub passim_pages: Option<usize>,
+}
+
+/// Byte offsets where pages start, and whether they had to be estimated from the word count
+pub fn page_starts(text: &str) -> (Vec<usize>, bool) {
+    if text.contains('\u{c}') {
+        let mut starts = vec![0];
+        starts.extend(text.match_indices('\u{c}').map(|(i, _)| i + 1).filter(|i| *i < text.len()));
+        return (starts, false);
+    }
+    let mut starts = vec![0];
+    let mut words = 0;
+    let mut in_word = false;
+    for (i, c) in text.char_indices() {
+        if c.is_whitespace() {
+            in_word = false;
+        } else if !in_word {
+            in_word = true;
+            if words > 0 && words % WORDS_PER_PAGE == 0 {
+                starts.push(i);
+            }
+            words += 1;
+        }
+    }
+    (starts, true)
+}
+
+/// Printed page number of a byte offset, given the page starts and the first page's number
+pub fn page_of(starts: &[usize], offset: usize, first_page: u32) -> u32 {
+    starts.iter().filter(|s| **s <= offset).count().max(1) as u32 - 1 + first_page
+}
+
+fn normalize(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+/// Cases are identified by where they are reported, so later cites with a pin page still match
+fn authority_key(citation: &LegalCitation, reporter: Option<&str>) -> String {
+    match (&citation.citation_type, &citation.volume, reporter, &citation.page) {
+        (CitationType::CaseLaw, Some(volume), Some(reporter), Some(page)) => normalize(&format!("{} {} {}", volume, reporter, page)),
+        _ => normalize(&citation.citation_text),
+    }
+}
+
+/// The full citation as listed in the table, without pin cites
+fn display(citation: &LegalCitation, reporter: Option<&str>) -> String {
+    match (&citation.case_name, &citation.volume, reporter, &citation.page) {
+        (Some(name), Some(volume), Some(reporter), Some(page)) => {
+            let court = citation.court.as_deref().map(|c| format!("{} ", c)).unwrap_or_default();
+            let year = citation.year.map(|y| y.to_string()).unwrap_or_default();
+            format!("{}, {} {} {} ({}{})", name, volume, reporter, page, court, year)
+        }
+        _ => citation.citation_text.split_whitespace().collect::<Vec<_>>().join(" "),
+    }
+}
+
+/// Reporter of a parsed case citation, read back from its text
+fn reporter_of(citation: &LegalCitation, reporter: &Regex) -> Option<String> {
+    let volume = citation.volume.as_deref()?;
+    let page = citation.page.as_deref()?;
+    reporter
+        .captures_iter(&citation.citation_text)
+        .find(|c| &c[1] == volume && &c[3] == page)
+        .map(|c| c[2].to_string())
+}
+
+/// Sort key comparing the numbers in section citations by value ("§ 9" before "§ 10")
+fn natural_key(text: &str) -> String {
+    let digits = Regex::new(r"\d+").unwrap();
+    digits
+        .replace_all(&text.to_lowercase(), |c: &regex::Captures| format!("{:0>10}", &c[0]))
+        .to_string()
+}
+
+/// Group the brief's citations into the table, with the pages each authority is cited on
+pub fn build_table(text: &str, first_page: u32, passim_pages: usize) -> (Vec<AuthorityTableGroup>, bool) {
+    let (starts, estimated) = page_starts(text);
+    let reporter = Regex::new(&format!(r"(\d+)\s+({})\s+(\d+)", REPORTER_PATTERN)).unwrap();
+    let short_form = Regex::new(&format!(r"\b(\d+)\s+({})\s+at\s+\d+", REPORTER_PATTERN)).unwrap();
+
+    let mut cited: Vec<(usize, String, AuthorityGroup, Option<String>)> = parse_citations(text)
+        .into_iter()
+        .map(|(offset, citation)| {
+            let reporter = reporter_of(&citation, &reporter);
+            (
+                offset,
+                authority_key(&citation, reporter.as_deref()),
+                AuthorityGroup::of(&citation.citation_type),
+                Some(display(&citation, reporter.as_deref())),
+            )
+        })
+        .collect();
+    // "410 U.S. at 153" counts for the case reported at 410 U.S. once it has been cited in full
+    for caps in short_form.captures_iter(text) {
+        let prefix = format!("{} ", normalize(&format!("{} {}", &caps[1], &caps[2])));
+        cited.push((caps.get(0).unwrap().start(), prefix, AuthorityGroup::Cases, None));
+    }
+    cited.sort_by_key(|(offset, ..)| *offset);
+
+    let mut entries: Vec<(String, AuthorityGroup, AuthorityEntry)> = Vec::new();
+    for (offset, key, group, authority) in cited {
+        let page = page_of(&starts, offset, first_page);
+        // A short form counts for the case whose key starts with its volume and reporter
+        let existing = match authority {
+            Some(_) => entries.iter_mut().find(|(k, ..)| *k == key),
+            None => entries.iter_mut().find(|(k, g, _)| *g == AuthorityGroup::Cases && k.starts_with(&key)),
+        };
+        match (existing, authority) {
+            (Some((_, _, entry)), _) => {
+                if !entry.pages.contains(&page) {
+                    entry.pages.push(page);
+                }
+                entry.citations += 1;
+            }
+            (None, Some(authority)) => entries.push((
+                key,
+                group,
+                AuthorityEntry {
+                    authority,
+                    pages: vec![page],
+                    passim: false,
+                    citations: 1,
+                },
+            )),
+            (None, None) => {}
+        }
+    }
+
+    let mut groups: Vec<AuthorityTableGroup> = Vec::new();
+    for (_, group, mut entry) in entries {
+        entry.pages.sort_unstable();
+        entry.passim = entry.pages.len() >= passim_pages;
+        match groups.iter_mut().find(|g| g.group == group) {
+            Some(existing) => existing.entries.push(entry),
+            None => groups.push(AuthorityTableGroup { group, entries: vec![entry] }),
+        }
+    }
+    groups.sort_by_key(|g| g.group);
+    for group in &mut groups {
+        group.entries.sort_by_key(|e| natural_key(&e.authority));
+    }
+    (groups, estimated)
+}
+
+fn pages_label(entry: &AuthorityEntry) -> String {
+    if entry.passim {
+        "passim".to_string()
+    } else {
+        entry.pages.iter().map(|p| p.to_string()).collect::<Vec<_>>().join(", ")
+    }
+}
+
+fn render_blocks(table: &TableOfAuthorities) -> Vec<DocxBlock> {
+    let mut blocks = vec![DocxBlock::Title("Table of Authorities".to_string())];
+    for group in &table.groups {
+        blocks.push(DocxBlock::Heading(1, group.group.heading().to_string()));
+        blocks.push(DocxBlock::Table {
+            header: vec!["Authority".to_string(), "Page(s)".to_string()],
+            rows: group.entries.iter().map(|e| vec![e.authority.clone(), pages_label(e)]).collect(),
+        });
+    }
+    if table.estimated_pages {
+        blocks.push(DocxBlock::Paragraph(
+            "Page references are estimated from the word count because the brief's file carries no page breaks. \
+             Check them against the final PDF before filing."
+                .to_string(),
+        ));
+    }
+    blocks
+}
+
+/// Where tables of authorities are written
+pub struct AuthorityTables {
+    pub dir: PathBuf,
+}
+
+impl AuthorityTables {
+    pub fn new(app_data_dir: &Path) -> Self {
+        AuthorityTables {
+            dir: app_data_dir.join("tables_of_authorities"),
+        }
+    }
+
+    pub fn generate(&self, brief_path: &str, text: &str, request: &TableOfAuthoritiesRequest) -> Result<TableOfAuthorities> {
+        let (groups, estimated_pages) = build_table(
+            text,
+            request.first_page.unwrap_or(1),
+            request.passim_pages.unwrap_or(DEFAULT_PASSIM_PAGES).max(1),
+        );
+        if groups.is_empty() {
+            return Err(anyhow!("No citations were found in the brief"));
+        }
+        let mut table = TableOfAuthorities {
+            id: Uuid::new_v4().to_string(),
+            brief_path: brief_path.to_string(),
+            groups,
+            estimated_pages,
+            generated_at: Utc::now().to_rfc3339(),
+            path: None,
+        };
+        let path = self.dir.join(format!("table_of_authorities_{}.docx", table.id));
+        docx_writer::write_docx(&path, &render_blocks(&table))?;
+        table.path = Some(path.to_string_lossy().to_string());
+        Ok(table)
+    }
+}
+
+pub type AuthorityTableStorage = Arc<AuthorityTables>;
+
+/// Build the table of authorities for a brief and write it as DOCX
+#[tauri::command]
+pub async fn generate_table_of_authorities(
+    request: TableOfAuthoritiesRequest,
+    tables: tauri::State<'_, AuthorityTableStorage>,
+    analyzer: tauri::State<'_, AnalyzerStorage>,
+) -> Result<TableOfAuthorities, String> {
+    let text = analyzer
+        .extract_text(Path::new(&request.brief_path))
+        .await
+        .map_err(|e| e.to_string())?;
+    tables
+        .generate(&request.brief_path, &text, &request)
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_groups_authorities_with_pages() {
+        let brief = "A state may not ban abortion before viability. Roe v. Wade, 410 U.S. 113, 164 (1973); \
+                     see 42 U.S.C. § 1983.\u{c}The statute is preempted. 21 C.F.R. § 314.50(d). \
+                     Id. The Court later refined the rule. Planned Parenthood v. Casey, 505 U.S. 833 (1992).\u{c}\
+                     Roe remains instructive. 410 U.S. at 153. See Fed. R. Civ. P. 12(b)(6); 42 U.S.C. § 9.";
+        let (groups, estimated) = build_table(brief, 3, 5);
+        assert!(!estimated);
+        let group = |g: AuthorityGroup| groups.iter().find(|x| x.group == g).unwrap();
+
+        let cases = &group(AuthorityGroup::Cases).entries;
+        assert_eq!(cases[0].authority, "Planned Parenthood v. Casey, 505 U.S. 833 (1992)");
+        assert_eq!(cases[0].pages, vec![4]);
+        assert_eq!(cases[1].authority, "Roe v. Wade, 410 U.S. 113 (1973)");
+        assert_eq!((cases[1].pages.clone(), cases[1].citations), (vec![3, 5], 2));
+
+        // Sections sort by number, not as text
+        let statutes: Vec<&str> = group(AuthorityGroup::Statutes).entries.iter().map(|e| e.authority.as_str()).collect();
+        assert_eq!(statutes, vec!["42 U.S.C. § 9", "42 U.S.C. § 1983"]);
+        assert_eq!(group(AuthorityGroup::Regulations).entries[0].authority, "21 C.F.R. § 314.50(d)");
+        assert_eq!(group(AuthorityGroup::Other).entries[0].authority, "Fed. R. Civ. P. 12(b)(6)");
+        assert_eq!(groups.iter().map(|g| g.group).collect::<Vec<_>>(), vec![
+            AuthorityGroup::Cases,
+            AuthorityGroup::Statutes,
+            AuthorityGroup::Regulations,
+            AuthorityGroup::Other
+        ]);
+    }
+
+    #[test]
+    fn test_estimated_pages_and_passim() {
+        let page = "word ".repeat(WORDS_PER_PAGE);
+        let brief = (0..6).map(|_| format!("{}42 U.S.C. § 1983 ", page)).collect::<String>();
+        let (starts, estimated) = page_starts(&brief);
+        assert!(estimated);
+        assert_eq!(page_of(&starts, 0, 1), 1);
+        assert_eq!(page_of(&starts, page.len() + 20, 1), 2);
+
+        let (groups, _) = build_table(&brief, 1, DEFAULT_PASSIM_PAGES);
+        let entry = &groups[0].entries[0];
+        assert!(entry.passim);
+        assert_eq!(pages_label(entry), "passim");
+        assert_eq!(entry.citations, 6);
+    }
+}
@@ -0,0 +1,146 @@
+use rust_stemmers::{Algorithm, Stemmer};
+use std::collections::HashSet;
+use unicode_segmentation::UnicodeSegmentation;
+use whatlang::{detect, Lang};
+
+/// Language-aware Tokenization for BEAR AI
+/// Splits text on Unicode word boundaries (so "l'accord", "März" and Cyrillic words survive
+/// intact), and picks the Snowball stemmer and NLTK stop-word list matching the language the
+/// analyzer detected. Languages without a Snowball stemmer are left unstemmed.
+// Drafting modals the general-purpose English list keeps but contracts use on every line
+const ENGLISH_DRAFTING_STOP_WORDS: &[&str] = &["shall", "may", "might", "must", "could", "would"];
+
+pub struct LanguageTools {
+    stemmer: Option<Stemmer>,
+    stop_words: HashSet<&'static str>,
+}
+
+impl LanguageTools {
+    pub fn for_language(language: &str) -> Self {
+        let algorithm = match language {
+            "en" => Some(Algorithm::English),
+            "nl" => Some(Algorithm::Dutch),
+            "de" => Some(Algorithm::German),
+            "fr" => Some(Algorithm::French),
+            "es" => Some(Algorithm::Spanish),
+            "it" => Some(Algorithm::Italian),
+            "pt" => Some(Algorithm::Portuguese),
+            "ru" => Some(Algorithm::Russian),
+            _ => None,
+        };
+
+        // The stop-word lists cover exactly the Snowball languages
+        let mut stop_words: HashSet<&'static str> = match algorithm {
+            Some(_) => stop_words::get(language).iter().copied().collect(),
+            None => HashSet::new(),
+        };
+        if language == "en" {
+            stop_words.extend(ENGLISH_DRAFTING_STOP_WORDS);
+        }
+
+        LanguageTools {
+            stemmer: algorithm.map(Stemmer::create),
+            stop_words,
+        }
+    }
+
+    /// Stem a lowercase word; unchanged when the language has no stemmer
+    pub fn stem(&self, word: &str) -> String {
+        match &self.stemmer {
+            Some(stemmer) => stemmer.stem(word).into_owned(),
+            None => word.to_string(),
+        }
+    }
+
+    pub fn is_stop_word(&self, word: &str) -> bool {
+        self.stop_words.contains(word.to_lowercase().as_str())
+    }
+}
+
+/// Two-letter code of the detected language; English when undetected or unsupported
+pub fn detect_language(text: &str) -> String {
+    let code = match detect(text).map(|info| info.lang()) {
+        Some(Lang::Spa) => "es",
+        Some(Lang::Fra) => "fr",
+        Some(Lang::Deu) => "de",
+        Some(Lang::Ita) => "it",
+        Some(Lang::Nld) => "nl",
+        Some(Lang::Por) => "pt",
+        Some(Lang::Rus) => "ru",
+        Some(Lang::Jpn) => "ja",
+        Some(Lang::Kor) => "ko",
+        Some(Lang::Cmn) => "zh",
+        _ => "en",
+    };
+    code.to_string()
+}
+
+/// Lowercase words on Unicode word boundaries, without punctuation or numbers
+pub fn words(text: &str) -> Vec<String> {
+    text.unicode_words()
+        .filter(|word| word.chars().any(char::is_alphabetic))
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Words containing a letter; CJK text counts one word per character, as UAX #29 segments it
+pub fn word_count(text: &str) -> u32 {
+    text.unicode_words()
+        .filter(|word| word.chars().any(char::is_alphabetic))
+        .count() as u32
+}
+
+/// The first `max` characters of `text`, cut on a character boundary
+pub fn truncate_chars(text: &str, max: usize) -> &str {
+    match text.char_indices().nth(max) {
+        Some((end, _)) => &text[..end],
+        None => text,
+    }
+}
+
+/// `text` cut to `max` characters, with an ellipsis when anything was cut
+pub fn elide_chars(text: &str, max: usize) -> String {
+    let kept = truncate_chars(text, max);
+    if kept.len() < text.len() {
+        format!("{}…", kept)
+    } else {
+        kept.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unicode_words() {
+        assert_eq!(words("Der Vertrag endet am 1. März."), vec!["der", "vertrag", "endet", "am", "märz"]);
+        assert_eq!(words("Договор расторгнут"), vec!["договор", "расторгнут"]);
+        assert_eq!(word_count("This is a test document with multiple words."), 8);
+        assert_eq!(word_count("合同终止"), 4);
+    }
+
+    #[test]
+    fn test_stop_words_per_language() {
+        let dutch = LanguageTools::for_language("nl");
+        assert!(dutch.is_stop_word("het"));
+        assert!(!dutch.is_stop_word("overeenkomst"));
+
+        let english = LanguageTools::for_language("en");
+        assert!(english.is_stop_word("The"));
+        assert!(english.is_stop_word("shall"));
+        assert!(!english.is_stop_word("het"));
+
+        let japanese = LanguageTools::for_language("ja");
+        assert!(!japanese.is_stop_word("the"));
+        assert_eq!(japanese.stem("契約"), "契約");
+    }
+
+    #[test]
+    fn test_truncation_keeps_whole_characters() {
+        assert_eq!(truncate_chars("März 2026", 3), "Mär");
+        assert_eq!(truncate_chars("short", 10), "short");
+        assert_eq!(elide_chars("合同终止", 2), "合同…");
+        assert_eq!(elide_chars("合同", 2), "合同");
+    }
+}
@@ -0,0 +1,667 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+use crate::enterprise_management::BarrierStorage;
+use crate::local_api::{authenticated_user, SessionStorage};
+use crate::security::SecurityManager;
+
+/// Timekeeping for BEAR AI
+/// Captures time per matter from focus signals sent by the UI while a chat or document is
+/// active, accepts manual entries, writes narratives from the recorded activity and exports
+/// entries as LEDES 1998B or CSV for billing systems.
+///
+/// Focus signals are heartbeats: a gap longer than IDLE_GAP_SECS ends the running segment,
+/// and idle time is never counted.
+const IDLE_GAP_SECS: i64 = 5 * 60;
+const MIN_CAPTURED_SECS: i64 = 60;
+const BILLING_INCREMENT_HOURS: f64 = 0.1;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FocusKind {
+    Chat,
+    Document,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ActivityKind {
+    ChatFocus,
+    DocumentReviewed,
+    AnalysisRun,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Activity {
+    pub kind: ActivityKind,
+    pub subject: String, // document or chat name, or "<analysis type> on <document>"
+    pub at: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EntrySource {
+    Captured,
+    Manual,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimeEntry {
+    pub id: String,
+    pub matter_id: String,
+    pub date: NaiveDate,
+    pub started_at: Option<String>,
+    pub duration_secs: i64,
+    pub source: EntrySource,
+    pub activities: Vec<Activity>,
+    pub narrative: String,
+    pub task_code: Option<String>,     // UTBMS task code, e.g. "L120"
+    pub activity_code: Option<String>, // UTBMS activity code, e.g. "A104"
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimekeeperProfile {
+    pub timekeeper_id: String,
+    pub name: String,
+    pub classification: String, // LEDES classification, e.g. "PT" (partner), "AS" (associate)
+    pub hourly_rate: f64,
+    pub law_firm_id: String,
+}
+
+impl Default for TimekeeperProfile {
+    fn default() -> Self {
+        Self {
+            timekeeper_id: "TK1".to_string(),
+            name: String::new(),
+            classification: "AS".to_string(),
+            hourly_rate: 0.0,
+            law_firm_id: String::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InvoiceDetails {
+    pub invoice_number: String,
+    pub invoice_date: NaiveDate,
+    pub client_id: String,
+    pub client_matter_id: Option<String>,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    Ledes,
+    Csv,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct OpenSegment {
+    matter_id: String,
+    started_at: DateTime<Utc>,
+    last_seen: DateTime<Utc>,
+    activities: Vec<Activity>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct TimekeepingState {
+    profile: TimekeeperProfile,
+    entries: Vec<TimeEntry>,
+    open: Option<OpenSegment>,
+}
+
+/// Hours billed for a duration, rounded up to the next tenth of an hour
+pub fn billable_hours(duration_secs: i64) -> f64 {
+    let tenths = (duration_secs.max(0) as f64 / 3600.0 / BILLING_INCREMENT_HOURS).ceil();
+    (tenths * BILLING_INCREMENT_HOURS * 10.0).round() / 10.0
+}
+
+fn join_subjects(subjects: &[&str]) -> String {
+    match subjects {
+        [] => String::new(),
+        [one] => one.to_string(),
+        [init @ .., last] => format!("{} and {}", init.join(", "), last),
+    }
+}
+
+/// Describe a segment's work from its activities, e.g.
+/// "Reviewed lease.pdf and nda.docx; ran risk assessment on lease.pdf; research in Deal chat."
+pub fn build_narrative(activities: &[Activity]) -> String {
+    let subjects = |kind: ActivityKind| {
+        let mut subjects: Vec<&str> = Vec::new();
+        for activity in activities.iter().filter(|a| a.kind == kind) {
+            if !subjects.contains(&activity.subject.as_str()) {
+                subjects.push(&activity.subject);
+            }
+        }
+        subjects
+    };
+
+    let mut parts = Vec::new();
+    let reviewed = subjects(ActivityKind::DocumentReviewed);
+    if !reviewed.is_empty() {
+        parts.push(format!("reviewed {}", join_subjects(&reviewed)));
+    }
+    let analyses = subjects(ActivityKind::AnalysisRun);
+    if !analyses.is_empty() {
+        parts.push(format!("ran {}", join_subjects(&analyses)));
+    }
+    let chats = subjects(ActivityKind::ChatFocus);
+    if !chats.is_empty() {
+        parts.push(format!("research in {}", join_subjects(&chats)));
+    }
+
+    if parts.is_empty() {
+        return "Work on matter.".to_string();
+    }
+    let narrative = parts.join("; ") + ".";
+    let mut chars = narrative.chars();
+    chars
+        .next()
+        .map(|c| c.to_uppercase().collect::<String>() + chars.as_str())
+        .unwrap_or_default()
+}
+
+// LEDES fields are pipe-delimited and records end in "[]"
+fn ledes_field(value: &str) -> String {
+    value.replace(['|', '\r', '\n'], " ").replace("[]", "")
+}
+
+/// LEDES 1998B invoice for one matter's fee entries
+pub fn render_ledes(entries: &[TimeEntry], profile: &TimekeeperProfile, invoice: &InvoiceDetails) -> String {
+    const HEADER: &str = "INVOICE_DATE|INVOICE_NUMBER|CLIENT_ID|LAW_FIRM_MATTER_ID|INVOICE_TOTAL|BILLING_START_DATE|BILLING_END_DATE|INVOICE_DESCRIPTION|LINE_ITEM_NUMBER|EXP/FEE/INV_ADJ_TYPE|LINE_ITEM_NUMBER_OF_UNITS|LINE_ITEM_ADJUSTMENT_AMOUNT|LINE_ITEM_TOTAL|LINE_ITEM_DATE|LINE_ITEM_TASK_CODE|LINE_ITEM_EXPENSE_CODE|LINE_ITEM_ACTIVITY_CODE|TIMEKEEPER_ID|LINE_ITEM_DESCRIPTION|LAW_FIRM_ID|LINE_ITEM_UNIT_COST|TIMEKEEPER_NAME|TIMEKEEPER_CLASSIFICATION|CLIENT_MATTER_ID[]";
+
+    let line_total = |e: &TimeEntry| billable_hours(e.duration_secs) * profile.hourly_rate;
+    let invoice_total: f64 = entries.iter().map(line_total).sum();
+    let start = entries.iter().map(|e| e.date).min().unwrap_or(invoice.invoice_date);
+    let end = entries.iter().map(|e| e.date).max().unwrap_or(invoice.invoice_date);
+
+    let mut lines = vec!["LEDES1998B[]".to_string(), HEADER.to_string()];
+    for (index, entry) in entries.iter().enumerate() {
+        let fields = [
+            invoice.invoice_date.format("%Y%m%d").to_string(),
+            ledes_field(&invoice.invoice_number),
+            ledes_field(&invoice.client_id),
+            ledes_field(&entry.matter_id),
+            format!("{:.2}", invoice_total),
+            start.format("%Y%m%d").to_string(),
+            end.format("%Y%m%d").to_string(),
+            ledes_field(invoice.description.as_deref().unwrap_or("Legal services")),
+            (index + 1).to_string(),
+            "F".to_string(),
+            format!("{:.1}", billable_hours(entry.duration_secs)),
+            "0.00".to_string(),
+            format!("{:.2}", line_total(entry)),
+            entry.date.format("%Y%m%d").to_string(),
+            ledes_field(entry.task_code.as_deref().unwrap_or_default()),
+            String::new(),
+            ledes_field(entry.activity_code.as_deref().unwrap_or_default()),
+            ledes_field(&profile.timekeeper_id),
+            ledes_field(&entry.narrative),
+            ledes_field(&profile.law_firm_id),
+            format!("{:.2}", profile.hourly_rate),
+            ledes_field(&profile.name),
+            ledes_field(&profile.classification),
+            ledes_field(invoice.client_matter_id.as_deref().unwrap_or(&entry.matter_id)),
+        ];
+        lines.push(format!("{}[]", fields.join("|")));
+    }
+    lines.join("\r\n") + "\r\n"
+}
+
+fn render_csv(entries: &[TimeEntry], profile: &TimekeeperProfile) -> Result<String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record([
+        "date", "matter_id", "timekeeper", "hours", "rate", "amount", "task_code", "activity_code", "source", "narrative",
+    ])?;
+    for entry in entries {
+        let hours = billable_hours(entry.duration_secs);
+        writer.write_record([
+            entry.date.to_string(),
+            entry.matter_id.clone(),
+            profile.timekeeper_id.clone(),
+            format!("{:.1}", hours),
+            format!("{:.2}", profile.hourly_rate),
+            format!("{:.2}", hours * profile.hourly_rate),
+            entry.task_code.clone().unwrap_or_default(),
+            entry.activity_code.clone().unwrap_or_default(),
+            format!("{:?}", entry.source).to_lowercase(),
+            entry.narrative.clone(),
+        ])?;
+    }
+    Ok(String::from_utf8(writer.into_inner()?)?)
+}
+
+pub struct Timekeeper {
+    path: PathBuf,
+    state: Mutex<TimekeepingState>,
+}
+
+impl Timekeeper {
+    pub fn new(app_data_dir: &Path) -> Result<Self> {
+        let path = app_data_dir.join("timekeeping.json");
+        let state = if path.exists() {
+            serde_json::from_str(&fs::read_to_string(&path)?)?
+        } else {
+            TimekeepingState::default()
+        };
+
+        Ok(Self {
+            path,
+            state: Mutex::new(state),
+        })
+    }
+
+    fn persist(&self, state: &TimekeepingState) -> Result<()> {
+        fs::write(&self.path, serde_json::to_string_pretty(state)?)?;
+        Ok(())
+    }
+
+    /// Turn the open segment into an entry if it is long enough to bill
+    fn close_segment(state: &mut TimekeepingState, ended_at: DateTime<Utc>) -> Option<TimeEntry> {
+        let segment = state.open.take()?;
+        let duration_secs = (ended_at - segment.started_at).num_seconds();
+        if duration_secs < MIN_CAPTURED_SECS {
+            return None;
+        }
+
+        let entry = TimeEntry {
+            id: Uuid::new_v4().to_string(),
+            matter_id: segment.matter_id,
+            date: segment.started_at.date_naive(),
+            started_at: Some(segment.started_at.to_rfc3339()),
+            duration_secs,
+            source: EntrySource::Captured,
+            narrative: build_narrative(&segment.activities),
+            activities: segment.activities,
+            task_code: None,
+            activity_code: None,
+            created_at: Utc::now().to_rfc3339(),
+        };
+        state.entries.push(entry.clone());
+        Some(entry)
+    }
+
+    /// Record a focus heartbeat for a chat or document on a matter
+    pub fn focus(&self, matter_id: &str, kind: FocusKind, subject: &str) -> Result<Option<TimeEntry>> {
+        let now = Utc::now();
+        let mut state = self.state.lock().unwrap();
+
+        let mut closed = None;
+        let continues = state
+            .open
+            .as_ref()
+            .map_or(false, |s| s.matter_id == matter_id && (now - s.last_seen).num_seconds() <= IDLE_GAP_SECS);
+        if !continues {
+            if let Some(last_seen) = state.open.as_ref().map(|s| s.last_seen) {
+                closed = Self::close_segment(&mut state, last_seen);
+            }
+            state.open = Some(OpenSegment {
+                matter_id: matter_id.to_string(),
+                started_at: now,
+                last_seen: now,
+                activities: Vec::new(),
+            });
+        }
+
+        let segment = state.open.as_mut().expect("segment opened above");
+        segment.last_seen = now;
+        let kind = match kind {
+            FocusKind::Chat => ActivityKind::ChatFocus,
+            FocusKind::Document => ActivityKind::DocumentReviewed,
+        };
+        if !segment.activities.iter().any(|a| a.kind == kind && a.subject == subject) {
+            segment.activities.push(Activity {
+                kind,
+                subject: subject.to_string(),
+                at: now.to_rfc3339(),
+            });
+        }
+
+        self.persist(&state)?;
+        Ok(closed)
+    }
+
+    /// The UI lost focus; time up to now counts unless the user had already gone idle
+    pub fn blur(&self) -> Result<Option<TimeEntry>> {
+        let now = Utc::now();
+        let mut state = self.state.lock().unwrap();
+        let Some(last_seen) = state.open.as_ref().map(|s| s.last_seen) else {
+            return Ok(None);
+        };
+        let ended_at = if (now - last_seen).num_seconds() <= IDLE_GAP_SECS { now } else { last_seen };
+        let closed = Self::close_segment(&mut state, ended_at);
+        self.persist(&state)?;
+        Ok(closed)
+    }
+
+    /// Attach an activity (e.g. an analysis run) on a matter to the segment being captured, if
+    /// that segment is for the same matter
+    pub fn note_activity(&self, matter_id: &str, kind: ActivityKind, subject: &str) {
+        let mut state = self.state.lock().unwrap();
+        let Some(segment) = state.open.as_mut().filter(|s| s.matter_id == matter_id) else {
+            return;
+        };
+        segment.activities.push(Activity {
+            kind,
+            subject: subject.to_string(),
+            at: Utc::now().to_rfc3339(),
+        });
+        if let Err(e) = self.persist(&state) {
+            log::warn!("Failed to save timekeeping activity: {}", e);
+        }
+    }
+
+    pub fn add_manual_entry(
+        &self,
+        matter_id: &str,
+        date: NaiveDate,
+        minutes: i64,
+        narrative: &str,
+        task_code: Option<String>,
+        activity_code: Option<String>,
+    ) -> Result<TimeEntry> {
+        if minutes <= 0 {
+            return Err(anyhow!("Duration must be positive"));
+        }
+
+        let entry = TimeEntry {
+            id: Uuid::new_v4().to_string(),
+            matter_id: matter_id.to_string(),
+            date,
+            started_at: None,
+            duration_secs: minutes * 60,
+            source: EntrySource::Manual,
+            activities: Vec::new(),
+            narrative: narrative.to_string(),
+            task_code,
+            activity_code,
+            created_at: Utc::now().to_rfc3339(),
+        };
+
+        let mut state = self.state.lock().unwrap();
+        state.entries.push(entry.clone());
+        self.persist(&state)?;
+        Ok(entry)
+    }
+
+    pub fn update_entry(
+        &self,
+        entry_id: &str,
+        narrative: Option<String>,
+        minutes: Option<i64>,
+        task_code: Option<String>,
+        activity_code: Option<String>,
+    ) -> Result<TimeEntry> {
+        let mut state = self.state.lock().unwrap();
+        let entry = state
+            .entries
+            .iter_mut()
+            .find(|e| e.id == entry_id)
+            .ok_or_else(|| anyhow!("Time entry {} not found", entry_id))?;
+
+        if let Some(narrative) = narrative {
+            entry.narrative = narrative;
+        }
+        if let Some(minutes) = minutes {
+            if minutes <= 0 {
+                return Err(anyhow!("Duration must be positive"));
+            }
+            entry.duration_secs = minutes * 60;
+        }
+        if task_code.is_some() {
+            entry.task_code = task_code;
+        }
+        if activity_code.is_some() {
+            entry.activity_code = activity_code;
+        }
+
+        let entry = entry.clone();
+        self.persist(&state)?;
+        Ok(entry)
+    }
+
+    pub fn delete_entry(&self, entry_id: &str) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        let before = state.entries.len();
+        state.entries.retain(|e| e.id != entry_id);
+        if state.entries.len() == before {
+            return Err(anyhow!("Time entry {} not found", entry_id));
+        }
+        self.persist(&state)
+    }
+
+    pub fn entries(&self, matter_id: Option<&str>, from: Option<NaiveDate>, to: Option<NaiveDate>) -> Vec<TimeEntry> {
+        let mut entries: Vec<TimeEntry> = self
+            .state
+            .lock()
+            .unwrap()
+            .entries
+            .iter()
+            .filter(|e| matter_id.map_or(true, |m| e.matter_id == m))
+            .filter(|e| from.map_or(true, |d| e.date >= d) && to.map_or(true, |d| e.date <= d))
+            .cloned()
+            .collect();
+        entries.sort_by(|a, b| a.date.cmp(&b.date).then_with(|| a.created_at.cmp(&b.created_at)));
+        entries
+    }
+
+    pub fn profile(&self) -> TimekeeperProfile {
+        self.state.lock().unwrap().profile.clone()
+    }
+
+    pub fn set_profile(&self, profile: TimekeeperProfile) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        state.profile = profile;
+        self.persist(&state)
+    }
+}
+
+pub type TimekeeperStorage = Arc<Timekeeper>;
+
+#[tauri::command]
+pub async fn timekeeping_focus(
+    matter_id: String,
+    kind: FocusKind,
+    subject: String,
+    timekeeper: tauri::State<'_, TimekeeperStorage>,
+) -> Result<Option<TimeEntry>, String> {
+    timekeeper.focus(&matter_id, kind, &subject).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn timekeeping_blur(
+    timekeeper: tauri::State<'_, TimekeeperStorage>,
+) -> Result<Option<TimeEntry>, String> {
+    timekeeper.blur().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn timekeeping_add_entry(
+    matter_id: String,
+    date: NaiveDate,
+    minutes: i64,
+    narrative: String,
+    task_code: Option<String>,
+    activity_code: Option<String>,
+    timekeeper: tauri::State<'_, TimekeeperStorage>,
+) -> Result<TimeEntry, String> {
+    timekeeper
+        .add_manual_entry(&matter_id, date, minutes, &narrative, task_code, activity_code)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn timekeeping_update_entry(
+    entry_id: String,
+    narrative: Option<String>,
+    minutes: Option<i64>,
+    task_code: Option<String>,
+    activity_code: Option<String>,
+    timekeeper: tauri::State<'_, TimekeeperStorage>,
+) -> Result<TimeEntry, String> {
+    timekeeper
+        .update_entry(&entry_id, narrative, minutes, task_code, activity_code)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn timekeeping_delete_entry(
+    entry_id: String,
+    timekeeper: tauri::State<'_, TimekeeperStorage>,
+) -> Result<(), String> {
+    timekeeper.delete_entry(&entry_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn timekeeping_list_entries(
+    matter_id: Option<String>,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    timekeeper: tauri::State<'_, TimekeeperStorage>,
+) -> Result<Vec<TimeEntry>, String> {
+    Ok(timekeeper.entries(matter_id.as_deref(), from, to))
+}
+
+#[tauri::command]
+pub async fn timekeeping_get_profile(
+    timekeeper: tauri::State<'_, TimekeeperStorage>,
+) -> Result<TimekeeperProfile, String> {
+    Ok(timekeeper.profile())
+}
+
+#[tauri::command]
+pub async fn timekeeping_set_profile(
+    profile: TimekeeperProfile,
+    timekeeper: tauri::State<'_, TimekeeperStorage>,
+) -> Result<(), String> {
+    timekeeper.set_profile(profile).map_err(|e| e.to_string())
+}
+
+/// Export one matter's entries in the period; returns the number of entries written
+#[tauri::command]
+pub async fn timekeeping_export(
+    session_id: String,
+    matter_id: String,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    format: ExportFormat,
+    invoice: Option<InvoiceDetails>,
+    output_path: String,
+    timekeeper: tauri::State<'_, TimekeeperStorage>,
+    sessions: tauri::State<'_, SessionStorage>,
+    barriers: tauri::State<'_, BarrierStorage>,
+    security: tauri::State<'_, Arc<Mutex<SecurityManager>>>,
+) -> Result<usize, String> {
+    // Narratives name the matter's documents, so a barrier around the matter covers the export
+    let user = authenticated_user(&session_id, &sessions)?;
+    let items = [(format!("timekeeping:{}", matter_id), Some(matter_id.clone()))];
+    barriers.check_export(&security, &user, &items)?;
+    let entries = timekeeper.entries(Some(&matter_id), from, to);
+    let profile = timekeeper.profile();
+
+    let content = match format {
+        ExportFormat::Ledes => {
+            let invoice = invoice.ok_or_else(|| "Invoice details are required for LEDES export".to_string())?;
+            render_ledes(&entries, &profile, &invoice)
+        }
+        ExportFormat::Csv => render_csv(&entries, &profile).map_err(|e| e.to_string())?,
+    };
+    fs::write(&output_path, content).map_err(|e| e.to_string())?;
+    Ok(entries.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn activity(kind: ActivityKind, subject: &str) -> Activity {
+        Activity {
+            kind,
+            subject: subject.to_string(),
+            at: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_build_narrative_and_billable_hours() {
+        let activities = vec![
+            activity(ActivityKind::DocumentReviewed, "lease.pdf"),
+            activity(ActivityKind::ChatFocus, "Deal chat"),
+            activity(ActivityKind::DocumentReviewed, "nda.docx"),
+            activity(ActivityKind::AnalysisRun, "risk assessment on lease.pdf"),
+            activity(ActivityKind::DocumentReviewed, "lease.pdf"),
+        ];
+        assert_eq!(
+            build_narrative(&activities),
+            "Reviewed lease.pdf and nda.docx; ran risk assessment on lease.pdf; research in Deal chat."
+        );
+        assert_eq!(build_narrative(&[]), "Work on matter.");
+
+        assert_eq!(billable_hours(60), 0.1);
+        assert_eq!(billable_hours(360), 0.1);
+        assert_eq!(billable_hours(361), 0.2);
+        assert_eq!(billable_hours(5400), 1.5);
+    }
+
+    #[test]
+    fn test_note_activity_only_joins_a_segment_on_the_same_matter() {
+        let dir = tempfile::tempdir().unwrap();
+        let timekeeper = Timekeeper::new(dir.path()).unwrap();
+        timekeeper.focus("M-100", FocusKind::Document, "lease.pdf").unwrap();
+
+        timekeeper.note_activity("M-200", ActivityKind::AnalysisRun, "risk assessment on nda.docx");
+        timekeeper.note_activity("M-100", ActivityKind::AnalysisRun, "risk assessment on lease.pdf");
+
+        let state = timekeeper.state.lock().unwrap();
+        let subjects: Vec<&str> = state.open.as_ref().unwrap().activities.iter().map(|a| a.subject.as_str()).collect();
+        assert_eq!(subjects, vec!["lease.pdf", "risk assessment on lease.pdf"]);
+    }
+
+    #[test]
+    fn test_render_ledes() {
+        let entry = TimeEntry {
+            id: "1".to_string(),
+            matter_id: "M-100".to_string(),
+            date: NaiveDate::from_ymd_opt(2025, 3, 4).unwrap(),
+            started_at: None,
+            duration_secs: 1800,
+            source: EntrySource::Manual,
+            activities: Vec::new(),
+            narrative: "Reviewed lease | drafted notes".to_string(),
+            task_code: Some("L120".to_string()),
+            activity_code: Some("A104".to_string()),
+            created_at: String::new(),
+        };
+        let profile = TimekeeperProfile {
+            timekeeper_id: "JD".to_string(),
+            name: "Doe, Jane".to_string(),
+            classification: "PT".to_string(),
+            hourly_rate: 300.0,
+            law_firm_id: "12-3456789".to_string(),
+        };
+        let invoice = InvoiceDetails {
+            invoice_number: "INV-7".to_string(),
+            invoice_date: NaiveDate::from_ymd_opt(2025, 3, 31).unwrap(),
+            client_id: "ACME".to_string(),
+            client_matter_id: None,
+            description: None,
+        };
+
+        let ledes = render_ledes(&[entry], &profile, &invoice);
+        let lines: Vec<&str> = ledes.lines().collect();
+        assert_eq!(lines[0], "LEDES1998B[]");
+        assert_eq!(lines[1].split('|').count(), 24);
+        assert_eq!(
+            lines[2],
+            "20250331|INV-7|ACME|M-100|150.00|20250304|20250304|Legal services|1|F|0.5|0.00|150.00|20250304|L120||A104|JD|Reviewed lease   drafted notes|12-3456789|300.00|Doe, Jane|PT|M-100[]"
+        );
+    }
+}
@@ -0,0 +1,498 @@
+use anyhow::{anyhow, Result};
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::collections::VecDeque;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+use crate::security::SecurityManager;
+
+/// Outbound webhooks for BEAR AI
+/// Pushes internal events to endpoints registered by the firm. Payloads are signed with
+/// HMAC-SHA256 over "<timestamp>.<body>" and carry identifiers and counts, never document text.
+/// Signing secrets are stored encrypted and shown in full only when an endpoint is registered.
+pub const EVENT_ANALYSIS_COMPLETED: &str = "analysis.completed";
+pub const EVENT_HIGH_RISK_CONTRACT: &str = "contract.high_risk_detected";
+pub const EVENT_RETENTION_PURGE: &str = "retention.purge_executed";
+
+pub const KNOWN_EVENTS: &[&str] = &[EVENT_ANALYSIS_COMPLETED, EVENT_HIGH_RISK_CONTRACT, EVENT_RETENTION_PURGE];
+
+/// Delivery attempts per event and endpoint, including the first
+const MAX_ATTEMPTS: u32 = 5;
+const INITIAL_BACKOFF: Duration = Duration::from_secs(2);
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+const MAX_DELIVERY_LOG: usize = 500;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookEndpoint {
+    pub id: String,
+    pub url: String,
+    pub events: Vec<String>, // empty subscribes to every event
+    pub secret: String, // in full on registration, redacted to its last characters afterwards
+    pub enabled: bool,
+    pub description: Option<String>,
+    pub created_at: String,
+}
+
+impl WebhookEndpoint {
+    fn subscribes_to(&self, event_type: &str) -> bool {
+        self.enabled && (self.events.is_empty() || self.events.iter().any(|e| e == event_type))
+    }
+}
+
+// An endpoint as kept in webhooks.json; files from before encryption hold the secret in the clear
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredEndpoint {
+    #[serde(flatten)]
+    endpoint: WebhookEndpoint,
+    #[serde(default)]
+    secret_encrypted: String, // base64 of the SecurityManager ciphertext of the signing secret
+}
+
+/// "whsec_…a1b2" for a secret ending in a1b2
+fn redact_secret(secret: &str) -> String {
+    let tail: String = secret.chars().rev().take(4).collect::<Vec<_>>().into_iter().rev().collect();
+    format!("whsec_…{}", tail)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookEvent {
+    pub id: String,
+    pub event_type: String,
+    pub created_at: String,
+    pub data: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeliveryRecord {
+    pub id: String,
+    pub endpoint_id: String,
+    pub event_id: String,
+    pub event_type: String,
+    pub attempt: u32,
+    pub status_code: Option<u16>,
+    pub success: bool,
+    pub error: Option<String>,
+    pub duration_ms: u64,
+    pub attempted_at: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct WebhookState {
+    endpoints: Vec<StoredEndpoint>,
+    deliveries: VecDeque<DeliveryRecord>,
+}
+
+type SecurityStorage = Arc<Mutex<SecurityManager>>;
+
+pub struct WebhookPublisher {
+    path: PathBuf,
+    state: Mutex<WebhookState>,
+    client: reqwest::Client,
+    security: SecurityStorage,
+}
+
+/// Value of the `X-Bear-Signature` header: "t=<unix seconds>,v1=<hex hmac>"
+pub fn sign_payload(secret: &str, timestamp: i64, body: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(format!("{}.{}", timestamp, body).as_bytes());
+    format!("t={},v1={}", timestamp, hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Wait before retry `attempt` (1-based): 2s, 4s, 8s, ...
+fn backoff(attempt: u32) -> Duration {
+    INITIAL_BACKOFF * 2u32.pow(attempt.saturating_sub(1).min(6))
+}
+
+/// Server errors, rate limiting and timeouts are retried; other client errors are final
+fn is_retryable(status: Option<u16>) -> bool {
+    match status {
+        None => true,
+        Some(code) => code == 408 || code == 429 || code >= 500,
+    }
+}
+
+impl WebhookPublisher {
+    pub fn new(app_data_dir: &Path, security: SecurityStorage) -> Result<Self> {
+        let path = app_data_dir.join("webhooks.json");
+        let state = if path.exists() {
+            serde_json::from_str(&fs::read_to_string(&path)?)?
+        } else {
+            WebhookState::default()
+        };
+
+        let publisher = Self {
+            path,
+            state: Mutex::new(state),
+            client: reqwest::Client::builder().timeout(DELIVERY_TIMEOUT).build()?,
+            security,
+        };
+
+        // Encrypt secrets saved in the clear by earlier versions
+        let mut state = publisher.state.lock().unwrap();
+        let mut migrated = false;
+        for stored in state.endpoints.iter_mut().filter(|e| e.secret_encrypted.is_empty()) {
+            stored.secret_encrypted = publisher.encrypt_secret(&stored.endpoint.secret)?;
+            stored.endpoint.secret = redact_secret(&stored.endpoint.secret);
+            migrated = true;
+        }
+        if migrated {
+            publisher.persist(&state)?;
+        }
+        drop(state);
+        Ok(publisher)
+    }
+
+    fn persist(&self, state: &WebhookState) -> Result<()> {
+        fs::write(&self.path, serde_json::to_string_pretty(state)?)?;
+        Ok(())
+    }
+
+    fn encrypt_secret(&self, secret: &str) -> Result<String> {
+        let ciphertext = self.security.lock().unwrap().encrypt_data(secret.as_bytes())?;
+        Ok(base64::engine::general_purpose::STANDARD.encode(ciphertext))
+    }
+
+    fn signing_secret(&self, stored: &StoredEndpoint) -> Result<String> {
+        let ciphertext = base64::engine::general_purpose::STANDARD.decode(&stored.secret_encrypted)?;
+        Ok(String::from_utf8(self.security.lock().unwrap().decrypt_data(&ciphertext)?)?)
+    }
+
+    pub fn register_endpoint(&self, url: &str, events: Vec<String>, description: Option<String>) -> Result<WebhookEndpoint> {
+        let parsed = reqwest::Url::parse(url).map_err(|e| anyhow!("Invalid webhook URL: {}", e))?;
+        let local = matches!(parsed.host_str(), Some("localhost") | Some("127.0.0.1"));
+        if parsed.scheme() != "https" && !local {
+            return Err(anyhow!("Webhook URLs must use https"));
+        }
+        if let Some(unknown) = events.iter().find(|e| !KNOWN_EVENTS.contains(&e.as_str())) {
+            return Err(anyhow!("Unknown event type: {}", unknown));
+        }
+
+        let secret = format!("whsec_{}", hex::encode(rand::random::<[u8; 24]>()));
+        let endpoint = WebhookEndpoint {
+            id: Uuid::new_v4().to_string(),
+            url: url.to_string(),
+            events,
+            secret: redact_secret(&secret),
+            enabled: true,
+            description,
+            created_at: chrono::Utc::now().to_rfc3339(),
+        };
+        let stored = StoredEndpoint {
+            endpoint: endpoint.clone(),
+            secret_encrypted: self.encrypt_secret(&secret)?,
+        };
+
+        let mut state = self.state.lock().unwrap();
+        state.endpoints.push(stored);
+        self.persist(&state)?;
+        // The only time the secret is handed out; receivers need it to check signatures
+        Ok(WebhookEndpoint { secret, ..endpoint })
+    }
+
+    pub fn remove_endpoint(&self, endpoint_id: &str) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        let before = state.endpoints.len();
+        state.endpoints.retain(|e| e.endpoint.id != endpoint_id);
+        if state.endpoints.len() == before {
+            return Err(anyhow!("Webhook endpoint {} not found", endpoint_id));
+        }
+        self.persist(&state)
+    }
+
+    pub fn set_endpoint_enabled(&self, endpoint_id: &str, enabled: bool) -> Result<WebhookEndpoint> {
+        let mut state = self.state.lock().unwrap();
+        let stored = state
+            .endpoints
+            .iter_mut()
+            .find(|e| e.endpoint.id == endpoint_id)
+            .ok_or_else(|| anyhow!("Webhook endpoint {} not found", endpoint_id))?;
+        stored.endpoint.enabled = enabled;
+        let endpoint = stored.endpoint.clone();
+        self.persist(&state)?;
+        Ok(endpoint)
+    }
+
+    /// Registered endpoints, with their secrets redacted
+    pub fn list_endpoints(&self) -> Vec<WebhookEndpoint> {
+        self.state.lock().unwrap().endpoints.iter().map(|e| e.endpoint.clone()).collect()
+    }
+
+    fn stored_endpoints(&self) -> Vec<StoredEndpoint> {
+        self.state.lock().unwrap().endpoints.clone()
+    }
+
+    /// Most recent deliveries first, optionally for one endpoint
+    pub fn delivery_log(&self, endpoint_id: Option<&str>, limit: usize) -> Vec<DeliveryRecord> {
+        self.state
+            .lock()
+            .unwrap()
+            .deliveries
+            .iter()
+            .rev()
+            .filter(|d| endpoint_id.map_or(true, |id| d.endpoint_id == id))
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+
+    fn record_delivery(&self, record: DeliveryRecord) {
+        let mut state = self.state.lock().unwrap();
+        state.deliveries.push_back(record);
+        while state.deliveries.len() > MAX_DELIVERY_LOG {
+            state.deliveries.pop_front();
+        }
+        if let Err(e) = self.persist(&state) {
+            log::warn!("Failed to save webhook delivery log: {}", e);
+        }
+    }
+
+    /// Queue an event for every subscribed endpoint. Deliveries run in the background.
+    pub fn publish(self: &Arc<Self>, event_type: &str, data: serde_json::Value) -> WebhookEvent {
+        let event = WebhookEvent {
+            id: Uuid::new_v4().to_string(),
+            event_type: event_type.to_string(),
+            created_at: chrono::Utc::now().to_rfc3339(),
+            data,
+        };
+
+        let endpoints: Vec<StoredEndpoint> = self
+            .stored_endpoints()
+            .into_iter()
+            .filter(|e| e.endpoint.subscribes_to(event_type))
+            .collect();
+
+        for endpoint in endpoints {
+            let publisher = Arc::clone(self);
+            let event = event.clone();
+            tauri::async_runtime::spawn(async move {
+                publisher.deliver(&endpoint, &event).await;
+            });
+        }
+
+        event
+    }
+
+    /// Deliver one event to one endpoint, retrying with exponential backoff
+    async fn deliver(&self, stored: &StoredEndpoint, event: &WebhookEvent) -> bool {
+        let endpoint = &stored.endpoint;
+        let body = match serde_json::to_string(event) {
+            Ok(body) => body,
+            Err(e) => {
+                log::error!("Failed to serialize webhook event {}: {}", event.id, e);
+                return false;
+            }
+        };
+        let secret = match self.signing_secret(stored) {
+            Ok(secret) => secret,
+            Err(e) => {
+                log::error!("Failed to read the signing secret of webhook endpoint {}: {}", endpoint.id, e);
+                return false;
+            }
+        };
+
+        for attempt in 1..=MAX_ATTEMPTS {
+            let started = Instant::now();
+            let timestamp = chrono::Utc::now().timestamp();
+            let response = self
+                .client
+                .post(&endpoint.url)
+                .header("Content-Type", "application/json")
+                .header("X-Bear-Event", &event.event_type)
+                .header("X-Bear-Delivery", &event.id)
+                .header("X-Bear-Signature", sign_payload(&secret, timestamp, &body))
+                .body(body.clone())
+                .send()
+                .await;
+
+            let (status_code, error) = match response {
+                Ok(response) if response.status().is_success() => (Some(response.status().as_u16()), None),
+                Ok(response) => (Some(response.status().as_u16()), Some(format!("HTTP {}", response.status()))),
+                Err(e) => (None, Some(e.to_string())),
+            };
+            let success = error.is_none();
+
+            self.record_delivery(DeliveryRecord {
+                id: Uuid::new_v4().to_string(),
+                endpoint_id: endpoint.id.clone(),
+                event_id: event.id.clone(),
+                event_type: event.event_type.clone(),
+                attempt,
+                status_code,
+                success,
+                error: error.clone(),
+                duration_ms: started.elapsed().as_millis() as u64,
+                attempted_at: chrono::Utc::now().to_rfc3339(),
+            });
+
+            if success {
+                return true;
+            }
+            if !is_retryable(status_code) || attempt == MAX_ATTEMPTS {
+                log::warn!(
+                    "Webhook {} to {} failed after {} attempt(s): {}",
+                    event.event_type,
+                    endpoint.url,
+                    attempt,
+                    error.unwrap_or_default()
+                );
+                return false;
+            }
+            tokio::time::sleep(backoff(attempt)).await;
+        }
+        false
+    }
+
+    /// Send a signed test event to one endpoint and wait for the outcome
+    pub async fn send_test(&self, endpoint_id: &str) -> Result<bool> {
+        let endpoint = self
+            .stored_endpoints()
+            .into_iter()
+            .find(|e| e.endpoint.id == endpoint_id)
+            .ok_or_else(|| anyhow!("Webhook endpoint {} not found", endpoint_id))?;
+        let event = WebhookEvent {
+            id: Uuid::new_v4().to_string(),
+            event_type: "webhook.test".to_string(),
+            created_at: chrono::Utc::now().to_rfc3339(),
+            data: serde_json::json!({ "message": "BEAR AI webhook test" }),
+        };
+        Ok(self.deliver(&endpoint, &event).await)
+    }
+}
+
+lazy_static! {
+    static ref GLOBAL_WEBHOOK_PUBLISHER: RwLock<Option<Arc<WebhookPublisher>>> = RwLock::new(None);
+}
+
+/// Initialize the global webhook publisher
+pub fn initialize_webhook_publisher(app_data_dir: &Path, security: SecurityStorage) -> Result<Arc<WebhookPublisher>> {
+    let publisher = Arc::new(WebhookPublisher::new(app_data_dir, security)?);
+    *GLOBAL_WEBHOOK_PUBLISHER.write().unwrap() = Some(publisher.clone());
+    Ok(publisher)
+}
+
+/// Get the global webhook publisher
+pub fn get_webhook_publisher() -> Option<Arc<WebhookPublisher>> {
+    GLOBAL_WEBHOOK_PUBLISHER.read().unwrap().clone()
+}
+
+/// Publish an event if webhooks are set up; a no-op otherwise
+pub fn publish_event(event_type: &str, data: serde_json::Value) {
+    if let Some(publisher) = get_webhook_publisher() {
+        publisher.publish(event_type, data);
+    }
+}
+
+pub type WebhookStorage = Arc<WebhookPublisher>;
+
+#[tauri::command]
+pub async fn webhook_register_endpoint(
+    url: String,
+    events: Option<Vec<String>>,
+    description: Option<String>,
+    webhooks: tauri::State<'_, WebhookStorage>,
+) -> Result<WebhookEndpoint, String> {
+    webhooks
+        .register_endpoint(&url, events.unwrap_or_default(), description)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn webhook_remove_endpoint(
+    endpoint_id: String,
+    webhooks: tauri::State<'_, WebhookStorage>,
+) -> Result<(), String> {
+    webhooks.remove_endpoint(&endpoint_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn webhook_set_endpoint_enabled(
+    endpoint_id: String,
+    enabled: bool,
+    webhooks: tauri::State<'_, WebhookStorage>,
+) -> Result<WebhookEndpoint, String> {
+    webhooks
+        .set_endpoint_enabled(&endpoint_id, enabled)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn webhook_list_endpoints(
+    webhooks: tauri::State<'_, WebhookStorage>,
+) -> Result<Vec<WebhookEndpoint>, String> {
+    Ok(webhooks.list_endpoints())
+}
+
+#[tauri::command]
+pub async fn webhook_delivery_log(
+    endpoint_id: Option<String>,
+    limit: Option<usize>,
+    webhooks: tauri::State<'_, WebhookStorage>,
+) -> Result<Vec<DeliveryRecord>, String> {
+    Ok(webhooks.delivery_log(endpoint_id.as_deref(), limit.unwrap_or(100)))
+}
+
+#[tauri::command]
+pub async fn webhook_send_test(
+    endpoint_id: String,
+    webhooks: tauri::State<'_, WebhookStorage>,
+) -> Result<bool, String> {
+    webhooks.send_test(&endpoint_id).await.map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_payload() {
+        let signature = sign_payload("secret", 1_700_000_000, "{}");
+        assert!(signature.starts_with("t=1700000000,v1="));
+        assert_eq!(signature.len(), "t=1700000000,v1=".len() + 64);
+        assert_eq!(signature, sign_payload("secret", 1_700_000_000, "{}"));
+        assert_ne!(signature, sign_payload("other", 1_700_000_000, "{}"));
+    }
+
+    #[test]
+    fn test_retry_policy() {
+        assert!(is_retryable(None));
+        assert!(is_retryable(Some(503)));
+        assert!(is_retryable(Some(429)));
+        assert!(!is_retryable(Some(400)));
+        assert!(!is_retryable(Some(410)));
+        assert_eq!(backoff(1), Duration::from_secs(2));
+        assert_eq!(backoff(4), Duration::from_secs(16));
+    }
+
+    #[test]
+    fn test_secrets_are_stored_encrypted_and_listed_redacted() {
+        let dir = tempfile::tempdir().unwrap();
+        let security = Arc::new(Mutex::new(SecurityManager::new(dir.path()).unwrap()));
+        let publisher = WebhookPublisher::new(dir.path(), security.clone()).unwrap();
+        let endpoint = publisher
+            .register_endpoint("https://hooks.example.com/bear", vec![EVENT_RETENTION_PURGE.to_string()], None)
+            .unwrap();
+        assert!(endpoint.secret.starts_with("whsec_") && endpoint.secret.len() > 40);
+
+        let saved = fs::read_to_string(dir.path().join("webhooks.json")).unwrap();
+        assert!(!saved.contains(&endpoint.secret));
+        let listed = publisher.list_endpoints();
+        assert_eq!(listed[0].secret, redact_secret(&endpoint.secret));
+        assert!(listed[0].secret.ends_with(&endpoint.secret[endpoint.secret.len() - 4..]));
+        assert_eq!(publisher.signing_secret(&publisher.stored_endpoints()[0]).unwrap(), endpoint.secret);
+
+        // A secret saved in the clear by an earlier version is encrypted on load
+        let legacy = saved.replace(&listed[0].secret, "whsec_legacy0001").replace("secret_encrypted", "unused");
+        fs::write(dir.path().join("webhooks.json"), legacy).unwrap();
+        let publisher = WebhookPublisher::new(dir.path(), security).unwrap();
+        assert_eq!(publisher.list_endpoints()[0].secret, "whsec_…0001");
+        assert_eq!(publisher.signing_secret(&publisher.stored_endpoints()[0]).unwrap(), "whsec_legacy0001");
+        assert!(!fs::read_to_string(dir.path().join("webhooks.json")).unwrap().contains("whsec_legacy0001"));
+    }
+}
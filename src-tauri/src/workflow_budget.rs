@@ -0,0 +1,306 @@
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+/// Workflow Token Budgets for BEAR AI
+/// Workflows and their steps can cap the tokens they consume, directly or as a cost at a
+/// firm-defined rate per 1,000 tokens. Every model call made while a step runs goes through
+/// the step's meter: a call that cannot fit the remaining budget is not started, and the calls
+/// that do start reserve their prompt and their completion cap until their actual usage is
+/// recorded, so calls running side by side cannot together spend more than is left.
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TokenBudget {
+    pub max_tokens: Option<u64>,
+    pub max_cost: Option<f64>,
+    pub cost_per_1k_tokens: Option<f64>, // a step's rate overrides the workflow's
+}
+
+impl TokenBudget {
+    pub fn validate(&self) -> Result<()> {
+        if self.max_cost.is_some() && self.cost_per_1k_tokens.is_none() {
+            return Err(anyhow!("A cost budget needs a cost per 1,000 tokens"));
+        }
+        if self.max_cost.map(|c| c < 0.0).unwrap_or(false) || self.cost_per_1k_tokens.map(|r| r < 0.0).unwrap_or(false) {
+            return Err(anyhow!("Budget costs cannot be negative"));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BudgetUsage {
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub cost: f64,
+}
+
+impl BudgetUsage {
+    pub fn total_tokens(&self) -> u64 {
+        self.prompt_tokens + self.completion_tokens
+    }
+
+    fn add(&mut self, prompt_tokens: u64, completion_tokens: u64, cost: f64) {
+        self.prompt_tokens += prompt_tokens;
+        self.completion_tokens += completion_tokens;
+        self.cost += cost;
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BudgetExceeded {
+    pub scope: String, // "workflow" or the step id
+    pub step_id: String,
+    pub needed_tokens: u64,
+    pub remaining_tokens: u64,
+}
+
+impl std::fmt::Display for BudgetExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let scope = if self.scope == "workflow" { "workflow".to_string() } else { format!("step '{}'", self.scope) };
+        write!(
+            f,
+            "Token budget of the {} exceeded at step '{}': {} tokens needed, {} remaining",
+            scope, self.step_id, self.needed_tokens, self.remaining_tokens
+        )
+    }
+}
+
+impl std::error::Error for BudgetExceeded {}
+
+/// Budget consumption reported with a workflow run
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BudgetConsumption {
+    pub total: BudgetUsage,
+    pub steps: HashMap<String, BudgetUsage>,
+    pub exceeded: Option<BudgetExceeded>,
+}
+
+#[derive(Debug)]
+struct MeterState {
+    workflow: TokenBudget,
+    step_id: String,
+    step: TokenBudget,
+    consumption: BudgetConsumption,
+    reserved: u64, // tokens held by calls still running
+}
+
+impl MeterState {
+    fn rate(&self) -> f64 {
+        self.step.cost_per_1k_tokens.or(self.workflow.cost_per_1k_tokens).unwrap_or(0.0)
+    }
+
+    /// Tightest remaining allowance in tokens and the scope that sets it
+    fn remaining(&self) -> Option<(u64, String)> {
+        let rate = self.rate();
+        let step_used = self.consumption.steps.get(&self.step_id).cloned().unwrap_or_default();
+        let limits = [
+            (&self.workflow, &self.consumption.total, "workflow".to_string()),
+            (&self.step, &step_used, self.step_id.clone()),
+        ];
+
+        let mut tightest: Option<(u64, String)> = None;
+        for (budget, used, scope) in limits {
+            let by_tokens = budget.max_tokens.map(|max| max.saturating_sub(used.total_tokens()));
+            let by_cost = budget
+                .max_cost
+                .filter(|_| rate > 0.0)
+                .map(|max| ((max - used.cost).max(0.0) / rate * 1000.0).floor() as u64);
+            for remaining in [by_tokens, by_cost].into_iter().flatten() {
+                let remaining = remaining.saturating_sub(self.reserved);
+                if tightest.as_ref().map(|(t, _)| remaining < *t).unwrap_or(true) {
+                    tightest = Some((remaining, scope.clone()));
+                }
+            }
+        }
+        tightest
+    }
+}
+
+/// Tracks consumption for one workflow run; cheap to clone into the calls of a step
+#[derive(Debug, Clone)]
+pub struct BudgetMeter(Arc<Mutex<MeterState>>);
+
+impl BudgetMeter {
+    /// `consumption` carries over what a paused run already used
+    pub fn new(workflow: TokenBudget, consumption: BudgetConsumption) -> Self {
+        BudgetMeter(Arc::new(Mutex::new(MeterState {
+            workflow,
+            step_id: String::new(),
+            step: TokenBudget::default(),
+            consumption,
+            reserved: 0,
+        })))
+    }
+
+    pub fn begin_step(&self, step_id: &str, budget: TokenBudget) {
+        let mut state = self.0.lock().unwrap();
+        state.step_id = step_id.to_string();
+        state.step = budget;
+    }
+
+    /// Fail when `needed_tokens` do not fit what is left, without holding them
+    pub fn check(&self, needed_tokens: u64) -> Result<(), BudgetExceeded> {
+        let mut state = self.0.lock().unwrap();
+        Self::fits(&mut state, needed_tokens).map(|_| ())
+    }
+
+    /// Allow a call with `prompt_tokens` that needs at least `min_completion` more, capping its
+    /// completion to `max_completion` and to what is left. The prompt and the cap stay reserved
+    /// until the reservation records the call's usage.
+    pub fn authorize(&self, prompt_tokens: u64, min_completion: u64, max_completion: u64) -> Result<Reservation, BudgetExceeded> {
+        let mut state = self.0.lock().unwrap();
+        let remaining = Self::fits(&mut state, prompt_tokens + min_completion)?;
+        let completion_tokens = remaining.map_or(max_completion, |r| max_completion.min(r - prompt_tokens));
+        let tokens = prompt_tokens + completion_tokens;
+        state.reserved += tokens;
+        Ok(Reservation {
+            meter: self.clone(),
+            tokens,
+            completion_tokens,
+        })
+    }
+
+    /// Tokens left when `needed_tokens` fit, None without a limit
+    fn fits(state: &mut MeterState, needed_tokens: u64) -> Result<Option<u64>, BudgetExceeded> {
+        let Some((remaining, scope)) = state.remaining() else {
+            return Ok(None);
+        };
+        if needed_tokens > remaining {
+            let exceeded = BudgetExceeded {
+                scope,
+                step_id: state.step_id.clone(),
+                needed_tokens,
+                remaining_tokens: remaining,
+            };
+            state.consumption.exceeded = Some(exceeded.clone());
+            return Err(exceeded);
+        }
+        Ok(Some(remaining))
+    }
+
+    fn release(&self, tokens: u64) {
+        let mut state = self.0.lock().unwrap();
+        state.reserved = state.reserved.saturating_sub(tokens);
+    }
+
+    pub fn record(&self, prompt_tokens: u64, completion_tokens: u64) {
+        let mut state = self.0.lock().unwrap();
+        let cost = (prompt_tokens + completion_tokens) as f64 / 1000.0 * state.rate();
+        let step_id = state.step_id.clone();
+        state.consumption.total.add(prompt_tokens, completion_tokens, cost);
+        state.consumption.steps.entry(step_id).or_default().add(prompt_tokens, completion_tokens, cost);
+    }
+
+    pub fn consumption(&self) -> BudgetConsumption {
+        self.0.lock().unwrap().consumption.clone()
+    }
+}
+
+/// Tokens held for one model call. Recording its usage gives the reservation back and charges
+/// what the call actually used; dropping it unrecorded, when the call failed, only gives it back.
+#[derive(Debug)]
+#[must_use]
+pub struct Reservation {
+    meter: BudgetMeter,
+    tokens: u64,
+    pub completion_tokens: u64, // the call's completion cap
+}
+
+impl Reservation {
+    pub fn record(mut self, prompt_tokens: u64, completion_tokens: u64) {
+        self.meter.release(std::mem::take(&mut self.tokens));
+        self.meter.record(prompt_tokens, completion_tokens);
+    }
+}
+
+impl Drop for Reservation {
+    fn drop(&mut self) {
+        self.meter.release(self.tokens);
+    }
+}
+
+tokio::task_local! {
+    static CURRENT_METER: BudgetMeter;
+}
+
+/// The meter of the workflow step running on this task, if any
+pub fn current_meter() -> Option<BudgetMeter> {
+    CURRENT_METER.try_with(|meter| meter.clone()).ok()
+}
+
+/// Run `fut` with every model call it makes charged to `meter`
+pub async fn metered<F: Future>(meter: BudgetMeter, fut: F) -> F::Output {
+    CURRENT_METER.scope(meter, fut).await
+}
+
+/// Offline token estimate for when the model's tokenizer is unavailable
+pub fn estimate_tokens(text: &str) -> u64 {
+    let words = text.split_whitespace().count() as u64;
+    let chars = text.chars().count() as u64;
+    (words * 4 / 3).max(chars / 4)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tightest_limit_wins() {
+        let workflow = TokenBudget {
+            max_tokens: Some(10_000),
+            max_cost: Some(1.0),
+            cost_per_1k_tokens: Some(0.25), // 1.00 buys 4,000 tokens
+        };
+        let meter = BudgetMeter::new(workflow, BudgetConsumption::default());
+        meter.begin_step("analysis", TokenBudget { max_tokens: Some(3_000), ..TokenBudget::default() });
+        let call = meter.authorize(1_000, 100, 4_096).unwrap();
+        assert_eq!(call.completion_tokens, 2_000);
+
+        // A call running alongside sees the first one's prompt and completion cap as spent
+        let exceeded = meter.authorize(10, 0, 4_096).unwrap_err();
+        assert_eq!((exceeded.scope.as_str(), exceeded.remaining_tokens), ("analysis", 0));
+        call.record(1_000, 1_500);
+        let exceeded = meter.authorize(400, 200, 4_096).unwrap_err();
+        assert_eq!((exceeded.scope.as_str(), exceeded.remaining_tokens), ("analysis", 500));
+
+        // A call that fails gives its reservation back
+        drop(meter.authorize(100, 0, 4_096).unwrap());
+        assert!(meter.check(500).is_ok());
+
+        // A new step has its own allowance; the cost budget of the workflow now binds
+        meter.begin_step("risks", TokenBudget::default());
+        let call = meter.authorize(500, 0, 4_096).unwrap();
+        assert_eq!(call.completion_tokens, 1_000);
+        call.record(500, 1_000);
+        let exceeded = meter.check(1).unwrap_err();
+        assert_eq!(exceeded.scope, "workflow");
+        assert!(exceeded.to_string().contains("step 'risks'"));
+
+        let consumption = meter.consumption();
+        assert_eq!(consumption.total.total_tokens(), 4_000);
+        assert!((consumption.total.cost - 1.0).abs() < 1e-9);
+        assert_eq!(consumption.steps["analysis"].completion_tokens, 1_500);
+        assert_eq!(consumption.exceeded, Some(exceeded));
+    }
+
+    #[tokio::test]
+    async fn test_meter_is_scoped_to_the_task() {
+        assert!(current_meter().is_none());
+        let meter = BudgetMeter::new(TokenBudget::default(), BudgetConsumption::default());
+        meter.begin_step("summary", TokenBudget::default());
+        metered(meter.clone(), async {
+            let meter = current_meter().unwrap();
+            let call = meter.authorize(1_000_000, 0, 2_048).unwrap();
+            assert_eq!(call.completion_tokens, 2_048);
+            call.record(120, estimate_tokens("The tenant shall pay rent monthly in advance."));
+        })
+        .await;
+        assert_eq!(meter.consumption().steps["summary"].total_tokens(), 131);
+
+        let invalid = TokenBudget { max_cost: Some(5.0), ..TokenBudget::default() };
+        assert!(invalid.validate().is_err());
+    }
+}
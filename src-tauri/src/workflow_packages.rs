@@ -0,0 +1,707 @@
+use anyhow::{anyhow, Context, Result};
+use base64::Engine;
+use ring::signature::{Ed25519KeyPair, KeyPair, UnparsedPublicKey, ED25519};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::document_analyzer::{RiskAssessment, RiskLevel, RiskType};
+use crate::mcp_server::{AgentType, MCPServer, WorkflowDefinition};
+use crate::security::SecurityManager;
+
+/// Workflow Packages for BEAR AI
+/// Firms and consultants share workflows, prompt templates and clause rules as a single signed
+/// .bearpkg file. The contents are signed with the exporter's Ed25519 key; an import verifies the
+/// signature, asks before trusting a new publisher and rejects packages built for a newer app.
+/// A package is identified by its id; installing it registers its workflows and prompt templates
+/// with the agent server, and the document analyzer applies the clause rules of every installed
+/// package.
+pub const PACKAGE_FORMAT: &str = "bear-workflow-package";
+pub const PACKAGE_FORMAT_VERSION: u32 = 1;
+pub const PACKAGE_EXTENSION: &str = "bearpkg";
+const APP_VERSION: &str = env!("CARGO_PKG_VERSION");
+/// Clause rules come from third parties; keep their compiled size bounded
+const MAX_RULE_REGEX_SIZE: usize = 1 << 20;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptTemplate {
+    pub id: String,
+    pub name: String,
+    pub agent_type: AgentType,
+    pub template: String, // "{input}" is replaced with the task input
+    pub model_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClauseRule {
+    pub id: String,
+    pub name: String,
+    pub pattern: String, // case-insensitive regex
+    pub required: bool,  // true: flag when missing, false: flag when present
+    pub severity: RiskLevel,
+    pub recommendation: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackageContents {
+    pub id: String, // stable across versions and renames, e.g. "vandijk.nda-review"
+    pub name: String,
+    pub version: String,
+    pub description: String,
+    pub author: String,
+    pub min_app_version: String,
+    pub workflows: Vec<WorkflowDefinition>,
+    pub prompt_templates: Vec<PromptTemplate>,
+    pub clause_rules: Vec<ClauseRule>,
+    pub created_at: String,
+}
+
+/// On-disk form of a package. The signature covers the exact bytes of `contents`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedPackage {
+    pub format: String,
+    pub format_version: u32,
+    pub contents: String,
+    pub publisher_key: String, // base64 Ed25519 public key
+    pub signature: String,     // base64
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportPackageRequest {
+    pub id: String,
+    pub name: String,
+    pub version: String,
+    pub description: String,
+    pub author: String,
+    pub min_app_version: Option<String>,
+    pub workflows: Vec<WorkflowDefinition>,
+    pub prompt_templates: Vec<PromptTemplate>,
+    pub clause_rules: Vec<ClauseRule>,
+    pub output_path: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackageExport {
+    pub package_path: String,
+    pub publisher_fingerprint: String,
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstalledPackage {
+    pub id: String,
+    pub name: String,
+    pub version: String,
+    pub author: String,
+    pub publisher_fingerprint: String,
+    pub workflow_count: usize,
+    pub template_count: usize,
+    pub rule_count: usize,
+    pub installed_at: String,
+    pub file: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct PackageIndex {
+    installed: Vec<InstalledPackage>,
+    trusted_publishers: Vec<String>, // fingerprints
+}
+
+fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+    let mut parts = version.trim().trim_start_matches('v').split('.');
+    // Missing minor and patch parts count as 0
+    let mut next = || parts.next().map(|p| p.parse::<u64>().ok()).unwrap_or(Some(0));
+    Some((next()?, next()?, next()?))
+}
+
+/// Short, stable identifier for a publisher key, shown to the user before trusting it
+pub fn publisher_fingerprint(public_key: &[u8]) -> String {
+    hex::encode(&Sha256::digest(public_key)[..16])
+}
+
+/// Package ids name the installed file, so they are limited to lowercase letters, digits, '.', '-'
+/// and '_'
+fn valid_package_id(id: &str) -> bool {
+    !id.is_empty()
+        && !id.starts_with('.')
+        && id.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '.' | '-' | '_'))
+}
+
+/// Structural checks shared by export and import
+pub fn validate_contents(contents: &PackageContents) -> Result<()> {
+    if !valid_package_id(&contents.id) {
+        return Err(anyhow!(
+            "Invalid package id {:?}; use lowercase letters, digits, '.', '-' and '_'",
+            contents.id
+        ));
+    }
+    if contents.name.trim().is_empty() {
+        return Err(anyhow!("Package name is required"));
+    }
+    if parse_version(&contents.version).is_none() {
+        return Err(anyhow!("Invalid package version {:?}; expected MAJOR.MINOR.PATCH", contents.version));
+    }
+    let required = parse_version(&contents.min_app_version)
+        .ok_or_else(|| anyhow!("Invalid minimum app version {:?}", contents.min_app_version))?;
+    if required > parse_version(APP_VERSION).unwrap_or_default() {
+        return Err(anyhow!(
+            "Package requires BEAR AI {} or later (this is {})",
+            contents.min_app_version,
+            APP_VERSION
+        ));
+    }
+    if contents.workflows.is_empty() && contents.prompt_templates.is_empty() && contents.clause_rules.is_empty() {
+        return Err(anyhow!("Package is empty"));
+    }
+
+    let mut workflow_ids = HashSet::new();
+    for workflow in &contents.workflows {
+        if !workflow_ids.insert(workflow.id.as_str()) {
+            return Err(anyhow!("Duplicate workflow id {}", workflow.id));
+        }
+        workflow
+            .coordination
+            .validate()
+            .and_then(|_| workflow.budget.validate())
+            .map_err(|e| anyhow!("Workflow {}: {}", workflow.id, e))?;
+        for step in &workflow.steps {
+            step.budget
+                .validate()
+                .map_err(|e| anyhow!("Step {} of workflow {}: {}", step.step_id, workflow.id, e))?;
+        }
+        let mut pending: HashMap<&str, Vec<&str>> = HashMap::new();
+        for step in &workflow.steps {
+            let dependencies = step.dependencies.iter().map(String::as_str).collect();
+            if pending.insert(step.step_id.as_str(), dependencies).is_some() {
+                return Err(anyhow!("Workflow {} repeats step {}", workflow.id, step.step_id));
+            }
+        }
+        for step in &workflow.steps {
+            if let Some(missing) = step.dependencies.iter().find(|d| !pending.contains_key(d.as_str())) {
+                return Err(anyhow!("Step {} of workflow {} depends on unknown step {}", step.step_id, workflow.id, missing));
+            }
+        }
+        // Resolve steps whose dependencies are done until nothing changes; leftovers form a cycle
+        let mut done: HashSet<&str> = HashSet::new();
+        while !pending.is_empty() {
+            let ready: Vec<&str> = pending
+                .iter()
+                .filter(|(_, dependencies)| dependencies.iter().all(|d| done.contains(d)))
+                .map(|(step, _)| *step)
+                .collect();
+            if ready.is_empty() {
+                return Err(anyhow!("Workflow {} has circular step dependencies", workflow.id));
+            }
+            for step in ready {
+                pending.remove(step);
+                done.insert(step);
+            }
+        }
+    }
+
+    let mut template_ids = HashSet::new();
+    for template in &contents.prompt_templates {
+        if !template_ids.insert(template.id.as_str()) {
+            return Err(anyhow!("Duplicate prompt template id {}", template.id));
+        }
+        if !template.template.contains("{input}") {
+            return Err(anyhow!("Prompt template {} has no {{input}} placeholder", template.id));
+        }
+    }
+
+    let mut rule_ids = HashSet::new();
+    for rule in &contents.clause_rules {
+        if !rule_ids.insert(rule.id.as_str()) {
+            return Err(anyhow!("Duplicate clause rule id {}", rule.id));
+        }
+        rule_pattern(rule)?;
+    }
+    Ok(())
+}
+
+pub fn sign_package(contents: &PackageContents, key_pair: &Ed25519KeyPair) -> Result<SignedPackage> {
+    let engine = base64::engine::general_purpose::STANDARD;
+    let contents = serde_json::to_string_pretty(contents)?;
+    Ok(SignedPackage {
+        format: PACKAGE_FORMAT.to_string(),
+        format_version: PACKAGE_FORMAT_VERSION,
+        signature: engine.encode(key_pair.sign(contents.as_bytes())),
+        publisher_key: engine.encode(key_pair.public_key()),
+        contents,
+    })
+}
+
+/// Check the format and signature, then parse and validate the contents.
+/// Returns the contents with the publisher fingerprint.
+pub fn verify_package(package: &SignedPackage) -> Result<(PackageContents, String)> {
+    if package.format != PACKAGE_FORMAT {
+        return Err(anyhow!("Not a BEAR AI workflow package"));
+    }
+    if package.format_version > PACKAGE_FORMAT_VERSION {
+        return Err(anyhow!(
+            "Package format version {} is newer than supported ({}); update BEAR AI to import it",
+            package.format_version,
+            PACKAGE_FORMAT_VERSION
+        ));
+    }
+
+    let engine = base64::engine::general_purpose::STANDARD;
+    let public_key = engine.decode(&package.publisher_key).context("Invalid publisher key encoding")?;
+    let signature = engine.decode(&package.signature).context("Invalid package signature encoding")?;
+    UnparsedPublicKey::new(&ED25519, &public_key)
+        .verify(package.contents.as_bytes(), &signature)
+        .map_err(|_| anyhow!("Package signature verification failed; the file was modified or corrupted"))?;
+
+    let contents: PackageContents = serde_json::from_str(&package.contents).context("Invalid package contents")?;
+    validate_contents(&contents)?;
+    Ok((contents, publisher_fingerprint(&public_key)))
+}
+
+fn rule_pattern(rule: &ClauseRule) -> Result<regex::Regex> {
+    regex::RegexBuilder::new(&rule.pattern)
+        .case_insensitive(true)
+        .size_limit(MAX_RULE_REGEX_SIZE)
+        .build()
+        .map_err(|e| anyhow!("Clause rule {} has an invalid pattern: {}", rule.id, e))
+}
+
+/// Risks the clause rules find in a document: a required clause that is missing, or a clause
+/// the rule warns against that is present
+pub fn clause_rule_risks(text: &str, rules: &[ClauseRule]) -> Vec<RiskAssessment> {
+    let mut risks = Vec::new();
+    for rule in rules {
+        let Ok(pattern) = rule_pattern(rule) else {
+            continue;
+        };
+        let matches: Vec<String> = pattern.find_iter(text).map(|m| m.as_str().to_string()).collect();
+        let (description, impact) = match (rule.required, matches.is_empty()) {
+            (true, true) => (format!("{} is missing", rule.name), "A clause the playbook requires is absent"),
+            (false, false) => (format!("{} is present", rule.name), "The playbook flags this clause"),
+            _ => continue,
+        };
+        risks.push(RiskAssessment {
+            risk_type: RiskType::Legal,
+            description,
+            severity: rule.severity.clone(),
+            likelihood: 1.0,
+            impact: impact.to_string(),
+            mitigation_strategies: vec![rule.recommendation.clone()],
+            related_clauses: matches,
+        });
+    }
+    risks
+}
+
+pub struct WorkflowPackages {
+    dir: PathBuf,
+    key_path: PathBuf,
+    state: Mutex<PackageIndex>,
+}
+
+impl WorkflowPackages {
+    pub fn new(app_data_dir: &Path) -> Result<Self> {
+        let dir = app_data_dir.join("workflow_packages");
+        fs::create_dir_all(&dir)?;
+        let index_path = dir.join("index.json");
+        let state = if index_path.exists() {
+            serde_json::from_str(&fs::read_to_string(&index_path)?)?
+        } else {
+            PackageIndex::default()
+        };
+
+        Ok(Self {
+            key_path: app_data_dir.join("workflow_signing_key.enc"),
+            dir,
+            state: Mutex::new(state),
+        })
+    }
+
+    fn persist(&self, state: &PackageIndex) -> Result<()> {
+        fs::write(self.dir.join("index.json"), serde_json::to_string_pretty(state)?)?;
+        Ok(())
+    }
+
+    /// The firm's package signing key, generated on first export and stored encrypted
+    fn signing_key(&self, security: &SecurityManager) -> Result<Ed25519KeyPair> {
+        security.signing_key(&self.key_path, "package signing key")
+    }
+
+    pub fn export(&self, request: ExportPackageRequest, security: &SecurityManager) -> Result<PackageExport> {
+        let contents = PackageContents {
+            id: request.id,
+            name: request.name,
+            version: request.version,
+            description: request.description,
+            author: request.author,
+            min_app_version: request.min_app_version.unwrap_or_else(|| APP_VERSION.to_string()),
+            workflows: request.workflows,
+            prompt_templates: request.prompt_templates,
+            clause_rules: request.clause_rules,
+            created_at: chrono::Utc::now().to_rfc3339(),
+        };
+        validate_contents(&contents)?;
+
+        let key_pair = self.signing_key(security)?;
+        let package = serde_json::to_vec_pretty(&sign_package(&contents, &key_pair)?)?;
+        let output = Path::new(&request.output_path);
+        if let Some(parent) = output.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(output, &package)?;
+
+        // Our own packages re-import without a trust prompt
+        let fingerprint = publisher_fingerprint(key_pair.public_key().as_ref());
+        let mut state = self.state.lock().unwrap();
+        if !state.trusted_publishers.contains(&fingerprint) {
+            state.trusted_publishers.push(fingerprint.clone());
+            self.persist(&state)?;
+        }
+
+        Ok(PackageExport {
+            package_path: request.output_path,
+            publisher_fingerprint: fingerprint,
+            sha256: hex::encode(Sha256::digest(&package)),
+        })
+    }
+
+    /// Install a package and hand its workflows and prompt templates to the agent server. A
+    /// publisher seen for the first time is only accepted with `trust_publisher`, after the user
+    /// has confirmed the fingerprint.
+    pub fn import(&self, package_path: &Path, trust_publisher: bool, server: &MCPServer) -> Result<InstalledPackage> {
+        let raw = fs::read_to_string(package_path).map_err(|e| anyhow!("Cannot read {}: {}", package_path.display(), e))?;
+        let package: SignedPackage = serde_json::from_str(&raw).context("Not a BEAR AI workflow package")?;
+        let (contents, fingerprint) = verify_package(&package)?;
+
+        let mut state = self.state.lock().unwrap();
+        let new_publisher = !state.trusted_publishers.contains(&fingerprint);
+        if new_publisher && !trust_publisher {
+            return Err(anyhow!(
+                "Package is signed by an untrusted publisher (fingerprint {}); confirm the publisher to import it",
+                fingerprint
+            ));
+        }
+        let existing = state.installed.iter().find(|p| p.id == contents.id).cloned();
+        if let Some(existing) = &existing {
+            if existing.publisher_fingerprint != fingerprint {
+                return Err(anyhow!(
+                    "Package {} is installed from another publisher (fingerprint {}); remove it before importing this one",
+                    existing.id,
+                    existing.publisher_fingerprint
+                ));
+            }
+            if parse_version(&existing.version) > parse_version(&contents.version) {
+                return Err(anyhow!(
+                    "{} {} is already installed; refusing to downgrade to {}",
+                    existing.name,
+                    existing.version,
+                    contents.version
+                ));
+            }
+        }
+
+        // Keep the signed file so installed packages stay verifiable
+        let file = self.dir.join(format!("{}-{}.{}", contents.id, contents.version.trim(), PACKAGE_EXTENSION));
+        fs::write(&file, &raw)?;
+        let installed = InstalledPackage {
+            id: contents.id.clone(),
+            name: contents.name.clone(),
+            version: contents.version.clone(),
+            author: contents.author.clone(),
+            publisher_fingerprint: fingerprint,
+            workflow_count: contents.workflows.len(),
+            template_count: contents.prompt_templates.len(),
+            rule_count: contents.clause_rules.len(),
+            installed_at: chrono::Utc::now().to_rfc3339(),
+            file: file.to_string_lossy().to_string(),
+        };
+        if new_publisher {
+            state.trusted_publishers.push(installed.publisher_fingerprint.clone());
+        }
+        state.installed.retain(|p| p.id != contents.id);
+        state.installed.push(installed.clone());
+        self.persist(&state)?;
+        if let Some(previous) = existing.filter(|p| Path::new(&p.file) != file) {
+            let _ = fs::remove_file(previous.file);
+        }
+
+        server.install_package(&contents.workflows, &contents.prompt_templates);
+        log::info!("Installed workflow package {} {}", installed.id, installed.version);
+        Ok(installed)
+    }
+
+    pub fn list(&self) -> Vec<InstalledPackage> {
+        self.state.lock().unwrap().installed.clone()
+    }
+
+    /// Contents of an installed package, re-verified from the stored file
+    pub fn contents(&self, id: &str) -> Result<PackageContents> {
+        let installed = self
+            .list()
+            .into_iter()
+            .find(|p| p.id == id)
+            .ok_or_else(|| anyhow!("Workflow package {} is not installed", id))?;
+        let package: SignedPackage = serde_json::from_str(&fs::read_to_string(&installed.file)?)?;
+        let (contents, fingerprint) = verify_package(&package)?;
+        if fingerprint != installed.publisher_fingerprint || contents.id != id {
+            return Err(anyhow!("Installed package {} was replaced by a different publisher", id));
+        }
+        Ok(contents)
+    }
+
+    /// Register the workflows and prompt templates of every installed package, as at startup
+    pub fn install_all(&self, server: &MCPServer) {
+        for package in self.list() {
+            match self.contents(&package.id) {
+                Ok(contents) => server.install_package(&contents.workflows, &contents.prompt_templates),
+                Err(e) => log::warn!("Skipping workflow package {}: {}", package.id, e),
+            }
+        }
+    }
+
+    /// Clause rules of the installed packages
+    pub fn clause_rules(&self) -> Vec<ClauseRule> {
+        self.list()
+            .iter()
+            .filter(|package| package.rule_count > 0)
+            .filter_map(|package| self.contents(&package.id).ok())
+            .flat_map(|contents| contents.clause_rules)
+            .collect()
+    }
+
+    /// "id@version" of the installed packages with clause rules, which analyses record with the
+    /// built-in rule pack
+    pub fn rule_packages(&self) -> Vec<String> {
+        let mut packages: Vec<String> = self
+            .list()
+            .iter()
+            .filter(|package| package.rule_count > 0)
+            .map(|package| format!("{}@{}", package.id, package.version))
+            .collect();
+        packages.sort();
+        packages
+    }
+}
+
+pub type WorkflowPackageStorage = Arc<WorkflowPackages>;
+
+#[tauri::command]
+pub async fn export_workflow_package(
+    request: ExportPackageRequest,
+    packages: tauri::State<'_, WorkflowPackageStorage>,
+    security: tauri::State<'_, Arc<Mutex<SecurityManager>>>,
+) -> Result<PackageExport, String> {
+    let security = security.lock().map_err(|e| e.to_string())?;
+    packages.export(request, &security).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn import_workflow_package(
+    package_path: String,
+    trust_publisher: Option<bool>,
+    packages: tauri::State<'_, WorkflowPackageStorage>,
+    server: tauri::State<'_, Arc<MCPServer>>,
+) -> Result<InstalledPackage, String> {
+    packages
+        .import(Path::new(&package_path), trust_publisher.unwrap_or(false), &server)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn list_workflow_packages(
+    packages: tauri::State<'_, WorkflowPackageStorage>,
+) -> Result<Vec<InstalledPackage>, String> {
+    Ok(packages.list())
+}
+
+#[tauri::command]
+pub async fn get_workflow_package(
+    id: String,
+    packages: tauri::State<'_, WorkflowPackageStorage>,
+) -> Result<PackageContents, String> {
+    packages.contents(&id).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::rand::SystemRandom;
+    use crate::coordination::CoordinationStrategy;
+    use crate::mcp_server::{LegalDomain, StepKind, WorkflowStep};
+    use crate::workflow_budget::TokenBudget;
+
+    fn key_pair() -> Ed25519KeyPair {
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+        Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap()
+    }
+
+    fn server(dir: &Path) -> MCPServer {
+        MCPServer::new(Arc::new(crate::llm_manager::LLMManager::new(dir).unwrap()), 0)
+    }
+
+    fn step(id: &str, dependencies: &[&str]) -> WorkflowStep {
+        WorkflowStep {
+            step_id: id.to_string(),
+            agent_type: AgentType::ContractAnalyzer,
+            input_mapping: HashMap::new(),
+            dependencies: dependencies.iter().map(|d| d.to_string()).collect(),
+            timeout_seconds: 120,
+            kind: StepKind::Agent,
+            budget: TokenBudget::default(),
+        }
+    }
+
+    fn contents() -> PackageContents {
+        PackageContents {
+            id: "vandijk.nda-review".to_string(),
+            name: "NDA Review".to_string(),
+            version: "1.2.0".to_string(),
+            description: "Mutual NDA review playbook".to_string(),
+            author: "Van Dijk Consulting".to_string(),
+            min_app_version: "1.0".to_string(),
+            workflows: vec![WorkflowDefinition {
+                id: "nda_review".to_string(),
+                name: "NDA review".to_string(),
+                description: String::new(),
+                steps: vec![step("analysis", &[]), step("risks", &["analysis"])],
+                legal_domain: LegalDomain::ContractLaw,
+                coordination: CoordinationStrategy::Hierarchical,
+                budget: TokenBudget::default(),
+            }],
+            prompt_templates: vec![PromptTemplate {
+                id: "nda_summary".to_string(),
+                name: "NDA summary".to_string(),
+                agent_type: AgentType::DocumentSummarizer,
+                template: "Summarise the confidentiality obligations in:\n\n{input}".to_string(),
+                model_id: None,
+            }],
+            clause_rules: vec![ClauseRule {
+                id: "residuals".to_string(),
+                name: "Residuals clause".to_string(),
+                pattern: r"\bresidual (knowledge|information)\b".to_string(),
+                required: false,
+                severity: RiskLevel::High,
+                recommendation: "Strike the residuals clause".to_string(),
+            }],
+            created_at: "2026-01-05T00:00:00Z".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_validates_compatibility_and_structure() {
+        assert!(validate_contents(&contents()).is_ok());
+        assert_eq!(parse_version("v1.2"), Some((1, 2, 0)));
+        assert_eq!(parse_version("1.x"), None);
+
+        let mut newer = contents();
+        newer.min_app_version = "99.0.0".to_string();
+        assert!(validate_contents(&newer).unwrap_err().to_string().contains("or later"));
+
+        let mut cyclic = contents();
+        cyclic.workflows[0].steps[0].dependencies = vec!["risks".to_string()];
+        assert!(validate_contents(&cyclic).unwrap_err().to_string().contains("circular"));
+
+        let mut unknown = contents();
+        unknown.workflows[0].steps[1].dependencies = vec!["drafting".to_string()];
+        assert!(validate_contents(&unknown).is_err());
+
+        let mut bad_id = contents();
+        bad_id.id = "../nda".to_string();
+        assert!(validate_contents(&bad_id).unwrap_err().to_string().contains("package id"));
+
+        let mut no_input = contents();
+        no_input.prompt_templates[0].template = "Summarise".to_string();
+        assert!(validate_contents(&no_input).is_err());
+
+        let mut bad_rule = contents();
+        bad_rule.clause_rules[0].pattern = "(unclosed".to_string();
+        assert!(validate_contents(&bad_rule).is_err());
+    }
+
+    #[test]
+    fn test_signed_package_import_requires_trust() {
+        let key_pair = key_pair();
+        let package = sign_package(&contents(), &key_pair).unwrap();
+        let (verified, fingerprint) = verify_package(&package).unwrap();
+        assert_eq!(verified.name, "NDA Review");
+        assert_eq!(fingerprint, publisher_fingerprint(key_pair.public_key().as_ref()));
+
+        let mut tampered = package.clone();
+        tampered.contents = tampered.contents.replace("Strike", "Accept");
+        assert!(verify_package(&tampered).unwrap_err().to_string().contains("signature"));
+
+        let mut future = package.clone();
+        future.format_version = PACKAGE_FORMAT_VERSION + 1;
+        assert!(verify_package(&future).is_err());
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nda.bearpkg");
+        fs::write(&path, serde_json::to_string(&package).unwrap()).unwrap();
+        let packages = WorkflowPackages::new(dir.path()).unwrap();
+        let server = server(dir.path());
+        assert!(packages.import(&path, false, &server).unwrap_err().to_string().contains(&fingerprint));
+        let installed = packages.import(&path, true, &server).unwrap();
+        assert_eq!((installed.workflow_count, installed.template_count, installed.rule_count), (1, 1, 1));
+        assert!(installed.file.ends_with("vandijk.nda-review-1.2.0.bearpkg"));
+
+        // The publisher is now trusted, but an older version does not replace the installed one
+        let mut older = contents();
+        older.version = "1.1.0".to_string();
+        fs::write(&path, serde_json::to_string(&sign_package(&older, &key_pair).unwrap()).unwrap()).unwrap();
+        assert!(packages.import(&path, false, &server).unwrap_err().to_string().contains("downgrade"));
+
+        // Another publisher cannot take over the id, even with a newer version
+        let mut hijack = contents();
+        hijack.version = "2.0.0".to_string();
+        fs::write(&path, serde_json::to_string(&sign_package(&hijack, &self::key_pair()).unwrap()).unwrap()).unwrap();
+        assert!(packages.import(&path, true, &server).unwrap_err().to_string().contains("another publisher"));
+
+        // A package with the same name but its own id installs alongside
+        let mut namesake = contents();
+        namesake.id = "other.nda-review".to_string();
+        fs::write(&path, serde_json::to_string(&sign_package(&namesake, &key_pair).unwrap()).unwrap()).unwrap();
+        packages.import(&path, false, &server).unwrap();
+        assert_eq!(packages.list().len(), 2);
+
+        let reopened = WorkflowPackages::new(dir.path()).unwrap();
+        assert_eq!(reopened.contents("vandijk.nda-review").unwrap().clause_rules[0].id, "residuals");
+        assert_eq!(reopened.rule_packages(), vec!["other.nda-review@1.2.0", "vandijk.nda-review@1.2.0"]);
+    }
+
+    #[test]
+    fn test_import_registers_workflows_templates_and_rules() {
+        let key_pair = key_pair();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nda.bearpkg");
+        fs::write(&path, serde_json::to_string(&sign_package(&contents(), &key_pair).unwrap()).unwrap()).unwrap();
+        let packages = WorkflowPackages::new(dir.path()).unwrap();
+        let server = server(dir.path());
+        packages.import(&path, true, &server).unwrap();
+
+        assert!(server.get_workflows().iter().any(|w| w.id == "nda_review"));
+        let summarizer = server
+            .get_agents()
+            .into_iter()
+            .find(|a| a.agent_type == AgentType::DocumentSummarizer)
+            .unwrap();
+        assert!(summarizer.prompt_template.starts_with("Summarise the confidentiality obligations"));
+
+        // A restarted app registers the installed packages again
+        let restarted = self::server(dir.path());
+        WorkflowPackages::new(dir.path()).unwrap().install_all(&restarted);
+        assert!(restarted.get_workflows().iter().any(|w| w.id == "nda_review"));
+
+        let rules = packages.clause_rules();
+        let flagged = clause_rule_risks("The Recipient may use Residual Information freely.", &rules);
+        assert_eq!(flagged.len(), 1);
+        assert_eq!(flagged[0].related_clauses, vec!["Residual Information"]);
+        assert!(clause_rule_risks("The Recipient keeps all information confidential.", &rules).is_empty());
+
+        let mut required = rules[0].clone();
+        required.required = true;
+        let missing = clause_rule_risks("The Recipient keeps all information confidential.", &[required]);
+        assert_eq!(missing[0].description, "Residuals clause is missing");
+    }
+}
@@ -0,0 +1,350 @@
+use chrono::{Datelike, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::document_acl::SHARED_WORKSPACE;
+use crate::local_api::Document;
+use crate::performance_tracker::{get_performance_tracker, PerformanceMetrics};
+
+/// Workspace Statistics Aggregation for BEAR AI
+/// Builds a single dashboard snapshot from documents, analyses and model usage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceStatsSnapshot {
+    pub generated_at: String,
+    pub total_documents: usize,
+    pub total_storage_bytes: u64,
+    pub documents_by_type: HashMap<String, usize>,
+    pub documents_by_jurisdiction: HashMap<String, usize>,
+    pub storage_by_category: HashMap<String, u64>,
+    pub tokens_per_week: Vec<WeeklyTokenUsage>,
+    pub analysis_counts: HashMap<String, u64>,
+    pub total_analyses: u64,
+    pub model_usage_share: Vec<ModelUsageShare>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeeklyTokenUsage {
+    pub week: String, // ISO week, e.g. "2024-W07"
+    pub tokens: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelUsageShare {
+    pub model_name: String,
+    pub requests: u64,
+    pub tokens: u64,
+    pub share_percent: f32,
+}
+
+/// Tag prefix used to attach a jurisdiction to an uploaded document
+pub const JURISDICTION_TAG_PREFIX: &str = "jurisdiction:";
+
+/// How long a snapshot is served from cache before it is refreshed
+const SNAPSHOT_TTL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Default)]
+struct StatsState {
+    // Cached snapshots per workspace with the time they were built
+    snapshots: HashMap<String, (WorkspaceStatsSnapshot, Instant)>,
+    usage: HashMap<String, WorkspaceUsage>,
+}
+
+/// Incremental token and analysis accounting of one workspace
+#[derive(Debug, Default)]
+struct WorkspaceUsage {
+    // Timestamp and sequence of the last metric folded in from the performance tracker
+    metrics_watermark: (u64, u64),
+    tokens_by_week: BTreeMap<String, u64>,
+    usage_by_model: HashMap<String, (u64, u64)>, // (requests, tokens)
+    analysis_counts: HashMap<String, u64>,
+}
+
+impl WorkspaceUsage {
+    /// Fold in the metrics recorded for `workspace_id` since the watermark; metrics without a
+    /// workspace were not recorded on behalf of a session and count towards the shared one
+    fn fold_metrics(&mut self, workspace_id: &str, metrics: &[PerformanceMetrics]) {
+        let mut new_metrics: Vec<&PerformanceMetrics> = metrics
+            .iter()
+            .filter(|m| (m.timestamp, m.sequence) > self.metrics_watermark)
+            .filter(|m| m.workspace.as_deref().unwrap_or(SHARED_WORKSPACE) == workspace_id)
+            .collect();
+        new_metrics.sort_by_key(|m| (m.timestamp, m.sequence));
+
+        for metric in new_metrics {
+            let tokens = metric.total_tokens as u64;
+            *self.tokens_by_week.entry(iso_week_key(metric.timestamp)).or_insert(0) += tokens;
+
+            let model = self.usage_by_model.entry(metric.model_name.clone()).or_insert((0, 0));
+            model.0 += 1;
+            model.1 += tokens;
+
+            self.metrics_watermark = (metric.timestamp, metric.sequence);
+        }
+    }
+}
+
+tokio::task_local! {
+    static CURRENT_WORKSPACE: String;
+}
+
+/// The workspace the work on this task is done for, if any
+pub fn current_workspace() -> Option<String> {
+    CURRENT_WORKSPACE.try_with(|workspace| workspace.clone()).ok()
+}
+
+/// Run `fut` on behalf of a workspace, so the model usage it records counts towards it
+pub async fn in_workspace<F: Future>(workspace_id: &str, fut: F) -> F::Output {
+    CURRENT_WORKSPACE.scope(workspace_id.to_string(), fut).await
+}
+
+#[derive(Debug, Default)]
+pub struct WorkspaceStatsService {
+    state: Mutex<StatsState>,
+}
+
+impl WorkspaceStatsService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a completed analysis so it shows up in the workspace's next snapshot
+    pub fn record_analysis(&self, workspace_id: &str, analysis_type: &str) {
+        let mut state = self.state.lock().unwrap();
+        *state
+            .usage
+            .entry(workspace_id.to_string())
+            .or_default()
+            .analysis_counts
+            .entry(analysis_type.to_string())
+            .or_insert(0) += 1;
+        state.snapshots.remove(workspace_id);
+    }
+
+    /// Mark the cached snapshots as stale (e.g. after uploads or deletions)
+    pub fn invalidate(&self) {
+        self.state.lock().unwrap().snapshots.clear();
+    }
+
+    /// Get the current snapshot, refreshing it if it is stale or forced
+    pub async fn snapshot(
+        &self,
+        workspace_id: &str,
+        documents: &[Document],
+        force_refresh: bool,
+    ) -> WorkspaceStatsSnapshot {
+        if !force_refresh {
+            let state = self.state.lock().unwrap();
+            if let Some((snapshot, refreshed)) = state.snapshots.get(workspace_id) {
+                if refreshed.elapsed() < SNAPSHOT_TTL {
+                    return snapshot.clone();
+                }
+            }
+        }
+
+        self.refresh(workspace_id, documents).await
+    }
+
+    /// Recompute the document aggregates and fold in new token metrics
+    async fn refresh(&self, workspace_id: &str, documents: &[Document]) -> WorkspaceStatsSnapshot {
+        let (since, _) = self
+            .state
+            .lock()
+            .unwrap()
+            .usage
+            .get(workspace_id)
+            .map(|usage| usage.metrics_watermark)
+            .unwrap_or_default();
+
+        // Only metrics from the second of the last one folded in onwards are pulled from the tracker
+        let new_metrics = match get_performance_tracker() {
+            Some(tracker) => tracker.get_metrics_since(since).await,
+            None => Vec::new(),
+        };
+
+        let mut state = self.state.lock().unwrap();
+        let usage = state.usage.entry(workspace_id.to_string()).or_default();
+        usage.fold_metrics(workspace_id, &new_metrics);
+
+        let mut documents_by_type = HashMap::new();
+        let mut documents_by_jurisdiction = HashMap::new();
+        let mut storage_by_category = HashMap::new();
+        let mut total_storage_bytes = 0u64;
+
+        for document in documents {
+            *documents_by_type.entry(document.content_type.clone()).or_insert(0) += 1;
+            *storage_by_category.entry(document.category.clone()).or_insert(0) += document.file_size;
+            total_storage_bytes += document.file_size;
+
+            let jurisdiction = document
+                .tags
+                .iter()
+                .find_map(|tag| tag.strip_prefix(JURISDICTION_TAG_PREFIX))
+                .map(|j| j.trim().to_string())
+                .unwrap_or_else(|| "unspecified".to_string());
+            *documents_by_jurisdiction.entry(jurisdiction).or_insert(0) += 1;
+        }
+
+        let total_model_tokens: u64 = usage.usage_by_model.values().map(|(_, tokens)| tokens).sum();
+        let total_model_requests: u64 = usage.usage_by_model.values().map(|(requests, _)| requests).sum();
+
+        let mut model_usage_share: Vec<ModelUsageShare> = usage
+            .usage_by_model
+            .iter()
+            .map(|(model_name, (requests, tokens))| {
+                // Share is by tokens; fall back to request count when no tokens were reported
+                let share_percent = if total_model_tokens > 0 {
+                    *tokens as f32 / total_model_tokens as f32 * 100.0
+                } else if total_model_requests > 0 {
+                    *requests as f32 / total_model_requests as f32 * 100.0
+                } else {
+                    0.0
+                };
+
+                ModelUsageShare {
+                    model_name: model_name.clone(),
+                    requests: *requests,
+                    tokens: *tokens,
+                    share_percent,
+                }
+            })
+            .collect();
+        model_usage_share.sort_by(|a, b| b.share_percent.partial_cmp(&a.share_percent).unwrap_or(std::cmp::Ordering::Equal));
+
+        let snapshot = WorkspaceStatsSnapshot {
+            generated_at: Utc::now().to_rfc3339(),
+            total_documents: documents.len(),
+            total_storage_bytes,
+            documents_by_type,
+            documents_by_jurisdiction,
+            storage_by_category,
+            tokens_per_week: usage
+                .tokens_by_week
+                .iter()
+                .map(|(week, tokens)| WeeklyTokenUsage {
+                    week: week.clone(),
+                    tokens: *tokens,
+                })
+                .collect(),
+            total_analyses: usage.analysis_counts.values().sum(),
+            analysis_counts: usage.analysis_counts.clone(),
+            model_usage_share,
+        };
+
+        state
+            .snapshots
+            .insert(workspace_id.to_string(), (snapshot.clone(), Instant::now()));
+
+        snapshot
+    }
+}
+
+/// Bucket a unix timestamp into its ISO week ("YYYY-Www")
+fn iso_week_key(timestamp: u64) -> String {
+    let week = Utc
+        .timestamp_opt(timestamp as i64, 0)
+        .single()
+        .unwrap_or_else(Utc::now)
+        .iso_week();
+    format!("{}-W{:02}", week.year(), week.week())
+}
+
+pub type WorkspaceStatsStorage = Arc<WorkspaceStatsService>;
+
+pub fn create_workspace_stats_service() -> WorkspaceStatsStorage {
+    Arc::new(WorkspaceStatsService::new())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document(category: &str, content_type: &str, size: u64, tags: &[&str]) -> Document {
+        Document {
+            id: format!("doc-{}-{}", category, size),
+            name: "test.pdf".to_string(),
+            category: category.to_string(),
+            file_size: size,
+            created_at: Utc::now().to_rfc3339(),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            status: "uploaded".to_string(),
+            content_type: content_type.to_string(),
+        }
+    }
+
+    fn metric(timestamp: u64, sequence: u64, workspace: Option<&str>, tokens: u32) -> PerformanceMetrics {
+        let timer = crate::performance_tracker::PerformanceTimer::new("model".to_string(), "test".to_string());
+        let mut metric = timer.finish_with_tokens(tokens, tokens, 0);
+        metric.timestamp = timestamp;
+        metric.sequence = sequence;
+        metric.workspace = workspace.map(str::to_string);
+        metric
+    }
+
+    #[test]
+    fn test_folds_metrics_recorded_in_the_same_second_once() {
+        let mut usage = WorkspaceUsage::default();
+        usage.fold_metrics("session", &[metric(100, 1, Some("session"), 10)]);
+
+        // A later refresh pulls the whole second again, including a metric recorded after the first
+        let since_watermark = [metric(100, 1, Some("session"), 10), metric(100, 2, Some("session"), 5)];
+        usage.fold_metrics("session", &since_watermark);
+
+        assert_eq!(usage.usage_by_model.get("model"), Some(&(2, 15)));
+        assert_eq!(usage.metrics_watermark, (100, 2));
+    }
+
+    #[test]
+    fn test_folds_only_the_workspaces_own_metrics() {
+        let metrics = [
+            metric(100, 1, Some("session"), 10),
+            metric(100, 2, Some("other-session"), 20),
+            metric(100, 3, None, 40),
+        ];
+
+        let mut own = WorkspaceUsage::default();
+        own.fold_metrics("session", &metrics);
+        assert_eq!(own.usage_by_model.get("model"), Some(&(1, 10)));
+
+        let mut shared = WorkspaceUsage::default();
+        shared.fold_metrics(SHARED_WORKSPACE, &metrics);
+        assert_eq!(shared.usage_by_model.get("model"), Some(&(1, 40)));
+    }
+
+    #[tokio::test]
+    async fn test_in_workspace_sets_the_current_workspace() {
+        assert_eq!(current_workspace(), None);
+        let inside = in_workspace("session", async { current_workspace() }).await;
+        assert_eq!(inside.as_deref(), Some("session"));
+    }
+
+    #[test]
+    fn test_iso_week_key() {
+        // 2024-02-14 is in ISO week 7
+        assert_eq!(iso_week_key(1_707_868_800), "2024-W07");
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_aggregates_documents() {
+        let service = WorkspaceStatsService::new();
+        let documents = vec![
+            document("contracts", "application/pdf", 100, &["jurisdiction:NL"]),
+            document("contracts", "application/pdf", 50, &[]),
+            document("briefs", "text/plain", 25, &["jurisdiction:NL"]),
+        ];
+
+        service.record_analysis("session", "risk_assessment");
+        service.record_analysis("other-session", "risk_assessment");
+        let snapshot = service.snapshot("session", &documents, false).await;
+
+        assert_eq!(snapshot.total_documents, 3);
+        assert_eq!(snapshot.total_storage_bytes, 175);
+        assert_eq!(snapshot.storage_by_category.get("contracts"), Some(&150));
+        assert_eq!(snapshot.documents_by_type.get("application/pdf"), Some(&2));
+        assert_eq!(snapshot.documents_by_jurisdiction.get("NL"), Some(&2));
+        assert_eq!(snapshot.documents_by_jurisdiction.get("unspecified"), Some(&1));
+        assert_eq!(snapshot.total_analyses, 1);
+    }
+}
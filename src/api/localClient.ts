@@ -251,7 +251,12 @@ export class LocalApiClient {
     }
   }
 
-  async sendMessage(chatSessionId: string, content: string, role?: string): Promise<LocalChatMessage> {
+  async sendMessage(
+    chatSessionId: string,
+    content: string,
+    role?: string,
+    retrievalId?: string
+  ): Promise<LocalChatMessage> {
     if (!this.sessionId) {
       throw new Error('Not authenticated');
     }
@@ -263,7 +268,8 @@ export class LocalApiClient {
         sessionId: this.sessionId,
         chatSessionId,
         content,
-        role
+        role,
+        retrievalId
       });
     } catch (error) {
       this.handleError(error instanceof Error ? error.message : 'Failed to send message');
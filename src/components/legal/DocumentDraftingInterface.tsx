@@ -487,7 +487,48 @@ export const DocumentDraftingInterface: React.FC<DocumentDraftingInterfaceProps>
           {activeTab === 'review' && draftingResult && (
             <div>
               <h3 className="font-medium text-gray-900 mb-4">Document Analysis</h3>
-              {/* Review content would go here */}
+              <h4 className="text-sm font-medium text-gray-700 mb-2">Terminology</h4>
+              {draftingResult.glossaryViolations && draftingResult.glossaryViolations.length > 0 ? (
+                <ul className="space-y-2">
+                  {draftingResult.glossaryViolations.map((violation) => (
+                    <li key={violation.found} className="flex items-center justify-between text-sm">
+                      <span>
+                        <span className="line-through text-gray-500">{violation.found}</span>
+                        {' → '}
+                        <span className="font-medium text-gray-900">{violation.preferred}</span>
+                        <span className="ml-2 text-gray-500">({violation.occurrences}x)</span>
+                      </span>
+                      <span className={violation.replaced ? 'text-green-600' : 'text-yellow-600'}>
+                        {violation.replaced ? 'Replaced' : 'Review manually'}
+                      </span>
+                    </li>
+                  ))}
+                </ul>
+              ) : (
+                <p className="text-sm text-gray-500">No glossary terms to review.</p>
+              )}
+              {draftingResult.style && (
+                <div className="mt-6">
+                  <div className="flex items-center justify-between mb-2">
+                    <h4 className="text-sm font-medium text-gray-700">Style and Readability</h4>
+                    <span className="text-sm font-medium text-gray-900">Score {draftingResult.style.score}/100</span>
+                  </div>
+                  <p className="text-xs text-gray-500 mb-2">
+                    {draftingResult.style.metrics.sentenceCount} sentences, {draftingResult.style.metrics.averageSentenceLength} words on average,{' '}
+                    {Math.round(draftingResult.style.metrics.passiveRatio * 100)}% passive
+                  </p>
+                  <ul className="space-y-2">
+                    {draftingResult.style.suggestions.map((suggestion) => (
+                      <li key={`${suggestion.kind}-${suggestion.start}`} className="text-sm">
+                        <span className={suggestion.severity === 'warning' ? 'text-yellow-600' : 'text-gray-500'}>
+                          “{suggestion.excerpt}”
+                        </span>
+                        <span className="ml-2 text-gray-700">{suggestion.message}</span>
+                      </li>
+                    ))}
+                  </ul>
+                </div>
+              )}
             </div>
           )}
         </div>
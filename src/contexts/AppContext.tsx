@@ -1,4 +1,5 @@
 import React, { createContext, useContext, useReducer, useEffect } from 'react';
+import { listen } from '@tauri-apps/api/event';
 import type { User, SystemStatus, ChatSession, Document as AppDocument } from '../types';
 
 // Combined state from both GUI variants
@@ -204,6 +205,42 @@ export const AppProvider: React.FC<AppProviderProps> = ({ children, initialUser
     };
   }, [state.notifications, dispatch]);
 
+  // Regulatory changes that affect indexed policies and contracts open review tasks in the backend
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+
+    const setupRegulatoryListener = async () => {
+      try {
+        unlisten = await listen<{ citation: string; tasks: Array<{ document_title: string; priority: 'high' | 'low' }> }>(
+          'regulatory-review-tasks',
+          (event) => {
+            const { citation, tasks } = event.payload;
+            const urgent = tasks.filter(task => task.priority === 'high').length;
+            const documents = tasks.map(task => task.document_title).join(', ');
+            dispatch({
+              type: 'ADD_NOTIFICATION',
+              payload: {
+                type: urgent > 0 ? 'warning' : 'info',
+                title: `${citation} changed`,
+                message: `${tasks.length} document(s) need review${urgent > 0 ? ` (${urgent} cite amended provisions)` : ''}: ${documents}`
+              }
+            });
+          }
+        );
+      } catch (error) {
+        // Not running inside the desktop app
+      }
+    };
+
+    setupRegulatoryListener();
+
+    return () => {
+      if (unlisten) {
+        unlisten();
+      }
+    };
+  }, [dispatch]);
+
   // Persist authentication state
   useEffect(() => {
     const savedUser = localStorage.getItem('bear-ai-user');
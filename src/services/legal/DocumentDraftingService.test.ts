@@ -0,0 +1,74 @@
+import DocumentDraftingService, { evaluateCondition, formatAmount, parseAssembly } from './DocumentDraftingService';
+
+describe('DocumentDraftingService conditional assembly', () => {
+  const service = new DocumentDraftingService();
+
+  test('selects clauses from questionnaire answers', async () => {
+    const result = await service.assembleFromQuestionnaire({
+      templateId: 'engagement_conditional',
+      answers: { jurisdiction: 'UK', worker_type: 'contractor', deal_size: 2000000, include_non_compete: true },
+      variables: { company_name: 'Acme Ltd', worker_name: 'Jane Doe', effective_date: '2025-01-01' },
+      outputFormat: 'markdown'
+    });
+
+    expect(result.includedClauses).toEqual([
+      'engagement_contractor',
+      'ir35_status',
+      'notice_period',
+      'ip_assignment',
+      'confidentiality',
+      'non_compete',
+      'liability_cap_enhanced',
+      'dispute_arbitration',
+      'governing_law'
+    ]);
+    expect(result.content).toContain('Acme Ltd engages Jane Doe as an independent contractor');
+    expect(result.content).toContain('For six months after termination');
+    expect(result.content).toContain('limited to the greater of £2,000,000 and');
+    expect(result.content).toContain('governed by the laws of England and Wales');
+    expect(result.content).not.toContain('{{');
+    expect(result.warnings).toEqual([]);
+  });
+
+  test('applies the glossary and reports terms it did not replace', async () => {
+    const glossaryService = new DocumentDraftingService(async text => ({
+      text: text.replace(/the company/g, 'the Employer'),
+      violations: [
+        { found: 'the company', preferred: 'the Employer', occurrences: 1, replaced: true },
+        { found: 'agreement', preferred: 'Contract', occurrences: 1, replaced: false }
+      ]
+    }));
+    const result = await glossaryService.assembleFromQuestionnaire({
+      templateId: 'engagement_conditional',
+      answers: { jurisdiction: 'NL', worker_type: 'employee', deal_size: 10000, include_non_compete: false },
+      variables: { company_name: 'Acme BV', worker_name: 'Jan Jansen', effective_date: '2025-01-01' },
+      outputFormat: 'markdown'
+    });
+
+    expect(result.content).toContain('follow its reasonable instructions');
+    expect(result.content).not.toContain('the company');
+    expect(result.glossaryViolations).toHaveLength(2);
+    expect(result.warnings).toEqual(['Use "Contract" instead of "agreement" (1x).']);
+  });
+
+  test('evaluates conditions and rejects unbalanced blocks', () => {
+    expect(evaluateCondition('jurisdiction == "NL" or jurisdiction == "DE"', { jurisdiction: 'de' })).toBe(true);
+    expect(evaluateCondition('deal_size >= 1000000 and not include_non_compete', { deal_size: 500000 })).toBe(false);
+    expect(() => parseAssembly('{{#if worker_type == "employee"}}text')).toThrow('Unclosed');
+  });
+
+  test('follows the jurisdiction for defaults the user did not override', async () => {
+    const result = await service.assembleFromQuestionnaire({
+      templateId: 'engagement_conditional',
+      answers: { jurisdiction: 'US', worker_type: 'contractor', deal_size: 1500000, currency: 'EUR' },
+      variables: { company_name: 'Acme Inc', worker_name: 'John Roe', effective_date: '2025-01-01' },
+      outputFormat: 'markdown'
+    });
+
+    expect(result.content).toContain('governed by the laws of the State of Delaware');
+    expect(result.content).toContain('the greater of €1,500,000 and');
+    expect(result.metadata.variables.currency).toBe('EUR');
+    expect(formatAmount(2500, 'USD')).toBe('$2,500');
+    expect(formatAmount('', 'USD')).toBe('');
+  });
+});
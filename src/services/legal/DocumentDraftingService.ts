@@ -1,10 +1,14 @@
 import type { LegalDocumentType, LegalCategory, RiskLevel } from '../../types/legal';
+import { reviewWithGlossary, type GlossaryReviewer, type GlossaryViolation } from './GlossaryBridge';
+import { checkDraftStyle, type StyleReport } from './DraftStyleChecker';
 
 export interface TemplateVariable {
   name: string;
   type: 'text' | 'textarea' | 'date' | 'number' | 'select' | 'boolean';
   required: boolean;
   defaultValue?: string | number | boolean;
+  // Defaults by the answer to the jurisdiction question, taking precedence over defaultValue
+  jurisdictionDefaults?: Record<string, string | number | boolean>;
   options?: string[];
   description?: string;
 }
@@ -16,6 +20,19 @@ export interface TemplateClause {
   riskLevel: RiskLevel;
 }
 
+export interface QuestionnaireQuestion {
+  id: string;
+  prompt: string;
+  type: 'select' | 'boolean' | 'number' | 'text';
+  required: boolean;
+  options?: string[];
+  defaultValue?: string | number | boolean;
+  jurisdictionDefaults?: Record<string, string | number | boolean>;
+  // A number answer that is an amount of money, also available to clauses as {{<id>_amount}}
+  // in the currency answered
+  money?: boolean;
+}
+
 export interface DraftingTemplate {
   id: string;
   name: string;
@@ -27,6 +44,10 @@ export interface DraftingTemplate {
   variables: TemplateVariable[];
   clauses: TemplateClause[];
   isActive: boolean;
+  // Conditional templates: questionnaire answers drive {{#if}} blocks in `assembly`,
+  // which pulls clauses from the clause library with {{clause:id}}
+  questionnaire?: QuestionnaireQuestion[];
+  assembly?: string;
 }
 
 export interface DocumentAssemblyRequest {
@@ -39,6 +60,13 @@ export interface DocumentAssemblyRequest {
   practiceArea: LegalCategory;
 }
 
+export interface QuestionnaireAssemblyRequest {
+  templateId: string;
+  answers: Record<string, unknown>;
+  variables?: Record<string, unknown>;
+  outputFormat: 'html' | 'markdown' | 'text';
+}
+
 export interface DraftingResult {
   documentId: string;
   content: string;
@@ -52,6 +80,9 @@ export interface DraftingResult {
   suggestions: string[];
   warnings: string[];
   revisions: number;
+  includedClauses?: string[];
+  glossaryViolations?: GlossaryViolation[];
+  style?: StyleReport;
 }
 
 interface TemplateFilters {
@@ -60,6 +91,110 @@ interface TemplateFilters {
   isActive?: boolean;
 }
 
+const CLAUSE_LIBRARY: TemplateClause[] = [
+  {
+    id: 'engagement_employee',
+    title: 'Employment',
+    content: '{{company_name}} employs {{worker_name}} as {{role}} from {{effective_date}}. The employee shall devote their full working time to the company and follow its reasonable instructions.',
+    riskLevel: 'low'
+  },
+  {
+    id: 'engagement_contractor',
+    title: 'Engagement of Services',
+    content: '{{company_name}} engages {{worker_name}} as an independent contractor to provide the services described in Schedule A from {{effective_date}}. The contractor controls the manner and means of performing the services, supplies their own equipment and may engage substitutes.',
+    riskLevel: 'medium'
+  },
+  {
+    id: 'at_will',
+    title: 'At-Will Employment',
+    content: 'Employment is at will and may be terminated by either party at any time, with or without cause or notice.',
+    riskLevel: 'low'
+  },
+  {
+    id: 'notice_period',
+    title: 'Notice Period',
+    content: 'Either party may terminate this agreement by giving {{#if jurisdiction == "NL"}}one month\'s{{else}}four weeks\'{{/if}} written notice, subject to any longer statutory minimum.',
+    riskLevel: 'low'
+  },
+  {
+    id: 'ir35_status',
+    title: 'Employment Status (IR35)',
+    content: 'The parties intend that the contractor is not an employee or worker of the company. The company has made a status determination under the off-payroll working rules and provided it to the contractor before the start date.',
+    riskLevel: 'high'
+  },
+  {
+    id: 'ip_assignment',
+    title: 'Intellectual Property',
+    content: 'All intellectual property created in the course of the {{#if worker_type == "employee"}}employment{{else}}services{{/if}} vests in and is hereby assigned to {{company_name}}.',
+    riskLevel: 'medium'
+  },
+  {
+    id: 'confidentiality',
+    title: 'Confidentiality',
+    content: '{{worker_name}} shall keep confidential all non-public information of {{company_name}} during and after the term of this agreement.',
+    riskLevel: 'low'
+  },
+  {
+    id: 'gdpr_processing',
+    title: 'Personal Data',
+    content: 'Each party shall process personal data in accordance with Regulation (EU) 2016/679 (GDPR). Where the contractor processes personal data on behalf of the company, the parties shall enter into a data processing agreement under Article 28 GDPR.',
+    riskLevel: 'medium'
+  },
+  {
+    id: 'non_compete',
+    title: 'Restrictive Covenants',
+    content: 'For {{#if jurisdiction == "UK"}}six{{else}}twelve{{/if}} months after termination, {{worker_name}} shall not provide competing services to clients they dealt with during the last twelve months of the engagement.',
+    riskLevel: 'high'
+  },
+  {
+    id: 'liability_cap_standard',
+    title: 'Limitation of Liability',
+    content: 'Each party\'s aggregate liability under this agreement is limited to the fees paid in the twelve months preceding the claim.',
+    riskLevel: 'medium'
+  },
+  {
+    id: 'liability_cap_enhanced',
+    title: 'Limitation of Liability',
+    content: 'Each party\'s aggregate liability under this agreement is limited to the greater of {{deal_size_amount}} and the fees paid in the twelve months preceding the claim. The cap does not apply to breach of confidentiality or fraud.',
+    riskLevel: 'high'
+  },
+  {
+    id: 'dispute_arbitration',
+    title: 'Dispute Resolution',
+    content: 'Disputes shall be finally resolved by arbitration under the {{#if jurisdiction == "US"}}AAA Commercial Arbitration Rules{{else}}ICC Rules of Arbitration{{/if}}.',
+    riskLevel: 'medium'
+  },
+  {
+    id: 'dispute_courts',
+    title: 'Dispute Resolution',
+    content: 'The competent courts of {{governing_law}} have exclusive jurisdiction over any dispute arising from this agreement.',
+    riskLevel: 'low'
+  },
+  {
+    id: 'governing_law',
+    title: 'Governing Law',
+    content: 'This agreement is governed by the laws of {{governing_law}}.',
+    riskLevel: 'low'
+  }
+];
+
+const ENGAGEMENT_ASSEMBLY = `{{#if worker_type == "employee"}}{{clause:engagement_employee}}
+{{#if jurisdiction == "US"}}{{clause:at_will}}
+{{else}}{{clause:notice_period}}
+{{/if}}{{else}}{{clause:engagement_contractor}}
+{{#if jurisdiction == "UK"}}{{clause:ir35_status}}
+{{/if}}{{clause:notice_period}}
+{{/if}}{{clause:ip_assignment}}
+{{clause:confidentiality}}
+{{#if jurisdiction == "NL" or jurisdiction == "DE"}}{{clause:gdpr_processing}}
+{{/if}}{{#if include_non_compete}}{{clause:non_compete}}
+{{/if}}{{#if deal_size >= 1000000}}{{clause:liability_cap_enhanced}}
+{{clause:dispute_arbitration}}
+{{else}}{{clause:liability_cap_standard}}
+{{clause:dispute_courts}}
+{{/if}}{{clause:governing_law}}
+`;
+
 const TEMPLATES: DraftingTemplate[] = [
   {
     id: 'contract_standard',
@@ -103,9 +238,181 @@ const TEMPLATES: DraftingTemplate[] = [
       { id: 'conclusion', title: 'Conclusion', content: 'Requested relief and conclusion.', riskLevel: 'low' }
     ],
     isActive: true
+  },
+  {
+    id: 'engagement_conditional',
+    name: 'Engagement Agreement (Employee or Contractor)',
+    type: 'agreement',
+    category: 'employment',
+    description: 'First-draft employment or consultancy agreement assembled from the clause library based on a short questionnaire.',
+    jurisdiction: ['US', 'UK', 'NL', 'DE'],
+    usageCount: 0,
+    variables: [
+      { name: 'company_name', type: 'text', required: true, description: 'Legal name of the engaging company' },
+      { name: 'worker_name', type: 'text', required: true, description: 'Name of the employee or contractor' },
+      { name: 'role', type: 'text', required: false, defaultValue: 'the agreed role' },
+      { name: 'effective_date', type: 'date', required: true },
+      {
+        name: 'governing_law',
+        type: 'text',
+        required: true,
+        jurisdictionDefaults: { US: 'the State of Delaware', UK: 'England and Wales', NL: 'the Netherlands', DE: 'Germany' }
+      }
+    ],
+    clauses: [],
+    questionnaire: [
+      { id: 'jurisdiction', prompt: 'Which jurisdiction governs the engagement?', type: 'select', required: true, options: ['US', 'UK', 'NL', 'DE'] },
+      { id: 'worker_type', prompt: 'Is the worker an employee or an independent contractor?', type: 'select', required: true, options: ['employee', 'contractor'] },
+      { id: 'deal_size', prompt: 'Expected total contract value', type: 'number', required: false, defaultValue: 0, money: true },
+      {
+        id: 'currency',
+        prompt: 'Currency of the contract value',
+        type: 'select',
+        required: false,
+        options: ['USD', 'GBP', 'EUR'],
+        defaultValue: 'EUR',
+        jurisdictionDefaults: { US: 'USD', UK: 'GBP', NL: 'EUR', DE: 'EUR' }
+      },
+      { id: 'include_non_compete', prompt: 'Include restrictive covenants?', type: 'boolean', required: false, defaultValue: false }
+    ],
+    assembly: ENGAGEMENT_ASSEMBLY,
+    isActive: true
   }
 ];
 
+type AssemblyNode =
+  | { kind: 'text'; text: string }
+  | { kind: 'if'; branches: Array<{ condition: string | null; body: AssemblyNode[] }> };
+
+const BLOCK_TAG = /{{\s*(#if\s+[^}]+?|else\s+if\s+[^}]+?|else|\/if)\s*}}/g;
+
+// Parse {{#if}} / {{else if}} / {{else}} / {{/if}} blocks into a tree
+export function parseAssembly(source: string): AssemblyNode[] {
+  const root: AssemblyNode[] = [];
+  const stack: Array<Extract<AssemblyNode, { kind: 'if' }>> = [];
+  const current = () => (stack.length ? stack[stack.length - 1].branches[stack[stack.length - 1].branches.length - 1].body : root);
+
+  let lastIndex = 0;
+  for (const match of source.matchAll(BLOCK_TAG)) {
+    const index = match.index ?? 0;
+    if (index > lastIndex) {
+      current().push({ kind: 'text', text: source.slice(lastIndex, index) });
+    }
+    lastIndex = index + match[0].length;
+
+    const tag = match[1].trim();
+    if (tag.startsWith('#if')) {
+      const node: Extract<AssemblyNode, { kind: 'if' }> = {
+        kind: 'if',
+        branches: [{ condition: tag.slice(3).trim(), body: [] }]
+      };
+      current().push(node);
+      stack.push(node);
+    } else if (tag === '/if') {
+      if (!stack.pop()) {
+        throw new Error('Unexpected {{/if}} without a matching {{#if}}');
+      }
+    } else {
+      const block = stack[stack.length - 1];
+      if (!block) {
+        throw new Error(`Unexpected {{${tag}}} outside an {{#if}} block`);
+      }
+      const condition = tag === 'else' ? null : tag.replace(/^else\s+if/, '').trim();
+      block.branches.push({ condition, body: [] });
+    }
+  }
+
+  if (stack.length) {
+    throw new Error('Unclosed {{#if}} block in template');
+  }
+  if (lastIndex < source.length) {
+    root.push({ kind: 'text', text: source.slice(lastIndex) });
+  }
+  return root;
+}
+
+function parseLiteral(raw: string): unknown {
+  const value = raw.trim();
+  if (/^(["']).*\1$/.test(value)) {
+    return value.slice(1, -1);
+  }
+  if (value === 'true' || value === 'false') {
+    return value === 'true';
+  }
+  if (value !== '' && !Number.isNaN(Number(value))) {
+    return Number(value);
+  }
+  return value;
+}
+
+// Conditions: `answer`, `not answer`, `answer == "value"`, `!=`, `>`, `>=`, `<`, `<=`,
+// combined with `and` / `or` (`and` binds tighter)
+export function evaluateCondition(expression: string, answers: Record<string, unknown>): boolean {
+  return expression.split(/\s+or\s+/).some(any =>
+    any.split(/\s+and\s+/).every(term => {
+      const comparison = term.match(/^\s*([\w.]+)\s*(==|!=|>=|<=|>|<)\s*(.+?)\s*$/);
+      if (!comparison) {
+        const negated = term.trim().startsWith('not ');
+        const value = answers[term.trim().replace(/^not\s+/, '')];
+        const truthy = Boolean(value) && value !== 'false' && value !== 'no';
+        return negated ? !truthy : truthy;
+      }
+
+      const [, key, operator, raw] = comparison;
+      const actual = answers[key];
+      const expected = parseLiteral(raw);
+      switch (operator) {
+        case '==':
+          return String(actual ?? '').toLowerCase() === String(expected).toLowerCase();
+        case '!=':
+          return String(actual ?? '').toLowerCase() !== String(expected).toLowerCase();
+        default: {
+          const left = Number(actual);
+          const right = Number(expected);
+          if (Number.isNaN(left) || Number.isNaN(right)) {
+            return false;
+          }
+          return operator === '>' ? left > right : operator === '>=' ? left >= right : operator === '<' ? left < right : left <= right;
+        }
+      }
+    })
+  );
+}
+
+function renderNodes(nodes: AssemblyNode[], answers: Record<string, unknown>): string {
+  return nodes
+    .map(node => {
+      if (node.kind === 'text') {
+        return node.text;
+      }
+      const branch = node.branches.find(candidate => candidate.condition === null || evaluateCondition(candidate.condition, answers));
+      return branch ? renderNodes(branch.body, answers) : '';
+    })
+    .join('');
+}
+
+function formatContent(body: string, outputFormat: 'html' | 'markdown' | 'text'): string {
+  return outputFormat === 'html'
+    ? `<article>${body.replace(/\n/g, '<br/>')}</article>`
+    : outputFormat === 'markdown'
+      ? body
+      : body.replace(/#+\s/g, '').replace(/\*/g, '');
+}
+
+// "£2,000,000" for 2000000 GBP; amounts that are not numbers or currencies that are not ISO
+// codes are shown as given
+export function formatAmount(value: unknown, currency: string): string {
+  const amount = Number(value);
+  if (value === undefined || value === '' || !Number.isFinite(amount)) {
+    return String(value ?? '');
+  }
+  try {
+    return new Intl.NumberFormat('en', { style: 'currency', currency, minimumFractionDigits: 0, maximumFractionDigits: 0 }).format(amount);
+  } catch {
+    return `${currency} ${amount.toLocaleString('en')}`;
+  }
+}
+
 function renderClause(clause: TemplateClause, variables: Record<string, unknown>): string {
   let content = clause.content;
   for (const [key, value] of Object.entries(variables)) {
@@ -140,6 +447,24 @@ function inferSuggestions(template: DraftingTemplate, variables: Record<string,
 export default class DocumentDraftingService {
   private readonly templates = TEMPLATES;
 
+  constructor(private readonly reviewGlossary: GlossaryReviewer = reviewWithGlossary) {}
+
+  // Every draft passes through the firm glossary, then the style checker, before it is returned for review
+  private async reviewDraft(result: DraftingResult): Promise<DraftingResult> {
+    const review = await this.reviewGlossary(result.content);
+    const reportOnly = review.violations.filter(violation => !violation.replaced);
+    return {
+      ...result,
+      content: review.text,
+      glossaryViolations: review.violations,
+      style: checkDraftStyle(review.text),
+      warnings: [
+        ...result.warnings,
+        ...reportOnly.map(violation => `Use "${violation.preferred}" instead of "${violation.found}" (${violation.occurrences}x).`)
+      ]
+    };
+  }
+
   async getTemplates(filters: TemplateFilters = {}): Promise<DraftingTemplate[]> {
     return this.templates.filter(template => {
       if (filters.isActive !== undefined && template.isActive !== filters.isActive) {
@@ -164,14 +489,9 @@ export default class DocumentDraftingService {
     const variables = { ...request.variables };
     const body = renderDocumentBody(template, variables, request.selectedClauses);
 
-    const content =
-      request.outputFormat === 'html'
-        ? `<article>${body.replace(/\n/g, '<br/>')}</article>`
-        : request.outputFormat === 'markdown'
-          ? body
-          : body.replace(/#+\s/g, '').replace(/\*/g, '');
+    const content = formatContent(body, request.outputFormat);
 
-    return {
+    return this.reviewDraft({
       documentId: `doc_${Date.now()}`,
       content,
       metadata: {
@@ -184,14 +504,104 @@ export default class DocumentDraftingService {
       suggestions: inferSuggestions(template, variables),
       warnings: [],
       revisions: 0
-    };
+    });
+  }
+
+  async getQuestionnaire(templateId: string): Promise<QuestionnaireQuestion[]> {
+    const template = this.templates.find(item => item.id === templateId);
+    if (!template) {
+      throw new Error(`Template ${templateId} not found`);
+    }
+    return template.questionnaire ?? [];
+  }
+
+  // Assemble a first draft from the clause library, choosing clauses with the template's
+  // conditional blocks and the questionnaire answers
+  async assembleFromQuestionnaire(request: QuestionnaireAssemblyRequest): Promise<DraftingResult> {
+    const template = this.templates.find(item => item.id === request.templateId);
+    if (!template) {
+      throw new Error(`Template ${request.templateId} not found`);
+    }
+    if (!template.assembly) {
+      throw new Error(`Template ${template.name} has no conditional assembly`);
+    }
+
+    const warnings: string[] = [];
+    const jurisdiction = String(request.answers.jurisdiction ?? '').toUpperCase();
+    const answers: Record<string, unknown> = {};
+    for (const question of template.questionnaire ?? []) {
+      const answer = request.answers[question.id];
+      if (answer === undefined || answer === '') {
+        if (question.required) {
+          warnings.push(`No answer for "${question.prompt}"; clauses depending on it were left out.`);
+        }
+        answers[question.id] = question.jurisdictionDefaults?.[jurisdiction] ?? question.defaultValue;
+      } else {
+        if (question.options && !question.options.includes(String(answer))) {
+          warnings.push(`"${String(answer)}" is not a listed option for "${question.prompt}".`);
+        }
+        answers[question.id] = answer;
+      }
+    }
+
+    const variables: Record<string, unknown> = {};
+    for (const variable of template.variables) {
+      const value = request.variables?.[variable.name] ?? variable.jurisdictionDefaults?.[jurisdiction] ?? variable.defaultValue;
+      if (value === undefined && variable.required) {
+        warnings.push(`Variable ${variable.name} is not filled in.`);
+      }
+      variables[variable.name] = value ?? `[${variable.name}]`;
+    }
+    // Answers are available to clause text as well, amounts of money also in their currency
+    const amounts: Record<string, unknown> = {};
+    for (const question of template.questionnaire ?? []) {
+      if (question.money) {
+        amounts[`${question.id}_amount`] = formatAmount(answers[question.id], String(answers.currency ?? 'EUR'));
+      }
+    }
+    const context = { ...variables, ...answers, ...amounts };
+
+    const includedClauses: string[] = [];
+    const assembled = renderNodes(parseAssembly(template.assembly), answers).replace(/{{\s*clause:([\w-]+)\s*}}/g, (_, clauseId: string) => {
+      const clause = CLAUSE_LIBRARY.find(item => item.id === clauseId);
+      if (!clause) {
+        warnings.push(`Clause ${clauseId} is not in the clause library.`);
+        return '';
+      }
+      includedClauses.push(clause.id);
+      const content = renderNodes(parseAssembly(clause.content), context);
+      return `## ${includedClauses.length}. ${clause.title}\n${renderClause({ ...clause, content }, context)}\n`;
+    });
+
+    const heading = `# ${template.name}\n\n`;
+    const body = heading + assembled.replace(/\n{3,}/g, '\n\n').trim() + '\n';
+
+    const suggestions = CLAUSE_LIBRARY
+      .filter(clause => includedClauses.includes(clause.id) && clause.riskLevel === 'high')
+      .map(clause => `Review "${clause.title}" with the responsible attorney before sending.`);
+
+    return this.reviewDraft({
+      documentId: `doc_${Date.now()}`,
+      content: formatContent(body, request.outputFormat),
+      metadata: {
+        type: template.type,
+        category: template.category,
+        createdAt: new Date(),
+        variables: context,
+        templateId: template.id
+      },
+      suggestions,
+      warnings,
+      revisions: 0,
+      includedClauses
+    });
   }
 
   async generateFromPrompt(prompt: string, type: LegalDocumentType, jurisdiction: string[]): Promise<DraftingResult> {
     const summary = prompt.trim().slice(0, 120) || 'Legal document';
     const content = `# ${type.toUpperCase()} Draft\n\nJurisdiction: ${jurisdiction.join(', ')}\n\n${prompt}\n\n## Key Considerations\n- Ensure compliance with local regulations.\n- Validate party authority.\n- Review risk allocation.`;
 
-    return {
+    return this.reviewDraft({
       documentId: `prompt_${Date.now()}`,
       content,
       metadata: {
@@ -203,7 +613,7 @@ export default class DocumentDraftingService {
       suggestions: ['Review generated content for accuracy before delivery.'],
       warnings: [],
       revisions: 0
-    };
+    });
   }
 
   async exportDocument(documentId: string, format: 'docx' | 'pdf' | 'html'): Promise<Blob> {
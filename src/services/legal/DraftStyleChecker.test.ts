@@ -0,0 +1,44 @@
+import { checkDraftStyle, splitSentences } from './DraftStyleChecker';
+
+describe('DraftStyleChecker', () => {
+  test('flags passive voice, archaic terms and defined-term drift with offsets', () => {
+    const draft =
+      'This Agreement (the "Agreement") is made between Acme BV (the "Company") and Jan Jansen. ' +
+      'The fees shall be paid forthwith by the company. "Confidential Information" means information disclosed hereinafter.';
+    const report = checkDraftStyle(draft);
+
+    expect(report.metrics.sentenceCount).toBe(3);
+    expect(report.metrics.passiveSentences).toBe(2);
+    expect(report.metrics.definedTerms).toEqual(['Agreement', 'Company', 'Confidential Information']);
+
+    const kinds = report.suggestions.map(suggestion => `${suggestion.kind}:${suggestion.excerpt}`);
+    expect(kinds).toEqual([
+      'passive_voice:is made',
+      'passive_voice:be paid',
+      'archaic_legalese:forthwith',
+      'defined_term:company',
+      'defined_term:Confidential Information',
+      'archaic_legalese:hereinafter'
+    ]);
+
+    const company = report.suggestions.find(suggestion => suggestion.excerpt === 'company')!;
+    expect(draft.slice(company.start, company.end)).toBe('company');
+    expect(company.replacement).toBe('Company');
+    expect(report.score).toBeLessThan(100);
+  });
+
+  test('splits sentences around abbreviations and flags long ones', () => {
+    expect(splitSentences('See Art. 28 GDPR. Fees are due monthly, e.g. on the 1st. 1. Scope').map(s => s.text)).toEqual([
+      'See Art. 28 GDPR.',
+      'Fees are due monthly, e.g. on the 1st.',
+      '1. Scope'
+    ]);
+
+    const plain = 'The Client pays each invoice within thirty days.';
+    expect(checkDraftStyle(plain).score).toBe(100);
+
+    const long = checkDraftStyle(plain.replace('.', ' and the Supplier sends a reminder, which the Client answers in writing.'), { maxSentenceWords: 12 });
+    expect(long.metrics.longSentences).toBe(1);
+    expect(long.suggestions[0].kind).toBe('long_sentence');
+  });
+});
@@ -0,0 +1,287 @@
+/**
+ * Style and readability checks for generated drafts. Flags passive constructions, long
+ * sentences, archaic legalese and defined terms that are used inconsistently. Every
+ * suggestion carries character offsets into the draft so the editor can mark it inline.
+ */
+
+export type StyleIssueKind = 'passive_voice' | 'long_sentence' | 'archaic_legalese' | 'defined_term';
+
+export interface StyleSuggestion {
+  kind: StyleIssueKind;
+  severity: 'info' | 'warning';
+  start: number;
+  end: number;
+  excerpt: string;
+  message: string;
+  replacement?: string;
+}
+
+export interface StyleMetrics {
+  sentenceCount: number;
+  averageSentenceLength: number;
+  longSentences: number;
+  passiveSentences: number;
+  passiveRatio: number;
+  archaicTerms: number;
+  definedTerms: string[];
+  definedTermIssues: number;
+}
+
+export interface StyleReport {
+  score: number; // 0-100, higher reads better
+  metrics: StyleMetrics;
+  suggestions: StyleSuggestion[];
+}
+
+export interface StyleCheckOptions {
+  maxSentenceWords?: number;
+  maxPassiveRatio?: number;
+}
+
+const DEFAULT_MAX_SENTENCE_WORDS = 40;
+const DEFAULT_MAX_PASSIVE_RATIO = 0.2;
+
+// Plain-language replacements; an empty replacement means the word can simply be dropped
+const ARCHAIC_TERMS: Array<[string, string]> = [
+  ['in witness whereof', ''],
+  ['know all men by these presents', ''],
+  ['witnesseth', ''],
+  ['hereinafter', 'below'],
+  ['hereinbefore', 'above'],
+  ['hereby', ''],
+  ['herein', 'in this agreement'],
+  ['hereof', 'of this agreement'],
+  ['hereto', 'to this agreement'],
+  ['hereunder', 'under this agreement'],
+  ['therein', 'in it'],
+  ['thereof', 'of it'],
+  ['thereto', 'to it'],
+  ['whereas', ''],
+  ['whereby', 'under which'],
+  ['aforesaid', 'that'],
+  ['aforementioned', 'that'],
+  ['forthwith', 'immediately'],
+  ['null and void', 'void'],
+  ['each and every', 'each'],
+  ['any and all', 'all'],
+  ['in the event that', 'if'],
+  ['pursuant to', 'under'],
+  ['prior to', 'before'],
+  ['subsequent to', 'after']
+];
+
+const IRREGULAR_PARTICIPLES = [
+  'made', 'given', 'paid', 'done', 'held', 'bound', 'brought', 'built', 'chosen', 'drawn', 'driven',
+  'found', 'known', 'kept', 'laid', 'left', 'lent', 'lost', 'meant', 'met', 'read', 'sent', 'set',
+  'shown', 'sold', 'spent', 'taken', 'told', 'thought', 'understood', 'withheld', 'written'
+];
+
+const PASSIVE_PATTERN = new RegExp(
+  `\\b(?:am|is|are|was|were|be|been|being)\\s+(?:\\w+ly\\s+)?(\\w+ed|${IRREGULAR_PARTICIPLES.join('|')})\\b`,
+  'gi'
+);
+
+// ("Agreement"), (the "Client"), (each a "Party") and "Confidential Information" means ...
+const DEFINITION_PATTERNS = [
+  /\((?:the|each|together|collectively)?\s*(?:an?\s+)?["“]([A-Z][\w\s-]*?)["”]\)/g,
+  /["“]([A-Z][\w\s-]*?)["”]\s+(?:means|shall mean|has the meaning)/g
+];
+
+interface Sentence {
+  start: number;
+  end: number;
+  text: string;
+}
+
+const ABBREVIATIONS = new Set(['art', 'no', 'nr', 'mr', 'mrs', 'ms', 'dr', 'ltd', 'inc', 'co', 'e.g', 'i.e', 'etc', 'cl', 'para', 'sec', 'vs']);
+
+/** Split on ., ! and ? followed by whitespace, skipping common abbreviations and "1." style numbering */
+export function splitSentences(text: string): Sentence[] {
+  const sentences: Sentence[] = [];
+  const boundary = /[.!?]+(?=\s|$)|\n\s*\n/g;
+  let start = 0;
+  let match: RegExpExecArray | null;
+  while ((match = boundary.exec(text)) !== null) {
+    const end = match.index + match[0].length;
+    const lastWord = text.slice(start, match.index).split(/\s+/).pop() ?? '';
+    if (match[0].startsWith('.') && (ABBREVIATIONS.has(lastWord.toLowerCase()) || /^\d+$/.test(lastWord))) {
+      continue;
+    }
+    pushSentence(text, start, end, sentences);
+    start = end;
+  }
+  pushSentence(text, start, text.length, sentences);
+  return sentences;
+}
+
+function pushSentence(text: string, start: number, end: number, sentences: Sentence[]) {
+  const raw = text.slice(start, end);
+  const leading = raw.length - raw.trimStart().length;
+  const trimmed = raw.trim();
+  if (/\w/.test(trimmed)) {
+    sentences.push({ start: start + leading, end: start + leading + trimmed.length, text: trimmed });
+  }
+}
+
+function countWords(text: string): number {
+  return text.split(/\s+/).filter(word => /\w/.test(word)).length;
+}
+
+function escapeRegExp(value: string): string {
+  return value.replace(/[.*+?^${}()|[\]\\]/g, '\\$&');
+}
+
+function findDefinitions(text: string): Map<string, number> {
+  const definitions = new Map<string, number>();
+  for (const pattern of DEFINITION_PATTERNS) {
+    for (const match of text.matchAll(pattern)) {
+      const term = match[1].trim();
+      const position = (match.index ?? 0) + match[0].indexOf(term);
+      if (!definitions.has(term) || position < definitions.get(term)!) {
+        definitions.set(term, position);
+      }
+    }
+  }
+  return definitions;
+}
+
+function checkDefinedTerms(text: string, sentences: Sentence[], definitions: Map<string, number>): StyleSuggestion[] {
+  const suggestions: StyleSuggestion[] = [];
+  definitions.forEach((definedAt, term) => {
+    // "This Agreement (the "Agreement")" uses the term in the sentence that defines it
+    const definingSentence = sentences.find(sentence => definedAt >= sentence.start && definedAt < sentence.end);
+    const usableFrom = definingSentence ? definingSentence.start : definedAt;
+    const uses = Array.from(text.matchAll(new RegExp(`\\b${escapeRegExp(term)}s?\\b`, 'gi')))
+      // The definition itself is quoted; only count uses outside quotes
+      .filter(match => !/["“]$/.test(text.slice(0, match.index)));
+
+    if (uses.length === 0) {
+      suggestions.push({
+        kind: 'defined_term',
+        severity: 'info',
+        start: definedAt,
+        end: definedAt + term.length,
+        excerpt: term,
+        message: `"${term}" is defined but never used.`
+      });
+    }
+
+    for (const match of uses) {
+      const start = match.index ?? 0;
+      const end = start + match[0].length;
+      if (!match[0].startsWith(term)) {
+        // "the company" after ("Company") was defined reads as something else
+        suggestions.push({
+          kind: 'defined_term',
+          severity: 'warning',
+          start,
+          end,
+          excerpt: match[0],
+          message: `Capitalise the defined term "${term}" consistently.`,
+          replacement: term + match[0].slice(term.length)
+        });
+      } else if (start < usableFrom) {
+        suggestions.push({
+          kind: 'defined_term',
+          severity: 'warning',
+          start,
+          end,
+          excerpt: match[0],
+          message: `"${term}" is used before it is defined.`
+        });
+      }
+    }
+  });
+  return suggestions;
+}
+
+/** Score a draft and return inline suggestions, ordered by position */
+export function checkDraftStyle(text: string, options: StyleCheckOptions = {}): StyleReport {
+  const maxSentenceWords = options.maxSentenceWords ?? DEFAULT_MAX_SENTENCE_WORDS;
+  const maxPassiveRatio = options.maxPassiveRatio ?? DEFAULT_MAX_PASSIVE_RATIO;
+  const suggestions: StyleSuggestion[] = [];
+
+  const sentences = splitSentences(text);
+  let totalWords = 0;
+  let longSentences = 0;
+  let passiveSentences = 0;
+
+  for (const sentence of sentences) {
+    const words = countWords(sentence.text);
+    totalWords += words;
+    if (words > maxSentenceWords) {
+      longSentences++;
+      suggestions.push({
+        kind: 'long_sentence',
+        severity: words > maxSentenceWords * 1.5 ? 'warning' : 'info',
+        start: sentence.start,
+        end: sentence.end,
+        excerpt: sentence.text.slice(0, 80),
+        message: `Sentence has ${words} words; consider splitting it (limit ${maxSentenceWords}).`
+      });
+    }
+
+    const passives = Array.from(sentence.text.matchAll(PASSIVE_PATTERN));
+    if (passives.length > 0) {
+      passiveSentences++;
+      for (const match of passives) {
+        const start = sentence.start + (match.index ?? 0);
+        suggestions.push({
+          kind: 'passive_voice',
+          severity: 'info',
+          start,
+          end: start + match[0].length,
+          excerpt: match[0],
+          message: 'Passive voice hides who must act; name the party responsible.'
+        });
+      }
+    }
+  }
+
+  let archaicTerms = 0;
+  for (const [term, replacement] of ARCHAIC_TERMS) {
+    for (const match of text.matchAll(new RegExp(`\\b${escapeRegExp(term)}\\b`, 'gi'))) {
+      const start = match.index ?? 0;
+      // "herein" must not also fire inside an already reported "hereinafter"
+      if (suggestions.some(s => s.kind === 'archaic_legalese' && start >= s.start && start < s.end)) {
+        continue;
+      }
+      archaicTerms++;
+      suggestions.push({
+        kind: 'archaic_legalese',
+        severity: 'info',
+        start,
+        end: start + match[0].length,
+        excerpt: match[0],
+        message: replacement ? `Replace "${match[0]}" with "${replacement}".` : `"${match[0]}" can be removed.`,
+        replacement
+      });
+    }
+  }
+
+  const definitions = findDefinitions(text);
+  const definedTermSuggestions = checkDefinedTerms(text, sentences, definitions);
+  suggestions.push(...definedTermSuggestions);
+
+  const passiveRatio = sentences.length > 0 ? passiveSentences / sentences.length : 0;
+  const penalty =
+    Math.max(0, passiveRatio - maxPassiveRatio) * 100 +
+    (sentences.length > 0 ? (longSentences / sentences.length) * 40 : 0) +
+    archaicTerms * 2 +
+    definedTermSuggestions.filter(s => s.severity === 'warning').length * 4;
+
+  return {
+    score: Math.max(0, Math.round(100 - penalty)),
+    metrics: {
+      sentenceCount: sentences.length,
+      averageSentenceLength: sentences.length > 0 ? Math.round((totalWords / sentences.length) * 10) / 10 : 0,
+      longSentences,
+      passiveSentences,
+      passiveRatio: Math.round(passiveRatio * 100) / 100,
+      archaicTerms,
+      definedTerms: Array.from(definitions.keys()),
+      definedTermIssues: definedTermSuggestions.length
+    },
+    suggestions: suggestions.sort((a, b) => a.start - b.start)
+  };
+}
@@ -0,0 +1,29 @@
+import { invoke } from '@tauri-apps/api/tauri';
+
+export interface GlossaryViolation {
+  found: string;
+  preferred: string;
+  occurrences: number;
+  replaced: boolean;
+  note?: string | null;
+}
+
+export interface GlossaryReview {
+  text: string;
+  violations: GlossaryViolation[];
+}
+
+export type GlossaryReviewer = (text: string) => Promise<GlossaryReview>;
+
+/**
+ * Apply the firm's terminology glossary through the Rust backend: enforced terms are
+ * replaced, report-only terms come back as violations. Outside the desktop app the
+ * text is returned unchanged.
+ */
+export const reviewWithGlossary: GlossaryReviewer = async (text: string) => {
+  try {
+    return await invoke<GlossaryReview>('glossary_review_text', { text, apply: true });
+  } catch {
+    return { text, violations: [] };
+  }
+};
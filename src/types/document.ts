@@ -83,6 +83,7 @@ export interface LegalEntity {
   start_pos: number;
   end_pos: number;
   context: string;
+  normalized_value?: string | null; // ISO 8601 date or "EUR 1000.00"
 }
 
 export interface EnhancedLegalEntity extends LegalEntity {